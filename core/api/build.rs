@@ -0,0 +1,10 @@
+// =============================================================================
+// api/build.rs: ビルドスクリプト
+// =============================================================================
+// sqlx::migrate!（commands.rs）は api/migrations をバイナリに埋め込む。
+// マイグレーションを追加・変更したときに再ビルドされるよう、ディレクトリを監視する。
+// =============================================================================
+
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+}
@@ -0,0 +1,7 @@
+-- =============================================================================
+-- import_jobs / import_job_items テーブルのロールバック
+-- =============================================================================
+
+DROP TABLE IF EXISTS import_job_items;
+DROP INDEX IF EXISTS idx_import_jobs_state_created_at;
+DROP TABLE IF EXISTS import_jobs;
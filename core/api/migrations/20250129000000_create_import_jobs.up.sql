@@ -0,0 +1,62 @@
+-- =============================================================================
+-- 20250129000000_create_import_jobs.up.sql: 非同期インポートジョブ
+-- =============================================================================
+-- 大量の TODO インポートをバックグラウンドで処理するためのテーブル。
+-- import_jobs: ジョブの状態と進捗（ワーカーが取得・更新する）
+-- import_job_items: インポート対象の項目（ペイロード）
+--
+-- ジョブはテーブルに永続化されるため、サーバーが再起動しても
+-- リース切れの running ジョブを別のワーカーが再取得して処理を再開できる。
+-- =============================================================================
+
+-- -----------------------------------------------------------------------------
+-- import_jobs テーブルを作成
+-- -----------------------------------------------------------------------------
+CREATE TABLE import_jobs (
+    -- 主キー（UUID）
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- ジョブを登録したユーザー（ユーザー削除時はジョブも削除）
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- 状態（pending → running → completed / failed、または cancelled）
+    state TEXT NOT NULL DEFAULT 'pending'
+        CHECK (state IN ('pending', 'running', 'completed', 'failed', 'cancelled')),
+
+    -- 項目数と進捗
+    -- processed_items はワーカーの再開位置（カーソル）を兼ねる
+    total_items INTEGER NOT NULL,
+    processed_items INTEGER NOT NULL DEFAULT 0,
+    failed_items INTEGER NOT NULL DEFAULT 0,
+
+    -- ジョブ自体が失敗した場合のエラーメッセージ
+    error TEXT,
+
+    -- メタデータ
+    -- updated_at はワーカーのリース判定にも使用（チャンク処理ごとに更新）
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- ワーカーの取得クエリ用インデックス（状態 + 登録順）
+CREATE INDEX idx_import_jobs_state_created_at ON import_jobs (state, created_at);
+
+-- -----------------------------------------------------------------------------
+-- import_job_items テーブルを作成
+-- -----------------------------------------------------------------------------
+CREATE TABLE import_job_items (
+    -- 所属するジョブ（ジョブ削除時は項目も削除）
+    job_id UUID NOT NULL REFERENCES import_jobs(id) ON DELETE CASCADE,
+
+    -- リクエスト内の位置（0 始まり）
+    position INTEGER NOT NULL,
+
+    -- インポートする TODO の内容（未検証）
+    title TEXT NOT NULL,
+    description TEXT,
+
+    -- 項目の処理に失敗した場合のエラーメッセージ
+    error TEXT,
+
+    PRIMARY KEY (job_id, position)
+);
@@ -0,0 +1,5 @@
+-- =============================================================================
+-- todos の正規化タイトルインデックスのロールバック
+-- =============================================================================
+
+DROP INDEX IF EXISTS idx_todos_user_open_normalized_title;
@@ -0,0 +1,20 @@
+-- =============================================================================
+-- 20250130000000_add_todos_normalized_title_index.up.sql: マイグレーション（UP）
+-- =============================================================================
+-- バッチ作成の重複検出（dedupe=true）で、ユーザーの未完了 TODO を
+-- 正規化したタイトルで検索するための式インデックス。
+--
+-- 正規化の規則は domain::Todo::normalize_title と同じ:
+-- - 前後の空白を除去（btrim）
+-- - 連続する空白を 1 つの半角スペースにまとめる（regexp_replace）
+-- - 小文字化（lower）
+-- =============================================================================
+
+-- -----------------------------------------------------------------------------
+-- 部分インデックス: 未完了の TODO のみ
+-- -----------------------------------------------------------------------------
+-- 重複検出の対象は未完了の TODO のみのため、完了済みの行はインデックスに含めない
+-- クエリの WHERE 句は同じ式・同じ条件で書くこと（インデックスが使われるように）
+CREATE INDEX idx_todos_user_open_normalized_title
+    ON todos (user_id, lower(regexp_replace(btrim(title), '\s+', ' ', 'g')))
+    WHERE completed = false;
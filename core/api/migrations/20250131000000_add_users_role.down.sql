@@ -0,0 +1,5 @@
+-- =============================================================================
+-- users のロールのロールバック
+-- =============================================================================
+
+ALTER TABLE users DROP COLUMN IF EXISTS role;
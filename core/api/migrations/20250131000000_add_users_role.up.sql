@@ -0,0 +1,13 @@
+-- =============================================================================
+-- 20250131000000_add_users_role.up.sql: マイグレーション（UP）
+-- =============================================================================
+-- users にロール（user / admin）を追加する。
+--
+-- - 既存ユーザーと新規登録ユーザーは user
+-- - admin は運用コマンド（api create-admin）でのみ付与する
+-- =============================================================================
+
+ALTER TABLE users
+ADD COLUMN role TEXT NOT NULL DEFAULT 'user'
+    -- domain::Role と同じ値のみ許可する
+    CONSTRAINT chk_users_role CHECK (role IN ('user', 'admin'));
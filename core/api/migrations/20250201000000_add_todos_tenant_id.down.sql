@@ -0,0 +1,6 @@
+-- =============================================================================
+-- todos のテナント ID のロールバック
+-- =============================================================================
+
+DROP INDEX IF EXISTS idx_todos_user_tenant;
+ALTER TABLE todos DROP COLUMN IF EXISTS tenant_id;
@@ -0,0 +1,14 @@
+-- =============================================================================
+-- 20250201000000_add_todos_tenant_id.up.sql: マイグレーション（UP）
+-- =============================================================================
+-- todos に作成時のテナント ID を追加する。
+--
+-- - Edge 層が転送した X-Claim-Tenant-Id を TENANT_SCOPING=true の場合のみ記録
+-- - 既存の TODO とテナントのないトークンで作成した TODO は NULL
+-- - テナントで絞り込む場合も user_id の条件は常に併用する
+-- =============================================================================
+
+ALTER TABLE todos ADD COLUMN tenant_id UUID;
+
+-- 一覧取得（WHERE user_id = $1 AND tenant_id = $2）用
+CREATE INDEX idx_todos_user_tenant ON todos (user_id, tenant_id);
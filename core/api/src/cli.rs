@@ -0,0 +1,282 @@
+// =============================================================================
+// api/src/cli.rs: コマンドライン引数
+// =============================================================================
+// api バイナリのサブコマンドを解析する。
+//
+// サブコマンド:
+// - serve（デフォルト）: HTTP サーバーを起動する（--backend postgres|memory）
+// - migrate: 埋め込みマイグレーションを適用して終了する
+// - create-admin: 管理者を作成する（既存ユーザーは昇格）
+// - cleanup-orphans: ストレージ上の孤立ファイルを削除する
+//
+// サブコマンドを省略した場合は serve として扱う（`api --backend memory` も従来どおり動く）。
+//
+// 終了コード:
+// - 0: 成功
+// - 1: コマンドの実行に失敗（main が Err を返す）
+// - 2: 引数が不正（USAGE を表示）
+// =============================================================================
+
+use crate::config::Backend;
+
+/// 使い方（--help と引数エラー時に表示）
+pub const USAGE: &str = "\
+Usage: api [COMMAND] [OPTIONS]
+
+Commands:
+  serve             Start the HTTP server (default)
+                      --backend <postgres|memory>   data store (default: postgres)
+  migrate           Apply the embedded database migrations and exit
+  create-admin      Create an admin user, or promote an existing user
+                      --email <EMAIL>               required
+                      --password <PASSWORD>         required for new users
+                                                    (or set ADMIN_PASSWORD)
+  cleanup-orphans   Delete stored files that no database row references
+                      --older-than-hours <HOURS>    grace period (default: 24)
+                      --dry-run                     report without deleting
+  help              Show this message";
+
+/// 孤立ファイル掃除の猶予期間のデフォルト（時間）
+///
+/// アップロード直後でまだ DB に行がないオブジェクトを消さないため。
+pub const DEFAULT_ORPHAN_GRACE_HOURS: u32 = 24;
+
+// =============================================================================
+// Command
+// =============================================================================
+
+/// 実行するサブコマンド
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// HTTP サーバーを起動する
+    Serve {
+        /// データストアのバックエンド
+        backend: Backend,
+    },
+    /// 埋め込みマイグレーションを適用する
+    Migrate,
+    /// 管理者を作成する（既存ユーザーは昇格）
+    CreateAdmin {
+        /// メールアドレス
+        email: String,
+        /// パスワード（None なら ADMIN_PASSWORD 環境変数）
+        password: Option<String>,
+    },
+    /// 孤立ファイルを削除する
+    CleanupOrphans {
+        /// 猶予期間（時間）
+        older_than_hours: u32,
+        /// 削除せずに結果のみ表示する
+        dry_run: bool,
+    },
+    /// 使い方を表示する
+    Help,
+}
+
+impl Command {
+    /// コマンドライン引数を解析する
+    ///
+    /// オプションは `--name value` と `--name=value` の両方を受け付ける。
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - プログラム名を除いた引数
+    ///
+    /// # Errors
+    ///
+    /// 未知のサブコマンド・オプション、値のないオプション、不正な値、必須オプションの欠落
+    pub fn parse<I>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter().peekable();
+
+        // サブコマンドの省略（引数なし、または先頭がオプション）は serve
+        let name = match args.peek() {
+            Some(arg) if !arg.starts_with('-') => args.next().unwrap_or_default(),
+            _ => "serve".to_string(),
+        };
+        let mut options = Options::parse(args)?;
+
+        let command = match name.as_str() {
+            "serve" => Self::Serve {
+                backend: options
+                    .take("backend")
+                    .map(|value| value.parse())
+                    .transpose()?
+                    .unwrap_or(Backend::Postgres),
+            },
+            "migrate" => Self::Migrate,
+            "create-admin" => Self::CreateAdmin {
+                email: options
+                    .take("email")
+                    .ok_or_else(|| "create-admin requires --email".to_string())?,
+                password: options.take("password"),
+            },
+            "cleanup-orphans" => Self::CleanupOrphans {
+                older_than_hours: options
+                    .take("older-than-hours")
+                    .map(|value| {
+                        value
+                            .parse()
+                            .map_err(|_| format!("invalid --older-than-hours: {}", value))
+                    })
+                    .transpose()?
+                    .unwrap_or(DEFAULT_ORPHAN_GRACE_HOURS),
+                dry_run: options.flag("dry-run"),
+            },
+            "help" => Self::Help,
+            other => return Err(format!("unknown command: {}", other)),
+        };
+
+        if options.flag("help") {
+            return Ok(Self::Help);
+        }
+        options.finish(&name)?;
+        Ok(command)
+    }
+}
+
+// =============================================================================
+// Options: `--name value` 形式のオプション
+// =============================================================================
+
+/// 解析済みのオプション（名前, 値）
+///
+/// サブコマンドが使ったオプションを取り除き、最後に残ったものを未知のオプションとして報告する。
+struct Options(Vec<(String, Option<String>)>);
+
+/// 値を取らないオプション
+const FLAGS: &[&str] = &["dry-run", "help"];
+
+impl Options {
+    fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Self, String> {
+        let mut options = Vec::new();
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                return Err(format!("unexpected argument: {}", arg));
+            };
+            let (name, value) = match name.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None if FLAGS.contains(&name) => (name.to_string(), None),
+                None => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("--{} requires a value", name))?;
+                    (name.to_string(), Some(value))
+                }
+            };
+            options.push((name, value));
+        }
+        Ok(Self(options))
+    }
+
+    /// 値付きオプションを取り出す
+    fn take(&mut self, name: &str) -> Option<String> {
+        let index = self.0.iter().position(|(n, _)| n == name)?;
+        self.0.remove(index).1
+    }
+
+    /// フラグを取り出す
+    fn flag(&mut self, name: &str) -> bool {
+        match self.0.iter().position(|(n, _)| n == name) {
+            Some(index) => {
+                self.0.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 使われなかったオプションがあればエラー
+    fn finish(self, command: &str) -> Result<(), String> {
+        match self.0.first() {
+            Some((name, _)) => Err(format!("unknown option for {}: --{}", command, name)),
+            None => Ok(()),
+        }
+    }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(list: &[&str]) -> Result<Command, String> {
+        Command::parse(list.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn test_serve_is_the_default_command() {
+        assert_eq!(
+            parse(&[]),
+            Ok(Command::Serve {
+                backend: Backend::Postgres
+            })
+        );
+        // サブコマンドなしの --backend（従来の起動方法）
+        assert_eq!(
+            parse(&["--backend", "memory"]),
+            Ok(Command::Serve {
+                backend: Backend::Memory
+            })
+        );
+        assert_eq!(
+            parse(&["serve", "--backend=postgres"]),
+            Ok(Command::Serve {
+                backend: Backend::Postgres
+            })
+        );
+        assert!(parse(&["--backend"]).is_err());
+        assert!(parse(&["--backend", "sqlite"]).is_err());
+        assert!(parse(&["--verbose"]).is_err());
+    }
+
+    #[test]
+    fn test_create_admin_requires_email() {
+        assert_eq!(
+            parse(&[
+                "create-admin",
+                "--email",
+                "a@example.com",
+                "--password=secret123"
+            ]),
+            Ok(Command::CreateAdmin {
+                email: "a@example.com".to_string(),
+                password: Some("secret123".to_string()),
+            })
+        );
+        assert!(parse(&["create-admin", "--password", "secret123"]).is_err());
+    }
+
+    #[test]
+    fn test_cleanup_orphans_options() {
+        assert_eq!(
+            parse(&["cleanup-orphans"]),
+            Ok(Command::CleanupOrphans {
+                older_than_hours: DEFAULT_ORPHAN_GRACE_HOURS,
+                dry_run: false,
+            })
+        );
+        assert_eq!(
+            parse(&["cleanup-orphans", "--dry-run", "--older-than-hours", "48"]),
+            Ok(Command::CleanupOrphans {
+                older_than_hours: 48,
+                dry_run: true,
+            })
+        );
+        assert!(parse(&["cleanup-orphans", "--older-than-hours", "-1"]).is_err());
+    }
+
+    #[test]
+    fn test_unknown_commands_and_options_are_rejected() {
+        assert!(parse(&["purge"]).is_err());
+        // 他のサブコマンドのオプションは受け付けない
+        assert!(parse(&["migrate", "--backend", "memory"]).is_err());
+        assert_eq!(parse(&["help"]), Ok(Command::Help));
+        assert_eq!(parse(&["migrate", "--help"]), Ok(Command::Help));
+    }
+}
@@ -0,0 +1,223 @@
+// =============================================================================
+// api/src/commands.rs: 運用コマンド
+// =============================================================================
+// serve 以外のサブコマンド（migrate / create-admin / cleanup-orphans）の本体。
+//
+// 設計判断:
+// - 各コマンドは必要な依存だけを受け取る（migrate は PgPool のみ、Redis は使わない）
+// - 依存の生成は main.rs、処理はここ（テストでは関数を直接呼び出す）
+// - 失敗は anyhow::Error で返し、main が終了コード 1 で終了する
+// =============================================================================
+
+use std::sync::Arc;
+
+use anyhow::Context;
+use application::{CreateAdminCommand, CreateAdminOutcome};
+use domain::{StorageOps, UserReader, UserWriter};
+use infrastructure::{OrphanCleanupReport, OrphanFileCleanup};
+use sqlx::migrate::Migrator;
+use sqlx::PgPool;
+
+/// api/migrations を埋め込んだマイグレーター
+///
+/// sqlx-cli（`sqlx migrate run`）と同じ _sqlx_migrations テーブルで適用履歴を管理する。
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+// =============================================================================
+// migrate
+// =============================================================================
+
+/// 未適用のマイグレーションを適用する
+///
+/// # Returns
+///
+/// 適用後のマイグレーション数（埋め込まれた件数）
+///
+/// # Errors
+///
+/// 接続・SQL の実行に失敗した場合、または適用済みのマイグレーションが変更されている場合
+pub async fn migrate(pool: &PgPool) -> anyhow::Result<usize> {
+    MIGRATOR
+        .run(pool)
+        .await
+        .context("failed to apply migrations")?;
+    Ok(MIGRATOR.iter().count())
+}
+
+// =============================================================================
+// create-admin
+// =============================================================================
+
+/// 管理者を作成する（既存ユーザーは昇格する）
+///
+/// 通常の登録と同じバリデーションと bcrypt ハッシュ化を行う（CreateAdminCommand）。
+///
+/// # Arguments
+///
+/// * `user_reader` - UserReader
+/// * `user_writer` - UserWriter
+/// * `email` - メールアドレス
+/// * `password` - パスワード（既存ユーザーの昇格では変更しない）
+///
+/// # Errors
+///
+/// メールアドレス・パスワードが不正な場合、または DB エラー
+pub async fn create_admin(
+    user_reader: Arc<dyn UserReader>,
+    user_writer: Arc<dyn UserWriter>,
+    email: &str,
+    password: &str,
+) -> anyhow::Result<CreateAdminOutcome> {
+    CreateAdminCommand::new(user_reader, user_writer)
+        .execute(email, password)
+        .await
+        .context("failed to create admin")
+}
+
+// =============================================================================
+// cleanup-orphans
+// =============================================================================
+
+/// ストレージ上の孤立ファイルを削除する
+///
+/// # Arguments
+///
+/// * `pool` - files テーブルを参照する接続プール
+/// * `storage` - ファイルストレージ
+/// * `older_than_hours` - 猶予期間（時間）
+/// * `dry_run` - true なら削除しない
+///
+/// # Errors
+///
+/// 一覧取得・DB の読み込みに失敗した場合、または削除に失敗したキーがある場合
+pub async fn cleanup_orphans(
+    pool: PgPool,
+    storage: Arc<dyn StorageOps>,
+    older_than_hours: u32,
+    dry_run: bool,
+) -> anyhow::Result<OrphanCleanupReport> {
+    let report = OrphanFileCleanup::new(pool, storage)
+        .run(chrono::Duration::hours(older_than_hours.into()), dry_run)
+        .await
+        .context("failed to clean up orphaned files")?;
+
+    if !report.failed.is_empty() {
+        anyhow::bail!(
+            "failed to delete {} of {} orphaned file(s): {}",
+            report.failed.len(),
+            report.orphans.len(),
+            report.failed.join(", ")
+        );
+    }
+    Ok(report)
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::Role;
+    use infrastructure::{
+        detached_pool, InMemoryStorage, InMemoryUserReader, InMemoryUserWriter, MemoryStore,
+    };
+
+    /// 埋め込まれたマイグレーションは api/migrations の全ファイルと一致する
+    #[test]
+    fn test_migrator_embeds_every_migration() {
+        let files = std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations"))
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".up.sql"))
+            .count();
+
+        assert!(files > 0);
+        assert_eq!(
+            MIGRATOR
+                .iter()
+                .filter(|m| m.migration_type.is_up_migration())
+                .count(),
+            files
+        );
+    }
+
+    /// 新規作成と既存ユーザーの昇格
+    #[tokio::test]
+    async fn test_create_admin_creates_then_promotes() {
+        let store = MemoryStore::new();
+        let reader: Arc<dyn UserReader> = Arc::new(InMemoryUserReader::new(store.clone()));
+        let writer: Arc<dyn UserWriter> = Arc::new(InMemoryUserWriter::new(store));
+
+        let created = create_admin(
+            reader.clone(),
+            writer.clone(),
+            "Admin@Example.com",
+            "password123",
+        )
+        .await
+        .unwrap();
+        assert!(matches!(created, CreateAdminOutcome::Created(_)));
+        assert_eq!(created.user().email, "admin@example.com");
+        assert_eq!(created.user().role, Role::Admin);
+
+        // 2 回目は変更なし
+        let again = create_admin(
+            reader.clone(),
+            writer.clone(),
+            "admin@example.com",
+            "password123",
+        )
+        .await
+        .unwrap();
+        assert!(matches!(again, CreateAdminOutcome::AlreadyAdmin(_)));
+
+        // 通常登録したユーザーは昇格する
+        let auth =
+            application::AuthService::new(reader.clone(), writer.clone(), "secret".into(), 1);
+        let user = auth
+            .register("user@example.com", "password123", None)
+            .await
+            .unwrap();
+        let promoted = create_admin(reader.clone(), writer, "user@example.com", "ignored-pw")
+            .await
+            .unwrap();
+        assert_eq!(
+            promoted,
+            CreateAdminOutcome::Promoted(promoted.user().clone())
+        );
+        assert_eq!(promoted.user().id, user.id);
+        let stored = reader.find_by_id(user.id).await.unwrap().unwrap();
+        assert_eq!(stored.role, Role::Admin);
+    }
+
+    /// 通常の登録と同じバリデーション
+    #[tokio::test]
+    async fn test_create_admin_validates_input() {
+        let store = MemoryStore::new();
+        let reader = Arc::new(InMemoryUserReader::new(store.clone()));
+        let writer = Arc::new(InMemoryUserWriter::new(store));
+
+        assert!(create_admin(
+            reader.clone(),
+            writer.clone(),
+            "not-an-email",
+            "password123"
+        )
+        .await
+        .is_err());
+        assert!(create_admin(reader, writer, "admin@example.com", "short")
+            .await
+            .is_err());
+    }
+
+    /// DB に接続できない場合はエラー（終了コード 1）
+    #[tokio::test]
+    async fn test_cleanup_orphans_reports_database_failure() {
+        let result =
+            cleanup_orphans(detached_pool(), Arc::new(InMemoryStorage::new()), 24, true).await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,217 @@
+// =============================================================================
+// api/src/jwt_keys.rs: JWT 鍵セットの読み込みとリロード
+// =============================================================================
+// 設定（JwtConfig）から AuthService が使う鍵セット（JwtKeyRing）を作る。
+//
+// 鍵の読み込み元:
+// - JWT_SECRETS_FILE 設定時: 鍵ファイル（KeyFile）。SIGHUP または
+//   POST /api/admin/reload-secrets で再読み込みできる
+// - 未設定時: JWT_SECRET 1 つ（key id は "default"）。リロードは不可
+//   （実行中のプロセスの環境変数は外から変更できないため）
+//
+// JWT_SECRET から鍵ファイルへの移行:
+//   鍵ファイルに `default:<JWT_SECRET の値>` を 2 行目以降で残しておけば、
+//   移行前に発行したトークン（kid = "default" または kid なし）も検証できる。
+//
+// 注意: Edge 層の JWT 検証は独自のシークレットを使う（この鍵セットとは別に設定する）。
+// =============================================================================
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use application::{JwtKeyRing, KeySet, KeySource};
+use domain::DomainError;
+
+use crate::config::JwtConfig;
+
+// =============================================================================
+// KeyFile
+// =============================================================================
+
+/// 鍵ファイル（JWT_SECRETS_FILE）からの読み込み
+///
+/// 形式は `KeySet::parse` を参照（1 行に `<key id>:<secret>`、先頭が署名用）。
+pub struct KeyFile {
+    /// 鍵ファイルのパス
+    path: PathBuf,
+    /// シークレットの最小長（バイト、production は 32）
+    min_secret_len: usize,
+}
+
+impl KeyFile {
+    /// 鍵ファイルの読み込み元を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - 鍵ファイルのパス
+    /// * `min_secret_len` - シークレットの最小長（0 なら確認しない）
+    pub fn new(path: PathBuf, min_secret_len: usize) -> Self {
+        Self {
+            path,
+            min_secret_len,
+        }
+    }
+}
+
+impl KeySource for KeyFile {
+    fn load(&self) -> Result<KeySet, DomainError> {
+        let text = std::fs::read_to_string(&self.path).map_err(|e| {
+            DomainError::Validation(format!(
+                "JWT_SECRETS_FILE: cannot read {}: {}",
+                self.path.display(),
+                e
+            ))
+        })?;
+        let keys = KeySet::parse(&text)
+            .map_err(|e| DomainError::Validation(format!("JWT_SECRETS_FILE: {}", e)))?;
+
+        if let Some(short) = keys
+            .keys()
+            .find(|key| key.secret_len() < self.min_secret_len)
+        {
+            return Err(DomainError::Validation(format!(
+                "JWT_SECRETS_FILE: key {:?} must be at least {} bytes",
+                short.id(),
+                self.min_secret_len
+            )));
+        }
+        Ok(keys)
+    }
+}
+
+// =============================================================================
+// 鍵セットの作成とリロード
+// =============================================================================
+
+/// 設定から鍵セットを作成する
+///
+/// # Errors
+///
+/// 鍵ファイルの読み込み・検証に失敗した場合（起動しない）
+pub fn key_ring(config: &JwtConfig) -> anyhow::Result<JwtKeyRing> {
+    match &config.secrets_file {
+        Some(path) => {
+            let source = Arc::new(KeyFile::new(path.clone(), config.min_secret_len));
+            let keys = JwtKeyRing::from_source(source)?;
+            tracing::info!(
+                file = %path.display(),
+                signing_key_id = keys.snapshot().current().id(),
+                "JWT keys loaded (reloadable with SIGHUP)"
+            );
+            Ok(keys)
+        }
+        None => Ok(JwtKeyRing::new(KeySet::single(config.secret.clone()))),
+    }
+}
+
+/// SIGHUP を受け取るたびに鍵セットをリロードする（Unix のみ）
+///
+/// 失敗した場合はエラーをログに出力し、古い鍵セットを使い続ける。
+/// リロードできない構成（JWT_SECRET のみ）では何もしない。
+pub fn reload_on_sighup(keys: JwtKeyRing) {
+    if !keys.is_reloadable() {
+        return;
+    }
+
+    #[cfg(unix)]
+    tokio::spawn(async move {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to install SIGHUP handler");
+                return;
+            }
+        };
+        while hangup.recv().await.is_some() {
+            match keys.reload() {
+                Ok(reloaded) => tracing::info!(
+                    signing_key_id = reloaded.current().id(),
+                    "JWT keys reloaded (SIGHUP)"
+                ),
+                Err(e) => tracing::error!(
+                    error = %e,
+                    "JWT key reload failed; keeping the previous keys"
+                ),
+            }
+        }
+    });
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// テストごとに一意な一時ファイル
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, content: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("jwt-keys-{}-{}", std::process::id(), name));
+            std::fs::write(&path, content).unwrap();
+            Self(path)
+        }
+
+        fn write(&self, content: &str) {
+            std::fs::write(&self.0, content).unwrap();
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn config(secrets_file: Option<PathBuf>, min_secret_len: usize) -> JwtConfig {
+        JwtConfig {
+            secret: "env-secret".to_string(),
+            secrets_file,
+            min_secret_len,
+            expiry_hours: 24,
+            issuer: None,
+        }
+    }
+
+    #[test]
+    fn test_key_ring_without_file_uses_jwt_secret() {
+        let keys = key_ring(&config(None, 0)).unwrap();
+
+        assert!(!keys.is_reloadable());
+        assert_eq!(keys.snapshot().current().id(), application::DEFAULT_KEY_ID);
+    }
+
+    #[test]
+    fn test_key_file_reload_keeps_old_keys_on_error() {
+        let file = TempFile::new("reload", "k1:first-secret\n");
+        let keys = key_ring(&config(Some(file.0.clone()), 0)).unwrap();
+        assert_eq!(keys.snapshot().current().id(), "k1");
+
+        file.write("k2:second-secret\nk1:first-secret\n");
+        keys.reload().unwrap();
+        assert_eq!(keys.snapshot().current().id(), "k2");
+
+        // 壊れた内容・削除されたファイルでは古い鍵を使い続ける
+        file.write("k3\n");
+        assert!(keys.reload().is_err());
+        std::fs::remove_file(&file.0).unwrap();
+        assert!(keys.reload().is_err());
+        assert_eq!(keys.snapshot().current().id(), "k2");
+    }
+
+    #[test]
+    fn test_key_file_enforces_min_secret_len() {
+        let file = TempFile::new("short", "k1:0123456789abcdef0123456789abcdef\nold:short\n");
+
+        let err = key_ring(&config(Some(file.0.clone()), 32)).unwrap_err();
+
+        assert!(err.to_string().contains("\"old\""), "{}", err);
+        assert!(key_ring(&config(Some(file.0.clone()), 0)).is_ok());
+    }
+}
@@ -9,7 +9,7 @@
 // - インフラ層のインスタンス生成（PostgreSQL、Redis、S3）
 // - リポジトリの組み立て（デコレータパターンでキャッシュを追加）
 // - プレゼンテーション層のルーター構築
-// - HTTP サーバー起動（グレースフルシャットダウン対応）
+// - HTTP / HTTPS / 内部用リスナーの起動（server.rs、グレースフルシャットダウン対応）
 //
 // CQRS パターン（Reader/Writer 分離）:
 // - DbPools: Writer（プライマリ）/ Reader（レプリカ）接続プール
@@ -17,8 +17,14 @@
 // - User: UserWriter（Commands）/ UserReader（Queries）
 // - File: FileWriter（Commands）/ FileReader（Queries）
 //
+// サブコマンド（cli.rs）:
+// - serve（デフォルト）: HTTP サーバー
+// - migrate / create-admin / cleanup-orphans: 運用コマンド（commands.rs）
+//   各コマンドは必要な依存だけを作る（例: migrate は PostgreSQL のみ）
+//
 // 依存性注入（DI）:
-// - 具象型（Postgres*, S3*）はここでのみ使用
+// - 具象型（Postgres*, S3*, InMemory*）はここでのみ使用
+// - `serve --backend memory` で外部サービスなしのインメモリ構成に切り替え
 // - 他の層はトレイト経由でアクセス
 // - テスト時はモック実装に差し替え可能
 // =============================================================================
@@ -27,25 +33,39 @@
 // モジュール宣言
 // -----------------------------------------------------------------------------
 
+mod cli;
+mod commands;
 mod config;
+mod jwt_keys;
+mod server;
 
 // -----------------------------------------------------------------------------
 // 外部クレートのインポート
 // -----------------------------------------------------------------------------
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use dotenvy::dotenv;
 use tracing_subscriber::EnvFilter;
 
+use application::{CreateAdminOutcome, JwtKeyRing};
+use axum::Router;
 use infrastructure::{
-    CachedTodoReader, DbPools, PostgresFileReader, PostgresFileWriter, PostgresTodoReader,
-    PostgresTodoWriter, PostgresUserReader, PostgresUserWriter, S3StorageService, TodoCache,
-    TransactionalTodoService,
+    detached_pool, CacheLookup, CachedTodoReader, DbPools, ImportJobService, ImportWorker,
+    ImportWorkerConfig, InMemoryFileReader, InMemoryFileWriter, InMemoryStorage, InMemoryTodoCache,
+    InMemoryTodoReader, InMemoryTodoWriter, InMemoryUserReader, InMemoryUserWriter, MemoryStore,
+    NoopCache, PostgresFileReader, PostgresFileWriter, PostgresReadiness, PostgresTodoReader,
+    PostgresTodoWriter, PostgresUserReader, PostgresUserWriter, ReadinessCheck, RedisReadiness,
+    S3StorageService, TodoCache, TransactionalTodoService,
+};
+use presentation::{
+    create_internal_router, create_router, AppState, BodyLimits, ConcurrencyLimits, CorsConfig,
+    EdgeVerifyConfig, RateLimit, RouterConfig,
 };
-use presentation::{create_router, AppState};
 
-use crate::config::AppConfig;
+use crate::cli::{Command, USAGE};
+use crate::config::{AppConfig, Backend, CacheBackend, DatabaseConfig};
 
 // =============================================================================
 // main 関数
@@ -53,24 +73,10 @@ use crate::config::AppConfig;
 
 /// アプリケーションのメインエントリーポイント
 ///
-/// # 起動手順
-///
-/// 1. .env ファイル読み込み（ローカル開発用）
-/// 2. ログ初期化（JSON 形式）
-/// 3. Config から設定を一括読み込み
-/// 4. PostgreSQL 接続プール作成（CQRS: Reader/Writer 分離）
-/// 5. Redis クライアント作成
-/// 6. S3 ストレージサービス作成
-/// 7. リポジトリ組み立て（DI）
-/// 8. アプリケーション状態作成
-/// 9. ルーター構築
-/// 10. サーバー起動（グレースフルシャットダウン対応）
+/// .env 読み込みとログ初期化の後、サブコマンドを実行する。
+/// 引数が不正な場合は使い方を表示して終了コード 2 で終了する。
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // =========================================================================
-    // 設定の読み込み
-    // =========================================================================
-
     // .env ファイルを読み込む（存在しなくても続行）
     // 本番環境では .env は使用せず、環境変数を直接設定する
     dotenv().ok();
@@ -86,12 +92,116 @@ async fn main() -> anyhow::Result<()> {
         .json()
         .init();
 
+    let command = match Command::parse(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    match command {
+        Command::Serve { backend } => serve(backend).await,
+        Command::Migrate => run_migrate().await,
+        Command::CreateAdmin { email, password } => run_create_admin(&email, password).await,
+        Command::CleanupOrphans {
+            older_than_hours,
+            dry_run,
+        } => run_cleanup_orphans(older_than_hours, dry_run).await,
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+    }
+}
+
+// =============================================================================
+// serve: HTTP サーバー
+// =============================================================================
+
+/// HTTP サーバーを起動する
+///
+/// # 起動手順
+///
+/// 1. Config から設定を一括読み込み
+/// 2. バックエンドごとにアプリケーション状態を組み立て（postgres_state / memory_state）
+///    - postgres: 接続プール・Redis・S3 作成 → リポジトリ組み立て（DI）
+///      → インポートワーカー起動 → アプリケーション状態作成
+///    - memory: インメモリ実装でアプリケーション状態作成
+/// 3. API ルーターと内部用ルーターを作成
+/// 4. リスナー（HTTP / HTTPS / 内部用）を起動（グレースフルシャットダウン対応）
+///
+/// # Errors
+///
+/// 設定エラー、データストアへの接続失敗、ポートのバインド失敗
+async fn serve(backend: Backend) -> anyhow::Result<()> {
     // -------------------------------------------------------------------------
     // Config から設定を一括読み込み
     // -------------------------------------------------------------------------
-    let config = AppConfig::from_env()?;
-    tracing::info!("Configuration loaded");
+    // 問題のある変数は 1 つのエラーにまとめて報告し、起動しない
+    // --backend（コマンドライン引数）でデータストアを選択
+    let config = AppConfig::from_env(backend)?;
+    for warning in &config.warnings {
+        tracing::warn!("{}", warning);
+    }
+    // 実効設定（シークレットは伏せる）: サポートが Pod の設定を確認できるようにする
+    tracing::info!(
+        environment = ?config.environment,
+        backend = ?config.backend,
+        effective = %config.effective_summary(),
+        "Configuration loaded"
+    );
 
+    // =========================================================================
+    // バックエンドごとのセットアップ
+    // =========================================================================
+    // postgres: PostgreSQL + Redis + S3（本番・docker compose）
+    // memory: インメモリ実装（外部サービスなしで `cargo run -- --backend memory`）
+    // JWT 鍵セット（JWT_SECRETS_FILE 設定時は SIGHUP でリロード可能）
+    let keys = jwt_keys::key_ring(&config.jwt)?;
+    jwt_keys::reload_on_sighup(keys.clone());
+
+    let state = match config.backend {
+        Backend::Postgres => postgres_state(&config, keys).await?,
+        Backend::Memory => memory_state(&config, keys)?,
+    };
+
+    // API（HTTP / HTTPS）と内部用（/livez, /readyz, /metrics）は同じ状態を共有する
+    let app = build_router(state.clone(), &config);
+    let internal_app = create_internal_router(state);
+
+    // =========================================================================
+    // サーバー起動
+    // =========================================================================
+    // 全てのアドレスを先にバインドし、証明書を読み込む（失敗したら起動しない）
+    // TLS 証明書は SIGHUP でリロード可能（JWT 鍵セットと同じシグナル）
+    let listeners = server::Listeners::bind(&config.server).await?;
+    if let Some(cert) = listeners.cert() {
+        server::reload_cert_on_sighup(cert);
+    }
+
+    // シャットダウンシグナルで全てのリスナーを停止し、処理中のリクエストの完了を待つ
+    listeners
+        .serve(app, internal_app, shutdown_signal())
+        .await?;
+
+    tracing::info!("Server shutdown complete");
+    Ok(())
+}
+
+// =============================================================================
+// バックエンドごとの組み立て
+// =============================================================================
+
+/// PostgreSQL + S3（+ Redis）でアプリケーション状態を組み立てる
+///
+/// 接続・マイグレーション済みのデータストアを前提とする。
+/// CACHE_BACKEND=none では Redis に接続せず NoopCache を使う。
+///
+/// # Errors
+///
+/// PostgreSQL / Redis / S3 への接続に失敗した場合
+async fn postgres_state(config: &AppConfig, keys: JwtKeyRing) -> anyhow::Result<AppState> {
     // =========================================================================
     // インフラ層のセットアップ（統一 CQRS: Reader/Writer 分離）
     // =========================================================================
@@ -106,19 +216,11 @@ async fn main() -> anyhow::Result<()> {
     .await?;
     tracing::info!("Connected to PostgreSQL (Reader/Writer pools initialized)");
 
-    // -------------------------------------------------------------------------
-    // Redis クライアントを作成
-    // -------------------------------------------------------------------------
-    let redis_client = redis::Client::open(config.redis.url.as_str())?;
-    tracing::info!("Connected to Redis");
-
     // -------------------------------------------------------------------------
     // S3 ストレージサービスを作成
     // -------------------------------------------------------------------------
-    // bucket: &String → &str に自動変換（Deref coercion）
-    // endpoint_url: Option<String> → Option<&str> に変換（as_deref() が必要）
-    let storage_service =
-        S3StorageService::from_config(&config.s3.bucket, config.s3.endpoint_url.as_deref()).await?;
+    // S3Config（リージョン、エンドポイント、認証情報、キープレフィックス）は config.rs で検証済み
+    let storage_service = S3StorageService::new(config.s3.clone()).await?;
 
     // バケットの存在確認と作成（LocalStack 用）
     storage_service.ensure_bucket_exists().await?;
@@ -127,6 +229,32 @@ async fn main() -> anyhow::Result<()> {
     // Arc でラップ（StorageOps トレイトを通じて共有）
     let storage = Arc::new(storage_service);
 
+    // -------------------------------------------------------------------------
+    // キャッシュを作成（CACHE_BACKEND）
+    // -------------------------------------------------------------------------
+    // AppState は依存をトレイトオブジェクトで保持するため、
+    // キャッシュの実装は実行時に選択できる
+    let (cache, cache_check): (Arc<dyn CacheLookup>, Option<Arc<dyn ReadinessCheck>>) =
+        match config.cache_backend {
+            CacheBackend::Redis => {
+                let redis_client = redis::Client::open(config.redis.url.as_str())?;
+                tracing::info!("Connected to Redis");
+
+                // Redis を使う構成のみ /ready で Redis を確認する
+                let redis_check = Arc::new(RedisReadiness::new(redis_client.clone()));
+                let cache = Arc::new(TodoCache::with_ttl(
+                    redis_client,
+                    config.redis.cache_ttl_secs,
+                ));
+                (cache, Some(redis_check))
+            }
+            CacheBackend::None => {
+                // NoopCache: 常にキャッシュミス（CachedTodoReader は PostgreSQL を直接参照）
+                tracing::info!("Cache disabled (CACHE_BACKEND=none)");
+                (Arc::new(NoopCache), None)
+            }
+        };
+
     // =========================================================================
     // リポジトリの組み立て（依存性注入 - 統一 CQRS + キャッシュ）
     // =========================================================================
@@ -134,9 +262,6 @@ async fn main() -> anyhow::Result<()> {
     // TODO Writer（Commands 用）
     let todo_writer = Arc::new(PostgresTodoWriter::new(db_pools.writer.clone()));
 
-    // キャッシュ（Redis）
-    let cache = Arc::new(TodoCache::new(redis_client));
-
     // TODO Reader（Queries 用、キャッシュ付きデコレータ）
     let postgres_reader = PostgresTodoReader::new(db_pools.reader.clone());
     let todo_reader = Arc::new(CachedTodoReader::new(postgres_reader, Arc::clone(&cache)));
@@ -152,6 +277,25 @@ async fn main() -> anyhow::Result<()> {
     // バッチ操作サービス（トランザクション対応）
     let batch_service = TransactionalTodoService::new(db_pools.writer.clone());
 
+    // インポートジョブサービス（非同期インポート）
+    let import_jobs = ImportJobService::new(db_pools.writer.clone());
+
+    // =========================================================================
+    // バックグラウンドワーカーの起動
+    // =========================================================================
+    // ジョブはテーブルに永続化されているため、再起動前に処理中だったジョブも
+    // リース切れ後に再取得して続きから処理する
+    let import_worker = ImportWorker::new(
+        import_jobs.clone(),
+        batch_service.clone(),
+        ImportWorkerConfig {
+            chunk_size: config.import.chunk_size,
+            poll_interval: Duration::from_millis(config.import.poll_interval_ms),
+            lease: Duration::from_secs(config.import.lease_secs),
+        },
+    );
+    tokio::spawn(import_worker.run());
+
     // =========================================================================
     // プレゼンテーション層のセットアップ
     // =========================================================================
@@ -177,37 +321,181 @@ async fn main() -> anyhow::Result<()> {
     // Arc::clone() はポインタのコピー + 参照カウント増加のみで、
     // 内部の大きなデータ（DB プール等）はコピーされない。
     // -------------------------------------------------------------------------
-    let state = AppState::new(
-        todo_writer,
-        todo_reader,
-        cache,
-        user_reader,
-        user_writer,
-        batch_service,
-        storage,
-        file_reader,
-        file_writer,
-        config.jwt.secret.clone(),
-        config.jwt.expiry_hours,
-    );
+    // AppState::builder(): 名前付きセッターで依存を設定
+    // 必須の依存が欠けている場合は build() がその名前を含むエラーを返す
+    let mut builder = AppState::builder()
+        .todo_writer(todo_writer)
+        .todo_reader(todo_reader)
+        .cache(cache)
+        .user_reader(user_reader)
+        .user_writer(user_writer)
+        .batch_service(batch_service)
+        .storage(storage)
+        .file_reader(file_reader)
+        .file_writer(file_writer)
+        .jwt_keys(keys)
+        .jwt_expiry_hours(config.jwt.expiry_hours)
+        .jwt_issuer(config.jwt.issuer.clone())
+        .jwt_algorithm(config.jwt.algorithm)
+        .max_batch_size(config.batch.max_items)
+        .import_jobs(import_jobs)
+        .max_import_items(config.import.max_items)
+        .tenant_scoping(config.tenant_scoping)
+        // /ready: PostgreSQL は常に確認、キャッシュは Redis 使用時のみ
+        .readiness_check(Arc::new(PostgresReadiness::new(db_pools.writer.clone())));
+    if let Some(check) = cache_check {
+        builder = builder.readiness_check(check);
+    }
+    Ok(builder.build()?)
+}
 
-    // ルーターを構築
-    let app = create_router(state, config.edge_secret.clone());
+/// インメモリ実装でアプリケーション状態を組み立てる
+///
+/// 外部サービスに接続しない。データはプロセス内にのみ保持され、再起動で消える。
+/// バッチ操作・インポートは PostgreSQL のトランザクションが必要なため、
+/// 接続先のない遅延プールを渡す（これらのエンドポイントはエラーを返す）。
+/// インポートワーカーは起動しない。
+///
+/// # Errors
+///
+/// AppState の組み立てに失敗した場合
+fn memory_state(config: &AppConfig, keys: JwtKeyRing) -> anyhow::Result<AppState> {
+    // Reader/Writer が共有するデータストア
+    let store = MemoryStore::new();
+    let pool = detached_pool();
+
+    let state = AppState::builder()
+        .todo_writer(Arc::new(InMemoryTodoWriter::new(store.clone())))
+        .todo_reader(Arc::new(InMemoryTodoReader::new(store.clone())))
+        .cache(Arc::new(InMemoryTodoCache::new()))
+        .user_reader(Arc::new(InMemoryUserReader::new(store.clone())))
+        .user_writer(Arc::new(InMemoryUserWriter::new(store.clone())))
+        .batch_service(TransactionalTodoService::new(pool.clone()))
+        .storage(Arc::new(InMemoryStorage::new()))
+        .file_reader(Arc::new(InMemoryFileReader::new(store.clone())))
+        .file_writer(Arc::new(InMemoryFileWriter::new(store)))
+        .jwt_keys(keys)
+        .jwt_expiry_hours(config.jwt.expiry_hours)
+        .jwt_issuer(config.jwt.issuer.clone())
+        .jwt_algorithm(config.jwt.algorithm)
+        .max_batch_size(config.batch.max_items)
+        .import_jobs(ImportJobService::new(pool))
+        .max_import_items(config.import.max_items)
+        .tenant_scoping(config.tenant_scoping)
+        .build()?;
+    tracing::info!("Using in-memory backend (no external services)");
+
+    Ok(state)
+}
 
-    // =========================================================================
-    // サーバー起動
-    // =========================================================================
+/// AppState と設定からルーターを構築する
+///
+/// postgres / memory のどちらのバックエンドでも共通。
+/// 本番のミドルウェア構成（RouterConfig::default()）に環境変数の値を設定する。
+fn build_router(state: AppState, config: &AppConfig) -> Router {
+    create_router(state, router_config(config))
+}
 
-    tracing::info!("Listening on {}", config.server.addr);
+/// AppConfig から RouterConfig を組み立てる
+fn router_config(config: &AppConfig) -> RouterConfig {
+    let mut router_config = RouterConfig::default()
+        // Edge 検証（EDGE_SECRET 未設定なら適用しない、development のみ）
+        .with_edge_verify(config.edge_secret.clone().map(|secret| {
+            EdgeVerifyConfig::new(secret)
+                .with_max_age_secs(config.edge_signature_max_age_secs)
+                .with_legacy_secret(config.accept_legacy_edge_secret)
+        }))
+        // X-Edge-Token の検証（REQUIRE_EDGE_TOKEN=true の場合のみ）
+        .with_edge_token(config.require_edge_token)
+        // ルートグループごとの同時実行制限（バッチ、アップロード）
+        .with_concurrency_limits(ConcurrencyLimits::new(
+            config.concurrency.batch_max_in_flight,
+            config.concurrency.upload_max_in_flight,
+        ))
+        .with_body_limits(BodyLimits {
+            max_body_bytes: config.http.max_body_bytes,
+            max_upload_bytes: config.http.max_upload_bytes,
+        });
+    // CORS・レート制限は設定された場合のみ（通常は Edge 層が担う）
+    if !config.http.cors_allowed_origins.is_empty() {
+        router_config =
+            router_config.with_cors(CorsConfig::new(config.http.cors_allowed_origins.clone()));
+    }
+    if let Some(limit) = &config.http.rate_limit {
+        router_config =
+            router_config.with_rate_limit(RateLimit::new(limit.per_second, limit.burst));
+    }
+    router_config
+}
 
-    let listener = tokio::net::TcpListener::bind(config.server.addr).await?;
+// =============================================================================
+// 運用コマンド
+// =============================================================================
+// 各コマンドは必要な依存だけを作り、処理は commands.rs に委譲する。
 
-    // グレースフルシャットダウン対応でサーバー起動
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+/// 運用コマンド用の PostgreSQL 接続（Writer のみ）
+async fn connect_writer() -> anyhow::Result<sqlx::PgPool> {
+    let database = DatabaseConfig::from_env()?;
+    let pools = DbPools::from_config(&database.writer_url, None).await?;
+    Ok(pools.writer().clone())
+}
 
-    tracing::info!("Server shutdown complete");
+/// migrate: 埋め込みマイグレーションを適用する（Redis・S3 には接続しない）
+async fn run_migrate() -> anyhow::Result<()> {
+    let pool = connect_writer().await?;
+    let count = commands::migrate(&pool).await?;
+    tracing::info!(migrations = count, "Migrations are up to date");
+    Ok(())
+}
+
+/// create-admin: 管理者を作成する（PostgreSQL のみ使用）
+///
+/// パスワードは --password、なければ ADMIN_PASSWORD 環境変数から読み込む
+/// （シェル履歴やプロセス一覧に残さないため、環境変数を推奨）。
+async fn run_create_admin(email: &str, password: Option<String>) -> anyhow::Result<()> {
+    let password = password
+        .or_else(|| std::env::var("ADMIN_PASSWORD").ok())
+        .ok_or_else(|| anyhow::anyhow!("create-admin requires --password or ADMIN_PASSWORD"))?;
+
+    let pool = connect_writer().await?;
+    let outcome = commands::create_admin(
+        Arc::new(PostgresUserReader::new(pool.clone())),
+        Arc::new(PostgresUserWriter::new(pool)),
+        email,
+        &password,
+    )
+    .await?;
+
+    let user = outcome.user();
+    match &outcome {
+        CreateAdminOutcome::Created(_) => tracing::info!(user_id = %user.id, "Admin user created"),
+        CreateAdminOutcome::Promoted(_) => {
+            tracing::info!(user_id = %user.id, "Existing user promoted to admin")
+        }
+        CreateAdminOutcome::AlreadyAdmin(_) => {
+            tracing::info!(user_id = %user.id, "User is already an admin")
+        }
+    }
+    Ok(())
+}
+
+/// cleanup-orphans: 孤立ファイルを削除する（PostgreSQL と S3 のみ使用）
+async fn run_cleanup_orphans(older_than_hours: u32, dry_run: bool) -> anyhow::Result<()> {
+    let s3 = config::s3_config_from_env()?;
+    let pool = connect_writer().await?;
+    let storage = Arc::new(S3StorageService::new(s3).await?);
+
+    let report = commands::cleanup_orphans(pool, storage, older_than_hours, dry_run).await?;
+    tracing::info!(
+        scanned = report.scanned,
+        orphans = report.orphans.len(),
+        deleted = report.deleted,
+        dry_run,
+        "Orphan cleanup complete"
+    );
+    for key in &report.orphans {
+        tracing::info!(storage_path = %key, dry_run, "Orphaned file");
+    }
     Ok(())
 }
 
@@ -0,0 +1,614 @@
+// =============================================================================
+// api/src/server.rs: リスナーの起動とグレースフルシャットダウン
+// =============================================================================
+// ServerConfig に従って最大 3 つのリスナーを起動する。
+//
+// リスナー:
+// - HTTP（APP_ADDR）: API 全体（create_router）
+// - HTTPS（TLS_ADDR）: API 全体。リバースプロキシなしで HTTPS を終端する構成向け
+// - 内部（INTERNAL_ADDR）: /livez, /readyz, /metrics のみ（create_internal_router）
+//
+// TLS:
+// - rustls（tokio-rustls）。証明書・秘密鍵は PEM ファイルから読み込む
+// - SIGHUP で証明書を再読み込みする（cert-manager などによる更新に追従）
+//   読み込みに失敗した場合は古い証明書を使い続ける
+// - TLS ハンドシェイクは接続ごとのタスクで行う（遅いクライアントが accept を止めない）
+//
+// グレースフルシャットダウン:
+// - shutdown（main.rs の shutdown_signal: SIGTERM / Ctrl+C）で全てのリスナーが
+//   新規接続の受け付けを止め、処理中のリクエストの完了を待ってから終了する
+// - いずれかのリスナーがエラーで終了した場合も、他のリスナーを停止する
+// =============================================================================
+
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use axum::serve::Listener;
+use axum::Router;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinSet;
+use tokio_rustls::rustls::crypto::{aws_lc_rs, CryptoProvider};
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::{ClientHello, ResolvesServerCert};
+use tokio_rustls::rustls::sign::CertifiedKey;
+use tokio_rustls::rustls::ServerConfig as RustlsConfig;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+use crate::config::{ServerConfig, TlsConfig};
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// TLS ハンドシェイクのタイムアウト（完了しない接続を破棄する）
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// ハンドシェイク済みで axum に渡す前の接続の上限（超えるとハンドシェイク側が待つ）
+const PENDING_CONNECTIONS: usize = 128;
+
+// =============================================================================
+// 証明書（SIGHUP でリロード可能）
+// =============================================================================
+
+/// リロード可能なサーバー証明書
+///
+/// rustls の ResolvesServerCert として ServerConfig に渡す。
+/// リロードは証明書だけを差し替えるため、処理中の接続には影響しない。
+#[derive(Debug)]
+pub struct ReloadableCert {
+    /// 証明書チェーン（PEM）
+    cert_file: PathBuf,
+    /// 秘密鍵（PEM）
+    key_file: PathBuf,
+    /// 暗号プロバイダ（秘密鍵の読み込みに使用）
+    provider: Arc<CryptoProvider>,
+    /// 現在の証明書
+    current: RwLock<Arc<CertifiedKey>>,
+}
+
+impl ReloadableCert {
+    /// 証明書と秘密鍵を読み込む
+    ///
+    /// # Errors
+    ///
+    /// ファイルが読めない、PEM が不正、秘密鍵が証明書と一致しない場合
+    pub fn load(tls: &TlsConfig, provider: Arc<CryptoProvider>) -> anyhow::Result<Arc<Self>> {
+        let current = load_certified_key(&tls.cert_file, &tls.key_file, &provider)?;
+        Ok(Arc::new(Self {
+            cert_file: tls.cert_file.clone(),
+            key_file: tls.key_file.clone(),
+            provider,
+            current: RwLock::new(Arc::new(current)),
+        }))
+    }
+
+    /// 証明書と秘密鍵を再読み込みする
+    ///
+    /// # Errors
+    ///
+    /// 読み込みに失敗した場合（古い証明書を使い続ける）
+    pub fn reload(&self) -> anyhow::Result<()> {
+        let reloaded = load_certified_key(&self.cert_file, &self.key_file, &self.provider)?;
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(reloaded);
+        Ok(())
+    }
+}
+
+impl ResolvesServerCert for ReloadableCert {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(Arc::clone(
+            &self.current.read().unwrap_or_else(|e| e.into_inner()),
+        ))
+    }
+}
+
+/// PEM ファイルから証明書チェーンと秘密鍵を読み込む
+fn load_certified_key(
+    cert_file: &Path,
+    key_file: &Path,
+    provider: &CryptoProvider,
+) -> anyhow::Result<CertifiedKey> {
+    let certs = CertificateDer::pem_file_iter(cert_file)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| anyhow::anyhow!("TLS_CERT_FILE {}: {}", cert_file.display(), e))?;
+    if certs.is_empty() {
+        anyhow::bail!(
+            "TLS_CERT_FILE {}: no certificate found",
+            cert_file.display()
+        );
+    }
+    let key = PrivateKeyDer::from_pem_file(key_file)
+        .map_err(|e| anyhow::anyhow!("TLS_KEY_FILE {}: {}", key_file.display(), e))?;
+
+    CertifiedKey::from_der(certs, key, provider)
+        .map_err(|e| anyhow::anyhow!("TLS_KEY_FILE {}: {}", key_file.display(), e))
+}
+
+/// 証明書から TLS アクセプターを作成する
+///
+/// ALPN は http/1.1 のみ（axum::serve は HTTP/2 feature なしでビルドしている）。
+fn tls_acceptor(
+    cert: Arc<ReloadableCert>,
+    provider: Arc<CryptoProvider>,
+) -> anyhow::Result<TlsAcceptor> {
+    let mut config = RustlsConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_cert_resolver(cert);
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// SIGHUP を受け取るたびに証明書をリロードする（Unix のみ）
+///
+/// JWT 鍵セットのリロード（jwt_keys::reload_on_sighup）と同じシグナルを使う。
+pub fn reload_cert_on_sighup(cert: Arc<ReloadableCert>) {
+    #[cfg(unix)]
+    tokio::spawn(async move {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to install SIGHUP handler");
+                return;
+            }
+        };
+        while hangup.recv().await.is_some() {
+            match cert.reload() {
+                Ok(()) => tracing::info!(
+                    cert_file = %cert.cert_file.display(),
+                    "TLS certificate reloaded (SIGHUP)"
+                ),
+                Err(e) => tracing::error!(
+                    error = %e,
+                    "TLS certificate reload failed; keeping the previous certificate"
+                ),
+            }
+        }
+    });
+    #[cfg(not(unix))]
+    let _ = cert;
+}
+
+// =============================================================================
+// TlsListener: axum::serve に渡す TLS リスナー
+// =============================================================================
+
+/// TLS リスナー
+///
+/// TCP の accept とハンドシェイクはバックグラウンドタスクで行い、
+/// ハンドシェイクが完了した接続だけを axum::serve に渡す。
+/// リスナーが破棄されると（サーバー終了時）バックグラウンドタスクも終了する。
+pub struct TlsListener {
+    /// ハンドシェイク済みの接続
+    connections: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+    /// バインドしたアドレス
+    local_addr: SocketAddr,
+}
+
+impl TlsListener {
+    /// TCP リスナーを TLS でラップする
+    ///
+    /// # Errors
+    ///
+    /// バインドしたアドレスを取得できない場合
+    pub fn new(tcp: TcpListener, acceptor: TlsAcceptor) -> io::Result<Self> {
+        let local_addr = tcp.local_addr()?;
+        let (sender, connections) = mpsc::channel(PENDING_CONNECTIONS);
+        tokio::spawn(accept_tls(tcp, acceptor, sender));
+        Ok(Self {
+            connections,
+            local_addr,
+        })
+    }
+}
+
+/// TCP 接続を受け付け、接続ごとのタスクで TLS ハンドシェイクを行う
+async fn accept_tls(
+    tcp: TcpListener,
+    acceptor: TlsAcceptor,
+    sender: mpsc::Sender<(TlsStream<TcpStream>, SocketAddr)>,
+) {
+    loop {
+        let (stream, peer) = tokio::select! {
+            // TlsListener が破棄された（サーバー終了）
+            _ = sender.closed() => return,
+            accepted = tcp.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    // EMFILE などは一時的なエラーとして待ってから再試行する
+                    tracing::warn!(error = %e, "Failed to accept TCP connection");
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+            },
+        };
+
+        let acceptor = acceptor.clone();
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                Ok(Ok(tls)) => {
+                    let _ = sender.send((tls, peer)).await;
+                }
+                Ok(Err(e)) => tracing::debug!(peer = %peer, error = %e, "TLS handshake failed"),
+                Err(_) => tracing::debug!(peer = %peer, "TLS handshake timed out"),
+            }
+        });
+    }
+}
+
+impl Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.connections.recv().await {
+            Some(connection) => connection,
+            // バックグラウンドタスクは TlsListener より先に終了しない
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+// =============================================================================
+// Listeners: バインド済みのリスナー
+// =============================================================================
+
+/// バインド済みのリスナー
+///
+/// `bind()` で全てのアドレスを先にバインドする（ポートの競合などで起動時に失敗させる）。
+pub struct Listeners {
+    /// HTTP（API 全体）
+    http: Option<TcpListener>,
+    /// HTTPS（API 全体）
+    https: Option<TlsListener>,
+    /// 内部用（監視エンドポイントのみ）
+    internal: Option<TcpListener>,
+    /// HTTPS の証明書（SIGHUP でのリロード用）
+    cert: Option<Arc<ReloadableCert>>,
+}
+
+impl Listeners {
+    /// 設定に従って全てのリスナーをバインドする
+    ///
+    /// # Errors
+    ///
+    /// バインドに失敗した場合、または証明書・秘密鍵を読み込めない場合
+    pub async fn bind(config: &ServerConfig) -> anyhow::Result<Self> {
+        let http = match config.addr {
+            Some(addr) => Some(bind_tcp("APP_ADDR", addr).await?),
+            None => None,
+        };
+
+        let (https, cert) = match &config.tls {
+            Some(tls) => {
+                let provider = Arc::new(aws_lc_rs::default_provider());
+                let cert = ReloadableCert::load(tls, Arc::clone(&provider))?;
+                let acceptor = tls_acceptor(Arc::clone(&cert), provider)?;
+                let tcp = bind_tcp("TLS_ADDR", tls.addr).await?;
+                (Some(TlsListener::new(tcp, acceptor)?), Some(cert))
+            }
+            None => (None, None),
+        };
+
+        let internal = match config.internal_addr {
+            Some(addr) => Some(bind_tcp("INTERNAL_ADDR", addr).await?),
+            None => None,
+        };
+
+        Ok(Self {
+            http,
+            https,
+            internal,
+            cert,
+        })
+    }
+
+    /// HTTPS の証明書（TLS 無効なら None）
+    pub fn cert(&self) -> Option<Arc<ReloadableCert>> {
+        self.cert.clone()
+    }
+
+    /// 全てのリスナーでサーバーを起動し、shutdown の完了後に全ての接続の終了を待つ
+    ///
+    /// # Arguments
+    ///
+    /// * `app` - API のルーター（HTTP / HTTPS）
+    /// * `internal_app` - 内部用ルーター（/livez, /readyz, /metrics）
+    /// * `shutdown` - 完了するとグレースフルシャットダウンを開始する Future
+    ///
+    /// # Errors
+    ///
+    /// いずれかのリスナーがエラーで終了した場合（他のリスナーも停止してから返す）
+    pub async fn serve(
+        self,
+        app: Router,
+        internal_app: Router,
+        shutdown: impl Future<Output = ()> + Send + 'static,
+    ) -> anyhow::Result<()> {
+        // 全てのリスナーが購読するシャットダウン通知
+        let (stop, stopped) = watch::channel(false);
+        let signal_stop = stop.clone();
+        tokio::spawn(async move {
+            shutdown.await;
+            let _ = signal_stop.send(true);
+        });
+
+        let mut servers = JoinSet::new();
+        if let Some(listener) = self.http {
+            log_listening(&listener.local_addr()?, "http", "api");
+            servers.spawn(run(listener, app.clone(), stopped.clone()));
+        }
+        if let Some(listener) = self.https {
+            log_listening(&listener.local_addr, "https", "api");
+            servers.spawn(run(listener, app, stopped.clone()));
+        }
+        if let Some(listener) = self.internal {
+            log_listening(
+                &listener.local_addr()?,
+                "http",
+                "internal (/livez, /readyz, /metrics)",
+            );
+            servers.spawn(run(listener, internal_app, stopped));
+        }
+
+        let mut result = Ok(());
+        while let Some(finished) = servers.join_next().await {
+            let finished = finished
+                .map_err(anyhow::Error::from)
+                .and_then(|served| served.map_err(anyhow::Error::from));
+            if let Err(e) = finished {
+                // 1 つでも異常終了したら他のリスナーも停止する
+                tracing::error!(error = %e, "Listener failed; shutting down the other listeners");
+                let _ = stop.send(true);
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// TCP リスナーをバインドする（エラーには環境変数名を含める）
+async fn bind_tcp(key: &str, addr: SocketAddr) -> anyhow::Result<TcpListener> {
+    TcpListener::bind(addr)
+        .await
+        .map_err(|e| anyhow::anyhow!("{}: cannot bind {}: {}", key, addr, e))
+}
+
+/// バインドしたアドレスとプロトコルをログに出力する
+fn log_listening(addr: &SocketAddr, protocol: &str, routes: &str) {
+    tracing::info!(addr = %addr, protocol, routes, "Listening on {}://{}", protocol, addr);
+}
+
+/// 1 つのリスナーでサーバーを実行する（シャットダウン通知で新規接続を止め、接続の完了を待つ）
+async fn run<L>(listener: L, app: Router, mut stopped: watch::Receiver<bool>) -> io::Result<()>
+where
+    L: Listener,
+    L::Addr: std::fmt::Debug,
+{
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            let _ = stopped.wait_for(|stopped| *stopped).await;
+        })
+        .await
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::routing::get;
+    use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+    use tokio::sync::oneshot;
+    use tokio_rustls::rustls::pki_types::ServerName;
+    use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+    use tokio_rustls::TlsConnector;
+
+    /// テスト用の自己署名証明書（localhost / 127.0.0.1、testdata/tls/README.md 参照）
+    fn testdata(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("testdata/tls")
+            .join(name)
+    }
+
+    /// 証明書と秘密鍵をテストごとの一時ファイルにコピーする（リロードのテスト用）
+    struct TempCert {
+        cert_file: PathBuf,
+        key_file: PathBuf,
+    }
+
+    impl TempCert {
+        fn new(name: &str, variant: &str) -> Self {
+            let dir = std::env::temp_dir();
+            let prefix = format!("server-{}-{}", std::process::id(), name);
+            let cert = Self {
+                cert_file: dir.join(format!("{}-cert.pem", prefix)),
+                key_file: dir.join(format!("{}-key.pem", prefix)),
+            };
+            cert.copy_from(variant);
+            cert
+        }
+
+        fn copy_from(&self, variant: &str) {
+            std::fs::copy(testdata(&format!("cert-{}.pem", variant)), &self.cert_file).unwrap();
+            std::fs::copy(testdata(&format!("key-{}.pem", variant)), &self.key_file).unwrap();
+        }
+    }
+
+    impl Drop for TempCert {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.cert_file);
+            let _ = std::fs::remove_file(&self.key_file);
+        }
+    }
+
+    fn local() -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], 0))
+    }
+
+    /// GET リクエストを送信し、レスポンス全体を文字列で返す
+    async fn request<S: AsyncRead + AsyncWrite + Unpin>(mut stream: S, path: &str) -> String {
+        let request = format!(
+            "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+            path
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        String::from_utf8(response).unwrap()
+    }
+
+    async fn get_http(addr: SocketAddr, path: &str) -> String {
+        request(TcpStream::connect(addr).await.unwrap(), path).await
+    }
+
+    /// 指定した証明書だけを信頼する TLS クライアントで接続する
+    async fn connect_tls(
+        addr: SocketAddr,
+        trusted: &str,
+    ) -> io::Result<tokio_rustls::client::TlsStream<TcpStream>> {
+        let mut roots = RootCertStore::empty();
+        roots
+            .add(CertificateDer::from_pem_file(testdata(trusted)).unwrap())
+            .unwrap();
+        let config = ClientConfig::builder_with_provider(Arc::new(aws_lc_rs::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let stream = TcpStream::connect(addr).await?;
+        TlsConnector::from(Arc::new(config))
+            .connect(ServerName::try_from("localhost").unwrap(), stream)
+            .await
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/health", get(|| async { "api" }))
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_millis(300)).await;
+                    "slow"
+                }),
+            )
+    }
+
+    fn internal_app() -> Router {
+        Router::new().route("/livez", get(|| async { "internal" }))
+    }
+
+    /// 内部用リスナーは内部用ルーターのみ、シャットダウンでは処理中のリクエストを待つ
+    #[tokio::test]
+    async fn test_http_and_internal_listeners_drain_on_shutdown() {
+        let config = ServerConfig {
+            addr: Some(local()),
+            tls: None,
+            internal_addr: Some(local()),
+        };
+        let listeners = Listeners::bind(&config).await.unwrap();
+        let http = listeners.http.as_ref().unwrap().local_addr().unwrap();
+        let internal = listeners.internal.as_ref().unwrap().local_addr().unwrap();
+
+        let (stop, stopped) = oneshot::channel::<()>();
+        let server = tokio::spawn(listeners.serve(app(), internal_app(), async {
+            let _ = stopped.await;
+        }));
+
+        assert!(get_http(http, "/health").await.ends_with("api"));
+        assert!(get_http(internal, "/livez").await.ends_with("internal"));
+        assert!(get_http(internal, "/health")
+            .await
+            .starts_with("HTTP/1.1 404"));
+
+        // 処理中のリクエストはシャットダウン後も完了する
+        let slow = tokio::spawn(get_http(http, "/slow"));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        stop.send(()).unwrap();
+
+        assert!(slow.await.unwrap().ends_with("slow"));
+        tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .expect("all listeners stop")
+            .unwrap()
+            .unwrap();
+        assert!(TcpStream::connect(internal).await.is_err());
+    }
+
+    /// HTTPS のみの構成で、リロード後は新しい証明書で応答する
+    #[tokio::test]
+    async fn test_https_listener_reloads_certificate() {
+        let files = TempCert::new("reload", "a");
+        let config = ServerConfig {
+            addr: None,
+            tls: Some(TlsConfig {
+                addr: local(),
+                cert_file: files.cert_file.clone(),
+                key_file: files.key_file.clone(),
+            }),
+            internal_addr: None,
+        };
+        let listeners = Listeners::bind(&config).await.unwrap();
+        assert!(listeners.http.is_none());
+        let https = listeners.https.as_ref().unwrap().local_addr;
+        let cert = listeners.cert().unwrap();
+
+        let (stop, stopped) = oneshot::channel::<()>();
+        let server = tokio::spawn(listeners.serve(app(), internal_app(), async {
+            let _ = stopped.await;
+        }));
+
+        let stream = connect_tls(https, "cert-a.pem").await.unwrap();
+        assert!(request(stream, "/health").await.ends_with("api"));
+
+        // 壊れたファイルではリロードに失敗し、古い証明書を使い続ける
+        std::fs::write(&files.key_file, "not a key").unwrap();
+        assert!(cert.reload().is_err());
+        assert!(connect_tls(https, "cert-a.pem").await.is_ok());
+
+        files.copy_from("b");
+        cert.reload().unwrap();
+        assert!(connect_tls(https, "cert-a.pem").await.is_err());
+        let stream = connect_tls(https, "cert-b.pem").await.unwrap();
+        assert!(request(stream, "/health").await.ends_with("api"));
+
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bind_rejects_mismatched_key() {
+        let config = ServerConfig {
+            addr: None,
+            tls: Some(TlsConfig {
+                addr: local(),
+                cert_file: testdata("cert-a.pem"),
+                key_file: testdata("key-b.pem"),
+            }),
+            internal_addr: None,
+        };
+
+        let err = Listeners::bind(&config).await.err().unwrap();
+
+        assert!(err.to_string().starts_with("TLS_KEY_FILE"), "{}", err);
+    }
+}
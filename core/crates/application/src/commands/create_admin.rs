@@ -0,0 +1,128 @@
+// =============================================================================
+// application/src/commands/create_admin.rs: 管理者作成コマンド
+// =============================================================================
+// 軽量 CQRS: 状態変更操作（Command）
+// Writer DB プールを使用。
+//
+// 運用コマンド（api create-admin）から呼び出す。HTTP エンドポイントは持たない。
+//
+// 動作:
+// - メールアドレスのユーザーが存在しない: 管理者として作成
+//   （通常の登録と同じバリデーションと bcrypt ハッシュ化）
+// - 既に存在する: ロールを admin に昇格（パスワードは変更しない）
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// 標準ライブラリのインポート
+// -----------------------------------------------------------------------------
+
+use std::sync::Arc; // スレッド安全な参照カウントスマートポインタ
+
+// -----------------------------------------------------------------------------
+// 外部クレートのインポート
+// -----------------------------------------------------------------------------
+
+use domain::{DomainError, Role, User, UserReader, UserWriter}; // ドメイン層の型
+use tracing::info; // 構造化ログ
+
+// 登録と同じハッシュ化
+use crate::services::auth_service::hash_password;
+
+// =============================================================================
+// 結果型
+// =============================================================================
+
+/// 管理者作成の結果
+#[derive(Debug, Clone, PartialEq)]
+pub enum CreateAdminOutcome {
+    /// 新しい管理者を作成した
+    Created(User),
+    /// 既存ユーザーを管理者に昇格した
+    Promoted(User),
+    /// 既に管理者だった（変更なし）
+    AlreadyAdmin(User),
+}
+
+impl CreateAdminOutcome {
+    /// 対象のユーザー
+    pub fn user(&self) -> &User {
+        match self {
+            Self::Created(user) | Self::Promoted(user) | Self::AlreadyAdmin(user) => user,
+        }
+    }
+}
+
+// =============================================================================
+// 管理者作成コマンド構造体
+// =============================================================================
+
+/// 管理者作成コマンド
+///
+/// # トレイトオブジェクト
+///
+/// - `user_reader: Arc<dyn UserReader>` - 既存ユーザーの検索
+/// - `user_writer: Arc<dyn UserWriter>` - 作成・昇格
+#[derive(Clone)]
+pub struct CreateAdminCommand {
+    /// ユーザー読み取りリポジトリ
+    user_reader: Arc<dyn UserReader>,
+
+    /// ユーザー書き込みリポジトリ
+    user_writer: Arc<dyn UserWriter>,
+}
+
+// -----------------------------------------------------------------------------
+// CreateAdminCommand の実装
+// -----------------------------------------------------------------------------
+
+impl CreateAdminCommand {
+    /// 新しいコマンドを作成
+    ///
+    /// # Arguments
+    /// * `user_reader` - UserReader の共有参照
+    /// * `user_writer` - UserWriter の共有参照
+    pub fn new(user_reader: Arc<dyn UserReader>, user_writer: Arc<dyn UserWriter>) -> Self {
+        Self {
+            user_reader,
+            user_writer,
+        }
+    }
+
+    /// 管理者を作成する（既存ユーザーは昇格する）
+    ///
+    /// # Arguments
+    /// * `email` - メールアドレス
+    /// * `password` - パスワード（平文、新規作成時のみ使用）
+    ///
+    /// # Returns
+    /// * `Ok(CreateAdminOutcome)` - 作成・昇格・変更なしのいずれか
+    /// * `Err(DomainError::Validation)` - メールアドレスまたはパスワードが不正
+    /// * `Err(DomainError::Repository)` - DB エラー
+    pub async fn execute(
+        &self,
+        email: &str,
+        password: &str,
+    ) -> Result<CreateAdminOutcome, DomainError> {
+        // 1. バリデーション（通常の登録と同じ規則）
+        let email = User::validate_email(email)?;
+        User::validate_password(password)?;
+
+        // 2. 既存ユーザーは昇格のみ（パスワードは変更しない）
+        if let Some(mut user) = self.user_reader.find_by_email(&email).await? {
+            if !user.promote_to_admin() {
+                return Ok(CreateAdminOutcome::AlreadyAdmin(user));
+            }
+            let promoted = self.user_writer.update(&user).await?;
+            info!(user_id = %promoted.id, email = %email, "User promoted to admin");
+            return Ok(CreateAdminOutcome::Promoted(promoted));
+        }
+
+        // 3. 新規作成（bcrypt でハッシュ化）
+        let mut user = User::new(email.clone(), hash_password(password)?, None);
+        user.role = Role::Admin;
+        let created = self.user_writer.create(&user).await?;
+        info!(user_id = %created.id, email = %email, "Admin user created");
+
+        Ok(CreateAdminOutcome::Created(created))
+    }
+}
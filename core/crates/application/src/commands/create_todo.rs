@@ -54,58 +54,40 @@ use crate::dto::CreateTodoDto;
 /// クリーンアーキテクチャでは、コマンドがアプリケーション固有の
 /// ビジネスロジックをカプセル化する。
 ///
-/// # ジェネリクス
+/// # トレイトオブジェクト
 ///
-/// - `W: TodoWriter` - 書き込みリポジトリの型
-/// - `C: TodoCacheOps` - キャッシュ操作の型
+/// - `writer: Arc<dyn TodoWriter>` - 書き込みリポジトリ
+/// - `cache: Option<Arc<dyn TodoCacheOps>>` - キャッシュ操作
 ///
-/// トレイト境界を使うことで、任意の実装を受け入れる。
+/// トレイトオブジェクトを使うことで、任意の実装を受け入れる。
 /// テスト時にはモック実装を注入できる（依存性注入）。
+/// 呼び出しは vtable 経由の動的ディスパッチになるが、
+/// DB・キャッシュへの I/O に比べて無視できるコスト。
 ///
 /// # Arc の役割
 ///
 /// 複数のリクエストが同時に処理される Web サーバーでは、
 /// Arc（Atomic Reference Counted）で Writer を共有する必要がある。
-pub struct CreateTodoCommand<W: TodoWriter, C: TodoCacheOps> {
+#[derive(Clone)]
+pub struct CreateTodoCommand {
     /// 書き込みリポジトリ（Arc でラップして共有可能に）
-    writer: Arc<W>,
+    writer: Arc<dyn TodoWriter>,
 
     /// キャッシュ操作（オプショナル - キャッシュなしでも動作可能）
-    cache: Option<Arc<C>>,
-}
-
-// -----------------------------------------------------------------------------
-// Clone トレイト実装
-// -----------------------------------------------------------------------------
-
-/// Clone トレイトの手動実装
-///
-/// #[derive(Clone)] を使わない理由:
-/// - derive(Clone) は W: Clone と C: Clone を要求する
-/// - しかし Arc<W> は W: Clone がなくてもクローン可能
-/// - 手動実装により、不要な制約を避けられる
-impl<W: TodoWriter, C: TodoCacheOps> Clone for CreateTodoCommand<W, C> {
-    fn clone(&self) -> Self {
-        Self {
-            // Arc::clone は参照カウントをインクリメントするだけ（効率的）
-            writer: Arc::clone(&self.writer),
-            // Option::as_ref() で参照を取得し、map で Some の場合のみクローン
-            cache: self.cache.as_ref().map(Arc::clone),
-        }
-    }
+    cache: Option<Arc<dyn TodoCacheOps>>,
 }
 
 // -----------------------------------------------------------------------------
 // CreateTodoCommand の実装
 // -----------------------------------------------------------------------------
 
-impl<W: TodoWriter, C: TodoCacheOps> CreateTodoCommand<W, C> {
+impl CreateTodoCommand {
     /// 新しいコマンドを作成
     ///
     /// # Arguments
     /// * `writer` - TodoWriter の共有参照（Arc でラップ）
     /// * `cache` - オプションのキャッシュ（Write-Through 用）
-    pub fn new(writer: Arc<W>, cache: Option<Arc<C>>) -> Self {
+    pub fn new(writer: Arc<dyn TodoWriter>, cache: Option<Arc<dyn TodoCacheOps>>) -> Self {
         Self { writer, cache }
     }
 
@@ -120,13 +102,31 @@ impl<W: TodoWriter, C: TodoCacheOps> CreateTodoCommand<W, C> {
     /// * `Err(DomainError::Validation)` - タイトルが空または長すぎる
     /// * `Err(DomainError::Repository)` - DB エラー
     pub async fn execute(&self, user_id: Uuid, dto: CreateTodoDto) -> Result<Todo, DomainError> {
+        self.execute_with_tenant(user_id, None, dto).await
+    }
+
+    /// テナント ID を記録して TODO を作成する
+    ///
+    /// # Arguments
+    /// * `user_id` - 所有者のユーザー ID（JWT から抽出）
+    /// * `tenant_id` - 作成時のテナント ID（None ならテナントなし）
+    /// * `dto` - 作成リクエスト DTO
+    ///
+    /// # Returns
+    /// `execute` と同じ
+    pub async fn execute_with_tenant(
+        &self,
+        user_id: Uuid,
+        tenant_id: Option<Uuid>,
+        dto: CreateTodoDto,
+    ) -> Result<Todo, DomainError> {
         // 1. バリデーション（ドメインロジックを呼び出す）
         // Todo::validate_title はタイトルの長さと空白をチェック
         let title = Todo::validate_title(&dto.title)?;
 
-        // 2. エンティティ作成（user_id を含む）
+        // 2. エンティティ作成（user_id と tenant_id を含む）
         // Todo::new は UUID を生成し、作成日時を設定
-        let todo = Todo::new(user_id, title, dto.description);
+        let todo = Todo::new(user_id, title, dto.description).with_tenant(tenant_id);
 
         // 3. 永続化（Writer に委譲）
         // INSERT クエリを実行し、作成された TODO を返す
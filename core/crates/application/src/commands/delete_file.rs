@@ -36,15 +36,14 @@ use uuid::Uuid;
 
 /// ファイル削除コマンド
 ///
-/// # ジェネリクス
-///
-/// - `TR: TodoReader` - TODO 読み取りの型（所有者確認用）
-/// - `S: StorageOps` - ストレージ操作の型
-///
 /// # トレイトオブジェクト
 ///
-/// - `file_reader: Arc<dyn FileReader>` - AppState と同じくトレイトオブジェクトを使用
-/// - `file_writer: Arc<dyn FileWriter>` - AppState と同じくトレイトオブジェクトを使用
+/// AppState と同じく、依存はすべてトレイトオブジェクトで受け取る。
+///
+/// - `file_reader: Arc<dyn FileReader>` - ファイルメタデータ読み取り
+/// - `file_writer: Arc<dyn FileWriter>` - ファイルメタデータ書き込み
+/// - `todo_reader: Arc<dyn TodoReader>` - TODO 読み取り（所有者確認用）
+/// - `storage: Arc<dyn StorageOps>` - ストレージ操作
 ///
 /// # アクセス制御
 ///
@@ -61,49 +60,35 @@ use uuid::Uuid;
 /// 逆の順序だと、DB 削除後にストレージ削除失敗した場合、
 /// 参照できないのにストレージにファイルが残る問題は同じだが、
 /// DB レコードがないためリカバリが難しくなる。
-pub struct DeleteFileCommand<TR: TodoReader, S: StorageOps> {
+#[derive(Clone)]
+pub struct DeleteFileCommand {
     /// ファイルメタデータ読み取り（トレイトオブジェクト）
     file_reader: Arc<dyn FileReader>,
     /// ファイルメタデータ書き込み（トレイトオブジェクト）
     file_writer: Arc<dyn FileWriter>,
     /// TODO 読み取り（所有者確認用）
-    todo_reader: Arc<TR>,
+    todo_reader: Arc<dyn TodoReader>,
     /// ストレージ操作
-    storage: Arc<S>,
-}
-
-// -----------------------------------------------------------------------------
-// Clone トレイト実装
-// -----------------------------------------------------------------------------
-
-impl<TR: TodoReader, S: StorageOps> Clone for DeleteFileCommand<TR, S> {
-    fn clone(&self) -> Self {
-        Self {
-            file_reader: Arc::clone(&self.file_reader),
-            file_writer: Arc::clone(&self.file_writer),
-            todo_reader: Arc::clone(&self.todo_reader),
-            storage: Arc::clone(&self.storage),
-        }
-    }
+    storage: Arc<dyn StorageOps>,
 }
 
 // -----------------------------------------------------------------------------
 // DeleteFileCommand の実装
 // -----------------------------------------------------------------------------
 
-impl<TR: TodoReader, S: StorageOps> DeleteFileCommand<TR, S> {
+impl DeleteFileCommand {
     /// 新しいコマンドを作成
     ///
     /// # Arguments
     /// * `file_reader` - FileReader のトレイトオブジェクト
     /// * `file_writer` - FileWriter のトレイトオブジェクト
-    /// * `todo_reader` - TodoReader の共有参照
-    /// * `storage` - StorageOps の共有参照
+    /// * `todo_reader` - TodoReader のトレイトオブジェクト
+    /// * `storage` - StorageOps のトレイトオブジェクト
     pub fn new(
         file_reader: Arc<dyn FileReader>,
         file_writer: Arc<dyn FileWriter>,
-        todo_reader: Arc<TR>,
-        storage: Arc<S>,
+        todo_reader: Arc<dyn TodoReader>,
+        storage: Arc<dyn StorageOps>,
     ) -> Self {
         Self {
             file_reader,
@@ -38,38 +38,26 @@ use uuid::Uuid; // 一意識別子
 /// # CASCADE 削除
 /// DB スキーマで files.todo_id に ON DELETE CASCADE を設定済み。
 /// TODO 削除時、関連ファイルメタデータも自動削除される。
-pub struct DeleteTodoCommand<W: TodoWriter, C: TodoCacheOps> {
+#[derive(Clone)]
+pub struct DeleteTodoCommand {
     /// 書き込みリポジトリ
-    writer: Arc<W>,
+    writer: Arc<dyn TodoWriter>,
 
     /// キャッシュ操作（オプショナル）
-    cache: Option<Arc<C>>,
-}
-
-// -----------------------------------------------------------------------------
-// Clone トレイト実装
-// -----------------------------------------------------------------------------
-
-impl<W: TodoWriter, C: TodoCacheOps> Clone for DeleteTodoCommand<W, C> {
-    fn clone(&self) -> Self {
-        Self {
-            writer: Arc::clone(&self.writer),
-            cache: self.cache.as_ref().map(Arc::clone),
-        }
-    }
+    cache: Option<Arc<dyn TodoCacheOps>>,
 }
 
 // -----------------------------------------------------------------------------
 // DeleteTodoCommand の実装
 // -----------------------------------------------------------------------------
 
-impl<W: TodoWriter, C: TodoCacheOps> DeleteTodoCommand<W, C> {
+impl DeleteTodoCommand {
     /// 新しいコマンドを作成
     ///
     /// # Arguments
     /// * `writer` - TodoWriter の共有参照（Arc でラップ）
     /// * `cache` - オプションのキャッシュ（無効化用）
-    pub fn new(writer: Arc<W>, cache: Option<Arc<C>>) -> Self {
+    pub fn new(writer: Arc<dyn TodoWriter>, cache: Option<Arc<dyn TodoCacheOps>>) -> Self {
         Self { writer, cache }
     }
 
@@ -21,6 +21,9 @@
 // サブモジュールの宣言
 // -----------------------------------------------------------------------------
 
+/// 管理者作成コマンド（運用コマンド用）
+mod create_admin;
+
 /// TODO 作成コマンド
 mod create_todo;
 
@@ -40,6 +43,9 @@ mod update_todo;
 // 再エクスポート（Re-export）
 // -----------------------------------------------------------------------------
 
+/// CreateAdminCommand, CreateAdminOutcome を公開
+pub use create_admin::{CreateAdminCommand, CreateAdminOutcome};
+
 /// CreateTodoCommand を公開
 pub use create_todo::CreateTodoCommand;
 
@@ -56,38 +56,26 @@ use crate::dto::UpdateTodoDto; // 更新リクエスト DTO
 /// - パフォーマンス向上
 /// - レースコンディションの回避
 /// - atomic な操作を保証
-pub struct UpdateTodoCommand<W: TodoWriter, C: TodoCacheOps> {
+#[derive(Clone)]
+pub struct UpdateTodoCommand {
     /// 書き込みリポジトリ
-    writer: Arc<W>,
+    writer: Arc<dyn TodoWriter>,
 
     /// キャッシュ操作（オプショナル）
-    cache: Option<Arc<C>>,
-}
-
-// -----------------------------------------------------------------------------
-// Clone トレイト実装
-// -----------------------------------------------------------------------------
-
-impl<W: TodoWriter, C: TodoCacheOps> Clone for UpdateTodoCommand<W, C> {
-    fn clone(&self) -> Self {
-        Self {
-            writer: Arc::clone(&self.writer),
-            cache: self.cache.as_ref().map(Arc::clone),
-        }
-    }
+    cache: Option<Arc<dyn TodoCacheOps>>,
 }
 
 // -----------------------------------------------------------------------------
 // UpdateTodoCommand の実装
 // -----------------------------------------------------------------------------
 
-impl<W: TodoWriter, C: TodoCacheOps> UpdateTodoCommand<W, C> {
+impl UpdateTodoCommand {
     /// 新しいコマンドを作成
     ///
     /// # Arguments
     /// * `writer` - TodoWriter の共有参照（Arc でラップ）
     /// * `cache` - オプションのキャッシュ（Write-Through 用）
-    pub fn new(writer: Arc<W>, cache: Option<Arc<C>>) -> Self {
+    pub fn new(writer: Arc<dyn TodoWriter>, cache: Option<Arc<dyn TodoCacheOps>>) -> Self {
         Self { writer, cache }
     }
 
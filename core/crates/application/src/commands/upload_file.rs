@@ -54,41 +54,30 @@ pub struct UploadFileResult {
 
 /// ファイルアップロードコマンド
 ///
-/// # ジェネリクス
+/// # トレイトオブジェクト
 ///
-/// - `S: StorageOps` - ストレージ操作の型（S3StorageService など）
+/// - `storage: Arc<dyn StorageOps>` - ストレージ操作（S3StorageService など）
 ///
 /// # 責務
 ///
 /// - ファイルのバリデーション（Domain 層に委譲）
 /// - ストレージへのアップロード
-pub struct UploadFileCommand<S: StorageOps> {
+#[derive(Clone)]
+pub struct UploadFileCommand {
     /// ストレージ操作（Arc でラップして共有可能に）
-    storage: Arc<S>,
-}
-
-// -----------------------------------------------------------------------------
-// Clone トレイト実装
-// -----------------------------------------------------------------------------
-
-impl<S: StorageOps> Clone for UploadFileCommand<S> {
-    fn clone(&self) -> Self {
-        Self {
-            storage: Arc::clone(&self.storage),
-        }
-    }
+    storage: Arc<dyn StorageOps>,
 }
 
 // -----------------------------------------------------------------------------
 // UploadFileCommand の実装
 // -----------------------------------------------------------------------------
 
-impl<S: StorageOps> UploadFileCommand<S> {
+impl UploadFileCommand {
     /// 新しいコマンドを作成
     ///
     /// # Arguments
     /// * `storage` - StorageOps の共有参照（Arc でラップ）
-    pub fn new(storage: Arc<S>) -> Self {
+    pub fn new(storage: Arc<dyn StorageOps>) -> Self {
         Self { storage }
     }
 
@@ -183,3 +183,38 @@ impl From<domain::User> for UserResponse {
         }
     }
 }
+
+// =============================================================================
+// 鍵セットのリロード結果
+// =============================================================================
+
+/// 鍵セットのリロード結果（POST /api/admin/reload-secrets）
+///
+/// key id のみを返す（シークレットは含めない）。
+///
+/// # 例
+///
+/// ```json
+/// {
+///   "signing_key_id": "2025-02",
+///   "key_ids": ["2025-02", "default"]
+/// }
+/// ```
+#[derive(Debug, Serialize)]
+pub struct ReloadSecretsResponse {
+    /// 新しいトークンの署名に使う鍵の key id
+    pub signing_key_id: String,
+
+    /// 検証に使える全ての鍵の key id（署名用の鍵が先頭）
+    pub key_ids: Vec<String>,
+}
+
+/// KeySet → ReloadSecretsResponse への変換
+impl From<&crate::services::KeySet> for ReloadSecretsResponse {
+    fn from(keys: &crate::services::KeySet) -> Self {
+        Self {
+            signing_key_id: keys.current().id().to_string(),
+            key_ids: keys.keys().map(|key| key.id().to_string()).collect(),
+        }
+    }
+}
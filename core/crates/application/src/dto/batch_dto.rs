@@ -1,7 +1,7 @@
 // =============================================================================
 // application/src/dto/batch_dto.rs: バッチ操作用 DTO
 // =============================================================================
-// バッチ作成や TODO + ファイル同時作成用のリクエスト/レスポンス DTO。
+// バッチ作成・バッチ更新や TODO + ファイル同時作成用のリクエスト/レスポンス DTO。
 //
 // バッチ操作のメリット:
 // - 複数の TODO を 1 リクエストで作成（ネットワーク往復を削減）
@@ -45,7 +45,9 @@ use super::CreateTodoDto;
 ///   "todos": [
 ///     { "title": "Task 1" },
 ///     { "title": "Task 2", "description": "Details" }
-///   ]
+///   ],
+///   "mode": "partial",
+///   "dedupe": true
 /// }
 /// ```
 #[derive(Debug, Clone, Deserialize)]
@@ -53,6 +55,34 @@ pub struct BatchCreateTodosRequest {
     /// 作成する TODO のリスト
     /// 既存の CreateTodoDto を再利用
     pub todos: Vec<CreateTodoDto>,
+
+    /// 処理モード（省略時は atomic）
+    #[serde(default)]
+    pub mode: BatchMode,
+
+    /// リクエスト内の重複を検出する（省略時は false）
+    ///
+    /// 正規化したタイトルが同じ項目は最初の 1 件だけ作成し、
+    /// 以降は status = skipped_duplicate として返す。
+    #[serde(default)]
+    pub dedupe: bool,
+
+    /// ユーザーの未完了 TODO との重複も検出する（省略時は false、`dedupe` を含む）
+    #[serde(default)]
+    pub dedupe_existing: bool,
+}
+
+/// バッチ作成の処理モード
+///
+/// JSON では小文字の文字列（"atomic" / "partial"）として指定する。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchMode {
+    /// 全件成功か全件失敗（1 件でも不正なら何も作成しない）
+    #[default]
+    Atomic,
+    /// 有効な項目のみ作成し、項目ごとの結果を返す
+    Partial,
 }
 
 // =============================================================================
@@ -204,3 +234,207 @@ pub struct TodoWithFilesResponse {
     /// 作成されたファイルのリスト
     pub files: Vec<FileResponse>,
 }
+
+// =============================================================================
+// バッチ TODO 更新リクエスト
+// =============================================================================
+
+/// バッチ更新の 1 件分
+///
+/// `id` 以外は UpdateTodoDto と同じ PATCH セマンティクス
+/// （未指定のフィールドは既存値を維持）。
+///
+/// # 例
+///
+/// ```json
+/// { "id": "uuid", "title": "New Title", "completed": true }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchUpdateTodoItem {
+    /// 更新対象の TODO ID
+    pub id: Uuid,
+
+    /// 新しいタイトル（指定時のみ更新）
+    pub title: Option<String>,
+
+    /// 新しい説明（指定時のみ更新）
+    pub description: Option<String>,
+
+    /// 新しい完了状態（指定時のみ更新）
+    pub completed: Option<bool>,
+}
+
+/// バッチ TODO 更新リクエスト
+///
+/// 複数の TODO を 1 リクエストで更新する。
+///
+/// # 例
+///
+/// ```json
+/// {
+///   "todos": [
+///     { "id": "uuid-1", "completed": true },
+///     { "id": "uuid-2", "title": "Renamed" }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchUpdateTodosRequest {
+    /// 更新する TODO のリスト
+    pub todos: Vec<BatchUpdateTodoItem>,
+}
+
+// =============================================================================
+// バッチ TODO 更新レスポンス
+// =============================================================================
+
+/// フィールド単位のバリデーションエラー
+///
+/// # 例
+///
+/// ```json
+/// { "field": "title", "message": "title cannot be empty" }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    /// エラーのあるフィールド名
+    pub field: String,
+
+    /// エラーメッセージ
+    pub message: String,
+}
+
+/// バッチ操作 1 件分の処理結果
+///
+/// JSON では snake_case の文字列としてシリアライズされる。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchItemStatus {
+    /// 作成された
+    Created,
+    /// 更新された
+    Updated,
+    /// 存在しない、または他ユーザーの TODO
+    NotFound,
+    /// バリデーションエラー
+    Invalid,
+    /// 他の項目のエラーによりロールバックされた（未適用）
+    Skipped,
+    /// 重複のため作成しなかった（重複検出を有効にした場合のみ）
+    SkippedDuplicate,
+}
+
+/// バッチ更新 1 件分の結果
+///
+/// # 例
+///
+/// ```json
+/// { "id": "uuid", "status": "invalid", "errors": [{ "field": "title", "message": "..." }] }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchUpdateItemResult {
+    /// 対象の TODO ID
+    pub id: Uuid,
+
+    /// 処理結果
+    pub status: BatchItemStatus,
+
+    /// 更新後の TODO（status = updated の場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub todo: Option<domain::Todo>,
+
+    /// バリデーションエラー（status = invalid の場合のみ）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+/// バッチ TODO 更新レスポンス
+///
+/// リクエストと同じ順序で各項目の結果を返す。
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchUpdateTodosResponse {
+    /// トランザクションがコミットされたか
+    pub committed: bool,
+
+    /// 各項目の結果（リクエスト順）
+    pub results: Vec<BatchUpdateItemResult>,
+}
+
+// =============================================================================
+// バッチ TODO 作成レスポンス（partial モード、重複検出あり）
+// =============================================================================
+
+/// バッチ作成（partial モード、または重複検出あり）1 件分の結果
+///
+/// # 例
+///
+/// ```json
+/// { "index": 1, "status": "invalid", "error": "title cannot be empty" }
+/// { "index": 2, "status": "skipped_duplicate", "existing_id": "uuid" }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchCreateItemResult {
+    /// リクエスト内の位置（0 始まり）
+    pub index: usize,
+
+    /// 処理結果（created / invalid / skipped_duplicate）
+    pub status: BatchItemStatus,
+
+    /// 作成された TODO（status = created の場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub todo: Option<domain::Todo>,
+
+    /// 作成された TODO の正規 URL（status = created の場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// 重複先の TODO ID（status = skipped_duplicate の場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub existing_id: Option<Uuid>,
+
+    /// エラーメッセージ（status = invalid の場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// =============================================================================
+// バッチ TODO 作成レスポンス（atomic モード）
+// =============================================================================
+
+/// 作成された TODO とその正規 URL
+///
+/// TODO のフィールドに `url` を加えた形でシリアライズされる。
+///
+/// # 例
+///
+/// ```json
+/// { "id": "uuid", "title": "Task 1", "...": "...", "url": "/api/todos/uuid" }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedTodoResponse {
+    /// 作成された TODO
+    #[serde(flatten)]
+    pub todo: domain::Todo,
+
+    /// 正規 URL（`/api/todos/{id}`）
+    pub url: String,
+}
+
+/// domain::Todo → CreatedTodoResponse への変換
+impl From<domain::Todo> for CreatedTodoResponse {
+    fn from(todo: domain::Todo) -> Self {
+        let url = todo_url(todo.id);
+        Self { todo, url }
+    }
+}
+
+/// TODO の正規 URL を返す
+///
+/// # 使用例
+///
+/// ```rust,ignore
+/// assert_eq!(todo_url(id), format!("/api/todos/{}", id));
+/// ```
+pub fn todo_url(id: Uuid) -> String {
+    format!("/api/todos/{}", id)
+}
@@ -0,0 +1,216 @@
+// =============================================================================
+// application/src/dto/import_dto.rs: インポート関連の DTO
+// =============================================================================
+// 大量の TODO をインポートするための DTO。
+//
+// 処理モード:
+// - 同期（?async=false、デフォルト）: リクエスト内で作成し、結果サマリーを返す
+// - 非同期（?async=true）: ジョブを登録して 202 を返し、ワーカーが処理する
+//   進捗は GET /api/jobs/{id} で確認する
+//
+// CSV インポート（POST /api/todos/import-csv）は行ごとの結果を CSV の行番号付きで返す。
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// 外部クレートのインポート
+// -----------------------------------------------------------------------------
+
+// serde: シリアライズ/デシリアライズ
+use serde::{Deserialize, Serialize};
+
+// uuid: 重複先の TODO ID
+use uuid::Uuid;
+
+// 同じモジュール内の DTO
+use super::{BatchItemStatus, CreateTodoDto, FieldError};
+
+// =============================================================================
+// インポートリクエスト
+// =============================================================================
+
+/// TODO インポートリクエスト
+///
+/// # 例
+///
+/// ```json
+/// {
+///     "todos": [
+///         {"title": "Task 1", "description": "Description 1"},
+///         {"title": "Task 2"}
+///     ]
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportTodosRequest {
+    /// インポートする TODO のリスト（空は不可）
+    pub todos: Vec<CreateTodoDto>,
+}
+
+// =============================================================================
+// インポート結果
+// =============================================================================
+
+/// インポートに失敗した項目（1 件分）
+///
+/// # 例
+///
+/// ```json
+/// {"index": 3, "error": "title cannot be empty"}
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportItemError {
+    /// リクエスト内の位置（0 始まり）
+    pub index: usize,
+
+    /// エラーメッセージ
+    pub error: String,
+}
+
+/// 重複のため作成しなかった項目
+///
+/// # 例
+///
+/// ```json
+/// { "index": 2, "status": "skipped_duplicate", "existing_id": "uuid" }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportDuplicate {
+    /// リクエスト内の位置（0 始まり）
+    pub index: usize,
+
+    /// 処理結果（常に skipped_duplicate）
+    pub status: BatchItemStatus,
+
+    /// 重複先の TODO ID
+    pub existing_id: Uuid,
+}
+
+/// 同期インポートの結果サマリー
+///
+/// # 例
+///
+/// ```json
+/// {
+///     "total": 3,
+///     "imported": 2,
+///     "failed": 1,
+///     "errors": [{"index": 1, "error": "title cannot be empty"}]
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportSummary {
+    /// リクエストされた項目数
+    pub total: usize,
+
+    /// 作成された TODO の数
+    pub imported: usize,
+
+    /// 失敗した項目数
+    pub failed: usize,
+
+    /// 失敗した項目（位置順）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ImportItemError>,
+
+    /// 重複のため作成しなかった項目（位置順、重複検出を有効にした場合のみ）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<ImportDuplicate>,
+}
+
+/// インポートジョブのレスポンス
+///
+/// ジョブの状態と進捗に、失敗した項目（先頭から最大件数まで）を加えたもの。
+///
+/// # 例
+///
+/// ```json
+/// {
+///     "id": "uuid",
+///     "user_id": "uuid",
+///     "state": "running",
+///     "total_items": 5000,
+///     "processed_items": 1200,
+///     "failed_items": 3,
+///     "error": null,
+///     "created_at": "2024-01-01T00:00:00Z",
+///     "updated_at": "2024-01-01T00:00:05Z",
+///     "errors": [{"index": 17, "error": "title cannot be empty"}]
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportJobResponse {
+    /// ジョブの状態と進捗
+    #[serde(flatten)]
+    pub job: domain::ImportJob,
+
+    /// 失敗した項目（位置順、先頭から最大件数まで）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ImportItemError>,
+}
+
+// =============================================================================
+// CSV インポート結果
+// =============================================================================
+
+/// CSV インポート 1 行分の結果
+///
+/// # 例
+///
+/// ```json
+/// {"line": 2, "status": "created", "todo": {...}, "url": "/api/todos/uuid"}
+/// {"line": 3, "status": "invalid", "errors": [{"field": "due_date", "message": "..."}]}
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct CsvImportRowResult {
+    /// CSV ファイル上の行番号（1 始まり、ヘッダー行が 1）
+    pub line: u64,
+
+    /// 処理結果（created / invalid / skipped）
+    pub status: BatchItemStatus,
+
+    /// 作成された TODO（status = created の場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub todo: Option<domain::Todo>,
+
+    /// 作成された TODO の正規 URL（status = created の場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// バリデーションエラー（status = invalid の場合のみ）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+/// CSV インポートのレスポンス
+///
+/// # 例
+///
+/// ```json
+/// {
+///     "committed": true,
+///     "total": 2,
+///     "imported": 1,
+///     "failed": 1,
+///     "results": [
+///         {"line": 2, "status": "created", "todo": {...}, "url": "/api/todos/uuid"},
+///         {"line": 3, "status": "invalid", "errors": [...]}
+///     ]
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct CsvImportResponse {
+    /// TODO が作成されたか（atomic モードで不正な行がある場合は false）
+    pub committed: bool,
+
+    /// データ行の数（ヘッダー行を除く）
+    pub total: usize,
+
+    /// 作成された TODO の数
+    pub imported: usize,
+
+    /// 不正な行の数
+    pub failed: usize,
+
+    /// 各行の結果（行番号順）
+    pub results: Vec<CsvImportRowResult>,
+}
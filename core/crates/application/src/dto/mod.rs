@@ -32,6 +32,9 @@ mod batch_dto;
 /// TODO 作成リクエスト DTO
 mod create_todo_dto;
 
+/// インポート関連の DTO（同期/非同期インポート、ジョブ）
+mod import_dto;
+
 /// TODO 更新リクエスト DTO
 mod update_todo_dto;
 
@@ -43,22 +46,50 @@ mod update_todo_dto;
 /// - LoginRequest: ログインリクエスト
 /// - RegisterRequest: ユーザー登録リクエスト
 /// - TokenResponse: JWT トークンレスポンス
+/// - ReloadSecretsResponse: 鍵セットのリロード結果
 /// - UserResponse: ユーザー情報レスポンス
-pub use auth_dto::{LoginRequest, RegisterRequest, TokenResponse, UserResponse};
+pub use auth_dto::{
+    LoginRequest, RegisterRequest, ReloadSecretsResponse, TokenResponse, UserResponse,
+};
 
 /// バッチ DTO を公開
+/// - BatchCreateItemResult: バッチ作成（partial モード）1 件分の結果
 /// - BatchCreateTodosRequest: TODO 一括作成リクエスト
+/// - BatchItemStatus: バッチ 1 件分の処理結果
+/// - BatchMode: バッチ作成の処理モード（atomic / partial）
+/// - BatchUpdateItemResult: バッチ更新 1 件分の結果
+/// - BatchUpdateTodoItem: バッチ更新 1 件分の入力
+/// - BatchUpdateTodosRequest: TODO 一括更新リクエスト
+/// - BatchUpdateTodosResponse: TODO 一括更新レスポンス
 /// - CreateTodoWithFilesRequest: TODO + ファイル作成リクエスト
+/// - CreatedTodoResponse: 作成された TODO + 正規 URL
+/// - FieldError: フィールド単位のバリデーションエラー
 /// - FileResponse: ファイル情報レスポンス
 /// - FileUploadDto: ファイルアップロード情報
 /// - TodoWithFilesResponse: TODO + ファイルレスポンス
+/// - todo_url: TODO の正規 URL を返す
 pub use batch_dto::{
-    BatchCreateTodosRequest, CreateTodoWithFilesRequest, FileResponse, FileUploadDto,
-    TodoWithFilesResponse,
+    BatchCreateItemResult, BatchCreateTodosRequest, BatchItemStatus, BatchMode,
+    BatchUpdateItemResult, BatchUpdateTodoItem, BatchUpdateTodosRequest, BatchUpdateTodosResponse,
+    CreateTodoWithFilesRequest, CreatedTodoResponse, FieldError, FileResponse, FileUploadDto,
+    TodoWithFilesResponse, todo_url,
 };
 
 /// TODO 作成 DTO を公開
 pub use create_todo_dto::CreateTodoDto;
 
+/// インポート DTO を公開
+/// - CsvImportResponse: CSV インポートのレスポンス
+/// - CsvImportRowResult: CSV インポート 1 行分の結果
+/// - ImportDuplicate: 重複のため作成しなかった項目
+/// - ImportItemError: インポートに失敗した項目
+/// - ImportJobResponse: インポートジョブのレスポンス
+/// - ImportSummary: 同期インポートの結果サマリー
+/// - ImportTodosRequest: TODO インポートリクエスト
+pub use import_dto::{
+    CsvImportResponse, CsvImportRowResult, ImportDuplicate, ImportItemError, ImportJobResponse,
+    ImportSummary, ImportTodosRequest,
+};
+
 /// TODO 更新 DTO を公開
 pub use update_todo_dto::UpdateTodoDto;
@@ -53,54 +53,44 @@ pub struct DownloadFileResult {
 
 /// ファイルダウンロードクエリ
 ///
-/// # ジェネリクス
-///
-/// - `TR: TodoReader` - TODO 読み取りの型（所有者確認用）
-/// - `S: StorageOps` - ストレージ操作の型
-///
 /// # トレイトオブジェクト
 ///
-/// - `file_reader: Arc<dyn FileReader>` - AppState と同じくトレイトオブジェクトを使用
+/// AppState と同じく、依存はすべてトレイトオブジェクトで受け取る。
+///
+/// - `file_reader: Arc<dyn FileReader>` - ファイルメタデータ読み取り
+/// - `todo_reader: Arc<dyn TodoReader>` - TODO 読み取り（所有者確認用）
+/// - `storage: Arc<dyn StorageOps>` - ストレージ操作
 ///
 /// # アクセス制御
 ///
 /// ファイルの所有者（= 親 TODO の所有者）のみがダウンロード可能。
 /// これにより他ユーザーのファイルへの不正アクセスを防止。
-pub struct DownloadFileQuery<TR: TodoReader, S: StorageOps> {
+#[derive(Clone)]
+pub struct DownloadFileQuery {
     /// ファイルメタデータ読み取り（トレイトオブジェクト）
     file_reader: Arc<dyn FileReader>,
     /// TODO 読み取り（所有者確認用）
-    todo_reader: Arc<TR>,
+    todo_reader: Arc<dyn TodoReader>,
     /// ストレージ操作
-    storage: Arc<S>,
-}
-
-// -----------------------------------------------------------------------------
-// Clone トレイト実装
-// -----------------------------------------------------------------------------
-
-impl<TR: TodoReader, S: StorageOps> Clone for DownloadFileQuery<TR, S> {
-    fn clone(&self) -> Self {
-        Self {
-            file_reader: Arc::clone(&self.file_reader),
-            todo_reader: Arc::clone(&self.todo_reader),
-            storage: Arc::clone(&self.storage),
-        }
-    }
+    storage: Arc<dyn StorageOps>,
 }
 
 // -----------------------------------------------------------------------------
 // DownloadFileQuery の実装
 // -----------------------------------------------------------------------------
 
-impl<TR: TodoReader, S: StorageOps> DownloadFileQuery<TR, S> {
+impl DownloadFileQuery {
     /// 新しいクエリを作成
     ///
     /// # Arguments
     /// * `file_reader` - FileReader のトレイトオブジェクト
-    /// * `todo_reader` - TodoReader の共有参照
-    /// * `storage` - StorageOps の共有参照
-    pub fn new(file_reader: Arc<dyn FileReader>, todo_reader: Arc<TR>, storage: Arc<S>) -> Self {
+    /// * `todo_reader` - TodoReader のトレイトオブジェクト
+    /// * `storage` - StorageOps のトレイトオブジェクト
+    pub fn new(
+        file_reader: Arc<dyn FileReader>,
+        todo_reader: Arc<dyn TodoReader>,
+        storage: Arc<dyn StorageOps>,
+    ) -> Self {
         Self {
             file_reader,
             todo_reader,
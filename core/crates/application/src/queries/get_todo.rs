@@ -35,38 +35,27 @@ use uuid::Uuid; // 一意識別子
 /// 単一の TODO を ID で取得する。
 /// 所有者のみ取得可能（user_id で認可チェック）。
 ///
-/// # ジェネリクス
+/// # トレイトオブジェクト
 ///
-/// - `R: TodoReader` - 読み取りリポジトリの型
+/// - `reader: Arc<dyn TodoReader>` - 読み取りリポジトリ
 ///
 /// CachedTodoReader を注入すれば、キャッシュを透過的に使用できる。
-pub struct GetTodoQuery<R: TodoReader> {
+#[derive(Clone)]
+pub struct GetTodoQuery {
     /// 読み取りリポジトリ
-    reader: Arc<R>,
-}
-
-// -----------------------------------------------------------------------------
-// Clone トレイト実装
-// -----------------------------------------------------------------------------
-
-impl<R: TodoReader> Clone for GetTodoQuery<R> {
-    fn clone(&self) -> Self {
-        Self {
-            reader: Arc::clone(&self.reader),
-        }
-    }
+    reader: Arc<dyn TodoReader>,
 }
 
 // -----------------------------------------------------------------------------
 // GetTodoQuery の実装
 // -----------------------------------------------------------------------------
 
-impl<R: TodoReader> GetTodoQuery<R> {
+impl GetTodoQuery {
     /// 新しいクエリを作成
     ///
     /// # Arguments
     /// * `reader` - TodoReader の共有参照（Arc でラップ）
-    pub fn new(reader: Arc<R>) -> Self {
+    pub fn new(reader: Arc<dyn TodoReader>) -> Self {
         Self { reader }
     }
 
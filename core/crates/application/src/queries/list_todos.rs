@@ -39,33 +39,22 @@ use domain::{DomainError, Todo, TodoFilter, TodoReader}; // ドメイン層の
 /// TodoFilter には以下のフィールドがある:
 /// - `user_id`: 必須。認可のため、所有する TODO のみ取得
 /// - `completed`: 任意。true/false/None で完了状態をフィルタ
-pub struct ListTodosQuery<R: TodoReader> {
+#[derive(Clone)]
+pub struct ListTodosQuery {
     /// 読み取りリポジトリ
-    reader: Arc<R>,
-}
-
-// -----------------------------------------------------------------------------
-// Clone トレイト実装
-// -----------------------------------------------------------------------------
-
-impl<R: TodoReader> Clone for ListTodosQuery<R> {
-    fn clone(&self) -> Self {
-        Self {
-            reader: Arc::clone(&self.reader),
-        }
-    }
+    reader: Arc<dyn TodoReader>,
 }
 
 // -----------------------------------------------------------------------------
 // ListTodosQuery の実装
 // -----------------------------------------------------------------------------
 
-impl<R: TodoReader> ListTodosQuery<R> {
+impl ListTodosQuery {
     /// 新しいクエリを作成
     ///
     /// # Arguments
     /// * `reader` - TodoReader の共有参照（Arc でラップ）
-    pub fn new(reader: Arc<R>) -> Self {
+    pub fn new(reader: Arc<dyn TodoReader>) -> Self {
         Self { reader }
     }
 
@@ -10,7 +10,9 @@
 //
 // セキュリティ:
 // - パスワード: bcrypt でハッシュ化（コスト係数: DEFAULT_COST = 12）
-// - JWT: HS256 アルゴリズムで署名
+// - JWT: HS256 アルゴリズムで署名（with_algorithm で HS384 / HS512 に変更可、
+//   鍵は JwtKeyRing、ヘッダーに kid を含める）
+//   署名・検証はエッジ層と共有の jwt クレートで行う
 // - 認証エラー: 詳細を漏らさない（"invalid credentials" のみ）
 // =============================================================================
 
@@ -32,51 +34,35 @@ use chrono::{Duration, Utc};
 // domain クレートの型
 use domain::{DomainError, User, UserReader, UserWriter};
 
-// jsonwebtoken: JWT のエンコード/デコード
-// encode: JWT トークン生成
-// EncodingKey: 署名用の鍵
-// Header: JWT ヘッダー（アルゴリズム指定）
-use jsonwebtoken::{EncodingKey, Header, encode};
-
-// serde: シリアライズ/デシリアライズ
-use serde::{Deserialize, Serialize};
+// uuid: ユーザー ID
+use uuid::Uuid;
 
 // tracing: 構造化ログ
 use tracing::info;
 
+// JWT 署名鍵（ホットリロード対応）
+use super::jwt_keys::{JwtAlgorithm, JwtKeyRing, KeySet};
+
 // =============================================================================
 // JWT クレーム
 // =============================================================================
 
 /// JWT クレーム（ペイロード）
 ///
-/// JWT トークンに含まれる情報。
-/// RFC 7519 の標準クレームに準拠。
-///
-/// # フィールド
+/// エッジ層の auth コンポーネントと共有する `jwt` クレートの型。
+/// AuthService が発行するトークンのフィールド:
 ///
 /// - `sub` (Subject): ユーザー ID（UUID 文字列）
 /// - `exp` (Expiration Time): 有効期限（Unix タイムスタンプ）
 /// - `iat` (Issued At): 発行日時（Unix タイムスタンプ）
+/// - `iss` (Issuer): 発行者（`JWT_ISSUER` を設定した場合のみ）
+/// - `extra`: 登録済み以外のクレーム（AuthService は `token_use: "access"` のみ発行する）
 ///
 /// # セキュリティ
 ///
 /// 機密情報（パスワード、メールアドレス）は含めない。
 /// クレームはデコードすれば誰でも読める（Base64）。
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Claims {
-    /// ユーザー ID（UUID 文字列）
-    /// JWT の "subject" クレーム
-    pub sub: String,
-
-    /// 有効期限（Unix タイムスタンプ）
-    /// この時刻を過ぎるとトークンは無効
-    pub exp: usize,
-
-    /// 発行日時（Unix タイムスタンプ）
-    /// トークンが生成された時刻
-    pub iat: usize,
-}
+pub use jwt::Claims;
 
 // =============================================================================
 // 認証サービス
@@ -86,10 +72,10 @@ pub struct Claims {
 ///
 /// ユーザー登録、ログイン、JWT 発行を担当する。
 ///
-/// # ジェネリクス
+/// # トレイトオブジェクト
 ///
-/// - `UR: UserReader` - ユーザー読み取り（ログイン認証用）
-/// - `UW: UserWriter` - ユーザー書き込み（ユーザー登録用）
+/// - `user_reader: Arc<dyn UserReader>` - ユーザー読み取り（ログイン認証用）
+/// - `user_writer: Arc<dyn UserWriter>` - ユーザー書き込み（ユーザー登録用）
 ///
 /// CQRS パターンに従い、読み取りと書き込みを分離。
 ///
@@ -99,46 +85,38 @@ pub struct Claims {
 /// これにより:
 /// - テスト時にモック実装を注入可能
 /// - 複数リクエストでリポジトリを共有
-pub struct AuthService<UR: UserReader, UW: UserWriter> {
+#[derive(Clone)]
+pub struct AuthService {
     /// ユーザー読み取りリポジトリ（Queries 用）
     /// ログイン時のユーザー検索に使用
-    user_reader: Arc<UR>,
+    user_reader: Arc<dyn UserReader>,
 
     /// ユーザー書き込みリポジトリ（Commands 用）
     /// 登録時のユーザー作成に使用
-    user_writer: Arc<UW>,
+    user_writer: Arc<dyn UserWriter>,
 
-    /// JWT 署名用シークレット
-    /// 環境変数から読み込む（本番環境では安全に管理）
-    jwt_secret: String,
+    /// JWT 署名・検証用の鍵セット
+    /// クローン間で共有され、リロードすると全てのクローンに反映される
+    jwt_keys: JwtKeyRing,
 
     /// JWT 有効期間（時間）
     /// 例: 24 = 24時間有効
     jwt_expiry_hours: i64,
-}
 
-// -----------------------------------------------------------------------------
-// Clone トレイト実装
-// -----------------------------------------------------------------------------
+    /// JWT 発行者（iss クレーム）
+    /// None の場合は iss クレームを含めない
+    jwt_issuer: Option<String>,
 
-impl<UR: UserReader, UW: UserWriter> Clone for AuthService<UR, UW> {
-    fn clone(&self) -> Self {
-        Self {
-            user_reader: Arc::clone(&self.user_reader),
-            user_writer: Arc::clone(&self.user_writer),
-            // String は Clone を実装しているので直接 clone()
-            jwt_secret: self.jwt_secret.clone(),
-            // i64 は Copy トレイトを実装しているのでそのままコピー
-            jwt_expiry_hours: self.jwt_expiry_hours,
-        }
-    }
+    /// 発行する JWT の署名アルゴリズム
+    /// 検証は HS256 / HS384 / HS512 のいずれも受け付ける
+    jwt_algorithm: JwtAlgorithm,
 }
 
 // -----------------------------------------------------------------------------
 // AuthService の実装
 // -----------------------------------------------------------------------------
 
-impl<UR: UserReader, UW: UserWriter> AuthService<UR, UW> {
+impl AuthService {
     /// 新しい AuthService を作成
     ///
     /// # Arguments
@@ -147,19 +125,56 @@ impl<UR: UserReader, UW: UserWriter> AuthService<UR, UW> {
     /// * `jwt_secret` - JWT 署名用シークレット（本番は安全に管理）
     /// * `jwt_expiry_hours` - JWT 有効期間（時間）
     pub fn new(
-        user_reader: Arc<UR>,
-        user_writer: Arc<UW>,
+        user_reader: Arc<dyn UserReader>,
+        user_writer: Arc<dyn UserWriter>,
         jwt_secret: String,
         jwt_expiry_hours: i64,
     ) -> Self {
         Self {
             user_reader,
             user_writer,
-            jwt_secret,
+            jwt_keys: JwtKeyRing::new(KeySet::single(jwt_secret)),
             jwt_expiry_hours,
+            jwt_issuer: None,
+            jwt_algorithm: JwtAlgorithm::default(),
         }
     }
 
+    /// JWT 発行者（iss クレーム）を設定する
+    ///
+    /// # Arguments
+    /// * `issuer` - 発行者（None の場合は iss クレームを含めない）
+    pub fn with_issuer(mut self, issuer: Option<String>) -> Self {
+        self.jwt_issuer = issuer;
+        self
+    }
+
+    /// 発行する JWT の署名アルゴリズムを設定する（既定は HS256）
+    ///
+    /// エッジ層の jwt_algorithms で許可していないアルゴリズムに切り替えると、
+    /// 発行したトークンがエッジ層で拒否される（先にエッジ層の許可リストに追加すること）。
+    ///
+    /// # Arguments
+    /// * `algorithm` - 署名アルゴリズム
+    pub fn with_algorithm(mut self, algorithm: JwtAlgorithm) -> Self {
+        self.jwt_algorithm = algorithm;
+        self
+    }
+
+    /// JWT の鍵セットを設定する（new() の jwt_secret を置き換える）
+    ///
+    /// # Arguments
+    /// * `keys` - 署名・検証用の鍵セット（リロード可能なものを渡すとホットリロードできる）
+    pub fn with_keys(mut self, keys: JwtKeyRing) -> Self {
+        self.jwt_keys = keys;
+        self
+    }
+
+    /// JWT の鍵セット（管理用エンドポイントからのリロードに使用）
+    pub fn keys(&self) -> &JwtKeyRing {
+        &self.jwt_keys
+    }
+
     /// ユーザー登録
     ///
     /// # Arguments
@@ -192,9 +207,7 @@ impl<UR: UserReader, UW: UserWriter> AuthService<UR, UW> {
         User::validate_password(password)?;
 
         // 2. パスワードハッシュ化（bcrypt）
-        // DEFAULT_COST = 12: 適度なセキュリティと速度のバランス
-        let password_hash = bcrypt::hash(password, bcrypt::DEFAULT_COST)
-            .map_err(|e| DomainError::Validation(format!("password hash error: {}", e)))?;
+        let password_hash = hash_password(password)?;
 
         // 3. ユーザー作成（UserWriter を使用）
         let user = User::new(email.clone(), password_hash, display_name);
@@ -267,22 +280,76 @@ impl<UR: UserReader, UW: UserWriter> AuthService<UR, UW> {
         // 有効期限 = 現在時刻 + 設定時間
         let exp = now + Duration::hours(self.jwt_expiry_hours);
 
+        // トークンの種類（エッジ層はアクセストークンのみ API へのリクエストに受け付ける）
+        let mut extra = jwt::Map::new();
+        extra.insert(
+            jwt::TOKEN_USE_CLAIM.to_string(),
+            jwt::TOKEN_USE_ACCESS.into(),
+        );
+
         // クレーム（ペイロード）を作成
         let claims = Claims {
-            sub: user.id.to_string(),      // ユーザー ID
-            exp: exp.timestamp() as usize, // 有効期限（Unix タイムスタンプ）
-            iat: now.timestamp() as usize, // 発行日時（Unix タイムスタンプ）
+            sub: user.id.to_string(),     // ユーザー ID
+            exp: exp.timestamp() as u64,  // 有効期限（Unix タイムスタンプ）
+            iat: now.timestamp() as u64,  // 発行日時（Unix タイムスタンプ）
+            iss: self.jwt_issuer.clone(), // 発行者（設定時のみ）
+            aud: None,                    // 対象者なし
+            extra,                        // token_use: "access"
         };
 
-        // JWT をエンコード
-        // Header::default() = HS256 アルゴリズム
-        encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.jwt_secret.as_bytes()),
-        )
-        .map_err(|e| DomainError::Authentication(format!("token generation error: {}", e)))
+        // 現在の署名用の鍵でエンコード（ヘッダーに kid を含める）
+        self.jwt_keys
+            .snapshot()
+            .sign_with(&claims, self.jwt_algorithm)
+    }
+
+    /// JWT トークンを検証する
+    ///
+    /// ヘッダーの kid で鍵を選ぶため、ローテーション前に発行されたトークンも
+    /// 古い鍵が鍵セットに残っている間は検証できる。
+    ///
+    /// # Arguments
+    /// * `token` - JWT トークン
+    ///
+    /// # Returns
+    /// * `Ok(Claims)` - 検証済みのクレーム
+    /// * `Err(DomainError::Authentication)` - 署名不正、期限切れ、不明な kid など
+    pub fn verify_token(&self, token: &str) -> Result<Claims, DomainError> {
+        self.jwt_keys
+            .snapshot()
+            .verify(token, self.jwt_issuer.as_deref())
     }
+
+    /// ユーザーが管理者かどうか
+    ///
+    /// # Arguments
+    /// * `user_id` - ユーザー ID（Edge 層で認証済み）
+    ///
+    /// # Returns
+    /// * `Ok(false)` - 一般ユーザー、またはユーザーが存在しない
+    pub async fn is_admin(&self, user_id: Uuid) -> Result<bool, DomainError> {
+        let user = self.user_reader.find_by_id(user_id).await?;
+        Ok(user.is_some_and(|user| user.is_admin()))
+    }
+}
+
+// =============================================================================
+// パスワードハッシュ化
+// =============================================================================
+
+/// パスワードを bcrypt でハッシュ化する
+///
+/// ユーザー登録（AuthService::register）と管理者作成（CreateAdminCommand）で共通。
+///
+/// # Arguments
+/// * `password` - パスワード（平文、バリデーション済み）
+///
+/// # Errors
+/// * `DomainError::Validation` - ハッシュ化に失敗した場合
+pub(crate) fn hash_password(password: &str) -> Result<String, DomainError> {
+    // DEFAULT_COST = 12: 適度なセキュリティと速度のバランス
+    bcrypt::hash(password, bcrypt::DEFAULT_COST)
+        .map_err(|e| DomainError::Validation(format!("password hash error: {}", e)))
 }
 
 // =============================================================================
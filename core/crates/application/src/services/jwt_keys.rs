@@ -0,0 +1,584 @@
+// =============================================================================
+// application/src/services/jwt_keys.rs: JWT 署名鍵のセット（ホットリロード対応）
+// =============================================================================
+// JWT の署名・検証に使う鍵を、再起動せずに入れ替えられるようにする。
+//
+// 鍵セット（KeySet）:
+// - current: 新しいトークンの署名に使う鍵（1 つ）
+// - previous: 検証のみに使う鍵（ローテーション前に発行されたトークン用）
+// - 各鍵は key id（kid）を持ち、発行するトークンのヘッダーに kid を含める
+// - 検証時はヘッダーの kid で鍵を選ぶ（kid がないトークンは current で検証）
+// - 署名のアルゴリズムは JwtAlgorithm（既定は HS256）、検証は HS256 / HS384 / HS512 のいずれも受け付ける
+//   （エッジ層の jwt_algorithms に HS512 を追加してから JWT_ALGORITHM=HS512 に切り替える）
+//
+// リロード（JwtKeyRing::reload）:
+// - KeySource（例: api の鍵ファイル）から新しい鍵セットを読み込んで差し替える
+// - 読み込み・検証に失敗した場合は古い鍵セットを使い続ける
+// - 差し替えは RwLock 内の Arc を交換するだけ（署名・検証中のリクエストは
+//   取得済みの Arc<KeySet> を使い続けるため、途中で鍵が変わらない）
+//
+// ローテーションの手順（例）:
+// 1. 鍵ファイルの先頭に新しい鍵を追加し、古い鍵を 2 行目以降に残す
+// 2. SIGHUP または POST /api/admin/reload-secrets でリロード
+// 3. 古い鍵で発行したトークンの有効期限が切れたら、古い鍵をファイルから削除してリロード
+// =============================================================================
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use chrono::Utc;
+use domain::DomainError;
+use jwt::{Header, Validation};
+
+use super::auth_service::Claims;
+
+/// JWT_SECRET だけで構成した場合の key id
+pub const DEFAULT_KEY_ID: &str = "default";
+
+// =============================================================================
+// JwtAlgorithm
+// =============================================================================
+
+/// 発行するトークンの署名アルゴリズム（JWT_ALGORITHM）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JwtAlgorithm {
+    /// HMAC-SHA256（既定、エッジ層の既定の許可リストに含まれる）
+    #[default]
+    Hs256,
+    /// HMAC-SHA384
+    Hs384,
+    /// HMAC-SHA512
+    Hs512,
+}
+
+impl JwtAlgorithm {
+    /// トークンヘッダーの alg
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Hs256 => "HS256",
+            Self::Hs384 => "HS384",
+            Self::Hs512 => "HS512",
+        }
+    }
+}
+
+impl FromStr for JwtAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "HS256" => Ok(Self::Hs256),
+            "HS384" => Ok(Self::Hs384),
+            "HS512" => Ok(Self::Hs512),
+            _ => Err("expected HS256, HS384 or HS512".to_string()),
+        }
+    }
+}
+
+impl fmt::Display for JwtAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// =============================================================================
+// SigningKey
+// =============================================================================
+
+/// JWT の署名鍵（HMAC の共有シークレット + key id）
+///
+/// Debug 出力ではシークレットを表示しない。
+#[derive(Clone, PartialEq, Eq)]
+pub struct SigningKey {
+    /// key id（トークンヘッダーの kid）
+    id: String,
+    /// HMAC の共有シークレット
+    secret: String,
+}
+
+impl SigningKey {
+    /// 新しい署名鍵を作成する
+    ///
+    /// # Arguments
+    /// * `id` - key id（トークンヘッダーの kid）
+    /// * `secret` - HMAC の共有シークレット
+    pub fn new(id: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            secret: secret.into(),
+        }
+    }
+
+    /// key id
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// シークレットのバイト長（設定の検証用）
+    pub fn secret_len(&self) -> usize {
+        self.secret.len()
+    }
+}
+
+impl fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningKey")
+            .field("id", &self.id)
+            .field("secret", &"<redacted>")
+            .finish()
+    }
+}
+
+// =============================================================================
+// KeySet
+// =============================================================================
+
+/// 署名用の鍵 1 つと、検証のみに使う以前の鍵
+#[derive(Debug, Clone)]
+pub struct KeySet {
+    /// 署名に使う鍵
+    current: SigningKey,
+    /// 検証のみに使う鍵（ローテーション前の鍵）
+    previous: Vec<SigningKey>,
+}
+
+impl KeySet {
+    /// 鍵セットを作成する
+    ///
+    /// # Arguments
+    /// * `current` - 署名に使う鍵
+    /// * `previous` - 検証のみに使う鍵
+    ///
+    /// # Errors
+    /// * `DomainError::Validation` - key id またはシークレットが空、key id が重複している場合
+    pub fn new(current: SigningKey, previous: Vec<SigningKey>) -> Result<Self, DomainError> {
+        let mut ids: Vec<&str> = Vec::new();
+        for key in std::iter::once(&current).chain(&previous) {
+            if key.id.is_empty() || key.id.chars().any(char::is_whitespace) {
+                return Err(DomainError::Validation(format!(
+                    "invalid JWT key id {:?}",
+                    key.id
+                )));
+            }
+            if key.secret.is_empty() {
+                return Err(DomainError::Validation(format!(
+                    "JWT key {:?} has an empty secret",
+                    key.id
+                )));
+            }
+            if ids.contains(&key.id.as_str()) {
+                return Err(DomainError::Validation(format!(
+                    "duplicate JWT key id {:?}",
+                    key.id
+                )));
+            }
+            ids.push(&key.id);
+        }
+        Ok(Self { current, previous })
+    }
+
+    /// シークレット 1 つだけの鍵セット（key id は DEFAULT_KEY_ID）
+    ///
+    /// JWT_SECRET 環境変数のみで構成する場合に使う。
+    pub fn single(secret: impl Into<String>) -> Self {
+        Self {
+            current: SigningKey::new(DEFAULT_KEY_ID, secret),
+            previous: Vec::new(),
+        }
+    }
+
+    /// 鍵ファイルの内容をパースする
+    ///
+    /// # 形式
+    ///
+    /// ```text
+    /// # 先頭の鍵が署名用、以降は検証のみ
+    /// 2025-02:new-secret-at-least-32-bytes........
+    /// default:old-secret-at-least-32-bytes........
+    /// ```
+    ///
+    /// - 1 行に 1 つ、`<key id>:<secret>`（シークレットには ':' を含められる）
+    /// - 空行と `#` で始まる行は無視する
+    ///
+    /// # Errors
+    /// * `DomainError::Validation` - 鍵がない、形式が不正、または `new()` の検証に失敗した場合
+    pub fn parse(text: &str) -> Result<Self, DomainError> {
+        let mut keys = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (id, secret) = line.split_once(':').ok_or_else(|| {
+                DomainError::Validation(format!("line {}: expected <key id>:<secret>", index + 1))
+            })?;
+            keys.push(SigningKey::new(id.trim(), secret.trim()));
+        }
+
+        let mut keys = keys.into_iter();
+        let current = keys
+            .next()
+            .ok_or_else(|| DomainError::Validation("no JWT keys defined".to_string()))?;
+        Self::new(current, keys.collect())
+    }
+
+    /// 署名に使う鍵
+    pub fn current(&self) -> &SigningKey {
+        &self.current
+    }
+
+    /// 全ての鍵（署名用の鍵が先頭）
+    pub fn keys(&self) -> impl Iterator<Item = &SigningKey> {
+        std::iter::once(&self.current).chain(&self.previous)
+    }
+
+    /// key id で鍵を探す（None の場合は署名用の鍵）
+    ///
+    /// kid を持たないトークン（鍵セット導入前に発行されたもの）は署名用の鍵で検証する。
+    pub fn find(&self, id: Option<&str>) -> Option<&SigningKey> {
+        match id {
+            Some(id) => self.keys().find(|key| key.id == id),
+            None => Some(&self.current),
+        }
+    }
+
+    /// 署名用の鍵で、HS256 でクレームに署名する（ヘッダーに kid を含める）
+    ///
+    /// # Errors
+    /// * `DomainError::Authentication` - エンコードに失敗した場合
+    pub fn sign(&self, claims: &Claims) -> Result<String, DomainError> {
+        self.sign_with(claims, JwtAlgorithm::Hs256)
+    }
+
+    /// 署名用の鍵で、指定したアルゴリズムでクレームに署名する（ヘッダーに kid を含める）
+    ///
+    /// # Arguments
+    /// * `claims` - クレーム
+    /// * `algorithm` - 署名アルゴリズム（エッジ層の jwt_algorithms で許可していること）
+    ///
+    /// # Errors
+    /// * `DomainError::Authentication` - エンコードに失敗した場合
+    pub fn sign_with(
+        &self,
+        claims: &Claims,
+        algorithm: JwtAlgorithm,
+    ) -> Result<String, DomainError> {
+        let header = Header::new(algorithm.as_str(), Some(&self.current.id));
+        jwt::encode(&header, claims, self.current.secret.as_bytes())
+            .map_err(|e| DomainError::Authentication(format!("token generation error: {}", e)))
+    }
+
+    /// トークンを検証し、クレームを返す
+    ///
+    /// ヘッダーの kid で鍵を選び、署名・有効期限・発行者（指定時）を検証する。
+    /// アルゴリズムは JWT_ALGORITHM にかかわらず HS256 / HS384 / HS512 を受け付ける
+    /// （切り替え前に発行したトークンと、エッジ層が HS256 で発行する X-Edge-Token を検証するため）。
+    /// 有効期限は jwt::DEFAULT_LEEWAY_SECS（60 秒）の時計のずれを許容する。
+    ///
+    /// # Arguments
+    /// * `token` - JWT
+    /// * `issuer` - 期待する発行者（None の場合は iss を検証しない）
+    ///
+    /// # Errors
+    /// * `DomainError::Authentication` - kid が不明、署名不正、期限切れなど
+    pub fn verify(&self, token: &str, issuer: Option<&str>) -> Result<Claims, DomainError> {
+        let header = jwt::decode_header(token)
+            .map_err(|e| DomainError::Authentication(format!("invalid token: {}", e)))?;
+        let key = self
+            .find(header.kid.as_deref())
+            .ok_or_else(|| DomainError::Authentication("unknown signing key".to_string()))?;
+
+        // 発行者を指定した場合は iss クレームがないトークンも拒否する
+        let validation = Validation::new(Utc::now().timestamp() as u64)
+            .with_issuer(issuer)
+            .with_algorithms(jwt::HMAC_ALGORITHMS);
+        jwt::decode(token, key.secret.as_bytes(), &validation)
+            .map_err(|e| DomainError::Authentication(format!("invalid token: {}", e)))
+    }
+}
+
+// =============================================================================
+// KeySource
+// =============================================================================
+
+/// 鍵セットの読み込み元（リロード時に呼び出す）
+///
+/// 実装例: api の鍵ファイル（JWT_SECRETS_FILE）
+pub trait KeySource: Send + Sync {
+    /// 鍵セットを読み込む
+    ///
+    /// # Errors
+    /// 読み込みまたは検証に失敗した場合
+    fn load(&self) -> Result<KeySet, DomainError>;
+}
+
+// =============================================================================
+// JwtKeyRing
+// =============================================================================
+
+/// 差し替え可能な鍵セット
+///
+/// Clone は Arc の参照カウントを増やすだけで、全てのクローンが同じ鍵セットを参照する
+/// （AuthService と管理用エンドポイント、SIGHUP ハンドラで共有する）。
+#[derive(Clone)]
+pub struct JwtKeyRing {
+    /// 現在の鍵セット（reload で Arc ごと差し替える）
+    keys: Arc<RwLock<Arc<KeySet>>>,
+    /// リロード時の読み込み元（None の場合はリロード不可）
+    source: Option<Arc<dyn KeySource>>,
+}
+
+impl JwtKeyRing {
+    /// 固定の鍵セットから作成する（リロード不可）
+    pub fn new(keys: KeySet) -> Self {
+        Self {
+            keys: Arc::new(RwLock::new(Arc::new(keys))),
+            source: None,
+        }
+    }
+
+    /// 読み込み元から最初の鍵セットを読み込んで作成する（リロード可能）
+    ///
+    /// # Errors
+    /// 最初の読み込みに失敗した場合（起動を中止する）
+    pub fn from_source(source: Arc<dyn KeySource>) -> Result<Self, DomainError> {
+        let keys = source.load()?;
+        Ok(Self {
+            keys: Arc::new(RwLock::new(Arc::new(keys))),
+            source: Some(source),
+        })
+    }
+
+    /// 現在の鍵セット
+    ///
+    /// 返した Arc はリロード後も古い鍵セットを指し続ける（1 回の署名・検証の間は不変）。
+    pub fn snapshot(&self) -> Arc<KeySet> {
+        // 書き込み側は Arc の差し替えのみで panic しないため、poison は無視してよい
+        Arc::clone(&self.keys.read().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// リロードできるか（読み込み元が設定されているか）
+    pub fn is_reloadable(&self) -> bool {
+        self.source.is_some()
+    }
+
+    /// 読み込み元から鍵セットを再読み込みして差し替える
+    ///
+    /// # Returns
+    /// * `Ok(Arc<KeySet>)` - 新しい鍵セット
+    ///
+    /// # Errors
+    /// * `DomainError::Validation` - 読み込み元が設定されていない場合
+    /// * 読み込み元のエラー - この場合は古い鍵セットを使い続ける
+    pub fn reload(&self) -> Result<Arc<KeySet>, DomainError> {
+        let source = self.source.as_ref().ok_or_else(|| {
+            DomainError::Validation("JWT key reload is not configured".to_string())
+        })?;
+        // 読み込みはロックの外で行い、成功した場合のみ差し替える
+        let keys = Arc::new(source.load()?);
+        *self.keys.write().unwrap_or_else(|e| e.into_inner()) = Arc::clone(&keys);
+        Ok(keys)
+    }
+}
+
+impl fmt::Debug for JwtKeyRing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwtKeyRing")
+            .field("keys", &self.snapshot())
+            .field("reloadable", &self.is_reloadable())
+            .finish()
+    }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Mutex;
+
+    use chrono::{Duration, Utc};
+
+    /// 返す内容をテストから差し替えられる読み込み元
+    struct StubSource(Mutex<Result<String, String>>);
+
+    impl StubSource {
+        fn set(&self, result: Result<&str, &str>) {
+            *self.0.lock().unwrap() = result.map(str::to_string).map_err(str::to_string);
+        }
+    }
+
+    impl KeySource for StubSource {
+        fn load(&self) -> Result<KeySet, DomainError> {
+            match &*self.0.lock().unwrap() {
+                Ok(text) => KeySet::parse(text),
+                Err(e) => Err(DomainError::Validation(e.clone())),
+            }
+        }
+    }
+
+    fn claims() -> Claims {
+        let now = Utc::now();
+        Claims {
+            sub: "user-1".to_string(),
+            exp: (now + Duration::hours(1)).timestamp() as u64,
+            iat: now.timestamp() as u64,
+            iss: None,
+            aud: None,
+            extra: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_parse_key_file() {
+        let keys =
+            KeySet::parse("# comment\n\nnew:secret:with:colons\n old : old-secret \n").unwrap();
+
+        assert_eq!(keys.current().id(), "new");
+        assert_eq!(
+            keys.keys().map(SigningKey::id).collect::<Vec<_>>(),
+            vec!["new", "old"]
+        );
+        assert_eq!(keys.find(Some("old")).unwrap().secret, "old-secret");
+        assert_eq!(keys.find(Some("new")).unwrap().secret, "secret:with:colons");
+        assert!(keys.find(Some("missing")).is_none());
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_files() {
+        assert!(KeySet::parse("# only comments\n").is_err());
+        assert!(KeySet::parse("no-separator").is_err());
+        assert!(KeySet::parse("a:one\na:two").is_err());
+        assert!(KeySet::parse("a:").is_err());
+    }
+
+    #[test]
+    fn test_sign_includes_kid_and_verifies() {
+        let keys = KeySet::parse("k1:first-secret").unwrap();
+
+        let token = keys.sign(&claims()).unwrap();
+
+        assert_eq!(
+            jwt::decode_header(&token).unwrap().kid.as_deref(),
+            Some("k1")
+        );
+        assert_eq!(keys.verify(&token, None).unwrap().sub, "user-1");
+        assert!(keys.verify(&token, Some("other-issuer")).is_err());
+    }
+
+    /// ローテーション前に発行したトークンは、古い鍵が残っている間は検証できる
+    #[test]
+    fn test_token_signed_before_rotation_still_verifies() {
+        let source = Arc::new(StubSource(Mutex::new(Ok("old:old-secret".to_string()))));
+        let ring = JwtKeyRing::from_source(source.clone()).unwrap();
+        let old_token = ring.snapshot().sign(&claims()).unwrap();
+
+        // 新しい鍵を先頭に追加し、古い鍵を検証用に残す
+        source.set(Ok("new:new-secret\nold:old-secret"));
+        ring.reload().unwrap();
+
+        let keys = ring.snapshot();
+        assert_eq!(keys.current().id(), "new");
+        assert_eq!(keys.verify(&old_token, None).unwrap().sub, "user-1");
+        let new_token = keys.sign(&claims()).unwrap();
+        assert_eq!(
+            jwt::decode_header(&new_token).unwrap().kid.as_deref(),
+            Some("new")
+        );
+
+        // 古い鍵を削除すると、古いトークンは検証できなくなる
+        source.set(Ok("new:new-secret"));
+        ring.reload().unwrap();
+        assert!(ring.snapshot().verify(&old_token, None).is_err());
+        assert!(ring.snapshot().verify(&new_token, None).is_ok());
+    }
+
+    /// リロードに失敗した場合は古い鍵セットを使い続ける
+    #[test]
+    fn test_failed_reload_keeps_old_keys() {
+        let source = Arc::new(StubSource(Mutex::new(Ok("k1:first-secret".to_string()))));
+        let ring = JwtKeyRing::from_source(source.clone()).unwrap();
+        let token = ring.snapshot().sign(&claims()).unwrap();
+
+        source.set(Err("file not found"));
+        assert!(ring.reload().is_err());
+        source.set(Ok("dup:a\ndup:b"));
+        assert!(ring.reload().is_err());
+
+        assert_eq!(ring.snapshot().current().id(), "k1");
+        assert!(ring.snapshot().verify(&token, None).is_ok());
+    }
+
+    /// 固定の鍵セットはリロードできない。kid のないトークンは署名用の鍵で検証する
+    #[test]
+    fn test_static_key_ring() {
+        let ring = JwtKeyRing::new(KeySet::single("secret"));
+        assert!(!ring.is_reloadable());
+        assert!(ring.reload().is_err());
+
+        let legacy = jwt::encode(&Header::hs256(None), &claims(), b"secret").unwrap();
+        assert!(ring.snapshot().verify(&legacy, None).is_ok());
+    }
+
+    /// エッジ層の auth コンポーネントが発行するサービス用トークン（kid=default、iss なし）を
+    /// JWT_SECRET だけの鍵セットで検証できる
+    #[test]
+    fn test_verifies_edge_issued_service_token() {
+        let keys = KeySet::single("super-secret-key");
+        let mut service = claims();
+        service.sub = "edge-gateway".to_string();
+        service
+            .extra
+            .insert("request_id".to_string(), "req-1".into());
+        let token = jwt::encode(
+            &Header::hs256(Some(DEFAULT_KEY_ID)),
+            &service,
+            b"super-secret-key",
+        )
+        .unwrap();
+
+        let verified = keys.verify(&token, None).unwrap();
+        assert_eq!(verified.sub, "edge-gateway");
+        assert_eq!(verified.claim_str("request_id").as_deref(), Some("req-1"));
+        // コア層のユーザー用の検証（発行者を指定）では iss がないため拒否される
+        assert!(keys.verify(&token, Some("todo-api")).is_err());
+    }
+
+    #[test]
+    fn test_sign_with_hs512() {
+        let keys = KeySet::parse("k1:first-secret").unwrap();
+
+        let token = keys.sign_with(&claims(), JwtAlgorithm::Hs512).unwrap();
+
+        let header = jwt::decode_header(&token).unwrap();
+        assert_eq!(header.alg, "HS512");
+        assert_eq!(header.kid.as_deref(), Some("k1"));
+        assert_eq!(keys.verify(&token, None).unwrap().sub, "user-1");
+        // 切り替え前に HS256 で発行したトークンも引き続き検証できる
+        let legacy = keys.sign(&claims()).unwrap();
+        assert_eq!(jwt::decode_header(&legacy).unwrap().alg, "HS256");
+        assert!(keys.verify(&legacy, None).is_ok());
+    }
+
+    #[test]
+    fn test_parse_jwt_algorithm() {
+        assert_eq!("HS256".parse(), Ok(JwtAlgorithm::Hs256));
+        assert_eq!("hs384".parse(), Ok(JwtAlgorithm::Hs384));
+        assert_eq!("HS512".parse(), Ok(JwtAlgorithm::Hs512));
+        for invalid in ["", "RS256", "none", "HS1024"] {
+            assert!(invalid.parse::<JwtAlgorithm>().is_err(), "{}", invalid);
+        }
+        assert_eq!(JwtAlgorithm::default().to_string(), "HS256");
+    }
+
+    #[test]
+    fn test_debug_hides_secret() {
+        let debug = format!("{:?}", KeySet::single("top-secret"));
+        assert!(debug.contains(DEFAULT_KEY_ID));
+        assert!(!debug.contains("top-secret"));
+    }
+}
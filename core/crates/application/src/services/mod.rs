@@ -9,6 +9,7 @@
 //
 // このプロジェクトのサービス:
 // - AuthService: ユーザー認証（登録 + ログイン + JWT 発行）
+// - JwtKeyRing: JWT 署名鍵のセット（ホットリロード対応）
 //
 // サービスが適切なケース:
 // - 複数のリポジトリを使う処理（AuthService は UserReader + UserWriter）
@@ -23,6 +24,9 @@
 /// 認証サービス（登録、ログイン、JWT 発行）
 pub mod auth_service;
 
+/// JWT 署名鍵のセット（key id 付き、ホットリロード対応）
+pub mod jwt_keys;
+
 // -----------------------------------------------------------------------------
 // 再エクスポート（Re-export）
 // -----------------------------------------------------------------------------
@@ -31,3 +35,9 @@ pub mod auth_service;
 /// - AuthService: 認証サービス本体
 /// - Claims: JWT クレーム構造体
 pub use auth_service::*;
+
+/// jwt_keys 内の全公開アイテムを再エクスポート
+/// - JwtKeyRing: 差し替え可能な鍵セット
+/// - KeySet / SigningKey: 鍵セットと鍵
+/// - KeySource: リロード時の読み込み元
+pub use jwt_keys::*;
@@ -0,0 +1,251 @@
+// =============================================================================
+// domain/src/entities/import_job.rs: ImportJob エンティティ
+// =============================================================================
+// 大量の TODO を非同期でインポートするジョブを表すエンティティ。
+// リクエストではジョブを登録するだけで、実際の作成はバックグラウンドの
+// ワーカーがチャンク単位で行う。
+//
+// このエンティティが持つ責務:
+// - ジョブの状態（ステートマシン）と進捗のデータ構造を定義
+// - 状態遷移の可否を判定
+//
+// 状態遷移:
+//
+//   pending ──→ running ──→ completed
+//      │           │  └───→ failed
+//      │           │
+//      └───────────┴──────→ cancelled
+//
+// ワーカーが停止した running ジョブは、リース切れ後に別のワーカーが
+// running のまま再取得する（状態遷移は発生しない）
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// 外部クレートのインポート
+// -----------------------------------------------------------------------------
+
+// std: 文字列からの変換
+use std::str::FromStr;
+
+// chrono: 日時処理
+use chrono::{DateTime, Utc};
+
+// serde: シリアライズ/デシリアライズ
+use serde::{Deserialize, Serialize};
+
+// uuid: 一意識別子
+use uuid::Uuid;
+
+// 同じクレート内のエラー型
+use crate::errors::DomainError;
+
+// =============================================================================
+// JobState 列挙型
+// =============================================================================
+
+/// インポートジョブの状態
+///
+/// JSON / DB では snake_case の文字列として扱う。
+///
+/// # derive マクロ
+///
+/// - `Copy`: 小さな列挙型のため値渡しで扱う
+/// - `Serialize/Deserialize`: `"pending"` などの文字列に変換
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    /// 登録済み、ワーカー未取得
+    Pending,
+    /// ワーカーが処理中
+    Running,
+    /// 全項目の処理が完了（一部の項目が失敗していても completed）
+    Completed,
+    /// ジョブ自体が失敗（DB エラーなど）
+    Failed,
+    /// ユーザーによりキャンセル
+    Cancelled,
+}
+
+impl JobState {
+    /// DB に保存する文字列表現を返す
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobState::Pending => "pending",
+            JobState::Running => "running",
+            JobState::Completed => "completed",
+            JobState::Failed => "failed",
+            JobState::Cancelled => "cancelled",
+        }
+    }
+
+    /// 終了状態か（これ以上遷移しない）
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            JobState::Completed | JobState::Failed | JobState::Cancelled
+        )
+    }
+
+    /// `next` への遷移が許可されているか
+    ///
+    /// # 許可される遷移
+    ///
+    /// - pending → running / cancelled
+    /// - running → completed / failed / cancelled
+    pub fn can_transition_to(&self, next: JobState) -> bool {
+        matches!(
+            (self, next),
+            (JobState::Pending, JobState::Running)
+                | (JobState::Pending, JobState::Cancelled)
+                | (JobState::Running, JobState::Completed)
+                | (JobState::Running, JobState::Failed)
+                | (JobState::Running, JobState::Cancelled)
+        )
+    }
+}
+
+/// DB の文字列から JobState への変換
+impl FromStr for JobState {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(JobState::Pending),
+            "running" => Ok(JobState::Running),
+            "completed" => Ok(JobState::Completed),
+            "failed" => Ok(JobState::Failed),
+            "cancelled" => Ok(JobState::Cancelled),
+            other => Err(DomainError::Repository(format!(
+                "unknown job state: {}",
+                other
+            ))),
+        }
+    }
+}
+
+// =============================================================================
+// ImportJob 構造体の定義
+// =============================================================================
+
+/// ImportJob エンティティ
+///
+/// # データベーステーブルとの対応
+///
+/// | フィールド | カラム | 型 |
+/// |-----------|--------|-----|
+/// | id | id | UUID (PRIMARY KEY) |
+/// | user_id | user_id | UUID (FOREIGN KEY → users.id) |
+/// | state | state | TEXT NOT NULL |
+/// | total_items | total_items | INTEGER NOT NULL |
+/// | processed_items | processed_items | INTEGER NOT NULL |
+/// | failed_items | failed_items | INTEGER NOT NULL |
+/// | error | error | TEXT |
+/// | created_at | created_at | TIMESTAMPTZ |
+/// | updated_at | updated_at | TIMESTAMPTZ |
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportJob {
+    /// ジョブの一意識別子
+    pub id: Uuid,
+
+    /// ジョブを登録したユーザーの ID
+    pub user_id: Uuid,
+
+    /// 現在の状態
+    pub state: JobState,
+
+    /// インポート対象の項目数
+    pub total_items: i32,
+
+    /// 処理済みの項目数（成功 + 失敗）
+    ///
+    /// ワーカーの再開位置（カーソル）を兼ねる
+    pub processed_items: i32,
+
+    /// 失敗した項目数（バリデーションエラーなど）
+    pub failed_items: i32,
+
+    /// ジョブ自体が失敗した場合のエラーメッセージ
+    pub error: Option<String>,
+
+    /// 登録日時
+    pub created_at: DateTime<Utc>,
+
+    /// 最終更新日時（ワーカーのリース判定にも使用）
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ImportJob {
+    /// DB から読み込んだ値で ImportJob を再構築する
+    ///
+    /// バリデーションは行わない（DB の値は検証済みとみなす）。
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_raw(
+        id: Uuid,
+        user_id: Uuid,
+        state: JobState,
+        total_items: i32,
+        processed_items: i32,
+        failed_items: i32,
+        error: Option<String>,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            state,
+            total_items,
+            processed_items,
+            failed_items,
+            error,
+            created_at,
+            updated_at,
+        }
+    }
+
+    /// 成功した項目数
+    pub fn succeeded_items(&self) -> i32 {
+        self.processed_items - self.failed_items
+    }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [JobState; 5] = [
+        JobState::Pending,
+        JobState::Running,
+        JobState::Completed,
+        JobState::Failed,
+        JobState::Cancelled,
+    ];
+
+    #[test]
+    fn test_job_state_round_trips_through_str() {
+        for state in ALL {
+            assert_eq!(state.as_str().parse::<JobState>().unwrap(), state);
+        }
+        assert!("done".parse::<JobState>().is_err());
+    }
+
+    #[test]
+    fn test_terminal_states_do_not_transition() {
+        for from in ALL.into_iter().filter(JobState::is_terminal) {
+            for to in ALL {
+                assert!(!from.can_transition_to(to), "{:?} -> {:?}", from, to);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cancellation_allowed_before_completion() {
+        assert!(JobState::Pending.can_transition_to(JobState::Cancelled));
+        assert!(JobState::Running.can_transition_to(JobState::Cancelled));
+        assert!(!JobState::Pending.can_transition_to(JobState::Completed));
+    }
+}
@@ -12,6 +12,7 @@
 // - Todo: TODO アイテム（タスク管理の中心）
 // - User: ユーザー（認証・認可の主体）
 // - File: 添付ファイル（TODO に紐づくファイルメタデータ）
+// - ImportJob: 非同期インポートジョブ（状態と進捗）
 // =============================================================================
 
 // -----------------------------------------------------------------------------
@@ -23,6 +24,8 @@
 
 /// File エンティティを定義するモジュール
 mod file;
+/// ImportJob エンティティを定義するモジュール
+mod import_job;
 
 /// Todo エンティティを定義するモジュール
 mod todo;
@@ -40,9 +43,11 @@ mod user;
 
 /// File エンティティを再エクスポート
 pub use file::File;
+/// ImportJob エンティティとジョブ状態を再エクスポート
+pub use import_job::{ImportJob, JobState};
 
 /// Todo エンティティを再エクスポート
 pub use todo::Todo;
 
 /// User エンティティを再エクスポート
-pub use user::User;
+pub use user::{Role, User};
@@ -50,6 +50,7 @@ use crate::errors::DomainError;
 /// |-----------|--------|-----|
 /// | id | id | UUID (PRIMARY KEY) |
 /// | user_id | user_id | UUID (FOREIGN KEY → users.id) |
+/// | tenant_id | tenant_id | UUID (NULL 許容) |
 /// | title | title | VARCHAR(255) NOT NULL |
 /// | description | description | TEXT |
 /// | completed | completed | BOOLEAN DEFAULT false |
@@ -78,6 +79,15 @@ pub struct Todo {
     /// SQL クエリでは必ず user_id でフィルタリングする。
     pub user_id: Uuid,
 
+    /// 作成時のテナント ID（任意）
+    ///
+    /// Edge 層が転送した X-Claim-Tenant-Id を、テナント単位の絞り込み
+    /// （TENANT_SCOPING=true）が有効な場合のみ記録する。
+    /// 無効な場合や、テナントのないトークンで作成した場合は None。
+    /// None の場合は JSON に含めない（キャッシュ済みの古い値は None として読む）。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<Uuid>,
+
     /// タイトル（必須、空文字不可）
     ///
     /// TODO の主要な識別名。一覧画面等で表示される。
@@ -150,6 +160,9 @@ impl Todo {
             // 所有者の ID を設定
             user_id,
 
+            // テナントは with_tenant() で設定する
+            tenant_id: None,
+
             // タイトルを設定（所有権を移動）
             title,
 
@@ -182,6 +195,7 @@ impl Todo {
     /// # Note
     /// このメソッドは既にバリデーション済みのデータを復元するため、
     /// 新規データには new() を使用すること。
+    /// tenant_id は None で復元される（必要なら with_tenant() で設定する）。
     pub fn from_raw(
         id: Uuid,
         user_id: Uuid,
@@ -196,6 +210,7 @@ impl Todo {
         Self {
             id,
             user_id,
+            tenant_id: None,
             title,
             description,
             completed,
@@ -204,6 +219,18 @@ impl Todo {
         }
     }
 
+    /// テナント ID を設定（ビルダーパターン）
+    ///
+    /// # Arguments
+    /// * `tenant_id` - テナント ID（None ならテナントなし）
+    ///
+    /// # Returns
+    /// 自身を返す（メソッドチェーン可能）
+    pub fn with_tenant(mut self, tenant_id: Option<Uuid>) -> Self {
+        self.tenant_id = tenant_id;
+        self
+    }
+
     /// タイトルのバリデーション
     ///
     /// # Arguments
@@ -241,6 +268,29 @@ impl Todo {
         Ok(trimmed.to_string())
     }
 
+    /// 重複判定用にタイトルを正規化する
+    ///
+    /// 前後の空白を除去し、連続する空白を 1 つの半角スペースにまとめ、小文字化する。
+    /// バッチ作成の重複検出など、「同じ TODO」を判定する処理はこの関数を共有する。
+    ///
+    /// # Note
+    /// DB 側の重複検索（`lower(regexp_replace(btrim(title), '\s+', ' ', 'g'))`）と
+    /// 同じ規則にすること。
+    ///
+    /// # Example
+    /// ```
+    /// use domain::Todo;
+    ///
+    /// assert_eq!(Todo::normalize_title("  Buy   Milk "), "buy milk");
+    /// ```
+    pub fn normalize_title(title: &str) -> String {
+        title
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    }
+
     /// Todo を更新
     ///
     /// 部分更新をサポート: None のフィールドは既存の値を維持。
@@ -341,6 +391,17 @@ mod tests {
         assert!(matches!(result, Err(DomainError::Validation(_))));
     }
 
+    /// タイトル正規化のテスト（大文字小文字・空白の違いを吸収）
+    #[test]
+    fn test_normalize_title_folds_case_and_whitespace() {
+        assert_eq!(Todo::normalize_title("Buy Milk"), "buy milk");
+        assert_eq!(Todo::normalize_title("  buy\t  MILK\n"), "buy milk");
+        assert_eq!(
+            Todo::normalize_title("買い物  リスト"),
+            Todo::normalize_title("買い物 リスト")
+        );
+    }
+
     /// 部分更新のテスト
     #[test]
     fn test_update_partial() {
@@ -367,4 +428,26 @@ mod tests {
         // updated_at は更新されていること
         assert!(todo.updated_at > original_created_at);
     }
+
+    /// テナント ID の設定のテスト（new / from_raw は None）
+    #[test]
+    fn test_with_tenant() {
+        let tenant_id = Uuid::new_v4();
+        let todo = Todo::new(Uuid::new_v4(), "タイトル".to_string(), None);
+        assert_eq!(todo.tenant_id, None);
+
+        let scoped = todo.with_tenant(Some(tenant_id));
+        assert_eq!(scoped.tenant_id, Some(tenant_id));
+
+        let restored = Todo::from_raw(
+            scoped.id,
+            scoped.user_id,
+            scoped.title.clone(),
+            None,
+            false,
+            scoped.created_at,
+            scoped.updated_at,
+        );
+        assert_eq!(restored.tenant_id, None);
+    }
 }
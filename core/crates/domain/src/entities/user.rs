@@ -8,6 +8,7 @@
 // - ユーザーのデータ構造を定義
 // - 認証に必要な情報（メール、パスワードハッシュ）を保持
 // - バリデーションルール（メール形式、パスワード長）をカプセル化
+// - ロール（一般ユーザー / 管理者）を保持
 //
 // セキュリティ考慮:
 // - password_hash は #[serde(skip_serializing)] で JSON 出力から除外
@@ -18,6 +19,9 @@
 // 外部クレートのインポート
 // -----------------------------------------------------------------------------
 
+// FromStr: DB の文字列から Role への変換
+use std::str::FromStr;
+
 // chrono: 日時処理ライブラリ
 use chrono::{DateTime, Utc};
 
@@ -30,6 +34,47 @@ use uuid::Uuid;
 // 同じクレート内のエラー型
 use crate::errors::DomainError;
 
+// =============================================================================
+// Role 列挙型
+// =============================================================================
+
+/// ユーザーのロール
+///
+/// JSON / DB では snake_case の文字列として扱う。
+/// 新規登録したユーザーは `User`。`Admin` は運用コマンド（create-admin）でのみ付与する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// 一般ユーザー
+    #[default]
+    User,
+    /// 管理者
+    Admin,
+}
+
+impl Role {
+    /// DB に保存する文字列表現を返す
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+/// DB の文字列から Role への変換
+impl FromStr for Role {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user" => Ok(Role::User),
+            "admin" => Ok(Role::Admin),
+            other => Err(DomainError::Repository(format!("unknown role: {}", other))),
+        }
+    }
+}
+
 // =============================================================================
 // User 構造体の定義
 // =============================================================================
@@ -47,6 +92,7 @@ use crate::errors::DomainError;
 /// | email | email | VARCHAR(255) UNIQUE NOT NULL |
 /// | password_hash | password_hash | VARCHAR(255) NOT NULL |
 /// | display_name | display_name | VARCHAR(255) |
+/// | role | role | TEXT NOT NULL（user / admin） |
 /// | created_at | created_at | TIMESTAMPTZ |
 /// | updated_at | updated_at | TIMESTAMPTZ |
 // -----------------------------------------------------------------------------
@@ -87,6 +133,9 @@ pub struct User {
     /// 未設定の場合は None。
     pub display_name: Option<String>,
 
+    /// ロール（新規登録時は User）
+    pub role: Role,
+
     /// 作成日時（UTC）
     pub created_at: DateTime<Utc>,
 
@@ -130,6 +179,9 @@ impl User {
             // 表示名を設定（None でも可）
             display_name,
 
+            // 新規登録は一般ユーザー
+            role: Role::User,
+
             // 作成日時と更新日時を現在時刻で初期化
             created_at: now,
             updated_at: now,
@@ -146,6 +198,7 @@ impl User {
     /// * `email` - メールアドレス
     /// * `password_hash` - パスワードハッシュ
     /// * `display_name` - 表示名
+    /// * `role` - ロール
     /// * `created_at` - 作成日時
     /// * `updated_at` - 最終更新日時
     pub fn from_raw(
@@ -153,6 +206,7 @@ impl User {
         email: String,
         password_hash: String,
         display_name: Option<String>,
+        role: Role,
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
     ) -> Self {
@@ -162,6 +216,7 @@ impl User {
             email,
             password_hash,
             display_name,
+            role,
             created_at,
             updated_at,
         }
@@ -259,6 +314,25 @@ impl User {
         // 更新日時を現在時刻に設定
         self.updated_at = Utc::now();
     }
+
+    /// 管理者か
+    pub fn is_admin(&self) -> bool {
+        self.role == Role::Admin
+    }
+
+    /// 管理者に昇格する
+    ///
+    /// # Returns
+    /// * `true` - 昇格した
+    /// * `false` - 既に管理者（変更なし）
+    pub fn promote_to_admin(&mut self) -> bool {
+        if self.is_admin() {
+            return false;
+        }
+        self.role = Role::Admin;
+        self.updated_at = Utc::now();
+        true
+    }
 }
 
 // =============================================================================
@@ -283,6 +357,28 @@ mod tests {
         assert_eq!(user.email, "test@example.com");
         assert_eq!(user.password_hash, "hashed_password");
         assert_eq!(user.display_name, Some("Test User".to_string()));
+        assert_eq!(user.role, Role::User);
+    }
+
+    #[test]
+    fn test_promote_to_admin() {
+        let mut user = User::new("admin@example.com".to_string(), "hash".to_string(), None);
+
+        assert!(user.promote_to_admin());
+        assert!(user.is_admin());
+        // 既に管理者なら変更しない
+        assert!(!user.promote_to_admin());
+    }
+
+    #[test]
+    fn test_role_round_trip() {
+        for role in [Role::User, Role::Admin] {
+            assert_eq!(role.as_str().parse::<Role>().unwrap(), role);
+        }
+        assert!(matches!(
+            "owner".parse::<Role>(),
+            Err(DomainError::Repository(_))
+        ));
     }
 
     /// メールバリデーション成功のテスト
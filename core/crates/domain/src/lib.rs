@@ -82,7 +82,7 @@ pub mod repositories;
 
 /// エンティティを直接アクセス可能に
 /// `domain::Todo`, `domain::User`, `domain::File` として使用可能
-pub use entities::{File, Todo, User};
+pub use entities::{File, ImportJob, JobState, Role, Todo, User};
 
 // -----------------------------------------------------------------------------
 // エラーの再エクスポート
@@ -105,7 +105,8 @@ pub use errors::DomainError;
 /// - `TodoCacheOps`: TODO キャッシュ操作
 /// - `TodoFilter`: TODO 一覧取得のフィルタ条件
 /// - `StorageOps`: ファイルストレージ操作（S3 等の抽象化）
+/// - `StoredObject`: ストレージ一覧の要素
 pub use repositories::{
-    FileReader, FileWriter, StorageOps, TodoCacheOps, TodoFilter, TodoReader, TodoWriter,
-    UserReader, UserWriter,
+    FileReader, FileWriter, StorageOps, StoredObject, TodoCacheOps, TodoFilter, TodoReader,
+    TodoWriter, UserReader, UserWriter,
 };
@@ -54,7 +54,7 @@ mod user_repository;
 pub use file_repository::{FileReader, FileWriter};
 
 /// ストレージ操作トレイトを再エクスポート
-pub use storage_repository::StorageOps;
+pub use storage_repository::{StorageOps, StoredObject};
 
 /// TODO キャッシュ操作トレイトを再エクスポート
 pub use todo_cache::TodoCacheOps;
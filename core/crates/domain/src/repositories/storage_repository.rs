@@ -24,12 +24,28 @@
 // async_trait: トレイト内で async fn を使用可能にする
 use async_trait::async_trait;
 
+// chrono: 最終更新日時
+use chrono::{DateTime, Utc};
+
 // uuid: 一意識別子
 use uuid::Uuid;
 
 // 同じクレート内のエラー型
 use crate::errors::DomainError;
 
+// =============================================================================
+// StoredObject 構造体
+// =============================================================================
+
+/// ストレージ上のオブジェクト（一覧取得の結果）
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredObject {
+    /// ストレージ上のキー（upload 時に返された値と同じ形式）
+    pub storage_path: String,
+    /// 最終更新日時（UTC）
+    pub last_modified: DateTime<Utc>,
+}
+
 // =============================================================================
 // StorageOps トレイト
 // =============================================================================
@@ -108,4 +124,16 @@ pub trait StorageOps: Send + Sync {
     /// # Note
     /// S3 の DeleteObject は冪等。存在しないキーを削除してもエラーにならない。
     async fn delete(&self, storage_path: &str) -> Result<(), DomainError>;
+
+    /// プレフィックスに一致するオブジェクトを一覧する
+    ///
+    /// 孤立ファイルの掃除（api cleanup-orphans）で使用する。
+    ///
+    /// # Arguments
+    /// * `prefix` - キーのプレフィックス（例: `users/`）
+    ///
+    /// # Returns
+    /// * `Ok(Vec<StoredObject>)` - 一致したオブジェクト（順序は実装依存）
+    /// * `Err(DomainError::External)` - ストレージエラー
+    async fn list(&self, prefix: &str) -> Result<Vec<StoredObject>, DomainError>;
 }
@@ -58,6 +58,9 @@ use crate::errors::DomainError;
 ///
 /// // 未完了のみ取得
 /// let filter = TodoFilter::new(user_id).with_completed(Some(false));
+///
+/// // テナントで絞り込み（TENANT_SCOPING=true の場合）
+/// let filter = TodoFilter::new(user_id).with_tenant(Some(Uuid::new_v4()));
 /// ```
 // -----------------------------------------------------------------------------
 // derive マクロ:
@@ -78,6 +81,14 @@ pub struct TodoFilter {
     /// - `Some(false)`: 未完了の TODO のみ取得
     /// - `None`: 全件取得（完了状態でフィルタリングしない）
     pub completed: Option<bool>,
+
+    /// テナント ID でフィルタリング
+    ///
+    /// - `Some(tenant_id)`: そのテナントで作成した TODO のみ取得
+    /// - `None`: テナントでフィルタリングしない
+    ///
+    /// user_id の条件に追加で適用する（他のユーザーの TODO は常に対象外）。
+    pub tenant_id: Option<Uuid>,
 }
 
 impl TodoFilter {
@@ -87,12 +98,13 @@ impl TodoFilter {
     /// * `user_id` - 所有者のユーザー ID
     ///
     /// # Returns
-    /// completed = None（フィルタなし）の TodoFilter
+    /// completed / tenant_id = None（フィルタなし）の TodoFilter
     pub fn new(user_id: Uuid) -> Self {
         Self {
             user_id,
             // デフォルトは None（全件取得）
             completed: None,
+            tenant_id: None,
         }
     }
 
@@ -114,6 +126,18 @@ impl TodoFilter {
         // 自身を返す（メソッドチェーン可能）
         self
     }
+
+    /// テナントフィルタを設定（ビルダーパターン）
+    ///
+    /// # Arguments
+    /// * `tenant_id` - テナント ID（None ならフィルタなし）
+    ///
+    /// # Returns
+    /// 自身を返す（メソッドチェーン可能）
+    pub fn with_tenant(mut self, tenant_id: Option<Uuid>) -> Self {
+        self.tenant_id = tenant_id;
+        self
+    }
 }
 
 // =============================================================================
@@ -0,0 +1,336 @@
+// =============================================================================
+// edge-signature/src/lib.rs: Edge 署名（X-Edge-Signature）の共有クレート
+// =============================================================================
+// エッジ層の gateway がコア層に転送するリクエストに署名し、
+// コア層の edge_verify ミドルウェアが検証する。
+//
+// 以前は固定のシークレット（X-Edge-Verified）をそのまま比較していたため、
+// 一度ヘッダーを見られると無期限に再利用（リプレイ）できた。
+// 署名にタイムスタンプ・メソッド・パス・ユーザー ID を含め、
+// 許容範囲を過ぎたものや別のリクエストに付け替えたものを拒否する。
+//
+// ヘッダーの形式:
+//   X-Edge-Signature: t=<unix_ts>,v1=<hex(hmac_sha256(署名対象, key))>
+//
+// 署名対象（mac）:
+//   "<unix_ts>\n<METHOD>\n<パス（クエリを含む）>\n<ユーザー ID>"
+//   区切りがないと "/api/a" + "b" と "/api/ab" + "" が同じになるため改行で区切る。
+//   ユーザー ID がない（パブリックパス）場合は空文字列。
+//
+// 利用側:
+// - edge/gateway: sign（proxy_to_core・proxy_to_core_public）
+// - core/crates/presentation: verify（edge_verify ミドルウェア）
+//
+// no_std:
+// - alloc のみを使い、wasm32-wasip1 でもビルドできる
+// - 現在時刻は呼び出し側が渡す
+// =============================================================================
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// HMAC-SHA256
+type HmacSha256 = Hmac<Sha256>;
+
+// =============================================================================
+// 定数
+// =============================================================================
+
+/// 署名のヘッダー名（小文字）
+pub const HEADER: &str = "x-edge-signature";
+
+/// 署名のバージョン（ヘッダーの v1=）
+///
+/// 署名対象や方式を変える場合は v2= を追加し、移行中は両方を付与できるようにする。
+const VERSION: &str = "v1";
+
+/// タイムスタンプの許容範囲の既定値（秒）
+///
+/// 署名から検証までの遅延と、エッジ層・コア層の時計のずれを吸収する。
+pub const DEFAULT_MAX_AGE_SECS: u64 = 60;
+
+// =============================================================================
+// Error
+// =============================================================================
+
+/// 検証のエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// ヘッダー値が t=<unix_ts>,v1=<hex> の形式でない
+    Malformed,
+    /// 署名が一致しない（鍵・メソッド・パス・ユーザー ID のいずれかが異なる）
+    InvalidSignature,
+    /// タイムスタンプが許容範囲より古い
+    Expired,
+    /// タイムスタンプが許容範囲より未来（時計のずれが大きい）
+    NotYetValid,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed => f.write_str("Malformed edge signature"),
+            Self::InvalidSignature => f.write_str("Invalid edge signature"),
+            Self::Expired => f.write_str("Edge signature expired"),
+            Self::NotYetValid => f.write_str("Edge signature timestamp is in the future"),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+// =============================================================================
+// 署名
+// =============================================================================
+
+/// リクエストに署名し、X-Edge-Signature の値を返す
+///
+/// # Arguments
+///
+/// * `key` - 共有鍵（コア層の EDGE_SECRET、エッジ層の edge_secret）
+/// * `timestamp` - 署名時刻（Unix タイムスタンプ秒）
+/// * `method` - HTTP メソッド（例: "GET"）
+/// * `path` - パス（クエリを含む、例: "/api/todos?completed=false"）
+/// * `user_id` - 認証済みユーザー ID（ない場合は ""）
+///
+/// # Returns
+///
+/// `t=<timestamp>,v1=<hex>` 形式のヘッダー値
+///
+/// # 使用例
+///
+/// ```
+/// let value = edge_signature::sign(b"key", 1_700_000_000, "GET", "/api/todos", "user-1");
+/// assert!(value.starts_with("t=1700000000,v1="));
+/// ```
+pub fn sign(key: &[u8], timestamp: u64, method: &str, path: &str, user_id: &str) -> String {
+    let signature = mac(key, timestamp, method, path, user_id)
+        .finalize()
+        .into_bytes();
+    format!("t={},{}={}", timestamp, VERSION, to_hex(&signature))
+}
+
+/// X-Edge-Signature の値を検証する
+///
+/// 署名を確認してからタイムスタンプを確認する（ログで改ざんと期限切れを区別できるように）。
+///
+/// # Arguments
+///
+/// * `value` - X-Edge-Signature の値
+/// * `key` - 共有鍵
+/// * `method` / `path` / `user_id` - 受信したリクエストの値（sign と同じ形式）
+/// * `now` - 現在時刻（Unix タイムスタンプ秒）
+/// * `max_age_secs` - タイムスタンプの許容範囲（秒、過去・未来の両方向）
+///
+/// # Errors
+///
+/// 形式が不正、署名が一致しない、またはタイムスタンプが許容範囲外の場合
+pub fn verify(
+    value: &str,
+    key: &[u8],
+    method: &str,
+    path: &str,
+    user_id: &str,
+    now: u64,
+    max_age_secs: u64,
+) -> Result<(), Error> {
+    let (timestamp, signature) = parse(value)?;
+
+    // 定数時間で比較
+    mac(key, timestamp, method, path, user_id)
+        .verify_slice(&signature)
+        .map_err(|_| Error::InvalidSignature)?;
+
+    if now > timestamp.saturating_add(max_age_secs) {
+        return Err(Error::Expired);
+    }
+    if timestamp > now.saturating_add(max_age_secs) {
+        return Err(Error::NotYetValid);
+    }
+    Ok(())
+}
+
+/// 署名対象に対する HMAC を計算する（finalize 前）
+fn mac(key: &[u8], timestamp: u64, method: &str, path: &str, user_id: &str) -> HmacSha256 {
+    // HMAC はどの長さの鍵も受け付ける（new_from_slice が失敗することはない）
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}\n{}\n{}\n{}", timestamp, method, path, user_id).as_bytes());
+    mac
+}
+
+// =============================================================================
+// ヘッダー値の解析
+// =============================================================================
+
+/// `t=<unix_ts>,v1=<hex>` を解析する
+///
+/// 順序は問わない。未知のキー（将来のバージョン）は無視する。
+fn parse(value: &str) -> Result<(u64, Vec<u8>), Error> {
+    let mut timestamp = None;
+    let mut signature = None;
+    for part in value.split(',') {
+        let (name, field) = part.trim().split_once('=').ok_or(Error::Malformed)?;
+        match name {
+            "t" => timestamp = Some(field.parse::<u64>().map_err(|_| Error::Malformed)?),
+            VERSION => signature = Some(from_hex(field).ok_or(Error::Malformed)?),
+            _ => {}
+        }
+    }
+    Ok((
+        timestamp.ok_or(Error::Malformed)?,
+        signature.ok_or(Error::Malformed)?,
+    ))
+}
+
+/// バイト列を小文字の 16 進数にする
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// 16 進数をバイト列にする（大文字小文字を区別しない、奇数桁・16 進数以外は None）
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    const KEY: &[u8] = b"shared-edge-key";
+    const NOW: u64 = 1_700_000_000;
+
+    fn check(value: &str, path: &str, now: u64) -> Result<(), Error> {
+        verify(value, KEY, "GET", path, "user-1", now, DEFAULT_MAX_AGE_SECS)
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let value = sign(KEY, NOW, "GET", "/api/todos?completed=false", "user-1");
+        assert!(value.starts_with("t=1700000000,v1="));
+        assert_eq!(value.len(), "t=1700000000,v1=".len() + 64);
+        assert_eq!(check(&value, "/api/todos?completed=false", NOW), Ok(()));
+
+        // ユーザー ID がない（パブリックパス）場合も署名できる
+        let value = sign(KEY, NOW, "POST", "/api/auth/login", "");
+        assert_eq!(
+            verify(&value, KEY, "POST", "/api/auth/login", "", NOW, 60),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_tampered_request_is_rejected() {
+        let value = sign(KEY, NOW, "GET", "/api/todos/1", "user-1");
+
+        // パス・クエリの改ざん
+        assert_eq!(
+            check(&value, "/api/todos/2", NOW),
+            Err(Error::InvalidSignature)
+        );
+        assert_eq!(
+            check(&value, "/api/todos/1?all=true", NOW),
+            Err(Error::InvalidSignature)
+        );
+        // メソッド・ユーザー ID・鍵の違い
+        assert_eq!(
+            verify(&value, KEY, "DELETE", "/api/todos/1", "user-1", NOW, 60),
+            Err(Error::InvalidSignature)
+        );
+        assert_eq!(
+            verify(&value, KEY, "GET", "/api/todos/1", "user-2", NOW, 60),
+            Err(Error::InvalidSignature)
+        );
+        assert_eq!(
+            verify(
+                &value,
+                b"other-key",
+                "GET",
+                "/api/todos/1",
+                "user-1",
+                NOW,
+                60
+            ),
+            Err(Error::InvalidSignature)
+        );
+        // 区切りをずらしても同じ署名にならない
+        let value = sign(KEY, NOW, "GET", "/api/a", "b");
+        assert_eq!(
+            verify(&value, KEY, "GET", "/api/ab", "", NOW, 60),
+            Err(Error::InvalidSignature)
+        );
+        // タイムスタンプの付け替え
+        let value = sign(KEY, NOW - 3600, "GET", "/api/todos/1", "user-1")
+            .replace("t=1699996400", "t=1700000000");
+        assert_eq!(
+            check(&value, "/api/todos/1", NOW),
+            Err(Error::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_clock_skew_window() {
+        let value = sign(KEY, NOW, "GET", "/api/todos", "user-1");
+
+        // 許容範囲内のずれ（過去・未来とも）
+        assert_eq!(check(&value, "/api/todos", NOW + 60), Ok(()));
+        assert_eq!(check(&value, "/api/todos", NOW - 60), Ok(()));
+
+        // 許容範囲外
+        assert_eq!(check(&value, "/api/todos", NOW + 61), Err(Error::Expired));
+        assert_eq!(
+            check(&value, "/api/todos", NOW - 61),
+            Err(Error::NotYetValid)
+        );
+        assert_eq!(
+            verify(&value, KEY, "GET", "/api/todos", "user-1", NOW + 10, 5),
+            Err(Error::Expired)
+        );
+    }
+
+    #[test]
+    fn test_malformed_values() {
+        let signature = sign(KEY, NOW, "GET", "/api/todos", "user-1");
+        let hex = signature.split_once("v1=").unwrap().1.to_string();
+
+        for value in [
+            "",
+            "super-secret-edge-key",
+            "t=1700000000",
+            &format!("v1={}", hex),
+            &format!("t=soon,v1={}", hex),
+            &format!("t=1700000000,v1={}", &hex[1..]),
+            "t=1700000000,v1=zz",
+        ] {
+            assert_eq!(
+                check(value, "/api/todos", NOW),
+                Err(Error::Malformed),
+                "{}",
+                value
+            );
+        }
+
+        // 順序・空白・未知のキーは許容する
+        let reordered = format!(" v1={} , t=1700000000, v2=abc", hex.to_uppercase());
+        assert_eq!(check(&reordered, "/api/todos", NOW), Ok(()));
+    }
+}
@@ -0,0 +1,275 @@
+// =============================================================================
+// error-code/src/lib.rs: エラーレスポンスの機械可読なコードの共有クレート
+// =============================================================================
+// エラーレスポンスの error は人が読むためのメッセージで、文言は変わりうる。
+// クライアントが "Missing token" と "Token expired" を文字列の一致で区別しなくて済むよう、
+// 変更しない code を併せて返す。
+//
+// レスポンスの形式:
+//   {"error": "Token expired", "code": "token_expired", "request_id": "..."}
+//   request_id はリクエスト ID が決まっている場合のみ（X-Request-Id と同じ値）。
+//
+// 利用側:
+// - edge/gateway: 全てのエラーレスポンス（ErrorResponse）
+// - core/crates/presentation: ApiError のうちコードを返すもの（batch_too_large など）
+//
+// コードを追加する場合:
+// - ErrorCode にバリアントを追加し、as_str・status・ALL に追加する
+// - 公開したコードの文字列は変更・削除しない
+// =============================================================================
+
+#![no_std]
+
+use core::fmt;
+
+// =============================================================================
+// ErrorCode
+// =============================================================================
+
+/// エラーレスポンスの code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    // -------------------------------------------------------------------------
+    // 認証（401）
+    // -------------------------------------------------------------------------
+    /// トークンがない（Authorization ヘッダー・Cookie のどちらもない）
+    MissingToken,
+    /// トークンの sub・発行者・対象者などが不正（他のコードに当たらないもの）
+    InvalidToken,
+    /// トークンの有効期限（exp）を過ぎた
+    TokenExpired,
+    /// トークンの有効期間の開始（nbf）より前、または発行日時（iat）が未来
+    TokenNotYetValid,
+    /// トークンの署名が一致しない、または署名鍵（kid）がない
+    InvalidSignature,
+    /// トークンの署名アルゴリズムを受け付けていない
+    UnsupportedAlgorithm,
+    /// トークンが失効している（ログアウト・パスワードの変更などで失効させた）
+    TokenRevoked,
+    /// Authorization ヘッダーが不正（制御文字・複数の資格情報・上限を超える長さ）
+    MalformedAuthorization,
+    /// X-Api-Key の API キーが一致しない
+    InvalidApiKey,
+
+    // -------------------------------------------------------------------------
+    // リクエストの誤り（4xx）
+    // -------------------------------------------------------------------------
+    /// Authorization ヘッダーが複数ある（400）
+    MultipleAuthorizationHeaders,
+    /// トークンが JWT の形式でない・Base64URL や JSON が不正（400）
+    MalformedToken,
+    /// トークンが暗号化されている（JWE、署名したトークン（JWS）のみ受け付ける、400）
+    EncryptedToken,
+    /// リクエストボディを読み込めない（400）
+    InvalidRequestBody,
+    /// 認可されていない・許可していない IP（403）
+    Forbidden,
+    /// パスが見つからない（404）
+    NotFound,
+    /// パスに対してメソッドを許可していない（405）
+    MethodNotAllowed,
+    /// リクエストボディが上限を超える（413）
+    PayloadTooLarge,
+    /// Content-Type に対応していない（415）
+    UnsupportedMediaType,
+    /// バッチの件数が上限を超える（422）
+    BatchTooLarge,
+    /// CSV を解析できない（422）
+    MalformedCsv,
+    /// レート制限を超えた（429）
+    RateLimited,
+
+    // -------------------------------------------------------------------------
+    // サーバー・転送先の状態（5xx）
+    // -------------------------------------------------------------------------
+    /// gateway の設定（Spin 変数）が不正（500）
+    GatewayMisconfigured,
+    /// プロトコルのアップグレード（WebSocket）に対応していない（501）
+    UpgradeNotSupported,
+    /// コア層に接続できない・ヘルスチェックに失敗した（502）
+    UpstreamUnavailable,
+    /// サーキットブレーカーが開いている（503）
+    CircuitOpen,
+    /// メンテナンス中（503）
+    Maintenance,
+    /// コア層の応答がタイムアウトした（504）
+    UpstreamTimeout,
+}
+
+impl ErrorCode {
+    /// 全てのコード（ドキュメント・テスト用）
+    pub const ALL: &'static [ErrorCode] = &[
+        Self::MissingToken,
+        Self::InvalidToken,
+        Self::TokenExpired,
+        Self::TokenNotYetValid,
+        Self::InvalidSignature,
+        Self::UnsupportedAlgorithm,
+        Self::TokenRevoked,
+        Self::MalformedAuthorization,
+        Self::InvalidApiKey,
+        Self::MultipleAuthorizationHeaders,
+        Self::MalformedToken,
+        Self::EncryptedToken,
+        Self::InvalidRequestBody,
+        Self::Forbidden,
+        Self::NotFound,
+        Self::MethodNotAllowed,
+        Self::PayloadTooLarge,
+        Self::UnsupportedMediaType,
+        Self::BatchTooLarge,
+        Self::MalformedCsv,
+        Self::RateLimited,
+        Self::GatewayMisconfigured,
+        Self::UpgradeNotSupported,
+        Self::UpstreamUnavailable,
+        Self::CircuitOpen,
+        Self::Maintenance,
+        Self::UpstreamTimeout,
+    ];
+
+    /// レスポンスの code に使う文字列（snake_case）
+    ///
+    /// # 使用例
+    ///
+    /// ```
+    /// assert_eq!(error_code::ErrorCode::TokenExpired.as_str(), "token_expired");
+    /// ```
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::MissingToken => "missing_token",
+            Self::InvalidToken => "invalid_token",
+            Self::TokenExpired => "token_expired",
+            Self::TokenNotYetValid => "token_not_yet_valid",
+            Self::InvalidSignature => "invalid_signature",
+            Self::UnsupportedAlgorithm => "unsupported_algorithm",
+            Self::TokenRevoked => "token_revoked",
+            Self::MalformedAuthorization => "malformed_authorization",
+            Self::InvalidApiKey => "invalid_api_key",
+            Self::MultipleAuthorizationHeaders => "multiple_authorization_headers",
+            Self::MalformedToken => "malformed_token",
+            Self::EncryptedToken => "encrypted_token",
+            Self::InvalidRequestBody => "invalid_request_body",
+            Self::Forbidden => "forbidden",
+            Self::NotFound => "not_found",
+            Self::MethodNotAllowed => "method_not_allowed",
+            Self::PayloadTooLarge => "payload_too_large",
+            Self::UnsupportedMediaType => "unsupported_media_type",
+            Self::BatchTooLarge => "batch_too_large",
+            Self::MalformedCsv => "malformed_csv",
+            Self::RateLimited => "rate_limited",
+            Self::GatewayMisconfigured => "gateway_misconfigured",
+            Self::UpgradeNotSupported => "upgrade_not_supported",
+            Self::UpstreamUnavailable => "upstream_unavailable",
+            Self::CircuitOpen => "circuit_open",
+            Self::Maintenance => "maintenance",
+            Self::UpstreamTimeout => "upstream_timeout",
+        }
+    }
+
+    /// コードに対応する HTTP ステータスの既定値
+    ///
+    /// ヘルスチェックの失敗（503）のように、利用側が別のステータスで返す場合がある。
+    pub const fn status(self) -> u16 {
+        match self {
+            Self::MultipleAuthorizationHeaders
+            | Self::MalformedToken
+            | Self::EncryptedToken
+            | Self::InvalidRequestBody => 400,
+            Self::MissingToken
+            | Self::InvalidToken
+            | Self::TokenExpired
+            | Self::TokenNotYetValid
+            | Self::InvalidSignature
+            | Self::UnsupportedAlgorithm
+            | Self::TokenRevoked
+            | Self::MalformedAuthorization
+            | Self::InvalidApiKey => 401,
+            Self::Forbidden => 403,
+            Self::NotFound => 404,
+            Self::MethodNotAllowed => 405,
+            Self::PayloadTooLarge => 413,
+            Self::UnsupportedMediaType => 415,
+            Self::BatchTooLarge | Self::MalformedCsv => 422,
+            Self::RateLimited => 429,
+            Self::GatewayMisconfigured => 500,
+            Self::UpgradeNotSupported => 501,
+            Self::UpstreamUnavailable => 502,
+            Self::CircuitOpen | Self::Maintenance => 503,
+            Self::UpstreamTimeout => 504,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_are_unique_snake_case() {
+        for (index, code) in ErrorCode::ALL.iter().enumerate() {
+            let name = code.as_str();
+            assert!(
+                !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c == '_'),
+                "{}",
+                name
+            );
+            assert!(
+                ErrorCode::ALL[..index]
+                    .iter()
+                    .all(|other| other.as_str() != name),
+                "duplicate code {}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_status() {
+        assert_eq!(ErrorCode::MissingToken.status(), 401);
+        assert_eq!(ErrorCode::TokenExpired.status(), 401);
+        assert_eq!(ErrorCode::InvalidSignature.status(), 401);
+        assert_eq!(ErrorCode::TokenRevoked.status(), 401);
+        assert_eq!(ErrorCode::MalformedToken.status(), 400);
+        assert_eq!(ErrorCode::EncryptedToken.status(), 400);
+        assert_eq!(ErrorCode::MethodNotAllowed.status(), 405);
+        assert_eq!(ErrorCode::RateLimited.status(), 429);
+        assert_eq!(ErrorCode::UpstreamUnavailable.status(), 502);
+        assert_eq!(ErrorCode::UpstreamTimeout.status(), 504);
+        for code in ErrorCode::ALL {
+            assert!((400..600).contains(&code.status()), "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_display_matches_as_str() {
+        use core::fmt::Write;
+
+        struct Buffer([u8; 64], usize);
+        impl Write for Buffer {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                let end = self.1 + s.len();
+                self.0
+                    .get_mut(self.1..end)
+                    .ok_or(fmt::Error)?
+                    .copy_from_slice(s.as_bytes());
+                self.1 = end;
+                Ok(())
+            }
+        }
+
+        let mut buffer = Buffer([0; 64], 0);
+        write!(buffer, "{}", ErrorCode::InvalidApiKey).unwrap();
+        assert_eq!(&buffer.0[..buffer.1], b"invalid_api_key");
+    }
+}
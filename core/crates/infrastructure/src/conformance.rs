@@ -0,0 +1,313 @@
+// =============================================================================
+// infrastructure/src/conformance.rs: ドメイントレイトの適合テスト
+// =============================================================================
+// Reader/Writer・ストレージの実装が、トレイトの契約（所有者チェック、
+// NotFound の扱い、フィルタ、並び順など）を満たすことを検証する共通テスト。
+//
+// 実装ごとにテストを書く代わりに、同じ関数を各実装に対して実行する。
+// 契約に違反すると panic する（#[tokio::test] から呼び出す想定）。
+//
+// 使用例:
+// ```rust,ignore
+// #[tokio::test]
+// async fn test_todo_repository_conformance() {
+//     let store = MemoryStore::new();
+//     conformance::todo_repository(
+//         &InMemoryTodoWriter::new(store.clone()),
+//         &InMemoryTodoReader::new(store),
+//     )
+//     .await;
+// }
+// ```
+//
+// 外部キー制約のある実装（PostgreSQL）で実行する場合:
+// - todo_repository はランダムな user_id を使うため、users に存在する必要がある
+//   実装では todo_repository_for_users を使う
+// - file_repository には存在する TODO の ID を渡す
+// =============================================================================
+
+use chrono::{Duration, Utc};
+use domain::{
+    DomainError, File, FileReader, FileWriter, Role, StorageOps, Todo, TodoFilter, TodoReader,
+    TodoWriter, User, UserReader, UserWriter,
+};
+use uuid::Uuid;
+
+// =============================================================================
+// TODO
+// =============================================================================
+
+/// TodoWriter / TodoReader の適合テスト（ランダムな 2 ユーザーで実行）
+pub async fn todo_repository<W: TodoWriter, R: TodoReader>(writer: &W, reader: &R) {
+    todo_repository_for_users(writer, reader, Uuid::new_v4(), Uuid::new_v4()).await;
+}
+
+/// TodoWriter / TodoReader の適合テスト
+///
+/// # Arguments
+///
+/// * `writer` / `reader` - 同じデータを参照する Writer と Reader
+/// * `owner` / `other` - TODO を持たない別々のユーザー ID
+pub async fn todo_repository_for_users<W: TodoWriter, R: TodoReader>(
+    writer: &W,
+    reader: &R,
+    owner: Uuid,
+    other: Uuid,
+) {
+    // created_at を 1 秒ずつずらして並び順を確定させる
+    // second のみテナント付きで作成する
+    let base = Utc::now();
+    let tenant = Uuid::new_v4();
+    let mut created = Vec::new();
+    for (i, title) in ["first", "second", "third"].into_iter().enumerate() {
+        let mut todo = Todo::new(owner, title.to_string(), Some("memo".to_string()))
+            .with_tenant((title == "second").then_some(tenant));
+        todo.created_at = base + Duration::seconds(i as i64);
+        todo.updated_at = todo.created_at;
+        created.push(writer.create(&todo).await.expect("create"));
+    }
+    assert_eq!(created[1].tenant_id, Some(tenant));
+    writer
+        .create(&Todo::new(other, "others".to_string(), None).with_tenant(Some(tenant)))
+        .await
+        .expect("create other user's todo");
+
+    // find_by_id: 所有者のみ取得できる
+    let first = &created[0];
+    let found = reader
+        .find_by_id(first.id, owner)
+        .await
+        .expect("find_by_id");
+    assert_eq!(found.map(|t| t.title), Some("first".to_string()));
+    assert!(reader
+        .find_by_id(first.id, other)
+        .await
+        .expect("find_by_id")
+        .is_none());
+    assert!(reader
+        .find_by_id(Uuid::new_v4(), owner)
+        .await
+        .expect("find_by_id")
+        .is_none());
+
+    // update_fields: None のフィールドは変更しない、Some(None) は説明をクリア
+    let updated = writer
+        .update_fields(first.id, owner, None, Some(None), Some(true))
+        .await
+        .expect("update_fields");
+    assert_eq!(updated.title, "first");
+    assert_eq!(updated.description, None);
+    assert!(updated.completed);
+
+    // update_fields: 他ユーザー・存在しない ID は NotFound
+    assert!(matches!(
+        writer
+            .update_fields(first.id, other, Some("stolen".to_string()), None, None)
+            .await,
+        Err(DomainError::NotFound)
+    ));
+    assert!(matches!(
+        writer
+            .update_fields(Uuid::new_v4(), owner, None, None, Some(true))
+            .await,
+        Err(DomainError::NotFound)
+    ));
+
+    // find_all: 所有者の TODO のみ、created_at 降順
+    let titles = |todos: Vec<Todo>| todos.into_iter().map(|t| t.title).collect::<Vec<_>>();
+    let all = reader
+        .find_all(TodoFilter::new(owner))
+        .await
+        .expect("find_all");
+    assert_eq!(titles(all), vec!["third", "second", "first"]);
+
+    // find_all: completed フィルタ
+    let done = reader
+        .find_all(TodoFilter::new(owner).with_completed(Some(true)))
+        .await
+        .expect("find_all");
+    assert_eq!(titles(done), vec!["first"]);
+    let open = reader
+        .find_all(TodoFilter::new(owner).with_completed(Some(false)))
+        .await
+        .expect("find_all");
+    assert_eq!(titles(open), vec!["third", "second"]);
+
+    // find_all: tenant フィルタ（同じテナントでも他ユーザーの TODO は含まない）
+    let scoped = reader
+        .find_all(TodoFilter::new(owner).with_tenant(Some(tenant)))
+        .await
+        .expect("find_all");
+    assert_eq!(scoped.len(), 1);
+    assert_eq!(scoped[0].tenant_id, Some(tenant));
+    assert_eq!(titles(scoped), vec!["second"]);
+    let none = reader
+        .find_all(TodoFilter::new(owner).with_tenant(Some(Uuid::new_v4())))
+        .await
+        .expect("find_all");
+    assert!(none.is_empty());
+
+    // delete: 他ユーザーは false、所有者は true、2 回目は false
+    assert!(!writer.delete(first.id, other).await.expect("delete"));
+    assert!(writer.delete(first.id, owner).await.expect("delete"));
+    assert!(!writer.delete(first.id, owner).await.expect("delete"));
+    assert!(reader
+        .find_by_id(first.id, owner)
+        .await
+        .expect("find_by_id")
+        .is_none());
+}
+
+// =============================================================================
+// User
+// =============================================================================
+
+/// UserReader / UserWriter の適合テスト
+pub async fn user_repository<R: UserReader, W: UserWriter>(reader: &R, writer: &W) {
+    let email = format!("conformance-{}@example.com", Uuid::new_v4());
+    let user = writer
+        .create(&User::new(email.clone(), "hash".to_string(), None))
+        .await
+        .expect("create");
+
+    // find_by_email / find_by_id
+    let by_email = reader.find_by_email(&email).await.expect("find_by_email");
+    assert_eq!(by_email.map(|u| u.id), Some(user.id));
+    let by_id = reader.find_by_id(user.id).await.expect("find_by_id");
+    assert_eq!(by_id.map(|u| u.email), Some(email.clone()));
+    assert!(reader
+        .find_by_email("missing@example.com")
+        .await
+        .expect("find_by_email")
+        .is_none());
+
+    // メールアドレスの重複は Duplicate
+    assert!(matches!(
+        writer
+            .create(&User::new(email.clone(), "other".to_string(), None))
+            .await,
+        Err(DomainError::Duplicate(_))
+    ));
+
+    // update: 表示名を変更できる
+    let mut changed = user.clone();
+    changed.display_name = Some("Renamed".to_string());
+    let updated = writer.update(&changed).await.expect("update");
+    assert_eq!(updated.display_name.as_deref(), Some("Renamed"));
+
+    // ロール: 新規作成は User、update で Admin に昇格できる
+    assert_eq!(user.role, Role::User);
+    let mut promoted = updated.clone();
+    promoted.promote_to_admin();
+    writer.update(&promoted).await.expect("update role");
+    let stored = reader.find_by_id(user.id).await.expect("find_by_id");
+    assert_eq!(stored.map(|u| u.role), Some(Role::Admin));
+
+    // delete: 1 回目は true、2 回目は false
+    assert!(writer.delete(user.id).await.expect("delete"));
+    assert!(!writer.delete(user.id).await.expect("delete"));
+    assert!(reader
+        .find_by_id(user.id)
+        .await
+        .expect("find_by_id")
+        .is_none());
+}
+
+// =============================================================================
+// File
+// =============================================================================
+
+/// FileReader / FileWriter の適合テスト
+///
+/// # Arguments
+///
+/// * `todo_id` - ファイルを紐付ける TODO の ID（ファイルを持たないこと）
+pub async fn file_repository<R: FileReader, W: FileWriter>(reader: &R, writer: &W, todo_id: Uuid) {
+    let base = Utc::now();
+    let mut created = Vec::new();
+    for (i, name) in ["a.txt", "b.txt", "c.txt"].into_iter().enumerate() {
+        let mut file = File::new(
+            todo_id,
+            name.to_string(),
+            "text/plain".to_string(),
+            1,
+            format!("conformance/{}", name),
+        );
+        file.created_at = base + Duration::seconds(i as i64);
+        created.push(writer.create(&file).await.expect("create"));
+    }
+
+    // find_by_id / find_by_todo_id（created_at 昇順）
+    let found = reader.find_by_id(created[1].id).await.expect("find_by_id");
+    assert_eq!(found.map(|f| f.filename), Some("b.txt".to_string()));
+    let names: Vec<String> = reader
+        .find_by_todo_id(todo_id)
+        .await
+        .expect("find_by_todo_id")
+        .into_iter()
+        .map(|f| f.filename)
+        .collect();
+    assert_eq!(names, vec!["a.txt", "b.txt", "c.txt"]);
+
+    // delete: 1 回目は true、2 回目は false
+    assert!(writer.delete(created[0].id).await.expect("delete"));
+    assert!(!writer.delete(created[0].id).await.expect("delete"));
+
+    // delete_by_todo_id: 削除件数を返す
+    assert_eq!(
+        writer
+            .delete_by_todo_id(todo_id)
+            .await
+            .expect("delete_by_todo_id"),
+        2
+    );
+    assert!(reader
+        .find_by_todo_id(todo_id)
+        .await
+        .expect("find_by_todo_id")
+        .is_empty());
+}
+
+// =============================================================================
+// Storage
+// =============================================================================
+
+/// StorageOps の適合テスト
+pub async fn storage<S: StorageOps>(storage: &S) {
+    let user_id = Uuid::new_v4();
+    let key = storage
+        .upload(user_id, "hello.txt", "text/plain", b"hello".to_vec())
+        .await
+        .expect("upload");
+
+    // キーの形式: users/{user_id}/files/{uuid}/{filename}
+    assert!(key.starts_with(&format!("users/{}/files/", user_id)));
+    assert!(key.ends_with("/hello.txt"));
+
+    assert_eq!(storage.download(&key).await.expect("download"), b"hello");
+
+    // list: ユーザーのプレフィックスで見つかり、他のプレフィックスには含まれない
+    let prefix = format!("users/{}/", user_id);
+    let listed = storage.list(&prefix).await.expect("list");
+    assert_eq!(
+        listed
+            .iter()
+            .map(|o| o.storage_path.as_str())
+            .collect::<Vec<_>>(),
+        vec![key.as_str()]
+    );
+    let other = storage
+        .list(&format!("users/{}/", Uuid::new_v4()))
+        .await
+        .expect("list");
+    assert!(other.is_empty());
+
+    // delete は冪等、削除後のダウンロードは NotFound
+    storage.delete(&key).await.expect("delete");
+    storage.delete(&key).await.expect("delete is idempotent");
+    assert!(matches!(
+        storage.download(&key).await,
+        Err(DomainError::NotFound)
+    ));
+    assert!(storage.list(&prefix).await.expect("list").is_empty());
+}
@@ -0,0 +1,102 @@
+// =============================================================================
+// infrastructure/src/health.rs: 依存サービスの疎通確認（readiness probe 用）
+// =============================================================================
+// GET /ready（presentation）で実行するチェックの実装。
+// main.rs で構成に応じて登録する（Redis を使わない構成では Redis のチェックを登録しない）。
+//
+// 実装:
+// - PostgresReadiness: SELECT 1
+// - RedisReadiness: PING
+// =============================================================================
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+/// 依存サービスの疎通確認
+#[async_trait]
+pub trait ReadinessCheck: Send + Sync {
+    /// レスポンスに表示するチェック名（例: "postgres"）
+    fn name(&self) -> &'static str;
+
+    /// 疎通を確認する
+    ///
+    /// # Errors
+    ///
+    /// 接続できない場合はエラーメッセージ
+    async fn check(&self) -> Result<(), String>;
+}
+
+// =============================================================================
+// PostgreSQL
+// =============================================================================
+
+/// PostgreSQL の疎通確認（SELECT 1）
+pub struct PostgresReadiness {
+    pool: PgPool,
+}
+
+impl PostgresReadiness {
+    /// 新しい PostgresReadiness を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - 確認する接続プール（Writer 用を推奨）
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ReadinessCheck for PostgresReadiness {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+// =============================================================================
+// Redis
+// =============================================================================
+
+/// Redis の疎通確認（PING）
+pub struct RedisReadiness {
+    client: redis::Client,
+}
+
+impl RedisReadiness {
+    /// 新しい RedisReadiness を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - 確認する Redis クライアント
+    pub fn new(client: redis::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl ReadinessCheck for RedisReadiness {
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        let mut conn = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| e.to_string())?;
+        redis::cmd("PING")
+            .query_async::<String>(&mut conn)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
@@ -21,13 +21,22 @@
 // │ キャッシュ                                                   │
 // │ - TodoCache: Redis キャッシュ操作                           │
 // │ - CachedTodoReader: キャッシュ付き TodoReader（デコレータ）  │
+// │ - NoopCache: 何もしないキャッシュ（CACHE_BACKEND=none）     │
 // ├─────────────────────────────────────────────────────────────┤
 // │ ファイルストレージ                                           │
 // │ - S3StorageService: S3 ファイルストレージ（LocalStack 対応）│
 // ├─────────────────────────────────────────────────────────────┤
+// │ インメモリ実装（features = ["memory"]）                      │
+// │ - MemoryStore + InMemory*: 外部サービスなしの開発・テスト用 │
+// │ - conformance: ドメイントレイトの適合テスト                 │
+// ├─────────────────────────────────────────────────────────────┤
 // │ トランザクション                                             │
 // │ - TransactionalTodoService: バッチ操作（複数 TODO 一括作成）│
 // │ - FileInput: ファイル作成用入力データ                       │
+// │ - TodoCreateInput: バッチ作成用入力データ                   │
+// │ - CreateOutcome: 重複検出付きバッチ作成の結果               │
+// │ - TransactionProvider: トランザクションの抽象               │
+// │ - TodoUpdateInput: バッチ更新用入力データ                   │
 // └─────────────────────────────────────────────────────────────┘
 //
 // 使用例（main.rs での DI）:
@@ -54,6 +63,13 @@ pub mod repositories;
 /// サービスモジュール（トランザクション対応）
 pub mod services;
 
+/// 依存サービスの疎通確認（readiness probe 用）
+pub mod health;
+
+/// ドメイントレイトの適合テスト（実装間で共通）
+#[cfg(any(test, feature = "memory"))]
+pub mod conformance;
+
 // -----------------------------------------------------------------------------
 // 再エクスポート（Re-export）
 // -----------------------------------------------------------------------------
@@ -76,13 +92,33 @@ pub use persistence::postgres::PostgresFileReader;
 pub use persistence::postgres::PostgresFileWriter;
 
 // Redis キャッシュ
-pub use persistence::redis::TodoCache;
+pub use persistence::redis::{TodoCache, DEFAULT_CACHE_TTL_SECONDS};
 
 // S3 ストレージ
-pub use persistence::s3::S3StorageService;
+pub use persistence::s3::{
+    S3Config, S3Credentials, S3StorageService, DEFAULT_PRESIGN_EXPIRY_SECS, MAX_PRESIGN_EXPIRY_SECS,
+};
+
+// インメモリ実装（開発・テスト用）
+#[cfg(any(test, feature = "memory"))]
+pub use persistence::memory::{
+    detached_pool, InMemoryFileReader, InMemoryFileWriter, InMemoryStorage, InMemoryTodoCache,
+    InMemoryTodoReader, InMemoryTodoWriter, InMemoryUserReader, InMemoryUserWriter, MemoryStore,
+};
+
+// 何もしないキャッシュ（CACHE_BACKEND=none）
+pub use persistence::noop_cache::NoopCache;
 
 // キャッシュ付きリポジトリ（デコレータ）
-pub use repositories::CachedTodoReader;
+pub use repositories::{CacheLookup, CachedTodoReader};
+
+// 疎通確認（readiness probe）
+pub use health::{PostgresReadiness, ReadinessCheck, RedisReadiness};
 
 // トランザクション対応サービス
-pub use services::{FileInput, TransactionalTodoService};
+pub use services::{
+    BatchDeleteOutcome, BatchUpdateOutcome, CreateOutcome, FileInput, FileUploadInput, ImportItem,
+    ImportJobService, ImportWorker, ImportWorkerConfig, OrphanCleanupReport, OrphanFileCleanup,
+    PgTodoTransaction, PgTransactionProvider, TodoCreateInput, TodoTransaction, TodoUpdateInput,
+    TransactionProvider, TransactionalTodoService,
+};
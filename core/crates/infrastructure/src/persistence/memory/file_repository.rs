@@ -0,0 +1,97 @@
+// =============================================================================
+// infrastructure/src/persistence/memory/file_repository.rs
+// =============================================================================
+// FileWriter / FileReader のインメモリ実装。
+// find_by_todo_id は PostgreSQL 実装と同じく created_at 昇順。
+// =============================================================================
+
+use async_trait::async_trait;
+use domain::{DomainError, File, FileReader, FileWriter};
+use uuid::Uuid;
+
+use super::MemoryStore;
+
+// =============================================================================
+// InMemoryFileReader
+// =============================================================================
+
+/// ファイルメタデータの読み取り（Queries 用）
+pub struct InMemoryFileReader {
+    store: MemoryStore,
+}
+
+impl InMemoryFileReader {
+    /// 新しい InMemoryFileReader を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - Writer と共有するデータストア
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl FileReader for InMemoryFileReader {
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<File>, DomainError> {
+        Ok(self.store.files.read().unwrap().get(&id).cloned())
+    }
+
+    async fn find_by_todo_id(&self, todo_id: Uuid) -> Result<Vec<File>, DomainError> {
+        let mut files: Vec<File> = self
+            .store
+            .files
+            .read()
+            .unwrap()
+            .values()
+            .filter(|file| file.todo_id == todo_id)
+            .cloned()
+            .collect();
+        // ORDER BY created_at ASC
+        files.sort_by_key(|file| file.created_at);
+        Ok(files)
+    }
+}
+
+// =============================================================================
+// InMemoryFileWriter
+// =============================================================================
+
+/// ファイルメタデータの書き込み（Commands 用）
+pub struct InMemoryFileWriter {
+    store: MemoryStore,
+}
+
+impl InMemoryFileWriter {
+    /// 新しい InMemoryFileWriter を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - Reader と共有するデータストア
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl FileWriter for InMemoryFileWriter {
+    async fn create(&self, file: &File) -> Result<File, DomainError> {
+        self.store
+            .files
+            .write()
+            .unwrap()
+            .insert(file.id, file.clone());
+        Ok(file.clone())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool, DomainError> {
+        Ok(self.store.files.write().unwrap().remove(&id).is_some())
+    }
+
+    async fn delete_by_todo_id(&self, todo_id: Uuid) -> Result<u64, DomainError> {
+        let mut files = self.store.files.write().unwrap();
+        let before = files.len();
+        files.retain(|_, file| file.todo_id != todo_id);
+        Ok((before - files.len()) as u64)
+    }
+}
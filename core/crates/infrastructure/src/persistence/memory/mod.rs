@@ -0,0 +1,106 @@
+// =============================================================================
+// infrastructure/src/persistence/memory/mod.rs: インメモリ実装モジュール
+// =============================================================================
+// Domain 層の Reader/Writer・キャッシュ・ストレージのトレイトを
+// `RwLock<HashMap>` で実装する。PostgreSQL / Redis / S3 なしで
+// API を起動（`--backend memory`）したり、ハンドラをテストするために使う。
+//
+// 有効化: features = ["memory"]（このクレートのテストでは常に有効）
+//
+// セマンティクスは PostgreSQL 実装に合わせ、conformance モジュールの
+// 適合テストで検証している:
+// - 所有者（user_id）以外の TODO は見えない・更新できない・削除できない
+// - 存在しない TODO の更新は NotFound、削除は false
+// - find_all は completed フィルタに対応し、created_at 降順
+// - メールアドレスの重複は Duplicate
+//
+// 各エンティティの実装（Reader/Writer は MemoryStore を共有する）:
+// - TODO:  InMemoryTodoReader / InMemoryTodoWriter
+// - User:  InMemoryUserReader / InMemoryUserWriter
+// - File:  InMemoryFileReader / InMemoryFileWriter
+// - キャッシュ: InMemoryTodoCache
+// - ストレージ: InMemoryStorage
+//
+// 使用例:
+// ```rust,ignore
+// let store = MemoryStore::new();
+// let todo_writer = Arc::new(InMemoryTodoWriter::new(store.clone()));
+// let todo_reader = Arc::new(InMemoryTodoReader::new(store.clone()));
+// ```
+//
+// 制約:
+// バッチ操作・インポートジョブ（TransactionalTodoService, ImportJobService）は
+// PostgreSQL のトランザクションを直接使用するため、インメモリ実装はない。
+// detached_pool() の遅延プールを渡すと、これらの操作はエラーになる。
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// サブモジュール宣言
+// -----------------------------------------------------------------------------
+
+mod file_repository; // FileReader / FileWriter のインメモリ実装
+mod storage; // StorageOps のインメモリ実装
+mod store; // Reader/Writer が共有するデータ
+mod todo_cache; // TodoCacheOps のインメモリ実装
+mod todo_repository; // TodoReader / TodoWriter のインメモリ実装
+mod user_repository; // UserReader / UserWriter のインメモリ実装
+
+// -----------------------------------------------------------------------------
+// 再エクスポート（Re-export）
+// -----------------------------------------------------------------------------
+
+pub use file_repository::{InMemoryFileReader, InMemoryFileWriter};
+pub use storage::InMemoryStorage;
+pub use store::{detached_pool, MemoryStore};
+pub use todo_cache::InMemoryTodoCache;
+pub use todo_repository::{InMemoryTodoReader, InMemoryTodoWriter};
+pub use user_repository::{InMemoryUserReader, InMemoryUserWriter};
+
+// =============================================================================
+// テスト（トレイト適合テスト）
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use uuid::Uuid;
+
+    use crate::conformance;
+
+    #[tokio::test]
+    async fn test_todo_repository_conformance() {
+        let store = MemoryStore::new();
+        conformance::todo_repository(
+            &InMemoryTodoWriter::new(store.clone()),
+            &InMemoryTodoReader::new(store),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_user_repository_conformance() {
+        let store = MemoryStore::new();
+        conformance::user_repository(
+            &InMemoryUserReader::new(store.clone()),
+            &InMemoryUserWriter::new(store),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_file_repository_conformance() {
+        let store = MemoryStore::new();
+        conformance::file_repository(
+            &InMemoryFileReader::new(store.clone()),
+            &InMemoryFileWriter::new(store),
+            Uuid::new_v4(),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_storage_conformance() {
+        conformance::storage(&InMemoryStorage::new()).await;
+    }
+}
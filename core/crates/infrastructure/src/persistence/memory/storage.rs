@@ -0,0 +1,87 @@
+// =============================================================================
+// infrastructure/src/persistence/memory/storage.rs
+// =============================================================================
+// StorageOps のインメモリ実装。
+// キーの形式は S3 実装と同じ `users/{user_id}/files/{uuid}/{filename}`。
+// =============================================================================
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{DomainError, StorageOps, StoredObject};
+use uuid::Uuid;
+
+/// 保存されたオブジェクト
+struct Entry {
+    /// 内容
+    data: Vec<u8>,
+    /// 最終更新日時（list で返す）
+    last_modified: DateTime<Utc>,
+}
+
+/// ファイルストレージのインメモリ実装
+///
+/// Clone は同じストレージを共有する（Arc）。
+#[derive(Clone, Default)]
+pub struct InMemoryStorage {
+    objects: Arc<RwLock<HashMap<String, Entry>>>,
+}
+
+impl InMemoryStorage {
+    /// 空のストレージを作成する
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl StorageOps for InMemoryStorage {
+    async fn upload(
+        &self,
+        user_id: Uuid,
+        filename: &str,
+        _content_type: &str,
+        data: Vec<u8>,
+    ) -> Result<String, DomainError> {
+        let key = format!("users/{}/files/{}/{}", user_id, Uuid::new_v4(), filename);
+        self.objects.write().unwrap().insert(
+            key.clone(),
+            Entry {
+                data,
+                last_modified: Utc::now(),
+            },
+        );
+        Ok(key)
+    }
+
+    async fn download(&self, storage_path: &str) -> Result<Vec<u8>, DomainError> {
+        self.objects
+            .read()
+            .unwrap()
+            .get(storage_path)
+            .map(|entry| entry.data.clone())
+            .ok_or(DomainError::NotFound)
+    }
+
+    async fn delete(&self, storage_path: &str) -> Result<(), DomainError> {
+        // S3 の DeleteObject と同じく冪等
+        self.objects.write().unwrap().remove(storage_path);
+        Ok(())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<StoredObject>, DomainError> {
+        Ok(self
+            .objects
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(key, _)| key.starts_with(prefix))
+            .map(|(key, entry)| StoredObject {
+                storage_path: key.clone(),
+                last_modified: entry.last_modified,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,55 @@
+// =============================================================================
+// infrastructure/src/persistence/memory/store.rs: インメモリのデータストア
+// =============================================================================
+// Reader/Writer が同じデータを参照できるよう、テーブルに相当する
+// HashMap を Arc<RwLock<...>> で共有する。
+// PostgreSQL 実装における DbPools（Reader/Writer が同じ DB を参照）に相当する。
+// =============================================================================
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use domain::{File, Todo, User};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// インメモリのデータストア
+///
+/// Clone は同じデータを共有する（Arc）。
+/// Reader/Writer には同じ MemoryStore の clone を渡すこと。
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    /// todos テーブル
+    pub(crate) todos: Arc<RwLock<HashMap<Uuid, Todo>>>,
+    /// users テーブル
+    pub(crate) users: Arc<RwLock<HashMap<Uuid, User>>>,
+    /// files テーブル
+    pub(crate) files: Arc<RwLock<HashMap<Uuid, File>>>,
+}
+
+impl MemoryStore {
+    /// 空のデータストアを作成する
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// 接続先のない遅延プールを作成する
+///
+/// TransactionalTodoService / ImportJobService は PgPool を必要とするため、
+/// インメモリ構成ではこのプールを渡す。最初のクエリまで接続しないので
+/// 起動時に PostgreSQL は不要だが、バッチ操作・インポートは
+/// 接続エラー（acquire タイムアウト 1 秒）で失敗する。
+///
+/// idle_timeout / max_lifetime を無効にしてバックグラウンドタスクを起動させないため、
+/// tokio ランタイムの外でも作成できる。
+pub fn detached_pool() -> PgPool {
+    PgPoolOptions::new()
+        .acquire_timeout(Duration::from_secs(1))
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect_lazy("postgres://localhost/unavailable")
+        .expect("static database URL is valid")
+}
@@ -0,0 +1,55 @@
+// =============================================================================
+// infrastructure/src/persistence/memory/todo_cache.rs
+// =============================================================================
+// TodoCacheOps のインメモリ実装（TTL なし）。
+// =============================================================================
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use domain::{DomainError, Todo, TodoCacheOps};
+use uuid::Uuid;
+
+use crate::repositories::CacheLookup;
+
+/// TODO キャッシュのインメモリ実装
+///
+/// Clone は同じキャッシュを共有する（Arc）。
+/// テストから中身を確認できるよう `get` を提供する。
+#[derive(Clone, Default)]
+pub struct InMemoryTodoCache {
+    entries: Arc<RwLock<HashMap<Uuid, Todo>>>,
+}
+
+impl InMemoryTodoCache {
+    /// 空のキャッシュを作成する
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// キャッシュされている TODO を取得する
+    pub fn get(&self, id: Uuid) -> Option<Todo> {
+        self.entries.read().unwrap().get(&id).cloned()
+    }
+}
+
+#[async_trait]
+impl TodoCacheOps for InMemoryTodoCache {
+    async fn set(&self, todo: &Todo) -> Result<(), DomainError> {
+        self.entries.write().unwrap().insert(todo.id, todo.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<(), DomainError> {
+        self.entries.write().unwrap().remove(&id);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CacheLookup for InMemoryTodoCache {
+    async fn get(&self, id: Uuid) -> Result<Option<Todo>, DomainError> {
+        Ok(InMemoryTodoCache::get(self, id))
+    }
+}
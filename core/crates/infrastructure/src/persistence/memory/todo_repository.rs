@@ -0,0 +1,145 @@
+// =============================================================================
+// infrastructure/src/persistence/memory/todo_repository.rs
+// =============================================================================
+// TodoWriter / TodoReader のインメモリ実装。
+// 所有者チェック（user_id）と並び順（created_at 降順）は
+// PostgreSQL 実装と同じ。
+// =============================================================================
+
+use std::cmp::Reverse;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use domain::{DomainError, Todo, TodoFilter, TodoReader, TodoWriter};
+use uuid::Uuid;
+
+use super::MemoryStore;
+
+// =============================================================================
+// InMemoryTodoWriter
+// =============================================================================
+
+/// TODO の書き込み（Commands 用）
+pub struct InMemoryTodoWriter {
+    store: MemoryStore,
+}
+
+impl InMemoryTodoWriter {
+    /// 新しい InMemoryTodoWriter を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - Reader と共有するデータストア
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl TodoWriter for InMemoryTodoWriter {
+    async fn create(&self, todo: &Todo) -> Result<Todo, DomainError> {
+        self.store
+            .todos
+            .write()
+            .unwrap()
+            .insert(todo.id, todo.clone());
+        Ok(todo.clone())
+    }
+
+    async fn update_fields(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        title: Option<String>,
+        description: Option<Option<String>>,
+        completed: Option<bool>,
+    ) -> Result<Todo, DomainError> {
+        let mut todos = self.store.todos.write().unwrap();
+        // 他ユーザーの TODO は存在しないものとして扱う（WHERE id = $1 AND user_id = $2）
+        let todo = todos
+            .get_mut(&id)
+            .filter(|todo| todo.user_id == user_id)
+            .ok_or(DomainError::NotFound)?;
+
+        // None のフィールドは変更しない（COALESCE と同じ）
+        if let Some(title) = title {
+            todo.title = title;
+        }
+        if let Some(description) = description {
+            todo.description = description;
+        }
+        if let Some(completed) = completed {
+            todo.completed = completed;
+        }
+        todo.updated_at = Utc::now();
+        Ok(todo.clone())
+    }
+
+    async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<bool, DomainError> {
+        let mut todos = self.store.todos.write().unwrap();
+        match todos.get(&id) {
+            Some(todo) if todo.user_id == user_id => {
+                todos.remove(&id);
+                // ON DELETE CASCADE: TODO に紐付くファイルも削除
+                self.store
+                    .files
+                    .write()
+                    .unwrap()
+                    .retain(|_, file| file.todo_id != id);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+// =============================================================================
+// InMemoryTodoReader
+// =============================================================================
+
+/// TODO の読み取り（Queries 用）
+pub struct InMemoryTodoReader {
+    store: MemoryStore,
+}
+
+impl InMemoryTodoReader {
+    /// 新しい InMemoryTodoReader を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - Writer と共有するデータストア
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl TodoReader for InMemoryTodoReader {
+    async fn find_by_id(&self, id: Uuid, user_id: Uuid) -> Result<Option<Todo>, DomainError> {
+        Ok(self
+            .store
+            .todos
+            .read()
+            .unwrap()
+            .get(&id)
+            .filter(|todo| todo.user_id == user_id)
+            .cloned())
+    }
+
+    async fn find_all(&self, filter: TodoFilter) -> Result<Vec<Todo>, DomainError> {
+        let mut todos: Vec<Todo> = self
+            .store
+            .todos
+            .read()
+            .unwrap()
+            .values()
+            .filter(|todo| todo.user_id == filter.user_id)
+            .filter(|todo| filter.completed.is_none_or(|c| todo.completed == c))
+            .filter(|todo| filter.tenant_id.is_none_or(|t| todo.tenant_id == Some(t)))
+            .cloned()
+            .collect();
+        // ORDER BY created_at DESC
+        todos.sort_by_key(|todo| Reverse(todo.created_at));
+        Ok(todos)
+    }
+}
@@ -0,0 +1,113 @@
+// =============================================================================
+// infrastructure/src/persistence/memory/user_repository.rs
+// =============================================================================
+// UserWriter / UserReader のインメモリ実装。
+// メールアドレスの重複は PostgreSQL の UNIQUE 制約と同じく Duplicate を返す。
+// =============================================================================
+
+use async_trait::async_trait;
+use domain::{DomainError, User, UserReader, UserWriter};
+use uuid::Uuid;
+
+use super::MemoryStore;
+
+// =============================================================================
+// InMemoryUserReader
+// =============================================================================
+
+/// ユーザーの読み取り（Queries 用）
+pub struct InMemoryUserReader {
+    store: MemoryStore,
+}
+
+impl InMemoryUserReader {
+    /// 新しい InMemoryUserReader を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - Writer と共有するデータストア
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl UserReader for InMemoryUserReader {
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, DomainError> {
+        Ok(self
+            .store
+            .users
+            .read()
+            .unwrap()
+            .values()
+            .find(|user| user.email == email)
+            .cloned())
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, DomainError> {
+        Ok(self.store.users.read().unwrap().get(&id).cloned())
+    }
+}
+
+// =============================================================================
+// InMemoryUserWriter
+// =============================================================================
+
+/// ユーザーの書き込み（Commands 用）
+pub struct InMemoryUserWriter {
+    store: MemoryStore,
+}
+
+impl InMemoryUserWriter {
+    /// 新しい InMemoryUserWriter を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - Reader と共有するデータストア
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl UserWriter for InMemoryUserWriter {
+    async fn create(&self, user: &User) -> Result<User, DomainError> {
+        let mut users = self.store.users.write().unwrap();
+        if users.values().any(|existing| existing.email == user.email) {
+            return Err(DomainError::Duplicate("email already exists".to_string()));
+        }
+        users.insert(user.id, user.clone());
+        Ok(user.clone())
+    }
+
+    async fn update(&self, user: &User) -> Result<User, DomainError> {
+        let mut users = self.store.users.write().unwrap();
+        let existing = users.get_mut(&user.id).ok_or(DomainError::NotFound)?;
+        // PostgreSQL 実装と同じく display_name・role・updated_at のみ更新する
+        existing.display_name = user.display_name.clone();
+        existing.role = user.role;
+        existing.updated_at = user.updated_at;
+        Ok(existing.clone())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool, DomainError> {
+        if self.store.users.write().unwrap().remove(&id).is_none() {
+            return Ok(false);
+        }
+
+        // ON DELETE CASCADE: ユーザーの TODO と、その TODO のファイルも削除
+        let mut todos = self.store.todos.write().unwrap();
+        let removed: Vec<Uuid> = todos
+            .values()
+            .filter(|todo| todo.user_id == id)
+            .map(|todo| todo.id)
+            .collect();
+        todos.retain(|_, todo| todo.user_id != id);
+        self.store
+            .files
+            .write()
+            .unwrap()
+            .retain(|_, file| !removed.contains(&file.todo_id));
+        Ok(true)
+    }
+}
@@ -21,6 +21,12 @@
 // │ s3/                                                         │
 // │ - S3StorageService: ファイルストレージ実装                  │
 // │ - LocalStack 対応（開発環境）                               │
+// ├─────────────────────────────────────────────────────────────┤
+// │ noop_cache                                                  │
+// │ - NoopCache: 何もしないキャッシュ（CACHE_BACKEND=none）     │
+// ├─────────────────────────────────────────────────────────────┤
+// │ memory/（features = ["memory"]）                            │
+// │ - InMemory*: 外部サービスなしで動くインメモリ実装           │
 // └─────────────────────────────────────────────────────────────┘
 //
 // CQRS パターン（Command Query Responsibility Segregation）:
@@ -44,6 +50,13 @@ pub mod redis;
 /// S3 ストレージ実装
 pub mod s3;
 
+/// 何もしないキャッシュ（Redis を使わない構成）
+pub mod noop_cache;
+
+/// インメモリ実装（開発・テスト用）
+#[cfg(any(test, feature = "memory"))]
+pub mod memory;
+
 // -----------------------------------------------------------------------------
 // 再エクスポート（Re-export）
 // -----------------------------------------------------------------------------
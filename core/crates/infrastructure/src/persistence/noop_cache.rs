@@ -0,0 +1,60 @@
+// =============================================================================
+// infrastructure/src/persistence/noop_cache.rs: 何もしないキャッシュ
+// =============================================================================
+// Redis を使わない構成（CACHE_BACKEND=none）で TodoCache の代わりに使う。
+// 全ての操作は成功し、読み取りは常にキャッシュミスになる。
+//
+// CachedTodoReader と組み合わせると、常に内部の Reader から取得するため
+// 内部の Reader を直接呼ぶのと同じ動作になる。
+// =============================================================================
+
+use async_trait::async_trait;
+use domain::{DomainError, Todo, TodoCacheOps};
+use uuid::Uuid;
+
+use crate::repositories::CacheLookup;
+
+/// 何もしないキャッシュ
+///
+/// - `set` / `delete`: 何もせず成功
+/// - `get`: 常に `Ok(None)`（キャッシュミス）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopCache;
+
+#[async_trait]
+impl TodoCacheOps for NoopCache {
+    async fn set(&self, _todo: &Todo) -> Result<(), DomainError> {
+        Ok(())
+    }
+
+    async fn delete(&self, _id: Uuid) -> Result<(), DomainError> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CacheLookup for NoopCache {
+    async fn get(&self, _id: Uuid) -> Result<Option<Todo>, DomainError> {
+        Ok(None)
+    }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 保存しても取得できない（常にキャッシュミス）
+    #[tokio::test]
+    async fn test_noop_cache_always_misses() {
+        let cache = NoopCache;
+        let todo = Todo::new(Uuid::new_v4(), "noop".to_string(), None);
+
+        cache.set(&todo).await.unwrap();
+        assert_eq!(cache.get(todo.id).await.unwrap(), None);
+        cache.delete(todo.id).await.unwrap();
+    }
+}
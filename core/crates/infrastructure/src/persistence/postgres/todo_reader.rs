@@ -110,6 +110,8 @@ struct TodoRow {
     id: Uuid,
     /// 所有者のユーザー ID（外部キー）
     user_id: Uuid,
+    /// 作成時のテナント ID（NULL 許容）
+    tenant_id: Option<Uuid>,
     /// タイトル（必須、1〜100文字）
     title: String,
     /// 詳細説明（任意）
@@ -153,6 +155,7 @@ impl From<TodoRow> for Todo {
             row.created_at,  // DateTime<Utc>: 作成日時
             row.updated_at,  // DateTime<Utc>: 更新日時
         )
+        .with_tenant(row.tenant_id) // Option<Uuid>: テナント ID
     }
 }
 
@@ -191,7 +194,7 @@ impl TodoReader for PostgresTodoReader {
         // r#"..."#: raw string literal（エスケープ不要）
         let row: Option<TodoRow> = sqlx::query_as(
             r#"
-            SELECT id, user_id, title, description, completed, created_at, updated_at
+            SELECT id, user_id, tenant_id, title, description, completed, created_at, updated_at
             FROM todos
             WHERE id = $1 AND user_id = $2
             "#,
@@ -211,7 +214,7 @@ impl TodoReader for PostgresTodoReader {
     ///
     /// # Arguments
     ///
-    /// * `filter` - 検索条件（user_id 必須、completed 任意）
+    /// * `filter` - 検索条件（user_id 必須、completed / tenant_id 任意）
     ///
     /// # Returns
     ///
@@ -230,14 +233,16 @@ impl TodoReader for PostgresTodoReader {
             // Some(completed): completed フィルタが指定されている場合
             Some(completed) => sqlx::query_as(
                 r#"
-                SELECT id, user_id, title, description, completed, created_at, updated_at
+                SELECT id, user_id, tenant_id, title, description, completed, created_at, updated_at
                 FROM todos
                 WHERE user_id = $1 AND completed = $2
+                  AND ($3::uuid IS NULL OR tenant_id = $3)
                 ORDER BY created_at DESC
                 "#,
             )
             .bind(filter.user_id) // $1: ユーザー ID
             .bind(completed) // $2: 完了フラグ
+            .bind(filter.tenant_id) // $3: テナント ID（NULL ならフィルタなし）
             .fetch_all(&self.pool) // 全件取得
             .await // 非同期実行
             .map_err(|e| DomainError::Repository(e.to_string()))?, // エラー変換
@@ -245,13 +250,15 @@ impl TodoReader for PostgresTodoReader {
             // None: completed フィルタなし（全件取得）
             None => sqlx::query_as(
                 r#"
-                SELECT id, user_id, title, description, completed, created_at, updated_at
+                SELECT id, user_id, tenant_id, title, description, completed, created_at, updated_at
                 FROM todos
                 WHERE user_id = $1
+                  AND ($2::uuid IS NULL OR tenant_id = $2)
                 ORDER BY created_at DESC
                 "#,
             )
-            .bind(filter.user_id) // $1: ユーザー ID のみ
+            .bind(filter.user_id) // $1: ユーザー ID
+            .bind(filter.tenant_id) // $2: テナント ID（NULL ならフィルタなし）
             .fetch_all(&self.pool) // 全件取得
             .await // 非同期実行
             .map_err(|e| DomainError::Repository(e.to_string()))?, // エラー変換
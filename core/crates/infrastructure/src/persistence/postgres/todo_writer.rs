@@ -81,6 +81,7 @@ impl PostgresTodoWriter {
 struct TodoRow {
     id: Uuid,
     user_id: Uuid,
+    tenant_id: Option<Uuid>,
     title: String,
     description: Option<String>,
     completed: bool,
@@ -100,6 +101,7 @@ impl From<TodoRow> for Todo {
             row.created_at,
             row.updated_at,
         )
+        .with_tenant(row.tenant_id)
     }
 }
 
@@ -131,9 +133,9 @@ impl TodoWriter for PostgresTodoWriter {
         // INSERT ... RETURNING で挿入と取得を同時に実行
         let row: TodoRow = sqlx::query_as(
             r#"
-            INSERT INTO todos (id, user_id, title, description, completed, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
-            RETURNING id, user_id, title, description, completed, created_at, updated_at
+            INSERT INTO todos (id, user_id, title, description, completed, created_at, updated_at, tenant_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, user_id, tenant_id, title, description, completed, created_at, updated_at
             "#,
         )
         .bind(todo.id) // $1: 事前に生成した UUID
@@ -143,6 +145,7 @@ impl TodoWriter for PostgresTodoWriter {
         .bind(todo.completed) // $5: 完了フラグ（通常 false）
         .bind(todo.created_at) // $6: 作成日時
         .bind(todo.updated_at) // $7: 更新日時（作成時は created_at と同じ）
+        .bind(todo.tenant_id) // $8: テナント ID（NULL 許容）
         .fetch_one(&self.pool) // 1行取得（RETURNING 句の結果）
         .await
         .map_err(|e| DomainError::Repository(e.to_string()))?;
@@ -206,7 +209,7 @@ impl TodoWriter for PostgresTodoWriter {
                 completed = COALESCE($6, completed),
                 updated_at = NOW()
             WHERE id = $1 AND user_id = $2
-            RETURNING id, user_id, title, description, completed, created_at, updated_at
+            RETURNING id, user_id, tenant_id, title, description, completed, created_at, updated_at
             "#,
         )
         .bind(id) // $1: 更新対象の ID
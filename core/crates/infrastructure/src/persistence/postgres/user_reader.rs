@@ -91,6 +91,8 @@ struct UserRow {
     password_hash: String,
     /// 表示名（任意）
     display_name: Option<String>,
+    /// ロール（user / admin）
+    role: String,
     /// 作成日時
     created_at: DateTime<Utc>,
     /// 更新日時
@@ -99,19 +101,22 @@ struct UserRow {
 
 /// UserRow から domain::User への変換
 ///
-/// From トレイトを実装することで `.into()` が使用可能に。
-impl From<UserRow> for User {
-    fn from(row: UserRow) -> Self {
+/// role の文字列が不正な場合はエラー（CHECK 制約があるため通常は発生しない）
+impl TryFrom<UserRow> for User {
+    type Error = DomainError;
+
+    fn try_from(row: UserRow) -> Result<Self, Self::Error> {
         // User::from_raw: バリデーションをスキップ
         // DB から取得したデータは信頼できる前提
-        User::from_raw(
+        Ok(User::from_raw(
             row.id,            // UUID: 主キー
             row.email,         // String: メールアドレス
             row.password_hash, // String: bcrypt ハッシュ
             row.display_name,  // Option<String>: 表示名
+            row.role.parse()?, // Role: user / admin
             row.created_at,    // DateTime<Utc>: 作成日時
             row.updated_at,    // DateTime<Utc>: 更新日時
-        )
+        ))
     }
 }
 
@@ -148,7 +153,7 @@ impl UserReader for PostgresUserReader {
         // メールアドレスで検索（UNIQUE 制約あり）
         let row: Option<UserRow> = sqlx::query_as(
             r#"
-            SELECT id, email, password_hash, display_name, created_at, updated_at
+            SELECT id, email, password_hash, display_name, role, created_at, updated_at
             FROM users
             WHERE email = $1
             "#,
@@ -159,7 +164,7 @@ impl UserReader for PostgresUserReader {
         .map_err(|e| DomainError::Repository(e.to_string()))?; // エラー変換
 
         // Option<UserRow> → Option<User> に変換
-        Ok(row.map(Into::into))
+        row.map(User::try_from).transpose()
     }
 
     /// ID でユーザーを検索
@@ -187,7 +192,7 @@ impl UserReader for PostgresUserReader {
         // UUID で検索（PRIMARY KEY）
         let row: Option<UserRow> = sqlx::query_as(
             r#"
-            SELECT id, email, password_hash, display_name, created_at, updated_at
+            SELECT id, email, password_hash, display_name, role, created_at, updated_at
             FROM users
             WHERE id = $1
             "#,
@@ -198,6 +203,6 @@ impl UserReader for PostgresUserReader {
         .map_err(|e| DomainError::Repository(e.to_string()))?; // エラー変換
 
         // Option<UserRow> → Option<User> に変換
-        Ok(row.map(Into::into))
+        row.map(User::try_from).transpose()
     }
 }
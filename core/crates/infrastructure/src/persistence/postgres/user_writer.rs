@@ -85,6 +85,8 @@ struct UserRow {
     password_hash: String,
     /// 表示名（任意）
     display_name: Option<String>,
+    /// ロール（user / admin）
+    role: String,
     /// 作成日時
     created_at: DateTime<Utc>,
     /// 更新日時
@@ -92,16 +94,21 @@ struct UserRow {
 }
 
 /// UserRow から domain::User への変換
-impl From<UserRow> for User {
-    fn from(row: UserRow) -> Self {
-        User::from_raw(
+///
+/// role の文字列が不正な場合はエラー（CHECK 制約があるため通常は発生しない）
+impl TryFrom<UserRow> for User {
+    type Error = DomainError;
+
+    fn try_from(row: UserRow) -> Result<Self, Self::Error> {
+        Ok(User::from_raw(
             row.id,
             row.email,
             row.password_hash,
             row.display_name,
+            row.role.parse()?,
             row.created_at,
             row.updated_at,
-        )
+        ))
     }
 }
 
@@ -134,17 +141,18 @@ impl UserWriter for PostgresUserWriter {
         // INSERT ... RETURNING で挿入と取得を同時に実行
         let row: UserRow = sqlx::query_as(
             r#"
-            INSERT INTO users (id, email, password_hash, display_name, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id, email, password_hash, display_name, created_at, updated_at
+            INSERT INTO users (id, email, password_hash, display_name, role, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, email, password_hash, display_name, role, created_at, updated_at
             "#,
         )
         .bind(user.id) // $1: 事前生成した UUID
         .bind(&user.email) // $2: メールアドレス（UNIQUE）
         .bind(&user.password_hash) // $3: bcrypt ハッシュ
         .bind(&user.display_name) // $4: 表示名（NULL 許容）
-        .bind(user.created_at) // $5: 作成日時
-        .bind(user.updated_at) // $6: 更新日時
+        .bind(user.role.as_str()) // $5: ロール
+        .bind(user.created_at) // $6: 作成日時
+        .bind(user.updated_at) // $7: 更新日時
         .fetch_one(&self.pool) // 1行取得
         .await
         .map_err(|e| {
@@ -162,12 +170,12 @@ impl UserWriter for PostgresUserWriter {
         })?;
 
         // UserRow → User に変換
-        Ok(row.into())
+        row.try_into()
     }
 
     /// ユーザーを更新
     ///
-    /// 現在は display_name のみ更新可能。
+    /// 現在は display_name と role のみ更新可能。
     /// email やパスワードの変更は別途エンドポイントが必要。
     ///
     /// # Arguments
@@ -191,20 +199,21 @@ impl UserWriter for PostgresUserWriter {
         let row: UserRow = sqlx::query_as(
             r#"
             UPDATE users
-            SET display_name = $2, updated_at = $3
+            SET display_name = $2, role = $3, updated_at = $4
             WHERE id = $1
-            RETURNING id, email, password_hash, display_name, created_at, updated_at
+            RETURNING id, email, password_hash, display_name, role, created_at, updated_at
             "#,
         )
         .bind(user.id) // $1: 更新対象の ID
         .bind(&user.display_name) // $2: 新しい表示名
-        .bind(user.updated_at) // $3: 更新日時
+        .bind(user.role.as_str()) // $3: ロール
+        .bind(user.updated_at) // $4: 更新日時
         .fetch_one(&self.pool) // 1行取得（存在しない場合はエラー）
         .await
         .map_err(|e| DomainError::Repository(e.to_string()))?;
 
         // UserRow → User に変換
-        Ok(row.into())
+        row.try_into()
     }
 
     /// ユーザーを削除（CASCADE で todos も削除）
@@ -4,7 +4,7 @@
 // Redis を使用したキャッシュ機能を提供する。
 //
 // Redis の用途:
-// - TODO のキャッシュ（5分間のTTL）
+// - TODO のキャッシュ（デフォルト 5 分間の TTL、CACHE_TTL_SECS で変更可能）
 // - セッション管理（将来的に）
 // - レート制限（将来的に）
 //
@@ -26,4 +26,5 @@ mod todo_cache;
 // -----------------------------------------------------------------------------
 
 // TodoCache: TODO のキャッシュ操作を提供する構造体
-pub use todo_cache::TodoCache;
+// DEFAULT_CACHE_TTL_SECONDS: キャッシュ有効期限のデフォルト値（秒）
+pub use todo_cache::{TodoCache, DEFAULT_CACHE_TTL_SECONDS};
@@ -23,6 +23,9 @@ use async_trait::async_trait;
 // TodoCacheOps: キャッシュ操作のトレイト（ドメイン層で定義）
 use domain::{DomainError, Todo, TodoCacheOps};
 
+// CacheLookup: CachedTodoReader が使用する読み取りトレイト
+use crate::repositories::CacheLookup;
+
 // redis: Redis クライアントライブラリ
 // AsyncCommands: 非同期コマンドのトレイト（get, set_ex, del など）
 use redis::AsyncCommands;
@@ -37,13 +40,13 @@ use uuid::Uuid;
 // 定数
 // =============================================================================
 
-/// キャッシュの有効期限（秒）
+/// キャッシュの有効期限（秒）のデフォルト値
 ///
-/// 5分（300秒）に設定。
+/// 5分（300秒）に設定。`TodoCache::with_ttl` で変更できる。
 /// - 短すぎる: キャッシュヒット率が下がる
 /// - 長すぎる: データの一貫性が低下
 /// - 5分は一般的なセッション中の操作に適切
-const CACHE_TTL_SECONDS: u64 = 300;
+pub const DEFAULT_CACHE_TTL_SECONDS: u64 = 300;
 
 // =============================================================================
 // TodoCache 構造体
@@ -69,6 +72,8 @@ pub struct TodoCache {
     /// Redis クライアント
     /// 接続プールは内部で管理される
     client: redis::Client,
+    /// キャッシュの有効期限（秒）
+    ttl_seconds: u64,
 }
 
 impl TodoCache {
@@ -83,7 +88,20 @@ impl TodoCache {
     /// クライアントは `redis::Client::open("redis://localhost:6379")` で作成。
     /// 接続は実際の操作時に確立される（遅延接続）。
     pub fn new(client: redis::Client) -> Self {
-        Self { client }
+        Self::with_ttl(client, DEFAULT_CACHE_TTL_SECONDS)
+    }
+
+    /// 有効期限を指定して TodoCache を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - Redis クライアント
+    /// * `ttl_seconds` - キャッシュの有効期限（秒、1 以上）
+    pub fn with_ttl(client: redis::Client, ttl_seconds: u64) -> Self {
+        Self {
+            client,
+            ttl_seconds,
+        }
     }
 
     /// キャッシュキーを生成する
@@ -113,7 +131,7 @@ impl TodoCache {
     ///
     /// # Note
     ///
-    /// このメソッドは pub であり、CachedTodoReader から呼び出される。
+    /// CachedTodoReader からは CacheLookup トレイト経由で呼び出される。
     /// TodoCacheOps トレイトには含まれない（読み取り専用操作のため）。
     pub async fn get(&self, id: Uuid) -> Result<Option<Todo>, DomainError> {
         // 構造化ログ: TODO ID を記録
@@ -204,7 +222,7 @@ impl TodoCacheOps for TodoCache {
         // set_ex: キーに値を設定し、TTL を指定
         // let _: () は結果を破棄することを明示
         let _: () = conn
-            .set_ex(&key, value, CACHE_TTL_SECONDS) // SETEX key value seconds
+            .set_ex(&key, value, self.ttl_seconds) // SETEX key value seconds
             .await // 非同期実行
             .map_err(|e| DomainError::Cache(e.to_string()))?; // エラー変換
 
@@ -250,3 +268,15 @@ impl TodoCacheOps for TodoCache {
         Ok(())
     }
 }
+
+// =============================================================================
+// CacheLookup トレイト実装
+// =============================================================================
+
+/// CachedTodoReader から読み取るための実装（固有メソッド get に委譲）
+#[async_trait]
+impl CacheLookup for TodoCache {
+    async fn get(&self, id: Uuid) -> Result<Option<Todo>, DomainError> {
+        TodoCache::get(self, id).await
+    }
+}
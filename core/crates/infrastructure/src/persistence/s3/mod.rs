@@ -9,7 +9,7 @@
 // - ファイルの削除
 //
 // LocalStack 対応:
-// - S3_ENDPOINT_URL 環境変数でカスタムエンドポイントを指定可能
+// - S3Config の endpoint_url でカスタムエンドポイントを指定可能（S3_ENDPOINT_URL）
 // - ローカル開発時は LocalStack を使用
 // - 本番環境では AWS S3 を使用
 // =============================================================================
@@ -18,6 +18,9 @@
 // サブモジュール宣言
 // -----------------------------------------------------------------------------
 
+// s3_config: S3 ストレージ設定（リージョン、認証情報、キープレフィックスなど）
+mod s3_config;
+
 // s3_storage_service: S3 ストレージの実装
 mod s3_storage_service;
 
@@ -25,5 +28,10 @@ mod s3_storage_service;
 // 公開する型
 // -----------------------------------------------------------------------------
 
+// S3Config / S3Credentials: S3StorageService::new() に渡す設定
+pub use s3_config::{
+    S3Config, S3Credentials, DEFAULT_PRESIGN_EXPIRY_SECS, MAX_PRESIGN_EXPIRY_SECS,
+};
+
 // S3StorageService: S3 ストレージ操作を提供する構造体
 pub use s3_storage_service::S3StorageService;
@@ -0,0 +1,340 @@
+// =============================================================================
+// infrastructure/src/persistence/s3/s3_config.rs: S3 ストレージ設定
+// =============================================================================
+// S3StorageService::new() に渡す型付きの設定。
+// 環境変数の読み込みは api（Composition Root）の config.rs で行い、
+// ここでは値の保持と検証（バケット名・キープレフィックス）のみを行う。
+//
+// 接続先ごとの設定例:
+// - AWS S3:     region = ap-northeast-1、credentials = DefaultChain
+// - MinIO:      endpoint_url = http://minio:9000、force_path_style = true、
+//               credentials = Static
+// - LocalStack: endpoint_url = http://localstack:4566、force_path_style = true
+//
+// キープレフィックス（key_prefix）:
+// - 複数のテナント・環境で 1 つのバケットを共有するためのプレフィックス
+// - S3StorageService が全てのキー操作（upload / download / delete / list）に付与し、
+//   呼び出し側（DB の storage_path）にはプレフィックスなしのキーを返す
+// =============================================================================
+
+use std::fmt;
+use std::time::Duration;
+
+use domain::DomainError;
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// 署名付き URL の有効期限のデフォルト（秒）
+pub const DEFAULT_PRESIGN_EXPIRY_SECS: u64 = 900;
+
+/// 署名付き URL の有効期限の上限（SigV4 の制約: 7 日）
+pub const MAX_PRESIGN_EXPIRY_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// キープレフィックスの最大長（S3 キーの上限 1024 バイトに対し、ファイル名の余地を残す）
+const MAX_KEY_PREFIX_LEN: usize = 256;
+
+// =============================================================================
+// S3Credentials: 認証情報の取得方法
+// =============================================================================
+
+/// S3 の認証情報の取得方法
+#[derive(Clone, PartialEq, Eq)]
+pub enum S3Credentials {
+    /// AWS SDK のデフォルトチェーン（環境変数、プロファイル、IAM ロールなど）
+    DefaultChain,
+    /// 静的なアクセスキー（MinIO など）
+    Static {
+        /// アクセスキー ID
+        access_key_id: String,
+        /// シークレットアクセスキー
+        secret_access_key: String,
+    },
+    /// 認証なし（公開バケット・署名なしのリクエスト）
+    Anonymous,
+}
+
+/// シークレットアクセスキーはログに出さない
+impl fmt::Debug for S3Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DefaultChain => f.write_str("DefaultChain"),
+            Self::Static { access_key_id, .. } => f
+                .debug_struct("Static")
+                .field("access_key_id", access_key_id)
+                .field("secret_access_key", &"***")
+                .finish(),
+            Self::Anonymous => f.write_str("Anonymous"),
+        }
+    }
+}
+
+// =============================================================================
+// S3Config: S3 ストレージ設定
+// =============================================================================
+
+/// S3 ストレージ設定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Config {
+    /// バケット名
+    pub bucket: String,
+    /// リージョン（None の場合は AWS SDK のデフォルト: AWS_REGION、プロファイルなど）
+    pub region: Option<String>,
+    /// カスタムエンドポイント URL（MinIO / LocalStack 用、None の場合は AWS 標準）
+    pub endpoint_url: Option<String>,
+    /// パススタイル（http://host/bucket/key）でアクセスするか（MinIO / LocalStack では true）
+    pub force_path_style: bool,
+    /// 認証情報の取得方法
+    pub credentials: S3Credentials,
+    /// 全てのキーに付与するプレフィックス（空文字列ならなし、末尾の `/` は不要）
+    pub key_prefix: String,
+    /// 署名付き URL の有効期限（秒）
+    pub presign_expiry_secs: u64,
+}
+
+impl S3Config {
+    /// デフォルト値で設定を作成する
+    ///
+    /// リージョン・エンドポイントは AWS SDK のデフォルト、パススタイルなし、
+    /// デフォルトの認証チェーン、プレフィックスなし。
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket` - バケット名
+    pub fn new(bucket: impl Into<String>) -> Self {
+        Self {
+            bucket: bucket.into(),
+            region: None,
+            endpoint_url: None,
+            force_path_style: false,
+            credentials: S3Credentials::DefaultChain,
+            key_prefix: String::new(),
+            presign_expiry_secs: DEFAULT_PRESIGN_EXPIRY_SECS,
+        }
+    }
+
+    /// カスタムエンドポイントを設定する（パススタイルも有効にする）
+    pub fn with_endpoint(mut self, endpoint_url: impl Into<String>) -> Self {
+        self.endpoint_url = Some(endpoint_url.into());
+        self.force_path_style = true;
+        self
+    }
+
+    /// キープレフィックスを設定する（前後の `/` は取り除く）
+    pub fn with_key_prefix(mut self, key_prefix: impl Into<String>) -> Self {
+        self.key_prefix = key_prefix.into().trim_matches('/').to_string();
+        self
+    }
+
+    /// 署名付き URL の有効期限
+    pub fn presign_expiry(&self) -> Duration {
+        Duration::from_secs(self.presign_expiry_secs)
+    }
+
+    /// 設定の問題を一覧する（問題がなければ空）
+    ///
+    /// バケット名は S3 の命名規則、キープレフィックスは安全なキーになるかを確認する。
+    /// 起動時の設定検証（api の config.rs）と `validate()` で使用する。
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if let Err(reason) = check_bucket_name(&self.bucket) {
+            problems.push(format!("S3_BUCKET: {}", reason));
+        }
+        if let Err(reason) = check_key_prefix(&self.key_prefix) {
+            problems.push(format!("S3_KEY_PREFIX: {}", reason));
+        }
+        if self.presign_expiry_secs == 0 || self.presign_expiry_secs > MAX_PRESIGN_EXPIRY_SECS {
+            problems.push(format!(
+                "S3_PRESIGN_EXPIRY_SECS: must be between 1 and {}",
+                MAX_PRESIGN_EXPIRY_SECS
+            ));
+        }
+        if let S3Credentials::Static {
+            access_key_id,
+            secret_access_key,
+        } = &self.credentials
+        {
+            if access_key_id.is_empty() || secret_access_key.is_empty() {
+                problems.push(
+                    "S3_CREDENTIALS: static credentials need an access key id and a secret"
+                        .to_string(),
+                );
+            }
+        }
+        problems
+    }
+
+    /// 設定を検証する
+    ///
+    /// # Errors
+    ///
+    /// `problems()` が空でない場合、全ての問題をまとめた `DomainError::Validation`
+    pub fn validate(&self) -> Result<(), DomainError> {
+        let problems = self.problems();
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(DomainError::Validation(format!(
+                "invalid S3 configuration: {}",
+                problems.join("; ")
+            )))
+        }
+    }
+}
+
+// =============================================================================
+// 検証ヘルパー
+// =============================================================================
+
+/// バケット名が S3 の命名規則（汎用バケット）に従っているか確認する
+///
+/// - 3〜63 文字
+/// - 小文字・数字・`.`・`-` のみ、先頭と末尾は英数字
+/// - `..` を含まない、IP アドレス形式でない
+fn check_bucket_name(bucket: &str) -> Result<(), String> {
+    if !(3..=63).contains(&bucket.len()) {
+        return Err(format!(
+            "bucket name {:?} must be 3 to 63 characters",
+            bucket
+        ));
+    }
+    if !bucket
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '.' || c == '-')
+    {
+        return Err(format!(
+            "bucket name {:?} may only contain lowercase letters, digits, '.' and '-'",
+            bucket
+        ));
+    }
+    let alphanumeric = |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphanumeric());
+    if !alphanumeric(bucket.chars().next()) || !alphanumeric(bucket.chars().last()) {
+        return Err(format!(
+            "bucket name {:?} must start and end with a letter or digit",
+            bucket
+        ));
+    }
+    if bucket.contains("..") {
+        return Err(format!("bucket name {:?} must not contain '..'", bucket));
+    }
+    if bucket.parse::<std::net::Ipv4Addr>().is_ok() {
+        return Err(format!(
+            "bucket name {:?} must not be an IP address",
+            bucket
+        ));
+    }
+    Ok(())
+}
+
+/// キープレフィックスが安全か確認する（空文字列はプレフィックスなし）
+///
+/// - 先頭・末尾の `/`、空のセグメント（`//`）、`.` / `..` セグメントを含まない
+/// - 空白・制御文字を含まない
+fn check_key_prefix(prefix: &str) -> Result<(), String> {
+    if prefix.is_empty() {
+        return Ok(());
+    }
+    if prefix.len() > MAX_KEY_PREFIX_LEN {
+        return Err(format!(
+            "key prefix must be at most {} bytes",
+            MAX_KEY_PREFIX_LEN
+        ));
+    }
+    if prefix
+        .chars()
+        .any(|c| c.is_whitespace() || c.is_control() || c == '\\')
+    {
+        return Err(format!(
+            "key prefix {:?} must not contain whitespace, control characters or '\\'",
+            prefix
+        ));
+    }
+    if prefix
+        .split('/')
+        .any(|segment| segment.is_empty() || segment == "." || segment == "..")
+    {
+        return Err(format!(
+            "key prefix {:?} must be '/'-separated names without empty, '.' or '..' segments",
+            prefix
+        ));
+    }
+    Ok(())
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_are_valid() {
+        let config = S3Config::new("todo-files");
+
+        assert!(config.validate().is_ok());
+        assert!(!config.force_path_style);
+        assert_eq!(config.credentials, S3Credentials::DefaultChain);
+        assert_eq!(config.presign_expiry(), Duration::from_secs(900));
+    }
+
+    #[test]
+    fn test_bucket_name_rules() {
+        for valid in ["abc", "todo-files", "my.bucket.2024"] {
+            assert!(check_bucket_name(valid).is_ok(), "{}", valid);
+        }
+        for invalid in [
+            "ab",
+            "Todo-Files",
+            "todo_files",
+            "-todo",
+            "todo.",
+            "todo..files",
+            "192.168.0.1",
+        ] {
+            assert!(check_bucket_name(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_key_prefix_is_normalized_and_validated() {
+        let config = S3Config::new("todo-files").with_key_prefix("/tenant-a/prod/");
+        assert_eq!(config.key_prefix, "tenant-a/prod");
+        assert!(config.validate().is_ok());
+
+        for invalid in ["a//b", "a/../b", "a b", "./a"] {
+            let problems = S3Config::new("todo-files")
+                .with_key_prefix(invalid)
+                .problems();
+            assert_eq!(problems.len(), 1, "{}: {:?}", invalid, problems);
+            assert!(problems[0].starts_with("S3_KEY_PREFIX: "));
+        }
+    }
+
+    #[test]
+    fn test_validate_reports_every_problem() {
+        let mut config = S3Config::new("Bad_Bucket").with_key_prefix("a//b");
+        config.presign_expiry_secs = 0;
+        config.credentials = S3Credentials::Static {
+            access_key_id: "minio".to_string(),
+            secret_access_key: String::new(),
+        };
+
+        assert_eq!(config.problems().len(), 4);
+        assert!(matches!(config.validate(), Err(DomainError::Validation(_))));
+    }
+
+    #[test]
+    fn test_debug_redacts_static_secret() {
+        let credentials = S3Credentials::Static {
+            access_key_id: "minio".to_string(),
+            secret_access_key: "minio-secret".to_string(),
+        };
+
+        let debug = format!("{:?}", credentials);
+        assert!(debug.contains("minio"));
+        assert!(!debug.contains("minio-secret"));
+    }
+}
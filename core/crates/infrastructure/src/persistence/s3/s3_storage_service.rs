@@ -7,6 +7,8 @@
 // - ファイルのアップロード（upload）
 // - ファイルのダウンロード（download）
 // - ファイルの削除（delete）
+// - プレフィックスでの一覧取得（list）
+// - 署名付きダウンロード URL の発行（presigned_download_url）
 // - バケットの存在確認と作成（ensure_bucket_exists）
 //
 // S3 キーフォーマット:
@@ -14,12 +16,12 @@
 // - ユーザーごとにファイルを分離
 // - UUID でファイルの一意性を保証
 //
-// 環境変数:
-// - S3_ENDPOINT_URL: カスタムエンドポイント（LocalStack 用）
-// - S3_BUCKET: バケット名（デフォルト: todo-files）
-// - AWS_DEFAULT_REGION: リージョン
-// - AWS_ACCESS_KEY_ID: アクセスキー
-// - AWS_SECRET_ACCESS_KEY: シークレットキー
+// キープレフィックス（S3Config::key_prefix）:
+// - 設定時は実際の S3 キーが `{key_prefix}/users/...` になる
+// - 全てのキー操作で付与し、list の結果からは取り除く
+// - 呼び出し側（DB の storage_path）はプレフィックスを意識しない
+//
+// 設定は S3Config（s3_config.rs）を参照。環境変数は api の config.rs で読み込む。
 // =============================================================================
 
 // -----------------------------------------------------------------------------
@@ -29,11 +31,19 @@
 // async_trait: トレイト内で async fn を使用可能にする
 use async_trait::async_trait;
 
+// chrono: 最終更新日時の変換
+use chrono::{DateTime, Utc};
+
 // aws_sdk_s3: AWS S3 クライアント
+use aws_sdk_s3::config::{Credentials, Region};
+use aws_sdk_s3::presigning::PresigningConfig;
 use aws_sdk_s3::{primitives::ByteStream, Client};
 
+// std: 署名付き URL の有効期限
+use std::time::Duration;
+
 // domain: ドメイン層の型をインポート
-use domain::{DomainError, StorageOps};
+use domain::{DomainError, StorageOps, StoredObject};
 
 // tracing: 構造化ログライブラリ
 use tracing::{debug, info, warn};
@@ -41,6 +51,9 @@ use tracing::{debug, info, warn};
 // uuid: 一意識別子ライブラリ
 use uuid::Uuid;
 
+// super: S3 ストレージ設定
+use super::s3_config::{S3Config, S3Credentials};
+
 // =============================================================================
 // S3StorageService 構造体
 // =============================================================================
@@ -63,65 +76,121 @@ pub struct S3StorageService {
     client: Client,
     /// バケット名
     bucket: String,
+    /// 全てのキーに付与するプレフィックス（空文字列ならなし）
+    key_prefix: String,
+    /// 署名付き URL の有効期限
+    presign_expiry: Duration,
 }
 
 impl S3StorageService {
-    /// 新しい S3StorageService を作成する
+    /// S3Config から S3StorageService を初期化する
     ///
     /// # Arguments
     ///
-    /// * `client` - S3 クライアント
-    /// * `bucket` - バケット名
-    pub fn new(client: Client, bucket: String) -> Self {
-        Self { client, bucket }
-    }
-
-    /// Config から S3StorageService を初期化する
-    ///
-    /// # Arguments
-    ///
-    /// * `bucket` - S3 バケット名
-    /// * `endpoint_url` - カスタムエンドポイント URL（LocalStack 用、None の場合は AWS 標準）
+    /// * `config` - S3 ストレージ設定（リージョン、エンドポイント、認証情報など）
     ///
     /// # 使用例
     ///
     /// ```rust,ignore
-    /// let config = AppConfig::from_env()?;
-    /// let storage = S3StorageService::from_config(
-    ///     &config.s3.bucket,
-    ///     config.s3.endpoint_url.as_deref(),
-    /// ).await?;
+    /// let config = S3Config::new("todo-files")
+    ///     .with_endpoint("http://localhost:4566")
+    ///     .with_key_prefix("tenant-a");
+    /// let storage = S3StorageService::new(config).await?;
     /// ```
     ///
-    /// # LocalStack 対応
+    /// # Errors
+    ///
+    /// バケット名・キープレフィックスなどが不正な場合（`S3Config::validate`）
+    pub async fn new(config: S3Config) -> Result<Self, DomainError> {
+        config.validate()?;
+
+        // AWS 設定をロード（リージョン・認証情報は設定があれば上書き）
+        let mut loader = aws_config::from_env();
+        if let Some(region) = &config.region {
+            loader = loader.region(Region::new(region.clone()));
+        }
+        loader = match &config.credentials {
+            S3Credentials::DefaultChain => loader,
+            S3Credentials::Static {
+                access_key_id,
+                secret_access_key,
+            } => loader.credentials_provider(Credentials::new(
+                access_key_id,
+                secret_access_key,
+                None,
+                None,
+                "s3-config",
+            )),
+            S3Credentials::Anonymous => loader.no_credentials(),
+        };
+        let sdk_config = loader.load().await;
+
+        // S3 クライアントを作成
+        // MinIO / LocalStack: カスタムエンドポイント + パススタイル
+        let mut builder = aws_sdk_s3::config::Builder::from(&sdk_config)
+            .force_path_style(config.force_path_style);
+        if let Some(endpoint) = &config.endpoint_url {
+            builder = builder.endpoint_url(endpoint);
+        }
+        let client = Client::from_conf(builder.build());
+
+        info!(
+            bucket = %config.bucket,
+            endpoint = config.endpoint_url.as_deref().unwrap_or("(aws default)"),
+            region = sdk_config.region().map(|r| r.as_ref()).unwrap_or("(unset)"),
+            force_path_style = config.force_path_style,
+            credentials = ?config.credentials,
+            key_prefix = %config.key_prefix,
+            "Initializing S3 storage"
+        );
+
+        Ok(Self {
+            client,
+            presign_expiry: config.presign_expiry(),
+            bucket: config.bucket,
+            key_prefix: config.key_prefix,
+        })
+    }
+
+    /// バケット名とエンドポイントだけで S3StorageService を初期化する
+    ///
+    /// `S3Config::new(bucket)`（エンドポイント指定時は `with_endpoint`）で `new()` を呼ぶ
+    /// 薄いラッパー。
+    ///
+    /// # Arguments
     ///
-    /// `endpoint_url` が指定されている場合、カスタムエンドポイントを使用。
-    /// `force_path_style(true)` を設定して LocalStack との互換性を確保。
+    /// * `bucket` - S3 バケット名
+    /// * `endpoint_url` - カスタムエンドポイント URL（LocalStack 用、None の場合は AWS 標準）
     pub async fn from_config(
         bucket: &str,
         endpoint_url: Option<&str>,
     ) -> Result<Self, DomainError> {
-        let bucket = bucket.to_string();
-
-        // AWS 設定をロード
-        let sdk_config = aws_config::from_env().load().await;
+        let config = S3Config::new(bucket);
+        let config = match endpoint_url {
+            Some(endpoint) => config.with_endpoint(endpoint),
+            None => config,
+        };
+        Self::new(config).await
+    }
 
-        // S3 クライアントを作成
-        let client = if let Some(endpoint) = endpoint_url {
-            info!(endpoint = %endpoint, bucket = %bucket, "Initializing S3 with custom endpoint (LocalStack)");
-            // LocalStack 用（カスタムエンドポイント + パススタイル強制）
-            let s3_config = aws_sdk_s3::config::Builder::from(&sdk_config)
-                .endpoint_url(endpoint)
-                .force_path_style(true)
-                .build();
-            Client::from_conf(s3_config)
+    /// 呼び出し側のキーにプレフィックスを付けた S3 キーを返す
+    fn object_key(&self, key: &str) -> String {
+        if self.key_prefix.is_empty() {
+            key.to_string()
         } else {
-            info!(bucket = %bucket, "Initializing S3 with AWS default endpoint");
-            // 本番用（AWS 標準）
-            Client::new(&sdk_config)
-        };
+            format!("{}/{}", self.key_prefix, key)
+        }
+    }
 
-        Ok(Self { client, bucket })
+    /// S3 キーからプレフィックスを取り除く（プレフィックス外のキーは None）
+    fn caller_key<'a>(&self, object_key: &'a str) -> Option<&'a str> {
+        if self.key_prefix.is_empty() {
+            Some(object_key)
+        } else {
+            object_key
+                .strip_prefix(self.key_prefix.as_str())
+                .and_then(|rest| rest.strip_prefix('/'))
+        }
     }
 
     /// バケットが存在することを確認し、なければ作成する
@@ -222,7 +291,7 @@ impl S3StorageService {
         self.client
             .put_object()
             .bucket(&self.bucket)
-            .key(&key)
+            .key(self.object_key(&key))
             .content_type(content_type)
             .body(ByteStream::from(data))
             .send()
@@ -253,7 +322,7 @@ impl S3StorageService {
             .client
             .get_object()
             .bucket(&self.bucket)
-            .key(storage_path)
+            .key(self.object_key(storage_path))
             .send()
             .await
             .map_err(|e| {
@@ -301,7 +370,7 @@ impl S3StorageService {
         self.client
             .delete_object()
             .bucket(&self.bucket)
-            .key(storage_path)
+            .key(self.object_key(storage_path))
             .send()
             .await
             .map_err(|e| DomainError::External(format!("S3 delete failed: {}", e)))?;
@@ -311,10 +380,87 @@ impl S3StorageService {
         Ok(())
     }
 
+    /// プレフィックスに一致するオブジェクトを一覧する
+    ///
+    /// ListObjectsV2 をページングしながら全件取得する（1 ページ最大 1000 件）。
+    /// キープレフィックスを付けて一覧し、結果のキーからは取り除く。
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - キーのプレフィックス
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<StoredObject>)` - 一致したオブジェクト
+    /// * `Err(DomainError::External)` - 一覧取得失敗
+    pub async fn list(&self, prefix: &str) -> Result<Vec<StoredObject>, DomainError> {
+        debug!(bucket = %self.bucket, prefix = %prefix, "Listing objects in S3");
+
+        let mut objects = Vec::new();
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(self.object_key(prefix))
+            .into_paginator()
+            .send();
+
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(|e| DomainError::External(format!("S3 list failed: {}", e)))?;
+            for object in page.contents() {
+                let Some(key) = object.key().and_then(|key| self.caller_key(key)) else {
+                    continue;
+                };
+                // 最終更新日時がない場合は古いものとして扱わない（現在時刻）
+                let last_modified = object
+                    .last_modified()
+                    .and_then(|t| DateTime::from_timestamp(t.secs(), t.subsec_nanos()))
+                    .unwrap_or_else(Utc::now);
+                objects.push(StoredObject {
+                    storage_path: key.to_string(),
+                    last_modified,
+                });
+            }
+        }
+
+        Ok(objects)
+    }
+
+    /// 署名付きのダウンロード URL を発行する
+    ///
+    /// 有効期限は `S3Config::presign_expiry_secs`（デフォルト 900 秒）。
+    ///
+    /// # Arguments
+    ///
+    /// * `storage_path` - S3 キー（DB に保存された値、プレフィックスなし）
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - 署名付き URL
+    /// * `Err(DomainError::External)` - 署名に失敗（認証情報がないなど）
+    pub async fn presigned_download_url(&self, storage_path: &str) -> Result<String, DomainError> {
+        let presigning = PresigningConfig::expires_in(self.presign_expiry)
+            .map_err(|e| DomainError::External(format!("Invalid presign expiry: {}", e)))?;
+        let request = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(self.object_key(storage_path))
+            .presigned(presigning)
+            .await
+            .map_err(|e| DomainError::External(format!("S3 presign failed: {}", e)))?;
+        Ok(request.uri().to_string())
+    }
+
     /// バケット名を取得する
     pub fn bucket(&self) -> &str {
         &self.bucket
     }
+
+    /// キープレフィックスを取得する（空文字列ならなし）
+    pub fn key_prefix(&self) -> &str {
+        &self.key_prefix
+    }
 }
 
 // =============================================================================
@@ -347,4 +493,143 @@ impl StorageOps for S3StorageService {
         // 既存の delete メソッドに委譲
         S3StorageService::delete(self, storage_path).await
     }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<StoredObject>, DomainError> {
+        // 既存の list メソッドに委譲
+        S3StorageService::list(self, prefix).await
+    }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+// LocalStack を使うテストは S3_ENDPOINT_URL_TEST が設定されている場合のみ実行する
+// （未設定ならスキップ）:
+//   docker compose up -d localstack
+//   S3_ENDPOINT_URL_TEST=http://localhost:4566 cargo test -p infrastructure s3
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conformance;
+
+    /// LocalStack のテスト用バケット
+    const TEST_BUCKET: &str = "todo-files-test";
+
+    /// ネットワークに接続せずに作成できる設定（リージョン・認証情報を固定）
+    fn offline_config(key_prefix: &str) -> S3Config {
+        let mut config = S3Config::new(TEST_BUCKET)
+            .with_endpoint("http://localhost:4566")
+            .with_key_prefix(key_prefix);
+        config.region = Some("us-east-1".to_string());
+        config.credentials = S3Credentials::Static {
+            access_key_id: "test".to_string(),
+            secret_access_key: "test".to_string(),
+        };
+        config
+    }
+
+    /// LocalStack に接続する S3StorageService（S3_ENDPOINT_URL_TEST 未設定なら None）
+    async fn localstack(key_prefix: &str) -> Option<S3StorageService> {
+        let endpoint = std::env::var("S3_ENDPOINT_URL_TEST").ok()?;
+        let mut config = offline_config(key_prefix);
+        config.endpoint_url = Some(endpoint);
+        let storage = S3StorageService::new(config).await.expect("S3 client");
+        storage
+            .ensure_bucket_exists()
+            .await
+            .expect("failed to create test bucket");
+        Some(storage)
+    }
+
+    #[tokio::test]
+    async fn test_key_prefix_is_applied_and_stripped() {
+        let storage = S3StorageService::new(offline_config("/tenant-a/"))
+            .await
+            .unwrap();
+
+        assert_eq!(storage.key_prefix(), "tenant-a");
+        assert_eq!(
+            storage.object_key("users/1/a.txt"),
+            "tenant-a/users/1/a.txt"
+        );
+        assert_eq!(
+            storage.caller_key("tenant-a/users/1/a.txt"),
+            Some("users/1/a.txt")
+        );
+        // 名前が前方一致するだけの別プレフィックスは対象外
+        assert_eq!(storage.caller_key("tenant-ab/users/1/a.txt"), None);
+
+        let unprefixed = S3StorageService::new(offline_config("")).await.unwrap();
+        assert_eq!(unprefixed.object_key("users/1/a.txt"), "users/1/a.txt");
+    }
+
+    #[tokio::test]
+    async fn test_new_rejects_invalid_bucket() {
+        let mut config = offline_config("");
+        config.bucket = "Todo_Files".to_string();
+
+        assert!(matches!(
+            S3StorageService::new(config).await,
+            Err(DomainError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_presigned_url_uses_prefixed_key() {
+        let storage = S3StorageService::new(offline_config("tenant-a"))
+            .await
+            .unwrap();
+
+        let url = storage
+            .presigned_download_url("users/1/a.txt")
+            .await
+            .unwrap();
+
+        assert!(
+            url.contains("/todo-files-test/tenant-a/users/1/a.txt?"),
+            "{}",
+            url
+        );
+        assert!(url.contains("X-Amz-Expires=900"), "{}", url);
+    }
+
+    #[tokio::test]
+    async fn test_storage_conformance_on_localstack() {
+        let Some(storage) = localstack("").await else {
+            return;
+        };
+        conformance::storage(&storage).await;
+    }
+
+    /// プレフィックス付きでも同じ契約を満たし、実際のキーはプレフィックスの下に置かれる
+    #[tokio::test]
+    async fn test_storage_conformance_on_localstack_with_prefix() {
+        let prefix = format!("tenant-{}", Uuid::new_v4().simple());
+        let Some(storage) = localstack(&prefix).await else {
+            return;
+        };
+        conformance::storage(&storage).await;
+
+        let user_id = Uuid::new_v4();
+        let key = storage
+            .upload(user_id, "a.txt", "text/plain", b"a".to_vec())
+            .await
+            .unwrap();
+        let unprefixed = localstack("").await.unwrap();
+        let listed = unprefixed.list(&format!("{}/", prefix)).await.unwrap();
+        assert_eq!(
+            listed
+                .iter()
+                .map(|o| o.storage_path.as_str())
+                .collect::<Vec<_>>(),
+            vec![format!("{}/{}", prefix, key)]
+        );
+        storage.delete(&key).await.unwrap();
+        assert!(unprefixed
+            .list(&format!("{}/", prefix))
+            .await
+            .unwrap()
+            .is_empty());
+    }
 }
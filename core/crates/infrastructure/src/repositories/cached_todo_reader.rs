@@ -14,7 +14,9 @@
 // - Reader Pool への負荷を軽減
 //
 // キャッシュの共有:
-// - Commands（Write-Through / 無効化）と共有するため Arc<TodoCache> を使用
+// - Commands（Write-Through / 無効化）と共有するため Arc<C> を使用
+// - C は CacheLookup（TodoCacheOps + get）を実装する型（デフォルト: TodoCache）
+// - NoopCache を渡すと常にキャッシュミスとなり、内部の Reader を直接呼ぶのと同じ
 // - 書き込み時にキャッシュを更新/無効化することで整合性を維持
 //
 // キャッシュ戦略（Cache-Aside パターン）:
@@ -51,6 +53,31 @@ use uuid::Uuid;
 // crate: 自クレート内のモジュール
 use crate::persistence::redis::TodoCache;
 
+// =============================================================================
+// CacheLookup トレイト
+// =============================================================================
+
+/// キャッシュからの読み取り（CachedTodoReader で使用）
+///
+/// domain の TodoCacheOps は Commands 専用で `get` を持たないため、
+/// 読み取りを含むキャッシュの抽象はインフラ層で定義する。
+///
+/// # 実装
+///
+/// - `TodoCache`: Redis
+/// - `NoopCache`: 常にキャッシュミス（キャッシュ無効時）
+#[async_trait]
+pub trait CacheLookup: TodoCacheOps {
+    /// キャッシュから TODO を取得する
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(Todo))` - キャッシュヒット
+    /// * `Ok(None)` - キャッシュミス
+    /// * `Err(DomainError::Cache)` - キャッシュのエラー
+    async fn get(&self, id: Uuid) -> Result<Option<Todo>, DomainError>;
+}
+
 // =============================================================================
 // CachedTodoReader 構造体
 // =============================================================================
@@ -63,12 +90,14 @@ use crate::persistence::redis::TodoCache;
 /// # 型パラメータ
 ///
 /// - `R`: 内部の TodoReader 実装（PostgresTodoReader など）
+/// - `C`: キャッシュ実装（デフォルト: TodoCache、キャッシュ無効時は NoopCache）
 ///
 /// # 注意
 ///
 /// - find_all（一覧取得）はキャッシュしない（フィルタ条件が多様なため）
 /// - キャッシュの有効期限は TodoCache で管理（デフォルト 5 分）
-/// - キャッシュは Commands と共有される（Arc<TodoCache>）
+/// - キャッシュは Commands と共有される（Arc<C>）
+/// - `C` は `?Sized` のため、構成で切り替える場合は `Arc<dyn CacheLookup>` も渡せる
 ///
 /// # 使用例
 ///
@@ -80,30 +109,30 @@ use crate::persistence::redis::TodoCache;
 /// // キャッシュを確認し、なければ DB から取得
 /// let todo = reader.find_by_id(id, user_id).await?;
 /// ```
-pub struct CachedTodoReader<R: TodoReader> {
+pub struct CachedTodoReader<R: TodoReader, C: CacheLookup + ?Sized = TodoCache> {
     /// 内部の TodoReader 実装
     ///
     /// キャッシュミス時にこの Reader から取得する
     reader: R,
 
-    /// Redis キャッシュ
+    /// キャッシュ（Redis または NoopCache）
     ///
     /// Arc でラップして Commands と共有する
-    cache: Arc<TodoCache>,
+    cache: Arc<C>,
 }
 
-impl<R: TodoReader> CachedTodoReader<R> {
+impl<R: TodoReader, C: CacheLookup + ?Sized> CachedTodoReader<R, C> {
     /// 新しい CachedTodoReader を作成する
     ///
     /// # Arguments
     ///
     /// * `reader` - 内部の TodoReader 実装（PostgresTodoReader など）
-    /// * `cache` - Redis キャッシュ（Arc でラップ、Commands と共有）
+    /// * `cache` - キャッシュ（Arc でラップ、Commands と共有）
     ///
     /// # Returns
     ///
     /// 新しい CachedTodoReader インスタンス
-    pub fn new(reader: R, cache: Arc<TodoCache>) -> Self {
+    pub fn new(reader: R, cache: Arc<C>) -> Self {
         Self { reader, cache }
     }
 }
@@ -119,7 +148,7 @@ impl<R: TodoReader> CachedTodoReader<R> {
 /// Rust のトレイトでは async fn を直接定義できないため、
 /// async_trait マクロを使用して非同期メソッドを実装する。
 #[async_trait]
-impl<R: TodoReader> TodoReader for CachedTodoReader<R> {
+impl<R: TodoReader, C: CacheLookup + ?Sized> TodoReader for CachedTodoReader<R, C> {
     /// ID とユーザー ID で TODO を取得する（キャッシュ対応）
     ///
     /// # 処理フロー
@@ -219,3 +248,72 @@ impl<R: TodoReader> TodoReader for CachedTodoReader<R> {
         self.reader.find_all(filter).await
     }
 }
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use domain::TodoWriter;
+
+    use crate::conformance;
+    use crate::persistence::memory::{InMemoryTodoReader, InMemoryTodoWriter, MemoryStore};
+    use crate::persistence::noop_cache::NoopCache;
+
+    fn noop_cached(store: &MemoryStore) -> CachedTodoReader<InMemoryTodoReader, NoopCache> {
+        CachedTodoReader::new(InMemoryTodoReader::new(store.clone()), Arc::new(NoopCache))
+    }
+
+    /// NoopCache では内部の Reader を直接呼ぶのと同じ結果になる
+    #[tokio::test]
+    async fn test_noop_cache_matches_inner_reader() {
+        let store = MemoryStore::new();
+        let writer = InMemoryTodoWriter::new(store.clone());
+        let inner = InMemoryTodoReader::new(store.clone());
+        let cached = noop_cached(&store);
+
+        let owner = Uuid::new_v4();
+        let todo = writer
+            .create(&Todo::new(owner, "cached".to_string(), None))
+            .await
+            .unwrap();
+
+        for (id, user_id) in [
+            (todo.id, owner),
+            (todo.id, Uuid::new_v4()),
+            (Uuid::new_v4(), owner),
+        ] {
+            assert_eq!(
+                cached.find_by_id(id, user_id).await.unwrap(),
+                inner.find_by_id(id, user_id).await.unwrap()
+            );
+        }
+
+        // キャッシュを無効化しない更新も即座に反映される（古い値を返さない）
+        writer
+            .update_fields(todo.id, owner, None, None, Some(true))
+            .await
+            .unwrap();
+        let found = cached.find_by_id(todo.id, owner).await.unwrap().unwrap();
+        assert!(found.completed);
+
+        assert_eq!(
+            cached.find_all(TodoFilter::new(owner)).await.unwrap(),
+            inner.find_all(TodoFilter::new(owner)).await.unwrap()
+        );
+    }
+
+    /// NoopCache 付きの CachedTodoReader もトレイトの契約を満たす
+    #[tokio::test]
+    async fn test_noop_cached_reader_conformance() {
+        let store = MemoryStore::new();
+        conformance::todo_repository(
+            &InMemoryTodoWriter::new(store.clone()),
+            &noop_cached(&store),
+        )
+        .await;
+    }
+}
@@ -39,4 +39,4 @@ mod cached_todo_reader;
 // -----------------------------------------------------------------------------
 
 /// CachedTodoReader を公開
-pub use cached_todo_reader::CachedTodoReader;
+pub use cached_todo_reader::{CacheLookup, CachedTodoReader};
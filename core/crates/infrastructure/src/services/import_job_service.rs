@@ -0,0 +1,383 @@
+// =============================================================================
+// infrastructure/src/services/import_job_service.rs
+// =============================================================================
+// 非同期インポートジョブの永続化を提供するサービス。
+// ジョブの登録、ワーカーによる取得（リース）、状態更新、キャンセルを担当する。
+//
+// 設計判断:
+// - ジョブとペイロードは PostgreSQL に保存（再起動後も処理を再開できる）
+// - ワーカーの取得は FOR UPDATE SKIP LOCKED で複数インスタンスに対応
+// - running のまま updated_at がリース期間を超えたジョブは、
+//   停止したワーカーのものとみなして再取得する
+// - 項目の作成と進捗更新は TransactionalTodoService::import_chunk で
+//   同一トランザクションにまとめる（二重作成を防ぐ）
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// 外部クレートのインポート
+// -----------------------------------------------------------------------------
+
+// std: 標準ライブラリ
+// Duration: ワーカーのリース期間
+use std::time::Duration;
+
+// chrono: 日付・時刻ライブラリ
+use chrono::{DateTime, Utc};
+
+// domain: ドメイン層の型をインポート
+use domain::{DomainError, ImportJob, JobState};
+
+// sqlx: PostgreSQL クライアントライブラリ
+use sqlx::{FromRow, PgPool};
+
+// tracing: 構造化ログライブラリ
+use tracing::{debug, info};
+
+// uuid: 一意識別子ライブラリ
+use uuid::Uuid;
+
+// =============================================================================
+// ImportJobService 構造体
+// =============================================================================
+
+/// インポートジョブサービス
+///
+/// # derive マクロ
+///
+/// - `Clone`: PgPool は内部で Arc を使用しているため、Clone は安価
+#[derive(Clone)]
+pub struct ImportJobService {
+    /// PostgreSQL 接続プール（Writer 用）
+    pool: PgPool,
+}
+
+impl ImportJobService {
+    /// 新しい ImportJobService を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - PostgreSQL 接続プール（Writer 用を推奨）
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+// =============================================================================
+// 行マッピング用構造体（内部用）
+// =============================================================================
+
+/// import_jobs テーブルの行
+#[derive(FromRow)]
+struct ImportJobRow {
+    id: Uuid,
+    user_id: Uuid,
+    state: String,
+    total_items: i32,
+    processed_items: i32,
+    failed_items: i32,
+    error: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// ImportJobRow から domain::ImportJob への変換
+///
+/// state の文字列が不正な場合はエラー（CHECK 制約があるため通常は発生しない）
+impl TryFrom<ImportJobRow> for ImportJob {
+    type Error = DomainError;
+
+    fn try_from(row: ImportJobRow) -> Result<Self, Self::Error> {
+        Ok(ImportJob::from_raw(
+            row.id,
+            row.user_id,
+            row.state.parse::<JobState>()?,
+            row.total_items,
+            row.processed_items,
+            row.failed_items,
+            row.error,
+            row.created_at,
+            row.updated_at,
+        ))
+    }
+}
+
+/// import_jobs の RETURNING / SELECT 句で使用するカラム
+const JOB_COLUMNS: &str =
+    "id, user_id, state, total_items, processed_items, failed_items, error, created_at, updated_at";
+
+// =============================================================================
+// ImportItem 構造体
+// =============================================================================
+
+/// インポート対象の項目（1 件分、未検証）
+///
+/// # derive マクロ
+///
+/// - `Debug`: デバッグ出力用
+/// - `Clone`: 複製可能に
+#[derive(Debug, Clone, FromRow)]
+pub struct ImportItem {
+    /// リクエスト内の位置（0 始まり）
+    pub position: i32,
+    /// タイトル（未検証）
+    pub title: String,
+    /// 説明
+    pub description: Option<String>,
+}
+
+// =============================================================================
+// ImportJobService メソッド実装
+// =============================================================================
+
+impl ImportJobService {
+    /// ジョブを登録する
+    ///
+    /// ジョブ行とペイロード（項目）を1トランザクションで保存する。
+    /// 項目のバリデーションはワーカーが行う（不正な項目は failed として数える）。
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - ジョブを登録するユーザーの ID
+    /// * `items` - (title, description) のリスト
+    ///
+    /// # Returns
+    ///
+    /// 登録されたジョブ（state = pending）
+    pub async fn enqueue(
+        &self,
+        user_id: Uuid,
+        items: Vec<(String, Option<String>)>,
+    ) -> Result<ImportJob, DomainError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DomainError::Repository(e.to_string()))?;
+
+        // 1. ジョブ行を作成
+        let row: ImportJobRow = sqlx::query_as(&format!(
+            "INSERT INTO import_jobs (user_id, total_items) VALUES ($1, $2) RETURNING {}",
+            JOB_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(items.len() as i32)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| DomainError::Repository(e.to_string()))?;
+
+        // 2. 項目を UNNEST で一括 INSERT
+        let positions: Vec<i32> = (0..items.len() as i32).collect();
+        let (titles, descriptions): (Vec<String>, Vec<Option<String>>) = items.into_iter().unzip();
+
+        sqlx::query(
+            r#"
+            INSERT INTO import_job_items (job_id, position, title, description)
+            SELECT $1, v.position, v.title, v.description
+            FROM UNNEST($2::int[], $3::text[], $4::text[]) AS v(position, title, description)
+            "#,
+        )
+        .bind(row.id)
+        .bind(&positions)
+        .bind(&titles)
+        .bind(&descriptions)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| DomainError::Repository(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DomainError::Repository(e.to_string()))?;
+
+        let job = ImportJob::try_from(row)?;
+        info!(job_id = %job.id, user_id = %user_id, total_items = job.total_items, "Import job enqueued");
+
+        Ok(job)
+    }
+
+    /// ジョブを取得する（所有者チェック付き）
+    ///
+    /// # Returns
+    ///
+    /// 存在しない、または他ユーザー所有の場合は `None`
+    pub async fn find(&self, id: Uuid, user_id: Uuid) -> Result<Option<ImportJob>, DomainError> {
+        let row: Option<ImportJobRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM import_jobs WHERE id = $1 AND user_id = $2",
+            JOB_COLUMNS
+        ))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::Repository(e.to_string()))?;
+
+        row.map(ImportJob::try_from).transpose()
+    }
+
+    /// 処理するジョブを1件取得し、running にする
+    ///
+    /// pending のジョブ、またはリース切れ（`updated_at` が `lease` より古い）の
+    /// running ジョブを登録順に取得する。
+    /// FOR UPDATE SKIP LOCKED により、複数のワーカーが同じジョブを取得しない。
+    ///
+    /// # Arguments
+    ///
+    /// * `lease` - running ジョブを停止したワーカーのものとみなすまでの期間
+    ///
+    /// # Returns
+    ///
+    /// 取得したジョブ（対象がなければ `None`）
+    pub async fn claim_next(&self, lease: Duration) -> Result<Option<ImportJob>, DomainError> {
+        let row: Option<ImportJobRow> = sqlx::query_as(&format!(
+            r#"
+            UPDATE import_jobs
+            SET state = 'running', updated_at = NOW()
+            WHERE id = (
+                SELECT id FROM import_jobs
+                WHERE state = 'pending'
+                   OR (state = 'running' AND updated_at < NOW() - make_interval(secs => $1))
+                ORDER BY created_at
+                FOR UPDATE SKIP LOCKED
+                LIMIT 1
+            )
+            RETURNING {}
+            "#,
+            JOB_COLUMNS
+        ))
+        .bind(lease.as_secs_f64())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::Repository(e.to_string()))?;
+
+        let job = row.map(ImportJob::try_from).transpose()?;
+        if let Some(job) = &job {
+            debug!(job_id = %job.id, processed_items = job.processed_items, "Import job claimed");
+        }
+        Ok(job)
+    }
+
+    /// 指定位置以降の項目を取得する
+    ///
+    /// # Arguments
+    ///
+    /// * `job_id` - ジョブ ID
+    /// * `from_position` - 取得を開始する位置（ジョブの processed_items）
+    /// * `limit` - 取得する最大件数（チャンクサイズ）
+    pub async fn fetch_items(
+        &self,
+        job_id: Uuid,
+        from_position: i32,
+        limit: i64,
+    ) -> Result<Vec<ImportItem>, DomainError> {
+        sqlx::query_as(
+            r#"
+            SELECT position, title, description
+            FROM import_job_items
+            WHERE job_id = $1 AND position >= $2
+            ORDER BY position
+            LIMIT $3
+            "#,
+        )
+        .bind(job_id)
+        .bind(from_position)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::Repository(e.to_string()))
+    }
+
+    /// ジョブを終了状態にする（running の場合のみ）
+    ///
+    /// キャンセル済みのジョブは上書きしない。
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ジョブ ID
+    /// * `state` - 終了状態（completed / failed）
+    /// * `error` - ジョブ自体のエラーメッセージ（failed の場合）
+    pub async fn finish(
+        &self,
+        id: Uuid,
+        state: JobState,
+        error: Option<&str>,
+    ) -> Result<(), DomainError> {
+        debug_assert!(JobState::Running.can_transition_to(state));
+
+        sqlx::query(
+            r#"
+            UPDATE import_jobs
+            SET state = $2, error = $3, updated_at = NOW()
+            WHERE id = $1 AND state = 'running'
+            "#,
+        )
+        .bind(id)
+        .bind(state.as_str())
+        .bind(error)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::Repository(e.to_string()))?;
+
+        info!(job_id = %id, state = state.as_str(), "Import job finished");
+        Ok(())
+    }
+
+    /// ジョブをキャンセルする（所有者チェック付き）
+    ///
+    /// pending / running のジョブのみ cancelled にする。
+    /// running のジョブは、ワーカーが次のチャンクを処理する前に停止する。
+    ///
+    /// # Returns
+    ///
+    /// * `Some(job)` - 更新後（またはすでに終了状態）のジョブ
+    /// * `None` - 存在しない、または他ユーザー所有
+    pub async fn cancel(&self, id: Uuid, user_id: Uuid) -> Result<Option<ImportJob>, DomainError> {
+        let row: Option<ImportJobRow> = sqlx::query_as(&format!(
+            r#"
+            UPDATE import_jobs
+            SET state = 'cancelled', updated_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND state IN ('pending', 'running')
+            RETURNING {}
+            "#,
+            JOB_COLUMNS
+        ))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::Repository(e.to_string()))?;
+
+        match row {
+            Some(row) => {
+                info!(job_id = %id, "Import job cancelled");
+                ImportJob::try_from(row).map(Some)
+            }
+            // 終了状態のジョブ（または存在しない）: 現在の状態を返す
+            None => self.find(id, user_id).await,
+        }
+    }
+
+    /// 失敗した項目のエラーを位置順に取得する（結果サマリー用）
+    ///
+    /// # Returns
+    ///
+    /// (位置, エラーメッセージ) のリスト（最大 `limit` 件）
+    pub async fn item_errors(
+        &self,
+        job_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<(i32, String)>, DomainError> {
+        sqlx::query_as(
+            r#"
+            SELECT position, error
+            FROM import_job_items
+            WHERE job_id = $1 AND error IS NOT NULL
+            ORDER BY position
+            LIMIT $2
+            "#,
+        )
+        .bind(job_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::Repository(e.to_string()))
+    }
+}
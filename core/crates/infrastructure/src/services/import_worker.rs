@@ -0,0 +1,251 @@
+// =============================================================================
+// infrastructure/src/services/import_worker.rs
+// =============================================================================
+// 非同期インポートジョブを処理するバックグラウンドワーカー。
+// main.rs で tokio::spawn され、ジョブテーブルをポーリングする。
+//
+// 処理の流れ:
+// 1. ImportJobService::claim_next でジョブを取得（running にする）
+// 2. processed_items の位置から項目をチャンク単位で取得
+// 3. タイトルを検証し、TransactionalTodoService::import_chunk で
+//    TODO 作成と進捗更新を1トランザクションで実行
+// 4. 全項目を処理したら completed、DB エラーなら failed にする
+//
+// キャンセル:
+// - import_chunk は running でないジョブの進捗を更新しないため、
+//   キャンセルされたジョブは次のチャンクの前に停止する
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// 外部クレートのインポート
+// -----------------------------------------------------------------------------
+
+// std: 標準ライブラリ
+// Duration: ポーリング間隔、リース期間
+use std::time::Duration;
+
+// domain: ドメイン層の型をインポート
+use domain::{DomainError, ImportJob, JobState, Todo};
+
+// tracing: 構造化ログライブラリ
+use tracing::{error, info, warn};
+
+// 同じモジュール内のサービス
+use super::import_job_service::{ImportItem, ImportJobService};
+use super::transactional_todo_service::TransactionalTodoService;
+
+// =============================================================================
+// ImportWorkerConfig 構造体
+// =============================================================================
+
+/// ワーカー設定
+///
+/// # derive マクロ
+///
+/// - `Debug`: デバッグ出力用
+/// - `Clone`: 複製可能に
+#[derive(Debug, Clone)]
+pub struct ImportWorkerConfig {
+    /// 1トランザクションで処理する項目数
+    pub chunk_size: usize,
+    /// ジョブがない場合の待機時間
+    pub poll_interval: Duration,
+    /// running ジョブを停止したワーカーのものとみなすまでの期間
+    ///
+    /// 1チャンクの処理時間より十分長くすること
+    pub lease: Duration,
+}
+
+// =============================================================================
+// ImportWorker 構造体
+// =============================================================================
+
+/// インポートジョブワーカー
+///
+/// # 使用例
+///
+/// ```rust,ignore
+/// let worker = ImportWorker::new(jobs, batch_service, config);
+/// tokio::spawn(worker.run());
+/// ```
+pub struct ImportWorker {
+    /// ジョブの取得・状態更新
+    jobs: ImportJobService,
+    /// チャンクの処理（TODO 作成 + 進捗更新）
+    todos: TransactionalTodoService,
+    /// ワーカー設定
+    config: ImportWorkerConfig,
+}
+
+impl ImportWorker {
+    /// 新しい ImportWorker を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `jobs` - インポートジョブサービス
+    /// * `todos` - トランザクション対応 TODO サービス
+    /// * `config` - ワーカー設定（chunk_size が 0 の場合は 1 に補正）
+    pub fn new(
+        jobs: ImportJobService,
+        todos: TransactionalTodoService,
+        mut config: ImportWorkerConfig,
+    ) -> Self {
+        config.chunk_size = config.chunk_size.max(1);
+        Self {
+            jobs,
+            todos,
+            config,
+        }
+    }
+
+    /// ワーカーのメインループ
+    ///
+    /// ジョブがあれば処理し、なければ poll_interval だけ待機する。
+    /// プロセス終了まで戻らない。
+    pub async fn run(self) {
+        info!(
+            chunk_size = self.config.chunk_size,
+            poll_interval_ms = self.config.poll_interval.as_millis() as u64,
+            lease_secs = self.config.lease.as_secs(),
+            "Import worker started"
+        );
+
+        loop {
+            match self.run_once().await {
+                // ジョブを処理した: すぐに次のジョブを確認
+                Ok(true) => continue,
+                // ジョブなし: 待機
+                Ok(false) => {}
+                // 取得自体の失敗（DB 接続エラーなど）: 待機して再試行
+                Err(e) => warn!(error = %e, "Failed to claim import job"),
+            }
+            tokio::time::sleep(self.config.poll_interval).await;
+        }
+    }
+
+    /// ジョブを1件取得して処理する
+    ///
+    /// # Returns
+    ///
+    /// ジョブを取得した場合は `true`
+    pub async fn run_once(&self) -> Result<bool, DomainError> {
+        let Some(job) = self.jobs.claim_next(self.config.lease).await? else {
+            return Ok(false);
+        };
+
+        if let Err(e) = self.process(&job).await {
+            // ジョブ自体の失敗: failed にして終了
+            error!(job_id = %job.id, error = %e, "Import job failed");
+            if let Err(e) = self
+                .jobs
+                .finish(job.id, JobState::Failed, Some(&e.to_string()))
+                .await
+            {
+                warn!(job_id = %job.id, error = %e, "Failed to mark import job as failed");
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// ジョブの残りの項目をチャンク単位で処理する
+    async fn process(&self, job: &ImportJob) -> Result<(), DomainError> {
+        // 再取得したジョブは processed_items の位置から再開する
+        let mut cursor = job.processed_items;
+
+        loop {
+            let items = self
+                .jobs
+                .fetch_items(job.id, cursor, self.config.chunk_size as i64)
+                .await?;
+
+            // 全項目を処理済み
+            if items.is_empty() {
+                return self.jobs.finish(job.id, JobState::Completed, None).await;
+            }
+
+            let chunk_len = items.len() as i32;
+            let chunk = partition_chunk(items);
+
+            let committed = self
+                .todos
+                .import_chunk(
+                    job.id,
+                    job.user_id,
+                    cursor,
+                    chunk_len,
+                    chunk.todos,
+                    &chunk.item_errors,
+                )
+                .await?;
+
+            if !committed {
+                // キャンセルされた、または別のワーカーに引き継がれた
+                info!(job_id = %job.id, cursor, "Import job stopped before next chunk");
+                return Ok(());
+            }
+
+            cursor += chunk_len;
+        }
+    }
+}
+
+// =============================================================================
+// ヘルパー関数
+// =============================================================================
+
+/// 検証済みのチャンク
+struct PartitionedChunk {
+    /// 有効な項目: (トリム済みタイトル, 説明) のリスト
+    todos: Vec<(String, Option<String>)>,
+    /// 無効な項目: (位置, エラーメッセージ) のリスト
+    item_errors: Vec<(i32, String)>,
+}
+
+/// チャンクの項目を検証し、有効な項目と無効な項目のエラーに分ける
+fn partition_chunk(items: Vec<ImportItem>) -> PartitionedChunk {
+    let mut todos = Vec::with_capacity(items.len());
+    let mut item_errors = Vec::new();
+
+    for item in items {
+        match Todo::validate_title(&item.title) {
+            Ok(title) => todos.push((title, item.description)),
+            Err(DomainError::Validation(msg)) => item_errors.push((item.position, msg)),
+            Err(e) => item_errors.push((item.position, e.to_string())),
+        }
+    }
+
+    PartitionedChunk { todos, item_errors }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(position: i32, title: &str) -> ImportItem {
+        ImportItem {
+            position,
+            title: title.to_string(),
+            description: None,
+        }
+    }
+
+    /// 無効な項目は元の位置とメッセージを保持し、有効な項目はトリムされる
+    #[test]
+    fn test_partition_chunk_keeps_positions_of_invalid_items() {
+        let chunk = partition_chunk(vec![item(100, " a "), item(101, "  "), item(102, "c")]);
+
+        assert_eq!(
+            chunk.todos,
+            vec![("a".to_string(), None), ("c".to_string(), None)]
+        );
+        assert_eq!(
+            chunk.item_errors,
+            vec![(101, "title cannot be empty".to_string())]
+        );
+    }
+}
@@ -8,6 +8,10 @@
 //
 // 提供するサービス:
 // - TransactionalTodoService: トランザクション対応バッチ操作
+// - TransactionProvider / TodoTransaction: トランザクションの抽象（テストで差し替え可能）
+// - ImportJobService: 非同期インポートジョブの永続化
+// - ImportWorker: 非同期インポートジョブのバックグラウンド処理
+// - OrphanFileCleanup: ストレージ上の孤立ファイルの掃除（運用コマンド用）
 //
 // 設計原則:
 // - Domain 層は sqlx に依存しない
@@ -22,10 +26,51 @@
 // transactional_todo_service: トランザクション対応バッチ操作
 mod transactional_todo_service;
 
+// todo_transaction: TransactionalTodoService のトランザクション抽象（PostgreSQL 実装を含む）
+mod todo_transaction;
+
+// import_job_service: 非同期インポートジョブの永続化
+mod import_job_service;
+
+// import_worker: 非同期インポートジョブのワーカー
+mod import_worker;
+
+// orphan_cleanup: 孤立ファイルの掃除
+mod orphan_cleanup;
+
 // -----------------------------------------------------------------------------
 // 公開する型
 // -----------------------------------------------------------------------------
 
+// BatchDeleteOutcome: バッチ削除の結果
+// BatchUpdateOutcome: バッチ更新の結果
+// CreateOutcome: 重複検出付きバッチ作成の結果（1 件分）
 // FileInput: ファイル作成用の入力データ
+// FileUploadInput: アップロード付きファイル作成用の入力データ
+// TodoCreateInput: バッチ作成用の入力データ（完了状態を含む）
+// TodoUpdateInput: バッチ更新用の入力データ
 // TransactionalTodoService: トランザクション対応 TODO サービス
-pub use transactional_todo_service::{FileInput, TransactionalTodoService};
+pub use transactional_todo_service::{
+    BatchDeleteOutcome, BatchUpdateOutcome, CreateOutcome, FileInput, FileUploadInput,
+    TodoCreateInput, TodoUpdateInput, TransactionalTodoService,
+};
+
+// PgTodoTransaction: PostgreSQL のトランザクション
+// PgTransactionProvider: PostgreSQL のトランザクションプロバイダ
+// TodoTransaction: トランザクション内で実行する操作
+// TransactionProvider: トランザクションを開始するトレイト
+pub use todo_transaction::{
+    PgTodoTransaction, PgTransactionProvider, TodoTransaction, TransactionProvider,
+};
+
+// ImportItem: インポート対象の項目
+// ImportJobService: インポートジョブサービス
+pub use import_job_service::{ImportItem, ImportJobService};
+
+// ImportWorker: インポートジョブワーカー
+// ImportWorkerConfig: ワーカー設定
+pub use import_worker::{ImportWorker, ImportWorkerConfig};
+
+// OrphanCleanupReport: 孤立ファイル掃除の結果
+// OrphanFileCleanup: 孤立ファイルの掃除
+pub use orphan_cleanup::{OrphanCleanupReport, OrphanFileCleanup};
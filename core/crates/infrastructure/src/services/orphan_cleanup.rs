@@ -0,0 +1,205 @@
+// =============================================================================
+// infrastructure/src/services/orphan_cleanup.rs
+// =============================================================================
+// ストレージ上の孤立ファイル（files テーブルから参照されないオブジェクト）を削除する。
+//
+// 孤立ファイルが発生する経路:
+// - TODO / ファイル削除のコミット後にストレージ削除が失敗した
+//   （TransactionalTodoService は失敗したキーを警告ログに出して処理を続ける）
+// - アップロード後の補償削除（upload_with_compensation）が失敗した
+//
+// 設計判断:
+// - 参照の有無は files.storage_path で判定する（UNIQUE インデックスあり）
+// - アップロード直後でまだ DB に行がないオブジェクトを消さないよう、
+//   最終更新日時が猶予期間より新しいオブジェクトは対象外
+// - 1 件の削除失敗で中断せず、失敗したキーをレポートに含める
+// - 運用コマンド（api cleanup-orphans）から呼び出す。バックグラウンドでは動かさない
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// 外部クレートのインポート
+// -----------------------------------------------------------------------------
+
+// std: 標準ライブラリ
+use std::collections::HashSet;
+use std::sync::Arc;
+
+// chrono: 猶予期間の判定
+use chrono::{DateTime, Duration, Utc};
+
+// domain: ドメイン層の型をインポート
+use domain::{DomainError, StorageOps, StoredObject};
+
+// sqlx: PostgreSQL クライアントライブラリ
+use sqlx::PgPool;
+
+// tracing: 構造化ログライブラリ
+use tracing::{info, warn};
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// 走査するキーのプレフィックス（StorageOps::upload のキー形式）
+const STORAGE_PREFIX: &str = "users/";
+
+// =============================================================================
+// OrphanCleanupReport 構造体
+// =============================================================================
+
+/// 孤立ファイル掃除の結果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrphanCleanupReport {
+    /// 走査したオブジェクト数
+    pub scanned: usize,
+    /// 孤立と判定したキー
+    pub orphans: Vec<String>,
+    /// 削除したオブジェクト数（dry run では 0）
+    pub deleted: usize,
+    /// 削除に失敗したキー
+    pub failed: Vec<String>,
+}
+
+// =============================================================================
+// OrphanFileCleanup 構造体
+// =============================================================================
+
+/// 孤立ファイルの掃除
+///
+/// # derive マクロ
+///
+/// - `Clone`: PgPool と Arc は Clone が安価
+#[derive(Clone)]
+pub struct OrphanFileCleanup {
+    /// PostgreSQL 接続プール（files テーブルの参照用）
+    pool: PgPool,
+    /// ファイルストレージ
+    storage: Arc<dyn StorageOps>,
+}
+
+impl OrphanFileCleanup {
+    /// 新しい OrphanFileCleanup を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - PostgreSQL 接続プール
+    /// * `storage` - ファイルストレージ
+    pub fn new(pool: PgPool, storage: Arc<dyn StorageOps>) -> Self {
+        Self { pool, storage }
+    }
+
+    /// 孤立ファイルを探して削除する
+    ///
+    /// # Arguments
+    ///
+    /// * `grace` - 猶予期間（最終更新からこの期間が経っていないオブジェクトは対象外）
+    /// * `dry_run` - true なら削除せずに判定結果のみ返す
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OrphanCleanupReport)` - 走査・削除の結果（削除失敗は failed に含む）
+    /// * `Err(DomainError::External)` - ストレージの一覧取得に失敗
+    /// * `Err(DomainError::Repository)` - files テーブルの読み込みに失敗
+    pub async fn run(
+        &self,
+        grace: Duration,
+        dry_run: bool,
+    ) -> Result<OrphanCleanupReport, DomainError> {
+        // 一覧取得後にアップロードされた行を取りこぼさないよう、ストレージを先に一覧する
+        let objects = self.storage.list(STORAGE_PREFIX).await?;
+        let referenced: HashSet<String> =
+            sqlx::query_scalar::<_, String>("SELECT storage_path FROM files")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| DomainError::Repository(e.to_string()))?
+                .into_iter()
+                .collect();
+
+        let orphans = find_orphans(&objects, &referenced, Utc::now() - grace);
+        let mut report = OrphanCleanupReport {
+            scanned: objects.len(),
+            orphans,
+            ..Default::default()
+        };
+
+        if dry_run {
+            info!(
+                scanned = report.scanned,
+                orphans = report.orphans.len(),
+                "Orphan cleanup dry run"
+            );
+            return Ok(report);
+        }
+
+        for key in &report.orphans {
+            match self.storage.delete(key).await {
+                Ok(()) => report.deleted += 1,
+                Err(e) => {
+                    warn!(storage_path = %key, error = %e, "Failed to delete orphaned object");
+                    report.failed.push(key.clone());
+                }
+            }
+        }
+
+        info!(
+            scanned = report.scanned,
+            deleted = report.deleted,
+            failed = report.failed.len(),
+            "Orphan cleanup finished"
+        );
+        Ok(report)
+    }
+}
+
+// =============================================================================
+// ヘルパー関数
+// =============================================================================
+
+/// 参照されておらず、`cutoff` より前に更新されたオブジェクトのキーを返す
+///
+/// 結果はキーの昇順（ログ・レポートを安定させるため）。
+fn find_orphans(
+    objects: &[StoredObject],
+    referenced: &HashSet<String>,
+    cutoff: DateTime<Utc>,
+) -> Vec<String> {
+    let mut orphans: Vec<String> = objects
+        .iter()
+        .filter(|object| object.last_modified < cutoff)
+        .filter(|object| !referenced.contains(&object.storage_path))
+        .map(|object| object.storage_path.clone())
+        .collect();
+    orphans.sort();
+    orphans
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(path: &str, age_hours: i64) -> StoredObject {
+        StoredObject {
+            storage_path: path.to_string(),
+            last_modified: Utc::now() - Duration::hours(age_hours),
+        }
+    }
+
+    /// 参照されているキーと猶予期間内のキーは孤立と判定しない
+    #[test]
+    fn test_find_orphans_skips_referenced_and_recent_objects() {
+        let objects = vec![
+            object("users/u/files/3/recent.txt", 1),
+            object("users/u/files/2/orphan.txt", 48),
+            object("users/u/files/1/kept.txt", 48),
+        ];
+        let referenced = HashSet::from(["users/u/files/1/kept.txt".to_string()]);
+
+        let orphans = find_orphans(&objects, &referenced, Utc::now() - Duration::hours(24));
+
+        assert_eq!(orphans, vec!["users/u/files/2/orphan.txt".to_string()]);
+    }
+}
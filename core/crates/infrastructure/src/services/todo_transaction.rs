@@ -0,0 +1,511 @@
+// =============================================================================
+// infrastructure/src/services/todo_transaction.rs
+// =============================================================================
+// TransactionalTodoService が使用するトランザクションの抽象化。
+// SQL を発行する部分だけをトレイトの背後に置き、
+// サービス側のオーケストレーション（順序、コミット/ロールバックの判断、
+// 結果の組み立て）を DB なしでテストできるようにする。
+//
+// 構成:
+// - TransactionProvider: トランザクションを開始する（PgPool::begin に相当）
+// - TodoTransaction: トランザクション内で実行する操作 + commit / rollback
+// - PgTransactionProvider / PgTodoTransaction: PostgreSQL 実装（本番用）
+//
+// ロールバックの規約:
+// - commit() を呼ばずに Drop した場合はロールバックされること
+//   （sqlx::Transaction の RAII と同じ）
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// 外部クレートのインポート
+// -----------------------------------------------------------------------------
+
+// async_trait: トレイトで async fn を使用するためのマクロ
+use async_trait::async_trait;
+
+// chrono: 日付・時刻ライブラリ
+use chrono::{DateTime, Utc};
+
+// domain: ドメイン層の型をインポート
+use domain::{DomainError, File, Todo};
+
+// sqlx: PostgreSQL クライアントライブラリ
+// FromRow: クエリ結果から構造体への自動マッピング
+// PgPool / Postgres / Transaction: 接続プールとトランザクション
+use sqlx::{FromRow, PgPool, Postgres, Transaction};
+
+// uuid: 一意識別子ライブラリ
+use uuid::Uuid;
+
+// 同じモジュール内の入力型
+use super::transactional_todo_service::TodoUpdateInput;
+
+// =============================================================================
+// TransactionProvider トレイト
+// =============================================================================
+
+/// トランザクションを開始するトレイト
+///
+/// TransactionalTodoService はこのトレイトを通してトランザクションを開始する。
+/// テストでは記録用・失敗注入用の実装を差し替える。
+#[async_trait]
+pub trait TransactionProvider: Send + Sync {
+    /// このプロバイダが開始するトランザクションの型
+    type Tx: TodoTransaction;
+
+    /// トランザクションを開始する
+    ///
+    /// # Errors
+    ///
+    /// 接続の取得や BEGIN に失敗した場合は `DomainError::Repository`
+    async fn begin(&self) -> Result<Self::Tx, DomainError>;
+}
+
+// =============================================================================
+// TodoTransaction トレイト
+// =============================================================================
+
+/// トランザクション内で実行する操作
+///
+/// 各メソッドは SQL 文 1 つに対応し、判断ロジックを持たない。
+/// commit() を呼ばずに Drop した場合はロールバックされること。
+#[async_trait]
+pub trait TodoTransaction: Send {
+    /// TODO を INSERT し、保存された TODO を返す
+    async fn insert_todo(&mut self, todo: &Todo) -> Result<Todo, DomainError>;
+
+    /// 正規化したタイトルが一致する、ユーザーの未完了 TODO を取得する
+    ///
+    /// 正規化の規則は `Todo::normalize_title` と同じ。
+    /// 重複検出（バッチ作成の dedupe）に使用する。
+    async fn find_open_todos_by_normalized_titles(
+        &mut self,
+        user_id: Uuid,
+        normalized_titles: &[String],
+    ) -> Result<Vec<Todo>, DomainError>;
+
+    /// ファイルメタデータを INSERT し、保存されたファイルを返す
+    async fn insert_file(&mut self, file: &File) -> Result<File, DomainError>;
+
+    /// 複数 TODO を更新し、更新された TODO を返す（順序は不定）
+    ///
+    /// 所有者（`user_id`）が一致しない TODO は更新されず、結果にも含まれない。
+    async fn update_todos(
+        &mut self,
+        user_id: Uuid,
+        updates: &[TodoUpdateInput],
+    ) -> Result<Vec<Todo>, DomainError>;
+
+    /// 対象 TODO に紐づくファイルのストレージキーを取得する（所有者チェック付き）
+    async fn file_storage_keys(
+        &mut self,
+        user_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<Vec<String>, DomainError>;
+
+    /// 対象 TODO に紐づくファイルメタデータを削除し、件数を返す（所有者チェック付き）
+    async fn delete_files(&mut self, user_id: Uuid, ids: &[Uuid]) -> Result<u64, DomainError>;
+
+    /// TODO を削除し、削除された ID を返す（所有者チェック付き）
+    async fn delete_todos(&mut self, user_id: Uuid, ids: &[Uuid])
+        -> Result<Vec<Uuid>, DomainError>;
+
+    /// インポートジョブの進捗を進める
+    ///
+    /// running かつ processed_items が `cursor` と一致する場合のみ更新する。
+    ///
+    /// # Returns
+    ///
+    /// 更新した場合は `true`
+    async fn advance_import_job(
+        &mut self,
+        job_id: Uuid,
+        cursor: i32,
+        chunk_len: i32,
+        failed: i32,
+    ) -> Result<bool, DomainError>;
+
+    /// インポート項目のエラーを記録する
+    async fn record_import_item_errors(
+        &mut self,
+        job_id: Uuid,
+        item_errors: &[(i32, String)],
+    ) -> Result<(), DomainError>;
+
+    /// コミットする
+    async fn commit(self) -> Result<(), DomainError>;
+
+    /// 明示的にロールバックする
+    async fn rollback(self) -> Result<(), DomainError>;
+}
+
+// =============================================================================
+// PostgreSQL 実装
+// =============================================================================
+
+/// PostgreSQL のトランザクションプロバイダ
+///
+/// # derive マクロ
+///
+/// - `Clone`: PgPool は内部で Arc を使用しているため、Clone は安価
+#[derive(Clone)]
+pub struct PgTransactionProvider {
+    /// PostgreSQL 接続プール（Writer 用推奨）
+    pool: PgPool,
+}
+
+impl PgTransactionProvider {
+    /// 新しい PgTransactionProvider を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - PostgreSQL 接続プール（Writer 用を推奨）
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TransactionProvider for PgTransactionProvider {
+    type Tx = PgTodoTransaction;
+
+    async fn begin(&self) -> Result<Self::Tx, DomainError> {
+        // RAII: tx が Drop されるとき、commit() されていなければ自動ロールバック
+        let tx = self.pool.begin().await.map_err(repository_error)?;
+        Ok(PgTodoTransaction { tx })
+    }
+}
+
+/// PostgreSQL のトランザクション
+///
+/// sqlx::Transaction をラップする。Drop 時の自動ロールバックは sqlx に委ねる。
+pub struct PgTodoTransaction {
+    /// sqlx のトランザクション
+    tx: Transaction<'static, Postgres>,
+}
+
+#[async_trait]
+impl TodoTransaction for PgTodoTransaction {
+    async fn insert_todo(&mut self, todo: &Todo) -> Result<Todo, DomainError> {
+        let row: TodoRow = sqlx::query_as(
+            r#"
+            INSERT INTO todos (id, user_id, title, description, completed, created_at, updated_at, tenant_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, user_id, tenant_id, title, description, completed, created_at, updated_at
+            "#,
+        )
+        .bind(todo.id) // $1: UUID
+        .bind(todo.user_id) // $2: 所有者 ID
+        .bind(&todo.title) // $3: タイトル
+        .bind(&todo.description) // $4: 説明
+        .bind(todo.completed) // $5: 完了フラグ
+        .bind(todo.created_at) // $6: 作成日時
+        .bind(todo.updated_at) // $7: 更新日時
+        .bind(todo.tenant_id) // $8: テナント ID
+        .fetch_one(&mut *self.tx) // トランザクション内で実行
+        .await
+        .map_err(repository_error)?;
+
+        Ok(row.into())
+    }
+
+    async fn find_open_todos_by_normalized_titles(
+        &mut self,
+        user_id: Uuid,
+        normalized_titles: &[String],
+    ) -> Result<Vec<Todo>, DomainError> {
+        // 式インデックス idx_todos_user_open_normalized_title と同じ式・条件で検索する
+        let rows: Vec<TodoRow> = sqlx::query_as(
+            r#"
+            SELECT id, user_id, tenant_id, title, description, completed, created_at, updated_at
+            FROM todos
+            WHERE user_id = $1
+              AND completed = false
+              AND lower(regexp_replace(btrim(title), '\s+', ' ', 'g')) = ANY($2)
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id) // $1: 所有者 ID
+        .bind(normalized_titles) // $2: 正規化済みタイトル
+        .fetch_all(&mut *self.tx)
+        .await
+        .map_err(repository_error)?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn insert_file(&mut self, file: &File) -> Result<File, DomainError> {
+        let row: FileRow = sqlx::query_as(
+            r#"
+            INSERT INTO files (id, todo_id, filename, mime_type, size_bytes, storage_path, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, todo_id, filename, mime_type, size_bytes, storage_path, created_at
+            "#,
+        )
+        .bind(file.id)
+        .bind(file.todo_id)
+        .bind(&file.filename)
+        .bind(&file.mime_type)
+        .bind(file.size_bytes)
+        .bind(&file.storage_path)
+        .bind(file.created_at)
+        .fetch_one(&mut *self.tx)
+        .await
+        .map_err(repository_error)?;
+
+        Ok(row.into())
+    }
+
+    async fn update_todos(
+        &mut self,
+        user_id: Uuid,
+        updates: &[TodoUpdateInput],
+    ) -> Result<Vec<Todo>, DomainError> {
+        // 入力を列ごとの配列に展開（UNNEST 用）
+        // description は「変更なし」と「NULL に設定」を区別するため、
+        // 設定フラグ（set_description）と値の 2 列に分ける
+        let ids: Vec<Uuid> = updates.iter().map(|u| u.id).collect();
+        let titles: Vec<Option<String>> = updates.iter().map(|u| u.title.clone()).collect();
+        let set_descriptions: Vec<bool> = updates.iter().map(|u| u.description.is_some()).collect();
+        let descriptions: Vec<Option<String>> = updates
+            .iter()
+            .map(|u| u.description.clone().flatten())
+            .collect();
+        let completed: Vec<Option<bool>> = updates.iter().map(|u| u.completed).collect();
+
+        // 単一 UPDATE ... FROM UNNEST で一括更新
+        // COALESCE: NULL（未指定）なら既存値を維持
+        // CASE: set_description が true の場合のみ description を置換
+        let rows: Vec<TodoRow> = sqlx::query_as(
+            r#"
+            UPDATE todos AS t
+            SET title = COALESCE(v.title, t.title),
+                description = CASE WHEN v.set_description THEN v.description ELSE t.description END,
+                completed = COALESCE(v.completed, t.completed),
+                updated_at = NOW()
+            FROM UNNEST($2::uuid[], $3::text[], $4::bool[], $5::text[], $6::bool[])
+                AS v(id, title, set_description, description, completed)
+            WHERE t.id = v.id AND t.user_id = $1
+            RETURNING t.id, t.user_id, t.tenant_id, t.title, t.description, t.completed, t.created_at, t.updated_at
+            "#,
+        )
+        .bind(user_id) // $1: 所有者 ID（所有者チェック）
+        .bind(&ids) // $2: 更新対象 ID
+        .bind(&titles) // $3: タイトル（NULL = 変更なし）
+        .bind(&set_descriptions) // $4: description 設定フラグ
+        .bind(&descriptions) // $5: 説明
+        .bind(&completed) // $6: 完了フラグ（NULL = 変更なし）
+        .fetch_all(&mut *self.tx)
+        .await
+        .map_err(repository_error)?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn file_storage_keys(
+        &mut self,
+        user_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<Vec<String>, DomainError> {
+        sqlx::query_scalar(
+            r#"
+            SELECT f.storage_path
+            FROM files f
+            JOIN todos t ON t.id = f.todo_id
+            WHERE t.id = ANY($1) AND t.user_id = $2
+            "#,
+        )
+        .bind(ids) // $1: 削除対象 ID
+        .bind(user_id) // $2: 所有者 ID
+        .fetch_all(&mut *self.tx)
+        .await
+        .map_err(repository_error)
+    }
+
+    async fn delete_files(&mut self, user_id: Uuid, ids: &[Uuid]) -> Result<u64, DomainError> {
+        // CASCADE でも削除されるが、件数を返すため明示的に削除する
+        let result = sqlx::query(
+            r#"
+            DELETE FROM files f
+            USING todos t
+            WHERE f.todo_id = t.id AND t.id = ANY($1) AND t.user_id = $2
+            "#,
+        )
+        .bind(ids)
+        .bind(user_id)
+        .execute(&mut *self.tx)
+        .await
+        .map_err(repository_error)?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_todos(
+        &mut self,
+        user_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<Vec<Uuid>, DomainError> {
+        sqlx::query_scalar(
+            r#"
+            DELETE FROM todos
+            WHERE id = ANY($1) AND user_id = $2
+            RETURNING id
+            "#,
+        )
+        .bind(ids)
+        .bind(user_id)
+        .fetch_all(&mut *self.tx)
+        .await
+        .map_err(repository_error)
+    }
+
+    async fn advance_import_job(
+        &mut self,
+        job_id: Uuid,
+        cursor: i32,
+        chunk_len: i32,
+        failed: i32,
+    ) -> Result<bool, DomainError> {
+        // 楽観的ロック: カーソルが一致しなければ 0 行
+        let result = sqlx::query(
+            r#"
+            UPDATE import_jobs
+            SET processed_items = processed_items + $3,
+                failed_items = failed_items + $4,
+                updated_at = NOW()
+            WHERE id = $1 AND state = 'running' AND processed_items = $2
+            "#,
+        )
+        .bind(job_id) // $1: ジョブ ID
+        .bind(cursor) // $2: 期待するカーソル
+        .bind(chunk_len) // $3: 処理した項目数
+        .bind(failed) // $4: 失敗した項目数
+        .execute(&mut *self.tx)
+        .await
+        .map_err(repository_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn record_import_item_errors(
+        &mut self,
+        job_id: Uuid,
+        item_errors: &[(i32, String)],
+    ) -> Result<(), DomainError> {
+        let (positions, errors): (Vec<i32>, Vec<String>) = item_errors.iter().cloned().unzip();
+
+        sqlx::query(
+            r#"
+            UPDATE import_job_items AS i
+            SET error = v.error
+            FROM UNNEST($2::int[], $3::text[]) AS v(position, error)
+            WHERE i.job_id = $1 AND i.position = v.position
+            "#,
+        )
+        .bind(job_id)
+        .bind(&positions)
+        .bind(&errors)
+        .execute(&mut *self.tx)
+        .await
+        .map_err(repository_error)?;
+
+        Ok(())
+    }
+
+    async fn commit(self) -> Result<(), DomainError> {
+        // 重要: commit() を呼ばないと、tx が Drop されるときに自動ロールバックされる
+        self.tx.commit().await.map_err(repository_error)
+    }
+
+    async fn rollback(self) -> Result<(), DomainError> {
+        self.tx.rollback().await.map_err(repository_error)
+    }
+}
+
+/// sqlx::Error を DomainError::Repository に変換する
+fn repository_error(e: sqlx::Error) -> DomainError {
+    DomainError::Repository(e.to_string())
+}
+
+// =============================================================================
+// TodoRow 構造体（内部用）
+// =============================================================================
+
+/// データベース行からのマッピング用構造体（TODO）
+///
+/// RETURNING 句の結果を受け取るために使用。
+#[derive(FromRow)]
+struct TodoRow {
+    /// TODO の一意識別子
+    id: Uuid,
+    /// 所有者のユーザー ID
+    user_id: Uuid,
+    /// 作成時のテナント ID
+    tenant_id: Option<Uuid>,
+    /// タイトル
+    title: String,
+    /// 詳細説明
+    description: Option<String>,
+    /// 完了フラグ
+    completed: bool,
+    /// 作成日時
+    created_at: DateTime<Utc>,
+    /// 更新日時
+    updated_at: DateTime<Utc>,
+}
+
+/// TodoRow から domain::Todo への変換
+impl From<TodoRow> for Todo {
+    fn from(row: TodoRow) -> Self {
+        Todo::from_raw(
+            row.id,
+            row.user_id,
+            row.title,
+            row.description,
+            row.completed,
+            row.created_at,
+            row.updated_at,
+        )
+        .with_tenant(row.tenant_id)
+    }
+}
+
+// =============================================================================
+// FileRow 構造体（内部用）
+// =============================================================================
+
+/// データベース行からのマッピング用構造体（ファイル）
+///
+/// RETURNING 句の結果を受け取るために使用。
+#[derive(FromRow)]
+struct FileRow {
+    /// ファイルの一意識別子
+    id: Uuid,
+    /// 紐付く TODO の ID
+    todo_id: Uuid,
+    /// ファイル名
+    filename: String,
+    /// MIME タイプ
+    mime_type: String,
+    /// ファイルサイズ（バイト）
+    size_bytes: i64,
+    /// ストレージ上のパス
+    storage_path: String,
+    /// 作成日時
+    created_at: DateTime<Utc>,
+}
+
+/// FileRow から domain::File への変換
+impl From<FileRow> for File {
+    fn from(row: FileRow) -> Self {
+        File::from_raw(
+            row.id,
+            row.todo_id,
+            row.filename,
+            row.mime_type,
+            row.size_bytes,
+            row.storage_path,
+            row.created_at,
+        )
+    }
+}
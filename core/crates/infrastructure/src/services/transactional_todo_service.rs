@@ -6,7 +6,11 @@
 // 複数テーブルを跨ぐアトミック操作を実現する。
 //
 // 設計判断:
-// - PgPool を直接使用してトランザクションを管理
+// - トランザクションは TransactionProvider から取得する
+//   （本番は PgTransactionProvider、テストでは記録用の実装に差し替え）
+// - SQL は TodoTransaction の実装に閉じ込め、このサービスは
+//   操作の順序、コミット/ロールバックの判断、結果の組み立てのみを担当する
+// - 結果の組み立ては sqlx に依存しない関数に分離する
 // - Domain 層のトレイトを sqlx に依存させない
 // - トランザクションが必要な操作のみこのサービスで提供
 //
@@ -20,23 +24,29 @@
 // 外部クレートのインポート
 // -----------------------------------------------------------------------------
 
-// chrono: 日付・時刻ライブラリ
-use chrono::{DateTime, Utc};
+// std: 標準ライブラリ
+// HashMap: バッチ更新結果を ID で引き当てる、重複検出で正規化タイトルを引き当てる
+// Future: アップロード後に実行する DB 処理の型
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::future::Future;
 
 // domain: ドメイン層の型をインポート
-use domain::{DomainError, File, Todo};
+// StorageOps: アップロードと補償削除、バッチ削除後のオブジェクト削除に使用
+use domain::{DomainError, File, StorageOps, Todo};
 
-// sqlx: PostgreSQL クライアントライブラリ
-// FromRow: クエリ結果から構造体への自動マッピング
-// PgPool: PostgreSQL 接続プール
-use sqlx::{FromRow, PgPool};
+// sqlx: PostgreSQL 接続プール（PgTransactionProvider の作成に使用）
+use sqlx::PgPool;
 
 // tracing: 構造化ログライブラリ
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 // uuid: 一意識別子ライブラリ
 use uuid::Uuid;
 
+// 同じモジュール内のトランザクション抽象
+use super::todo_transaction::{PgTransactionProvider, TodoTransaction, TransactionProvider};
+
 // =============================================================================
 // TransactionalTodoService 構造体
 // =============================================================================
@@ -57,14 +67,18 @@ use uuid::Uuid;
 /// - 単一 TODO の CRUD 操作（TodoWriter で十分）
 /// - 読み取り専用操作（TodoReader を使用）
 ///
+/// # 型パラメータ
+///
+/// - `P`: トランザクションプロバイダ（デフォルト: PostgreSQL）
+///
 /// # derive マクロ
 ///
 /// - `Clone`: サービスを複数箇所で共有可能に
 ///   PgPool は内部で Arc を使用しているため、Clone は安価
 #[derive(Clone)]
-pub struct TransactionalTodoService {
-    /// PostgreSQL 接続プール（Writer 用推奨）
-    pool: PgPool,
+pub struct TransactionalTodoService<P: TransactionProvider = PgTransactionProvider> {
+    /// トランザクションプロバイダ
+    provider: P,
 }
 
 impl TransactionalTodoService {
@@ -79,87 +93,18 @@ impl TransactionalTodoService {
     /// トランザクションを使用するため、Writer プールを指定すること。
     /// Reader プールでは書き込み操作が失敗する可能性がある。
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
-    }
-}
-
-// =============================================================================
-// TodoRow 構造体（内部用）
-// =============================================================================
-
-/// データベース行からのマッピング用構造体（TODO）
-///
-/// RETURNING 句の結果を受け取るために使用。
-#[derive(FromRow)]
-struct TodoRow {
-    /// TODO の一意識別子
-    id: Uuid,
-    /// 所有者のユーザー ID
-    user_id: Uuid,
-    /// タイトル
-    title: String,
-    /// 詳細説明
-    description: Option<String>,
-    /// 完了フラグ
-    completed: bool,
-    /// 作成日時
-    created_at: DateTime<Utc>,
-    /// 更新日時
-    updated_at: DateTime<Utc>,
-}
-
-/// TodoRow から domain::Todo への変換
-impl From<TodoRow> for Todo {
-    fn from(row: TodoRow) -> Self {
-        Todo::from_raw(
-            row.id,
-            row.user_id,
-            row.title,
-            row.description,
-            row.completed,
-            row.created_at,
-            row.updated_at,
-        )
+        Self::with_provider(PgTransactionProvider::new(pool))
     }
 }
 
-// =============================================================================
-// FileRow 構造体（内部用）
-// =============================================================================
-
-/// データベース行からのマッピング用構造体（ファイル）
-///
-/// RETURNING 句の結果を受け取るために使用。
-#[derive(FromRow)]
-struct FileRow {
-    /// ファイルの一意識別子
-    id: Uuid,
-    /// 紐付く TODO の ID
-    todo_id: Uuid,
-    /// ファイル名
-    filename: String,
-    /// MIME タイプ
-    mime_type: String,
-    /// ファイルサイズ（バイト）
-    size_bytes: i64,
-    /// ストレージ上のパス
-    storage_path: String,
-    /// 作成日時
-    created_at: DateTime<Utc>,
-}
-
-/// FileRow から domain::File への変換
-impl From<FileRow> for File {
-    fn from(row: FileRow) -> Self {
-        File::from_raw(
-            row.id,
-            row.todo_id,
-            row.filename,
-            row.mime_type,
-            row.size_bytes,
-            row.storage_path,
-            row.created_at,
-        )
+impl<P: TransactionProvider> TransactionalTodoService<P> {
+    /// 任意のトランザクションプロバイダで TransactionalTodoService を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - トランザクションプロバイダ（テストでは記録用の実装など）
+    pub fn with_provider(provider: P) -> Self {
+        Self { provider }
     }
 }
 
@@ -187,11 +132,148 @@ pub struct FileInput {
     pub storage_path: String,
 }
 
+// =============================================================================
+// FileUploadInput 構造体
+// =============================================================================
+
+/// アップロード付きファイル作成用の入力データ
+///
+/// ファイル本体を含む。ストレージへのアップロードは
+/// `TransactionalTodoService::create_with_uploads` が行う。
+///
+/// # derive マクロ
+///
+/// - `Debug`: デバッグ出力用
+/// - `Clone`: 複製可能に
+#[derive(Debug, Clone)]
+pub struct FileUploadInput {
+    /// ファイル名（バリデーション済み）
+    pub filename: String,
+    /// MIME タイプ（バリデーション済み）
+    pub mime_type: String,
+    /// ファイルの内容（バイト列）
+    pub data: Vec<u8>,
+}
+
+// =============================================================================
+// TodoCreateInput 構造体
+// =============================================================================
+
+/// バッチ作成用の入力データ（1 件分）
+///
+/// タイトルはバリデーション済みであること。
+/// CSV インポートのように完了状態を指定して作成する場合に使用する。
+///
+/// # derive マクロ
+///
+/// - `Debug`: デバッグ出力用
+/// - `Clone`: 複製可能に
+#[derive(Debug, Clone)]
+pub struct TodoCreateInput {
+    /// タイトル（バリデーション済み）
+    pub title: String,
+    /// 説明
+    pub description: Option<String>,
+    /// 完了状態
+    pub completed: bool,
+    /// 作成時のテナント ID（TENANT_SCOPING=true の場合のみ設定）
+    pub tenant_id: Option<Uuid>,
+}
+
+// =============================================================================
+// CreateOutcome 列挙型
+// =============================================================================
+
+/// 重複検出付きバッチ作成の結果（1 件分）
+///
+/// # derive マクロ
+///
+/// - `Debug`: デバッグ出力用
+#[derive(Debug)]
+pub enum CreateOutcome {
+    /// 作成された
+    Created(Todo),
+    /// 重複のため作成されなかった
+    Duplicate {
+        /// 重複先の TODO ID
+        /// （既存の未完了 TODO、または同じリクエスト内で先に作成された TODO）
+        existing_id: Uuid,
+    },
+}
+
+// =============================================================================
+// TodoUpdateInput 構造体
+// =============================================================================
+
+/// バッチ更新用の入力データ（1 件分）
+///
+/// PATCH セマンティクス: None のフィールドは既存値を維持する。
+/// タイトルはバリデーション済みであること。
+///
+/// # derive マクロ
+///
+/// - `Debug`: デバッグ出力用
+/// - `Clone`: 複製可能に
+#[derive(Debug, Clone)]
+pub struct TodoUpdateInput {
+    /// 更新対象の TODO ID
+    pub id: Uuid,
+    /// 新しいタイトル（None なら変更なし、バリデーション済み）
+    pub title: Option<String>,
+    /// 新しい説明
+    /// * `Some(Some("説明"))`: 説明を設定
+    /// * `Some(None)`: 説明を削除（NULL に設定）
+    /// * `None`: 変更なし
+    pub description: Option<Option<String>>,
+    /// 新しい完了状態（None なら変更なし）
+    pub completed: Option<bool>,
+}
+
+// =============================================================================
+// BatchUpdateOutcome 構造体
+// =============================================================================
+
+/// バッチ更新の結果
+///
+/// # derive マクロ
+///
+/// - `Debug`: デバッグ出力用
+#[derive(Debug)]
+pub struct BatchUpdateOutcome {
+    /// 更新された TODO（committed = false の場合はロールバック済み）
+    pub updated: Vec<Todo>,
+    /// 存在しない、または他ユーザー所有の TODO ID
+    pub not_found: Vec<Uuid>,
+    /// トランザクションがコミットされたか
+    pub committed: bool,
+}
+
+// =============================================================================
+// BatchDeleteOutcome 構造体
+// =============================================================================
+
+/// バッチ削除の結果
+///
+/// # derive マクロ
+///
+/// - `Debug`: デバッグ出力用
+#[derive(Debug, Default)]
+pub struct BatchDeleteOutcome {
+    /// 削除された TODO の件数（todos テーブル）
+    pub todos_deleted: u64,
+    /// 削除されたファイルメタデータの件数（files テーブル）
+    pub files_deleted: u64,
+    /// 存在しない、または他ユーザー所有の TODO ID
+    pub not_found: Vec<Uuid>,
+    /// コミット後の削除に失敗したストレージキー（クリーンアップジョブで再試行）
+    pub failed_storage_keys: Vec<String>,
+}
+
 // =============================================================================
 // TransactionalTodoService メソッド実装
 // =============================================================================
 
-impl TransactionalTodoService {
+impl<P: TransactionProvider> TransactionalTodoService<P> {
     /// 複数 TODO を1トランザクションで作成
     ///
     /// バリデーション済みの TODO リストを受け取り、
@@ -224,55 +306,58 @@ impl TransactionalTodoService {
         &self,
         user_id: Uuid,
         todos: Vec<(String, Option<String>)>, // (title, description) のタプル
+    ) -> Result<Vec<Todo>, DomainError> {
+        let inputs = todos
+            .into_iter()
+            .map(|(title, description)| TodoCreateInput {
+                title,
+                description,
+                completed: false,
+                tenant_id: None,
+            })
+            .collect();
+        self.batch_create_inputs(user_id, inputs).await
+    }
+
+    /// 複数 TODO を完了状態を含めて1トランザクションで作成
+    ///
+    /// `batch_create` と同じく、全て成功するか全てロールバックされる。
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - 所有者のユーザー ID
+    /// * `todos` - 作成する TODO の入力データ（タイトルはバリデーション済み）
+    ///
+    /// # Returns
+    ///
+    /// 作成された TODO のリスト（入力順）
+    ///
+    /// # Errors
+    ///
+    /// いずれかの INSERT が失敗した場合、全てロールバックされる。
+    pub async fn batch_create_inputs(
+        &self,
+        user_id: Uuid,
+        todos: Vec<TodoCreateInput>,
     ) -> Result<Vec<Todo>, DomainError> {
         // 構造化ログ: ユーザー ID と件数を記録
         debug!(user_id = %user_id, count = todos.len(), "Starting batch create transaction");
 
         // トランザクション開始
-        // begin(): トランザクションを開始し、Transaction<Postgres> を返す
         // RAII: tx が Drop されるとき、commit() されていなければ自動ロールバック
-        let mut tx = self
-            .pool
-            .begin() // BEGIN 文を実行
-            .await // 非同期実行
-            .map_err(|e| DomainError::Repository(e.to_string()))?; // エラー変換
+        let mut tx = self.provider.begin().await?;
 
         // 結果を格納する Vec を事前確保（パフォーマンス最適化）
         let mut created = Vec::with_capacity(todos.len());
 
         // 各 TODO を順番に作成
-        for (title, description) in todos {
-            // 新しい Todo エンティティを作成
-            let todo = Todo::new(user_id, title, description);
-
-            // INSERT 文を実行（トランザクション内）
-            let row: TodoRow = sqlx::query_as(
-                r#"
-                INSERT INTO todos (id, user_id, title, description, completed, created_at, updated_at)
-                VALUES ($1, $2, $3, $4, $5, $6, $7)
-                RETURNING id, user_id, title, description, completed, created_at, updated_at
-                "#,
-            )
-            .bind(todo.id) // $1: UUID
-            .bind(todo.user_id) // $2: 所有者 ID
-            .bind(&todo.title) // $3: タイトル
-            .bind(&todo.description) // $4: 説明
-            .bind(todo.completed) // $5: 完了フラグ
-            .bind(todo.created_at) // $6: 作成日時
-            .bind(todo.updated_at) // $7: 更新日時
-            .fetch_one(&mut *tx) // トランザクション内で実行（&mut *tx でデリファレンス）
-            .await // 非同期実行
-            .map_err(|e| DomainError::Repository(e.to_string()))?; // エラー変換
-
-            // 作成結果を追加
-            created.push(row.into()); // TodoRow → Todo に変換
+        // エラー時は ? で早期リターンし、tx の Drop でロールバックされる
+        for input in todos {
+            created.push(tx.insert_todo(&new_todo(user_id, input)).await?);
         }
 
         // コミット
-        // 重要: commit() を呼ばないと、tx が Drop されるときに自動ロールバックされる
-        tx.commit() // COMMIT 文を実行
-            .await // 非同期実行
-            .map_err(|e| DomainError::Repository(e.to_string()))?; // エラー変換
+        tx.commit().await?;
 
         // 成功ログ
         info!(user_id = %user_id, count = created.len(), "Batch create transaction committed");
@@ -280,6 +365,100 @@ impl TransactionalTodoService {
         Ok(created)
     }
 
+    /// 重複を除外して複数 TODO を1トランザクションで作成
+    ///
+    /// 正規化したタイトル（`Todo::normalize_title`）が同じ項目を重複とみなし、
+    /// 最初の 1 件だけを作成する。`against_existing` が true の場合は、
+    /// ユーザーの未完了 TODO と重複する項目も作成しない
+    /// （式インデックスを使う 1 回のクエリで検索する）。
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - 所有者のユーザー ID
+    /// * `todos` - 作成する TODO の入力データ（タイトルはバリデーション済み）
+    /// * `against_existing` - 既存の未完了 TODO との重複も検出するか
+    ///
+    /// # Returns
+    ///
+    /// 項目ごとの結果（入力順）
+    ///
+    /// # Errors
+    ///
+    /// 検索またはいずれかの INSERT が失敗した場合、全てロールバックされる。
+    ///
+    /// # Note
+    ///
+    /// 一意制約ではないため、同時に実行された別リクエストとの重複は検出できない。
+    pub async fn batch_create_dedupe(
+        &self,
+        user_id: Uuid,
+        todos: Vec<TodoCreateInput>,
+        against_existing: bool,
+    ) -> Result<Vec<CreateOutcome>, DomainError> {
+        debug!(
+            user_id = %user_id,
+            count = todos.len(),
+            against_existing,
+            "Starting deduplicated batch create transaction"
+        );
+
+        let keys: Vec<String> = todos
+            .iter()
+            .map(|t| Todo::normalize_title(&t.title))
+            .collect();
+
+        let mut tx = self.provider.begin().await?;
+
+        // 1. 既存の未完了 TODO を検索（重複のないキーで 1 回だけ）
+        let existing = if against_existing {
+            let mut unique = keys.clone();
+            unique.sort();
+            unique.dedup();
+            tx.find_open_todos_by_normalized_titles(user_id, &unique)
+                .await?
+        } else {
+            Vec::new()
+        };
+
+        // 2. 項目ごとに作成するか判定し、作成する項目だけ INSERT
+        let plan = plan_dedupe(&keys, &existing);
+        let mut outcomes: Vec<CreateOutcome> = Vec::with_capacity(todos.len());
+        for (input, action) in todos.into_iter().zip(plan) {
+            let outcome = match action {
+                DedupeAction::Create => {
+                    CreateOutcome::Created(tx.insert_todo(&new_todo(user_id, input)).await?)
+                }
+                DedupeAction::Existing(existing_id) => CreateOutcome::Duplicate { existing_id },
+                // 先行する項目は必ず Create（plan_dedupe の規約）
+                DedupeAction::SameAs(index) => match &outcomes[index] {
+                    CreateOutcome::Created(todo) => CreateOutcome::Duplicate {
+                        existing_id: todo.id,
+                    },
+                    CreateOutcome::Duplicate { existing_id } => CreateOutcome::Duplicate {
+                        existing_id: *existing_id,
+                    },
+                },
+            };
+            outcomes.push(outcome);
+        }
+
+        // 3. コミット
+        tx.commit().await?;
+
+        let created = outcomes
+            .iter()
+            .filter(|o| matches!(o, CreateOutcome::Created(_)))
+            .count();
+        info!(
+            user_id = %user_id,
+            count = created,
+            duplicates = outcomes.len() - created,
+            "Deduplicated batch create transaction committed"
+        );
+
+        Ok(outcomes)
+    }
+
     /// TODO + ファイルを1トランザクションで作成
     ///
     /// TODO とその添付ファイルを同時に作成する。
@@ -301,6 +480,7 @@ impl TransactionalTodoService {
     /// ファイル本体は事前にストレージにアップロード済みであること。
     /// トランザクション失敗時、ストレージのファイルは手動で削除が必要
     /// （補償トランザクション）。
+    /// アップロードから補償まで任せる場合は `create_with_uploads` を使用する。
     pub async fn create_with_files(
         &self,
         user_id: Uuid,
@@ -312,41 +492,16 @@ impl TransactionalTodoService {
         debug!(user_id = %user_id, file_count = files.len(), "Starting create with files transaction");
 
         // トランザクション開始
-        let mut tx = self
-            .pool
-            .begin()
-            .await
-            .map_err(|e| DomainError::Repository(e.to_string()))?;
+        let mut tx = self.provider.begin().await?;
 
         // 1. TODO 作成
-        let todo = Todo::new(user_id, title, description);
-
-        let todo_row: TodoRow = sqlx::query_as(
-            r#"
-            INSERT INTO todos (id, user_id, title, description, completed, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
-            RETURNING id, user_id, title, description, completed, created_at, updated_at
-            "#,
-        )
-        .bind(todo.id)
-        .bind(todo.user_id)
-        .bind(&todo.title)
-        .bind(&todo.description)
-        .bind(todo.completed)
-        .bind(todo.created_at)
-        .bind(todo.updated_at)
-        .fetch_one(&mut *tx) // トランザクション内で実行
-        .await
-        .map_err(|e| DomainError::Repository(e.to_string()))?;
+        let created_todo = tx
+            .insert_todo(&Todo::new(user_id, title, description))
+            .await?;
 
-        // TodoRow → Todo に変換
-        let created_todo: Todo = todo_row.into();
-
-        // 2. ファイル作成
+        // 2. ファイル作成（todo_id は作成した TODO の ID）
         let mut created_files = Vec::with_capacity(files.len());
-
         for f in files {
-            // File エンティティを作成（todo_id は作成した TODO の ID）
             let file = File::new(
                 created_todo.id, // 親 TODO の ID
                 f.filename,      // ファイル名
@@ -354,33 +509,11 @@ impl TransactionalTodoService {
                 f.size_bytes,    // サイズ
                 f.storage_path,  // ストレージパス
             );
-
-            // ファイルメタデータを INSERT
-            let file_row: FileRow = sqlx::query_as(
-                r#"
-                INSERT INTO files (id, todo_id, filename, mime_type, size_bytes, storage_path, created_at)
-                VALUES ($1, $2, $3, $4, $5, $6, $7)
-                RETURNING id, todo_id, filename, mime_type, size_bytes, storage_path, created_at
-                "#,
-            )
-            .bind(file.id)
-            .bind(file.todo_id)
-            .bind(&file.filename)
-            .bind(&file.mime_type)
-            .bind(file.size_bytes)
-            .bind(&file.storage_path)
-            .bind(file.created_at)
-            .fetch_one(&mut *tx) // トランザクション内で実行
-            .await
-            .map_err(|e| DomainError::Repository(e.to_string()))?;
-
-            created_files.push(file_row.into()); // FileRow → File に変換
+            created_files.push(tx.insert_file(&file).await?);
         }
 
         // 3. コミット
-        tx.commit()
-            .await
-            .map_err(|e| DomainError::Repository(e.to_string()))?;
+        tx.commit().await?;
 
         // 成功ログ
         info!(
@@ -392,4 +525,1222 @@ impl TransactionalTodoService {
         // タプルで返す: (TODO, ファイルリスト)
         Ok((created_todo, created_files))
     }
+
+    /// ファイルをアップロードし、TODO + ファイルを1トランザクションで作成
+    ///
+    /// ストレージと DB を跨ぐ操作を小さな Saga として実行する。
+    /// 途中で失敗した場合、アップロード済みのオブジェクトを削除して補償する。
+    ///
+    /// # 処理フロー
+    ///
+    /// 1. ファイルを順番にアップロードし、ストレージキーを記録
+    /// 2. `create_with_files` で TODO + ファイルメタデータを作成（トランザクション）
+    /// 3. 1 または 2 が失敗した場合、記録済みのキーを削除（ベストエフォート）
+    ///
+    /// # Note
+    ///
+    /// ライブラリ API。POST /api/todos/with-files はアップロード済みのメタデータを
+    /// 受け取る契約のため、`create_with_files` を使用する（ルートからは呼ばれない）。
+    ///
+    /// # Arguments
+    ///
+    /// * `storage` - ストレージ操作（アップロードと補償削除に使用）
+    /// * `user_id` - 所有者のユーザー ID
+    /// * `title` - TODO タイトル（バリデーション済み）
+    /// * `description` - TODO 説明
+    /// * `uploads` - ファイル本体を含む入力データのリスト
+    ///
+    /// # Returns
+    ///
+    /// (作成された TODO, 作成されたファイルのリスト)
+    ///
+    /// # Errors
+    ///
+    /// アップロードまたは DB 操作のエラーをそのまま返す。
+    /// 補償削除の失敗はエラーにせず、警告ログに残す（クリーンアップジョブで再試行）。
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let uploads = vec![FileUploadInput {
+    ///     filename: "a.png".to_string(),
+    ///     mime_type: "image/png".to_string(),
+    ///     data: bytes,
+    /// }];
+    /// let (todo, files) = service
+    ///     .create_with_uploads(storage.as_ref(), user_id, title, None, uploads)
+    ///     .await?;
+    /// ```
+    pub async fn create_with_uploads<S: StorageOps + ?Sized>(
+        &self,
+        storage: &S,
+        user_id: Uuid,
+        title: String,
+        description: Option<String>,
+        uploads: Vec<FileUploadInput>,
+    ) -> Result<(Todo, Vec<File>), DomainError> {
+        upload_with_compensation(storage, user_id, uploads, |files| {
+            self.create_with_files(user_id, title, description, files)
+        })
+        .await
+    }
+
+    /// インポートジョブの1チャンクを1トランザクションで処理
+    ///
+    /// TODO の作成、失敗した項目のエラー記録、ジョブの進捗更新を
+    /// 同一トランザクションで行う。ワーカーが途中で停止しても、
+    /// 進捗（カーソル）と作成済みの TODO が食い違わない。
+    ///
+    /// # 処理フロー
+    ///
+    /// 1. 進捗を更新（running かつカーソルが一致する場合のみ、行ロックを取得）
+    /// 2. 有効な項目の TODO を作成
+    /// 3. 無効な項目のエラーを import_job_items に記録
+    /// 4. コミット
+    ///
+    /// # Arguments
+    ///
+    /// * `job_id` - ジョブ ID
+    /// * `user_id` - ジョブ所有者のユーザー ID（作成する TODO の所有者）
+    /// * `cursor` - チャンク開始時点の processed_items
+    /// * `chunk_len` - チャンクの項目数（有効 + 無効）
+    /// * `todos` - 有効な項目の (title, description) のリスト（バリデーション済み）
+    /// * `item_errors` - 無効な項目の (位置, エラーメッセージ) のリスト
+    ///
+    /// # Returns
+    ///
+    /// * `true` - チャンクを処理してコミットした
+    /// * `false` - ジョブがキャンセルされた、または別のワーカーが処理済み（何もしない）
+    ///
+    /// # Errors
+    ///
+    /// DB 操作が失敗した場合、全てロールバックされる。
+    pub async fn import_chunk(
+        &self,
+        job_id: Uuid,
+        user_id: Uuid,
+        cursor: i32,
+        chunk_len: i32,
+        todos: Vec<(String, Option<String>)>,
+        item_errors: &[(i32, String)],
+    ) -> Result<bool, DomainError> {
+        let mut tx = self.provider.begin().await?;
+
+        // 1. 進捗を更新（楽観的ロック: カーソルが一致しなければ更新されない）
+        let advanced = tx
+            .advance_import_job(job_id, cursor, chunk_len, item_errors.len() as i32)
+            .await?;
+
+        if !advanced {
+            // キャンセル済み、またはリース切れで別のワーカーが処理した
+            // tx は Drop で自動ロールバック
+            debug!(job_id = %job_id, cursor, "Import chunk skipped (job no longer owned)");
+            return Ok(false);
+        }
+
+        // 2. TODO を作成
+        for (title, description) in todos {
+            tx.insert_todo(&Todo::new(user_id, title, description))
+                .await?;
+        }
+
+        // 3. 無効な項目のエラーを記録
+        if !item_errors.is_empty() {
+            tx.record_import_item_errors(job_id, item_errors).await?;
+        }
+
+        // 4. コミット
+        tx.commit().await?;
+
+        debug!(
+            job_id = %job_id,
+            cursor,
+            chunk_len,
+            failed = item_errors.len(),
+            "Import chunk committed"
+        );
+
+        Ok(true)
+    }
+
+    /// 複数 TODO を1トランザクションで更新
+    ///
+    /// UNNEST で入力を仮想テーブルに展開し、単一の UPDATE ... FROM で一括更新する。
+    /// 所有者チェックは WHERE 句（user_id = $1）で SQL 側に強制する。
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - 所有者のユーザー ID
+    /// * `updates` - 更新内容のリスト（ID の重複がないこと）
+    /// * `partial` - 部分適用モード
+    ///   * `true`: 見つからない ID があってもコミット
+    ///   * `false`: 1 件でも見つからなければ全てロールバック
+    ///
+    /// # Returns
+    ///
+    /// 更新結果（更新された TODO、見つからなかった ID、コミット有無）
+    ///
+    /// # Errors
+    ///
+    /// UPDATE が失敗した場合、全てロールバックされる。
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let updates = vec![TodoUpdateInput {
+    ///     id: todo_id,
+    ///     title: None,
+    ///     description: None,
+    ///     completed: Some(true),
+    /// }];
+    /// let outcome = service.batch_update(user_id, updates, false).await?;
+    /// assert!(outcome.committed);
+    /// ```
+    pub async fn batch_update(
+        &self,
+        user_id: Uuid,
+        updates: Vec<TodoUpdateInput>,
+        partial: bool,
+    ) -> Result<BatchUpdateOutcome, DomainError> {
+        // 構造化ログ
+        debug!(user_id = %user_id, count = updates.len(), partial, "Starting batch update transaction");
+
+        // トランザクション開始
+        let mut tx = self.provider.begin().await?;
+
+        // 単一 UPDATE で一括更新（RETURNING の順序は不定）
+        let rows = tx.update_todos(user_id, &updates).await?;
+
+        // 入力順に並べ替え、見つからなかった ID を抽出
+        let ids: Vec<Uuid> = updates.iter().map(|u| u.id).collect();
+        let outcome = assemble_update_outcome(&ids, rows, partial);
+
+        // 全件モードで見つからない ID がある場合はロールバック
+        if !outcome.committed {
+            tx.rollback().await?;
+
+            info!(
+                user_id = %user_id,
+                not_found = outcome.not_found.len(),
+                "Batch update transaction rolled back"
+            );
+
+            return Ok(outcome);
+        }
+
+        // コミット
+        tx.commit().await?;
+
+        // 成功ログ
+        info!(
+            user_id = %user_id,
+            count = outcome.updated.len(),
+            not_found = outcome.not_found.len(),
+            "Batch update transaction committed"
+        );
+
+        Ok(outcome)
+    }
+
+    /// 複数 TODO と関連ファイルを1トランザクションで削除
+    ///
+    /// DB の削除をコミットした後にストレージのオブジェクトを削除する。
+    /// 逆順（ストレージ → DB）だと、DB のロールバック時に
+    /// メタデータだけが残り、実体のないファイルを参照してしまうため。
+    ///
+    /// # 処理フロー
+    ///
+    /// 1. 対象 TODO に紐づくファイルのストレージキーを取得
+    /// 2. files テーブルの行を削除
+    /// 3. todos テーブルの行を削除（所有者チェック付き）
+    /// 4. コミット
+    /// 5. ストレージのオブジェクトを削除（失敗したキーは結果に含める）
+    ///
+    /// # Arguments
+    ///
+    /// * `storage` - ストレージ操作（コミット後のオブジェクト削除に使用）
+    /// * `user_id` - 所有者のユーザー ID
+    /// * `ids` - 削除対象の TODO ID
+    ///
+    /// # Returns
+    ///
+    /// テーブルごとの削除件数、見つからなかった ID、削除に失敗したストレージキー
+    ///
+    /// # Errors
+    ///
+    /// DB 操作が失敗した場合、全てロールバックされる。
+    /// ストレージ削除の失敗はエラーにせず、`failed_storage_keys` で返す。
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let outcome = service.batch_delete(storage.as_ref(), user_id, &ids).await?;
+    /// if !outcome.failed_storage_keys.is_empty() {
+    ///     // クリーンアップジョブに登録して再試行
+    /// }
+    /// ```
+    pub async fn batch_delete<S: StorageOps + ?Sized>(
+        &self,
+        storage: &S,
+        user_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<BatchDeleteOutcome, DomainError> {
+        // 構造化ログ
+        debug!(user_id = %user_id, count = ids.len(), "Starting batch delete transaction");
+
+        // トランザクション開始
+        let mut tx = self.provider.begin().await?;
+
+        // 1. 削除対象ファイルのストレージキーを取得（所有者チェック付き）
+        let storage_keys = tx.file_storage_keys(user_id, ids).await?;
+
+        // 2. ファイルメタデータを削除
+        let files_deleted = tx.delete_files(user_id, ids).await?;
+
+        // 3. TODO を削除（所有者チェック付き）
+        let deleted_ids = tx.delete_todos(user_id, ids).await?;
+
+        // 4. コミット
+        tx.commit().await?;
+
+        info!(
+            user_id = %user_id,
+            todos_deleted = deleted_ids.len(),
+            files_deleted,
+            "Batch delete transaction committed"
+        );
+
+        // 5. コミット後にストレージのオブジェクトを削除
+        let failed_storage_keys = delete_storage_objects(storage, &storage_keys).await;
+
+        Ok(BatchDeleteOutcome {
+            todos_deleted: deleted_ids.len() as u64,
+            files_deleted,
+            not_found: missing_ids(ids, &deleted_ids),
+            failed_storage_keys,
+        })
+    }
+}
+
+// =============================================================================
+// ヘルパー関数
+// =============================================================================
+
+/// 作成入力から新しい Todo エンティティを作成する（完了状態・テナントは入力に従う）
+fn new_todo(user_id: Uuid, input: TodoCreateInput) -> Todo {
+    let mut todo = Todo::new(user_id, input.title, input.description).with_tenant(input.tenant_id);
+    todo.completed = input.completed;
+    todo
+}
+
+/// 重複検出付きバッチ作成での 1 件分の処理
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DedupeAction {
+    /// 作成する
+    Create,
+    /// 既存の未完了 TODO と重複（その ID）
+    Existing(Uuid),
+    /// 同じリクエスト内の先行する項目と重複（その位置）
+    SameAs(usize),
+}
+
+/// 正規化済みタイトルから、項目ごとの処理を決める
+///
+/// 既存 TODO との重複を優先し、次にリクエスト内で最初に現れた項目との重複を判定する。
+/// `SameAs` が指す項目は常に `Create` になる。
+/// 同じタイトルの既存 TODO が複数ある場合は `existing` の先頭（作成日時が最も古いもの）を使う。
+fn plan_dedupe(keys: &[String], existing: &[Todo]) -> Vec<DedupeAction> {
+    let mut existing_ids: HashMap<String, Uuid> = HashMap::new();
+    for todo in existing {
+        existing_ids
+            .entry(Todo::normalize_title(&todo.title))
+            .or_insert(todo.id);
+    }
+
+    let mut first_seen: HashMap<&str, usize> = HashMap::new();
+    keys.iter()
+        .enumerate()
+        .map(|(index, key)| {
+            if let Some(id) = existing_ids.get(key) {
+                return DedupeAction::Existing(*id);
+            }
+            match first_seen.entry(key.as_str()) {
+                Entry::Occupied(e) => DedupeAction::SameAs(*e.get()),
+                Entry::Vacant(e) => {
+                    e.insert(index);
+                    DedupeAction::Create
+                }
+            }
+        })
+        .collect()
+}
+
+/// バッチ更新の結果を組み立てる
+///
+/// RETURNING の順序は保証されないため、ID をキーにした HashMap を経由して
+/// 入力順に並べ替え、見つからなかった ID（存在しない or 他ユーザー所有）を抽出する。
+/// 全件モード（`partial = false`）で見つからない ID がある場合は `committed = false`。
+fn assemble_update_outcome(ids: &[Uuid], rows: Vec<Todo>, partial: bool) -> BatchUpdateOutcome {
+    let mut by_id: HashMap<Uuid, Todo> = rows.into_iter().map(|t| (t.id, t)).collect();
+    let mut updated: Vec<Todo> = Vec::with_capacity(by_id.len());
+    let mut not_found = Vec::new();
+    for id in ids {
+        match by_id.remove(id) {
+            Some(todo) => updated.push(todo),
+            None => not_found.push(*id),
+        }
+    }
+
+    let committed = partial || not_found.is_empty();
+    BatchUpdateOutcome {
+        updated,
+        not_found,
+        committed,
+    }
+}
+
+/// 要求された ID のうち、削除されなかったものを返す
+///
+/// 他ユーザー所有の ID も「存在しない」として扱う（存在の有無を漏らさない）。
+/// 重複した ID は 1 回だけ返す。
+fn missing_ids(requested: &[Uuid], deleted: &[Uuid]) -> Vec<Uuid> {
+    let mut missing: Vec<Uuid> = Vec::new();
+    for id in requested {
+        if !deleted.contains(id) && !missing.contains(id) {
+            missing.push(*id);
+        }
+    }
+    missing
+}
+
+/// ストレージのオブジェクトを削除し、失敗したキーを返す
+///
+/// 1 件の失敗で中断せず、全キーの削除を試みる。
+async fn delete_storage_objects<S: StorageOps + ?Sized>(
+    storage: &S,
+    keys: &[String],
+) -> Vec<String> {
+    let mut failed = Vec::new();
+    for key in keys {
+        if let Err(e) = storage.delete(key).await {
+            // DB はコミット済みのため警告のみ（クリーンアップジョブで再試行）
+            warn!(storage_path = %key, error = %e, "Failed to delete storage object after commit");
+            failed.push(key.clone());
+        }
+    }
+    failed
+}
+
+/// ファイルをアップロードしてから `persist` を実行し、失敗時はアップロード済みのキーを削除する
+///
+/// `persist` にはアップロード結果（ストレージキー付きの FileInput）が渡される。
+/// アップロードの途中で失敗した場合、`persist` は呼ばれない。
+async fn upload_with_compensation<S, F, Fut, T>(
+    storage: &S,
+    user_id: Uuid,
+    uploads: Vec<FileUploadInput>,
+    persist: F,
+) -> Result<T, DomainError>
+where
+    S: StorageOps + ?Sized,
+    F: FnOnce(Vec<FileInput>) -> Fut,
+    Fut: Future<Output = Result<T, DomainError>>,
+{
+    // 1. アップロード（成功したキーを記録）
+    let mut inputs: Vec<FileInput> = Vec::with_capacity(uploads.len());
+
+    for upload in uploads {
+        let size_bytes = upload.data.len() as i64;
+        match storage
+            .upload(user_id, &upload.filename, &upload.mime_type, upload.data)
+            .await
+        {
+            Ok(storage_path) => inputs.push(FileInput {
+                filename: upload.filename,
+                mime_type: upload.mime_type,
+                size_bytes,
+                storage_path,
+            }),
+            Err(e) => {
+                // 途中で失敗: それまでにアップロードしたオブジェクトを削除
+                let keys: Vec<String> = inputs.into_iter().map(|f| f.storage_path).collect();
+                compensate_uploads(storage, &keys).await;
+                return Err(e);
+            }
+        }
+    }
+
+    // 2. DB 処理（失敗時はロールバック済み）
+    let keys: Vec<String> = inputs.iter().map(|f| f.storage_path.clone()).collect();
+    match persist(inputs).await {
+        Ok(value) => Ok(value),
+        Err(e) => {
+            // 3. 補償: DB に参照されないオブジェクトを残さない
+            compensate_uploads(storage, &keys).await;
+            Err(e)
+        }
+    }
+}
+
+/// アップロード済みオブジェクトを削除する（補償処理）
+///
+/// 削除に失敗したキーは警告ログに残す（クリーンアップジョブで再試行）。
+async fn compensate_uploads<S: StorageOps + ?Sized>(storage: &S, keys: &[String]) {
+    if keys.is_empty() {
+        return;
+    }
+
+    let failed = delete_storage_objects(storage, keys).await;
+    if failed.is_empty() {
+        info!(count = keys.len(), "Compensated uploaded storage objects");
+    } else {
+        warn!(
+            failed_storage_keys = ?failed,
+            "Failed to compensate uploaded storage objects; left for cleanup job"
+        );
+    }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use domain::StoredObject;
+    use std::sync::{Arc, Mutex};
+
+    /// 指定したキーの削除だけ失敗するストレージ
+    struct FlakyStorage {
+        fail_on: Vec<String>,
+        deleted: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl StorageOps for FlakyStorage {
+        async fn upload(
+            &self,
+            _user_id: Uuid,
+            _filename: &str,
+            _content_type: &str,
+            _data: Vec<u8>,
+        ) -> Result<String, DomainError> {
+            panic!("unexpected call to StorageOps::upload")
+        }
+
+        async fn download(&self, _storage_path: &str) -> Result<Vec<u8>, DomainError> {
+            panic!("unexpected call to StorageOps::download")
+        }
+
+        async fn delete(&self, storage_path: &str) -> Result<(), DomainError> {
+            if self.fail_on.iter().any(|k| k == storage_path) {
+                return Err(DomainError::External("S3 unavailable".into()));
+            }
+            self.deleted.lock().unwrap().push(storage_path.to_string());
+            Ok(())
+        }
+
+        async fn list(&self, _prefix: &str) -> Result<Vec<StoredObject>, DomainError> {
+            panic!("unexpected call to StorageOps::list")
+        }
+    }
+
+    /// 他ユーザー所有の ID は削除されず、not_found として返る
+    #[test]
+    fn test_missing_ids_with_partial_ownership() {
+        let owned = Uuid::new_v4();
+        let other_users = Uuid::new_v4();
+        let nonexistent = Uuid::new_v4();
+
+        let requested = [owned, other_users, nonexistent, other_users];
+        let deleted = [owned];
+
+        assert_eq!(
+            missing_ids(&requested, &deleted),
+            vec![other_users, nonexistent]
+        );
+    }
+
+    /// コミット後のストレージ削除失敗はキーとして返り、残りの削除は継続する
+    #[tokio::test]
+    async fn test_delete_storage_objects_reports_failed_keys() {
+        let storage = FlakyStorage {
+            fail_on: vec!["users/u/files/2/b.txt".to_string()],
+            deleted: Mutex::new(Vec::new()),
+        };
+        let keys = vec![
+            "users/u/files/1/a.txt".to_string(),
+            "users/u/files/2/b.txt".to_string(),
+            "users/u/files/3/c.txt".to_string(),
+        ];
+
+        let failed = delete_storage_objects(&storage, &keys).await;
+
+        assert_eq!(failed, vec!["users/u/files/2/b.txt".to_string()]);
+        assert_eq!(
+            *storage.deleted.lock().unwrap(),
+            vec![
+                "users/u/files/1/a.txt".to_string(),
+                "users/u/files/3/c.txt".to_string()
+            ]
+        );
+    }
+
+    /// 指定回数目のアップロードで失敗するストレージ
+    struct FailingUploadStorage {
+        /// 失敗させるアップロードの番号（0 始まり、None なら失敗しない）
+        fail_at: Option<usize>,
+        uploaded: Mutex<Vec<String>>,
+        deleted: Mutex<Vec<String>>,
+    }
+
+    impl FailingUploadStorage {
+        fn new(fail_at: Option<usize>) -> Self {
+            Self {
+                fail_at,
+                uploaded: Mutex::new(Vec::new()),
+                deleted: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl StorageOps for FailingUploadStorage {
+        async fn upload(
+            &self,
+            user_id: Uuid,
+            filename: &str,
+            _content_type: &str,
+            _data: Vec<u8>,
+        ) -> Result<String, DomainError> {
+            let mut uploaded = self.uploaded.lock().unwrap();
+            if self.fail_at == Some(uploaded.len()) {
+                return Err(DomainError::External("S3 unavailable".into()));
+            }
+            let key = format!("users/{}/files/{}/{}", user_id, uploaded.len(), filename);
+            uploaded.push(key.clone());
+            Ok(key)
+        }
+
+        async fn download(&self, _storage_path: &str) -> Result<Vec<u8>, DomainError> {
+            panic!("unexpected call to StorageOps::download")
+        }
+
+        async fn delete(&self, storage_path: &str) -> Result<(), DomainError> {
+            self.deleted.lock().unwrap().push(storage_path.to_string());
+            Ok(())
+        }
+
+        async fn list(&self, _prefix: &str) -> Result<Vec<StoredObject>, DomainError> {
+            panic!("unexpected call to StorageOps::list")
+        }
+    }
+
+    fn uploads(names: &[&str]) -> Vec<FileUploadInput> {
+        names
+            .iter()
+            .map(|name| FileUploadInput {
+                filename: name.to_string(),
+                mime_type: "text/plain".to_string(),
+                data: b"hello".to_vec(),
+            })
+            .collect()
+    }
+
+    /// 成功時は persist にストレージキーが渡され、何も削除されない
+    #[tokio::test]
+    async fn test_upload_with_compensation_success() {
+        let storage = FailingUploadStorage::new(None);
+
+        let files = upload_with_compensation(
+            &storage,
+            Uuid::new_v4(),
+            uploads(&["a.txt", "b.txt"]),
+            |files| async move { Ok(files) },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].size_bytes, 5);
+        assert_eq!(
+            files
+                .iter()
+                .map(|f| f.storage_path.clone())
+                .collect::<Vec<_>>(),
+            *storage.uploaded.lock().unwrap()
+        );
+        assert!(storage.deleted.lock().unwrap().is_empty());
+    }
+
+    /// DB 処理が失敗した場合、アップロード済みの全オブジェクトを削除する
+    #[tokio::test]
+    async fn test_upload_with_compensation_db_failure_deletes_uploads() {
+        let storage = FailingUploadStorage::new(None);
+
+        let result: Result<(), DomainError> = upload_with_compensation(
+            &storage,
+            Uuid::new_v4(),
+            uploads(&["a.txt", "b.txt"]),
+            |_| async { Err(DomainError::Repository("insert failed".into())) },
+        )
+        .await;
+
+        assert!(matches!(result, Err(DomainError::Repository(_))));
+        assert_eq!(
+            *storage.deleted.lock().unwrap(),
+            *storage.uploaded.lock().unwrap()
+        );
+        assert_eq!(storage.deleted.lock().unwrap().len(), 2);
+    }
+
+    /// アップロードが途中で失敗した場合、DB 処理は呼ばれず、それまでのオブジェクトを削除する
+    #[tokio::test]
+    async fn test_upload_with_compensation_mid_upload_failure() {
+        let storage = FailingUploadStorage::new(Some(2));
+        let persisted = Mutex::new(false);
+
+        let result = upload_with_compensation(
+            &storage,
+            Uuid::new_v4(),
+            uploads(&["a.txt", "b.txt", "c.txt", "d.txt"]),
+            |_| async {
+                *persisted.lock().unwrap() = true;
+                Ok(())
+            },
+        )
+        .await;
+
+        assert!(matches!(result, Err(DomainError::External(_))));
+        assert!(!*persisted.lock().unwrap());
+        // 成功した 2 件のみ削除され、失敗以降はアップロードされない
+        assert_eq!(storage.uploaded.lock().unwrap().len(), 2);
+        assert_eq!(
+            *storage.deleted.lock().unwrap(),
+            *storage.uploaded.lock().unwrap()
+        );
+    }
+
+    // -------------------------------------------------------------------------
+    // トランザクションのオーケストレーション
+    // -------------------------------------------------------------------------
+
+    /// トランザクション内の操作を記録するプロバイダ
+    ///
+    /// commit / rollback されずに Drop されたトランザクションは "drop" として記録する
+    /// （PostgreSQL 実装ではロールバックに相当）。
+    #[derive(Default)]
+    struct RecordingProvider {
+        log: Arc<Mutex<Vec<String>>>,
+        /// 失敗させる insert_todo の番号（0 始まり、None なら失敗しない）
+        fail_insert_at: Option<usize>,
+        /// DB に存在する（所有者が一致する）TODO の ID
+        existing: Vec<Uuid>,
+        /// advance_import_job の結果
+        advance: bool,
+        /// ユーザーの未完了 TODO（重複検出の検索対象）
+        open: Vec<Todo>,
+    }
+
+    impl RecordingProvider {
+        fn log(&self) -> Vec<String> {
+            self.log.lock().unwrap().clone()
+        }
+    }
+
+    struct RecordingTx {
+        log: Arc<Mutex<Vec<String>>>,
+        inserts: usize,
+        fail_insert_at: Option<usize>,
+        existing: Vec<Uuid>,
+        advance: bool,
+        open: Vec<Todo>,
+        finished: bool,
+    }
+
+    impl RecordingTx {
+        fn record(&self, entry: impl Into<String>) {
+            self.log.lock().unwrap().push(entry.into());
+        }
+    }
+
+    impl Drop for RecordingTx {
+        fn drop(&mut self) {
+            if !self.finished {
+                self.record("drop");
+            }
+        }
+    }
+
+    #[async_trait]
+    impl TransactionProvider for RecordingProvider {
+        type Tx = RecordingTx;
+
+        async fn begin(&self) -> Result<Self::Tx, DomainError> {
+            Ok(RecordingTx {
+                log: self.log.clone(),
+                inserts: 0,
+                fail_insert_at: self.fail_insert_at,
+                existing: self.existing.clone(),
+                advance: self.advance,
+                open: self.open.clone(),
+                finished: false,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl TodoTransaction for RecordingTx {
+        async fn insert_todo(&mut self, todo: &Todo) -> Result<Todo, DomainError> {
+            self.record(format!("insert_todo:{}", todo.title));
+            self.inserts += 1;
+            if self.fail_insert_at == Some(self.inserts - 1) {
+                return Err(DomainError::Repository("insert failed".into()));
+            }
+            Ok(todo.clone())
+        }
+
+        async fn find_open_todos_by_normalized_titles(
+            &mut self,
+            _user_id: Uuid,
+            normalized_titles: &[String],
+        ) -> Result<Vec<Todo>, DomainError> {
+            self.record(format!("find_open_todos:{}", normalized_titles.join(",")));
+            Ok(self
+                .open
+                .iter()
+                .filter(|t| normalized_titles.contains(&Todo::normalize_title(&t.title)))
+                .cloned()
+                .collect())
+        }
+
+        async fn insert_file(&mut self, file: &File) -> Result<File, DomainError> {
+            self.record(format!("insert_file:{}", file.filename));
+            Ok(file.clone())
+        }
+
+        async fn update_todos(
+            &mut self,
+            user_id: Uuid,
+            updates: &[TodoUpdateInput],
+        ) -> Result<Vec<Todo>, DomainError> {
+            self.record("update_todos");
+            // RETURNING の順序は不定のため、逆順で返す
+            Ok(updates
+                .iter()
+                .rev()
+                .filter(|u| self.existing.contains(&u.id))
+                .map(|u| {
+                    let title = u.title.clone().unwrap_or_else(|| "unchanged".into());
+                    Todo::from_raw(u.id, user_id, title, None, false, Utc::now(), Utc::now())
+                })
+                .collect())
+        }
+
+        async fn file_storage_keys(
+            &mut self,
+            _user_id: Uuid,
+            _ids: &[Uuid],
+        ) -> Result<Vec<String>, DomainError> {
+            self.record("file_storage_keys");
+            Ok(vec!["users/u/files/1/a.txt".to_string()])
+        }
+
+        async fn delete_files(
+            &mut self,
+            _user_id: Uuid,
+            _ids: &[Uuid],
+        ) -> Result<u64, DomainError> {
+            self.record("delete_files");
+            Ok(1)
+        }
+
+        async fn delete_todos(
+            &mut self,
+            _user_id: Uuid,
+            ids: &[Uuid],
+        ) -> Result<Vec<Uuid>, DomainError> {
+            self.record("delete_todos");
+            Ok(ids
+                .iter()
+                .filter(|id| self.existing.contains(id))
+                .copied()
+                .collect())
+        }
+
+        async fn advance_import_job(
+            &mut self,
+            _job_id: Uuid,
+            _cursor: i32,
+            _chunk_len: i32,
+            _failed: i32,
+        ) -> Result<bool, DomainError> {
+            self.record("advance_import_job");
+            Ok(self.advance)
+        }
+
+        async fn record_import_item_errors(
+            &mut self,
+            _job_id: Uuid,
+            item_errors: &[(i32, String)],
+        ) -> Result<(), DomainError> {
+            self.record(format!("record_import_item_errors:{}", item_errors.len()));
+            Ok(())
+        }
+
+        async fn commit(mut self) -> Result<(), DomainError> {
+            self.finished = true;
+            self.record("commit");
+            Ok(())
+        }
+
+        async fn rollback(mut self) -> Result<(), DomainError> {
+            self.finished = true;
+            self.record("rollback");
+            Ok(())
+        }
+    }
+
+    fn input(title: &str, completed: bool) -> TodoCreateInput {
+        TodoCreateInput {
+            title: title.to_string(),
+            description: None,
+            completed,
+            tenant_id: None,
+        }
+    }
+
+    fn update(id: Uuid) -> TodoUpdateInput {
+        TodoUpdateInput {
+            id,
+            title: Some("new".to_string()),
+            description: None,
+            completed: None,
+        }
+    }
+
+    /// 全件成功: 入力順に作成してコミットし、完了状態・テナントを保持する
+    #[tokio::test]
+    async fn test_batch_create_commits_in_input_order() {
+        let service = TransactionalTodoService::with_provider(RecordingProvider::default());
+        let tenant = Uuid::new_v4();
+        let scoped = TodoCreateInput {
+            tenant_id: Some(tenant),
+            ..input("b", true)
+        };
+
+        let created = service
+            .batch_create_inputs(Uuid::new_v4(), vec![input("a", false), scoped])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            created
+                .iter()
+                .map(|t| (t.title.as_str(), t.completed, t.tenant_id))
+                .collect::<Vec<_>>(),
+            vec![("a", false, None), ("b", true, Some(tenant))]
+        );
+        assert_eq!(
+            service.provider.log(),
+            vec!["insert_todo:a", "insert_todo:b", "commit"]
+        );
+    }
+
+    /// 途中の INSERT が失敗した場合、以降の INSERT は行わずコミットもしない
+    #[tokio::test]
+    async fn test_batch_create_failure_rolls_back() {
+        let service = TransactionalTodoService::with_provider(RecordingProvider {
+            fail_insert_at: Some(1),
+            ..Default::default()
+        });
+
+        let result = service
+            .batch_create(
+                Uuid::new_v4(),
+                vec![
+                    ("a".to_string(), None),
+                    ("b".to_string(), None),
+                    ("c".to_string(), None),
+                ],
+            )
+            .await;
+
+        assert!(matches!(result, Err(DomainError::Repository(_))));
+        assert_eq!(
+            service.provider.log(),
+            vec!["insert_todo:a", "insert_todo:b", "drop"]
+        );
+    }
+
+    fn duplicate_of(outcome: &CreateOutcome) -> Option<Uuid> {
+        match outcome {
+            CreateOutcome::Created(_) => None,
+            CreateOutcome::Duplicate { existing_id } => Some(*existing_id),
+        }
+    }
+
+    /// リクエスト内の重複: 最初の項目だけ作成し、以降は作成した TODO の ID を返す
+    #[tokio::test]
+    async fn test_batch_create_dedupe_skips_intra_batch_duplicates() {
+        let service = TransactionalTodoService::with_provider(RecordingProvider::default());
+
+        let outcomes = service
+            .batch_create_dedupe(
+                Uuid::new_v4(),
+                vec![
+                    input("Buy milk", false),
+                    input("  buy   MILK ", false),
+                    input("Clean room", false),
+                    input("BUY MILK", false),
+                ],
+                false,
+            )
+            .await
+            .unwrap();
+
+        let first_id = match &outcomes[0] {
+            CreateOutcome::Created(todo) => todo.id,
+            other => panic!("expected created, got {:?}", other),
+        };
+        assert_eq!(
+            outcomes.iter().map(duplicate_of).collect::<Vec<_>>(),
+            vec![None, Some(first_id), None, Some(first_id)]
+        );
+        // 既存 TODO の検索は行わない
+        assert_eq!(
+            service.provider.log(),
+            vec!["insert_todo:Buy milk", "insert_todo:Clean room", "commit"]
+        );
+    }
+
+    /// 既存の未完了 TODO との重複: 1 回の検索で判定し、既存 TODO の ID を返す
+    #[tokio::test]
+    async fn test_batch_create_dedupe_skips_existing_duplicates() {
+        let user_id = Uuid::new_v4();
+        let existing = Todo::new(user_id, "Buy Milk".to_string(), None);
+        let service = TransactionalTodoService::with_provider(RecordingProvider {
+            open: vec![existing.clone()],
+            ..Default::default()
+        });
+
+        let outcomes = service
+            .batch_create_dedupe(
+                user_id,
+                vec![
+                    input("buy milk", false),
+                    input("Clean room", false),
+                    input(" Buy  milk", false),
+                ],
+                true,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            outcomes.iter().map(duplicate_of).collect::<Vec<_>>(),
+            vec![Some(existing.id), None, Some(existing.id)]
+        );
+        assert_eq!(
+            service.provider.log(),
+            vec![
+                "find_open_todos:buy milk,clean room",
+                "insert_todo:Clean room",
+                "commit"
+            ]
+        );
+    }
+
+    /// 既存 TODO が複数ある場合は先頭（作成日時が最も古いもの）を重複先にする
+    #[test]
+    fn test_plan_dedupe_prefers_oldest_existing() {
+        let user_id = Uuid::new_v4();
+        let oldest = Todo::new(user_id, "a".to_string(), None);
+        let newer = Todo::new(user_id, "A".to_string(), None);
+        let keys = vec!["a".to_string(), "b".to_string(), "b".to_string()];
+
+        assert_eq!(
+            plan_dedupe(&keys, &[oldest.clone(), newer]),
+            vec![
+                DedupeAction::Existing(oldest.id),
+                DedupeAction::Create,
+                DedupeAction::SameAs(1)
+            ]
+        );
+    }
+
+    /// 全件モード: 見つからない ID があれば明示的にロールバックし、結果は入力順
+    #[tokio::test]
+    async fn test_batch_update_all_or_nothing_rolls_back_on_missing_id() {
+        let (a, b, missing) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let service = TransactionalTodoService::with_provider(RecordingProvider {
+            existing: vec![a, b],
+            ..Default::default()
+        });
+
+        let outcome = service
+            .batch_update(
+                Uuid::new_v4(),
+                vec![update(a), update(missing), update(b)],
+                false,
+            )
+            .await
+            .unwrap();
+
+        assert!(!outcome.committed);
+        assert_eq!(outcome.not_found, vec![missing]);
+        assert_eq!(
+            outcome.updated.iter().map(|t| t.id).collect::<Vec<_>>(),
+            vec![a, b]
+        );
+        assert_eq!(service.provider.log(), vec!["update_todos", "rollback"]);
+    }
+
+    /// 部分適用モード: 見つからない ID があってもコミットし、結果は入力順
+    #[tokio::test]
+    async fn test_batch_update_partial_commits_and_orders_results() {
+        let (a, b, missing) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let service = TransactionalTodoService::with_provider(RecordingProvider {
+            existing: vec![a, b],
+            ..Default::default()
+        });
+
+        let outcome = service
+            .batch_update(
+                Uuid::new_v4(),
+                vec![update(a), update(missing), update(b)],
+                true,
+            )
+            .await
+            .unwrap();
+
+        assert!(outcome.committed);
+        assert_eq!(outcome.not_found, vec![missing]);
+        assert_eq!(
+            outcome.updated.iter().map(|t| t.id).collect::<Vec<_>>(),
+            vec![a, b]
+        );
+        assert_eq!(service.provider.log(), vec!["update_todos", "commit"]);
+    }
+
+    /// ジョブを所有していない場合、TODO を作成せずに終了する
+    #[tokio::test]
+    async fn test_import_chunk_skips_when_job_not_advanced() {
+        let service = TransactionalTodoService::with_provider(RecordingProvider::default());
+
+        let committed = service
+            .import_chunk(
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                0,
+                2,
+                vec![("a".to_string(), None)],
+                &[(1, "title cannot be empty".to_string())],
+            )
+            .await
+            .unwrap();
+
+        assert!(!committed);
+        assert_eq!(service.provider.log(), vec!["advance_import_job", "drop"]);
+    }
+
+    /// 進捗更新、TODO 作成、エラー記録を同じトランザクションでコミットする
+    #[tokio::test]
+    async fn test_import_chunk_commits_todos_and_item_errors_together() {
+        let service = TransactionalTodoService::with_provider(RecordingProvider {
+            advance: true,
+            ..Default::default()
+        });
+
+        let committed = service
+            .import_chunk(
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                0,
+                2,
+                vec![("a".to_string(), None)],
+                &[(1, "title cannot be empty".to_string())],
+            )
+            .await
+            .unwrap();
+
+        assert!(committed);
+        assert_eq!(
+            service.provider.log(),
+            vec![
+                "advance_import_job",
+                "insert_todo:a",
+                "record_import_item_errors:1",
+                "commit"
+            ]
+        );
+    }
+
+    /// ストレージの削除はコミット後に行い、削除されなかった ID を返す
+    #[tokio::test]
+    async fn test_batch_delete_removes_storage_after_commit() {
+        let (owned, missing) = (Uuid::new_v4(), Uuid::new_v4());
+        let service = TransactionalTodoService::with_provider(RecordingProvider {
+            existing: vec![owned],
+            ..Default::default()
+        });
+        let storage = FlakyStorage {
+            fail_on: Vec::new(),
+            deleted: Mutex::new(Vec::new()),
+        };
+
+        let outcome = service
+            .batch_delete(&storage, Uuid::new_v4(), &[owned, missing])
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.todos_deleted, 1);
+        assert_eq!(outcome.not_found, vec![missing]);
+        assert!(outcome.failed_storage_keys.is_empty());
+        assert_eq!(
+            service.provider.log(),
+            vec![
+                "file_storage_keys",
+                "delete_files",
+                "delete_todos",
+                "commit"
+            ]
+        );
+        assert_eq!(
+            *storage.deleted.lock().unwrap(),
+            vec!["users/u/files/1/a.txt".to_string()]
+        );
+    }
+
+    /// コミット後のストレージ削除に失敗しても DB の削除は確定し、キーが返る
+    #[tokio::test]
+    async fn test_batch_delete_reports_failed_storage_keys_after_commit() {
+        let owned = Uuid::new_v4();
+        let service = TransactionalTodoService::with_provider(RecordingProvider {
+            existing: vec![owned],
+            ..Default::default()
+        });
+        let storage = FlakyStorage {
+            fail_on: vec!["users/u/files/1/a.txt".to_string()],
+            deleted: Mutex::new(Vec::new()),
+        };
+
+        let outcome = service
+            .batch_delete(&storage, Uuid::new_v4(), &[owned])
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.todos_deleted, 1);
+        assert_eq!(outcome.files_deleted, 1);
+        assert!(outcome.not_found.is_empty());
+        assert_eq!(
+            outcome.failed_storage_keys,
+            vec!["users/u/files/1/a.txt".to_string()]
+        );
+        assert_eq!(service.provider.log().last().unwrap(), "commit");
+        assert!(storage.deleted.lock().unwrap().is_empty());
+    }
 }
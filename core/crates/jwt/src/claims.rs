@@ -0,0 +1,261 @@
+// =============================================================================
+// jwt/src/claims.rs: JWT のクレームとヘッダー
+// =============================================================================
+// コア層が発行するユーザーのトークンと、エッジ層が発行するサービス用トークン
+// （X-Edge-Token）は、どちらもこの Claims を使う。
+//
+// 発行側ごとの違い:
+// - コア層: sub = ユーザー ID、iss = JWT_ISSUER（設定時のみ）
+// - エッジ層: sub = "edge-gateway"、iss なし、extra に request_id
+// - 他の発行者: aud が文字列・配列のどちらの場合もある（Audience）
+// =============================================================================
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+// =============================================================================
+// Header
+// =============================================================================
+
+/// JWT ヘッダー
+///
+/// 例: `{"alg":"HS256","typ":"JWT","kid":"default"}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Header {
+    /// 署名アルゴリズム（ALLOWED_ALGORITHMS のいずれか）
+    pub alg: String,
+
+    /// トークンタイプ（通常は "JWT"、検証には使わない）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>,
+
+    /// 署名に使った鍵の key id（検証側は kid で鍵を選ぶ）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+
+    /// JWE の暗号化アルゴリズム（JWS にはない。ある場合は Error::EncryptedToken で拒否する）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enc: Option<String>,
+}
+
+impl Header {
+    /// HS256 のヘッダーを作成する
+    ///
+    /// # Arguments
+    /// * `kid` - 署名に使う鍵の key id（None の場合はヘッダーに含めない）
+    pub fn hs256(kid: Option<&str>) -> Self {
+        Self::new("HS256", kid)
+    }
+
+    /// アルゴリズムを指定してヘッダーを作成する
+    ///
+    /// # Arguments
+    /// * `alg` - 署名アルゴリズム（発行する場合は HMAC_ALGORITHMS のいずれか）
+    /// * `kid` - 署名に使う鍵の key id（None の場合はヘッダーに含めない）
+    pub fn new(alg: &str, kid: Option<&str>) -> Self {
+        Self {
+            alg: alg.to_string(),
+            typ: Some("JWT".to_string()),
+            kid: kid.map(str::to_string),
+            enc: None,
+        }
+    }
+}
+
+// =============================================================================
+// Audience
+// =============================================================================
+
+/// aud クレーム（RFC 7519 4.1.3）
+///
+/// 対象者が 1 つの場合は文字列、複数の場合は文字列の配列で表す。
+/// 他の発行者のトークンはどちらの形式も使うため、両方をそのままの形で読み書きする。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Audience {
+    /// `"aud": "todo-api"`
+    Single(String),
+    /// `"aud": ["todo-api", "todo-edge"]`（空の配列は対象者なし）
+    Multiple(Vec<String>),
+}
+
+impl Audience {
+    /// 対象者の一覧（Single は 1 件、Multiple は配列の順）
+    pub fn values(&self) -> &[String] {
+        match self {
+            Self::Single(audience) => core::slice::from_ref(audience),
+            Self::Multiple(audiences) => audiences,
+        }
+    }
+
+    /// 対象者のいずれかが `allowed` に含まれるか
+    ///
+    /// # Arguments
+    /// * `allowed` - 受け付ける対象者（空の場合は常に false）
+    pub fn matches_any(&self, allowed: &[&str]) -> bool {
+        self.values()
+            .iter()
+            .any(|audience| allowed.contains(&audience.as_str()))
+    }
+}
+
+// =============================================================================
+// Claims
+// =============================================================================
+
+/// JWT クレーム（ペイロード）
+///
+/// RFC 7519 の登録済みクレームのうち、両方の層が使うものをフィールドとして持つ。
+/// それ以外のクレーム（roles、tenant_id、request_id など）は `extra` に入る。
+///
+/// # 機密情報
+///
+/// クレームは Base64 をデコードすれば誰でも読めるため、
+/// パスワードやメールアドレスなどは含めない。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Claims {
+    /// Subject: ユーザー ID、またはサービス用トークンでは発行元（"edge-gateway"）
+    pub sub: String,
+
+    /// 有効期限（Unix タイムスタンプ秒）
+    pub exp: u64,
+
+    /// 発行日時（Unix タイムスタンプ秒、ない場合は 0）
+    #[serde(default)]
+    pub iat: u64,
+
+    /// 発行者（コア層で JWT_ISSUER を設定した場合のみ）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+
+    /// 対象者（文字列・文字列の配列のどちらも受け付ける、両方の層とも発行しない）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<Audience>,
+
+    /// 上記以外のクレーム（JSON の値のまま保持する）
+    ///
+    /// 型が想定と異なっても検証は失敗させず、取り出す側（`roles` / `claim_str`）で無視する。
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Claims {
+    /// roles クレームのロール
+    ///
+    /// 文字列の配列のみ受け付け、文字列以外の要素は無視する。
+    /// roles がない・配列でない場合は空。
+    pub fn roles(&self) -> Vec<String> {
+        match self.extra.get("roles") {
+            Some(Value::Array(roles)) => roles
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// 登録済み以外のクレームを文字列として取り出す
+    ///
+    /// 文字列・数値・真偽値のみ文字列に変換する（配列・オブジェクト・null は None）。
+    ///
+    /// # Arguments
+    /// * `name` - クレーム名（例: "tenant_id"）
+    pub fn claim_str(&self, name: &str) -> Option<String> {
+        match self.extra.get(name)? {
+            Value::String(value) => Some(value.clone()),
+            Value::Number(value) => Some(value.to_string()),
+            Value::Bool(value) => Some(value.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// UUID の文字列表現（ハイフン区切りの 36 文字）か
+///
+/// エッジ層はユーザーのトークンの sub がこの形式の場合のみ X-User-Id に設定し、
+/// コア層の UserContext も同じ形式の X-User-Id のみ受け付ける（testdata/subjects の共通のテストケース）。
+/// 16 進数の大文字小文字は区別しない。ハイフンのない形式・波括弧・`urn:uuid:`・前後の空白は受け付けない。
+///
+/// ```
+/// assert!(jwt::is_uuid("550e8400-e29b-41d4-a716-446655440000"));
+/// assert!(!jwt::is_uuid("1 OR 1=1"));
+/// ```
+pub fn is_uuid(value: &str) -> bool {
+    value.len() == 36
+        && value.bytes().enumerate().all(|(index, byte)| match index {
+            8 | 13 | 18 | 23 => byte == b'-',
+            _ => byte.is_ascii_hexdigit(),
+        })
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::vec;
+
+    fn parse(payload: &str) -> Claims {
+        serde_json::from_str(payload).unwrap()
+    }
+
+    /// エッジ層・コア層と共通の sub のテストケース
+    const SUBJECTS: &str = include_str!("../testdata/subjects/subjects.json");
+
+    #[test]
+    fn test_is_uuid_matches_shared_subjects() {
+        let subjects: Value = serde_json::from_str(SUBJECTS).unwrap();
+        for (group, expected) in [("valid", true), ("invalid", false)] {
+            for subject in subjects[group].as_array().unwrap() {
+                let subject = subject.as_str().unwrap();
+                assert_eq!(is_uuid(subject), expected, "{:?}", subject);
+            }
+        }
+        assert!(!is_uuid(&"a".repeat(10 * 1024)));
+    }
+
+    #[test]
+    fn test_audience_accepts_string_and_array() {
+        let single = parse(r#"{"sub":"user-1","exp":4102444800,"aud":"todo-api"}"#);
+        assert_eq!(single.aud, Some(Audience::Single("todo-api".to_string())));
+        assert!(!single.extra.contains_key("aud"));
+
+        let multiple = parse(r#"{"sub":"user-1","exp":4102444800,"aud":["todo-api","todo-edge"]}"#);
+        assert_eq!(
+            multiple.aud.as_ref().map(Audience::values),
+            Some(&["todo-api".to_string(), "todo-edge".to_string()][..])
+        );
+        assert!(multiple.aud.unwrap().matches_any(&["todo-edge"]));
+    }
+
+    #[test]
+    fn test_audience_empty_array_and_missing_claim() {
+        let empty = parse(r#"{"sub":"user-1","exp":4102444800,"aud":[]}"#);
+        assert_eq!(empty.aud, Some(Audience::Multiple(vec![])));
+        assert!(!empty.aud.unwrap().matches_any(&["todo-api"]));
+
+        let missing = parse(r#"{"sub":"user-1","exp":4102444800}"#);
+        assert_eq!(missing.aud, None);
+
+        // 文字列・配列以外（数値、文字列以外の要素）は不正なペイロード
+        for aud in ["42", "[1]", r#"{"name":"todo-api"}"#] {
+            let payload = format!(r#"{{"sub":"user-1","exp":4102444800,"aud":{}}}"#, aud);
+            assert!(serde_json::from_str::<Claims>(&payload).is_err(), "{}", aud);
+        }
+    }
+
+    #[test]
+    fn test_audience_serializes_in_original_form() {
+        let single = Audience::Single("todo-api".to_string());
+        assert_eq!(serde_json::to_string(&single).unwrap(), r#""todo-api""#);
+        let multiple = Audience::Multiple(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(serde_json::to_string(&multiple).unwrap(), r#"["a","b"]"#);
+    }
+}
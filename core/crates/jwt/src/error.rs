@@ -0,0 +1,88 @@
+// =============================================================================
+// jwt/src/error.rs: JWT のエラー型
+// =============================================================================
+// 表示用のメッセージ（Display）は以前のエッジ層の実装と同じ文言にしている。
+// エッジ層は auth-result の error としてクライアントに返すため、文言を変えない。
+// =============================================================================
+
+use alloc::string::String;
+use core::fmt;
+
+/// 署名・検証のエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// ドットで区切られた 3 つの部分がない
+    InvalidFormat,
+    /// 暗号化したトークン（JWE）。5 つの部分に分かれる、またはヘッダーに enc がある
+    EncryptedToken,
+    /// トークン全体・ヘッダー・署名・デコードした JSON が長さの上限を超える
+    TooLarge,
+    /// ヘッダーの Base64URL が不正
+    InvalidHeaderEncoding,
+    /// ヘッダーの JSON が不正
+    InvalidHeaderJson,
+    /// 署名なしのアルゴリズム（"none"、大文字小文字を区別しない）
+    ///
+    /// 許可リストの設定にかかわらず拒否する。値はヘッダーの alg のまま。
+    UnsecuredAlgorithm(String),
+    /// 許可されていない署名アルゴリズム（ALLOWED_ALGORITHMS・Validation の許可リスト以外、または鍵の種類と異なる）
+    UnsupportedAlgorithm(String),
+    /// 署名の Base64URL が不正
+    InvalidSignatureEncoding,
+    /// 署名が一致しない
+    InvalidSignature,
+    /// ペイロードの Base64URL が不正
+    InvalidPayloadEncoding,
+    /// ペイロードの JSON が不正（クレームの型が異なる場合を含む）
+    InvalidPayloadJson,
+    /// sub クレームがない
+    MissingSubject,
+    /// exp クレームがない
+    MissingExpiration,
+    /// 有効期限切れ
+    Expired,
+    /// nbf（この時刻より前は無効）より前
+    NotYetValid,
+    /// iat（発行日時）が現在時刻より未来（時計の誤りか偽造）
+    IssuedInFuture,
+    /// 発行者（iss）が期待する値と異なる、または iss がない
+    InvalidIssuer,
+    /// 対象者（aud）に受け付ける値が含まれない、または aud がない
+    InvalidAudience,
+    /// 署名鍵が不正
+    InvalidKey,
+    /// RS256 / ES256 の公開鍵（PEM・JWK）が不正
+    InvalidPublicKey,
+    /// ヘッダー・ペイロードのシリアライズに失敗
+    Encoding,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFormat => f.write_str("Invalid token format"),
+            Self::EncryptedToken => f.write_str("Encrypted tokens (JWE) are not supported"),
+            Self::TooLarge => f.write_str("Token too large"),
+            Self::InvalidHeaderEncoding => f.write_str("Invalid header encoding"),
+            Self::InvalidHeaderJson => f.write_str("Invalid header JSON"),
+            Self::UnsecuredAlgorithm(alg) => write!(f, "Unsecured algorithm not allowed: {}", alg),
+            Self::UnsupportedAlgorithm(alg) => write!(f, "Unsupported algorithm: {}", alg),
+            Self::InvalidSignatureEncoding => f.write_str("Invalid signature encoding"),
+            Self::InvalidSignature => f.write_str("Invalid signature"),
+            Self::InvalidPayloadEncoding => f.write_str("Invalid payload encoding"),
+            Self::InvalidPayloadJson => f.write_str("Invalid payload JSON"),
+            Self::MissingSubject => f.write_str("Missing subject claim"),
+            Self::MissingExpiration => f.write_str("Missing expiration claim"),
+            Self::Expired => f.write_str("Token expired"),
+            Self::NotYetValid => f.write_str("Token not yet valid"),
+            Self::IssuedInFuture => f.write_str("Token issued in the future"),
+            Self::InvalidIssuer => f.write_str("Invalid issuer"),
+            Self::InvalidAudience => f.write_str("Invalid audience"),
+            Self::InvalidKey => f.write_str("Invalid key length"),
+            Self::InvalidPublicKey => f.write_str("Invalid public key"),
+            Self::Encoding => f.write_str("Token encoding error"),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
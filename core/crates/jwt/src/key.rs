@@ -0,0 +1,416 @@
+// =============================================================================
+// jwt/src/key.rs: 検証に使う鍵
+// =============================================================================
+// 鍵の種類ごとに受け付けるアルゴリズムを固定する。
+// - DecodingKey::Hmac: HS256 / HS384 / HS512（共有シークレット、コア層とエッジ層の両方）
+//   （HMAC のハッシュ関数はヘッダーの alg で選ぶ。受け付けるかは Validation の許可リストで決める）
+// - DecodingKey::Rsa: RS256（外部の ID プロバイダーの公開鍵、エッジ層のみ）
+// - DecodingKey::Ec: ES256（パートナーの ECDSA P-256 公開鍵、エッジ層のみ）
+//
+// アルゴリズム混同攻撃（RS256 の公開鍵を HS256 のシークレットとして使わせる）を防ぐため、
+// ヘッダーの alg から鍵を作らず、呼び出し側が用意した鍵の種類とヘッダーの alg を照合する。
+//
+// JwkSet: ID プロバイダーが公開する JWKS（RFC 7517 5）。kid で RS256 / ES256 の鍵を選ぶ。
+// 取得・キャッシュは呼び出し側（エッジ層）が行い、このクレートは JSON の解析のみ行う。
+// =============================================================================
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use hmac::{Hmac, Mac};
+use p256::ecdsa::signature::Verifier;
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::pkcs8::DecodePublicKey;
+use rsa::{BigUint, Pkcs1v15Sign};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::Error;
+
+/// HMAC-SHA256（HS256）
+pub(crate) type HmacSha256 = Hmac<Sha256>;
+
+/// HMAC-SHA384（HS384）
+type HmacSha384 = Hmac<Sha384>;
+
+/// HMAC-SHA512（HS512）
+type HmacSha512 = Hmac<Sha512>;
+
+/// 共有シークレットで署名するアルゴリズム
+pub(crate) const HS256: &str = "HS256";
+const HS384: &str = "HS384";
+const HS512: &str = "HS512";
+
+/// RSA 公開鍵で検証するアルゴリズム
+pub(crate) const RS256: &str = "RS256";
+
+/// ECDSA P-256 公開鍵で検証するアルゴリズム
+pub(crate) const ES256: &str = "ES256";
+
+/// ES256 の署名の長さ（JOSE の形式: r || s、各 32 バイトのビッグエンディアン、RFC 7518 3.4）
+const ES256_SIGNATURE_LEN: usize = 64;
+
+// =============================================================================
+// Jwk
+// =============================================================================
+
+/// JWK（RFC 7517、検証に使うメンバーのみ）
+///
+/// 鍵の種類ごとのメンバー（EC: crv / x / y、RSA: n / e）は RsaPublicKey / EcPublicKey で確認する。
+#[derive(Deserialize)]
+struct Jwk {
+    kty: String,
+    #[serde(default)]
+    kid: Option<String>,
+    #[serde(default, rename = "use")]
+    key_use: Option<String>,
+    #[serde(default)]
+    alg: Option<String>,
+    #[serde(default)]
+    crv: Option<String>,
+    #[serde(default)]
+    x: Option<String>,
+    #[serde(default)]
+    y: Option<String>,
+    #[serde(default)]
+    n: Option<String>,
+    #[serde(default)]
+    e: Option<String>,
+}
+
+impl Jwk {
+    /// JSON から読み込む
+    fn from_json(jwk: &str) -> Result<Self, Error> {
+        serde_json::from_str(jwk).map_err(|_| Error::InvalidPublicKey)
+    }
+}
+
+/// JWK のメンバー（Base64URL、パディングなし）をデコードする
+fn decode_member(value: Option<&str>) -> Result<Vec<u8>, Error> {
+    value
+        .and_then(|value| URL_SAFE_NO_PAD.decode(value).ok())
+        .filter(|bytes| !bytes.is_empty())
+        .ok_or(Error::InvalidPublicKey)
+}
+
+// =============================================================================
+// RsaPublicKey
+// =============================================================================
+
+/// RS256 の検証に使う RSA 公開鍵
+///
+/// PEM の解析は起動時・設定の読み込み時に 1 回だけ行い、検証ではこの値を使い回す。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsaPublicKey(rsa::RsaPublicKey);
+
+impl RsaPublicKey {
+    /// PEM から読み込む
+    ///
+    /// `-----BEGIN PUBLIC KEY-----`（SubjectPublicKeyInfo）と
+    /// `-----BEGIN RSA PUBLIC KEY-----`（PKCS#1）のどちらも受け付ける。
+    ///
+    /// # Errors
+    /// * `Error::InvalidPublicKey` - PEM・鍵の形式が不正、または RSA の鍵でない場合
+    pub fn from_pem(pem: &str) -> Result<Self, Error> {
+        let pem = pem.trim();
+        let key = rsa::RsaPublicKey::from_public_key_pem(pem)
+            .or_else(|_| rsa::RsaPublicKey::from_pkcs1_pem(pem))
+            .map_err(|_| Error::InvalidPublicKey)?;
+        Ok(Self(key))
+    }
+
+    /// JWK（`{"kty":"RSA","n":"...","e":"..."}`）から読み込む
+    ///
+    /// kid・use など検証に使わないメンバーは無視する。
+    ///
+    /// # Errors
+    /// * `Error::InvalidPublicKey` - JSON が不正、kty が異なる、または n / e が不正な場合
+    pub fn from_jwk(jwk: &str) -> Result<Self, Error> {
+        Self::from_parsed_jwk(&Jwk::from_json(jwk)?)
+    }
+
+    fn from_parsed_jwk(jwk: &Jwk) -> Result<Self, Error> {
+        if jwk.kty != "RSA" {
+            return Err(Error::InvalidPublicKey);
+        }
+        let n = BigUint::from_bytes_be(&decode_member(jwk.n.as_deref())?);
+        let e = BigUint::from_bytes_be(&decode_member(jwk.e.as_deref())?);
+        rsa::RsaPublicKey::new(n, e)
+            .map(Self)
+            .map_err(|_| Error::InvalidPublicKey)
+    }
+}
+
+// =============================================================================
+// EcPublicKey
+// =============================================================================
+
+/// ES256 の検証に使う ECDSA P-256 公開鍵
+///
+/// RsaPublicKey と同じく、PEM・JWK の解析は設定の読み込み時に 1 回だけ行う。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EcPublicKey(p256::PublicKey);
+
+impl EcPublicKey {
+    /// PEM（`-----BEGIN PUBLIC KEY-----`、SubjectPublicKeyInfo）から読み込む
+    ///
+    /// # Errors
+    /// * `Error::InvalidPublicKey` - PEM・鍵の形式が不正、または P-256 の鍵でない場合
+    pub fn from_pem(pem: &str) -> Result<Self, Error> {
+        p256::PublicKey::from_public_key_pem(pem.trim())
+            .map(Self)
+            .map_err(|_| Error::InvalidPublicKey)
+    }
+
+    /// JWK（`{"kty":"EC","crv":"P-256","x":"...","y":"..."}`）から読み込む
+    ///
+    /// kid・use など検証に使わないメンバーは無視する。
+    ///
+    /// # Errors
+    /// * `Error::InvalidPublicKey` - JSON が不正、kty / crv が異なる、または座標が曲線上の点でない場合
+    pub fn from_jwk(jwk: &str) -> Result<Self, Error> {
+        Self::from_parsed_jwk(&Jwk::from_json(jwk)?)
+    }
+
+    fn from_parsed_jwk(jwk: &Jwk) -> Result<Self, Error> {
+        if jwk.kty != "EC" || jwk.crv.as_deref() != Some("P-256") {
+            return Err(Error::InvalidPublicKey);
+        }
+        let coordinate = |value: Option<&str>| match decode_member(value) {
+            Ok(bytes) if bytes.len() == 32 => Ok(bytes),
+            _ => Err(Error::InvalidPublicKey),
+        };
+
+        // SEC1 の非圧縮形式（0x04 || x || y）にして、曲線上の点か確認する
+        let mut point = Vec::with_capacity(65);
+        point.push(0x04);
+        point.extend(coordinate(jwk.x.as_deref())?);
+        point.extend(coordinate(jwk.y.as_deref())?);
+        p256::PublicKey::from_sec1_bytes(&point)
+            .map(Self)
+            .map_err(|_| Error::InvalidPublicKey)
+    }
+}
+
+// =============================================================================
+// JwkSet
+// =============================================================================
+
+/// JWKS の公開鍵
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PublicKey {
+    Rsa(RsaPublicKey),
+    Ec(EcPublicKey),
+}
+
+/// ID プロバイダーが公開する JWK Set（RFC 7517 5、`{"keys":[...]}`）
+///
+/// kid で RS256 / ES256 の鍵を選ぶ。次の鍵は読み飛ばし、残りの鍵だけを使う
+/// （暗号化用の鍵や他の曲線の鍵が同じ JWKS に含まれていても、署名用の鍵は使えるようにする）。
+/// - kid がない鍵（kid で選べない）
+/// - use が "sig" 以外の鍵
+/// - kty / crv が未対応、または alg が鍵の種類と異なる鍵（RSA の鍵の PS256・RS512 など）
+/// - メンバーが不正な鍵
+///
+/// # 使用例
+///
+/// ```
+/// let jwks = jwt::JwkSet::from_json(r#"{"keys":[]}"#).unwrap();
+/// assert!(jwks.find("key-1").is_none());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JwkSet {
+    /// (kid, 公開鍵)（JWKS の順）
+    keys: Vec<(String, PublicKey)>,
+}
+
+/// JWK Set の JSON（鍵は 1 つずつ解析する）
+#[derive(Deserialize)]
+struct JwkSetJson {
+    keys: Vec<Value>,
+}
+
+/// 署名の検証に使える鍵を (kid, 公開鍵) にする（使えない鍵は None）
+fn signing_key(jwk: Jwk) -> Option<(String, PublicKey)> {
+    if jwk
+        .key_use
+        .as_deref()
+        .is_some_and(|key_use| key_use != "sig")
+    {
+        return None;
+    }
+    let (key, alg) = match jwk.kty.as_str() {
+        "RSA" => (
+            PublicKey::Rsa(RsaPublicKey::from_parsed_jwk(&jwk).ok()?),
+            RS256,
+        ),
+        "EC" => (
+            PublicKey::Ec(EcPublicKey::from_parsed_jwk(&jwk).ok()?),
+            ES256,
+        ),
+        _ => return None,
+    };
+    if jwk.alg.as_deref().is_some_and(|jwk_alg| jwk_alg != alg) {
+        return None;
+    }
+    Some((jwk.kid?, key))
+}
+
+impl JwkSet {
+    /// JSON から読み込む
+    ///
+    /// # Errors
+    /// * `Error::InvalidPublicKey` - JSON が不正、または keys の配列がない場合
+    ///   （個々の鍵が不正な場合はその鍵を読み飛ばす）
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let set: JwkSetJson = serde_json::from_str(json).map_err(|_| Error::InvalidPublicKey)?;
+        let keys = set
+            .keys
+            .into_iter()
+            .filter_map(|key| serde_json::from_value::<Jwk>(key).ok())
+            .filter_map(signing_key)
+            .collect();
+        Ok(Self { keys })
+    }
+
+    /// kid の鍵を選ぶ（同じ kid が複数ある場合は最初の鍵）
+    pub fn find(&self, kid: &str) -> Option<DecodingKey<'_>> {
+        self.keys
+            .iter()
+            .find(|(key_id, _)| key_id == kid)
+            .map(|(_, key)| match key {
+                PublicKey::Rsa(key) => DecodingKey::Rsa(key),
+                PublicKey::Ec(key) => DecodingKey::Ec(key),
+            })
+    }
+
+    /// 使える鍵の数
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// 使える鍵がない場合は true
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+// =============================================================================
+// DecodingKey
+// =============================================================================
+
+/// 検証に使う鍵
+///
+/// # 使用例
+///
+/// ```
+/// let key = jwt::DecodingKey::Hmac(b"super-secret-key");
+/// assert_eq!(key.algorithms(), &["HS256", "HS384", "HS512"]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodingKey<'a> {
+    /// HS256 / HS384 / HS512 の共有シークレット
+    Hmac(&'a [u8]),
+    /// RS256 の公開鍵
+    Rsa(&'a RsaPublicKey),
+    /// ES256 の公開鍵
+    Ec(&'a EcPublicKey),
+}
+
+impl DecodingKey<'_> {
+    /// この鍵で検証するアルゴリズム（ヘッダーの alg がいずれかと一致する必要がある）
+    ///
+    /// 共有シークレットは HMAC_ALGORITHMS のすべて（受け付けるかは Validation の許可リストで決める）。
+    pub fn algorithms(&self) -> &'static [&'static str] {
+        match self {
+            Self::Hmac(_) => crate::HMAC_ALGORITHMS,
+            Self::Rsa(_) => &[RS256],
+            Self::Ec(_) => &[ES256],
+        }
+    }
+
+    /// 「ヘッダー.ペイロード」の署名を検証する
+    ///
+    /// # Arguments
+    /// * `alg` - ヘッダーの alg（algorithms() のいずれか、HMAC のハッシュ関数を選ぶ）
+    ///
+    /// # Errors
+    /// * `Error::InvalidKey` - HMAC のシークレットが不正な場合
+    /// * `Error::UnsupportedAlgorithm` - 共有シークレットで alg が HMAC_ALGORITHMS 以外の場合
+    /// * `Error::InvalidSignatureEncoding` - ES256 の署名が 64 バイトでない場合（DER の署名を含む）
+    /// * `Error::InvalidSignature` - 署名が一致しない場合
+    pub(crate) fn verify(
+        &self,
+        alg: &str,
+        header_b64: &str,
+        payload_b64: &str,
+        signature: &[u8],
+    ) -> Result<(), Error> {
+        match self {
+            Self::Hmac(secret) => {
+                let message = [header_b64, ".", payload_b64].concat();
+                match alg {
+                    HS256 => verify_mac(HmacSha256::new_from_slice(secret), &message, signature),
+                    HS384 => verify_mac(HmacSha384::new_from_slice(secret), &message, signature),
+                    HS512 => verify_mac(HmacSha512::new_from_slice(secret), &message, signature),
+                    _ => Err(Error::UnsupportedAlgorithm(alg.into())),
+                }
+            }
+            Self::Rsa(key) => {
+                // RSASSA-PKCS1-v1_5 + SHA-256（RFC 7518 3.3）
+                let hashed = Sha256::new()
+                    .chain_update(header_b64)
+                    .chain_update(b".")
+                    .chain_update(payload_b64)
+                    .finalize();
+                key.0
+                    .verify(Pkcs1v15Sign::new::<Sha256>(), &hashed, signature)
+                    .map_err(|_| Error::InvalidSignature)
+            }
+            Self::Ec(key) => {
+                // JOSE の署名は r || s の固定長。OpenSSL などが出力する DER（ASN.1）の署名は
+                // 長さが 70〜72 バイトになるため、署名の比較の前に形式の誤りとして拒否する
+                if signature.len() != ES256_SIGNATURE_LEN {
+                    return Err(Error::InvalidSignatureEncoding);
+                }
+                let signature = p256::ecdsa::Signature::from_slice(signature)
+                    .map_err(|_| Error::InvalidSignature)?;
+                let message = [header_b64, ".", payload_b64].concat();
+                p256::ecdsa::VerifyingKey::from(&key.0)
+                    .verify(message.as_bytes(), &signature)
+                    .map_err(|_| Error::InvalidSignature)
+            }
+        }
+    }
+}
+
+/// 共有シークレットで「ヘッダー.ペイロード」に署名する
+///
+/// # Errors
+/// * `Error::UnsupportedAlgorithm` - alg が HMAC_ALGORITHMS 以外の場合
+/// * `Error::InvalidKey` - シークレットが不正な場合
+pub(crate) fn sign_hmac(alg: &str, secret: &[u8], message: &str) -> Result<Vec<u8>, Error> {
+    match alg {
+        HS256 => sign_mac(HmacSha256::new_from_slice(secret), message),
+        HS384 => sign_mac(HmacSha384::new_from_slice(secret), message),
+        HS512 => sign_mac(HmacSha512::new_from_slice(secret), message),
+        _ => Err(Error::UnsupportedAlgorithm(alg.into())),
+    }
+}
+
+/// HMAC を計算する
+fn sign_mac<M: Mac, E>(mac: Result<M, E>, message: &str) -> Result<Vec<u8>, Error> {
+    let mut mac = mac.map_err(|_| Error::InvalidKey)?;
+    mac.update(message.as_bytes());
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+/// HMAC の署名を定数時間で比較する
+fn verify_mac<M: Mac, E>(mac: Result<M, E>, message: &str, signature: &[u8]) -> Result<(), Error> {
+    let mut mac = mac.map_err(|_| Error::InvalidKey)?;
+    mac.update(message.as_bytes());
+    mac.verify_slice(signature)
+        .map_err(|_| Error::InvalidSignature)
+}
@@ -0,0 +1,123 @@
+// =============================================================================
+// jwt/src/lib.rs: 共有 JWT クレートのルートモジュール
+// =============================================================================
+// コア層とエッジ層で共通の JWT 実装。
+//
+// 以前は発行（コア層の jsonwebtoken）と検証（エッジ層の手書きの実装）が別々で、
+// 有効期限の扱いや kid・iss の解釈が少しずつ異なっていた。
+// このクレートに集約し、両方の層が同じコードで署名・検証する。
+//
+// 利用側:
+// - core/crates/application: KeySet::sign / KeySet::verify（kid で鍵を選ぶ）
+// - edge/auth: verify-token（ユーザーの JWT、外部の ID プロバイダーの JWKS）、token-issuer（X-Edge-Token）
+//
+// 検証の規則（decode）:
+// - アルゴリズムは ALLOWED_ALGORITHMS（HS256 / HS384 / HS512 / RS256 / ES256）のうち、Validation で許可したもののみ
+//   （既定は DEFAULT_ALGORITHMS の HS256 / RS256 / ES256、HS384 / HS512 は with_algorithms で許可する）
+//   （"none" は大文字小文字・許可リストの設定にかかわらず UnsecuredAlgorithm で拒否）
+// - alg は鍵の種類（DecodingKey）と一致すること
+//   （HS256 / HS384 / HS512 ⇔ 共有シークレット、RS256 ⇔ RSA 公開鍵、ES256 ⇔ ECDSA P-256 公開鍵）
+// - HMAC の署名は定数時間で比較
+// - sub と exp は必須、exp・nbf・iat（未来に発行されたトークンを拒否）は leeway（既定 60 秒）を考慮
+// - Validation で発行者を指定した場合のみ iss を検証（iss がないトークンも拒否）
+// - Validation で対象者を指定した場合のみ aud を検証（いずれかが一致すること、aud がないトークンも拒否）
+// - Base64URL はパディングなしのみ（Validation::with_lenient_base64 の場合のみ末尾の "=" を許容）
+// - トークン全体（既定 8 KiB）・ヘッダー・署名の長さの上限を超えるトークンはデコードする前に拒否
+//
+// no_std:
+// - alloc のみを使い、wasm32-wasip1 でもビルドできる
+// - 現在時刻は呼び出し側が Validation に渡す
+// =============================================================================
+
+#![no_std]
+
+extern crate alloc;
+
+// =============================================================================
+// モジュール宣言
+// =============================================================================
+
+/// クレームとヘッダー（Claims, Audience, Header, is_uuid）
+mod claims;
+
+/// エラー型（Error）
+mod error;
+
+/// 検証に使う鍵（DecodingKey, RsaPublicKey, EcPublicKey, JwkSet）
+mod key;
+
+/// 署名と検証（encode, decode, decode_header, decode_header_with, decode_issuer_with, Validation）
+mod token;
+
+// =============================================================================
+// 再エクスポート
+// =============================================================================
+
+pub use claims::{Audience, Claims, Header, is_uuid};
+pub use error::Error;
+pub use key::{DecodingKey, EcPublicKey, JwkSet, RsaPublicKey};
+pub use token::{
+    Validation, decode, decode_header, decode_header_with, decode_issuer_with, decode_with_key,
+    encode,
+};
+
+/// 登録済み以外のクレームの型（`Claims::extra`）
+pub use serde_json::{Map, Value};
+
+// =============================================================================
+// 定数
+// =============================================================================
+
+/// 受け付ける署名アルゴリズム
+///
+/// ヘッダーの alg がこれ以外のトークン（"none"、RS512 など）は署名を確認する前に拒否する
+/// （"none" は大文字小文字にかかわらず `Error::UnsecuredAlgorithm`、それ以外は `Error::UnsupportedAlgorithm`）。
+/// 含まれていても、`Validation::with_algorithms` で許可していない alg と、
+/// 検証に使う鍵の種類と異なる alg は拒否する（`DecodingKey::algorithms`）。
+pub const ALLOWED_ALGORITHMS: &[&str] = &["HS256", "HS384", "HS512", "RS256", "ES256"];
+
+/// `Validation::new` で受け付ける署名アルゴリズム
+///
+/// HS384 / HS512 は発行側と検証側を揃えて移行するため、`Validation::with_algorithms` で明示した場合のみ受け付ける。
+pub const DEFAULT_ALGORITHMS: &[&str] = &["HS256", "RS256", "ES256"];
+
+/// 共有シークレットで署名・検証するアルゴリズム（`encode` で発行できるもの）
+pub const HMAC_ALGORITHMS: &[&str] = &["HS256", "HS384", "HS512"];
+
+/// 署名なしのアルゴリズム（RFC 7518 の "none"）
+///
+/// 許可リストの設定にかかわらず拒否する。比較は大文字小文字を区別しない（"None"・"NONE" も拒否）。
+pub const UNSECURED_ALGORITHM: &str = "none";
+
+/// exp / nbf / iat の検証で許容する時計のずれ（秒）
+///
+/// 以前のコア層の実装（jsonwebtoken の既定値）と同じ 60 秒。
+pub const DEFAULT_LEEWAY_SECS: u64 = 60;
+
+/// トークン全体の長さの上限（バイト、`Validation::with_max_token_bytes` で変更できる）
+///
+/// これを超えるトークンは Base64URL をデコードする前に `Error::TooLarge` で拒否する
+/// （数 MB のトークンで、メモリの確保と巨大なメッセージの HMAC を行わない）。
+pub const DEFAULT_MAX_TOKEN_BYTES: usize = 8 * 1024;
+
+/// ヘッダーの Base64URL の長さの上限（バイト）
+///
+/// alg・typ・kid だけのヘッダーは 100 バイト程度。
+pub const MAX_HEADER_BYTES: usize = 1024;
+
+/// 署名の Base64URL の長さの上限（バイト）
+///
+/// RS256 の 4096 ビットの鍵の署名（512 バイト）が 683 バイトになる。
+pub const MAX_SIGNATURE_BYTES: usize = 1024;
+
+/// トークンの種類のクレーム名（`Claims::extra` に入る）
+///
+/// 同じ鍵で署名したアクセストークンとリフレッシュトークンを区別する。
+/// Edge 層は `TOKEN_USE_ACCESS` のトークンのみ API へのリクエストに受け付ける（decode では検証しない）。
+pub const TOKEN_USE_CLAIM: &str = "token_use";
+
+/// アクセストークンの `token_use`（Core 層の AuthService・Edge 層の auth が発行するトークン）
+pub const TOKEN_USE_ACCESS: &str = "access";
+
+/// リフレッシュトークンの `token_use`（リフレッシュのエンドポイントのみが受け付ける）
+pub const TOKEN_USE_REFRESH: &str = "refresh";
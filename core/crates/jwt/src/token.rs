@@ -0,0 +1,1580 @@
+// =============================================================================
+// jwt/src/token.rs: JWT の署名と検証
+// =============================================================================
+// JWT の構造: Base64URL(ヘッダー).Base64URL(ペイロード).Base64URL(署名)
+// 署名は「ヘッダー.ペイロード」の文字列に対する HMAC-SHA256。
+//
+// RS256 の署名は RSASSA-PKCS1-v1_5 + SHA-256、ES256 の署名は ECDSA P-256 + SHA-256
+// （どちらも検証のみ、発行は HS256 / HS384 / HS512 だけ）。
+//
+// 鍵の選択（kid・alg）はこのクレートでは行わない:
+// - コア層の KeySet は decode_header で kid を読み、鍵を選んでから decode を呼ぶ
+// - エッジ層は HS256 のシークレットと RS256 の公開鍵（設定時のみ）を持ち、
+//   ES256 の公開鍵（設定時のみ）を持ち、decode_header の alg で鍵を選んでから decode_with_key を呼ぶ
+//   （JWKS を設定した場合は、kid で JwkSet::find の鍵を選ぶ。
+//   発行者ごとの鍵を設定した場合は、decode_issuer_with の iss で発行者の鍵を選ぶ）
+// =============================================================================
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::key::sign_hmac;
+use crate::{
+    ALLOWED_ALGORITHMS, Claims, DEFAULT_ALGORITHMS, DEFAULT_LEEWAY_SECS, DEFAULT_MAX_TOKEN_BYTES,
+    DecodingKey, Error, HMAC_ALGORITHMS, Header, MAX_HEADER_BYTES, MAX_SIGNATURE_BYTES,
+    UNSECURED_ALGORITHM,
+};
+
+/// JWE の Compact Serialization（RFC 7516 7.1）の部分の数
+const JWE_PARTS: usize = 5;
+
+// =============================================================================
+// Validation
+// =============================================================================
+
+/// 検証の条件
+///
+/// # 使用例
+///
+/// ```
+/// let validation = jwt::Validation::new(1_700_000_000).with_issuer(Some("todo-api"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Validation<'a> {
+    /// 現在時刻（Unix タイムスタンプ秒）
+    now: u64,
+    /// exp / nbf / iat で許容する時計のずれ（秒）
+    leeway: u64,
+    /// 期待する発行者（None の場合は iss を検証しない）
+    issuer: Option<&'a str>,
+    /// 受け付ける対象者（空の場合は aud を検証しない）
+    audiences: &'a [&'a str],
+    /// 受け付けるアルゴリズム（ALLOWED_ALGORITHMS のうち、これに含まれるもののみ、既定は DEFAULT_ALGORITHMS）
+    algorithms: &'a [&'a str],
+    /// パディング（末尾の "="）のある Base64URL を受け付けるか
+    lenient_base64: bool,
+    /// トークン全体の長さの上限（バイト）
+    max_token_bytes: usize,
+}
+
+impl<'a> Validation<'a> {
+    /// 現在時刻を指定して作成する
+    ///
+    /// leeway は DEFAULT_LEEWAY_SECS、発行者・対象者は検証しない、アルゴリズムは DEFAULT_ALGORITHMS、
+    /// Base64URL はパディングなしのみ、トークンの長さの上限は DEFAULT_MAX_TOKEN_BYTES。
+    pub fn new(now: u64) -> Self {
+        Self {
+            now,
+            leeway: DEFAULT_LEEWAY_SECS,
+            issuer: None,
+            audiences: &[],
+            algorithms: DEFAULT_ALGORITHMS,
+            lenient_base64: false,
+            max_token_bytes: DEFAULT_MAX_TOKEN_BYTES,
+        }
+    }
+
+    /// exp / nbf / iat で許容する時計のずれ（秒）を変更する
+    pub fn with_leeway(mut self, leeway: u64) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// 期待する発行者を指定する
+    ///
+    /// Some の場合は iss が一致しないトークンに加え、iss がないトークンも拒否する。
+    pub fn with_issuer(mut self, issuer: Option<&'a str>) -> Self {
+        self.issuer = issuer;
+        self
+    }
+
+    /// 受け付ける対象者を指定する
+    ///
+    /// 空でない場合は aud のいずれかが含まれないトークンに加え、aud がない・空の配列のトークンも拒否する。
+    pub fn with_audiences(mut self, audiences: &'a [&'a str]) -> Self {
+        self.audiences = audiences;
+        self
+    }
+
+    /// 受け付けるアルゴリズムを制限する
+    ///
+    /// ALLOWED_ALGORITHMS に含まれない値（"none" など）を指定しても受け付けない。
+    /// 空の場合はすべてのトークンを拒否する。
+    pub fn with_algorithms(mut self, algorithms: &'a [&'a str]) -> Self {
+        self.algorithms = algorithms;
+        self
+    }
+
+    /// パディングのある Base64URL の部分（ヘッダー・ペイロード・署名）を受け付ける
+    ///
+    /// RFC 7515 の Base64URL はパディングなしだが、"=" を付けるクライアントライブラリがある。
+    /// true の場合、パディングなしでデコードできない部分は末尾の "="（1〜2 文字）を除いて再試行する
+    /// （decode_base64）。署名は受け取ったままのヘッダー・ペイロード（パディングを含む）に対して検証する。
+    pub fn with_lenient_base64(mut self, lenient: bool) -> Self {
+        self.lenient_base64 = lenient;
+        self
+    }
+
+    /// トークン全体の長さの上限（バイト）を変更する
+    ///
+    /// ヘッダー・署名の上限（MAX_HEADER_BYTES・MAX_SIGNATURE_BYTES）は変わらない。
+    pub fn with_max_token_bytes(mut self, max_token_bytes: usize) -> Self {
+        self.max_token_bytes = max_token_bytes;
+        self
+    }
+}
+
+// =============================================================================
+// 署名
+// =============================================================================
+
+/// クレームに署名してトークンを作成する
+///
+/// # Arguments
+/// * `header` - ヘッダー（alg は HMAC_ALGORITHMS のいずれか）
+/// * `claims` - クレーム
+/// * `secret` - 共有シークレット
+///
+/// # Errors
+/// * `Error::UnsupportedAlgorithm` - alg が HS256 / HS384 / HS512 以外の場合（RS256 / ES256 は検証のみ）
+/// * `Error::Encoding` - JSON へのシリアライズに失敗した場合
+pub fn encode(header: &Header, claims: &Claims, secret: &[u8]) -> Result<String, Error> {
+    if !HMAC_ALGORITHMS.contains(&header.alg.as_str()) {
+        return Err(Error::UnsupportedAlgorithm(header.alg.clone()));
+    }
+    let alg = header.alg.as_str();
+
+    let header = serde_json::to_vec(header).map_err(|_| Error::Encoding)?;
+    let payload = serde_json::to_vec(claims).map_err(|_| Error::Encoding)?;
+    let message = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(header),
+        URL_SAFE_NO_PAD.encode(payload)
+    );
+
+    let signature = sign_hmac(alg, secret, &message)?;
+
+    Ok(format!("{}.{}", message, URL_SAFE_NO_PAD.encode(signature)))
+}
+
+// =============================================================================
+// 検証
+// =============================================================================
+
+/// 署名を検証せずにヘッダーを読む（kid で鍵を選ぶため）
+///
+/// # Errors
+/// * `Error::InvalidFormat` / `Error::EncryptedToken` / `Error::InvalidHeaderEncoding` / `Error::InvalidHeaderJson`
+pub fn decode_header(token: &str) -> Result<Header, Error> {
+    decode_header_with(token, &Validation::new(0))
+}
+
+/// Validation の Base64URL の扱い（with_lenient_base64）・長さの上限で、署名を検証せずにヘッダーを読む
+///
+/// 現在時刻・発行者などの条件は使わない。
+///
+/// # Errors
+/// * `Error::TooLarge` / `Error::InvalidFormat` / `Error::EncryptedToken` / `Error::InvalidHeaderEncoding` /
+///   `Error::InvalidHeaderJson`
+pub fn decode_header_with(token: &str, validation: &Validation<'_>) -> Result<Header, Error> {
+    let [header, _, _] = split_within_limits(token, validation.max_token_bytes)?;
+    decode_header_part(header, validation.lenient_base64)
+}
+
+/// Validation の Base64URL の扱い・長さの上限で、署名を検証せずに発行者（iss）を読む
+///
+/// 発行者ごとに鍵が異なる場合に、鍵を選ぶためだけに使う。戻り値は署名を検証する前の値のため信頼せず、
+/// 選んだ鍵と `with_issuer` に同じ発行者を指定した Validation で decode_with_key を呼ぶこと。
+///
+/// # Returns
+/// iss の値（iss がない・文字列でない場合は None）
+///
+/// # Errors
+/// * `Error::TooLarge` / `Error::InvalidFormat` / `Error::EncryptedToken` / `Error::InvalidPayloadEncoding` /
+///   `Error::InvalidPayloadJson`
+pub fn decode_issuer_with(
+    token: &str,
+    validation: &Validation<'_>,
+) -> Result<Option<String>, Error> {
+    let [_, payload, _] = split_within_limits(token, validation.max_token_bytes)?;
+    let payload: Map<String, Value> = decode_part(
+        payload,
+        validation.lenient_base64,
+        validation.max_token_bytes,
+        Error::InvalidPayloadEncoding,
+        Error::InvalidPayloadJson,
+    )?;
+    Ok(payload.get("iss").and_then(Value::as_str).map(String::from))
+}
+
+/// 共有シークレットで署名したトークン（HS256 / HS384 / HS512）を検証し、クレームを返す
+///
+/// `decode_with_key(token, &DecodingKey::Hmac(secret), validation)` と同じ。
+/// Validation の許可リストにない alg（既定では HS384 / HS512）・RS256 などのトークンは
+/// `Error::UnsupportedAlgorithm` で拒否する。
+///
+/// # Arguments
+/// * `token` - JWT
+/// * `secret` - 共有シークレット
+/// * `validation` - 現在時刻・leeway・期待する発行者・受け付ける対象者
+///
+/// # Errors
+/// 検証に失敗した場合（`Error` の各バリアント）
+pub fn decode(token: &str, secret: &[u8], validation: &Validation<'_>) -> Result<Claims, Error> {
+    decode_with_key(token, &DecodingKey::Hmac(secret), validation)
+}
+
+/// 鍵を指定してトークンを検証し、クレームを返す
+///
+/// # 検証手順
+/// 1. トークン全体・ヘッダー・署名が長さの上限以下で（Base64URL をデコードする前に確認する）、
+///    ドットで 3 つの部分に分割できること（5 つの部分の JWE は Base64URL をデコードせずに
+///    `Error::EncryptedToken`、ヘッダーに enc がある場合も同じ）
+/// 2. ヘッダーの alg が "none"（大文字小文字を区別しない）でないこと、
+///    ALLOWED_ALGORITHMS と Validation の許可リストの両方に含まれ、鍵の種類と一致すること
+///    （RS256 の公開鍵を HS256 のシークレットとして使わない）
+/// 3. 署名が一致すること（HS256 / HS384 / HS512 は定数時間で比較、RS256 は RSASSA-PKCS1-v1_5、
+///    ES256 は JOSE の形式（r || s の 64 バイト）の ECDSA 署名）
+/// 4. sub と exp があること
+/// 5. exp + leeway が現在時刻以降であること、nbf - leeway が現在時刻以前であること、
+///    iat - leeway が現在時刻以前であること（validate_time_claims）
+/// 6. 発行者を指定した場合、iss が一致すること
+/// 7. 対象者を指定した場合、aud のいずれかが含まれること
+///
+/// # Arguments
+/// * `token` - JWT
+/// * `key` - 検証に使う鍵（HMAC の共有シークレット、または RS256 / ES256 の公開鍵）
+/// * `validation` - 現在時刻・leeway・期待する発行者・受け付ける対象者
+///
+/// # Errors
+/// 検証に失敗した場合（`Error` の各バリアント）
+pub fn decode_with_key(
+    token: &str,
+    key: &DecodingKey<'_>,
+    validation: &Validation<'_>,
+) -> Result<Claims, Error> {
+    // --------------------------------------------------------
+    // Step 1-2: 形式とアルゴリズム
+    // --------------------------------------------------------
+    let [header_b64, payload_b64, signature_b64] =
+        split_within_limits(token, validation.max_token_bytes)?;
+    let header = decode_header_part(header_b64, validation.lenient_base64)?;
+    check_algorithm(&header.alg, validation.algorithms)?;
+    // 許可リストに含まれていても、鍵の種類と異なるアルゴリズムは受け付けない
+    if !key.algorithms().contains(&header.alg.as_str()) {
+        return Err(Error::UnsupportedAlgorithm(header.alg));
+    }
+
+    // --------------------------------------------------------
+    // Step 3: 署名
+    // --------------------------------------------------------
+    let signature = decode_base64(signature_b64, validation.lenient_base64)
+        .map_err(|_| Error::InvalidSignatureEncoding)?;
+    key.verify(&header.alg, header_b64, payload_b64, &signature)?;
+
+    // --------------------------------------------------------
+    // Step 4: 必須クレーム
+    // --------------------------------------------------------
+    // 欠落と型の誤りを区別するため、一度 JSON のオブジェクトとして読む
+    let payload: Map<String, Value> = decode_part(
+        payload_b64,
+        validation.lenient_base64,
+        validation.max_token_bytes,
+        Error::InvalidPayloadEncoding,
+        Error::InvalidPayloadJson,
+    )?;
+    if !payload.contains_key("sub") {
+        return Err(Error::MissingSubject);
+    }
+    if !payload.contains_key("exp") {
+        return Err(Error::MissingExpiration);
+    }
+
+    // --------------------------------------------------------
+    // Step 5: 有効期間
+    // --------------------------------------------------------
+    validate_time_claims(&payload, validation.now, validation.leeway)?;
+    let claims: Claims =
+        serde_json::from_value(Value::Object(payload)).map_err(|_| Error::InvalidPayloadJson)?;
+
+    // --------------------------------------------------------
+    // Step 6: 発行者
+    // --------------------------------------------------------
+    if let Some(issuer) = validation.issuer
+        && claims.iss.as_deref() != Some(issuer)
+    {
+        return Err(Error::InvalidIssuer);
+    }
+
+    // --------------------------------------------------------
+    // Step 7: 対象者
+    // --------------------------------------------------------
+    if !validation.audiences.is_empty()
+        && !claims
+            .aud
+            .as_ref()
+            .is_some_and(|aud| aud.matches_any(validation.audiences))
+    {
+        return Err(Error::InvalidAudience);
+    }
+
+    Ok(claims)
+}
+
+// =============================================================================
+// 内部ヘルパー
+// =============================================================================
+
+/// トークンをヘッダー・ペイロード・署名に分割する
+///
+/// JWE の Compact Serialization（RFC 7516 7.1、ヘッダー・暗号化した鍵・IV・暗号文・認証タグの 5 つの部分）は
+/// 形式の誤りと区別し、Base64URL をデコードせずに `Error::EncryptedToken` を返す。
+fn split(token: &str) -> Result<[&str; 3], Error> {
+    let parts: Vec<&str> = token.split('.').collect();
+    if parts.len() == JWE_PARTS {
+        return Err(Error::EncryptedToken);
+    }
+    <[&str; 3]>::try_from(parts).map_err(|_| Error::InvalidFormat)
+}
+
+/// 長さの上限を確認してから、トークンをヘッダー・ペイロード・署名に分割する
+///
+/// トークン全体を max_token_bytes、ヘッダーを MAX_HEADER_BYTES、署名を MAX_SIGNATURE_BYTES で制限する
+/// （ペイロードはトークン全体の上限で制限する）。
+fn split_within_limits(token: &str, max_token_bytes: usize) -> Result<[&str; 3], Error> {
+    if token.len() > max_token_bytes {
+        return Err(Error::TooLarge);
+    }
+    let parts = split(token)?;
+    let [header, _, signature] = parts;
+    if header.len() > MAX_HEADER_BYTES || signature.len() > MAX_SIGNATURE_BYTES {
+        return Err(Error::TooLarge);
+    }
+    Ok(parts)
+}
+
+/// Base64URL の部分をデコードして JSON として読む
+///
+/// デコードした JSON が max_json_bytes を超える場合は、serde_json に渡さずに `Error::TooLarge`。
+fn decode_part<T: DeserializeOwned>(
+    part: &str,
+    lenient: bool,
+    max_json_bytes: usize,
+    encoding_error: Error,
+    json_error: Error,
+) -> Result<T, Error> {
+    let json = decode_base64(part, lenient).map_err(|_| encoding_error)?;
+    if json.len() > max_json_bytes {
+        return Err(Error::TooLarge);
+    }
+    serde_json::from_slice(&json).map_err(|_| json_error)
+}
+
+/// ヘッダーの部分をデコードする（enc がある場合は JWE として `Error::EncryptedToken`）
+fn decode_header_part(part: &str, lenient: bool) -> Result<Header, Error> {
+    let header: Header = decode_part(
+        part,
+        lenient,
+        MAX_HEADER_BYTES,
+        Error::InvalidHeaderEncoding,
+        Error::InvalidHeaderJson,
+    )?;
+    if header.enc.is_some() {
+        return Err(Error::EncryptedToken);
+    }
+    Ok(header)
+}
+
+/// Base64URL の部分をデコードする
+///
+/// まずパディングなしでデコードする。失敗し、lenient の場合は末尾の "=" を除いて再試行する。
+/// 除くのはパディングとして正しいもの（1〜2 文字で、パディングを含めた長さが 4 の倍数）のみ。
+fn decode_base64(part: &str, lenient: bool) -> Result<Vec<u8>, base64::DecodeError> {
+    URL_SAFE_NO_PAD.decode(part).or_else(|e| {
+        let unpadded = part.trim_end_matches('=');
+        let padding = part.len() - unpadded.len();
+        if lenient && (1..=2).contains(&padding) && part.len().is_multiple_of(4) {
+            URL_SAFE_NO_PAD.decode(unpadded)
+        } else {
+            Err(e)
+        }
+    })
+}
+
+/// exp / nbf / iat を現在時刻と比較する
+///
+/// どのクレームも leeway（秒）だけ時計のずれを許容する（前後どちらにも同じ値）。
+/// - exp + leeway が現在時刻より前: `Error::Expired`
+/// - nbf - leeway が現在時刻より後: `Error::NotYetValid`（nbf がない場合は確認しない）
+/// - iat - leeway が現在時刻より後: `Error::IssuedInFuture`（時計の誤りか偽造、iat がない場合は確認しない）
+///
+/// exp がない・exp / nbf / iat が 0 以上の整数でない場合は `Error::InvalidPayloadJson`
+/// （exp がないトークンは呼び出し側で `Error::MissingExpiration` として先に拒否する）。
+fn validate_time_claims(payload: &Map<String, Value>, now: u64, leeway: u64) -> Result<(), Error> {
+    let time_claim = |name: &str| match payload.get(name) {
+        None => Ok(None),
+        Some(value) => value.as_u64().map(Some).ok_or(Error::InvalidPayloadJson),
+    };
+    let exp = time_claim("exp")?.ok_or(Error::InvalidPayloadJson)?;
+    let nbf = time_claim("nbf")?;
+    let iat = time_claim("iat")?;
+
+    if exp.saturating_add(leeway) < now {
+        return Err(Error::Expired);
+    }
+    let latest = now.saturating_add(leeway);
+    if nbf.is_some_and(|nbf| nbf > latest) {
+        return Err(Error::NotYetValid);
+    }
+    if iat.is_some_and(|iat| iat > latest) {
+        return Err(Error::IssuedInFuture);
+    }
+    Ok(())
+}
+
+/// alg が許可リスト（ALLOWED_ALGORITHMS と Validation の許可リストの両方）に含まれるか確認する
+///
+/// "none" は大文字小文字・前後の空白にかかわらず、許可リストより先に `Error::UnsecuredAlgorithm` で拒否する。
+/// それ以外の alg は RFC 7515 のとおり大文字小文字を区別して比較する（"hs256" は受け付けない）。
+fn check_algorithm(alg: &str, allowed: &[&str]) -> Result<(), Error> {
+    if alg.trim().eq_ignore_ascii_case(UNSECURED_ALGORITHM) {
+        return Err(Error::UnsecuredAlgorithm(alg.into()));
+    }
+    if ALLOWED_ALGORITHMS.contains(&alg) && allowed.contains(&alg) {
+        Ok(())
+    } else {
+        Err(Error::UnsupportedAlgorithm(alg.into()))
+    }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key::HmacSha256;
+    use crate::{EcPublicKey, JwkSet, RsaPublicKey};
+    use alloc::string::ToString;
+    use alloc::vec;
+    use hmac::Mac;
+
+    const SECRET: &[u8] = b"shared-test-secret";
+    const NOW: u64 = 1_700_000_000;
+
+    fn claims(sub: &str, exp: u64) -> Claims {
+        Claims {
+            sub: sub.to_string(),
+            exp,
+            iat: NOW,
+            iss: None,
+            aud: None,
+            extra: Map::new(),
+        }
+    }
+
+    /// 任意のヘッダー・ペイロードに SECRET で署名する（不正なトークンの作成用）
+    fn raw_token(header: &str, payload: &str) -> String {
+        let message = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header),
+            URL_SAFE_NO_PAD.encode(payload)
+        );
+        let mut mac = HmacSha256::new_from_slice(SECRET).unwrap();
+        mac.update(message.as_bytes());
+        format!(
+            "{}.{}",
+            message,
+            URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+        )
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let mut original = claims("user-1", NOW + 3600);
+        original.extra.insert("tenant_id".to_string(), "t-1".into());
+        let token = encode(&Header::hs256(Some("k1")), &original, SECRET).unwrap();
+
+        assert_eq!(decode_header(&token).unwrap().kid.as_deref(), Some("k1"));
+        assert_eq!(decode(&token, SECRET, &Validation::new(NOW)), Ok(original));
+        assert_eq!(
+            decode(&token, b"other-secret", &Validation::new(NOW)),
+            Err(Error::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_hs384_and_hs512_round_trip() {
+        let original = claims("user-1", NOW + 3600);
+        for (alg, signature_len) in [("HS384", 48), ("HS512", 64)] {
+            let token = encode(&Header::new(alg, Some("k1")), &original, SECRET).unwrap();
+            assert_eq!(decode_header(&token).unwrap().alg, alg);
+            let signature = URL_SAFE_NO_PAD
+                .decode(token.rsplit('.').next().unwrap())
+                .unwrap();
+            assert_eq!(signature.len(), signature_len);
+
+            let validation = Validation::new(NOW).with_algorithms(&["HS256", alg]);
+            assert_eq!(decode(&token, SECRET, &validation), Ok(original.clone()));
+            assert_eq!(
+                decode(&token, b"other-secret", &validation),
+                Err(Error::InvalidSignature)
+            );
+
+            // 既定（HS256 / RS256 / ES256）と HS256 のみの許可リストでは、署名を確認する前に拒否する
+            for validation in [
+                Validation::new(NOW),
+                Validation::new(NOW).with_algorithms(&["HS256"]),
+            ] {
+                assert_eq!(
+                    decode(&token, SECRET, &validation),
+                    Err(Error::UnsupportedAlgorithm(alg.to_string()))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_signature_of_other_hmac_algorithm() {
+        // HS256 の署名のまま alg だけを HS512 に書き換えたトークンは署名が一致しない
+        let token = raw_token(r#"{"alg":"HS512"}"#, r#"{"sub":"user-1","exp":4102444800}"#);
+        let validation = Validation::new(NOW).with_algorithms(HMAC_ALGORITHMS);
+        assert_eq!(
+            decode(&token, SECRET, &validation),
+            Err(Error::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_rejects_algorithms_outside_allowlist() {
+        let payload = r#"{"sub":"user-1","exp":4102444800}"#;
+        // HS512 は既定では許可しない、RS256 は許可リストに含まれるが HS256 のシークレットでは検証しない
+        for alg in ["HS512", "RS256"] {
+            let token = raw_token(&format!(r#"{{"alg":"{}"}}"#, alg), payload);
+            assert_eq!(
+                decode(&token, SECRET, &Validation::new(NOW)),
+                Err(Error::UnsupportedAlgorithm(alg.to_string()))
+            );
+        }
+
+        // 署名部分が空の "none" トークンも署名の確認前に拒否する
+        let unsigned = format!(
+            "{}.{}.",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"none"}"#),
+            URL_SAFE_NO_PAD.encode(payload)
+        );
+        assert_eq!(
+            decode(&unsigned, SECRET, &Validation::new(NOW)),
+            Err(Error::UnsecuredAlgorithm("none".to_string()))
+        );
+        let mut header = Header::hs256(None);
+        header.alg = "none".to_string();
+        assert!(encode(&header, &claims("user-1", NOW), SECRET).is_err());
+    }
+
+    #[test]
+    fn test_rejects_none_regardless_of_case() {
+        let payload = r#"{"sub":"user-1","exp":4102444800}"#;
+        for alg in ["none", "None", "nOnE", "NONE", " none "] {
+            let token = raw_token(&format!(r#"{{"alg":"{}"}}"#, alg), payload);
+            assert_eq!(
+                decode(&token, SECRET, &Validation::new(NOW)),
+                Err(Error::UnsecuredAlgorithm(alg.to_string()))
+            );
+        }
+
+        // none 以外の alg は大文字小文字を区別する。空の alg も許可リストにない
+        for alg in ["hs256", "Hs256", ""] {
+            let token = raw_token(&format!(r#"{{"alg":"{}"}}"#, alg), payload);
+            assert_eq!(
+                decode(&token, SECRET, &Validation::new(NOW)),
+                Err(Error::UnsupportedAlgorithm(alg.to_string()))
+            );
+        }
+    }
+
+    /// 標準の Base64URL（パディングあり）で、"=" が padding 個になるよう JSON の末尾に空白を足してエンコードする
+    fn padded(json: &str, padding: usize) -> String {
+        use base64::engine::general_purpose::URL_SAFE;
+
+        let mut json = json.to_string();
+        while (3 - json.len() % 3) % 3 != padding {
+            json.push(' ');
+        }
+        let encoded = URL_SAFE.encode(json);
+        assert!(encoded.ends_with(&"=".repeat(padding)));
+        encoded
+    }
+
+    /// エンコード済みのヘッダー・ペイロードに SECRET で署名する（pad_signature の場合は署名にもパディングを付ける）
+    fn hs256_with_segments(header_b64: &str, payload_b64: &str, pad_signature: bool) -> String {
+        use base64::engine::general_purpose::URL_SAFE;
+
+        let message = format!("{}.{}", header_b64, payload_b64);
+        let mut mac = HmacSha256::new_from_slice(SECRET).unwrap();
+        mac.update(message.as_bytes());
+        let signature = mac.finalize().into_bytes();
+        let signature = if pad_signature {
+            URL_SAFE.encode(signature)
+        } else {
+            URL_SAFE_NO_PAD.encode(signature)
+        };
+        format!("{}.{}", message, signature)
+    }
+
+    #[test]
+    fn test_padded_segments_are_rejected_by_default() {
+        let header = r#"{"alg":"HS256"}"#;
+        let payload = r#"{"sub":"user-1","exp":4102444800}"#;
+        let strict = Validation::new(NOW);
+
+        for padding in [1, 2] {
+            let token = hs256_with_segments(
+                &padded(header, padding),
+                &URL_SAFE_NO_PAD.encode(payload),
+                false,
+            );
+            assert_eq!(
+                decode(&token, SECRET, &strict),
+                Err(Error::InvalidHeaderEncoding)
+            );
+            assert_eq!(decode_header(&token), Err(Error::InvalidHeaderEncoding));
+
+            let token = hs256_with_segments(
+                &URL_SAFE_NO_PAD.encode(header),
+                &padded(payload, padding),
+                false,
+            );
+            assert_eq!(
+                decode(&token, SECRET, &strict),
+                Err(Error::InvalidPayloadEncoding)
+            );
+        }
+
+        // HS256 の署名（32 バイト）は "=" が 1 つ
+        let token = hs256_with_segments(
+            &URL_SAFE_NO_PAD.encode(header),
+            &URL_SAFE_NO_PAD.encode(payload),
+            true,
+        );
+        assert!(token.ends_with('='));
+        assert_eq!(
+            decode(&token, SECRET, &strict),
+            Err(Error::InvalidSignatureEncoding)
+        );
+    }
+
+    #[test]
+    fn test_lenient_base64_accepts_single_and_double_padding() {
+        let header = r#"{"alg":"HS256"}"#;
+        let payload = r#"{"sub":"user-1","exp":4102444800}"#;
+        let lenient = Validation::new(NOW).with_lenient_base64(true);
+
+        for padding in [1, 2] {
+            // 署名は受け取ったままの（パディングを含む）ヘッダー・ペイロードに対して検証する
+            let token = hs256_with_segments(
+                &padded(header, padding),
+                &URL_SAFE_NO_PAD.encode(payload),
+                false,
+            );
+            assert_eq!(decode(&token, SECRET, &lenient).unwrap().sub, "user-1");
+            assert_eq!(decode_header_with(&token, &lenient).unwrap().alg, "HS256");
+
+            let token = hs256_with_segments(
+                &URL_SAFE_NO_PAD.encode(header),
+                &padded(payload, padding),
+                false,
+            );
+            assert_eq!(decode(&token, SECRET, &lenient).unwrap().sub, "user-1");
+
+            // パディングを除いたトークンは署名が一致しない（署名の対象が異なる）
+            let stripped = token.replace('=', "");
+            assert_eq!(
+                decode(&stripped, SECRET, &lenient),
+                Err(Error::InvalidSignature)
+            );
+        }
+
+        // 署名: HS256（32 バイト）は "=" が 1 つ、ES256（64 バイト）は "=" が 2 つ
+        let token = hs256_with_segments(
+            &URL_SAFE_NO_PAD.encode(header),
+            &URL_SAFE_NO_PAD.encode(payload),
+            true,
+        );
+        assert_eq!(decode(&token, SECRET, &lenient).unwrap().sub, "user-1");
+
+        let message = es256_message(payload);
+        let signature = es256_signature(&message).to_bytes();
+        let token = format!(
+            "{}.{}",
+            message,
+            base64::engine::general_purpose::URL_SAFE.encode(signature)
+        );
+        assert!(token.ends_with("=="));
+        let key = EcPublicKey::from_pem(ES256_PUBLIC_KEY).unwrap();
+        assert_eq!(
+            decode_with_key(&token, &DecodingKey::Ec(&key), &Validation::new(NOW)),
+            Err(Error::InvalidSignatureEncoding)
+        );
+        assert!(decode_with_key(&token, &DecodingKey::Ec(&key), &lenient).is_ok());
+    }
+
+    #[test]
+    fn test_lenient_base64_rejects_invalid_padding() {
+        let lenient = Validation::new(NOW).with_lenient_base64(true);
+        let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256"}"#);
+        let payload = URL_SAFE_NO_PAD.encode(r#"{"sub":"user-1","exp":4102444800}"#);
+
+        // 3 文字以上の "="・長さが 4 の倍数にならない "=" はパディングとして扱わない
+        for suffix in ["===", "=", "===="] {
+            let token = hs256_with_segments(&format!("{}{}", header, suffix), &payload, false);
+            assert_eq!(
+                decode(&token, SECRET, &lenient),
+                Err(Error::InvalidHeaderEncoding),
+                "{}",
+                suffix
+            );
+        }
+    }
+
+    /// RFC 7516 付録 A.3 の JWE（A128KW / A128CBC-HS256）
+    const JWE: &str = concat!(
+        "eyJhbGciOiJBMTI4S1ciLCJlbmMiOiJBMTI4Q0JDLUhTMjU2In0.",
+        "6KB707dM9YTIgHtLvtgWQ8mKwboJW3of9locizkDTHzBC2IlrT1oOQ.",
+        "AxY8DCtDaGlsbGljb3RoZQ.",
+        "KDlTtXchhZTGufMYmOYGS4HffxPSUrfmqCHXaI9wOGY.",
+        "U0m_YmjN04DJvceFICbCVQ"
+    );
+
+    #[test]
+    fn test_rejects_encrypted_tokens() {
+        let validation = Validation::new(NOW);
+        assert_eq!(decode(JWE, SECRET, &validation), Err(Error::EncryptedToken));
+        assert_eq!(decode_header(JWE), Err(Error::EncryptedToken));
+        assert_eq!(
+            decode_issuer_with(JWE, &validation),
+            Err(Error::EncryptedToken)
+        );
+        assert_eq!(
+            Error::EncryptedToken.to_string(),
+            "Encrypted tokens (JWE) are not supported"
+        );
+
+        // 5 つの部分は Base64URL として不正でもデコードせずに判定する
+        assert_eq!(
+            decode("!.!.!.!.!", SECRET, &validation),
+            Err(Error::EncryptedToken)
+        );
+        // 5 つ以外の部分の数は形式の誤り
+        for token in ["a.b.c.d", "a.b.c.d.e.f"] {
+            assert_eq!(
+                decode(token, SECRET, &validation),
+                Err(Error::InvalidFormat)
+            );
+        }
+
+        // 3 つの部分でも、ヘッダーに enc がある場合は JWE として扱う
+        let token = raw_token(
+            r#"{"alg":"HS256","enc":"A256GCM"}"#,
+            r#"{"sub":"user-1","exp":4102444800}"#,
+        );
+        assert_eq!(decode_header(&token), Err(Error::EncryptedToken));
+        assert_eq!(
+            decode(&token, SECRET, &validation),
+            Err(Error::EncryptedToken)
+        );
+    }
+
+    #[test]
+    fn test_rejects_oversized_tokens_before_decoding() {
+        let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256"}"#);
+
+        // ペイロードが大きいトークン（8 KiB を超える）は署名を確認する前に拒否する
+        let large = format!(
+            r#"{{"sub":"user-1","exp":4102444800,"pad":"{}"}}"#,
+            "x".repeat(DEFAULT_MAX_TOKEN_BYTES)
+        );
+        let token = raw_token(r#"{"alg":"HS256"}"#, &large);
+        assert!(token.len() > DEFAULT_MAX_TOKEN_BYTES);
+        assert_eq!(
+            decode(&token, SECRET, &Validation::new(NOW)),
+            Err(Error::TooLarge)
+        );
+        assert_eq!(decode_header(&token), Err(Error::TooLarge));
+        // 上限を変更した場合は受け付ける
+        let relaxed = Validation::new(NOW).with_max_token_bytes(64 * 1024);
+        assert_eq!(decode(&token, SECRET, &relaxed).unwrap().sub, "user-1");
+
+        // 上限以下でも、Base64URL として不正な巨大な署名は上限で拒否する（デコードしない）
+        let absurd = format!(
+            "{}.{}.{}",
+            header,
+            URL_SAFE_NO_PAD.encode(r#"{"sub":"user-1","exp":4102444800}"#),
+            "!".repeat(4 * 1024 * 1024)
+        );
+        assert_eq!(
+            decode(&absurd, SECRET, &Validation::new(NOW)),
+            Err(Error::TooLarge)
+        );
+        let unlimited = Validation::new(NOW).with_max_token_bytes(usize::MAX);
+        assert_eq!(decode(&absurd, SECRET, &unlimited), Err(Error::TooLarge));
+
+        // ヘッダーの上限
+        let kid = "k".repeat(MAX_HEADER_BYTES);
+        let token = raw_token(
+            &format!(r#"{{"alg":"HS256","kid":"{}"}}"#, kid),
+            r#"{"sub":"user-1","exp":4102444800}"#,
+        );
+        assert_eq!(decode_header_with(&token, &unlimited), Err(Error::TooLarge));
+        assert_eq!(decode(&token, SECRET, &unlimited), Err(Error::TooLarge));
+    }
+
+    #[test]
+    fn test_decoded_json_size_is_bounded() {
+        let payload = URL_SAFE_NO_PAD.encode(r#"{"sub":"user-1"}"#);
+        assert_eq!(
+            decode_part::<Map<String, Value>>(
+                &payload,
+                false,
+                8,
+                Error::InvalidPayloadEncoding,
+                Error::InvalidPayloadJson
+            ),
+            Err(Error::TooLarge)
+        );
+        assert!(
+            decode_part::<Map<String, Value>>(
+                &payload,
+                false,
+                16,
+                Error::InvalidPayloadEncoding,
+                Error::InvalidPayloadJson
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_expiry_and_not_before_use_leeway() {
+        let token = encode(&Header::hs256(None), &claims("user-1", NOW), SECRET).unwrap();
+
+        // exp + leeway（60 秒）までは有効
+        assert!(decode(&token, SECRET, &Validation::new(NOW + 60)).is_ok());
+        assert_eq!(
+            decode(&token, SECRET, &Validation::new(NOW + 61)),
+            Err(Error::Expired)
+        );
+        assert_eq!(
+            decode(&token, SECRET, &Validation::new(NOW + 1).with_leeway(0)),
+            Err(Error::Expired)
+        );
+
+        let not_before = raw_token(
+            r#"{"alg":"HS256"}"#,
+            &format!(
+                r#"{{"sub":"user-1","exp":{},"nbf":{}}}"#,
+                NOW + 3600,
+                NOW + 120
+            ),
+        );
+        assert_eq!(
+            decode(&not_before, SECRET, &Validation::new(NOW)),
+            Err(Error::NotYetValid)
+        );
+        assert!(decode(&not_before, SECRET, &Validation::new(NOW + 60)).is_ok());
+    }
+
+    /// exp / nbf / iat と leeway の境界（前後どちらにも同じ leeway を許容する）
+    #[test]
+    fn test_validate_time_claims() {
+        let check = |payload: &str, leeway: u64| {
+            let payload: Map<String, Value> = serde_json::from_str(payload).unwrap();
+            validate_time_claims(&payload, NOW, leeway)
+        };
+        let exp = |offset: i64| format!(r#"{{"exp":{}}}"#, NOW.saturating_add_signed(offset));
+        let with = |name: &str, offset: i64| {
+            format!(
+                r#"{{"exp":{},"{}":{}}}"#,
+                NOW + 3600,
+                name,
+                NOW.saturating_add_signed(offset)
+            )
+        };
+
+        // (ペイロード, leeway, 期待する結果)
+        let cases = [
+            // exp + leeway が現在時刻以降なら有効
+            (exp(3600), 0, Ok(())),
+            (exp(0), 0, Ok(())),
+            (exp(-1), 0, Err(Error::Expired)),
+            (exp(-30), 30, Ok(())),
+            (exp(-31), 30, Err(Error::Expired)),
+            (exp(-(NOW as i64)), 30, Err(Error::Expired)),
+            // nbf - leeway が現在時刻以前なら有効
+            (with("nbf", 0), 0, Ok(())),
+            (with("nbf", 1), 0, Err(Error::NotYetValid)),
+            (with("nbf", 30), 30, Ok(())),
+            (with("nbf", 31), 30, Err(Error::NotYetValid)),
+            (with("nbf", -86_400), 0, Ok(())),
+            // iat - leeway が現在時刻以前なら有効（未来に発行されたトークンは拒否）
+            (with("iat", 0), 0, Ok(())),
+            (with("iat", 1), 0, Err(Error::IssuedInFuture)),
+            (with("iat", 30), 30, Ok(())),
+            (with("iat", 31), 30, Err(Error::IssuedInFuture)),
+            (with("iat", 86_400), 300, Err(Error::IssuedInFuture)),
+            (with("iat", -86_400), 0, Ok(())),
+            (with("iat", -(NOW as i64)), 0, Ok(())),
+        ];
+        for (payload, leeway, expected) in cases {
+            assert_eq!(
+                check(&payload, leeway),
+                expected,
+                "{} leeway={}",
+                payload,
+                leeway
+            );
+        }
+
+        // leeway が大きくても桁あふれしない
+        assert_eq!(check(&exp(0), u64::MAX), Ok(()));
+        assert_eq!(check(&with("iat", 86_400), u64::MAX), Ok(()));
+
+        // 期限切れを先に確認する
+        let expired_and_future = format!(
+            r#"{{"exp":{},"nbf":{},"iat":{}}}"#,
+            NOW - 1,
+            NOW + 1,
+            NOW + 1
+        );
+        assert_eq!(check(&expired_and_future, 0), Err(Error::Expired));
+        let future = format!(
+            r#"{{"exp":{},"nbf":{},"iat":{}}}"#,
+            NOW + 60,
+            NOW + 1,
+            NOW + 1
+        );
+        assert_eq!(check(&future, 0), Err(Error::NotYetValid));
+
+        // exp がない・数値でない・負数・小数
+        for payload in [
+            r#"{}"#,
+            r#"{"exp":"tomorrow"}"#,
+            r#"{"exp":-1}"#,
+            r#"{"exp":4102444800.5}"#,
+            r#"{"exp":4102444800,"nbf":"now"}"#,
+            r#"{"exp":4102444800,"iat":null}"#,
+            r#"{"exp":4102444800,"iat":-1}"#,
+        ] {
+            assert_eq!(
+                check(payload, 0),
+                Err(Error::InvalidPayloadJson),
+                "{}",
+                payload
+            );
+        }
+    }
+
+    /// 発行日時が未来のトークン（時計の誤りか偽造）は leeway を超えると拒否する
+    #[test]
+    fn test_rejects_token_issued_in_the_future() {
+        let mut future = claims("user-1", NOW + 3600);
+        future.iat = NOW + 120;
+        let token = encode(&Header::hs256(None), &future, SECRET).unwrap();
+
+        assert_eq!(
+            decode(&token, SECRET, &Validation::new(NOW)),
+            Err(Error::IssuedInFuture)
+        );
+        assert_eq!(
+            Error::IssuedInFuture.to_string(),
+            "Token issued in the future"
+        );
+        assert!(decode(&token, SECRET, &Validation::new(NOW + 60)).is_ok());
+        assert!(decode(&token, SECRET, &Validation::new(NOW).with_leeway(120)).is_ok());
+    }
+
+    #[test]
+    fn test_missing_and_mistyped_claims() {
+        let validation = Validation::new(NOW);
+        let decode_payload = |payload: &str| {
+            decode(
+                &raw_token(r#"{"alg":"HS256"}"#, payload),
+                SECRET,
+                &validation,
+            )
+        };
+
+        assert_eq!(
+            decode_payload(r#"{"exp":4102444800}"#),
+            Err(Error::MissingSubject)
+        );
+        assert_eq!(
+            decode_payload(r#"{"sub":"user-1"}"#),
+            Err(Error::MissingExpiration)
+        );
+        assert_eq!(
+            decode_payload(r#"{"sub":"user-1","exp":"tomorrow"}"#),
+            Err(Error::InvalidPayloadJson)
+        );
+        assert_eq!(decode_payload("[]"), Err(Error::InvalidPayloadJson));
+        assert_eq!(
+            decode("a.b", SECRET, &validation),
+            Err(Error::InvalidFormat)
+        );
+
+        // iat がないトークンは 0、登録済み以外のクレームは型を問わない
+        let claims =
+            decode_payload(r#"{"sub":"user-1","exp":4102444800,"roles":"admin","tenant_id":42}"#)
+                .unwrap();
+        assert_eq!(claims.iat, 0);
+        assert!(claims.roles().is_empty());
+        assert_eq!(claims.claim_str("tenant_id").as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn test_issuer_is_checked_only_when_expected() {
+        let mut with_issuer = claims("user-1", NOW + 3600);
+        with_issuer.iss = Some("todo-api".to_string());
+        let issued = encode(&Header::hs256(None), &with_issuer, SECRET).unwrap();
+        let anonymous =
+            encode(&Header::hs256(None), &claims("user-1", NOW + 3600), SECRET).unwrap();
+
+        let expect = |issuer| Validation::new(NOW).with_issuer(Some(issuer));
+        assert!(decode(&issued, SECRET, &expect("todo-api")).is_ok());
+        assert_eq!(
+            decode(&issued, SECRET, &expect("other")),
+            Err(Error::InvalidIssuer)
+        );
+        // iss がないトークンは、発行者を指定した検証では拒否する
+        assert_eq!(
+            decode(&anonymous, SECRET, &expect("todo-api")),
+            Err(Error::InvalidIssuer)
+        );
+        assert!(decode(&anonymous, SECRET, &Validation::new(NOW)).is_ok());
+    }
+
+    #[test]
+    fn test_decode_issuer_with_does_not_verify_signature() {
+        let mut with_issuer = claims("user-1", NOW + 3600);
+        with_issuer.iss = Some("tenant-a".to_string());
+        // 別のシークレットで署名したトークン・期限切れのトークンでも iss を読める（鍵を選ぶ前のため）
+        let issued = encode(&Header::hs256(None), &with_issuer, b"other-secret").unwrap();
+        with_issuer.exp = NOW - 3600;
+        let expired = encode(&Header::hs256(None), &with_issuer, SECRET).unwrap();
+        for token in [&issued, &expired] {
+            assert_eq!(
+                decode_issuer_with(token, &Validation::new(NOW)),
+                Ok(Some("tenant-a".to_string()))
+            );
+        }
+
+        // iss がない・文字列でない場合は None
+        let anonymous =
+            encode(&Header::hs256(None), &claims("user-1", NOW + 3600), SECRET).unwrap();
+        let numeric = raw_token(
+            r#"{"alg":"HS256","typ":"JWT"}"#,
+            r#"{"sub":"user-1","exp":4102444800,"iss":42}"#,
+        );
+        for token in [&anonymous, &numeric] {
+            assert_eq!(decode_issuer_with(token, &Validation::new(NOW)), Ok(None));
+        }
+
+        // 形式・長さの確認は decode_with_key と同じ
+        assert_eq!(
+            decode_issuer_with("a.b", &Validation::new(NOW)),
+            Err(Error::InvalidFormat)
+        );
+        assert_eq!(
+            decode_issuer_with("e30.!!!.c2ln", &Validation::new(NOW)),
+            Err(Error::InvalidPayloadEncoding)
+        );
+        assert_eq!(
+            decode_issuer_with(&issued, &Validation::new(NOW).with_max_token_bytes(16)),
+            Err(Error::TooLarge)
+        );
+    }
+
+    #[test]
+    fn test_audience_is_checked_only_when_expected() {
+        let with_aud = |aud: &str| {
+            raw_token(
+                r#"{"alg":"HS256"}"#,
+                &format!(r#"{{"sub":"user-1","exp":4102444800,"aud":{}}}"#, aud),
+            )
+        };
+        let allowed = ["todo-api", "todo-edge"];
+        let expect = Validation::new(NOW).with_audiences(&allowed);
+
+        // 文字列・配列のどちらも、いずれかが一致すれば受け付ける
+        for aud in [r#""todo-edge""#, r#"["partner","todo-api"]"#] {
+            assert!(decode(&with_aud(aud), SECRET, &expect).is_ok(), "{}", aud);
+        }
+        for aud in [r#""partner""#, r#"["partner"]"#, "[]"] {
+            assert_eq!(
+                decode(&with_aud(aud), SECRET, &expect),
+                Err(Error::InvalidAudience),
+                "{}",
+                aud
+            );
+        }
+        // aud がないトークンは、対象者を指定した検証では拒否する
+        let anonymous =
+            encode(&Header::hs256(None), &claims("user-1", NOW + 3600), SECRET).unwrap();
+        assert_eq!(
+            decode(&anonymous, SECRET, &expect),
+            Err(Error::InvalidAudience)
+        );
+        assert!(decode(&anonymous, SECRET, &Validation::new(NOW)).is_ok());
+        assert!(decode(&with_aud("[]"), SECRET, &Validation::new(NOW)).is_ok());
+    }
+
+    /// コア層が発行するトークン（kid・iss・roles 付き）をエッジ層の検証（発行者なし）で読める
+    #[test]
+    fn test_core_issued_token_verifies_on_edge_path() {
+        let mut core = claims("7f1c0d2e-0000-4000-8000-000000000001", NOW + 3600);
+        core.iss = Some("todo-api".to_string());
+        core.extra
+            .insert("roles".to_string(), vec![Value::from("admin")].into());
+        let token = encode(&Header::hs256(Some("default")), &core, SECRET).unwrap();
+
+        let verified = decode(&token, SECRET, &Validation::new(NOW)).unwrap();
+        assert_eq!(verified.sub, core.sub);
+        assert_eq!(verified.roles(), vec!["admin".to_string()]);
+    }
+
+    /// エッジ層が発行するサービス用トークン（iss なし、request_id 付き）をコア層の検証で読める
+    #[test]
+    fn test_edge_issued_token_verifies_on_core_path() {
+        let mut edge = claims("edge-gateway", NOW + 60);
+        edge.extra
+            .insert("request_id".to_string(), Value::from("req-1"));
+        let token = encode(&Header::hs256(Some("default")), &edge, SECRET).unwrap();
+
+        // コア層は kid で鍵を選んでから検証する（X-Edge-Token は発行者を検証しない）
+        assert_eq!(
+            decode_header(&token).unwrap().kid.as_deref(),
+            Some("default")
+        );
+        let verified = decode(&token, SECRET, &Validation::new(NOW)).unwrap();
+        assert_eq!(verified.sub, "edge-gateway");
+        assert_eq!(verified.claim_str("request_id").as_deref(), Some("req-1"));
+    }
+
+    /// testdata/rs256 の秘密鍵で RS256 のトークンに署名する
+    fn rs256_token(payload: &str) -> String {
+        use rsa::pkcs8::DecodePrivateKey;
+        use sha2::{Digest, Sha256};
+
+        let private_key = rsa::RsaPrivateKey::from_pkcs8_pem(RS256_PRIVATE_KEY).unwrap();
+        let message = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"RS256","typ":"JWT"}"#),
+            URL_SAFE_NO_PAD.encode(payload)
+        );
+        let signature = private_key
+            .sign(
+                rsa::Pkcs1v15Sign::new::<Sha256>(),
+                &Sha256::digest(message.as_bytes()),
+            )
+            .unwrap();
+        format!("{}.{}", message, URL_SAFE_NO_PAD.encode(signature))
+    }
+
+    const RS256_PRIVATE_KEY: &str = include_str!("../testdata/rs256/private.pem");
+    const RS256_PUBLIC_KEY: &str = include_str!("../testdata/rs256/public.pem");
+
+    #[test]
+    fn test_rs256_verifies_with_public_key() {
+        let public_key = RsaPublicKey::from_pem(RS256_PUBLIC_KEY).unwrap();
+        let key = DecodingKey::Rsa(&public_key);
+        let token = rs256_token(r#"{"sub":"user-1","exp":4102444800,"iss":"idp"}"#);
+
+        let claims = decode_with_key(&token, &key, &Validation::new(NOW)).unwrap();
+        assert_eq!(claims.sub, "user-1");
+        assert_eq!(claims.iss.as_deref(), Some("idp"));
+
+        // ペイロードを書き換えたトークンは署名が一致しない
+        let [header, _, signature] = split(&token).unwrap();
+        let tampered = format!(
+            "{}.{}.{}",
+            header,
+            URL_SAFE_NO_PAD.encode(r#"{"sub":"admin","exp":4102444800,"iss":"idp"}"#),
+            signature
+        );
+        assert_eq!(
+            decode_with_key(&tampered, &key, &Validation::new(NOW)),
+            Err(Error::InvalidSignature)
+        );
+
+        // 有効期限などの検証は HS256 と同じ
+        let expired = rs256_token(&format!(r#"{{"sub":"user-1","exp":{}}}"#, NOW - 61));
+        assert_eq!(
+            decode_with_key(&expired, &key, &Validation::new(NOW)),
+            Err(Error::Expired)
+        );
+    }
+
+    /// アルゴリズム混同攻撃: 公開鍵の PEM を HS256 のシークレットとして署名したトークン
+    #[test]
+    fn test_rejects_algorithm_and_key_type_mismatch() {
+        let public_key = RsaPublicKey::from_pem(RS256_PUBLIC_KEY).unwrap();
+        let payload = r#"{"sub":"admin","exp":4102444800}"#;
+        let message = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#),
+            URL_SAFE_NO_PAD.encode(payload)
+        );
+        let mut mac = HmacSha256::new_from_slice(RS256_PUBLIC_KEY.as_bytes()).unwrap();
+        mac.update(message.as_bytes());
+        let forged = format!(
+            "{}.{}",
+            message,
+            URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+        );
+
+        // RSA 公開鍵では HS256 のトークンを検証しない（公開鍵を HMAC のシークレットにしない）
+        assert_eq!(
+            decode_with_key(
+                &forged,
+                &DecodingKey::Rsa(&public_key),
+                &Validation::new(NOW)
+            ),
+            Err(Error::UnsupportedAlgorithm("HS256".to_string()))
+        );
+        // HS256 のシークレットでは署名が一致しない
+        assert_eq!(
+            decode(&forged, SECRET, &Validation::new(NOW)),
+            Err(Error::InvalidSignature)
+        );
+        // 逆に、RS256 のトークンは HS256 のシークレットでは検証しない
+        assert_eq!(
+            decode(&rs256_token(payload), SECRET, &Validation::new(NOW)),
+            Err(Error::UnsupportedAlgorithm("RS256".to_string()))
+        );
+        // RS256 は検証のみ（発行は HMAC だけ）
+        let mut header = Header::hs256(None);
+        header.alg = "RS256".to_string();
+        assert_eq!(
+            encode(&header, &claims("user-1", NOW), SECRET),
+            Err(Error::UnsupportedAlgorithm("RS256".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_public_key_from_pem() {
+        use rsa::pkcs1::{EncodeRsaPublicKey, LineEnding};
+        use rsa::pkcs8::DecodePrivateKey;
+
+        // PKCS#1（BEGIN RSA PUBLIC KEY）も受け付ける
+        let spki = RsaPublicKey::from_pem(RS256_PUBLIC_KEY).unwrap();
+        let pkcs1 = rsa::RsaPrivateKey::from_pkcs8_pem(RS256_PRIVATE_KEY)
+            .unwrap()
+            .to_public_key()
+            .to_pkcs1_pem(LineEnding::LF)
+            .unwrap();
+        assert!(pkcs1.starts_with("-----BEGIN RSA PUBLIC KEY-----"));
+        assert_eq!(RsaPublicKey::from_pem(&pkcs1), Ok(spki));
+
+        // 不正な PEM・途中で切れた PEM・秘密鍵は拒否する
+        let truncated = &RS256_PUBLIC_KEY[..RS256_PUBLIC_KEY.len() / 2];
+        for pem in ["", "not a pem", truncated, RS256_PRIVATE_KEY] {
+            assert_eq!(RsaPublicKey::from_pem(pem), Err(Error::InvalidPublicKey));
+        }
+    }
+
+    const ES256_PRIVATE_KEY: &str = include_str!("../testdata/es256/private.pem");
+    const ES256_PUBLIC_KEY: &str = include_str!("../testdata/es256/public.pem");
+    const ES256_PUBLIC_JWK: &str = include_str!("../testdata/es256/public.jwk");
+
+    /// testdata/es256 の秘密鍵で ES256 の署名（JOSE の形式、r || s）を作成する
+    fn es256_signature(message: &str) -> p256::ecdsa::Signature {
+        use p256::ecdsa::signature::Signer;
+        use p256::pkcs8::DecodePrivateKey;
+
+        let signing_key = p256::ecdsa::SigningKey::from_pkcs8_pem(ES256_PRIVATE_KEY).unwrap();
+        signing_key.sign(message.as_bytes())
+    }
+
+    /// ES256 のヘッダー・ペイロードと、署名前のメッセージ
+    fn es256_message(payload: &str) -> String {
+        format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"ES256","typ":"JWT"}"#),
+            URL_SAFE_NO_PAD.encode(payload)
+        )
+    }
+
+    #[test]
+    fn test_es256_verifies_with_pem_and_jwk() {
+        let message = es256_message(r#"{"sub":"partner-1","exp":4102444800}"#);
+        let signature = es256_signature(&message).to_bytes();
+        assert_eq!(signature.len(), 64);
+        let token = format!("{}.{}", message, URL_SAFE_NO_PAD.encode(signature));
+
+        let from_pem = EcPublicKey::from_pem(ES256_PUBLIC_KEY).unwrap();
+        let from_jwk = EcPublicKey::from_jwk(ES256_PUBLIC_JWK).unwrap();
+        assert_eq!(from_pem, from_jwk);
+        let claims =
+            decode_with_key(&token, &DecodingKey::Ec(&from_jwk), &Validation::new(NOW)).unwrap();
+        assert_eq!(claims.sub, "partner-1");
+
+        // 他のトークンの署名は一致しない
+        let other = es256_message(r#"{"sub":"admin","exp":4102444800}"#);
+        let replayed = format!("{}.{}", other, URL_SAFE_NO_PAD.encode(signature));
+        assert_eq!(
+            decode_with_key(
+                &replayed,
+                &DecodingKey::Ec(&from_pem),
+                &Validation::new(NOW)
+            ),
+            Err(Error::InvalidSignature)
+        );
+        // ES256 のトークンは HS256 のシークレット・RSA の公開鍵では検証しない
+        assert_eq!(
+            decode(&token, SECRET, &Validation::new(NOW)),
+            Err(Error::UnsupportedAlgorithm("ES256".to_string()))
+        );
+    }
+
+    /// 署名の長さ: DER（ASN.1）の署名・64 バイト以外の署名は形式の誤り
+    #[test]
+    fn test_es256_rejects_der_and_wrong_length_signatures() {
+        let key = EcPublicKey::from_pem(ES256_PUBLIC_KEY).unwrap();
+        let message = es256_message(r#"{"sub":"partner-1","exp":4102444800}"#);
+        let signature = es256_signature(&message);
+        let raw = signature.to_bytes();
+        let der = signature.to_der();
+        assert_ne!(der.as_bytes().len(), 64);
+
+        let token = |signature: &[u8]| format!("{}.{}", message, URL_SAFE_NO_PAD.encode(signature));
+        for signature in [
+            der.as_bytes(),
+            &raw[..63],
+            &[raw.as_slice(), &[0]].concat(),
+            &[],
+        ] {
+            assert_eq!(
+                decode_with_key(
+                    &token(signature),
+                    &DecodingKey::Ec(&key),
+                    &Validation::new(NOW)
+                ),
+                Err(Error::InvalidSignatureEncoding),
+                "{} bytes",
+                signature.len()
+            );
+        }
+
+        // 長さが正しくても r = 0 / s = 0 の署名、ビットを反転した署名は一致しない
+        let mut flipped = raw;
+        flipped[63] ^= 1;
+        for signature in [[0u8; 64].as_slice(), flipped.as_slice()] {
+            assert_eq!(
+                decode_with_key(
+                    &token(signature),
+                    &DecodingKey::Ec(&key),
+                    &Validation::new(NOW)
+                ),
+                Err(Error::InvalidSignature)
+            );
+        }
+    }
+
+    #[test]
+    fn test_ec_public_key_rejects_invalid_input() {
+        for pem in ["", "not a pem", RS256_PUBLIC_KEY, ES256_PRIVATE_KEY] {
+            assert_eq!(EcPublicKey::from_pem(pem), Err(Error::InvalidPublicKey));
+        }
+
+        // kty / crv が異なる、座標の長さ・曲線上の点でない JWK は拒否する
+        let jwk: Value = serde_json::from_str(ES256_PUBLIC_JWK).unwrap();
+        let with = |name: &str, value: &str| {
+            let mut jwk = jwk.clone();
+            jwk[name] = Value::from(value);
+            jwk.to_string()
+        };
+        for invalid in [
+            "{}".to_string(),
+            "not json".to_string(),
+            with("kty", "RSA"),
+            with("crv", "P-384"),
+            with("x", "AQID"),
+            with("y", &URL_SAFE_NO_PAD.encode([1u8; 32])),
+        ] {
+            assert_eq!(
+                EcPublicKey::from_jwk(&invalid),
+                Err(Error::InvalidPublicKey),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    const JWKS: &str = include_str!("../testdata/jwks/jwks.json");
+
+    #[test]
+    fn test_jwk_set_selects_key_by_kid() {
+        let jwks = JwkSet::from_json(JWKS).unwrap();
+        assert_eq!(jwks.len(), 2);
+
+        // PEM と同じ鍵を kid で選ぶ
+        let rsa = RsaPublicKey::from_pem(RS256_PUBLIC_KEY).unwrap();
+        let ec = EcPublicKey::from_pem(ES256_PUBLIC_KEY).unwrap();
+        assert_eq!(jwks.find("rs256-test"), Some(DecodingKey::Rsa(&rsa)));
+        assert_eq!(jwks.find("es256-test"), Some(DecodingKey::Ec(&ec)));
+        assert_eq!(jwks.find("unknown"), None);
+
+        let token = rs256_token(r#"{"sub":"user-1","exp":4102444800}"#);
+        let key = jwks.find("rs256-test").unwrap();
+        assert_eq!(
+            decode_with_key(&token, &key, &Validation::new(NOW))
+                .unwrap()
+                .sub,
+            "user-1"
+        );
+        // kid が RSA の鍵でも、ES256 の鍵で RS256 のトークンは検証しない
+        assert_eq!(
+            decode_with_key(
+                &token,
+                &jwks.find("es256-test").unwrap(),
+                &Validation::new(NOW)
+            ),
+            Err(Error::UnsupportedAlgorithm("RS256".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_jwk_set_skips_unusable_keys() {
+        let jwks: Value = serde_json::from_str(JWKS).unwrap();
+        let rsa = &jwks["keys"][0];
+        let with = |name: &str, value: Value| {
+            let mut jwk = rsa.clone();
+            jwk[name] = value;
+            jwk
+        };
+        let mut without_kid = rsa.clone();
+        without_kid.as_object_mut().unwrap().remove("kid");
+        let keys = vec![
+            without_kid,
+            with("use", Value::from("enc")),
+            with("alg", Value::from("PS256")),
+            with("kty", Value::from("oct")),
+            with("n", Value::from("not base64!")),
+            with("e", Value::from("")),
+            Value::from("not an object"),
+            with("kid", Value::from("usable")),
+        ];
+        let set = JwkSet::from_json(&serde_json::json!({ "keys": keys }).to_string()).unwrap();
+
+        assert_eq!(set.len(), 1);
+        assert!(set.find("usable").is_some());
+        assert!(set.find("rs256-test").is_none());
+
+        // 鍵がない JWKS は空、JSON・keys が不正な場合はエラー
+        assert!(JwkSet::from_json(r#"{"keys":[]}"#).unwrap().is_empty());
+        for invalid in ["", "not json", "{}", r#"{"keys":{}}"#] {
+            assert_eq!(
+                JwkSet::from_json(invalid),
+                Err(Error::InvalidPublicKey),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_rsa_public_key_from_jwk() {
+        let jwks: Value = serde_json::from_str(JWKS).unwrap();
+        let jwk = jwks["keys"][0].to_string();
+        assert_eq!(
+            RsaPublicKey::from_jwk(&jwk),
+            Ok(RsaPublicKey::from_pem(RS256_PUBLIC_KEY).unwrap())
+        );
+
+        // EC の JWK・n / e のない JWK は拒否する
+        for invalid in [
+            ES256_PUBLIC_JWK.to_string(),
+            r#"{"kty":"RSA","e":"AQAB"}"#.to_string(),
+            "not json".to_string(),
+        ] {
+            assert_eq!(
+                RsaPublicKey::from_jwk(&invalid),
+                Err(Error::InvalidPublicKey),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    /// Validation で許可していないアルゴリズムは、鍵があっても拒否する
+    #[test]
+    fn test_rejects_algorithms_outside_configured_allowlist() {
+        let token = encode(&Header::hs256(None), &claims("user-1", NOW + 3600), SECRET).unwrap();
+
+        let es256_only = Validation::new(NOW).with_algorithms(&["ES256"]);
+        assert_eq!(
+            decode(&token, SECRET, &es256_only),
+            Err(Error::UnsupportedAlgorithm("HS256".to_string()))
+        );
+        assert!(
+            decode(
+                &token,
+                SECRET,
+                &Validation::new(NOW).with_algorithms(&["HS256"])
+            )
+            .is_ok()
+        );
+
+        // ALLOWED_ALGORITHMS 以外は許可リストに含めても受け付けない（none は専用のエラー）
+        let none = raw_token(r#"{"alg":"None"}"#, r#"{"sub":"user-1","exp":4102444800}"#);
+        let with_none = Validation::new(NOW).with_algorithms(&["none", "None", "HS256"]);
+        assert_eq!(
+            decode(&none, SECRET, &with_none),
+            Err(Error::UnsecuredAlgorithm("None".to_string()))
+        );
+        // 空の許可リストはすべて拒否する
+        assert!(decode(&token, SECRET, &Validation::new(NOW).with_algorithms(&[])).is_err());
+    }
+
+    /// 以前のコア層の実装（jsonwebtoken）と相互に検証できる
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_interoperates_with_jsonwebtoken() {
+        extern crate std;
+        use jsonwebtoken::{DecodingKey, EncodingKey, Validation as JwtValidation};
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let mut original = claims("user-1", now + 3600);
+        original.iss = Some("todo-api".to_string());
+
+        // jsonwebtoken で発行 → このクレートで検証
+        let legacy = jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &original,
+            &EncodingKey::from_secret(SECRET),
+        )
+        .unwrap();
+        let validation = Validation::new(now).with_issuer(Some("todo-api"));
+        assert_eq!(decode(&legacy, SECRET, &validation), Ok(original.clone()));
+
+        // このクレートで発行 → jsonwebtoken で検証
+        let token = encode(&Header::hs256(Some("default")), &original, SECRET).unwrap();
+        let decoded = jsonwebtoken::decode::<Claims>(
+            &token,
+            &DecodingKey::from_secret(SECRET),
+            &JwtValidation::default(),
+        )
+        .unwrap();
+        assert_eq!(decoded.claims, original);
+        assert_eq!(decoded.header.kid.as_deref(), Some("default"));
+    }
+}
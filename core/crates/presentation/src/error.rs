@@ -11,6 +11,10 @@
 // - DomainError::NotFound → 404 Not Found
 // - DomainError::Duplicate → 409 Conflict
 // - DomainError::Repository/Cache → 500 Internal Server Error
+//
+// プレゼンテーション層固有のエラー:
+// - BatchTooLarge → 422 Unprocessable Entity（code=batch_too_large）
+// - MalformedCsv → 422 Unprocessable Entity（code=malformed_csv）
 // =============================================================================
 
 // -----------------------------------------------------------------------------
@@ -31,6 +35,9 @@ use axum::{
 // domain: ドメイン層のエラー型
 use domain::DomainError;
 
+// error_code: エラーレスポンスの code（エッジ層の gateway と共有）
+use error_code::ErrorCode;
+
 // thiserror: エラー型定義を簡略化するマクロ
 // #[error("...")] でエラーメッセージを定義
 use thiserror::Error;
@@ -72,6 +79,12 @@ pub enum ApiError {
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
 
+    /// 403 Forbidden: 権限エラー
+    ///
+    /// 認証済みだが操作の権限がない場合に使用（例: 管理者専用エンドポイント）。
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     /// 404 Not Found: リソースが見つからない
     ///
     /// 存在しない TODO や User へのアクセスに使用。
@@ -84,6 +97,29 @@ pub enum ApiError {
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    /// 422 Unprocessable Entity: バッチの件数が上限を超えている
+    ///
+    /// DB 処理の前に検出し、上限と送信件数をレスポンスに含める。
+    #[error("Batch too large: {submitted} items (limit {limit})")]
+    BatchTooLarge {
+        /// 設定された上限
+        limit: usize,
+        /// 送信された件数
+        submitted: usize,
+    },
+
+    /// 422 Unprocessable Entity: CSV ファイルとして解析できない
+    ///
+    /// 列数の不一致や不正な UTF-8 など、最初の解析エラーの位置を含める。
+    /// 行単位のバリデーションエラーはこのエラーにせず、行ごとの結果で返す。
+    #[error("Malformed CSV at line {line:?}: {message}")]
+    MalformedCsv {
+        /// エラーが発生した行番号（1 始まり、ヘッダー行を含む。不明な場合は None）
+        line: Option<u64>,
+        /// 解析エラーの内容
+        message: String,
+    },
+
     /// 500 Internal Server Error: 内部エラー
     ///
     /// DB エラー、キャッシュエラーなど予期しないエラーに使用。
@@ -162,6 +198,9 @@ impl IntoResponse for ApiError {
             // 401 Unauthorized
             ApiError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg.clone()),
 
+            // 403 Forbidden
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg.clone()),
+
             // 404 Not Found
             ApiError::NotFound => (StatusCode::NOT_FOUND, "not found".to_string()),
 
@@ -170,6 +209,35 @@ impl IntoResponse for ApiError {
 
             // 500 Internal Server Error
             ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
+
+            // 422 Unprocessable Entity: 機械可読なコードと上限・件数を含めて即座に返す
+            // {"error": "...", "code": "batch_too_large", "limit": 100, "submitted": 101}
+            ApiError::BatchTooLarge { limit, submitted } => {
+                return (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(serde_json::json!({
+                        "error": format!("batch cannot exceed {} items", limit),
+                        "code": ErrorCode::BatchTooLarge.as_str(),
+                        "limit": limit,
+                        "submitted": submitted,
+                    })),
+                )
+                    .into_response();
+            }
+
+            // 422 Unprocessable Entity: 最初の解析エラーの位置を含めて即座に返す
+            // {"error": "...", "code": "malformed_csv", "line": 3}
+            ApiError::MalformedCsv { line, message } => {
+                return (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(serde_json::json!({
+                        "error": message,
+                        "code": ErrorCode::MalformedCsv.as_str(),
+                        "line": line,
+                    })),
+                )
+                    .into_response();
+            }
         };
 
         // JSON 形式でエラーレスポンスを返す
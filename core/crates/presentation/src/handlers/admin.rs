@@ -0,0 +1,248 @@
+// =============================================================================
+// presentation/src/handlers/admin.rs: 管理者専用ハンドラ
+// =============================================================================
+// 運用操作のためのエンドポイント。管理者（users.role = 'admin'）のみ実行できる。
+//
+// エンドポイント:
+// - POST /api/admin/reload-secrets - JWT 鍵セットのリロード
+//
+// 権限:
+// - Edge 検証 + UserContext（X-User-Id）で認証済みであること
+// - ユーザーが管理者であること（AuthService::is_admin）。一般ユーザーは 403
+// - 管理者は api の create-admin サブコマンドで作成する
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// 外部クレートのインポート
+// -----------------------------------------------------------------------------
+
+// axum: Web フレームワーク
+use axum::{extract::State, response::IntoResponse, Json};
+
+// application: Application 層の DTO
+use application::dto::ReloadSecretsResponse;
+
+// crate: このクレート内のモジュール
+use crate::error::ApiError; // API エラー型
+use crate::middleware::UserContext; // 認証済みユーザー
+use crate::state::AppState; // アプリケーション状態
+
+// =============================================================================
+// reload_secrets ハンドラ
+// =============================================================================
+
+/// JWT 鍵セットのリロード
+///
+/// POST /api/admin/reload-secrets
+///
+/// 鍵ファイル（JWT_SECRETS_FILE）を再読み込みし、署名・検証に使う鍵を差し替える。
+/// SIGHUP と同じ処理（api/src/main.rs）。
+///
+/// # Response (200 OK)
+///
+/// ```json
+/// {
+///     "signing_key_id": "2025-02",
+///     "key_ids": ["2025-02", "default"]
+/// }
+/// ```
+///
+/// # Errors
+///
+/// - 403 Forbidden: 管理者ではない
+/// - 409 Conflict: リロードが構成されていない（JWT_SECRET のみで起動している）
+/// - 500 Internal Server Error: 鍵ファイルの読み込み・検証に失敗（古い鍵を使い続ける）
+pub async fn reload_secrets(
+    user: UserContext,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApiError> {
+    if !state.auth_service.is_admin(user.user_id).await? {
+        tracing::warn!(user_id = %user.user_id, "Non-admin user attempted to reload JWT keys");
+        return Err(ApiError::Forbidden("admin role required".to_string()));
+    }
+
+    let keys = state.auth_service.keys();
+    if !keys.is_reloadable() {
+        return Err(ApiError::Conflict(
+            "JWT key reload is not configured (set JWT_SECRETS_FILE)".to_string(),
+        ));
+    }
+
+    // 失敗した場合、JwtKeyRing は古い鍵セットを使い続ける
+    let reloaded = keys.reload().map_err(|e| {
+        tracing::error!(error = %e, "JWT key reload failed; keeping the previous keys");
+        ApiError::Internal(format!("JWT key reload failed: {}", e))
+    })?;
+
+    tracing::info!(
+        user_id = %user.user_id,
+        signing_key_id = reloaded.current().id(),
+        "JWT keys reloaded"
+    );
+    Ok(Json(ReloadSecretsResponse::from(reloaded.as_ref())))
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    use application::{JwtKeyRing, KeySet, KeySource};
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use domain::{DomainError, User, UserWriter};
+    use infrastructure::{InMemoryUserWriter, MemoryStore};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use crate::{create_router, RouterConfig};
+
+    /// 返す内容をテストから差し替えられる鍵の読み込み元（None は読み込み失敗）
+    struct StubSource(Mutex<Option<&'static str>>);
+
+    impl StubSource {
+        fn new(text: &'static str) -> Arc<Self> {
+            Arc::new(Self(Mutex::new(Some(text))))
+        }
+
+        fn set(&self, text: Option<&'static str>) {
+            *self.0.lock().unwrap() = text;
+        }
+    }
+
+    impl KeySource for StubSource {
+        fn load(&self) -> Result<KeySet, DomainError> {
+            match *self.0.lock().unwrap() {
+                Some(text) => KeySet::parse(text),
+                None => Err(DomainError::Validation("file not found".to_string())),
+            }
+        }
+    }
+
+    /// ユーザーを作成し（admin なら昇格）、その ID を返す
+    async fn create_user(store: &MemoryStore, admin: bool) -> Uuid {
+        let mut user = User::new(
+            format!("{}@example.com", Uuid::new_v4().simple()),
+            "hash".to_string(),
+            None,
+        );
+        if admin {
+            user.promote_to_admin();
+        }
+        InMemoryUserWriter::new(store.clone())
+            .create(&user)
+            .await
+            .unwrap()
+            .id
+    }
+
+    /// 鍵セットを差し替えた AppState を作成する
+    fn state_with_keys(store: MemoryStore, keys: JwtKeyRing) -> AppState {
+        let mut state = AppState::for_tests_with_store(store);
+        state.auth_service = state.auth_service.clone().with_keys(keys);
+        state
+    }
+
+    /// POST /api/admin/reload-secrets を送信する
+    async fn post_reload(state: AppState, user_id: Uuid) -> (StatusCode, serde_json::Value) {
+        let response = create_router(state, RouterConfig::minimal())
+            .oneshot(
+                Request::post("/api/admin/reload-secrets")
+                    .header("x-user-id", user_id.to_string())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (
+            status,
+            serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null),
+        )
+    }
+
+    /// 管理者はリロードでき、新しい鍵セットが AuthService に反映される
+    #[tokio::test]
+    async fn test_admin_reloads_keys() {
+        let store = MemoryStore::new();
+        let admin = create_user(&store, true).await;
+        let source = StubSource::new("old:old-secret");
+        let keys = JwtKeyRing::from_source(source.clone()).unwrap();
+
+        // 起動後に鍵ファイルが更新された想定
+        source.set(Some("new:new-secret\nold:old-secret"));
+        let (status, body) = post_reload(state_with_keys(store, keys.clone()), admin).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["signing_key_id"], "new");
+        assert_eq!(body["key_ids"], serde_json::json!(["new", "old"]));
+        assert_eq!(keys.snapshot().current().id(), "new");
+    }
+
+    /// X-User-Roles は認可に使わない（クライアントが偽装しても管理者にならない）
+    #[tokio::test]
+    async fn test_roles_header_does_not_grant_admin() {
+        let store = MemoryStore::new();
+        let user = create_user(&store, false).await;
+        let keys = JwtKeyRing::from_source(StubSource::new("old:old-secret")).unwrap();
+
+        let response = create_router(state_with_keys(store, keys), RouterConfig::minimal())
+            .oneshot(
+                Request::post("/api/admin/reload-secrets")
+                    .header("x-user-id", user.to_string())
+                    .header("x-user-roles", "admin")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_non_admin_is_forbidden() {
+        let store = MemoryStore::new();
+        let user = create_user(&store, false).await;
+        let source = StubSource::new("old:old-secret");
+        let keys = JwtKeyRing::from_source(source.clone()).unwrap();
+
+        source.set(Some("new:new-secret"));
+        let (status, _) = post_reload(state_with_keys(store, keys.clone()), user).await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(keys.snapshot().current().id(), "old");
+    }
+
+    /// 読み込みに失敗したら 500 を返し、古い鍵を使い続ける
+    #[tokio::test]
+    async fn test_failed_reload_keeps_previous_keys() {
+        let store = MemoryStore::new();
+        let admin = create_user(&store, true).await;
+        let source = StubSource::new("k1:secret");
+        let keys = JwtKeyRing::from_source(source.clone()).unwrap();
+
+        source.set(None);
+        let (status, _) = post_reload(state_with_keys(store, keys.clone()), admin).await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(keys.snapshot().current().id(), "k1");
+    }
+
+    /// JWT_SECRET のみで起動した場合はリロードできない
+    #[tokio::test]
+    async fn test_reload_without_source_is_conflict() {
+        let store = MemoryStore::new();
+        let admin = create_user(&store, true).await;
+
+        let (status, _) = post_reload(AppState::for_tests_with_store(store), admin).await;
+
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+}
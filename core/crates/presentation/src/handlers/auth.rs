@@ -28,9 +28,6 @@
 // Json: JSON リクエスト/レスポンス
 use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
 
-// domain: ドメイン層のトレイト（ジェネリクス制約用）
-use domain::{StorageOps, TodoCacheOps, TodoReader, TodoWriter, UserReader, UserWriter};
-
 // application: Application 層の DTO
 use application::dto::{LoginRequest, RegisterRequest, TokenResponse, UserResponse};
 
@@ -71,22 +68,10 @@ use crate::state::AppState; // アプリケーション状態
 ///
 /// - 400 Bad Request: バリデーションエラー（メール形式不正、パスワード短すぎなど）
 /// - 409 Conflict: メールアドレスが既に使用されている
-///
-/// # Type Parameters
-///
-/// ジェネリクスは AppState の型パラメータを引き継ぐ。
-/// 実際の実装は依存性注入で決定される。
-pub async fn register<
-    TW: TodoWriter,  // TODO 書き込み（このハンドラでは未使用）
-    TR: TodoReader,  // TODO 読み取り（このハンドラでは未使用）
-    C: TodoCacheOps, // キャッシュ操作（このハンドラでは未使用）
-    UR: UserReader,  // ユーザー読み取り（重複チェック）
-    UW: UserWriter,  // ユーザー書き込み（新規作成）
-    S: StorageOps,   // ストレージ操作（このハンドラでは未使用）
->(
+pub async fn register(
     // State エクストラクタ: AppState を取得（axum 推奨）
     // axum が各リクエストで state.clone() を呼び出す
-    State(state): State<AppState<TW, TR, C, UR, UW, S>>,
+    State(state): State<AppState>,
     // Json エクストラクタ: リクエストボディを RegisterRequest にデシリアライズ
     // デシリアライズ失敗時は 400 Bad Request が自動返却される
     Json(req): Json<RegisterRequest>,
@@ -139,16 +124,9 @@ pub async fn register<
 /// - パスワードは bcrypt で検証（平文比較ではない）
 /// - 成功時に JWT トークンを発行
 /// - トークンには user_id と有効期限が含まれる
-pub async fn login<
-    TW: TodoWriter,  // TODO 書き込み（このハンドラでは未使用）
-    TR: TodoReader,  // TODO 読み取り（このハンドラでは未使用）
-    C: TodoCacheOps, // キャッシュ操作（このハンドラでは未使用）
-    UR: UserReader,  // ユーザー読み取り（メールで検索）
-    UW: UserWriter,  // ユーザー書き込み（このハンドラでは未使用）
-    S: StorageOps,   // ストレージ操作（このハンドラでは未使用）
->(
+pub async fn login(
     // State エクストラクタ: AppState を取得（axum 推奨）
-    State(state): State<AppState<TW, TR, C, UR, UW, S>>,
+    State(state): State<AppState>,
     // Json エクストラクタ: リクエストボディを LoginRequest にデシリアライズ
     Json(req): Json<LoginRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
@@ -5,12 +5,14 @@
 // トランザクション管理は TransactionalTodoService に委譲する。
 //
 // エンドポイント:
-// - POST /api/todos/batch      - 複数 TODO を一括作成
-// - POST /api/todos/with-files - TODO + ファイルを同時作成
+// - POST /api/todos/batch        - 複数 TODO を一括作成
+// - POST /api/todos/batch-update - 複数 TODO を一括更新
+// - POST /api/todos/with-files   - TODO + ファイルを同時作成
 //
 // トランザクション保証:
 // - いずれかの操作が失敗した場合、全てロールバック
 // - 部分的な成功は発生しない（All or Nothing）
+// - 例外: バッチ更新で partial=true を指定した場合は有効な項目のみ適用
 //
 // バリデーション:
 // - ハンドラ内でバリデーションを実行
@@ -24,28 +26,58 @@
 // axum: Web フレームワーク
 // extract::State: アプリケーション状態の抽出
 // http::StatusCode: HTTP ステータスコード
+// http::header / HeaderValue: Location ヘッダー
 // response::IntoResponse: レスポンス変換トレイト
 // Json: JSON リクエスト/レスポンス
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+// std: 標準ライブラリ
+// HashSet: バッチ内の ID 重複チェック
+use std::collections::HashSet;
+
+// serde: クエリパラメータのデシリアライズ
+use serde::Deserialize;
 
-// domain: ドメイン層の型とトレイト
+// domain: ドメイン層の型
 // File: ファイルエンティティ（バリデーション用）
 // Todo: TODO エンティティ（バリデーション用）
-// TodoCacheOps: キャッシュ操作トレイト
-// TodoReader/Writer: TODO 読み書きトレイト
-// UserReader/Writer: ユーザー読み書きトレイト
-use domain::{
-    File, StorageOps, Todo, TodoCacheOps, TodoReader, TodoWriter, UserReader, UserWriter,
-};
+// TodoCacheOps: バッチ更新後のキャッシュ無効化
+use domain::{DomainError, File, Todo, TodoCacheOps};
 
 // infrastructure: Infrastructure 層の型
+// BatchUpdateOutcome: バッチ更新の結果
+// CreateOutcome: 重複検出付きバッチ作成の結果
 // FileInput: ファイル作成入力パラメータ
-use infrastructure::FileInput;
+// TodoCreateInput: バッチ作成入力パラメータ
+// TodoUpdateInput: バッチ更新入力パラメータ
+use infrastructure::{
+    BatchUpdateOutcome, CreateOutcome, FileInput, TodoCreateInput, TodoUpdateInput,
+    TransactionalTodoService,
+};
+
+// uuid: ユーザー ID
+use uuid::Uuid;
 
 // application: Application 層の DTO
 use application::dto::{
+    todo_url,                   // TODO の正規 URL
+    BatchCreateItemResult,      // バッチ作成（partial モード）1 件分の結果
     BatchCreateTodosRequest,    // バッチ作成リクエスト
+    BatchItemStatus,            // バッチ 1 件分の処理結果
+    BatchMode,                  // バッチ作成の処理モード
+    BatchUpdateItemResult,      // バッチ更新 1 件分の結果
+    BatchUpdateTodoItem,        // バッチ更新 1 件分の入力
+    BatchUpdateTodosRequest,    // バッチ更新リクエスト
+    BatchUpdateTodosResponse,   // バッチ更新レスポンス
+    CreateTodoDto,              // TODO 作成 DTO
     CreateTodoWithFilesRequest, // TODO + ファイル作成リクエスト
+    CreatedTodoResponse,        // 作成された TODO + 正規 URL
+    FieldError,                 // フィールド単位のバリデーションエラー
     FileResponse,               // ファイルレスポンス
     TodoWithFilesResponse,      // TODO + ファイルレスポンス
 };
@@ -71,11 +103,24 @@ use crate::state::AppState; // アプリケーション状態
 ///         {"title": "タスク1", "description": "説明1"},
 ///         {"title": "タスク2", "description": null},
 ///         {"title": "タスク3"}
-///     ]
+///     ],
+///     "mode": "atomic",
+///     "dedupe": false
 /// }
 /// ```
 ///
-/// # Response (201 Created)
+/// `mode` は省略可能（デフォルト: `atomic`）。
+///
+/// 重複検出（省略時は無効）:
+/// - `dedupe: true`: リクエスト内で正規化したタイトル（前後の空白除去、空白の圧縮、
+///   小文字化）が同じ項目は最初の 1 件だけ作成する
+/// - `dedupe_existing: true`: ユーザーの未完了 TODO と重複する項目も作成しない
+///   （`dedupe` を含む）
+///
+/// # Response: atomic モード (201 Created)
+///
+/// 各 TODO に正規 URL（`url`）を付与して返す。
+/// 作成が 1 件の場合は `Location: /api/todos/{id}` ヘッダーも付与する。
 ///
 /// ```json
 /// [
@@ -86,36 +131,60 @@ use crate::state::AppState; // アプリケーション状態
 ///         "description": "説明1",
 ///         "completed": false,
 ///         "created_at": "2024-01-01T00:00:00Z",
-///         "updated_at": "2024-01-01T00:00:00Z"
+///         "updated_at": "2024-01-01T00:00:00Z",
+///         "url": "/api/todos/uuid"
 ///     },
 ///     ...
 /// ]
 /// ```
 ///
+/// # Response: partial モード (207 Multi-Status)
+///
+/// リクエスト順に項目ごとの結果を返す。
+///
+/// ```json
+/// [
+///     {"index": 0, "status": "created", "todo": {...}, "url": "/api/todos/uuid"},
+///     {"index": 1, "status": "invalid", "error": "title cannot be empty"}
+/// ]
+/// ```
+///
+/// # Response: 重複検出あり (atomic: 201 Created / partial: 207 Multi-Status)
+///
+/// モードに関わらず partial モードと同じ項目ごとの結果を返す。
+/// 重複した項目は `skipped_duplicate` と重複先の TODO ID（`existing_id`）を持つ。
+///
+/// ```json
+/// [
+///     {"index": 0, "status": "created", "todo": {...}, "url": "/api/todos/uuid"},
+///     {"index": 1, "status": "skipped_duplicate", "existing_id": "uuid"}
+/// ]
+/// ```
+///
 /// # Errors
 ///
-/// - 400 Bad Request: バリデーションエラー（空配列、タイトル不正など）
+/// - 400 Bad Request: 空配列、atomic モードでのバリデーションエラー
+///   （メッセージに失敗した項目のインデックスを含む: `todos[1]: ...`）
+/// - 422 Unprocessable Entity: 件数が上限（`MAX_BATCH_SIZE`、デフォルト 100）を超える
+///   （`code=batch_too_large`、`limit`、`submitted` を含む）
 ///
 /// # トランザクション
 ///
-/// 複数の TODO を1トランザクションで作成する。
-/// いずれかが失敗した場合、全てロールバックされる。
-pub async fn batch_create_todos<
-    TW: TodoWriter,  // TODO 書き込み（未使用、TransactionalTodoService 使用）
-    TR: TodoReader,  // TODO 読み取り（未使用）
-    C: TodoCacheOps, // キャッシュ操作（未使用）
-    UR: UserReader,  // ユーザー読み取り（未使用）
-    UW: UserWriter,  // ユーザー書き込み（未使用）
-    S: StorageOps,   // ストレージ操作（未使用）
->(
+/// 有効な TODO を1トランザクションで作成する。
+/// INSERT のいずれかが失敗した場合、全てロールバックされる（両モード共通）。
+///
+/// # キャッシュ
+///
+/// Write-Through: コミット後に作成された TODO をキャッシュに保存する。
+pub async fn batch_create_todos(
     // UserContext エクストラクタ: 認証済みユーザー情報
     user: UserContext,
     // State エクストラクタ: AppState を取得（axum 推奨）
     // axum が各リクエストで state.clone() を呼び出す
-    State(state): State<AppState<TW, TR, C, UR, UW, S>>,
+    State(state): State<AppState>,
     // Json エクストラクタ: リクエストボディを BatchCreateTodosRequest にデシリアライズ
     Json(req): Json<BatchCreateTodosRequest>,
-) -> Result<impl IntoResponse, ApiError> {
+) -> Result<Response, ApiError> {
     // -------------------------------------------------------------------------
     // バリデーション: 空配列チェック
     // -------------------------------------------------------------------------
@@ -124,40 +193,289 @@ pub async fn batch_create_todos<
         return Err(ApiError::BadRequest("todos cannot be empty".to_string()));
     }
 
+    // バリデーション: 件数上限（DB 処理の前に検出）
+    ensure_batch_size(req.todos.len(), state.max_batch_size)?;
+
     // -------------------------------------------------------------------------
     // バリデーション: 各 TODO のタイトル
     // -------------------------------------------------------------------------
-    // Domain 層の validate_title を使用して検証
-    // エラーの場合は早期リターン（DomainError → ApiError に自動変換）
-    for todo in &req.todos {
-        Todo::validate_title(&todo.title)?; // 1-200文字のチェック
+    // 有効な項目（トリム済み）と無効な項目の結果に分ける
+    let (valid, invalid) = partition_create_items(req.todos);
+
+    // atomic モード: 1 件でも不正なら失敗した項目を示して 400
+    if req.mode == BatchMode::Atomic {
+        if let Some(first) = invalid.first() {
+            return Err(ApiError::BadRequest(format!(
+                "todos[{}]: {}",
+                first.index,
+                first.error.as_deref().unwrap_or("invalid item")
+            )));
+        }
     }
 
     // -------------------------------------------------------------------------
-    // リクエストを内部形式に変換
+    // 重複検出あり: 項目ごとの結果を返す
     // -------------------------------------------------------------------------
-    // BatchCreateTodosRequest の todos を (title, description) のタプルに変換
-    // into_iter(): 所有権を移動するイテレータ（req.todos を消費）
-    // map(): 各要素を変換
-    // collect(): Vec に収集
-    let todos: Vec<(String, Option<String>)> = req
-        .todos
-        .into_iter() // イテレータに変換
-        .map(|t| (t.title, t.description)) // (title, description) タプルに変換
-        .collect(); // Vec に収集
+    if req.dedupe || req.dedupe_existing {
+        let outcomes = create_deduplicated(
+            &state.batch_service,
+            user.user_id,
+            user.tenant_scope(state.tenant_scoping),
+            valid,
+            req.dedupe_existing,
+        )
+        .await?;
+
+        // キャッシュ更新（コミット後、Write-Through）
+        for (_, outcome) in &outcomes {
+            if let CreateOutcome::Created(todo) = outcome {
+                // キャッシュエラーは警告のみ（DB はコミット済み）
+                if let Err(e) = state.cache.set(todo).await {
+                    tracing::warn!(todo_id = %todo.id, error = %e, "Failed to cache created todo");
+                }
+            }
+        }
+
+        let status = match req.mode {
+            BatchMode::Atomic => StatusCode::CREATED,
+            BatchMode::Partial => StatusCode::MULTI_STATUS,
+        };
+        let results = merge_dedupe_results(outcomes, invalid);
+        return Ok((status, Json(results)).into_response());
+    }
 
     // -------------------------------------------------------------------------
     // TransactionalTodoService で一括作成
     // -------------------------------------------------------------------------
-    // batch_create: トランザクション内で複数 TODO を作成
+    // batch_create: トランザクション内で有効な TODO を作成
     // 失敗時は全てロールバック
-    let created: Vec<Todo> = state
-        .batch_service // TransactionalTodoService を取得
-        .batch_create(user.user_id, todos) // バッチ作成実行
-        .await?; // 非同期待機 + エラー伝播
+    // 有効な項目がない場合（partial モードで全件不正）は DB に触れない
+    let (indexes, inputs) = create_inputs(valid, user.tenant_scope(state.tenant_scoping));
+    let created: Vec<Todo> = if inputs.is_empty() {
+        Vec::new()
+    } else {
+        state
+            .batch_service // TransactionalTodoService を取得
+            .batch_create_inputs(user.user_id, inputs) // バッチ作成実行
+            .await? // 非同期待機 + エラー伝播
+    };
+
+    // -------------------------------------------------------------------------
+    // キャッシュ更新（コミット後、Write-Through）
+    // -------------------------------------------------------------------------
+    // 単一作成（CreateTodoCommand）と同様に、作成した TODO をキャッシュに保存する
+    // 一覧はキャッシュしないため、直後の一覧取得には常に新しい項目が含まれる
+    for todo in &created {
+        // キャッシュエラーは警告のみ（DB はコミット済み）
+        if let Err(e) = state.cache.set(todo).await {
+            tracing::warn!(todo_id = %todo.id, error = %e, "Failed to cache created todo");
+        }
+    }
+
+    // atomic モード: 201 Created + 作成された TODO（正規 URL 付き）
+    if req.mode == BatchMode::Atomic {
+        return Ok(created_response(created));
+    }
+
+    // partial モード: 207 Multi-Status + 項目ごとの結果（リクエスト順）
+    let results = merge_create_results(indexes.into_iter().zip(created).collect(), invalid);
+    Ok((StatusCode::MULTI_STATUS, Json(results)).into_response())
+}
+
+// -----------------------------------------------------------------------------
+// バッチ作成のヘルパー関数
+// -----------------------------------------------------------------------------
+
+/// 検証済みのバッチ作成項目: (インデックス, (トリム済みタイトル, 説明))
+pub(crate) type ValidCreateItem = (usize, (String, Option<String>));
+
+/// バッチ作成の各項目を検証し、有効な項目と無効な項目の結果に分ける
+///
+/// # Returns
+///
+/// * 有効な項目: (インデックス, (トリム済みタイトル, 説明)) のリスト
+/// * 無効な項目: status = invalid の結果リスト
+pub(crate) fn partition_create_items(
+    todos: Vec<CreateTodoDto>,
+) -> (Vec<ValidCreateItem>, Vec<BatchCreateItemResult>) {
+    let mut valid = Vec::with_capacity(todos.len());
+    let mut invalid = Vec::new();
+
+    for (index, todo) in todos.into_iter().enumerate() {
+        match Todo::validate_title(&todo.title) {
+            Ok(title) => valid.push((index, (title, todo.description))),
+            Err(e) => invalid.push(BatchCreateItemResult {
+                index,
+                status: BatchItemStatus::Invalid,
+                todo: None,
+                url: None,
+                existing_id: None,
+                error: Some(validation_message(e)),
+            }),
+        }
+    }
+
+    (valid, invalid)
+}
+
+/// 有効な項目を作成入力に変換する
+///
+/// # Arguments
+///
+/// * `valid` - `partition_create_items` が返す有効な項目
+/// * `tenant_id` - 作成時のテナント ID（UserContext::tenant_scope）
+///
+/// # Returns
+///
+/// (リクエスト内の位置のリスト, 作成入力のリスト)
+pub(crate) fn create_inputs(
+    valid: Vec<ValidCreateItem>,
+    tenant_id: Option<Uuid>,
+) -> (Vec<usize>, Vec<TodoCreateInput>) {
+    valid
+        .into_iter()
+        .map(|(index, (title, description))| {
+            (
+                index,
+                TodoCreateInput {
+                    title,
+                    description,
+                    completed: false,
+                    tenant_id,
+                },
+            )
+        })
+        .unzip()
+}
 
-    // 成功時: 201 Created + 作成された TODO 配列
-    Ok((StatusCode::CREATED, Json(created)))
+/// 重複検出付きで有効な項目を作成し、項目ごとの結果を返す
+///
+/// 有効な項目がない場合は DB に触れない。
+///
+/// # Arguments
+///
+/// * `service` - トランザクション対応 TODO サービス
+/// * `user_id` - 所有者のユーザー ID
+/// * `tenant_id` - 作成時のテナント ID（UserContext::tenant_scope）
+/// * `valid` - `partition_create_items` が返す有効な項目
+/// * `against_existing` - ユーザーの未完了 TODO との重複も検出するか
+///
+/// # Returns
+///
+/// (リクエスト内の位置, 作成結果) のリスト（位置順）
+pub(crate) async fn create_deduplicated(
+    service: &TransactionalTodoService,
+    user_id: Uuid,
+    tenant_id: Option<Uuid>,
+    valid: Vec<ValidCreateItem>,
+    against_existing: bool,
+) -> Result<Vec<(usize, CreateOutcome)>, ApiError> {
+    if valid.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let (indexes, inputs) = create_inputs(valid, tenant_id);
+
+    let outcomes = service
+        .batch_create_dedupe(user_id, inputs, against_existing)
+        .await?;
+
+    Ok(indexes.into_iter().zip(outcomes).collect())
+}
+
+/// 重複検出付きの作成結果と無効な項目の結果をリクエスト順にまとめる
+fn merge_dedupe_results(
+    outcomes: Vec<(usize, CreateOutcome)>,
+    invalid: Vec<BatchCreateItemResult>,
+) -> Vec<BatchCreateItemResult> {
+    let mut results: Vec<BatchCreateItemResult> = outcomes
+        .into_iter()
+        .map(|(index, outcome)| match outcome {
+            CreateOutcome::Created(todo) => BatchCreateItemResult {
+                index,
+                status: BatchItemStatus::Created,
+                url: Some(todo_url(todo.id)),
+                todo: Some(todo),
+                existing_id: None,
+                error: None,
+            },
+            CreateOutcome::Duplicate { existing_id } => BatchCreateItemResult {
+                index,
+                status: BatchItemStatus::SkippedDuplicate,
+                todo: None,
+                url: None,
+                existing_id: Some(existing_id),
+                error: None,
+            },
+        })
+        .chain(invalid)
+        .collect();
+    results.sort_by_key(|r| r.index);
+    results
+}
+
+/// atomic モードのレスポンスを構築する
+///
+/// 201 Created + 正規 URL 付きの TODO 配列。
+/// 作成が 1 件の場合のみ Location ヘッダーを付与する
+/// （複数件では単一のリソースを指せないため）。
+fn created_response(created: Vec<Todo>) -> Response {
+    let location = match created.as_slice() {
+        [todo] => HeaderValue::from_str(&todo_url(todo.id)).ok(),
+        _ => None,
+    };
+
+    let body: Vec<CreatedTodoResponse> = created.into_iter().map(Into::into).collect();
+    let mut response = (StatusCode::CREATED, Json(body)).into_response();
+
+    if let Some(location) = location {
+        response.headers_mut().insert(header::LOCATION, location);
+    }
+
+    response
+}
+
+/// 作成結果と無効な項目の結果をリクエスト順にまとめる
+fn merge_create_results(
+    created: Vec<(usize, Todo)>,
+    invalid: Vec<BatchCreateItemResult>,
+) -> Vec<BatchCreateItemResult> {
+    let mut results: Vec<BatchCreateItemResult> = created
+        .into_iter()
+        .map(|(index, todo)| BatchCreateItemResult {
+            index,
+            status: BatchItemStatus::Created,
+            url: Some(todo_url(todo.id)),
+            todo: Some(todo),
+            existing_id: None,
+            error: None,
+        })
+        .chain(invalid)
+        .collect();
+    results.sort_by_key(|r| r.index);
+    results
+}
+
+/// バッチの件数が上限以内であることを確認する
+///
+/// # Errors
+///
+/// 上限を超えている場合は `ApiError::BatchTooLarge`（422, code=batch_too_large）
+pub(crate) fn ensure_batch_size(submitted: usize, limit: usize) -> Result<(), ApiError> {
+    if submitted > limit {
+        return Err(ApiError::BatchTooLarge { limit, submitted });
+    }
+    Ok(())
+}
+
+/// DomainError から利用者向けのメッセージを取り出す
+///
+/// Validation の場合は "Validation error: " の接頭辞を除いたメッセージを返す。
+pub(crate) fn validation_message(err: DomainError) -> String {
+    match err {
+        DomainError::Validation(msg) => msg,
+        other => other.to_string(),
+    }
 }
 
 // =============================================================================
@@ -214,6 +532,7 @@ pub async fn batch_create_todos<
 /// # Errors
 ///
 /// - 400 Bad Request: バリデーションエラー
+/// - 422 Unprocessable Entity: ファイル数が上限（`MAX_BATCH_SIZE`）を超える
 ///
 /// # トランザクション
 ///
@@ -224,19 +543,14 @@ pub async fn batch_create_todos<
 ///
 /// ファイル本体は事前にストレージにアップロード済みの前提。
 /// このエンドポイントはメタデータのみを DB に登録する。
-pub async fn create_todo_with_files<
-    TW: TodoWriter,  // TODO 書き込み（未使用、TransactionalTodoService 使用）
-    TR: TodoReader,  // TODO 読み取り（未使用）
-    C: TodoCacheOps, // キャッシュ操作（未使用）
-    UR: UserReader,  // ユーザー読み取り（未使用）
-    UW: UserWriter,  // ユーザー書き込み（未使用）
-    S: StorageOps,   // ストレージ操作（未使用）
->(
+/// アップロードと失敗時の補償削除まで行う `TransactionalTodoService::create_with_uploads` は
+/// ルートに公開していない。
+pub async fn create_todo_with_files(
     // UserContext エクストラクタ: 認証済みユーザー情報
     user: UserContext,
     // State エクストラクタ: AppState を取得（axum 推奨）
     // axum が各リクエストで state.clone() を呼び出す
-    State(state): State<AppState<TW, TR, C, UR, UW, S>>,
+    State(state): State<AppState>,
     // Json エクストラクタ: リクエストボディを CreateTodoWithFilesRequest にデシリアライズ
     Json(req): Json<CreateTodoWithFilesRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
@@ -247,6 +561,11 @@ pub async fn create_todo_with_files<
     // バリデーション済みのタイトルを取得（トリム済み）
     let title = Todo::validate_title(&req.title)?;
 
+    // -------------------------------------------------------------------------
+    // バリデーション: ファイル数の上限（DB 処理の前に検出）
+    // -------------------------------------------------------------------------
+    ensure_batch_size(req.files.len(), state.max_batch_size)?;
+
     // -------------------------------------------------------------------------
     // バリデーション: ファイル情報
     // -------------------------------------------------------------------------
@@ -302,3 +621,646 @@ pub async fn create_todo_with_files<
     // 成功時: 201 Created + レスポンス
     Ok((StatusCode::CREATED, Json(response)))
 }
+
+// =============================================================================
+// batch_update_todos ハンドラ
+// =============================================================================
+
+/// バッチ更新のクエリパラメータ
+///
+/// POST /api/todos/batch-update?partial=true のようなクエリパラメータを受け取る。
+#[derive(Debug, Deserialize)]
+pub struct BatchUpdateQuery {
+    /// 部分適用モード（デフォルト: false）
+    ///
+    /// - false: 1 件でも invalid / not_found があれば全てロールバック
+    /// - true: 有効な項目のみ適用してコミット
+    #[serde(default)]
+    pub partial: bool,
+}
+
+/// バッチ TODO 更新
+///
+/// POST /api/todos/batch-update
+///
+/// 最大 `MAX_BATCH_SIZE` 件（デフォルト 100）まで。
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///     "todos": [
+///         {"id": "uuid-1", "completed": true},
+///         {"id": "uuid-2", "title": "新しいタイトル", "description": "説明"}
+///     ]
+/// }
+/// ```
+///
+/// # Response (200 OK / 422 Unprocessable Entity)
+///
+/// ```json
+/// {
+///     "committed": true,
+///     "results": [
+///         {"id": "uuid-1", "status": "updated", "todo": {...}},
+///         {"id": "uuid-2", "status": "not_found"}
+///     ]
+/// }
+/// ```
+///
+/// - 200 OK: コミットされた（partial=true の場合は一部失敗を含みうる）
+/// - 422 Unprocessable Entity: invalid / not_found を含むため全てロールバック
+///
+/// # Errors
+///
+/// - 400 Bad Request: 空配列
+/// - 422 Unprocessable Entity: 件数が上限（`MAX_BATCH_SIZE`、デフォルト 100）を超える
+///
+/// # トランザクション
+///
+/// 全項目を単一の UPDATE 文で1トランザクション内で更新する。
+/// 所有者チェックは SQL 側（WHERE user_id = ...）で行う。
+///
+/// # キャッシュ
+///
+/// Cache Invalidation: コミット後、更新された全 ID のキャッシュを無効化する。
+pub async fn batch_update_todos(
+    // UserContext エクストラクタ: 認証済みユーザー情報
+    user: UserContext,
+    // State エクストラクタ: AppState を取得（axum 推奨）
+    State(state): State<AppState>,
+    // Query エクストラクタ: ?partial=true を BatchUpdateQuery にデシリアライズ
+    Query(query): Query<BatchUpdateQuery>,
+    // Json エクストラクタ: リクエストボディを BatchUpdateTodosRequest にデシリアライズ
+    Json(req): Json<BatchUpdateTodosRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    // -------------------------------------------------------------------------
+    // バリデーション: 件数チェック
+    // -------------------------------------------------------------------------
+    if req.todos.is_empty() {
+        return Err(ApiError::BadRequest("todos cannot be empty".to_string()));
+    }
+    ensure_batch_size(req.todos.len(), state.max_batch_size)?;
+
+    // -------------------------------------------------------------------------
+    // バリデーション: 各項目（UpdateTodoDto と同じルール + ID 重複チェック）
+    // -------------------------------------------------------------------------
+    // results: リクエスト順の結果（None = 未確定、DB 更新結果で埋める）
+    let mut results: Vec<Option<BatchUpdateItemResult>> = Vec::with_capacity(req.todos.len());
+    let mut updates: Vec<TodoUpdateInput> = Vec::with_capacity(req.todos.len());
+    let mut seen = HashSet::with_capacity(req.todos.len());
+
+    for item in &req.todos {
+        let mut errors = Vec::new();
+
+        // 同一バッチ内での ID 重複は曖昧なため invalid とする
+        if !seen.insert(item.id) {
+            errors.push(FieldError {
+                field: "id".to_string(),
+                message: "duplicate id in batch".to_string(),
+            });
+        }
+
+        // タイトルのバリデーション（指定時のみ、トリム済みの値を使用）
+        let title = match &item.title {
+            Some(t) => match Todo::validate_title(t) {
+                Ok(t) => Some(t),
+                Err(e) => {
+                    errors.push(FieldError {
+                        field: "title".to_string(),
+                        message: validation_message(e),
+                    });
+                    None
+                }
+            },
+            None => None,
+        };
+
+        if errors.is_empty() {
+            // 有効な項目: DB 更新の入力に追加
+            updates.push(TodoUpdateInput {
+                id: item.id,
+                title,
+                description: item.description.clone().map(Some), // UpdateTodoCommand と同じ扱い
+                completed: item.completed,
+            });
+            results.push(None);
+        } else {
+            // 無効な項目: invalid として確定
+            results.push(Some(BatchUpdateItemResult {
+                id: item.id,
+                status: BatchItemStatus::Invalid,
+                todo: None,
+                errors,
+            }));
+        }
+    }
+
+    let has_invalid = updates.len() < req.todos.len();
+
+    // -------------------------------------------------------------------------
+    // TransactionalTodoService で一括更新
+    // -------------------------------------------------------------------------
+    // 全件モードで invalid がある場合は DB に触れずに全てスキップ
+    let outcome = if updates.is_empty() || (has_invalid && !query.partial) {
+        None
+    } else {
+        Some(
+            state
+                .batch_service
+                .batch_update(user.user_id, updates, query.partial)
+                .await?,
+        )
+    };
+
+    // -------------------------------------------------------------------------
+    // キャッシュ無効化（コミット時のみ）
+    // -------------------------------------------------------------------------
+    invalidate_updated(state.cache.as_ref(), outcome.as_ref()).await;
+
+    // -------------------------------------------------------------------------
+    // レスポンス構築（リクエスト順）
+    // -------------------------------------------------------------------------
+    // コミット時は 200 OK、ロールバック時は 422 Unprocessable Entity
+    let (status, response) = batch_update_response(&req.todos, results, outcome);
+
+    Ok((status, Json(response)))
+}
+
+/// コミットされたバッチ更新の TODO のキャッシュを無効化する
+///
+/// ロールバックされた（committed = false）、または DB に触れなかった（None）場合は何もしない。
+/// キャッシュエラーは警告のみ（DB は更新済み）。
+async fn invalidate_updated(cache: &dyn TodoCacheOps, outcome: Option<&BatchUpdateOutcome>) {
+    let Some(outcome) = outcome.filter(|o| o.committed) else {
+        return;
+    };
+
+    for todo in &outcome.updated {
+        if let Err(e) = cache.delete(todo.id).await {
+            tracing::warn!(todo_id = %todo.id, error = %e, "Failed to invalidate cache");
+        }
+    }
+}
+
+/// バッチ更新のレスポンスをリクエスト順に組み立てる
+///
+/// # Arguments
+///
+/// * `items` - リクエストの項目
+/// * `results` - バリデーションで確定した結果（invalid は Some、それ以外は None）
+/// * `outcome` - DB 更新の結果（DB に触れなかった場合は None）
+///
+/// # Returns
+///
+/// コミット時は 200 OK、ロールバック時（DB に触れなかった場合を含む）は 422 Unprocessable Entity
+fn batch_update_response(
+    items: &[BatchUpdateTodoItem],
+    results: Vec<Option<BatchUpdateItemResult>>,
+    outcome: Option<BatchUpdateOutcome>,
+) -> (StatusCode, BatchUpdateTodosResponse) {
+    let committed = outcome.as_ref().is_some_and(|o| o.committed);
+    let (mut updated, not_found) = match outcome {
+        Some(o) => (o.updated, o.not_found),
+        None => (Vec::new(), Vec::new()),
+    };
+
+    let results = items
+        .iter()
+        .zip(results)
+        .map(|(item, result)| {
+            // invalid は確定済み
+            if let Some(result) = result {
+                return result;
+            }
+
+            let (status, todo) = if not_found.contains(&item.id) {
+                (BatchItemStatus::NotFound, None)
+            } else if committed {
+                // 更新済み: 結果から取り出す（ID は一意）
+                let todo = updated
+                    .iter()
+                    .position(|t| t.id == item.id)
+                    .map(|i| updated.swap_remove(i));
+                (BatchItemStatus::Updated, todo)
+            } else {
+                // ロールバックされたため未適用
+                (BatchItemStatus::Skipped, None)
+            };
+
+            BatchUpdateItemResult {
+                id: item.id,
+                status,
+                todo,
+                errors: Vec::new(),
+            }
+        })
+        .collect();
+
+    let status = if committed {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+
+    (status, BatchUpdateTodosResponse { committed, results })
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use infrastructure::InMemoryTodoCache;
+    use uuid::Uuid;
+
+    fn item(title: &str) -> CreateTodoDto {
+        CreateTodoDto {
+            title: title.to_string(),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_partition_create_items_mixed() {
+        let (valid, invalid) = partition_create_items(vec![item(" a "), item("  "), item("c")]);
+
+        // 有効な項目はトリム済みタイトルと元のインデックスを保持
+        assert_eq!(valid.len(), 2);
+        assert_eq!(valid[0].0, 0);
+        assert_eq!(valid[0].1 .0, "a");
+        assert_eq!(valid[1].0, 2);
+
+        // 無効な項目はインデックスと理由を持つ
+        assert_eq!(invalid.len(), 1);
+        assert_eq!(invalid[0].index, 1);
+        assert_eq!(invalid[0].status, BatchItemStatus::Invalid);
+        assert_eq!(invalid[0].error.as_deref(), Some("title cannot be empty"));
+    }
+
+    #[test]
+    fn test_partition_create_items_all_invalid() {
+        let (valid, invalid) = partition_create_items(vec![item(""), item(" "), item("\t")]);
+
+        assert!(valid.is_empty());
+        assert_eq!(
+            invalid.iter().map(|r| r.index).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn test_merge_create_results_preserves_input_order() {
+        let (valid, invalid) = partition_create_items(vec![item(""), item("b"), item(" ")]);
+        let created = valid
+            .into_iter()
+            .map(|(index, (title, description))| {
+                (index, Todo::new(Uuid::new_v4(), title, description))
+            })
+            .collect();
+
+        let results = merge_create_results(created, invalid);
+
+        assert_eq!(
+            results
+                .iter()
+                .map(|r| (r.index, r.status))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, BatchItemStatus::Invalid),
+                (1, BatchItemStatus::Created),
+                (2, BatchItemStatus::Invalid),
+            ]
+        );
+        let created = results[1].todo.as_ref().unwrap();
+        assert_eq!(created.title, "b");
+        assert_eq!(
+            results[1].url.as_deref(),
+            Some(format!("/api/todos/{}", created.id).as_str())
+        );
+    }
+
+    #[test]
+    fn test_merge_dedupe_results_reports_existing_id() {
+        let (valid, invalid) = partition_create_items(vec![item("a"), item(""), item("A")]);
+        let todo = Todo::new(Uuid::new_v4(), valid[0].1 .0.clone(), None);
+        let first_id = todo.id;
+        let outcomes = vec![
+            (0, CreateOutcome::Created(todo)),
+            (
+                2,
+                CreateOutcome::Duplicate {
+                    existing_id: first_id,
+                },
+            ),
+        ];
+
+        let results = merge_dedupe_results(outcomes, invalid);
+
+        assert_eq!(
+            results
+                .iter()
+                .map(|r| (r.index, r.status, r.existing_id))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, BatchItemStatus::Created, None),
+                (1, BatchItemStatus::Invalid, None),
+                (2, BatchItemStatus::SkippedDuplicate, Some(first_id)),
+            ]
+        );
+        let json = serde_json::to_value(&results[2]).unwrap();
+        assert_eq!(json["status"], "skipped_duplicate");
+        assert_eq!(json["existing_id"], first_id.to_string());
+        assert!(json.get("todo").is_none());
+    }
+
+    #[test]
+    fn test_merge_create_results_all_invalid() {
+        let (_, invalid) = partition_create_items(vec![item(""), item("")]);
+
+        let results = merge_create_results(Vec::new(), invalid);
+
+        assert!(results
+            .iter()
+            .all(|r| r.status == BatchItemStatus::Invalid && r.todo.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_created_response_single_item_sets_location() {
+        let todo = Todo::new(Uuid::new_v4(), "a".to_string(), None);
+        let id = todo.id;
+
+        let response = created_response(vec![todo]);
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(
+            response.headers().get(header::LOCATION).unwrap(),
+            format!("/api/todos/{}", id).as_str()
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        // TODO のフィールドはそのまま（flatten）、url が追加される
+        assert_eq!(json[0]["id"], id.to_string());
+        assert_eq!(json[0]["title"], "a");
+        assert_eq!(json[0]["url"], format!("/api/todos/{}", id));
+    }
+
+    #[tokio::test]
+    async fn test_created_response_multiple_items_omits_location() {
+        let user_id = Uuid::new_v4();
+        let todos = vec![
+            Todo::new(user_id, "a".to_string(), None),
+            Todo::new(user_id, "b".to_string(), None),
+        ];
+        let ids: Vec<Uuid> = todos.iter().map(|t| t.id).collect();
+
+        let response = created_response(todos);
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert!(response.headers().get(header::LOCATION).is_none());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let items = json.as_array().unwrap();
+        assert_eq!(items.len(), 2);
+        for (item, id) in items.iter().zip(ids) {
+            assert_eq!(item["url"], format!("/api/todos/{}", id));
+        }
+    }
+
+    #[test]
+    fn test_ensure_batch_size_at_limit() {
+        assert!(ensure_batch_size(100, 100).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_ensure_batch_size_over_limit() {
+        let err = ensure_batch_size(101, 100).unwrap_err();
+        assert!(matches!(
+            err,
+            ApiError::BatchTooLarge {
+                limit: 100,
+                submitted: 101
+            }
+        ));
+
+        // 422 + code=batch_too_large + limit/submitted
+        let response = err.into_response();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], "batch_too_large");
+        assert_eq!(json["limit"], 100);
+        assert_eq!(json["submitted"], 101);
+    }
+
+    #[test]
+    fn test_batch_mode_defaults_to_atomic() {
+        let req: BatchCreateTodosRequest =
+            serde_json::from_str(r#"{"todos": [{"title": "a"}]}"#).unwrap();
+        assert_eq!(req.mode, BatchMode::Atomic);
+
+        let req: BatchCreateTodosRequest =
+            serde_json::from_str(r#"{"todos": [], "mode": "partial"}"#).unwrap();
+        assert_eq!(req.mode, BatchMode::Partial);
+    }
+
+    #[test]
+    fn test_batch_create_dedupe_defaults_to_false() {
+        let req: BatchCreateTodosRequest =
+            serde_json::from_str(r#"{"todos": [{"title": "a"}]}"#).unwrap();
+        assert!(!req.dedupe && !req.dedupe_existing);
+
+        let req: BatchCreateTodosRequest =
+            serde_json::from_str(r#"{"todos": [], "dedupe": true}"#).unwrap();
+        assert!(req.dedupe && !req.dedupe_existing);
+    }
+
+    // -------------------------------------------------------------------------
+    // batch_update_todos
+    // -------------------------------------------------------------------------
+
+    /// バッチ更新を送信し、ステータスと JSON を返す
+    ///
+    /// AppState::for_tests() の batch_service は接続しないプールを使うため、
+    /// DB に触れると 500 になる（= 422 なら DB に触れていない）。
+    async fn post_batch_update(
+        uri: &str,
+        todos: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        use tower::ServiceExt;
+
+        let app = crate::create_router(AppState::for_tests(), crate::RouterConfig::minimal());
+        let request = axum::http::Request::post(uri)
+            .header("content-type", "application/json")
+            .header("x-user-id", Uuid::new_v4().to_string())
+            .body(axum::body::Body::from(
+                serde_json::json!({ "todos": todos }).to_string(),
+            ))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    fn statuses(json: &serde_json::Value) -> Vec<&str> {
+        json["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["status"].as_str().unwrap())
+            .collect()
+    }
+
+    fn update_item(id: Uuid, title: Option<&str>) -> BatchUpdateTodoItem {
+        BatchUpdateTodoItem {
+            id,
+            title: title.map(str::to_string),
+            description: None,
+            completed: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_update_duplicate_id_is_invalid() {
+        let id = Uuid::new_v4();
+
+        let (status, json) = post_batch_update(
+            "/api/todos/batch-update",
+            serde_json::json!([{ "id": id, "completed": true }, { "id": id, "completed": false }]),
+        )
+        .await;
+
+        // 2 件目が invalid のため全件モードでは DB に触れずにスキップ
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(json["committed"], false);
+        assert_eq!(statuses(&json), vec!["skipped", "invalid"]);
+        assert_eq!(json["results"][1]["errors"][0]["field"], "id");
+        assert_eq!(
+            json["results"][1]["errors"][0]["message"],
+            "duplicate id in batch"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_batch_update_title_error_reports_field_errors() {
+        let (status, json) = post_batch_update(
+            "/api/todos/batch-update?partial=true",
+            serde_json::json!([{ "id": Uuid::new_v4(), "title": "   " }]),
+        )
+        .await;
+
+        // 有効な項目がないため partial でも DB に触れない
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(statuses(&json), vec!["invalid"]);
+        let errors = json["results"][0]["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0]["field"], "title");
+        assert!(!errors[0]["message"].as_str().unwrap().is_empty());
+        assert!(json["results"][0].get("todo").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_batch_update_atomic_invalid_item_skips_rest_without_db() {
+        let (status, json) = post_batch_update(
+            "/api/todos/batch-update",
+            serde_json::json!([
+                { "id": Uuid::new_v4(), "completed": true },
+                { "id": Uuid::new_v4(), "title": "" },
+                { "id": Uuid::new_v4(), "title": "ok" },
+            ]),
+        )
+        .await;
+
+        // 500 ではなく 422: 接続しないプールに触れていない
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(json["committed"], false);
+        assert_eq!(statuses(&json), vec!["skipped", "invalid", "skipped"]);
+    }
+
+    #[test]
+    fn test_batch_update_response_partial_mixed_statuses() {
+        let user_id = Uuid::new_v4();
+        let updated = Todo::new(user_id, "a".to_string(), None);
+        let missing = Uuid::new_v4();
+        let items = vec![
+            update_item(updated.id, Some("a")),
+            update_item(missing, None),
+            update_item(Uuid::new_v4(), Some("")),
+        ];
+        let invalid = BatchUpdateItemResult {
+            id: items[2].id,
+            status: BatchItemStatus::Invalid,
+            todo: None,
+            errors: vec![FieldError {
+                field: "title".to_string(),
+                message: "title cannot be empty".to_string(),
+            }],
+        };
+        let outcome = BatchUpdateOutcome {
+            updated: vec![updated.clone()],
+            not_found: vec![missing],
+            committed: true,
+        };
+
+        let (status, response) =
+            batch_update_response(&items, vec![None, None, Some(invalid)], Some(outcome));
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(response.committed);
+        assert_eq!(
+            response
+                .results
+                .iter()
+                .map(|r| r.status)
+                .collect::<Vec<_>>(),
+            vec![
+                BatchItemStatus::Updated,
+                BatchItemStatus::NotFound,
+                BatchItemStatus::Invalid,
+            ]
+        );
+        assert_eq!(response.results[0].todo.as_ref().unwrap().id, updated.id);
+        assert_eq!(response.results[2].errors[0].field, "title");
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_updated_only_removes_committed_ids() {
+        let user_id = Uuid::new_v4();
+        let updated = Todo::new(user_id, "a".to_string(), None);
+        let untouched = Todo::new(user_id, "b".to_string(), None);
+        let cache = InMemoryTodoCache::new();
+        cache.set(&updated).await.unwrap();
+        cache.set(&untouched).await.unwrap();
+
+        // ロールバック: 何も削除しない
+        let rolled_back = BatchUpdateOutcome {
+            updated: vec![updated.clone()],
+            not_found: Vec::new(),
+            committed: false,
+        };
+        invalidate_updated(&cache, Some(&rolled_back)).await;
+        invalidate_updated(&cache, None).await;
+        assert!(cache.get(updated.id).is_some());
+
+        // コミット: 更新された ID のみ削除
+        let committed = BatchUpdateOutcome {
+            committed: true,
+            ..rolled_back
+        };
+        invalidate_updated(&cache, Some(&committed)).await;
+        assert!(cache.get(updated.id).is_none());
+        assert!(cache.get(untouched.id).is_some());
+    }
+}
@@ -0,0 +1,718 @@
+// =============================================================================
+// presentation/src/handlers/csv_import.rs: CSV インポートハンドラ
+// =============================================================================
+// CSV ファイルから TODO を一括作成する。
+// 作成は JSON のバッチ作成と同じく TransactionalTodoService を使用する。
+//
+// エンドポイント:
+// - POST /api/todos/import-csv               - atomic モード（デフォルト）
+// - POST /api/todos/import-csv?mode=partial  - partial モード
+//
+// 受け付ける形式:
+// - multipart/form-data: 最初のフィールドを CSV ファイルとして扱う
+// - text/csv: リクエストボディをそのまま CSV として扱う
+//
+// CSV の形式:
+// - 1 行目はヘッダー行（列名は大文字・小文字を区別しない、未知の列は無視）
+// - title（必須）, description, completed, priority, due_date, tags
+// - tags はセミコロン区切り（例: "work;urgent"）
+// - UTF-8（先頭の BOM は無視）
+//
+// 処理モード:
+// - atomic: 1 行でも不正なら何も作成しない。MAX_BATCH_SIZE 行まで、1トランザクション
+// - partial: 有効な行のみ作成する。IMPORT_MAX_ITEMS 行まで、
+//   MAX_BATCH_SIZE 行ごとのチャンク単位でコミットする
+//
+// 制限事項:
+// - priority / due_date / tags は検証のみ行い、保存しない
+//   （todos テーブルに対応するカラムがないため）
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// 外部クレートのインポート
+// -----------------------------------------------------------------------------
+
+// axum: Web フレームワーク
+// body::Bytes: text/csv のリクエストボディ
+// extract::{FromRequest, Multipart, Query, Request, State}: リクエストの抽出
+// http::{header, StatusCode}: Content-Type ヘッダー、ステータスコード
+// response::{IntoResponse, Response}: レスポンス変換
+use axum::{
+    body::Bytes,
+    extract::{FromRequest, Multipart, Query, Request, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+// chrono: due_date の検証
+use chrono::NaiveDate;
+
+// csv: CSV の解析
+use csv::{ReaderBuilder, StringRecord, Trim};
+
+// serde: クエリパラメータのデシリアライズ
+use serde::Deserialize;
+
+// domain: ドメイン層の型
+use domain::Todo;
+
+// infrastructure: Infrastructure 層の型
+// TodoCreateInput: 完了状態を含むバッチ作成入力
+use infrastructure::TodoCreateInput;
+
+// application: Application 層の DTO
+use application::dto::{
+    todo_url,           // TODO の正規 URL
+    BatchItemStatus,    // 1 行分の処理結果
+    BatchMode,          // 処理モード（atomic / partial）
+    CsvImportResponse,  // CSV インポートのレスポンス
+    CsvImportRowResult, // CSV インポート 1 行分の結果
+    FieldError,         // フィールド単位のバリデーションエラー
+};
+
+// crate: このクレート内のモジュール
+use crate::error::ApiError; // API エラー型
+use crate::handlers::batch::{ensure_batch_size, validation_message}; // バッチ作成と共通の検証
+use crate::middleware::UserContext; // 認証済みユーザー情報
+use crate::state::AppState; // アプリケーション状態
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// UTF-8 の BOM（Excel などが先頭に付与する）
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// priority 列で許可される値
+const PRIORITIES: [&str; 3] = ["low", "medium", "high"];
+
+/// タグ 1 つあたりの最大文字数
+const MAX_TAG_LENGTH: usize = 50;
+
+// =============================================================================
+// import_todos_csv ハンドラ
+// =============================================================================
+
+/// CSV インポートのクエリパラメータ
+///
+/// POST /api/todos/import-csv?mode=partial のようなクエリパラメータを受け取る。
+#[derive(Debug, Deserialize)]
+pub struct CsvImportQuery {
+    /// 処理モード（省略時は atomic）
+    #[serde(default)]
+    pub mode: BatchMode,
+}
+
+/// CSV から TODO をインポート
+///
+/// POST /api/todos/import-csv
+///
+/// # Request Body
+///
+/// `multipart/form-data`（最初のフィールド）または `text/csv`:
+///
+/// ```csv
+/// title,description,completed,priority,due_date,tags
+/// 買い物,牛乳と卵,false,high,2024-01-31,home;errand
+/// 資料作成,,true,,,
+/// ```
+///
+/// # Response
+///
+/// 行ごとの結果を CSV の行番号（ヘッダー行が 1）付きで返す。
+///
+/// ```json
+/// {
+///     "committed": true,
+///     "total": 2,
+///     "imported": 2,
+///     "failed": 0,
+///     "results": [
+///         {"line": 2, "status": "created", "todo": {...}, "url": "/api/todos/uuid"},
+///         {"line": 3, "status": "created", "todo": {...}, "url": "/api/todos/uuid"}
+///     ]
+/// }
+/// ```
+///
+/// - 201 Created: atomic モードで全行を作成した
+/// - 400 Bad Request: atomic モードで不正な行がある（`committed: false`、有効な行は skipped）
+/// - 207 Multi-Status: partial モード（不正な行は invalid、DB エラー以降の行は skipped）
+///
+/// # Errors
+///
+/// - 400 Bad Request: Content-Type が不正、データ行がない
+/// - 422 Unprocessable Entity: CSV として解析できない（`code=malformed_csv`、`line` を含む）
+/// - 422 Unprocessable Entity: 行数が上限を超える（`code=batch_too_large`）
+///   - atomic モード: `MAX_BATCH_SIZE`（デフォルト 100）
+///   - partial モード: `IMPORT_MAX_ITEMS`（デフォルト 10000）
+///
+/// # キャッシュ
+///
+/// Write-Through: コミット後に作成された TODO をキャッシュに保存する。
+pub async fn import_todos_csv(
+    // UserContext エクストラクタ: 認証済みユーザー情報
+    user: UserContext,
+    // State エクストラクタ: AppState を取得（axum 推奨）
+    State(state): State<AppState>,
+    // Query エクストラクタ: ?mode=partial を CsvImportQuery にデシリアライズ
+    Query(query): Query<CsvImportQuery>,
+    // Request: Content-Type に応じてボディの読み取り方法を切り替える（最後の引数であること）
+    request: Request,
+) -> Result<Response, ApiError> {
+    // -------------------------------------------------------------------------
+    // ボディの読み取りと解析
+    // -------------------------------------------------------------------------
+    let data = match read_csv_body(request).await? {
+        Ok(data) => data,
+        // エクストラクタの拒否（ボディサイズ超過など）はそのまま返す
+        Err(rejection) => return Ok(rejection),
+    };
+    let rows = parse_csv(&data)?;
+
+    if rows.is_empty() {
+        return Err(ApiError::BadRequest("csv contains no rows".to_string()));
+    }
+
+    // 行数の上限: atomic は1トランザクションのため MAX_BATCH_SIZE、
+    // partial はチャンク単位でコミットするため IMPORT_MAX_ITEMS
+    let limit = match query.mode {
+        BatchMode::Atomic => state.max_batch_size,
+        BatchMode::Partial => state.max_import_items,
+    };
+    ensure_batch_size(rows.len(), limit)?;
+
+    let total = rows.len();
+    let (valid, invalid) = partition_rows(rows);
+
+    // -------------------------------------------------------------------------
+    // atomic モード: 1 行でも不正なら何も作成しない
+    // -------------------------------------------------------------------------
+    if query.mode == BatchMode::Atomic && !invalid.is_empty() {
+        let failed = invalid.len();
+        let skipped = valid
+            .into_iter()
+            .map(|(line, _)| row_result(line, BatchItemStatus::Skipped));
+        let response = CsvImportResponse {
+            committed: false,
+            total,
+            imported: 0,
+            failed,
+            results: sort_results(invalid.into_iter().chain(skipped).collect()),
+        };
+        return Ok((StatusCode::BAD_REQUEST, Json(response)).into_response());
+    }
+
+    // -------------------------------------------------------------------------
+    // TransactionalTodoService でチャンクごとに作成
+    // -------------------------------------------------------------------------
+    // atomic モードは行数が MAX_BATCH_SIZE 以下のため、常に1チャンク（1トランザクション）
+    let mut results = invalid;
+    let mut created = Vec::with_capacity(valid.len());
+    // TENANT_SCOPING=true の場合は各行にテナント ID を記録する
+    let tenant_id = user.tenant_scope(state.tenant_scoping);
+    let mut remaining = valid
+        .into_iter()
+        .map(|(line, input)| (line, TodoCreateInput { tenant_id, ..input }));
+
+    loop {
+        let (lines, inputs): (Vec<u64>, Vec<TodoCreateInput>) =
+            remaining.by_ref().take(state.max_batch_size.max(1)).unzip();
+        if inputs.is_empty() {
+            break;
+        }
+
+        match state
+            .batch_service
+            .batch_create_inputs(user.user_id, inputs)
+            .await
+        {
+            Ok(todos) => created.extend(lines.into_iter().zip(todos)),
+            // atomic モード: 作成済みのチャンクはないため、そのままエラーを返す
+            Err(e) if query.mode == BatchMode::Atomic => return Err(e.into()),
+            // partial モード: コミット済みのチャンクは残し、以降の行は skipped とする
+            Err(e) => {
+                tracing::warn!(
+                    user_id = %user.user_id,
+                    imported = created.len(),
+                    error = %e,
+                    "CSV import stopped after chunk failure"
+                );
+                results.extend(
+                    lines
+                        .into_iter()
+                        .chain(remaining.by_ref().map(|(line, _)| line))
+                        .map(|line| row_result(line, BatchItemStatus::Skipped)),
+                );
+                break;
+            }
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // キャッシュ更新（コミット後、Write-Through）
+    // -------------------------------------------------------------------------
+    for (_, todo) in &created {
+        // キャッシュエラーは警告のみ（DB はコミット済み）
+        if let Err(e) = state.cache.set(todo).await {
+            tracing::warn!(todo_id = %todo.id, error = %e, "Failed to cache imported todo");
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // レスポンス構築（行番号順）
+    // -------------------------------------------------------------------------
+    let failed = results
+        .iter()
+        .filter(|r| r.status == BatchItemStatus::Invalid)
+        .count();
+    let imported = created.len();
+    results.extend(created.into_iter().map(|(line, todo)| CsvImportRowResult {
+        line,
+        status: BatchItemStatus::Created,
+        url: Some(todo_url(todo.id)),
+        todo: Some(todo),
+        errors: Vec::new(),
+    }));
+
+    let response = CsvImportResponse {
+        committed: imported > 0,
+        total,
+        imported,
+        failed,
+        results: sort_results(results),
+    };
+
+    // atomic モード: 201 Created、partial モード: 207 Multi-Status
+    let status = match query.mode {
+        BatchMode::Atomic => StatusCode::CREATED,
+        BatchMode::Partial => StatusCode::MULTI_STATUS,
+    };
+    Ok((status, Json(response)).into_response())
+}
+
+// -----------------------------------------------------------------------------
+// ボディの読み取り
+// -----------------------------------------------------------------------------
+
+/// Content-Type に応じて CSV のバイト列を読み取る
+///
+/// # Returns
+///
+/// * `Ok(Ok(bytes))` - CSV のバイト列
+/// * `Ok(Err(response))` - エクストラクタの拒否レスポンス（413 など、そのまま返す）
+///
+/// # Errors
+///
+/// Content-Type が multipart/form-data / text/csv 以外、またはファイルがない場合は 400
+async fn read_csv_body(request: Request) -> Result<Result<Bytes, Response>, ApiError> {
+    let content_type = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    // multipart/form-data: 最初のフィールドを CSV ファイルとして扱う
+    if content_type.starts_with("multipart/form-data") {
+        let mut multipart = match Multipart::from_request(request, &()).await {
+            Ok(multipart) => multipart,
+            Err(rejection) => return Ok(Err(rejection.into_response())),
+        };
+        let Some(field) = multipart
+            .next_field()
+            .await
+            .map_err(|e| ApiError::BadRequest(format!("Failed to read multipart field: {}", e)))?
+        else {
+            return Err(ApiError::BadRequest("No file provided".to_string()));
+        };
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| ApiError::BadRequest(format!("Failed to read file data: {}", e)))?;
+        return Ok(Ok(data));
+    }
+
+    // text/csv: ボディをそのまま CSV として扱う
+    if content_type.starts_with("text/csv") {
+        return Ok(Bytes::from_request(request, &())
+            .await
+            .map_err(IntoResponse::into_response));
+    }
+
+    Err(ApiError::BadRequest(
+        "Content-Type must be multipart/form-data or text/csv".to_string(),
+    ))
+}
+
+// -----------------------------------------------------------------------------
+// CSV の解析
+// -----------------------------------------------------------------------------
+
+/// CSV のデータ行（1 行分、検証済み）
+#[derive(Debug)]
+struct CsvRow {
+    /// CSV ファイル上の行番号（1 始まり、ヘッダー行が 1）
+    line: u64,
+    /// 作成入力、またはフィールドごとのバリデーションエラー
+    result: Result<TodoCreateInput, Vec<FieldError>>,
+}
+
+/// ヘッダー行から求めた各列の位置
+#[derive(Debug)]
+struct CsvColumns {
+    title: usize,
+    description: Option<usize>,
+    completed: Option<usize>,
+    priority: Option<usize>,
+    due_date: Option<usize>,
+    tags: Option<usize>,
+}
+
+impl CsvColumns {
+    /// ヘッダー行から列の位置を求める
+    ///
+    /// 列名は大文字・小文字を区別しない。同名の列がある場合は最初の列を使用する。
+    ///
+    /// # Errors
+    ///
+    /// ヘッダー行がない、または title 列がない場合は `ApiError::MalformedCsv`（line = 1）
+    fn from_headers(headers: &StringRecord) -> Result<Self, ApiError> {
+        let find = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name));
+
+        let title = find("title").ok_or_else(|| ApiError::MalformedCsv {
+            line: Some(1),
+            message: "missing required column: title".to_string(),
+        })?;
+
+        Ok(Self {
+            title,
+            description: find("description"),
+            completed: find("completed"),
+            priority: find("priority"),
+            due_date: find("due_date"),
+            tags: find("tags"),
+        })
+    }
+}
+
+/// CSV を解析し、各データ行を検証する
+///
+/// 行単位のバリデーションエラーは `CsvRow::result` に格納し、
+/// CSV として解析できない場合（列数の不一致、不正な UTF-8 など）のみエラーを返す。
+///
+/// # Errors
+///
+/// 最初の解析エラーの位置を含む `ApiError::MalformedCsv`
+fn parse_csv(data: &[u8]) -> Result<Vec<CsvRow>, ApiError> {
+    // UTF-8 の BOM を除去（ヘッダー名の先頭に混入しないように）
+    let data = data.strip_prefix(UTF8_BOM).unwrap_or(data);
+
+    let mut reader = ReaderBuilder::new()
+        .has_headers(true)
+        .trim(Trim::All)
+        .from_reader(data);
+
+    let malformed = |err: csv::Error| ApiError::MalformedCsv {
+        line: err.position().map(|p| line_at(data, p)),
+        message: err.to_string(),
+    };
+
+    let columns = CsvColumns::from_headers(reader.headers().map_err(malformed)?)?;
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(malformed)?;
+        let line = record.position().map_or(0, |p| line_at(data, p));
+        rows.push(CsvRow {
+            line,
+            result: parse_row(&columns, &record),
+        });
+    }
+
+    Ok(rows)
+}
+
+/// 1 行分のフィールドを検証し、作成入力に変換する
+///
+/// 全フィールドを検証し、エラーはまとめて返す。
+fn parse_row(
+    columns: &CsvColumns,
+    record: &StringRecord,
+) -> Result<TodoCreateInput, Vec<FieldError>> {
+    // 列がない、または空の値は未指定とみなす
+    let value = |index: Option<usize>| index.and_then(|i| record.get(i)).filter(|v| !v.is_empty());
+
+    let mut errors = Vec::new();
+    let mut push_error = |field: &str, message: String| {
+        errors.push(FieldError {
+            field: field.to_string(),
+            message,
+        })
+    };
+
+    // title: 必須（Todo::validate_title と同じルール）
+    let title = match Todo::validate_title(record.get(columns.title).unwrap_or_default()) {
+        Ok(title) => Some(title),
+        Err(e) => {
+            push_error("title", validation_message(e));
+            None
+        }
+    };
+
+    // completed: 省略時は false
+    let completed = match value(columns.completed).map(parse_bool) {
+        None => false,
+        Some(Some(completed)) => completed,
+        Some(None) => {
+            push_error("completed", "must be true or false".to_string());
+            false
+        }
+    };
+
+    // priority: low / medium / high（検証のみ）
+    if let Some(priority) = value(columns.priority) {
+        if !PRIORITIES.iter().any(|p| p.eq_ignore_ascii_case(priority)) {
+            push_error(
+                "priority",
+                format!("must be one of {}", PRIORITIES.join(", ")),
+            );
+        }
+    }
+
+    // due_date: YYYY-MM-DD（検証のみ）
+    if let Some(due_date) = value(columns.due_date) {
+        if NaiveDate::parse_from_str(due_date, "%Y-%m-%d").is_err() {
+            push_error(
+                "due_date",
+                "must be a date in YYYY-MM-DD format".to_string(),
+            );
+        }
+    }
+
+    // tags: セミコロン区切り、空のタグは無視（検証のみ）
+    if let Some(tags) = value(columns.tags) {
+        if tags
+            .split(';')
+            .any(|tag| tag.trim().chars().count() > MAX_TAG_LENGTH)
+        {
+            push_error(
+                "tags",
+                format!("each tag must be at most {} characters", MAX_TAG_LENGTH),
+            );
+        }
+    }
+
+    match title {
+        Some(title) if errors.is_empty() => Ok(TodoCreateInput {
+            title,
+            description: value(columns.description).map(str::to_string),
+            completed,
+            tenant_id: None,
+        }),
+        _ => Err(errors),
+    }
+}
+
+/// 真偽値の表記を解釈する（大文字・小文字を区別しない）
+///
+/// true / false / 1 / 0 / yes / no を受け付ける。
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" => Some(true),
+        "false" | "0" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// レコードの開始位置からファイル上の行番号（1 始まり）を求める
+///
+/// csv::Position は読み飛ばした空行の位置を指すことがあるため、
+/// 空行を飛ばした後のバイト位置までの改行数から求める。
+fn line_at(data: &[u8], position: &csv::Position) -> u64 {
+    let start = (position.byte() as usize).min(data.len());
+    let skipped = data[start..]
+        .iter()
+        .take_while(|&&b| b == b'\n' || b == b'\r')
+        .count();
+    data[..start + skipped]
+        .iter()
+        .filter(|&&b| b == b'\n')
+        .count() as u64
+        + 1
+}
+
+// -----------------------------------------------------------------------------
+// 結果のヘルパー関数
+// -----------------------------------------------------------------------------
+
+/// 有効な行と無効な行の結果に分ける
+///
+/// # Returns
+///
+/// * 有効な行: (行番号, 作成入力) のリスト
+/// * 無効な行: status = invalid の結果リスト
+fn partition_rows(rows: Vec<CsvRow>) -> (Vec<(u64, TodoCreateInput)>, Vec<CsvImportRowResult>) {
+    let mut valid = Vec::with_capacity(rows.len());
+    let mut invalid = Vec::new();
+
+    for row in rows {
+        match row.result {
+            Ok(input) => valid.push((row.line, input)),
+            Err(errors) => invalid.push(CsvImportRowResult {
+                errors,
+                ..row_result(row.line, BatchItemStatus::Invalid)
+            }),
+        }
+    }
+
+    (valid, invalid)
+}
+
+/// TODO を含まない 1 行分の結果を作成する
+fn row_result(line: u64, status: BatchItemStatus) -> CsvImportRowResult {
+    CsvImportRowResult {
+        line,
+        status,
+        todo: None,
+        url: None,
+        errors: Vec::new(),
+    }
+}
+
+/// 結果を行番号順に並べる
+fn sort_results(mut results: Vec<CsvImportRowResult>) -> Vec<CsvImportRowResult> {
+    results.sort_by_key(|r| r.line);
+    results
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors_of(row: &CsvRow) -> Vec<&str> {
+        match &row.result {
+            Ok(_) => Vec::new(),
+            Err(errors) => errors.iter().map(|e| e.field.as_str()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_csv_strips_bom_and_matches_headers_case_insensitively() {
+        let data = b"\xEF\xBB\xBFTitle,DESCRIPTION,Completed\n a ,desc,yes\nb,,\n";
+
+        let rows = parse_csv(data).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        let first = rows[0].result.as_ref().unwrap();
+        assert_eq!(first.title, "a");
+        assert_eq!(first.description.as_deref(), Some("desc"));
+        assert!(first.completed);
+
+        // 空の値は未指定（description = None、completed = false）
+        let second = rows[1].result.as_ref().unwrap();
+        assert_eq!(second.description, None);
+        assert!(!second.completed);
+    }
+
+    #[test]
+    fn test_parse_csv_reports_file_line_numbers() {
+        // 引用符内の改行を含む行があっても、ファイル上の開始行を返す
+        let data = b"title,description\na,\"line1\nline2\"\n\nb,\n";
+
+        let rows = parse_csv(data).unwrap();
+
+        assert_eq!(rows.iter().map(|r| r.line).collect::<Vec<_>>(), vec![2, 5]);
+    }
+
+    #[test]
+    fn test_parse_csv_collects_row_validation_errors() {
+        let data = b"title,completed,priority,due_date,tags\n\
+            ,maybe,urgent,2024-13-01,ok;x\n\
+            a,0,HIGH,2024-01-31,work; home\n";
+
+        let rows = parse_csv(data).unwrap();
+
+        assert_eq!(
+            errors_of(&rows[0]),
+            vec!["title", "completed", "priority", "due_date"]
+        );
+        assert!(rows[1].result.is_ok());
+    }
+
+    #[test]
+    fn test_parse_csv_rejects_overlong_tag() {
+        let data = format!("title,tags\na,ok;{}\n", "x".repeat(MAX_TAG_LENGTH + 1));
+
+        let rows = parse_csv(data.as_bytes()).unwrap();
+
+        assert_eq!(errors_of(&rows[0]), vec!["tags"]);
+    }
+
+    #[test]
+    fn test_parse_csv_missing_title_column_is_malformed() {
+        let err = parse_csv(b"name,description\na,b\n").unwrap_err();
+
+        assert!(matches!(err, ApiError::MalformedCsv { line: Some(1), .. }));
+    }
+
+    #[tokio::test]
+    async fn test_parse_csv_unequal_fields_reports_line() {
+        let err = parse_csv(b"title,description\na,b\nc\n").unwrap_err();
+
+        assert!(matches!(err, ApiError::MalformedCsv { line: Some(3), .. }));
+
+        // 422 + code=malformed_csv + line
+        let response = err.into_response();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], "malformed_csv");
+        assert_eq!(json["line"], 3);
+    }
+
+    #[test]
+    fn test_parse_csv_invalid_utf8_is_malformed() {
+        let err = parse_csv(b"title\n\xFF\xFE\n").unwrap_err();
+
+        assert!(matches!(err, ApiError::MalformedCsv { line: Some(2), .. }));
+    }
+
+    #[test]
+    fn test_partition_rows_keeps_line_numbers() {
+        let rows = parse_csv(b"title\na\n \nc\n").unwrap();
+
+        let (valid, invalid) = partition_rows(rows);
+
+        assert_eq!(
+            valid.iter().map(|(line, _)| *line).collect::<Vec<_>>(),
+            vec![2, 4]
+        );
+        assert_eq!(invalid.len(), 1);
+        assert_eq!(invalid[0].line, 3);
+        assert_eq!(invalid[0].status, BatchItemStatus::Invalid);
+        assert_eq!(invalid[0].errors[0].message, "title cannot be empty");
+    }
+
+    #[test]
+    fn test_csv_import_query_defaults_to_atomic() {
+        let parse = |uri: &str| {
+            Query::<CsvImportQuery>::try_from_uri(&uri.parse().unwrap())
+                .unwrap()
+                .0
+                .mode
+        };
+
+        assert_eq!(parse("/api/todos/import-csv"), BatchMode::Atomic);
+        assert_eq!(
+            parse("/api/todos/import-csv?mode=partial"),
+            BatchMode::Partial
+        );
+    }
+}
@@ -34,9 +34,6 @@ use serde::Serialize;
 // uuid: 一意識別子
 use uuid::Uuid;
 
-// domain: ドメイン層の型とトレイト
-use domain::{StorageOps, TodoCacheOps, TodoReader, TodoWriter, UserReader, UserWriter};
-
 // crate: このクレート内のモジュール
 use crate::error::ApiError;
 use crate::middleware::UserContext;
@@ -103,17 +100,10 @@ pub struct FileUploadResponse {
 ///
 /// Handler → UploadFileCommand → StorageOps
 /// バリデーションは Application 層（UploadFileCommand）で実行される。
-pub async fn upload_file<
-    TW: TodoWriter + 'static,
-    TR: TodoReader + 'static,
-    C: TodoCacheOps + 'static,
-    UR: UserReader + 'static,
-    UW: UserWriter + 'static,
-    S: StorageOps + 'static,
->(
+pub async fn upload_file(
     // State エクストラクタ: AppState を取得（axum 推奨）
     // axum が各リクエストで state.clone() を呼び出す
-    State(state): State<AppState<TW, TR, C, UR, UW, S>>,
+    State(state): State<AppState>,
     user: UserContext,
     mut multipart: Multipart,
 ) -> Result<impl IntoResponse, ApiError> {
@@ -189,17 +179,10 @@ pub async fn upload_file<
 /// # Clean Architecture
 ///
 /// Handler → DownloadFileQuery → FileReader + TodoReader + StorageOps
-pub async fn download_file<
-    TW: TodoWriter + 'static,
-    TR: TodoReader + 'static,
-    C: TodoCacheOps + 'static,
-    UR: UserReader + 'static,
-    UW: UserWriter + 'static,
-    S: StorageOps + 'static,
->(
+pub async fn download_file(
     // State エクストラクタ: AppState を取得（axum 推奨）
     // axum が各リクエストで state.clone() を呼び出す
-    State(state): State<AppState<TW, TR, C, UR, UW, S>>,
+    State(state): State<AppState>,
     user: UserContext,
     Path(id): Path<Uuid>,
 ) -> Result<Response, ApiError> {
@@ -246,17 +229,10 @@ pub async fn download_file<
 /// # Clean Architecture
 ///
 /// Handler → DeleteFileCommand → FileReader + FileWriter + TodoReader + StorageOps
-pub async fn delete_file<
-    TW: TodoWriter + 'static,
-    TR: TodoReader + 'static,
-    C: TodoCacheOps + 'static,
-    UR: UserReader + 'static,
-    UW: UserWriter + 'static,
-    S: StorageOps + 'static,
->(
+pub async fn delete_file(
     // State エクストラクタ: AppState を取得（axum 推奨）
     // axum が各リクエストで state.clone() を呼び出す
-    State(state): State<AppState<TW, TR, C, UR, UW, S>>,
+    State(state): State<AppState>,
     user: UserContext,
     Path(id): Path<Uuid>,
 ) -> Result<StatusCode, ApiError> {
@@ -10,7 +10,12 @@
 // - ロードバランサーのヘルスチェック: インスタンスが正常か確認
 //
 // エンドポイント:
-// GET /health → 200 OK {"status": "ok"}
+// GET /health → 200 OK {"status": "ok"}（liveness: 依存サービスを確認しない）
+// GET /health?deep=true → 200 / 503（/ready と同じ疎通確認の結果を checks に含める、gateway の deep ヘルスチェック）
+// GET /ready  → 200 / 503（readiness: 登録された依存サービスの疎通を確認）
+//
+// 内部リスナー（INTERNAL_ADDR、create_internal_router）では同じハンドラを
+// /livez・/readyz として公開し、/metrics（Prometheus 形式）を追加する。
 // =============================================================================
 
 // -----------------------------------------------------------------------------
@@ -21,35 +26,397 @@
 // StatusCode: HTTP ステータスコード（200, 404 など）
 // IntoResponse: 任意の型を HTTP レスポンスに変換するトレイト
 // Json: JSON レスポンスを構築するヘルパー
-use axum::{http::StatusCode, response::IntoResponse, Json};
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+
+use crate::state::AppState;
 
 // =============================================================================
 // healthz ハンドラ
 // =============================================================================
 
+/// ヘルスチェックのクエリパラメータ
+#[derive(Debug, Default, Deserialize)]
+pub struct HealthQuery {
+    /// "true" の場合は依存サービスの疎通も確認する（それ以外の値・未指定は確認しない）
+    deep: Option<String>,
+}
+
 /// ヘルスチェックエンドポイント
 ///
 /// GET /health
 /// サーバーが稼働中かどうかを確認する。
 ///
+/// # Arguments
+///
+/// * `query` - `deep=true` の場合は登録された疎通確認（readiness_checks）も実行する
+///
 /// # Returns
 ///
-/// `impl IntoResponse` - HTTP レスポンス（200 OK + JSON）
+/// `impl IntoResponse` - HTTP レスポンス（200 OK + JSON、deep で失敗したチェックがあれば 503）
 ///
 /// # Response Format
 ///
 /// ```json
 /// {"status": "ok"}
+/// {"status": "ok", "checks": {"postgres": "ok", "redis": "ok"}}
+/// {"status": "unavailable", "checks": {"postgres": "ok", "redis": "error: ..."}}
 /// ```
 ///
 /// # Note
 ///
 /// このエンドポイントは認証不要。
-/// DB 接続やキャッシュ接続のチェックは行わない（シンプルな liveness check）。
-/// より詳細なヘルスチェックが必要な場合は、/ready エンドポイントを追加検討。
-pub async fn healthz() -> impl IntoResponse {
-    // タプル (StatusCode, Json<Value>) を返す
-    // axum は IntoResponse を実装しているため、自動的に HTTP レスポンスに変換される
-    // serde_json::json! マクロで JSON オブジェクトを構築
-    (StatusCode::OK, Json(serde_json::json!({"status": "ok"})))
+/// 既定では DB 接続やキャッシュ接続のチェックは行わない（シンプルな liveness check）。
+/// gateway の /health?deep=true はこのパラメータをそのまま転送する。
+pub async fn healthz(
+    State(state): State<AppState>,
+    Query(query): Query<HealthQuery>,
+) -> impl IntoResponse {
+    if query.deep.as_deref() != Some("true") {
+        // タプル (StatusCode, Json<Value>) を返す
+        // axum は IntoResponse を実装しているため、自動的に HTTP レスポンスに変換される
+        // serde_json::json! マクロで JSON オブジェクトを構築
+        return (StatusCode::OK, Json(serde_json::json!({"status": "ok"})));
+    }
+
+    let (ready, checks) = run_checks(&state).await;
+    let (status, label) = if ready {
+        (StatusCode::OK, "ok")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "unavailable")
+    };
+    (
+        status,
+        Json(serde_json::json!({"status": label, "checks": checks})),
+    )
+}
+
+// =============================================================================
+// readyz ハンドラ
+// =============================================================================
+
+/// レディネスチェックエンドポイント
+///
+/// GET /ready
+/// AppState に登録された疎通確認（readiness_checks）を順に実行する。
+/// 登録されるチェックは構成に依存する（CACHE_BACKEND=none では Redis を確認しない）。
+///
+/// # Returns
+///
+/// - 200 OK: 全てのチェックが成功（チェックが登録されていない場合も含む）
+/// - 503 Service Unavailable: いずれかのチェックが失敗
+///
+/// # Response Format
+///
+/// ```json
+/// {"status": "ready", "checks": {"postgres": "ok", "redis": "ok"}}
+/// {"status": "unavailable", "checks": {"postgres": "ok", "redis": "error: ..."}}
+/// ```
+pub async fn readyz(State(state): State<AppState>) -> impl IntoResponse {
+    let (ready, checks) = run_checks(&state).await;
+
+    let (status, label) = if ready {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "unavailable")
+    };
+    (
+        status,
+        Json(serde_json::json!({"status": label, "checks": checks})),
+    )
+}
+
+/// 登録された疎通確認を順に実行する（readyz・healthz の deep）
+///
+/// # Returns
+///
+/// `(bool, Map)` - 全てのチェックが成功したか、チェック名ごとの結果（"ok" / "error: ..."）
+async fn run_checks(state: &AppState) -> (bool, serde_json::Map<String, serde_json::Value>) {
+    let mut ready = true;
+    let mut checks = serde_json::Map::new();
+
+    for check in &state.readiness_checks {
+        let result = match check.check().await {
+            Ok(()) => "ok".to_string(),
+            Err(e) => {
+                tracing::warn!(check = check.name(), error = %e, "Readiness check failed");
+                ready = false;
+                format!("error: {}", e)
+            }
+        };
+        checks.insert(check.name().to_string(), result.into());
+    }
+
+    (ready, checks)
+}
+
+// =============================================================================
+// metrics ハンドラ
+// =============================================================================
+
+/// メトリクスエンドポイント（Prometheus テキスト形式）
+///
+/// GET /metrics（内部リスナーのみ）
+///
+/// プロセスの稼働・ビルド情報と、登録された疎通確認の結果を gauge として返す。
+/// 疎通確認はスクレイプのたびに実行する（/readyz と同じ）。
+/// API ルーターの HTTP メトリクス（RouterConfig::metrics）と、
+/// 同時実行制限（RouterConfig::concurrency）のグループごとの処理中リクエスト数・上限も出力する。
+///
+/// # Response Format
+///
+/// ```text
+/// todo_api_up 1
+/// todo_api_build_info{version="0.1.0"} 1
+/// todo_api_dependency_ready{check="postgres"} 1
+/// todo_api_http_requests_total{class="2xx"} 42
+/// todo_api_http_requests_in_flight 3
+/// todo_api_concurrency_in_flight{group="batch"} 1
+/// todo_api_concurrency_limit{group="batch"} 8
+/// ```
+pub async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    let mut body = String::new();
+    body.push_str("# HELP todo_api_up Whether the API process is running.\n");
+    body.push_str("# TYPE todo_api_up gauge\n");
+    body.push_str("todo_api_up 1\n");
+    body.push_str("# HELP todo_api_build_info Build information.\n");
+    body.push_str("# TYPE todo_api_build_info gauge\n");
+    body.push_str(&format!(
+        "todo_api_build_info{{version=\"{}\"}} 1\n",
+        env!("CARGO_PKG_VERSION")
+    ));
+    body.push_str(
+        "# HELP todo_api_dependency_ready Result of the readiness check per dependency (1 = ok).\n",
+    );
+    body.push_str("# TYPE todo_api_dependency_ready gauge\n");
+    for check in &state.readiness_checks {
+        let ready = u8::from(check.check().await.is_ok());
+        body.push_str(&format!(
+            "todo_api_dependency_ready{{check=\"{}\"}} {}\n",
+            check.name(),
+            ready
+        ));
+    }
+    body.push_str("# HELP todo_api_http_requests_total Completed HTTP requests by status class.\n");
+    body.push_str("# TYPE todo_api_http_requests_total counter\n");
+    for (class, count) in state.http_metrics.requests_by_class() {
+        body.push_str(&format!(
+            "todo_api_http_requests_total{{class=\"{}\"}} {}\n",
+            class, count
+        ));
+    }
+    body.push_str("# HELP todo_api_http_requests_in_flight HTTP requests being processed.\n");
+    body.push_str("# TYPE todo_api_http_requests_in_flight gauge\n");
+    body.push_str(&format!(
+        "todo_api_http_requests_in_flight {}\n",
+        state.http_metrics.in_flight()
+    ));
+    if let Some(limits) = state.http_metrics.concurrency_limits() {
+        body.push_str(
+            "# HELP todo_api_concurrency_in_flight Requests being processed per concurrency-limited route group.\n",
+        );
+        body.push_str("# TYPE todo_api_concurrency_in_flight gauge\n");
+        for limit in limits.groups() {
+            body.push_str(&format!(
+                "todo_api_concurrency_in_flight{{group=\"{}\"}} {}\n",
+                limit.group(),
+                limit.in_flight()
+            ));
+        }
+        body.push_str(
+            "# HELP todo_api_concurrency_limit Maximum concurrent requests per route group.\n",
+        );
+        body.push_str("# TYPE todo_api_concurrency_limit gauge\n");
+        for limit in limits.groups() {
+            body.push_str(&format!(
+                "todo_api_concurrency_limit{{group=\"{}\"}} {}\n",
+                limit.group(),
+                limit.max_in_flight()
+            ));
+        }
+    }
+
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use infrastructure::ReadinessCheck;
+    use tower::ServiceExt;
+
+    use crate::middleware::ConcurrencyLimits;
+    use crate::{create_internal_router, create_router, RouterConfig};
+
+    /// 結果を固定した疎通確認
+    struct StaticCheck(&'static str, Result<(), String>);
+
+    #[async_trait]
+    impl ReadinessCheck for StaticCheck {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        async fn check(&self) -> Result<(), String> {
+            self.1.clone()
+        }
+    }
+
+    async fn get_json(state: AppState, path: &str) -> (StatusCode, serde_json::Value) {
+        let app = create_router(state, RouterConfig::minimal());
+        let response = app
+            .oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    async fn get_ready(state: AppState) -> (StatusCode, serde_json::Value) {
+        get_json(state, "/ready").await
+    }
+
+    /// 失敗するチェックを登録した状態
+    fn state_with_failing_check() -> AppState {
+        let mut state = AppState::for_tests();
+        state
+            .readiness_checks
+            .push(Arc::new(StaticCheck("postgres", Ok(()))));
+        state
+            .readiness_checks
+            .push(Arc::new(StaticCheck("redis", Err("refused".to_string()))));
+        state
+    }
+
+    /// 既定の /health は疎通確認を行わない（deep=true 以外の値も同じ）
+    #[tokio::test]
+    async fn test_health_is_shallow_by_default() {
+        for path in ["/health", "/health?deep=false", "/health?deep=1"] {
+            let (status, body) = get_json(state_with_failing_check(), path).await;
+
+            assert_eq!(status, StatusCode::OK, "{}", path);
+            assert_eq!(body, serde_json::json!({"status": "ok"}), "{}", path);
+        }
+    }
+
+    /// /health?deep=true は疎通確認の結果を含め、失敗があれば 503
+    #[tokio::test]
+    async fn test_health_deep_runs_checks() {
+        let (status, body) = get_json(state_with_failing_check(), "/health?deep=true").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "unavailable");
+        assert_eq!(body["checks"]["postgres"], "ok");
+        assert_eq!(body["checks"]["redis"], "error: refused");
+
+        let (status, body) = get_json(AppState::for_tests(), "/health?deep=true").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, serde_json::json!({"status": "ok", "checks": {}}));
+    }
+
+    /// チェックが登録されていなければ 200（例: キャッシュ無効 + インメモリ構成）
+    #[tokio::test]
+    async fn test_ready_without_checks() {
+        let (status, body) = get_ready(AppState::for_tests()).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ready");
+        assert_eq!(body["checks"], serde_json::json!({}));
+    }
+
+    /// 失敗したチェックがあれば 503 とチェックごとの結果
+    #[tokio::test]
+    async fn test_ready_reports_failed_check() {
+        let mut state = AppState::for_tests();
+        state
+            .readiness_checks
+            .push(Arc::new(StaticCheck("postgres", Ok(()))));
+        state
+            .readiness_checks
+            .push(Arc::new(StaticCheck("redis", Err("refused".to_string()))));
+
+        let (status, body) = get_ready(state).await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["checks"]["postgres"], "ok");
+        assert_eq!(body["checks"]["redis"], "error: refused");
+    }
+
+    /// 内部ルーターは監視エンドポイントのみを公開する
+    #[tokio::test]
+    async fn test_internal_router_serves_only_probes_and_metrics() {
+        let mut state = AppState::for_tests();
+        state
+            .readiness_checks
+            .push(Arc::new(StaticCheck("postgres", Ok(()))));
+        state
+            .readiness_checks
+            .push(Arc::new(StaticCheck("redis", Err("refused".to_string()))));
+        let app = create_internal_router(state);
+
+        let get = |path: &'static str| {
+            let app = app.clone();
+            async move {
+                let response = app
+                    .oneshot(Request::get(path).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                let status = response.status();
+                let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, String::from_utf8(body.to_vec()).unwrap())
+            }
+        };
+
+        assert_eq!(get("/livez").await.0, StatusCode::OK);
+        assert_eq!(get("/readyz").await.0, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(get("/api/todos").await.0, StatusCode::NOT_FOUND);
+
+        let (status, body) = get("/metrics").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("todo_api_up 1\n"), "{}", body);
+        assert!(body.contains("todo_api_dependency_ready{check=\"postgres\"} 1\n"));
+        assert!(body.contains("todo_api_dependency_ready{check=\"redis\"} 0\n"));
+        assert!(body.contains("todo_api_http_requests_total{class=\"2xx\"} 0\n"));
+        // 同時実行制限を適用したルーターがない場合はグループのゲージを出力しない
+        assert!(!body.contains("todo_api_concurrency_in_flight"));
+    }
+
+    /// 同時実行制限を適用したルーターを作成すると、グループごとのゲージと上限を出力する
+    #[tokio::test]
+    async fn test_metrics_reports_concurrency_groups() {
+        let state = AppState::for_tests();
+        let limits = ConcurrencyLimits::new(8, 16);
+        let _app = create_router(
+            state.clone(),
+            RouterConfig::minimal().with_concurrency_limits(limits),
+        );
+
+        let response = create_internal_router(state)
+            .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body.contains("# TYPE todo_api_concurrency_in_flight gauge\n"));
+        assert!(body.contains("todo_api_concurrency_in_flight{group=\"batch\"} 0\n"));
+        assert!(body.contains("todo_api_concurrency_in_flight{group=\"upload\"} 0\n"));
+        assert!(body.contains("todo_api_concurrency_limit{group=\"batch\"} 8\n"));
+        assert!(body.contains("todo_api_concurrency_limit{group=\"upload\"} 16\n"));
+    }
 }
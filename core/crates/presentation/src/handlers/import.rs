@@ -0,0 +1,391 @@
+// =============================================================================
+// presentation/src/handlers/import.rs: インポートハンドラ
+// =============================================================================
+// 大量の TODO のインポートと、非同期インポートジョブの参照・キャンセルを処理する。
+//
+// エンドポイント:
+// - POST   /api/todos/import             - 同期インポート（MAX_BATCH_SIZE 件まで）
+// - POST   /api/todos/import?async=true  - 非同期インポート（ジョブを登録して 202）
+// - GET    /api/jobs/{id}                - ジョブの状態・進捗・結果サマリー
+// - DELETE /api/jobs/{id}                - ジョブのキャンセル
+//
+// 非同期インポート:
+// - リクエストではエンベロープ（空でないこと、件数上限）のみ検証し、
+//   項目はジョブテーブルに保存する
+// - バックグラウンドの ImportWorker がチャンク単位で処理する
+// - 各項目のタイトル検証はワーカーが行い、失敗は failed_items として数える
+//
+// 重複検出（?dedupe=true / ?dedupe_existing=true）は同期モードのみ対応する。
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// 外部クレートのインポート
+// -----------------------------------------------------------------------------
+
+// axum: Web フレームワーク
+// extract::Path / Query / State: パス、クエリ、状態の抽出
+// http::header: Location ヘッダー
+// response::IntoResponse / Response: レスポンス変換
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+// serde: クエリパラメータのデシリアライズ
+use serde::Deserialize;
+
+// uuid: ジョブ ID
+use uuid::Uuid;
+
+// domain: ドメイン層の型
+use domain::{JobState, Todo};
+
+// infrastructure: 重複検出付きバッチ作成の結果
+use infrastructure::CreateOutcome;
+
+// application: Application 層の DTO
+use application::dto::{
+    BatchItemStatus,    // 重複した項目の処理結果
+    ImportDuplicate,    // 重複のため作成しなかった項目
+    ImportItemError,    // インポートに失敗した項目
+    ImportJobResponse,  // インポートジョブのレスポンス
+    ImportSummary,      // 同期インポートの結果サマリー
+    ImportTodosRequest, // インポートリクエスト
+};
+
+// crate: このクレート内のモジュール
+use crate::error::ApiError; // API エラー型
+use crate::handlers::batch::{
+    create_deduplicated, create_inputs, ensure_batch_size, partition_create_items,
+}; // バッチ作成と共通の処理
+use crate::middleware::UserContext; // 認証済みユーザー情報
+use crate::state::AppState; // アプリケーション状態
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// ジョブのレスポンスに含める失敗項目の最大件数
+const MAX_REPORTED_ERRORS: i64 = 100;
+
+// =============================================================================
+// import_todos ハンドラ
+// =============================================================================
+
+/// インポートのクエリパラメータ
+///
+/// POST /api/todos/import?async=true のようなクエリパラメータを受け取る。
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    /// 非同期モード（デフォルト: false）
+    ///
+    /// - false: リクエスト内で作成し、結果サマリーを返す
+    /// - true: ジョブを登録して 202 Accepted を返す
+    #[serde(default, rename = "async")]
+    pub run_async: bool,
+
+    /// リクエスト内の重複を検出する（デフォルト: false、同期モードのみ）
+    #[serde(default)]
+    pub dedupe: bool,
+
+    /// ユーザーの未完了 TODO との重複も検出する（デフォルト: false、`dedupe` を含む）
+    #[serde(default)]
+    pub dedupe_existing: bool,
+}
+
+/// TODO インポート
+///
+/// POST /api/todos/import
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///     "todos": [
+///         {"title": "タスク1", "description": "説明1"},
+///         {"title": "タスク2"}
+///     ]
+/// }
+/// ```
+///
+/// # Response: 同期モード (200 OK)
+///
+/// 有効な項目を1トランザクションで作成し、結果サマリーを返す。
+///
+/// ```json
+/// {"total": 2, "imported": 1, "failed": 1, "errors": [{"index": 1, "error": "..."}]}
+/// ```
+///
+/// `?dedupe=true`（または `?dedupe_existing=true`）を指定した場合、重複した項目は作成せず
+/// `duplicates`（`{"index", "status": "skipped_duplicate", "existing_id"}`）に含める。
+///
+/// # Response: 非同期モード (202 Accepted)
+///
+/// `Location: /api/jobs/{id}` ヘッダーと登録されたジョブ（state = pending）を返す。
+/// ジョブはテナントを保持しないため、TENANT_SCOPING=true でも作成される TODO に
+/// テナント ID は記録されない（同期モードは記録する）。
+///
+/// # Errors
+///
+/// - 400 Bad Request: 空配列、非同期モードでの重複検出の指定
+/// - 422 Unprocessable Entity: 件数が上限を超える（`code=batch_too_large`）
+///   - 同期モード: `MAX_BATCH_SIZE`（デフォルト 100）
+///   - 非同期モード: `IMPORT_MAX_ITEMS`（デフォルト 10000）
+pub async fn import_todos(
+    // UserContext エクストラクタ: 認証済みユーザー情報
+    user: UserContext,
+    // State エクストラクタ: AppState を取得（axum 推奨）
+    State(state): State<AppState>,
+    // Query エクストラクタ: ?async=true を ImportQuery にデシリアライズ
+    Query(query): Query<ImportQuery>,
+    // Json エクストラクタ: リクエストボディを ImportTodosRequest にデシリアライズ
+    Json(req): Json<ImportTodosRequest>,
+) -> Result<Response, ApiError> {
+    // バリデーション: 空配列チェック
+    if req.todos.is_empty() {
+        return Err(ApiError::BadRequest("todos cannot be empty".to_string()));
+    }
+
+    // -------------------------------------------------------------------------
+    // 非同期モード: ジョブを登録して 202 Accepted
+    // -------------------------------------------------------------------------
+    if query.run_async {
+        // 重複検出はリクエスト内で完結する同期モードのみ対応
+        if query.dedupe || query.dedupe_existing {
+            return Err(ApiError::BadRequest(
+                "dedupe is not supported for async import".to_string(),
+            ));
+        }
+
+        ensure_batch_size(req.todos.len(), state.max_import_items)?;
+
+        let items = req
+            .todos
+            .into_iter()
+            .map(|t| (t.title, t.description))
+            .collect();
+        let job = state.import_jobs.enqueue(user.user_id, items).await?;
+
+        let location = job_url(job.id);
+        let body = ImportJobResponse {
+            job,
+            errors: Vec::new(),
+        };
+        return Ok((
+            StatusCode::ACCEPTED,
+            [(header::LOCATION, location)],
+            Json(body),
+        )
+            .into_response());
+    }
+
+    // -------------------------------------------------------------------------
+    // 同期モード: 有効な項目を1トランザクションで作成
+    // -------------------------------------------------------------------------
+    ensure_batch_size(req.todos.len(), state.max_batch_size)?;
+
+    let total = req.todos.len();
+    let (valid, invalid) = partition_create_items(req.todos);
+
+    let mut duplicates: Vec<ImportDuplicate> = Vec::new();
+    let created: Vec<Todo> = if query.dedupe || query.dedupe_existing {
+        // 重複検出あり: 作成された TODO と重複した項目に分ける
+        let outcomes = create_deduplicated(
+            &state.batch_service,
+            user.user_id,
+            user.tenant_scope(state.tenant_scoping),
+            valid,
+            query.dedupe_existing,
+        )
+        .await?;
+        let mut created = Vec::with_capacity(outcomes.len());
+        for (index, outcome) in outcomes {
+            match outcome {
+                CreateOutcome::Created(todo) => created.push(todo),
+                CreateOutcome::Duplicate { existing_id } => duplicates.push(ImportDuplicate {
+                    index,
+                    status: BatchItemStatus::SkippedDuplicate,
+                    existing_id,
+                }),
+            }
+        }
+        created
+    } else {
+        let (_, inputs) = create_inputs(valid, user.tenant_scope(state.tenant_scoping));
+        if inputs.is_empty() {
+            Vec::new()
+        } else {
+            state
+                .batch_service
+                .batch_create_inputs(user.user_id, inputs)
+                .await?
+        }
+    };
+
+    // キャッシュ更新（コミット後、Write-Through）
+    for todo in &created {
+        // キャッシュエラーは警告のみ（DB はコミット済み）
+        if let Err(e) = state.cache.set(todo).await {
+            tracing::warn!(todo_id = %todo.id, error = %e, "Failed to cache imported todo");
+        }
+    }
+
+    let summary = ImportSummary {
+        total,
+        imported: created.len(),
+        failed: invalid.len(),
+        errors: invalid
+            .into_iter()
+            .map(|r| ImportItemError {
+                index: r.index,
+                error: r.error.unwrap_or_default(),
+            })
+            .collect(),
+        duplicates,
+    };
+
+    Ok((StatusCode::OK, Json(summary)).into_response())
+}
+
+// =============================================================================
+// get_import_job ハンドラ
+// =============================================================================
+
+/// インポートジョブ取得
+///
+/// GET /api/jobs/{id}
+///
+/// # Response (200 OK)
+///
+/// ジョブの状態、進捗（processed_items / failed_items）と、
+/// 失敗した項目（位置順、最大 100 件）を返す。
+///
+/// # Errors
+///
+/// - 404 Not Found: ジョブが存在しない、または他ユーザーのジョブ
+pub async fn get_import_job(
+    // UserContext エクストラクタ: 認証済みユーザー情報
+    user: UserContext,
+    // State エクストラクタ: AppState を取得
+    State(state): State<AppState>,
+    // Path エクストラクタ: /api/jobs/{id} の id
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let job = state
+        .import_jobs
+        .find(id, user.user_id)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    let errors = state
+        .import_jobs
+        .item_errors(job.id, MAX_REPORTED_ERRORS)
+        .await?
+        .into_iter()
+        .map(|(position, error)| ImportItemError {
+            index: position as usize,
+            error,
+        })
+        .collect();
+
+    Ok((StatusCode::OK, Json(ImportJobResponse { job, errors })))
+}
+
+// =============================================================================
+// cancel_import_job ハンドラ
+// =============================================================================
+
+/// インポートジョブのキャンセル
+///
+/// DELETE /api/jobs/{id}
+///
+/// pending / running のジョブを cancelled にする。
+/// running のジョブは処理中のチャンクをコミットした後に停止する
+/// （作成済みの TODO は残る）。
+///
+/// # Response (200 OK)
+///
+/// キャンセル後のジョブ（すでにキャンセル済みの場合もそのまま返す）
+///
+/// # Errors
+///
+/// - 404 Not Found: ジョブが存在しない、または他ユーザーのジョブ
+/// - 409 Conflict: ジョブがすでに completed / failed
+pub async fn cancel_import_job(
+    // UserContext エクストラクタ: 認証済みユーザー情報
+    user: UserContext,
+    // State エクストラクタ: AppState を取得
+    State(state): State<AppState>,
+    // Path エクストラクタ: /api/jobs/{id} の id
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let job = state
+        .import_jobs
+        .cancel(id, user.user_id)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    // 終了済みのジョブはキャンセルできない
+    if job.state != JobState::Cancelled {
+        return Err(ApiError::Conflict(format!(
+            "job is already {}",
+            job.state.as_str()
+        )));
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(ImportJobResponse {
+            job,
+            errors: Vec::new(),
+        }),
+    ))
+}
+
+// -----------------------------------------------------------------------------
+// ヘルパー関数
+// -----------------------------------------------------------------------------
+
+/// ジョブの URL を返す（Location ヘッダー用）
+fn job_url(id: Uuid) -> String {
+    format!("/api/jobs/{}", id)
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_query(uri: &str) -> ImportQuery {
+        Query::<ImportQuery>::try_from_uri(&uri.parse().unwrap())
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn test_import_query_defaults_to_sync() {
+        assert!(!parse_query("/api/todos/import").run_async);
+        assert!(parse_query("/api/todos/import?async=true").run_async);
+        assert!(!parse_query("/api/todos/import?async=false").run_async);
+    }
+
+    #[test]
+    fn test_import_query_dedupe_flags_default_to_false() {
+        let query = parse_query("/api/todos/import");
+        assert!(!query.dedupe && !query.dedupe_existing);
+
+        let query = parse_query("/api/todos/import?dedupe=true&dedupe_existing=true");
+        assert!(query.dedupe && query.dedupe_existing);
+    }
+
+    #[test]
+    fn test_job_url() {
+        let id = Uuid::new_v4();
+        assert_eq!(job_url(id), format!("/api/jobs/{}", id));
+    }
+}
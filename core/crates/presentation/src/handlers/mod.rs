@@ -5,8 +5,10 @@
 // 各ハンドラは対応する HTTP エンドポイントにマッピングされる。
 //
 // モジュール構成:
+// - admin: 管理者専用（JWT 鍵セットのリロード）
 // - auth: 認証関連（登録、ログイン）
 // - batch: バッチ操作（一括作成、TODO + ファイル同時作成）
+// - csv_import: CSV インポート
 // - file: ファイル操作（アップロード、ダウンロード、削除）
 // - healthz: ヘルスチェック
 // - todo: TODO CRUD 操作
@@ -17,26 +19,31 @@
 // - エラーは ApiError に変換して統一的に処理
 // =============================================================================
 
-// AppState のジェネリックパラメータが多いため、型の複雑性警告を抑制
-// これは Clean Architecture のトレイト境界による設計上の制約
-#![allow(clippy::type_complexity)]
-
 // -----------------------------------------------------------------------------
 // サブモジュール宣言
 // -----------------------------------------------------------------------------
 
+// admin: 管理者専用ハンドラ（reload_secrets）
+pub mod admin;
+
 // auth: 認証ハンドラ（register, login）
 pub mod auth;
 
-// batch: バッチ操作ハンドラ（batch_create_todos, create_todo_with_files）
+// batch: バッチ操作ハンドラ（batch_create_todos, batch_update_todos, create_todo_with_files）
 pub mod batch;
 
+// csv_import: CSV インポートハンドラ（import_todos_csv）
+pub mod csv_import;
+
 // file: ファイル操作ハンドラ（upload_file, download_file, delete_file）
 pub mod file;
 
 // healthz: ヘルスチェックハンドラ
 pub mod healthz;
 
+// import: インポートハンドラ（import_todos, get_import_job, cancel_import_job）
+pub mod import;
+
 // todo: TODO CRUD ハンドラ（list, create, get, update, delete）
 pub mod todo;
 
@@ -45,6 +52,10 @@ pub mod todo;
 // -----------------------------------------------------------------------------
 // pub use xxx::* で、handlers::register のように直接アクセス可能にする
 
+// admin モジュールの全公開アイテムを再エクスポート
+// これにより handlers::reload_secrets でアクセス可能
+pub use admin::*;
+
 // auth モジュールの全公開アイテムを再エクスポート
 // これにより handlers::register, handlers::login でアクセス可能
 pub use auth::*;
@@ -53,13 +64,21 @@ pub use auth::*;
 // これにより handlers::batch_create_todos, handlers::create_todo_with_files でアクセス可能
 pub use batch::*;
 
+// csv_import モジュールの全公開アイテムを再エクスポート
+// これにより handlers::import_todos_csv でアクセス可能
+pub use csv_import::*;
+
 // file モジュールの全公開アイテムを再エクスポート
 // これにより handlers::upload_file, handlers::download_file, handlers::delete_file でアクセス可能
 pub use file::*;
 
 // healthz 関数のみを再エクスポート
 // 単一関数なので明示的に指定
-pub use healthz::healthz;
+pub use healthz::{healthz, metrics, readyz};
+
+// import モジュールの全公開アイテムを再エクスポート
+// これにより handlers::import_todos, handlers::get_import_job でアクセス可能
+pub use import::*;
 
 // todo モジュールの全公開アイテムを再エクスポート
 // これにより handlers::list_todos, handlers::create_todo などでアクセス可能
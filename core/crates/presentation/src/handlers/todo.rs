@@ -38,14 +38,9 @@ use axum::{
     Json,
 };
 
-// domain: ドメイン層の型とトレイト
-// TodoCacheOps: キャッシュ操作トレイト
+// domain: ドメイン層の型
 // TodoFilter: TODO 検索フィルタ（ビルダーパターン）
-// TodoReader/Writer: TODO 読み書きトレイト
-// UserReader/Writer: ユーザー読み書きトレイト
-use domain::{
-    StorageOps, TodoCacheOps, TodoFilter, TodoReader, TodoWriter, UserReader, UserWriter,
-};
+use domain::TodoFilter;
 
 // serde: シリアライズ/デシリアライズ
 // Deserialize: JSON → 構造体 変換
@@ -129,6 +124,8 @@ pub struct UpdateTodoRequest {
 /// GET /api/todos?completed=true
 /// GET /api/todos?completed=false
 ///
+/// TENANT_SCOPING=true の場合は、X-Claim-Tenant-Id のテナントで作成した TODO のみ返す。
+///
 /// # Response (200 OK)
 ///
 /// ```json
@@ -148,26 +145,21 @@ pub struct UpdateTodoRequest {
 /// # Note
 ///
 /// 一覧取得はキャッシュしない（フィルタ条件が多様なため）。
-pub async fn list_todos<
-    TW: TodoWriter,  // TODO 書き込み（このハンドラでは未使用）
-    TR: TodoReader,  // TODO 読み取り（一覧取得）
-    C: TodoCacheOps, // キャッシュ操作（このハンドラでは未使用）
-    UR: UserReader,  // ユーザー読み取り（未使用）
-    UW: UserWriter,  // ユーザー書き込み（未使用）
-    S: StorageOps,   // ストレージ操作（未使用）
->(
+pub async fn list_todos(
     // UserContext エクストラクタ: X-User-Id ヘッダーから抽出
     user: UserContext,
     // State エクストラクタ: AppState を取得
-    State(state): State<AppState<TW, TR, C, UR, UW, S>>,
+    State(state): State<AppState>,
     // Query エクストラクタ: クエリパラメータを ListQuery にデシリアライズ
     Query(query): Query<ListQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
     // TodoFilter を構築
     // - user_id: 自分の TODO のみを取得（マルチテナント対応）
     // - with_completed: 完了状態フィルタ（Option<bool>）
+    // - with_tenant: テナントフィルタ（TENANT_SCOPING=true かつ X-Claim-Tenant-Id がある場合のみ）
     let filter = TodoFilter::new(user.user_id) // user_id でフィルタ
-        .with_completed(query.completed); // 完了状態フィルタを追加
+        .with_completed(query.completed) // 完了状態フィルタを追加
+        .with_tenant(user.tenant_scope(state.tenant_scoping)); // テナントフィルタを追加
 
     // ListTodosQuery を実行
     // エラー時は `?` で早期リターン（DomainError → ApiError に自動変換）
@@ -211,18 +203,11 @@ pub async fn list_todos<
 /// # キャッシュ
 ///
 /// Write-Through: 作成後にキャッシュにも保存される。
-pub async fn create_todo<
-    TW: TodoWriter,  // TODO 書き込み（作成）
-    TR: TodoReader,  // TODO 読み取り（未使用）
-    C: TodoCacheOps, // キャッシュ操作（Write-Through）
-    UR: UserReader,  // ユーザー読み取り（未使用）
-    UW: UserWriter,  // ユーザー書き込み（未使用）
-    S: StorageOps,   // ストレージ操作（未使用）
->(
+pub async fn create_todo(
     // UserContext エクストラクタ: 認証済みユーザー情報
     user: UserContext,
     // State エクストラクタ: AppState を取得
-    State(state): State<AppState<TW, TR, C, UR, UW, S>>,
+    State(state): State<AppState>,
     // Json エクストラクタ: リクエストボディを CreateTodoRequest にデシリアライズ
     Json(req): Json<CreateTodoRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
@@ -235,9 +220,12 @@ pub async fn create_todo<
 
     // CreateTodoCommand を実行
     // - バリデーション
-    // - DB 保存
+    // - DB 保存（TENANT_SCOPING=true の場合はテナント ID も記録）
     // - キャッシュ保存（Write-Through）
-    let todo = state.create_todo.execute(user.user_id, dto).await?;
+    let todo = state
+        .create_todo
+        .execute_with_tenant(user.user_id, user.tenant_scope(state.tenant_scoping), dto)
+        .await?;
 
     // 成功時: 201 Created + 作成された TODO
     Ok((StatusCode::CREATED, Json(todo)))
@@ -272,18 +260,11 @@ pub async fn create_todo<
 /// # キャッシュ
 ///
 /// CachedTodoReader を使用している場合、キャッシュから取得される。
-pub async fn get_todo<
-    TW: TodoWriter,  // TODO 書き込み（未使用）
-    TR: TodoReader,  // TODO 読み取り（詳細取得）
-    C: TodoCacheOps, // キャッシュ操作（Reader 側で処理）
-    UR: UserReader,  // ユーザー読み取り（未使用）
-    UW: UserWriter,  // ユーザー書き込み（未使用）
-    S: StorageOps,   // ストレージ操作（未使用）
->(
+pub async fn get_todo(
     // UserContext エクストラクタ: 認証済みユーザー情報
     user: UserContext,
     // State エクストラクタ: AppState を取得
-    State(state): State<AppState<TW, TR, C, UR, UW, S>>,
+    State(state): State<AppState>,
     // Path エクストラクタ: URL パスから id を抽出
     // /api/todos/{id} の {id} 部分が Uuid としてパースされる
     Path(id): Path<Uuid>,
@@ -327,18 +308,11 @@ pub async fn get_todo<
 /// # キャッシュ
 ///
 /// Cache Invalidation: 更新時にキャッシュが無効化される。
-pub async fn update_todo<
-    TW: TodoWriter,  // TODO 書き込み（更新）
-    TR: TodoReader,  // TODO 読み取り（未使用）
-    C: TodoCacheOps, // キャッシュ操作（Cache Invalidation）
-    UR: UserReader,  // ユーザー読み取り（未使用）
-    UW: UserWriter,  // ユーザー書き込み（未使用）
-    S: StorageOps,   // ストレージ操作（未使用）
->(
+pub async fn update_todo(
     // UserContext エクストラクタ: 認証済みユーザー情報
     user: UserContext,
     // State エクストラクタ: AppState を取得
-    State(state): State<AppState<TW, TR, C, UR, UW, S>>,
+    State(state): State<AppState>,
     // Path エクストラクタ: URL パスから id を抽出
     Path(id): Path<Uuid>,
     // Json エクストラクタ: リクエストボディを UpdateTodoRequest にデシリアライズ
@@ -386,18 +360,11 @@ pub async fn update_todo<
 /// # Note
 ///
 /// この操作は取り消せない。関連するファイルも削除される（CASCADE）。
-pub async fn delete_todo<
-    TW: TodoWriter,  // TODO 書き込み（削除）
-    TR: TodoReader,  // TODO 読み取り（未使用）
-    C: TodoCacheOps, // キャッシュ操作（Cache Invalidation）
-    UR: UserReader,  // ユーザー読み取り（未使用）
-    UW: UserWriter,  // ユーザー書き込み（未使用）
-    S: StorageOps,   // ストレージ操作（未使用）
->(
+pub async fn delete_todo(
     // UserContext エクストラクタ: 認証済みユーザー情報
     user: UserContext,
     // State エクストラクタ: AppState を取得
-    State(state): State<AppState<TW, TR, C, UR, UW, S>>,
+    State(state): State<AppState>,
     // Path エクストラクタ: URL パスから id を抽出
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse, ApiError> {
@@ -410,3 +377,124 @@ pub async fn delete_todo<
     // 成功時: 204 No Content（ボディなし）
     Ok(StatusCode::NO_CONTENT)
 }
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    use crate::{create_router, RouterConfig};
+
+    /// テナントの有効・無効を指定したルーター
+    fn app(tenant_scoping: bool) -> Router {
+        let mut state = AppState::for_tests();
+        state.tenant_scoping = tenant_scoping;
+        create_router(state, RouterConfig::minimal())
+    }
+
+    /// 識別ヘッダー付きのリクエストを送信し、ステータスと JSON を返す
+    async fn send(
+        app: &Router,
+        request: axum::http::request::Builder,
+        user_id: Uuid,
+        tenant: Option<&str>,
+        body: Body,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = request
+            .header("content-type", "application/json")
+            .header("x-user-id", user_id.to_string());
+        if let Some(tenant) = tenant {
+            request = request.header("x-claim-tenant-id", tenant);
+        }
+        let response = app
+            .clone()
+            .oneshot(request.body(body).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    /// TODO を作成する
+    async fn create(app: &Router, user_id: Uuid, tenant: Option<&str>, title: &str) {
+        let body = Body::from(serde_json::json!({ "title": title }).to_string());
+        let (status, _) = send(app, Request::post("/api/todos"), user_id, tenant, body).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    /// 一覧のタイトル（新しい順）
+    async fn titles(app: &Router, user_id: Uuid, tenant: Option<&str>) -> Vec<String> {
+        let (status, body) = send(
+            app,
+            Request::get("/api/todos"),
+            user_id,
+            tenant,
+            Body::empty(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        body.as_array()
+            .unwrap()
+            .iter()
+            .map(|todo| todo["title"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_tenant_scoping_filters_list_by_tenant_header() {
+        let app = app(true);
+        let user_id = Uuid::new_v4();
+        let tenant_a = Uuid::new_v4().to_string();
+        let tenant_b = Uuid::new_v4().to_string();
+
+        create(&app, user_id, Some(&tenant_a), "a").await;
+        create(&app, user_id, Some(&tenant_b), "b").await;
+        create(&app, user_id, None, "none").await;
+
+        assert_eq!(titles(&app, user_id, Some(&tenant_a)).await, vec!["a"]);
+        assert_eq!(titles(&app, user_id, Some(&tenant_b)).await, vec!["b"]);
+        // テナントのないリクエストはユーザーの全 TODO
+        assert_eq!(titles(&app, user_id, None).await.len(), 3);
+        // 他のユーザーは同じテナントでも見えない
+        assert!(titles(&app, Uuid::new_v4(), Some(&tenant_a))
+            .await
+            .is_empty());
+        // UUID でないテナントは無いものとして扱う
+        assert_eq!(titles(&app, user_id, Some("tenant-a")).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_tenant_header_is_ignored_when_scoping_is_disabled() {
+        let app = app(false);
+        let user_id = Uuid::new_v4();
+        let tenant = Uuid::new_v4().to_string();
+
+        create(&app, user_id, Some(&tenant), "a").await;
+        create(&app, user_id, None, "b").await;
+
+        // 絞り込まず、作成時のテナントも記録しない
+        let (_, todos) = send(
+            &app,
+            Request::get("/api/todos"),
+            user_id,
+            Some(&tenant),
+            Body::empty(),
+        )
+        .await;
+        assert_eq!(todos.as_array().unwrap().len(), 2);
+        assert!(todos
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|todo| todo.get("tenant_id").is_none()));
+    }
+}
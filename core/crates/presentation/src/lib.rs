@@ -13,6 +13,7 @@
 // 公開する主な要素:
 // - AppState: アプリケーション状態（ユースケースを保持）
 // - create_router: ルーター構築関数
+// - RouterConfig: ルーター構築オプション
 // - ApiError: HTTP エラーレスポンス
 // - UserContext: 認証済みユーザー情報
 //
@@ -34,12 +35,20 @@ pub mod handlers;
 // middleware: カスタムミドルウェア（Edge 検証、ユーザーコンテキスト）
 pub mod middleware;
 
+// router_config: ルーター構築オプション（適用するミドルウェアの切り替え）
+pub mod router_config;
+
 // routes: ルーティング設定
 pub mod routes;
 
 // state: アプリケーション状態（DI コンテナ）
 pub mod state;
 
+// testing: テスト用のインメモリ実装と AppState::for_tests()
+// 他のクレートからは features = ["testing"] で有効化する
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
 // -----------------------------------------------------------------------------
 // 主要な型の再エクスポート
 // -----------------------------------------------------------------------------
@@ -47,11 +56,17 @@ pub mod state;
 // ApiError: API エラー型（IntoResponse を実装）
 pub use error::ApiError;
 
+// ConcurrencyLimits: ルートグループごとの同時実行制限（RouterConfig に設定する）
 // UserContext: 認証済みユーザー情報（ミドルウェアで設定）
-pub use middleware::UserContext;
+// CorsConfig / EdgeVerifyConfig / RateLimit: RouterConfig で有効にするミドルウェアの設定
+pub use middleware::{ConcurrencyLimits, CorsConfig, EdgeVerifyConfig, RateLimit, UserContext};
 
 // create_router: ルーター構築関数
-pub use routes::create_router;
+pub use routes::{create_internal_router, create_router};
+
+// RouterConfig: create_router に渡すミドルウェアの切り替え
+// BodyLimits: リクエストボディの上限（通常・アップロード）
+pub use router_config::{BodyLimits, RouterConfig};
 
 // AppState: アプリケーション状態（ユースケースを保持）
-pub use state::AppState;
+pub use state::{AppState, AppStateBuilder, MissingComponent};
@@ -0,0 +1,412 @@
+// =============================================================================
+// presentation/src/middleware/concurrency_limit.rs: ルートグループ単位の同時実行制限
+// =============================================================================
+// 重いエンドポイント（バッチ作成、ファイルアップロード）の同時実行数を制限し、
+// 上限に達した場合はキューイングせず即座に 503 を返す（ロードシェディング）。
+//
+// 目的:
+// - バッチ/アップロードのバーストが DB コネクションを使い切り、
+//   軽量な CRUD エンドポイントが巻き添えで遅延・失敗するのを防ぐ
+// - 無制限に待たせるよりも、早期に 503 + Retry-After を返して
+//   クライアントにリトライを促す方が全体のスループットが安定する
+//
+// 仕組み:
+// - グループごとに tokio::sync::Semaphore を 1 つ保持
+// - try_acquire_owned() で許可を取得（待機しない）
+// - 取得できなければ 503 Service Unavailable（code=overloaded）
+// - 処理中リクエスト数をゲージ（AtomicUsize）として公開
+//   （/metrics の todo_api_concurrency_in_flight{group="..."}）
+//
+// レイヤー順序:
+// - route_layer で適用するため、Edge 検証より内側（後）で実行される
+//   → Edge 検証に失敗したリクエストは許可を消費しない
+// - 許可はハンドラ完了（レスポンス生成）まで保持される
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// 外部クレートのインポート
+// -----------------------------------------------------------------------------
+
+// std: 標準ライブラリ
+// Arc: スレッド安全な参照カウント（グループ状態をルーターと呼び出し元で共有）
+// AtomicUsize/Ordering: 処理中リクエスト数のゲージ
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+// axum: Web フレームワーク
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, Request, StatusCode},
+    middleware::{from_fn_with_state, Next},
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+
+// tokio: 非同期セマフォ（同時実行数の上限管理）
+use tokio::sync::Semaphore;
+
+// =============================================================================
+// 定数
+// =============================================================================
+
+/// 503 レスポンスに付与する Retry-After ヘッダーの値（秒）
+///
+/// 重い処理は数秒で完了する想定のため、短めの値を返す。
+const RETRY_AFTER_SECONDS: &str = "1";
+
+// =============================================================================
+// ConcurrencyLimit 構造体
+// =============================================================================
+
+/// ルートグループ単位の同時実行制限
+///
+/// セマフォと処理中リクエスト数のゲージを保持する。
+/// 内部は Arc で共有されるため、clone() したインスタンスは同じ状態を参照する。
+/// ルーターに渡した後も、呼び出し元で `in_flight()` を参照できる。
+///
+/// # derive マクロ
+///
+/// - `Clone`: 複製可能（ミドルウェアの状態として必要）
+///
+/// # 使用例
+///
+/// ```rust,ignore
+/// let batch_limit = ConcurrencyLimit::new("batch", 8);
+/// let routes = with_concurrency_limit(batch_routes, batch_limit.clone());
+///
+/// // 処理中リクエスト数を参照
+/// tracing::info!(in_flight = batch_limit.in_flight(), "batch group");
+/// ```
+#[derive(Clone)]
+pub struct ConcurrencyLimit {
+    /// グループ名（ログ出力用、例: "batch", "upload"）
+    group: &'static str,
+
+    /// 同時実行数の上限
+    max_in_flight: usize,
+
+    /// 同時実行数を管理するセマフォ
+    semaphore: Arc<Semaphore>,
+
+    /// 処理中リクエスト数（ゲージ）
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl ConcurrencyLimit {
+    /// 新しい同時実行制限を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `group` - グループ名（ログ出力用）
+    /// * `max_in_flight` - 同時実行数の上限（0 の場合は 1 に補正）
+    pub fn new(group: &'static str, max_in_flight: usize) -> Self {
+        // 上限 0 だと全リクエストが拒否されるため、最低 1 を保証
+        let max_in_flight = max_in_flight.max(1);
+
+        Self {
+            group,
+            max_in_flight,
+            semaphore: Arc::new(Semaphore::new(max_in_flight)),
+            in_flight: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// グループ名を取得する
+    pub fn group(&self) -> &'static str {
+        self.group
+    }
+
+    /// 同時実行数の上限を取得する
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight
+    }
+
+    /// 処理中リクエスト数（ゲージ）を取得する
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    /// 全ての許可を取得して上限到達の状態にする（テスト用、許可は Drop で返却）
+    #[cfg(test)]
+    pub(crate) fn saturate(&self) -> tokio::sync::OwnedSemaphorePermit {
+        self.semaphore
+            .clone()
+            .try_acquire_many_owned(self.max_in_flight as u32)
+            .expect("no permits are in use")
+    }
+}
+
+// =============================================================================
+// ConcurrencyLimits 構造体
+// =============================================================================
+
+/// ルートグループごとの同時実行制限の組
+///
+/// create_router に渡し、各ルートグループに適用する。
+/// 呼び出し元でも clone() を保持しておけば、ゲージを参照できる。
+///
+/// # グループ
+///
+/// - `batch`: POST /api/todos/batch, POST /api/todos/batch-update, POST /api/todos/with-files
+/// - `upload`: POST /api/files/upload
+#[derive(Clone)]
+pub struct ConcurrencyLimits {
+    /// バッチ系ルートの同時実行制限
+    pub batch: ConcurrencyLimit,
+
+    /// ファイルアップロードの同時実行制限
+    pub upload: ConcurrencyLimit,
+}
+
+impl ConcurrencyLimits {
+    /// 各グループの上限から同時実行制限の組を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `batch_max_in_flight` - バッチ系ルートの同時実行数上限
+    /// * `upload_max_in_flight` - ファイルアップロードの同時実行数上限
+    pub fn new(batch_max_in_flight: usize, upload_max_in_flight: usize) -> Self {
+        Self {
+            batch: ConcurrencyLimit::new("batch", batch_max_in_flight),
+            upload: ConcurrencyLimit::new("upload", upload_max_in_flight),
+        }
+    }
+
+    /// 全てのグループ（/metrics の出力順）
+    pub fn groups(&self) -> [&ConcurrencyLimit; 2] {
+        [&self.batch, &self.upload]
+    }
+}
+
+// =============================================================================
+// InFlightGuard 構造体
+// =============================================================================
+
+/// 処理中リクエスト数のゲージを管理するガード
+///
+/// 生成時にゲージを加算し、Drop 時に減算する。
+/// ハンドラがパニックした場合やリクエストがキャンセルされた場合でも
+/// ゲージが正しく戻ることを保証する。
+struct InFlightGuard {
+    /// 共有ゲージ
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl InFlightGuard {
+    /// ゲージを加算してガードを作成する
+    fn new(in_flight: Arc<AtomicUsize>) -> Self {
+        in_flight.fetch_add(1, Ordering::Relaxed);
+        Self { in_flight }
+    }
+}
+
+impl Drop for InFlightGuard {
+    /// ガード破棄時にゲージを減算する
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+// =============================================================================
+// concurrency_limit ミドルウェア関数
+// =============================================================================
+
+/// 同時実行制限ミドルウェア
+///
+/// セマフォの許可を待機せずに取得し、取得できた場合のみ次に進む。
+///
+/// # Arguments
+///
+/// * `limit` - ConcurrencyLimit（セマフォとゲージを保持）
+/// * `request` - HTTP リクエスト
+/// * `next` - 次のミドルウェア/ハンドラ
+///
+/// # Returns
+///
+/// * 許可取得成功: 次のミドルウェア/ハンドラの結果
+/// * 上限到達: 503 Service Unavailable（Retry-After 付き）
+async fn concurrency_limit(
+    State(limit): State<ConcurrencyLimit>, // ミドルウェア用状態を抽出
+    request: Request<Body>,                // HTTP リクエスト
+    next: Next,                            // 次のミドルウェア/ハンドラ
+) -> Response {
+    // -------------------------------------------------------------------------
+    // 許可の取得（待機しない）
+    // -------------------------------------------------------------------------
+    // try_acquire_owned: 許可がなければ即座に Err を返す
+    // OwnedSemaphorePermit は Drop 時に許可を返却する
+    let Ok(_permit) = limit.semaphore.clone().try_acquire_owned() else {
+        // 警告ログ: ロードシェディングを記録
+        tracing::warn!(
+            group = limit.group,
+            max_in_flight = limit.max_in_flight,
+            in_flight = limit.in_flight(),
+            "Request shed: concurrency limit reached"
+        );
+
+        // 503 Service Unavailable を返す
+        // {"error": "...", "code": "overloaded"}
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(header::RETRY_AFTER, RETRY_AFTER_SECONDS)],
+            Json(serde_json::json!({
+                "error": format!("Too many concurrent {} requests", limit.group),
+                "code": "overloaded",
+            })),
+        )
+            .into_response();
+    };
+
+    // -------------------------------------------------------------------------
+    // ハンドラの実行
+    // -------------------------------------------------------------------------
+    // ガードと許可はレスポンス生成までスコープ内に保持する
+    let _guard = InFlightGuard::new(limit.in_flight.clone());
+    next.run(request).await
+}
+
+// =============================================================================
+// with_concurrency_limit 関数
+// =============================================================================
+
+/// 同時実行制限ミドルウェアを Router に適用する
+///
+/// Router::route_layer を使用して、指定された Router の全ルートで
+/// 1 つのセマフォを共有する（ルートグループ単位の制限）。
+///
+/// # Arguments
+///
+/// * `router` - ミドルウェアを適用する Router
+/// * `limit` - 同時実行制限
+///
+/// # Returns
+///
+/// ミドルウェアが適用された Router
+///
+/// # 使用例
+///
+/// ```rust,ignore
+/// let batch_routes = Router::new()
+///     .route("/batch", post(batch_create_todos));
+///
+/// let batch_routes = with_concurrency_limit(batch_routes, ConcurrencyLimit::new("batch", 8));
+/// ```
+pub fn with_concurrency_limit<S: Clone + Send + Sync + 'static>(
+    router: Router<S>,       // 適用対象の Router
+    limit: ConcurrencyLimit, // 同時実行制限
+) -> Router<S> {
+    router.route_layer(from_fn_with_state(limit, concurrency_limit))
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    /// セマフォで待機するハンドラを持つテスト用ルーター
+    fn blocking_router(limit: ConcurrencyLimit, gate: Arc<Semaphore>) -> Router {
+        let router = Router::new().route(
+            "/slow",
+            get(move || {
+                let gate = gate.clone();
+                async move {
+                    // テスト側が許可を追加するまでブロック
+                    let _ = gate.acquire().await;
+                    "done"
+                }
+            }),
+        );
+        with_concurrency_limit(router, limit)
+    }
+
+    fn request() -> Request<Body> {
+        Request::builder().uri("/slow").body(Body::empty()).unwrap()
+    }
+
+    /// ゲージが期待値になるまで待機する
+    async fn wait_for_in_flight(limit: &ConcurrencyLimit, expected: usize) {
+        for _ in 0..100 {
+            if limit.in_flight() == expected {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!(
+            "in_flight did not reach {} (actual: {})",
+            expected,
+            limit.in_flight()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sheds_requests_over_limit() {
+        let limit = ConcurrencyLimit::new("batch", 1);
+        let gate = Arc::new(Semaphore::new(0));
+        let app = blocking_router(limit.clone(), gate.clone());
+
+        // 1 件目: ハンドラ内でブロックさせる
+        let first = tokio::spawn(app.clone().oneshot(request()));
+        wait_for_in_flight(&limit, 1).await;
+
+        // 2 件目: 上限到達のため即座に 503
+        let response = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            response.headers().get(header::RETRY_AFTER).unwrap(),
+            RETRY_AFTER_SECONDS
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], "overloaded");
+
+        // 1 件目を解放すると 200 で完了し、ゲージが 0 に戻る
+        gate.add_permits(1);
+        let response = first.await.unwrap().unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(limit.in_flight(), 0);
+
+        // 許可が返却されたため、次のリクエストは受け付けられる
+        gate.add_permits(1);
+        let response = app.oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_groups_are_independent() {
+        let batch = ConcurrencyLimit::new("batch", 1);
+        let upload = ConcurrencyLimit::new("upload", 1);
+        let batch_gate = Arc::new(Semaphore::new(0));
+        let batch_app = blocking_router(batch.clone(), batch_gate.clone());
+        let upload_app = blocking_router(upload.clone(), Arc::new(Semaphore::new(1)));
+
+        // batch グループを飽和させる
+        let blocked = tokio::spawn(batch_app.oneshot(request()));
+        wait_for_in_flight(&batch, 1).await;
+
+        // upload グループは影響を受けない
+        let response = upload_app.oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(upload.in_flight(), 0);
+
+        batch_gate.add_permits(1);
+        blocked.await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_zero_limit_is_clamped_to_one() {
+        let limit = ConcurrencyLimit::new("upload", 0);
+        assert_eq!(limit.max_in_flight(), 1);
+        assert_eq!(limit.group(), "upload");
+    }
+}
@@ -0,0 +1,248 @@
+// =============================================================================
+// presentation/src/middleware/cors.rs: CORS ミドルウェア
+// =============================================================================
+// 許可したオリジンからのブラウザのリクエストに CORS ヘッダーを付与する。
+//
+// 用途:
+// - 通常はブラウザからのリクエストを Edge 層が受けるため、Core 層では不要
+// - Edge 層を経由せずにフロントエンドから Core API を呼ぶ開発構成で有効化する
+//
+// 動作:
+// - プリフライト（OPTIONS + Access-Control-Request-Method）:
+//   許可オリジンなら 204 + Access-Control-Allow-* ヘッダー、それ以外は素通し（405）
+// - 通常のリクエスト: 許可オリジンなら Access-Control-Allow-Origin を付与
+// - オリジンに "*" を指定すると全てのオリジンを許可する
+// - レスポンスは Origin によって変わるため、常に Vary: Origin を付与する
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// 外部クレートのインポート
+// -----------------------------------------------------------------------------
+
+// std: 標準ライブラリ
+// Arc: 許可オリジンの一覧をミドルウェアのクローン間で共有
+use std::sync::Arc;
+
+// axum: Web フレームワーク
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderValue, Method, Request, StatusCode},
+    middleware::{from_fn_with_state, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+
+// =============================================================================
+// 定数
+// =============================================================================
+
+/// プリフライトで許可するメソッド（API が使用するもの）
+const ALLOWED_METHODS: &str = "GET, POST, PATCH, DELETE";
+
+/// プリフライトで許可するリクエストヘッダー
+const ALLOWED_HEADERS: &str = "authorization, content-type";
+
+/// プリフライト結果のキャッシュ期間のデフォルト（秒）
+pub const DEFAULT_CORS_MAX_AGE_SECS: u64 = 600;
+
+// =============================================================================
+// CorsConfig 構造体
+// =============================================================================
+
+/// CORS 設定
+///
+/// # 使用例
+///
+/// ```rust,ignore
+/// let cors = CorsConfig::new(vec!["http://localhost:5173".to_string()]);
+/// let config = RouterConfig::default().with_cors(cors);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorsConfig {
+    /// 許可するオリジン（`scheme://host[:port]`、"*" で全て許可）
+    pub allowed_origins: Vec<String>,
+
+    /// プリフライト結果のキャッシュ期間（秒、Access-Control-Max-Age）
+    pub max_age_secs: u64,
+}
+
+impl CorsConfig {
+    /// 許可オリジンから CORS 設定を作成する（末尾の `/` は取り除く）
+    ///
+    /// # Arguments
+    ///
+    /// * `allowed_origins` - 許可するオリジン
+    pub fn new(allowed_origins: Vec<String>) -> Self {
+        Self {
+            allowed_origins: allowed_origins
+                .into_iter()
+                .map(|origin| origin.trim_end_matches('/').to_string())
+                .collect(),
+            max_age_secs: DEFAULT_CORS_MAX_AGE_SECS,
+        }
+    }
+
+    /// オリジンが許可されているか
+    fn allows(&self, origin: &str) -> bool {
+        self.allowed_origins
+            .iter()
+            .any(|allowed| allowed == "*" || allowed == origin)
+    }
+}
+
+// =============================================================================
+// cors ミドルウェア関数
+// =============================================================================
+
+/// CORS ミドルウェア
+///
+/// # Returns
+///
+/// * 許可オリジンのプリフライト: 204 No Content（Access-Control-Allow-* 付き）
+/// * それ以外: 次のミドルウェア/ハンドラの結果（許可オリジンなら Allow-Origin 付き）
+async fn cors(
+    State(config): State<Arc<CorsConfig>>, // 許可オリジン
+    request: Request<Body>,                // HTTP リクエスト
+    next: Next,                            // 次のミドルウェア/ハンドラ
+) -> Response {
+    // 許可オリジンの Origin ヘッダー（不正な値・未許可は None）
+    let origin = request
+        .headers()
+        .get(header::ORIGIN)
+        .filter(|origin| origin.to_str().is_ok_and(|origin| config.allows(origin)))
+        .cloned();
+
+    let preflight = request.method() == Method::OPTIONS
+        && request
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD);
+
+    let mut response = match (&origin, preflight) {
+        // プリフライト: ハンドラを呼ばずに応答する
+        (Some(_), true) => {
+            let mut response = StatusCode::NO_CONTENT.into_response();
+            let headers = response.headers_mut();
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_METHODS,
+                HeaderValue::from_static(ALLOWED_METHODS),
+            );
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_HEADERS,
+                HeaderValue::from_static(ALLOWED_HEADERS),
+            );
+            headers.insert(
+                header::ACCESS_CONTROL_MAX_AGE,
+                HeaderValue::from(config.max_age_secs),
+            );
+            response
+        }
+        _ => next.run(request).await,
+    };
+
+    let headers = response.headers_mut();
+    if let Some(origin) = origin {
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    }
+    headers.append(header::VARY, HeaderValue::from_static("origin"));
+    response
+}
+
+// =============================================================================
+// with_cors 関数
+// =============================================================================
+
+/// CORS ミドルウェアを Router に適用する
+///
+/// プリフライトはルートが存在しない OPTIONS にも届くため、
+/// route_layer ではなく layer で適用する。
+///
+/// # Arguments
+///
+/// * `router` - ミドルウェアを適用する Router
+/// * `config` - CORS 設定
+pub fn with_cors<S: Clone + Send + Sync + 'static>(
+    router: Router<S>,  // 適用対象の Router
+    config: CorsConfig, // CORS 設定
+) -> Router<S> {
+    router.layer(from_fn_with_state(Arc::new(config), cors))
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        with_cors(
+            Router::new().route("/api/todos", get(|| async { "[]" })),
+            CorsConfig::new(vec!["http://localhost:5173/".to_string()]),
+        )
+    }
+
+    fn preflight(origin: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/api/todos")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_preflight_from_allowed_origin() {
+        let response = app()
+            .oneshot(preflight("http://localhost:5173"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let headers = response.headers();
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "http://localhost:5173"
+        );
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_METHODS],
+            ALLOWED_METHODS
+        );
+        assert_eq!(headers[header::ACCESS_CONTROL_MAX_AGE], "600");
+    }
+
+    #[tokio::test]
+    async fn test_other_origins_get_no_cors_headers() {
+        let response = app()
+            .oneshot(preflight("https://evil.example"))
+            .await
+            .unwrap();
+
+        // プリフライトとして扱わない（OPTIONS のルートはないため 405）
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    #[tokio::test]
+    async fn test_simple_request_gets_allow_origin() {
+        let request = Request::builder()
+            .uri("/api/todos")
+            .header(header::ORIGIN, "http://localhost:5173")
+            .body(Body::empty())
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "http://localhost:5173"
+        );
+        assert_eq!(response.headers()[header::VARY], "origin");
+    }
+}
@@ -0,0 +1,182 @@
+// =============================================================================
+// presentation/src/middleware/edge_token.rs: Edge トークン検証ミドルウェア
+// =============================================================================
+// Edge 層の auth コンポーネントが発行したサービス用トークン（X-Edge-Token）を検証する。
+//
+// X-Edge-Verified（edge_verify）との違い:
+// - X-Edge-Verified は固定のシークレットで、漏れると無期限に使える
+// - X-Edge-Token は短期間（Edge 層の既定は 60 秒）だけ有効な署名付きトークン
+//   → 署名鍵は Edge 層の auth コンポーネントだけが持ち、gateway には共有しない
+//
+// 検証内容:
+// - 署名（JWT の鍵セット、ヘッダーの kid で鍵を選ぶ）と有効期限
+// - sub が EDGE_TOKEN_SUBJECT であること
+//   → 同じ鍵で署名したユーザーの JWT（sub はユーザー ID）を使い回せない
+// - 発行者（JWT_ISSUER）は検証しない（Edge 層のトークンは iss を含まない）
+//
+// 設定:
+// - RouterConfig::edge_token が true の場合のみ適用（api: REQUIRE_EDGE_TOKEN）
+// - Edge 検証（edge_verify）と同じルートグループに適用する
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// 外部クレートのインポート
+// -----------------------------------------------------------------------------
+
+// axum: Web フレームワーク
+use axum::{
+    body::Body,
+    extract::State,
+    http::{HeaderName, Request, StatusCode},
+    middleware::{from_fn_with_state, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+
+// application: JWT の鍵セット
+use application::JwtKeyRing;
+
+// =============================================================================
+// 定数
+// =============================================================================
+
+/// サービス用トークンのヘッダー名
+pub const EDGE_TOKEN_HEADER: HeaderName = HeaderName::from_static("x-edge-token");
+
+/// サービス用トークンの sub クレーム（Edge 層の gateway と同じ値）
+pub const EDGE_TOKEN_SUBJECT: &str = "edge-gateway";
+
+// =============================================================================
+// edge_token ミドルウェア関数
+// =============================================================================
+
+/// Edge トークン検証ミドルウェア
+///
+/// # Returns
+///
+/// * 検証成功: 次のミドルウェア/ハンドラの結果
+/// * ヘッダーなし・検証失敗: 403 Forbidden
+async fn edge_token(
+    State(keys): State<JwtKeyRing>, // JWT の鍵セット（リロードに追従する）
+    request: Request<Body>,         // HTTP リクエスト
+    next: Next,                     // 次のミドルウェア/ハンドラ
+) -> Response {
+    let token = request
+        .headers()
+        .get(&EDGE_TOKEN_HEADER)
+        .and_then(|v| v.to_str().ok());
+
+    let Some(token) = token else {
+        tracing::warn!("Edge token verification failed: missing X-Edge-Token header");
+        return (StatusCode::FORBIDDEN, "Forbidden: Missing edge token").into_response();
+    };
+
+    // 発行者は検証しない（Edge 層のトークンは iss を含まない）
+    match keys.snapshot().verify(token, None) {
+        Ok(claims) if claims.sub == EDGE_TOKEN_SUBJECT => next.run(request).await,
+        Ok(claims) => {
+            tracing::warn!(
+                sub = %claims.sub,
+                "Edge token verification failed: unexpected subject"
+            );
+            (StatusCode::FORBIDDEN, "Forbidden: Invalid edge token").into_response()
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Edge token verification failed");
+            (StatusCode::FORBIDDEN, "Forbidden: Invalid edge token").into_response()
+        }
+    }
+}
+
+// =============================================================================
+// with_edge_token 関数
+// =============================================================================
+
+/// Edge トークン検証ミドルウェアを Router に適用する
+///
+/// with_edge_verify と同様に route_layer で適用する。
+///
+/// # Arguments
+///
+/// * `router` - ミドルウェアを適用する Router
+/// * `keys` - JWT の鍵セット（`AuthService::keys()` を渡す）
+pub fn with_edge_token<S: Clone + Send + Sync + 'static>(
+    router: Router<S>, // 適用対象の Router
+    keys: JwtKeyRing,  // JWT の鍵セット
+) -> Router<S> {
+    router.route_layer(from_fn_with_state(keys, edge_token))
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use application::{Claims, KeySet};
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    fn keys() -> JwtKeyRing {
+        JwtKeyRing::new(KeySet::single("edge-token-test-secret"))
+    }
+
+    fn token(keys: &JwtKeyRing, sub: &str, exp_offset: i64) -> String {
+        let now = chrono::Utc::now().timestamp();
+        keys.snapshot()
+            .sign(&Claims {
+                sub: sub.to_string(),
+                exp: (now + exp_offset) as u64,
+                iat: now as u64,
+                iss: None,
+                aud: None,
+                extra: Default::default(),
+            })
+            .unwrap()
+    }
+
+    async fn status(keys: &JwtKeyRing, token: Option<String>) -> StatusCode {
+        let app = with_edge_token(
+            Router::new().route("/api/todos", get(|| async { "[]" })),
+            keys.clone(),
+        );
+        let mut request = Request::builder().uri("/api/todos");
+        if let Some(token) = token {
+            request = request.header(EDGE_TOKEN_HEADER, token);
+        }
+        app.oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_accepts_edge_token() {
+        let keys = keys();
+        let token = token(&keys, EDGE_TOKEN_SUBJECT, 60);
+        assert_eq!(status(&keys, Some(token)).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_missing_expired_and_user_tokens() {
+        let keys = keys();
+        assert_eq!(status(&keys, None).await, StatusCode::FORBIDDEN);
+
+        // 期限切れ（jwt::DEFAULT_LEEWAY_SECS の 60 秒を超える）
+        let expired = token(&keys, EDGE_TOKEN_SUBJECT, -120);
+        assert_eq!(status(&keys, Some(expired)).await, StatusCode::FORBIDDEN);
+
+        // 同じ鍵で署名したユーザーの JWT は使えない
+        let user = token(&keys, &uuid::Uuid::new_v4().to_string(), 60);
+        assert_eq!(status(&keys, Some(user)).await, StatusCode::FORBIDDEN);
+
+        // 別の鍵で署名したトークン
+        let other = token(
+            &JwtKeyRing::new(KeySet::single("other-secret")),
+            EDGE_TOKEN_SUBJECT,
+            60,
+        );
+        assert_eq!(status(&keys, Some(other)).await, StatusCode::FORBIDDEN);
+    }
+}
@@ -2,19 +2,26 @@
 // presentation/src/middleware/edge_verify.rs: Edge 検証ミドルウェア
 // =============================================================================
 // Defense in Depth: Core 層でも Edge 層からのリクエストを検証する。
-// X-Edge-Verified ヘッダーのシークレットを検証。
+// X-Edge-Signature ヘッダーの HMAC 署名を検証（edge-signature クレート）。
 //
 // セキュリティ目的:
 // - Core API に直接アクセスする攻撃を防ぐ
 // - Edge 層を経由しないリクエストを拒否
-// - シークレットの一致で正当な Edge 層からのリクエストを確認
+// - 署名の一致で正当な Edge 層からのリクエストを確認
+// - タイムスタンプ・メソッド・パス・ユーザー ID を署名に含めるため、
+//   一度見られたヘッダーを別のリクエストや許容範囲の後に再利用（リプレイ）できない
 //
 // 多層防御（Defense in Depth）:
 // 1. Edge 層: JWT 検証、レート制限、WAF
 // 2. Core 層: Edge 検証（このミドルウェア）、所有者チェック
 //
+// 移行（ロールアウト）:
+// - 以前の gateway は固定のシークレット（X-Edge-Verified）を送る
+// - EdgeVerifyConfig::accept_legacy_secret が true の間は X-Edge-Verified も受け付ける
+//   → コア層を先にデプロイし、全ての gateway が署名を送るようになったら無効にする
+//
 // 設定:
-// - 本番環境: EDGE_VERIFY_SECRET 環境変数で設定
+// - 本番環境: EDGE_SECRET 環境変数（署名の共有鍵）で設定
 // - 開発環境: 空にして検証をスキップ可能
 // =============================================================================
 
@@ -24,7 +31,7 @@
 
 // axum: Web フレームワーク
 // body::Body: リクエスト/レスポンスボディ
-// extract::State: ミドルウェア用状態抽出
+// extract::{OriginalUri, State}: ネスト前の URI / ミドルウェア用状態抽出
 // http::Request/StatusCode: HTTP リクエスト/ステータスコード
 // middleware::from_fn_with_state: 状態付きミドルウェア構築
 // middleware::Next: 次のミドルウェア/ハンドラ
@@ -32,7 +39,7 @@
 // Router: ルーターオブジェクト
 use axum::{
     body::Body,
-    extract::State,
+    extract::{OriginalUri, State},
     http::{Request, StatusCode},
     middleware::{from_fn_with_state, Next},
     response::{IntoResponse, Response},
@@ -40,24 +47,62 @@ use axum::{
 };
 
 // =============================================================================
-// EdgeVerifyState 構造体
+// EdgeVerifyConfig 構造体
 // =============================================================================
 
-/// Edge 検証用のシークレットを保持する状態
+/// Edge 検証の設定
 ///
-/// ミドルウェアで使用するシークレット値を保持する。
+/// ミドルウェアで使用する共有鍵と許容範囲を保持する。
 /// `from_fn_with_state` でミドルウェアに注入される。
 ///
+/// # 使用例
+///
+/// ```rust,ignore
+/// let config = EdgeVerifyConfig::new("my-secret".to_string())
+///     .with_max_age_secs(30)
+///     .with_legacy_secret(true);
+/// ```
+///
 /// # derive マクロ
 ///
 /// - `Clone`: 複製可能（ミドルウェアで必要）
-#[derive(Clone)]
-pub struct EdgeVerifyState {
-    /// Edge 検証用シークレット
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeVerifyConfig {
+    /// 署名の共有鍵（Edge 層の edge_secret と同じ値）
     ///
-    /// Edge 層が設定する X-Edge-Verified ヘッダーの値と一致する必要がある。
     /// 本番環境では長い乱数文字列を使用。
     pub secret: String,
+
+    /// 署名のタイムスタンプの許容範囲（秒、過去・未来の両方向）
+    pub max_age_secs: u64,
+
+    /// 以前の固定シークレット（X-Edge-Verified == secret）も受け付けるか（移行用）
+    pub accept_legacy_secret: bool,
+}
+
+impl EdgeVerifyConfig {
+    /// 共有鍵を指定して作成する
+    ///
+    /// 許容範囲は `edge_signature::DEFAULT_MAX_AGE_SECS`、X-Edge-Verified は受け付けない。
+    pub fn new(secret: String) -> Self {
+        Self {
+            secret,
+            max_age_secs: edge_signature::DEFAULT_MAX_AGE_SECS,
+            accept_legacy_secret: false,
+        }
+    }
+
+    /// 署名のタイムスタンプの許容範囲（秒）を変更する
+    pub fn with_max_age_secs(mut self, max_age_secs: u64) -> Self {
+        self.max_age_secs = max_age_secs;
+        self
+    }
+
+    /// X-Edge-Verified（固定シークレット）を受け付けるかを切り替える
+    pub fn with_legacy_secret(mut self, enabled: bool) -> Self {
+        self.accept_legacy_secret = enabled;
+        self
+    }
 }
 
 // =============================================================================
@@ -66,12 +111,12 @@ pub struct EdgeVerifyState {
 
 /// Edge 検証ミドルウェア
 ///
-/// X-Edge-Verified ヘッダーを検証し、正当な Edge 層からのリクエストであることを確認。
+/// X-Edge-Signature ヘッダーを検証し、正当な Edge 層からのリクエストであることを確認。
 /// これにより、Core 層に直接アクセスしようとする攻撃を防ぐ。
 ///
 /// # Arguments
 ///
-/// * `state` - EdgeVerifyState（シークレットを保持）
+/// * `state` - EdgeVerifyConfig（共有鍵と許容範囲を保持）
 /// * `request` - HTTP リクエスト
 /// * `next` - 次のミドルウェア/ハンドラ
 ///
@@ -82,72 +127,80 @@ pub struct EdgeVerifyState {
 ///
 /// # 処理フロー
 ///
-/// 1. X-Edge-Verified ヘッダーを取得
-/// 2. シークレットと比較
+/// 1. X-Edge-Signature がある: メソッド・パス（クエリを含む）・X-User-Id で署名を検証
+/// 2. ない: accept_legacy_secret の場合のみ X-Edge-Verified をシークレットと比較
 /// 3. 一致: next.run() で次に進む
 /// 4. 不一致/なし: 403 Forbidden を返す
 async fn edge_verify(
-    State(state): State<EdgeVerifyState>, // ミドルウェア用状態を抽出
-    request: Request<Body>,               // HTTP リクエスト
-    next: Next,                           // 次のミドルウェア/ハンドラ
+    State(state): State<EdgeVerifyConfig>, // ミドルウェア用状態を抽出
+    request: Request<Body>,                // HTTP リクエスト
+    next: Next,                            // 次のミドルウェア/ハンドラ
 ) -> Response {
     // -------------------------------------------------------------------------
-    // X-Edge-Verified ヘッダーの取得
+    // ヘッダーの取得
     // -------------------------------------------------------------------------
-    // Option チェーンで安全にヘッダー値を取得
-    let edge_verified = request
-        .headers() // HeaderMap を取得
-        .get("X-Edge-Verified") // ヘッダー値を取得（Option<&HeaderValue>）
-        .and_then(|v| v.to_str().ok()); // &str に変換（無効な UTF-8 なら None）
+    let signature = header(&request, edge_signature::HEADER);
+    let edge_verified = header(&request, "X-Edge-Verified");
 
-    // -------------------------------------------------------------------------
-    // X-Request-Id ヘッダーの取得（ログ用）
-    // -------------------------------------------------------------------------
     // デバッグ用にリクエスト ID を取得
     // 無い場合は "unknown" を使用
-    let request_id = request
-        .headers() // HeaderMap を取得
-        .get("X-Request-Id") // ヘッダー値を取得
-        .and_then(|v| v.to_str().ok()) // &str に変換
-        .unwrap_or("unknown"); // デフォルト値
+    let request_id = header(&request, "X-Request-Id").unwrap_or_else(|| "unknown".to_string());
 
     // -------------------------------------------------------------------------
-    // 検証と分岐
+    // 署名の検証
+    // -------------------------------------------------------------------------
+    if let Some(signature) = signature {
+        return match verify_signature(&request, &state, &signature) {
+            // 署名が一致し、許容範囲内: 検証成功
+            Ok(()) => next.run(request).await,
+
+            // 形式不正・署名不一致・許容範囲外: 検証失敗
+            Err(e) => {
+                // 警告ログ: 理由（改ざん・期限切れなど）を記録
+                tracing::warn!(
+                    request_id = %request_id,
+                    error = %e,
+                    "Edge verification failed: invalid signature"
+                );
+                (StatusCode::FORBIDDEN, "Forbidden: Invalid edge signature").into_response()
+            }
+        };
+    }
+
+    // -------------------------------------------------------------------------
+    // 固定シークレット（移行用）
     // -------------------------------------------------------------------------
     match edge_verified {
-        // シークレットが一致する場合: 検証成功
-        Some(secret) if secret == state.secret => {
-            // 次のミドルウェア/ハンドラに進む
-            // next.run(request) は async で実行される
+        // 移行中かつシークレットが一致する場合: 検証成功
+        Some(secret) if state.accept_legacy_secret && secret == state.secret => {
+            tracing::debug!(
+                request_id = %request_id,
+                "Edge verification passed with legacy X-Edge-Verified header"
+            );
             next.run(request).await
         }
 
-        // シークレットが一致しない場合: 検証失敗
-        Some(_) => {
+        // 移行中でシークレットが一致しない場合: 検証失敗
+        Some(_) if state.accept_legacy_secret => {
             // 警告ログ: シークレット不一致を記録
             tracing::warn!(
-                request_id = %request_id,      // Display フォーマット
+                request_id = %request_id,
                 "Edge verification failed: invalid secret"
             );
-
-            // 403 Forbidden を返す
-            // タプル (StatusCode, &str) は IntoResponse を実装
             (
                 StatusCode::FORBIDDEN,
                 "Forbidden: Invalid edge verification",
             )
-                .into_response() // Response に変換
+                .into_response()
         }
 
-        // ヘッダーが無い場合: 検証失敗
-        None => {
+        // 署名が無い場合（移行後は X-Edge-Verified だけでは通さない）: 検証失敗
+        _ => {
             // 警告ログ: ヘッダー欠落を記録
             tracing::warn!(
                 request_id = %request_id,
-                "Edge verification failed: missing X-Edge-Verified header"
+                "Edge verification failed: missing X-Edge-Signature header"
             );
-
-            // 403 Forbidden を返す
             (
                 StatusCode::FORBIDDEN,
                 "Forbidden: Missing edge verification",
@@ -157,6 +210,46 @@ async fn edge_verify(
     }
 }
 
+/// ヘッダー値を取得する（無い・無効な UTF-8 なら None）
+fn header(request: &Request<Body>, name: &str) -> Option<String> {
+    request
+        .headers()
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_owned)
+}
+
+/// X-Edge-Signature をリクエストのメソッド・パス・X-User-Id で検証する
+///
+/// # Errors
+///
+/// 形式が不正、署名が一致しない、またはタイムスタンプが許容範囲外の場合
+fn verify_signature(
+    request: &Request<Body>,
+    state: &EdgeVerifyConfig,
+    signature: &str,
+) -> Result<(), edge_signature::Error> {
+    // ネストしたルーター（/api/todos など）ではプレフィックスが取り除かれるため、
+    // Edge 層が署名したパスと比べられるよう元の URI を使う
+    let uri = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map_or(request.uri(), |original| &original.0);
+    let path = uri.path_and_query().map_or(uri.path(), |pq| pq.as_str());
+    let user_id = header(request, "X-User-Id").unwrap_or_default();
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+
+    edge_signature::verify(
+        signature,
+        state.secret.as_bytes(),
+        request.method().as_str(),
+        path,
+        &user_id,
+        now,
+        state.max_age_secs,
+    )
+}
+
 // =============================================================================
 // with_edge_verify 関数
 // =============================================================================
@@ -176,7 +269,7 @@ async fn edge_verify(
 /// # Arguments
 ///
 /// * `router` - ミドルウェアを適用する Router
-/// * `secret` - Edge 検証用シークレット
+/// * `config` - Edge 検証の設定（共有鍵・許容範囲・移行用フラグ）
 ///
 /// # Returns
 ///
@@ -188,18 +281,126 @@ async fn edge_verify(
 /// let protected_routes = Router::new()
 ///     .route("/api/todos", get(list_todos));
 ///
-/// let protected_routes = with_edge_verify(protected_routes, "my-secret".to_string());
+/// let protected_routes =
+///     with_edge_verify(protected_routes, EdgeVerifyConfig::new("my-secret".to_string()));
 /// ```
 pub fn with_edge_verify<S: Clone + Send + Sync + 'static>(
-    router: Router<S>, // 適用対象の Router
-    secret: String,    // Edge 検証用シークレット
+    router: Router<S>,        // 適用対象の Router
+    config: EdgeVerifyConfig, // Edge 検証の設定
 ) -> Router<S> {
     // route_layer: Router の全ルートにミドルウェアを適用
     // from_fn_with_state: 状態付きの関数をミドルウェアに変換
-    // EdgeVerifyState: シークレットを保持する状態
     // edge_verify: ミドルウェア関数
-    router.route_layer(from_fn_with_state(
-        EdgeVerifyState { secret }, // シークレットを状態に設定
-        edge_verify,                // ミドルウェア関数
-    ))
+    router.route_layer(from_fn_with_state(config, edge_verify))
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    const SECRET: &str = "edge-verify-test-secret";
+
+    fn now() -> u64 {
+        chrono::Utc::now().timestamp() as u64
+    }
+
+    /// /api/todos にネストしたルーター（本番と同じくプレフィックスが取り除かれる）
+    fn app(config: EdgeVerifyConfig) -> Router {
+        let todos = Router::new().route("/{id}", get(|| async { "{}" }));
+        Router::new().nest("/api/todos", with_edge_verify(todos, config))
+    }
+
+    async fn status(config: EdgeVerifyConfig, headers: &[(&str, String)]) -> StatusCode {
+        let mut request = Request::builder().uri("/api/todos/1?fields=title");
+        for (name, value) in headers {
+            request = request.header(*name, value);
+        }
+        app(config)
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    fn signed(timestamp: u64, path: &str, user_id: &str) -> Vec<(&'static str, String)> {
+        vec![
+            (
+                edge_signature::HEADER,
+                edge_signature::sign(SECRET.as_bytes(), timestamp, "GET", path, user_id),
+            ),
+            ("x-user-id", "user-1".to_string()),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_accepts_signed_request() {
+        let config = EdgeVerifyConfig::new(SECRET.to_string());
+        let headers = signed(now(), "/api/todos/1?fields=title", "user-1");
+        assert_eq!(status(config, &headers).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_tampered_path_and_user() {
+        let config = EdgeVerifyConfig::new(SECRET.to_string());
+
+        // 別のパス・クエリに署名したヘッダーを付け替えた
+        for path in [
+            "/api/todos/2?fields=title",
+            "/api/todos/1",
+            "/todos/1?fields=title",
+        ] {
+            let headers = signed(now(), path, "user-1");
+            assert_eq!(
+                status(config.clone(), &headers).await,
+                StatusCode::FORBIDDEN,
+                "{}",
+                path
+            );
+        }
+
+        // 署名時とは別のユーザー（X-User-Id を書き換えた）
+        let headers = signed(now(), "/api/todos/1?fields=title", "user-2");
+        assert_eq!(status(config, &headers).await, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_skewed_clocks() {
+        let config = EdgeVerifyConfig::new(SECRET.to_string()).with_max_age_secs(30);
+        let path = "/api/todos/1?fields=title";
+
+        // 許容範囲内のずれ
+        for timestamp in [now() - 20, now() + 20] {
+            let headers = signed(timestamp, path, "user-1");
+            assert_eq!(status(config.clone(), &headers).await, StatusCode::OK);
+        }
+        // 古すぎる・未来すぎる
+        for timestamp in [now() - 120, now() + 120] {
+            let headers = signed(timestamp, path, "user-1");
+            assert_eq!(
+                status(config.clone(), &headers).await,
+                StatusCode::FORBIDDEN
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_legacy_secret_only_behind_flag() {
+        let legacy = [("x-edge-verified", SECRET.to_string())];
+        let wrong = [("x-edge-verified", "guess".to_string())];
+
+        let config = EdgeVerifyConfig::new(SECRET.to_string());
+        assert_eq!(status(config.clone(), &legacy).await, StatusCode::FORBIDDEN);
+        assert_eq!(status(config, &[]).await, StatusCode::FORBIDDEN);
+
+        let config = EdgeVerifyConfig::new(SECRET.to_string()).with_legacy_secret(true);
+        assert_eq!(status(config.clone(), &legacy).await, StatusCode::OK);
+        assert_eq!(status(config.clone(), &wrong).await, StatusCode::FORBIDDEN);
+        assert_eq!(status(config, &[]).await, StatusCode::FORBIDDEN);
+    }
 }
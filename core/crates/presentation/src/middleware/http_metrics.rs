@@ -0,0 +1,216 @@
+// =============================================================================
+// presentation/src/middleware/http_metrics.rs: HTTP リクエストメトリクス
+// =============================================================================
+// リクエスト数（ステータスクラス別）と処理中リクエスト数を数え、
+// 内部リスナーの /metrics（Prometheus 形式）で公開する。
+//
+// 仕組み:
+// - カウンタは AppState（http_metrics）が保持し、/metrics ハンドラが読み出す
+// - ミドルウェアはルーター全体に layer で適用する（404 も数える）
+// - RouterConfig::metrics が false の場合は適用しない（カウンタは 0 のまま）
+// - create_router が適用した同時実行制限（RouterConfig::concurrency）も登録し、
+//   /metrics がグループごとの処理中リクエスト数と上限を出力する
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// 外部クレートのインポート
+// -----------------------------------------------------------------------------
+
+// std: 標準ライブラリ
+// Arc: カウンタを AppState とミドルウェアで共有
+// AtomicU64/AtomicUsize/Ordering: ロックなしのカウンタとゲージ
+// Mutex: ルーターが登録する同時実行制限
+use std::sync::{
+    atomic::{AtomicU64, AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+// axum: Web フレームワーク
+use axum::{
+    body::Body,
+    extract::State,
+    http::Request,
+    middleware::{from_fn_with_state, Next},
+    response::Response,
+    Router,
+};
+
+// crate: 同時実行制限（グループごとのゲージ）
+use crate::middleware::ConcurrencyLimits;
+
+// =============================================================================
+// 定数
+// =============================================================================
+
+/// ステータスクラスのラベル（インデックス = ステータスコード / 100 - 1）
+const STATUS_CLASSES: [&str; 5] = ["1xx", "2xx", "3xx", "4xx", "5xx"];
+
+// =============================================================================
+// HttpMetrics 構造体
+// =============================================================================
+
+/// HTTP リクエストのカウンタ
+///
+/// 内部は Arc で共有されるため、clone() したインスタンスは同じカウンタを参照する。
+///
+/// # derive マクロ
+///
+/// - `Clone`: 複製可能（AppState とミドルウェアの状態で共有）
+/// - `Default`: 全て 0 で作成
+#[derive(Clone, Default)]
+pub struct HttpMetrics {
+    /// ステータスクラス別のリクエスト数（1xx〜5xx）
+    responses: Arc<[AtomicU64; 5]>,
+
+    /// 処理中リクエスト数（ゲージ）
+    in_flight: Arc<AtomicUsize>,
+
+    /// ルーターが適用した同時実行制限（未登録の場合は None）
+    concurrency: Arc<Mutex<Option<ConcurrencyLimits>>>,
+}
+
+impl HttpMetrics {
+    /// 完了したリクエストの合計数
+    pub fn requests_total(&self) -> u64 {
+        self.responses
+            .iter()
+            .map(|count| count.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    /// ステータスクラス別のリクエスト数（`("2xx", 10)` の形式）
+    pub fn requests_by_class(&self) -> Vec<(&'static str, u64)> {
+        STATUS_CLASSES
+            .iter()
+            .zip(self.responses.iter())
+            .map(|(class, count)| (*class, count.load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    /// 処理中リクエスト数
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    /// ルーターが適用した同時実行制限（グループごとの処理中リクエスト数・上限）
+    ///
+    /// create_router が RouterConfig::concurrency を登録する。未登録の場合は None。
+    pub fn concurrency_limits(&self) -> Option<ConcurrencyLimits> {
+        self.concurrency
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// 同時実行制限を登録する（後に作成したルーターの制限で置き換える）
+    pub(crate) fn track_concurrency(&self, limits: ConcurrencyLimits) {
+        *self
+            .concurrency
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(limits);
+    }
+
+    /// 完了したリクエストを記録する
+    fn record(&self, status: u16) {
+        let index = usize::from(status / 100).clamp(1, STATUS_CLASSES.len()) - 1;
+        self.responses[index].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+// =============================================================================
+// http_metrics ミドルウェア関数
+// =============================================================================
+
+/// HTTP メトリクスミドルウェア
+///
+/// 処理中ゲージを加算してから次に進み、レスポンスのステータスクラスを記録する。
+async fn http_metrics(
+    State(metrics): State<HttpMetrics>, // 共有カウンタ
+    request: Request<Body>,             // HTTP リクエスト
+    next: Next,                         // 次のミドルウェア/ハンドラ
+) -> Response {
+    let response = {
+        // ガード: リクエストがキャンセルされた場合もゲージを戻す
+        let _guard = InFlight::new(metrics.in_flight.clone());
+        next.run(request).await
+    };
+
+    metrics.record(response.status().as_u16());
+    response
+}
+
+/// 処理中ゲージを生成時に加算し、Drop 時に減算するガード
+struct InFlight(Arc<AtomicUsize>);
+
+impl InFlight {
+    fn new(in_flight: Arc<AtomicUsize>) -> Self {
+        in_flight.fetch_add(1, Ordering::Relaxed);
+        Self(in_flight)
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+// =============================================================================
+// with_http_metrics 関数
+// =============================================================================
+
+/// HTTP メトリクスミドルウェアを Router に適用する
+///
+/// Router::layer で適用するため、マッチしないパス（404）も数える。
+///
+/// # Arguments
+///
+/// * `router` - ミドルウェアを適用する Router
+/// * `metrics` - 記録先のカウンタ（AppState::http_metrics を渡す）
+pub fn with_http_metrics<S: Clone + Send + Sync + 'static>(
+    router: Router<S>,    // 適用対象の Router
+    metrics: HttpMetrics, // 記録先のカウンタ
+) -> Router<S> {
+    router.layer(from_fn_with_state(metrics, http_metrics))
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_counts_responses_by_status_class() {
+        let metrics = HttpMetrics::default();
+        let app = with_http_metrics(
+            Router::new().route("/ok", get(|| async { "ok" })),
+            metrics.clone(),
+        );
+
+        for uri in ["/ok", "/ok", "/missing"] {
+            let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+            app.clone().oneshot(request).await.unwrap();
+        }
+
+        assert_eq!(metrics.requests_total(), 3);
+        let by_class = metrics.requests_by_class();
+        assert!(by_class.contains(&("2xx", 2)));
+        assert!(by_class.contains(&("4xx", 1)));
+        assert_eq!(metrics.in_flight(), 0);
+    }
+
+    #[test]
+    fn test_record_clamps_unknown_status() {
+        let metrics = HttpMetrics::default();
+        metrics.record(StatusCode::INTERNAL_SERVER_ERROR.as_u16());
+        metrics.record(999);
+
+        assert!(metrics.requests_by_class().contains(&("5xx", 2)));
+    }
+}
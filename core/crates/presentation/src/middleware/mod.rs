@@ -5,9 +5,17 @@
 // Edge 層からのリクエスト検証と認証情報の抽出を担当。
 //
 // モジュール構成:
+// - concurrency_limit: ルートグループ単位の同時実行制限（ロードシェディング）
+// - cors: CORS ヘッダーの付与（Edge 層を経由しない開発構成用）
+// - edge_token: Edge 層が発行したサービス用トークン（X-Edge-Token）の検証
 // - edge_verify: Edge 検証ミドルウェア（Defense in Depth）
+// - http_metrics: リクエスト数・処理中リクエスト数のカウンタ（/metrics）
+// - rate_limit: プロセス単位のレート制限（トークンバケット）
+// - request_trace: リクエストのスパンと X-Request-Id
 // - user_context: UserContext エクストラクタ（認証情報）
 //
+// どのミドルウェアを適用するかは RouterConfig（router_config.rs）で切り替える。
+//
 // セキュリティ戦略:
 // - Defense in Depth（多層防御）パターンを採用
 // - Edge 層: JWT 検証、レート制限
@@ -25,22 +33,79 @@
 // サブモジュール宣言
 // -----------------------------------------------------------------------------
 
+// concurrency_limit: 同時実行制限ミドルウェア
+// 重いルートグループの同時実行数を制限し、超過時は 503 を返す
+mod concurrency_limit;
+
+// cors: CORS ミドルウェア
+// 許可オリジンのプリフライトに応答し、Access-Control-Allow-Origin を付与
+mod cors;
+
+// edge_token: Edge トークン検証ミドルウェア
+// X-Edge-Token（Edge 層の auth コンポーネントが発行した短期トークン）を検証
+mod edge_token;
+
 // edge_verify: Edge 検証ミドルウェア
-// X-Edge-Verified ヘッダーでリクエスト元を検証
+// X-Edge-Signature ヘッダー（HMAC 署名）でリクエスト元を検証
 mod edge_verify;
 
+// http_metrics: HTTP メトリクスミドルウェア
+// ステータスクラス別のリクエスト数と処理中リクエスト数を数える
+mod http_metrics;
+
+// rate_limit: レート制限ミドルウェア
+// トークン不足のリクエストに 429 を返す
+mod rate_limit;
+
+// request_trace: トレースミドルウェア
+// リクエストごとのスパンと X-Request-Id の引き継ぎ・生成
+mod request_trace;
+
 // user_context: UserContext エクストラクタ
-// X-User-Id ヘッダーから認証済みユーザー情報を抽出
+// X-User-Id（と X-User-Roles / X-Claim-Tenant-Id）ヘッダーから認証済みユーザー情報を抽出
 mod user_context;
 
 // -----------------------------------------------------------------------------
 // 再エクスポート
 // -----------------------------------------------------------------------------
 
+// ConcurrencyLimit: ルートグループ単位の同時実行制限（ゲージを保持）
+// ConcurrencyLimits: ルートグループごとの同時実行制限の組（create_router に渡す）
+// with_concurrency_limit: Router に同時実行制限を適用する関数
+pub use concurrency_limit::{with_concurrency_limit, ConcurrencyLimit, ConcurrencyLimits};
+
+// CorsConfig: 許可オリジンとプリフライトのキャッシュ期間
+// with_cors: Router に CORS を適用する関数
+pub use cors::{with_cors, CorsConfig, DEFAULT_CORS_MAX_AGE_SECS};
+
+// EDGE_TOKEN_HEADER: サービス用トークンのヘッダー名（x-edge-token）
+// EDGE_TOKEN_SUBJECT: サービス用トークンの sub クレーム（edge-gateway）
+// with_edge_token: Router に Edge トークン検証を適用する関数
+pub use edge_token::{with_edge_token, EDGE_TOKEN_HEADER, EDGE_TOKEN_SUBJECT};
+
+// EdgeVerifyConfig: 署名の共有鍵・許容範囲・移行用フラグ
 // with_edge_verify: Router に Edge 検証を適用する関数
-// 使用例: with_edge_verify(router, "secret".to_string())
-pub use edge_verify::with_edge_verify;
+// 使用例: with_edge_verify(router, EdgeVerifyConfig::new("secret".to_string()))
+pub use edge_verify::{with_edge_verify, EdgeVerifyConfig};
+
+// HttpMetrics: リクエストのカウンタ（AppState が保持し、/metrics で公開）
+// with_http_metrics: Router に HTTP メトリクスを適用する関数
+pub use http_metrics::{with_http_metrics, HttpMetrics};
+
+// RateLimit: プロセス単位のトークンバケット
+// with_rate_limit: Router にレート制限を適用する関数
+pub use rate_limit::{with_rate_limit, RateLimit};
+
+// REQUEST_ID_HEADER: リクエスト ID のヘッダー名（x-request-id）
+// with_request_trace: Router にトレースを適用する関数
+pub use request_trace::{with_request_trace, REQUEST_ID_HEADER};
 
 // UserContext: 認証済みユーザー情報（ハンドラの引数として使用）
 // 使用例: async fn handler(user: UserContext) -> impl IntoResponse
-pub use user_context::UserContext;
+// USER_ROLES_HEADER / TENANT_ID_HEADER / SCOPE_HEADER: Edge 層が転送する識別ヘッダー名
+// REVOKE_TOKENS_HEADER / REVOKE_ALL_TOKENS: Edge 層にトークンの失効を指示するレスポンスヘッダー
+// TOKEN_VERSION_HEADER: Edge 層にユーザーのトークンのバージョンを通知するレスポンスヘッダー
+pub use user_context::{
+    UserContext, REVOKE_ALL_TOKENS, REVOKE_TOKENS_HEADER, SCOPE_HEADER, TENANT_ID_HEADER,
+    TOKEN_VERSION_HEADER, USER_ROLES_HEADER,
+};
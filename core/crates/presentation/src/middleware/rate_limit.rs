@@ -0,0 +1,250 @@
+// =============================================================================
+// presentation/src/middleware/rate_limit.rs: プロセス単位のレート制限
+// =============================================================================
+// トークンバケットで /api/* のリクエストレートを制限し、超過時は 429 を返す。
+//
+// 目的:
+// - ユーザー単位のレート制限は Edge 層が行う（Defense in Depth の 1 層目）
+// - Core 層のレート制限は Edge 層を経由しない構成（memory バックエンドでの開発、
+//   ロードバランサーから直接受ける構成）でプロセス全体を保護する最後の砦
+//
+// 仕組み:
+// - バケットはプロセス全体で 1 つ（インスタンス数倍が全体の上限になる）
+// - 1 秒あたり per_second 個のトークンを補充し、最大 burst 個まで貯める
+// - トークンがなければ 429 Too Many Requests（Retry-After 付き、code=rate_limited）
+//
+// レイヤー順序:
+// - /api/* のルーターに layer で適用する（/health・/ready は制限しない）
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// 外部クレートのインポート
+// -----------------------------------------------------------------------------
+
+// std: 標準ライブラリ
+// Arc/Mutex: バケットの状態をミドルウェアのクローン間で共有
+// Instant: トークン補充の経過時間
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+// axum: Web フレームワーク
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, Request, StatusCode},
+    middleware::{from_fn_with_state, Next},
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+
+// =============================================================================
+// RateLimit 構造体
+// =============================================================================
+
+/// プロセス単位のレート制限（トークンバケット）
+///
+/// 内部は Arc で共有されるため、clone() したインスタンスは同じバケットを参照する。
+///
+/// # 使用例
+///
+/// ```rust,ignore
+/// // 平均 100 req/s、瞬間的には 200 件まで許容
+/// let config = RouterConfig::default().with_rate_limit(RateLimit::new(100, 200));
+/// ```
+#[derive(Clone)]
+pub struct RateLimit {
+    /// 1 秒あたりに補充するトークン数
+    per_second: u32,
+
+    /// バケットの容量（瞬間的に許容するリクエスト数）
+    burst: u32,
+
+    /// バケットの状態
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+/// トークンバケットの状態
+struct Bucket {
+    /// 残りトークン数
+    tokens: f64,
+
+    /// 最後にトークンを補充した時刻
+    refilled_at: Instant,
+}
+
+impl RateLimit {
+    /// 新しいレート制限を作成する（バケットは満杯の状態で始まる）
+    ///
+    /// # Arguments
+    ///
+    /// * `per_second` - 1 秒あたりに補充するトークン数（0 の場合は 1 に補正）
+    /// * `burst` - バケットの容量（per_second 未満の場合は per_second に補正）
+    pub fn new(per_second: u32, burst: u32) -> Self {
+        let per_second = per_second.max(1);
+        let burst = burst.max(per_second);
+
+        Self {
+            per_second,
+            burst,
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: f64::from(burst),
+                refilled_at: Instant::now(),
+            })),
+        }
+    }
+
+    /// 1 秒あたりに補充するトークン数
+    pub fn per_second(&self) -> u32 {
+        self.per_second
+    }
+
+    /// バケットの容量
+    pub fn burst(&self) -> u32 {
+        self.burst
+    }
+
+    /// トークンを 1 つ取得する
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - 取得できた
+    /// * `Err(secs)` - トークン不足（次のトークンまでの秒数、切り上げ）
+    fn try_acquire(&self) -> Result<(), u64> {
+        // ロックは補充と取得の間だけ保持する（await を挟まない）
+        let mut bucket = self
+            .bucket
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens =
+            (bucket.tokens + elapsed * f64::from(self.per_second)).min(f64::from(self.burst));
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let wait = (1.0 - bucket.tokens) / f64::from(self.per_second);
+            Err(wait.ceil().max(1.0) as u64)
+        }
+    }
+}
+
+// =============================================================================
+// rate_limit ミドルウェア関数
+// =============================================================================
+
+/// レート制限ミドルウェア
+///
+/// # Returns
+///
+/// * トークン取得成功: 次のミドルウェア/ハンドラの結果
+/// * トークン不足: 429 Too Many Requests（Retry-After 付き）
+async fn rate_limit(
+    State(limit): State<RateLimit>, // 共有バケット
+    request: Request<Body>,         // HTTP リクエスト
+    next: Next,                     // 次のミドルウェア/ハンドラ
+) -> Response {
+    if let Err(retry_after) = limit.try_acquire() {
+        tracing::warn!(
+            per_second = limit.per_second,
+            burst = limit.burst,
+            "Request rejected: rate limit exceeded"
+        );
+
+        // {"error": "...", "code": "rate_limited"}
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after.to_string())],
+            Json(serde_json::json!({
+                "error": "Too many requests",
+                "code": "rate_limited",
+            })),
+        )
+            .into_response();
+    }
+
+    next.run(request).await
+}
+
+// =============================================================================
+// with_rate_limit 関数
+// =============================================================================
+
+/// レート制限ミドルウェアを Router に適用する
+///
+/// Router::layer で適用するため、Router の全ルートで 1 つのバケットを共有する。
+///
+/// # Arguments
+///
+/// * `router` - ミドルウェアを適用する Router
+/// * `limit` - レート制限
+pub fn with_rate_limit<S: Clone + Send + Sync + 'static>(
+    router: Router<S>, // 適用対象の Router
+    limit: RateLimit,  // レート制限
+) -> Router<S> {
+    router.layer(from_fn_with_state(limit, rate_limit))
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    fn request() -> Request<Body> {
+        Request::builder().uri("/ping").body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rejects_requests_over_burst() {
+        let app = with_rate_limit(
+            Router::new().route("/ping", get(|| async { "pong" })),
+            RateLimit::new(1, 2),
+        );
+
+        // バケットの容量（burst = 2）までは通す
+        for _ in 0..2 {
+            let response = app.clone().oneshot(request()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        // 3 件目: トークン不足で 429
+        let response = app.oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "1");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], "rate_limited");
+    }
+
+    #[test]
+    fn test_tokens_refill_over_time() {
+        let limit = RateLimit::new(10, 10);
+        for _ in 0..10 {
+            assert!(limit.try_acquire().is_ok());
+        }
+        assert!(limit.try_acquire().is_err());
+
+        // 0.1 秒で 1 トークン補充される
+        std::thread::sleep(std::time::Duration::from_millis(150));
+        assert!(limit.try_acquire().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_values_are_clamped() {
+        let limit = RateLimit::new(0, 0);
+        assert_eq!(limit.per_second(), 1);
+        assert_eq!(limit.burst(), 1);
+    }
+}
@@ -0,0 +1,175 @@
+// =============================================================================
+// presentation/src/middleware/request_trace.rs: リクエストのトレース
+// =============================================================================
+// リクエストごとに tracing のスパンを作り、完了時にステータスと所要時間を記録する。
+//
+// リクエスト ID（X-Request-Id）:
+// - Edge 層・ロードバランサーが付与した値があれば引き継ぎ、なければ UUID v4 を生成
+// - スパンのフィールドとレスポンスヘッダーの両方に設定する
+//   → クライアントの問い合わせ（レスポンスの ID）からログを検索できる
+//
+// ログ出力例:
+//   INFO request{method=GET path=/api/todos request_id=...}: Request completed status=200 latency_ms=3
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// 外部クレートのインポート
+// -----------------------------------------------------------------------------
+
+// std: 標準ライブラリ
+// Instant: 所要時間の計測
+use std::time::Instant;
+
+// axum: Web フレームワーク
+use axum::{
+    body::Body,
+    http::{HeaderName, HeaderValue, Request},
+    middleware::{from_fn, Next},
+    response::Response,
+    Router,
+};
+
+// tracing: スパンを非同期処理に関連付ける
+use tracing::Instrument;
+
+// uuid: リクエスト ID の生成
+use uuid::Uuid;
+
+// =============================================================================
+// 定数
+// =============================================================================
+
+/// リクエスト ID のヘッダー名
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// 引き継ぐリクエスト ID の最大長（それ以上は新しい ID を生成する）
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+// =============================================================================
+// request_trace ミドルウェア関数
+// =============================================================================
+
+/// リクエストのトレースミドルウェア
+///
+/// スパン内で次のミドルウェア/ハンドラを実行し、レスポンスに X-Request-Id を付与する。
+async fn request_trace(
+    mut request: Request<Body>, // HTTP リクエスト
+    next: Next,                 // 次のミドルウェア/ハンドラ
+) -> Response {
+    // 受け取った ID は表示可能な ASCII で短いものだけ引き継ぐ（ログの汚染を防ぐ）
+    let request_id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .filter(|id| {
+            id.len() <= MAX_REQUEST_ID_LEN
+                && id
+                    .to_str()
+                    .is_ok_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_graphic()))
+        })
+        .cloned()
+        .unwrap_or_else(|| {
+            HeaderValue::from_str(&Uuid::new_v4().to_string())
+                .expect("a UUID is a valid header value")
+        });
+    // 後続（ハンドラ・他のミドルウェア）からも同じ ID を参照できるようにする
+    request
+        .headers_mut()
+        .insert(REQUEST_ID_HEADER, request_id.clone());
+
+    let span = tracing::info_span!(
+        "request",
+        method = %request.method(),
+        path = %request.uri().path(),
+        request_id = request_id.to_str().unwrap_or_default(),
+    );
+
+    let started = Instant::now();
+    let mut response = next.run(request).instrument(span.clone()).await;
+    span.in_scope(|| {
+        tracing::info!(
+            status = response.status().as_u16(),
+            latency_ms = started.elapsed().as_millis() as u64,
+            "Request completed"
+        );
+    });
+
+    response.headers_mut().insert(REQUEST_ID_HEADER, request_id);
+    response
+}
+
+// =============================================================================
+// with_request_trace 関数
+// =============================================================================
+
+/// トレースミドルウェアを Router に適用する
+///
+/// 他のミドルウェア（レート制限・Edge 検証）が返すレスポンスも記録するため、
+/// 最も外側に layer で適用する。
+///
+/// # Arguments
+///
+/// * `router` - ミドルウェアを適用する Router
+pub fn with_request_trace<S: Clone + Send + Sync + 'static>(router: Router<S>) -> Router<S> {
+    router.layer(from_fn(request_trace))
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    /// リクエストヘッダーの X-Request-Id をそのまま返すルーター
+    fn app() -> Router {
+        with_request_trace(Router::new().route(
+            "/echo",
+            get(|request: Request<Body>| async move {
+                request.headers()[REQUEST_ID_HEADER]
+                    .to_str()
+                    .unwrap()
+                    .to_string()
+            }),
+        ))
+    }
+
+    async fn call(request_id: Option<&str>) -> (String, String) {
+        let mut request = Request::builder().uri("/echo");
+        if let Some(id) = request_id {
+            request = request.header(REQUEST_ID_HEADER, id);
+        }
+        let response = app()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let header = response.headers()[REQUEST_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (header, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_propagates_incoming_request_id() {
+        let (header, seen_by_handler) = call(Some("edge-123")).await;
+        assert_eq!(header, "edge-123");
+        assert_eq!(seen_by_handler, "edge-123");
+    }
+
+    #[tokio::test]
+    async fn test_generates_request_id_when_missing_or_invalid() {
+        let (header, seen_by_handler) = call(None).await;
+        assert!(Uuid::parse_str(&header).is_ok());
+        assert_eq!(header, seen_by_handler);
+
+        let too_long = "x".repeat(MAX_REQUEST_ID_LEN + 1);
+        let (header, _) = call(Some(&too_long)).await;
+        assert!(Uuid::parse_str(&header).is_ok());
+    }
+}
@@ -10,8 +10,16 @@
 // 3. Core 層（このモジュール）が X-User-Id を抽出
 // 4. ハンドラで UserContext として利用可能
 //
+// 追加の識別情報（任意、無ければ安全側のデフォルト）:
+// - X-User-Roles: JWT の roles クレーム（カンマ区切り）→ roles（未知のロールは無視）
+// - X-Claim-Tenant-Id: 許可リストの tenant_id クレーム → tenant_id（UUID 以外は無視）
+// - X-Claim-Scope: 許可リストの scope クレーム（空白区切り）→ scopes
+//
 // セキュリティ:
-// - X-User-Id は Edge 層でのみ設定される想定
+// - X-User-Id / X-User-Roles / X-Claim-* は Edge 層でのみ設定される想定
+//   （Edge 層はクライアントが送った同名のヘッダーを転送しない）
+// - X-User-Id はハイフン区切りの 36 文字の UUID のみ受け付ける（Edge 層の auth コンポーネントが
+//   sub を検証する形式と同じ、共通のテストケースは core/crates/jwt/testdata/subjects）
 // - Edge 検証ミドルウェアと組み合わせて使用
 // - 外部から直接 X-User-Id を設定しても Edge 検証で弾かれる
 // =============================================================================
@@ -32,6 +40,38 @@ use axum::{
 // uuid: 一意識別子
 use uuid::Uuid;
 
+// domain: ユーザーのロール（X-User-Roles の値）
+use domain::Role;
+
+// =============================================================================
+// ヘッダー名
+// =============================================================================
+
+/// JWT の roles クレームを転送するヘッダー（カンマ区切り、Edge 層が設定）
+pub const USER_ROLES_HEADER: &str = "x-user-roles";
+
+/// JWT の tenant_id クレームを転送するヘッダー（Edge 層の許可リストのクレーム）
+pub const TENANT_ID_HEADER: &str = "x-claim-tenant-id";
+
+/// JWT の scope クレームを転送するヘッダー（空白区切り、Edge 層の許可リストのクレーム）
+pub const SCOPE_HEADER: &str = "x-claim-scope";
+
+/// リクエストのユーザーの発行済みトークンの失効を Edge 層に指示するレスポンスヘッダー
+///
+/// ログアウト・パスワードの変更のハンドラが認証済みのリクエストへのレスポンスに付ける。
+/// Edge 層の gateway が key-value ストアの失効リストに書き込み、クライアントには返さない。
+pub const REVOKE_TOKENS_HEADER: &str = "x-revoke-tokens";
+
+/// REVOKE_TOKENS_HEADER の値（ユーザーの発行済みトークンをすべて失効させる）
+pub const REVOKE_ALL_TOKENS: &str = "all";
+
+/// リクエストのユーザーのトークンのバージョンを Edge 層に通知するレスポンスヘッダー（10 進数の整数）
+///
+/// パスワードの変更・全端末からのログアウトでバージョンを上げたハンドラが付ける。
+/// Edge 層の gateway が key-value ストアに書き込み（小さい値では下げない）、auth コンポーネントは
+/// ver クレーム（ない場合は 0）がこの値より小さいトークンを拒否する。クライアントには返さない。
+pub const TOKEN_VERSION_HEADER: &str = "x-token-version";
+
 // =============================================================================
 // UserContext 構造体
 // =============================================================================
@@ -58,9 +98,9 @@ use uuid::Uuid;
 ///
 /// # セキュリティ
 ///
-/// このエクストラクタは X-User-Id ヘッダーが存在し、有効な UUID である
-/// ことを検証する。ヘッダーが無い場合や不正な形式の場合は 401 Unauthorized
-/// を返す。
+/// このエクストラクタは X-User-Id ヘッダーが存在し、ハイフン区切りの UUID である
+/// ことを検証する（Edge 層の検証に加えた多層防御）。ヘッダーが無い場合や
+/// 不正な形式の場合は 401 Unauthorized を返す。
 #[derive(Debug, Clone)]
 pub struct UserContext {
     /// 認証済みユーザーの ID（UUID）
@@ -74,6 +114,51 @@ pub struct UserContext {
     /// X-Request-Id ヘッダーから取得。
     /// 分散トレーシングやデバッグに使用。
     pub request_id: Option<String>,
+
+    /// JWT の roles クレーム（X-User-Roles ヘッダー）
+    ///
+    /// ヘッダーが無い場合は空。未知のロール名は無視する。
+    /// 認可の判断には DB のロール（users.role）を使うこと。
+    pub roles: Vec<Role>,
+
+    /// JWT の tenant_id クレーム（X-Claim-Tenant-Id ヘッダー）
+    ///
+    /// ヘッダーが無い場合や UUID でない場合は None。
+    /// 一覧の絞り込みには `tenant_scope()` を使う。
+    pub tenant_id: Option<Uuid>,
+
+    /// JWT の scope クレーム（X-Claim-Scope ヘッダー、空白区切り）
+    ///
+    /// ヘッダーが無い場合は空。ロールと同じく参考情報で、確認には `has_scope()` を使う。
+    pub scopes: Vec<String>,
+}
+
+impl UserContext {
+    /// 一覧の絞り込み・作成時の記録に使うテナント ID
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_scoping` - テナント単位の絞り込みが有効か（AppState::tenant_scoping）
+    ///
+    /// # Returns
+    ///
+    /// 有効な場合は tenant_id、無効な場合は常に None
+    pub fn tenant_scope(&self, tenant_scoping: bool) -> Option<Uuid> {
+        if tenant_scoping {
+            self.tenant_id
+        } else {
+            None
+        }
+    }
+
+    /// scope クレームに指定したスコープが含まれるか
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - スコープ名（例: "todos:write"）
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
 }
 
 // =============================================================================
@@ -124,7 +209,7 @@ where
             .headers // HeaderMap を取得
             .get("X-User-Id") // ヘッダー値を取得（Option<&HeaderValue>）
             .and_then(|v| v.to_str().ok()) // &str に変換（無効な UTF-8 なら None）
-            .and_then(|s| Uuid::parse_str(s).ok()); // UUID にパース（無効な形式なら None）
+            .and_then(parse_user_id); // UUID にパース（無効な形式なら None）
 
         // ---------------------------------------------------------------------
         // X-Request-Id ヘッダーの抽出（オプショナル）
@@ -136,6 +221,28 @@ where
             .and_then(|v| v.to_str().ok()) // &str に変換
             .map(|s| s.to_string()); // String に変換
 
+        // ---------------------------------------------------------------------
+        // X-User-Roles / X-Claim-Tenant-Id / X-Claim-Scope ヘッダーの抽出（オプショナル）
+        // ---------------------------------------------------------------------
+        // 不正な値はエラーにせず、無いものとして扱う
+        let roles = parts
+            .headers
+            .get(USER_ROLES_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(parse_roles)
+            .unwrap_or_default();
+        let tenant_id = parts
+            .headers
+            .get(TENANT_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| Uuid::parse_str(s.trim()).ok());
+        let scopes = parts
+            .headers
+            .get(SCOPE_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(parse_scopes)
+            .unwrap_or_default();
+
         // ---------------------------------------------------------------------
         // 結果の判定
         // ---------------------------------------------------------------------
@@ -146,6 +253,9 @@ where
                 tracing::debug!(
                     user_id = %user_id,        // Display フォーマットで出力
                     request_id = ?request_id,  // Debug フォーマットで出力
+                    roles = ?roles,
+                    tenant_id = ?tenant_id,
+                    scopes = ?scopes,
                     "User context extracted"
                 );
 
@@ -153,6 +263,9 @@ where
                 Ok(UserContext {
                     user_id,
                     request_id,
+                    roles,
+                    tenant_id,
+                    scopes,
                 })
             }
             // user_id が取得できなかった場合
@@ -172,3 +285,159 @@ where
         }
     }
 }
+
+// =============================================================================
+// ヘルパー関数
+// =============================================================================
+
+/// X-User-Id の値をユーザー ID に変換する
+///
+/// ハイフン区切りの 36 文字の UUID のみ受け付ける（Edge 層の jwt::is_uuid と同じ形式）。
+/// `Uuid::parse_str` が受け付けるハイフンのない形式・波括弧・`urn:uuid:` は拒否する。
+fn parse_user_id(value: &str) -> Option<Uuid> {
+    if value.len() != 36 {
+        return None;
+    }
+    Uuid::parse_str(value).ok()
+}
+
+/// X-User-Roles の値（カンマ区切り）をロールのリストに変換する
+///
+/// 前後の空白と空の要素、未知のロール名、重複は無視する。
+fn parse_roles(value: &str) -> Vec<Role> {
+    let mut roles = Vec::new();
+    for role in value
+        .split(',')
+        .filter_map(|s| s.trim().parse::<Role>().ok())
+    {
+        if !roles.contains(&role) {
+            roles.push(role);
+        }
+    }
+    roles
+}
+
+/// X-Claim-Scope の値（空白区切り、RFC 8693 の scope）をスコープのリストに変換する
+///
+/// 連続した空白と重複は無視する。
+fn parse_scopes(value: &str) -> Vec<String> {
+    let mut scopes: Vec<String> = Vec::new();
+    for scope in value.split_whitespace() {
+        if !scopes.iter().any(|s| s == scope) {
+            scopes.push(scope.to_string());
+        }
+    }
+    scopes
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+
+    /// ヘッダーを指定して UserContext を抽出する
+    async fn extract(headers: &[(&str, &str)]) -> Result<UserContext, (StatusCode, &'static str)> {
+        let mut request = Request::builder();
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        let (mut parts, _) = request.body(()).unwrap().into_parts();
+        UserContext::from_request_parts(&mut parts, &()).await
+    }
+
+    #[tokio::test]
+    async fn test_extracts_roles_and_tenant() {
+        let user_id = Uuid::new_v4();
+        let tenant_id = Uuid::new_v4();
+        let user = extract(&[
+            ("x-user-id", &user_id.to_string()),
+            ("x-user-roles", "user, admin"),
+            ("x-claim-tenant-id", &tenant_id.to_string()),
+            ("x-claim-scope", "todos:read todos:write"),
+        ])
+        .await
+        .unwrap();
+
+        assert_eq!(user.user_id, user_id);
+        assert_eq!(user.roles, vec![Role::User, Role::Admin]);
+        assert_eq!(user.scopes, vec!["todos:read", "todos:write"]);
+        assert!(user.has_scope("todos:write"));
+        assert!(!user.has_scope("todos"));
+        assert_eq!(user.tenant_id, Some(tenant_id));
+        assert_eq!(user.tenant_scope(true), Some(tenant_id));
+        assert_eq!(user.tenant_scope(false), None);
+    }
+
+    #[tokio::test]
+    async fn test_defaults_when_identity_headers_are_absent() {
+        let user = extract(&[("x-user-id", &Uuid::new_v4().to_string())])
+            .await
+            .unwrap();
+
+        assert!(user.roles.is_empty());
+        assert_eq!(user.tenant_id, None);
+        assert_eq!(user.tenant_scope(true), None);
+        assert!(user.scopes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ignores_malformed_identity_headers() {
+        // 未知のロール・空の要素・重複、UUID でないテナントは無視する
+        let user = extract(&[
+            ("x-user-id", &Uuid::new_v4().to_string()),
+            ("x-user-roles", "superuser,,admin,ADMIN,admin"),
+            ("x-claim-tenant-id", "tenant-a' OR 1=1"),
+            ("x-claim-scope", "  todos:read   todos:read "),
+        ])
+        .await
+        .unwrap();
+
+        assert_eq!(user.roles, vec![Role::Admin]);
+        assert_eq!(user.tenant_id, None);
+        assert_eq!(user.scopes, vec!["todos:read"]);
+    }
+
+    /// Edge 層の auth コンポーネント・jwt クレートと共通の sub のテストケース
+    const SUBJECTS: &str = include_str!("../../../jwt/testdata/subjects/subjects.json");
+
+    #[test]
+    fn test_parse_user_id_matches_shared_subjects() {
+        let subjects: serde_json::Value = serde_json::from_str(SUBJECTS).unwrap();
+        for (group, expected) in [("valid", true), ("invalid", false)] {
+            for subject in subjects[group].as_array().unwrap() {
+                let subject = subject.as_str().unwrap();
+                assert_eq!(parse_user_id(subject).is_some(), expected, "{:?}", subject);
+            }
+        }
+        assert_eq!(parse_user_id(&"a".repeat(10 * 1024)), None);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_user_id_is_rejected() {
+        for user_id in ["1 OR 1=1", "550e8400e29b41d4a716446655440000"] {
+            let result = extract(&[("x-user-id", user_id)]).await;
+            assert_eq!(
+                result.unwrap_err().0,
+                StatusCode::UNAUTHORIZED,
+                "{}",
+                user_id
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_identity_headers_without_user_id_are_rejected() {
+        // X-User-Id が無ければ、他の識別ヘッダーがあっても 401
+        let result = extract(&[
+            ("x-user-roles", "admin"),
+            ("x-claim-tenant-id", &Uuid::new_v4().to_string()),
+        ])
+        .await;
+
+        assert_eq!(result.unwrap_err().0, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,575 @@
+// =============================================================================
+// presentation/src/router_config.rs: ルーター構築オプション
+// =============================================================================
+// create_router が適用するミドルウェアを切り替える設定。
+//
+// 目的:
+// - 本番（api の serve）は `RouterConfig::default()` に環境変数の値を設定して全て適用
+// - テストや別のバイナリ（memory バックエンドの開発モードなど）は
+//   `RouterConfig::minimal()` から必要なレイヤーだけを有効にする
+//
+// ミドルウェアの適用順（外側 → 内側）:
+//   request_trace → http_metrics → cors → rate_limit(/api/*) → body_limit
+//     → edge_verify（/api/todos, files, jobs, admin） → edge_token（同じルート）
+//     → concurrency_limit（batch, upload）
+//     → ハンドラ
+//
+// 圧縮:
+// - レスポンスの圧縮は Edge 層（Spin）・ロードバランサーで行うため、Core 層では扱わない
+// =============================================================================
+
+// -----------------------------------------------------------------------------
+// 外部クレートのインポート
+// -----------------------------------------------------------------------------
+
+// crate: 各ミドルウェアの設定型
+use crate::middleware::{ConcurrencyLimits, CorsConfig, EdgeVerifyConfig, RateLimit};
+
+// =============================================================================
+// 定数
+// =============================================================================
+
+/// リクエストボディの上限のデフォルト（バイト、axum の既定値と同じ 2MB）
+pub const DEFAULT_MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// ファイルアップロードのボディの上限のデフォルト（バイト）
+///
+/// domain のファイルサイズ上限（100MB）に multipart のヘッダー分の余裕を加える。
+pub const DEFAULT_MAX_UPLOAD_BYTES: usize = 100 * 1024 * 1024 + 64 * 1024;
+
+/// バッチ系ルートの同時実行数上限のデフォルト
+pub const DEFAULT_BATCH_MAX_IN_FLIGHT: usize = 8;
+
+/// ファイルアップロードの同時実行数上限のデフォルト
+pub const DEFAULT_UPLOAD_MAX_IN_FLIGHT: usize = 16;
+
+// =============================================================================
+// BodyLimits 構造体
+// =============================================================================
+
+/// リクエストボディの上限（超過時は 413 Payload Too Large）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyLimits {
+    /// アップロード以外のルート（JSON・CSV）の上限（バイト）
+    pub max_body_bytes: usize,
+
+    /// POST /api/files/upload の上限（バイト）
+    pub max_upload_bytes: usize,
+}
+
+impl Default for BodyLimits {
+    fn default() -> Self {
+        Self {
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            max_upload_bytes: DEFAULT_MAX_UPLOAD_BYTES,
+        }
+    }
+}
+
+// =============================================================================
+// RouterConfig 構造体
+// =============================================================================
+
+/// ルーター構築オプション（create_router に渡す）
+///
+/// 各フィールドが 1 つのミドルウェアに対応する。`None` / `false` のレイヤーは
+/// ルーターに適用しない（無効化ではなく、存在しない）。
+///
+/// # 使用例
+///
+/// ```rust,ignore
+/// // 本番: デフォルト（トレース、メトリクス、ボディ上限、同時実行制限）+ Edge 検証
+/// let app = create_router(
+///     state,
+///     RouterConfig::default()
+///         .with_edge_verify(config.edge_secret.clone().map(EdgeVerifyConfig::new)),
+/// );
+///
+/// // テスト: レート制限だけを有効にしたルーター
+/// let app = create_router(
+///     state,
+///     RouterConfig::minimal().with_rate_limit(RateLimit::new(1, 1)),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct RouterConfig {
+    /// Edge 検証（X-Edge-Signature）の設定（None の場合は Edge 検証を適用しない）
+    pub edge_verify: Option<EdgeVerifyConfig>,
+
+    /// Edge 層が発行したサービス用トークン（X-Edge-Token）を要求するか
+    ///
+    /// Edge 検証と同じルートに適用し、JWT の鍵セット（AuthService::keys）で検証する。
+    pub edge_token: bool,
+
+    /// ルートグループごとの同時実行制限（None の場合は適用しない）
+    pub concurrency: Option<ConcurrencyLimits>,
+
+    /// CORS（None の場合は適用しない）
+    pub cors: Option<CorsConfig>,
+
+    /// /api/* のレート制限（None の場合は適用しない）
+    pub rate_limit: Option<RateLimit>,
+
+    /// リクエストボディの上限（None の場合は axum の既定値 2MB のまま）
+    pub body_limits: Option<BodyLimits>,
+
+    /// リクエストのスパンと X-Request-Id を付与するか
+    pub request_tracing: bool,
+
+    /// HTTP メトリクス（AppState::http_metrics）を記録するか
+    pub metrics: bool,
+}
+
+impl Default for RouterConfig {
+    /// 本番のデフォルト
+    ///
+    /// トレース・メトリクス・ボディ上限・同時実行制限を適用する。
+    /// Edge 検証はシークレットが必要なため `with_edge_verify` で設定する。
+    /// CORS とレート制限は Edge 層が担うため無効。
+    fn default() -> Self {
+        Self {
+            edge_verify: None,
+            edge_token: false,
+            concurrency: Some(ConcurrencyLimits::new(
+                DEFAULT_BATCH_MAX_IN_FLIGHT,
+                DEFAULT_UPLOAD_MAX_IN_FLIGHT,
+            )),
+            cors: None,
+            rate_limit: None,
+            body_limits: Some(BodyLimits::default()),
+            request_tracing: true,
+            metrics: true,
+        }
+    }
+}
+
+impl RouterConfig {
+    /// ミドルウェアを何も適用しない設定（ルートとハンドラのみ）
+    ///
+    /// テストで対象のレイヤーだけを有効にする起点として使う。
+    pub fn minimal() -> Self {
+        Self {
+            edge_verify: None,
+            edge_token: false,
+            concurrency: None,
+            cors: None,
+            rate_limit: None,
+            body_limits: None,
+            request_tracing: false,
+            metrics: false,
+        }
+    }
+
+    /// Edge 検証を設定する（None なら Edge 検証を適用しない）
+    pub fn with_edge_verify(mut self, edge_verify: Option<EdgeVerifyConfig>) -> Self {
+        self.edge_verify = edge_verify;
+        self
+    }
+
+    /// X-Edge-Token の検証を切り替える
+    pub fn with_edge_token(mut self, enabled: bool) -> Self {
+        self.edge_token = enabled;
+        self
+    }
+
+    /// 同時実行制限を設定する
+    pub fn with_concurrency_limits(mut self, limits: ConcurrencyLimits) -> Self {
+        self.concurrency = Some(limits);
+        self
+    }
+
+    /// CORS を設定する
+    pub fn with_cors(mut self, cors: CorsConfig) -> Self {
+        self.cors = Some(cors);
+        self
+    }
+
+    /// /api/* のレート制限を設定する
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// リクエストボディの上限を設定する
+    pub fn with_body_limits(mut self, body_limits: BodyLimits) -> Self {
+        self.body_limits = Some(body_limits);
+        self
+    }
+
+    /// リクエストのトレースを切り替える
+    pub fn with_request_tracing(mut self, enabled: bool) -> Self {
+        self.request_tracing = enabled;
+        self
+    }
+
+    /// HTTP メトリクスの記録を切り替える
+    pub fn with_metrics(mut self, enabled: bool) -> Self {
+        self.metrics = enabled;
+        self
+    }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+// 各レイヤーについて、有効にした場合に効果があり、無効の場合に存在しないことを
+// create_router 経由で確認する。
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use application::UploadFileCommand;
+    use async_trait::async_trait;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{header, Method, Request, StatusCode};
+    use axum::Router;
+    use domain::{DomainError, StorageOps, StoredObject};
+    use infrastructure::InMemoryStorage;
+    use tokio::sync::Semaphore;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use crate::middleware::REQUEST_ID_HEADER;
+    use crate::{create_internal_router, create_router, AppState};
+
+    fn get(uri: &str) -> Request<Body> {
+        Request::builder().uri(uri).body(Body::empty()).unwrap()
+    }
+
+    /// 認証済みユーザーとして TODO 一覧を取得する（Edge 検証ヘッダーなし）
+    fn list_todos() -> Request<Body> {
+        Request::builder()
+            .uri("/api/todos")
+            .header("X-User-Id", uuid::Uuid::new_v4().to_string())
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    async fn status(app: &Router, request: Request<Body>) -> StatusCode {
+        app.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[test]
+    fn test_default_is_production_stack() {
+        let config = RouterConfig::default();
+        assert!(config.request_tracing && config.metrics);
+        assert!(config.concurrency.is_some());
+        assert_eq!(config.body_limits, Some(BodyLimits::default()));
+        assert!(config.edge_verify.is_none() && config.cors.is_none());
+        assert!(!config.edge_token);
+        assert!(config.rate_limit.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_edge_verification_toggle() {
+        let state = AppState::for_tests();
+        let with = create_router(
+            state.clone(),
+            RouterConfig::minimal()
+                .with_edge_verify(Some(EdgeVerifyConfig::new("edge-secret".to_string()))),
+        );
+        let without = create_router(state, RouterConfig::minimal());
+
+        assert_eq!(status(&with, list_todos()).await, StatusCode::FORBIDDEN);
+        assert_eq!(status(&without, list_todos()).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_edge_token_toggle() {
+        let state = AppState::for_tests();
+        let with = create_router(state.clone(), RouterConfig::minimal().with_edge_token(true));
+        let without = create_router(state.clone(), RouterConfig::minimal());
+
+        assert_eq!(status(&with, list_todos()).await, StatusCode::FORBIDDEN);
+        assert_eq!(status(&without, list_todos()).await, StatusCode::OK);
+
+        // AppState の鍵セットで署名したサービス用トークンなら通す
+        let now = chrono::Utc::now().timestamp() as u64;
+        let token = state
+            .auth_service
+            .keys()
+            .snapshot()
+            .sign(&application::Claims {
+                sub: crate::middleware::EDGE_TOKEN_SUBJECT.to_string(),
+                exp: now + 60,
+                iat: now,
+                iss: None,
+                aud: None,
+                extra: Default::default(),
+            })
+            .unwrap();
+        let mut request = list_todos();
+        request
+            .headers_mut()
+            .insert(crate::middleware::EDGE_TOKEN_HEADER, token.parse().unwrap());
+        assert_eq!(status(&with, request).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_toggle() {
+        let state = AppState::for_tests();
+        let limits = ConcurrencyLimits::new(1, 1);
+        let with = create_router(
+            state.clone(),
+            RouterConfig::minimal().with_concurrency_limits(limits.clone()),
+        );
+        let without = create_router(state, RouterConfig::minimal());
+
+        // upload グループの許可を全て使い切った状態にする
+        let _permit = limits.upload.saturate();
+        let upload = || {
+            Request::builder()
+                .method(Method::POST)
+                .uri("/api/files/upload")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        assert_eq!(
+            status(&with, upload()).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_ne!(
+            status(&without, upload()).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cors_toggle() {
+        let state = AppState::for_tests();
+        let with = create_router(
+            state.clone(),
+            RouterConfig::minimal()
+                .with_cors(CorsConfig::new(vec!["http://localhost:5173".to_string()])),
+        );
+        let without = create_router(state, RouterConfig::minimal());
+        let preflight = || {
+            Request::builder()
+                .method(Method::OPTIONS)
+                .uri("/api/todos")
+                .header(header::ORIGIN, "http://localhost:5173")
+                .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = with.oneshot(preflight()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        let response = without.oneshot(preflight()).await.unwrap();
+        assert_ne!(response.status(), StatusCode::NO_CONTENT);
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_toggle_applies_to_api_only() {
+        let state = AppState::for_tests();
+        let with = create_router(
+            state.clone(),
+            RouterConfig::minimal().with_rate_limit(RateLimit::new(1, 1)),
+        );
+        let without = create_router(state, RouterConfig::minimal());
+
+        assert_eq!(status(&with, list_todos()).await, StatusCode::OK);
+        assert_eq!(
+            status(&with, list_todos()).await,
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        // ヘルスチェックは制限しない
+        assert_eq!(status(&with, get("/health")).await, StatusCode::OK);
+
+        for _ in 0..3 {
+            assert_eq!(status(&without, list_todos()).await, StatusCode::OK);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_body_limit_toggle() {
+        let state = AppState::for_tests();
+        let with = create_router(
+            state.clone(),
+            RouterConfig::minimal().with_body_limits(BodyLimits {
+                max_body_bytes: 64,
+                max_upload_bytes: 64,
+            }),
+        );
+        let without = create_router(state, RouterConfig::minimal());
+        let login = || {
+            let body = serde_json::json!({
+                "email": "someone@example.com",
+                "password": "x".repeat(128),
+            });
+            Request::builder()
+                .method(Method::POST)
+                .uri("/api/auth/login")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        assert_eq!(status(&with, login()).await, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_ne!(
+            status(&without, login()).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_request_tracing_toggle() {
+        let state = AppState::for_tests();
+        let with = create_router(
+            state.clone(),
+            RouterConfig::minimal().with_request_tracing(true),
+        );
+        let without = create_router(state, RouterConfig::minimal());
+
+        let response = with.oneshot(get("/health")).await.unwrap();
+        assert!(response.headers().contains_key(REQUEST_ID_HEADER));
+
+        let response = without.oneshot(get("/health")).await.unwrap();
+        assert!(!response.headers().contains_key(REQUEST_ID_HEADER));
+    }
+
+    /// アップロードをテスト側が許可を追加するまでブロックするストレージ
+    struct GatedStorage {
+        gate: Arc<Semaphore>,
+        inner: InMemoryStorage,
+    }
+
+    #[async_trait]
+    impl StorageOps for GatedStorage {
+        async fn upload(
+            &self,
+            user_id: Uuid,
+            filename: &str,
+            content_type: &str,
+            data: Vec<u8>,
+        ) -> Result<String, DomainError> {
+            let _permit = self.gate.acquire().await;
+            self.inner
+                .upload(user_id, filename, content_type, data)
+                .await
+        }
+
+        async fn download(&self, storage_path: &str) -> Result<Vec<u8>, DomainError> {
+            self.inner.download(storage_path).await
+        }
+
+        async fn delete(&self, storage_path: &str) -> Result<(), DomainError> {
+            self.inner.delete(storage_path).await
+        }
+
+        async fn list(&self, prefix: &str) -> Result<Vec<StoredObject>, DomainError> {
+            self.inner.list(prefix).await
+        }
+    }
+
+    /// 本番と同じレイヤー（トレース → メトリクス → ボディ上限 → 同時実行制限）で、
+    /// ハンドラ内でブロックしたリクエストが各ゲージに反映される
+    #[tokio::test]
+    async fn test_full_stack_layer_ordering() {
+        let gate = Arc::new(Semaphore::new(0));
+        let mut state = AppState::for_tests();
+        state.upload_file = UploadFileCommand::new(Arc::new(GatedStorage {
+            gate: gate.clone(),
+            inner: InMemoryStorage::new(),
+        }));
+        let limits = ConcurrencyLimits::new(1, 1);
+        let app = create_router(
+            state.clone(),
+            RouterConfig::default().with_concurrency_limits(limits.clone()),
+        );
+        let internal = create_internal_router(state.clone());
+        let user_id = Uuid::new_v4();
+        let upload = || {
+            let body = "--boundary\r\n\
+                Content-Disposition: form-data; name=\"file\"; filename=\"note.txt\"\r\n\
+                Content-Type: text/plain\r\n\r\n\
+                hello\r\n\
+                --boundary--\r\n";
+            Request::builder()
+                .method(Method::POST)
+                .uri("/api/files/upload")
+                .header(
+                    header::CONTENT_TYPE,
+                    "multipart/form-data; boundary=boundary",
+                )
+                .header("X-User-Id", user_id.to_string())
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let scrape = || async {
+            let response = internal.clone().oneshot(get("/metrics")).await.unwrap();
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            String::from_utf8(body.to_vec()).unwrap()
+        };
+
+        // 1 件目: ストレージへのアップロードでブロックさせる
+        let blocked = tokio::spawn(app.clone().oneshot(upload()));
+        for _ in 0..100 {
+            if limits.upload.in_flight() == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(limits.upload.in_flight(), 1);
+
+        // メトリクスは同時実行制限の外側: ブロック中のリクエストを処理中として数える
+        assert_eq!(state.http_metrics.in_flight(), 1);
+        let body = scrape().await;
+        assert!(
+            body.contains("todo_api_http_requests_in_flight 1\n"),
+            "{}",
+            body
+        );
+        assert!(body.contains("todo_api_concurrency_in_flight{group=\"upload\"} 1\n"));
+        assert!(body.contains("todo_api_concurrency_in_flight{group=\"batch\"} 0\n"));
+        assert!(body.contains("todo_api_concurrency_limit{group=\"upload\"} 1\n"));
+
+        // 2 件目: 同時実行制限が 503 を返し、外側のトレース・メトリクスを通る
+        let response = app.clone().oneshot(upload()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(response.headers().contains_key(REQUEST_ID_HEADER));
+        assert_eq!(limits.upload.in_flight(), 1);
+        let body = scrape().await;
+        assert!(body.contains("todo_api_http_requests_total{class=\"5xx\"} 1\n"));
+
+        // ブロックを解除すると 1 件目が完了し、全てのゲージが 0 に戻る
+        gate.add_permits(1);
+        let response = blocked.await.unwrap().unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert!(response.headers().contains_key(REQUEST_ID_HEADER));
+        assert_eq!(limits.upload.in_flight(), 0);
+        assert_eq!(state.http_metrics.in_flight(), 0);
+        let body = scrape().await;
+        assert!(body.contains("todo_api_http_requests_total{class=\"2xx\"} 1\n"));
+        assert!(body.contains("todo_api_concurrency_in_flight{group=\"upload\"} 0\n"));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_toggle() {
+        let with_state = AppState::for_tests();
+        let with = create_router(
+            with_state.clone(),
+            RouterConfig::minimal().with_metrics(true),
+        );
+        let without_state = AppState::for_tests();
+        let without = create_router(without_state.clone(), RouterConfig::minimal());
+
+        status(&with, get("/health")).await;
+        status(&without, get("/health")).await;
+
+        assert_eq!(with_state.http_metrics.requests_total(), 1);
+        assert_eq!(without_state.http_metrics.requests_total(), 0);
+    }
+}
@@ -10,14 +10,20 @@
 // - /api/auth/login      - ログイン（認証不要）
 // - /api/todos/*         - TODO 操作（Edge 検証 + 認証必須）
 // - /api/files/*         - ファイル操作（Edge 検証 + 認証必須）
+// - /api/jobs/*          - 非同期インポートジョブ（Edge 検証 + 認証必須）
+// - /api/admin/*         - 管理者専用（Edge 検証 + 認証 + admin ロール）
 //
-// 統一 CQRS パターン:
-// - TW: TodoWriter（Commands 用）
-// - TR: TodoReader（Queries 用）
-// - C: TodoCacheOps（キャッシュ操作用）
-// - UR: UserReader（Queries 用）
-// - UW: UserWriter（Commands 用）
-// - S: StorageOps（ファイルストレージ操作用）
+// 内部ルーター（create_internal_router、INTERNAL_ADDR で公開）:
+// - /livez, /readyz, /metrics のみ（クラスタ内部からの監視用、認証なし）
+//
+// 同時実行制限（ロードシェディング）:
+// - batch:  /api/todos/batch, /api/todos/batch-update, /api/todos/with-files,
+//           /api/todos/import, /api/todos/import-csv
+// - upload: /api/files/upload
+// 上限を超えたリクエストはキューイングせず 503 を返す
+//
+// 適用するミドルウェア（Edge 検証、Edge トークン検証、同時実行制限、CORS、レート制限、ボディ上限、
+// トレース、メトリクス）は RouterConfig で切り替える
 // =============================================================================
 
 // -----------------------------------------------------------------------------
@@ -28,19 +34,23 @@
 // routing: ルーティングヘルパー（get, post, delete など）
 // Router: ルーターオブジェクト
 use axum::{
+    extract::DefaultBodyLimit,
     routing::{delete, get, post},
     Router,
 };
 
-// domain: ドメイン層のトレイト
-use domain::{StorageOps, TodoCacheOps, TodoReader, TodoWriter, UserReader, UserWriter};
-
 // crate: このクレート内のモジュール
 use crate::handlers::{
-    batch_create_todos, create_todo, create_todo_with_files, delete_file, delete_todo,
-    download_file, get_todo, healthz, list_todos, login, register, update_todo, upload_file,
+    batch_create_todos, batch_update_todos, cancel_import_job, create_todo, create_todo_with_files,
+    delete_file, delete_todo, download_file, get_import_job, get_todo, healthz, import_todos,
+    import_todos_csv, list_todos, login, metrics, readyz, register, reload_secrets, update_todo,
+    upload_file,
+};
+use crate::middleware::{
+    with_concurrency_limit, with_cors, with_edge_token, with_edge_verify, with_http_metrics,
+    with_rate_limit, with_request_trace,
 };
-use crate::middleware::with_edge_verify;
+use crate::router_config::RouterConfig;
 use crate::state::AppState;
 
 // =============================================================================
@@ -52,21 +62,13 @@ use crate::state::AppState;
 /// # Arguments
 ///
 /// * `state` - アプリケーション状態（Clone 可能、内部で Arc を使用）
-/// * `edge_secret` - Edge 検証用シークレット（None の場合は検証をスキップ）
+/// * `config` - 適用するミドルウェア（本番は `RouterConfig::default()`、
+///   テストは `RouterConfig::minimal()` から必要なものだけ）
 ///
 /// # Returns
 ///
 /// 設定済みの axum Router
 ///
-/// # Type Parameters
-///
-/// * `TW` - TodoWriter 実装（Commands 用）
-/// * `TR` - TodoReader 実装（Queries 用）
-/// * `C` - TodoCacheOps 実装（キャッシュ操作用）
-/// * `UR` - UserReader 実装（Queries 用）
-/// * `UW` - UserWriter 実装（Commands 用）
-/// * `S` - StorageOps 実装（ファイルストレージ操作用）
-///
 /// # Architecture
 ///
 /// ```text
@@ -74,29 +76,19 @@ use crate::state::AppState;
 /// /api/auth/register   - 認証不要（ユーザー登録）
 /// /api/auth/login      - 認証不要（ログイン）
 /// /api/todos/*         - Edge 検証 + UserContext 必須
+/// /api/todos/batch     - Edge 検証 → 同時実行制限（batch）
+/// /api/files/upload    - Edge 検証 → 同時実行制限（upload）、ボディ上限（upload）
+/// /api/jobs/{id}       - Edge 検証 + UserContext 必須
+/// /api/admin/*         - Edge 検証 + UserContext 必須（ハンドラで admin ロールを確認）
 /// ```
 ///
-/// # ジェネリクスの制約
+/// # 依存の差し替え
 ///
-/// - `TW: TodoWriter + 'static`: TODO 書き込み操作を提供、静的ライフタイム
-/// - `TR: TodoReader + 'static`: TODO 読み取り操作を提供、静的ライフタイム
-/// - `C: TodoCacheOps + 'static`: キャッシュ操作を提供、静的ライフタイム
-/// - `UR: UserReader + 'static`: ユーザー読み取り操作を提供、静的ライフタイム
-/// - `UW: UserWriter + 'static`: ユーザー書き込み操作を提供、静的ライフタイム
-/// - `S: StorageOps + 'static`: ストレージ操作を提供、静的ライフタイム
-///
-/// `'static` 制約は、これらの型が任意の長さのライフタイムを持てることを保証。
-/// axum のハンドラは 'static 境界を要求するため必要。
-pub fn create_router<
-    TW: TodoWriter + 'static,
-    TR: TodoReader + 'static,
-    C: TodoCacheOps + 'static,
-    UR: UserReader + 'static,
-    UW: UserWriter + 'static,
-    S: StorageOps + 'static,
->(
-    state: AppState<TW, TR, C, UR, UW, S>, // axum 推奨: Clone 可能な AppState
-    edge_secret: Option<String>,           // Option: None なら検証をスキップ
+/// AppState は依存をトレイトオブジェクトで保持するため、ルーターは
+/// 実装（PostgreSQL / インメモリ / モック）に関係なく同じ型になる。
+pub fn create_router(
+    state: AppState,      // axum 推奨: Clone 可能な AppState
+    config: RouterConfig, // 適用するミドルウェア
 ) -> Router {
     // -------------------------------------------------------------------------
     // 認証ルート（Edge 検証不要、パブリック）
@@ -104,9 +96,9 @@ pub fn create_router<
     // ユーザー登録とログインは認証なしでアクセス可能
     let auth_routes = Router::new()
         // POST /api/auth/register - ユーザー登録
-        .route("/register", post(register::<TW, TR, C, UR, UW, S>))
+        .route("/register", post(register))
         // POST /api/auth/login - ログイン
-        .route("/login", post(login::<TW, TR, C, UR, UW, S>));
+        .route("/login", post(login));
 
     // -------------------------------------------------------------------------
     // TODO ルート（Edge 検証が必要）
@@ -115,63 +107,124 @@ pub fn create_router<
     let todo_routes = Router::new()
         // GET /api/todos - TODO 一覧取得
         // POST /api/todos - TODO 作成
-        .route(
-            "/",
-            get(list_todos::<TW, TR, C, UR, UW, S>).post(create_todo::<TW, TR, C, UR, UW, S>),
-        )
+        .route("/", get(list_todos).post(create_todo))
         // GET /api/todos/{id} - TODO 詳細取得
         // PATCH /api/todos/{id} - TODO 更新
         // DELETE /api/todos/{id} - TODO 削除
         .route(
             "/{id}", // {id} はパスパラメータ（UUID）
-            get(get_todo::<TW, TR, C, UR, UW, S>)
-                .patch(update_todo::<TW, TR, C, UR, UW, S>)
-                .delete(delete_todo::<TW, TR, C, UR, UW, S>),
-        )
+            get(get_todo).patch(update_todo).delete(delete_todo),
+        );
+
+    // -------------------------------------------------------------------------
+    // バッチ系ルート（同時実行制限: batch）
+    // -------------------------------------------------------------------------
+    // トランザクションで DB コネクションを長く保持するため、
+    // 同時実行数を制限して通常の CRUD を巻き添えにしない
+    let batch_routes = Router::new()
         // POST /api/todos/batch - バッチ作成（トランザクション対応）
-        .route("/batch", post(batch_create_todos::<TW, TR, C, UR, UW, S>))
+        .route("/batch", post(batch_create_todos))
+        // POST /api/todos/batch-update - バッチ更新（トランザクション対応）
+        .route("/batch-update", post(batch_update_todos))
         // POST /api/todos/with-files - TODO + ファイル同時作成
-        .route(
-            "/with-files",
-            post(create_todo_with_files::<TW, TR, C, UR, UW, S>),
-        );
+        .route("/with-files", post(create_todo_with_files))
+        // POST /api/todos/import - インポート（?async=true でジョブ登録）
+        .route("/import", post(import_todos))
+        // POST /api/todos/import-csv - CSV インポート（?mode=partial で有効な行のみ作成）
+        .route("/import-csv", post(import_todos_csv));
+    let batch_routes = match &config.concurrency {
+        Some(limits) => with_concurrency_limit(batch_routes, limits.batch.clone()),
+        None => batch_routes,
+    };
+    let todo_routes = todo_routes.merge(batch_routes);
+
+    // 同時実行制限のゲージを /metrics（内部ルーター）で公開する
+    if let Some(limits) = &config.concurrency {
+        state.http_metrics.track_concurrency(limits.clone());
+    }
 
     // -------------------------------------------------------------------------
     // ファイルルート（Edge 検証が必要）
     // -------------------------------------------------------------------------
     // ファイルのアップロード、ダウンロード、削除
-    let file_routes = Router::new()
-        // POST /api/files/upload - ファイルアップロード
-        .route("/upload", post(upload_file::<TW, TR, C, UR, UW, S>))
+    // POST /api/files/upload - ファイルアップロード（同時実行制限: upload）
+    // リクエストボディ全体をメモリに読み込むため、同時実行数を制限する
+    // ボディ上限はアップロード用の値で全体の上限を上書きする（内側のレイヤーが優先）
+    let mut upload_routes = Router::new().route("/upload", post(upload_file));
+    if let Some(limits) = &config.body_limits {
+        upload_routes = upload_routes.layer(DefaultBodyLimit::max(limits.max_upload_bytes));
+    }
+    if let Some(limits) = &config.concurrency {
+        upload_routes = with_concurrency_limit(upload_routes, limits.upload.clone());
+    }
+    let file_routes = upload_routes
         // GET /api/files/{id}/download - ファイルダウンロード
-        .route("/{id}/download", get(download_file::<TW, TR, C, UR, UW, S>))
+        .route("/{id}/download", get(download_file))
         // DELETE /api/files/{id} - ファイル削除
-        .route("/{id}", delete(delete_file::<TW, TR, C, UR, UW, S>));
+        .route("/{id}", delete(delete_file));
+
+    // -------------------------------------------------------------------------
+    // ジョブルート（Edge 検証が必要）
+    // -------------------------------------------------------------------------
+    // GET /api/jobs/{id} - インポートジョブの状態・進捗
+    // DELETE /api/jobs/{id} - インポートジョブのキャンセル
+    let job_routes = Router::new().route("/{id}", get(get_import_job).delete(cancel_import_job));
+
+    // -------------------------------------------------------------------------
+    // 管理者ルート（Edge 検証が必要、admin ロールはハンドラで確認）
+    // -------------------------------------------------------------------------
+    // POST /api/admin/reload-secrets - JWT 鍵セットのリロード
+    let admin_routes = Router::new().route("/reload-secrets", post(reload_secrets));
+
+    // -------------------------------------------------------------------------
+    // Edge トークン検証ミドルウェアを適用
+    // -------------------------------------------------------------------------
+    // edge_token が有効な場合のみ X-Edge-Token を要求する
+    // Edge 検証より先に route_layer で適用するため、Edge 検証の内側で実行される
+    let (todo_routes, file_routes, job_routes, admin_routes) = if config.edge_token {
+        tracing::info!("Edge token verification enabled for Edge-verified routes");
+        let keys = state.auth_service.keys().clone();
+        (
+            with_edge_token(todo_routes, keys.clone()),
+            with_edge_token(file_routes, keys.clone()),
+            with_edge_token(job_routes, keys.clone()),
+            with_edge_token(admin_routes, keys),
+        )
+    } else {
+        (todo_routes, file_routes, job_routes, admin_routes)
+    };
 
     // -------------------------------------------------------------------------
     // Edge 検証ミドルウェアを適用
     // -------------------------------------------------------------------------
-    // edge_secret が設定されている場合のみ Edge 検証を有効化
-    let (todo_routes, file_routes) = if let Some(secret) = edge_secret {
+    // edge_verify が設定されている場合のみ Edge 検証を有効化
+    // 同時実行制限より後に route_layer で適用するため、Edge 検証が外側で先に実行される
+    // → 検証に失敗したリクエストは同時実行の枠を消費しない
+    let (todo_routes, file_routes, job_routes, admin_routes) = if let Some(edge_verify) =
+        config.edge_verify
+    {
         // 本番モード: Edge 検証を有効化
-        tracing::info!("Edge verification enabled for /api/todos/* and /api/files/* routes");
+        tracing::info!(
+            max_age_secs = edge_verify.max_age_secs,
+            legacy_secret = edge_verify.accept_legacy_secret,
+            "Edge verification enabled for /api/todos/*, /api/files/*, /api/jobs/* and /api/admin/* routes"
+        );
         (
-            with_edge_verify(todo_routes, secret.clone()),
-            with_edge_verify(file_routes, secret),
+            with_edge_verify(todo_routes, edge_verify.clone()),
+            with_edge_verify(file_routes, edge_verify.clone()),
+            with_edge_verify(job_routes, edge_verify.clone()),
+            with_edge_verify(admin_routes, edge_verify),
         )
     } else {
         // 開発モード: Edge 検証をスキップ（警告を出力）
         tracing::warn!("Edge verification disabled - running in development mode");
-        (todo_routes, file_routes)
+        (todo_routes, file_routes, job_routes, admin_routes)
     };
 
     // -------------------------------------------------------------------------
-    // ルーターを組み立てて返す
+    // /api/* を組み立てる
     // -------------------------------------------------------------------------
-    Router::new()
-        // ヘルスチェック（認証不要、Edge 検証不要）
-        // Kubernetes の liveness/readiness probe などで使用
-        .route("/health", get(healthz))
+    let api_routes = Router::new()
         // 認証ルート（認証不要、Edge 検証不要）
         // /api/auth/* にネスト
         .nest("/api/auth", auth_routes)
@@ -181,6 +234,51 @@ pub fn create_router<
         // ファイルルート（Edge 検証あり）
         // /api/files/* にネスト
         .nest("/api/files", file_routes)
+        // ジョブルート（Edge 検証あり）
+        // /api/jobs/* にネスト
+        .nest("/api/jobs", job_routes)
+        // 管理者ルート（Edge 検証あり）
+        // /api/admin/* にネスト
+        .nest("/api/admin", admin_routes);
+
+    // レート制限は /api/* のみ（ヘルスチェックはプローブのため制限しない）
+    let api_routes = match config.rate_limit {
+        Some(rate_limit) => with_rate_limit(api_routes, rate_limit),
+        None => api_routes,
+    };
+
+    // -------------------------------------------------------------------------
+    // ルーターを組み立てる
+    // -------------------------------------------------------------------------
+    let mut router = Router::new()
+        // ヘルスチェック（認証不要、Edge 検証不要）
+        // Kubernetes の liveness/readiness probe などで使用
+        .route("/health", get(healthz))
+        // レディネスチェック（認証不要、登録された依存サービスの疎通を確認）
+        .route("/ready", get(readyz))
+        .merge(api_routes);
+
+    // -------------------------------------------------------------------------
+    // ルーター全体のミドルウェア（後に適用したものほど外側）
+    // -------------------------------------------------------------------------
+    // ボディ上限: 未設定の場合は axum の既定値（2MB）のまま
+    if let Some(limits) = config.body_limits {
+        router = router.layer(DefaultBodyLimit::max(limits.max_body_bytes));
+    }
+    // CORS: プリフライトはルートがない OPTIONS にも届く
+    if let Some(cors) = config.cors {
+        router = with_cors(router, cors);
+    }
+    // メトリクス: 他のミドルウェアが返した 4xx/5xx も数える
+    if config.metrics {
+        router = with_http_metrics(router, state.http_metrics.clone());
+    }
+    // トレース: 最も外側（全てのレスポンスに X-Request-Id を付与）
+    if config.request_tracing {
+        router = with_request_trace(router);
+    }
+
+    router
         // with_state: 状態をルーターに関連付け（axum 推奨パターン）
         //
         // Clone が必要な理由:
@@ -188,6 +286,32 @@ pub fn create_router<
         //    → Clone 未実装だとここでコンパイルエラー
         // 2. State エクストラクタが各リクエストで state.clone() を呼び出す
         //
-        // ハンドラ内で State<AppState<...>> として取得可能
+        // ハンドラ内で State<AppState> として取得可能
+        .with_state(state)
+}
+
+// =============================================================================
+// create_internal_router 関数
+// =============================================================================
+
+/// 内部用ルーターを作成する（監視エンドポイントのみ）
+///
+/// API とは別のポート（INTERNAL_ADDR）で公開し、外部には公開しない想定。
+/// Edge 検証・認証は行わない。
+///
+/// ```text
+/// /livez   - liveness（/health と同じ）
+/// /readyz  - readiness（/ready と同じ）
+/// /metrics - Prometheus 形式のメトリクス
+/// ```
+///
+/// # Arguments
+///
+/// * `state` - アプリケーション状態（疎通確認に使用、API のルーターと共有）
+pub fn create_internal_router(state: AppState) -> Router {
+    Router::new()
+        .route("/livez", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/metrics", get(metrics))
         .with_state(state)
 }
@@ -3,6 +3,7 @@
 // =============================================================================
 // ユースケースインスタンスを保持し、各ハンドラで共有するための構造体。
 // 依存性注入（DI）コンテナとして機能する。
+// 組み立てには AppState::builder()（AppStateBuilder）を使用する。
 //
 // 統一 CQRS パターン:
 // - TODO Commands: TodoWriter を使用 + キャッシュ無効化/更新
@@ -26,7 +27,7 @@ use std::sync::Arc;
 // application: Application 層のユースケース
 use application::{
     // Services
-    services::AuthService,
+    services::{AuthService, JwtAlgorithm, JwtKeyRing},
     // Commands（状態変更操作 - Writer DB プール使用）
     CreateTodoCommand,
     DeleteFileCommand,
@@ -46,7 +47,10 @@ use domain::{
 };
 
 // infrastructure: Infrastructure 層のサービス
-use infrastructure::TransactionalTodoService;
+use infrastructure::{ImportJobService, ReadinessCheck, TransactionalTodoService};
+
+// crate: HTTP メトリクスのカウンタ（ミドルウェアと /metrics で共有）
+use crate::middleware::HttpMetrics;
 
 // =============================================================================
 // AppState 構造体
@@ -57,26 +61,60 @@ use infrastructure::TransactionalTodoService;
 /// ユースケースインスタンスを保持し、axum の State エクストラクタ経由で
 /// 各ハンドラに渡される。依存性注入（DI）コンテナとして機能する。
 ///
-/// # なぜジェネリクスを使用するか
+/// # なぜトレイトオブジェクトを使用するか
+///
+/// 以前は依存ごとに型パラメータ（`AppState<TW, TR, C, UR, UW, S>`）を持っていたが、
+/// 全ハンドラ・ルーター・ユースケースに型パラメータが伝播し、
+/// シグネチャの可読性とコンパイル時間が悪化していた。
+///
+/// 依存を `Arc<dyn Trait>` で保持することで：
+///
+/// - **シンプルなシグネチャ**: ハンドラは `State<AppState>` を受け取るだけ
+/// - **差し替えの容易さ**: PostgreSQL 実装・インメモリ実装・モックを
+///   `Arc::new(...)` で渡すだけで組み込める
+/// - **コンパイル時間の短縮**: ハンドラが依存の組み合わせごとに単相化されない
+///
+/// トレードオフとして、リポジトリ呼び出しごとに vtable 経由の動的ディスパッチが
+/// 1 回発生するが、DB・Redis・S3 への I/O に比べて無視できるコスト。
 ///
-/// axum 公式ドキュメントでは「ジェネリクスをトレイトオブジェクトより優先」と
-/// 推奨されている。ジェネリクスを使用することで：
+/// # 依存（トレイトオブジェクト）
 ///
-/// - **コンパイル時型安全性**: 型エラーをコンパイル時に検出
-/// - **ゼロコスト抽象化**: 動的ディスパッチのオーバーヘッドなし
-/// - **テスト容易性**: モック実装への差し替えが容易
+/// - `TodoWriter` - TODO 書き込み実装（Commands 用）
+/// - `TodoReader` - TODO 読み取り実装（Queries 用）
+/// - `TodoCacheOps` - キャッシュ操作実装（Write-Through/無効化用）
+/// - `UserReader` - ユーザー読み取り実装（Queries 用）
+/// - `UserWriter` - ユーザー書き込み実装（Commands 用）
+/// - `StorageOps` - ストレージ操作実装（ファイルアップロード/ダウンロード/削除用）
 ///
-/// トレードオフとして、ハンドラの型シグネチャが複雑になるが、
-/// 型安全性とパフォーマンスのメリットが上回ると判断。
+/// # Clone（axum 推奨パターン）
 ///
-/// # ジェネリクス
+/// axum 公式ドキュメント (https://docs.rs/axum/latest/axum/extract/struct.State.html):
+/// > "Your top level state needs to derive Clone"
+///
+/// axum メンテナー mladedav (GitHub Discussion #3223):
+/// > "When you extract the state, axum will clone it and pass it to your handler.
+/// >  You can use Arc to make the clone cheap if your state is large or expensive to clone."
 ///
-/// - `TW: TodoWriter` - TODO 書き込み実装（Commands 用）
-/// - `TR: TodoReader` - TODO 読み取り実装（Queries 用）
-/// - `C: TodoCacheOps` - キャッシュ操作実装（Write-Through/無効化用）
-/// - `UR: UserReader` - ユーザー読み取り実装（Queries 用）
-/// - `UW: UserWriter` - ユーザー書き込み実装（Commands 用）
-/// - `S: StorageOps` - ストレージ操作実装（ファイルアップロード/ダウンロード/削除用）
+/// # Clone が必要な箇所（2箇所）
+///
+/// 1. **Router::with_state()**: `S: Clone` トレイト境界を持つ
+///    ```text
+///    impl<S> Router<S> {
+///        pub fn with_state<S2>(self, state: S) -> Router<S2>
+///        where
+///            S: Clone,  // ← ここで Clone が要求される
+///    ```
+///    → `with_state(state)` の呼び出し時点でコンパイルエラーになる
+///
+/// 2. **State エクストラクタ**: 内部で `FromRef::from_ref(&state)` を呼び出し、
+///    これが `state.clone()` を実行する
+///    → 各リクエスト処理時に clone() が呼ばれる
+///
+/// # パフォーマンス
+///
+/// 全フィールドは内部で Arc を使用しているため、derive(Clone) の clone() は
+/// 各フィールドの Arc 参照カウントを +1 するだけ。
+/// 実際のデータはコピーされず、O(1)（約5ナノ秒/フィールド）。
 ///
 /// # 使用例
 ///
@@ -87,24 +125,19 @@ use infrastructure::TransactionalTodoService;
 /// ```rust,ignore
 /// // ハンドラ内で State エクストラクタを使用（axum 推奨）
 /// async fn handler(
-///     State(state): State<AppState<TW, TR, C, UR, UW, S>>,
+///     State(state): State<AppState>,
 /// ) -> Result<Json<Todo>, ApiError> {
 ///     let todo = state.get_todo.execute(id, user_id).await?;
 ///     Ok(Json(todo))
 /// }
 /// ```
-pub struct AppState<
-    TW: TodoWriter,
-    TR: TodoReader,
-    C: TodoCacheOps,
-    UR: UserReader,
-    UW: UserWriter,
-    S: StorageOps,
-> {
+#[derive(Clone)]
+pub struct AppState {
     /// 認証サービス（UserReader + UserWriter を使用）
     ///
-    /// ログイン、登録、JWT 検証を担当
-    pub auth_service: AuthService<UR, UW>,
+    /// ログイン、登録、JWT 検証を担当。
+    /// JWT の鍵セット（`auth_service.keys()`）は管理用エンドポイントからリロードできる
+    pub auth_service: AuthService,
 
     // -------------------------------------------------------------------------
     // TODO Commands（状態変更操作 - Writer DB プール使用 + キャッシュ操作）
@@ -112,17 +145,17 @@ pub struct AppState<
     /// TODO 作成コマンド
     ///
     /// Write-Through: 作成後にキャッシュにも保存
-    pub create_todo: CreateTodoCommand<TW, C>,
+    pub create_todo: CreateTodoCommand,
 
     /// TODO 更新コマンド
     ///
     /// Cache Invalidation: 更新時にキャッシュを無効化
-    pub update_todo: UpdateTodoCommand<TW, C>,
+    pub update_todo: UpdateTodoCommand,
 
     /// TODO 削除コマンド
     ///
     /// Cache Invalidation: 削除時にキャッシュを無効化
-    pub delete_todo: DeleteTodoCommand<TW, C>,
+    pub delete_todo: DeleteTodoCommand,
 
     // -------------------------------------------------------------------------
     // TODO Queries（参照操作 - Reader DB プール使用）
@@ -130,35 +163,59 @@ pub struct AppState<
     /// TODO 取得クエリ
     ///
     /// Note: CachedTodoReader を使用する場合、キャッシュは Reader 側で処理
-    pub get_todo: GetTodoQuery<TR>,
+    pub get_todo: GetTodoQuery,
 
     /// TODO 一覧取得クエリ
     ///
     /// Note: 一覧はキャッシュしない（フィルタ条件が多様なため）
-    pub list_todos: ListTodosQuery<TR>,
+    pub list_todos: ListTodosQuery,
 
     /// バッチ操作サービス（トランザクション対応）
     ///
     /// 複数 TODO の一括作成や TODO + ファイル同時作成に使用
     pub batch_service: TransactionalTodoService,
 
+    /// キャッシュ操作（バッチ操作用）
+    ///
+    /// バッチ更新後の Cache Invalidation に使用
+    pub cache: Arc<dyn TodoCacheOps>,
+
+    /// バッチ操作で受け付ける最大件数
+    ///
+    /// バッチ作成・バッチ更新・TODO + ファイル作成のファイル数に適用
+    pub max_batch_size: usize,
+
+    /// インポートジョブサービス
+    ///
+    /// 非同期インポートのジョブ登録、参照、キャンセルに使用
+    pub import_jobs: ImportJobService,
+
+    /// 非同期インポートで受け付ける最大件数
+    pub max_import_items: usize,
+
+    /// テナント単位の絞り込み（TENANT_SCOPING）
+    ///
+    /// true の場合、TODO の作成時に X-Claim-Tenant-Id を記録し、
+    /// 一覧取得をそのテナントで絞り込む（UserContext::tenant_scope）。
+    pub tenant_scoping: bool,
+
     // -------------------------------------------------------------------------
     // ファイル操作（Clean Architecture: Application 層のユースケース経由）
     // -------------------------------------------------------------------------
     /// ファイルアップロードコマンド
     ///
     /// ファイルをストレージにアップロード
-    pub upload_file: UploadFileCommand<S>,
+    pub upload_file: UploadFileCommand,
 
     /// ファイルダウンロードクエリ
     ///
     /// ファイルをストレージからダウンロード（所有者確認付き）
-    pub download_file: DownloadFileQuery<TR, S>,
+    pub download_file: DownloadFileQuery,
 
     /// ファイル削除コマンド
     ///
     /// ファイルをストレージと DB から削除（所有者確認付き）
-    pub delete_file: DeleteFileCommand<TR, S>,
+    pub delete_file: DeleteFileCommand,
 
     /// ファイル読み取りリポジトリ（バッチ操作用）
     ///
@@ -173,22 +230,51 @@ pub struct AppState<
     /// ストレージサービス（バッチ操作用）
     ///
     /// TransactionalTodoService からストレージを参照する場合に使用
-    pub storage: Arc<S>,
+    pub storage: Arc<dyn StorageOps>,
+
+    /// 依存サービスの疎通確認（GET /ready）
+    ///
+    /// 構成に応じて登録する（キャッシュ無効時は Redis のチェックを登録しない）
+    pub readiness_checks: Vec<Arc<dyn ReadinessCheck>>,
+
+    /// HTTP リクエストのカウンタ（GET /metrics）
+    ///
+    /// RouterConfig::metrics が有効な場合にミドルウェアが記録する
+    pub http_metrics: HttpMetrics,
 }
 
 // =============================================================================
 // AppState 実装
 // =============================================================================
 
-impl<
-        TW: TodoWriter,
-        TR: TodoReader,
-        C: TodoCacheOps,
-        UR: UserReader,
-        UW: UserWriter,
-        S: StorageOps,
-    > AppState<TW, TR, C, UR, UW, S>
-{
+impl AppState {
+    /// AppState のビルダーを作成する
+    ///
+    /// 依存を名前付きのセッターで設定し、`build()` で AppState を組み立てる。
+    /// 依存の設定漏れは `build()` が `MissingComponent` で報告する。
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let state = AppState::builder()
+    ///     .todo_writer(todo_writer)
+    ///     .todo_reader(todo_reader)
+    ///     .cache(cache)
+    ///     .user_reader(user_reader)
+    ///     .user_writer(user_writer)
+    ///     .storage(storage)
+    ///     .file_reader(file_reader)
+    ///     .file_writer(file_writer)
+    ///     .batch_service(batch_service)
+    ///     .import_jobs(import_jobs)
+    ///     .jwt_secret(config.jwt.secret.clone())
+    ///     .max_batch_size(config.batch.max_items)
+    ///     .build()?;
+    /// ```
+    pub fn builder() -> AppStateBuilder {
+        AppStateBuilder::default()
+    }
+
     /// 新しい AppState を作成する
     ///
     /// # Arguments
@@ -204,41 +290,306 @@ impl<
     /// * `file_writer` - FileWriter の実装（Arc でラップ）
     /// * `jwt_secret` - JWT 署名用シークレット
     /// * `jwt_expiry_hours` - JWT 有効期間（時間）
+    /// * `jwt_issuer` - JWT 発行者（iss クレーム、None なら含めない）
+    /// * `max_batch_size` - バッチ操作で受け付ける最大件数
+    /// * `import_jobs` - インポートジョブサービス
+    /// * `max_import_items` - 非同期インポートで受け付ける最大件数
     ///
     /// # 属性
     ///
     /// `#[allow(clippy::too_many_arguments)]`: 引数が多いことを許容
-    /// DI コンテナとしての役割上、多くの依存を受け取る必要がある
+    /// 依存を追加するたびに全ての呼び出し元を変更する必要があるため、
+    /// `AppState::builder()` への移行後に削除する
+    #[deprecated(note = "use AppState::builder() instead")]
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        todo_writer: Arc<TW>,                    // Arc: スレッド安全な共有参照
-        todo_reader: Arc<TR>,                    // Arc: スレッド安全な共有参照
-        cache: Arc<C>,                           // Arc: Commands 間でキャッシュを共有
-        user_reader: Arc<UR>,                    // Arc: AuthService で使用
-        user_writer: Arc<UW>,                    // Arc: AuthService で使用
+        todo_writer: Arc<dyn TodoWriter>, // Arc: スレッド安全な共有参照
+        todo_reader: Arc<dyn TodoReader>, // Arc: スレッド安全な共有参照
+        cache: Arc<dyn TodoCacheOps>,     // Arc: Commands 間でキャッシュを共有
+        user_reader: Arc<dyn UserReader>, // Arc: AuthService で使用
+        user_writer: Arc<dyn UserWriter>, // Arc: AuthService で使用
         batch_service: TransactionalTodoService, // Clone 可能
-        storage: Arc<S>,                         // ストレージ操作
-        file_reader: Arc<dyn FileReader>,        // ファイル読み取り
-        file_writer: Arc<dyn FileWriter>,        // ファイル書き込み
-        jwt_secret: String,                      // JWT 署名用シークレット
-        jwt_expiry_hours: i64,                   // JWT 有効期間（時間）
+        storage: Arc<dyn StorageOps>,     // ストレージ操作
+        file_reader: Arc<dyn FileReader>, // ファイル読み取り
+        file_writer: Arc<dyn FileWriter>, // ファイル書き込み
+        jwt_secret: String,               // JWT 署名用シークレット
+        jwt_expiry_hours: i64,            // JWT 有効期間（時間）
+        jwt_issuer: Option<String>,       // JWT 発行者
+        max_batch_size: usize,            // バッチ操作の最大件数
+        import_jobs: ImportJobService,    // Clone 可能
+        max_import_items: usize,          // 非同期インポートの最大件数
     ) -> Self {
+        Self::builder()
+            .todo_writer(todo_writer)
+            .todo_reader(todo_reader)
+            .cache(cache)
+            .user_reader(user_reader)
+            .user_writer(user_writer)
+            .batch_service(batch_service)
+            .storage(storage)
+            .file_reader(file_reader)
+            .file_writer(file_writer)
+            .jwt_secret(jwt_secret)
+            .jwt_expiry_hours(jwt_expiry_hours)
+            .jwt_issuer(jwt_issuer)
+            .max_batch_size(max_batch_size)
+            .import_jobs(import_jobs)
+            .max_import_items(max_import_items)
+            .build()
+            .expect("all components are provided by the constructor arguments")
+    }
+}
+
+// =============================================================================
+// AppStateBuilder
+// =============================================================================
+
+/// AppState の組み立てに必要な依存が設定されていない
+///
+/// フィールドはセッター名（= AppStateBuilder のメソッド名）。
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("AppState is missing required component `{0}` (set it with AppStateBuilder::{0})")]
+pub struct MissingComponent(pub &'static str);
+
+/// AppState のビルダー
+///
+/// 必須の依存（未設定なら `build()` が `MissingComponent` を返す）:
+/// - todo_writer / todo_reader / cache / user_reader / user_writer / storage
+/// - file_reader / file_writer / batch_service / import_jobs
+/// - jwt_secret または jwt_keys（両方設定した場合は jwt_keys を使う）
+///
+/// 任意の設定（デフォルト値）:
+/// - jwt_expiry_hours: 24
+/// - jwt_issuer: None
+/// - jwt_algorithm: HS256
+/// - max_batch_size: 100
+/// - max_import_items: 10000
+/// - tenant_scoping: false
+/// - readiness_check: なし（GET /ready は常に 200）
+pub struct AppStateBuilder {
+    todo_writer: Option<Arc<dyn TodoWriter>>,
+    todo_reader: Option<Arc<dyn TodoReader>>,
+    cache: Option<Arc<dyn TodoCacheOps>>,
+    user_reader: Option<Arc<dyn UserReader>>,
+    user_writer: Option<Arc<dyn UserWriter>>,
+    storage: Option<Arc<dyn StorageOps>>,
+    file_reader: Option<Arc<dyn FileReader>>,
+    file_writer: Option<Arc<dyn FileWriter>>,
+    batch_service: Option<TransactionalTodoService>,
+    import_jobs: Option<ImportJobService>,
+    jwt_secret: Option<String>,
+    jwt_keys: Option<JwtKeyRing>,
+    jwt_expiry_hours: i64,
+    jwt_issuer: Option<String>,
+    jwt_algorithm: JwtAlgorithm,
+    max_batch_size: usize,
+    max_import_items: usize,
+    tenant_scoping: bool,
+    readiness_checks: Vec<Arc<dyn ReadinessCheck>>,
+}
+
+/// 全て未設定、任意の設定はデフォルト値
+///
+/// 任意の設定にデフォルト値を入れるため手動で実装する。
+impl Default for AppStateBuilder {
+    fn default() -> Self {
         Self {
+            todo_writer: None,
+            todo_reader: None,
+            cache: None,
+            user_reader: None,
+            user_writer: None,
+            storage: None,
+            file_reader: None,
+            file_writer: None,
+            batch_service: None,
+            import_jobs: None,
+            jwt_secret: None,
+            jwt_keys: None,
+            jwt_expiry_hours: 24,
+            jwt_issuer: None,
+            jwt_algorithm: JwtAlgorithm::default(),
+            max_batch_size: 100,
+            max_import_items: 10000,
+            tenant_scoping: false,
+            readiness_checks: Vec::new(),
+        }
+    }
+}
+
+impl AppStateBuilder {
+    /// TodoWriter の実装（Commands 用）
+    pub fn todo_writer(mut self, todo_writer: Arc<dyn TodoWriter>) -> Self {
+        self.todo_writer = Some(todo_writer);
+        self
+    }
+
+    /// TodoReader の実装（Queries 用）
+    pub fn todo_reader(mut self, todo_reader: Arc<dyn TodoReader>) -> Self {
+        self.todo_reader = Some(todo_reader);
+        self
+    }
+
+    /// TodoCacheOps の実装（Write-Through/無効化用）
+    pub fn cache(mut self, cache: Arc<dyn TodoCacheOps>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// UserReader の実装（AuthService で使用）
+    pub fn user_reader(mut self, user_reader: Arc<dyn UserReader>) -> Self {
+        self.user_reader = Some(user_reader);
+        self
+    }
+
+    /// UserWriter の実装（AuthService で使用）
+    pub fn user_writer(mut self, user_writer: Arc<dyn UserWriter>) -> Self {
+        self.user_writer = Some(user_writer);
+        self
+    }
+
+    /// StorageOps の実装（ファイルストレージ）
+    pub fn storage(mut self, storage: Arc<dyn StorageOps>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// FileReader の実装
+    pub fn file_reader(mut self, file_reader: Arc<dyn FileReader>) -> Self {
+        self.file_reader = Some(file_reader);
+        self
+    }
+
+    /// FileWriter の実装
+    pub fn file_writer(mut self, file_writer: Arc<dyn FileWriter>) -> Self {
+        self.file_writer = Some(file_writer);
+        self
+    }
+
+    /// トランザクション対応バッチサービス
+    pub fn batch_service(mut self, batch_service: TransactionalTodoService) -> Self {
+        self.batch_service = Some(batch_service);
+        self
+    }
+
+    /// インポートジョブサービス
+    pub fn import_jobs(mut self, import_jobs: ImportJobService) -> Self {
+        self.import_jobs = Some(import_jobs);
+        self
+    }
+
+    /// JWT 署名用シークレット
+    pub fn jwt_secret(mut self, jwt_secret: impl Into<String>) -> Self {
+        self.jwt_secret = Some(jwt_secret.into());
+        self
+    }
+
+    /// JWT の鍵セット（jwt_secret の代わりに設定する、ホットリロード用）
+    pub fn jwt_keys(mut self, jwt_keys: JwtKeyRing) -> Self {
+        self.jwt_keys = Some(jwt_keys);
+        self
+    }
+
+    /// JWT 有効期間（時間、デフォルト: 24）
+    pub fn jwt_expiry_hours(mut self, jwt_expiry_hours: i64) -> Self {
+        self.jwt_expiry_hours = jwt_expiry_hours;
+        self
+    }
+
+    /// JWT 発行者（iss クレーム、デフォルト: None）
+    pub fn jwt_issuer(mut self, jwt_issuer: Option<String>) -> Self {
+        self.jwt_issuer = jwt_issuer;
+        self
+    }
+
+    /// 発行する JWT の署名アルゴリズム（デフォルト: HS256）
+    pub fn jwt_algorithm(mut self, jwt_algorithm: JwtAlgorithm) -> Self {
+        self.jwt_algorithm = jwt_algorithm;
+        self
+    }
+
+    /// バッチ操作で受け付ける最大件数（デフォルト: 100）
+    pub fn max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size;
+        self
+    }
+
+    /// 非同期インポートで受け付ける最大件数（デフォルト: 10000）
+    pub fn max_import_items(mut self, max_import_items: usize) -> Self {
+        self.max_import_items = max_import_items;
+        self
+    }
+
+    /// テナント単位の絞り込みを有効にするか（デフォルト: false）
+    pub fn tenant_scoping(mut self, tenant_scoping: bool) -> Self {
+        self.tenant_scoping = tenant_scoping;
+        self
+    }
+
+    /// GET /ready で実行する疎通確認を追加する（複数回呼び出し可）
+    pub fn readiness_check(mut self, check: Arc<dyn ReadinessCheck>) -> Self {
+        self.readiness_checks.push(check);
+        self
+    }
+
+    /// AppState を組み立てる
+    ///
+    /// # Errors
+    ///
+    /// 必須の依存が設定されていない場合は、最初に見つかった依存の名前を持つ
+    /// `MissingComponent`
+    pub fn build(self) -> Result<AppState, MissingComponent> {
+        let todo_writer = self.todo_writer.ok_or(MissingComponent("todo_writer"))?;
+        let todo_reader = self.todo_reader.ok_or(MissingComponent("todo_reader"))?;
+        let cache = self.cache.ok_or(MissingComponent("cache"))?;
+        let user_reader = self.user_reader.ok_or(MissingComponent("user_reader"))?;
+        let user_writer = self.user_writer.ok_or(MissingComponent("user_writer"))?;
+        let storage = self.storage.ok_or(MissingComponent("storage"))?;
+        let file_reader = self.file_reader.ok_or(MissingComponent("file_reader"))?;
+        let file_writer = self.file_writer.ok_or(MissingComponent("file_writer"))?;
+        let batch_service = self
+            .batch_service
+            .ok_or(MissingComponent("batch_service"))?;
+        let import_jobs = self.import_jobs.ok_or(MissingComponent("import_jobs"))?;
+        // 鍵セットが設定されていればそれを使い、なければ jwt_secret 1 つの鍵セットにする
+        let (jwt_secret, jwt_keys) = match (self.jwt_secret, self.jwt_keys) {
+            (secret, Some(keys)) => (secret.unwrap_or_default(), Some(keys)),
+            (Some(secret), None) => (secret, None),
+            (None, None) => return Err(MissingComponent("jwt_secret")),
+        };
+        let mut auth_service =
+            AuthService::new(user_reader, user_writer, jwt_secret, self.jwt_expiry_hours)
+                .with_issuer(self.jwt_issuer)
+                .with_algorithm(self.jwt_algorithm);
+        if let Some(keys) = jwt_keys {
+            auth_service = auth_service.with_keys(keys);
+        }
+
+        Ok(AppState {
             // AuthService: UserReader + UserWriter + JWT 設定
-            auth_service: AuthService::new(user_reader, user_writer, jwt_secret, jwt_expiry_hours),
+            auth_service,
 
             // TODO Commands（キャッシュ操作を含む）
             // Arc::clone: 参照カウントを増やすだけ（安価な操作）
             create_todo: CreateTodoCommand::new(Arc::clone(&todo_writer), Some(Arc::clone(&cache))),
             update_todo: UpdateTodoCommand::new(Arc::clone(&todo_writer), Some(Arc::clone(&cache))),
-            delete_todo: DeleteTodoCommand::new(todo_writer, Some(cache)),
+            delete_todo: DeleteTodoCommand::new(todo_writer, Some(Arc::clone(&cache))),
 
             // TODO Queries
             get_todo: GetTodoQuery::new(Arc::clone(&todo_reader)),
             list_todos: ListTodosQuery::new(Arc::clone(&todo_reader)),
 
-            // バッチサービス
+            // バッチサービス + キャッシュ（バッチ操作の Cache Invalidation 用）
             batch_service,
+            cache,
+            max_batch_size: self.max_batch_size,
+
+            // 非同期インポート
+            import_jobs,
+            max_import_items: self.max_import_items,
+
+            // テナント単位の絞り込み
+            tenant_scoping: self.tenant_scoping,
 
             // ファイル操作（Clean Architecture: Application 層経由）
             upload_file: UploadFileCommand::new(Arc::clone(&storage)),
@@ -256,69 +607,108 @@ impl<
             file_reader,
             file_writer,
             storage,
-        }
+            readiness_checks: self.readiness_checks,
+            http_metrics: HttpMetrics::default(),
+        })
     }
 }
 
-// =============================================================================
-// Clone トレイト実装
-// =============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-/// AppState の Clone 実装
-///
-/// # なぜ Clone 実装が必要か（axum 推奨パターン）
-///
-/// axum 公式ドキュメント (https://docs.rs/axum/latest/axum/extract/struct.State.html):
-/// > "Your top level state needs to derive Clone"
-///
-/// axum メンテナー mladedav (GitHub Discussion #3223):
-/// > "When you extract the state, axum will clone it and pass it to your handler.
-/// >  You can use Arc to make the clone cheap if your state is large or expensive to clone."
-///
-/// # Clone が必要な箇所（2箇所）
-///
-/// 1. **Router::with_state()**: `S: Clone` トレイト境界を持つ
-///    ```text
-///    impl<S> Router<S> {
-///        pub fn with_state<S2>(self, state: S) -> Router<S2>
-///        where
-///            S: Clone,  // ← ここで Clone が要求される
-///    ```
-///    → `with_state(state)` の呼び出し時点でコンパイルエラーになる
-///
-/// 2. **State エクストラクタ**: 内部で `FromRef::from_ref(&state)` を呼び出し、
-///    これが `state.clone()` を実行する
-///    → 各リクエスト処理時に clone() が呼ばれる
-///
-/// # パフォーマンス
-///
-/// 全フィールドは内部で Arc を使用しているため、clone() は
-/// 各フィールドの Arc 参照カウントを +1 するだけ。
-/// 実際のデータはコピーされず、O(1)（約5ナノ秒/フィールド）。
-impl<
-        TW: TodoWriter,
-        TR: TodoReader,
-        C: TodoCacheOps,
-        UR: UserReader,
-        UW: UserWriter,
-        S: StorageOps,
-    > Clone for AppState<TW, TR, C, UR, UW, S>
-{
-    fn clone(&self) -> Self {
-        Self {
-            auth_service: self.auth_service.clone(),
-            create_todo: self.create_todo.clone(),
-            update_todo: self.update_todo.clone(),
-            delete_todo: self.delete_todo.clone(),
-            get_todo: self.get_todo.clone(),
-            list_todos: self.list_todos.clone(),
-            batch_service: self.batch_service.clone(),
-            upload_file: self.upload_file.clone(),
-            download_file: self.download_file.clone(),
-            delete_file: self.delete_file.clone(),
-            file_reader: Arc::clone(&self.file_reader),
-            file_writer: Arc::clone(&self.file_writer),
-            storage: Arc::clone(&self.storage),
+    use async_trait::async_trait;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use domain::{DomainError, Todo};
+    use infrastructure::{
+        detached_pool, InMemoryFileReader, InMemoryFileWriter, InMemoryStorage, InMemoryTodoCache,
+        InMemoryTodoReader, InMemoryTodoWriter, InMemoryUserReader, InMemoryUserWriter,
+        MemoryStore,
+    };
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use crate::{create_router, RouterConfig};
+
+    /// 常に失敗する TodoWriter（モック）
+    struct FailingTodoWriter;
+
+    #[async_trait]
+    impl TodoWriter for FailingTodoWriter {
+        async fn create(&self, _todo: &Todo) -> Result<Todo, DomainError> {
+            Err(DomainError::Repository("unavailable".to_string()))
         }
+
+        async fn update_fields(
+            &self,
+            _id: Uuid,
+            _user_id: Uuid,
+            _title: Option<String>,
+            _description: Option<Option<String>>,
+            _completed: Option<bool>,
+        ) -> Result<Todo, DomainError> {
+            Err(DomainError::Repository("unavailable".to_string()))
+        }
+
+        async fn delete(&self, _id: Uuid, _user_id: Uuid) -> Result<bool, DomainError> {
+            Err(DomainError::Repository("unavailable".to_string()))
+        }
+    }
+
+    /// 必須の依存が欠けていると build() がその名前を返す
+    #[test]
+    fn test_builder_reports_missing_component() {
+        let store = MemoryStore::new();
+
+        let result = AppState::builder()
+            .todo_writer(Arc::new(InMemoryTodoWriter::new(store.clone())))
+            .todo_reader(Arc::new(InMemoryTodoReader::new(store.clone())))
+            .cache(Arc::new(InMemoryTodoCache::new()))
+            .user_reader(Arc::new(InMemoryUserReader::new(store.clone())))
+            .user_writer(Arc::new(InMemoryUserWriter::new(store.clone())))
+            .storage(Arc::new(InMemoryStorage::new()))
+            .file_reader(Arc::new(InMemoryFileReader::new(store.clone())))
+            .file_writer(Arc::new(InMemoryFileWriter::new(store)))
+            .jwt_secret("secret")
+            .build();
+
+        let err = result.err().expect("batch_service is missing");
+        assert_eq!(err, MissingComponent("batch_service"));
+        assert!(err.to_string().contains("AppStateBuilder::batch_service"));
+    }
+    /// モック実装を型パラメータなしで組み込める（TodoWriter だけ差し替え）
+    #[tokio::test]
+    async fn test_builder_accepts_mock_implementation() {
+        let store = MemoryStore::new();
+        let pool = detached_pool();
+
+        let state = AppState::builder()
+            .todo_writer(Arc::new(FailingTodoWriter))
+            .todo_reader(Arc::new(InMemoryTodoReader::new(store.clone())))
+            .cache(Arc::new(InMemoryTodoCache::new()))
+            .user_reader(Arc::new(InMemoryUserReader::new(store.clone())))
+            .user_writer(Arc::new(InMemoryUserWriter::new(store.clone())))
+            .storage(Arc::new(InMemoryStorage::new()))
+            .file_reader(Arc::new(InMemoryFileReader::new(store.clone())))
+            .file_writer(Arc::new(InMemoryFileWriter::new(store)))
+            .batch_service(TransactionalTodoService::new(pool.clone()))
+            .import_jobs(ImportJobService::new(pool))
+            .jwt_secret("secret")
+            .build()
+            .expect("all components are provided");
+
+        let response = create_router(state, RouterConfig::minimal())
+            .oneshot(
+                Request::post("/api/todos")
+                    .header("content-type", "application/json")
+                    .header("x-user-id", Uuid::new_v4().to_string())
+                    .body(Body::from(r#"{"title":"mock"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
     }
 }
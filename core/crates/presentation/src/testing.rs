@@ -0,0 +1,112 @@
+// =============================================================================
+// presentation/src/testing.rs: テスト用の AppState
+// =============================================================================
+// PostgreSQL / Redis / S3 を起動せずにハンドラやルーターをテストするため、
+// infrastructure のインメモリ実装（features = ["memory"]）を組み込んだ
+// AppState::for_tests() を提供する。
+//
+// 有効化:
+// - presentation クレート内のテスト: 常に有効（cfg(test)）
+// - 他のクレートのテスト: dev-dependencies で features = ["testing"] を指定
+//
+// 制約:
+// - バッチ操作・インポートジョブは PostgreSQL のトランザクションを直接使用するため、
+//   接続先のない遅延プール（infrastructure::detached_pool）を渡している。
+//   これらのエンドポイントを呼ぶと接続エラー（500）になる。
+// =============================================================================
+
+use std::sync::Arc;
+
+use infrastructure::{
+    detached_pool, ImportJobService, InMemoryFileReader, InMemoryFileWriter, InMemoryStorage,
+    InMemoryTodoCache, InMemoryTodoReader, InMemoryTodoWriter, InMemoryUserReader,
+    InMemoryUserWriter, MemoryStore, TransactionalTodoService,
+};
+
+use crate::state::AppState;
+
+/// テスト用 AppState の JWT シークレット
+pub const TEST_JWT_SECRET: &str = "test-secret";
+
+impl AppState {
+    /// インメモリ実装を組み込んだ AppState を作成する
+    ///
+    /// 空の MemoryStore から作成する。
+    /// データを事前投入する場合は `for_tests_with_store` を使う。
+    pub fn for_tests() -> Self {
+        Self::for_tests_with_store(MemoryStore::new())
+    }
+
+    /// 指定した MemoryStore を使う AppState を作成する
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - TODO・ユーザー・ファイルのデータストア（テスト側と共有できる）
+    ///
+    /// ランタイムやデータベースは不要（バッチ・インポートのエンドポイントは 500 を返す）。
+    pub fn for_tests_with_store(store: MemoryStore) -> Self {
+        let pool = detached_pool();
+
+        AppState::builder()
+            .todo_writer(Arc::new(InMemoryTodoWriter::new(store.clone())))
+            .todo_reader(Arc::new(InMemoryTodoReader::new(store.clone())))
+            .cache(Arc::new(InMemoryTodoCache::new()))
+            .user_reader(Arc::new(InMemoryUserReader::new(store.clone())))
+            .user_writer(Arc::new(InMemoryUserWriter::new(store.clone())))
+            .storage(Arc::new(InMemoryStorage::new()))
+            .file_reader(Arc::new(InMemoryFileReader::new(store.clone())))
+            .file_writer(Arc::new(InMemoryFileWriter::new(store)))
+            .batch_service(TransactionalTodoService::new(pool.clone()))
+            .import_jobs(ImportJobService::new(pool))
+            .jwt_secret(TEST_JWT_SECRET)
+            .build()
+            .expect("all components are provided")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use crate::{create_router, RouterConfig};
+
+    /// for_tests() の AppState でルーターが動作する（作成 → 一覧取得）
+    #[tokio::test]
+    async fn test_for_tests_router_round_trip() {
+        let app = create_router(AppState::for_tests(), RouterConfig::minimal());
+        let user_id = Uuid::new_v4();
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::post("/api/todos")
+                    .header("content-type", "application/json")
+                    .header("x-user-id", user_id.to_string())
+                    .body(Body::from(r#"{"title":"in-memory"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response = app
+            .oneshot(
+                Request::get("/api/todos")
+                    .header("x-user-id", user_id.to_string())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let todos: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(todos.as_array().unwrap().len(), 1);
+        assert_eq!(todos[0]["title"], "in-memory");
+    }
+}
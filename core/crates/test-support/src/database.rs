@@ -0,0 +1,120 @@
+// =============================================================================
+// test-support/src/database.rs: テストごとの PostgreSQL スキーマ
+// =============================================================================
+// DATABASE_URL_TEST のデータベースに、テストごとに新しいスキーマを作成する。
+//
+// 分離の方法:
+// - スキーマ名: test_{uuid}（テストごとに一意）
+// - 接続プールの search_path をそのスキーマに固定する
+// - api/migrations の *.up.sql をそのスキーマに適用する
+//
+// トランザクションのロールバックではなくスキーマを使う理由:
+// - リポジトリは PgPool から都度接続を取得するため、
+//   1 つのトランザクションにリクエストをまとめられない
+// - バッチ操作（TransactionalTodoService）は内部でトランザクションを開始する
+//
+// 後片付け:
+// - TestDatabase::drop_schema() で DROP SCHEMA ... CASCADE
+// - テストが panic した場合はスキーマが残る（test_ で始まるため手動で削除できる）
+// =============================================================================
+
+use std::path::Path;
+use std::str::FromStr;
+
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// マイグレーションのディレクトリ（api/migrations）
+const MIGRATIONS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../api/migrations");
+
+/// テスト専用スキーマを持つ PostgreSQL 接続
+pub struct TestDatabase {
+    /// search_path をテスト用スキーマに固定した接続プール
+    pool: PgPool,
+    /// スキーマの作成・削除に使う接続プール（search_path は既定のまま）
+    admin: PgPool,
+    /// テスト用スキーマ名（test_{uuid}）
+    schema: String,
+}
+
+impl TestDatabase {
+    /// 新しいスキーマを作成し、マイグレーションを適用する
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - 接続先（DATABASE_URL_TEST）
+    ///
+    /// # Panics
+    ///
+    /// 接続・スキーマ作成・マイグレーションに失敗した場合（テスト用のため）
+    pub async fn create(url: &str) -> Self {
+        let schema = format!("test_{}", Uuid::new_v4().simple());
+
+        let admin = PgPoolOptions::new()
+            .max_connections(1)
+            .connect(url)
+            .await
+            .expect("failed to connect to DATABASE_URL_TEST");
+        sqlx::query(&format!("CREATE SCHEMA \"{schema}\""))
+            .execute(&admin)
+            .await
+            .expect("failed to create test schema");
+
+        // 全接続の search_path をテスト用スキーマに固定する
+        let options = PgConnectOptions::from_str(url)
+            .expect("invalid DATABASE_URL_TEST")
+            .options([("search_path", schema.as_str())]);
+        let pool = PgPoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await
+            .expect("failed to connect to test schema");
+
+        let database = Self {
+            pool,
+            admin,
+            schema,
+        };
+        database.migrate().await;
+        database
+    }
+
+    /// api/migrations の *.up.sql をファイル名順に適用する
+    async fn migrate(&self) {
+        let mut files: Vec<_> = std::fs::read_dir(Path::new(MIGRATIONS_DIR))
+            .expect("failed to read api/migrations")
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.to_string_lossy().ends_with(".up.sql"))
+            .collect();
+        files.sort();
+
+        for file in files {
+            let sql = std::fs::read_to_string(&file).expect("failed to read migration");
+            sqlx::raw_sql(&sql)
+                .execute(&self.pool)
+                .await
+                .unwrap_or_else(|e| panic!("migration {} failed: {}", file.display(), e));
+        }
+    }
+
+    /// テスト用スキーマの接続プール
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// テスト用スキーマ名
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    /// テスト用スキーマを削除する
+    pub async fn drop_schema(self) {
+        self.pool.close().await;
+        sqlx::query(&format!("DROP SCHEMA \"{}\" CASCADE", self.schema))
+            .execute(&self.admin)
+            .await
+            .expect("failed to drop test schema");
+        self.admin.close().await;
+    }
+}
@@ -0,0 +1,27 @@
+// =============================================================================
+// test-support/src/lib.rs: 統合テスト用のフィクスチャ
+// =============================================================================
+// 統合テストで共通の準備（接続プール、マイグレーション、AppState の組み立て、
+// ユーザー登録とトークン取得）を 1 か所にまとめる。
+//
+// 公開する主な要素:
+// - TestApp: ルーター + リクエスト・認証ヘルパー
+// - TestUser: 登録済みユーザー（ID、メールアドレス、JWT）
+// - TestResponse: ステータスと JSON ボディ
+// - TestDatabase: テストごとの PostgreSQL スキーマ
+//
+// バックエンド:
+// - DATABASE_URL_TEST 設定時: PostgreSQL（テストごとに新しいスキーマ、終了時に削除）
+// - 未設定時: インメモリ実装（テストごとに新しいストア）
+// どちらの場合もテスト間でデータを共有しない。
+//
+// 使い方（dev-dependencies に追加）:
+//   test-support = { path = "../crates/test-support" }
+// =============================================================================
+
+pub mod app;
+pub mod database;
+
+pub use app::{TestApp, TestResponse, TestUser, TEST_EDGE_SECRET, TEST_PASSWORD};
+pub use database::TestDatabase;
+pub use presentation::testing::TEST_JWT_SECRET;
@@ -8,17 +8,96 @@
 //! - JWT トークンの構造検証（ヘッダー、ペイロード、署名）
 //! - HMAC-SHA256 署名の検証
 //! - ユーザーIDの抽出
+//! - 有効期限・ロール・許可リストに含まれるクレームの抽出
+//! - 短期間有効なサービス用トークンの発行（`demo:auth/token-issuer`）
+//! - 開発用のユーザーのトークンの発行（`issue-token`、Spin 変数 `jwt_dev_tokens=on` の場合のみ）
+//! - 検証の成功・失敗（理由の分類ごと）のカウント（`demo:auth/metrics`）
 //!
 //! ## 使用方法
-//! gateway コンポーネントから WIT 経由で `verify_token` 関数が呼び出されます。
+//! gateway コンポーネントから WIT 経由で `verify_token` / `issue` / `get_metrics` 関数が呼び出されます。
+//! 署名鍵はこのコンポーネントだけが持ち、gateway には共有しません。
+//!
+//! ## JWT の実装
+//! 署名・検証はコア層の AuthService と共有する `jwt` クレート（core/crates/jwt）で行います。
+//! アルゴリズムの許可リスト・クレームの扱いはコア層と同じです。
+//! 有効期限（exp、必須）・nbf・iat は WASI の壁時計の現在時刻と比較し、許容する時計のずれ（leeway）は
+//! Spin 変数 `jwt_leeway_secs`（既定 30 秒、上限 300 秒）で変更できます。
+//! Spin 変数 `jwt_issuer` を指定した場合は、発行者（iss）がその値のトークンのみ受け付けます
+//! （コア層の `JWT_ISSUER` と同じ値にする。未指定の場合は検証しない）。
+//! Spin 変数 `jwt_audiences`（カンマ区切り）を指定した場合は、対象者（aud、文字列または配列）の
+//! いずれかがその一覧に含まれるトークンのみ受け付けます（未指定の場合は検証しない）。
+//! HS256 のシークレットは Spin 変数 `jwt_secret`（コア層の `JWT_SECRET` と同じ値）から読み込み、
+//! Wasm バイナリには埋め込みません（未設定の場合、HS256 のトークンは "Auth misconfigured" で拒否）。
+//! HS256 の鍵は Spin 変数 `jwt_hs256_keys`（kid → シークレットの JSON、先頭が最新の鍵）で複数指定でき、
+//! ヘッダーの kid で鍵を選びます（kid のないトークンは新しい鍵から順に試す）。
+//! シークレットのローテーション中は新旧の鍵の両方で検証し、発行には最新の鍵を使います。
+//! 受け付けるアルゴリズムは Spin 変数 `jwt_algorithms` の許可リストで決まり、既定は HS256 のみです。
+//! 外部の ID プロバイダーが RS256 で署名したトークンは Spin 変数 `jwt_rs256_public_key`（PEM）、
+//! パートナーが ES256 で署名したトークンは `jwt_es256_public_key`（PEM または JWK）を指定し、
+//! `jwt_algorithms` にも RS256 / ES256 を含めた場合のみ受け付けます。ヘッダーの alg で鍵を選び、
+//! 公開鍵を HS256 のシークレットとして使うことはありません。
+//! HS384 / HS512 のトークンは HS256 と同じ鍵で検証し、`jwt_algorithms` に含めた場合のみ受け付けます
+//! （コア層の `JWT_ALGORITHM` を HS512 にする前に追加する）。
+//! Spin 変数 `jwt_tenants`（発行者 → alg・kid → シークレット・対象者の JSON）を指定した場合は、署名を検証する前の
+//! iss で発行者（テナント）を選び、その発行者の鍵・対象者・アルゴリズムだけで検証します
+//! （未知の発行者は署名を確認せずに "Unknown issuer" で拒否し、他の発行者の鍵では検証しない）。
+//! Spin 変数 `jwt_jwks_url` を指定した場合は、ID プロバイダーの JWKS を取得して key-value ストアに
+//! キャッシュし、kid のある RS256 / ES256 のトークンはその kid の鍵で検証します（jwks モジュール）。
+//! 検証に成功したトークンは key-value ストアの失効リスト（jti・ユーザーごとの失効時刻）と照合し、
+//! ログアウト・パスワードの変更で失効させたトークンを "Token revoked" で拒否します（revocation モジュール）。
+//! 数値の `ver` クレーム（ない場合は 0）がユーザーのバージョン（key-value ストア、コア層の `X-Token-Version` を
+//! gateway が書き込む）より小さいトークンは "Token superseded" で拒否します。
+//! gateway は sub をそのまま X-User-Id としてコア層に転送するため、sub が UUID でないトークンは
+//! "Invalid subject claim" で拒否します（Spin 変数 `jwt_subject_pattern` で形式を変更できる）。
+//! 同じ鍵で署名したリフレッシュトークンを API に使わせないよう、`token_use` クレームが `access` でない
+//! トークンは "Wrong token type" で拒否します（`token_use` のないトークンは Spin 変数
+//! `jwt_allow_untyped_tokens=on` の場合のみ受け付ける）。
+//! ヘッダーの `typ` は Spin 変数 `jwt_typ_policy` で検証します（既定の `validate-if-present` は `typ` がある場合に
+//! `JWT`（大文字小文字を区別しない）のみ受け付け、`require` は `typ` のないトークンも拒否、`ignore` は検証しない）。
+//! 失効リストを読めない場合は拒否し、Spin 変数 `jwt_revocation_fail_open=on` の場合のみ検証を通します。
+//! `jti` と `one_time: true` のクレームを持つトークン（パスワードのリセットなど）は、最初の提示で jti を
+//! key-value ストアに使用済みとして記録し、2 回目以降を "Token already used" で拒否します（one_time モジュール）。
+//!
+//! ## Spin 変数
+//! 以下の変数は spin.toml の `[component.gateway.variables]` で設定し、
+//! dependencies_inherit_configuration により gateway の設定を引き継ぎます。
+//!
+//! | 変数 | 既定 | 内容 |
+//! |------|------|------|
+//! | `jwt_secret` | なし | HS256 の共有シークレット（コア層の `JWT_SECRET` と同じ値）。`jwt_hs256_keys` も空の場合、HS256 のトークンは "Auth misconfigured" で拒否（デモ用の鍵は使わない） |
+//! | `jwt_hs256_keys` | なし | HS256 の鍵リング（kid → シークレットの JSON、例: `{"2025-02":"new-secret","default":"super-secret-key"}`、先頭が最新で発行に使う）。空の場合は `jwt_secret`（kid は `default`）のみ |
+//! | `jwt_leeway_secs` | 30 | exp / nbf / iat で許容する時計のずれ（秒、上限 300、超える値は既定値） |
+//! | `jwt_max_token_bytes` | 8192 | トークン全体の長さの上限（バイト、`jwt::MAX_HEADER_BYTES`〜64 KiB）。ヘッダー・署名の上限は jwt クレートの定数 |
+//! | `jwt_issuer` | なし | 期待する発行者（iss）。空の場合は検証しない |
+//! | `jwt_audiences` | なし | 受け付ける対象者（aud、カンマ区切り）。空の場合は検証しない |
+//! | `jwt_subject_pattern` | UUID | sub に許可する形式（正規表現、sub 全体が一致するもの、例: `idp-user-[0-9]+`）。不正な正規表現の場合はログを出力して UUID のみ |
+//! | `jwt_tenants` | なし | 発行者 → 検証の設定の JSON（例: `{"https://a.example.com":{"alg":"HS256","keys":{"a-1":"secret-a"},"audiences":["todo-api"]}}`）。指定した場合は `jwt_secret`・`jwt_issuer`・`jwt_audiences`・`jwt_algorithms`・公開鍵・JWKS を検証に使わない（`jwt_secret` / `jwt_hs256_keys` は発行にのみ使う）。不正な値の場合はログを出力して全てのトークンを拒否 |
+//! | `jwt_rs256_public_key` | なし | RS256 の公開鍵（PEM）。`jwt_algorithms` に RS256 も含めること。空の場合は RS256 のトークンを受け付けない |
+//! | `jwt_es256_public_key` | なし | ES256 の公開鍵（PEM または JWK）。`jwt_algorithms` に ES256 も含めること。空の場合は ES256 のトークンを受け付けない |
+//! | `jwt_algorithms` | HS256 | 受け付けるアルゴリズム（カンマ区切り）。HS384 / HS512 / RS256 / ES256 は明示した場合のみ（例: `HS256,HS512`、`HS256,RS256`）。RS256 / ES256 は公開鍵・JWKS も必要 |
+//! | `jwt_jwks_url` | なし | ID プロバイダーの JWKS の URL。JWKS の鍵の alg（RS256 / ES256）を `jwt_algorithms` にも含めること。取得先のホストは gateway の allowed_outbound_hosts にも追加する |
+//! | `jwt_revocation_fail_open` | off | 失効リストを読めない場合に検証を通すか。off は "Revocation check unavailable" で拒否、on は障害中も認証を続けるが失効させたトークンも受け付ける |
+//! | `jwt_dev_tokens` | off | 開発用のトークンの発行（authenticator の issue-token）を許可するか。off は "issue-token is disabled" で拒否。Edge 層だけの結合テスト・デモ用で、本番では on にしない |
+//! | `jwt_lenient_base64` | off | パディング（末尾の "="）のある Base64URL のトークンを受け付けるか。on の場合は受け付けるたびに警告を出力（`Validation::with_lenient_base64`） |
+//! | `jwt_allow_untyped_tokens` | off | token_use クレームのないトークンを受け付けるか。off は "Wrong token type" で拒否。token_use を付けずに発行したトークン（以前のコア層・ID プロバイダー）を受け付ける間だけ on にする |
+//! | `jwt_typ_policy` | validate-if-present | ヘッダーの typ の検証（ignore / validate-if-present / require、`TypPolicy`）。validate-if-present は typ がある場合は "JWT" のみ受け付ける |
+//!
+//! ## カウンタの保存先
+//! Spin の key-value ストア（`default`）に保存し、アトミックにインクリメントします。
+//! Wasm インスタンスはリクエストごとに作られるため、メモリ上のカウンタは使えません。
 #[macro_use]
 extern crate std;
 #[prelude_import]
 use std::prelude::rust_2021::*;
-use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
-use hmac::{Hmac, Mac};
-use serde::Deserialize;
-use sha2::Sha256;
+use jwt::{
+    Claims, DecodingKey, EcPublicKey, Header, JwkSet, Map, RsaPublicKey, Validation, Value,
+};
+use std::fmt;
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use regex_lite::Regex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::OnceLock;
+use spin_sdk::wit::wasi::keyvalue::{atomics, store};
 #[allow(dead_code, clippy::all)]
 pub mod exports {
     pub mod demo {
@@ -38,6 +117,155 @@ pub mod exports {
                 static __FORCE_SECTION_REF: fn() = super::super::super::super::__link_custom_section_describing_imports;
                 use super::super::super::super::_rt;
                 /// -------------------------------------------------------------------------
+                /// 列挙型の定義
+                /// -------------------------------------------------------------------------
+                ///
+                /// enum はフィールドを持たない Rust の enum に相当します。
+                /// ケースはケバブケースで記述し、生成されるコードではパスカルケース（PascalCase）に変換されます。
+                /// 認証失敗の理由を表す列挙型
+                ///
+                /// error のメッセージは人が読むためのもので文言が変わりうるため、
+                /// gateway はこのコードでレスポンス（ステータス・code）を決めます。
+                /// 失敗の理由を追加する場合はケースを末尾に追加し、既存のケースは変えません。
+                #[repr(u8)]
+                pub enum AuthErrorCode {
+                    /// トークンがない
+                    MissingToken,
+                    /// JWT の形式・Base64URL・JSON が不正
+                    Malformed,
+                    /// 許可されていない署名アルゴリズム
+                    UnsupportedAlgorithm,
+                    /// 署名が一致しない、または kid の鍵がない
+                    InvalidSignature,
+                    /// 有効期限（exp）を過ぎた
+                    Expired,
+                    /// nbf より前、または iat が未来
+                    NotYetValid,
+                    /// sub クレームがない
+                    MissingSubject,
+                    /// exp がない、または発行者（iss）・対象者（aud）が不正
+                    InvalidClaims,
+                    /// 検証に使う鍵・失効の確認に使うストアを用意できない（設定の誤り・JWKS を取得できない）
+                    Unavailable,
+                    /// 失効したトークン（ログアウト・パスワードの変更などで失効させた）
+                    Revoked,
+                    /// 暗号化したトークン（JWE）。署名を検証できないため受け付けない
+                    Encrypted,
+                }
+                #[automatically_derived]
+                impl ::core::clone::Clone for AuthErrorCode {
+                    #[inline]
+                    fn clone(&self) -> AuthErrorCode {
+                        *self
+                    }
+                }
+                #[automatically_derived]
+                impl ::core::marker::Copy for AuthErrorCode {}
+                #[automatically_derived]
+                impl ::core::cmp::Eq for AuthErrorCode {
+                    #[inline]
+                    #[doc(hidden)]
+                    #[coverage(off)]
+                    fn assert_receiver_is_total_eq(&self) -> () {}
+                }
+                #[automatically_derived]
+                impl ::core::cmp::Ord for AuthErrorCode {
+                    #[inline]
+                    fn cmp(&self, other: &AuthErrorCode) -> ::core::cmp::Ordering {
+                        let __self_discr = ::core::intrinsics::discriminant_value(self);
+                        let __arg1_discr = ::core::intrinsics::discriminant_value(other);
+                        ::core::cmp::Ord::cmp(&__self_discr, &__arg1_discr)
+                    }
+                }
+                #[automatically_derived]
+                impl ::core::marker::StructuralPartialEq for AuthErrorCode {}
+                #[automatically_derived]
+                impl ::core::cmp::PartialEq for AuthErrorCode {
+                    #[inline]
+                    fn eq(&self, other: &AuthErrorCode) -> bool {
+                        let __self_discr = ::core::intrinsics::discriminant_value(self);
+                        let __arg1_discr = ::core::intrinsics::discriminant_value(other);
+                        __self_discr == __arg1_discr
+                    }
+                }
+                #[automatically_derived]
+                impl ::core::cmp::PartialOrd for AuthErrorCode {
+                    #[inline]
+                    fn partial_cmp(
+                        &self,
+                        other: &AuthErrorCode,
+                    ) -> ::core::option::Option<::core::cmp::Ordering> {
+                        let __self_discr = ::core::intrinsics::discriminant_value(self);
+                        let __arg1_discr = ::core::intrinsics::discriminant_value(other);
+                        ::core::cmp::PartialOrd::partial_cmp(&__self_discr, &__arg1_discr)
+                    }
+                }
+                impl ::core::fmt::Debug for AuthErrorCode {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        match self {
+                            AuthErrorCode::MissingToken => {
+                                f.debug_tuple("AuthErrorCode::MissingToken").finish()
+                            }
+                            AuthErrorCode::Malformed => {
+                                f.debug_tuple("AuthErrorCode::Malformed").finish()
+                            }
+                            AuthErrorCode::UnsupportedAlgorithm => {
+                                f.debug_tuple("AuthErrorCode::UnsupportedAlgorithm").finish()
+                            }
+                            AuthErrorCode::InvalidSignature => {
+                                f.debug_tuple("AuthErrorCode::InvalidSignature").finish()
+                            }
+                            AuthErrorCode::Expired => {
+                                f.debug_tuple("AuthErrorCode::Expired").finish()
+                            }
+                            AuthErrorCode::NotYetValid => {
+                                f.debug_tuple("AuthErrorCode::NotYetValid").finish()
+                            }
+                            AuthErrorCode::MissingSubject => {
+                                f.debug_tuple("AuthErrorCode::MissingSubject").finish()
+                            }
+                            AuthErrorCode::InvalidClaims => {
+                                f.debug_tuple("AuthErrorCode::InvalidClaims").finish()
+                            }
+                            AuthErrorCode::Unavailable => {
+                                f.debug_tuple("AuthErrorCode::Unavailable").finish()
+                            }
+                            AuthErrorCode::Revoked => {
+                                f.debug_tuple("AuthErrorCode::Revoked").finish()
+                            }
+                            AuthErrorCode::Encrypted => {
+                                f.debug_tuple("AuthErrorCode::Encrypted").finish()
+                            }
+                        }
+                    }
+                }
+                impl AuthErrorCode {
+                    #[doc(hidden)]
+                    pub unsafe fn _lift(val: u8) -> AuthErrorCode {
+                        if !true {
+                            return unsafe { ::core::mem::transmute(val) };
+                        }
+                        match val {
+                            0 => AuthErrorCode::MissingToken,
+                            1 => AuthErrorCode::Malformed,
+                            2 => AuthErrorCode::UnsupportedAlgorithm,
+                            3 => AuthErrorCode::InvalidSignature,
+                            4 => AuthErrorCode::Expired,
+                            5 => AuthErrorCode::NotYetValid,
+                            6 => AuthErrorCode::MissingSubject,
+                            7 => AuthErrorCode::InvalidClaims,
+                            8 => AuthErrorCode::Unavailable,
+                            9 => AuthErrorCode::Revoked,
+                            10 => AuthErrorCode::Encrypted,
+                            _ => {
+                                ::core::panicking::panic_fmt(
+                                    format_args!("invalid enum discriminant"),
+                                );
+                            }
+                        }
+                    }
+                }
+                /// -------------------------------------------------------------------------
                 /// レコード型の定義
                 /// -------------------------------------------------------------------------
                 ///
@@ -47,6 +275,10 @@ pub mod exports {
                 /// JWT 検証の結果を表すレコード型
                 ///
                 /// 認証の成功/失敗を示し、成功時はユーザーID、失敗時はエラーメッセージを含みます。
+                /// 成功時は有効期限・発行時刻・ロール・許可リストに含まれるクレームも返します。
+                ///
+                /// 互換性: 既存のフィールド（authenticated / user-id / error）の値と意味は
+                /// 0.1 から変えず、新しいフィールドは末尾に追加しています。
                 pub struct AuthResult {
                     /// 認証が成功したかどうか
                     /// - true: JWT が有効で認証成功
@@ -60,6 +292,33 @@ pub mod exports {
                     /// 例: "Missing token", "Invalid signature", "Token expired"
                     /// 認証成功時は None (null)
                     pub error: Option<_rt::String>,
+                    /// トークンの有効期限（Unix タイムスタンプ、秒）
+                    /// JWT の exp クレームの値（exp がないトークンは None）
+                    /// gateway が検証結果をキャッシュする期間の上限に使用する
+                    /// 認証失敗時は None (null)
+                    pub expires_at: Option<u64>,
+                    /// ユーザーのロール
+                    /// JWT の roles クレーム（文字列の配列）の値
+                    /// roles がないトークン・認証失敗時は空リスト
+                    pub roles: _rt::Vec<_rt::String>,
+                    /// 許可リストに含まれるクレーム（名前, 値）
+                    /// 許可リストは auth コンポーネントの CLAIM_ALLOWLIST で設定する
+                    /// 値は文字列に変換する（文字列・数値・真偽値のみ、それ以外は含めない）
+                    /// 例: [("tenant_id", "6f1c...")]
+                    /// 認証失敗時は空リスト
+                    pub claims: _rt::Vec<(_rt::String, _rt::String)>,
+                    /// トークンの発行時刻（Unix タイムスタンプ、秒）
+                    /// JWT の iat クレームの値（iat がないトークンは None）
+                    /// 認証失敗時は None (null)
+                    pub issued_at: Option<u64>,
+                    /// 認証失敗の理由のコード
+                    /// error のメッセージに対応する（例: "Token expired" → expired）
+                    /// 認証成功時は None (null)
+                    pub error_code: Option<AuthErrorCode>,
+                    /// 使い捨てのトークンか（JWT の one_time クレームが真偽値の true）
+                    /// 2 回目以降の提示を auth が拒否できるよう、gateway は検証結果をキャッシュしない
+                    /// 認証失敗時は false
+                    pub one_time: bool,
                 }
                 #[automatically_derived]
                 impl ::core::clone::Clone for AuthResult {
@@ -71,6 +330,12 @@ pub mod exports {
                             ),
                             user_id: ::core::clone::Clone::clone(&self.user_id),
                             error: ::core::clone::Clone::clone(&self.error),
+                            expires_at: ::core::clone::Clone::clone(&self.expires_at),
+                            roles: ::core::clone::Clone::clone(&self.roles),
+                            claims: ::core::clone::Clone::clone(&self.claims),
+                            issued_at: ::core::clone::Clone::clone(&self.issued_at),
+                                error_code: ::core::clone::Clone::clone(&self.error_code),
+                                one_time: ::core::clone::Clone::clone(&self.one_time),
                         }
                     }
                 }
@@ -83,6 +348,12 @@ pub mod exports {
                             .field("authenticated", &self.authenticated)
                             .field("user-id", &self.user_id)
                             .field("error", &self.error)
+                            .field("expires-at", &self.expires_at)
+                            .field("roles", &self.roles)
+                            .field("claims", &self.claims)
+                            .field("issued-at", &self.issued_at)
+                            .field("error-code", &self.error_code)
+                            .field("one-time", &self.one_time)
                             .finish()
                     }
                 }
@@ -108,6 +379,12 @@ pub mod exports {
                             authenticated: authenticated3,
                             user_id: user_id3,
                             error: error3,
+                            expires_at: expires_at3,
+                            roles: roles3,
+                            claims: claims3,
+                            issued_at: issued_at3,
+                            error_code: error_code3,
+                            one_time: one_time3,
                         } = result1;
                         *ptr2.add(0).cast::<u8>() = (match authenticated3 {
                             true => 1,
@@ -157,6 +434,134 @@ pub mod exports {
                                     .cast::<u8>() = (0i32) as u8;
                             }
                         };
+                        match expires_at3 {
+                            Some(e) => {
+                                *ptr2
+                                    .add(8 + 6 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<u8>() = (1i32) as u8;
+                                *ptr2
+                                    .add(16 + 6 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<i64>() = _rt::as_i64(e);
+                            }
+                            None => {
+                                *ptr2
+                                    .add(8 + 6 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<u8>() = (0i32) as u8;
+                            }
+                        };
+                        let vec7 = roles3;
+                        let len7 = vec7.len();
+                        let layout7 = _rt::alloc::Layout::from_size_align(
+                                vec7.len() * (2 * ::core::mem::size_of::<*const u8>()),
+                                ::core::mem::size_of::<*const u8>(),
+                            )
+                            .unwrap();
+                        let (result7, _cleanup7) = wit_bindgen::rt::Cleanup::new(
+                            layout7,
+                        );
+                        if let Some(cleanup) = _cleanup7 {
+                            cleanup.forget();
+                        }
+                        for (i, e) in vec7.into_iter().enumerate() {
+                            let base = result7
+                                .add(i * (2 * ::core::mem::size_of::<*const u8>()));
+                            {
+                                let vec6 = (e.into_bytes()).into_boxed_slice();
+                                let ptr6 = vec6.as_ptr().cast::<u8>();
+                                let len6 = vec6.len();
+                                ::core::mem::forget(vec6);
+                                *base
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>() = len6;
+                                *base.add(0).cast::<*mut u8>() = ptr6.cast_mut();
+                            }
+                        }
+                        *ptr2
+                            .add(24 + 7 * ::core::mem::size_of::<*const u8>())
+                            .cast::<usize>() = len7;
+                        *ptr2
+                            .add(24 + 6 * ::core::mem::size_of::<*const u8>())
+                            .cast::<*mut u8>() = result7;
+                        let vec11 = claims3;
+                        let len11 = vec11.len();
+                        let layout11 = _rt::alloc::Layout::from_size_align(
+                                vec11.len() * (4 * ::core::mem::size_of::<*const u8>()),
+                                ::core::mem::size_of::<*const u8>(),
+                            )
+                            .unwrap();
+                        let (result11, _cleanup11) = wit_bindgen::rt::Cleanup::new(
+                            layout11,
+                        );
+                        if let Some(cleanup) = _cleanup11 {
+                            cleanup.forget();
+                        }
+                        for (i, e) in vec11.into_iter().enumerate() {
+                            let base = result11
+                                .add(i * (4 * ::core::mem::size_of::<*const u8>()));
+                            {
+                                let (t8_0, t8_1) = e;
+                                let vec9 = (t8_0.into_bytes()).into_boxed_slice();
+                                let ptr9 = vec9.as_ptr().cast::<u8>();
+                                let len9 = vec9.len();
+                                ::core::mem::forget(vec9);
+                                *base
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>() = len9;
+                                *base.add(0).cast::<*mut u8>() = ptr9.cast_mut();
+                                let vec10 = (t8_1.into_bytes()).into_boxed_slice();
+                                let ptr10 = vec10.as_ptr().cast::<u8>();
+                                let len10 = vec10.len();
+                                ::core::mem::forget(vec10);
+                                *base
+                                    .add(3 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>() = len10;
+                                *base
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>() = ptr10.cast_mut();
+                            }
+                        }
+                        *ptr2
+                            .add(24 + 9 * ::core::mem::size_of::<*const u8>())
+                            .cast::<usize>() = len11;
+                        *ptr2
+                            .add(24 + 8 * ::core::mem::size_of::<*const u8>())
+                            .cast::<*mut u8>() = result11;
+                        match issued_at3 {
+                            Some(e) => {
+                                *ptr2
+                                    .add(24 + 10 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<u8>() = (1i32) as u8;
+                                *ptr2
+                                    .add(32 + 10 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<i64>() = _rt::as_i64(e);
+                            }
+                            None => {
+                                *ptr2
+                                    .add(24 + 10 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<u8>() = (0i32) as u8;
+                            }
+                        };
+                        match error_code3 {
+                            Some(e) => {
+                                *ptr2
+                                    .add(40 + 10 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<u8>() = (1i32) as u8;
+                                *ptr2
+                                    .add(41 + 10 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<u8>() = (e.clone() as i32) as u8;
+                            }
+                            None => {
+                                *ptr2
+                                    .add(40 + 10 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<u8>() = (0i32) as u8;
+                            }
+                        };
+                        *ptr2
+                            .add(42 + 10 * ::core::mem::size_of::<*const u8>())
+                            .cast::<u8>() = (match one_time3 {
+                            true => 1,
+                            false => 0,
+                        }) as u8;
                         ptr2
                     }
                 }
@@ -196,6 +601,138 @@ pub mod exports {
                                 _rt::cabi_dealloc(l4, l5, 1);
                             }
                         }
+                        let l6 = *arg0
+                            .add(24 + 6 * ::core::mem::size_of::<*const u8>())
+                            .cast::<*mut u8>();
+                        let l7 = *arg0
+                            .add(24 + 7 * ::core::mem::size_of::<*const u8>())
+                            .cast::<usize>();
+                        let base10 = l6;
+                        let len10 = l7;
+                        for i in 0..len10 {
+                            let base = base10
+                                .add(i * (2 * ::core::mem::size_of::<*const u8>()));
+                            {
+                                let l8 = *base.add(0).cast::<*mut u8>();
+                                let l9 = *base
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                _rt::cabi_dealloc(l8, l9, 1);
+                            }
+                        }
+                        _rt::cabi_dealloc(
+                            base10,
+                            len10 * (2 * ::core::mem::size_of::<*const u8>()),
+                            ::core::mem::size_of::<*const u8>(),
+                        );
+                        let l11 = *arg0
+                            .add(24 + 8 * ::core::mem::size_of::<*const u8>())
+                            .cast::<*mut u8>();
+                        let l12 = *arg0
+                            .add(24 + 9 * ::core::mem::size_of::<*const u8>())
+                            .cast::<usize>();
+                        let base17 = l11;
+                        let len17 = l12;
+                        for i in 0..len17 {
+                            let base = base17
+                                .add(i * (4 * ::core::mem::size_of::<*const u8>()));
+                            {
+                                let l13 = *base.add(0).cast::<*mut u8>();
+                                let l14 = *base
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                _rt::cabi_dealloc(l13, l14, 1);
+                                let l15 = *base
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l16 = *base
+                                    .add(3 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                _rt::cabi_dealloc(l15, l16, 1);
+                            }
+                        }
+                        _rt::cabi_dealloc(
+                            base17,
+                            len17 * (4 * ::core::mem::size_of::<*const u8>()),
+                            ::core::mem::size_of::<*const u8>(),
+                        );
+                    }
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case, unused_unsafe)]
+                pub unsafe fn _export_issue_token_cabi<T: Guest>(
+                    arg0: *mut u8,
+                    arg1: usize,
+                    arg2: i64,
+                ) -> *mut u8 {
+                    unsafe {
+                        _rt::run_ctors_once();
+                        let result1 = {
+                            let len0 = arg1;
+                            let bytes0 = _rt::Vec::from_raw_parts(
+                                arg0.cast(),
+                                len0,
+                                len0,
+                            );
+                            T::issue_token(_rt::string_lift(bytes0), arg2 as u64)
+                        };
+                        let ptr2 = (&raw mut _RET_AREA.0).cast::<u8>();
+                        match result1 {
+                            Ok(e) => {
+                                *ptr2.add(0).cast::<u8>() = (0i32) as u8;
+                                let vec3 = (e.into_bytes()).into_boxed_slice();
+                                let ptr3 = vec3.as_ptr().cast::<u8>();
+                                let len3 = vec3.len();
+                                ::core::mem::forget(vec3);
+                                *ptr2
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>() = len3;
+                                *ptr2
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>() = ptr3.cast_mut();
+                            }
+                            Err(e) => {
+                                *ptr2.add(0).cast::<u8>() = (1i32) as u8;
+                                let vec4 = (e.into_bytes()).into_boxed_slice();
+                                let ptr4 = vec4.as_ptr().cast::<u8>();
+                                let len4 = vec4.len();
+                                ::core::mem::forget(vec4);
+                                *ptr2
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>() = len4;
+                                *ptr2
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>() = ptr4.cast_mut();
+                            }
+                        };
+                        ptr2
+                    }
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn __post_return_issue_token<T: Guest>(arg0: *mut u8) {
+                    unsafe {
+                        let l0 = i32::from(*arg0.add(0).cast::<u8>());
+                        match l0 {
+                            0 => {
+                                let l1 = *arg0
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l2 = *arg0
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                _rt::cabi_dealloc(l1, l2, 1);
+                            }
+                            _ => {
+                                let l3 = *arg0
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l4 = *arg0
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                _rt::cabi_dealloc(l3, l4, 1);
+                            }
+                        }
                     }
                 }
                 pub trait Guest {
@@ -217,8 +754,10 @@ pub mod exports {
                     ///
                     /// # 戻り値
                     /// * `auth-result` - 認証結果
-                    ///   - 成功時: authenticated=true, user-id=Some(ユーザーID)
-                    ///   - 失敗時: authenticated=false, error=Some(エラーメッセージ)
+                    ///   - 成功時: authenticated=true, user-id=Some(ユーザーID),
+                    ///     expires-at/issued-at/roles/claims/one-time=ペイロードの値
+                    ///   - 失敗時: authenticated=false, error=Some(エラーメッセージ),
+                    ///     error-code=Some(失敗の理由), expires-at/issued-at=None, roles/claims=空リスト, one-time=false
                     ///
                     /// # 検証内容
                     /// 1. トークンが空でないこと
@@ -226,32 +765,340 @@ pub mod exports {
                     /// 3. アルゴリズムが HS256 であること
                     /// 4. HMAC-SHA256 署名が正しいこと
                     /// 5. sub クレームが存在すること
+                    /// 6. 失効していないこと（key-value ストアの jti・ユーザーごとの失効時刻）
                     #[allow(async_fn_in_trait)]
                     fn verify_token(token: _rt::String) -> AuthResult;
+                    /// 開発用にユーザーのトークン（HS256）を発行する
+                    ///
+                    /// Edge 層だけの結合テスト・デモの CLI で、コア層にログインせずにトークンを用意するための関数です。
+                    /// Spin 変数 jwt_dev_tokens=on の場合のみ発行し、既定（off）ではエラーを返します。本番では有効にしないでください。
+                    /// verify-token と同じ鍵（jwt_hs256_keys の最新の鍵・jwt_secret）で署名し、ヘッダーには kid を含めます。
+                    ///
+                    /// # 引数
+                    /// * `user-id` - sub クレーム
+                    /// * `ttl-seconds` - 有効期間（秒）。exp = 現在時刻 + ttl-seconds、iat = 現在時刻
+                    ///   - 0、または token-issuer の issue と同じ上限（MAX_ISSUE_TTL_SECS）を超える場合はエラー
+                    ///
+                    /// # 戻り値
+                    /// * `ok(string)` - 発行したトークン（header.payload.signature）
+                    /// * `err(string)` - エラーメッセージ（例: "issue-token is disabled (jwt_dev_tokens=off)"）
+                    #[allow(async_fn_in_trait)]
+                    fn issue_token(
+                        user_id: _rt::String,
+                        ttl_seconds: u64,
+                    ) -> Result<_rt::String, _rt::String>;
+                }
+                #[doc(hidden)]
+                pub(crate) use __export_demo_auth_authenticator_0_10_0_cabi;
+                #[repr(align(8))]
+                struct _RetArea(
+                    [::core::mem::MaybeUninit<
+                        u8,
+                    >; 48 + 10 * ::core::mem::size_of::<*const u8>()],
+                );
+                static mut _RET_AREA: _RetArea = _RetArea(
+                    [::core::mem::MaybeUninit::uninit(); 48
+                        + 10 * ::core::mem::size_of::<*const u8>()],
+                );
+            }
+            /// =============================================================================
+            /// token-issuer インターフェース
+            /// =============================================================================
+            ///
+            /// 短期間だけ有効なサービス用トークンを発行するインターフェースです。
+            /// 署名鍵は auth コンポーネントだけが持ち、gateway には共有しません。
+            ///
+            /// 用途:
+            /// - gateway からコア層へのリクエストに付与する X-Edge-Token
+            /// - カナリアのヘルスプローブなど、gateway 自身が行う呼び出し
+            /// サービス用トークンを発行するインターフェース
+            #[allow(dead_code, async_fn_in_trait, unused_imports, clippy::all)]
+            pub mod token_issuer {
+                #[used]
+                #[doc(hidden)]
+                static __FORCE_SECTION_REF: fn() = super::super::super::super::__link_custom_section_describing_imports;
+                use super::super::super::super::_rt;
+                #[doc(hidden)]
+                #[allow(non_snake_case, unused_unsafe)]
+                pub unsafe fn _export_issue_cabi<T: Guest>(
+                    arg0: *mut u8,
+                    arg1: usize,
+                    arg2: i64,
+                    arg3: *mut u8,
+                    arg4: usize,
+                ) -> *mut u8 {
+                    unsafe {
+                        _rt::run_ctors_once();
+                        let result8 = {
+                            let len0 = arg1;
+                            let bytes0 = _rt::Vec::from_raw_parts(
+                                arg0.cast(),
+                                len0,
+                                len0,
+                            );
+                            let base7 = arg3;
+                            let len7 = arg4;
+                            let mut result7 = _rt::Vec::with_capacity(len7);
+                            for i in 0..len7 {
+                                let base = base7
+                                    .add(i * (4 * ::core::mem::size_of::<*const u8>()));
+                                let e7 = {
+                                    let l1 = *base.add(0).cast::<*mut u8>();
+                                    let l2 = *base
+                                        .add(::core::mem::size_of::<*const u8>())
+                                        .cast::<usize>();
+                                    let len3 = l2;
+                                    let bytes3 = _rt::Vec::from_raw_parts(
+                                        l1.cast(),
+                                        len3,
+                                        len3,
+                                    );
+                                    let l4 = *base
+                                        .add(2 * ::core::mem::size_of::<*const u8>())
+                                        .cast::<*mut u8>();
+                                    let l5 = *base
+                                        .add(3 * ::core::mem::size_of::<*const u8>())
+                                        .cast::<usize>();
+                                    let len6 = l5;
+                                    let bytes6 = _rt::Vec::from_raw_parts(
+                                        l4.cast(),
+                                        len6,
+                                        len6,
+                                    );
+                                    (_rt::string_lift(bytes3), _rt::string_lift(bytes6))
+                                };
+                                result7.push(e7);
+                            }
+                            _rt::cabi_dealloc(
+                                base7,
+                                len7 * (4 * ::core::mem::size_of::<*const u8>()),
+                                ::core::mem::size_of::<*const u8>(),
+                            );
+                            T::issue(_rt::string_lift(bytes0), arg2 as u64, result7)
+                        };
+                        let ptr9 = (&raw mut _RET_AREA.0).cast::<u8>();
+                        match result8 {
+                            Ok(e) => {
+                                *ptr9.add(0).cast::<u8>() = (0i32) as u8;
+                                let vec10 = (e.into_bytes()).into_boxed_slice();
+                                let ptr10 = vec10.as_ptr().cast::<u8>();
+                                let len10 = vec10.len();
+                                ::core::mem::forget(vec10);
+                                *ptr9
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>() = len10;
+                                *ptr9
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>() = ptr10.cast_mut();
+                            }
+                            Err(e) => {
+                                *ptr9.add(0).cast::<u8>() = (1i32) as u8;
+                                let vec11 = (e.into_bytes()).into_boxed_slice();
+                                let ptr11 = vec11.as_ptr().cast::<u8>();
+                                let len11 = vec11.len();
+                                ::core::mem::forget(vec11);
+                                *ptr9
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>() = len11;
+                                *ptr9
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>() = ptr11.cast_mut();
+                            }
+                        };
+                        ptr9
+                    }
                 }
                 #[doc(hidden)]
-                pub(crate) use __export_demo_auth_authenticator_cabi;
+                #[allow(non_snake_case)]
+                pub unsafe fn __post_return_issue<T: Guest>(arg0: *mut u8) {
+                    unsafe {
+                        let l0 = i32::from(*arg0.add(0).cast::<u8>());
+                        match l0 {
+                            0 => {
+                                let l1 = *arg0
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l2 = *arg0
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                _rt::cabi_dealloc(l1, l2, 1);
+                            }
+                            _ => {
+                                let l3 = *arg0
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l4 = *arg0
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                _rt::cabi_dealloc(l3, l4, 1);
+                            }
+                        }
+                    }
+                }
+                pub trait Guest {
+                    /// 署名済みの JWT（HS256）を発行する
+                    ///
+                    /// ヘッダーには署名鍵の key id（kid）を含めます。
+                    ///
+                    /// # 引数
+                    /// * `subject` - sub クレーム（例: "edge-gateway"）
+                    /// * `ttl-seconds` - 有効期間（秒）。exp = 現在時刻 + ttl-seconds
+                    ///   - 0、または auth コンポーネントの上限（MAX_ISSUE_TTL_SECS）を超える場合はエラー
+                    /// * `claims` - 追加のクレーム（名前, 値）。値は文字列としてペイロードに入る
+                    ///   - 登録済みクレーム（sub, exp, iat など）は上書きできない（エラー）
+                    ///
+                    /// # 戻り値
+                    /// * `ok(string)` - 発行したトークン
+                    /// * `err(string)` - エラーメッセージ（例: "Missing subject", "Reserved claim: exp"）
+                    #[allow(async_fn_in_trait)]
+                    fn issue(
+                        subject: _rt::String,
+                        ttl_seconds: u64,
+                        claims: _rt::Vec<(_rt::String, _rt::String)>,
+                    ) -> Result<_rt::String, _rt::String>;
+                }
+                #[doc(hidden)]
+                pub(crate) use __export_demo_auth_token_issuer_0_10_0_cabi;
                 #[repr(align(4))]
                 struct _RetArea(
                     [::core::mem::MaybeUninit<
                         u8,
-                    >; 7 * ::core::mem::size_of::<*const u8>()],
+                    >; 3 * ::core::mem::size_of::<*const u8>()],
                 );
                 static mut _RET_AREA: _RetArea = _RetArea(
-                    [::core::mem::MaybeUninit::uninit(); 7
+                    [::core::mem::MaybeUninit::uninit(); 3
                         * ::core::mem::size_of::<*const u8>()],
                 );
             }
-        }
-    }
+            /// =============================================================================
+            /// metrics インターフェース
+            /// =============================================================================
+            ///
+            /// auth コンポーネントが数えている検証のカウンタを返すインターフェースです。
+            /// カウンタは Spin の key-value ストアに保存し、アトミックにインクリメントするため、
+            /// インスタンスが作り直されても、同時に実行されたリクエストがあっても失われません。
+            /// 検証のメトリクスを提供するインターフェース
+            #[allow(dead_code, async_fn_in_trait, unused_imports, clippy::all)]
+            pub mod metrics {
+                #[used]
+                #[doc(hidden)]
+                static __FORCE_SECTION_REF: fn() = super::super::super::super::__link_custom_section_describing_imports;
+                use super::super::super::super::_rt;
+                #[doc(hidden)]
+                #[allow(non_snake_case, unused_unsafe)]
+                pub unsafe fn _export_get_metrics_cabi<T: Guest>() -> *mut u8 {
+                    unsafe {
+                        _rt::run_ctors_once();
+                        let result0 = { T::get_metrics() };
+                        let ptr1 = (&raw mut _RET_AREA.0).cast::<u8>();
+                        let vec4 = result0;
+                        let len4 = vec4.len();
+                        let layout4 = _rt::alloc::Layout::from_size_align(
+                                vec4.len() * (8 + 2 * ::core::mem::size_of::<*const u8>()),
+                                8,
+                            )
+                            .unwrap();
+                        let (result4, _cleanup4) = wit_bindgen::rt::Cleanup::new(
+                            layout4,
+                        );
+                        if let Some(cleanup) = _cleanup4 {
+                            cleanup.forget();
+                        }
+                        for (i, e) in vec4.into_iter().enumerate() {
+                            let base = result4
+                                .add(i * (8 + 2 * ::core::mem::size_of::<*const u8>()));
+                            {
+                                let (t2_0, t2_1) = e;
+                                let vec3 = (t2_0.into_bytes()).into_boxed_slice();
+                                let ptr3 = vec3.as_ptr().cast::<u8>();
+                                let len3 = vec3.len();
+                                ::core::mem::forget(vec3);
+                                *base
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>() = len3;
+                                *base.add(0).cast::<*mut u8>() = ptr3.cast_mut();
+                                *base
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<i64>() = _rt::as_i64(t2_1);
+                            }
+                        }
+                        *ptr1.add(::core::mem::size_of::<*const u8>()).cast::<usize>() = len4;
+                        *ptr1.add(0).cast::<*mut u8>() = result4;
+                        ptr1
+                    }
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn __post_return_get_metrics<T: Guest>(arg0: *mut u8) {
+                    unsafe {
+                        let l0 = *arg0.add(0).cast::<*mut u8>();
+                        let l1 = *arg0
+                            .add(::core::mem::size_of::<*const u8>())
+                            .cast::<usize>();
+                        let base4 = l0;
+                        let len4 = l1;
+                        for i in 0..len4 {
+                            let base = base4
+                                .add(i * (8 + 2 * ::core::mem::size_of::<*const u8>()));
+                            {
+                                let l2 = *base.add(0).cast::<*mut u8>();
+                                let l3 = *base
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                _rt::cabi_dealloc(l2, l3, 1);
+                            }
+                        }
+                        _rt::cabi_dealloc(
+                            base4,
+                            len4 * (8 + 2 * ::core::mem::size_of::<*const u8>()),
+                            8,
+                        );
+                    }
+                }
+                pub trait Guest {
+                    /// 検証のカウンタ（名前, 値）の一覧を返す
+                    ///
+                    /// 名前は以下の固定の一覧で、まだ数えていないカウンタも 0 として返します。
+                    /// - auth_verifications_total: verify-token の呼び出し回数
+                    /// - auth_verifications_success: 検証に成功した回数
+                    /// - auth_failures_missing_token: トークンがない
+                    /// - auth_failures_expired: 有効期限切れ
+                    /// - auth_failures_bad_signature: 署名が一致しない
+                    /// - auth_failures_malformed: 形式が不正（Base64URL / JSON / 必須クレームの欠落）
+                    /// - auth_failures_unsupported_alg: 許可されていないアルゴリズム
+                    /// - auth_failures_other: 上記以外の失敗（nbf より前など）
+                    ///
+                    /// # 戻り値
+                    /// * `list<tuple<string, u64>>` - (カウンタ名, 値) の一覧（上記の順）
+                    ///
+                    /// # 障害時
+                    /// key-value ストアにアクセスできない場合は空リストを返します。
+                    #[allow(async_fn_in_trait)]
+                    fn get_metrics() -> _rt::Vec<(_rt::String, u64)>;
+                }
+                #[doc(hidden)]
+                pub(crate) use __export_demo_auth_metrics_0_10_0_cabi;
+                #[repr(align(4))]
+                struct _RetArea(
+                    [::core::mem::MaybeUninit<
+                        u8,
+                    >; 2 * ::core::mem::size_of::<*const u8>()],
+                );
+                static mut _RET_AREA: _RetArea = _RetArea(
+                    [::core::mem::MaybeUninit::uninit(); 2
+                        * ::core::mem::size_of::<*const u8>()],
+                );
+            }
+        }
+    }
 }
 mod _rt {
     #![allow(dead_code, unused_imports, clippy::all)]
     pub use alloc_crate::string::String;
+    pub use alloc_crate::vec::Vec;
     pub fn run_ctors_once() {
         wit_bindgen::rt::run_ctors_once();
     }
-    pub use alloc_crate::vec::Vec;
     pub unsafe fn string_lift(bytes: Vec<u8>) -> String {
         if true {
             String::from_utf8(bytes).unwrap()
@@ -259,6 +1106,30 @@ mod _rt {
             unsafe { String::from_utf8_unchecked(bytes) }
         }
     }
+    pub fn as_i64<T: AsI64>(t: T) -> i64 {
+        t.as_i64()
+    }
+    pub trait AsI64 {
+        fn as_i64(self) -> i64;
+    }
+    impl<'a, T: Copy + AsI64> AsI64 for &'a T {
+        fn as_i64(self) -> i64 {
+            (*self).as_i64()
+        }
+    }
+    impl AsI64 for i64 {
+        #[inline]
+        fn as_i64(self) -> i64 {
+            self as i64
+        }
+    }
+    impl AsI64 for u64 {
+        #[inline]
+        fn as_i64(self) -> i64 {
+            self as i64
+        }
+    }
+    pub use alloc_crate::alloc;
     pub unsafe fn cabi_dealloc(ptr: *mut u8, size: usize, align: usize) {
         if size == 0 {
             return;
@@ -269,704 +1140,2924 @@ mod _rt {
         }
     }
     extern crate alloc as alloc_crate;
-    pub use alloc_crate::alloc;
 }
 #[doc(inline)]
 pub(crate) use __export_auth_world_impl as export;
 #[unsafe(
-    link_section = "component-type:wit-bindgen:0.51.0:demo:auth:auth-world:encoded world"
+    link_section = "component-type:wit-bindgen:0.51.0:demo:auth@0.10.0:auth-world:encoded world"
 )]
 #[doc(hidden)]
 #[allow(clippy::octal_escapes)]
-pub static __WIT_BINDGEN_COMPONENT_TYPE: [u8; 274] = *b"\
-\0asm\x0d\0\x01\0\0\x19\x16wit-component-encoding\x04\0\x07\x91\x01\x01A\x02\x01\
-A\x02\x01B\x05\x01ks\x01r\x03\x0dauthenticated\x7f\x07user-id\0\x05error\0\x04\0\
-\x0bauth-result\x03\0\x01\x01@\x01\x05tokens\0\x02\x04\0\x0cverify-token\x01\x03\
-\x04\0\x17demo:auth/authenticator\x05\0\x04\0\x14demo:auth/auth-world\x04\0\x0b\x10\
-\x01\0\x0aauth-world\x03\0\0\0G\x09producers\x01\x0cprocessed-by\x02\x0dwit-comp\
-onent\x070.244.0\x10wit-bindgen-rust\x060.51.0";
+pub static __WIT_BINDGEN_COMPONENT_TYPE: [u8; 742] = *b"\
+\0asm\x0d\0\x01\0\0\x19\x16wit-component-encoding\x04\0\x07\xe5\x04\x01A\x02\x01\
+A\x06\x01B\x0f\x01m\x0b\x0dmissing-token\x09malformed\x15unsupported-algorithm\x11\
+invalid-signature\x07expired\x0dnot-yet-valid\x0fmissing-subject\x0einvalid-clai\
+ms\x0bunavailable\x07revoked\x09encrypted\x04\0\x0fauth-error-code\x03\0\0\x01ks\
+\x01kw\x01ps\x01o\x02ss\x01p\x05\x01k\x01\x01r\x09\x0dauthenticated\x7f\x07user-\
+id\x02\x05error\x02\x0aexpires-at\x03\x05roles\x04\x06claims\x06\x09issued-at\x03\
+\x0aerror-code\x07\x08one-time\x7f\x04\0\x0bauth-result\x03\0\x08\x01@\x01\x05to\
+kens\0\x09\x04\0\x0cverify-token\x01\x0a\x01j\x01s\x01s\x01@\x02\x07user-ids\x0b\
+ttl-secondsw\0\x0b\x04\0\x0bissue-token\x01\x0c\x04\0\x1edemo:auth/authenticator\
+@0.10.0\x05\0\x01B\x05\x01o\x02ss\x01p\0\x01j\x01s\x01s\x01@\x03\x07subjects\x0b\
+ttl-secondsw\x06claims\x01\0\x02\x04\0\x05issue\x01\x03\x04\0\x1ddemo:auth/token\
+-issuer@0.10.0\x05\x01\x01B\x04\x01o\x02sw\x01p\0\x01@\0\0\x01\x04\0\x0bget-metr\
+ics\x01\x02\x04\0\x18demo:auth/metrics@0.10.0\x05\x02\x04\0\x1bdemo:auth/auth-wo\
+rld@0.10.0\x04\0\x0b\x10\x01\0\x0aauth-world\x03\0\0\0G\x09producers\x01\x0cproc\
+essed-by\x02\x0dwit-component\x070.244.0\x10wit-bindgen-rust\x060.51.0";
 #[inline(never)]
 #[doc(hidden)]
 pub fn __link_custom_section_describing_imports() {
     wit_bindgen::rt::maybe_link_cabi_realloc();
 }
-const _: &[u8] = b"// =============================================================================\n// WIT (WebAssembly Interface Types) \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe5\xae\x9a\xe7\xbe\xa9\n// =============================================================================\n//\n// \xe3\x81\x93\xe3\x81\xae\xe3\x83\x95\xe3\x82\xa1\xe3\x82\xa4\xe3\x83\xab\xe3\x81\xaf Wasm \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe9\x96\x93\xe3\x81\xae\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe5\xae\x9a\xe7\xbe\xa9\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n// WIT \xe3\x81\xaf Wasm \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x83\xa2\xe3\x83\x87\xe3\x83\xab\xe3\x81\xae\xe4\xb8\x80\xe9\x83\xa8\xe3\x81\xa7\xe3\x80\x81\xe5\x9e\x8b\xe5\xae\x89\xe5\x85\xa8\xe3\x81\xaa\xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe9\x96\x93\xe9\x80\x9a\xe4\xbf\xa1\xe3\x82\x92\xe5\x8f\xaf\xe8\x83\xbd\xe3\x81\xab\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n//\n// \xe5\x8f\x82\xe7\x85\xa7:\n// - WIT \xe4\xbb\x95\xe6\xa7\x98: https://github.com/WebAssembly/component-model/blob/main/design/mvp/WIT.md\n// - \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x83\xa2\xe3\x83\x87\xe3\x83\xab: https://component-model.bytecodealliance.org/\n//\n// =============================================================================\n\n// -----------------------------------------------------------------------------\n// \xe3\x83\x91\xe3\x83\x83\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb8\xe5\xae\xa3\xe8\xa8\x80\n// -----------------------------------------------------------------------------\n// \xe3\x83\x91\xe3\x83\x83\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb8\xe5\x90\x8d\xe3\x81\xaf \"namespace:package\" \xe3\x81\xae\xe5\xbd\xa2\xe5\xbc\x8f\xe3\x81\xa7\xe6\x8c\x87\xe5\xae\x9a\n// - namespace: \xe7\xb5\x84\xe7\xb9\x94\xe3\x82\x84\xe3\x83\x97\xe3\x83\xad\xe3\x82\xb8\xe3\x82\xa7\xe3\x82\xaf\xe3\x83\x88\xe3\x82\x92\xe8\xad\x98\xe5\x88\xa5\xef\xbc\x88\xe4\xbe\x8b: wasi, demo\xef\xbc\x89\n// - package: \xe3\x83\x91\xe3\x83\x83\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb8\xe5\x90\x8d\xef\xbc\x88\xe4\xbe\x8b: auth, http\xef\xbc\x89\npackage demo:auth;\n\n// =============================================================================\n// authenticator \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\n// =============================================================================\n//\n// JWT \xe8\xaa\x8d\xe8\xa8\xbc\xe6\xa9\x9f\xe8\x83\xbd\xe3\x82\x92\xe6\x8f\x90\xe4\xbe\x9b\xe3\x81\x99\xe3\x82\x8b\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x81\xa7\xe3\x81\x99\xe3\x80\x82\n// auth \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe3\x81\x93\xe3\x81\xae\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe3\x82\xa8\xe3\x82\xaf\xe3\x82\xb9\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xef\xbc\x88\xe5\xae\x9f\xe8\xa3\x85\xef\xbc\x89\xe3\x81\x97\xe3\x80\x81\n// gateway \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe3\x82\xa4\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xef\xbc\x88\xe4\xbd\xbf\xe7\x94\xa8\xef\xbc\x89\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n\n/// \xe8\xaa\x8d\xe8\xa8\xbc\xe6\xa9\x9f\xe8\x83\xbd\xe3\x82\x92\xe6\x8f\x90\xe4\xbe\x9b\xe3\x81\x99\xe3\x82\x8b\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\ninterface authenticator {\n    // -------------------------------------------------------------------------\n    // \xe3\x83\xac\xe3\x82\xb3\xe3\x83\xbc\xe3\x83\x89\xe5\x9e\x8b\xe3\x81\xae\xe5\xae\x9a\xe7\xbe\xa9\n    // -------------------------------------------------------------------------\n    //\n    // record \xe3\x81\xaf Rust \xe3\x81\xae struct\xe3\x80\x81TypeScript \xe3\x81\xae interface \xe3\x81\xab\xe7\x9b\xb8\xe5\xbd\x93\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    // \xe3\x83\x95\xe3\x82\xa3\xe3\x83\xbc\xe3\x83\xab\xe3\x83\x89\xe3\x81\xaf\xe3\x82\xb1\xe3\x83\x90\xe3\x83\x96\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb9\xef\xbc\x88kebab-case\xef\xbc\x89\xe3\x81\xa7\xe8\xa8\x98\xe8\xbf\xb0\xe3\x81\x97\xe3\x80\x81\n    // \xe7\x94\x9f\xe6\x88\x90\xe3\x81\x95\xe3\x82\x8c\xe3\x82\x8b\xe3\x82\xb3\xe3\x83\xbc\xe3\x83\x89\xe3\x81\xa7\xe3\x81\xaf\xe3\x82\xb9\xe3\x83\x8d\xe3\x83\xbc\xe3\x82\xaf\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb9\xef\xbc\x88snake_case\xef\xbc\x89\xe3\x81\xab\xe5\xa4\x89\xe6\x8f\x9b\xe3\x81\x95\xe3\x82\x8c\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n\n    /// JWT \xe6\xa4\x9c\xe8\xa8\xbc\xe3\x81\xae\xe7\xb5\x90\xe6\x9e\x9c\xe3\x82\x92\xe8\xa1\xa8\xe3\x81\x99\xe3\x83\xac\xe3\x82\xb3\xe3\x83\xbc\xe3\x83\x89\xe5\x9e\x8b\n    ///\n    /// \xe8\xaa\x8d\xe8\xa8\xbc\xe3\x81\xae\xe6\x88\x90\xe5\x8a\x9f/\xe5\xa4\xb1\xe6\x95\x97\xe3\x82\x92\xe7\xa4\xba\xe3\x81\x97\xe3\x80\x81\xe6\x88\x90\xe5\x8a\x9f\xe6\x99\x82\xe3\x81\xaf\xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbcID\xe3\x80\x81\xe5\xa4\xb1\xe6\x95\x97\xe6\x99\x82\xe3\x81\xaf\xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\xe3\x83\xa1\xe3\x83\x83\xe3\x82\xbb\xe3\x83\xbc\xe3\x82\xb8\xe3\x82\x92\xe5\x90\xab\xe3\x81\xbf\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    record auth-result {\n        /// \xe8\xaa\x8d\xe8\xa8\xbc\xe3\x81\x8c\xe6\x88\x90\xe5\x8a\x9f\xe3\x81\x97\xe3\x81\x9f\xe3\x81\x8b\xe3\x81\xa9\xe3\x81\x86\xe3\x81\x8b\n        /// - true: JWT \xe3\x81\x8c\xe6\x9c\x89\xe5\x8a\xb9\xe3\x81\xa7\xe8\xaa\x8d\xe8\xa8\xbc\xe6\x88\x90\xe5\x8a\x9f\n        /// - false: JWT \xe3\x81\x8c\xe7\x84\xa1\xe5\x8a\xb9\xe3\x81\xbe\xe3\x81\x9f\xe3\x81\xaf\xe6\x9c\x9f\xe9\x99\x90\xe5\x88\x87\xe3\x82\x8c\xe3\x81\xa7\xe8\xaa\x8d\xe8\xa8\xbc\xe5\xa4\xb1\xe6\x95\x97\n        authenticated: bool,\n\n        /// \xe8\xaa\x8d\xe8\xa8\xbc\xe6\x88\x90\xe5\x8a\x9f\xe6\x99\x82\xe3\x81\xae\xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbcID\n        /// JWT \xe3\x81\xae sub (Subject) \xe3\x82\xaf\xe3\x83\xac\xe3\x83\xbc\xe3\x83\xa0\xe3\x81\x8b\xe3\x82\x89\xe6\x8a\xbd\xe5\x87\xba\xe3\x81\x97\xe3\x81\x9f\xe5\x80\xa4\n        /// \xe8\xaa\x8d\xe8\xa8\xbc\xe5\xa4\xb1\xe6\x95\x97\xe6\x99\x82\xe3\x81\xaf None (null)\n        user-id: option<string>,\n\n        /// \xe8\xaa\x8d\xe8\xa8\xbc\xe5\xa4\xb1\xe6\x95\x97\xe6\x99\x82\xe3\x81\xae\xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\xe3\x83\xa1\xe3\x83\x83\xe3\x82\xbb\xe3\x83\xbc\xe3\x82\xb8\n        /// \xe4\xbe\x8b: \"Missing token\", \"Invalid signature\", \"Token expired\"\n        /// \xe8\xaa\x8d\xe8\xa8\xbc\xe6\x88\x90\xe5\x8a\x9f\xe6\x99\x82\xe3\x81\xaf None (null)\n        error: option<string>,\n    }\n\n    // -------------------------------------------------------------------------\n    // \xe9\x96\xa2\xe6\x95\xb0\xe3\x81\xae\xe5\xae\x9a\xe7\xbe\xa9\n    // -------------------------------------------------------------------------\n    //\n    // func \xe3\x82\xad\xe3\x83\xbc\xe3\x83\xaf\xe3\x83\xbc\xe3\x83\x89\xe3\x81\xa7\xe9\x96\xa2\xe6\x95\xb0\xe3\x82\x92\xe5\xae\x9a\xe7\xbe\xa9\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    // \xe5\xbc\x95\xe6\x95\xb0\xe3\x81\xa8\xe6\x88\xbb\xe3\x82\x8a\xe5\x80\xa4\xe3\x81\xae\xe5\x9e\x8b\xe3\x82\x92\xe6\x8c\x87\xe5\xae\x9a\xe3\x81\x97\xe3\x80\x81\xe5\xae\x9f\xe8\xa3\x85\xe3\x81\xaf\xe5\x90\x84\xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xa7\xe8\xa1\x8c\xe3\x81\x84\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n\n    /// JWT \xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x82\x92\xe6\xa4\x9c\xe8\xa8\xbc\xe3\x81\x99\xe3\x82\x8b\n    ///\n    /// Authorization \xe3\x83\x98\xe3\x83\x83\xe3\x83\x80\xe3\x83\xbc\xe3\x81\x8b\xe3\x82\x89\xe5\x8f\x96\xe5\xbe\x97\xe3\x81\x97\xe3\x81\x9f Bearer \xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x82\x92\xe6\xa4\x9c\xe8\xa8\xbc\xe3\x81\x97\xe3\x80\x81\n    /// \xe8\xaa\x8d\xe8\xa8\xbc\xe7\xb5\x90\xe6\x9e\x9c\xe3\x82\x92\xe8\xbf\x94\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    ///\n    /// # \xe5\xbc\x95\xe6\x95\xb0\n    /// * `token` - \xe6\xa4\x9c\xe8\xa8\xbc\xe5\xaf\xbe\xe8\xb1\xa1\xe3\x81\xae JWT \xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe6\x96\x87\xe5\xad\x97\xe5\x88\x97\n    ///   - \xe7\xa9\xba\xe6\x96\x87\xe5\xad\x97\xe5\x88\x97\xe3\x81\xae\xe5\xa0\xb4\xe5\x90\x88\xe3\x80\x81\"Missing token\" \xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\n    ///   - \xe4\xb8\x8d\xe6\xad\xa3\xe3\x81\xaa\xe5\xbd\xa2\xe5\xbc\x8f\xe3\x81\xae\xe5\xa0\xb4\xe5\x90\x88\xe3\x80\x81\"Invalid token format\" \xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\n    ///\n    /// # \xe6\x88\xbb\xe3\x82\x8a\xe5\x80\xa4\n    /// * `auth-result` - \xe8\xaa\x8d\xe8\xa8\xbc\xe7\xb5\x90\xe6\x9e\x9c\n    ///   - \xe6\x88\x90\xe5\x8a\x9f\xe6\x99\x82: authenticated=true, user-id=Some(\xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbcID)\n    ///   - \xe5\xa4\xb1\xe6\x95\x97\xe6\x99\x82: authenticated=false, error=Some(\xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\xe3\x83\xa1\xe3\x83\x83\xe3\x82\xbb\xe3\x83\xbc\xe3\x82\xb8)\n    ///\n    /// # \xe6\xa4\x9c\xe8\xa8\xbc\xe5\x86\x85\xe5\xae\xb9\n    /// 1. \xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x81\x8c\xe7\xa9\xba\xe3\x81\xa7\xe3\x81\xaa\xe3\x81\x84\xe3\x81\x93\xe3\x81\xa8\n    /// 2. JWT \xe3\x83\x95\xe3\x82\xa9\xe3\x83\xbc\xe3\x83\x9e\xe3\x83\x83\xe3\x83\x88\xef\xbc\x88header.payload.signature\xef\xbc\x89\xe3\x81\xa7\xe3\x81\x82\xe3\x82\x8b\xe3\x81\x93\xe3\x81\xa8\n    /// 3. \xe3\x82\xa2\xe3\x83\xab\xe3\x82\xb4\xe3\x83\xaa\xe3\x82\xba\xe3\x83\xa0\xe3\x81\x8c HS256 \xe3\x81\xa7\xe3\x81\x82\xe3\x82\x8b\xe3\x81\x93\xe3\x81\xa8\n    /// 4. HMAC-SHA256 \xe7\xbd\xb2\xe5\x90\x8d\xe3\x81\x8c\xe6\xad\xa3\xe3\x81\x97\xe3\x81\x84\xe3\x81\x93\xe3\x81\xa8\n    /// 5. sub \xe3\x82\xaf\xe3\x83\xac\xe3\x83\xbc\xe3\x83\xa0\xe3\x81\x8c\xe5\xad\x98\xe5\x9c\xa8\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x93\xe3\x81\xa8\n    verify-token: func(token: string) -> auth-result;\n}\n\n// =============================================================================\n// World \xe5\xae\x9a\xe7\xbe\xa9\n// =============================================================================\n//\n// World \xe3\x81\xaf\xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xae\xe3\x80\x8c\xe9\xa1\x94\xe3\x80\x8d\xe3\x82\x92\xe5\xae\x9a\xe7\xbe\xa9\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n// - export: \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe5\xa4\x96\xe9\x83\xa8\xe3\x81\xab\xe6\x8f\x90\xe4\xbe\x9b\xe3\x81\x99\xe3\x82\x8b\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\n// - import: \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe5\xa4\x96\xe9\x83\xa8\xe3\x81\x8b\xe3\x82\x89\xe4\xbd\xbf\xe7\x94\xa8\xe3\x81\x99\xe3\x82\x8b\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\n//\n// \xe5\x90\x84 Wasm \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xaf 1 \xe3\x81\xa4\xe3\x81\xae world \xe3\x82\x92\xe5\xae\x9f\xe8\xa3\x85\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n\n/// auth \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe5\xae\x9f\xe8\xa3\x85\xe3\x81\x99\xe3\x82\x8b world\n///\n/// \xe3\x81\x93\xe3\x81\xae\xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xaf authenticator \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe3\x82\xa8\xe3\x82\xaf\xe3\x82\xb9\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xe3\x81\x97\xe3\x80\x81\n/// JWT \xe6\xa4\x9c\xe8\xa8\xbc\xe6\xa9\x9f\xe8\x83\xbd\xe3\x82\x92\xe5\xa4\x96\xe9\x83\xa8\xe3\x81\xab\xe6\x8f\x90\xe4\xbe\x9b\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n///\n/// \xe4\xbd\xbf\xe7\x94\xa8\xe4\xbe\x8b:\n/// - gateway \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8b\xe3\x82\x89 verify_token \xe9\x96\xa2\xe6\x95\xb0\xe3\x81\x8c\xe5\x91\xbc\xe3\x81\xb3\xe5\x87\xba\xe3\x81\x95\xe3\x82\x8c\xe3\x82\x8b\nworld auth-world {\n    // authenticator \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe5\xa4\x96\xe9\x83\xa8\xe3\x81\xab\xe5\x85\xac\xe9\x96\x8b\n    // \xe3\x81\x93\xe3\x82\x8c\xe3\x81\xab\xe3\x82\x88\xe3\x82\x8a\xe3\x80\x81\xe4\xbb\x96\xe3\x81\xae\xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe3\x81\x93\xe3\x81\xae\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe4\xbd\xbf\xe7\x94\xa8\xe5\x8f\xaf\xe8\x83\xbd\n    export authenticator;\n}\n\n/// gateway \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe5\xae\x9f\xe8\xa3\x85\xe3\x81\x99\xe3\x82\x8b world\n///\n/// \xe3\x81\x93\xe3\x81\xae\xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xaf authenticator \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe3\x82\xa4\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xe3\x81\x97\xe3\x80\x81\n/// auth \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xae\xe6\xa9\x9f\xe8\x83\xbd\xe3\x82\x92\xe5\x88\xa9\xe7\x94\xa8\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n///\n/// \xe4\xbd\xbf\xe7\x94\xa8\xe4\xbe\x8b:\n/// - HTTP \xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x82\x92\xe5\x8f\x97\xe4\xbf\xa1\n/// - auth \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xae verify_token \xe3\x82\x92\xe5\x91\xbc\xe3\x81\xb3\xe5\x87\xba\xe3\x81\x97\xe3\x81\xa6\xe8\xaa\x8d\xe8\xa8\xbc\n/// - \xe8\xaa\x8d\xe8\xa8\xbc\xe6\x88\x90\xe5\x8a\x9f\xe6\x99\x82\xe3\x80\x81\xe3\x82\xb3\xe3\x82\xa2\xe5\xb1\xa4\xe3\x81\xab\xe3\x83\x97\xe3\x83\xad\xe3\x82\xad\xe3\x82\xb7\nworld gateway-world {\n    // authenticator \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe4\xbd\xbf\xe7\x94\xa8\n    // Spin \xe3\x81\xae\xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe5\x90\x88\xe6\x88\x90\xe3\x81\xab\xe3\x82\x88\xe3\x82\x8a\xe3\x80\x81auth \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xab\xe6\x8e\xa5\xe7\xb6\x9a\xe3\x81\x95\xe3\x82\x8c\xe3\x82\x8b\n    import authenticator;\n}\n";
-use exports::demo::auth::authenticator::{AuthResult, Guest};
-/// JWT 署名検証用の秘密鍵
-///
-/// 注意: これはデモ用のハードコードされた秘密鍵です。
-/// 本番環境では以下の方法で安全に管理してください：
-/// - 環境変数から取得
-/// - シークレット管理サービス（HashiCorp Vault など）を使用
-/// - Spin の変数機能を使用
-const SECRET_KEY: &[u8] = b"super-secret-key";
-/// JWT ヘッダーを表す構造体
-///
-/// JWT のヘッダー部分には、トークンのタイプと署名アルゴリズムが含まれます。
-/// 例: {"alg":"HS256","typ":"JWT"}
-struct JwtHeader {
-    /// 署名アルゴリズム（例: "HS256", "RS256"）
-    /// このデモでは HS256 のみサポート
-    alg: String,
-    /// トークンタイプ（通常は "JWT"）
-    /// 検証には使用しないため、dead_code 警告を抑制
-    #[allow(dead_code)]
-    typ: Option<String>,
-}
-#[automatically_derived]
-impl ::core::fmt::Debug for JwtHeader {
-    #[inline]
-    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
-        ::core::fmt::Formatter::debug_struct_field2_finish(
-            f,
-            "JwtHeader",
-            "alg",
-            &self.alg,
-            "typ",
-            &&self.typ,
-        )
+const _: &[u8] = b"// =============================================================================\n// WIT (WebAssembly Interface Types) \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe5\xae\x9a\xe7\xbe\xa9\n// =============================================================================\n//\n// \xe3\x81\x93\xe3\x81\xae\xe3\x83\x95\xe3\x82\xa1\xe3\x82\xa4\xe3\x83\xab\xe3\x81\xaf Wasm \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe9\x96\x93\xe3\x81\xae\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe5\xae\x9a\xe7\xbe\xa9\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n// WIT \xe3\x81\xaf Wasm \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x83\xa2\xe3\x83\x87\xe3\x83\xab\xe3\x81\xae\xe4\xb8\x80\xe9\x83\xa8\xe3\x81\xa7\xe3\x80\x81\xe5\x9e\x8b\xe5\xae\x89\xe5\x85\xa8\xe3\x81\xaa\xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe9\x96\x93\xe9\x80\x9a\xe4\xbf\xa1\xe3\x82\x92\xe5\x8f\xaf\xe8\x83\xbd\xe3\x81\xab\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n//\n// \xe5\x8f\x82\xe7\x85\xa7:\n// - WIT \xe4\xbb\x95\xe6\xa7\x98: https://github.com/WebAssembly/component-model/blob/main/design/mvp/WIT.md\n// - \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x83\xa2\xe3\x83\x87\xe3\x83\xab: https://component-model.bytecodealliance.org/\n//\n// =============================================================================\n\n// -----------------------------------------------------------------------------\n// \xe3\x83\x91\xe3\x83\x83\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb8\xe5\xae\xa3\xe8\xa8\x80\n// -----------------------------------------------------------------------------\n// \xe3\x83\x91\xe3\x83\x83\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb8\xe5\x90\x8d\xe3\x81\xaf \"namespace:package\" \xe3\x81\xae\xe5\xbd\xa2\xe5\xbc\x8f\xe3\x81\xa7\xe6\x8c\x87\xe5\xae\x9a\n// - namespace: \xe7\xb5\x84\xe7\xb9\x94\xe3\x82\x84\xe3\x83\x97\xe3\x83\xad\xe3\x82\xb8\xe3\x82\xa7\xe3\x82\xaf\xe3\x83\x88\xe3\x82\x92\xe8\xad\x98\xe5\x88\xa5\xef\xbc\x88\xe4\xbe\x8b: wasi, demo\xef\xbc\x89\n// - package: \xe3\x83\x91\xe3\x83\x83\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb8\xe5\x90\x8d\xef\xbc\x88\xe4\xbe\x8b: auth, http\xef\xbc\x89\n//\n// \xe3\x83\x90\xe3\x83\xbc\xe3\x82\xb8\xe3\x83\xa7\xe3\x83\xb3\xef\xbc\x88@x.y.z\xef\xbc\x89:\n// - record \xe3\x81\xae\xe3\x83\x95\xe3\x82\xa3\xe3\x83\xbc\xe3\x83\xab\xe3\x83\x89\xe8\xbf\xbd\xe5\x8a\xa0\xe3\x81\xaf\xe3\x83\x90\xe3\x82\xa4\xe3\x83\xb3\xe3\x83\x87\xe3\x82\xa3\xe3\x83\xb3\xe3\x82\xb0\xe3\x81\xae\xe4\xba\x92\xe6\x8f\x9b\xe6\x80\xa7\xe3\x82\x92\xe5\xa3\x8a\xe3\x81\x99\xe3\x81\x9f\xe3\x82\x81\xe3\x80\x81\xe3\x83\x9e\xe3\x82\xa4\xe3\x83\x8a\xe3\x83\xbc\xe3\x83\x90\xe3\x83\xbc\xe3\x82\xb8\xe3\x83\xa7\xe3\x83\xb3\xe3\x82\x92\xe4\xb8\x8a\xe3\x81\x92\xe3\x82\x8b\n// - 0.2.0: auth-result \xe3\x81\xab expires-at / roles / claims \xe3\x82\x92\xe8\xbf\xbd\xe5\x8a\xa0\n// - 0.3.0: token-issuer \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe8\xbf\xbd\xe5\x8a\xa0\n// - 0.4.0: metrics \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe8\xbf\xbd\xe5\x8a\xa0\n// - 0.5.0: auth-result \xe3\x81\xab issued-at \xe3\x82\x92\xe8\xbf\xbd\xe5\x8a\xa0\n// - 0.6.0: auth-error-code \xe3\x81\xa8 auth-result \xe3\x81\xae error-code \xe3\x82\x92\xe8\xbf\xbd\xe5\x8a\xa0\n// - 0.7.0: auth-error-code \xe3\x81\xab revoked \xe3\x82\x92\xe8\xbf\xbd\xe5\x8a\xa0\n// - 0.8.0: authenticator \xe3\x81\xab issue-token \xe3\x82\x92\xe8\xbf\xbd\xe5\x8a\xa0\xef\xbc\x88\xe9\x96\x8b\xe7\x99\xba\xe7\x94\xa8\xef\xbc\x89\n// - 0.9.0: auth-error-code \xe3\x81\xab encrypted \xe3\x82\x92\xe8\xbf\xbd\xe5\x8a\xa0\n// - 0.10.0: auth-result \xe3\x81\xab one-time \xe3\x82\x92\xe8\xbf\xbd\xe5\x8a\xa0\n// - \xe3\x82\xa4\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xe5\x81\xb4\xef\xbc\x88spin.toml \xe3\x81\xae dependencies\xef\xbc\x89\xe3\x82\x82\xe5\x90\x8c\xe3\x81\x98\xe3\x83\x90\xe3\x83\xbc\xe3\x82\xb8\xe3\x83\xa7\xe3\x83\xb3\xe3\x82\x92\xe6\x8c\x87\xe5\xae\x9a\xe3\x81\x99\xe3\x82\x8b\npackage demo:auth@0.10.0;\n\n// =============================================================================\n// authenticator \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\n// =============================================================================\n//\n// JWT \xe8\xaa\x8d\xe8\xa8\xbc\xe6\xa9\x9f\xe8\x83\xbd\xe3\x82\x92\xe6\x8f\x90\xe4\xbe\x9b\xe3\x81\x99\xe3\x82\x8b\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x81\xa7\xe3\x81\x99\xe3\x80\x82\n// auth \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe3\x81\x93\xe3\x81\xae\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe3\x82\xa8\xe3\x82\xaf\xe3\x82\xb9\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xef\xbc\x88\xe5\xae\x9f\xe8\xa3\x85\xef\xbc\x89\xe3\x81\x97\xe3\x80\x81\n// gateway \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe3\x82\xa4\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xef\xbc\x88\xe4\xbd\xbf\xe7\x94\xa8\xef\xbc\x89\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n\n/// \xe8\xaa\x8d\xe8\xa8\xbc\xe6\xa9\x9f\xe8\x83\xbd\xe3\x82\x92\xe6\x8f\x90\xe4\xbe\x9b\xe3\x81\x99\xe3\x82\x8b\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\ninterface authenticator {\n    // -------------------------------------------------------------------------\n    // \xe5\x88\x97\xe6\x8c\x99\xe5\x9e\x8b\xe3\x81\xae\xe5\xae\x9a\xe7\xbe\xa9\n    // -------------------------------------------------------------------------\n    //\n    // enum \xe3\x81\xaf\xe3\x83\x95\xe3\x82\xa3\xe3\x83\xbc\xe3\x83\xab\xe3\x83\x89\xe3\x82\x92\xe6\x8c\x81\xe3\x81\x9f\xe3\x81\xaa\xe3\x81\x84 Rust \xe3\x81\xae enum \xe3\x81\xab\xe7\x9b\xb8\xe5\xbd\x93\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    // \xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb9\xe3\x81\xaf\xe3\x82\xb1\xe3\x83\x90\xe3\x83\x96\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb9\xe3\x81\xa7\xe8\xa8\x98\xe8\xbf\xb0\xe3\x81\x97\xe3\x80\x81\xe7\x94\x9f\xe6\x88\x90\xe3\x81\x95\xe3\x82\x8c\xe3\x82\x8b\xe3\x82\xb3\xe3\x83\xbc\xe3\x83\x89\xe3\x81\xa7\xe3\x81\xaf\xe3\x83\x91\xe3\x82\xb9\xe3\x82\xab\xe3\x83\xab\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb9\xef\xbc\x88PascalCase\xef\xbc\x89\xe3\x81\xab\xe5\xa4\x89\xe6\x8f\x9b\xe3\x81\x95\xe3\x82\x8c\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n\n    /// \xe8\xaa\x8d\xe8\xa8\xbc\xe5\xa4\xb1\xe6\x95\x97\xe3\x81\xae\xe7\x90\x86\xe7\x94\xb1\xe3\x82\x92\xe8\xa1\xa8\xe3\x81\x99\xe5\x88\x97\xe6\x8c\x99\xe5\x9e\x8b\n    ///\n    /// error \xe3\x81\xae\xe3\x83\xa1\xe3\x83\x83\xe3\x82\xbb\xe3\x83\xbc\xe3\x82\xb8\xe3\x81\xaf\xe4\xba\xba\xe3\x81\x8c\xe8\xaa\xad\xe3\x82\x80\xe3\x81\x9f\xe3\x82\x81\xe3\x81\xae\xe3\x82\x82\xe3\x81\xae\xe3\x81\xa7\xe6\x96\x87\xe8\xa8\x80\xe3\x81\x8c\xe5\xa4\x89\xe3\x82\x8f\xe3\x82\x8a\xe3\x81\x86\xe3\x82\x8b\xe3\x81\x9f\xe3\x82\x81\xe3\x80\x81\n    /// gateway \xe3\x81\xaf\xe3\x81\x93\xe3\x81\xae\xe3\x82\xb3\xe3\x83\xbc\xe3\x83\x89\xe3\x81\xa7\xe3\x83\xac\xe3\x82\xb9\xe3\x83\x9d\xe3\x83\xb3\xe3\x82\xb9\xef\xbc\x88\xe3\x82\xb9\xe3\x83\x86\xe3\x83\xbc\xe3\x82\xbf\xe3\x82\xb9\xe3\x83\xbbcode\xef\xbc\x89\xe3\x82\x92\xe6\xb1\xba\xe3\x82\x81\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    /// \xe5\xa4\xb1\xe6\x95\x97\xe3\x81\xae\xe7\x90\x86\xe7\x94\xb1\xe3\x82\x92\xe8\xbf\xbd\xe5\x8a\xa0\xe3\x81\x99\xe3\x82\x8b\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe6\x9c\xab\xe5\xb0\xbe\xe3\x81\xab\xe8\xbf\xbd\xe5\x8a\xa0\xe3\x81\x97\xe3\x80\x81\xe6\x97\xa2\xe5\xad\x98\xe3\x81\xae\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb9\xe3\x81\xaf\xe5\xa4\x89\xe3\x81\x88\xe3\x81\xbe\xe3\x81\x9b\xe3\x82\x93\xe3\x80\x82\n    enum auth-error-code {\n        /// \xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x81\x8c\xe3\x81\xaa\xe3\x81\x84\n        missing-token,\n        /// JWT \xe3\x81\xae\xe5\xbd\xa2\xe5\xbc\x8f\xe3\x83\xbbBase64URL\xe3\x83\xbbJSON \xe3\x81\x8c\xe4\xb8\x8d\xe6\xad\xa3\n        malformed,\n        /// \xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x95\xe3\x82\x8c\xe3\x81\xa6\xe3\x81\x84\xe3\x81\xaa\xe3\x81\x84\xe7\xbd\xb2\xe5\x90\x8d\xe3\x82\xa2\xe3\x83\xab\xe3\x82\xb4\xe3\x83\xaa\xe3\x82\xba\xe3\x83\xa0\n        unsupported-algorithm,\n        /// \xe7\xbd\xb2\xe5\x90\x8d\xe3\x81\x8c\xe4\xb8\x80\xe8\x87\xb4\xe3\x81\x97\xe3\x81\xaa\xe3\x81\x84\xe3\x80\x81\xe3\x81\xbe\xe3\x81\x9f\xe3\x81\xaf kid \xe3\x81\xae\xe9\x8d\xb5\xe3\x81\x8c\xe3\x81\xaa\xe3\x81\x84\n        invalid-signature,\n        /// \xe6\x9c\x89\xe5\x8a\xb9\xe6\x9c\x9f\xe9\x99\x90\xef\xbc\x88exp\xef\xbc\x89\xe3\x82\x92\xe9\x81\x8e\xe3\x81\x8e\xe3\x81\x9f\n        expired,\n        /// nbf \xe3\x82\x88\xe3\x82\x8a\xe5\x89\x8d\xe3\x80\x81\xe3\x81\xbe\xe3\x81\x9f\xe3\x81\xaf iat \xe3\x81\x8c\xe6\x9c\xaa\xe6\x9d\xa5\n        not-yet-valid,\n        /// sub \xe3\x82\xaf\xe3\x83\xac\xe3\x83\xbc\xe3\x83\xa0\xe3\x81\x8c\xe3\x81\xaa\xe3\x81\x84\n        missing-subject,\n        /// exp \xe3\x81\x8c\xe3\x81\xaa\xe3\x81\x84\xe3\x80\x81\xe3\x81\xbe\xe3\x81\x9f\xe3\x81\xaf\xe7\x99\xba\xe8\xa1\x8c\xe8\x80\x85\xef\xbc\x88iss\xef\xbc\x89\xe3\x83\xbb\xe5\xaf\xbe\xe8\xb1\xa1\xe8\x80\x85\xef\xbc\x88aud\xef\xbc\x89\xe3\x81\x8c\xe4\xb8\x8d\xe6\xad\xa3\n        invalid-claims,\n        /// \xe6\xa4\x9c\xe8\xa8\xbc\xe3\x81\xab\xe4\xbd\xbf\xe3\x81\x86\xe9\x8d\xb5\xe3\x83\xbb\xe5\xa4\xb1\xe5\x8a\xb9\xe3\x81\xae\xe7\xa2\xba\xe8\xaa\x8d\xe3\x81\xab\xe4\xbd\xbf\xe3\x81\x86\xe3\x82\xb9\xe3\x83\x88\xe3\x82\xa2\xe3\x82\x92\xe7\x94\xa8\xe6\x84\x8f\xe3\x81\xa7\xe3\x81\x8d\xe3\x81\xaa\xe3\x81\x84\xef\xbc\x88\xe8\xa8\xad\xe5\xae\x9a\xe3\x81\xae\xe8\xaa\xa4\xe3\x82\x8a\xe3\x83\xbbJWKS \xe3\x82\x92\xe5\x8f\x96\xe5\xbe\x97\xe3\x81\xa7\xe3\x81\x8d\xe3\x81\xaa\xe3\x81\x84\xef\xbc\x89\n        unavailable,\n        /// \xe5\xa4\xb1\xe5\x8a\xb9\xe3\x81\x97\xe3\x81\x9f\xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xef\xbc\x88\xe3\x83\xad\xe3\x82\xb0\xe3\x82\xa2\xe3\x82\xa6\xe3\x83\x88\xe3\x83\xbb\xe3\x83\x91\xe3\x82\xb9\xe3\x83\xaf\xe3\x83\xbc\xe3\x83\x89\xe3\x81\xae\xe5\xa4\x89\xe6\x9b\xb4\xe3\x81\xaa\xe3\x81\xa9\xe3\x81\xa7\xe5\xa4\xb1\xe5\x8a\xb9\xe3\x81\x95\xe3\x81\x9b\xe3\x81\x9f\xef\xbc\x89\n        revoked,\n        /// \xe6\x9a\x97\xe5\x8f\xb7\xe5\x8c\x96\xe3\x81\x97\xe3\x81\x9f\xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xef\xbc\x88JWE\xef\xbc\x89\xe3\x80\x82\xe7\xbd\xb2\xe5\x90\x8d\xe3\x82\x92\xe6\xa4\x9c\xe8\xa8\xbc\xe3\x81\xa7\xe3\x81\x8d\xe3\x81\xaa\xe3\x81\x84\xe3\x81\x9f\xe3\x82\x81\xe5\x8f\x97\xe3\x81\x91\xe4\xbb\x98\xe3\x81\x91\xe3\x81\xaa\xe3\x81\x84\n        encrypted,\n    }\n\n    // -------------------------------------------------------------------------\n    // \xe3\x83\xac\xe3\x82\xb3\xe3\x83\xbc\xe3\x83\x89\xe5\x9e\x8b\xe3\x81\xae\xe5\xae\x9a\xe7\xbe\xa9\n    // -------------------------------------------------------------------------\n    //\n    // record \xe3\x81\xaf Rust \xe3\x81\xae struct\xe3\x80\x81TypeScript \xe3\x81\xae interface \xe3\x81\xab\xe7\x9b\xb8\xe5\xbd\x93\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    // \xe3\x83\x95\xe3\x82\xa3\xe3\x83\xbc\xe3\x83\xab\xe3\x83\x89\xe3\x81\xaf\xe3\x82\xb1\xe3\x83\x90\xe3\x83\x96\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb9\xef\xbc\x88kebab-case\xef\xbc\x89\xe3\x81\xa7\xe8\xa8\x98\xe8\xbf\xb0\xe3\x81\x97\xe3\x80\x81\n    // \xe7\x94\x9f\xe6\x88\x90\xe3\x81\x95\xe3\x82\x8c\xe3\x82\x8b\xe3\x82\xb3\xe3\x83\xbc\xe3\x83\x89\xe3\x81\xa7\xe3\x81\xaf\xe3\x82\xb9\xe3\x83\x8d\xe3\x83\xbc\xe3\x82\xaf\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb9\xef\xbc\x88snake_case\xef\xbc\x89\xe3\x81\xab\xe5\xa4\x89\xe6\x8f\x9b\xe3\x81\x95\xe3\x82\x8c\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n\n    /// JWT \xe6\xa4\x9c\xe8\xa8\xbc\xe3\x81\xae\xe7\xb5\x90\xe6\x9e\x9c\xe3\x82\x92\xe8\xa1\xa8\xe3\x81\x99\xe3\x83\xac\xe3\x82\xb3\xe3\x83\xbc\xe3\x83\x89\xe5\x9e\x8b\n    ///\n    /// \xe8\xaa\x8d\xe8\xa8\xbc\xe3\x81\xae\xe6\x88\x90\xe5\x8a\x9f/\xe5\xa4\xb1\xe6\x95\x97\xe3\x82\x92\xe7\xa4\xba\xe3\x81\x97\xe3\x80\x81\xe6\x88\x90\xe5\x8a\x9f\xe6\x99\x82\xe3\x81\xaf\xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbcID\xe3\x80\x81\xe5\xa4\xb1\xe6\x95\x97\xe6\x99\x82\xe3\x81\xaf\xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\xe3\x83\xa1\xe3\x83\x83\xe3\x82\xbb\xe3\x83\xbc\xe3\x82\xb8\xe3\x82\x92\xe5\x90\xab\xe3\x81\xbf\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    /// \xe6\x88\x90\xe5\x8a\x9f\xe6\x99\x82\xe3\x81\xaf\xe6\x9c\x89\xe5\x8a\xb9\xe6\x9c\x9f\xe9\x99\x90\xe3\x83\xbb\xe7\x99\xba\xe8\xa1\x8c\xe6\x99\x82\xe5\x88\xbb\xe3\x83\xbb\xe3\x83\xad\xe3\x83\xbc\xe3\x83\xab\xe3\x83\xbb\xe8\xa8\xb1\xe5\x8f\xaf\xe3\x83\xaa\xe3\x82\xb9\xe3\x83\x88\xe3\x81\xab\xe5\x90\xab\xe3\x81\xbe\xe3\x82\x8c\xe3\x82\x8b\xe3\x82\xaf\xe3\x83\xac\xe3\x83\xbc\xe3\x83\xa0\xe3\x82\x82\xe8\xbf\x94\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    ///\n    /// \xe4\xba\x92\xe6\x8f\x9b\xe6\x80\xa7: \xe6\x97\xa2\xe5\xad\x98\xe3\x81\xae\xe3\x83\x95\xe3\x82\xa3\xe3\x83\xbc\xe3\x83\xab\xe3\x83\x89\xef\xbc\x88authenticated / user-id / error\xef\xbc\x89\xe3\x81\xae\xe5\x80\xa4\xe3\x81\xa8\xe6\x84\x8f\xe5\x91\xb3\xe3\x81\xaf\n    /// 0.1 \xe3\x81\x8b\xe3\x82\x89\xe5\xa4\x89\xe3\x81\x88\xe3\x81\x9a\xe3\x80\x81\xe6\x96\xb0\xe3\x81\x97\xe3\x81\x84\xe3\x83\x95\xe3\x82\xa3\xe3\x83\xbc\xe3\x83\xab\xe3\x83\x89\xe3\x81\xaf\xe6\x9c\xab\xe5\xb0\xbe\xe3\x81\xab\xe8\xbf\xbd\xe5\x8a\xa0\xe3\x81\x97\xe3\x81\xa6\xe3\x81\x84\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    record auth-result {\n        /// \xe8\xaa\x8d\xe8\xa8\xbc\xe3\x81\x8c\xe6\x88\x90\xe5\x8a\x9f\xe3\x81\x97\xe3\x81\x9f\xe3\x81\x8b\xe3\x81\xa9\xe3\x81\x86\xe3\x81\x8b\n        /// - true: JWT \xe3\x81\x8c\xe6\x9c\x89\xe5\x8a\xb9\xe3\x81\xa7\xe8\xaa\x8d\xe8\xa8\xbc\xe6\x88\x90\xe5\x8a\x9f\n        /// - false: JWT \xe3\x81\x8c\xe7\x84\xa1\xe5\x8a\xb9\xe3\x81\xbe\xe3\x81\x9f\xe3\x81\xaf\xe6\x9c\x9f\xe9\x99\x90\xe5\x88\x87\xe3\x82\x8c\xe3\x81\xa7\xe8\xaa\x8d\xe8\xa8\xbc\xe5\xa4\xb1\xe6\x95\x97\n        authenticated: bool,\n\n        /// \xe8\xaa\x8d\xe8\xa8\xbc\xe6\x88\x90\xe5\x8a\x9f\xe6\x99\x82\xe3\x81\xae\xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbcID\n        /// JWT \xe3\x81\xae sub (Subject) \xe3\x82\xaf\xe3\x83\xac\xe3\x83\xbc\xe3\x83\xa0\xe3\x81\x8b\xe3\x82\x89\xe6\x8a\xbd\xe5\x87\xba\xe3\x81\x97\xe3\x81\x9f\xe5\x80\xa4\n        /// \xe8\xaa\x8d\xe8\xa8\xbc\xe5\xa4\xb1\xe6\x95\x97\xe6\x99\x82\xe3\x81\xaf None (null)\n        user-id: option<string>,\n\n        /// \xe8\xaa\x8d\xe8\xa8\xbc\xe5\xa4\xb1\xe6\x95\x97\xe6\x99\x82\xe3\x81\xae\xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\xe3\x83\xa1\xe3\x83\x83\xe3\x82\xbb\xe3\x83\xbc\xe3\x82\xb8\n        /// \xe4\xbe\x8b: \"Missing token\", \"Invalid signature\", \"Token expired\"\n        /// \xe8\xaa\x8d\xe8\xa8\xbc\xe6\x88\x90\xe5\x8a\x9f\xe6\x99\x82\xe3\x81\xaf None (null)\n        error: option<string>,\n\n        /// \xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x81\xae\xe6\x9c\x89\xe5\x8a\xb9\xe6\x9c\x9f\xe9\x99\x90\xef\xbc\x88Unix \xe3\x82\xbf\xe3\x82\xa4\xe3\x83\xa0\xe3\x82\xb9\xe3\x82\xbf\xe3\x83\xb3\xe3\x83\x97\xe3\x80\x81\xe7\xa7\x92\xef\xbc\x89\n        /// JWT \xe3\x81\xae exp \xe3\x82\xaf\xe3\x83\xac\xe3\x83\xbc\xe3\x83\xa0\xe3\x81\xae\xe5\x80\xa4\xef\xbc\x88exp \xe3\x81\x8c\xe3\x81\xaa\xe3\x81\x84\xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x81\xaf None\xef\xbc\x89\n        /// gateway \xe3\x81\x8c\xe6\xa4\x9c\xe8\xa8\xbc\xe7\xb5\x90\xe6\x9e\x9c\xe3\x82\x92\xe3\x82\xad\xe3\x83\xa3\xe3\x83\x83\xe3\x82\xb7\xe3\x83\xa5\xe3\x81\x99\xe3\x82\x8b\xe6\x9c\x9f\xe9\x96\x93\xe3\x81\xae\xe4\xb8\x8a\xe9\x99\x90\xe3\x81\xab\xe4\xbd\xbf\xe7\x94\xa8\xe3\x81\x99\xe3\x82\x8b\n        /// \xe8\xaa\x8d\xe8\xa8\xbc\xe5\xa4\xb1\xe6\x95\x97\xe6\x99\x82\xe3\x81\xaf None (null)\n        expires-at: option<u64>,\n\n        /// \xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbc\xe3\x81\xae\xe3\x83\xad\xe3\x83\xbc\xe3\x83\xab\n        /// JWT \xe3\x81\xae roles \xe3\x82\xaf\xe3\x83\xac\xe3\x83\xbc\xe3\x83\xa0\xef\xbc\x88\xe6\x96\x87\xe5\xad\x97\xe5\x88\x97\xe3\x81\xae\xe9\x85\x8d\xe5\x88\x97\xef\xbc\x89\xe3\x81\xae\xe5\x80\xa4\n        /// roles \xe3\x81\x8c\xe3\x81\xaa\xe3\x81\x84\xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x83\xbb\xe8\xaa\x8d\xe8\xa8\xbc\xe5\xa4\xb1\xe6\x95\x97\xe6\x99\x82\xe3\x81\xaf\xe7\xa9\xba\xe3\x83\xaa\xe3\x82\xb9\xe3\x83\x88\n        roles: list<string>,\n\n        /// \xe8\xa8\xb1\xe5\x8f\xaf\xe3\x83\xaa\xe3\x82\xb9\xe3\x83\x88\xe3\x81\xab\xe5\x90\xab\xe3\x81\xbe\xe3\x82\x8c\xe3\x82\x8b\xe3\x82\xaf\xe3\x83\xac\xe3\x83\xbc\xe3\x83\xa0\xef\xbc\x88\xe5\x90\x8d\xe5\x89\x8d, \xe5\x80\xa4\xef\xbc\x89\n        /// \xe8\xa8\xb1\xe5\x8f\xaf\xe3\x83\xaa\xe3\x82\xb9\xe3\x83\x88\xe3\x81\xaf auth \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xae CLAIM_ALLOWLIST \xe3\x81\xa7\xe8\xa8\xad\xe5\xae\x9a\xe3\x81\x99\xe3\x82\x8b\n        /// \xe5\x80\xa4\xe3\x81\xaf\xe6\x96\x87\xe5\xad\x97\xe5\x88\x97\xe3\x81\xab\xe5\xa4\x89\xe6\x8f\x9b\xe3\x81\x99\xe3\x82\x8b\xef\xbc\x88\xe6\x96\x87\xe5\xad\x97\xe5\x88\x97\xe3\x83\xbb\xe6\x95\xb0\xe5\x80\xa4\xe3\x83\xbb\xe7\x9c\x9f\xe5\x81\xbd\xe5\x80\xa4\xe3\x81\xae\xe3\x81\xbf\xe3\x80\x81\xe3\x81\x9d\xe3\x82\x8c\xe4\xbb\xa5\xe5\xa4\x96\xe3\x81\xaf\xe5\x90\xab\xe3\x82\x81\xe3\x81\xaa\xe3\x81\x84\xef\xbc\x89\n        /// \xe4\xbe\x8b: [(\"tenant_id\", \"6f1c...\")]\n        /// \xe8\xaa\x8d\xe8\xa8\xbc\xe5\xa4\xb1\xe6\x95\x97\xe6\x99\x82\xe3\x81\xaf\xe7\xa9\xba\xe3\x83\xaa\xe3\x82\xb9\xe3\x83\x88\n        claims: list<tuple<string, string>>,\n\n        /// \xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x81\xae\xe7\x99\xba\xe8\xa1\x8c\xe6\x99\x82\xe5\x88\xbb\xef\xbc\x88Unix \xe3\x82\xbf\xe3\x82\xa4\xe3\x83\xa0\xe3\x82\xb9\xe3\x82\xbf\xe3\x83\xb3\xe3\x83\x97\xe3\x80\x81\xe7\xa7\x92\xef\xbc\x89\n        /// JWT \xe3\x81\xae iat \xe3\x82\xaf\xe3\x83\xac\xe3\x83\xbc\xe3\x83\xa0\xe3\x81\xae\xe5\x80\xa4\xef\xbc\x88iat \xe3\x81\x8c\xe3\x81\xaa\xe3\x81\x84\xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x81\xaf None\xef\xbc\x89\n        /// \xe8\xaa\x8d\xe8\xa8\xbc\xe5\xa4\xb1\xe6\x95\x97\xe6\x99\x82\xe3\x81\xaf None (null)\n        issued-at: option<u64>,\n\n        /// \xe8\xaa\x8d\xe8\xa8\xbc\xe5\xa4\xb1\xe6\x95\x97\xe3\x81\xae\xe7\x90\x86\xe7\x94\xb1\xe3\x81\xae\xe3\x82\xb3\xe3\x83\xbc\xe3\x83\x89\n        /// error \xe3\x81\xae\xe3\x83\xa1\xe3\x83\x83\xe3\x82\xbb\xe3\x83\xbc\xe3\x82\xb8\xe3\x81\xab\xe5\xaf\xbe\xe5\xbf\x9c\xe3\x81\x99\xe3\x82\x8b\xef\xbc\x88\xe4\xbe\x8b: \"Token expired\" \xe2\x86\x92 expired\xef\xbc\x89\n        /// \xe8\xaa\x8d\xe8\xa8\xbc\xe6\x88\x90\xe5\x8a\x9f\xe6\x99\x82\xe3\x81\xaf None (null)\n        error-code: option<auth-error-code>,\n\n        /// \xe4\xbd\xbf\xe3\x81\x84\xe6\x8d\xa8\xe3\x81\xa6\xe3\x81\xae\xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x81\x8b\xef\xbc\x88JWT \xe3\x81\xae one_time \xe3\x82\xaf\xe3\x83\xac\xe3\x83\xbc\xe3\x83\xa0\xe3\x81\x8c\xe7\x9c\x9f\xe5\x81\xbd\xe5\x80\xa4\xe3\x81\xae true\xef\xbc\x89\n        /// 2 \xe5\x9b\x9e\xe7\x9b\xae\xe4\xbb\xa5\xe9\x99\x8d\xe3\x81\xae\xe6\x8f\x90\xe7\xa4\xba\xe3\x82\x92 auth \xe3\x81\x8c\xe6\x8b\x92\xe5\x90\xa6\xe3\x81\xa7\xe3\x81\x8d\xe3\x82\x8b\xe3\x82\x88\xe3\x81\x86\xe3\x80\x81gateway \xe3\x81\xaf\xe6\xa4\x9c\xe8\xa8\xbc\xe7\xb5\x90\xe6\x9e\x9c\xe3\x82\x92\xe3\x82\xad\xe3\x83\xa3\xe3\x83\x83\xe3\x82\xb7\xe3\x83\xa5\xe3\x81\x97\xe3\x81\xaa\xe3\x81\x84\n        /// \xe8\xaa\x8d\xe8\xa8\xbc\xe5\xa4\xb1\xe6\x95\x97\xe6\x99\x82\xe3\x81\xaf false\n        one-time: bool,\n    }\n\n    // -------------------------------------------------------------------------\n    // \xe9\x96\xa2\xe6\x95\xb0\xe3\x81\xae\xe5\xae\x9a\xe7\xbe\xa9\n    // -------------------------------------------------------------------------\n    //\n    // func \xe3\x82\xad\xe3\x83\xbc\xe3\x83\xaf\xe3\x83\xbc\xe3\x83\x89\xe3\x81\xa7\xe9\x96\xa2\xe6\x95\xb0\xe3\x82\x92\xe5\xae\x9a\xe7\xbe\xa9\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    // \xe5\xbc\x95\xe6\x95\xb0\xe3\x81\xa8\xe6\x88\xbb\xe3\x82\x8a\xe5\x80\xa4\xe3\x81\xae\xe5\x9e\x8b\xe3\x82\x92\xe6\x8c\x87\xe5\xae\x9a\xe3\x81\x97\xe3\x80\x81\xe5\xae\x9f\xe8\xa3\x85\xe3\x81\xaf\xe5\x90\x84\xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xa7\xe8\xa1\x8c\xe3\x81\x84\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n\n    /// JWT \xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x82\x92\xe6\xa4\x9c\xe8\xa8\xbc\xe3\x81\x99\xe3\x82\x8b\n    ///\n    /// Authorization \xe3\x83\x98\xe3\x83\x83\xe3\x83\x80\xe3\x83\xbc\xe3\x81\x8b\xe3\x82\x89\xe5\x8f\x96\xe5\xbe\x97\xe3\x81\x97\xe3\x81\x9f Bearer \xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x82\x92\xe6\xa4\x9c\xe8\xa8\xbc\xe3\x81\x97\xe3\x80\x81\n    /// \xe8\xaa\x8d\xe8\xa8\xbc\xe7\xb5\x90\xe6\x9e\x9c\xe3\x82\x92\xe8\xbf\x94\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    ///\n    /// # \xe5\xbc\x95\xe6\x95\xb0\n    /// * `token` - \xe6\xa4\x9c\xe8\xa8\xbc\xe5\xaf\xbe\xe8\xb1\xa1\xe3\x81\xae JWT \xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe6\x96\x87\xe5\xad\x97\xe5\x88\x97\n    ///   - \xe7\xa9\xba\xe6\x96\x87\xe5\xad\x97\xe5\x88\x97\xe3\x81\xae\xe5\xa0\xb4\xe5\x90\x88\xe3\x80\x81\"Missing token\" \xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\n    ///   - \xe4\xb8\x8d\xe6\xad\xa3\xe3\x81\xaa\xe5\xbd\xa2\xe5\xbc\x8f\xe3\x81\xae\xe5\xa0\xb4\xe5\x90\x88\xe3\x80\x81\"Invalid token format\" \xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\n    ///\n    /// # \xe6\x88\xbb\xe3\x82\x8a\xe5\x80\xa4\n    /// * `auth-result` - \xe8\xaa\x8d\xe8\xa8\xbc\xe7\xb5\x90\xe6\x9e\x9c\n    ///   - \xe6\x88\x90\xe5\x8a\x9f\xe6\x99\x82: authenticated=true, user-id=Some(\xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbcID),\n    ///     expires-at/issued-at/roles/claims/one-time=\xe3\x83\x9a\xe3\x82\xa4\xe3\x83\xad\xe3\x83\xbc\xe3\x83\x89\xe3\x81\xae\xe5\x80\xa4\n    ///   - \xe5\xa4\xb1\xe6\x95\x97\xe6\x99\x82: authenticated=false, error=Some(\xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\xe3\x83\xa1\xe3\x83\x83\xe3\x82\xbb\xe3\x83\xbc\xe3\x82\xb8),\n    ///     error-code=Some(\xe5\xa4\xb1\xe6\x95\x97\xe3\x81\xae\xe7\x90\x86\xe7\x94\xb1), expires-at/issued-at=None, roles/claims=\xe7\xa9\xba\xe3\x83\xaa\xe3\x82\xb9\xe3\x83\x88, one-time=false\n    ///\n    /// # \xe6\xa4\x9c\xe8\xa8\xbc\xe5\x86\x85\xe5\xae\xb9\n    /// 1. \xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x81\x8c\xe7\xa9\xba\xe3\x81\xa7\xe3\x81\xaa\xe3\x81\x84\xe3\x81\x93\xe3\x81\xa8\n    /// 2. JWT \xe3\x83\x95\xe3\x82\xa9\xe3\x83\xbc\xe3\x83\x9e\xe3\x83\x83\xe3\x83\x88\xef\xbc\x88header.payload.signature\xef\xbc\x89\xe3\x81\xa7\xe3\x81\x82\xe3\x82\x8b\xe3\x81\x93\xe3\x81\xa8\n    /// 3. \xe3\x82\xa2\xe3\x83\xab\xe3\x82\xb4\xe3\x83\xaa\xe3\x82\xba\xe3\x83\xa0\xe3\x81\x8c HS256 \xe3\x81\xa7\xe3\x81\x82\xe3\x82\x8b\xe3\x81\x93\xe3\x81\xa8\n    /// 4. HMAC-SHA256 \xe7\xbd\xb2\xe5\x90\x8d\xe3\x81\x8c\xe6\xad\xa3\xe3\x81\x97\xe3\x81\x84\xe3\x81\x93\xe3\x81\xa8\n    /// 5. sub \xe3\x82\xaf\xe3\x83\xac\xe3\x83\xbc\xe3\x83\xa0\xe3\x81\x8c\xe5\xad\x98\xe5\x9c\xa8\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x93\xe3\x81\xa8\n    /// 6. \xe5\xa4\xb1\xe5\x8a\xb9\xe3\x81\x97\xe3\x81\xa6\xe3\x81\x84\xe3\x81\xaa\xe3\x81\x84\xe3\x81\x93\xe3\x81\xa8\xef\xbc\x88key-value \xe3\x82\xb9\xe3\x83\x88\xe3\x82\xa2\xe3\x81\xae jti\xe3\x83\xbb\xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbc\xe3\x81\x94\xe3\x81\xa8\xe3\x81\xae\xe5\xa4\xb1\xe5\x8a\xb9\xe6\x99\x82\xe5\x88\xbb\xef\xbc\x89\n    verify-token: func(token: string) -> auth-result;\n\n    /// \xe9\x96\x8b\xe7\x99\xba\xe7\x94\xa8\xe3\x81\xab\xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbc\xe3\x81\xae\xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xef\xbc\x88HS256\xef\xbc\x89\xe3\x82\x92\xe7\x99\xba\xe8\xa1\x8c\xe3\x81\x99\xe3\x82\x8b\n    ///\n    /// Edge \xe5\xb1\xa4\xe3\x81\xa0\xe3\x81\x91\xe3\x81\xae\xe7\xb5\x90\xe5\x90\x88\xe3\x83\x86\xe3\x82\xb9\xe3\x83\x88\xe3\x83\xbb\xe3\x83\x87\xe3\x83\xa2\xe3\x81\xae CLI \xe3\x81\xa7\xe3\x80\x81\xe3\x82\xb3\xe3\x82\xa2\xe5\xb1\xa4\xe3\x81\xab\xe3\x83\xad\xe3\x82\xb0\xe3\x82\xa4\xe3\x83\xb3\xe3\x81\x9b\xe3\x81\x9a\xe3\x81\xab\xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x82\x92\xe7\x94\xa8\xe6\x84\x8f\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x9f\xe3\x82\x81\xe3\x81\xae\xe9\x96\xa2\xe6\x95\xb0\xe3\x81\xa7\xe3\x81\x99\xe3\x80\x82\n    /// Spin \xe5\xa4\x89\xe6\x95\xb0 jwt_dev_tokens=on \xe3\x81\xae\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xae\xe3\x81\xbf\xe7\x99\xba\xe8\xa1\x8c\xe3\x81\x97\xe3\x80\x81\xe6\x97\xa2\xe5\xae\x9a\xef\xbc\x88off\xef\xbc\x89\xe3\x81\xa7\xe3\x81\xaf\xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\xe3\x82\x92\xe8\xbf\x94\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\xe6\x9c\xac\xe7\x95\xaa\xe3\x81\xa7\xe3\x81\xaf\xe6\x9c\x89\xe5\x8a\xb9\xe3\x81\xab\xe3\x81\x97\xe3\x81\xaa\xe3\x81\x84\xe3\x81\xa7\xe3\x81\x8f\xe3\x81\xa0\xe3\x81\x95\xe3\x81\x84\xe3\x80\x82\n    /// verify-token \xe3\x81\xa8\xe5\x90\x8c\xe3\x81\x98\xe9\x8d\xb5\xef\xbc\x88jwt_hs256_keys \xe3\x81\xae\xe6\x9c\x80\xe6\x96\xb0\xe3\x81\xae\xe9\x8d\xb5\xe3\x83\xbbjwt_secret\xef\xbc\x89\xe3\x81\xa7\xe7\xbd\xb2\xe5\x90\x8d\xe3\x81\x97\xe3\x80\x81\xe3\x83\x98\xe3\x83\x83\xe3\x83\x80\xe3\x83\xbc\xe3\x81\xab\xe3\x81\xaf kid \xe3\x82\x92\xe5\x90\xab\xe3\x82\x81\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    ///\n    /// # \xe5\xbc\x95\xe6\x95\xb0\n    /// * `user-id` - sub \xe3\x82\xaf\xe3\x83\xac\xe3\x83\xbc\xe3\x83\xa0\n    /// * `ttl-seconds` - \xe6\x9c\x89\xe5\x8a\xb9\xe6\x9c\x9f\xe9\x96\x93\xef\xbc\x88\xe7\xa7\x92\xef\xbc\x89\xe3\x80\x82exp = \xe7\x8f\xbe\xe5\x9c\xa8\xe6\x99\x82\xe5\x88\xbb + ttl-seconds\xe3\x80\x81iat = \xe7\x8f\xbe\xe5\x9c\xa8\xe6\x99\x82\xe5\x88\xbb\n    ///   - 0\xe3\x80\x81\xe3\x81\xbe\xe3\x81\x9f\xe3\x81\xaf token-issuer \xe3\x81\xae issue \xe3\x81\xa8\xe5\x90\x8c\xe3\x81\x98\xe4\xb8\x8a\xe9\x99\x90\xef\xbc\x88MAX_ISSUE_TTL_SECS\xef\xbc\x89\xe3\x82\x92\xe8\xb6\x85\xe3\x81\x88\xe3\x82\x8b\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf\xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\n    ///\n    /// # \xe6\x88\xbb\xe3\x82\x8a\xe5\x80\xa4\n    /// * `ok(string)` - \xe7\x99\xba\xe8\xa1\x8c\xe3\x81\x97\xe3\x81\x9f\xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xef\xbc\x88header.payload.signature\xef\xbc\x89\n    /// * `err(string)` - \xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\xe3\x83\xa1\xe3\x83\x83\xe3\x82\xbb\xe3\x83\xbc\xe3\x82\xb8\xef\xbc\x88\xe4\xbe\x8b: \"issue-token is disabled (jwt_dev_tokens=off)\"\xef\xbc\x89\n    issue-token: func(user-id: string, ttl-seconds: u64) -> result<string, string>;\n}\n\n// =============================================================================\n// token-issuer \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\n// =============================================================================\n//\n// \xe7\x9f\xad\xe6\x9c\x9f\xe9\x96\x93\xe3\x81\xa0\xe3\x81\x91\xe6\x9c\x89\xe5\x8a\xb9\xe3\x81\xaa\xe3\x82\xb5\xe3\x83\xbc\xe3\x83\x93\xe3\x82\xb9\xe7\x94\xa8\xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x82\x92\xe7\x99\xba\xe8\xa1\x8c\xe3\x81\x99\xe3\x82\x8b\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x81\xa7\xe3\x81\x99\xe3\x80\x82\n// \xe7\xbd\xb2\xe5\x90\x8d\xe9\x8d\xb5\xe3\x81\xaf auth \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xa0\xe3\x81\x91\xe3\x81\x8c\xe6\x8c\x81\xe3\x81\xa1\xe3\x80\x81gateway \xe3\x81\xab\xe3\x81\xaf\xe5\x85\xb1\xe6\x9c\x89\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x9b\xe3\x82\x93\xe3\x80\x82\n//\n// \xe7\x94\xa8\xe9\x80\x94:\n// - gateway \xe3\x81\x8b\xe3\x82\x89\xe3\x82\xb3\xe3\x82\xa2\xe5\xb1\xa4\xe3\x81\xb8\xe3\x81\xae\xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x81\xab\xe4\xbb\x98\xe4\xb8\x8e\xe3\x81\x99\xe3\x82\x8b X-Edge-Token\n// - \xe3\x82\xab\xe3\x83\x8a\xe3\x83\xaa\xe3\x82\xa2\xe3\x81\xae\xe3\x83\x98\xe3\x83\xab\xe3\x82\xb9\xe3\x83\x97\xe3\x83\xad\xe3\x83\xbc\xe3\x83\x96\xe3\x81\xaa\xe3\x81\xa9\xe3\x80\x81gateway \xe8\x87\xaa\xe8\xba\xab\xe3\x81\x8c\xe8\xa1\x8c\xe3\x81\x86\xe5\x91\xbc\xe3\x81\xb3\xe5\x87\xba\xe3\x81\x97\n\n/// \xe3\x82\xb5\xe3\x83\xbc\xe3\x83\x93\xe3\x82\xb9\xe7\x94\xa8\xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x82\x92\xe7\x99\xba\xe8\xa1\x8c\xe3\x81\x99\xe3\x82\x8b\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\ninterface token-issuer {\n    /// \xe7\xbd\xb2\xe5\x90\x8d\xe6\xb8\x88\xe3\x81\xbf\xe3\x81\xae JWT\xef\xbc\x88HS256\xef\xbc\x89\xe3\x82\x92\xe7\x99\xba\xe8\xa1\x8c\xe3\x81\x99\xe3\x82\x8b\n    ///\n    /// \xe3\x83\x98\xe3\x83\x83\xe3\x83\x80\xe3\x83\xbc\xe3\x81\xab\xe3\x81\xaf\xe7\xbd\xb2\xe5\x90\x8d\xe9\x8d\xb5\xe3\x81\xae key id\xef\xbc\x88kid\xef\xbc\x89\xe3\x82\x92\xe5\x90\xab\xe3\x82\x81\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    ///\n    /// # \xe5\xbc\x95\xe6\x95\xb0\n    /// * `subject` - sub \xe3\x82\xaf\xe3\x83\xac\xe3\x83\xbc\xe3\x83\xa0\xef\xbc\x88\xe4\xbe\x8b: \"edge-gateway\"\xef\xbc\x89\n    /// * `ttl-seconds` - \xe6\x9c\x89\xe5\x8a\xb9\xe6\x9c\x9f\xe9\x96\x93\xef\xbc\x88\xe7\xa7\x92\xef\xbc\x89\xe3\x80\x82exp = \xe7\x8f\xbe\xe5\x9c\xa8\xe6\x99\x82\xe5\x88\xbb + ttl-seconds\n    ///   - 0\xe3\x80\x81\xe3\x81\xbe\xe3\x81\x9f\xe3\x81\xaf auth \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xae\xe4\xb8\x8a\xe9\x99\x90\xef\xbc\x88MAX_ISSUE_TTL_SECS\xef\xbc\x89\xe3\x82\x92\xe8\xb6\x85\xe3\x81\x88\xe3\x82\x8b\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf\xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\n    /// * `claims` - \xe8\xbf\xbd\xe5\x8a\xa0\xe3\x81\xae\xe3\x82\xaf\xe3\x83\xac\xe3\x83\xbc\xe3\x83\xa0\xef\xbc\x88\xe5\x90\x8d\xe5\x89\x8d, \xe5\x80\xa4\xef\xbc\x89\xe3\x80\x82\xe5\x80\xa4\xe3\x81\xaf\xe6\x96\x87\xe5\xad\x97\xe5\x88\x97\xe3\x81\xa8\xe3\x81\x97\xe3\x81\xa6\xe3\x83\x9a\xe3\x82\xa4\xe3\x83\xad\xe3\x83\xbc\xe3\x83\x89\xe3\x81\xab\xe5\x85\xa5\xe3\x82\x8b\n    ///   - \xe7\x99\xbb\xe9\x8c\xb2\xe6\xb8\x88\xe3\x81\xbf\xe3\x82\xaf\xe3\x83\xac\xe3\x83\xbc\xe3\x83\xa0\xef\xbc\x88sub, exp, iat \xe3\x81\xaa\xe3\x81\xa9\xef\xbc\x89\xe3\x81\xaf\xe4\xb8\x8a\xe6\x9b\xb8\xe3\x81\x8d\xe3\x81\xa7\xe3\x81\x8d\xe3\x81\xaa\xe3\x81\x84\xef\xbc\x88\xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\xef\xbc\x89\n    ///\n    /// # \xe6\x88\xbb\xe3\x82\x8a\xe5\x80\xa4\n    /// * `ok(string)` - \xe7\x99\xba\xe8\xa1\x8c\xe3\x81\x97\xe3\x81\x9f\xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\n    /// * `err(string)` - \xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\xe3\x83\xa1\xe3\x83\x83\xe3\x82\xbb\xe3\x83\xbc\xe3\x82\xb8\xef\xbc\x88\xe4\xbe\x8b: \"Missing subject\", \"Reserved claim: exp\"\xef\xbc\x89\n    issue: func(subject: string, ttl-seconds: u64, claims: list<tuple<string, string>>) -> result<string, string>;\n}\n\n// =============================================================================\n// metrics \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\n// =============================================================================\n//\n// auth \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe6\x95\xb0\xe3\x81\x88\xe3\x81\xa6\xe3\x81\x84\xe3\x82\x8b\xe6\xa4\x9c\xe8\xa8\xbc\xe3\x81\xae\xe3\x82\xab\xe3\x82\xa6\xe3\x83\xb3\xe3\x82\xbf\xe3\x82\x92\xe8\xbf\x94\xe3\x81\x99\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x81\xa7\xe3\x81\x99\xe3\x80\x82\n// \xe3\x82\xab\xe3\x82\xa6\xe3\x83\xb3\xe3\x82\xbf\xe3\x81\xaf Spin \xe3\x81\xae key-value \xe3\x82\xb9\xe3\x83\x88\xe3\x82\xa2\xe3\x81\xab\xe4\xbf\x9d\xe5\xad\x98\xe3\x81\x97\xe3\x80\x81\xe3\x82\xa2\xe3\x83\x88\xe3\x83\x9f\xe3\x83\x83\xe3\x82\xaf\xe3\x81\xab\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xaf\xe3\x83\xaa\xe3\x83\xa1\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x9f\xe3\x82\x81\xe3\x80\x81\n// \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xb9\xe3\x82\xbf\xe3\x83\xb3\xe3\x82\xb9\xe3\x81\x8c\xe4\xbd\x9c\xe3\x82\x8a\xe7\x9b\xb4\xe3\x81\x95\xe3\x82\x8c\xe3\x81\xa6\xe3\x82\x82\xe3\x80\x81\xe5\x90\x8c\xe6\x99\x82\xe3\x81\xab\xe5\xae\x9f\xe8\xa1\x8c\xe3\x81\x95\xe3\x82\x8c\xe3\x81\x9f\xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x81\x8c\xe3\x81\x82\xe3\x81\xa3\xe3\x81\xa6\xe3\x82\x82\xe5\xa4\xb1\xe3\x82\x8f\xe3\x82\x8c\xe3\x81\xbe\xe3\x81\x9b\xe3\x82\x93\xe3\x80\x82\n\n/// \xe6\xa4\x9c\xe8\xa8\xbc\xe3\x81\xae\xe3\x83\xa1\xe3\x83\x88\xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xb9\xe3\x82\x92\xe6\x8f\x90\xe4\xbe\x9b\xe3\x81\x99\xe3\x82\x8b\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\ninterface metrics {\n    /// \xe6\xa4\x9c\xe8\xa8\xbc\xe3\x81\xae\xe3\x82\xab\xe3\x82\xa6\xe3\x83\xb3\xe3\x82\xbf\xef\xbc\x88\xe5\x90\x8d\xe5\x89\x8d, \xe5\x80\xa4\xef\xbc\x89\xe3\x81\xae\xe4\xb8\x80\xe8\xa6\xa7\xe3\x82\x92\xe8\xbf\x94\xe3\x81\x99\n    ///\n    /// \xe5\x90\x8d\xe5\x89\x8d\xe3\x81\xaf\xe4\xbb\xa5\xe4\xb8\x8b\xe3\x81\xae\xe5\x9b\xba\xe5\xae\x9a\xe3\x81\xae\xe4\xb8\x80\xe8\xa6\xa7\xe3\x81\xa7\xe3\x80\x81\xe3\x81\xbe\xe3\x81\xa0\xe6\x95\xb0\xe3\x81\x88\xe3\x81\xa6\xe3\x81\x84\xe3\x81\xaa\xe3\x81\x84\xe3\x82\xab\xe3\x82\xa6\xe3\x83\xb3\xe3\x82\xbf\xe3\x82\x82 0 \xe3\x81\xa8\xe3\x81\x97\xe3\x81\xa6\xe8\xbf\x94\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    /// - auth_verifications_total: verify-token \xe3\x81\xae\xe5\x91\xbc\xe3\x81\xb3\xe5\x87\xba\xe3\x81\x97\xe5\x9b\x9e\xe6\x95\xb0\n    /// - auth_verifications_success: \xe6\xa4\x9c\xe8\xa8\xbc\xe3\x81\xab\xe6\x88\x90\xe5\x8a\x9f\xe3\x81\x97\xe3\x81\x9f\xe5\x9b\x9e\xe6\x95\xb0\n    /// - auth_failures_missing_token: \xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x81\x8c\xe3\x81\xaa\xe3\x81\x84\n    /// - auth_failures_expired: \xe6\x9c\x89\xe5\x8a\xb9\xe6\x9c\x9f\xe9\x99\x90\xe5\x88\x87\xe3\x82\x8c\n    /// - auth_failures_bad_signature: \xe7\xbd\xb2\xe5\x90\x8d\xe3\x81\x8c\xe4\xb8\x80\xe8\x87\xb4\xe3\x81\x97\xe3\x81\xaa\xe3\x81\x84\n    /// - auth_failures_malformed: \xe5\xbd\xa2\xe5\xbc\x8f\xe3\x81\x8c\xe4\xb8\x8d\xe6\xad\xa3\xef\xbc\x88Base64URL / JSON / \xe5\xbf\x85\xe9\xa0\x88\xe3\x82\xaf\xe3\x83\xac\xe3\x83\xbc\xe3\x83\xa0\xe3\x81\xae\xe6\xac\xa0\xe8\x90\xbd\xef\xbc\x89\n    /// - auth_failures_unsupported_alg: \xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x95\xe3\x82\x8c\xe3\x81\xa6\xe3\x81\x84\xe3\x81\xaa\xe3\x81\x84\xe3\x82\xa2\xe3\x83\xab\xe3\x82\xb4\xe3\x83\xaa\xe3\x82\xba\xe3\x83\xa0\n    /// - auth_failures_other: \xe4\xb8\x8a\xe8\xa8\x98\xe4\xbb\xa5\xe5\xa4\x96\xe3\x81\xae\xe5\xa4\xb1\xe6\x95\x97\xef\xbc\x88nbf \xe3\x82\x88\xe3\x82\x8a\xe5\x89\x8d\xe3\x81\xaa\xe3\x81\xa9\xef\xbc\x89\n    ///\n    /// # \xe6\x88\xbb\xe3\x82\x8a\xe5\x80\xa4\n    /// * `list<tuple<string, u64>>` - (\xe3\x82\xab\xe3\x82\xa6\xe3\x83\xb3\xe3\x82\xbf\xe5\x90\x8d, \xe5\x80\xa4) \xe3\x81\xae\xe4\xb8\x80\xe8\xa6\xa7\xef\xbc\x88\xe4\xb8\x8a\xe8\xa8\x98\xe3\x81\xae\xe9\xa0\x86\xef\xbc\x89\n    ///\n    /// # \xe9\x9a\x9c\xe5\xae\xb3\xe6\x99\x82\n    /// key-value \xe3\x82\xb9\xe3\x83\x88\xe3\x82\xa2\xe3\x81\xab\xe3\x82\xa2\xe3\x82\xaf\xe3\x82\xbb\xe3\x82\xb9\xe3\x81\xa7\xe3\x81\x8d\xe3\x81\xaa\xe3\x81\x84\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf\xe7\xa9\xba\xe3\x83\xaa\xe3\x82\xb9\xe3\x83\x88\xe3\x82\x92\xe8\xbf\x94\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    get-metrics: func() -> list<tuple<string, u64>>;\n}\n\n// =============================================================================\n// World \xe5\xae\x9a\xe7\xbe\xa9\n// =============================================================================\n//\n// World \xe3\x81\xaf\xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xae\xe3\x80\x8c\xe9\xa1\x94\xe3\x80\x8d\xe3\x82\x92\xe5\xae\x9a\xe7\xbe\xa9\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n// - export: \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe5\xa4\x96\xe9\x83\xa8\xe3\x81\xab\xe6\x8f\x90\xe4\xbe\x9b\xe3\x81\x99\xe3\x82\x8b\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\n// - import: \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe5\xa4\x96\xe9\x83\xa8\xe3\x81\x8b\xe3\x82\x89\xe4\xbd\xbf\xe7\x94\xa8\xe3\x81\x99\xe3\x82\x8b\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\n//\n// \xe5\x90\x84 Wasm \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xaf 1 \xe3\x81\xa4\xe3\x81\xae world \xe3\x82\x92\xe5\xae\x9f\xe8\xa3\x85\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n\n/// auth \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe5\xae\x9f\xe8\xa3\x85\xe3\x81\x99\xe3\x82\x8b world\n///\n/// \xe3\x81\x93\xe3\x81\xae\xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xaf authenticator / token-issuer / metrics \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe3\x82\xa8\xe3\x82\xaf\xe3\x82\xb9\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xe3\x81\x97\xe3\x80\x81\n/// JWT \xe6\xa4\x9c\xe8\xa8\xbc\xe3\x83\xbb\xe7\x99\xba\xe8\xa1\x8c\xe6\xa9\x9f\xe8\x83\xbd\xe3\x81\xa8\xe6\xa4\x9c\xe8\xa8\xbc\xe3\x81\xae\xe3\x83\xa1\xe3\x83\x88\xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xb9\xe3\x82\x92\xe5\xa4\x96\xe9\x83\xa8\xe3\x81\xab\xe6\x8f\x90\xe4\xbe\x9b\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n///\n/// \xe4\xbd\xbf\xe7\x94\xa8\xe4\xbe\x8b:\n/// - gateway \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8b\xe3\x82\x89 verify_token \xe9\x96\xa2\xe6\x95\xb0\xe3\x81\x8c\xe5\x91\xbc\xe3\x81\xb3\xe5\x87\xba\xe3\x81\x95\xe3\x82\x8c\xe3\x82\x8b\n/// - gateway \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8b\xe3\x82\x89 issue \xe9\x96\xa2\xe6\x95\xb0\xe3\x81\x8c\xe5\x91\xbc\xe3\x81\xb3\xe5\x87\xba\xe3\x81\x95\xe3\x82\x8c\xe3\x82\x8b\xef\xbc\x88X-Edge-Token \xe3\x81\xae\xe7\x99\xba\xe8\xa1\x8c\xef\xbc\x89\n/// - gateway \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8b\xe3\x82\x89 get_metrics \xe9\x96\xa2\xe6\x95\xb0\xe3\x81\x8c\xe5\x91\xbc\xe3\x81\xb3\xe5\x87\xba\xe3\x81\x95\xe3\x82\x8c\xe3\x82\x8b\xef\xbc\x88/gateway/metrics\xef\xbc\x89\nworld auth-world {\n    // authenticator \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe5\xa4\x96\xe9\x83\xa8\xe3\x81\xab\xe5\x85\xac\xe9\x96\x8b\n    // \xe3\x81\x93\xe3\x82\x8c\xe3\x81\xab\xe3\x82\x88\xe3\x82\x8a\xe3\x80\x81\xe4\xbb\x96\xe3\x81\xae\xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe3\x81\x93\xe3\x81\xae\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe4\xbd\xbf\xe7\x94\xa8\xe5\x8f\xaf\xe8\x83\xbd\n    export authenticator;\n\n    // token-issuer \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe5\xa4\x96\xe9\x83\xa8\xe3\x81\xab\xe5\x85\xac\xe9\x96\x8b\n    export token-issuer;\n\n    // metrics \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe5\xa4\x96\xe9\x83\xa8\xe3\x81\xab\xe5\x85\xac\xe9\x96\x8b\n    export metrics;\n}\n\n/// gateway \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe5\xae\x9f\xe8\xa3\x85\xe3\x81\x99\xe3\x82\x8b world\n///\n/// \xe3\x81\x93\xe3\x81\xae\xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xaf authenticator / token-issuer / metrics \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe3\x82\xa4\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xe3\x81\x97\xe3\x80\x81\n/// auth \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xae\xe6\xa9\x9f\xe8\x83\xbd\xe3\x82\x92\xe5\x88\xa9\xe7\x94\xa8\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n///\n/// \xe4\xbd\xbf\xe7\x94\xa8\xe4\xbe\x8b:\n/// - HTTP \xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x82\x92\xe5\x8f\x97\xe4\xbf\xa1\n/// - auth \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xae verify_token \xe3\x82\x92\xe5\x91\xbc\xe3\x81\xb3\xe5\x87\xba\xe3\x81\x97\xe3\x81\xa6\xe8\xaa\x8d\xe8\xa8\xbc\n/// - auth \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xae issue \xe3\x81\xa7 X-Edge-Token \xe3\x82\x92\xe7\x99\xba\xe8\xa1\x8c\xe3\x81\x97\xe3\x80\x81\xe3\x82\xb3\xe3\x82\xa2\xe5\xb1\xa4\xe3\x81\xab\xe3\x83\x97\xe3\x83\xad\xe3\x82\xad\xe3\x82\xb7\n/// - auth \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xae get_metrics \xe3\x81\xa7\xe6\xa4\x9c\xe8\xa8\xbc\xe3\x81\xae\xe3\x82\xab\xe3\x82\xa6\xe3\x83\xb3\xe3\x82\xbf\xe3\x82\x92\xe5\x8f\x96\xe5\xbe\x97\xef\xbc\x88/gateway/metrics\xef\xbc\x89\n/// - ratelimit \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xae check \xe3\x81\xa7 IP \xe5\x8d\x98\xe4\xbd\x8d\xe3\x83\xbb\xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbc\xe5\x8d\x98\xe4\xbd\x8d\xe3\x81\xae\xe3\x83\xac\xe3\x83\xbc\xe3\x83\x88\xe5\x88\xb6\xe9\x99\x90\n/// - authz \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xae allow \xe3\x81\xa7\xe3\x83\xad\xe3\x83\xbc\xe3\x83\xab\xe3\x83\xbb\xe3\x83\xa1\xe3\x82\xbd\xe3\x83\x83\xe3\x83\x89\xe3\x83\xbb\xe3\x83\x91\xe3\x82\xb9\xe3\x81\xab\xe3\x82\x88\xe3\x82\x8b\xe8\xaa\x8d\xe5\x8f\xaf\nworld gateway-world {\n    // authenticator \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe4\xbd\xbf\xe7\x94\xa8\n    // Spin \xe3\x81\xae\xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe5\x90\x88\xe6\x88\x90\xe3\x81\xab\xe3\x82\x88\xe3\x82\x8a\xe3\x80\x81auth \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xab\xe6\x8e\xa5\xe7\xb6\x9a\xe3\x81\x95\xe3\x82\x8c\xe3\x82\x8b\n    import authenticator;\n\n    // token-issuer \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe4\xbd\xbf\xe7\x94\xa8\xef\xbc\x88\xe7\xbd\xb2\xe5\x90\x8d\xe9\x8d\xb5\xe3\x81\xaf auth \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xae\xe3\x81\xbf\xe3\x81\x8c\xe6\x8c\x81\xe3\x81\xa4\xef\xbc\x89\n    import token-issuer;\n\n    // metrics \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe4\xbd\xbf\xe7\x94\xa8\xef\xbc\x88/gateway/metrics \xe3\x81\xa7\xe5\x85\xac\xe9\x96\x8b\xe3\x81\x99\xe3\x82\x8b\xef\xbc\x89\n    import metrics;\n\n    // \xe3\x83\xac\xe3\x83\xbc\xe3\x83\x88\xe5\x88\xb6\xe9\x99\x90\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe4\xbd\xbf\xe7\x94\xa8\xef\xbc\x88ratelimit \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xab\xe6\x8e\xa5\xe7\xb6\x9a\xe3\x81\x95\xe3\x82\x8c\xe3\x82\x8b\xef\xbc\x89\n    // \xe5\xae\x9a\xe7\xbe\xa9\xe3\x81\xaf wit/deps/ratelimit/limiter.wit\xef\xbc\x88\xe5\x88\xa5\xe3\x83\x91\xe3\x83\x83\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb8 demo:ratelimit\xef\xbc\x89\n    import demo:ratelimit/limiter@0.1.0;\n\n    // \xe8\xaa\x8d\xe5\x8f\xaf\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe4\xbd\xbf\xe7\x94\xa8\xef\xbc\x88authz \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xab\xe6\x8e\xa5\xe7\xb6\x9a\xe3\x81\x95\xe3\x82\x8c\xe3\x82\x8b\xef\xbc\x89\n    // \xe5\xae\x9a\xe7\xbe\xa9\xe3\x81\xaf wit/deps/authz/policy.wit\xef\xbc\x88\xe5\x88\xa5\xe3\x83\x91\xe3\x83\x83\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb8 demo:authz\xef\xbc\x89\n    import demo:authz/policy@0.1.0;\n}\n";
+const _: &[u8] = b"// =============================================================================\n// WIT \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe5\xae\x9a\xe7\xbe\xa9: \xe8\xaa\x8d\xe5\x8f\xaf\xef\xbc\x88\xe3\x83\x91\xe3\x83\xbc\xe3\x83\x9f\xe3\x83\x83\xe3\x82\xb7\xe3\x83\xa7\xe3\x83\xb3\xef\xbc\x89\n// =============================================================================\n//\n// \xe8\xaa\x8d\xe5\x8f\xaf\xe5\x88\xa4\xe5\xae\x9a\xe3\x82\x92\xe7\x8b\xac\xe7\xab\x8b\xe3\x81\x97\xe3\x81\x9f Wasm \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xef\xbc\x88authz\xef\xbc\x89\xe3\x81\xa8\xe3\x81\x97\xe3\x81\xa6\xe6\x8f\x90\xe4\xbe\x9b\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x9f\xe3\x82\x81\xe3\x81\xae\n// \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x81\xa7\xe3\x81\x99\xe3\x80\x82gateway \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe3\x82\xa4\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xe3\x81\x97\xe3\x80\x81\n// \xe8\xaa\x8d\xe8\xa8\xbc\xe6\x88\x90\xe5\x8a\x9f\xe5\xbe\x8c\xe3\x81\xae\xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x81\x94\xe3\x81\xa8\xe3\x81\xab\xe3\x80\x81\xe3\x83\xad\xe3\x83\xbc\xe3\x83\xab\xe3\x83\xbb\xe3\x83\xa1\xe3\x82\xbd\xe3\x83\x83\xe3\x83\x89\xe3\x83\xbb\xe3\x83\x91\xe3\x82\xb9\xe3\x81\x8b\xe3\x82\x89\xe8\xa8\xb1\xe5\x8f\xaf/\xe6\x8b\x92\xe5\x90\xa6\xe3\x82\x92\xe5\x88\xa4\xe5\xae\x9a\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n//\n// \xe9\x85\x8d\xe7\xbd\xae:\n// - demo:auth\xef\xbc\x88wit/auth.wit\xef\xbc\x89\xe3\x81\xae gateway-world \xe3\x81\x8b\xe3\x82\x89\xe3\x82\xa4\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x9f\xe3\x82\x81\xe3\x80\x81\n//   \xe4\xbe\x9d\xe5\xad\x98\xe3\x83\x91\xe3\x83\x83\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb8\xe3\x81\xa8\xe3\x81\x97\xe3\x81\xa6 wit/deps/authz/ \xe3\x81\xab\xe7\xbd\xae\xe3\x81\x84\xe3\x81\xa6\xe3\x81\x84\xe3\x81\xbe\xe3\x81\x99\n// - authz \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xaf\xe3\x81\x93\xe3\x81\xae\xe3\x83\x87\xe3\x82\xa3\xe3\x83\xac\xe3\x82\xaf\xe3\x83\x88\xe3\x83\xaa\xe3\x81\xa0\xe3\x81\x91\xe3\x82\x92\xe5\x8f\x82\xe7\x85\xa7\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\n//\n// =============================================================================\n\n// -----------------------------------------------------------------------------\n// \xe3\x83\x91\xe3\x83\x83\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb8\xe5\xae\xa3\xe8\xa8\x80\n// -----------------------------------------------------------------------------\npackage demo:authz@0.1.0;\n\n// =============================================================================\n// policy \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\n// =============================================================================\n\n/// \xe8\xaa\x8d\xe5\x8f\xaf\xe5\x88\xa4\xe5\xae\x9a\xe3\x82\x92\xe6\x8f\x90\xe4\xbe\x9b\xe3\x81\x99\xe3\x82\x8b\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\ninterface policy {\n    /// \xe8\xaa\x8d\xe5\x8f\xaf\xe3\x81\xae\xe5\x88\xa4\xe5\xae\x9a\xe7\xb5\x90\xe6\x9e\x9c\n    record decision {\n        /// \xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x82\x92\xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x8b\xe3\x81\xa9\xe3\x81\x86\xe3\x81\x8b\n        allowed: bool,\n\n        /// \xe6\x8b\x92\xe5\x90\xa6\xe3\x81\x97\xe3\x81\x9f\xe7\x90\x86\xe7\x94\xb1\xef\xbc\x88403 \xe3\x83\xac\xe3\x82\xb9\xe3\x83\x9d\xe3\x83\xb3\xe3\x82\xb9\xe3\x81\xae\xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\xe3\x83\xa1\xe3\x83\x83\xe3\x82\xbb\xe3\x83\xbc\xe3\x82\xb8\xe3\x81\xab\xe4\xbd\xbf\xe7\x94\xa8\xef\xbc\x89\n        /// \xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x97\xe3\x81\x9f\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf none\n        reason: option<string>,\n    }\n\n    /// \xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x82\x92\xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x8b\xe3\x81\xa9\xe3\x81\x86\xe3\x81\x8b\xe3\x82\x92\xe5\x88\xa4\xe5\xae\x9a\xe3\x81\x99\xe3\x82\x8b\n    ///\n    /// \xe3\x83\xab\xe3\x83\xbc\xe3\x83\xab\xe3\x81\xaf Spin \xe5\xa4\x89\xe6\x95\xb0 `authz_rules`\xef\xbc\x88JSON: \xe3\x83\xad\xe3\x83\xbc\xe3\x83\xab \xe2\x86\x92 \xe3\x83\xa1\xe3\x82\xbd\xe3\x83\x83\xe3\x83\x89\xe3\x81\xa8\xe3\x83\x91\xe3\x82\xb9\xe3\x83\x97\xe3\x83\xac\xe3\x83\x95\xe3\x82\xa3\xe3\x83\x83\xe3\x82\xaf\xe3\x82\xb9\xe3\x81\xae\xe8\xa8\xb1\xe5\x8f\xaf\xe3\x83\xaa\xe3\x82\xb9\xe3\x83\x88\xef\xbc\x89\n    /// \xe3\x81\x8b\xe3\x82\x89\xe8\xaa\xad\xe3\x81\xbf\xe8\xbe\xbc\xe3\x81\xbf\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\xe3\x81\x84\xe3\x81\x9a\xe3\x82\x8c\xe3\x81\x8b\xe3\x81\xae\xe3\x83\xad\xe3\x83\xbc\xe3\x83\xab\xe3\x81\xae\xe3\x83\xab\xe3\x83\xbc\xe3\x83\xab\xe3\x81\xab\xe4\xb8\x80\xe8\x87\xb4\xe3\x81\x99\xe3\x82\x8c\xe3\x81\xb0\xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    ///\n    /// # \xe5\xbc\x95\xe6\x95\xb0\n    /// * `user-id` - \xe8\xaa\x8d\xe8\xa8\xbc\xe6\xb8\x88\xe3\x81\xbf\xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbcID\xef\xbc\x88\xe3\x83\xad\xe3\x82\xb0\xe7\x94\xa8\xef\xbc\x89\n    /// * `roles` - \xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x81\xae\xe3\x83\xad\xe3\x83\xbc\xe3\x83\xab\xef\xbc\x88\xe5\x85\xa8\xe3\x81\xa6\xe3\x81\xae\xe8\xaa\x8d\xe8\xa8\xbc\xe6\xb8\x88\xe3\x81\xbf\xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbc\xe3\x81\xab\xe3\x81\xaf \"*\" \xe3\x81\xae\xe3\x83\xab\xe3\x83\xbc\xe3\x83\xab\xe3\x82\x82\xe9\x81\xa9\xe7\x94\xa8\xef\xbc\x89\n    /// * `method` - HTTP \xe3\x83\xa1\xe3\x82\xbd\xe3\x83\x83\xe3\x83\x89\xef\xbc\x88\xe4\xbe\x8b: \"GET\"\xef\xbc\x89\n    /// * `path` - \xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x83\x91\xe3\x82\xb9\xef\xbc\x88\xe4\xbe\x8b: \"/api/todos/1\"\xe3\x80\x81\xe3\x82\xaf\xe3\x82\xa8\xe3\x83\xaa\xe6\x96\x87\xe5\xad\x97\xe5\x88\x97\xe3\x82\x92\xe5\x90\xab\xe3\x81\xbe\xe3\x81\xaa\xe3\x81\x84\xef\xbc\x89\n    ///\n    /// # \xe6\x88\xbb\xe3\x82\x8a\xe5\x80\xa4\n    /// * `decision` - \xe5\x88\xa4\xe5\xae\x9a\xe7\xb5\x90\xe6\x9e\x9c\n    ///\n    /// # \xe9\x9a\x9c\xe5\xae\xb3\xe6\x99\x82\n    /// \xe3\x83\xab\xe3\x83\xbc\xe3\x83\xab\xe3\x81\x8c\xe8\xaa\xad\xe3\x81\xbf\xe8\xbe\xbc\xe3\x82\x81\xe3\x81\xaa\xe3\x81\x84\xe3\x83\xbb\xe8\xa7\xa3\xe6\x9e\x90\xe3\x81\xa7\xe3\x81\x8d\xe3\x81\xaa\xe3\x81\x84\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf\xe6\x8b\x92\xe5\x90\xa6\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xef\xbc\x88\xe3\x83\x95\xe3\x82\xa7\xe3\x82\xa4\xe3\x83\xab\xe3\x82\xaf\xe3\x83\xad\xe3\x83\xbc\xe3\x82\xba\xef\xbc\x89\xe3\x80\x82\n    /// \xe8\xa8\xad\xe5\xae\x9a\xe3\x81\xae\xe8\xaa\xa4\xe3\x82\x8a\xe3\x81\xa7\xe5\x85\xa8\xe3\x81\xa6\xe3\x81\xae\xe6\x93\x8d\xe4\xbd\x9c\xe3\x82\x92\xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x97\xe3\x81\xa6\xe3\x81\x97\xe3\x81\xbe\xe3\x82\x8f\xe3\x81\xaa\xe3\x81\x84\xe3\x81\x9f\xe3\x82\x81\xe3\x81\xa7\xe3\x81\x99\xe3\x80\x82\n    allow: func(user-id: string, roles: list<string>, method: string, path: string) -> decision;\n}\n\n// =============================================================================\n// World \xe5\xae\x9a\xe7\xbe\xa9\n// =============================================================================\n\n/// authz \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe5\xae\x9f\xe8\xa3\x85\xe3\x81\x99\xe3\x82\x8b world\n///\n/// policy \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe3\x82\xa8\xe3\x82\xaf\xe3\x82\xb9\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\nworld authz-world {\n    export policy;\n}\n";
+const _: &[u8] = b"// =============================================================================\n// WIT \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe5\xae\x9a\xe7\xbe\xa9: \xe3\x83\xac\xe3\x83\xbc\xe3\x83\x88\xe5\x88\xb6\xe9\x99\x90\n// =============================================================================\n//\n// \xe3\x83\xac\xe3\x83\xbc\xe3\x83\x88\xe5\x88\xb6\xe9\x99\x90\xe3\x82\x92\xe7\x8b\xac\xe7\xab\x8b\xe3\x81\x97\xe3\x81\x9f Wasm \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xef\xbc\x88ratelimit\xef\xbc\x89\xe3\x81\xa8\xe3\x81\x97\xe3\x81\xa6\xe6\x8f\x90\xe4\xbe\x9b\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x9f\xe3\x82\x81\xe3\x81\xae\n// \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x81\xa7\xe3\x81\x99\xe3\x80\x82gateway \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe3\x82\xa4\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xe3\x81\x97\xe3\x80\x81\n// \xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbc\xe5\x8d\x98\xe4\xbd\x8d\xe3\x83\xbbIP \xe5\x8d\x98\xe4\xbd\x8d\xe3\x81\xae\xe5\x88\xb6\xe9\x99\x90\xe3\x81\xab\xe4\xbd\xbf\xe7\x94\xa8\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n//\n// \xe9\x85\x8d\xe7\xbd\xae:\n// - demo:auth\xef\xbc\x88wit/auth.wit\xef\xbc\x89\xe3\x81\xae gateway-world \xe3\x81\x8b\xe3\x82\x89\xe3\x82\xa4\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x9f\xe3\x82\x81\xe3\x80\x81\n//   \xe4\xbe\x9d\xe5\xad\x98\xe3\x83\x91\xe3\x83\x83\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb8\xe3\x81\xa8\xe3\x81\x97\xe3\x81\xa6 wit/deps/ratelimit/ \xe3\x81\xab\xe7\xbd\xae\xe3\x81\x84\xe3\x81\xa6\xe3\x81\x84\xe3\x81\xbe\xe3\x81\x99\n// - ratelimit \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xaf\xe3\x81\x93\xe3\x81\xae\xe3\x83\x87\xe3\x82\xa3\xe3\x83\xac\xe3\x82\xaf\xe3\x83\x88\xe3\x83\xaa\xe3\x81\xa0\xe3\x81\x91\xe3\x82\x92\xe5\x8f\x82\xe7\x85\xa7\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\n//\n// =============================================================================\n\n// -----------------------------------------------------------------------------\n// \xe3\x83\x91\xe3\x83\x83\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb8\xe5\xae\xa3\xe8\xa8\x80\n// -----------------------------------------------------------------------------\npackage demo:ratelimit@0.1.0;\n\n// =============================================================================\n// limiter \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\n// =============================================================================\n\n/// \xe3\x83\xac\xe3\x83\xbc\xe3\x83\x88\xe5\x88\xb6\xe9\x99\x90\xe3\x82\x92\xe6\x8f\x90\xe4\xbe\x9b\xe3\x81\x99\xe3\x82\x8b\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\ninterface limiter {\n    /// \xe3\x83\xac\xe3\x83\xbc\xe3\x83\x88\xe5\x88\xb6\xe9\x99\x90\xe3\x81\xae\xe5\x88\xa4\xe5\xae\x9a\xe7\xb5\x90\xe6\x9e\x9c\n    record check-result {\n        /// \xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x82\x92\xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x8b\xe3\x81\xa9\xe3\x81\x86\xe3\x81\x8b\n        allowed: bool,\n\n        /// \xe7\x8f\xbe\xe5\x9c\xa8\xe3\x81\xae\xe3\x82\xa6\xe3\x82\xa3\xe3\x83\xb3\xe3\x83\x89\xe3\x82\xa6\xe3\x81\xa7\xe6\xae\x8b\xe3\x81\xa3\xe3\x81\xa6\xe3\x81\x84\xe3\x82\x8b\xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe6\x95\xb0\n        /// \xe6\x8b\x92\xe5\x90\xa6\xe3\x81\x97\xe3\x81\x9f\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf 0\n        remaining: u32,\n\n        /// \xe6\xac\xa1\xe3\x81\xae\xe3\x82\xa6\xe3\x82\xa3\xe3\x83\xb3\xe3\x83\x89\xe3\x82\xa6\xe3\x81\xbe\xe3\x81\xa7\xe3\x81\xae\xe7\xa7\x92\xe6\x95\xb0\xef\xbc\x88Retry-After \xe3\x83\x98\xe3\x83\x83\xe3\x83\x80\xe3\x83\xbc\xe3\x81\xae\xe5\x80\xa4\xef\xbc\x89\n        /// \xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x97\xe3\x81\x9f\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf 0\n        retry-after: u32,\n    }\n\n    /// \xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x82\x92 1 \xe4\xbb\xb6\xe6\x95\xb0\xe3\x81\x88\xe3\x80\x81\xe5\x88\xb6\xe9\x99\x90\xe5\x86\x85\xe3\x81\x8b\xe3\x81\xa9\xe3\x81\x86\xe3\x81\x8b\xe3\x82\x92\xe5\x88\xa4\xe5\xae\x9a\xe3\x81\x99\xe3\x82\x8b\n    ///\n    /// \xe5\x9b\xba\xe5\xae\x9a\xe3\x82\xa6\xe3\x82\xa3\xe3\x83\xb3\xe3\x83\x89\xe3\x82\xa6\xe6\x96\xb9\xe5\xbc\x8f: window-seconds \xe3\x81\x94\xe3\x81\xa8\xe3\x81\xae\xe5\x8c\xba\xe9\x96\x93\xe3\x81\xa7\xe3\x80\x81key \xe3\x81\x94\xe3\x81\xa8\xe3\x81\xab limit \xe4\xbb\xb6\xe3\x81\xbe\xe3\x81\xa7\xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    /// \xe3\x82\xab\xe3\x82\xa6\xe3\x83\xb3\xe3\x82\xbf\xe3\x81\xaf Spin \xe3\x81\xae key-value \xe3\x82\xb9\xe3\x83\x88\xe3\x82\xa2\xe3\x81\xab\xe3\x81\x82\xe3\x82\x8a\xe3\x80\x81\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xb9\xe3\x82\xbf\xe3\x83\xb3\xe3\x82\xb9\xe9\x96\x93\xe3\x81\xa7\xe5\x85\xb1\xe6\x9c\x89\xe3\x81\x95\xe3\x82\x8c\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    ///\n    /// # \xe5\xbc\x95\xe6\x95\xb0\n    /// * `key` - \xe5\x88\xb6\xe9\x99\x90\xe3\x81\xae\xe5\x8d\x98\xe4\xbd\x8d\xef\xbc\x88\xe4\xbe\x8b: \"user:{\xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbcID}\", \"ip:{IP \xe3\x82\xa2\xe3\x83\x89\xe3\x83\xac\xe3\x82\xb9}\"\xef\xbc\x89\n    /// * `limit` - \xe3\x82\xa6\xe3\x82\xa3\xe3\x83\xb3\xe3\x83\x89\xe3\x82\xa6\xe3\x81\x82\xe3\x81\x9f\xe3\x82\x8a\xe3\x81\xae\xe4\xb8\x8a\xe9\x99\x90\xef\xbc\x880 \xe3\x81\xae\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf\xe5\xb8\xb8\xe3\x81\xab\xe6\x8b\x92\xe5\x90\xa6\xef\xbc\x89\n    /// * `window-seconds` - \xe3\x82\xa6\xe3\x82\xa3\xe3\x83\xb3\xe3\x83\x89\xe3\x82\xa6\xe3\x81\xae\xe9\x95\xb7\xe3\x81\x95\xef\xbc\x88\xe7\xa7\x92\xe3\x80\x810 \xe3\x81\xae\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf 1 \xe7\xa7\x92\xe3\x81\xa8\xe3\x81\x97\xe3\x81\xa6\xe6\x89\xb1\xe3\x81\x86\xef\xbc\x89\n    ///\n    /// # \xe6\x88\xbb\xe3\x82\x8a\xe5\x80\xa4\n    /// * `check-result` - \xe5\x88\xa4\xe5\xae\x9a\xe7\xb5\x90\xe6\x9e\x9c\n    ///\n    /// # \xe9\x9a\x9c\xe5\xae\xb3\xe6\x99\x82\n    /// key-value \xe3\x82\xb9\xe3\x83\x88\xe3\x82\xa2\xe3\x81\xab\xe3\x82\xa2\xe3\x82\xaf\xe3\x82\xbb\xe3\x82\xb9\xe3\x81\xa7\xe3\x81\x8d\xe3\x81\xaa\xe3\x81\x84\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf\xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xef\xbc\x88\xe3\x83\x95\xe3\x82\xa7\xe3\x82\xa4\xe3\x83\xab\xe3\x82\xaa\xe3\x83\xbc\xe3\x83\x97\xe3\x83\xb3\xef\xbc\x89\xe3\x80\x82\n    /// \xe3\x83\xac\xe3\x83\xbc\xe3\x83\x88\xe5\x88\xb6\xe9\x99\x90\xe3\x81\xae\xe9\x9a\x9c\xe5\xae\xb3\xe3\x81\xa7\xe5\x85\xa8\xe3\x81\xa6\xe3\x81\xae\xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x82\x92\xe6\xad\xa2\xe3\x82\x81\xe3\x81\xaa\xe3\x81\x84\xe3\x81\x9f\xe3\x82\x81\xe3\x81\xa7\xe3\x81\x99\xe3\x80\x82\n    check: func(key: string, limit: u32, window-seconds: u32) -> check-result;\n}\n\n// =============================================================================\n// World \xe5\xae\x9a\xe7\xbe\xa9\n// =============================================================================\n\n/// ratelimit \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe5\xae\x9f\xe8\xa3\x85\xe3\x81\x99\xe3\x82\x8b world\n///\n/// limiter \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe3\x82\xa8\xe3\x82\xaf\xe3\x82\xb9\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\nworld ratelimit-world {\n    export limiter;\n}\n";
+use exports::demo::auth::authenticator::{AuthErrorCode, AuthResult, Guest};
+use exports::demo::auth::token_issuer::Guest as TokenIssuerGuest;
+use exports::demo::auth::metrics::Guest as MetricsGuest;
+mod jwks {
+    //! # JWKS（ID プロバイダーの公開鍵）の取得とキャッシュ
+    //!
+    //! Spin 変数 `jwt_jwks_url` を指定した場合、ID プロバイダーが公開する JWKS を Spin の outbound HTTP で取得し、
+    //! key-value ストア（`default`）にキャッシュします。トークンのヘッダーの kid で鍵を選ぶため、
+    //! ID プロバイダーが鍵をローテーションしても `jwt_rs256_public_key` などを書き換える必要はありません。
+    //!
+    //! | 項目 | 内容 |
+    //! |------|------|
+    //! | キー | `auth:jwks:<JWKS の URL>` |
+    //! | 値 | 1 行目: 取得時刻と期限（Unix タイムスタンプ秒、空白区切り）、2 行目以降: JWKS の JSON |
+    //! | 期間 | レスポンスの `Cache-Control: max-age`（ない場合は 300 秒、上限 1 日、`no-cache` / `no-store` は 0 秒） |
+    //! | 不明な kid | 1 回だけ取得し直す（前回の取得から 30 秒以内の場合は取得しない） |
+    //! | 取得の失敗 | 期限切れでもキャッシュした JWKS を使う（キャッシュがない場合のみ検証に失敗する） |
+    //!
+    //! Spin の key-value ストアには TTL がないため、期限を値に含めて読み込み時に判定します。
+    //! 期限切れの JWKS も削除せず、ID プロバイダーに接続できない間の代わりとして残します。
+    //! 不明な kid による取得し直しに間隔を設け、任意の kid のトークンを送り続けて
+    //! ID プロバイダーへのリクエストを増やす攻撃を防ぎます。
+    use jwt::JwkSet;
+    use spin_sdk::http::{Method, Request, Response};
+    use spin_sdk::wit::wasi::keyvalue::store;
+    use crate::STORE_NAME;
+    /// キャッシュのキーのプレフィックス
+    ///
+    /// 同じストアを使う auth のメトリクス・gateway・ratelimit のキーと衝突しないようにする。
+    const KEY_PREFIX: &str = "auth:jwks";
+    /// Cache-Control に max-age がない場合のキャッシュの期間（秒）
+    const DEFAULT_TTL_SECS: u64 = 300;
+    /// キャッシュの期間の上限（秒）
+    ///
+    /// max-age が長すぎても、失効した鍵を 1 日以上使い続けないようにする。
+    const MAX_TTL_SECS: u64 = 86_400;
+    /// 不明な kid で取得し直す間隔の下限（秒）
+    const MIN_REFRESH_INTERVAL_SECS: u64 = 30;
+    /// キャッシュの保存先
+    ///
+    /// テストではメモリ上の実装に差し替え、key-value ストアなしで検証します。
+    pub(crate) trait Store {
+        /// キーの値を取得する（キーがない場合は None）
+        fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String>;
+        /// キーに値を設定する
+        fn set(&self, key: &str, value: &[u8]) -> Result<(), String>;
     }
-}
-#[doc(hidden)]
-#[allow(
-    non_upper_case_globals,
-    unused_attributes,
-    unused_qualifications,
-    clippy::absolute_paths,
-)]
-const _: () = {
-    #[allow(unused_extern_crates, clippy::useless_attribute)]
-    extern crate serde as _serde;
-    #[automatically_derived]
-    impl<'de> _serde::Deserialize<'de> for JwtHeader {
-        fn deserialize<__D>(
-            __deserializer: __D,
-        ) -> _serde::__private228::Result<Self, __D::Error>
-        where
-            __D: _serde::Deserializer<'de>,
-        {
-            #[allow(non_camel_case_types)]
-            #[doc(hidden)]
-            enum __Field {
-                __field0,
-                __field1,
-                __ignore,
-            }
-            #[doc(hidden)]
-            struct __FieldVisitor;
-            #[automatically_derived]
-            impl<'de> _serde::de::Visitor<'de> for __FieldVisitor {
-                type Value = __Field;
-                fn expecting(
-                    &self,
-                    __formatter: &mut _serde::__private228::Formatter,
-                ) -> _serde::__private228::fmt::Result {
-                    _serde::__private228::Formatter::write_str(
-                        __formatter,
-                        "field identifier",
-                    )
-                }
-                fn visit_u64<__E>(
-                    self,
-                    __value: u64,
-                ) -> _serde::__private228::Result<Self::Value, __E>
-                where
-                    __E: _serde::de::Error,
-                {
-                    match __value {
-                        0u64 => _serde::__private228::Ok(__Field::__field0),
-                        1u64 => _serde::__private228::Ok(__Field::__field1),
-                        _ => _serde::__private228::Ok(__Field::__ignore),
-                    }
-                }
-                fn visit_str<__E>(
-                    self,
-                    __value: &str,
-                ) -> _serde::__private228::Result<Self::Value, __E>
-                where
-                    __E: _serde::de::Error,
-                {
-                    match __value {
-                        "alg" => _serde::__private228::Ok(__Field::__field0),
-                        "typ" => _serde::__private228::Ok(__Field::__field1),
-                        _ => _serde::__private228::Ok(__Field::__ignore),
-                    }
-                }
-                fn visit_bytes<__E>(
-                    self,
-                    __value: &[u8],
-                ) -> _serde::__private228::Result<Self::Value, __E>
-                where
-                    __E: _serde::de::Error,
-                {
-                    match __value {
-                        b"alg" => _serde::__private228::Ok(__Field::__field0),
-                        b"typ" => _serde::__private228::Ok(__Field::__field1),
-                        _ => _serde::__private228::Ok(__Field::__ignore),
-                    }
-                }
-            }
-            #[automatically_derived]
-            impl<'de> _serde::Deserialize<'de> for __Field {
-                #[inline]
-                fn deserialize<__D>(
-                    __deserializer: __D,
-                ) -> _serde::__private228::Result<Self, __D::Error>
-                where
-                    __D: _serde::Deserializer<'de>,
-                {
-                    _serde::Deserializer::deserialize_identifier(
-                        __deserializer,
-                        __FieldVisitor,
-                    )
-                }
-            }
-            #[doc(hidden)]
-            struct __Visitor<'de> {
-                marker: _serde::__private228::PhantomData<JwtHeader>,
-                lifetime: _serde::__private228::PhantomData<&'de ()>,
-            }
-            #[automatically_derived]
-            impl<'de> _serde::de::Visitor<'de> for __Visitor<'de> {
-                type Value = JwtHeader;
-                fn expecting(
-                    &self,
-                    __formatter: &mut _serde::__private228::Formatter,
-                ) -> _serde::__private228::fmt::Result {
-                    _serde::__private228::Formatter::write_str(
-                        __formatter,
-                        "struct JwtHeader",
+    /// Spin の key-value ストアを使う保存先
+    pub(crate) struct KvStore {
+        bucket: store::Bucket,
+    }
+    impl KvStore {
+        /// key-value ストアを開く（失効リストの確認にも使う）
+        pub(crate) fn open() -> Result<Self, String> {
+            store::open(STORE_NAME)
+                .map(|bucket| Self { bucket })
+                .map_err(|e| ::alloc::__export::must_use({
+                    ::alloc::fmt::format(
+                        format_args!("Failed to open key-value store: {0:?}", e),
                     )
-                }
-                #[inline]
-                fn visit_seq<__A>(
-                    self,
-                    mut __seq: __A,
-                ) -> _serde::__private228::Result<Self::Value, __A::Error>
-                where
-                    __A: _serde::de::SeqAccess<'de>,
-                {
-                    let __field0 = match _serde::de::SeqAccess::next_element::<
-                        String,
-                    >(&mut __seq)? {
-                        _serde::__private228::Some(__value) => __value,
-                        _serde::__private228::None => {
-                            return _serde::__private228::Err(
-                                _serde::de::Error::invalid_length(
-                                    0usize,
-                                    &"struct JwtHeader with 2 elements",
-                                ),
-                            );
-                        }
-                    };
-                    let __field1 = match _serde::de::SeqAccess::next_element::<
-                        Option<String>,
-                    >(&mut __seq)? {
-                        _serde::__private228::Some(__value) => __value,
-                        _serde::__private228::None => {
-                            return _serde::__private228::Err(
-                                _serde::de::Error::invalid_length(
-                                    1usize,
-                                    &"struct JwtHeader with 2 elements",
-                                ),
-                            );
-                        }
-                    };
-                    _serde::__private228::Ok(JwtHeader {
-                        alg: __field0,
-                        typ: __field1,
-                    })
-                }
-                #[inline]
-                fn visit_map<__A>(
-                    self,
-                    mut __map: __A,
-                ) -> _serde::__private228::Result<Self::Value, __A::Error>
-                where
-                    __A: _serde::de::MapAccess<'de>,
-                {
-                    let mut __field0: _serde::__private228::Option<String> = _serde::__private228::None;
-                    let mut __field1: _serde::__private228::Option<Option<String>> = _serde::__private228::None;
-                    while let _serde::__private228::Some(__key) = _serde::de::MapAccess::next_key::<
-                        __Field,
-                    >(&mut __map)? {
-                        match __key {
-                            __Field::__field0 => {
-                                if _serde::__private228::Option::is_some(&__field0) {
-                                    return _serde::__private228::Err(
-                                        <__A::Error as _serde::de::Error>::duplicate_field("alg"),
-                                    );
-                                }
-                                __field0 = _serde::__private228::Some(
-                                    _serde::de::MapAccess::next_value::<String>(&mut __map)?,
-                                );
-                            }
-                            __Field::__field1 => {
-                                if _serde::__private228::Option::is_some(&__field1) {
-                                    return _serde::__private228::Err(
-                                        <__A::Error as _serde::de::Error>::duplicate_field("typ"),
-                                    );
-                                }
-                                __field1 = _serde::__private228::Some(
-                                    _serde::de::MapAccess::next_value::<
-                                        Option<String>,
-                                    >(&mut __map)?,
-                                );
-                            }
-                            _ => {
-                                let _ = _serde::de::MapAccess::next_value::<
-                                    _serde::de::IgnoredAny,
-                                >(&mut __map)?;
-                            }
-                        }
-                    }
-                    let __field0 = match __field0 {
-                        _serde::__private228::Some(__field0) => __field0,
-                        _serde::__private228::None => {
-                            _serde::__private228::de::missing_field("alg")?
-                        }
-                    };
-                    let __field1 = match __field1 {
-                        _serde::__private228::Some(__field1) => __field1,
-                        _serde::__private228::None => {
-                            _serde::__private228::de::missing_field("typ")?
-                        }
-                    };
-                    _serde::__private228::Ok(JwtHeader {
-                        alg: __field0,
-                        typ: __field1,
-                    })
-                }
-            }
-            #[doc(hidden)]
-            const FIELDS: &'static [&'static str] = &["alg", "typ"];
-            _serde::Deserializer::deserialize_struct(
-                __deserializer,
-                "JwtHeader",
-                FIELDS,
-                __Visitor {
-                    marker: _serde::__private228::PhantomData::<JwtHeader>,
-                    lifetime: _serde::__private228::PhantomData,
-                },
-            )
+                }))
         }
     }
-};
-/// JWT ペイロード（クレーム）を表す構造体
-///
-/// JWT のペイロード部分には、トークンに関する情報（クレーム）が含まれます。
-/// 標準クレーム（RFC 7519）の一部を定義しています。
-struct JwtPayload {
-    /// Subject（サブジェクト）: トークンの主体を識別
-    /// 通常はユーザーIDを格納
-    sub: Option<String>,
-    /// Expiration Time（有効期限）: Unix タイムスタンプ（秒）
-    /// この時刻を過ぎるとトークンは無効
-    exp: Option<u64>,
-    /// Issued At（発行時刻）: Unix タイムスタンプ（秒）
-    /// トークンがいつ発行されたかを示す
-    /// 検証には使用しないため、dead_code 警告を抑制
-    #[allow(dead_code)]
-    iat: Option<u64>,
-}
-#[automatically_derived]
-impl ::core::fmt::Debug for JwtPayload {
-    #[inline]
-    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
-        ::core::fmt::Formatter::debug_struct_field3_finish(
-            f,
-            "JwtPayload",
-            "sub",
-            &self.sub,
-            "exp",
-            &self.exp,
-            "iat",
-            &&self.iat,
-        )
+    impl Store for KvStore {
+        fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+            self.bucket
+                .get(key)
+                .map_err(|e| ::alloc::__export::must_use({
+                    ::alloc::fmt::format(format_args!("Failed to get {0}: {1:?}", key, e))
+                }))
+        }
+        fn set(&self, key: &str, value: &[u8]) -> Result<(), String> {
+            self.bucket
+                .set(key, value)
+                .map_err(|e| ::alloc::__export::must_use({
+                    ::alloc::fmt::format(format_args!("Failed to set {0}: {1:?}", key, e))
+                }))
+        }
+    }
+    /// 取得した JWKS
+    pub(crate) struct Fetched {
+        /// レスポンスのボディ（JWKS の JSON）
+        body: String,
+        /// キャッシュの期間（秒、cache_ttl で計算した値）
+        ttl_secs: u64,
     }
-}
-#[doc(hidden)]
-#[allow(
-    non_upper_case_globals,
-    unused_attributes,
-    unused_qualifications,
-    clippy::absolute_paths,
-)]
-const _: () = {
-    #[allow(unused_extern_crates, clippy::useless_attribute)]
-    extern crate serde as _serde;
     #[automatically_derived]
-    impl<'de> _serde::Deserialize<'de> for JwtPayload {
-        fn deserialize<__D>(
-            __deserializer: __D,
-        ) -> _serde::__private228::Result<Self, __D::Error>
-        where
-            __D: _serde::Deserializer<'de>,
-        {
-            #[allow(non_camel_case_types)]
-            #[doc(hidden)]
-            enum __Field {
-                __field0,
-                __field1,
-                __field2,
-                __ignore,
-            }
-            #[doc(hidden)]
-            struct __FieldVisitor;
-            #[automatically_derived]
-            impl<'de> _serde::de::Visitor<'de> for __FieldVisitor {
-                type Value = __Field;
-                fn expecting(
-                    &self,
-                    __formatter: &mut _serde::__private228::Formatter,
-                ) -> _serde::__private228::fmt::Result {
-                    _serde::__private228::Formatter::write_str(
-                        __formatter,
-                        "field identifier",
-                    )
-                }
-                fn visit_u64<__E>(
-                    self,
-                    __value: u64,
-                ) -> _serde::__private228::Result<Self::Value, __E>
-                where
-                    __E: _serde::de::Error,
-                {
-                    match __value {
-                        0u64 => _serde::__private228::Ok(__Field::__field0),
-                        1u64 => _serde::__private228::Ok(__Field::__field1),
-                        2u64 => _serde::__private228::Ok(__Field::__field2),
-                        _ => _serde::__private228::Ok(__Field::__ignore),
-                    }
-                }
-                fn visit_str<__E>(
-                    self,
-                    __value: &str,
-                ) -> _serde::__private228::Result<Self::Value, __E>
-                where
-                    __E: _serde::de::Error,
-                {
-                    match __value {
-                        "sub" => _serde::__private228::Ok(__Field::__field0),
-                        "exp" => _serde::__private228::Ok(__Field::__field1),
-                        "iat" => _serde::__private228::Ok(__Field::__field2),
-                        _ => _serde::__private228::Ok(__Field::__ignore),
-                    }
-                }
-                fn visit_bytes<__E>(
-                    self,
-                    __value: &[u8],
-                ) -> _serde::__private228::Result<Self::Value, __E>
-                where
-                    __E: _serde::de::Error,
-                {
-                    match __value {
-                        b"sub" => _serde::__private228::Ok(__Field::__field0),
-                        b"exp" => _serde::__private228::Ok(__Field::__field1),
-                        b"iat" => _serde::__private228::Ok(__Field::__field2),
-                        _ => _serde::__private228::Ok(__Field::__ignore),
-                    }
-                }
+    impl ::core::fmt::Debug for Fetched {
+        #[inline]
+        fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+            ::core::fmt::Formatter::debug_struct_field2_finish(
+                f,
+                "Fetched",
+                "body",
+                &self.body,
+                "ttl_secs",
+                &&self.ttl_secs,
+            )
+        }
+    }
+    /// JWKS の取得元
+    ///
+    /// テストでは固定のレスポンス・失敗を返す実装に差し替えます。
+    pub(crate) trait Fetcher {
+        /// URL から JWKS を取得する
+        fn fetch(&self, url: &str) -> Result<Fetched, String>;
+    }
+    /// Spin の outbound HTTP で取得する
+    ///
+    /// 取得先のホストは gateway の allowed_outbound_hosts に追加する
+    /// （auth は dependencies_inherit_configuration で gateway の設定を引き継ぐ）。
+    pub(crate) struct HttpFetcher;
+    impl Fetcher for HttpFetcher {
+        fn fetch(&self, url: &str) -> Result<Fetched, String> {
+            let request = Request::builder()
+                .method(Method::Get)
+                .uri(url)
+                .header("Accept", "application/json")
+                .build();
+            let response = spin_executor::run(
+                    spin_sdk::http::send::<_, Response>(request),
+                )
+                .map_err(|e| ::alloc::__export::must_use({
+                    ::alloc::fmt::format(format_args!("Failed to fetch JWKS: {0}", e))
+                }))?;
+            let status = *response.status();
+            if !(200..300).contains(&status) {
+                return Err(
+                    ::alloc::__export::must_use({
+                        ::alloc::fmt::format(
+                            format_args!("Failed to fetch JWKS: status {0}", status),
+                        )
+                    }),
+                );
             }
-            #[automatically_derived]
-            impl<'de> _serde::Deserialize<'de> for __Field {
-                #[inline]
-                fn deserialize<__D>(
-                    __deserializer: __D,
-                ) -> _serde::__private228::Result<Self, __D::Error>
-                where
-                    __D: _serde::Deserializer<'de>,
-                {
-                    _serde::Deserializer::deserialize_identifier(
-                        __deserializer,
-                        __FieldVisitor,
-                    )
-                }
+            let cache_control = response
+                .header("cache-control")
+                .and_then(|value| value.as_str());
+            Ok(Fetched {
+                ttl_secs: cache_ttl(cache_control),
+                body: String::from_utf8(response.body().to_vec())
+                    .map_err(|_| "Failed to fetch JWKS: body is not UTF-8".to_string())?,
+            })
+        }
+    }
+    /// キャッシュした JWKS
+    struct CachedJwks {
+        /// 取得した時刻（Unix タイムスタンプ秒）
+        fetched_at: u64,
+        /// 取得し直さずに使ってよい期限（Unix タイムスタンプ秒）
+        expires_at: u64,
+        /// 解析した JWKS
+        jwks: JwkSet,
+    }
+    #[automatically_derived]
+    impl ::core::fmt::Debug for CachedJwks {
+        #[inline]
+        fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+            ::core::fmt::Formatter::debug_struct_field3_finish(
+                f,
+                "CachedJwks",
+                "fetched_at",
+                &self.fetched_at,
+                "expires_at",
+                &self.expires_at,
+                "jwks",
+                &&self.jwks,
+            )
+        }
+    }
+    /// JWKS のキャッシュ
+    pub(crate) struct JwksCache<S, F> {
+        store: S,
+        fetcher: F,
+        /// JWKS の URL（Spin 変数 jwt_jwks_url）
+        url: String,
+    }
+    impl JwksCache<KvStore, HttpFetcher> {
+        /// key-value ストアを開き、outbound HTTP で取得するキャッシュを作る
+        ///
+        /// # 引数
+        /// * `url` - JWKS の URL
+        pub(crate) fn open(url: &str) -> Result<Self, String> {
+            KvStore::open().map(|store| Self::new(store, HttpFetcher, url))
+        }
+    }
+    impl<S: Store, F: Fetcher> JwksCache<S, F> {
+        /// キャッシュを作る
+        ///
+        /// # 引数
+        /// * `store` - 保存先
+        /// * `fetcher` - 取得元
+        /// * `url` - JWKS の URL
+        fn new(store: S, fetcher: F, url: &str) -> Self {
+            Self {
+                store,
+                fetcher,
+                url: url.to_string(),
             }
-            #[doc(hidden)]
-            struct __Visitor<'de> {
-                marker: _serde::__private228::PhantomData<JwtPayload>,
-                lifetime: _serde::__private228::PhantomData<&'de ()>,
-            }
-            #[automatically_derived]
-            impl<'de> _serde::de::Visitor<'de> for __Visitor<'de> {
-                type Value = JwtPayload;
-                fn expecting(
-                    &self,
-                    __formatter: &mut _serde::__private228::Formatter,
-                ) -> _serde::__private228::fmt::Result {
-                    _serde::__private228::Formatter::write_str(
-                        __formatter,
-                        "struct JwtPayload",
-                    )
-                }
-                #[inline]
-                fn visit_seq<__A>(
-                    self,
-                    mut __seq: __A,
-                ) -> _serde::__private228::Result<Self::Value, __A::Error>
-                where
-                    __A: _serde::de::SeqAccess<'de>,
-                {
-                    let __field0 = match _serde::de::SeqAccess::next_element::<
-                        Option<String>,
-                    >(&mut __seq)? {
-                        _serde::__private228::Some(__value) => __value,
-                        _serde::__private228::None => {
-                            return _serde::__private228::Err(
-                                _serde::de::Error::invalid_length(
-                                    0usize,
-                                    &"struct JwtPayload with 3 elements",
-                                ),
-                            );
-                        }
-                    };
-                    let __field1 = match _serde::de::SeqAccess::next_element::<
-                        Option<u64>,
-                    >(&mut __seq)? {
-                        _serde::__private228::Some(__value) => __value,
-                        _serde::__private228::None => {
-                            return _serde::__private228::Err(
-                                _serde::de::Error::invalid_length(
-                                    1usize,
-                                    &"struct JwtPayload with 3 elements",
-                                ),
-                            );
-                        }
-                    };
-                    let __field2 = match _serde::de::SeqAccess::next_element::<
-                        Option<u64>,
-                    >(&mut __seq)? {
-                        _serde::__private228::Some(__value) => __value,
-                        _serde::__private228::None => {
-                            return _serde::__private228::Err(
-                                _serde::de::Error::invalid_length(
-                                    2usize,
-                                    &"struct JwtPayload with 3 elements",
-                                ),
-                            );
-                        }
+        }
+        /// kid の鍵を探すための JWKS を返す
+        ///
+        /// 1. キャッシュが期限内ならそれを使い、期限切れ・ない場合は取得する
+        ///    （取得に失敗した場合は期限切れのキャッシュを使う）
+        /// 2. kid の鍵がない場合は、ID プロバイダーが鍵をローテーションした直後の可能性があるため
+        ///    1 回だけ取得し直す（1 で取得した場合・前回の取得から MIN_REFRESH_INTERVAL_SECS 以内の場合を除く）
+        ///
+        /// # 引数
+        /// * `kid` - トークンのヘッダーの kid
+        /// * `now` - 現在時刻（Unix タイムスタンプ秒）
+        ///
+        /// # 戻り値
+        /// * `Some(JwkSet)` - JWKS（kid の鍵を含むとは限らない）
+        /// * `None` - 取得に失敗し、キャッシュもない場合
+        pub(crate) fn key_set(&self, kid: &str, now: u64) -> Option<JwkSet> {
+            let (cached, refreshed) = match self.read() {
+                Some(cached) if now < cached.expires_at => (Some(cached), false),
+                stale => (self.refresh(now).or(stale), true),
+            };
+            let cached = match cached {
+                Some(
+                    cached,
+                ) if cached.jwks.find(kid).is_none() && !refreshed
+                    && now
+                        >= cached.fetched_at.saturating_add(MIN_REFRESH_INTERVAL_SECS) => {
+                    {
+                        ::std::io::_print(
+                            format_args!("[Auth] Unknown kid {0:?}, refreshing JWKS\n", kid),
+                        );
                     };
-                    _serde::__private228::Ok(JwtPayload {
-                        sub: __field0,
-                        exp: __field1,
-                        iat: __field2,
-                    })
+                    Some(self.refresh(now).unwrap_or(cached))
                 }
-                #[inline]
-                fn visit_map<__A>(
-                    self,
-                    mut __map: __A,
-                ) -> _serde::__private228::Result<Self::Value, __A::Error>
-                where
-                    __A: _serde::de::MapAccess<'de>,
-                {
-                    let mut __field0: _serde::__private228::Option<Option<String>> = _serde::__private228::None;
-                    let mut __field1: _serde::__private228::Option<Option<u64>> = _serde::__private228::None;
-                    let mut __field2: _serde::__private228::Option<Option<u64>> = _serde::__private228::None;
-                    while let _serde::__private228::Some(__key) = _serde::de::MapAccess::next_key::<
-                        __Field,
-                    >(&mut __map)? {
-                        match __key {
-                            __Field::__field0 => {
-                                if _serde::__private228::Option::is_some(&__field0) {
-                                    return _serde::__private228::Err(
-                                        <__A::Error as _serde::de::Error>::duplicate_field("sub"),
-                                    );
-                                }
-                                __field0 = _serde::__private228::Some(
-                                    _serde::de::MapAccess::next_value::<
-                                        Option<String>,
-                                    >(&mut __map)?,
-                                );
-                            }
-                            __Field::__field1 => {
-                                if _serde::__private228::Option::is_some(&__field1) {
-                                    return _serde::__private228::Err(
-                                        <__A::Error as _serde::de::Error>::duplicate_field("exp"),
-                                    );
-                                }
-                                __field1 = _serde::__private228::Some(
-                                    _serde::de::MapAccess::next_value::<
-                                        Option<u64>,
-                                    >(&mut __map)?,
-                                );
-                            }
-                            __Field::__field2 => {
-                                if _serde::__private228::Option::is_some(&__field2) {
-                                    return _serde::__private228::Err(
-                                        <__A::Error as _serde::de::Error>::duplicate_field("iat"),
-                                    );
-                                }
-                                __field2 = _serde::__private228::Some(
-                                    _serde::de::MapAccess::next_value::<
-                                        Option<u64>,
-                                    >(&mut __map)?,
-                                );
-                            }
-                            _ => {
-                                let _ = _serde::de::MapAccess::next_value::<
-                                    _serde::de::IgnoredAny,
-                                >(&mut __map)?;
-                            }
-                        }
-                    }
-                    let __field0 = match __field0 {
-                        _serde::__private228::Some(__field0) => __field0,
-                        _serde::__private228::None => {
-                            _serde::__private228::de::missing_field("sub")?
-                        }
+                cached => cached,
+            };
+            cached.map(|cached| cached.jwks)
+        }
+        /// キャッシュした JWKS を読み込む（ない・壊れている場合は None）
+        fn read(&self) -> Option<CachedJwks> {
+            let value = match self.store.get(&self.key()) {
+                Ok(value) => value?,
+                Err(e) => {
+                    {
+                        ::std::io::_print(format_args!("[Auth] JWKS cache error: {0}\n", e));
                     };
-                    let __field1 = match __field1 {
-                        _serde::__private228::Some(__field1) => __field1,
-                        _serde::__private228::None => {
-                            _serde::__private228::de::missing_field("exp")?
-                        }
+                    return None;
+                }
+            };
+            let value = String::from_utf8(value).ok()?;
+            let (times, body) = value.split_once('\n')?;
+            let (fetched_at, expires_at) = times.split_once(' ')?;
+            Some(CachedJwks {
+                fetched_at: fetched_at.parse().ok()?,
+                expires_at: expires_at.parse().ok()?,
+                jwks: JwkSet::from_json(body).ok()?,
+            })
+        }
+        /// JWKS を取得してキャッシュに保存する
+        ///
+        /// 取得・解析に失敗した場合、使える鍵がない場合はログを出力して None を返す
+        /// （キャッシュは書き換えない）。
+        fn refresh(&self, now: u64) -> Option<CachedJwks> {
+            let fetched = match self.fetcher.fetch(&self.url) {
+                Ok(fetched) => fetched,
+                Err(e) => {
+                    {
+                        ::std::io::_print(
+                            format_args!(
+                                "[Auth] {0} ({1}), using cached JWKS if any\n", e, self.url
+                            ),
+                        );
                     };
-                    let __field2 = match __field2 {
-                        _serde::__private228::Some(__field2) => __field2,
-                        _serde::__private228::None => {
-                            _serde::__private228::de::missing_field("iat")?
-                        }
+                    return None;
+                }
+            };
+            let jwks = match JwkSet::from_json(&fetched.body) {
+                Ok(jwks) if !jwks.is_empty() => jwks,
+                _ => {
+                    {
+                        ::std::io::_print(
+                            format_args!(
+                                "[Auth] JWKS from {0} has no usable keys, using cached JWKS if any\n",
+                                self.url,
+                            ),
+                        );
                     };
-                    _serde::__private228::Ok(JwtPayload {
-                        sub: __field0,
-                        exp: __field1,
-                        iat: __field2,
-                    })
+                    return None;
                 }
+            };
+            let expires_at = now.saturating_add(fetched.ttl_secs);
+            let value = ::alloc::__export::must_use({
+                ::alloc::fmt::format(
+                    format_args!("{0} {1}\n{2}", now, expires_at, fetched.body),
+                )
+            });
+            if let Err(e) = self.store.set(&self.key(), value.as_bytes()) {
+                {
+                    ::std::io::_print(format_args!("[Auth] Failed to store JWKS: {0}\n", e));
+                };
             }
-            #[doc(hidden)]
-            const FIELDS: &'static [&'static str] = &["sub", "exp", "iat"];
-            _serde::Deserializer::deserialize_struct(
-                __deserializer,
-                "JwtPayload",
-                FIELDS,
-                __Visitor {
-                    marker: _serde::__private228::PhantomData::<JwtPayload>,
-                    lifetime: _serde::__private228::PhantomData,
-                },
-            )
+            Some(CachedJwks {
+                fetched_at: now,
+                expires_at,
+                jwks,
+            })
+        }
+        /// キャッシュのキー（`auth:jwks:<URL>`）
+        fn key(&self) -> String {
+            ::alloc::__export::must_use({
+                ::alloc::fmt::format(format_args!("{0}:{1}", KEY_PREFIX, self.url))
+            })
         }
     }
-};
-/// 認証コンポーネントの実装構造体
-///
-/// WIT の Guest トレイトを実装することで、
-/// 他のコンポーネントから呼び出し可能な関数を公開します。
-struct AuthComponent;
-/// Guest トレイトの実装
-///
-/// WIT で定義された authenticator インターフェースの関数を実装します。
-impl Guest for AuthComponent {
-    /// JWT トークンを検証し、認証結果を返す
+    /// Cache-Control ヘッダーからキャッシュの期間（秒）を計算する
     ///
-    /// # 引数
-    /// * `token` - 検証対象の JWT トークン文字列
+    /// - `no-cache` / `no-store`: 0（毎回取得し直す。取得に失敗した場合の代わりとしては保存する）
+    /// - `max-age=N`: N（MAX_TTL_SECS で打ち切り）
+    /// - ヘッダーがない・max-age がない・値が不正: DEFAULT_TTL_SECS
     ///
-    /// # 戻り値
-    /// * `AuthResult` - 認証結果
-    ///   - 成功時: authenticated=true, user_id=Some(ユーザーID)
-    ///   - 失敗時: authenticated=false, error=Some(エラーメッセージ)
-    fn verify_token(token: String) -> AuthResult {
-        match verify_jwt(&token) {
-            Ok(user_id) => {
-                AuthResult {
-                    authenticated: true,
-                    user_id: Some(user_id),
-                    error: None,
-                }
+    /// # 引数
+    /// * `cache_control` - Cache-Control ヘッダーの値（ない場合は None）
+    fn cache_ttl(cache_control: Option<&str>) -> u64 {
+        let mut ttl = None;
+        for directive in cache_control.unwrap_or_default().split(',') {
+            let directive = directive.trim();
+            if directive.eq_ignore_ascii_case("no-cache")
+                || directive.eq_ignore_ascii_case("no-store")
+            {
+                return 0;
             }
-            Err(e) => {
-                AuthResult {
-                    authenticated: false,
-                    user_id: None,
-                    error: Some(e),
-                }
+            let max_age = directive
+                .split_once('=')
+                .filter(|(name, _)| name.trim().eq_ignore_ascii_case("max-age"));
+            if let Some((_, value)) = max_age {
+                ttl = value.trim().trim_matches('"').parse::<u64>().ok();
             }
         }
+        ttl.unwrap_or(DEFAULT_TTL_SECS).min(MAX_TTL_SECS)
     }
 }
-/// JWT トークンを検証し、ユーザーIDを抽出する
-///
-/// JWT の構造: ヘッダー.ペイロード.署名（すべて Base64URL エンコード）
-///
-/// # 検証手順
-/// 1. トークンが空でないことを確認
-/// 2. ドットで3つの部分に分割できることを確認
-/// 3. ヘッダーをデコードし、アルゴリズムが HS256 であることを確認
-/// 4. HMAC-SHA256 で署名を検証
-/// 5. ペイロードをデコードし、有効期限を確認
-/// 6. ユーザーID（sub クレーム）を抽出
-///
-/// # 引数
-/// * `token` - 検証対象の JWT トークン文字列
-///
-/// # 戻り値
-/// * `Ok(String)` - 検証成功時、ユーザーIDを返す
-/// * `Err(String)` - 検証失敗時、エラーメッセージを返す
-fn verify_jwt(token: &str) -> Result<String, String> {
-    if token.is_empty() {
-        return Err("Missing token".to_string());
-    }
-    let parts: Vec<&str> = token.split('.').collect();
-    if parts.len() != 3 {
-        return Err("Invalid token format".to_string());
-    }
-    let header_b64 = parts[0];
-    let payload_b64 = parts[1];
-    let signature_b64 = parts[2];
-    let header_json = URL_SAFE_NO_PAD
-        .decode(header_b64)
-        .map_err(|_| "Invalid header encoding".to_string())?;
-    let header: JwtHeader = serde_json::from_slice(&header_json)
-        .map_err(|_| "Invalid header JSON".to_string())?;
-    if header.alg != "HS256" {
-        return Err(
-            ::alloc::__export::must_use({
+mod revocation {
+    //! # トークンの失効（ログアウト・パスワードの変更）
+    //!
+    //! JWT は exp まで有効なため、ログアウト・パスワードの変更の後も発行済みのトークンを使い続けられます。
+    //! 署名・クレームの検証に成功したトークンについて、key-value ストア（`default`）の失効リストを確認し、
+    //! 失効したトークンは "Token revoked" で拒否します。
+    //!
+    //! | 単位 | キー | 値 |
+    //! |------|------|----|
+    //! | トークン（jti） | `auth:revocation:jti:<jti>` | 任意（キーがあれば失効） |
+    //! | ユーザー（sub） | `auth:revocation:user:<sub>:revoked_before` | Unix タイムスタンプ秒（この時刻より前に発行したトークンは失効） |
+    //! | ユーザー（sub）のバージョン | `auth:revocation:user:<sub>:token_version` | 整数（ver クレームがこの値より小さいトークンは "Token superseded"） |
+    //!
+    //! ユーザー単位の失効時刻は、コア層のレスポンスの `X-Revoke-Tokens: all` を受けて gateway が書き込みます
+    //! （gateway の revocation モジュール）。バージョンも同様に `X-Token-Version: <整数>` を受けて gateway が
+    //! 書き込み、個々のトークンを記録せずに「全端末からのログアウト」を実現します。
+    //! ver クレームのないトークンはバージョン 0 として扱います（バージョンを書き込むまでは従来どおり受け付ける）。
+    //! コア層が発行するトークンには jti がないため、
+    //! jti 単位の失効は ID プロバイダーが発行したトークンを運用者が個別に失効させる場合に使います。
+    //! iat のないトークンは発行時刻を確認できないため、ユーザー単位の失効時刻がある場合は失効として扱います。
+    //!
+    //! Spin の key-value ストアには TTL がないため、失効リストのキーは削除されません。
+    //! ストアを読めない場合に検証を通すか（fail-open）拒否するか（fail-closed、既定）は、
+    //! Spin 変数 `jwt_revocation_fail_open` で決めます（lib の check_revocation）。
+    use crate::jwks::Store;
+    /// 失効リストのキーのプレフィックス
+    ///
+    /// gateway の revocation モジュールも同じキーに書き込む（変更する場合は両方を変更すること）。
+    const KEY_PREFIX: &str = "auth:revocation";
+    /// トークンが失効しているか
+    ///
+    /// 1. jti がある場合、jti のキーがあれば失効
+    /// 2. ユーザーの失効時刻がある場合、iat がその時刻より前（iat がない場合を含む）なら失効
+    ///
+    /// 失効時刻と同じ秒に発行したトークンは失効させない（ログアウト直後のログインで発行したトークンを拒否しない）。
+    ///
+    /// # 引数
+    /// * `store` - 失効リストの保存先
+    /// * `user_id` - sub クレーム
+    /// * `jti` - jti クレーム（ない場合は None）
+    /// * `issued_at` - iat クレーム（ない場合は None）
+    ///
+    /// # 戻り値
+    /// * `Ok(bool)` - 失効している場合は true
+    /// * `Err(String)` - ストアを読めない、またはユーザーの失効時刻が不正な場合
+    pub(crate) fn is_revoked(
+        store: &impl Store,
+        user_id: &str,
+        jti: Option<&str>,
+        issued_at: Option<u64>,
+    ) -> Result<bool, String> {
+        if let Some(jti) = jti.filter(|jti| !jti.is_empty()) {
+            if store.get(&jti_key(jti))?.is_some() {
+                return Ok(true);
+            }
+        }
+        let key = user_key(user_id);
+        let Some(value) = store.get(&key)? else {
+            return Ok(false);
+        };
+        let revoked_before = std::str::from_utf8(&value)
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .ok_or_else(|| ::alloc::__export::must_use({
                 ::alloc::fmt::format(
-                    format_args!("Unsupported algorithm: {0}", header.alg),
+                    format_args!("Invalid revocation time in {0}", key),
                 )
-            }),
-        );
+            }))?;
+        Ok(issued_at.is_none_or(|iat| iat < revoked_before))
     }
-    let signature = URL_SAFE_NO_PAD
-        .decode(signature_b64)
-        .map_err(|_| "Invalid signature encoding".to_string())?;
-    let message = ::alloc::__export::must_use({
-        ::alloc::fmt::format(format_args!("{0}.{1}", header_b64, payload_b64))
-    });
-    verify_signature(&message, &signature)?;
-    let payload_json = URL_SAFE_NO_PAD
-        .decode(payload_b64)
-        .map_err(|_| "Invalid payload encoding".to_string())?;
-    let payload: JwtPayload = serde_json::from_slice(&payload_json)
-        .map_err(|_| "Invalid payload JSON".to_string())?;
-    if let Some(exp) = payload.exp {
-        if exp < 1577836800 {
-            return Err("Token expired".to_string());
+    /// トークンがユーザーの新しいバージョンで置き換えられたか
+    ///
+    /// ユーザーのバージョンがない場合は 0 として扱う（全てのトークンを受け付ける）。
+    ///
+    /// # 引数
+    /// * `store` - 失効リストの保存先
+    /// * `user_id` - sub クレーム
+    /// * `token_version` - ver クレーム（ない場合は 0）
+    ///
+    /// # 戻り値
+    /// * `Ok(bool)` - ver がユーザーのバージョンより小さい場合は true
+    /// * `Err(String)` - ストアを読めない、またはユーザーのバージョンが不正な場合
+    pub(crate) fn is_superseded(
+        store: &impl Store,
+        user_id: &str,
+        token_version: u64,
+    ) -> Result<bool, String> {
+        let key = version_key(user_id);
+        let Some(value) = store.get(&key)? else {
+            return Ok(false);
+        };
+        let version = std::str::from_utf8(&value)
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .ok_or_else(|| ::alloc::__export::must_use({
+                ::alloc::fmt::format(format_args!("Invalid token version in {0}", key))
+            }))?;
+        Ok(token_version < version)
+    }
+    /// jti 単位の失効のキー（`auth:revocation:jti:<jti>`）
+    fn jti_key(jti: &str) -> String {
+        ::alloc::__export::must_use({
+            ::alloc::fmt::format(format_args!("{0}:jti:{1}", KEY_PREFIX, jti))
+        })
+    }
+    /// ユーザー単位の失効時刻のキー（`auth:revocation:user:<sub>:revoked_before`）
+    fn user_key(user_id: &str) -> String {
+        ::alloc::__export::must_use({
+            ::alloc::fmt::format(
+                format_args!("{0}:user:{1}:revoked_before", KEY_PREFIX, user_id),
+            )
+        })
+    }
+    /// ユーザーのトークンのバージョンのキー（`auth:revocation:user:<sub>:token_version`）
+    fn version_key(user_id: &str) -> String {
+        ::alloc::__export::must_use({
+            ::alloc::fmt::format(
+                format_args!("{0}:user:{1}:token_version", KEY_PREFIX, user_id),
+            )
+        })
+    }
+}
+mod one_time {
+    //! # 使い捨てのトークン（パスワードのリセット・メールアドレスの確認）
+    //!
+    //! パスワードのリセット・メールアドレスの確認のリンクのトークンは 1 回だけ使えるようにします。
+    //! `jti` と `one_time: true`（真偽値）のクレームを持つトークンは、署名・クレーム・失効の検証に成功した後に
+    //! key-value ストア（`default`）に jti を使用済みとして記録し、2 回目以降の提示を "Token already used" で拒否します。
+    //!
+    //! | 項目 | 内容 |
+    //! |------|------|
+    //! | キー | `auth:one-time:jti:<jti>` |
+    //! | 値 | 使用済みとして扱う期限（Unix タイムスタンプ秒、トークンの exp + leeway） |
+    //!
+    //! Spin の key-value ストアには TTL がないため、期限を値に含めて読み込み時に判定します（jwks モジュールと同じ）。
+    //! 期限を過ぎた記録は、同じ jti の次のトークンで上書きします（期限までに同じトークンが受け付けられることはない）。
+    //!
+    //! 同じトークンが同時に提示された場合に両方を受け付けないよう、読み込みと書き込みは
+    //! wasi:keyvalue の compare-and-swap（atomics::Cas / atomics::swap）で行います。
+    //! 読み込んだ後に別のリクエストが書き込んでいた場合は swap が失敗し、使用済みとして拒否します。
+    //! `one_time` のないトークンはストアにアクセスしません。
+    use spin_sdk::wit::wasi::keyvalue::{atomics, store};
+    use crate::STORE_NAME;
+    /// 使用済みの jti のキーのプレフィックス
+    ///
+    /// 同じストアを使う失効リスト（auth:revocation）・JWKS のキャッシュ（auth:jwks）と衝突しないようにする。
+    const KEY_PREFIX: &str = "auth:one-time";
+    /// 使用済みの jti の保存先（compare-and-swap）
+    ///
+    /// テストではメモリ上の実装に差し替え、key-value ストアなしで検証します。
+    pub(crate) trait CasStore {
+        /// current で読んだ値から置き換えるためのハンドル
+        type Handle;
+        /// キーの現在の値（キーがない場合は None）と、その値から置き換えるためのハンドルを取得する
+        fn current(
+            &self,
+            key: &str,
+        ) -> Result<(Option<Vec<u8>>, Self::Handle), String>;
+        /// current で読んだ後に別のリクエストが書き込んでいない場合のみ、キーの値を置き換える
+        ///
+        /// # 戻り値
+        /// * `Ok(true)` - 置き換えた場合
+        /// * `Ok(false)` - 別のリクエストが先に書き込んでいた場合
+        fn swap(&self, handle: Self::Handle, value: &[u8]) -> Result<bool, String>;
+    }
+    /// Spin の key-value ストアを使う保存先
+    pub(crate) struct KvCasStore {
+        bucket: store::Bucket,
+    }
+    impl KvCasStore {
+        /// key-value ストアを開く
+        pub(crate) fn open() -> Result<Self, String> {
+            store::open(STORE_NAME)
+                .map(|bucket| Self { bucket })
+                .map_err(|e| ::alloc::__export::must_use({
+                    ::alloc::fmt::format(
+                        format_args!("Failed to open key-value store: {0:?}", e),
+                    )
+                }))
+        }
+    }
+    impl CasStore for KvCasStore {
+        type Handle = atomics::Cas;
+        fn current(
+            &self,
+            key: &str,
+        ) -> Result<(Option<Vec<u8>>, Self::Handle), String> {
+            let cas = atomics::Cas::new(&self.bucket, key)
+                .map_err(|e| ::alloc::__export::must_use({
+                    ::alloc::fmt::format(
+                        format_args!("Failed to watch {0}: {1:?}", key, e),
+                    )
+                }))?;
+            let value = cas
+                .current()
+                .map_err(|e| ::alloc::__export::must_use({
+                    ::alloc::fmt::format(format_args!("Failed to get {0}: {1:?}", key, e))
+                }))?;
+            Ok((value, cas))
+        }
+        fn swap(&self, handle: Self::Handle, value: &[u8]) -> Result<bool, String> {
+            match atomics::swap(handle, value) {
+                Ok(()) => Ok(true),
+                Err(atomics::CasError::CasFailed(_)) => Ok(false),
+                Err(atomics::CasError::StoreError(e)) => {
+                    Err(
+                        ::alloc::__export::must_use({
+                            ::alloc::fmt::format(
+                                format_args!("Failed to swap one-time token: {0:?}", e),
+                            )
+                        }),
+                    )
+                }
+            }
+        }
+    }
+    /// 使い捨てのトークンの jti を使用済みとして記録する
+    ///
+    /// 1. jti のキーの現在の値（使用済みとして扱う期限）を読む
+    /// 2. 期限が現在時刻以降の場合は使用済み
+    /// 3. キーがない・期限を過ぎた場合は valid_until を書き込む
+    ///    （読み込んだ後に別のリクエストが書き込んでいた場合は使用済み）
+    ///
+    /// # 引数
+    /// * `store` - 使用済みの jti の保存先
+    /// * `jti` - jti クレーム（空でないこと）
+    /// * `valid_until` - トークンを受け付ける期限（exp + leeway、Unix タイムスタンプ秒）
+    /// * `now` - 現在時刻（Unix タイムスタンプ秒）
+    ///
+    /// # 戻り値
+    /// * `Ok(true)` - 初めての提示（使用済みとして記録した）
+    /// * `Ok(false)` - 使用済み
+    /// * `Err(String)` - ストアを読み書きできない、または記録した期限が不正な場合
+    pub(crate) fn consume(
+        store: &impl CasStore,
+        jti: &str,
+        valid_until: u64,
+        now: u64,
+    ) -> Result<bool, String> {
+        let key = jti_key(jti);
+        let (current, handle) = store.current(&key)?;
+        if let Some(value) = current {
+            let used_until = std::str::from_utf8(&value)
+                .ok()
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .ok_or_else(|| ::alloc::__export::must_use({
+                    ::alloc::fmt::format(
+                        format_args!("Invalid one-time token record in {0}", key),
+                    )
+                }))?;
+            if used_until >= now {
+                return Ok(false);
+            }
+        }
+        store.swap(handle, valid_until.to_string().as_bytes())
+    }
+    /// 使用済みの jti のキー（`auth:one-time:jti:<jti>`）
+    fn jti_key(jti: &str) -> String {
+        ::alloc::__export::must_use({
+            ::alloc::fmt::format(format_args!("{0}:jti:{1}", KEY_PREFIX, jti))
+        })
+    }
+}
+/// HS256 の共有シークレット（コア層の JWT_SECRET と同じ値）を読み込む Spin 変数名
+const SECRET_VARIABLE: &str = "jwt_secret";
+/// jwt_secret の key id（jwt_hs256_keys を指定しない場合に発行するトークンのヘッダーの kid）
+///
+/// コア層が JWT_SECRET だけで構成した場合の key id（DEFAULT_KEY_ID）と同じ値。
+/// コア層は kid で鍵を選ぶため、鍵をローテーションする場合はコア層の鍵ファイルと
+/// 同じ key id・シークレットを jwt_hs256_keys に設定すること。
+const SIGNING_KEY_ID: &str = "default";
+/// HS256 の鍵リング（kid → シークレットの JSON オブジェクト）を読み込む Spin 変数名
+const HS256_KEYS_VARIABLE: &str = "jwt_hs256_keys";
+/// 発行するトークンの有効期間の上限（秒）
+///
+/// token-issuer はサービス間の呼び出し用の短期トークンだけを発行する。
+const MAX_ISSUE_TTL_SECS: u64 = 300;
+/// exp / nbf / iat で許容する時計のずれを読み込む Spin 変数名
+const LEEWAY_VARIABLE: &str = "jwt_leeway_secs";
+/// jwt_leeway_secs を指定しない場合の leeway（秒）
+///
+/// コア層（jwt::DEFAULT_LEEWAY_SECS、60 秒）とは別に、Edge 層ではより短い 30 秒とする。
+const DEFAULT_LEEWAY_SECS: u64 = 30;
+/// トークン全体の長さの上限（バイト）を読み込む Spin 変数名
+const MAX_TOKEN_BYTES_VARIABLE: &str = "jwt_max_token_bytes";
+/// jwt_max_token_bytes に指定できる範囲（バイト）
+///
+/// 下限はヘッダーの上限（jwt::MAX_HEADER_BYTES）、上限は 64 KiB（巨大なトークンの HMAC を防ぐ）。
+const MAX_TOKEN_BYTES_RANGE: std::ops::RangeInclusive<usize> = jwt::MAX_HEADER_BYTES..=64 * 1024;
+/// jwt_leeway_secs の上限（秒）
+///
+/// 設定の誤りで期限切れのトークンを長時間受け入れないよう、これを超える値は既定値に戻す。
+const MAX_LEEWAY_SECS: u64 = 300;
+/// 期待する発行者（iss）を読み込む Spin 変数名
+const ISSUER_VARIABLE: &str = "jwt_issuer";
+/// 受け付ける対象者（aud）の一覧を読み込む Spin 変数名（カンマ区切り）
+const AUDIENCES_VARIABLE: &str = "jwt_audiences";
+/// ユーザーのトークンの sub に許可する形式（正規表現）を読み込む Spin 変数名
+const SUBJECT_PATTERN_VARIABLE: &str = "jwt_subject_pattern";
+/// sub の長さの上限（バイト、jwt_subject_pattern を指定した場合も適用する）
+const MAX_SUBJECT_BYTES: usize = 255;
+/// 発行者（テナント）ごとの検証の設定（発行者 → 設定の JSON オブジェクト）を読み込む Spin 変数名
+const TENANTS_VARIABLE: &str = "jwt_tenants";
+/// jwt_tenants の発行者ごとの設定に指定できるフィールド
+///
+/// alg（HS256 / HS384 / HS512 のいずれか 1 つ）・keys（kid → シークレット）は必須、audiences（対象者の配列）は任意。
+/// 綴りの誤り（audience など）で検証を緩めないよう、それ以外のフィールドは拒否する。
+const TENANT_FIELDS: &[&str] = &["alg", "keys", "audiences"];
+/// RS256 の検証に使う公開鍵（PEM）を読み込む Spin 変数名
+const RS256_PUBLIC_KEY_VARIABLE: &str = "jwt_rs256_public_key";
+/// ES256 の検証に使う公開鍵（PEM または JWK）を読み込む Spin 変数名
+const ES256_PUBLIC_KEY_VARIABLE: &str = "jwt_es256_public_key";
+/// 受け付けるアルゴリズムの一覧を読み込む Spin 変数名（カンマ区切り）
+const ALGORITHMS_VARIABLE: &str = "jwt_algorithms";
+/// jwt_algorithms を指定しない場合に受け付けるアルゴリズム
+///
+/// RS256 / ES256 は公開鍵を指定しただけでは受け付けず、jwt_algorithms に明示した場合のみ受け付ける。
+const DEFAULT_ALGORITHMS: &[&str] = &["HS256"];
+/// ID プロバイダーの JWKS の URL を読み込む Spin 変数名
+const JWKS_URL_VARIABLE: &str = "jwt_jwks_url";
+/// 失効リストを読めない場合に検証を通すか（on / off）を読み込む Spin 変数名
+const REVOCATION_FAIL_OPEN_VARIABLE: &str = "jwt_revocation_fail_open";
+/// 開発用のトークンの発行（authenticator の issue-token）を許可するか（on / off）を読み込む Spin 変数名
+const DEV_TOKENS_VARIABLE: &str = "jwt_dev_tokens";
+/// パディング（末尾の "="）のある Base64URL のトークンを受け付けるか（on / off）を読み込む Spin 変数名
+const LENIENT_BASE64_VARIABLE: &str = "jwt_lenient_base64";
+/// token_use クレームのないトークンを受け付けるか（on / off）を読み込む Spin 変数名
+const UNTYPED_TOKENS_VARIABLE: &str = "jwt_allow_untyped_tokens";
+/// ヘッダーの typ の検証（ignore / validate-if-present / require）を読み込む Spin 変数名
+const TYP_POLICY_VARIABLE: &str = "jwt_typ_policy";
+/// ヘッダーの typ に受け付ける値（RFC 7519 5.1、大文字小文字を区別しない）
+const JWT_TYP: &str = "JWT";
+/// 発行時に claims で上書きできない登録済みクレーム（RFC 7519）とトークンの種類（token_use）
+const RESERVED_CLAIMS: &[&str] = &[
+    "sub",
+    "exp",
+    "iat",
+    "nbf",
+    "iss",
+    "aud",
+    "jti",
+    jwt::TOKEN_USE_CLAIM,
+];
+/// gateway に返すクレームの許可リスト
+///
+/// ここに含まれるクレームだけを auth-result の claims に入れる。
+/// gateway はこれらをヘッダーとしてコア層に転送するため、
+/// 個人情報など転送すべきでないクレームは追加しないこと。
+///
+/// 注意: sub / exp / iat / roles は専用のフィールド（user-id / expires-at / roles）で返すため、
+/// ここに指定しても claims には含まれない。
+/// scope（空白区切りのスコープ）は文字列のまま返し、コア層の UserContext が分割する。
+const CLAIM_ALLOWLIST: &[&str] = &["tenant_id", "scope"];
+/// カウンタ・JWKS のキャッシュ・失効リストを保存する key-value ストア名
+///
+/// spin.toml の `key_value_stores` で許可したストアと一致させます。
+const STORE_NAME: &str = "default";
+/// カウンタのキーのプレフィックス
+///
+/// 同じストアを使う ratelimit・gateway のキーと衝突しないようにします。
+const METRICS_KEY_PREFIX: &str = "auth:metrics";
+/// verify-token の呼び出し回数
+const VERIFICATIONS_TOTAL: &str = "auth_verifications_total";
+/// 検証に成功した回数
+const VERIFICATIONS_SUCCESS: &str = "auth_verifications_success";
+/// 失敗: トークンがない
+const FAILURES_MISSING_TOKEN: &str = "auth_failures_missing_token";
+/// 失敗: 有効期限切れ
+const FAILURES_EXPIRED: &str = "auth_failures_expired";
+/// 失敗: 署名が一致しない
+const FAILURES_BAD_SIGNATURE: &str = "auth_failures_bad_signature";
+/// 失敗: 形式が不正（Base64URL / JSON / 必須クレームの欠落）
+const FAILURES_MALFORMED: &str = "auth_failures_malformed";
+/// 失敗: 許可されていないアルゴリズム
+const FAILURES_UNSUPPORTED_ALG: &str = "auth_failures_unsupported_alg";
+/// 失敗: 上記以外（nbf より前、iat が未来、失効したトークンなど）
+const FAILURES_OTHER: &str = "auth_failures_other";
+/// get-metrics が返すカウンタ名（WIT の metrics インターフェースに記載した順）
+const METRIC_NAMES: [&str; 8] = [
+    VERIFICATIONS_TOTAL,
+    VERIFICATIONS_SUCCESS,
+    FAILURES_MISSING_TOKEN,
+    FAILURES_EXPIRED,
+    FAILURES_BAD_SIGNATURE,
+    FAILURES_MALFORMED,
+    FAILURES_UNSUPPORTED_ALG,
+    FAILURES_OTHER,
+];
+/// HS256 の鍵（key id + 共有シークレット）
+///
+/// Debug 出力ではシークレットを表示しない。
+struct HmacKey {
+    /// key id（トークンヘッダーの kid）
+    id: String,
+    /// HS256 の共有シークレット
+    secret: Vec<u8>,
+}
+#[automatically_derived]
+impl ::core::clone::Clone for HmacKey {
+    #[inline]
+    fn clone(&self) -> HmacKey {
+        HmacKey {
+            id: ::core::clone::Clone::clone(&self.id),
+            secret: ::core::clone::Clone::clone(&self.secret),
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for HmacKey {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for HmacKey {
+    #[inline]
+    fn eq(&self, other: &HmacKey) -> bool {
+        self.id == other.id && self.secret == other.secret
+    }
+}
+#[automatically_derived]
+impl ::core::cmp::Eq for HmacKey {
+    #[inline]
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn assert_receiver_is_total_eq(&self) -> () {
+        let _: ::core::cmp::AssertParamIsEq<String>;
+        let _: ::core::cmp::AssertParamIsEq<Vec<u8>>;
+    }
+}
+impl fmt::Debug for HmacKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HmacKey")
+            .field("id", &self.id)
+            .field("secret", &"<redacted>")
+            .finish()
+    }
+}
+impl HmacKey {
+    /// 検証に使う鍵
+    fn decoding_key(&self) -> DecodingKey<'_> {
+        DecodingKey::Hmac(&self.secret)
+    }
+}
+/// HS256 の鍵リング
+///
+/// 先頭が最新の鍵（発行に使う）で、以降はローテーション前に発行したトークンの検証のみに使う。
+/// 古い鍵で発行したトークンの有効期限が切れたら、jwt_hs256_keys から古い鍵を削除する。
+struct HmacKeyring {
+    /// 鍵（1 つ以上、新しい順）
+    keys: Vec<HmacKey>,
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for HmacKeyring {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::debug_struct_field1_finish(
+            f,
+            "HmacKeyring",
+            "keys",
+            &&self.keys,
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::clone::Clone for HmacKeyring {
+    #[inline]
+    fn clone(&self) -> HmacKeyring {
+        HmacKeyring {
+            keys: ::core::clone::Clone::clone(&self.keys),
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for HmacKeyring {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for HmacKeyring {
+    #[inline]
+    fn eq(&self, other: &HmacKeyring) -> bool {
+        self.keys == other.keys
+    }
+}
+#[automatically_derived]
+impl ::core::cmp::Eq for HmacKeyring {
+    #[inline]
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn assert_receiver_is_total_eq(&self) -> () {
+        let _: ::core::cmp::AssertParamIsEq<Vec<HmacKey>>;
+    }
+}
+impl HmacKeyring {
+    /// jwt_secret の 1 つだけの鍵リング（key id は SIGNING_KEY_ID）
+    fn from_secret(secret: &[u8]) -> Self {
+        Self {
+            keys: <[_]>::into_vec(
+                ::alloc::boxed::box_new([
+                    HmacKey {
+                        id: SIGNING_KEY_ID.to_string(),
+                        secret: secret.to_vec(),
+                    },
+                ]),
+            ),
+        }
+    }
+    /// Spin 変数から読み込む（インスタンスごとに 1 回だけ読み込み、以降はキャッシュを使う）
+    ///
+    /// # 戻り値
+    /// * `Some(HmacKeyring)` - jwt_hs256_keys、未定義・空・不正な場合は jwt_secret の鍵
+    /// * `None` - どちらも指定していない場合
+    fn load() -> Option<Self> {
+        static KEYRING: OnceLock<Option<HmacKeyring>> = OnceLock::new();
+        KEYRING.get_or_init(Self::read).clone()
+    }
+    /// Spin 変数 jwt_hs256_keys・jwt_secret を読み込む（不正な jwt_hs256_keys はログを出力して jwt_secret を使う）
+    fn read() -> Option<Self> {
+        let value = spin_sdk::variables::get(HS256_KEYS_VARIABLE).ok();
+        match parse_hs256_keys(value.as_deref()) {
+            Ok(Some(keyring)) => return Some(keyring),
+            Ok(None) => {}
+            Err(e) => {
+                ::std::io::_print(format_args!("[Auth] {0}, using {1}\n", e, SECRET_VARIABLE));
+            }
+        }
+        match spin_sdk::variables::get(SECRET_VARIABLE) {
+            Ok(secret) if !secret.is_empty() => Some(Self::from_secret(secret.as_bytes())),
+            _ => {
+                {
+                    ::std::io::_print(
+                        format_args!(
+                            "[Auth] Neither {0} nor {1} is set, rejecting HS256 tokens\n",
+                            SECRET_VARIABLE, HS256_KEYS_VARIABLE
+                        ),
+                    );
+                };
+                None
+            }
+        }
+    }
+    /// 発行に使う鍵（最新の鍵）
+    fn signing_key(&self) -> &HmacKey {
+        &self.keys[0]
+    }
+    /// kid で検証に使う鍵を選ぶ
+    ///
+    /// # 戻り値
+    /// * `Ok(Vec<DecodingKey>)` - kid の鍵 1 つ、kid がない場合は全ての鍵（新しい順に試す）
+    /// * `Err(VerifyError::UnknownKeyId)` - kid の鍵がない場合
+    fn decoding_keys(
+        &self,
+        kid: Option<&str>,
+    ) -> Result<Vec<DecodingKey<'_>>, VerifyError> {
+        match kid {
+            Some(kid) => {
+                self.keys
+                    .iter()
+                    .find(|key| key.id == kid)
+                    .map(|key| <[_]>::into_vec(
+                        ::alloc::boxed::box_new([key.decoding_key()]),
+                    ))
+                    .ok_or(VerifyError::UnknownKeyId)
+            }
+            None => Ok(self.keys.iter().map(HmacKey::decoding_key).collect()),
+        }
+    }
+}
+/// jwt_hs256_keys の JSON オブジェクト（kid, シークレット）の一覧
+///
+/// jwt::Map（serde_json の Map）はキーの順に並べ替えるため、記載順（新しい順）を保って読み込む。
+struct OrderedSecrets(Vec<(String, String)>);
+impl<'de> Deserialize<'de> for OrderedSecrets {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SecretsVisitor;
+        impl<'de> Visitor<'de> for SecretsVisitor {
+            type Value = OrderedSecrets;
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an object mapping key ids to secrets")
+            }
+            fn visit_map<A: MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry::<String, String>()? {
+                    entries.push(entry);
+                }
+                Ok(OrderedSecrets(entries))
+            }
+        }
+        deserializer.deserialize_map(SecretsVisitor)
+    }
+}
+/// 発行者（テナント）ごとの検証の設定（jwt_tenants の 1 つの発行者）
+///
+/// 発行者ごとに別のシークレットで署名したトークンを、その発行者の鍵だけで検証する。
+struct Tenant {
+    /// 発行者（iss クレームと完全に一致すること）
+    issuer: String,
+    /// 受け付けるアルゴリズム（HS256 / HS384 / HS512 のいずれか 1 つ）
+    algorithm: String,
+    /// 検証に使う鍵リング（kid のないトークンは全ての鍵を試す）
+    keyring: HmacKeyring,
+    /// 受け付ける対象者（空の場合は aud を検証しない）
+    audiences: Vec<String>,
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for Tenant {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::debug_struct_field4_finish(
+            f,
+            "Tenant",
+            "issuer",
+            &self.issuer,
+            "algorithm",
+            &self.algorithm,
+            "keyring",
+            &self.keyring,
+            "audiences",
+            &&self.audiences,
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::clone::Clone for Tenant {
+    #[inline]
+    fn clone(&self) -> Tenant {
+        Tenant {
+            issuer: ::core::clone::Clone::clone(&self.issuer),
+            algorithm: ::core::clone::Clone::clone(&self.algorithm),
+            keyring: ::core::clone::Clone::clone(&self.keyring),
+            audiences: ::core::clone::Clone::clone(&self.audiences),
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for Tenant {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for Tenant {
+    #[inline]
+    fn eq(&self, other: &Tenant) -> bool {
+        self.issuer == other.issuer && self.algorithm == other.algorithm
+            && self.keyring == other.keyring && self.audiences == other.audiences
+    }
+}
+#[automatically_derived]
+impl ::core::cmp::Eq for Tenant {
+    #[inline]
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn assert_receiver_is_total_eq(&self) -> () {
+        let _: ::core::cmp::AssertParamIsEq<String>;
+        let _: ::core::cmp::AssertParamIsEq<HmacKeyring>;
+        let _: ::core::cmp::AssertParamIsEq<Vec<String>>;
+    }
+}
+impl Tenant {
+    /// Spin 変数 jwt_tenants から読み込む（インスタンスごとに 1 回だけ読み込み、以降はキャッシュを使う）
+    ///
+    /// # 戻り値
+    /// * `Some(Vec<Tenant>)` - 発行者ごとの設定（不正な場合はログを出力して空、全てのトークンを拒否する）
+    /// * `None` - 未定義・空の場合（jwt_secret などの設定で検証する）
+    fn load() -> Option<Vec<Self>> {
+        static TENANTS: OnceLock<Option<Vec<Tenant>>> = OnceLock::new();
+        TENANTS
+            .get_or_init(|| {
+                let value = spin_sdk::variables::get(TENANTS_VARIABLE).ok();
+                parse_tenants(value.as_deref())
+                    .unwrap_or_else(|e| {
+                        {
+                            ::std::io::_print(
+                                format_args!("[Auth] {0}, rejecting all tokens\n", e),
+                            );
+                        };
+                        Some(Vec::new())
+                    })
+            })
+            .clone()
+    }
+}
+/// 検証に使う鍵（HS256 の鍵リングと RS256 / ES256 の公開鍵）
+///
+/// Spin 変数で指定した鍵だけを持つ。公開鍵がないアルゴリズムのトークンには空の HS256 の鍵を渡し、
+/// jwt クレートが "Unsupported algorithm" で拒否する（署名の検証には使わない）。
+struct VerificationKeys {
+    /// HS256 の鍵リング（jwt_hs256_keys / jwt_secret、どちらも指定していない場合は None）
+    hs256: Option<HmacKeyring>,
+    /// RS256 の公開鍵（jwt_rs256_public_key）
+    rs256: Option<RsaPublicKey>,
+    /// ES256 の公開鍵（jwt_es256_public_key）
+    es256: Option<EcPublicKey>,
+    /// ID プロバイダーの JWKS（jwt_jwks_url を指定し、トークンに kid がある場合のみ）
+    jwks: Option<JwkSet>,
+    /// 発行者ごとの設定（jwt_tenants を指定した場合のみ、不正な場合は空）
+    /// Some の場合は iss で選んだ発行者の鍵だけを使い、上記の鍵は使わない
+    tenants: Option<Vec<Tenant>>,
+    /// ヘッダーの typ の検証（jwt_typ_policy、発行者の設定に関わらず全てのトークンに適用する）
+    typ_policy: TypPolicy,
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for VerificationKeys {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        let names: &'static _ = &[
+            "hs256",
+            "rs256",
+            "es256",
+            "jwks",
+            "tenants",
+            "typ_policy",
+        ];
+        let values: &[&dyn ::core::fmt::Debug] = &[
+            &self.hs256,
+            &self.rs256,
+            &self.es256,
+            &self.jwks,
+            &self.tenants,
+            &&self.typ_policy,
+        ];
+        ::core::fmt::Formatter::debug_struct_fields_finish(
+            f,
+            "VerificationKeys",
+            names,
+            values,
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for VerificationKeys {
+    #[inline]
+    fn default() -> VerificationKeys {
+        VerificationKeys {
+            hs256: ::core::default::Default::default(),
+            rs256: ::core::default::Default::default(),
+            es256: ::core::default::Default::default(),
+            jwks: ::core::default::Default::default(),
+            tenants: ::core::default::Default::default(),
+            typ_policy: ::core::default::Default::default(),
+        }
+    }
+}
+impl VerificationKeys {
+    /// Spin 変数から読み込む（未定義・空の場合は None、不正な公開鍵はログを出力して None）
+    fn load() -> Self {
+        Self {
+            hs256: HmacKeyring::load(),
+            rs256: load_public_key(RS256_PUBLIC_KEY_VARIABLE, parse_rsa_public_key),
+            es256: load_public_key(ES256_PUBLIC_KEY_VARIABLE, parse_ec_public_key),
+            jwks: None,
+            tenants: Tenant::load(),
+            typ_policy: typ_policy(),
+        }
+    }
+    /// ヘッダーの alg・kid で検証に使う鍵を選ぶ
+    ///
+    /// - HS256 / HS384 / HS512: 鍵リングの kid の鍵（kid がない場合は全ての鍵を新しい順に試す）
+    /// - RS256 / ES256: JWKS がある場合は kid の鍵（jwt_rs256_public_key などより優先する）、
+    ///   ない場合は Spin 変数の公開鍵
+    ///
+    /// 鍵の種類は alg ごとに固定する（公開鍵を HS256 のシークレットとして使わない）。
+    ///
+    /// # 戻り値
+    /// * `Ok(Vec<DecodingKey>)` - 試す鍵（1 つ以上）
+    /// * `Err(VerifyError::UnknownKeyId)` - 鍵リング・JWKS に kid の鍵がない場合
+    /// * `Err(VerifyError::Misconfigured)` - HMAC のトークンで、jwt_secret・jwt_hs256_keys がない場合
+    fn decoding_keys(
+        &self,
+        header: &Header,
+    ) -> Result<Vec<DecodingKey<'_>>, VerifyError> {
+        if jwt::HMAC_ALGORITHMS.contains(&header.alg.as_str()) {
+            let keyring = self.hs256.as_ref().ok_or(VerifyError::Misconfigured)?;
+            return keyring.decoding_keys(header.kid.as_deref());
+        }
+        if let (Some(jwks), Some(kid)) = (&self.jwks, header.kid.as_deref()) {
+            return jwks
+                .find(kid)
+                .map(|key| <[_]>::into_vec(::alloc::boxed::box_new([key])))
+                .ok_or(VerifyError::UnknownKeyId);
+        }
+        Ok(
+            <[_]>::into_vec(
+                ::alloc::boxed::box_new([
+                    match (header.alg.as_str(), &self.rs256, &self.es256) {
+                        ("RS256", Some(key), _) => DecodingKey::Rsa(key),
+                        ("ES256", _, Some(key)) => DecodingKey::Ec(key),
+                        _ => DecodingKey::Hmac(&[]),
+                    },
+                ]),
+            ),
+        )
+    }
+}
+/// 検証に成功したトークンから取り出した情報
+///
+/// WIT の auth-result の成功時のフィールドに対応します。
+struct VerifiedToken {
+    /// ユーザーID（sub クレーム）
+    user_id: String,
+    /// 有効期限（exp クレーム、Unix タイムスタンプ秒）
+    /// jwt クレートは exp を必須とするため、検証に成功した場合は常に Some
+    expires_at: Option<u64>,
+    /// ロール（roles クレームの文字列要素）
+    roles: Vec<String>,
+    /// 許可リストに含まれるクレーム（名前, 値）
+    claims: Vec<(String, String)>,
+    /// 発行時刻（iat クレーム、Unix タイムスタンプ秒）
+    /// iat がないトークンは None
+    issued_at: Option<u64>,
+    /// トークンの ID（jti クレーム、ない場合は None）
+    /// 失効の確認・使い捨てのトークンの記録にのみ使い、auth-result には含めない
+    jti: Option<String>,
+    /// トークンの種類（token_use クレーム、ない場合は None）
+    /// アクセストークンの確認にのみ使い、auth-result には含めない
+    token_use: Option<String>,
+    /// トークンのバージョン（ver クレーム、ない・0 以上の整数でない場合は 0）
+    /// ユーザーのバージョンとの比較にのみ使い、auth-result には含めない
+    token_version: u64,
+    /// 使い捨てのトークンか（one_time クレームが真偽値の true）
+    /// auth-result の one-time として返し、gateway は検証結果をキャッシュしない
+    one_time: bool,
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for VerifiedToken {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        let names: &'static _ = &[
+            "user_id",
+            "expires_at",
+            "roles",
+            "claims",
+            "issued_at",
+            "jti",
+            "token_use",
+            "token_version",
+            "one_time",
+        ];
+        let values: &[&dyn ::core::fmt::Debug] = &[
+            &self.user_id,
+            &self.expires_at,
+            &self.roles,
+            &self.claims,
+            &self.issued_at,
+            &self.jti,
+            &self.token_use,
+            &self.token_version,
+            &&self.one_time,
+        ];
+        ::core::fmt::Formatter::debug_struct_fields_finish(
+            f,
+            "VerifiedToken",
+            names,
+            values,
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for VerifiedToken {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for VerifiedToken {
+    #[inline]
+    fn eq(&self, other: &VerifiedToken) -> bool {
+        self.user_id == other.user_id && self.expires_at == other.expires_at
+            && self.roles == other.roles && self.claims == other.claims
+            && self.issued_at == other.issued_at && self.jti == other.jti
+            && self.token_use == other.token_use
+            && self.token_version == other.token_version
+            && self.one_time == other.one_time
+    }
+}
+/// トークンの検証エラー
+///
+/// 表示用のメッセージ（auth-result の error）とは別に、
+/// メトリクスのために失敗の理由を分類します。
+enum VerifyError {
+    /// トークンがない（Authorization ヘッダーがない）
+    MissingToken,
+    /// HS256 の鍵リング・JWKS に kid の鍵がない（JWKS は取得し直しても見つからない）
+    UnknownKeyId,
+    /// JWKS を取得できず、キャッシュもない
+    KeysUnavailable,
+    /// HS256 の鍵（jwt_secret / jwt_hs256_keys）が設定されていない
+    Misconfigured,
+    /// 失効リスト（jti・ユーザーごとの失効時刻）によって失効したトークン
+    Revoked,
+    /// 失効リストを読めない（jwt_revocation_fail_open=off の場合のみ）
+    RevocationUnavailable,
+    /// sub が UUID（jwt_subject_pattern を指定した場合はその形式）でない
+    InvalidSubject,
+    /// iss がない・jwt_tenants にない発行者のトークン（jwt_tenants を指定した場合のみ、署名は確認しない）
+    UnknownIssuer,
+    /// ヘッダーの typ が JWT でない（jwt_typ_policy=require の場合は typ がない場合を含む）
+    InvalidTyp,
+    /// token_use が access でない（リフレッシュトークンなど）、または token_use がないトークン
+    WrongTokenType,
+    /// ver クレームがユーザーのバージョンより小さいトークン（パスワードの変更・全端末からのログアウトの前に発行）
+    Superseded,
+    /// 使用済みの使い捨てのトークン（one_time: true）
+    Reused,
+    /// jti のない使い捨てのトークン（使用済みか判定できない）
+    OneTimeWithoutJti,
+    /// 使い捨てのトークンの使用済みの記録を読み書きできない（常に拒否する）
+    OneTimeUnavailable,
+    /// jwt クレートの検証エラー
+    Jwt(jwt::Error),
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for VerifyError {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        match self {
+            VerifyError::MissingToken => {
+                ::core::fmt::Formatter::write_str(f, "MissingToken")
+            }
+            VerifyError::UnknownKeyId => {
+                ::core::fmt::Formatter::write_str(f, "UnknownKeyId")
+            }
+            VerifyError::KeysUnavailable => {
+                ::core::fmt::Formatter::write_str(f, "KeysUnavailable")
+            }
+            VerifyError::Misconfigured => {
+                ::core::fmt::Formatter::write_str(f, "Misconfigured")
+            }
+            VerifyError::Revoked => ::core::fmt::Formatter::write_str(f, "Revoked"),
+            VerifyError::RevocationUnavailable => {
+                ::core::fmt::Formatter::write_str(f, "RevocationUnavailable")
+            }
+            VerifyError::InvalidSubject => {
+                ::core::fmt::Formatter::write_str(f, "InvalidSubject")
+            }
+            VerifyError::UnknownIssuer => {
+                ::core::fmt::Formatter::write_str(f, "UnknownIssuer")
+            }
+            VerifyError::InvalidTyp => {
+                ::core::fmt::Formatter::write_str(f, "InvalidTyp")
+            }
+            VerifyError::WrongTokenType => {
+                ::core::fmt::Formatter::write_str(f, "WrongTokenType")
+            }
+            VerifyError::Superseded => {
+                ::core::fmt::Formatter::write_str(f, "Superseded")
+            }
+            VerifyError::Reused => ::core::fmt::Formatter::write_str(f, "Reused"),
+            VerifyError::OneTimeWithoutJti => {
+                ::core::fmt::Formatter::write_str(f, "OneTimeWithoutJti")
+            }
+            VerifyError::OneTimeUnavailable => {
+                ::core::fmt::Formatter::write_str(f, "OneTimeUnavailable")
+            }
+            VerifyError::Jwt(__self_0) => {
+                ::core::fmt::Formatter::debug_tuple_field1_finish(f, "Jwt", &__self_0)
+            }
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for VerifyError {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for VerifyError {
+    #[inline]
+    fn eq(&self, other: &VerifyError) -> bool {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        let __arg1_discr = ::core::intrinsics::discriminant_value(other);
+        __self_discr == __arg1_discr
+            && match (self, other) {
+                (VerifyError::Jwt(__self_0), VerifyError::Jwt(__arg1_0)) => {
+                    __self_0 == __arg1_0
+                }
+                _ => true,
+            }
+    }
+}
+impl VerifyError {
+    /// 失敗の理由を数えるカウンタ名
+    fn metric(&self) -> &'static str {
+        match self {
+            Self::MissingToken => FAILURES_MISSING_TOKEN,
+            Self::UnknownKeyId
+            | Self::KeysUnavailable
+            | Self::Misconfigured
+            | Self::Revoked
+            | Self::RevocationUnavailable
+            | Self::InvalidSubject
+            | Self::UnknownIssuer
+            | Self::InvalidTyp
+            | Self::WrongTokenType
+            | Self::Superseded
+            | Self::Reused
+            | Self::OneTimeWithoutJti
+            | Self::OneTimeUnavailable => FAILURES_OTHER,
+            Self::Jwt(jwt::Error::Expired) => FAILURES_EXPIRED,
+            Self::Jwt(jwt::Error::InvalidSignature) => FAILURES_BAD_SIGNATURE,
+            Self::Jwt(jwt::Error::UnsecuredAlgorithm(_) | jwt::Error::UnsupportedAlgorithm(_)) => {
+                FAILURES_UNSUPPORTED_ALG
+            }
+            Self::Jwt(
+                jwt::Error::InvalidFormat
+                | jwt::Error::EncryptedToken
+                | jwt::Error::TooLarge
+                | jwt::Error::InvalidHeaderEncoding
+                | jwt::Error::InvalidHeaderJson
+                | jwt::Error::InvalidSignatureEncoding
+                | jwt::Error::InvalidPayloadEncoding
+                | jwt::Error::InvalidPayloadJson
+                | jwt::Error::MissingSubject
+                | jwt::Error::MissingExpiration,
+            ) => FAILURES_MALFORMED,
+            Self::Jwt(_) => FAILURES_OTHER,
+        }
+    }
+    /// auth-result の error-code に返す失敗の理由
+    ///
+    /// jwt クレートのエラーを追加した場合に対応漏れがないよう、ワイルドカードを使わない。
+    fn code(&self) -> AuthErrorCode {
+        match self {
+            Self::MissingToken => AuthErrorCode::MissingToken,
+            Self::UnknownKeyId => AuthErrorCode::InvalidSignature,
+            Self::KeysUnavailable
+            | Self::Misconfigured
+            | Self::RevocationUnavailable
+            | Self::OneTimeUnavailable => AuthErrorCode::Unavailable,
+            Self::Revoked | Self::Superseded | Self::Reused => AuthErrorCode::Revoked,
+            Self::InvalidSubject
+            | Self::UnknownIssuer
+            | Self::InvalidTyp
+            | Self::WrongTokenType
+            | Self::OneTimeWithoutJti => AuthErrorCode::InvalidClaims,
+            Self::Jwt(e) => {
+                match e {
+                    jwt::Error::InvalidFormat
+                    | jwt::Error::TooLarge
+                    | jwt::Error::InvalidHeaderEncoding
+                    | jwt::Error::InvalidHeaderJson
+                    | jwt::Error::InvalidSignatureEncoding
+                    | jwt::Error::InvalidPayloadEncoding
+                    | jwt::Error::InvalidPayloadJson => AuthErrorCode::Malformed,
+                    jwt::Error::EncryptedToken => AuthErrorCode::Encrypted,
+                    jwt::Error::UnsecuredAlgorithm(_)
+                    | jwt::Error::UnsupportedAlgorithm(_) => {
+                        AuthErrorCode::UnsupportedAlgorithm
+                    }
+                    jwt::Error::InvalidSignature => AuthErrorCode::InvalidSignature,
+                    jwt::Error::Expired => AuthErrorCode::Expired,
+                    jwt::Error::NotYetValid | jwt::Error::IssuedInFuture => {
+                        AuthErrorCode::NotYetValid
+                    }
+                    jwt::Error::MissingSubject => AuthErrorCode::MissingSubject,
+                    jwt::Error::MissingExpiration
+                    | jwt::Error::InvalidIssuer
+                    | jwt::Error::InvalidAudience => AuthErrorCode::InvalidClaims,
+                    jwt::Error::InvalidKey
+                    | jwt::Error::InvalidPublicKey
+                    | jwt::Error::Encoding => AuthErrorCode::Unavailable,
+                }
+            }
+        }
+    }
+}
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingToken => f.write_str("Missing token"),
+            Self::UnknownKeyId => f.write_str("Unknown signing key"),
+            Self::KeysUnavailable => f.write_str("Signing keys unavailable"),
+            Self::Misconfigured => f.write_str("Auth misconfigured"),
+            Self::Revoked => f.write_str("Token revoked"),
+            Self::RevocationUnavailable => f.write_str("Revocation check unavailable"),
+            Self::InvalidSubject => f.write_str("Invalid subject claim"),
+            Self::UnknownIssuer => f.write_str("Unknown issuer"),
+            Self::InvalidTyp => f.write_str("Invalid typ header"),
+            Self::WrongTokenType => f.write_str("Wrong token type"),
+            Self::Superseded => f.write_str("Token superseded"),
+            Self::Reused => f.write_str("Token already used"),
+            Self::OneTimeWithoutJti => f.write_str("One-time token without jti"),
+            Self::OneTimeUnavailable => f.write_str("One-time token check unavailable"),
+            Self::Jwt(e) => e.fmt(f),
+        }
+    }
+}
+/// ヘッダーの typ の検証（jwt_typ_policy）
+enum TypPolicy {
+    /// 検証しない（RFC 7519 では typ は任意）
+    Ignore,
+    /// typ がある場合のみ "JWT"（大文字小文字を区別しない）であること
+    #[default]
+    ValidateIfPresent,
+    /// typ が "JWT"（大文字小文字を区別しない）であること（typ のないトークンも拒否する）
+    Require,
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for TypPolicy {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::write_str(
+            f,
+            match self {
+                TypPolicy::Ignore => "Ignore",
+                TypPolicy::ValidateIfPresent => "ValidateIfPresent",
+                TypPolicy::Require => "Require",
+            },
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::clone::Clone for TypPolicy {
+    #[inline]
+    fn clone(&self) -> TypPolicy {
+        *self
+    }
+}
+#[automatically_derived]
+impl ::core::marker::Copy for TypPolicy {}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for TypPolicy {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for TypPolicy {
+    #[inline]
+    fn eq(&self, other: &TypPolicy) -> bool {
+        let __self_discr = ::core::intrinsics::discriminant_value(self);
+        let __arg1_discr = ::core::intrinsics::discriminant_value(other);
+        __self_discr == __arg1_discr
+    }
+}
+#[automatically_derived]
+impl ::core::cmp::Eq for TypPolicy {
+    #[inline]
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn assert_receiver_is_total_eq(&self) -> () {}
+}
+#[automatically_derived]
+impl ::core::default::Default for TypPolicy {
+    #[inline]
+    fn default() -> TypPolicy {
+        Self::ValidateIfPresent
+    }
+}
+impl TypPolicy {
+    /// ヘッダーの typ（ない場合は None）を受け付けるか
+    fn accepts(self, typ: Option<&str>) -> bool {
+        let is_jwt = |typ: &str| typ.eq_ignore_ascii_case(JWT_TYP);
+        match self {
+            Self::Ignore => true,
+            Self::ValidateIfPresent => typ.is_none_or(is_jwt),
+            Self::Require => typ.is_some_and(is_jwt),
+        }
+    }
+}
+/// sub クレーム（gateway が X-User-Id に設定する値）に許可する形式
+enum SubjectRule {
+    /// UUID（ハイフン区切りの 36 文字）のみ
+    #[default]
+    Uuid,
+    /// jwt_subject_pattern の正規表現に sub 全体が一致するもの
+    /// （MAX_SUBJECT_BYTES 以下で、制御文字を含まないこと）
+    Pattern(Regex),
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for SubjectRule {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        match self {
+            SubjectRule::Uuid => ::core::fmt::Formatter::write_str(f, "Uuid"),
+            SubjectRule::Pattern(__self_0) => {
+                ::core::fmt::Formatter::debug_tuple_field1_finish(
+                    f,
+                    "Pattern",
+                    &__self_0,
+                )
+            }
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::clone::Clone for SubjectRule {
+    #[inline]
+    fn clone(&self) -> SubjectRule {
+        match self {
+            SubjectRule::Uuid => SubjectRule::Uuid,
+            SubjectRule::Pattern(__self_0) => {
+                SubjectRule::Pattern(::core::clone::Clone::clone(__self_0))
+            }
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for SubjectRule {
+    #[inline]
+    fn default() -> SubjectRule {
+        Self::Uuid
+    }
+}
+impl SubjectRule {
+    /// sub がこの形式か
+    fn matches(&self, subject: &str) -> bool {
+        match self {
+            Self::Uuid => jwt::is_uuid(subject),
+            Self::Pattern(pattern) => {
+                subject.len() <= MAX_SUBJECT_BYTES
+                    && !subject.chars().any(char::is_control)
+                    && pattern.is_match(subject)
+            }
+        }
+    }
+}
+/// 認証コンポーネントの実装構造体
+///
+/// WIT の Guest トレイトを実装することで、
+/// 他のコンポーネントから呼び出し可能な関数を公開します。
+struct AuthComponent;
+/// Guest トレイトの実装
+///
+/// WIT で定義された authenticator インターフェースの関数を実装します。
+impl Guest for AuthComponent {
+    /// JWT トークンを検証し、認証結果を返す
+    ///
+    /// # 引数
+    /// * `token` - 検証対象の JWT トークン文字列
+    ///
+    /// # 戻り値
+    /// * `AuthResult` - 認証結果
+    ///   - 成功時: authenticated=true, user_id=Some(ユーザーID),
+    ///     expires_at/issued_at/roles/claims=ペイロードの値
+    ///   - 失敗時: authenticated=false, error=Some(エラーメッセージ), error_code=Some(失敗の理由),
+    ///     expires_at/issued_at=None, roles/claims=空
+    fn verify_token(token: String) -> AuthResult {
+        let now = unix_now();
+        let leeway = leeway();
+        let issuer = expected_issuer();
+        let audiences = allowed_audiences();
+        let audiences: Vec<&str> = audiences.iter().map(String::as_str).collect();
+        let algorithms = allowed_algorithms();
+        let algorithms: Vec<&str> = algorithms.iter().map(String::as_str).collect();
+        let lenient_base64 = switch_variable(LENIENT_BASE64_VARIABLE);
+        if lenient_base64 && token.contains('=') {
+            {
+                ::std::io::_print(
+                    format_args!(
+                        "[Auth] Warning: accepting padded base64url token segments ({0}=on)\n",
+                        LENIENT_BASE64_VARIABLE,
+                    ),
+                );
+            };
+        }
+        let validation = Validation::new(now)
+            .with_leeway(leeway)
+            .with_issuer(issuer.as_deref())
+            .with_audiences(&audiences)
+            .with_algorithms(&algorithms)
+            .with_lenient_base64(lenient_base64)
+            .with_max_token_bytes(max_token_bytes());
+        let subject = subject_rule();
+        let result = load_keys(&token, now, &validation)
+            .and_then(|keys| verify_jwt(&token, &keys, &validation))
+            .and_then(|verified| check_subject(verified, &subject))
+            .and_then(|verified| {
+                check_token_use(verified, switch_variable(UNTYPED_TOKENS_VARIABLE))
+            })
+            .and_then(|verified| {
+                let revoked = jwks::KvStore::open()
+                    .and_then(|store| {
+                        revocation::is_revoked(
+                            &store,
+                            &verified.user_id,
+                            verified.jti.as_deref(),
+                            verified.issued_at,
+                        )
+                    });
+                check_revocation(
+                    verified,
+                    revoked,
+                    switch_variable(REVOCATION_FAIL_OPEN_VARIABLE),
+                )
+            })
+            .and_then(|verified| {
+                let superseded = jwks::KvStore::open()
+                    .and_then(|store| {
+                        revocation::is_superseded(
+                            &store,
+                            &verified.user_id,
+                            verified.token_version,
+                        )
+                    });
+                check_token_version(
+                    verified,
+                    superseded,
+                    switch_variable(REVOCATION_FAIL_OPEN_VARIABLE),
+                )
+            })
+            .and_then(|verified| {
+                check_one_time(verified, one_time::KvCasStore::open, now, leeway)
+            });
+        if let Err(e) = KvCounter::open()
+            .and_then(|counter| record_verification(&counter, &result))
+        {
+            {
+                ::std::io::_print(
+                    format_args!("[Auth] Failed to record metrics: {0}\n", e),
+                );
+            };
+        }
+        auth_result(result)
+    }
+    /// 開発用にユーザーのトークンを発行する（jwt_dev_tokens=on の場合のみ）
+    ///
+    /// # 引数
+    /// * `user_id` - sub クレーム
+    /// * `ttl_seconds` - 有効期間（秒）
+    ///
+    /// # 戻り値
+    /// * `Ok(String)` - 発行したトークン
+    /// * `Err(String)` - 無効・引数が不正・HS256 の鍵が設定されていない場合のエラーメッセージ
+    fn issue_token(user_id: String, ttl_seconds: u64) -> Result<String, String> {
+        if !switch_variable(DEV_TOKENS_VARIABLE) {
+            return Err(
+                ::alloc::__export::must_use({
+                    ::alloc::fmt::format(
+                        format_args!(
+                            "issue-token is disabled ({0}=off)", DEV_TOKENS_VARIABLE
+                        ),
+                    )
+                }),
+            );
+        }
+        let keyring = HmacKeyring::load()
+            .ok_or_else(|| VerifyError::Misconfigured.to_string())?;
+        let token = issue_jwt(&user_id, ttl_seconds, &[], &keyring, unix_now())?;
+        {
+            ::std::io::_print(
+                format_args!(
+                    "[Auth] Issued dev token: sub={0} ttl={1}s kid={2}\n", user_id,
+                    ttl_seconds, keyring.signing_key().id
+                ),
+            );
+        };
+        Ok(token)
+    }
+}
+/// token-issuer インターフェースの実装
+impl TokenIssuerGuest for AuthComponent {
+    /// サービス用トークンを発行する
+    ///
+    /// # 引数
+    /// * `subject` - sub クレーム
+    /// * `ttl_seconds` - 有効期間（秒）
+    /// * `claims` - 追加のクレーム（名前, 値）
+    ///
+    /// # 戻り値
+    /// * `Ok(String)` - 発行したトークン
+    /// * `Err(String)` - 引数が不正な場合のエラーメッセージ
+    fn issue(
+        subject: String,
+        ttl_seconds: u64,
+        claims: Vec<(String, String)>,
+    ) -> Result<String, String> {
+        let keyring = HmacKeyring::load()
+            .ok_or_else(|| VerifyError::Misconfigured.to_string())?;
+        let token = issue_jwt(&subject, ttl_seconds, &claims, &keyring, unix_now())?;
+        {
+            ::std::io::_print(
+                format_args!(
+                    "[Auth] Issued token: sub={0} ttl={1}s kid={2}\n", subject,
+                    ttl_seconds, keyring.signing_key().id
+                ),
+            );
+        };
+        Ok(token)
+    }
+}
+/// metrics インターフェースの実装
+impl MetricsGuest for AuthComponent {
+    /// 検証のカウンタ（名前, 値）の一覧を返す
+    ///
+    /// key-value ストアにアクセスできない場合は空リストを返します。
+    fn get_metrics() -> Vec<(String, u64)> {
+        match KvCounter::open().and_then(|counter| read_metrics(&counter)) {
+            Ok(metrics) => metrics,
+            Err(e) => {
+                {
+                    ::std::io::_print(
+                        format_args!("[Auth] Failed to read metrics: {0}\n", e),
+                    );
+                };
+                Vec::new()
+            }
+        }
+    }
+}
+/// 検証結果を WIT の auth-result に変換する
+///
+/// # 戻り値
+/// * `AuthResult` - 認証結果
+///   - 成功時: authenticated=true, user_id=Some(ユーザーID),
+///     expires_at/issued_at/roles/claims=ペイロードの値
+///   - 失敗時: authenticated=false, error=Some(エラーメッセージ), error_code=Some(失敗の理由),
+///     expires_at/issued_at=None, roles/claims=空
+fn auth_result(result: Result<VerifiedToken, VerifyError>) -> AuthResult {
+    match result {
+        Ok(verified) => {
+            AuthResult {
+                authenticated: true,
+                user_id: Some(verified.user_id),
+                error: None,
+                expires_at: verified.expires_at,
+                roles: verified.roles,
+                claims: verified.claims,
+                issued_at: verified.issued_at,
+                error_code: None,
+                one_time: verified.one_time,
+            }
+        }
+        Err(e) => {
+            AuthResult {
+                authenticated: false,
+                user_id: None,
+                error: Some(e.to_string()),
+                expires_at: None,
+                roles: Vec::new(),
+                claims: Vec::new(),
+                issued_at: None,
+                error_code: Some(e.code()),
+                one_time: false,
+            }
+        }
+    }
+}
+/// 署名済みの JWT を作成する
+///
+/// ヘッダー: {"alg":"HS256","typ":"JWT","kid":最新の鍵の key id}
+/// ペイロード: sub / exp / iat と追加のクレーム（値は文字列、iss なし）
+///
+/// # 引数
+/// * `subject` - sub クレーム
+/// * `ttl_seconds` - 有効期間（秒、1〜MAX_ISSUE_TTL_SECS）
+/// * `claims` - 追加のクレーム（登録済みクレームは指定できない）
+/// * `keyring` - HS256 の鍵リング（最新の鍵で署名する）
+/// * `now` - 現在時刻（Unix タイムスタンプ秒）
+///
+/// # 戻り値
+/// * `Ok(String)` - 発行したトークン
+/// * `Err(String)` - 引数が不正な場合のエラーメッセージ
+fn issue_jwt(
+    subject: &str,
+    ttl_seconds: u64,
+    claims: &[(String, String)],
+    keyring: &HmacKeyring,
+    now: u64,
+) -> Result<String, String> {
+    if subject.is_empty() {
+        return Err("Missing subject".to_string());
+    }
+    if ttl_seconds == 0 || ttl_seconds > MAX_ISSUE_TTL_SECS {
+        return Err(
+            ::alloc::__export::must_use({
+                ::alloc::fmt::format(
+                    format_args!(
+                        "Invalid ttl: must be between 1 and {0} seconds",
+                        MAX_ISSUE_TTL_SECS
+                    ),
+                )
+            }),
+        );
+    }
+    let mut extra = Map::new();
+    for (name, value) in claims {
+        if RESERVED_CLAIMS.contains(&name.as_str()) {
+            return Err(
+                ::alloc::__export::must_use({
+                    ::alloc::fmt::format(format_args!("Reserved claim: {0}", name))
+                }),
+            );
+        }
+        if extra.insert(name.clone(), Value::String(value.clone())).is_some() {
+            return Err(
+                ::alloc::__export::must_use({
+                    ::alloc::fmt::format(format_args!("Duplicate claim: {0}", name))
+                }),
+            );
+        }
+    }
+    extra
+        .insert(
+            jwt::TOKEN_USE_CLAIM.to_string(),
+            Value::String(jwt::TOKEN_USE_ACCESS.to_string()),
+        );
+    let claims = Claims {
+        sub: subject.to_string(),
+        exp: now + ttl_seconds,
+        iat: now,
+        iss: None,
+        aud: None,
+        extra,
+    };
+    let key = keyring.signing_key();
+    jwt::encode(&Header::hs256(Some(&key.id)), &claims, &key.secret)
+        .map_err(|e| e.to_string())
+}
+/// 現在時刻（Unix タイムスタンプ秒）
+///
+/// WASI の壁時計から取得する。取得できない場合は 0。
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+/// 検証に使う鍵を読み込む
+///
+/// jwt_jwks_url を指定した場合は、kid のある RS256 / ES256 のトークンに限り JWKS も読み込む
+/// （HS256・kid のないトークン・形式が不正なトークン・jwt_tenants を指定した場合は取得しない）。
+///
+/// # 引数
+/// * `token` - 検証対象の JWT トークン文字列
+/// * `now` - 現在時刻（Unix タイムスタンプ秒、JWKS のキャッシュの期限）
+/// * `validation` - ヘッダーの Base64URL の扱い（jwt_lenient_base64）
+///
+/// # 戻り値
+/// * `Err(VerifyError::KeysUnavailable)` - JWKS を取得できず、キャッシュもない場合
+fn load_keys(
+    token: &str,
+    now: u64,
+    validation: &Validation,
+) -> Result<VerificationKeys, VerifyError> {
+    let mut keys = VerificationKeys::load();
+    if keys.tenants.is_some() {
+        return Ok(keys);
+    }
+    let Some(url) = jwks_url() else {
+        return Ok(keys);
+    };
+    let Some(kid) = jwks_kid(token, validation) else {
+        return Ok(keys);
+    };
+    let cache = jwks::JwksCache::open(&url)
+        .map_err(|e| {
+            {
+                ::std::io::_print(format_args!("[Auth] {0}, JWKS unavailable\n", e));
+            };
+            VerifyError::KeysUnavailable
+        })?;
+    keys.jwks = Some(cache.key_set(&kid, now).ok_or(VerifyError::KeysUnavailable)?);
+    Ok(keys)
+}
+/// JWKS の URL（Spin 変数 jwt_jwks_url、未定義・空の場合は None で JWKS を使わない）
+fn jwks_url() -> Option<String> {
+    spin_sdk::variables::get(JWKS_URL_VARIABLE)
+        .ok()
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+}
+/// JWKS で鍵を選ぶトークンの kid（RS256 / ES256 で kid がある場合のみ）
+fn jwks_kid(token: &str, validation: &Validation) -> Option<String> {
+    let header = jwt::decode_header_with(token, validation).ok()?;
+    #[allow(non_exhaustive_omitted_patterns)]
+    match header.alg.as_str() {
+        "RS256" | "ES256" => true,
+        _ => false,
+    }
+        .then_some(header.kid)
+        .flatten()
+}
+/// exp / nbf / iat で許容する時計のずれ（秒）
+///
+/// Spin 変数 jwt_leeway_secs から読み込む。未定義の場合は既定値、不正な値の場合はログを出力して既定値。
+fn leeway() -> u64 {
+    let value = spin_sdk::variables::get(LEEWAY_VARIABLE).ok();
+    parse_leeway(value.as_deref())
+        .unwrap_or_else(|e| {
+            {
+                ::std::io::_print(
+                    format_args!("[Auth] {0}, using {1}s\n", e, DEFAULT_LEEWAY_SECS),
+                );
+            };
+            DEFAULT_LEEWAY_SECS
+        })
+}
+/// トークン全体の長さの上限（バイト）
+///
+/// Spin 変数 jwt_max_token_bytes から読み込む。未定義の場合は既定値、不正な値の場合はログを出力して既定値。
+fn max_token_bytes() -> usize {
+    let value = spin_sdk::variables::get(MAX_TOKEN_BYTES_VARIABLE).ok();
+    parse_max_token_bytes(value.as_deref())
+        .unwrap_or_else(|e| {
+            {
+                ::std::io::_print(
+                    format_args!("[Auth] {0}, using {1}\n", e, jwt::DEFAULT_MAX_TOKEN_BYTES),
+                );
+            };
+            jwt::DEFAULT_MAX_TOKEN_BYTES
+        })
+}
+/// on / off の Spin 変数（jwt_revocation_fail_open・jwt_dev_tokens・jwt_lenient_base64）を読み込む
+///
+/// 未定義の場合は off、不正な値の場合はログを出力して off。
+fn switch_variable(name: &str) -> bool {
+    let value = spin_sdk::variables::get(name).ok();
+    parse_switch(name, value.as_deref())
+        .unwrap_or_else(|e| {
+            {
+                ::std::io::_print(format_args!("[Auth] {0}, using off\n", e));
+            };
+            false
+        })
+}
+/// 期待する発行者（Spin 変数 jwt_issuer、未定義・空の場合は None で iss を検証しない）
+fn expected_issuer() -> Option<String> {
+    spin_sdk::variables::get(ISSUER_VARIABLE)
+        .ok()
+        .map(|issuer| issuer.trim().to_string())
+        .filter(|issuer| !issuer.is_empty())
+}
+/// 受け付ける対象者（Spin 変数 jwt_audiences、未定義・空の場合は空で aud を検証しない）
+fn allowed_audiences() -> Vec<String> {
+    let value = spin_sdk::variables::get(AUDIENCES_VARIABLE).ok();
+    parse_audiences(value.as_deref())
+}
+/// jwt_audiences の値を解析する
+///
+/// カンマで区切り、前後の空白を除く。空の要素は無視する。
+///
+/// # 引数
+/// * `value` - Spin 変数の値（未定義の場合は None）
+fn parse_audiences(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|audience| !audience.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+/// 受け付けるアルゴリズム
+///
+/// Spin 変数 jwt_algorithms から読み込む。未定義の場合は既定値、不正な値の場合はログを出力して既定値。
+fn allowed_algorithms() -> Vec<String> {
+    let value = spin_sdk::variables::get(ALGORITHMS_VARIABLE).ok();
+    parse_algorithms(value.as_deref())
+        .unwrap_or_else(|e| {
+            {
+                ::std::io::_print(
+                    format_args!(
+                        "[Auth] {0}, using {1}\n", e, DEFAULT_ALGORITHMS.join(",")
+                    ),
+                );
+            };
+            parse_algorithms(None).unwrap_or_default()
+        })
+}
+/// jwt_algorithms の値を解析する
+///
+/// カンマで区切り、前後の空白を除く。空の要素は無視する。
+///
+/// # 引数
+/// * `value` - 変数の値（未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(Vec<String>)` - 受け付けるアルゴリズム（未定義・空の場合は DEFAULT_ALGORITHMS）
+/// * `Err(String)` - jwt::ALLOWED_ALGORITHMS に含まれない値（"none"、RS512 など）がある場合
+fn parse_algorithms(value: Option<&str>) -> Result<Vec<String>, String> {
+    let algorithms: Vec<String> = value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|alg| !alg.is_empty())
+        .map(str::to_string)
+        .collect();
+    if algorithms.is_empty() {
+        return Ok(DEFAULT_ALGORITHMS.iter().map(|alg| alg.to_string()).collect());
+    }
+    match algorithms.iter().find(|alg| !jwt::ALLOWED_ALGORITHMS.contains(&alg.as_str()))
+    {
+        Some(unknown) => {
+            Err(
+                ::alloc::__export::must_use({
+                    ::alloc::fmt::format(
+                        format_args!(
+                            "Invalid {0} {1:?}: must be a comma-separated list of {2}",
+                            ALGORITHMS_VARIABLE, unknown, jwt::ALLOWED_ALGORITHMS.join(", ")
+                        ),
+                    )
+                }),
+            )
+        }
+        None => Ok(algorithms),
+    }
+}
+/// ヘッダーの typ の検証
+///
+/// Spin 変数 jwt_typ_policy から読み込む。未定義・空の場合は validate-if-present、
+/// 不正な値の場合はログを出力して validate-if-present。
+fn typ_policy() -> TypPolicy {
+    let value = spin_sdk::variables::get(TYP_POLICY_VARIABLE).ok();
+    parse_typ_policy(value.as_deref()).unwrap_or_else(|e| {
+        {
+            ::std::io::_print(
+                format_args!("[Auth] {0}, using validate-if-present\n", e),
+            );
+        };
+        TypPolicy::default()
+    })
+}
+/// jwt_typ_policy の値を解析する
+///
+/// # 引数
+/// * `value` - 変数の値（未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(TypPolicy)` - 検証の方法（未定義・空の場合は TypPolicy::ValidateIfPresent、大文字小文字を区別しない）
+/// * `Err(String)` - ignore / validate-if-present / require 以外の場合
+fn parse_typ_policy(value: Option<&str>) -> Result<TypPolicy, String> {
+    let value = value.map(str::trim).unwrap_or_default();
+    match value.to_ascii_lowercase().as_str() {
+        "" => Ok(TypPolicy::default()),
+        "ignore" => Ok(TypPolicy::Ignore),
+        "validate-if-present" => Ok(TypPolicy::ValidateIfPresent),
+        "require" => Ok(TypPolicy::Require),
+        _ => {
+            Err(
+                ::alloc::__export::must_use({
+                    ::alloc::fmt::format(
+                        format_args!(
+                            "Invalid {0} {1:?}: must be ignore, validate-if-present or require",
+                            TYP_POLICY_VARIABLE,
+                            value,
+                        ),
+                    )
+                }),
+            )
+        }
+    }
+}
+/// sub クレームに許可する形式
+///
+/// Spin 変数 jwt_subject_pattern から読み込む。未定義・空の場合は UUID、不正な値の場合はログを出力して UUID。
+fn subject_rule() -> SubjectRule {
+    let value = spin_sdk::variables::get(SUBJECT_PATTERN_VARIABLE).ok();
+    parse_subject_pattern(value.as_deref())
+        .unwrap_or_else(|e| {
+            {
+                ::std::io::_print(
+                    format_args!("[Auth] {0}, accepting UUID subjects only\n", e),
+                );
+            };
+            SubjectRule::Uuid
+        })
+}
+/// jwt_subject_pattern の値を解析する
+///
+/// 部分一致で受け付けないよう、sub 全体に一致する正規表現（`^(?:...)$`）にする。
+///
+/// # 引数
+/// * `value` - 変数の値（未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(SubjectRule)` - 許可する形式（未定義・空の場合は SubjectRule::Uuid）
+/// * `Err(String)` - 正規表現として不正な場合
+fn parse_subject_pattern(value: Option<&str>) -> Result<SubjectRule, String> {
+    let value = value.map(str::trim).unwrap_or_default();
+    if value.is_empty() {
+        return Ok(SubjectRule::Uuid);
+    }
+    Regex::new(
+            &::alloc::__export::must_use({
+                ::alloc::fmt::format(format_args!("^(?:{0})$", value))
+            }),
+        )
+        .map(SubjectRule::Pattern)
+        .map_err(|e| ::alloc::__export::must_use({
+            ::alloc::fmt::format(
+                format_args!(
+                    "Invalid {0} {1:?}: {2}", SUBJECT_PATTERN_VARIABLE, value, e
+                ),
+            )
+        }))
+}
+/// jwt_hs256_keys の値を解析する
+///
+/// # 引数
+/// * `value` - 変数の値（kid → シークレットの JSON オブジェクト、未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(Some(HmacKeyring))` - 鍵リング（記載順）
+/// * `Ok(None)` - 未定義・空の場合（jwt_secret を使う）
+/// * `Err(String)` - JSON が不正、鍵がない、kid・シークレットが空、kid に空白を含む・重複している場合
+fn parse_hs256_keys(value: Option<&str>) -> Result<Option<HmacKeyring>, String> {
+    let value = value.map(str::trim).unwrap_or_default();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let invalid = |reason: String| ::alloc::__export::must_use({
+        ::alloc::fmt::format(format_args!("Invalid {0}: {1}", HS256_KEYS_VARIABLE, reason))
+    });
+    let OrderedSecrets(entries) = serde_json::from_str(value)
+        .map_err(|e| invalid(e.to_string()))?;
+    hmac_keyring(entries).map(Some).map_err(invalid)
+}
+/// kid → シークレットの一覧から鍵リングを作る（jwt_hs256_keys・jwt_tenants の keys）
+///
+/// # 戻り値
+/// * `Ok(HmacKeyring)` - 鍵リング（一覧の順）
+/// * `Err(String)` - 鍵がない、kid・シークレットが空、kid に空白を含む・重複している場合の理由
+fn hmac_keyring(entries: Vec<(String, String)>) -> Result<HmacKeyring, String> {
+    if entries.is_empty() {
+        return Err("no keys defined".to_string());
+    }
+    let mut keys: Vec<HmacKey> = Vec::with_capacity(entries.len());
+    for (id, secret) in entries {
+        if id.is_empty() || id.chars().any(char::is_whitespace) {
+            return Err(
+                ::alloc::__export::must_use({
+                    ::alloc::fmt::format(format_args!("invalid key id {0:?}", id))
+                }),
+            );
+        }
+        if secret.is_empty() {
+            return Err(
+                ::alloc::__export::must_use({
+                    ::alloc::fmt::format(
+                        format_args!("key {0:?} has an empty secret", id),
+                    )
+                }),
+            );
+        }
+        if keys.iter().any(|key| key.id == id) {
+            return Err(
+                ::alloc::__export::must_use({
+                    ::alloc::fmt::format(format_args!("duplicate key id {0:?}", id))
+                }),
+            );
+        }
+        keys.push(HmacKey {
+            id,
+            secret: secret.into_bytes(),
+        });
+    }
+    Ok(HmacKeyring { keys })
+}
+/// jwt_tenants の値を解析する
+///
+/// # 引数
+/// * `value` - 変数の値（発行者 → 設定の JSON オブジェクト、未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(Some(Vec<Tenant>))` - 発行者ごとの設定
+/// * `Ok(None)` - 未定義・空の場合（発行者ごとの設定を使わない）
+/// * `Err(String)` - JSON が不正、発行者がない、いずれかの発行者の設定が不正な場合
+fn parse_tenants(value: Option<&str>) -> Result<Option<Vec<Tenant>>, String> {
+    let value = value.map(str::trim).unwrap_or_default();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let invalid = |reason: String| ::alloc::__export::must_use({
+        ::alloc::fmt::format(format_args!("Invalid {0}: {1}", TENANTS_VARIABLE, reason))
+    });
+    let tenants: Map<String, Value> = serde_json::from_str(value)
+        .map_err(|e| invalid(e.to_string()))?;
+    if tenants.is_empty() {
+        return Err(invalid("no issuers defined".to_string()));
+    }
+    tenants
+        .into_iter()
+        .map(|(issuer, config)| parse_tenant(issuer, &config).map_err(invalid))
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+/// jwt_tenants の 1 つの発行者の設定を解析する
+///
+/// # 引数
+/// * `issuer` - 発行者（iss）
+/// * `config` - 設定（TENANT_FIELDS のフィールドを持つ JSON オブジェクト）
+///
+/// # 戻り値
+/// * `Ok(Tenant)` - 発行者の設定
+/// * `Err(String)` - 発行者が空、alg が HMAC のアルゴリズムでない、keys が不正、audiences が空でない文字列の配列でない、
+///   未知のフィールドがある場合の理由（シークレットは含めない）
+fn parse_tenant(issuer: String, config: &Value) -> Result<Tenant, String> {
+    if issuer.trim().is_empty() {
+        return Err(
+            ::alloc::__export::must_use({
+                ::alloc::fmt::format(format_args!("invalid issuer {0:?}", issuer))
+            }),
+        );
+    }
+    let invalid = |reason: &str| ::alloc::__export::must_use({
+        ::alloc::fmt::format(format_args!("issuer {0:?} {1}", issuer, reason))
+    });
+    let Some(config) = config.as_object() else {
+        return Err(invalid("must be an object"));
+    };
+    if let Some(field) = config
+        .keys()
+        .find(|field| !TENANT_FIELDS.contains(&field.as_str()))
+    {
+        return Err(
+            invalid(
+                &::alloc::__export::must_use({
+                    ::alloc::fmt::format(format_args!("has an unknown field {0:?}", field))
+                }),
+            ),
+        );
+    }
+    let algorithm = config
+        .get("alg")
+        .and_then(Value::as_str)
+        .filter(|alg| jwt::HMAC_ALGORITHMS.contains(alg))
+        .ok_or_else(|| {
+            invalid(
+                &::alloc::__export::must_use({
+                    ::alloc::fmt::format(
+                        format_args!(
+                            "must set alg to one of {0}", jwt::HMAC_ALGORITHMS.join(", ")
+                        ),
+                    )
+                }),
+            )
+        })?
+        .to_string();
+    let OrderedSecrets(entries) = config
+        .get("keys")
+        .ok_or_else(|| invalid("has no keys"))
+        .and_then(|keys| {
+            OrderedSecrets::deserialize(keys)
+                .map_err(|e| invalid(
+                    &::alloc::__export::must_use({
+                        ::alloc::fmt::format(format_args!("has invalid keys: {0}", e))
+                    }),
+                ))
+        })?;
+    let keyring = hmac_keyring(entries)
+        .map_err(|reason| invalid(
+            &::alloc::__export::must_use({
+                ::alloc::fmt::format(format_args!("has invalid keys: {0}", reason))
+            }),
+        ))?;
+    let audiences = match config.get("audiences") {
+        None => Vec::new(),
+        Some(audiences) => {
+            Vec::<String>::deserialize(audiences)
+                .ok()
+                .filter(|audiences| {
+                    audiences.iter().all(|audience| !audience.trim().is_empty())
+                })
+                .ok_or_else(|| invalid(
+                    "must set audiences to an array of non-empty strings",
+                ))?
+        }
+    };
+    Ok(Tenant {
+        issuer,
+        algorithm,
+        keyring,
+        audiences,
+    })
+}
+/// 公開鍵を Spin 変数から読み込む（不正な鍵の場合はログを出力して None）
+///
+/// # 引数
+/// * `variable` - Spin 変数名
+/// * `parse` - 値の解析（parse_rsa_public_key / parse_ec_public_key）
+fn load_public_key<K>(
+    variable: &str,
+    parse: fn(Option<&str>) -> Result<Option<K>, String>,
+) -> Option<K> {
+    let value = spin_sdk::variables::get(variable).ok();
+    parse(value.as_deref())
+        .unwrap_or_else(|e| {
+            {
+                ::std::io::_print(format_args!("[Auth] {0}, ignoring the public key\n", e));
+            };
+            None
+        })
+}
+/// 公開鍵の Spin 変数の値を整える
+///
+/// 前後の空白を除き、`\n` を改行に戻す
+/// （Spin 変数（環境変数 SPIN_VARIABLE_*）では改行を書きにくいため）。未定義・空の場合は None。
+fn key_material(value: Option<&str>) -> Option<String> {
+    let value = value.map(str::trim).unwrap_or_default();
+    (!value.is_empty()).then(|| value.replace("\\n", "\n"))
+}
+/// jwt_rs256_public_key の値を解析する
+///
+/// # 引数
+/// * `value` - 変数の値（PEM、未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(Some(RsaPublicKey))` - 公開鍵
+/// * `Ok(None)` - 未定義・空の場合
+/// * `Err(String)` - PEM・鍵の形式が不正な場合
+fn parse_rsa_public_key(value: Option<&str>) -> Result<Option<RsaPublicKey>, String> {
+    let Some(pem) = key_material(value) else {
+        return Ok(None);
+    };
+    RsaPublicKey::from_pem(&pem)
+        .map(Some)
+        .map_err(|e| ::alloc::__export::must_use({
+            ::alloc::fmt::format(
+                format_args!("Invalid {0}: {1}", RS256_PUBLIC_KEY_VARIABLE, e),
+            )
+        }))
+}
+/// jwt_es256_public_key の値を解析する
+///
+/// `{` で始まる場合は JWK、それ以外は PEM として読み込む。
+///
+/// # 引数
+/// * `value` - 変数の値（PEM または JWK、未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(Some(EcPublicKey))` - 公開鍵
+/// * `Ok(None)` - 未定義・空の場合
+/// * `Err(String)` - PEM・JWK・鍵の形式が不正、または P-256 の鍵でない場合
+fn parse_ec_public_key(value: Option<&str>) -> Result<Option<EcPublicKey>, String> {
+    let Some(key) = key_material(value) else {
+        return Ok(None);
+    };
+    let parsed = if key.starts_with('{') {
+        EcPublicKey::from_jwk(&key)
+    } else {
+        EcPublicKey::from_pem(&key)
+    };
+    parsed
+        .map(Some)
+        .map_err(|e| ::alloc::__export::must_use({
+            ::alloc::fmt::format(
+                format_args!("Invalid {0}: {1}", ES256_PUBLIC_KEY_VARIABLE, e),
+            )
+        }))
+}
+/// on / off の変数の値を解析する
+///
+/// # 引数
+/// * `name` - 変数名（エラーメッセージに使う）
+/// * `value` - 変数の値（未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(bool)` - on の場合は true、off・未定義・空の場合は false（大文字小文字を区別しない）
+/// * `Err(String)` - on / off 以外の場合
+fn parse_switch(name: &str, value: Option<&str>) -> Result<bool, String> {
+    let value = value.map(str::trim).unwrap_or_default();
+    if value.is_empty() || value.eq_ignore_ascii_case("off") {
+        return Ok(false);
+    }
+    if value.eq_ignore_ascii_case("on") {
+        return Ok(true);
+    }
+    Err(
+        ::alloc::__export::must_use({
+            ::alloc::fmt::format(
+                format_args!("Invalid {0} {1:?}: must be on or off", name, value),
+            )
+        }),
+    )
+}
+/// jwt_leeway_secs の値を解析する
+///
+/// # 引数
+/// * `value` - 変数の値（未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(u64)` - leeway（秒、未定義・空の場合は DEFAULT_LEEWAY_SECS）
+/// * `Err(String)` - 0 以上の整数でない、または MAX_LEEWAY_SECS を超える場合
+fn parse_leeway(value: Option<&str>) -> Result<u64, String> {
+    let value = value.map(str::trim).unwrap_or_default();
+    if value.is_empty() {
+        return Ok(DEFAULT_LEEWAY_SECS);
+    }
+    match value.parse::<u64>() {
+        Ok(leeway) if leeway <= MAX_LEEWAY_SECS => Ok(leeway),
+        _ => {
+            Err(
+                ::alloc::__export::must_use({
+                    ::alloc::fmt::format(
+                        format_args!(
+                            "Invalid {0} {1:?}: must be an integer between 0 and {2}",
+                            LEEWAY_VARIABLE, value, MAX_LEEWAY_SECS
+                        ),
+                    )
+                }),
+            )
+        }
+    }
+}
+/// jwt_max_token_bytes の値を解析する
+///
+/// # 引数
+/// * `value` - 変数の値（未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(usize)` - 上限（バイト、未定義・空の場合は jwt::DEFAULT_MAX_TOKEN_BYTES）
+/// * `Err(String)` - 整数でない、または MAX_TOKEN_BYTES_RANGE の範囲外の場合
+fn parse_max_token_bytes(value: Option<&str>) -> Result<usize, String> {
+    let value = value.map(str::trim).unwrap_or_default();
+    if value.is_empty() {
+        return Ok(jwt::DEFAULT_MAX_TOKEN_BYTES);
+    }
+    match value.parse::<usize>() {
+        Ok(bytes) if MAX_TOKEN_BYTES_RANGE.contains(&bytes) => Ok(bytes),
+        _ => {
+            Err(
+                ::alloc::__export::must_use({
+                    ::alloc::fmt::format(
+                        format_args!(
+                            "Invalid {0} {1:?}: must be an integer between {2} and {3}",
+                            MAX_TOKEN_BYTES_VARIABLE, value, MAX_TOKEN_BYTES_RANGE.start(),
+                            MAX_TOKEN_BYTES_RANGE.end()
+                        ),
+                    )
+                }),
+            )
+        }
+    }
+}
+/// JWT トークンを検証し、ユーザーIDとクレームを抽出する
+///
+/// JWT の構造: ヘッダー.ペイロード.署名（すべて Base64URL エンコード）
+///
+/// # 検証手順
+/// 1. トークンが空でないことを確認
+///    - 長さの上限（jwt_max_token_bytes、既定 8 KiB。ヘッダー・署名は jwt クレートの上限）を超える場合は、
+///      Base64URL をデコードする前に "Token too large"
+///    - 5 つの部分の JWE（暗号化したトークン）は、Base64URL をデコードする前に
+///      "Encrypted tokens (JWE) are not supported"（ヘッダーに enc があるトークンも同じ）
+/// 2. ヘッダーの alg・kid で鍵を選ぶ（RS256 / ES256 は公開鍵を指定した場合のみ公開鍵、それ以外は HS256 のシークレット）
+///    - HS256 / HS384 / HS512 は鍵リング（jwt_hs256_keys、ない場合は jwt_secret）の kid の鍵。kid がない場合は新しい鍵から順に試し、
+///      署名が一致した鍵で検証する（ない場合は "Unknown signing key"、どちらも未設定の場合は "Auth misconfigured"）
+///    - JWKS がある場合は kid の鍵（ない場合は "Unknown signing key"）
+///    - jwt_tenants を指定した場合は、署名を検証する前の iss で発行者を選び、その発行者の kid の鍵のみ
+///      （iss がない・未知の発行者は署名を確認せずに "Unknown issuer"。発行者・対象者・アルゴリズムも
+///      jwt_issuer などの代わりにその発行者の設定で検証する）
+///    - 鍵を選ぶ前に、ヘッダーの typ を jwt_typ_policy で検証する（JWT 以外・require で typ がない場合は
+///      "Invalid typ header"）
+/// 3. jwt クレートで検証（コア層の KeySet::verify と同じ規則）
+///    - アルゴリズムが許可リスト（jwt_algorithms、既定は HS256 / RS256 / ES256。HS384 / HS512 は明示した場合のみ）に含まれ、
+///      鍵の種類と一致すること（公開鍵を指定していない RS256 / ES256・公開鍵で署名した HS256 は拒否する）
+///    - "none" は大文字小文字・jwt_algorithms にかかわらず、鍵を選ぶ前に
+///      "Unsecured algorithm not allowed: none" で拒否する
+///    - HMAC-SHA256 / 384 / 512（alg で選ぶ）/ RSASSA-PKCS1-v1_5 / ECDSA P-256 の署名（ES256 は r || s の 64 バイト、DER は拒否する）
+///    - sub と exp があること（exp のないトークンは拒否する）
+///    - exp + leeway が現在時刻以降であること（過ぎた場合は "Token expired"）
+///    - nbf がある場合、nbf - leeway が現在時刻以前であること（前の場合は "Token not yet valid"、
+///      予約したジョブ用に事前に発行したトークン。nbf がないトークンは許可する）
+///    - iat がある場合、iat - leeway が現在時刻以前であること（未来の場合は "Token issued in the future"、
+///      時計の誤りか偽造）
+///    - 発行者を指定した場合（jwt_issuer）、iss が一致すること（iss がない・異なる場合は "Invalid issuer"）
+///    - 対象者を指定した場合（jwt_audiences）、aud のいずれかが含まれること
+///      （aud がない・空の配列・一致しない場合は "Invalid audience"）
+/// 4. 有効期限・ロール・許可リストのクレームを抽出
+///
+/// # 引数
+/// * `token` - 検証対象の JWT トークン文字列
+/// * `keys` - 検証に使う鍵（jwt_secret / jwt_hs256_keys / jwt_rs256_public_key / jwt_es256_public_key / jwt_jwks_url / jwt_tenants）
+/// * `validation` - 現在時刻（WASI の壁時計）・exp / nbf / iat の leeway（jwt_leeway_secs）・
+///   期待する発行者（jwt_issuer）・受け付ける対象者（jwt_audiences）・アルゴリズム（jwt_algorithms）
+///
+/// # 戻り値
+/// * `Ok(VerifiedToken)` - 検証成功時、ユーザーIDとクレームを返す
+/// * `Err(VerifyError)` - 検証失敗時、失敗の理由を返す
+fn verify_jwt(
+    token: &str,
+    keys: &VerificationKeys,
+    validation: &Validation,
+) -> Result<VerifiedToken, VerifyError> {
+    if token.is_empty() {
+        return Err(VerifyError::MissingToken);
+    }
+    let header = jwt::decode_header_with(token, validation).map_err(VerifyError::Jwt)?;
+    if header.alg.trim().eq_ignore_ascii_case(jwt::UNSECURED_ALGORITHM) {
+        return Err(VerifyError::Jwt(jwt::Error::UnsecuredAlgorithm(header.alg)));
+    }
+    if !keys.typ_policy.accepts(header.typ.as_deref()) {
+        return Err(VerifyError::InvalidTyp);
+    }
+    let claims = match &keys.tenants {
+        Some(tenants) => {
+            let tenant = select_tenant(token, tenants, validation)?;
+            let audiences: Vec<&str> = tenant
+                .audiences
+                .iter()
+                .map(String::as_str)
+                .collect();
+            let algorithms = [tenant.algorithm.as_str()];
+            let validation = validation
+                .with_issuer(Some(&tenant.issuer))
+                .with_audiences(&audiences)
+                .with_algorithms(&algorithms);
+            let candidates = tenant.keyring.decoding_keys(header.kid.as_deref())?;
+            decode_with_candidates(token, &candidates, &validation)?
+        }
+        None => decode_with_candidates(token, &keys.decoding_keys(&header)?, validation)?,
+    };
+    Ok(VerifiedToken {
+        expires_at: Some(claims.exp),
+        issued_at: (claims.iat != 0).then_some(claims.iat),
+        roles: claims.roles(),
+        claims: extract_claims(&claims),
+        jti: claims.claim_str("jti"),
+        token_use: claims.claim_str(jwt::TOKEN_USE_CLAIM),
+        token_version: claims.extra.get("ver").and_then(Value::as_u64).unwrap_or(0),
+        one_time: claims.extra.get("one_time") == Some(&Value::Bool(true)),
+        user_id: claims.sub,
+    })
+}
+/// 署名を検証する前の iss で jwt_tenants の発行者を選ぶ
+///
+/// iss は鍵を選ぶためだけに使い、選んだ発行者の設定で署名・iss を検証する（verify_jwt）。
+///
+/// # 引数
+/// * `token` - 検証対象の JWT トークン文字列
+/// * `tenants` - 発行者ごとの設定（jwt_tenants）
+/// * `validation` - ペイロードの Base64URL の扱い（jwt_lenient_base64）・長さの上限
+///
+/// # 戻り値
+/// * `Ok(&Tenant)` - iss の発行者の設定
+/// * `Err(VerifyError::UnknownIssuer)` - iss がない・文字列でない・jwt_tenants にない発行者の場合
+/// * `Err(VerifyError::Misconfigured)` - jwt_tenants が不正な場合（発行者の設定が空）
+/// * `Err(VerifyError::Jwt)` - ペイロードを読めない場合
+fn select_tenant<'a>(
+    token: &str,
+    tenants: &'a [Tenant],
+    validation: &Validation,
+) -> Result<&'a Tenant, VerifyError> {
+    if tenants.is_empty() {
+        return Err(VerifyError::Misconfigured);
+    }
+    let issuer = jwt::decode_issuer_with(token, validation).map_err(VerifyError::Jwt)?;
+    issuer
+        .and_then(|issuer| tenants.iter().find(|tenant| tenant.issuer == issuer))
+        .ok_or(VerifyError::UnknownIssuer)
+}
+/// 候補の鍵で順に検証する
+///
+/// kid のない HMAC のトークンは署名が一致しない間だけ次の鍵を試す（最後の鍵の結果を返す）。
+///
+/// # 引数
+/// * `token` - 検証対象の JWT トークン文字列
+/// * `candidates` - 試す鍵（1 つ以上）
+/// * `validation` - 検証の条件
+fn decode_with_candidates(
+    token: &str,
+    candidates: &[DecodingKey<'_>],
+    validation: &Validation,
+) -> Result<Claims, VerifyError> {
+    let mut result = Err(jwt::Error::InvalidSignature);
+    for key in candidates {
+        result = jwt::decode_with_key(token, key, validation);
+        if result != Err(jwt::Error::InvalidSignature) {
+            break;
+        }
+    }
+    result.map_err(VerifyError::Jwt)
+}
+/// 使い捨てのトークン（one_time: true）の jti を使用済みとして記録する
+///
+/// 使い捨てでないトークンはストアを開かずに検証を通す。
+/// 使用済みの記録を読み書きできない場合は、jwt_revocation_fail_open に関わらず拒否する
+/// （同じトークンを 2 回受け付けないため）。
+///
+/// # 引数
+/// * `verified` - 失効リストとの照合まで成功したトークン
+/// * `open` - 使用済みの jti の保存先を開く（使い捨てのトークンの場合のみ呼ぶ）
+/// * `now` - 現在時刻（Unix タイムスタンプ秒）
+/// * `leeway` - 許容する時計のずれ（秒、exp + leeway まで使用済みとして記録する）
+///
+/// # 戻り値
+/// * `Ok(VerifiedToken)` - 使い捨てでない、または初めて提示された場合
+/// * `Err(VerifyError::Reused)` - 使用済みの場合
+/// * `Err(VerifyError::OneTimeWithoutJti)` - jti がない・空の場合
+/// * `Err(VerifyError::OneTimeUnavailable)` - 使用済みの記録を読み書きできない場合
+fn check_one_time<S: one_time::CasStore>(
+    verified: VerifiedToken,
+    open: impl FnOnce() -> Result<S, String>,
+    now: u64,
+    leeway: u64,
+) -> Result<VerifiedToken, VerifyError> {
+    if !verified.one_time {
+        return Ok(verified);
+    }
+    let Some(jti) = verified.jti.as_deref().filter(|jti| !jti.is_empty()) else {
+        {
+            ::std::io::_print(
+                format_args!(
+                    "[Auth] One-time token without jti: sub={0}\n",
+                    verified.user_id,
+                ),
+            );
+        };
+        return Err(VerifyError::OneTimeWithoutJti);
+    };
+    let valid_until = verified.expires_at.unwrap_or(now).saturating_add(leeway);
+    match open().and_then(|store| one_time::consume(&store, jti, valid_until, now)) {
+        Ok(true) => Ok(verified),
+        Ok(false) => {
+            {
+                ::std::io::_print(
+                    format_args!(
+                        "[Auth] One-time token already used: sub={0}\n",
+                        verified.user_id,
+                    ),
+                );
+            };
+            Err(VerifyError::Reused)
+        }
+        Err(e) => {
+            {
+                ::std::io::_print(
+                    format_args!("[Auth] {0}, rejecting one-time token\n", e),
+                );
+            };
+            Err(VerifyError::OneTimeUnavailable)
+        }
+    }
+}
+/// 失効リストとの照合の結果を検証結果に反映する
+///
+/// 失効リストを読めない場合、fail_open（jwt_revocation_fail_open=on）なら警告して検証を通し、
+/// それ以外は "Revocation check unavailable" で拒否する。
+///
+/// # 引数
+/// * `verified` - verify_jwt で検証に成功したトークン
+/// * `revoked` - revocation::is_revoked の結果（ストアを開けない場合を含む）
+/// * `fail_open` - 失効リストを読めない場合に検証を通すか
+///
+/// # 戻り値
+/// * `Ok(VerifiedToken)` - 失効していない場合（fail_open で確認できなかった場合を含む）
+/// * `Err(VerifyError::Revoked)` - 失効している場合
+/// * `Err(VerifyError::RevocationUnavailable)` - 失効リストを読めず、fail_open でない場合
+fn check_revocation(
+    verified: VerifiedToken,
+    revoked: Result<bool, String>,
+    fail_open: bool,
+) -> Result<VerifiedToken, VerifyError> {
+    match revoked {
+        Ok(false) => Ok(verified),
+        Ok(true) => {
+            {
+                ::std::io::_print(
+                    format_args!("[Auth] Token revoked: sub={0}\n", verified.user_id),
+                );
+            };
+            Err(VerifyError::Revoked)
+        }
+        Err(e) if fail_open => {
+            {
+                ::std::io::_print(
+                    format_args!(
+                        "[Auth] {0}, skipping revocation check ({1}=on)\n",
+                        e,
+                        REVOCATION_FAIL_OPEN_VARIABLE,
+                    ),
+                );
+            };
+            Ok(verified)
         }
+        Err(e) => {
+            {
+                ::std::io::_print(format_args!("[Auth] {0}, rejecting token\n", e));
+            };
+            Err(VerifyError::RevocationUnavailable)
+        }
+    }
+}
+/// ユーザーのバージョンとの比較の結果を検証結果に反映する
+///
+/// ストアを読めない場合は失効リストと同じく、fail_open（jwt_revocation_fail_open=on）なら警告して検証を通し、
+/// それ以外は "Revocation check unavailable" で拒否する。
+///
+/// # 引数
+/// * `verified` - 失効リストとの照合まで成功したトークン
+/// * `superseded` - revocation::is_superseded の結果（ストアを開けない場合を含む）
+/// * `fail_open` - ストアを読めない場合に検証を通すか
+///
+/// # 戻り値
+/// * `Ok(VerifiedToken)` - ver がユーザーのバージョン以上の場合（fail_open で確認できなかった場合を含む）
+/// * `Err(VerifyError::Superseded)` - ver がユーザーのバージョンより小さい場合
+/// * `Err(VerifyError::RevocationUnavailable)` - ストアを読めず、fail_open でない場合
+fn check_token_version(
+    verified: VerifiedToken,
+    superseded: Result<bool, String>,
+    fail_open: bool,
+) -> Result<VerifiedToken, VerifyError> {
+    match superseded {
+        Ok(false) => Ok(verified),
+        Ok(true) => {
+            {
+                ::std::io::_print(
+                    format_args!(
+                        "[Auth] Token superseded: sub={0} ver={1}\n",
+                        verified.user_id,
+                        verified.token_version,
+                    ),
+                );
+            };
+            Err(VerifyError::Superseded)
+        }
+        Err(e) if fail_open => {
+            {
+                ::std::io::_print(
+                    format_args!(
+                        "[Auth] {0}, skipping token version check ({1}=on)\n",
+                        e,
+                        REVOCATION_FAIL_OPEN_VARIABLE,
+                    ),
+                );
+            };
+            Ok(verified)
+        }
+        Err(e) => {
+            {
+                ::std::io::_print(format_args!("[Auth] {0}, rejecting token\n", e));
+            };
+            Err(VerifyError::RevocationUnavailable)
+        }
+    }
+}
+/// sub クレームの形式を確認する
+///
+/// gateway は sub をそのまま X-User-Id に設定するため、SQL の断片・長い文字列・改行などを
+/// コア層に転送しない（コア層の UserContext も UUID のみ受け付ける）。
+///
+/// # 引数
+/// * `verified` - verify_jwt で検証に成功したトークン
+/// * `rule` - sub に許可する形式（jwt_subject_pattern）
+///
+/// # 戻り値
+/// * `Ok(VerifiedToken)` - sub が形式に一致する場合
+/// * `Err(VerifyError::InvalidSubject)` - 一致しない場合
+fn check_subject(
+    verified: VerifiedToken,
+    rule: &SubjectRule,
+) -> Result<VerifiedToken, VerifyError> {
+    if rule.matches(&verified.user_id) {
+        Ok(verified)
+    } else {
+        Err(VerifyError::InvalidSubject)
+    }
+}
+/// トークンの種類（token_use クレーム）を確認する
+///
+/// リフレッシュトークンもアクセストークンと同じ鍵で署名するため、漏えいしたリフレッシュトークンで
+/// API を呼び出せないよう access のみ受け付ける（コア層のリフレッシュのエンドポイントは独自に検証する）。
+///
+/// # 引数
+/// * `verified` - verify_jwt で検証に成功したトークン
+/// * `allow_untyped` - token_use のないトークンを受け付けるか（jwt_allow_untyped_tokens）
+///
+/// # 戻り値
+/// * `Ok(VerifiedToken)` - token_use が access、または token_use がなく allow_untyped の場合
+/// * `Err(VerifyError::WrongTokenType)` - それ以外の場合
+fn check_token_use(
+    verified: VerifiedToken,
+    allow_untyped: bool,
+) -> Result<VerifiedToken, VerifyError> {
+    match verified.token_use.as_deref() {
+        Some(jwt::TOKEN_USE_ACCESS) => Ok(verified),
+        None if allow_untyped => Ok(verified),
+        _ => Err(VerifyError::WrongTokenType),
+    }
+}
+/// 許可リスト（CLAIM_ALLOWLIST）に含まれるクレームを取り出す
+///
+/// 値は文字列に変換する。ヘッダーとして転送できない配列・オブジェクト・null は含めない。
+///
+/// # 引数
+/// * `claims` - 検証済みのクレーム
+///
+/// # 戻り値
+/// * `Vec<(String, String)>` - (クレーム名, 値) の一覧（CLAIM_ALLOWLIST の順）
+fn extract_claims(claims: &Claims) -> Vec<(String, String)> {
+    CLAIM_ALLOWLIST
+        .iter()
+        .filter_map(|&name| Some((name.to_string(), claims.claim_str(name)?)))
+        .collect()
+}
+/// カウンタの保存先
+///
+/// テストではメモリ上の実装に差し替え、key-value ストアなしで集計を検証します。
+trait Counter {
+    /// キーの値を delta 増やし、増やした後の値を返す（キーがなければ 0 から数える）
+    fn increment(&self, key: &str, delta: u64) -> Result<u64, String>;
+}
+/// Spin の key-value ストアを使うカウンタ
+struct KvCounter {
+    bucket: store::Bucket,
+}
+impl KvCounter {
+    /// key-value ストアを開く
+    fn open() -> Result<Self, String> {
+        store::open(STORE_NAME)
+            .map(|bucket| Self { bucket })
+            .map_err(|e| ::alloc::__export::must_use({
+                ::alloc::fmt::format(
+                    format_args!("Failed to open key-value store: {0:?}", e),
+                )
+            }))
     }
-    payload.sub.ok_or_else(|| "Missing subject claim".to_string())
 }
-/// HMAC-SHA256 で署名を検証する
+impl Counter for KvCounter {
+    fn increment(&self, key: &str, delta: u64) -> Result<u64, String> {
+        atomics::increment(&self.bucket, key, delta as i64)
+            .map(|count| count.max(0) as u64)
+            .map_err(|e| ::alloc::__export::must_use({
+                ::alloc::fmt::format(
+                    format_args!("Failed to increment counter: {0:?}", e),
+                )
+            }))
+    }
+}
+/// 検証結果を数える
+///
+/// 呼び出し回数（auth_verifications_total）と、成功または失敗の理由のカウンタを 1 増やす。
+/// それぞれのカウンタはアトミックにインクリメントするため、同時に実行されても失われない。
 ///
 /// # 引数
-/// * `message` - 署名対象のメッセージ（ヘッダー.ペイロード）
-/// * `signature` - 検証する署名（バイト列）
+/// * `counter` - カウンタの保存先
+/// * `result` - verify_jwt の結果
+fn record_verification(
+    counter: &impl Counter,
+    result: &Result<VerifiedToken, VerifyError>,
+) -> Result<(), String> {
+    let outcome = match result {
+        Ok(_) => VERIFICATIONS_SUCCESS,
+        Err(e) => e.metric(),
+    };
+    counter.increment(&metric_key(VERIFICATIONS_TOTAL), 1)?;
+    counter.increment(&metric_key(outcome), 1)?;
+    Ok(())
+}
+/// 全てのカウンタの現在の値を読む
+///
+/// 0 を加算して現在の値を取得する（まだ数えていないカウンタは 0）。
+/// 値の読み出しもアトミック操作で行うため、保存形式に依存しない。
 ///
 /// # 戻り値
-/// * `Ok(())` - 署名が正しい場合
-/// * `Err(String)` - 署名が不正な場合
-fn verify_signature(message: &str, signature: &[u8]) -> Result<(), String> {
-    type HmacSha256 = Hmac<Sha256>;
-    let mut mac = HmacSha256::new_from_slice(SECRET_KEY)
-        .map_err(|_| "Invalid key length".to_string())?;
-    mac.update(message.as_bytes());
-    mac.verify_slice(signature).map_err(|_| "Invalid signature".to_string())
+/// * `Ok(Vec<(String, u64)>)` - (カウンタ名, 値) の一覧（METRIC_NAMES の順）
+/// * `Err(String)` - カウンタの読み出しに失敗した場合のエラーメッセージ
+fn read_metrics(counter: &impl Counter) -> Result<Vec<(String, u64)>, String> {
+    METRIC_NAMES
+        .iter()
+        .map(|&name| Ok((name.to_string(), counter.increment(&metric_key(name), 0)?)))
+        .collect()
+}
+/// カウンタのキーを組み立てる
+///
+/// 例: `auth:metrics:auth_failures_expired`
+fn metric_key(name: &str) -> String {
+    ::alloc::__export::must_use({
+        ::alloc::fmt::format(format_args!("{0}:{1}", METRICS_KEY_PREFIX, name))
+    })
 }
 const _: () = {
-    #[unsafe(export_name = "demo:auth/authenticator#verify-token")]
+    #[unsafe(export_name = "demo:auth/authenticator@0.10.0#verify-token")]
     unsafe extern "C" fn export_verify_token(arg0: *mut u8, arg1: usize) -> *mut u8 {
         unsafe {
             self::exports::demo::auth::authenticator::_export_verify_token_cabi::<
@@ -974,7 +4065,7 @@ const _: () = {
             >(arg0, arg1)
         }
     }
-    #[unsafe(export_name = "cabi_post_demo:auth/authenticator#verify-token")]
+    #[unsafe(export_name = "cabi_post_demo:auth/authenticator@0.10.0#verify-token")]
     unsafe extern "C" fn _post_return_verify_token(arg0: *mut u8) {
         unsafe {
             self::exports::demo::auth::authenticator::__post_return_verify_token::<
@@ -982,4 +4073,66 @@ const _: () = {
             >(arg0)
         }
     }
+    #[unsafe(export_name = "demo:auth/authenticator@0.10.0#issue-token")]
+    unsafe extern "C" fn export_issue_token(
+        arg0: *mut u8,
+        arg1: usize,
+        arg2: i64,
+    ) -> *mut u8 {
+        unsafe {
+            self::exports::demo::auth::authenticator::_export_issue_token_cabi::<
+                AuthComponent,
+            >(arg0, arg1, arg2)
+        }
+    }
+    #[unsafe(export_name = "cabi_post_demo:auth/authenticator@0.10.0#issue-token")]
+    unsafe extern "C" fn _post_return_issue_token(arg0: *mut u8) {
+        unsafe {
+            self::exports::demo::auth::authenticator::__post_return_issue_token::<
+                AuthComponent,
+            >(arg0)
+        }
+    }
+};
+const _: () = {
+    #[unsafe(export_name = "demo:auth/token-issuer@0.10.0#issue")]
+    unsafe extern "C" fn export_issue(
+        arg0: *mut u8,
+        arg1: usize,
+        arg2: i64,
+        arg3: *mut u8,
+        arg4: usize,
+    ) -> *mut u8 {
+        unsafe {
+            self::exports::demo::auth::token_issuer::_export_issue_cabi::<
+                AuthComponent,
+            >(arg0, arg1, arg2, arg3, arg4)
+        }
+    }
+    #[unsafe(export_name = "cabi_post_demo:auth/token-issuer@0.10.0#issue")]
+    unsafe extern "C" fn _post_return_issue(arg0: *mut u8) {
+        unsafe {
+            self::exports::demo::auth::token_issuer::__post_return_issue::<
+                AuthComponent,
+            >(arg0)
+        }
+    }
+};
+const _: () = {
+    #[unsafe(export_name = "demo:auth/metrics@0.10.0#get-metrics")]
+    unsafe extern "C" fn export_get_metrics() -> *mut u8 {
+        unsafe {
+            self::exports::demo::auth::metrics::_export_get_metrics_cabi::<
+                AuthComponent,
+            >()
+        }
+    }
+    #[unsafe(export_name = "cabi_post_demo:auth/metrics@0.10.0#get-metrics")]
+    unsafe extern "C" fn _post_return_get_metrics(arg0: *mut u8) {
+        unsafe {
+            self::exports::demo::auth::metrics::__post_return_get_metrics::<
+                AuthComponent,
+            >(arg0)
+        }
+    }
 };
@@ -0,0 +1,504 @@
+//! # JWKS（ID プロバイダーの公開鍵）の取得とキャッシュ
+//!
+//! Spin 変数 `jwt_jwks_url` を指定した場合、ID プロバイダーが公開する JWKS を Spin の outbound HTTP で取得し、
+//! key-value ストア（`default`）にキャッシュします。トークンのヘッダーの kid で鍵を選ぶため、
+//! ID プロバイダーが鍵をローテーションしても `jwt_rs256_public_key` などを書き換える必要はありません。
+//!
+//! | 項目 | 内容 |
+//! |------|------|
+//! | キー | `auth:jwks:<JWKS の URL>` |
+//! | 値 | 1 行目: 取得時刻と期限（Unix タイムスタンプ秒、空白区切り）、2 行目以降: JWKS の JSON |
+//! | 期間 | レスポンスの `Cache-Control: max-age`（ない場合は 300 秒、上限 1 日、`no-cache` / `no-store` は 0 秒） |
+//! | 不明な kid | 1 回だけ取得し直す（前回の取得から 30 秒以内の場合は取得しない） |
+//! | 取得の失敗 | 期限切れでもキャッシュした JWKS を使う（キャッシュがない場合のみ検証に失敗する） |
+//!
+//! Spin の key-value ストアには TTL がないため、期限を値に含めて読み込み時に判定します。
+//! 期限切れの JWKS も削除せず、ID プロバイダーに接続できない間の代わりとして残します。
+//! 不明な kid による取得し直しに間隔を設け、任意の kid のトークンを送り続けて
+//! ID プロバイダーへのリクエストを増やす攻撃を防ぎます。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+// JWKS の解析（kid で RS256 / ES256 の鍵を選ぶ）
+use jwt::JwkSet;
+
+// JWKS の取得（outbound HTTP）
+use spin_sdk::http::{Method, Request, Response};
+
+// key-value ストア
+use spin_sdk::wit::wasi::keyvalue::store;
+
+use crate::STORE_NAME;
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// キャッシュのキーのプレフィックス
+///
+/// 同じストアを使う auth のメトリクス・gateway・ratelimit のキーと衝突しないようにする。
+const KEY_PREFIX: &str = "auth:jwks";
+
+/// Cache-Control に max-age がない場合のキャッシュの期間（秒）
+const DEFAULT_TTL_SECS: u64 = 300;
+
+/// キャッシュの期間の上限（秒）
+///
+/// max-age が長すぎても、失効した鍵を 1 日以上使い続けないようにする。
+const MAX_TTL_SECS: u64 = 86_400;
+
+/// 不明な kid で取得し直す間隔の下限（秒）
+const MIN_REFRESH_INTERVAL_SECS: u64 = 30;
+
+// =============================================================================
+// 保存先・取得元
+// =============================================================================
+
+/// キャッシュの保存先
+///
+/// テストではメモリ上の実装に差し替え、key-value ストアなしで検証します。
+pub(crate) trait Store {
+    /// キーの値を取得する（キーがない場合は None）
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String>;
+
+    /// キーに値を設定する
+    fn set(&self, key: &str, value: &[u8]) -> Result<(), String>;
+}
+
+/// Spin の key-value ストアを使う保存先
+pub(crate) struct KvStore {
+    bucket: store::Bucket,
+}
+
+impl KvStore {
+    /// key-value ストアを開く（失効リストの確認にも使う）
+    pub(crate) fn open() -> Result<Self, String> {
+        store::open(STORE_NAME)
+            .map(|bucket| Self { bucket })
+            .map_err(|e| format!("Failed to open key-value store: {:?}", e))
+    }
+}
+
+impl Store for KvStore {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        self.bucket
+            .get(key)
+            .map_err(|e| format!("Failed to get {}: {:?}", key, e))
+    }
+
+    fn set(&self, key: &str, value: &[u8]) -> Result<(), String> {
+        self.bucket
+            .set(key, value)
+            .map_err(|e| format!("Failed to set {}: {:?}", key, e))
+    }
+}
+
+/// 取得した JWKS
+#[derive(Debug)]
+pub(crate) struct Fetched {
+    /// レスポンスのボディ（JWKS の JSON）
+    body: String,
+
+    /// キャッシュの期間（秒、cache_ttl で計算した値）
+    ttl_secs: u64,
+}
+
+/// JWKS の取得元
+///
+/// テストでは固定のレスポンス・失敗を返す実装に差し替えます。
+pub(crate) trait Fetcher {
+    /// URL から JWKS を取得する
+    fn fetch(&self, url: &str) -> Result<Fetched, String>;
+}
+
+/// Spin の outbound HTTP で取得する
+///
+/// 取得先のホストは gateway の allowed_outbound_hosts に追加する
+/// （auth は dependencies_inherit_configuration で gateway の設定を引き継ぐ）。
+pub(crate) struct HttpFetcher;
+
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, url: &str) -> Result<Fetched, String> {
+        let request = Request::builder()
+            .method(Method::Get)
+            .uri(url)
+            .header("Accept", "application/json")
+            .build();
+        // verify-token は同期関数のため、送信の完了まで待つ
+        let response = spin_executor::run(spin_sdk::http::send::<_, Response>(request))
+            .map_err(|e| format!("Failed to fetch JWKS: {}", e))?;
+
+        let status = *response.status();
+        if !(200..300).contains(&status) {
+            return Err(format!("Failed to fetch JWKS: status {}", status));
+        }
+        let cache_control = response
+            .header("cache-control")
+            .and_then(|value| value.as_str());
+        Ok(Fetched {
+            ttl_secs: cache_ttl(cache_control),
+            body: String::from_utf8(response.body().to_vec())
+                .map_err(|_| "Failed to fetch JWKS: body is not UTF-8".to_string())?,
+        })
+    }
+}
+
+// =============================================================================
+// キャッシュ
+// =============================================================================
+
+/// キャッシュした JWKS
+#[derive(Debug)]
+struct CachedJwks {
+    /// 取得した時刻（Unix タイムスタンプ秒）
+    fetched_at: u64,
+
+    /// 取得し直さずに使ってよい期限（Unix タイムスタンプ秒）
+    expires_at: u64,
+
+    /// 解析した JWKS
+    jwks: JwkSet,
+}
+
+/// JWKS のキャッシュ
+pub(crate) struct JwksCache<S, F> {
+    store: S,
+    fetcher: F,
+
+    /// JWKS の URL（Spin 変数 jwt_jwks_url）
+    url: String,
+}
+
+impl JwksCache<KvStore, HttpFetcher> {
+    /// key-value ストアを開き、outbound HTTP で取得するキャッシュを作る
+    ///
+    /// # 引数
+    /// * `url` - JWKS の URL
+    pub(crate) fn open(url: &str) -> Result<Self, String> {
+        KvStore::open().map(|store| Self::new(store, HttpFetcher, url))
+    }
+}
+
+impl<S: Store, F: Fetcher> JwksCache<S, F> {
+    /// キャッシュを作る
+    ///
+    /// # 引数
+    /// * `store` - 保存先
+    /// * `fetcher` - 取得元
+    /// * `url` - JWKS の URL
+    fn new(store: S, fetcher: F, url: &str) -> Self {
+        Self {
+            store,
+            fetcher,
+            url: url.to_string(),
+        }
+    }
+
+    /// kid の鍵を探すための JWKS を返す
+    ///
+    /// 1. キャッシュが期限内ならそれを使い、期限切れ・ない場合は取得する
+    ///    （取得に失敗した場合は期限切れのキャッシュを使う）
+    /// 2. kid の鍵がない場合は、ID プロバイダーが鍵をローテーションした直後の可能性があるため
+    ///    1 回だけ取得し直す（1 で取得した場合・前回の取得から MIN_REFRESH_INTERVAL_SECS 以内の場合を除く）
+    ///
+    /// # 引数
+    /// * `kid` - トークンのヘッダーの kid
+    /// * `now` - 現在時刻（Unix タイムスタンプ秒）
+    ///
+    /// # 戻り値
+    /// * `Some(JwkSet)` - JWKS（kid の鍵を含むとは限らない）
+    /// * `None` - 取得に失敗し、キャッシュもない場合
+    pub(crate) fn key_set(&self, kid: &str, now: u64) -> Option<JwkSet> {
+        let (cached, refreshed) = match self.read() {
+            Some(cached) if now < cached.expires_at => (Some(cached), false),
+            stale => (self.refresh(now).or(stale), true),
+        };
+
+        let cached = match cached {
+            Some(cached)
+                if cached.jwks.find(kid).is_none()
+                    && !refreshed
+                    && now >= cached.fetched_at.saturating_add(MIN_REFRESH_INTERVAL_SECS) =>
+            {
+                println!("[Auth] Unknown kid {:?}, refreshing JWKS", kid);
+                Some(self.refresh(now).unwrap_or(cached))
+            }
+            cached => cached,
+        };
+        cached.map(|cached| cached.jwks)
+    }
+
+    /// キャッシュした JWKS を読み込む（ない・壊れている場合は None）
+    fn read(&self) -> Option<CachedJwks> {
+        let value = match self.store.get(&self.key()) {
+            Ok(value) => value?,
+            Err(e) => {
+                println!("[Auth] JWKS cache error: {}", e);
+                return None;
+            }
+        };
+        let value = String::from_utf8(value).ok()?;
+        let (times, body) = value.split_once('\n')?;
+        let (fetched_at, expires_at) = times.split_once(' ')?;
+        Some(CachedJwks {
+            fetched_at: fetched_at.parse().ok()?,
+            expires_at: expires_at.parse().ok()?,
+            jwks: JwkSet::from_json(body).ok()?,
+        })
+    }
+
+    /// JWKS を取得してキャッシュに保存する
+    ///
+    /// 取得・解析に失敗した場合、使える鍵がない場合はログを出力して None を返す
+    /// （キャッシュは書き換えない）。
+    fn refresh(&self, now: u64) -> Option<CachedJwks> {
+        let fetched = match self.fetcher.fetch(&self.url) {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                println!("[Auth] {} ({}), using cached JWKS if any", e, self.url);
+                return None;
+            }
+        };
+        let jwks = match JwkSet::from_json(&fetched.body) {
+            Ok(jwks) if !jwks.is_empty() => jwks,
+            _ => {
+                println!(
+                    "[Auth] JWKS from {} has no usable keys, using cached JWKS if any",
+                    self.url
+                );
+                return None;
+            }
+        };
+
+        let expires_at = now.saturating_add(fetched.ttl_secs);
+        let value = format!("{} {}\n{}", now, expires_at, fetched.body);
+        if let Err(e) = self.store.set(&self.key(), value.as_bytes()) {
+            println!("[Auth] Failed to store JWKS: {}", e);
+        }
+        Some(CachedJwks {
+            fetched_at: now,
+            expires_at,
+            jwks,
+        })
+    }
+
+    /// キャッシュのキー（`auth:jwks:<URL>`）
+    fn key(&self) -> String {
+        format!("{}:{}", KEY_PREFIX, self.url)
+    }
+}
+
+/// Cache-Control ヘッダーからキャッシュの期間（秒）を計算する
+///
+/// - `no-cache` / `no-store`: 0（毎回取得し直す。取得に失敗した場合の代わりとしては保存する）
+/// - `max-age=N`: N（MAX_TTL_SECS で打ち切り）
+/// - ヘッダーがない・max-age がない・値が不正: DEFAULT_TTL_SECS
+///
+/// # 引数
+/// * `cache_control` - Cache-Control ヘッダーの値（ない場合は None）
+fn cache_ttl(cache_control: Option<&str>) -> u64 {
+    let mut ttl = None;
+    for directive in cache_control.unwrap_or_default().split(',') {
+        let directive = directive.trim();
+        if directive.eq_ignore_ascii_case("no-cache") || directive.eq_ignore_ascii_case("no-store")
+        {
+            return 0;
+        }
+        let max_age = directive
+            .split_once('=')
+            .filter(|(name, _)| name.trim().eq_ignore_ascii_case("max-age"));
+        if let Some((_, value)) = max_age {
+            ttl = value.trim().trim_matches('"').parse::<u64>().ok();
+        }
+    }
+    ttl.unwrap_or(DEFAULT_TTL_SECS).min(MAX_TTL_SECS)
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
+
+    /// メモリ上の保存先
+    #[derive(Default)]
+    struct MemoryStore {
+        values: RefCell<HashMap<String, Vec<u8>>>,
+    }
+
+    impl Store for &MemoryStore {
+        fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+            Ok(self.values.borrow().get(key).cloned())
+        }
+
+        fn set(&self, key: &str, value: &[u8]) -> Result<(), String> {
+            self.values
+                .borrow_mut()
+                .insert(key.to_string(), value.to_vec());
+            Ok(())
+        }
+    }
+
+    /// 設定したレスポンスを返し、取得した回数を数える取得元（None の場合は失敗する）
+    #[derive(Default)]
+    struct StubFetcher {
+        response: RefCell<Option<Fetched>>,
+        calls: Cell<u32>,
+    }
+
+    impl StubFetcher {
+        fn respond(&self, body: &str, ttl_secs: u64) {
+            *self.response.borrow_mut() = Some(Fetched {
+                body: body.to_string(),
+                ttl_secs,
+            });
+        }
+
+        fn fail(&self) {
+            *self.response.borrow_mut() = None;
+        }
+    }
+
+    impl Fetcher for &StubFetcher {
+        fn fetch(&self, _url: &str) -> Result<Fetched, String> {
+            self.calls.set(self.calls.get() + 1);
+            self.response
+                .borrow()
+                .as_ref()
+                .map(|fetched| Fetched {
+                    body: fetched.body.clone(),
+                    ttl_secs: fetched.ttl_secs,
+                })
+                .ok_or_else(|| "Failed to fetch JWKS: connection refused".to_string())
+        }
+    }
+
+    const NOW: u64 = 1_700_000_000;
+    const URL: &str = "https://idp.example.com/.well-known/jwks.json";
+    const JWKS: &str = include_str!("../../../core/crates/jwt/testdata/jwks/jwks.json");
+
+    /// testdata の JWKS から kid の鍵だけを残した JWKS（ローテーション前後の鍵セット）
+    fn jwks_with(kid: &str) -> String {
+        let mut jwks: serde_json::Value = serde_json::from_str(JWKS).unwrap();
+        jwks["keys"]
+            .as_array_mut()
+            .unwrap()
+            .retain(|key| key["kid"] == kid);
+        jwks.to_string()
+    }
+
+    #[test]
+    fn test_fetches_once_and_reuses_until_ttl() {
+        let store = MemoryStore::default();
+        let fetcher = StubFetcher::default();
+        fetcher.respond(JWKS, 300);
+        let cache = JwksCache::new(&store, &fetcher, URL);
+
+        assert!(cache
+            .key_set("rs256-test", NOW)
+            .unwrap()
+            .find("rs256-test")
+            .is_some());
+        assert!(cache.key_set("es256-test", NOW + 299).is_some());
+        assert_eq!(fetcher.calls.get(), 1);
+        assert!(store
+            .values
+            .borrow()
+            .contains_key(&format!("auth:jwks:{}", URL)));
+
+        // 期限を過ぎたら取得し直す
+        assert!(cache.key_set("rs256-test", NOW + 300).is_some());
+        assert_eq!(fetcher.calls.get(), 2);
+    }
+
+    #[test]
+    fn test_unknown_kid_triggers_one_refresh() {
+        let store = MemoryStore::default();
+        let fetcher = StubFetcher::default();
+        fetcher.respond(&jwks_with("rs256-test"), 300);
+        let cache = JwksCache::new(&store, &fetcher, URL);
+        assert!(cache.key_set("rs256-test", NOW).is_some());
+
+        // ID プロバイダーが鍵をローテーションした: 新しい kid のトークンで 1 回だけ取得し直す
+        fetcher.respond(&jwks_with("es256-test"), 300);
+        let jwks = cache.key_set("es256-test", NOW + 60).unwrap();
+        assert!(jwks.find("es256-test").is_some());
+        assert_eq!(fetcher.calls.get(), 2);
+
+        // 取得し直しても見つからない kid は 1 回の取得で諦める（前回の取得から間隔を空ける）
+        let jwks = cache.key_set("unknown", NOW + 61).unwrap();
+        assert!(jwks.find("unknown").is_none());
+        assert_eq!(fetcher.calls.get(), 2);
+        assert!(cache
+            .key_set("unknown", NOW + 90)
+            .unwrap()
+            .find("unknown")
+            .is_none());
+        assert_eq!(fetcher.calls.get(), 3);
+    }
+
+    #[test]
+    fn test_falls_back_to_cached_jwks_when_fetch_fails() {
+        let store = MemoryStore::default();
+        let fetcher = StubFetcher::default();
+        let cache = JwksCache::new(&store, &fetcher, URL);
+
+        // キャッシュがない状態で取得に失敗した場合のみ None
+        fetcher.fail();
+        assert!(cache.key_set("rs256-test", NOW).is_none());
+
+        fetcher.respond(JWKS, 60);
+        assert!(cache.key_set("rs256-test", NOW).is_some());
+
+        // 期限切れでも取得に失敗した場合はキャッシュを使う（キャッシュは書き換えない）
+        fetcher.fail();
+        let jwks = cache.key_set("rs256-test", NOW + 3600).unwrap();
+        assert!(jwks.find("rs256-test").is_some());
+        // 使える鍵がない・JSON が不正なレスポンスでもキャッシュを使う
+        for body in [r#"{"keys":[]}"#, "<html>maintenance</html>"] {
+            fetcher.respond(body, 300);
+            assert!(
+                cache.key_set("es256-test", NOW + 7200).is_some(),
+                "{}",
+                body
+            );
+        }
+        assert_eq!(cache.read().unwrap().fetched_at, NOW);
+    }
+
+    #[test]
+    fn test_corrupt_cache_entry_is_refetched() {
+        let store = MemoryStore::default();
+        let fetcher = StubFetcher::default();
+        fetcher.respond(JWKS, 300);
+        let cache = JwksCache::new(&store, &fetcher, URL);
+        store
+            .values
+            .borrow_mut()
+            .insert(cache.key(), b"not a cache entry".to_vec());
+
+        assert!(cache.key_set("rs256-test", NOW).is_some());
+        assert_eq!(fetcher.calls.get(), 1);
+        assert_eq!(cache.read().unwrap().expires_at, NOW + 300);
+    }
+
+    #[test]
+    fn test_cache_ttl() {
+        assert_eq!(cache_ttl(None), DEFAULT_TTL_SECS);
+        assert_eq!(cache_ttl(Some("public, max-age=600")), 600);
+        assert_eq!(cache_ttl(Some("Max-Age = 120 , must-revalidate")), 120);
+        assert_eq!(cache_ttl(Some("max-age=\"90\"")), 90);
+        assert_eq!(cache_ttl(Some("max-age=31536000")), MAX_TTL_SECS);
+        assert_eq!(cache_ttl(Some("max-age=0")), 0);
+        assert_eq!(cache_ttl(Some("public, no-cache")), 0);
+        assert_eq!(cache_ttl(Some("no-store, max-age=600")), 0);
+        assert_eq!(cache_ttl(Some("max-age=soon")), DEFAULT_TTL_SECS);
+        assert_eq!(cache_ttl(Some("")), DEFAULT_TTL_SECS);
+    }
+}
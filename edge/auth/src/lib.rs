@@ -7,31 +7,117 @@
 //! - JWT トークンの構造検証（ヘッダー、ペイロード、署名）
 //! - HMAC-SHA256 署名の検証
 //! - ユーザーIDの抽出
+//! - 有効期限・ロール・許可リストに含まれるクレームの抽出
+//! - 短期間有効なサービス用トークンの発行（`demo:auth/token-issuer`）
+//! - 開発用のユーザーのトークンの発行（`issue-token`、Spin 変数 `jwt_dev_tokens=on` の場合のみ）
+//! - 検証の成功・失敗（理由の分類ごと）のカウント（`demo:auth/metrics`）
 //!
 //! ## 使用方法
-//! gateway コンポーネントから WIT 経由で `verify_token` 関数が呼び出されます。
+//! gateway コンポーネントから WIT 経由で `verify_token` / `issue` / `get_metrics` 関数が呼び出されます。
+//! 署名鍵はこのコンポーネントだけが持ち、gateway には共有しません。
+//!
+//! ## JWT の実装
+//! 署名・検証はコア層の AuthService と共有する `jwt` クレート（core/crates/jwt）で行います。
+//! アルゴリズムの許可リスト・クレームの扱いはコア層と同じです。
+//! 有効期限（exp、必須）・nbf・iat は WASI の壁時計の現在時刻と比較し、許容する時計のずれ（leeway）は
+//! Spin 変数 `jwt_leeway_secs`（既定 30 秒、上限 300 秒）で変更できます。
+//! Spin 変数 `jwt_issuer` を指定した場合は、発行者（iss）がその値のトークンのみ受け付けます
+//! （コア層の `JWT_ISSUER` と同じ値にする。未指定の場合は検証しない）。
+//! Spin 変数 `jwt_audiences`（カンマ区切り）を指定した場合は、対象者（aud、文字列または配列）の
+//! いずれかがその一覧に含まれるトークンのみ受け付けます（未指定の場合は検証しない）。
+//! HS256 のシークレットは Spin 変数 `jwt_secret`（コア層の `JWT_SECRET` と同じ値）から読み込み、
+//! Wasm バイナリには埋め込みません（未設定の場合、HS256 のトークンは "Auth misconfigured" で拒否）。
+//! HS256 の鍵は Spin 変数 `jwt_hs256_keys`（kid → シークレットの JSON、先頭が最新の鍵）で複数指定でき、
+//! ヘッダーの kid で鍵を選びます（kid のないトークンは新しい鍵から順に試す）。
+//! シークレットのローテーション中は新旧の鍵の両方で検証し、発行には最新の鍵を使います。
+//! 受け付けるアルゴリズムは Spin 変数 `jwt_algorithms` の許可リストで決まり、既定は HS256 のみです。
+//! 外部の ID プロバイダーが RS256 で署名したトークンは Spin 変数 `jwt_rs256_public_key`（PEM）、
+//! パートナーが ES256 で署名したトークンは `jwt_es256_public_key`（PEM または JWK）を指定し、
+//! `jwt_algorithms` にも RS256 / ES256 を含めた場合のみ受け付けます。ヘッダーの alg で鍵を選び、
+//! 公開鍵を HS256 のシークレットとして使うことはありません。
+//! HS384 / HS512 のトークンは HS256 と同じ鍵で検証し、`jwt_algorithms` に含めた場合のみ受け付けます
+//! （コア層の `JWT_ALGORITHM` を HS512 にする前に追加する）。
+//! Spin 変数 `jwt_tenants`（発行者 → alg・kid → シークレット・対象者の JSON）を指定した場合は、署名を検証する前の
+//! iss で発行者（テナント）を選び、その発行者の鍵・対象者・アルゴリズムだけで検証します
+//! （未知の発行者は署名を確認せずに "Unknown issuer" で拒否し、他の発行者の鍵では検証しない）。
+//! Spin 変数 `jwt_jwks_url` を指定した場合は、ID プロバイダーの JWKS を取得して key-value ストアに
+//! キャッシュし、kid のある RS256 / ES256 のトークンはその kid の鍵で検証します（jwks モジュール）。
+//! 検証に成功したトークンは key-value ストアの失効リスト（jti・ユーザーごとの失効時刻）と照合し、
+//! ログアウト・パスワードの変更で失効させたトークンを "Token revoked" で拒否します（revocation モジュール）。
+//! 数値の `ver` クレーム（ない場合は 0）がユーザーのバージョン（key-value ストア、コア層の `X-Token-Version` を
+//! gateway が書き込む）より小さいトークンは "Token superseded" で拒否します。
+//! gateway は sub をそのまま X-User-Id としてコア層に転送するため、sub が UUID でないトークンは
+//! "Invalid subject claim" で拒否します（Spin 変数 `jwt_subject_pattern` で形式を変更できる）。
+//! 同じ鍵で署名したリフレッシュトークンを API に使わせないよう、`token_use` クレームが `access` でない
+//! トークンは "Wrong token type" で拒否します（`token_use` のないトークンは Spin 変数
+//! `jwt_allow_untyped_tokens=on` の場合のみ受け付ける）。
+//! ヘッダーの `typ` は Spin 変数 `jwt_typ_policy` で検証します（既定の `validate-if-present` は `typ` がある場合に
+//! `JWT`（大文字小文字を区別しない）のみ受け付け、`require` は `typ` のないトークンも拒否、`ignore` は検証しない）。
+//! 失効リストを読めない場合は拒否し、Spin 変数 `jwt_revocation_fail_open=on` の場合のみ検証を通します。
+//! `jti` と `one_time: true` のクレームを持つトークン（パスワードのリセットなど）は、最初の提示で jti を
+//! key-value ストアに使用済みとして記録し、2 回目以降を "Token already used" で拒否します（one_time モジュール）。
+//!
+//! ## Spin 変数
+//! 以下の変数は spin.toml の `[component.gateway.variables]` で設定し、
+//! dependencies_inherit_configuration により gateway の設定を引き継ぎます。
+//!
+//! | 変数 | 既定 | 内容 |
+//! |------|------|------|
+//! | `jwt_secret` | なし | HS256 の共有シークレット（コア層の `JWT_SECRET` と同じ値）。`jwt_hs256_keys` も空の場合、HS256 のトークンは "Auth misconfigured" で拒否（デモ用の鍵は使わない） |
+//! | `jwt_hs256_keys` | なし | HS256 の鍵リング（kid → シークレットの JSON、例: `{"2025-02":"new-secret","default":"super-secret-key"}`、先頭が最新で発行に使う）。空の場合は `jwt_secret`（kid は `default`）のみ |
+//! | `jwt_leeway_secs` | 30 | exp / nbf / iat で許容する時計のずれ（秒、上限 300、超える値は既定値） |
+//! | `jwt_max_token_bytes` | 8192 | トークン全体の長さの上限（バイト、`jwt::MAX_HEADER_BYTES`〜64 KiB）。ヘッダー・署名の上限は jwt クレートの定数 |
+//! | `jwt_issuer` | なし | 期待する発行者（iss）。空の場合は検証しない |
+//! | `jwt_audiences` | なし | 受け付ける対象者（aud、カンマ区切り）。空の場合は検証しない |
+//! | `jwt_subject_pattern` | UUID | sub に許可する形式（正規表現、sub 全体が一致するもの、例: `idp-user-[0-9]+`）。不正な正規表現の場合はログを出力して UUID のみ |
+//! | `jwt_tenants` | なし | 発行者 → 検証の設定の JSON（例: `{"https://a.example.com":{"alg":"HS256","keys":{"a-1":"secret-a"},"audiences":["todo-api"]}}`）。指定した場合は `jwt_secret`・`jwt_issuer`・`jwt_audiences`・`jwt_algorithms`・公開鍵・JWKS を検証に使わない（`jwt_secret` / `jwt_hs256_keys` は発行にのみ使う）。不正な値の場合はログを出力して全てのトークンを拒否 |
+//! | `jwt_rs256_public_key` | なし | RS256 の公開鍵（PEM）。`jwt_algorithms` に RS256 も含めること。空の場合は RS256 のトークンを受け付けない |
+//! | `jwt_es256_public_key` | なし | ES256 の公開鍵（PEM または JWK）。`jwt_algorithms` に ES256 も含めること。空の場合は ES256 のトークンを受け付けない |
+//! | `jwt_algorithms` | HS256 | 受け付けるアルゴリズム（カンマ区切り）。HS384 / HS512 / RS256 / ES256 は明示した場合のみ（例: `HS256,HS512`、`HS256,RS256`）。RS256 / ES256 は公開鍵・JWKS も必要 |
+//! | `jwt_jwks_url` | なし | ID プロバイダーの JWKS の URL。JWKS の鍵の alg（RS256 / ES256）を `jwt_algorithms` にも含めること。取得先のホストは gateway の allowed_outbound_hosts にも追加する |
+//! | `jwt_revocation_fail_open` | off | 失効リストを読めない場合に検証を通すか。off は "Revocation check unavailable" で拒否、on は障害中も認証を続けるが失効させたトークンも受け付ける |
+//! | `jwt_dev_tokens` | off | 開発用のトークンの発行（authenticator の issue-token）を許可するか。off は "issue-token is disabled" で拒否。Edge 層だけの結合テスト・デモ用で、本番では on にしない |
+//! | `jwt_lenient_base64` | off | パディング（末尾の "="）のある Base64URL のトークンを受け付けるか。on の場合は受け付けるたびに警告を出力（`Validation::with_lenient_base64`） |
+//! | `jwt_allow_untyped_tokens` | off | token_use クレームのないトークンを受け付けるか。off は "Wrong token type" で拒否。token_use を付けずに発行したトークン（以前のコア層・ID プロバイダー）を受け付ける間だけ on にする |
+//! | `jwt_typ_policy` | validate-if-present | ヘッダーの typ の検証（ignore / validate-if-present / require、`TypPolicy`）。validate-if-present は typ がある場合は "JWT" のみ受け付ける |
+//!
+//! ## カウンタの保存先
+//! Spin の key-value ストア（`default`）に保存し、アトミックにインクリメントします。
+//! Wasm インスタンスはリクエストごとに作られるため、メモリ上のカウンタは使えません。
 
 // =============================================================================
 // 外部クレートのインポート
 // =============================================================================
 
-// Base64 エンコーディング/デコーディング用
-// URL_SAFE_NO_PAD: URL セーフな Base64（パディングなし）を使用
-// JWT は URL セーフな Base64 でエンコードされているため、この形式を使用
-use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+// 共有 JWT クレート（コア層の AuthService と同じ実装）
+// Claims: クレーム（sub / exp / iat / iss と、それ以外のクレーム）
+// Header: JWT ヘッダー（alg / typ / kid）
+// Validation: 検証の条件（現在時刻、leeway、発行者、対象者）
+// DecodingKey / RsaPublicKey / EcPublicKey: 検証に使う鍵（HS256 のシークレット、RS256 / ES256 の公開鍵）
+// JwkSet: ID プロバイダーの JWKS（kid で鍵を選ぶ）
+// Map / Value: 発行するトークンの追加のクレーム
+use jwt::{Claims, DecodingKey, EcPublicKey, Header, JwkSet, Map, RsaPublicKey, Validation, Value};
 
-// HMAC (Hash-based Message Authentication Code) 用
-// Hmac: HMAC アルゴリズムの実装
-// Mac: Message Authentication Code のトレイト（共通インターフェース）
-use hmac::{Hmac, Mac};
+// 検証エラーの表示（auth-result の error）、HS256 の鍵のシークレットを隠した Debug 出力
+use std::fmt;
 
-// JSON デシリアライズ用
-// Deserialize: JSON から Rust 構造体への変換を自動生成
-use serde::Deserialize;
+// jwt_hs256_keys の JSON（kid の記載順を保って読み込む）
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
 
-// SHA-256 ハッシュアルゴリズム
-// HMAC-SHA256 の内部ハッシュ関数として使用
-use sha2::Sha256;
+// jwt_subject_pattern（sub クレームに許可する形式）
+use regex_lite::Regex;
+
+// 現在時刻の取得（発行するトークンの iat / exp）
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// HS256 の鍵（jwt_secret / jwt_hs256_keys）のキャッシュ（インスタンスごと）
+use std::sync::OnceLock;
+
+// Spin の key-value ストア（wasi:keyvalue@0.2.0-draft2）
+// store: ストアを開く
+// atomics: アトミックなインクリメント
+// spin_sdk::key_value::Store には increment がないため、WIT バインディングを直接使用
+use spin_sdk::wit::wasi::keyvalue::{atomics, store};
 
 // =============================================================================
 // WIT バインディングの生成
@@ -48,60 +134,689 @@ wit_bindgen::generate!({
 // wit-bindgen が生成した型をインポート
 // Guest: WIT の export を実装するためのトレイト
 // AuthResult: 認証結果を表す構造体（WIT の auth-result レコードに対応）
-use exports::demo::auth::authenticator::{AuthResult, Guest};
+// AuthErrorCode: 認証失敗の理由（WIT の auth-error-code 列挙型に対応）
+use exports::demo::auth::authenticator::{AuthErrorCode, AuthResult, Guest};
+
+// token-issuer インターフェースの Guest トレイト
+// authenticator の Guest と名前が衝突するため別名でインポート
+use exports::demo::auth::token_issuer::Guest as TokenIssuerGuest;
+
+// metrics インターフェースの Guest トレイト
+use exports::demo::auth::metrics::Guest as MetricsGuest;
+
+// =============================================================================
+// モジュール
+// =============================================================================
+
+// JWKS の取得とキャッシュ（jwt_jwks_url、kid で鍵を選ぶ）
+mod jwks;
+
+// トークンの失効の確認（key-value ストアの失効リスト）
+mod revocation;
+
+// 使い捨てのトークンの使用済みの記録（key-value ストアの compare-and-swap）
+mod one_time;
 
 // =============================================================================
 // 定数定義
 // =============================================================================
 
-/// JWT 署名検証用の秘密鍵
+/// HS256 の共有シークレット（コア層の JWT_SECRET と同じ値）を読み込む Spin 変数名
+const SECRET_VARIABLE: &str = "jwt_secret";
+
+/// jwt_secret の key id（jwt_hs256_keys を指定しない場合に発行するトークンのヘッダーの kid）
+///
+/// コア層が JWT_SECRET だけで構成した場合の key id（DEFAULT_KEY_ID）と同じ値。
+/// コア層は kid で鍵を選ぶため、鍵をローテーションする場合はコア層の鍵ファイルと
+/// 同じ key id・シークレットを jwt_hs256_keys に設定すること。
+const SIGNING_KEY_ID: &str = "default";
+
+/// HS256 の鍵リング（kid → シークレットの JSON オブジェクト）を読み込む Spin 変数名
+const HS256_KEYS_VARIABLE: &str = "jwt_hs256_keys";
+
+/// 発行するトークンの有効期間の上限（秒）
+///
+/// token-issuer はサービス間の呼び出し用の短期トークンだけを発行する。
+const MAX_ISSUE_TTL_SECS: u64 = 300;
+
+/// exp / nbf / iat で許容する時計のずれを読み込む Spin 変数名
+const LEEWAY_VARIABLE: &str = "jwt_leeway_secs";
+
+/// jwt_leeway_secs を指定しない場合の leeway（秒）
+///
+/// コア層（jwt::DEFAULT_LEEWAY_SECS、60 秒）とは別に、Edge 層ではより短い 30 秒とする。
+const DEFAULT_LEEWAY_SECS: u64 = 30;
+
+/// トークン全体の長さの上限（バイト）を読み込む Spin 変数名
+const MAX_TOKEN_BYTES_VARIABLE: &str = "jwt_max_token_bytes";
+
+/// jwt_max_token_bytes に指定できる範囲（バイト）
+///
+/// 下限はヘッダーの上限（jwt::MAX_HEADER_BYTES）、上限は 64 KiB（巨大なトークンの HMAC を防ぐ）。
+const MAX_TOKEN_BYTES_RANGE: std::ops::RangeInclusive<usize> = jwt::MAX_HEADER_BYTES..=64 * 1024;
+
+/// jwt_leeway_secs の上限（秒）
+///
+/// 設定の誤りで期限切れのトークンを長時間受け入れないよう、これを超える値は既定値に戻す。
+const MAX_LEEWAY_SECS: u64 = 300;
+
+/// 期待する発行者（iss）を読み込む Spin 変数名
+const ISSUER_VARIABLE: &str = "jwt_issuer";
+
+/// 受け付ける対象者（aud）の一覧を読み込む Spin 変数名（カンマ区切り）
+const AUDIENCES_VARIABLE: &str = "jwt_audiences";
+
+/// ユーザーのトークンの sub に許可する形式（正規表現）を読み込む Spin 変数名
+const SUBJECT_PATTERN_VARIABLE: &str = "jwt_subject_pattern";
+
+/// sub の長さの上限（バイト、jwt_subject_pattern を指定した場合も適用する）
+const MAX_SUBJECT_BYTES: usize = 255;
+
+/// 発行者（テナント）ごとの検証の設定（発行者 → 設定の JSON オブジェクト）を読み込む Spin 変数名
+const TENANTS_VARIABLE: &str = "jwt_tenants";
+
+/// jwt_tenants の発行者ごとの設定に指定できるフィールド
+///
+/// alg（HS256 / HS384 / HS512 のいずれか 1 つ）・keys（kid → シークレット）は必須、audiences（対象者の配列）は任意。
+/// 綴りの誤り（audience など）で検証を緩めないよう、それ以外のフィールドは拒否する。
+const TENANT_FIELDS: &[&str] = &["alg", "keys", "audiences"];
+
+/// RS256 の検証に使う公開鍵（PEM）を読み込む Spin 変数名
+const RS256_PUBLIC_KEY_VARIABLE: &str = "jwt_rs256_public_key";
+
+/// ES256 の検証に使う公開鍵（PEM または JWK）を読み込む Spin 変数名
+const ES256_PUBLIC_KEY_VARIABLE: &str = "jwt_es256_public_key";
+
+/// 受け付けるアルゴリズムの一覧を読み込む Spin 変数名（カンマ区切り）
+const ALGORITHMS_VARIABLE: &str = "jwt_algorithms";
+
+/// jwt_algorithms を指定しない場合に受け付けるアルゴリズム
+///
+/// RS256 / ES256 は公開鍵を指定しただけでは受け付けず、jwt_algorithms に明示した場合のみ受け付ける。
+const DEFAULT_ALGORITHMS: &[&str] = &["HS256"];
+
+/// ID プロバイダーの JWKS の URL を読み込む Spin 変数名
+const JWKS_URL_VARIABLE: &str = "jwt_jwks_url";
+
+/// 失効リストを読めない場合に検証を通すか（on / off）を読み込む Spin 変数名
+const REVOCATION_FAIL_OPEN_VARIABLE: &str = "jwt_revocation_fail_open";
+
+/// 開発用のトークンの発行（authenticator の issue-token）を許可するか（on / off）を読み込む Spin 変数名
+const DEV_TOKENS_VARIABLE: &str = "jwt_dev_tokens";
+
+/// パディング（末尾の "="）のある Base64URL のトークンを受け付けるか（on / off）を読み込む Spin 変数名
+const LENIENT_BASE64_VARIABLE: &str = "jwt_lenient_base64";
+
+/// token_use クレームのないトークンを受け付けるか（on / off）を読み込む Spin 変数名
+const UNTYPED_TOKENS_VARIABLE: &str = "jwt_allow_untyped_tokens";
+
+/// ヘッダーの typ の検証（ignore / validate-if-present / require）を読み込む Spin 変数名
+const TYP_POLICY_VARIABLE: &str = "jwt_typ_policy";
+
+/// ヘッダーの typ に受け付ける値（RFC 7519 5.1、大文字小文字を区別しない）
+const JWT_TYP: &str = "JWT";
+
+/// 発行時に claims で上書きできない登録済みクレーム（RFC 7519）とトークンの種類（token_use）
+const RESERVED_CLAIMS: &[&str] = &[
+    "sub",
+    "exp",
+    "iat",
+    "nbf",
+    "iss",
+    "aud",
+    "jti",
+    jwt::TOKEN_USE_CLAIM,
+];
+
+/// gateway に返すクレームの許可リスト
+///
+/// ここに含まれるクレームだけを auth-result の claims に入れる。
+/// gateway はこれらをヘッダーとしてコア層に転送するため、
+/// 個人情報など転送すべきでないクレームは追加しないこと。
+///
+/// 注意: sub / exp / iat / roles は専用のフィールド（user-id / expires-at / roles）で返すため、
+/// ここに指定しても claims には含まれない。
+/// scope（空白区切りのスコープ）は文字列のまま返し、コア層の UserContext が分割する。
+const CLAIM_ALLOWLIST: &[&str] = &["tenant_id", "scope"];
+
+/// カウンタ・JWKS のキャッシュ・失効リストを保存する key-value ストア名
 ///
-/// 注意: これはデモ用のハードコードされた秘密鍵です。
-/// 本番環境では以下の方法で安全に管理してください：
-/// - 環境変数から取得
-/// - シークレット管理サービス（HashiCorp Vault など）を使用
-/// - Spin の変数機能を使用
-const SECRET_KEY: &[u8] = b"super-secret-key";
+/// spin.toml の `key_value_stores` で許可したストアと一致させます。
+const STORE_NAME: &str = "default";
+
+/// カウンタのキーのプレフィックス
+///
+/// 同じストアを使う ratelimit・gateway のキーと衝突しないようにします。
+const METRICS_KEY_PREFIX: &str = "auth:metrics";
+
+/// verify-token の呼び出し回数
+const VERIFICATIONS_TOTAL: &str = "auth_verifications_total";
+
+/// 検証に成功した回数
+const VERIFICATIONS_SUCCESS: &str = "auth_verifications_success";
+
+/// 失敗: トークンがない
+const FAILURES_MISSING_TOKEN: &str = "auth_failures_missing_token";
+
+/// 失敗: 有効期限切れ
+const FAILURES_EXPIRED: &str = "auth_failures_expired";
+
+/// 失敗: 署名が一致しない
+const FAILURES_BAD_SIGNATURE: &str = "auth_failures_bad_signature";
+
+/// 失敗: 形式が不正（Base64URL / JSON / 必須クレームの欠落）
+const FAILURES_MALFORMED: &str = "auth_failures_malformed";
+
+/// 失敗: 許可されていないアルゴリズム
+const FAILURES_UNSUPPORTED_ALG: &str = "auth_failures_unsupported_alg";
+
+/// 失敗: 上記以外（nbf より前、iat が未来、失効したトークンなど）
+const FAILURES_OTHER: &str = "auth_failures_other";
+
+/// get-metrics が返すカウンタ名（WIT の metrics インターフェースに記載した順）
+const METRIC_NAMES: [&str; 8] = [
+    VERIFICATIONS_TOTAL,
+    VERIFICATIONS_SUCCESS,
+    FAILURES_MISSING_TOKEN,
+    FAILURES_EXPIRED,
+    FAILURES_BAD_SIGNATURE,
+    FAILURES_MALFORMED,
+    FAILURES_UNSUPPORTED_ALG,
+    FAILURES_OTHER,
+];
 
 // =============================================================================
-// JWT 構造体定義
+// 構造体定義
 // =============================================================================
 
-/// JWT ヘッダーを表す構造体
+/// HS256 の鍵（key id + 共有シークレット）
+///
+/// Debug 出力ではシークレットを表示しない。
+#[derive(Clone, PartialEq, Eq)]
+struct HmacKey {
+    /// key id（トークンヘッダーの kid）
+    id: String,
+
+    /// HS256 の共有シークレット
+    secret: Vec<u8>,
+}
+
+impl fmt::Debug for HmacKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HmacKey")
+            .field("id", &self.id)
+            .field("secret", &"<redacted>")
+            .finish()
+    }
+}
+
+impl HmacKey {
+    /// 検証に使う鍵
+    fn decoding_key(&self) -> DecodingKey<'_> {
+        DecodingKey::Hmac(&self.secret)
+    }
+}
+
+/// HS256 の鍵リング
+///
+/// 先頭が最新の鍵（発行に使う）で、以降はローテーション前に発行したトークンの検証のみに使う。
+/// 古い鍵で発行したトークンの有効期限が切れたら、jwt_hs256_keys から古い鍵を削除する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HmacKeyring {
+    /// 鍵（1 つ以上、新しい順）
+    keys: Vec<HmacKey>,
+}
+
+impl HmacKeyring {
+    /// jwt_secret の 1 つだけの鍵リング（key id は SIGNING_KEY_ID）
+    fn from_secret(secret: &[u8]) -> Self {
+        Self {
+            keys: vec![HmacKey {
+                id: SIGNING_KEY_ID.to_string(),
+                secret: secret.to_vec(),
+            }],
+        }
+    }
+
+    /// Spin 変数から読み込む（インスタンスごとに 1 回だけ読み込み、以降はキャッシュを使う）
+    ///
+    /// # 戻り値
+    /// * `Some(HmacKeyring)` - jwt_hs256_keys、未定義・空・不正な場合は jwt_secret の鍵
+    /// * `None` - どちらも指定していない場合
+    fn load() -> Option<Self> {
+        static KEYRING: OnceLock<Option<HmacKeyring>> = OnceLock::new();
+        KEYRING.get_or_init(Self::read).clone()
+    }
+
+    /// Spin 変数 jwt_hs256_keys・jwt_secret を読み込む（不正な jwt_hs256_keys はログを出力して jwt_secret を使う）
+    fn read() -> Option<Self> {
+        let value = spin_sdk::variables::get(HS256_KEYS_VARIABLE).ok();
+        match parse_hs256_keys(value.as_deref()) {
+            Ok(Some(keyring)) => return Some(keyring),
+            Ok(None) => {}
+            Err(e) => println!("[Auth] {}, using {}", e, SECRET_VARIABLE),
+        }
+
+        match spin_sdk::variables::get(SECRET_VARIABLE) {
+            Ok(secret) if !secret.is_empty() => Some(Self::from_secret(secret.as_bytes())),
+            _ => {
+                println!(
+                    "[Auth] Neither {} nor {} is set, rejecting HS256 tokens",
+                    SECRET_VARIABLE, HS256_KEYS_VARIABLE
+                );
+                None
+            }
+        }
+    }
+
+    /// 発行に使う鍵（最新の鍵）
+    fn signing_key(&self) -> &HmacKey {
+        &self.keys[0]
+    }
+
+    /// kid で検証に使う鍵を選ぶ
+    ///
+    /// # 戻り値
+    /// * `Ok(Vec<DecodingKey>)` - kid の鍵 1 つ、kid がない場合は全ての鍵（新しい順に試す）
+    /// * `Err(VerifyError::UnknownKeyId)` - kid の鍵がない場合
+    fn decoding_keys(&self, kid: Option<&str>) -> Result<Vec<DecodingKey<'_>>, VerifyError> {
+        match kid {
+            Some(kid) => self
+                .keys
+                .iter()
+                .find(|key| key.id == kid)
+                .map(|key| vec![key.decoding_key()])
+                .ok_or(VerifyError::UnknownKeyId),
+            None => Ok(self.keys.iter().map(HmacKey::decoding_key).collect()),
+        }
+    }
+}
+
+/// jwt_hs256_keys の JSON オブジェクト（kid, シークレット）の一覧
+///
+/// jwt::Map（serde_json の Map）はキーの順に並べ替えるため、記載順（新しい順）を保って読み込む。
+struct OrderedSecrets(Vec<(String, String)>);
+
+impl<'de> Deserialize<'de> for OrderedSecrets {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SecretsVisitor;
+
+        impl<'de> Visitor<'de> for SecretsVisitor {
+            type Value = OrderedSecrets;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an object mapping key ids to secrets")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry::<String, String>()? {
+                    entries.push(entry);
+                }
+                Ok(OrderedSecrets(entries))
+            }
+        }
+
+        deserializer.deserialize_map(SecretsVisitor)
+    }
+}
+
+/// 発行者（テナント）ごとの検証の設定（jwt_tenants の 1 つの発行者）
+///
+/// 発行者ごとに別のシークレットで署名したトークンを、その発行者の鍵だけで検証する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Tenant {
+    /// 発行者（iss クレームと完全に一致すること）
+    issuer: String,
+
+    /// 受け付けるアルゴリズム（HS256 / HS384 / HS512 のいずれか 1 つ）
+    algorithm: String,
+
+    /// 検証に使う鍵リング（kid のないトークンは全ての鍵を試す）
+    keyring: HmacKeyring,
+
+    /// 受け付ける対象者（空の場合は aud を検証しない）
+    audiences: Vec<String>,
+}
+
+impl Tenant {
+    /// Spin 変数 jwt_tenants から読み込む（インスタンスごとに 1 回だけ読み込み、以降はキャッシュを使う）
+    ///
+    /// # 戻り値
+    /// * `Some(Vec<Tenant>)` - 発行者ごとの設定（不正な場合はログを出力して空、全てのトークンを拒否する）
+    /// * `None` - 未定義・空の場合（jwt_secret などの設定で検証する）
+    fn load() -> Option<Vec<Self>> {
+        static TENANTS: OnceLock<Option<Vec<Tenant>>> = OnceLock::new();
+        TENANTS
+            .get_or_init(|| {
+                let value = spin_sdk::variables::get(TENANTS_VARIABLE).ok();
+                parse_tenants(value.as_deref()).unwrap_or_else(|e| {
+                    println!("[Auth] {}, rejecting all tokens", e);
+                    Some(Vec::new())
+                })
+            })
+            .clone()
+    }
+}
+
+/// 検証に使う鍵（HS256 の鍵リングと RS256 / ES256 の公開鍵）
+///
+/// Spin 変数で指定した鍵だけを持つ。公開鍵がないアルゴリズムのトークンには空の HS256 の鍵を渡し、
+/// jwt クレートが "Unsupported algorithm" で拒否する（署名の検証には使わない）。
+#[derive(Debug, Default)]
+struct VerificationKeys {
+    /// HS256 の鍵リング（jwt_hs256_keys / jwt_secret、どちらも指定していない場合は None）
+    hs256: Option<HmacKeyring>,
+
+    /// RS256 の公開鍵（jwt_rs256_public_key）
+    rs256: Option<RsaPublicKey>,
+
+    /// ES256 の公開鍵（jwt_es256_public_key）
+    es256: Option<EcPublicKey>,
+
+    /// ID プロバイダーの JWKS（jwt_jwks_url を指定し、トークンに kid がある場合のみ）
+    jwks: Option<JwkSet>,
+
+    /// 発行者ごとの設定（jwt_tenants を指定した場合のみ、不正な場合は空）
+    /// Some の場合は iss で選んだ発行者の鍵だけを使い、上記の鍵は使わない
+    tenants: Option<Vec<Tenant>>,
+
+    /// ヘッダーの typ の検証（jwt_typ_policy、発行者の設定に関わらず全てのトークンに適用する）
+    typ_policy: TypPolicy,
+}
+
+impl VerificationKeys {
+    /// Spin 変数から読み込む（未定義・空の場合は None、不正な公開鍵はログを出力して None）
+    fn load() -> Self {
+        Self {
+            hs256: HmacKeyring::load(),
+            rs256: load_public_key(RS256_PUBLIC_KEY_VARIABLE, parse_rsa_public_key),
+            es256: load_public_key(ES256_PUBLIC_KEY_VARIABLE, parse_ec_public_key),
+            jwks: None,
+            tenants: Tenant::load(),
+            typ_policy: typ_policy(),
+        }
+    }
+
+    /// ヘッダーの alg・kid で検証に使う鍵を選ぶ
+    ///
+    /// - HS256 / HS384 / HS512: 鍵リングの kid の鍵（kid がない場合は全ての鍵を新しい順に試す）
+    /// - RS256 / ES256: JWKS がある場合は kid の鍵（jwt_rs256_public_key などより優先する）、
+    ///   ない場合は Spin 変数の公開鍵
+    ///
+    /// 鍵の種類は alg ごとに固定する（公開鍵を HS256 のシークレットとして使わない）。
+    ///
+    /// # 戻り値
+    /// * `Ok(Vec<DecodingKey>)` - 試す鍵（1 つ以上）
+    /// * `Err(VerifyError::UnknownKeyId)` - 鍵リング・JWKS に kid の鍵がない場合
+    /// * `Err(VerifyError::Misconfigured)` - HMAC のトークンで、jwt_secret・jwt_hs256_keys がない場合
+    fn decoding_keys(&self, header: &Header) -> Result<Vec<DecodingKey<'_>>, VerifyError> {
+        if jwt::HMAC_ALGORITHMS.contains(&header.alg.as_str()) {
+            let keyring = self.hs256.as_ref().ok_or(VerifyError::Misconfigured)?;
+            return keyring.decoding_keys(header.kid.as_deref());
+        }
+        if let (Some(jwks), Some(kid)) = (&self.jwks, header.kid.as_deref()) {
+            return jwks
+                .find(kid)
+                .map(|key| vec![key])
+                .ok_or(VerifyError::UnknownKeyId);
+        }
+        Ok(vec![
+            match (header.alg.as_str(), &self.rs256, &self.es256) {
+                ("RS256", Some(key), _) => DecodingKey::Rsa(key),
+                ("ES256", _, Some(key)) => DecodingKey::Ec(key),
+                _ => DecodingKey::Hmac(&[]),
+            },
+        ])
+    }
+}
+
+/// 検証に成功したトークンから取り出した情報
 ///
-/// JWT のヘッダー部分には、トークンのタイプと署名アルゴリズムが含まれます。
-/// 例: {"alg":"HS256","typ":"JWT"}
-#[derive(Debug, Deserialize)]
-struct JwtHeader {
-    /// 署名アルゴリズム（例: "HS256", "RS256"）
-    /// このデモでは HS256 のみサポート
-    alg: String,
+/// WIT の auth-result の成功時のフィールドに対応します。
+#[derive(Debug, PartialEq)]
+struct VerifiedToken {
+    /// ユーザーID（sub クレーム）
+    user_id: String,
+
+    /// 有効期限（exp クレーム、Unix タイムスタンプ秒）
+    /// jwt クレートは exp を必須とするため、検証に成功した場合は常に Some
+    expires_at: Option<u64>,
+
+    /// ロール（roles クレームの文字列要素）
+    roles: Vec<String>,
+
+    /// 許可リストに含まれるクレーム（名前, 値）
+    claims: Vec<(String, String)>,
+
+    /// 発行時刻（iat クレーム、Unix タイムスタンプ秒）
+    /// iat がないトークンは None
+    issued_at: Option<u64>,
+
+    /// トークンの ID（jti クレーム、ない場合は None）
+    /// 失効の確認・使い捨てのトークンの記録にのみ使い、auth-result には含めない
+    jti: Option<String>,
+
+    /// トークンの種類（token_use クレーム、ない場合は None）
+    /// アクセストークンの確認にのみ使い、auth-result には含めない
+    token_use: Option<String>,
 
-    /// トークンタイプ（通常は "JWT"）
-    /// 検証には使用しないため、dead_code 警告を抑制
-    #[allow(dead_code)]
-    typ: Option<String>,
+    /// トークンのバージョン（ver クレーム、ない・0 以上の整数でない場合は 0）
+    /// ユーザーのバージョンとの比較にのみ使い、auth-result には含めない
+    token_version: u64,
+
+    /// 使い捨てのトークンか（one_time クレームが真偽値の true）
+    /// auth-result の one-time として返し、gateway は検証結果をキャッシュしない
+    one_time: bool,
 }
 
-/// JWT ペイロード（クレーム）を表す構造体
+/// トークンの検証エラー
 ///
-/// JWT のペイロード部分には、トークンに関する情報（クレーム）が含まれます。
-/// 標準クレーム（RFC 7519）の一部を定義しています。
-#[derive(Debug, Deserialize)]
-struct JwtPayload {
-    /// Subject（サブジェクト）: トークンの主体を識別
-    /// 通常はユーザーIDを格納
-    sub: Option<String>,
+/// 表示用のメッセージ（auth-result の error）とは別に、
+/// メトリクスのために失敗の理由を分類します。
+#[derive(Debug, PartialEq)]
+enum VerifyError {
+    /// トークンがない（Authorization ヘッダーがない）
+    MissingToken,
+
+    /// HS256 の鍵リング・JWKS に kid の鍵がない（JWKS は取得し直しても見つからない）
+    UnknownKeyId,
+
+    /// JWKS を取得できず、キャッシュもない
+    KeysUnavailable,
+
+    /// HS256 の鍵（jwt_secret / jwt_hs256_keys）が設定されていない
+    Misconfigured,
+
+    /// 失効リスト（jti・ユーザーごとの失効時刻）によって失効したトークン
+    Revoked,
+
+    /// 失効リストを読めない（jwt_revocation_fail_open=off の場合のみ）
+    RevocationUnavailable,
+
+    /// sub が UUID（jwt_subject_pattern を指定した場合はその形式）でない
+    InvalidSubject,
+
+    /// iss がない・jwt_tenants にない発行者のトークン（jwt_tenants を指定した場合のみ、署名は確認しない）
+    UnknownIssuer,
+
+    /// ヘッダーの typ が JWT でない（jwt_typ_policy=require の場合は typ がない場合を含む）
+    InvalidTyp,
+
+    /// token_use が access でない（リフレッシュトークンなど）、または token_use がないトークン
+    WrongTokenType,
+
+    /// ver クレームがユーザーのバージョンより小さいトークン（パスワードの変更・全端末からのログアウトの前に発行）
+    Superseded,
+
+    /// 使用済みの使い捨てのトークン（one_time: true）
+    Reused,
+
+    /// jti のない使い捨てのトークン（使用済みか判定できない）
+    OneTimeWithoutJti,
+
+    /// 使い捨てのトークンの使用済みの記録を読み書きできない（常に拒否する）
+    OneTimeUnavailable,
+
+    /// jwt クレートの検証エラー
+    Jwt(jwt::Error),
+}
 
-    /// Expiration Time（有効期限）: Unix タイムスタンプ（秒）
-    /// この時刻を過ぎるとトークンは無効
-    exp: Option<u64>,
+impl VerifyError {
+    /// 失敗の理由を数えるカウンタ名
+    fn metric(&self) -> &'static str {
+        match self {
+            Self::MissingToken => FAILURES_MISSING_TOKEN,
+            Self::UnknownKeyId
+            | Self::KeysUnavailable
+            | Self::Misconfigured
+            | Self::Revoked
+            | Self::RevocationUnavailable
+            | Self::InvalidSubject
+            | Self::UnknownIssuer
+            | Self::InvalidTyp
+            | Self::WrongTokenType
+            | Self::Superseded
+            | Self::Reused
+            | Self::OneTimeWithoutJti
+            | Self::OneTimeUnavailable => FAILURES_OTHER,
+            Self::Jwt(jwt::Error::Expired) => FAILURES_EXPIRED,
+            Self::Jwt(jwt::Error::InvalidSignature) => FAILURES_BAD_SIGNATURE,
+            Self::Jwt(jwt::Error::UnsecuredAlgorithm(_) | jwt::Error::UnsupportedAlgorithm(_)) => {
+                FAILURES_UNSUPPORTED_ALG
+            }
+            Self::Jwt(
+                jwt::Error::InvalidFormat
+                | jwt::Error::EncryptedToken
+                | jwt::Error::TooLarge
+                | jwt::Error::InvalidHeaderEncoding
+                | jwt::Error::InvalidHeaderJson
+                | jwt::Error::InvalidSignatureEncoding
+                | jwt::Error::InvalidPayloadEncoding
+                | jwt::Error::InvalidPayloadJson
+                | jwt::Error::MissingSubject
+                | jwt::Error::MissingExpiration,
+            ) => FAILURES_MALFORMED,
+            Self::Jwt(_) => FAILURES_OTHER,
+        }
+    }
+
+    /// auth-result の error-code に返す失敗の理由
+    ///
+    /// jwt クレートのエラーを追加した場合に対応漏れがないよう、ワイルドカードを使わない。
+    fn code(&self) -> AuthErrorCode {
+        match self {
+            Self::MissingToken => AuthErrorCode::MissingToken,
+            Self::UnknownKeyId => AuthErrorCode::InvalidSignature,
+            Self::KeysUnavailable
+            | Self::Misconfigured
+            | Self::RevocationUnavailable
+            | Self::OneTimeUnavailable => AuthErrorCode::Unavailable,
+            Self::Revoked | Self::Superseded | Self::Reused => AuthErrorCode::Revoked,
+            Self::InvalidSubject
+            | Self::UnknownIssuer
+            | Self::InvalidTyp
+            | Self::WrongTokenType
+            | Self::OneTimeWithoutJti => AuthErrorCode::InvalidClaims,
+            Self::Jwt(e) => match e {
+                jwt::Error::InvalidFormat
+                | jwt::Error::TooLarge
+                | jwt::Error::InvalidHeaderEncoding
+                | jwt::Error::InvalidHeaderJson
+                | jwt::Error::InvalidSignatureEncoding
+                | jwt::Error::InvalidPayloadEncoding
+                | jwt::Error::InvalidPayloadJson => AuthErrorCode::Malformed,
+                jwt::Error::EncryptedToken => AuthErrorCode::Encrypted,
+                jwt::Error::UnsecuredAlgorithm(_) | jwt::Error::UnsupportedAlgorithm(_) => {
+                    AuthErrorCode::UnsupportedAlgorithm
+                }
+                jwt::Error::InvalidSignature => AuthErrorCode::InvalidSignature,
+                jwt::Error::Expired => AuthErrorCode::Expired,
+                jwt::Error::NotYetValid | jwt::Error::IssuedInFuture => AuthErrorCode::NotYetValid,
+                jwt::Error::MissingSubject => AuthErrorCode::MissingSubject,
+                jwt::Error::MissingExpiration
+                | jwt::Error::InvalidIssuer
+                | jwt::Error::InvalidAudience => AuthErrorCode::InvalidClaims,
+                jwt::Error::InvalidKey | jwt::Error::InvalidPublicKey | jwt::Error::Encoding => {
+                    AuthErrorCode::Unavailable
+                }
+            },
+        }
+    }
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingToken => f.write_str("Missing token"),
+            Self::UnknownKeyId => f.write_str("Unknown signing key"),
+            Self::KeysUnavailable => f.write_str("Signing keys unavailable"),
+            Self::Misconfigured => f.write_str("Auth misconfigured"),
+            Self::Revoked => f.write_str("Token revoked"),
+            Self::RevocationUnavailable => f.write_str("Revocation check unavailable"),
+            Self::InvalidSubject => f.write_str("Invalid subject claim"),
+            Self::UnknownIssuer => f.write_str("Unknown issuer"),
+            Self::InvalidTyp => f.write_str("Invalid typ header"),
+            Self::WrongTokenType => f.write_str("Wrong token type"),
+            Self::Superseded => f.write_str("Token superseded"),
+            Self::Reused => f.write_str("Token already used"),
+            Self::OneTimeWithoutJti => f.write_str("One-time token without jti"),
+            Self::OneTimeUnavailable => f.write_str("One-time token check unavailable"),
+            Self::Jwt(e) => e.fmt(f),
+        }
+    }
+}
+
+/// ヘッダーの typ の検証（jwt_typ_policy）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum TypPolicy {
+    /// 検証しない（RFC 7519 では typ は任意）
+    Ignore,
+
+    /// typ がある場合のみ "JWT"（大文字小文字を区別しない）であること
+    #[default]
+    ValidateIfPresent,
+
+    /// typ が "JWT"（大文字小文字を区別しない）であること（typ のないトークンも拒否する）
+    Require,
+}
 
-    /// Issued At（発行時刻）: Unix タイムスタンプ（秒）
-    /// トークンがいつ発行されたかを示す
-    /// 検証には使用しないため、dead_code 警告を抑制
-    #[allow(dead_code)]
-    iat: Option<u64>,
+impl TypPolicy {
+    /// ヘッダーの typ（ない場合は None）を受け付けるか
+    fn accepts(self, typ: Option<&str>) -> bool {
+        let is_jwt = |typ: &str| typ.eq_ignore_ascii_case(JWT_TYP);
+        match self {
+            Self::Ignore => true,
+            Self::ValidateIfPresent => typ.is_none_or(is_jwt),
+            Self::Require => typ.is_some_and(is_jwt),
+        }
+    }
+}
+
+/// sub クレーム（gateway が X-User-Id に設定する値）に許可する形式
+#[derive(Debug, Clone, Default)]
+enum SubjectRule {
+    /// UUID（ハイフン区切りの 36 文字）のみ
+    #[default]
+    Uuid,
+
+    /// jwt_subject_pattern の正規表現に sub 全体が一致するもの
+    /// （MAX_SUBJECT_BYTES 以下で、制御文字を含まないこと）
+    Pattern(Regex),
+}
+
+impl SubjectRule {
+    /// sub がこの形式か
+    fn matches(&self, subject: &str) -> bool {
+        match self {
+            Self::Uuid => jwt::is_uuid(subject),
+            Self::Pattern(pattern) => {
+                subject.len() <= MAX_SUBJECT_BYTES
+                    && !subject.chars().any(char::is_control)
+                    && pattern.is_match(subject)
+            }
+        }
+    }
 }
 
 // =============================================================================
@@ -125,175 +840,3203 @@ impl Guest for AuthComponent {
     ///
     /// # 戻り値
     /// * `AuthResult` - 認証結果
-    ///   - 成功時: authenticated=true, user_id=Some(ユーザーID)
-    ///   - 失敗時: authenticated=false, error=Some(エラーメッセージ)
+    ///   - 成功時: authenticated=true, user_id=Some(ユーザーID),
+    ///     expires_at/issued_at/roles/claims=ペイロードの値
+    ///   - 失敗時: authenticated=false, error=Some(エラーメッセージ), error_code=Some(失敗の理由),
+    ///     expires_at/issued_at=None, roles/claims=空
     fn verify_token(token: String) -> AuthResult {
-        // verify_jwt 関数でトークンを検証
-        match verify_jwt(&token) {
-            // 検証成功: ユーザーIDを含む成功レスポンスを返す
-            Ok(user_id) => AuthResult {
-                authenticated: true,
-                user_id: Some(user_id),
-                error: None,
-            },
-            // 検証失敗: エラーメッセージを含む失敗レスポンスを返す
-            Err(e) => AuthResult {
-                authenticated: false,
-                user_id: None,
-                error: Some(e),
-            },
+        // verify_jwt 関数でトークンを検証（WASI の壁時計で有効期限を確認）
+        // 発行者・対象者は jwt_issuer / jwt_audiences を指定した場合のみ検証する
+        // RS256 / ES256 のトークンは公開鍵を指定した場合のみ、jwt_algorithms に含まれる場合のみ受け付ける
+        // jwt_tenants を指定した場合は、発行者ごとの鍵・対象者・アルゴリズムで検証する
+        let now = unix_now();
+        let leeway = leeway();
+        let issuer = expected_issuer();
+        let audiences = allowed_audiences();
+        let audiences: Vec<&str> = audiences.iter().map(String::as_str).collect();
+        let algorithms = allowed_algorithms();
+        let algorithms: Vec<&str> = algorithms.iter().map(String::as_str).collect();
+        let lenient_base64 = switch_variable(LENIENT_BASE64_VARIABLE);
+        if lenient_base64 && token.contains('=') {
+            // Base64URL のアルファベットに "=" はないため、"=" があればパディングを許容して読む
+            println!(
+                "[Auth] Warning: accepting padded base64url token segments ({}=on)",
+                LENIENT_BASE64_VARIABLE
+            );
+        }
+        let validation = Validation::new(now)
+            .with_leeway(leeway)
+            .with_issuer(issuer.as_deref())
+            .with_audiences(&audiences)
+            .with_algorithms(&algorithms)
+            .with_lenient_base64(lenient_base64)
+            .with_max_token_bytes(max_token_bytes());
+        let subject = subject_rule();
+        let result = load_keys(&token, now, &validation)
+            .and_then(|keys| verify_jwt(&token, &keys, &validation))
+            .and_then(|verified| check_subject(verified, &subject))
+            // リフレッシュトークンなど、アクセストークンでないトークンを拒否する
+            .and_then(|verified| {
+                check_token_use(verified, switch_variable(UNTYPED_TOKENS_VARIABLE))
+            })
+            .and_then(|verified| {
+                // 署名・クレームの検証に成功したトークンのみ失効リストと照合する
+                let revoked = jwks::KvStore::open().and_then(|store| {
+                    revocation::is_revoked(
+                        &store,
+                        &verified.user_id,
+                        verified.jti.as_deref(),
+                        verified.issued_at,
+                    )
+                });
+                check_revocation(
+                    verified,
+                    revoked,
+                    switch_variable(REVOCATION_FAIL_OPEN_VARIABLE),
+                )
+            })
+            .and_then(|verified| {
+                // ver がユーザーのバージョン（X-Token-Version）より小さいトークンを拒否する
+                let superseded = jwks::KvStore::open().and_then(|store| {
+                    revocation::is_superseded(&store, &verified.user_id, verified.token_version)
+                });
+                check_token_version(
+                    verified,
+                    superseded,
+                    switch_variable(REVOCATION_FAIL_OPEN_VARIABLE),
+                )
+            })
+            .and_then(|verified| {
+                // 使い捨てのトークンは、他の検証にすべて成功した後に使用済みとして記録する
+                check_one_time(verified, one_time::KvCasStore::open, now, leeway)
+            });
+
+        // 検証結果を数える（失敗しても認証結果には影響しない）
+        if let Err(e) = KvCounter::open().and_then(|counter| record_verification(&counter, &result))
+        {
+            println!("[Auth] Failed to record metrics: {}", e);
+        }
+
+        auth_result(result)
+    }
+
+    /// 開発用にユーザーのトークンを発行する（jwt_dev_tokens=on の場合のみ）
+    ///
+    /// # 引数
+    /// * `user_id` - sub クレーム
+    /// * `ttl_seconds` - 有効期間（秒）
+    ///
+    /// # 戻り値
+    /// * `Ok(String)` - 発行したトークン
+    /// * `Err(String)` - 無効・引数が不正・HS256 の鍵が設定されていない場合のエラーメッセージ
+    fn issue_token(user_id: String, ttl_seconds: u64) -> Result<String, String> {
+        if !switch_variable(DEV_TOKENS_VARIABLE) {
+            return Err(format!(
+                "issue-token is disabled ({}=off)",
+                DEV_TOKENS_VARIABLE
+            ));
+        }
+        let keyring = HmacKeyring::load().ok_or_else(|| VerifyError::Misconfigured.to_string())?;
+        // 追加のクレームなし（sub / iat / exp のみ）、有効期間の上限は token-issuer と同じ
+        let token = issue_jwt(&user_id, ttl_seconds, &[], &keyring, unix_now())?;
+        println!(
+            "[Auth] Issued dev token: sub={} ttl={}s kid={}",
+            user_id,
+            ttl_seconds,
+            keyring.signing_key().id
+        );
+        Ok(token)
+    }
+}
+
+/// token-issuer インターフェースの実装
+impl TokenIssuerGuest for AuthComponent {
+    /// サービス用トークンを発行する
+    ///
+    /// # 引数
+    /// * `subject` - sub クレーム
+    /// * `ttl_seconds` - 有効期間（秒）
+    /// * `claims` - 追加のクレーム（名前, 値）
+    ///
+    /// # 戻り値
+    /// * `Ok(String)` - 発行したトークン
+    /// * `Err(String)` - 引数が不正・HS256 の鍵が設定されていない場合のエラーメッセージ
+    fn issue(
+        subject: String,
+        ttl_seconds: u64,
+        claims: Vec<(String, String)>,
+    ) -> Result<String, String> {
+        let keyring = HmacKeyring::load().ok_or_else(|| VerifyError::Misconfigured.to_string())?;
+        let token = issue_jwt(&subject, ttl_seconds, &claims, &keyring, unix_now())?;
+        println!(
+            "[Auth] Issued token: sub={} ttl={}s kid={}",
+            subject,
+            ttl_seconds,
+            keyring.signing_key().id
+        );
+        Ok(token)
+    }
+}
+
+/// metrics インターフェースの実装
+impl MetricsGuest for AuthComponent {
+    /// 検証のカウンタ（名前, 値）の一覧を返す
+    ///
+    /// key-value ストアにアクセスできない場合は空リストを返します。
+    fn get_metrics() -> Vec<(String, u64)> {
+        match KvCounter::open().and_then(|counter| read_metrics(&counter)) {
+            Ok(metrics) => metrics,
+            Err(e) => {
+                println!("[Auth] Failed to read metrics: {}", e);
+                Vec::new()
+            }
         }
     }
 }
 
+/// 検証結果を WIT の auth-result に変換する
+///
+/// # 戻り値
+/// * `AuthResult` - 認証結果
+///   - 成功時: authenticated=true, user_id=Some(ユーザーID),
+///     expires_at/issued_at/roles/claims=ペイロードの値
+///   - 失敗時: authenticated=false, error=Some(エラーメッセージ), error_code=Some(失敗の理由),
+///     expires_at/issued_at=None, roles/claims=空
+fn auth_result(result: Result<VerifiedToken, VerifyError>) -> AuthResult {
+    match result {
+        // 検証成功: ユーザーIDと取り出したクレームを含む成功レスポンスを返す
+        Ok(verified) => AuthResult {
+            authenticated: true,
+            user_id: Some(verified.user_id),
+            error: None,
+            expires_at: verified.expires_at,
+            roles: verified.roles,
+            claims: verified.claims,
+            issued_at: verified.issued_at,
+            error_code: None,
+            one_time: verified.one_time,
+        },
+        // 検証失敗: エラーメッセージを含む失敗レスポンスを返す
+        // 失敗時はクレームを一切返さない
+        Err(e) => AuthResult {
+            authenticated: false,
+            user_id: None,
+            error: Some(e.to_string()),
+            expires_at: None,
+            roles: Vec::new(),
+            claims: Vec::new(),
+            issued_at: None,
+            error_code: Some(e.code()),
+            one_time: false,
+        },
+    }
+}
+
 // =============================================================================
-// JWT 検証ロジック
+// JWT 発行ロジック
 // =============================================================================
 
-/// JWT トークンを検証し、ユーザーIDを抽出する
-///
-/// JWT の構造: ヘッダー.ペイロード.署名（すべて Base64URL エンコード）
+/// 署名済みの JWT を作成する
 ///
-/// # 検証手順
-/// 1. トークンが空でないことを確認
-/// 2. ドットで3つの部分に分割できることを確認
-/// 3. ヘッダーをデコードし、アルゴリズムが HS256 であることを確認
-/// 4. HMAC-SHA256 で署名を検証
-/// 5. ペイロードをデコードし、有効期限を確認
-/// 6. ユーザーID（sub クレーム）を抽出
+/// ヘッダー: {"alg":"HS256","typ":"JWT","kid":最新の鍵の key id}
+/// ペイロード: sub / exp / iat と追加のクレーム（値は文字列、iss なし）
 ///
 /// # 引数
-/// * `token` - 検証対象の JWT トークン文字列
+/// * `subject` - sub クレーム
+/// * `ttl_seconds` - 有効期間（秒、1〜MAX_ISSUE_TTL_SECS）
+/// * `claims` - 追加のクレーム（登録済みクレームは指定できない）
+/// * `keyring` - HS256 の鍵リング（最新の鍵で署名する）
+/// * `now` - 現在時刻（Unix タイムスタンプ秒）
 ///
 /// # 戻り値
-/// * `Ok(String)` - 検証成功時、ユーザーIDを返す
-/// * `Err(String)` - 検証失敗時、エラーメッセージを返す
-fn verify_jwt(token: &str) -> Result<String, String> {
-    // --------------------------------------------------------
-    // Step 1: 空トークンのチェック
-    // --------------------------------------------------------
-    // Authorization ヘッダーがない場合、空文字列が渡される
-    if token.is_empty() {
-        return Err("Missing token".to_string());
-    }
-
+/// * `Ok(String)` - 発行したトークン
+/// * `Err(String)` - 引数が不正な場合のエラーメッセージ
+fn issue_jwt(
+    subject: &str,
+    ttl_seconds: u64,
+    claims: &[(String, String)],
+    keyring: &HmacKeyring,
+    now: u64,
+) -> Result<String, String> {
     // --------------------------------------------------------
-    // Step 2: JWT フォーマットの検証
+    // Step 1: 引数の検証
     // --------------------------------------------------------
-    // JWT は必ず3つの部分（ヘッダー、ペイロード、署名）で構成される
-    // 各部分はドット（.）で区切られている
-    let parts: Vec<&str> = token.split('.').collect();
-    if parts.len() != 3 {
-        return Err("Invalid token format".to_string());
+    if subject.is_empty() {
+        return Err("Missing subject".to_string());
     }
-
-    // 各部分を変数に格納
-    let header_b64 = parts[0]; // Base64 エンコードされたヘッダー
-    let payload_b64 = parts[1]; // Base64 エンコードされたペイロード
-    let signature_b64 = parts[2]; // Base64 エンコードされた署名
-
-    // --------------------------------------------------------
-    // Step 3: ヘッダーのデコードと検証
-    // --------------------------------------------------------
-    // Base64 をデコードして JSON 文字列を取得
-    let header_json = URL_SAFE_NO_PAD
-        .decode(header_b64)
-        .map_err(|_| "Invalid header encoding".to_string())?;
-
-    // JSON をパースして JwtHeader 構造体に変換
-    let header: JwtHeader =
-        serde_json::from_slice(&header_json).map_err(|_| "Invalid header JSON".to_string())?;
-
-    // アルゴリズムが HS256 であることを確認
-    // セキュリティ上、サポートするアルゴリズムを明示的に制限
-    if header.alg != "HS256" {
-        return Err(format!("Unsupported algorithm: {}", header.alg));
+    if ttl_seconds == 0 || ttl_seconds > MAX_ISSUE_TTL_SECS {
+        return Err(format!(
+            "Invalid ttl: must be between 1 and {} seconds",
+            MAX_ISSUE_TTL_SECS
+        ));
     }
 
     // --------------------------------------------------------
-    // Step 4: 署名の検証
-    // --------------------------------------------------------
-    // Base64 エンコードされた署名をデコード
-    let signature = URL_SAFE_NO_PAD
-        .decode(signature_b64)
-        .map_err(|_| "Invalid signature encoding".to_string())?;
-
-    // 署名対象のメッセージを構築（ヘッダー.ペイロード）
-    // 署名はヘッダーとペイロードの Base64 文字列に対して行われる
-    let message = format!("{}.{}", header_b64, payload_b64);
-
-    // HMAC-SHA256 で署名を検証
-    verify_signature(&message, &signature)?;
-
-    // --------------------------------------------------------
-    // Step 5: ペイロードのデコード
-    // --------------------------------------------------------
-    // Base64 をデコードして JSON 文字列を取得
-    let payload_json = URL_SAFE_NO_PAD
-        .decode(payload_b64)
-        .map_err(|_| "Invalid payload encoding".to_string())?;
-
-    // JSON をパースして JwtPayload 構造体に変換
-    let payload: JwtPayload =
-        serde_json::from_slice(&payload_json).map_err(|_| "Invalid payload JSON".to_string())?;
-
-    // --------------------------------------------------------
-    // Step 6: 有効期限のチェック（簡易実装）
+    // Step 2: クレームの組み立て
     // --------------------------------------------------------
-    // 注意: Wasm 環境では現在時刻の取得に制限があります。
-    // 本番環境では WASI の clock_time_get を使用して正確に検証してください。
-    // ここでは簡易的なチェックのみ実施。
-    if let Some(exp) = payload.exp {
-        // 2020年1月1日 00:00:00 UTC = 1577836800 秒
-        // この値より小さい exp は明らかに期限切れ
-        if exp < 1577836800 {
-            return Err("Token expired".to_string());
+    let mut extra = Map::new();
+    for (name, value) in claims {
+        // 登録済みクレームの上書き・同じクレームの重複は呼び出し側の誤り
+        if RESERVED_CLAIMS.contains(&name.as_str()) {
+            return Err(format!("Reserved claim: {}", name));
+        }
+        if extra
+            .insert(name.clone(), Value::String(value.clone()))
+            .is_some()
+        {
+            return Err(format!("Duplicate claim: {}", name));
         }
     }
+    // verify-token はアクセストークン（token_use: access）のみ受け付ける
+    extra.insert(
+        jwt::TOKEN_USE_CLAIM.to_string(),
+        Value::String(jwt::TOKEN_USE_ACCESS.to_string()),
+    );
+    let claims = Claims {
+        sub: subject.to_string(),
+        exp: now + ttl_seconds,
+        iat: now,
+        iss: None,
+        aud: None,
+        extra,
+    };
 
     // --------------------------------------------------------
-    // Step 7: ユーザーIDの抽出
+    // Step 3: 署名（jwt クレート）
     // --------------------------------------------------------
-    // sub（Subject）クレームからユーザーIDを取得
-    // sub がない場合はエラー
-    payload
-        .sub
-        .ok_or_else(|| "Missing subject claim".to_string())
+    let key = keyring.signing_key();
+    jwt::encode(&Header::hs256(Some(&key.id)), &claims, &key.secret).map_err(|e| e.to_string())
+}
+
+/// 現在時刻（Unix タイムスタンプ秒）
+///
+/// WASI の壁時計から取得する。取得できない場合は 0。
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
 }
 
-/// HMAC-SHA256 で署名を検証する
+/// 検証に使う鍵を読み込む
+///
+/// jwt_jwks_url を指定した場合は、kid のある RS256 / ES256 のトークンに限り JWKS も読み込む
+/// （HS256・kid のないトークン・形式が不正なトークン・jwt_tenants を指定した場合は取得しない）。
 ///
 /// # 引数
-/// * `message` - 署名対象のメッセージ（ヘッダー.ペイロード）
-/// * `signature` - 検証する署名（バイト列）
+/// * `token` - 検証対象の JWT トークン文字列
+/// * `now` - 現在時刻（Unix タイムスタンプ秒、JWKS のキャッシュの期限）
+/// * `validation` - ヘッダーの Base64URL の扱い（jwt_lenient_base64）
 ///
 /// # 戻り値
-/// * `Ok(())` - 署名が正しい場合
-/// * `Err(String)` - 署名が不正な場合
-fn verify_signature(message: &str, signature: &[u8]) -> Result<(), String> {
-    // HMAC-SHA256 の型エイリアスを定義
-    type HmacSha256 = Hmac<Sha256>;
+/// * `Err(VerifyError::KeysUnavailable)` - JWKS を取得できず、キャッシュもない場合
+fn load_keys(
+    token: &str,
+    now: u64,
+    validation: &Validation,
+) -> Result<VerificationKeys, VerifyError> {
+    let mut keys = VerificationKeys::load();
+    // 発行者ごとの鍵は HMAC のみのため、未知の発行者のトークンで JWKS を取得しない
+    if keys.tenants.is_some() {
+        return Ok(keys);
+    }
+    let Some(url) = jwks_url() else {
+        return Ok(keys);
+    };
+    let Some(kid) = jwks_kid(token, validation) else {
+        return Ok(keys);
+    };
 
-    // 秘密鍵で HMAC インスタンスを初期化
-    // new_from_slice: 任意長のスライスから HMAC を作成
-    let mut mac =
-        HmacSha256::new_from_slice(SECRET_KEY).map_err(|_| "Invalid key length".to_string())?;
+    let cache = jwks::JwksCache::open(&url).map_err(|e| {
+        println!("[Auth] {}, JWKS unavailable", e);
+        VerifyError::KeysUnavailable
+    })?;
+    keys.jwks = Some(
+        cache
+            .key_set(&kid, now)
+            .ok_or(VerifyError::KeysUnavailable)?,
+    );
+    Ok(keys)
+}
 
-    // メッセージを HMAC に入力
-    // update: データを追加（複数回呼び出し可能）
-    mac.update(message.as_bytes());
+/// JWKS の URL（Spin 変数 jwt_jwks_url、未定義・空の場合は None で JWKS を使わない）
+fn jwks_url() -> Option<String> {
+    spin_sdk::variables::get(JWKS_URL_VARIABLE)
+        .ok()
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+}
 
-    // 署名を検証
-    // verify_slice: 期待される署名と比較し、一致しなければエラー
-    // タイミング攻撃を防ぐため、定数時間で比較を行う
-    mac.verify_slice(signature)
-        .map_err(|_| "Invalid signature".to_string())
+/// JWKS で鍵を選ぶトークンの kid（RS256 / ES256 で kid がある場合のみ）
+fn jwks_kid(token: &str, validation: &Validation) -> Option<String> {
+    let header = jwt::decode_header_with(token, validation).ok()?;
+    matches!(header.alg.as_str(), "RS256" | "ES256")
+        .then_some(header.kid)
+        .flatten()
 }
 
-// =============================================================================
-// コンポーネントのエクスポート
-// =============================================================================
+/// exp / nbf / iat で許容する時計のずれ（秒）
+///
+/// Spin 変数 jwt_leeway_secs から読み込む。未定義の場合は既定値、不正な値の場合はログを出力して既定値。
+fn leeway() -> u64 {
+    let value = spin_sdk::variables::get(LEEWAY_VARIABLE).ok();
+    parse_leeway(value.as_deref()).unwrap_or_else(|e| {
+        println!("[Auth] {}, using {}s", e, DEFAULT_LEEWAY_SECS);
+        DEFAULT_LEEWAY_SECS
+    })
+}
+
+/// トークン全体の長さの上限（バイト）
+///
+/// Spin 変数 jwt_max_token_bytes から読み込む。未定義の場合は既定値、不正な値の場合はログを出力して既定値。
+fn max_token_bytes() -> usize {
+    let value = spin_sdk::variables::get(MAX_TOKEN_BYTES_VARIABLE).ok();
+    parse_max_token_bytes(value.as_deref()).unwrap_or_else(|e| {
+        println!("[Auth] {}, using {}", e, jwt::DEFAULT_MAX_TOKEN_BYTES);
+        jwt::DEFAULT_MAX_TOKEN_BYTES
+    })
+}
+
+/// on / off の Spin 変数（jwt_revocation_fail_open・jwt_dev_tokens・jwt_lenient_base64）を読み込む
+///
+/// 未定義の場合は off、不正な値の場合はログを出力して off。
+fn switch_variable(name: &str) -> bool {
+    let value = spin_sdk::variables::get(name).ok();
+    parse_switch(name, value.as_deref()).unwrap_or_else(|e| {
+        println!("[Auth] {}, using off", e);
+        false
+    })
+}
+
+/// 期待する発行者（Spin 変数 jwt_issuer、未定義・空の場合は None で iss を検証しない）
+fn expected_issuer() -> Option<String> {
+    spin_sdk::variables::get(ISSUER_VARIABLE)
+        .ok()
+        .map(|issuer| issuer.trim().to_string())
+        .filter(|issuer| !issuer.is_empty())
+}
+
+/// 受け付ける対象者（Spin 変数 jwt_audiences、未定義・空の場合は空で aud を検証しない）
+fn allowed_audiences() -> Vec<String> {
+    let value = spin_sdk::variables::get(AUDIENCES_VARIABLE).ok();
+    parse_audiences(value.as_deref())
+}
+
+/// jwt_audiences の値を解析する
+///
+/// カンマで区切り、前後の空白を除く。空の要素は無視する。
+///
+/// # 引数
+/// * `value` - Spin 変数の値（未定義の場合は None）
+fn parse_audiences(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|audience| !audience.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 受け付けるアルゴリズム
+///
+/// Spin 変数 jwt_algorithms から読み込む。未定義の場合は既定値、不正な値の場合はログを出力して既定値。
+fn allowed_algorithms() -> Vec<String> {
+    let value = spin_sdk::variables::get(ALGORITHMS_VARIABLE).ok();
+    parse_algorithms(value.as_deref()).unwrap_or_else(|e| {
+        println!("[Auth] {}, using {}", e, DEFAULT_ALGORITHMS.join(","));
+        parse_algorithms(None).unwrap_or_default()
+    })
+}
+
+/// jwt_algorithms の値を解析する
+///
+/// カンマで区切り、前後の空白を除く。空の要素は無視する。
+///
+/// # 引数
+/// * `value` - 変数の値（未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(Vec<String>)` - 受け付けるアルゴリズム（未定義・空の場合は DEFAULT_ALGORITHMS）
+/// * `Err(String)` - jwt::ALLOWED_ALGORITHMS に含まれない値（"none"、RS512 など）がある場合
+fn parse_algorithms(value: Option<&str>) -> Result<Vec<String>, String> {
+    let algorithms: Vec<String> = value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|alg| !alg.is_empty())
+        .map(str::to_string)
+        .collect();
+    if algorithms.is_empty() {
+        return Ok(DEFAULT_ALGORITHMS
+            .iter()
+            .map(|alg| alg.to_string())
+            .collect());
+    }
+
+    match algorithms
+        .iter()
+        .find(|alg| !jwt::ALLOWED_ALGORITHMS.contains(&alg.as_str()))
+    {
+        Some(unknown) => Err(format!(
+            "Invalid {} {:?}: must be a comma-separated list of {}",
+            ALGORITHMS_VARIABLE,
+            unknown,
+            jwt::ALLOWED_ALGORITHMS.join(", ")
+        )),
+        None => Ok(algorithms),
+    }
+}
+
+/// ヘッダーの typ の検証
+///
+/// Spin 変数 jwt_typ_policy から読み込む。未定義・空の場合は validate-if-present、
+/// 不正な値の場合はログを出力して validate-if-present。
+fn typ_policy() -> TypPolicy {
+    let value = spin_sdk::variables::get(TYP_POLICY_VARIABLE).ok();
+    parse_typ_policy(value.as_deref()).unwrap_or_else(|e| {
+        println!("[Auth] {}, using validate-if-present", e);
+        TypPolicy::default()
+    })
+}
+
+/// jwt_typ_policy の値を解析する
+///
+/// # 引数
+/// * `value` - 変数の値（未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(TypPolicy)` - 検証の方法（未定義・空の場合は TypPolicy::ValidateIfPresent、大文字小文字を区別しない）
+/// * `Err(String)` - ignore / validate-if-present / require 以外の場合
+fn parse_typ_policy(value: Option<&str>) -> Result<TypPolicy, String> {
+    let value = value.map(str::trim).unwrap_or_default();
+    match value.to_ascii_lowercase().as_str() {
+        "" => Ok(TypPolicy::default()),
+        "ignore" => Ok(TypPolicy::Ignore),
+        "validate-if-present" => Ok(TypPolicy::ValidateIfPresent),
+        "require" => Ok(TypPolicy::Require),
+        _ => Err(format!(
+            "Invalid {} {:?}: must be ignore, validate-if-present or require",
+            TYP_POLICY_VARIABLE, value
+        )),
+    }
+}
+
+/// sub クレームに許可する形式
+///
+/// Spin 変数 jwt_subject_pattern から読み込む。未定義・空の場合は UUID、不正な値の場合はログを出力して UUID。
+fn subject_rule() -> SubjectRule {
+    let value = spin_sdk::variables::get(SUBJECT_PATTERN_VARIABLE).ok();
+    parse_subject_pattern(value.as_deref()).unwrap_or_else(|e| {
+        println!("[Auth] {}, accepting UUID subjects only", e);
+        SubjectRule::Uuid
+    })
+}
+
+/// jwt_subject_pattern の値を解析する
+///
+/// 部分一致で受け付けないよう、sub 全体に一致する正規表現（`^(?:...)$`）にする。
+///
+/// # 引数
+/// * `value` - 変数の値（未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(SubjectRule)` - 許可する形式（未定義・空の場合は SubjectRule::Uuid）
+/// * `Err(String)` - 正規表現として不正な場合
+fn parse_subject_pattern(value: Option<&str>) -> Result<SubjectRule, String> {
+    let value = value.map(str::trim).unwrap_or_default();
+    if value.is_empty() {
+        return Ok(SubjectRule::Uuid);
+    }
+
+    Regex::new(&format!("^(?:{})$", value))
+        .map(SubjectRule::Pattern)
+        .map_err(|e| format!("Invalid {} {:?}: {}", SUBJECT_PATTERN_VARIABLE, value, e))
+}
+
+/// jwt_hs256_keys の値を解析する
+///
+/// # 引数
+/// * `value` - 変数の値（kid → シークレットの JSON オブジェクト、未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(Some(HmacKeyring))` - 鍵リング（記載順）
+/// * `Ok(None)` - 未定義・空の場合（jwt_secret を使う）
+/// * `Err(String)` - JSON が不正、鍵がない、kid・シークレットが空、kid に空白を含む・重複している場合
+fn parse_hs256_keys(value: Option<&str>) -> Result<Option<HmacKeyring>, String> {
+    let value = value.map(str::trim).unwrap_or_default();
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    let invalid = |reason: String| format!("Invalid {}: {}", HS256_KEYS_VARIABLE, reason);
+    let OrderedSecrets(entries) =
+        serde_json::from_str(value).map_err(|e| invalid(e.to_string()))?;
+    hmac_keyring(entries).map(Some).map_err(invalid)
+}
+
+/// kid → シークレットの一覧から鍵リングを作る（jwt_hs256_keys・jwt_tenants の keys）
+///
+/// # 戻り値
+/// * `Ok(HmacKeyring)` - 鍵リング（一覧の順）
+/// * `Err(String)` - 鍵がない、kid・シークレットが空、kid に空白を含む・重複している場合の理由
+fn hmac_keyring(entries: Vec<(String, String)>) -> Result<HmacKeyring, String> {
+    if entries.is_empty() {
+        return Err("no keys defined".to_string());
+    }
+
+    let mut keys: Vec<HmacKey> = Vec::with_capacity(entries.len());
+    for (id, secret) in entries {
+        // シークレットはログに出力しない（kid のみ）
+        if id.is_empty() || id.chars().any(char::is_whitespace) {
+            return Err(format!("invalid key id {:?}", id));
+        }
+        if secret.is_empty() {
+            return Err(format!("key {:?} has an empty secret", id));
+        }
+        if keys.iter().any(|key| key.id == id) {
+            return Err(format!("duplicate key id {:?}", id));
+        }
+        keys.push(HmacKey {
+            id,
+            secret: secret.into_bytes(),
+        });
+    }
+    Ok(HmacKeyring { keys })
+}
+
+/// jwt_tenants の値を解析する
+///
+/// # 引数
+/// * `value` - 変数の値（発行者 → 設定の JSON オブジェクト、未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(Some(Vec<Tenant>))` - 発行者ごとの設定
+/// * `Ok(None)` - 未定義・空の場合（発行者ごとの設定を使わない）
+/// * `Err(String)` - JSON が不正、発行者がない、いずれかの発行者の設定が不正な場合
+fn parse_tenants(value: Option<&str>) -> Result<Option<Vec<Tenant>>, String> {
+    let value = value.map(str::trim).unwrap_or_default();
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    let invalid = |reason: String| format!("Invalid {}: {}", TENANTS_VARIABLE, reason);
+    let tenants: Map<String, Value> =
+        serde_json::from_str(value).map_err(|e| invalid(e.to_string()))?;
+    if tenants.is_empty() {
+        return Err(invalid("no issuers defined".to_string()));
+    }
+
+    tenants
+        .into_iter()
+        .map(|(issuer, config)| parse_tenant(issuer, &config).map_err(invalid))
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+/// jwt_tenants の 1 つの発行者の設定を解析する
+///
+/// # 引数
+/// * `issuer` - 発行者（iss）
+/// * `config` - 設定（TENANT_FIELDS のフィールドを持つ JSON オブジェクト）
+///
+/// # 戻り値
+/// * `Ok(Tenant)` - 発行者の設定
+/// * `Err(String)` - 発行者が空、alg が HMAC のアルゴリズムでない、keys が不正、audiences が空でない文字列の配列でない、
+///   未知のフィールドがある場合の理由（シークレットは含めない）
+fn parse_tenant(issuer: String, config: &Value) -> Result<Tenant, String> {
+    if issuer.trim().is_empty() {
+        return Err(format!("invalid issuer {:?}", issuer));
+    }
+    let invalid = |reason: &str| format!("issuer {:?} {}", issuer, reason);
+    let Some(config) = config.as_object() else {
+        return Err(invalid("must be an object"));
+    };
+    if let Some(field) = config
+        .keys()
+        .find(|field| !TENANT_FIELDS.contains(&field.as_str()))
+    {
+        return Err(invalid(&format!("has an unknown field {:?}", field)));
+    }
+
+    let algorithm = config
+        .get("alg")
+        .and_then(Value::as_str)
+        .filter(|alg| jwt::HMAC_ALGORITHMS.contains(alg))
+        .ok_or_else(|| {
+            invalid(&format!(
+                "must set alg to one of {}",
+                jwt::HMAC_ALGORITHMS.join(", ")
+            ))
+        })?
+        .to_string();
+    let OrderedSecrets(entries) = config
+        .get("keys")
+        .ok_or_else(|| invalid("has no keys"))
+        .and_then(|keys| {
+            OrderedSecrets::deserialize(keys)
+                .map_err(|e| invalid(&format!("has invalid keys: {}", e)))
+        })?;
+    let keyring = hmac_keyring(entries)
+        .map_err(|reason| invalid(&format!("has invalid keys: {}", reason)))?;
+    let audiences = match config.get("audiences") {
+        None => Vec::new(),
+        Some(audiences) => Vec::<String>::deserialize(audiences)
+            .ok()
+            .filter(|audiences| audiences.iter().all(|audience| !audience.trim().is_empty()))
+            .ok_or_else(|| invalid("must set audiences to an array of non-empty strings"))?,
+    };
+
+    Ok(Tenant {
+        issuer,
+        algorithm,
+        keyring,
+        audiences,
+    })
+}
+
+/// 公開鍵を Spin 変数から読み込む（不正な鍵の場合はログを出力して None）
+///
+/// # 引数
+/// * `variable` - Spin 変数名
+/// * `parse` - 値の解析（parse_rsa_public_key / parse_ec_public_key）
+fn load_public_key<K>(
+    variable: &str,
+    parse: fn(Option<&str>) -> Result<Option<K>, String>,
+) -> Option<K> {
+    let value = spin_sdk::variables::get(variable).ok();
+    parse(value.as_deref()).unwrap_or_else(|e| {
+        println!("[Auth] {}, ignoring the public key", e);
+        None
+    })
+}
+
+/// 公開鍵の Spin 変数の値を整える
+///
+/// 前後の空白を除き、`\n` を改行に戻す
+/// （Spin 変数（環境変数 SPIN_VARIABLE_*）では改行を書きにくいため）。未定義・空の場合は None。
+fn key_material(value: Option<&str>) -> Option<String> {
+    let value = value.map(str::trim).unwrap_or_default();
+    (!value.is_empty()).then(|| value.replace("\\n", "\n"))
+}
+
+/// jwt_rs256_public_key の値を解析する
+///
+/// # 引数
+/// * `value` - 変数の値（PEM、未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(Some(RsaPublicKey))` - 公開鍵
+/// * `Ok(None)` - 未定義・空の場合
+/// * `Err(String)` - PEM・鍵の形式が不正な場合
+fn parse_rsa_public_key(value: Option<&str>) -> Result<Option<RsaPublicKey>, String> {
+    let Some(pem) = key_material(value) else {
+        return Ok(None);
+    };
+
+    RsaPublicKey::from_pem(&pem)
+        .map(Some)
+        .map_err(|e| format!("Invalid {}: {}", RS256_PUBLIC_KEY_VARIABLE, e))
+}
+
+/// jwt_es256_public_key の値を解析する
+///
+/// `{` で始まる場合は JWK、それ以外は PEM として読み込む。
+///
+/// # 引数
+/// * `value` - 変数の値（PEM または JWK、未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(Some(EcPublicKey))` - 公開鍵
+/// * `Ok(None)` - 未定義・空の場合
+/// * `Err(String)` - PEM・JWK・鍵の形式が不正、または P-256 の鍵でない場合
+fn parse_ec_public_key(value: Option<&str>) -> Result<Option<EcPublicKey>, String> {
+    let Some(key) = key_material(value) else {
+        return Ok(None);
+    };
+
+    let parsed = if key.starts_with('{') {
+        EcPublicKey::from_jwk(&key)
+    } else {
+        EcPublicKey::from_pem(&key)
+    };
+    parsed
+        .map(Some)
+        .map_err(|e| format!("Invalid {}: {}", ES256_PUBLIC_KEY_VARIABLE, e))
+}
+
+/// on / off の変数の値を解析する
+///
+/// # 引数
+/// * `name` - 変数名（エラーメッセージに使う）
+/// * `value` - 変数の値（未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(bool)` - on の場合は true、off・未定義・空の場合は false（大文字小文字を区別しない）
+/// * `Err(String)` - on / off 以外の場合
+fn parse_switch(name: &str, value: Option<&str>) -> Result<bool, String> {
+    let value = value.map(str::trim).unwrap_or_default();
+    if value.is_empty() || value.eq_ignore_ascii_case("off") {
+        return Ok(false);
+    }
+    if value.eq_ignore_ascii_case("on") {
+        return Ok(true);
+    }
+    Err(format!("Invalid {} {:?}: must be on or off", name, value))
+}
+
+/// jwt_leeway_secs の値を解析する
+///
+/// # 引数
+/// * `value` - 変数の値（未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(u64)` - leeway（秒、未定義・空の場合は DEFAULT_LEEWAY_SECS）
+/// * `Err(String)` - 0 以上の整数でない、または MAX_LEEWAY_SECS を超える場合
+fn parse_leeway(value: Option<&str>) -> Result<u64, String> {
+    let value = value.map(str::trim).unwrap_or_default();
+    if value.is_empty() {
+        return Ok(DEFAULT_LEEWAY_SECS);
+    }
+
+    match value.parse::<u64>() {
+        Ok(leeway) if leeway <= MAX_LEEWAY_SECS => Ok(leeway),
+        _ => Err(format!(
+            "Invalid {} {:?}: must be an integer between 0 and {}",
+            LEEWAY_VARIABLE, value, MAX_LEEWAY_SECS
+        )),
+    }
+}
+
+/// jwt_max_token_bytes の値を解析する
+///
+/// # 引数
+/// * `value` - 変数の値（未定義の場合は None）
+///
+/// # 戻り値
+/// * `Ok(usize)` - 上限（バイト、未定義・空の場合は jwt::DEFAULT_MAX_TOKEN_BYTES）
+/// * `Err(String)` - 整数でない、または MAX_TOKEN_BYTES_RANGE の範囲外の場合
+fn parse_max_token_bytes(value: Option<&str>) -> Result<usize, String> {
+    let value = value.map(str::trim).unwrap_or_default();
+    if value.is_empty() {
+        return Ok(jwt::DEFAULT_MAX_TOKEN_BYTES);
+    }
+
+    match value.parse::<usize>() {
+        Ok(bytes) if MAX_TOKEN_BYTES_RANGE.contains(&bytes) => Ok(bytes),
+        _ => Err(format!(
+            "Invalid {} {:?}: must be an integer between {} and {}",
+            MAX_TOKEN_BYTES_VARIABLE,
+            value,
+            MAX_TOKEN_BYTES_RANGE.start(),
+            MAX_TOKEN_BYTES_RANGE.end()
+        )),
+    }
+}
+
+// =============================================================================
+// JWT 検証ロジック
+// =============================================================================
+
+/// JWT トークンを検証し、ユーザーIDとクレームを抽出する
+///
+/// JWT の構造: ヘッダー.ペイロード.署名（すべて Base64URL エンコード）
+///
+/// # 検証手順
+/// 1. トークンが空でないことを確認
+///    - 長さの上限（jwt_max_token_bytes、既定 8 KiB。ヘッダー・署名は jwt クレートの上限）を超える場合は、
+///      Base64URL をデコードする前に "Token too large"
+///    - 5 つの部分の JWE（暗号化したトークン）は、Base64URL をデコードする前に
+///      "Encrypted tokens (JWE) are not supported"（ヘッダーに enc があるトークンも同じ）
+/// 2. ヘッダーの alg・kid で鍵を選ぶ（RS256 / ES256 は公開鍵を指定した場合のみ公開鍵、それ以外は HS256 のシークレット）
+///    - HS256 / HS384 / HS512 は鍵リング（jwt_hs256_keys、ない場合は jwt_secret）の kid の鍵。kid がない場合は新しい鍵から順に試し、
+///      署名が一致した鍵で検証する（ない場合は "Unknown signing key"、どちらも未設定の場合は "Auth misconfigured"）
+///    - JWKS がある場合は kid の鍵（ない場合は "Unknown signing key"）
+///    - jwt_tenants を指定した場合は、署名を検証する前の iss で発行者を選び、その発行者の kid の鍵のみ
+///      （iss がない・未知の発行者は署名を確認せずに "Unknown issuer"。発行者・対象者・アルゴリズムも
+///      jwt_issuer などの代わりにその発行者の設定で検証する）
+///    - 鍵を選ぶ前に、ヘッダーの typ を jwt_typ_policy で検証する（JWT 以外・require で typ がない場合は
+///      "Invalid typ header"）
+/// 3. jwt クレートで検証（コア層の KeySet::verify と同じ規則）
+///    - アルゴリズムが許可リスト（jwt_algorithms、既定は HS256 / RS256 / ES256。HS384 / HS512 は明示した場合のみ）に含まれ、
+///      鍵の種類と一致すること（公開鍵を指定していない RS256 / ES256・公開鍵で署名した HS256 は拒否する）
+///    - "none" は大文字小文字・jwt_algorithms にかかわらず、鍵を選ぶ前に
+///      "Unsecured algorithm not allowed: none" で拒否する
+///    - HMAC-SHA256 / 384 / 512（alg で選ぶ）/ RSASSA-PKCS1-v1_5 / ECDSA P-256 の署名（ES256 は r || s の 64 バイト、DER は拒否する）
+///    - sub と exp があること（exp のないトークンは拒否する）
+///    - exp + leeway が現在時刻以降であること（過ぎた場合は "Token expired"）
+///    - nbf がある場合、nbf - leeway が現在時刻以前であること（前の場合は "Token not yet valid"、
+///      予約したジョブ用に事前に発行したトークン。nbf がないトークンは許可する）
+///    - iat がある場合、iat - leeway が現在時刻以前であること（未来の場合は "Token issued in the future"、
+///      時計の誤りか偽造）
+///    - 発行者を指定した場合（jwt_issuer）、iss が一致すること（iss がない・異なる場合は "Invalid issuer"）
+///    - 対象者を指定した場合（jwt_audiences）、aud のいずれかが含まれること
+///      （aud がない・空の配列・一致しない場合は "Invalid audience"）
+/// 4. 有効期限・ロール・許可リストのクレームを抽出
+///
+/// # 引数
+/// * `token` - 検証対象の JWT トークン文字列
+/// * `keys` - 検証に使う鍵（jwt_secret / jwt_hs256_keys / jwt_rs256_public_key / jwt_es256_public_key / jwt_jwks_url / jwt_tenants）
+/// * `validation` - 現在時刻（WASI の壁時計）・exp / nbf / iat の leeway（jwt_leeway_secs）・
+///   期待する発行者（jwt_issuer）・受け付ける対象者（jwt_audiences）・アルゴリズム（jwt_algorithms）
+///
+/// # 戻り値
+/// * `Ok(VerifiedToken)` - 検証成功時、ユーザーIDとクレームを返す
+/// * `Err(VerifyError)` - 検証失敗時、失敗の理由を返す
+fn verify_jwt(
+    token: &str,
+    keys: &VerificationKeys,
+    validation: &Validation,
+) -> Result<VerifiedToken, VerifyError> {
+    // --------------------------------------------------------
+    // Step 1: 空トークンのチェック
+    // --------------------------------------------------------
+    // Authorization ヘッダーがない場合、空文字列が渡される
+    if token.is_empty() {
+        return Err(VerifyError::MissingToken);
+    }
+
+    // --------------------------------------------------------
+    // Step 2: 鍵の選択
+    // --------------------------------------------------------
+    // RS256 / ES256 でも公開鍵がない場合は空の HS256 の鍵を渡し、jwt クレートが
+    // "Unsupported algorithm: RS256" などで拒否する
+    // 長さの上限を超えるトークンは decode_header_with が Base64URL をデコードする前に拒否する
+    // "none"（大文字小文字を区別しない）は鍵の設定・kid より先に拒否する
+    let header = jwt::decode_header_with(token, validation).map_err(VerifyError::Jwt)?;
+    if header
+        .alg
+        .trim()
+        .eq_ignore_ascii_case(jwt::UNSECURED_ALGORITHM)
+    {
+        return Err(VerifyError::Jwt(jwt::Error::UnsecuredAlgorithm(header.alg)));
+    }
+    // typ は jwt_typ_policy で検証する（既定は typ がある場合のみ "JWT"）
+    if !keys.typ_policy.accepts(header.typ.as_deref()) {
+        return Err(VerifyError::InvalidTyp);
+    }
+
+    // --------------------------------------------------------
+    // Step 3: 署名・有効期限の検証
+    // --------------------------------------------------------
+    // エラーメッセージ（"Invalid signature", "Token expired" など）はそのまま gateway に返す
+    // jwt_tenants を指定した場合は、iss で選んだ発行者の鍵・発行者・対象者・アルゴリズムだけで検証する
+    // （他の発行者の鍵・jwt_secret は試さない）
+    let claims = match &keys.tenants {
+        Some(tenants) => {
+            let tenant = select_tenant(token, tenants, validation)?;
+            let audiences: Vec<&str> = tenant.audiences.iter().map(String::as_str).collect();
+            let algorithms = [tenant.algorithm.as_str()];
+            let validation = validation
+                .with_issuer(Some(&tenant.issuer))
+                .with_audiences(&audiences)
+                .with_algorithms(&algorithms);
+            let candidates = tenant.keyring.decoding_keys(header.kid.as_deref())?;
+            decode_with_candidates(token, &candidates, &validation)?
+        }
+        None => decode_with_candidates(token, &keys.decoding_keys(&header)?, validation)?,
+    };
+
+    // --------------------------------------------------------
+    // Step 4: 有効期限・発行時刻・ロール・クレームの抽出
+    // --------------------------------------------------------
+    // ロール・クレームは検証結果を補足する情報のため、型が想定と異なっても失敗にしない
+    // （0.1 の頃に受け入れていたトークンを拒否しない）
+    // jwt クレートは iat がない場合に 0 とするため、0 は「iat なし」として扱う
+    Ok(VerifiedToken {
+        expires_at: Some(claims.exp),
+        issued_at: (claims.iat != 0).then_some(claims.iat),
+        roles: claims.roles(),
+        claims: extract_claims(&claims),
+        jti: claims.claim_str("jti"),
+        token_use: claims.claim_str(jwt::TOKEN_USE_CLAIM),
+        token_version: claims.extra.get("ver").and_then(Value::as_u64).unwrap_or(0),
+        one_time: claims.extra.get("one_time") == Some(&Value::Bool(true)),
+        user_id: claims.sub,
+    })
+}
+
+/// 署名を検証する前の iss で jwt_tenants の発行者を選ぶ
+///
+/// iss は鍵を選ぶためだけに使い、選んだ発行者の設定で署名・iss を検証する（verify_jwt）。
+///
+/// # 引数
+/// * `token` - 検証対象の JWT トークン文字列
+/// * `tenants` - 発行者ごとの設定（jwt_tenants）
+/// * `validation` - ペイロードの Base64URL の扱い（jwt_lenient_base64）・長さの上限
+///
+/// # 戻り値
+/// * `Ok(&Tenant)` - iss の発行者の設定
+/// * `Err(VerifyError::UnknownIssuer)` - iss がない・文字列でない・jwt_tenants にない発行者の場合
+/// * `Err(VerifyError::Misconfigured)` - jwt_tenants が不正な場合（発行者の設定が空）
+/// * `Err(VerifyError::Jwt)` - ペイロードを読めない場合
+fn select_tenant<'a>(
+    token: &str,
+    tenants: &'a [Tenant],
+    validation: &Validation,
+) -> Result<&'a Tenant, VerifyError> {
+    if tenants.is_empty() {
+        return Err(VerifyError::Misconfigured);
+    }
+    let issuer = jwt::decode_issuer_with(token, validation).map_err(VerifyError::Jwt)?;
+    issuer
+        .and_then(|issuer| tenants.iter().find(|tenant| tenant.issuer == issuer))
+        .ok_or(VerifyError::UnknownIssuer)
+}
+
+/// 候補の鍵で順に検証する
+///
+/// kid のない HMAC のトークンは署名が一致しない間だけ次の鍵を試す（最後の鍵の結果を返す）。
+///
+/// # 引数
+/// * `token` - 検証対象の JWT トークン文字列
+/// * `candidates` - 試す鍵（1 つ以上）
+/// * `validation` - 検証の条件
+fn decode_with_candidates(
+    token: &str,
+    candidates: &[DecodingKey<'_>],
+    validation: &Validation,
+) -> Result<Claims, VerifyError> {
+    let mut result = Err(jwt::Error::InvalidSignature);
+    for key in candidates {
+        result = jwt::decode_with_key(token, key, validation);
+        if result != Err(jwt::Error::InvalidSignature) {
+            break;
+        }
+    }
+    result.map_err(VerifyError::Jwt)
+}
+
+/// 使い捨てのトークン（one_time: true）の jti を使用済みとして記録する
+///
+/// 使い捨てでないトークンはストアを開かずに検証を通す。
+/// 使用済みの記録を読み書きできない場合は、jwt_revocation_fail_open に関わらず拒否する
+/// （同じトークンを 2 回受け付けないため）。
+///
+/// # 引数
+/// * `verified` - 失効リストとの照合まで成功したトークン
+/// * `open` - 使用済みの jti の保存先を開く（使い捨てのトークンの場合のみ呼ぶ）
+/// * `now` - 現在時刻（Unix タイムスタンプ秒）
+/// * `leeway` - 許容する時計のずれ（秒、exp + leeway まで使用済みとして記録する）
+///
+/// # 戻り値
+/// * `Ok(VerifiedToken)` - 使い捨てでない、または初めて提示された場合
+/// * `Err(VerifyError::Reused)` - 使用済みの場合
+/// * `Err(VerifyError::OneTimeWithoutJti)` - jti がない・空の場合
+/// * `Err(VerifyError::OneTimeUnavailable)` - 使用済みの記録を読み書きできない場合
+fn check_one_time<S: one_time::CasStore>(
+    verified: VerifiedToken,
+    open: impl FnOnce() -> Result<S, String>,
+    now: u64,
+    leeway: u64,
+) -> Result<VerifiedToken, VerifyError> {
+    if !verified.one_time {
+        return Ok(verified);
+    }
+    let Some(jti) = verified.jti.as_deref().filter(|jti| !jti.is_empty()) else {
+        println!(
+            "[Auth] One-time token without jti: sub={}",
+            verified.user_id
+        );
+        return Err(VerifyError::OneTimeWithoutJti);
+    };
+    let valid_until = verified.expires_at.unwrap_or(now).saturating_add(leeway);
+    match open().and_then(|store| one_time::consume(&store, jti, valid_until, now)) {
+        Ok(true) => Ok(verified),
+        Ok(false) => {
+            println!(
+                "[Auth] One-time token already used: sub={}",
+                verified.user_id
+            );
+            Err(VerifyError::Reused)
+        }
+        Err(e) => {
+            println!("[Auth] {}, rejecting one-time token", e);
+            Err(VerifyError::OneTimeUnavailable)
+        }
+    }
+}
+
+/// 失効リストとの照合の結果を検証結果に反映する
+///
+/// 失効リストを読めない場合、fail_open（jwt_revocation_fail_open=on）なら警告して検証を通し、
+/// それ以外は "Revocation check unavailable" で拒否する。
+///
+/// # 引数
+/// * `verified` - verify_jwt で検証に成功したトークン
+/// * `revoked` - revocation::is_revoked の結果（ストアを開けない場合を含む）
+/// * `fail_open` - 失効リストを読めない場合に検証を通すか
+///
+/// # 戻り値
+/// * `Ok(VerifiedToken)` - 失効していない場合（fail_open で確認できなかった場合を含む）
+/// * `Err(VerifyError::Revoked)` - 失効している場合
+/// * `Err(VerifyError::RevocationUnavailable)` - 失効リストを読めず、fail_open でない場合
+fn check_revocation(
+    verified: VerifiedToken,
+    revoked: Result<bool, String>,
+    fail_open: bool,
+) -> Result<VerifiedToken, VerifyError> {
+    match revoked {
+        Ok(false) => Ok(verified),
+        Ok(true) => {
+            println!("[Auth] Token revoked: sub={}", verified.user_id);
+            Err(VerifyError::Revoked)
+        }
+        Err(e) if fail_open => {
+            println!(
+                "[Auth] {}, skipping revocation check ({}=on)",
+                e, REVOCATION_FAIL_OPEN_VARIABLE
+            );
+            Ok(verified)
+        }
+        Err(e) => {
+            println!("[Auth] {}, rejecting token", e);
+            Err(VerifyError::RevocationUnavailable)
+        }
+    }
+}
+
+/// ユーザーのバージョンとの比較の結果を検証結果に反映する
+///
+/// ストアを読めない場合は失効リストと同じく、fail_open（jwt_revocation_fail_open=on）なら警告して検証を通し、
+/// それ以外は "Revocation check unavailable" で拒否する。
+///
+/// # 引数
+/// * `verified` - 失効リストとの照合まで成功したトークン
+/// * `superseded` - revocation::is_superseded の結果（ストアを開けない場合を含む）
+/// * `fail_open` - ストアを読めない場合に検証を通すか
+///
+/// # 戻り値
+/// * `Ok(VerifiedToken)` - ver がユーザーのバージョン以上の場合（fail_open で確認できなかった場合を含む）
+/// * `Err(VerifyError::Superseded)` - ver がユーザーのバージョンより小さい場合
+/// * `Err(VerifyError::RevocationUnavailable)` - ストアを読めず、fail_open でない場合
+fn check_token_version(
+    verified: VerifiedToken,
+    superseded: Result<bool, String>,
+    fail_open: bool,
+) -> Result<VerifiedToken, VerifyError> {
+    match superseded {
+        Ok(false) => Ok(verified),
+        Ok(true) => {
+            println!(
+                "[Auth] Token superseded: sub={} ver={}",
+                verified.user_id, verified.token_version
+            );
+            Err(VerifyError::Superseded)
+        }
+        Err(e) if fail_open => {
+            println!(
+                "[Auth] {}, skipping token version check ({}=on)",
+                e, REVOCATION_FAIL_OPEN_VARIABLE
+            );
+            Ok(verified)
+        }
+        Err(e) => {
+            println!("[Auth] {}, rejecting token", e);
+            Err(VerifyError::RevocationUnavailable)
+        }
+    }
+}
+
+/// sub クレームの形式を確認する
+///
+/// gateway は sub をそのまま X-User-Id に設定するため、SQL の断片・長い文字列・改行などを
+/// コア層に転送しない（コア層の UserContext も UUID のみ受け付ける）。
+///
+/// # 引数
+/// * `verified` - verify_jwt で検証に成功したトークン
+/// * `rule` - sub に許可する形式（jwt_subject_pattern）
+///
+/// # 戻り値
+/// * `Ok(VerifiedToken)` - sub が形式に一致する場合
+/// * `Err(VerifyError::InvalidSubject)` - 一致しない場合
+fn check_subject(
+    verified: VerifiedToken,
+    rule: &SubjectRule,
+) -> Result<VerifiedToken, VerifyError> {
+    if rule.matches(&verified.user_id) {
+        Ok(verified)
+    } else {
+        Err(VerifyError::InvalidSubject)
+    }
+}
+
+/// トークンの種類（token_use クレーム）を確認する
+///
+/// リフレッシュトークンもアクセストークンと同じ鍵で署名するため、漏えいしたリフレッシュトークンで
+/// API を呼び出せないよう access のみ受け付ける（コア層のリフレッシュのエンドポイントは独自に検証する）。
+///
+/// # 引数
+/// * `verified` - verify_jwt で検証に成功したトークン
+/// * `allow_untyped` - token_use のないトークンを受け付けるか（jwt_allow_untyped_tokens）
+///
+/// # 戻り値
+/// * `Ok(VerifiedToken)` - token_use が access、または token_use がなく allow_untyped の場合
+/// * `Err(VerifyError::WrongTokenType)` - それ以外の場合
+fn check_token_use(
+    verified: VerifiedToken,
+    allow_untyped: bool,
+) -> Result<VerifiedToken, VerifyError> {
+    match verified.token_use.as_deref() {
+        Some(jwt::TOKEN_USE_ACCESS) => Ok(verified),
+        None if allow_untyped => Ok(verified),
+        _ => Err(VerifyError::WrongTokenType),
+    }
+}
+
+/// 許可リスト（CLAIM_ALLOWLIST）に含まれるクレームを取り出す
+///
+/// 値は文字列に変換する。ヘッダーとして転送できない配列・オブジェクト・null は含めない。
+///
+/// # 引数
+/// * `claims` - 検証済みのクレーム
+///
+/// # 戻り値
+/// * `Vec<(String, String)>` - (クレーム名, 値) の一覧（CLAIM_ALLOWLIST の順）
+fn extract_claims(claims: &Claims) -> Vec<(String, String)> {
+    CLAIM_ALLOWLIST
+        .iter()
+        .filter_map(|&name| Some((name.to_string(), claims.claim_str(name)?)))
+        .collect()
+}
+
+// =============================================================================
+// メトリクス
+// =============================================================================
+
+/// カウンタの保存先
+///
+/// テストではメモリ上の実装に差し替え、key-value ストアなしで集計を検証します。
+trait Counter {
+    /// キーの値を delta 増やし、増やした後の値を返す（キーがなければ 0 から数える）
+    fn increment(&self, key: &str, delta: u64) -> Result<u64, String>;
+}
+
+/// Spin の key-value ストアを使うカウンタ
+struct KvCounter {
+    bucket: store::Bucket,
+}
+
+impl KvCounter {
+    /// key-value ストアを開く
+    fn open() -> Result<Self, String> {
+        store::open(STORE_NAME)
+            .map(|bucket| Self { bucket })
+            .map_err(|e| format!("Failed to open key-value store: {:?}", e))
+    }
+}
+
+impl Counter for KvCounter {
+    fn increment(&self, key: &str, delta: u64) -> Result<u64, String> {
+        atomics::increment(&self.bucket, key, delta as i64)
+            .map(|count| count.max(0) as u64)
+            .map_err(|e| format!("Failed to increment counter: {:?}", e))
+    }
+}
+
+/// 検証結果を数える
+///
+/// 呼び出し回数（auth_verifications_total）と、成功または失敗の理由のカウンタを 1 増やす。
+/// それぞれのカウンタはアトミックにインクリメントするため、同時に実行されても失われない。
+///
+/// # 引数
+/// * `counter` - カウンタの保存先
+/// * `result` - verify_jwt の結果
+fn record_verification(
+    counter: &impl Counter,
+    result: &Result<VerifiedToken, VerifyError>,
+) -> Result<(), String> {
+    let outcome = match result {
+        Ok(_) => VERIFICATIONS_SUCCESS,
+        Err(e) => e.metric(),
+    };
+
+    counter.increment(&metric_key(VERIFICATIONS_TOTAL), 1)?;
+    counter.increment(&metric_key(outcome), 1)?;
+    Ok(())
+}
+
+/// 全てのカウンタの現在の値を読む
+///
+/// 0 を加算して現在の値を取得する（まだ数えていないカウンタは 0）。
+/// 値の読み出しもアトミック操作で行うため、保存形式に依存しない。
+///
+/// # 戻り値
+/// * `Ok(Vec<(String, u64)>)` - (カウンタ名, 値) の一覧（METRIC_NAMES の順）
+/// * `Err(String)` - カウンタの読み出しに失敗した場合のエラーメッセージ
+fn read_metrics(counter: &impl Counter) -> Result<Vec<(String, u64)>, String> {
+    METRIC_NAMES
+        .iter()
+        .map(|&name| Ok((name.to_string(), counter.increment(&metric_key(name), 0)?)))
+        .collect()
+}
+
+/// カウンタのキーを組み立てる
+///
+/// 例: `auth:metrics:auth_failures_expired`
+fn metric_key(name: &str) -> String {
+    format!("{}:{}", METRICS_KEY_PREFIX, name)
+}
+
+// =============================================================================
+// コンポーネントのエクスポート
+// =============================================================================
 
 // AuthComponent を Wasm コンポーネントとしてエクスポート
 // これにより、WIT で定義したインターフェースが外部から呼び出し可能になる
 export!(AuthComponent);
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    /// メモリ上のカウンタ（key-value ストアの代わり）
+    #[derive(Default)]
+    struct MemoryCounter {
+        values: Mutex<HashMap<String, u64>>,
+    }
+
+    impl Counter for MemoryCounter {
+        fn increment(&self, key: &str, delta: u64) -> Result<u64, String> {
+            let mut values = self.values.lock().unwrap();
+            let value = values.entry(key.to_string()).or_default();
+            *value += delta;
+            Ok(*value)
+        }
+    }
+
+    /// 検証に使う現在時刻（Unix タイムスタンプ秒）
+    const NOW: u64 = 1_700_000_000;
+
+    /// テスト用の HS256 の共有シークレット（spin.toml の jwt_secret の既定値）
+    const SECRET_KEY: &[u8] = b"super-secret-key";
+
+    /// jwt_secret に SECRET_KEY を指定した場合の鍵リング
+    fn default_keyring() -> HmacKeyring {
+        HmacKeyring::from_secret(SECRET_KEY)
+    }
+
+    /// jwt_secret に SECRET_KEY を指定し、公開鍵を指定していない場合の鍵
+    fn default_keys() -> VerificationKeys {
+        VerificationKeys {
+            hs256: Some(default_keyring()),
+            ..Default::default()
+        }
+    }
+
+    /// SECRET_KEY で署名した HS256 トークンを作成する（任意のペイロード）
+    fn token(payload: &str) -> String {
+        let message = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#),
+            URL_SAFE_NO_PAD.encode(payload)
+        );
+        let mut mac = Hmac::<Sha256>::new_from_slice(SECRET_KEY).unwrap();
+        mac.update(message.as_bytes());
+        let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+        format!("{}.{}", message, signature)
+    }
+
+    /// テスト用の RS256 鍵ペア（jwt クレートのテストと共有、本番では使用しない）
+    const RS256_PRIVATE_KEY: &str =
+        include_str!("../../../core/crates/jwt/testdata/rs256/private.pem");
+    const RS256_PUBLIC_KEY: &str =
+        include_str!("../../../core/crates/jwt/testdata/rs256/public.pem");
+
+    /// テスト用の秘密鍵で署名した RS256 トークンを作成する（外部の ID プロバイダーの代わり）
+    fn rs256_token(payload: &str) -> String {
+        rs256_token_with_header(r#"{"alg":"RS256","typ":"JWT"}"#, payload)
+    }
+
+    /// 任意のヘッダー（kid など）の RS256 トークンを作成する
+    fn rs256_token_with_header(header: &str, payload: &str) -> String {
+        use rsa::pkcs8::DecodePrivateKey;
+        use sha2::Digest;
+
+        let message = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header),
+            URL_SAFE_NO_PAD.encode(payload)
+        );
+        let signature = rsa::RsaPrivateKey::from_pkcs8_pem(RS256_PRIVATE_KEY)
+            .unwrap()
+            .sign(
+                rsa::Pkcs1v15Sign::new::<Sha256>(),
+                &Sha256::digest(message.as_bytes()),
+            )
+            .unwrap();
+        format!("{}.{}", message, URL_SAFE_NO_PAD.encode(signature))
+    }
+
+    /// テスト用の ES256 鍵ペア（jwt クレートのテストと共有、本番では使用しない）
+    const ES256_PRIVATE_KEY: &str =
+        include_str!("../../../core/crates/jwt/testdata/es256/private.pem");
+    const ES256_PUBLIC_KEY: &str =
+        include_str!("../../../core/crates/jwt/testdata/es256/public.pem");
+    const ES256_PUBLIC_JWK: &str =
+        include_str!("../../../core/crates/jwt/testdata/es256/public.jwk");
+
+    /// テスト用の秘密鍵で署名した ES256 トークンを作成する（パートナーの代わり）
+    ///
+    /// `der` が true の場合は JOSE の形式（r || s）ではなく DER の署名を付ける（よくある実装の誤り）。
+    fn es256_token(payload: &str, der: bool) -> String {
+        use p256::ecdsa::signature::Signer;
+        use p256::pkcs8::DecodePrivateKey;
+
+        let message = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"ES256","typ":"JWT"}"#),
+            URL_SAFE_NO_PAD.encode(payload)
+        );
+        let signature: p256::ecdsa::Signature =
+            p256::ecdsa::SigningKey::from_pkcs8_pem(ES256_PRIVATE_KEY)
+                .unwrap()
+                .sign(message.as_bytes());
+        let signature = if der {
+            signature.to_der().as_bytes().to_vec()
+        } else {
+            signature.to_bytes().to_vec()
+        };
+        format!("{}.{}", message, URL_SAFE_NO_PAD.encode(signature))
+    }
+
+    /// テスト用の JWKS（testdata の RS256 / ES256 の公開鍵、kid は rs256-test / es256-test）
+    const JWKS: &str = include_str!("../../../core/crates/jwt/testdata/jwks/jwks.json");
+
+    /// トークンのヘッダーとペイロードをデコードする
+    fn decode(token: &str) -> (Value, Value) {
+        let parts: Vec<&str> = token.split('.').collect();
+        let decode =
+            |part: &str| serde_json::from_slice(&URL_SAFE_NO_PAD.decode(part).unwrap()).unwrap();
+        (decode(parts[0]), decode(parts[1]))
+    }
+
+    #[test]
+    fn test_returns_expiry_roles_and_allowlisted_claims() {
+        let token = token(
+            r#"{"sub":"user-1","exp":4102444800,"iat":1700000000,
+                "roles":["admin",1,"editor"],"tenant_id":"tenant-a","email":"a@example.com",
+                "scope":"todos:read todos:write"}"#,
+        );
+
+        let verified = verify_jwt(&token, &default_keys(), &Validation::new(NOW)).unwrap();
+        assert_eq!(verified.user_id, "user-1");
+        assert_eq!(verified.expires_at, Some(4102444800));
+        assert_eq!(verified.issued_at, Some(1700000000));
+        // 文字列以外の要素は無視する
+        assert_eq!(verified.roles, vec!["admin", "editor"]);
+        // 許可リストにない email は含めない
+        assert_eq!(
+            verified.claims,
+            vec![
+                ("tenant_id".to_string(), "tenant-a".to_string()),
+                ("scope".to_string(), "todos:read todos:write".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_or_unexpected_claims_do_not_fail_verification() {
+        // roles が配列でない・tenant_id が数値でも、0.1 と同じく検証は成功する
+        let token = token(r#"{"sub":"user-1","exp":4102444800,"roles":"admin","tenant_id":42}"#);
+
+        let verified = verify_jwt(&token, &default_keys(), &Validation::new(NOW)).unwrap();
+        assert_eq!(verified.expires_at, Some(4102444800));
+        assert_eq!(verified.issued_at, None);
+        assert!(verified.roles.is_empty());
+        assert_eq!(
+            verified.claims,
+            vec![("tenant_id".to_string(), "42".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_rejects_expired_and_unsupported_tokens() {
+        // コア層と同じく exp は必須、leeway（60 秒）を超えて期限切れなら拒否する
+        let no_exp = token(r#"{"sub":"user-1"}"#);
+        assert_eq!(
+            verify_jwt(&no_exp, &default_keys(), &Validation::new(NOW))
+                .unwrap_err()
+                .to_string(),
+            "Missing expiration claim"
+        );
+        let expired = token(&format!(r#"{{"sub":"user-1","exp":{}}}"#, NOW - 61));
+        assert_eq!(
+            verify_jwt(&expired, &default_keys(), &Validation::new(NOW))
+                .unwrap_err()
+                .to_string(),
+            "Token expired"
+        );
+        assert!(verify_jwt(&expired, &default_keys(), &Validation::new(NOW - 1)).is_ok());
+
+        let unsigned = format!(
+            "{}.{}.",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"none"}"#),
+            URL_SAFE_NO_PAD.encode(r#"{"sub":"user-1","exp":4102444800}"#)
+        );
+        assert_eq!(
+            verify_jwt(&unsigned, &default_keys(), &Validation::new(NOW))
+                .unwrap_err()
+                .to_string(),
+            "Unsupported algorithm: none"
+        );
+        assert_eq!(
+            verify_jwt("", &default_keys(), &Validation::new(NOW))
+                .unwrap_err()
+                .to_string(),
+            "Missing token"
+        );
+    }
+
+    #[test]
+    fn test_expiry_boundary_with_leeway() {
+        // (exp の現在時刻からの差, leeway, 有効か)
+        let cases: [(i64, u64, bool); 10] = [
+            // 昨日期限切れになったトークンは既定の leeway でも拒否する
+            (-86_400, DEFAULT_LEEWAY_SECS, false),
+            // leeway 0: exp ちょうどまで有効、1 秒過ぎたら拒否
+            (1, 0, true),
+            (0, 0, true),
+            (-1, 0, false),
+            // 既定の leeway（30 秒）: exp + 30 秒まで有効
+            (-30, DEFAULT_LEEWAY_SECS, true),
+            (-31, DEFAULT_LEEWAY_SECS, false),
+            // 変更した leeway
+            (-299, MAX_LEEWAY_SECS, true),
+            (-300, MAX_LEEWAY_SECS, true),
+            (-301, MAX_LEEWAY_SECS, false),
+            (-5, 10, true),
+        ];
+
+        for (offset, leeway, valid) in cases {
+            let exp = NOW.checked_add_signed(offset).unwrap();
+            let token = token(&format!(r#"{{"sub":"user-1","exp":{}}}"#, exp));
+            let result = verify_jwt(
+                &token,
+                &default_keys(),
+                &Validation::new(NOW).with_leeway(leeway),
+            );
+            if valid {
+                assert_eq!(
+                    result.unwrap().expires_at,
+                    Some(exp),
+                    "{} {}",
+                    offset,
+                    leeway
+                );
+            } else {
+                assert_eq!(
+                    result.unwrap_err(),
+                    VerifyError::Jwt(jwt::Error::Expired),
+                    "{} {}",
+                    offset,
+                    leeway
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_token_without_expiration_regardless_of_leeway() {
+        let no_exp = token(r#"{"sub":"user-1","iat":1700000000}"#);
+        for leeway in [0, DEFAULT_LEEWAY_SECS, MAX_LEEWAY_SECS] {
+            assert_eq!(
+                verify_jwt(
+                    &no_exp,
+                    &default_keys(),
+                    &Validation::new(NOW).with_leeway(leeway)
+                )
+                .unwrap_err(),
+                VerifyError::Jwt(jwt::Error::MissingExpiration)
+            );
+        }
+    }
+
+    #[test]
+    fn test_not_before_boundary_with_leeway() {
+        // (nbf の現在時刻からの差, leeway, 有効か)
+        let cases: [(u64, u64, bool); 7] = [
+            // 現在時刻ちょうどは有効
+            (0, 0, true),
+            (0, DEFAULT_LEEWAY_SECS, true),
+            // 少し先: leeway の範囲内なら有効、超えたら拒否
+            (1, 0, false),
+            (30, DEFAULT_LEEWAY_SECS, true),
+            (31, DEFAULT_LEEWAY_SECS, false),
+            // ずっと先（翌日のジョブ用）は leeway の上限でも拒否
+            (86_400, DEFAULT_LEEWAY_SECS, false),
+            (86_400, MAX_LEEWAY_SECS, false),
+        ];
+
+        for (offset, leeway, valid) in cases {
+            let token = token(&format!(
+                r#"{{"sub":"svc-cron","exp":{},"nbf":{}}}"#,
+                NOW + 172_800,
+                NOW + offset
+            ));
+            let result = verify_jwt(
+                &token,
+                &default_keys(),
+                &Validation::new(NOW).with_leeway(leeway),
+            );
+            if valid {
+                assert_eq!(result.unwrap().user_id, "svc-cron", "{} {}", offset, leeway);
+            } else {
+                let error = result.unwrap_err();
+                assert_eq!(
+                    error.to_string(),
+                    "Token not yet valid",
+                    "{} {}",
+                    offset,
+                    leeway
+                );
+                assert_eq!(error.metric(), FAILURES_OTHER);
+            }
+        }
+
+        // nbf がないトークンは許可する、nbf が数値でない場合は不正な形式
+        let no_nbf = token(r#"{"sub":"svc-cron","exp":4102444800}"#);
+        assert!(verify_jwt(
+            &no_nbf,
+            &default_keys(),
+            &Validation::new(NOW).with_leeway(0)
+        )
+        .is_ok());
+        let mistyped = token(r#"{"sub":"svc-cron","exp":4102444800,"nbf":"tomorrow"}"#);
+        assert_eq!(
+            verify_jwt(
+                &mistyped,
+                &default_keys(),
+                &Validation::new(NOW).with_leeway(0)
+            )
+            .unwrap_err(),
+            VerifyError::Jwt(jwt::Error::InvalidPayloadJson)
+        );
+    }
+
+    #[test]
+    fn test_issued_at_boundary_with_leeway() {
+        // (iat の現在時刻からの差, leeway, 有効か)
+        let cases: [(u64, u64, bool); 6] = [
+            (0, 0, true),
+            (1, 0, false),
+            (30, DEFAULT_LEEWAY_SECS, true),
+            (31, DEFAULT_LEEWAY_SECS, false),
+            (300, MAX_LEEWAY_SECS, true),
+            (86_400, MAX_LEEWAY_SECS, false),
+        ];
+
+        for (offset, leeway, valid) in cases {
+            let token = token(&format!(
+                r#"{{"sub":"user-1","exp":{},"iat":{}}}"#,
+                NOW + 172_800,
+                NOW + offset
+            ));
+            let result = verify_jwt(
+                &token,
+                &default_keys(),
+                &Validation::new(NOW).with_leeway(leeway),
+            );
+            if valid {
+                assert!(result.is_ok(), "{} {}", offset, leeway);
+            } else {
+                let error = result.unwrap_err();
+                assert_eq!(
+                    error.to_string(),
+                    "Token issued in the future",
+                    "{} {}",
+                    offset,
+                    leeway
+                );
+                assert_eq!(error.metric(), FAILURES_OTHER);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_leeway() {
+        assert_eq!(parse_leeway(None), Ok(DEFAULT_LEEWAY_SECS));
+        assert_eq!(parse_leeway(Some(" ")), Ok(DEFAULT_LEEWAY_SECS));
+        assert_eq!(parse_leeway(Some("0")), Ok(0));
+        assert_eq!(parse_leeway(Some(" 30 ")), Ok(30));
+        assert_eq!(parse_leeway(Some("300")), Ok(MAX_LEEWAY_SECS));
+
+        for value in ["301", "-1", "60s", "1.5", "86400"] {
+            let error = parse_leeway(Some(value)).unwrap_err();
+            assert!(error.starts_with("Invalid jwt_leeway_secs"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_parse_max_token_bytes() {
+        assert_eq!(
+            parse_max_token_bytes(None),
+            Ok(jwt::DEFAULT_MAX_TOKEN_BYTES)
+        );
+        assert_eq!(
+            parse_max_token_bytes(Some(" ")),
+            Ok(jwt::DEFAULT_MAX_TOKEN_BYTES)
+        );
+        assert_eq!(parse_max_token_bytes(Some(" 16384 ")), Ok(16384));
+        assert_eq!(parse_max_token_bytes(Some("1024")), Ok(1024));
+
+        for value in ["0", "1023", "65537", "8k", "-1"] {
+            let error = parse_max_token_bytes(Some(value)).unwrap_err();
+            assert!(
+                error.starts_with("Invalid jwt_max_token_bytes"),
+                "{}",
+                error
+            );
+        }
+    }
+
+    /// 長さの上限を超えるトークンは鍵を選ぶ前・デコードする前に拒否する
+    #[test]
+    fn test_rejects_oversized_tokens() {
+        let large = token(&format!(
+            r#"{{"sub":"user-1","exp":4102444800,"pad":"{}"}}"#,
+            "x".repeat(jwt::DEFAULT_MAX_TOKEN_BYTES)
+        ));
+        // HS256 の鍵がない場合も Auth misconfigured ではなく Token too large
+        for keys in [default_keys(), VerificationKeys::default()] {
+            let error = verify_jwt(&large, &keys, &Validation::new(NOW)).unwrap_err();
+            assert_eq!(error.to_string(), "Token too large");
+            assert_eq!(error.metric(), FAILURES_MALFORMED);
+            assert_eq!(error.code(), AuthErrorCode::Malformed);
+        }
+        let relaxed = Validation::new(NOW).with_max_token_bytes(16 * 1024);
+        assert!(verify_jwt(&large, &default_keys(), &relaxed).is_ok());
+
+        // 署名の部分だけが巨大なトークン
+        let mut absurd = token(r#"{"sub":"user-1","exp":4102444800}"#);
+        absurd.push_str(&"A".repeat(1024 * 1024));
+        let error = verify_jwt(&absurd, &default_keys(), &relaxed).unwrap_err();
+        assert_eq!(error.to_string(), "Token too large");
+    }
+
+    #[test]
+    fn test_failure_result_carries_no_claims() {
+        let token = token(r#"{"exp":4102444800,"roles":["admin"],"tenant_id":"tenant-a"}"#);
+
+        let result = auth_result(verify_jwt(&token, &default_keys(), &Validation::new(NOW)));
+        assert!(!result.authenticated);
+        assert_eq!(result.user_id, None);
+        assert_eq!(result.error.as_deref(), Some("Missing subject claim"));
+        assert_eq!(result.expires_at, None);
+        assert_eq!(result.issued_at, None);
+        assert_eq!(result.error_code, Some(AuthErrorCode::MissingSubject));
+        assert!(result.roles.is_empty());
+        assert!(result.claims.is_empty());
+    }
+
+    #[test]
+    fn test_failure_metric_categories() {
+        let expired = token(&format!(r#"{{"sub":"user-1","exp":{}}}"#, NOW - 61));
+        let mut tampered = token(r#"{"sub":"user-1","exp":4102444800}"#);
+        tampered.push('A');
+        let forged = format!(
+            "{}.{}",
+            token(r#"{"sub":"user-1","exp":4102444800}"#)
+                .rsplit_once('.')
+                .unwrap()
+                .0,
+            URL_SAFE_NO_PAD.encode([0u8; 32])
+        );
+        let unsigned = format!(
+            "{}.{}.",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"none"}"#),
+            URL_SAFE_NO_PAD.encode(r#"{"sub":"user-1","exp":4102444800}"#)
+        );
+
+        for (token, metric) in [
+            ("", FAILURES_MISSING_TOKEN),
+            (expired.as_str(), FAILURES_EXPIRED),
+            (forged.as_str(), FAILURES_BAD_SIGNATURE),
+            (unsigned.as_str(), FAILURES_UNSUPPORTED_ALG),
+            ("not-a-jwt", FAILURES_MALFORMED),
+            ("a.b.c", FAILURES_MALFORMED),
+            (tampered.as_str(), FAILURES_BAD_SIGNATURE),
+        ] {
+            let error = verify_jwt(token, &default_keys(), &Validation::new(NOW)).unwrap_err();
+            assert_eq!(error.metric(), metric, "{:?}: {}", token, error);
+        }
+
+        // 署名は正しいが sub がない → 形式の不正として数える
+        let no_sub = token(r#"{"exp":4102444800}"#);
+        assert_eq!(
+            verify_jwt(&no_sub, &default_keys(), &Validation::new(NOW))
+                .unwrap_err()
+                .metric(),
+            FAILURES_MALFORMED
+        );
+        assert_eq!(
+            VerifyError::Jwt(jwt::Error::NotYetValid).metric(),
+            FAILURES_OTHER
+        );
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let expired = token(&format!(r#"{{"sub":"user-1","exp":{}}}"#, NOW - 61));
+        let future = token(&format!(
+            r#"{{"sub":"user-1","exp":4102444800,"nbf":{}}}"#,
+            NOW + 3600
+        ));
+        let no_sub = token(r#"{"exp":4102444800}"#);
+        let no_exp = token(r#"{"sub":"user-1"}"#);
+        let mut tampered = token(r#"{"sub":"user-1","exp":4102444800}"#);
+        tampered.push('A');
+        let unsigned = format!(
+            "{}.{}.",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"none"}"#),
+            URL_SAFE_NO_PAD.encode(r#"{"sub":"user-1","exp":4102444800}"#)
+        );
+        // RFC 7516 付録 A.3 の JWE（A128KW / A128CBC-HS256）
+        let encrypted = concat!(
+            "eyJhbGciOiJBMTI4S1ciLCJlbmMiOiJBMTI4Q0JDLUhTMjU2In0.",
+            "6KB707dM9YTIgHtLvtgWQ8mKwboJW3of9locizkDTHzBC2IlrT1oOQ.",
+            "AxY8DCtDaGlsbGljb3RoZQ.",
+            "KDlTtXchhZTGufMYmOYGS4HffxPSUrfmqCHXaI9wOGY.",
+            "U0m_YmjN04DJvceFICbCVQ"
+        );
+
+        for (token, code) in [
+            ("", AuthErrorCode::MissingToken),
+            ("not-a-jwt", AuthErrorCode::Malformed),
+            ("a.b.c", AuthErrorCode::Malformed),
+            (encrypted, AuthErrorCode::Encrypted),
+            (unsigned.as_str(), AuthErrorCode::UnsupportedAlgorithm),
+            (tampered.as_str(), AuthErrorCode::InvalidSignature),
+            (expired.as_str(), AuthErrorCode::Expired),
+            (future.as_str(), AuthErrorCode::NotYetValid),
+            (no_sub.as_str(), AuthErrorCode::MissingSubject),
+            (no_exp.as_str(), AuthErrorCode::InvalidClaims),
+        ] {
+            let result = auth_result(verify_jwt(token, &default_keys(), &Validation::new(NOW)));
+            assert!(!result.authenticated);
+            assert_eq!(
+                result.error_code,
+                Some(code),
+                "{:?}: {:?}",
+                token,
+                result.error
+            );
+        }
+
+        let result = auth_result(verify_jwt(
+            encrypted,
+            &default_keys(),
+            &Validation::new(NOW),
+        ));
+        assert_eq!(
+            result.error.as_deref(),
+            Some("Encrypted tokens (JWE) are not supported")
+        );
+
+        // kid の鍵がない・検証に使う鍵を用意できない場合
+        assert_eq!(
+            VerifyError::UnknownKeyId.code(),
+            AuthErrorCode::InvalidSignature
+        );
+        assert_eq!(
+            VerifyError::KeysUnavailable.code(),
+            AuthErrorCode::Unavailable
+        );
+        assert_eq!(
+            VerifyError::Misconfigured.code(),
+            AuthErrorCode::Unavailable
+        );
+        assert_eq!(
+            VerifyError::Jwt(jwt::Error::IssuedInFuture).code(),
+            AuthErrorCode::NotYetValid
+        );
+        assert_eq!(
+            VerifyError::Jwt(jwt::Error::InvalidAudience).code(),
+            AuthErrorCode::InvalidClaims
+        );
+
+        // 成功時は None
+        let valid = token(r#"{"sub":"user-1","exp":4102444800}"#);
+        let result = auth_result(verify_jwt(&valid, &default_keys(), &Validation::new(NOW)));
+        assert!(result.authenticated);
+        assert_eq!(result.error_code, None);
+    }
+
+    #[test]
+    fn test_check_revocation() {
+        let token = token(r#"{"sub":"user-1","exp":4102444800,"iat":1700000000,"jti":"t-1"}"#);
+        let verified = || verify_jwt(&token, &default_keys(), &Validation::new(NOW)).unwrap();
+        assert_eq!(verified().jti.as_deref(), Some("t-1"));
+
+        assert_eq!(
+            check_revocation(verified(), Ok(false), false),
+            Ok(verified())
+        );
+
+        let revoked = auth_result(check_revocation(verified(), Ok(true), true));
+        assert!(!revoked.authenticated);
+        assert_eq!(revoked.error.as_deref(), Some("Token revoked"));
+        assert_eq!(revoked.error_code, Some(AuthErrorCode::Revoked));
+        assert_eq!(revoked.user_id, None);
+
+        // 失効リストを読めない場合は既定で拒否し、fail-open の場合のみ通す
+        let down = || Err("Failed to open key-value store".to_string());
+        let closed = auth_result(check_revocation(verified(), down(), false));
+        assert_eq!(
+            closed.error.as_deref(),
+            Some("Revocation check unavailable")
+        );
+        assert_eq!(closed.error_code, Some(AuthErrorCode::Unavailable));
+        assert_eq!(check_revocation(verified(), down(), true), Ok(verified()));
+    }
+
+    /// メモリ上の使用済みの jti の保存先（版で compare-and-swap を再現する）
+    #[derive(Default)]
+    struct MemoryCasStore {
+        values: Mutex<HashMap<String, (Vec<u8>, u64)>>,
+    }
+
+    impl one_time::CasStore for &MemoryCasStore {
+        type Handle = (String, u64);
+
+        fn current(&self, key: &str) -> Result<(Option<Vec<u8>>, Self::Handle), String> {
+            let values = self.values.lock().unwrap();
+            let (value, version) = match values.get(key) {
+                Some((value, version)) => (Some(value.clone()), *version),
+                None => (None, 0),
+            };
+            Ok((value, (key.to_string(), version)))
+        }
+
+        fn swap(&self, (key, version): Self::Handle, value: &[u8]) -> Result<bool, String> {
+            let mut values = self.values.lock().unwrap();
+            if values.get(&key).map_or(0, |(_, version)| *version) != version {
+                return Ok(false);
+            }
+            values.insert(key, (value.to_vec(), version + 1));
+            Ok(true)
+        }
+    }
+
+    /// 使い捨てのトークンは 2 回目の提示を拒否し、それ以外のトークンはストアを開かない
+    #[test]
+    fn test_check_one_time() {
+        let store = MemoryCasStore::default();
+        let open = || Ok::<_, String>(&store);
+        let verified = |payload: &str| {
+            verify_jwt(&token(payload), &default_keys(), &Validation::new(NOW)).unwrap()
+        };
+
+        let reset = r#"{"sub":"user-1","exp":1700000900,"jti":"reset-1","one_time":true}"#;
+        assert!(verified(reset).one_time);
+        // gateway が検証結果をキャッシュしないよう auth-result で伝える
+        assert!(auth_result(Ok(verified(reset))).one_time);
+
+        // 1 回目は受け付け、同じトークンの 2 回目は拒否する
+        assert_eq!(
+            check_one_time(verified(reset), open, NOW, 60),
+            Ok(verified(reset))
+        );
+        let reused = auth_result(check_one_time(verified(reset), open, NOW + 10, 60));
+        assert!(!reused.authenticated);
+        assert_eq!(reused.error.as_deref(), Some("Token already used"));
+        assert_eq!(reused.error_code, Some(AuthErrorCode::Revoked));
+        assert_eq!(reused.user_id, None);
+        assert!(!reused.one_time);
+
+        // exp + leeway まで使用済みとして記録する
+        let record = store.values.lock().unwrap()["auth:one-time:jti:reset-1"].clone();
+        assert_eq!(record.0, b"1700000960");
+
+        // one_time が true（真偽値）でないトークンはストアを開かない
+        let never = || -> Result<&MemoryCasStore, String> { panic!("store opened") };
+        for payload in [
+            r#"{"sub":"user-1","exp":1700000900,"jti":"reset-1"}"#,
+            r#"{"sub":"user-1","exp":1700000900,"jti":"reset-1","one_time":false}"#,
+            r#"{"sub":"user-1","exp":1700000900,"jti":"reset-1","one_time":"true"}"#,
+        ] {
+            assert!(!verified(payload).one_time, "{}", payload);
+            assert_eq!(
+                check_one_time(verified(payload), never, NOW, 60),
+                Ok(verified(payload))
+            );
+        }
+
+        // jti がない場合は使用済みか判定できないため拒否する
+        let without_jti = r#"{"sub":"user-1","exp":1700000900,"one_time":true}"#;
+        let rejected = auth_result(check_one_time(verified(without_jti), never, NOW, 60));
+        assert_eq!(
+            rejected.error.as_deref(),
+            Some("One-time token without jti")
+        );
+        assert_eq!(rejected.error_code, Some(AuthErrorCode::InvalidClaims));
+
+        // ストアを開けない場合は常に拒否する
+        let down = || -> Result<&MemoryCasStore, String> {
+            Err("Failed to open key-value store".to_string())
+        };
+        let closed = auth_result(check_one_time(verified(reset), down, NOW, 60));
+        assert_eq!(
+            closed.error.as_deref(),
+            Some("One-time token check unavailable")
+        );
+        assert_eq!(closed.error_code, Some(AuthErrorCode::Unavailable));
+    }
+
+    /// ヘッダーの typ を jwt_typ_policy の 3 つの段階で検証する
+    #[test]
+    fn test_typ_policy() {
+        let payload = r#"{"sub":"user-1","exp":4102444800}"#;
+        let with_typ = |typ: Option<&str>| {
+            let header = match typ {
+                Some(typ) => format!(r#"{{"alg":"HS256","typ":"{}"}}"#, typ),
+                None => r#"{"alg":"HS256"}"#.to_string(),
+            };
+            let message = format!(
+                "{}.{}",
+                URL_SAFE_NO_PAD.encode(header),
+                URL_SAFE_NO_PAD.encode(payload)
+            );
+            let mut mac = Hmac::<Sha256>::new_from_slice(SECRET_KEY).unwrap();
+            mac.update(message.as_bytes());
+            let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+            format!("{}.{}", message, signature)
+        };
+
+        // (typ, ignore, validate-if-present, require)
+        let cases = [
+            (Some("JWT"), true, true, true),
+            (Some("jwt"), true, true, true),
+            (Some("at+jwt"), true, false, false),
+            (None, true, true, false),
+        ];
+        for (typ, ignore, validate, require) in cases {
+            let token = with_typ(typ);
+            for (policy, expected) in [
+                (TypPolicy::Ignore, ignore),
+                (TypPolicy::ValidateIfPresent, validate),
+                (TypPolicy::Require, require),
+            ] {
+                let keys = VerificationKeys {
+                    typ_policy: policy,
+                    ..default_keys()
+                };
+                let result = auth_result(verify_jwt(&token, &keys, &Validation::new(NOW)));
+                assert_eq!(result.authenticated, expected, "{:?} {:?}", typ, policy);
+                if !expected {
+                    assert_eq!(result.error.as_deref(), Some("Invalid typ header"));
+                    assert_eq!(result.error_code, Some(AuthErrorCode::InvalidClaims));
+                }
+            }
+        }
+
+        // 署名が不正なトークンも、鍵を選ぶ前に typ で拒否する
+        let forged = format!("{}x", with_typ(Some("at+jwt")));
+        assert_eq!(
+            verify_jwt(&forged, &default_keys(), &Validation::new(NOW)),
+            Err(VerifyError::InvalidTyp)
+        );
+    }
+
+    #[test]
+    fn test_parse_typ_policy() {
+        assert_eq!(parse_typ_policy(None), Ok(TypPolicy::ValidateIfPresent));
+        assert_eq!(
+            parse_typ_policy(Some(" ")),
+            Ok(TypPolicy::ValidateIfPresent)
+        );
+        assert_eq!(parse_typ_policy(Some("ignore")), Ok(TypPolicy::Ignore));
+        assert_eq!(
+            parse_typ_policy(Some("Validate-If-Present")),
+            Ok(TypPolicy::ValidateIfPresent)
+        );
+        assert_eq!(parse_typ_policy(Some(" REQUIRE ")), Ok(TypPolicy::Require));
+        let error = parse_typ_policy(Some("strict")).unwrap_err();
+        assert!(error.starts_with("Invalid jwt_typ_policy"), "{}", error);
+    }
+
+    /// アクセストークン以外（リフレッシュトークン）は "Wrong token type" で拒否する
+    #[test]
+    fn test_check_token_use() {
+        let verified = |token_use: &str| {
+            let payload = format!(r#"{{"sub":"user-1","exp":4102444800{}}}"#, token_use);
+            verify_jwt(&token(&payload), &default_keys(), &Validation::new(NOW)).unwrap()
+        };
+
+        // 発行したトークンは access
+        let issued = issue_jwt("user-1", 60, &[], &default_keyring(), NOW).unwrap();
+        let issued = || verify_jwt(&issued, &default_keys(), &Validation::new(NOW)).unwrap();
+        assert_eq!(issued().token_use.as_deref(), Some("access"));
+        assert_eq!(check_token_use(issued(), false), Ok(issued()));
+
+        let access = r#","token_use":"access""#;
+        assert_eq!(
+            check_token_use(verified(access), false),
+            Ok(verified(access))
+        );
+
+        for token_use in [
+            r#","token_use":"refresh""#,
+            r#","token_use":"ACCESS""#,
+            r#","token_use":"""#,
+            r#","token_use":true"#,
+        ] {
+            for allow_untyped in [false, true] {
+                let rejected = auth_result(check_token_use(verified(token_use), allow_untyped));
+                assert!(!rejected.authenticated, "{}", token_use);
+                assert_eq!(rejected.error.as_deref(), Some("Wrong token type"));
+                assert_eq!(rejected.error_code, Some(AuthErrorCode::InvalidClaims));
+            }
+        }
+
+        // token_use のないトークンは jwt_allow_untyped_tokens=on の場合のみ受け付ける
+        assert_eq!(
+            check_token_use(verified(""), false),
+            Err(VerifyError::WrongTokenType)
+        );
+        assert_eq!(check_token_use(verified(""), true), Ok(verified("")));
+    }
+
+    /// ver がユーザーのバージョンより小さいトークンは "Token superseded" で拒否する
+    #[test]
+    fn test_check_token_version() {
+        let verified = |payload: &str| {
+            verify_jwt(&token(payload), &default_keys(), &Validation::new(NOW)).unwrap()
+        };
+
+        // ver は 0 以上の整数のみ、それ以外・ない場合は 0
+        for (payload, version) in [
+            (r#"{"sub":"user-1","exp":4102444800,"ver":3}"#, 3),
+            (r#"{"sub":"user-1","exp":4102444800}"#, 0),
+            (r#"{"sub":"user-1","exp":4102444800,"ver":"3"}"#, 0),
+            (r#"{"sub":"user-1","exp":4102444800,"ver":-1}"#, 0),
+            (r#"{"sub":"user-1","exp":4102444800,"ver":2.5}"#, 0),
+        ] {
+            assert_eq!(verified(payload).token_version, version, "{}", payload);
+        }
+
+        let current = r#"{"sub":"user-1","exp":4102444800,"ver":3}"#;
+        assert_eq!(
+            check_token_version(verified(current), Ok(false), false),
+            Ok(verified(current))
+        );
+
+        let superseded = auth_result(check_token_version(verified(current), Ok(true), true));
+        assert!(!superseded.authenticated);
+        assert_eq!(superseded.error.as_deref(), Some("Token superseded"));
+        assert_eq!(superseded.error_code, Some(AuthErrorCode::Revoked));
+        assert_eq!(superseded.user_id, None);
+
+        // ストアを読めない場合は失効リストと同じく既定で拒否し、fail-open の場合のみ通す
+        let down = || Err("Failed to open key-value store".to_string());
+        let closed = auth_result(check_token_version(verified(current), down(), false));
+        assert_eq!(
+            closed.error.as_deref(),
+            Some("Revocation check unavailable")
+        );
+        assert_eq!(
+            check_token_version(verified(current), down(), true),
+            Ok(verified(current))
+        );
+    }
+
+    /// コア層の UserContext・jwt クレートと共通の sub のテストケース
+    const SUBJECTS: &str = include_str!("../../../core/crates/jwt/testdata/subjects/subjects.json");
+
+    /// sub が UUID でないトークンは、署名が正しくても X-User-Id に設定する前に拒否する
+    #[test]
+    fn test_check_subject() {
+        let subjects: Value = serde_json::from_str(SUBJECTS).unwrap();
+        let oversized = "a".repeat(10 * 1024);
+        for (group, expected) in [("valid", true), ("invalid", false)] {
+            let cases = subjects[group].as_array().unwrap().iter();
+            let cases = cases.map(|subject| subject.as_str().unwrap());
+            let cases = cases.chain((!expected).then_some(oversized.as_str()));
+            for subject in cases {
+                let token =
+                    jwt::encode(&Header::hs256(None), &hs256_claims(subject), SECRET_KEY).unwrap();
+                // 10 KB の sub も jwt クレートの長さの上限ではなく sub の形式で拒否されることを確認する
+                let validation = Validation::new(NOW).with_max_token_bytes(32 * 1024);
+                let result = verify_jwt(&token, &default_keys(), &validation)
+                    .and_then(|verified| check_subject(verified, &SubjectRule::Uuid));
+                if expected {
+                    assert_eq!(result.unwrap().user_id, subject);
+                    continue;
+                }
+                let error = result.unwrap_err();
+                assert_eq!(error, VerifyError::InvalidSubject, "{:?}", subject);
+                assert_eq!(error.to_string(), "Invalid subject claim");
+                assert_eq!(error.metric(), FAILURES_OTHER);
+                let result = auth_result(Err(error));
+                assert_eq!(result.error_code, Some(AuthErrorCode::InvalidClaims));
+                assert_eq!(result.user_id, None);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_subject_pattern() {
+        assert!(matches!(parse_subject_pattern(None), Ok(SubjectRule::Uuid)));
+        assert!(matches!(
+            parse_subject_pattern(Some(" ")),
+            Ok(SubjectRule::Uuid)
+        ));
+
+        // sub 全体が一致するもののみ受け付ける
+        let rule = parse_subject_pattern(Some("idp-user-[0-9]+")).unwrap();
+        assert!(rule.matches("idp-user-1"));
+        for subject in [
+            "idp-user-",
+            "idp-user-1 OR 1=1",
+            "x-idp-user-1",
+            "idp-user-1\n",
+        ] {
+            assert!(!rule.matches(subject), "{:?}", subject);
+        }
+
+        // 正規表現に一致しても、長すぎる値・制御文字を含む値は拒否する
+        let rule = parse_subject_pattern(Some(".+")).unwrap();
+        assert!(rule.matches("auth0|0123456789abcdef01234567"));
+        assert!(rule.matches(&"a".repeat(MAX_SUBJECT_BYTES)));
+        assert!(!rule.matches(&"a".repeat(MAX_SUBJECT_BYTES + 1)));
+        assert!(!rule.matches("user-1\r\nX-Admin: true"));
+
+        let error = parse_subject_pattern(Some("(")).unwrap_err();
+        assert!(
+            error.starts_with("Invalid jwt_subject_pattern"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_parse_switch() {
+        let parse = |value| parse_switch(REVOCATION_FAIL_OPEN_VARIABLE, value);
+        assert_eq!(parse(None), Ok(false));
+        assert_eq!(parse(Some(" ")), Ok(false));
+        assert_eq!(parse(Some("off")), Ok(false));
+        assert_eq!(parse(Some(" ON ")), Ok(true));
+
+        let error = parse(Some("true")).unwrap_err();
+        assert!(
+            error.starts_with("Invalid jwt_revocation_fail_open"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_record_and_read_metrics() {
+        let counter = MemoryCounter::default();
+
+        // まだ数えていないカウンタも 0 として全て返す
+        let metrics = read_metrics(&counter).unwrap();
+        assert_eq!(
+            metrics
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            METRIC_NAMES
+        );
+        assert!(metrics.iter().all(|(_, value)| *value == 0));
+
+        let valid = token(r#"{"sub":"user-1","exp":4102444800}"#);
+        for token in [valid.as_str(), valid.as_str(), "", "a.b.c"] {
+            record_verification(
+                &counter,
+                &verify_jwt(token, &default_keys(), &Validation::new(NOW)),
+            )
+            .unwrap();
+        }
+
+        let metrics: HashMap<_, _> = read_metrics(&counter).unwrap().into_iter().collect();
+        assert_eq!(metrics[VERIFICATIONS_TOTAL], 4);
+        assert_eq!(metrics[VERIFICATIONS_SUCCESS], 2);
+        assert_eq!(metrics[FAILURES_MISSING_TOKEN], 1);
+        assert_eq!(metrics[FAILURES_MALFORMED], 1);
+        assert_eq!(metrics[FAILURES_EXPIRED], 0);
+
+        // キーは他のコンポーネントと衝突しないプレフィックス付き
+        assert!(counter
+            .values
+            .lock()
+            .unwrap()
+            .contains_key("auth:metrics:auth_verifications_total"));
+    }
+
+    #[test]
+    fn test_concurrent_recording_does_not_lose_counts() {
+        let counter = Arc::new(MemoryCounter::default());
+        let expired = token(&format!(r#"{{"sub":"user-1","exp":{}}}"#, NOW - 61));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let counter = Arc::clone(&counter);
+                let expired = expired.clone();
+                thread::spawn(move || {
+                    for _ in 0..25 {
+                        record_verification(
+                            counter.as_ref(),
+                            &verify_jwt(&expired, &default_keys(), &Validation::new(NOW)),
+                        )
+                        .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let metrics: HashMap<_, _> = read_metrics(counter.as_ref())
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(metrics[VERIFICATIONS_TOTAL], 200);
+        assert_eq!(metrics[FAILURES_EXPIRED], 200);
+        assert_eq!(metrics[VERIFICATIONS_SUCCESS], 0);
+    }
+
+    #[test]
+    fn test_issued_token_passes_verification() {
+        let claims = vec![("request_id".to_string(), "req-1".to_string())];
+        let token = issue_jwt("edge-gateway", 60, &claims, &default_keyring(), NOW).unwrap();
+
+        let (header, payload) = decode(&token);
+        assert_eq!(header["kid"], SIGNING_KEY_ID);
+        assert_eq!(payload["iat"], NOW);
+        assert_eq!(payload["exp"], NOW + 60);
+        assert_eq!(payload["request_id"], "req-1");
+
+        let verified = verify_jwt(&token, &default_keys(), &Validation::new(NOW)).unwrap();
+        assert_eq!(verified.user_id, "edge-gateway");
+        assert_eq!(verified.expires_at, Some(NOW + 60));
+    }
+
+    /// issue-token と同じ引数（追加のクレームなし）で発行したトークンを verify-token で検証できる
+    #[test]
+    fn test_dev_token_round_trip() {
+        let token = issue_jwt("user-1", 120, &[], &default_keyring(), NOW).unwrap();
+
+        let (header, payload) = decode(&token);
+        assert_eq!(header["alg"], "HS256");
+        assert_eq!(header["kid"], SIGNING_KEY_ID);
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "sub": "user-1",
+                "iat": NOW,
+                "exp": NOW + 120,
+                "token_use": "access",
+            })
+        );
+
+        let result = auth_result(verify_jwt(&token, &default_keys(), &Validation::new(NOW)));
+        assert!(result.authenticated);
+        assert_eq!(result.user_id.as_deref(), Some("user-1"));
+        assert_eq!(result.issued_at, Some(NOW));
+        assert_eq!(result.expires_at, Some(NOW + 120));
+        assert!(result.roles.is_empty());
+        assert!(result.claims.is_empty());
+
+        // 有効期間を過ぎたトークン・別の鍵では検証に失敗する
+        let expired = auth_result(verify_jwt(
+            &token,
+            &default_keys(),
+            &Validation::new(NOW + 120 + jwt::DEFAULT_LEEWAY_SECS + 1),
+        ));
+        assert_eq!(expired.error_code, Some(AuthErrorCode::Expired));
+        let other = VerificationKeys {
+            hs256: Some(HmacKeyring::from_secret(b"other-secret")),
+            ..Default::default()
+        };
+        let forged = auth_result(verify_jwt(&token, &other, &Validation::new(NOW)));
+        assert_eq!(forged.error_code, Some(AuthErrorCode::InvalidSignature));
+    }
+
+    /// コア層の AuthService が発行する形式のトークン（kid・iss 付き）を検証できる
+    #[test]
+    fn test_verifies_core_issued_token() {
+        let mut core = Claims {
+            sub: "7f1c0d2e-0000-4000-8000-000000000001".to_string(),
+            exp: NOW + 3600,
+            iat: NOW,
+            iss: Some("todo-api".to_string()),
+            aud: None,
+            extra: Map::new(),
+        };
+        core.extra
+            .insert("tenant_id".to_string(), Value::from("tenant-a"));
+        let token = jwt::encode(&Header::hs256(Some("default")), &core, SECRET_KEY).unwrap();
+
+        let verified = verify_jwt(&token, &default_keys(), &Validation::new(NOW)).unwrap();
+        assert_eq!(verified.user_id, core.sub);
+        assert_eq!(
+            verified.claims,
+            vec![("tenant_id".to_string(), "tenant-a".to_string())]
+        );
+    }
+
+    /// テスト用の HS256 トークンのクレーム（sub / exp / iat のみ）
+    fn hs256_claims(sub: &str) -> Claims {
+        Claims {
+            sub: sub.to_string(),
+            exp: NOW + 60,
+            iat: NOW,
+            iss: None,
+            aud: None,
+            extra: Map::new(),
+        }
+    }
+
+    /// シークレットのローテーション: 新しい鍵で発行し、古い鍵で発行したトークンも有効期限まで受け付ける
+    #[test]
+    fn test_hs256_key_rotation() {
+        let before = parse_hs256_keys(Some(r#"{"default":"super-secret-key"}"#))
+            .unwrap()
+            .unwrap();
+        let after = parse_hs256_keys(Some(
+            r#"{"2025-02":"rotated-secret","default":"super-secret-key"}"#,
+        ))
+        .unwrap()
+        .unwrap();
+        let keys = VerificationKeys {
+            hs256: Some(after.clone()),
+            ..default_keys()
+        };
+
+        // ローテーション前に発行したトークン（kid あり・kid なし）
+        let old = issue_jwt("user-1", 3600, &[], &before, NOW).unwrap();
+        let old_without_kid = token(r#"{"sub":"user-1","exp":4102444800}"#);
+        for token in [&old, &old_without_kid] {
+            let verified = verify_jwt(token, &keys, &Validation::new(NOW)).unwrap();
+            assert_eq!(verified.user_id, "user-1");
+        }
+
+        // ローテーション後は最新の鍵の kid で発行する
+        let new = issue_jwt("user-2", 3600, &[], &after, NOW).unwrap();
+        let (header, _) = decode(&new);
+        assert_eq!(header["kid"], "2025-02");
+        assert!(verify_jwt(&new, &keys, &Validation::new(NOW)).is_ok());
+
+        // 新しい鍵のトークンはローテーション前の鍵リングでは検証できない
+        let error = verify_jwt(&new, &default_keys(), &Validation::new(NOW)).unwrap_err();
+        assert_eq!(error, VerifyError::UnknownKeyId);
+
+        // kid が別の鍵を指す・どの鍵でも署名が一致しないトークンは拒否する
+        let claims = hs256_claims("user-3");
+        let mismatched = jwt::encode(&Header::hs256(Some("2025-02")), &claims, SECRET_KEY).unwrap();
+        let forged = jwt::encode(&Header::hs256(None), &claims, b"other-secret").unwrap();
+        for token in [&mismatched, &forged] {
+            let error = verify_jwt(token, &keys, &Validation::new(NOW)).unwrap_err();
+            assert_eq!(error.to_string(), "Invalid signature");
+        }
+    }
+
+    /// 鍵リングにない kid のトークンは拒否する
+    #[test]
+    fn test_hs256_unknown_kid() {
+        let claims = hs256_claims("user-1");
+        let token = jwt::encode(&Header::hs256(Some("retired")), &claims, SECRET_KEY).unwrap();
+
+        let error = verify_jwt(&token, &default_keys(), &Validation::new(NOW)).unwrap_err();
+        assert_eq!(error, VerifyError::UnknownKeyId);
+        assert_eq!(error.to_string(), "Unknown signing key");
+        assert_eq!(error.metric(), FAILURES_OTHER);
+    }
+
+    /// jwt_secret・jwt_hs256_keys がない場合、デモ用の鍵を使わずに HS256 のトークンを拒否する
+    #[test]
+    fn test_hs256_without_secret_is_misconfigured() {
+        let keys = VerificationKeys {
+            rs256: parse_rsa_public_key(Some(RS256_PUBLIC_KEY)).unwrap(),
+            ..Default::default()
+        };
+
+        let hs256 = token(r#"{"sub":"user-1","exp":4102444800}"#);
+        let error = verify_jwt(&hs256, &keys, &Validation::new(NOW)).unwrap_err();
+        assert_eq!(error, VerifyError::Misconfigured);
+        assert_eq!(error.to_string(), "Auth misconfigured");
+        assert_eq!(error.metric(), FAILURES_OTHER);
+
+        // 公開鍵で検証するトークンは影響を受けない
+        let rs256 = rs256_token(r#"{"sub":"idp-user-1","exp":4102444800}"#);
+        assert!(verify_jwt(&rs256, &keys, &Validation::new(NOW)).is_ok());
+    }
+
+    #[test]
+    fn test_parse_hs256_keys() {
+        // 未定義・空の場合は jwt_secret を使う
+        for value in [None, Some(""), Some("  ")] {
+            assert_eq!(parse_hs256_keys(value).unwrap(), None);
+        }
+
+        // 記載順（新しい順）を保つ（kid の順に並べ替えない）
+        let keyring = parse_hs256_keys(Some(r#"{"b-new":"secret-2","a-old":"secret-1"}"#))
+            .unwrap()
+            .unwrap();
+        let ids: Vec<&str> = keyring.keys.iter().map(|key| key.id.as_str()).collect();
+        assert_eq!(ids, vec!["b-new", "a-old"]);
+        assert_eq!(keyring.signing_key().secret, b"secret-2");
+
+        // シークレットは Debug 出力に含めない
+        assert!(!format!("{:?}", keyring).contains("secret-2"));
+
+        for invalid in [
+            "not-json",
+            r#"["secret"]"#,
+            "{}",
+            r#"{"key 1":"secret"}"#,
+            r#"{"":"secret"}"#,
+            r#"{"key-1":""}"#,
+            r#"{"key-1":1}"#,
+            r#"{"key-1":"a","key-1":"b"}"#,
+        ] {
+            let error = parse_hs256_keys(Some(invalid)).unwrap_err();
+            assert!(error.starts_with("Invalid jwt_hs256_keys: "), "{}", error);
+        }
+    }
+
+    /// 2 つの発行者（テナント）の jwt_tenants
+    ///
+    /// 同じ kid（default）に別のシークレットを設定し、kid だけで鍵を選ばないことを確認する。
+    const TENANTS: &str = r#"{
+        "https://a.example.com": {
+            "alg": "HS256",
+            "keys": {"default": "tenant-a-secret"},
+            "audiences": ["todo-api"]
+        },
+        "https://b.example.com": {
+            "alg": "HS512",
+            "keys": {"default": "tenant-b-secret", "b-2025": "tenant-b-rotated"}
+        }
+    }"#;
+
+    const TENANT_A: &str = "https://a.example.com";
+    const TENANT_B: &str = "https://b.example.com";
+
+    /// jwt_tenants に TENANTS を指定した場合の鍵（jwt_secret の鍵もあるが検証には使わない）
+    fn tenant_keys() -> VerificationKeys {
+        VerificationKeys {
+            tenants: parse_tenants(Some(TENANTS)).unwrap(),
+            ..default_keys()
+        }
+    }
+
+    /// 発行者のトークンを作成する
+    fn tenant_token(
+        alg: &str,
+        kid: Option<&str>,
+        iss: &str,
+        aud: Option<&str>,
+        secret: &[u8],
+    ) -> String {
+        let claims = Claims {
+            iss: Some(iss.to_string()),
+            aud: aud.map(|aud| jwt::Audience::Single(aud.to_string())),
+            ..hs256_claims("user-1")
+        };
+        jwt::encode(&Header::new(alg, kid), &claims, secret).unwrap()
+    }
+
+    /// 各発行者のトークンは、その発行者の鍵・アルゴリズム・対象者で検証する
+    #[test]
+    fn test_tenant_tokens_verify_with_their_own_keys() {
+        let keys = tenant_keys();
+        for token in [
+            tenant_token(
+                "HS256",
+                Some("default"),
+                TENANT_A,
+                Some("todo-api"),
+                b"tenant-a-secret",
+            ),
+            tenant_token(
+                "HS256",
+                None,
+                TENANT_A,
+                Some("todo-api"),
+                b"tenant-a-secret",
+            ),
+            tenant_token("HS512", Some("default"), TENANT_B, None, b"tenant-b-secret"),
+            tenant_token("HS512", Some("b-2025"), TENANT_B, None, b"tenant-b-rotated"),
+            tenant_token("HS512", None, TENANT_B, None, b"tenant-b-rotated"),
+        ] {
+            let verified = verify_jwt(&token, &keys, &Validation::new(NOW)).unwrap();
+            assert_eq!(verified.user_id, "user-1");
+        }
+
+        // jwt_algorithms・jwt_audiences ではなく発行者の設定で検証する
+        let validation = Validation::new(NOW)
+            .with_algorithms(&["RS256"])
+            .with_audiences(&["other-api"]);
+        let token = tenant_token(
+            "HS256",
+            None,
+            TENANT_A,
+            Some("todo-api"),
+            b"tenant-a-secret",
+        );
+        assert!(verify_jwt(&token, &keys, &validation).is_ok());
+
+        // 発行者の対象者を指定した場合は aud が必要
+        let without_aud = tenant_token("HS256", None, TENANT_A, None, b"tenant-a-secret");
+        assert_eq!(
+            verify_jwt(&without_aud, &keys, &Validation::new(NOW)).unwrap_err(),
+            VerifyError::Jwt(jwt::Error::InvalidAudience)
+        );
+
+        // 発行者のアルゴリズム以外は同じ鍵で署名しても拒否する
+        let wrong_alg = tenant_token("HS256", None, TENANT_B, None, b"tenant-b-secret");
+        assert_eq!(
+            verify_jwt(&wrong_alg, &keys, &Validation::new(NOW)).unwrap_err(),
+            VerifyError::Jwt(jwt::Error::UnsupportedAlgorithm("HS256".to_string()))
+        );
+    }
+
+    /// ある発行者の鍵で、他の発行者を名乗るトークンを検証することはない
+    #[test]
+    fn test_tenant_key_never_verifies_other_tenants_tokens() {
+        let keys = tenant_keys();
+        let forged = [
+            // tenant-a の鍵で署名し、tenant-b を名乗る（kid あり・なし）
+            tenant_token("HS512", Some("default"), TENANT_B, None, b"tenant-a-secret"),
+            tenant_token("HS512", None, TENANT_B, None, b"tenant-a-secret"),
+            // tenant-b の鍵で署名し、tenant-a を名乗る
+            tenant_token(
+                "HS256",
+                Some("default"),
+                TENANT_A,
+                Some("todo-api"),
+                b"tenant-b-secret",
+            ),
+            tenant_token(
+                "HS256",
+                None,
+                TENANT_A,
+                Some("todo-api"),
+                b"tenant-b-rotated",
+            ),
+            // jwt_secret で署名し、tenant-a を名乗る
+            tenant_token(
+                "HS256",
+                Some("default"),
+                TENANT_A,
+                Some("todo-api"),
+                SECRET_KEY,
+            ),
+        ];
+        for token in &forged {
+            let error = verify_jwt(token, &keys, &Validation::new(NOW)).unwrap_err();
+            assert_eq!(
+                error,
+                VerifyError::Jwt(jwt::Error::InvalidSignature),
+                "{}",
+                token
+            );
+        }
+
+        // 他の発行者にしかない kid は、その発行者の鍵リングにない
+        let other_kid = tenant_token(
+            "HS256",
+            Some("b-2025"),
+            TENANT_A,
+            Some("todo-api"),
+            b"tenant-b-rotated",
+        );
+        assert_eq!(
+            verify_jwt(&other_kid, &keys, &Validation::new(NOW)).unwrap_err(),
+            VerifyError::UnknownKeyId
+        );
+    }
+
+    /// 未知の発行者・iss のないトークンは、署名を確認せずに拒否する
+    #[test]
+    fn test_unknown_issuer_is_rejected_before_signature() {
+        let keys = tenant_keys();
+
+        // jwt_secret で正しく署名したトークンも、発行者を選べなければ拒否する
+        let unknown = token(r#"{"sub":"user-1","exp":4102444800,"iss":"https://c.example.com"}"#);
+        let missing = token(r#"{"sub":"user-1","exp":4102444800}"#);
+        let numeric = token(r#"{"sub":"user-1","exp":4102444800,"iss":42}"#);
+        // 署名が不正でも "Invalid signature" ではなく "Unknown issuer"（署名を確認していない）
+        let unsigned_unknown = format!(
+            "{}.{}",
+            unknown.rsplit_once('.').unwrap().0,
+            URL_SAFE_NO_PAD.encode([0u8; 32])
+        );
+        // 発行者の名前の大文字小文字・末尾のスラッシュが異なるものは別の発行者
+        let similar = tenant_token(
+            "HS256",
+            None,
+            "https://A.example.com/",
+            Some("todo-api"),
+            b"tenant-a-secret",
+        );
+        for token in [&unknown, &missing, &numeric, &unsigned_unknown, &similar] {
+            let error = verify_jwt(token, &keys, &Validation::new(NOW)).unwrap_err();
+            assert_eq!(error, VerifyError::UnknownIssuer, "{}", token);
+            assert_eq!(error.to_string(), "Unknown issuer");
+            assert_eq!(error.metric(), FAILURES_OTHER);
+            assert_eq!(error.code(), AuthErrorCode::InvalidClaims);
+        }
+
+        // jwt_tenants が不正な場合（発行者の設定が空）は全てのトークンを拒否する
+        let misconfigured = VerificationKeys {
+            tenants: Some(Vec::new()),
+            ..default_keys()
+        };
+        let valid = tenant_token(
+            "HS256",
+            None,
+            TENANT_A,
+            Some("todo-api"),
+            b"tenant-a-secret",
+        );
+        for token in [&valid, &missing] {
+            assert_eq!(
+                verify_jwt(token, &misconfigured, &Validation::new(NOW)).unwrap_err(),
+                VerifyError::Misconfigured
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_tenants() {
+        // 未定義・空の場合は発行者ごとの設定を使わない
+        for value in [None, Some(""), Some("  ")] {
+            assert_eq!(parse_tenants(value).unwrap(), None);
+        }
+
+        let tenants = parse_tenants(Some(TENANTS)).unwrap().unwrap();
+        let issuers: Vec<&str> = tenants
+            .iter()
+            .map(|tenant| tenant.issuer.as_str())
+            .collect();
+        assert_eq!(issuers, vec![TENANT_A, TENANT_B]);
+        assert_eq!(tenants[0].algorithm, "HS256");
+        assert_eq!(tenants[0].audiences, vec!["todo-api".to_string()]);
+        assert_eq!(tenants[1].algorithm, "HS512");
+        assert!(tenants[1].audiences.is_empty());
+        assert_eq!(tenants[1].keyring.keys.len(), 2);
+
+        // シークレットは Debug 出力に含めない
+        assert!(!format!("{:?}", tenants).contains("tenant-a-secret"));
+
+        for invalid in [
+            "not-json",
+            "[]",
+            "{}",
+            r#"{"":{"alg":"HS256","keys":{"k":"s"}}}"#,
+            r#"{"iss":"HS256"}"#,
+            r#"{"iss":{"keys":{"k":"s"}}}"#,
+            r#"{"iss":{"alg":"RS256","keys":{"k":"s"}}}"#,
+            r#"{"iss":{"alg":"none","keys":{"k":"s"}}}"#,
+            r#"{"iss":{"alg":"HS256"}}"#,
+            r#"{"iss":{"alg":"HS256","keys":{}}}"#,
+            r#"{"iss":{"alg":"HS256","keys":{"k":""}}}"#,
+            r#"{"iss":{"alg":"HS256","keys":["s"]}}"#,
+            r#"{"iss":{"alg":"HS256","keys":{"k":"s"},"audiences":"todo-api"}}"#,
+            r#"{"iss":{"alg":"HS256","keys":{"k":"s"},"audiences":[""]}}"#,
+            r#"{"iss":{"alg":"HS256","keys":{"k":"s"},"audience":["todo-api"]}}"#,
+        ] {
+            let error = parse_tenants(Some(invalid)).unwrap_err();
+            assert!(error.starts_with("Invalid jwt_tenants: "), "{}", error);
+        }
+    }
+
+    /// jwt_issuer を指定した場合、iss が一致するトークンのみ受け付ける
+    #[test]
+    fn test_issuer_validation() {
+        let expect = |issuer| Validation::new(NOW).with_issuer(Some(issuer));
+
+        let own = token(r#"{"sub":"user-1","exp":4102444800,"iss":"todo-api"}"#);
+        assert_eq!(
+            verify_jwt(&own, &default_keys(), &expect("todo-api"))
+                .unwrap()
+                .user_id,
+            "user-1"
+        );
+
+        // iss が異なる・iss がない → "Invalid issuer"（その他の失敗として数える）
+        let other = token(r#"{"sub":"user-1","exp":4102444800,"iss":"partner-idp"}"#);
+        let missing = token(r#"{"sub":"user-1","exp":4102444800}"#);
+        for token in [other.as_str(), missing.as_str()] {
+            let error = verify_jwt(token, &default_keys(), &expect("todo-api")).unwrap_err();
+            assert_eq!(error, VerifyError::Jwt(jwt::Error::InvalidIssuer));
+            assert_eq!(error.to_string(), "Invalid issuer");
+            assert_eq!(error.metric(), FAILURES_OTHER);
+        }
+
+        // 未指定の場合は iss を検証しない
+        for token in [own.as_str(), other.as_str(), missing.as_str()] {
+            assert!(verify_jwt(token, &default_keys(), &Validation::new(NOW)).is_ok());
+        }
+    }
+
+    /// jwt_audiences を指定した場合、aud（文字列・配列）のいずれかが含まれるトークンのみ受け付ける
+    #[test]
+    fn test_audience_validation() {
+        let allowed = ["todo-api", "todo-edge"];
+        let expect = Validation::new(NOW).with_audiences(&allowed);
+        let with_aud = |aud: &str| {
+            token(&format!(
+                r#"{{"sub":"user-1","exp":4102444800,"aud":{}}}"#,
+                aud
+            ))
+        };
+
+        for aud in [r#""todo-api""#, r#"["partner","todo-edge"]"#] {
+            assert_eq!(
+                verify_jwt(&with_aud(aud), &default_keys(), &expect)
+                    .unwrap()
+                    .user_id,
+                "user-1"
+            );
+        }
+
+        // 一致しない・空の配列・aud がない → "Invalid audience"（その他の失敗として数える）
+        let missing = token(r#"{"sub":"user-1","exp":4102444800}"#);
+        for token in [with_aud(r#""partner""#), with_aud("[]"), missing.clone()] {
+            let error = verify_jwt(&token, &default_keys(), &expect).unwrap_err();
+            assert_eq!(error, VerifyError::Jwt(jwt::Error::InvalidAudience));
+            assert_eq!(error.to_string(), "Invalid audience");
+            assert_eq!(error.metric(), FAILURES_OTHER);
+        }
+
+        // 未指定の場合は aud を検証しない（aud は gateway に返さない）
+        let verified = verify_jwt(
+            &with_aud(r#""partner""#),
+            &default_keys(),
+            &Validation::new(NOW),
+        )
+        .unwrap();
+        assert!(verified.claims.is_empty());
+        assert!(verify_jwt(&missing, &default_keys(), &Validation::new(NOW)).is_ok());
+    }
+
+    #[test]
+    fn test_parse_audiences() {
+        assert!(parse_audiences(None).is_empty());
+        assert!(parse_audiences(Some("")).is_empty());
+        assert!(parse_audiences(Some(" , ")).is_empty());
+        assert_eq!(
+            parse_audiences(Some("todo-api, todo-edge,")),
+            vec!["todo-api".to_string(), "todo-edge".to_string()]
+        );
+    }
+
+    /// jwt_rs256_public_key を指定した場合、外部の ID プロバイダーの RS256 トークンを受け付ける
+    #[test]
+    fn test_rs256_token_verifies_with_configured_public_key() {
+        let keys = VerificationKeys {
+            rs256: parse_rsa_public_key(Some(RS256_PUBLIC_KEY)).unwrap(),
+            ..default_keys()
+        };
+        let token = rs256_token(
+            r#"{"sub":"idp-user-1","exp":4102444800,"roles":["viewer"],"tenant_id":"tenant-a"}"#,
+        );
+
+        let verified = verify_jwt(&token, &keys, &Validation::new(NOW)).unwrap();
+        assert_eq!(verified.user_id, "idp-user-1");
+        assert_eq!(verified.roles, vec!["viewer".to_string()]);
+        assert_eq!(
+            verified.claims,
+            vec![("tenant_id".to_string(), "tenant-a".to_string())]
+        );
+
+        // HS256 のトークンは公開鍵を指定しても従来どおり SECRET_KEY で検証する
+        let hs256 = token(r#"{"sub":"user-1","exp":4102444800}"#);
+        assert!(verify_jwt(&hs256, &keys, &Validation::new(NOW)).is_ok());
+
+        // 公開鍵を指定していない場合は RS256 を受け付けない
+        let error = verify_jwt(&token, &default_keys(), &Validation::new(NOW)).unwrap_err();
+        assert_eq!(error.to_string(), "Unsupported algorithm: RS256");
+        assert_eq!(error.metric(), FAILURES_UNSUPPORTED_ALG);
+    }
+
+    /// アルゴリズム混同攻撃: 公開鍵の PEM を HS256 のシークレットとして署名したトークンは拒否する
+    #[test]
+    fn test_rejects_hs256_token_signed_with_public_key() {
+        let keys = VerificationKeys {
+            rs256: parse_rsa_public_key(Some(RS256_PUBLIC_KEY)).unwrap(),
+            ..default_keys()
+        };
+        let message = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#),
+            URL_SAFE_NO_PAD.encode(r#"{"sub":"admin","exp":4102444800,"roles":["admin"]}"#)
+        );
+        let mut mac = Hmac::<Sha256>::new_from_slice(RS256_PUBLIC_KEY.as_bytes()).unwrap();
+        mac.update(message.as_bytes());
+        let forged = format!(
+            "{}.{}",
+            message,
+            URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+        );
+
+        let error = verify_jwt(&forged, &keys, &Validation::new(NOW)).unwrap_err();
+        assert_eq!(error, VerifyError::Jwt(jwt::Error::InvalidSignature));
+        assert_eq!(error.metric(), FAILURES_BAD_SIGNATURE);
+    }
+
+    #[test]
+    fn test_parse_rsa_public_key() {
+        assert_eq!(parse_rsa_public_key(None), Ok(None));
+        assert_eq!(parse_rsa_public_key(Some(" ")), Ok(None));
+
+        // 環境変数で渡す場合の "\n" のエスケープも受け付ける
+        let escaped = RS256_PUBLIC_KEY.trim().replace('\n', "\\n");
+        assert_eq!(
+            parse_rsa_public_key(Some(&escaped)),
+            parse_rsa_public_key(Some(RS256_PUBLIC_KEY))
+        );
+        assert!(parse_rsa_public_key(Some(&escaped)).unwrap().is_some());
+
+        // 不正な PEM・途中で切れた PEM・秘密鍵はエラー（ログを出力して RS256 を拒否する）
+        let truncated = &RS256_PUBLIC_KEY[..RS256_PUBLIC_KEY.len() / 2];
+        for pem in ["not a pem", truncated, RS256_PRIVATE_KEY] {
+            assert_eq!(
+                parse_rsa_public_key(Some(pem)).unwrap_err(),
+                "Invalid jwt_rs256_public_key: Invalid public key"
+            );
+        }
+    }
+
+    /// jwt_es256_public_key（PEM・JWK）を指定した場合、パートナーの ES256 トークンを受け付ける
+    #[test]
+    fn test_es256_token_verifies_with_configured_public_key() {
+        let token = es256_token(r#"{"sub":"partner-1","exp":4102444800}"#, false);
+
+        for configured in [ES256_PUBLIC_KEY, ES256_PUBLIC_JWK] {
+            let keys = VerificationKeys {
+                es256: parse_ec_public_key(Some(configured)).unwrap(),
+                ..default_keys()
+            };
+            let verified = verify_jwt(&token, &keys, &Validation::new(NOW)).unwrap();
+            assert_eq!(verified.user_id, "partner-1");
+        }
+
+        // 公開鍵を指定していない・RS256 の公開鍵しかない場合は ES256 を受け付けない
+        let rs256_only = VerificationKeys {
+            rs256: parse_rsa_public_key(Some(RS256_PUBLIC_KEY)).unwrap(),
+            ..default_keys()
+        };
+        for keys in [default_keys(), rs256_only] {
+            let error = verify_jwt(&token, &keys, &Validation::new(NOW)).unwrap_err();
+            assert_eq!(error.to_string(), "Unsupported algorithm: ES256");
+        }
+    }
+
+    /// DER の署名（JOSE の r || s ではない）は署名の形式の誤りとして拒否する
+    #[test]
+    fn test_es256_rejects_der_signature() {
+        let keys = VerificationKeys {
+            es256: parse_ec_public_key(Some(ES256_PUBLIC_KEY)).unwrap(),
+            ..default_keys()
+        };
+        let token = es256_token(r#"{"sub":"partner-1","exp":4102444800}"#, true);
+
+        let error = verify_jwt(&token, &keys, &Validation::new(NOW)).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid signature encoding");
+        assert_eq!(error.metric(), FAILURES_MALFORMED);
+    }
+
+    /// jwt_algorithms に含まれないアルゴリズムは、鍵があっても拒否する
+    #[test]
+    fn test_rejects_algorithms_outside_configured_allowlist() {
+        let keys = VerificationKeys {
+            es256: parse_ec_public_key(Some(ES256_PUBLIC_JWK)).unwrap(),
+            ..default_keys()
+        };
+        let algorithms = parse_algorithms(Some("ES256")).unwrap();
+        let algorithms: Vec<&str> = algorithms.iter().map(String::as_str).collect();
+        let validation = Validation::new(NOW).with_algorithms(&algorithms);
+
+        let es256 = es256_token(r#"{"sub":"partner-1","exp":4102444800}"#, false);
+        assert!(verify_jwt(&es256, &keys, &validation).is_ok());
+
+        let hs256 = token(r#"{"sub":"user-1","exp":4102444800}"#);
+        let error = verify_jwt(&hs256, &keys, &validation).unwrap_err();
+        assert_eq!(error.to_string(), "Unsupported algorithm: HS256");
+        assert_eq!(error.metric(), FAILURES_UNSUPPORTED_ALG);
+    }
+
+    /// HS512 のトークンは HS256 と同じ鍵で検証し、jwt_algorithms に含めた場合のみ受け付ける
+    #[test]
+    fn test_hs512_tokens_require_opt_in() {
+        let hs512 = jwt::encode(
+            &Header::new("HS512", None),
+            &hs256_claims("user-1"),
+            SECRET_KEY,
+        )
+        .unwrap();
+
+        let algorithms = parse_algorithms(Some("HS256,HS512")).unwrap();
+        let algorithms: Vec<&str> = algorithms.iter().map(String::as_str).collect();
+        let validation = Validation::new(NOW).with_algorithms(&algorithms);
+        let verified = verify_jwt(&hs512, &default_keys(), &validation).unwrap();
+        assert_eq!(verified.user_id, "user-1");
+        // HS256 のトークンも引き続き受け付ける
+        let hs256 = token(r#"{"sub":"user-1","exp":4102444800}"#);
+        assert!(verify_jwt(&hs256, &default_keys(), &validation).is_ok());
+
+        // 署名が一致しない HS512 のトークンは拒否する
+        let forged = jwt::encode(
+            &Header::new("HS512", None),
+            &hs256_claims("user-1"),
+            b"other-secret",
+        )
+        .unwrap();
+        let error = verify_jwt(&forged, &default_keys(), &validation).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid signature");
+
+        // HS256 のみ・既定の許可リストでは、署名が正しくても拒否する
+        let hs256_only = parse_algorithms(Some("HS256")).unwrap();
+        let hs256_only: Vec<&str> = hs256_only.iter().map(String::as_str).collect();
+        for validation in [
+            Validation::new(NOW).with_algorithms(&hs256_only),
+            Validation::new(NOW),
+        ] {
+            let error = verify_jwt(&hs512, &default_keys(), &validation).unwrap_err();
+            assert_eq!(error.to_string(), "Unsupported algorithm: HS512");
+            assert_eq!(error.metric(), FAILURES_UNSUPPORTED_ALG);
+            assert_eq!(error.code(), AuthErrorCode::UnsupportedAlgorithm);
+        }
+    }
+
+    /// "none" は大文字小文字・鍵の設定・kid にかかわらず拒否する
+    #[test]
+    fn test_rejects_unsecured_algorithm_regardless_of_case() {
+        let payload = URL_SAFE_NO_PAD.encode(r#"{"sub":"user-1","exp":4102444800}"#);
+        for header in [
+            r#"{"alg":"none"}"#,
+            r#"{"alg":"None"}"#,
+            r#"{"alg":"NONE"}"#,
+            r#"{"alg":"nOnE","kid":"k1"}"#,
+        ] {
+            let unsigned = format!("{}.{}.", URL_SAFE_NO_PAD.encode(header), payload);
+            for keys in [default_keys(), VerificationKeys::default()] {
+                let error = verify_jwt(&unsigned, &keys, &Validation::new(NOW)).unwrap_err();
+                assert!(
+                    error
+                        .to_string()
+                        .starts_with("Unsecured algorithm not allowed: "),
+                    "{}: {}",
+                    header,
+                    error
+                );
+                assert_eq!(error.metric(), FAILURES_UNSUPPORTED_ALG);
+                assert_eq!(error.code(), AuthErrorCode::UnsupportedAlgorithm);
+            }
+        }
+
+        // 大文字小文字の異なる HS256 は none ではないが、許可リストにない
+        let lowercase = format!(
+            "{}.{}.",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"hs256"}"#),
+            payload
+        );
+        let error = verify_jwt(&lowercase, &default_keys(), &Validation::new(NOW)).unwrap_err();
+        assert_eq!(error.to_string(), "Unsupported algorithm: hs256");
+    }
+
+    /// パディングのある Base64URL は jwt_lenient_base64=on の場合のみ受け付ける
+    #[test]
+    fn test_lenient_base64_accepts_padded_tokens() {
+        use base64::engine::general_purpose::URL_SAFE;
+
+        // ヘッダー（16 バイト）・ペイロード（34 バイト）・署名（32 バイト）のすべてに "=" が付く
+        let message = format!(
+            "{}.{}",
+            URL_SAFE.encode(r#"{"alg":"HS256"} "#),
+            URL_SAFE.encode(r#"{"sub":"user-1","exp":4102444800} "#)
+        );
+        let mut mac = Hmac::<Sha256>::new_from_slice(SECRET_KEY).unwrap();
+        mac.update(message.as_bytes());
+        let token = format!(
+            "{}.{}",
+            message,
+            URL_SAFE.encode(mac.finalize().into_bytes())
+        );
+        assert_eq!(
+            token.split('.').filter(|part| part.ends_with('=')).count(),
+            3
+        );
+
+        let error = verify_jwt(&token, &default_keys(), &Validation::new(NOW)).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid header encoding");
+        assert_eq!(error.metric(), FAILURES_MALFORMED);
+
+        let lenient = Validation::new(NOW).with_lenient_base64(true);
+        let verified = verify_jwt(&token, &default_keys(), &lenient).unwrap();
+        assert_eq!(verified.user_id, "user-1");
+    }
+
+    #[test]
+    fn test_parse_algorithms() {
+        // 既定は HS256 のみ（RS256 / ES256 / HS512 は明示した場合のみ）
+        let defaults = vec!["HS256".to_string()];
+        assert_eq!(parse_algorithms(None).unwrap(), defaults);
+        assert_eq!(parse_algorithms(Some(" , ")).unwrap(), defaults);
+        assert_eq!(
+            parse_algorithms(Some("HS256, ES256")).unwrap(),
+            vec!["HS256".to_string(), "ES256".to_string()]
+        );
+        assert_eq!(
+            parse_algorithms(Some("HS256,HS512")).unwrap(),
+            vec!["HS256".to_string(), "HS512".to_string()]
+        );
+
+        // 許可リスト以外（none、HS1024、小文字）はエラー（ログを出力して既定値）
+        for value in ["none", "HS256,HS1024", "es256"] {
+            assert!(
+                parse_algorithms(Some(value))
+                    .unwrap_err()
+                    .starts_with("Invalid jwt_algorithms"),
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_ec_public_key() {
+        assert_eq!(parse_ec_public_key(None), Ok(None));
+        assert_eq!(
+            parse_ec_public_key(Some(ES256_PUBLIC_KEY)),
+            parse_ec_public_key(Some(ES256_PUBLIC_JWK))
+        );
+
+        // 不正な PEM・JWK、RSA の公開鍵はエラー（ログを出力して ES256 を拒否する）
+        for value in [
+            "not a pem",
+            r#"{"kty":"EC","crv":"P-256"}"#,
+            RS256_PUBLIC_KEY,
+        ] {
+            assert_eq!(
+                parse_ec_public_key(Some(value)).unwrap_err(),
+                "Invalid jwt_es256_public_key: Invalid public key"
+            );
+        }
+    }
+
+    /// jwt_jwks_url を指定した場合、kid のある RS256 / ES256 のトークンは JWKS の鍵で検証する
+    #[test]
+    fn test_jwks_key_is_selected_by_kid() {
+        let keys = VerificationKeys {
+            jwks: Some(JwkSet::from_json(JWKS).unwrap()),
+            ..default_keys()
+        };
+        let payload = r#"{"sub":"idp-user-1","exp":4102444800,"tenant_id":"tenant-a"}"#;
+        let with_kid = |kid: &str| {
+            rs256_token_with_header(&format!(r#"{{"alg":"RS256","kid":"{}"}}"#, kid), payload)
+        };
+
+        let verified = verify_jwt(&with_kid("rs256-test"), &keys, &Validation::new(NOW)).unwrap();
+        assert_eq!(verified.user_id, "idp-user-1");
+
+        // 取得し直しても JWKS にない kid
+        let error =
+            verify_jwt(&with_kid("rotated-away"), &keys, &Validation::new(NOW)).unwrap_err();
+        assert_eq!(error, VerifyError::UnknownKeyId);
+        assert_eq!(error.to_string(), "Unknown signing key");
+        assert_eq!(error.metric(), FAILURES_OTHER);
+
+        // kid が ES256 の鍵を指す RS256 のトークンは鍵の種類が異なるため拒否する
+        let error = verify_jwt(&with_kid("es256-test"), &keys, &Validation::new(NOW)).unwrap_err();
+        assert_eq!(error.to_string(), "Unsupported algorithm: RS256");
+
+        // kid のない RS256 のトークンは jwt_rs256_public_key（未指定なら拒否）、HS256 は従来どおり
+        let error = verify_jwt(&rs256_token(payload), &keys, &Validation::new(NOW)).unwrap_err();
+        assert_eq!(error.to_string(), "Unsupported algorithm: RS256");
+        let hs256 = token(r#"{"sub":"user-1","exp":4102444800}"#);
+        assert!(verify_jwt(&hs256, &keys, &Validation::new(NOW)).is_ok());
+
+        // JWKS を取得できない場合
+        assert_eq!(
+            VerifyError::KeysUnavailable.to_string(),
+            "Signing keys unavailable"
+        );
+        assert_eq!(VerifyError::KeysUnavailable.metric(), FAILURES_OTHER);
+    }
+
+    /// JWKS を取得するのは kid のある RS256 / ES256 のトークンのみ
+    #[test]
+    fn test_jwks_kid() {
+        let strict = Validation::new(NOW);
+        let rs256 = rs256_token_with_header(r#"{"alg":"RS256","kid":"key-1"}"#, "{}");
+        assert_eq!(jwks_kid(&rs256, &strict).as_deref(), Some("key-1"));
+        let es256 = format!(
+            "{}.e30.sig",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"ES256","kid":"key-2"}"#)
+        );
+        assert_eq!(jwks_kid(&es256, &strict).as_deref(), Some("key-2"));
+
+        // HS256（kid はコア層の鍵）・kid のないトークン・形式が不正なトークンでは取得しない
+        let hs256 = issue_jwt("svc", 60, &[], &default_keyring(), NOW).unwrap();
+        assert_eq!(jwks_kid(&hs256, &strict), None);
+        assert_eq!(jwks_kid(&rs256_token("{}"), &strict), None);
+        assert_eq!(jwks_kid("", &strict), None);
+        assert_eq!(jwks_kid("not-a-jwt", &strict), None);
+
+        // パディングのあるヘッダーは jwt_lenient_base64=on の場合のみ読む
+        let padded = format!(
+            "{}.e30.sig",
+            base64::engine::general_purpose::URL_SAFE.encode(r#"{"alg":"RS256","kid":"key-1"}"#)
+        );
+        assert!(padded.contains('='));
+        assert_eq!(jwks_kid(&padded, &strict), None);
+        let lenient = Validation::new(NOW).with_lenient_base64(true);
+        assert_eq!(jwks_kid(&padded, &lenient).as_deref(), Some("key-1"));
+    }
+
+    #[test]
+    fn test_issue_rejects_invalid_arguments() {
+        let claim = |name: &str| vec![(name.to_string(), "x".to_string())];
+
+        assert_eq!(
+            issue_jwt("", 60, &[], &default_keyring(), NOW).unwrap_err(),
+            "Missing subject"
+        );
+        assert!(issue_jwt("edge-gateway", 0, &[], &default_keyring(), NOW).is_err());
+        assert!(issue_jwt(
+            "edge-gateway",
+            MAX_ISSUE_TTL_SECS + 1,
+            &[],
+            &default_keyring(),
+            NOW
+        )
+        .is_err());
+        assert_eq!(
+            issue_jwt("edge-gateway", 60, &claim("exp"), &default_keyring(), NOW).unwrap_err(),
+            "Reserved claim: exp"
+        );
+        assert_eq!(
+            issue_jwt(
+                "edge-gateway",
+                60,
+                &claim("token_use"),
+                &default_keyring(),
+                NOW
+            )
+            .unwrap_err(),
+            "Reserved claim: token_use"
+        );
+        let duplicated = [claim("scope"), claim("scope")].concat();
+        assert_eq!(
+            issue_jwt("edge-gateway", 60, &duplicated, &default_keyring(), NOW).unwrap_err(),
+            "Duplicate claim: scope"
+        );
+    }
+}
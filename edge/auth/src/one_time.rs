@@ -0,0 +1,298 @@
+//! # 使い捨てのトークン（パスワードのリセット・メールアドレスの確認）
+//!
+//! パスワードのリセット・メールアドレスの確認のリンクのトークンは 1 回だけ使えるようにします。
+//! `jti` と `one_time: true`（真偽値）のクレームを持つトークンは、署名・クレーム・失効の検証に成功した後に
+//! key-value ストア（`default`）に jti を使用済みとして記録し、2 回目以降の提示を "Token already used" で拒否します。
+//!
+//! | 項目 | 内容 |
+//! |------|------|
+//! | キー | `auth:one-time:jti:<jti>` |
+//! | 値 | 使用済みとして扱う期限（Unix タイムスタンプ秒、トークンの exp + leeway） |
+//!
+//! Spin の key-value ストアには TTL がないため、期限を値に含めて読み込み時に判定します（jwks モジュールと同じ）。
+//! 期限を過ぎた記録は、同じ jti の次のトークンで上書きします（期限までに同じトークンが受け付けられることはない）。
+//!
+//! 同じトークンが同時に提示された場合に両方を受け付けないよう、読み込みと書き込みは
+//! wasi:keyvalue の compare-and-swap（atomics::Cas / atomics::swap）で行います。
+//! 読み込んだ後に別のリクエストが書き込んでいた場合は swap が失敗し、使用済みとして拒否します。
+//! `one_time` のないトークンはストアにアクセスしません。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+// Spin の key-value ストア（wasi:keyvalue@0.2.0-draft2）
+// spin_sdk::key_value::Store には compare-and-swap がないため、WIT バインディングを直接使用
+use spin_sdk::wit::wasi::keyvalue::{atomics, store};
+
+use crate::STORE_NAME;
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// 使用済みの jti のキーのプレフィックス
+///
+/// 同じストアを使う失効リスト（auth:revocation）・JWKS のキャッシュ（auth:jwks）と衝突しないようにする。
+const KEY_PREFIX: &str = "auth:one-time";
+
+// =============================================================================
+// 保存先
+// =============================================================================
+
+/// 使用済みの jti の保存先（compare-and-swap）
+///
+/// テストではメモリ上の実装に差し替え、key-value ストアなしで検証します。
+pub(crate) trait CasStore {
+    /// current で読んだ値から置き換えるためのハンドル
+    type Handle;
+
+    /// キーの現在の値（キーがない場合は None）と、その値から置き換えるためのハンドルを取得する
+    fn current(&self, key: &str) -> Result<(Option<Vec<u8>>, Self::Handle), String>;
+
+    /// current で読んだ後に別のリクエストが書き込んでいない場合のみ、キーの値を置き換える
+    ///
+    /// # 戻り値
+    /// * `Ok(true)` - 置き換えた場合
+    /// * `Ok(false)` - 別のリクエストが先に書き込んでいた場合
+    fn swap(&self, handle: Self::Handle, value: &[u8]) -> Result<bool, String>;
+}
+
+/// Spin の key-value ストアを使う保存先
+pub(crate) struct KvCasStore {
+    bucket: store::Bucket,
+}
+
+impl KvCasStore {
+    /// key-value ストアを開く
+    pub(crate) fn open() -> Result<Self, String> {
+        store::open(STORE_NAME)
+            .map(|bucket| Self { bucket })
+            .map_err(|e| format!("Failed to open key-value store: {:?}", e))
+    }
+}
+
+impl CasStore for KvCasStore {
+    type Handle = atomics::Cas;
+
+    fn current(&self, key: &str) -> Result<(Option<Vec<u8>>, Self::Handle), String> {
+        let cas = atomics::Cas::new(&self.bucket, key)
+            .map_err(|e| format!("Failed to watch {}: {:?}", key, e))?;
+        let value = cas
+            .current()
+            .map_err(|e| format!("Failed to get {}: {:?}", key, e))?;
+        Ok((value, cas))
+    }
+
+    fn swap(&self, handle: Self::Handle, value: &[u8]) -> Result<bool, String> {
+        match atomics::swap(handle, value) {
+            Ok(()) => Ok(true),
+            Err(atomics::CasError::CasFailed(_)) => Ok(false),
+            Err(atomics::CasError::StoreError(e)) => {
+                Err(format!("Failed to swap one-time token: {:?}", e))
+            }
+        }
+    }
+}
+
+// =============================================================================
+// 使用済みの記録
+// =============================================================================
+
+/// 使い捨てのトークンの jti を使用済みとして記録する
+///
+/// 1. jti のキーの現在の値（使用済みとして扱う期限）を読む
+/// 2. 期限が現在時刻以降の場合は使用済み
+/// 3. キーがない・期限を過ぎた場合は valid_until を書き込む
+///    （読み込んだ後に別のリクエストが書き込んでいた場合は使用済み）
+///
+/// # 引数
+/// * `store` - 使用済みの jti の保存先
+/// * `jti` - jti クレーム（空でないこと）
+/// * `valid_until` - トークンを受け付ける期限（exp + leeway、Unix タイムスタンプ秒）
+/// * `now` - 現在時刻（Unix タイムスタンプ秒）
+///
+/// # 戻り値
+/// * `Ok(true)` - 初めての提示（使用済みとして記録した）
+/// * `Ok(false)` - 使用済み
+/// * `Err(String)` - ストアを読み書きできない、または記録した期限が不正な場合
+pub(crate) fn consume(
+    store: &impl CasStore,
+    jti: &str,
+    valid_until: u64,
+    now: u64,
+) -> Result<bool, String> {
+    let key = jti_key(jti);
+    let (current, handle) = store.current(&key)?;
+    if let Some(value) = current {
+        let used_until = std::str::from_utf8(&value)
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .ok_or_else(|| format!("Invalid one-time token record in {}", key))?;
+        if used_until >= now {
+            return Ok(false);
+        }
+    }
+    store.swap(handle, valid_until.to_string().as_bytes())
+}
+
+/// 使用済みの jti のキー（`auth:one-time:jti:<jti>`）
+fn jti_key(jti: &str) -> String {
+    format!("{}:jti:{}", KEY_PREFIX, jti)
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    /// メモリ上の保存先（キーごとの版で compare-and-swap を再現する）
+    #[derive(Default)]
+    struct MemoryStore {
+        values: Mutex<HashMap<String, (Vec<u8>, u64)>>,
+    }
+
+    impl MemoryStore {
+        fn value(&self, key: &str) -> Option<String> {
+            let values = self.values.lock().unwrap();
+            values
+                .get(key)
+                .map(|(value, _)| String::from_utf8(value.clone()).unwrap())
+        }
+    }
+
+    impl CasStore for MemoryStore {
+        /// (キー, current で読んだ版（キーがない場合は 0）)
+        type Handle = (String, u64);
+
+        fn current(&self, key: &str) -> Result<(Option<Vec<u8>>, Self::Handle), String> {
+            let values = self.values.lock().unwrap();
+            let (value, version) = match values.get(key) {
+                Some((value, version)) => (Some(value.clone()), *version),
+                None => (None, 0),
+            };
+            Ok((value, (key.to_string(), version)))
+        }
+
+        fn swap(&self, (key, version): Self::Handle, value: &[u8]) -> Result<bool, String> {
+            let mut values = self.values.lock().unwrap();
+            let current = values.get(&key).map_or(0, |(_, version)| *version);
+            if current != version {
+                return Ok(false);
+            }
+            values.insert(key, (value.to_vec(), version + 1));
+            Ok(true)
+        }
+    }
+
+    /// 最初の current の直後に、別のリクエストが同じトークンを提示する保存先
+    ///
+    /// 2 つのリクエストがどちらも「未使用」を読んだ後に書き込む状況を再現する。
+    struct RacingStore {
+        inner: MemoryStore,
+        raced: Cell<bool>,
+    }
+
+    impl CasStore for RacingStore {
+        type Handle = (String, u64);
+
+        fn current(&self, key: &str) -> Result<(Option<Vec<u8>>, Self::Handle), String> {
+            let current = self.inner.current(key)?;
+            if !self.raced.replace(true) {
+                // 別のリクエストは読み込みから書き込みまでを先に終える
+                assert_eq!(consume(&self.inner, JTI, VALID_UNTIL, NOW), Ok(true));
+            }
+            Ok(current)
+        }
+
+        fn swap(&self, handle: Self::Handle, value: &[u8]) -> Result<bool, String> {
+            self.inner.swap(handle, value)
+        }
+    }
+
+    const JTI: &str = "reset-1";
+    const NOW: u64 = 1_700_000_000;
+    const VALID_UNTIL: u64 = NOW + 900;
+    const KEY: &str = "auth:one-time:jti:reset-1";
+
+    #[test]
+    fn test_second_presentation_is_rejected() {
+        let store = MemoryStore::default();
+        assert_eq!(consume(&store, JTI, VALID_UNTIL, NOW), Ok(true));
+        assert_eq!(store.value(KEY).as_deref(), Some("1700000900"));
+
+        // 期限まで（期限と同じ秒を含む）は使用済み
+        for now in [NOW, NOW + 1, VALID_UNTIL] {
+            assert_eq!(consume(&store, JTI, VALID_UNTIL, now), Ok(false));
+        }
+
+        // 別の jti は影響を受けない
+        assert_eq!(consume(&store, "reset-2", VALID_UNTIL, NOW), Ok(true));
+    }
+
+    #[test]
+    fn test_expired_record_is_replaced() {
+        let store = MemoryStore::default();
+        assert_eq!(consume(&store, JTI, VALID_UNTIL, NOW), Ok(true));
+
+        // 期限を過ぎた記録は同じ jti の次のトークンで上書きする（TTL の代わり）
+        let later = VALID_UNTIL + 1;
+        assert_eq!(consume(&store, JTI, later + 900, later), Ok(true));
+        assert_eq!(store.value(KEY).as_deref(), Some("1700001801"));
+        assert_eq!(consume(&store, JTI, later + 900, later), Ok(false));
+    }
+
+    #[test]
+    fn test_concurrent_presentation_after_read_is_rejected() {
+        let store = RacingStore {
+            inner: MemoryStore::default(),
+            raced: Cell::new(false),
+        };
+
+        // 読み込んだ時点では未使用だが、書き込む前に別のリクエストが使用済みにした
+        assert_eq!(consume(&store, JTI, VALID_UNTIL, NOW), Ok(false));
+        assert_eq!(store.inner.value(KEY).as_deref(), Some("1700000900"));
+    }
+
+    #[test]
+    fn test_only_one_of_simultaneous_presentations_succeeds() {
+        let store = Arc::new(MemoryStore::default());
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let store = Arc::clone(&store);
+                thread::spawn(move || consume(store.as_ref(), JTI, VALID_UNTIL, NOW).unwrap())
+            })
+            .collect();
+        let accepted = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .filter(|&accepted| accepted)
+            .count();
+        assert_eq!(accepted, 1);
+    }
+
+    #[test]
+    fn test_invalid_record_is_an_error() {
+        let store = MemoryStore::default();
+        store
+            .values
+            .lock()
+            .unwrap()
+            .insert(KEY.to_string(), (b"not-a-time".to_vec(), 1));
+
+        let error = consume(&store, JTI, VALID_UNTIL, NOW).unwrap_err();
+        assert!(
+            error.starts_with("Invalid one-time token record"),
+            "{}",
+            error
+        );
+    }
+}
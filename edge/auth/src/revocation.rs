@@ -0,0 +1,246 @@
+//! # トークンの失効（ログアウト・パスワードの変更）
+//!
+//! JWT は exp まで有効なため、ログアウト・パスワードの変更の後も発行済みのトークンを使い続けられます。
+//! 署名・クレームの検証に成功したトークンについて、key-value ストア（`default`）の失効リストを確認し、
+//! 失効したトークンは "Token revoked" で拒否します。
+//!
+//! | 単位 | キー | 値 |
+//! |------|------|----|
+//! | トークン（jti） | `auth:revocation:jti:<jti>` | 任意（キーがあれば失効） |
+//! | ユーザー（sub） | `auth:revocation:user:<sub>:revoked_before` | Unix タイムスタンプ秒（この時刻より前に発行したトークンは失効） |
+//! | ユーザー（sub）のバージョン | `auth:revocation:user:<sub>:token_version` | 整数（ver クレームがこの値より小さいトークンは "Token superseded"） |
+//!
+//! ユーザー単位の失効時刻は、コア層のレスポンスの `X-Revoke-Tokens: all` を受けて gateway が書き込みます
+//! （gateway の revocation モジュール）。バージョンも同様に `X-Token-Version: <整数>` を受けて gateway が
+//! 書き込み、個々のトークンを記録せずに「全端末からのログアウト」を実現します。
+//! ver クレームのないトークンはバージョン 0 として扱います（バージョンを書き込むまでは従来どおり受け付ける）。
+//! コア層が発行するトークンには jti がないため、
+//! jti 単位の失効は ID プロバイダーが発行したトークンを運用者が個別に失効させる場合に使います。
+//! iat のないトークンは発行時刻を確認できないため、ユーザー単位の失効時刻がある場合は失効として扱います。
+//!
+//! Spin の key-value ストアには TTL がないため、失効リストのキーは削除されません。
+//! ストアを読めない場合に検証を通すか（fail-open）拒否するか（fail-closed、既定）は、
+//! Spin 変数 `jwt_revocation_fail_open` で決めます（lib の check_revocation）。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+use crate::jwks::Store;
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// 失効リストのキーのプレフィックス
+///
+/// gateway の revocation モジュールも同じキーに書き込む（変更する場合は両方を変更すること）。
+const KEY_PREFIX: &str = "auth:revocation";
+
+// =============================================================================
+// 失効の確認
+// =============================================================================
+
+/// トークンが失効しているか
+///
+/// 1. jti がある場合、jti のキーがあれば失効
+/// 2. ユーザーの失効時刻がある場合、iat がその時刻より前（iat がない場合を含む）なら失効
+///
+/// 失効時刻と同じ秒に発行したトークンは失効させない（ログアウト直後のログインで発行したトークンを拒否しない）。
+///
+/// # 引数
+/// * `store` - 失効リストの保存先
+/// * `user_id` - sub クレーム
+/// * `jti` - jti クレーム（ない場合は None）
+/// * `issued_at` - iat クレーム（ない場合は None）
+///
+/// # 戻り値
+/// * `Ok(bool)` - 失効している場合は true
+/// * `Err(String)` - ストアを読めない、またはユーザーの失効時刻が不正な場合
+pub(crate) fn is_revoked(
+    store: &impl Store,
+    user_id: &str,
+    jti: Option<&str>,
+    issued_at: Option<u64>,
+) -> Result<bool, String> {
+    if let Some(jti) = jti.filter(|jti| !jti.is_empty()) {
+        if store.get(&jti_key(jti))?.is_some() {
+            return Ok(true);
+        }
+    }
+
+    let key = user_key(user_id);
+    let Some(value) = store.get(&key)? else {
+        return Ok(false);
+    };
+    let revoked_before = std::str::from_utf8(&value)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .ok_or_else(|| format!("Invalid revocation time in {}", key))?;
+    Ok(issued_at.is_none_or(|iat| iat < revoked_before))
+}
+
+/// トークンがユーザーの新しいバージョンで置き換えられたか
+///
+/// ユーザーのバージョンがない場合は 0 として扱う（全てのトークンを受け付ける）。
+///
+/// # 引数
+/// * `store` - 失効リストの保存先
+/// * `user_id` - sub クレーム
+/// * `token_version` - ver クレーム（ない場合は 0）
+///
+/// # 戻り値
+/// * `Ok(bool)` - ver がユーザーのバージョンより小さい場合は true
+/// * `Err(String)` - ストアを読めない、またはユーザーのバージョンが不正な場合
+pub(crate) fn is_superseded(
+    store: &impl Store,
+    user_id: &str,
+    token_version: u64,
+) -> Result<bool, String> {
+    let key = version_key(user_id);
+    let Some(value) = store.get(&key)? else {
+        return Ok(false);
+    };
+    let version = std::str::from_utf8(&value)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .ok_or_else(|| format!("Invalid token version in {}", key))?;
+    Ok(token_version < version)
+}
+
+/// jti 単位の失効のキー（`auth:revocation:jti:<jti>`）
+fn jti_key(jti: &str) -> String {
+    format!("{}:jti:{}", KEY_PREFIX, jti)
+}
+
+/// ユーザー単位の失効時刻のキー（`auth:revocation:user:<sub>:revoked_before`）
+fn user_key(user_id: &str) -> String {
+    format!("{}:user:{}:revoked_before", KEY_PREFIX, user_id)
+}
+
+/// ユーザーのトークンのバージョンのキー（`auth:revocation:user:<sub>:token_version`）
+fn version_key(user_id: &str) -> String {
+    format!("{}:user:{}:token_version", KEY_PREFIX, user_id)
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::collections::HashMap;
+
+    /// メモリ上の保存先（fail を true にすると読み込みに失敗する）
+    #[derive(Default)]
+    struct MemoryStore {
+        values: HashMap<String, Vec<u8>>,
+        fail: Cell<bool>,
+    }
+
+    impl MemoryStore {
+        fn with(entries: &[(&str, &str)]) -> Self {
+            Self {
+                values: entries
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.as_bytes().to_vec()))
+                    .collect(),
+                fail: Cell::new(false),
+            }
+        }
+    }
+
+    impl Store for MemoryStore {
+        fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+            if self.fail.get() {
+                return Err(format!("Failed to get {}", key));
+            }
+            Ok(self.values.get(key).cloned())
+        }
+
+        fn set(&self, _key: &str, _value: &[u8]) -> Result<(), String> {
+            unreachable!("revocation never writes");
+        }
+    }
+
+    const USER: &str = "user-1";
+    const IAT: u64 = 1_700_000_000;
+
+    #[test]
+    fn test_not_revoked_without_entries() {
+        let store = MemoryStore::default();
+        assert_eq!(
+            is_revoked(&store, USER, Some("jti-1"), Some(IAT)),
+            Ok(false)
+        );
+        assert_eq!(is_revoked(&store, USER, None, None), Ok(false));
+    }
+
+    #[test]
+    fn test_revoked_by_jti() {
+        let store = MemoryStore::with(&[("auth:revocation:jti:jti-1", "1700003600")]);
+        assert_eq!(is_revoked(&store, USER, Some("jti-1"), Some(IAT)), Ok(true));
+        assert_eq!(
+            is_revoked(&store, USER, Some("jti-2"), Some(IAT)),
+            Ok(false)
+        );
+        assert_eq!(is_revoked(&store, USER, None, Some(IAT)), Ok(false));
+    }
+
+    #[test]
+    fn test_revoked_before_applies_to_older_tokens_only() {
+        let store =
+            MemoryStore::with(&[("auth:revocation:user:user-1:revoked_before", "1700000000")]);
+        assert_eq!(is_revoked(&store, USER, None, Some(IAT - 1)), Ok(true));
+        // ログアウトと同じ秒・後に発行したトークンは有効
+        assert_eq!(is_revoked(&store, USER, None, Some(IAT)), Ok(false));
+        assert_eq!(is_revoked(&store, USER, None, Some(IAT + 60)), Ok(false));
+        // 発行時刻を確認できないトークンは失効
+        assert_eq!(is_revoked(&store, USER, None, None), Ok(true));
+        // 他のユーザーには影響しない
+        assert_eq!(is_revoked(&store, "user-2", None, Some(IAT - 1)), Ok(false));
+    }
+
+    #[test]
+    fn test_superseded_by_newer_user_version() {
+        // バージョンがない場合は ver に関わらず有効
+        let store = MemoryStore::default();
+        assert_eq!(is_superseded(&store, USER, 0), Ok(false));
+
+        let store = MemoryStore::with(&[("auth:revocation:user:user-1:token_version", "2")]);
+        // ver のないトークン（0）・古いバージョンは置き換えられている
+        assert_eq!(is_superseded(&store, USER, 0), Ok(true));
+        assert_eq!(is_superseded(&store, USER, 1), Ok(true));
+        // 同じ・新しいバージョンは有効
+        assert_eq!(is_superseded(&store, USER, 2), Ok(false));
+        assert_eq!(is_superseded(&store, USER, 3), Ok(false));
+        // 他のユーザーには影響しない
+        assert_eq!(is_superseded(&store, "user-2", 0), Ok(false));
+
+        let store = MemoryStore::with(&[("auth:revocation:user:user-1:token_version", "-1")]);
+        assert_eq!(
+            is_superseded(&store, USER, 0),
+            Err("Invalid token version in auth:revocation:user:user-1:token_version".to_string())
+        );
+        store.fail.set(true);
+        assert!(is_superseded(&store, USER, 0).is_err());
+    }
+
+    #[test]
+    fn test_store_errors_and_invalid_times_are_errors() {
+        let store = MemoryStore::default();
+        store.fail.set(true);
+        assert!(is_revoked(&store, USER, Some("jti-1"), Some(IAT)).is_err());
+
+        let store =
+            MemoryStore::with(&[("auth:revocation:user:user-1:revoked_before", "yesterday")]);
+        assert_eq!(
+            is_revoked(&store, USER, None, Some(IAT)),
+            Err(
+                "Invalid revocation time in auth:revocation:user:user-1:revoked_before".to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,897 @@
+#![feature(prelude_import)]
+//! # 認可コンポーネント
+//!
+//! このコンポーネントは WIT インターフェース `demo:authz/policy` を実装し、
+//! ロール・HTTP メソッド・パスによる認可判定を提供します。
+//!
+//! ## 責務
+//! - Spin 変数 `authz_rules` からルール（JSON）を読み込み、検証する
+//! - リクエストのロール・メソッド・パスがいずれかのルールに一致するかを判定する
+//!
+//! ## ルールの形式
+//! ```json
+//! {
+//!   "*":     [{ "methods": ["*"], "path": "/api/todos" }],
+//!   "admin": [{ "methods": ["GET", "POST"], "path": "/api/admin/*" }]
+//! }
+//! ```
+//! - キー: ロール名（`"*"` は全ての認証済みユーザーに適用）
+//! - `methods`: 許可する HTTP メソッド（大文字小文字を区別しない、`"*"` は全メソッド）
+//! - `path`: パスプレフィックス（セグメント単位で比較）
+//!   - `/api/todos`: `/api/todos` 自身と `/api/todos/...` に一致（`/api/todos2` には一致しない）
+//!   - `/api/todos/*`: `/api/todos/...` のみに一致（`/api/todos` 自身には一致しない）
+//!   - `/`: 全てのパスに一致
+//!
+//! ## 障害時
+//! ルールが読み込めない・解析できない場合は拒否します（フェイルクローズ）。
+//!
+//! ## 使用方法
+//! gateway コンポーネントから WIT 経由で `allow` 関数が呼び出されます。
+#[macro_use]
+extern crate std;
+#[prelude_import]
+use std::prelude::rust_2021::*;
+use std::collections::HashMap;
+use serde::Deserialize;
+#[allow(dead_code, clippy::all)]
+pub mod exports {
+    pub mod demo {
+        pub mod authz {
+            /// =============================================================================
+            /// policy インターフェース
+            /// =============================================================================
+            /// 認可判定を提供するインターフェース
+            #[allow(dead_code, async_fn_in_trait, unused_imports, clippy::all)]
+            pub mod policy {
+                #[used]
+                #[doc(hidden)]
+                static __FORCE_SECTION_REF: fn() = super::super::super::super::__link_custom_section_describing_imports;
+                use super::super::super::super::_rt;
+                /// 認可の判定結果
+                pub struct Decision {
+                    /// リクエストを許可するかどうか
+                    pub allowed: bool,
+                    /// 拒否した理由（403 レスポンスのエラーメッセージに使用）
+                    /// 許可した場合は none
+                    pub reason: Option<_rt::String>,
+                }
+                #[automatically_derived]
+                impl ::core::clone::Clone for Decision {
+                    #[inline]
+                    fn clone(&self) -> Decision {
+                        Decision {
+                            allowed: ::core::clone::Clone::clone(&self.allowed),
+                            reason: ::core::clone::Clone::clone(&self.reason),
+                        }
+                    }
+                }
+                impl ::core::fmt::Debug for Decision {
+                    fn fmt(
+                        &self,
+                        f: &mut ::core::fmt::Formatter<'_>,
+                    ) -> ::core::fmt::Result {
+                        f.debug_struct("Decision")
+                            .field("allowed", &self.allowed)
+                            .field("reason", &self.reason)
+                            .finish()
+                    }
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case, unused_unsafe)]
+                pub unsafe fn _export_allow_cabi<T: Guest>(
+                    arg0: *mut u8,
+                    arg1: usize,
+                    arg2: *mut u8,
+                    arg3: usize,
+                    arg4: *mut u8,
+                    arg5: usize,
+                    arg6: *mut u8,
+                    arg7: usize,
+                ) -> *mut u8 {
+                    unsafe {
+                        _rt::run_ctors_once();
+                        let result7 = {
+                            let len0 = arg1;
+                            let bytes0 = _rt::Vec::from_raw_parts(
+                                arg0.cast(),
+                                len0,
+                                len0,
+                            );
+                            let base4 = arg2;
+                            let len4 = arg3;
+                            let mut result4 = _rt::Vec::with_capacity(len4);
+                            for i in 0..len4 {
+                                let base = base4
+                                    .add(i * (2 * ::core::mem::size_of::<*const u8>()));
+                                let e4 = {
+                                    let l1 = *base.add(0).cast::<*mut u8>();
+                                    let l2 = *base
+                                        .add(::core::mem::size_of::<*const u8>())
+                                        .cast::<usize>();
+                                    let len3 = l2;
+                                    let bytes3 = _rt::Vec::from_raw_parts(
+                                        l1.cast(),
+                                        len3,
+                                        len3,
+                                    );
+                                    _rt::string_lift(bytes3)
+                                };
+                                result4.push(e4);
+                            }
+                            _rt::cabi_dealloc(
+                                base4,
+                                len4 * (2 * ::core::mem::size_of::<*const u8>()),
+                                ::core::mem::size_of::<*const u8>(),
+                            );
+                            let len5 = arg5;
+                            let bytes5 = _rt::Vec::from_raw_parts(
+                                arg4.cast(),
+                                len5,
+                                len5,
+                            );
+                            let len6 = arg7;
+                            let bytes6 = _rt::Vec::from_raw_parts(
+                                arg6.cast(),
+                                len6,
+                                len6,
+                            );
+                            T::allow(
+                                _rt::string_lift(bytes0),
+                                result4,
+                                _rt::string_lift(bytes5),
+                                _rt::string_lift(bytes6),
+                            )
+                        };
+                        let ptr8 = (&raw mut _RET_AREA.0).cast::<u8>();
+                        let Decision { allowed: allowed9, reason: reason9 } = result7;
+                        *ptr8.add(0).cast::<u8>() = (match allowed9 {
+                            true => 1,
+                            false => 0,
+                        }) as u8;
+                        match reason9 {
+                            Some(e) => {
+                                *ptr8
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<u8>() = (1i32) as u8;
+                                let vec10 = (e.into_bytes()).into_boxed_slice();
+                                let ptr10 = vec10.as_ptr().cast::<u8>();
+                                let len10 = vec10.len();
+                                ::core::mem::forget(vec10);
+                                *ptr8
+                                    .add(3 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>() = len10;
+                                *ptr8
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>() = ptr10.cast_mut();
+                            }
+                            None => {
+                                *ptr8
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<u8>() = (0i32) as u8;
+                            }
+                        };
+                        ptr8
+                    }
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn __post_return_allow<T: Guest>(arg0: *mut u8) {
+                    unsafe {
+                        let l0 = i32::from(
+                            *arg0.add(::core::mem::size_of::<*const u8>()).cast::<u8>(),
+                        );
+                        match l0 {
+                            0 => {}
+                            _ => {
+                                let l1 = *arg0
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l2 = *arg0
+                                    .add(3 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                _rt::cabi_dealloc(l1, l2, 1);
+                            }
+                        }
+                    }
+                }
+                pub trait Guest {
+                    /// リクエストを許可するかどうかを判定する
+                    ///
+                    /// ルールは Spin 変数 `authz_rules`（JSON: ロール → メソッドとパスプレフィックスの許可リスト）
+                    /// から読み込みます。いずれかのロールのルールに一致すれば許可します。
+                    ///
+                    /// # 引数
+                    /// * `user-id` - 認証済みユーザーID（ログ用）
+                    /// * `roles` - トークンのロール（全ての認証済みユーザーには "*" のルールも適用）
+                    /// * `method` - HTTP メソッド（例: "GET"）
+                    /// * `path` - リクエストパス（例: "/api/todos/1"、クエリ文字列を含まない）
+                    ///
+                    /// # 戻り値
+                    /// * `decision` - 判定結果
+                    ///
+                    /// # 障害時
+                    /// ルールが読み込めない・解析できない場合は拒否します（フェイルクローズ）。
+                    /// 設定の誤りで全ての操作を許可してしまわないためです。
+                    #[allow(async_fn_in_trait)]
+                    fn allow(
+                        user_id: _rt::String,
+                        roles: _rt::Vec<_rt::String>,
+                        method: _rt::String,
+                        path: _rt::String,
+                    ) -> Decision;
+                }
+                #[doc(hidden)]
+                pub(crate) use __export_demo_authz_policy_0_1_0_cabi;
+                #[repr(align(4))]
+                struct _RetArea(
+                    [::core::mem::MaybeUninit<
+                        u8,
+                    >; 4 * ::core::mem::size_of::<*const u8>()],
+                );
+                static mut _RET_AREA: _RetArea = _RetArea(
+                    [::core::mem::MaybeUninit::uninit(); 4
+                        * ::core::mem::size_of::<*const u8>()],
+                );
+            }
+        }
+    }
+}
+mod _rt {
+    #![allow(dead_code, unused_imports, clippy::all)]
+    pub use alloc_crate::string::String;
+    pub fn run_ctors_once() {
+        wit_bindgen::rt::run_ctors_once();
+    }
+    pub use alloc_crate::vec::Vec;
+    pub unsafe fn string_lift(bytes: Vec<u8>) -> String {
+        if true {
+            String::from_utf8(bytes).unwrap()
+        } else {
+            unsafe { String::from_utf8_unchecked(bytes) }
+        }
+    }
+    pub unsafe fn cabi_dealloc(ptr: *mut u8, size: usize, align: usize) {
+        if size == 0 {
+            return;
+        }
+        unsafe {
+            let layout = alloc::Layout::from_size_align_unchecked(size, align);
+            alloc::dealloc(ptr, layout);
+        }
+    }
+    extern crate alloc as alloc_crate;
+    pub use alloc_crate::alloc;
+}
+#[doc(inline)]
+pub(crate) use __export_authz_world_impl as export;
+#[unsafe(
+    link_section = "component-type:wit-bindgen:0.51.0:demo:authz@0.1.0:authz-world:encoded world"
+)]
+#[doc(hidden)]
+#[allow(clippy::octal_escapes)]
+pub static __WIT_BINDGEN_COMPONENT_TYPE: [u8; 285] = *b"\
+\0asm\x0d\0\x01\0\0\x19\x16wit-component-encoding\x04\0\x07\x9b\x01\x01A\x02\x01\
+A\x02\x01B\x06\x01ks\x01r\x02\x07allowed\x7f\x06reason\0\x04\0\x08decision\x03\0\
+\x01\x01ps\x01@\x04\x07user-ids\x05roles\x03\x06methods\x04paths\0\x02\x04\0\x05\
+allow\x01\x04\x04\0\x17demo:authz/policy@0.1.0\x05\0\x04\0\x1cdemo:authz/authz-w\
+orld@0.1.0\x04\0\x0b\x11\x01\0\x0bauthz-world\x03\0\0\0G\x09producers\x01\x0cpro\
+cessed-by\x02\x0dwit-component\x070.244.0\x10wit-bindgen-rust\x060.51.0";
+#[inline(never)]
+#[doc(hidden)]
+pub fn __link_custom_section_describing_imports() {
+    wit_bindgen::rt::maybe_link_cabi_realloc();
+}
+const _: &[u8] = b"// =============================================================================\n// WIT \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe5\xae\x9a\xe7\xbe\xa9: \xe8\xaa\x8d\xe5\x8f\xaf\xef\xbc\x88\xe3\x83\x91\xe3\x83\xbc\xe3\x83\x9f\xe3\x83\x83\xe3\x82\xb7\xe3\x83\xa7\xe3\x83\xb3\xef\xbc\x89\n// =============================================================================\n//\n// \xe8\xaa\x8d\xe5\x8f\xaf\xe5\x88\xa4\xe5\xae\x9a\xe3\x82\x92\xe7\x8b\xac\xe7\xab\x8b\xe3\x81\x97\xe3\x81\x9f Wasm \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xef\xbc\x88authz\xef\xbc\x89\xe3\x81\xa8\xe3\x81\x97\xe3\x81\xa6\xe6\x8f\x90\xe4\xbe\x9b\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x9f\xe3\x82\x81\xe3\x81\xae\n// \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x81\xa7\xe3\x81\x99\xe3\x80\x82gateway \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe3\x82\xa4\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xe3\x81\x97\xe3\x80\x81\n// \xe8\xaa\x8d\xe8\xa8\xbc\xe6\x88\x90\xe5\x8a\x9f\xe5\xbe\x8c\xe3\x81\xae\xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x81\x94\xe3\x81\xa8\xe3\x81\xab\xe3\x80\x81\xe3\x83\xad\xe3\x83\xbc\xe3\x83\xab\xe3\x83\xbb\xe3\x83\xa1\xe3\x82\xbd\xe3\x83\x83\xe3\x83\x89\xe3\x83\xbb\xe3\x83\x91\xe3\x82\xb9\xe3\x81\x8b\xe3\x82\x89\xe8\xa8\xb1\xe5\x8f\xaf/\xe6\x8b\x92\xe5\x90\xa6\xe3\x82\x92\xe5\x88\xa4\xe5\xae\x9a\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n//\n// \xe9\x85\x8d\xe7\xbd\xae:\n// - demo:auth\xef\xbc\x88wit/auth.wit\xef\xbc\x89\xe3\x81\xae gateway-world \xe3\x81\x8b\xe3\x82\x89\xe3\x82\xa4\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x9f\xe3\x82\x81\xe3\x80\x81\n//   \xe4\xbe\x9d\xe5\xad\x98\xe3\x83\x91\xe3\x83\x83\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb8\xe3\x81\xa8\xe3\x81\x97\xe3\x81\xa6 wit/deps/authz/ \xe3\x81\xab\xe7\xbd\xae\xe3\x81\x84\xe3\x81\xa6\xe3\x81\x84\xe3\x81\xbe\xe3\x81\x99\n// - authz \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xaf\xe3\x81\x93\xe3\x81\xae\xe3\x83\x87\xe3\x82\xa3\xe3\x83\xac\xe3\x82\xaf\xe3\x83\x88\xe3\x83\xaa\xe3\x81\xa0\xe3\x81\x91\xe3\x82\x92\xe5\x8f\x82\xe7\x85\xa7\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\n//\n// =============================================================================\n\n// -----------------------------------------------------------------------------\n// \xe3\x83\x91\xe3\x83\x83\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb8\xe5\xae\xa3\xe8\xa8\x80\n// -----------------------------------------------------------------------------\npackage demo:authz@0.1.0;\n\n// =============================================================================\n// policy \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\n// =============================================================================\n\n/// \xe8\xaa\x8d\xe5\x8f\xaf\xe5\x88\xa4\xe5\xae\x9a\xe3\x82\x92\xe6\x8f\x90\xe4\xbe\x9b\xe3\x81\x99\xe3\x82\x8b\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\ninterface policy {\n    /// \xe8\xaa\x8d\xe5\x8f\xaf\xe3\x81\xae\xe5\x88\xa4\xe5\xae\x9a\xe7\xb5\x90\xe6\x9e\x9c\n    record decision {\n        /// \xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x82\x92\xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x8b\xe3\x81\xa9\xe3\x81\x86\xe3\x81\x8b\n        allowed: bool,\n\n        /// \xe6\x8b\x92\xe5\x90\xa6\xe3\x81\x97\xe3\x81\x9f\xe7\x90\x86\xe7\x94\xb1\xef\xbc\x88403 \xe3\x83\xac\xe3\x82\xb9\xe3\x83\x9d\xe3\x83\xb3\xe3\x82\xb9\xe3\x81\xae\xe3\x82\xa8\xe3\x83\xa9\xe3\x83\xbc\xe3\x83\xa1\xe3\x83\x83\xe3\x82\xbb\xe3\x83\xbc\xe3\x82\xb8\xe3\x81\xab\xe4\xbd\xbf\xe7\x94\xa8\xef\xbc\x89\n        /// \xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x97\xe3\x81\x9f\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf none\n        reason: option<string>,\n    }\n\n    /// \xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x82\x92\xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x8b\xe3\x81\xa9\xe3\x81\x86\xe3\x81\x8b\xe3\x82\x92\xe5\x88\xa4\xe5\xae\x9a\xe3\x81\x99\xe3\x82\x8b\n    ///\n    /// \xe3\x83\xab\xe3\x83\xbc\xe3\x83\xab\xe3\x81\xaf Spin \xe5\xa4\x89\xe6\x95\xb0 `authz_rules`\xef\xbc\x88JSON: \xe3\x83\xad\xe3\x83\xbc\xe3\x83\xab \xe2\x86\x92 \xe3\x83\xa1\xe3\x82\xbd\xe3\x83\x83\xe3\x83\x89\xe3\x81\xa8\xe3\x83\x91\xe3\x82\xb9\xe3\x83\x97\xe3\x83\xac\xe3\x83\x95\xe3\x82\xa3\xe3\x83\x83\xe3\x82\xaf\xe3\x82\xb9\xe3\x81\xae\xe8\xa8\xb1\xe5\x8f\xaf\xe3\x83\xaa\xe3\x82\xb9\xe3\x83\x88\xef\xbc\x89\n    /// \xe3\x81\x8b\xe3\x82\x89\xe8\xaa\xad\xe3\x81\xbf\xe8\xbe\xbc\xe3\x81\xbf\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\xe3\x81\x84\xe3\x81\x9a\xe3\x82\x8c\xe3\x81\x8b\xe3\x81\xae\xe3\x83\xad\xe3\x83\xbc\xe3\x83\xab\xe3\x81\xae\xe3\x83\xab\xe3\x83\xbc\xe3\x83\xab\xe3\x81\xab\xe4\xb8\x80\xe8\x87\xb4\xe3\x81\x99\xe3\x82\x8c\xe3\x81\xb0\xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    ///\n    /// # \xe5\xbc\x95\xe6\x95\xb0\n    /// * `user-id` - \xe8\xaa\x8d\xe8\xa8\xbc\xe6\xb8\x88\xe3\x81\xbf\xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbcID\xef\xbc\x88\xe3\x83\xad\xe3\x82\xb0\xe7\x94\xa8\xef\xbc\x89\n    /// * `roles` - \xe3\x83\x88\xe3\x83\xbc\xe3\x82\xaf\xe3\x83\xb3\xe3\x81\xae\xe3\x83\xad\xe3\x83\xbc\xe3\x83\xab\xef\xbc\x88\xe5\x85\xa8\xe3\x81\xa6\xe3\x81\xae\xe8\xaa\x8d\xe8\xa8\xbc\xe6\xb8\x88\xe3\x81\xbf\xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbc\xe3\x81\xab\xe3\x81\xaf \"*\" \xe3\x81\xae\xe3\x83\xab\xe3\x83\xbc\xe3\x83\xab\xe3\x82\x82\xe9\x81\xa9\xe7\x94\xa8\xef\xbc\x89\n    /// * `method` - HTTP \xe3\x83\xa1\xe3\x82\xbd\xe3\x83\x83\xe3\x83\x89\xef\xbc\x88\xe4\xbe\x8b: \"GET\"\xef\xbc\x89\n    /// * `path` - \xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x83\x91\xe3\x82\xb9\xef\xbc\x88\xe4\xbe\x8b: \"/api/todos/1\"\xe3\x80\x81\xe3\x82\xaf\xe3\x82\xa8\xe3\x83\xaa\xe6\x96\x87\xe5\xad\x97\xe5\x88\x97\xe3\x82\x92\xe5\x90\xab\xe3\x81\xbe\xe3\x81\xaa\xe3\x81\x84\xef\xbc\x89\n    ///\n    /// # \xe6\x88\xbb\xe3\x82\x8a\xe5\x80\xa4\n    /// * `decision` - \xe5\x88\xa4\xe5\xae\x9a\xe7\xb5\x90\xe6\x9e\x9c\n    ///\n    /// # \xe9\x9a\x9c\xe5\xae\xb3\xe6\x99\x82\n    /// \xe3\x83\xab\xe3\x83\xbc\xe3\x83\xab\xe3\x81\x8c\xe8\xaa\xad\xe3\x81\xbf\xe8\xbe\xbc\xe3\x82\x81\xe3\x81\xaa\xe3\x81\x84\xe3\x83\xbb\xe8\xa7\xa3\xe6\x9e\x90\xe3\x81\xa7\xe3\x81\x8d\xe3\x81\xaa\xe3\x81\x84\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf\xe6\x8b\x92\xe5\x90\xa6\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xef\xbc\x88\xe3\x83\x95\xe3\x82\xa7\xe3\x82\xa4\xe3\x83\xab\xe3\x82\xaf\xe3\x83\xad\xe3\x83\xbc\xe3\x82\xba\xef\xbc\x89\xe3\x80\x82\n    /// \xe8\xa8\xad\xe5\xae\x9a\xe3\x81\xae\xe8\xaa\xa4\xe3\x82\x8a\xe3\x81\xa7\xe5\x85\xa8\xe3\x81\xa6\xe3\x81\xae\xe6\x93\x8d\xe4\xbd\x9c\xe3\x82\x92\xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x97\xe3\x81\xa6\xe3\x81\x97\xe3\x81\xbe\xe3\x82\x8f\xe3\x81\xaa\xe3\x81\x84\xe3\x81\x9f\xe3\x82\x81\xe3\x81\xa7\xe3\x81\x99\xe3\x80\x82\n    allow: func(user-id: string, roles: list<string>, method: string, path: string) -> decision;\n}\n\n// =============================================================================\n// World \xe5\xae\x9a\xe7\xbe\xa9\n// =============================================================================\n\n/// authz \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe5\xae\x9f\xe8\xa3\x85\xe3\x81\x99\xe3\x82\x8b world\n///\n/// policy \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe3\x82\xa8\xe3\x82\xaf\xe3\x82\xb9\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\nworld authz-world {\n    export policy;\n}\n";
+use exports::demo::authz::policy::{Decision, Guest};
+/// ルールを読み込む Spin 変数名
+///
+/// spin.toml の `[component.gateway.variables]` で設定し、
+/// dependencies_inherit_configuration により authz コンポーネントに引き継がれます。
+const RULES_VARIABLE: &str = "authz_rules";
+/// 全ての認証済みユーザーに適用されるロール名
+///
+/// コア層が発行するトークンにはロールが含まれないため、
+/// ロールを持たないユーザーにもこのキーのルールを適用します。
+const ANY_ROLE: &str = "*";
+/// 全てのメソッドに一致するメソッド名
+const ANY_METHOD: &str = "*";
+/// ルールに指定できる HTTP メソッド
+///
+/// 綴りの誤り（例: "GTE"）を設定時のエラーにするため、既知のメソッドだけを受け付けます。
+const KNOWN_METHODS: [&str; 7] = [
+    "GET",
+    "POST",
+    "PUT",
+    "PATCH",
+    "DELETE",
+    "HEAD",
+    "OPTIONS",
+];
+/// ルールが使えない場合の拒否理由
+const POLICY_UNAVAILABLE: &str = "Forbidden: authorization policy unavailable";
+/// JSON 上の 1 件のルール
+#[serde(deny_unknown_fields)]
+struct RawRule {
+    /// 許可する HTTP メソッド
+    methods: Vec<String>,
+    /// パスプレフィックス
+    path: String,
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for RawRule {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::debug_struct_field2_finish(
+            f,
+            "RawRule",
+            "methods",
+            &self.methods,
+            "path",
+            &&self.path,
+        )
+    }
+}
+#[doc(hidden)]
+#[allow(
+    non_upper_case_globals,
+    unused_attributes,
+    unused_qualifications,
+    clippy::absolute_paths,
+)]
+const _: () = {
+    #[allow(unused_extern_crates, clippy::useless_attribute)]
+    extern crate serde as _serde;
+    #[automatically_derived]
+    impl<'de> _serde::Deserialize<'de> for RawRule {
+        fn deserialize<__D>(
+            __deserializer: __D,
+        ) -> _serde::__private228::Result<Self, __D::Error>
+        where
+            __D: _serde::Deserializer<'de>,
+        {
+            #[allow(non_camel_case_types)]
+            #[doc(hidden)]
+            enum __Field {
+                __field0,
+                __field1,
+            }
+            #[doc(hidden)]
+            struct __FieldVisitor;
+            #[automatically_derived]
+            impl<'de> _serde::de::Visitor<'de> for __FieldVisitor {
+                type Value = __Field;
+                fn expecting(
+                    &self,
+                    __formatter: &mut _serde::__private228::Formatter,
+                ) -> _serde::__private228::fmt::Result {
+                    _serde::__private228::Formatter::write_str(
+                        __formatter,
+                        "field identifier",
+                    )
+                }
+                fn visit_u64<__E>(
+                    self,
+                    __value: u64,
+                ) -> _serde::__private228::Result<Self::Value, __E>
+                where
+                    __E: _serde::de::Error,
+                {
+                    match __value {
+                        0u64 => _serde::__private228::Ok(__Field::__field0),
+                        1u64 => _serde::__private228::Ok(__Field::__field1),
+                        _ => {
+                            _serde::__private228::Err(
+                                _serde::de::Error::invalid_value(
+                                    _serde::de::Unexpected::Unsigned(__value),
+                                    &"field index 0 <= i < 2",
+                                ),
+                            )
+                        }
+                    }
+                }
+                fn visit_str<__E>(
+                    self,
+                    __value: &str,
+                ) -> _serde::__private228::Result<Self::Value, __E>
+                where
+                    __E: _serde::de::Error,
+                {
+                    match __value {
+                        "methods" => _serde::__private228::Ok(__Field::__field0),
+                        "path" => _serde::__private228::Ok(__Field::__field1),
+                        _ => {
+                            _serde::__private228::Err(
+                                _serde::de::Error::unknown_field(__value, FIELDS),
+                            )
+                        }
+                    }
+                }
+                fn visit_bytes<__E>(
+                    self,
+                    __value: &[u8],
+                ) -> _serde::__private228::Result<Self::Value, __E>
+                where
+                    __E: _serde::de::Error,
+                {
+                    match __value {
+                        b"methods" => _serde::__private228::Ok(__Field::__field0),
+                        b"path" => _serde::__private228::Ok(__Field::__field1),
+                        _ => {
+                            let __value = &_serde::__private228::from_utf8_lossy(
+                                __value,
+                            );
+                            _serde::__private228::Err(
+                                _serde::de::Error::unknown_field(__value, FIELDS),
+                            )
+                        }
+                    }
+                }
+            }
+            #[automatically_derived]
+            impl<'de> _serde::Deserialize<'de> for __Field {
+                #[inline]
+                fn deserialize<__D>(
+                    __deserializer: __D,
+                ) -> _serde::__private228::Result<Self, __D::Error>
+                where
+                    __D: _serde::Deserializer<'de>,
+                {
+                    _serde::Deserializer::deserialize_identifier(
+                        __deserializer,
+                        __FieldVisitor,
+                    )
+                }
+            }
+            #[doc(hidden)]
+            struct __Visitor<'de> {
+                marker: _serde::__private228::PhantomData<RawRule>,
+                lifetime: _serde::__private228::PhantomData<&'de ()>,
+            }
+            #[automatically_derived]
+            impl<'de> _serde::de::Visitor<'de> for __Visitor<'de> {
+                type Value = RawRule;
+                fn expecting(
+                    &self,
+                    __formatter: &mut _serde::__private228::Formatter,
+                ) -> _serde::__private228::fmt::Result {
+                    _serde::__private228::Formatter::write_str(
+                        __formatter,
+                        "struct RawRule",
+                    )
+                }
+                #[inline]
+                fn visit_seq<__A>(
+                    self,
+                    mut __seq: __A,
+                ) -> _serde::__private228::Result<Self::Value, __A::Error>
+                where
+                    __A: _serde::de::SeqAccess<'de>,
+                {
+                    let __field0 = match _serde::de::SeqAccess::next_element::<
+                        Vec<String>,
+                    >(&mut __seq)? {
+                        _serde::__private228::Some(__value) => __value,
+                        _serde::__private228::None => {
+                            return _serde::__private228::Err(
+                                _serde::de::Error::invalid_length(
+                                    0usize,
+                                    &"struct RawRule with 2 elements",
+                                ),
+                            );
+                        }
+                    };
+                    let __field1 = match _serde::de::SeqAccess::next_element::<
+                        String,
+                    >(&mut __seq)? {
+                        _serde::__private228::Some(__value) => __value,
+                        _serde::__private228::None => {
+                            return _serde::__private228::Err(
+                                _serde::de::Error::invalid_length(
+                                    1usize,
+                                    &"struct RawRule with 2 elements",
+                                ),
+                            );
+                        }
+                    };
+                    _serde::__private228::Ok(RawRule {
+                        methods: __field0,
+                        path: __field1,
+                    })
+                }
+                #[inline]
+                fn visit_map<__A>(
+                    self,
+                    mut __map: __A,
+                ) -> _serde::__private228::Result<Self::Value, __A::Error>
+                where
+                    __A: _serde::de::MapAccess<'de>,
+                {
+                    let mut __field0: _serde::__private228::Option<Vec<String>> = _serde::__private228::None;
+                    let mut __field1: _serde::__private228::Option<String> = _serde::__private228::None;
+                    while let _serde::__private228::Some(__key) = _serde::de::MapAccess::next_key::<
+                        __Field,
+                    >(&mut __map)? {
+                        match __key {
+                            __Field::__field0 => {
+                                if _serde::__private228::Option::is_some(&__field0) {
+                                    return _serde::__private228::Err(
+                                        <__A::Error as _serde::de::Error>::duplicate_field(
+                                            "methods",
+                                        ),
+                                    );
+                                }
+                                __field0 = _serde::__private228::Some(
+                                    _serde::de::MapAccess::next_value::<
+                                        Vec<String>,
+                                    >(&mut __map)?,
+                                );
+                            }
+                            __Field::__field1 => {
+                                if _serde::__private228::Option::is_some(&__field1) {
+                                    return _serde::__private228::Err(
+                                        <__A::Error as _serde::de::Error>::duplicate_field("path"),
+                                    );
+                                }
+                                __field1 = _serde::__private228::Some(
+                                    _serde::de::MapAccess::next_value::<String>(&mut __map)?,
+                                );
+                            }
+                        }
+                    }
+                    let __field0 = match __field0 {
+                        _serde::__private228::Some(__field0) => __field0,
+                        _serde::__private228::None => {
+                            _serde::__private228::de::missing_field("methods")?
+                        }
+                    };
+                    let __field1 = match __field1 {
+                        _serde::__private228::Some(__field1) => __field1,
+                        _serde::__private228::None => {
+                            _serde::__private228::de::missing_field("path")?
+                        }
+                    };
+                    _serde::__private228::Ok(RawRule {
+                        methods: __field0,
+                        path: __field1,
+                    })
+                }
+            }
+            #[doc(hidden)]
+            const FIELDS: &'static [&'static str] = &["methods", "path"];
+            _serde::Deserializer::deserialize_struct(
+                __deserializer,
+                "RawRule",
+                FIELDS,
+                __Visitor {
+                    marker: _serde::__private228::PhantomData::<RawRule>,
+                    lifetime: _serde::__private228::PhantomData,
+                },
+            )
+        }
+    }
+};
+/// 検証済みの 1 件のルール
+struct Rule {
+    /// 許可する HTTP メソッド（大文字に正規化済み、`"*"` を含む場合は全メソッド）
+    methods: Vec<String>,
+    /// パスプレフィックス（`/` で始まる）
+    path: String,
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for Rule {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::debug_struct_field2_finish(
+            f,
+            "Rule",
+            "methods",
+            &self.methods,
+            "path",
+            &&self.path,
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::clone::Clone for Rule {
+    #[inline]
+    fn clone(&self) -> Rule {
+        Rule {
+            methods: ::core::clone::Clone::clone(&self.methods),
+            path: ::core::clone::Clone::clone(&self.path),
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for Rule {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for Rule {
+    #[inline]
+    fn eq(&self, other: &Rule) -> bool {
+        self.methods == other.methods && self.path == other.path
+    }
+}
+/// ロールごとのルール
+struct Policy {
+    rules: HashMap<String, Vec<Rule>>,
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for Policy {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Formatter::debug_struct_field1_finish(
+            f,
+            "Policy",
+            "rules",
+            &&self.rules,
+        )
+    }
+}
+#[automatically_derived]
+impl ::core::default::Default for Policy {
+    #[inline]
+    fn default() -> Policy {
+        Policy {
+            rules: ::core::default::Default::default(),
+        }
+    }
+}
+/// 認可コンポーネントの実装構造体
+struct AuthzComponent;
+impl Guest for AuthzComponent {
+    /// リクエストを許可するかどうかを判定する
+    ///
+    /// ルールが読み込めない・解析できない場合は拒否します（フェイルクローズ）。
+    fn allow(
+        user_id: String,
+        roles: Vec<String>,
+        method: String,
+        path: String,
+    ) -> Decision {
+        let policy = spin_sdk::variables::get(RULES_VARIABLE)
+            .map_err(|e| ::alloc::__export::must_use({
+                ::alloc::fmt::format(
+                    format_args!("Failed to read variable {0}: {1}", RULES_VARIABLE, e),
+                )
+            }))
+            .and_then(|json| parse_rules(&json));
+        let decision = match policy {
+            Ok(policy) => evaluate(&policy, &roles, &method, &path),
+            Err(e) => {
+                {
+                    ::std::io::_print(format_args!("[authz] {0}, denying request\n", e));
+                };
+                deny(POLICY_UNAVAILABLE.to_string())
+            }
+        };
+        if !decision.allowed {
+            {
+                ::std::io::_print(
+                    format_args!(
+                        "[authz] Denied: user_id={0} roles={1:?} {2} {3}\n", user_id,
+                        roles, method, path
+                    ),
+                );
+            };
+        }
+        decision
+    }
+}
+const _: () = {
+    #[unsafe(export_name = "demo:authz/policy@0.1.0#allow")]
+    unsafe extern "C" fn export_allow(
+        arg0: *mut u8,
+        arg1: usize,
+        arg2: *mut u8,
+        arg3: usize,
+        arg4: *mut u8,
+        arg5: usize,
+        arg6: *mut u8,
+        arg7: usize,
+    ) -> *mut u8 {
+        unsafe {
+            self::exports::demo::authz::policy::_export_allow_cabi::<
+                AuthzComponent,
+            >(arg0, arg1, arg2, arg3, arg4, arg5, arg6, arg7)
+        }
+    }
+    #[unsafe(export_name = "cabi_post_demo:authz/policy@0.1.0#allow")]
+    unsafe extern "C" fn _post_return_allow(arg0: *mut u8) {
+        unsafe {
+            self::exports::demo::authz::policy::__post_return_allow::<
+                AuthzComponent,
+            >(arg0)
+        }
+    }
+};
+/// ルールの JSON を解析し、検証する
+///
+/// # 引数
+/// * `json` - ロール → ルールの配列の JSON オブジェクト
+///
+/// # 戻り値
+/// * `Ok(Policy)` - 検証済みのルール
+/// * `Err(String)` - JSON の形式・ロール名・メソッド・パスが不正な場合のエラーメッセージ
+fn parse_rules(json: &str) -> Result<Policy, String> {
+    let raw: HashMap<String, Vec<RawRule>> = serde_json::from_str(json)
+        .map_err(|e| ::alloc::__export::must_use({
+            ::alloc::fmt::format(format_args!("Invalid authz rules: {0}", e))
+        }))?;
+    let mut rules = HashMap::with_capacity(raw.len());
+    for (role, raw_rules) in raw {
+        if role.trim().is_empty() {
+            return Err("Invalid authz rules: role name must not be empty".to_string());
+        }
+        let parsed = raw_rules
+            .into_iter()
+            .map(|rule| {
+                parse_rule(rule)
+                    .map_err(|e| ::alloc::__export::must_use({
+                        ::alloc::fmt::format(
+                            format_args!(
+                                "Invalid authz rules for role {0}: {1}", role, e
+                            ),
+                        )
+                    }))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        rules.insert(role, parsed);
+    }
+    Ok(Policy { rules })
+}
+/// 1 件のルールを検証する
+///
+/// メソッドは大文字に正規化し、パスは `/` 始まり・`.` / `..` セグメントなし・
+/// ワイルドカードは末尾の `/*` のみを許可します。
+fn parse_rule(raw: RawRule) -> Result<Rule, String> {
+    if raw.methods.is_empty() {
+        return Err(
+            ::alloc::__export::must_use({
+                ::alloc::fmt::format(
+                    format_args!("methods must not be empty (path: {0})", raw.path),
+                )
+            }),
+        );
+    }
+    let methods = raw
+        .methods
+        .iter()
+        .map(|method| {
+            let method = method.trim().to_ascii_uppercase();
+            if method == ANY_METHOD || KNOWN_METHODS.contains(&method.as_str()) {
+                Ok(method)
+            } else {
+                Err(
+                    ::alloc::__export::must_use({
+                        ::alloc::fmt::format(
+                            format_args!("unknown method {0:?}", method),
+                        )
+                    }),
+                )
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let path = raw.path;
+    if !path.starts_with('/') {
+        return Err(
+            ::alloc::__export::must_use({
+                ::alloc::fmt::format(
+                    format_args!("path must start with \'/\': {0:?}", path),
+                )
+            }),
+        );
+    }
+    if has_dot_segment(&path) {
+        return Err(
+            ::alloc::__export::must_use({
+                ::alloc::fmt::format(
+                    format_args!(
+                        "path must not contain \'.\' or \'..\' segments: {0:?}", path
+                    ),
+                )
+            }),
+        );
+    }
+    let base = path.strip_suffix("/*").unwrap_or(&path);
+    if base.contains('*') {
+        return Err(
+            ::alloc::__export::must_use({
+                ::alloc::fmt::format(
+                    format_args!(
+                        "\'*\' is only allowed as a trailing \'/*\': {0:?}", path
+                    ),
+                )
+            }),
+        );
+    }
+    Ok(Rule { methods, path })
+}
+/// リクエストがいずれかのルールに一致するかを判定する
+///
+/// トークンのロールに加えて、`"*"` のルールを全ての認証済みユーザーに適用します。
+/// `.` / `..` セグメントを含むパスは、プレフィックスの比較をすり抜けられるため常に拒否します。
+///
+/// # 引数
+/// * `policy` - 検証済みのルール
+/// * `roles` - トークンのロール
+/// * `method` - HTTP メソッド（大文字小文字を区別しない）
+/// * `path` - リクエストパス
+///
+/// # 戻り値
+/// * `Decision` - 判定結果（拒否した場合は理由付き）
+fn evaluate(policy: &Policy, roles: &[String], method: &str, path: &str) -> Decision {
+    if !path.starts_with('/') || has_dot_segment(path) {
+        return deny(
+            ::alloc::__export::must_use({
+                ::alloc::fmt::format(
+                    format_args!("Forbidden: invalid request path {0}", path),
+                )
+            }),
+        );
+    }
+    let allowed = roles
+        .iter()
+        .map(String::as_str)
+        .chain(std::iter::once(ANY_ROLE))
+        .filter_map(|role| policy.rules.get(role))
+        .flatten()
+        .any(|rule| {
+            method_matches(&rule.methods, method) && path_matches(&rule.path, path)
+        });
+    if allowed {
+        Decision {
+            allowed: true,
+            reason: None,
+        }
+    } else {
+        deny(
+            ::alloc::__export::must_use({
+                ::alloc::fmt::format(
+                    format_args!(
+                        "Forbidden: {0} {1} is not allowed", method.to_ascii_uppercase(),
+                        path
+                    ),
+                )
+            }),
+        )
+    }
+}
+/// メソッドが許可リストに含まれるかを判定する（`"*"` は全メソッド）
+fn method_matches(methods: &[String], method: &str) -> bool {
+    methods
+        .iter()
+        .any(|allowed| allowed == ANY_METHOD || allowed.eq_ignore_ascii_case(method))
+}
+/// パスがパスプレフィックスに一致するかを判定する
+///
+/// 比較はセグメント単位です（`/api/todos` は `/api/todos2` に一致しない）。
+/// 末尾の `/*` はサブパスのみに一致します。
+fn path_matches(pattern: &str, path: &str) -> bool {
+    if let Some(base) = pattern.strip_suffix("/*") {
+        return path
+            .strip_prefix(base)
+            .is_some_and(|rest| rest.len() > 1 && rest.starts_with('/'));
+    }
+    let base = pattern.trim_end_matches('/');
+    if base.is_empty() {
+        return true;
+    }
+    path.strip_prefix(base).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+/// パスに `.` / `..` セグメント（パーセントエンコードを含む）があるかを判定する
+///
+/// 例: `/api/todos/../admin` はプレフィックス `/api/todos` に一致するが、
+/// 正規化されると `/api/admin` になるため拒否する
+fn has_dot_segment(path: &str) -> bool {
+    path.split('/')
+        .any(|segment| {
+            let decoded = segment.to_ascii_lowercase().replace("%2e", ".");
+            decoded == "." || decoded == ".."
+        })
+}
+/// 拒否の判定結果を作る
+fn deny(reason: String) -> Decision {
+    Decision {
+        allowed: false,
+        reason: Some(reason),
+    }
+}
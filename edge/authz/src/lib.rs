@@ -0,0 +1,509 @@
+//! # 認可コンポーネント
+//!
+//! このコンポーネントは WIT インターフェース `demo:authz/policy` を実装し、
+//! ロール・HTTP メソッド・パスによる認可判定を提供します。
+//!
+//! ## 責務
+//! - Spin 変数 `authz_rules` からルール（JSON）を読み込み、検証する
+//! - リクエストのロール・メソッド・パスがいずれかのルールに一致するかを判定する
+//!
+//! ## ルールの形式
+//! ```json
+//! {
+//!   "*":     [{ "methods": ["*"], "path": "/api/todos" }],
+//!   "admin": [{ "methods": ["GET", "POST"], "path": "/api/admin/*" }]
+//! }
+//! ```
+//! - キー: ロール名（`"*"` は全ての認証済みユーザーに適用）
+//! - `methods`: 許可する HTTP メソッド（大文字小文字を区別しない、`"*"` は全メソッド）
+//! - `path`: パスプレフィックス（セグメント単位で比較）
+//!   - `/api/todos`: `/api/todos` 自身と `/api/todos/...` に一致（`/api/todos2` には一致しない）
+//!   - `/api/todos/*`: `/api/todos/...` のみに一致（`/api/todos` 自身には一致しない）
+//!   - `/`: 全てのパスに一致
+//!
+//! ## 障害時
+//! ルールが読み込めない・解析できない場合は拒否します（フェイルクローズ）。
+//!
+//! ## 使用方法
+//! gateway コンポーネントから WIT 経由で `allow` 関数が呼び出されます。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+// ロールごとのルールの保持
+use std::collections::HashMap;
+
+// JSON デシリアライズ用
+use serde::Deserialize;
+
+// =============================================================================
+// WIT バインディングの生成
+// =============================================================================
+
+// wit_bindgen マクロで WIT インターフェースから Rust コードを自動生成
+// world: このコンポーネントが実装する world 名（authz-world）
+// path: demo:authz パッケージのディレクトリへの相対パス
+wit_bindgen::generate!({
+    world: "authz-world",
+    path: "../wit/deps/authz",
+});
+
+// wit-bindgen が生成した型をインポート
+// Guest: WIT の export を実装するためのトレイト
+// Decision: 判定結果を表す構造体（WIT の decision レコードに対応）
+use exports::demo::authz::policy::{Decision, Guest};
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// ルールを読み込む Spin 変数名
+///
+/// spin.toml の `[component.gateway.variables]` で設定し、
+/// dependencies_inherit_configuration により authz コンポーネントに引き継がれます。
+const RULES_VARIABLE: &str = "authz_rules";
+
+/// 全ての認証済みユーザーに適用されるロール名
+///
+/// コア層が発行するトークンにはロールが含まれないため、
+/// ロールを持たないユーザーにもこのキーのルールを適用します。
+const ANY_ROLE: &str = "*";
+
+/// 全てのメソッドに一致するメソッド名
+const ANY_METHOD: &str = "*";
+
+/// ルールに指定できる HTTP メソッド
+///
+/// 綴りの誤り（例: "GTE"）を設定時のエラーにするため、既知のメソッドだけを受け付けます。
+const KNOWN_METHODS: [&str; 7] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+/// ルールが使えない場合の拒否理由
+const POLICY_UNAVAILABLE: &str = "Forbidden: authorization policy unavailable";
+
+// =============================================================================
+// ルール
+// =============================================================================
+
+/// JSON 上の 1 件のルール
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRule {
+    /// 許可する HTTP メソッド
+    methods: Vec<String>,
+
+    /// パスプレフィックス
+    path: String,
+}
+
+/// 検証済みの 1 件のルール
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    /// 許可する HTTP メソッド（大文字に正規化済み、`"*"` を含む場合は全メソッド）
+    methods: Vec<String>,
+
+    /// パスプレフィックス（`/` で始まる）
+    path: String,
+}
+
+/// ロールごとのルール
+#[derive(Debug, Default)]
+struct Policy {
+    rules: HashMap<String, Vec<Rule>>,
+}
+
+// =============================================================================
+// コンポーネント実装
+// =============================================================================
+
+/// 認可コンポーネントの実装構造体
+struct AuthzComponent;
+
+impl Guest for AuthzComponent {
+    /// リクエストを許可するかどうかを判定する
+    ///
+    /// ルールが読み込めない・解析できない場合は拒否します（フェイルクローズ）。
+    fn allow(user_id: String, roles: Vec<String>, method: String, path: String) -> Decision {
+        let policy = spin_sdk::variables::get(RULES_VARIABLE)
+            .map_err(|e| format!("Failed to read variable {}: {}", RULES_VARIABLE, e))
+            .and_then(|json| parse_rules(&json));
+
+        let decision = match policy {
+            Ok(policy) => evaluate(&policy, &roles, &method, &path),
+            Err(e) => {
+                println!("[authz] {}, denying request", e);
+                deny(POLICY_UNAVAILABLE.to_string())
+            }
+        };
+
+        if !decision.allowed {
+            println!(
+                "[authz] Denied: user_id={} roles={:?} {} {}",
+                user_id, roles, method, path
+            );
+        }
+
+        decision
+    }
+}
+
+// wit-bindgen マクロでコンポーネントをエクスポート
+export!(AuthzComponent);
+
+// =============================================================================
+// ルールの解析
+// =============================================================================
+
+/// ルールの JSON を解析し、検証する
+///
+/// # 引数
+/// * `json` - ロール → ルールの配列の JSON オブジェクト
+///
+/// # 戻り値
+/// * `Ok(Policy)` - 検証済みのルール
+/// * `Err(String)` - JSON の形式・ロール名・メソッド・パスが不正な場合のエラーメッセージ
+fn parse_rules(json: &str) -> Result<Policy, String> {
+    let raw: HashMap<String, Vec<RawRule>> =
+        serde_json::from_str(json).map_err(|e| format!("Invalid authz rules: {}", e))?;
+
+    let mut rules = HashMap::with_capacity(raw.len());
+    for (role, raw_rules) in raw {
+        if role.trim().is_empty() {
+            return Err("Invalid authz rules: role name must not be empty".to_string());
+        }
+
+        let parsed = raw_rules
+            .into_iter()
+            .map(|rule| {
+                parse_rule(rule)
+                    .map_err(|e| format!("Invalid authz rules for role {}: {}", role, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        rules.insert(role, parsed);
+    }
+
+    Ok(Policy { rules })
+}
+
+/// 1 件のルールを検証する
+///
+/// メソッドは大文字に正規化し、パスは `/` 始まり・`.` / `..` セグメントなし・
+/// ワイルドカードは末尾の `/*` のみを許可します。
+fn parse_rule(raw: RawRule) -> Result<Rule, String> {
+    if raw.methods.is_empty() {
+        return Err(format!("methods must not be empty (path: {})", raw.path));
+    }
+
+    let methods = raw
+        .methods
+        .iter()
+        .map(|method| {
+            let method = method.trim().to_ascii_uppercase();
+            if method == ANY_METHOD || KNOWN_METHODS.contains(&method.as_str()) {
+                Ok(method)
+            } else {
+                Err(format!("unknown method {:?}", method))
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let path = raw.path;
+    if !path.starts_with('/') {
+        return Err(format!("path must start with '/': {:?}", path));
+    }
+    if has_dot_segment(&path) {
+        return Err(format!(
+            "path must not contain '.' or '..' segments: {:?}",
+            path
+        ));
+    }
+    let base = path.strip_suffix("/*").unwrap_or(&path);
+    if base.contains('*') {
+        return Err(format!(
+            "'*' is only allowed as a trailing '/*': {:?}",
+            path
+        ));
+    }
+
+    Ok(Rule { methods, path })
+}
+
+// =============================================================================
+// 判定ロジック
+// =============================================================================
+
+/// リクエストがいずれかのルールに一致するかを判定する
+///
+/// トークンのロールに加えて、`"*"` のルールを全ての認証済みユーザーに適用します。
+/// `.` / `..` セグメントを含むパスは、プレフィックスの比較をすり抜けられるため常に拒否します。
+///
+/// # 引数
+/// * `policy` - 検証済みのルール
+/// * `roles` - トークンのロール
+/// * `method` - HTTP メソッド（大文字小文字を区別しない）
+/// * `path` - リクエストパス
+///
+/// # 戻り値
+/// * `Decision` - 判定結果（拒否した場合は理由付き）
+fn evaluate(policy: &Policy, roles: &[String], method: &str, path: &str) -> Decision {
+    if !path.starts_with('/') || has_dot_segment(path) {
+        return deny(format!("Forbidden: invalid request path {}", path));
+    }
+
+    let allowed = roles
+        .iter()
+        .map(String::as_str)
+        .chain(std::iter::once(ANY_ROLE))
+        .filter_map(|role| policy.rules.get(role))
+        .flatten()
+        .any(|rule| method_matches(&rule.methods, method) && path_matches(&rule.path, path));
+
+    if allowed {
+        Decision {
+            allowed: true,
+            reason: None,
+        }
+    } else {
+        deny(format!(
+            "Forbidden: {} {} is not allowed",
+            method.to_ascii_uppercase(),
+            path
+        ))
+    }
+}
+
+/// メソッドが許可リストに含まれるかを判定する（`"*"` は全メソッド）
+fn method_matches(methods: &[String], method: &str) -> bool {
+    methods
+        .iter()
+        .any(|allowed| allowed == ANY_METHOD || allowed.eq_ignore_ascii_case(method))
+}
+
+/// パスがパスプレフィックスに一致するかを判定する
+///
+/// 比較はセグメント単位です（`/api/todos` は `/api/todos2` に一致しない）。
+/// 末尾の `/*` はサブパスのみに一致します。
+fn path_matches(pattern: &str, path: &str) -> bool {
+    // "/api/todos/*": "/api/todos/..." のみ（"/api/todos" と "/api/todos/" は含まない）
+    if let Some(base) = pattern.strip_suffix("/*") {
+        return path
+            .strip_prefix(base)
+            .is_some_and(|rest| rest.len() > 1 && rest.starts_with('/'));
+    }
+
+    // "/api/todos" と "/api/todos/" は同じプレフィックスとして扱う
+    let base = pattern.trim_end_matches('/');
+    if base.is_empty() {
+        return true;
+    }
+
+    path.strip_prefix(base)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// パスに `.` / `..` セグメント（パーセントエンコードを含む）があるかを判定する
+///
+/// 例: `/api/todos/../admin` はプレフィックス `/api/todos` に一致するが、
+/// 正規化されると `/api/admin` になるため拒否する
+fn has_dot_segment(path: &str) -> bool {
+    path.split('/').any(|segment| {
+        let decoded = segment.to_ascii_lowercase().replace("%2e", ".");
+        decoded == "." || decoded == ".."
+    })
+}
+
+/// 拒否の判定結果を作る
+fn deny(reason: String) -> Decision {
+    Decision {
+        allowed: false,
+        reason: Some(reason),
+    }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = r#"{
+        "*": [
+            { "methods": ["*"], "path": "/api/todos" },
+            { "methods": ["get"], "path": "/api/users/me" }
+        ],
+        "admin": [
+            { "methods": ["GET", "POST"], "path": "/api/admin/*" }
+        ],
+        "auditor": [
+            { "methods": ["GET"], "path": "/" }
+        ]
+    }"#;
+
+    fn policy() -> Policy {
+        parse_rules(RULES).unwrap()
+    }
+
+    fn roles(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn allowed(roles_: &[&str], method: &str, path: &str) -> bool {
+        evaluate(&policy(), &roles(roles_), method, path).allowed
+    }
+
+    #[test]
+    fn test_parse_rules_normalizes_methods() {
+        let policy = policy();
+
+        assert_eq!(policy.rules.len(), 3);
+        assert_eq!(
+            policy.rules["*"][1],
+            Rule {
+                methods: vec!["GET".to_string()],
+                path: "/api/users/me".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_rules_rejects_invalid_rules() {
+        for json in [
+            // JSON として不正・形が違う
+            "",
+            "not json",
+            "[]",
+            r#"{"*": {"methods": ["GET"], "path": "/api"}}"#,
+            // フィールドの不足・未知のフィールド
+            r#"{"*": [{"methods": ["GET"]}]}"#,
+            r#"{"*": [{"methods": ["GET"], "path": "/api", "paths": ["/x"]}]}"#,
+            // ロール名が空
+            r#"{" ": [{"methods": ["GET"], "path": "/api"}]}"#,
+            // メソッドが空・未知
+            r#"{"*": [{"methods": [], "path": "/api"}]}"#,
+            r#"{"*": [{"methods": ["GTE"], "path": "/api"}]}"#,
+            // パスが不正
+            r#"{"*": [{"methods": ["GET"], "path": "api"}]}"#,
+            r#"{"*": [{"methods": ["GET"], "path": "/api/../admin"}]}"#,
+            r#"{"*": [{"methods": ["GET"], "path": "/api/*/items"}]}"#,
+            r#"{"*": [{"methods": ["GET"], "path": "/api/todos*"}]}"#,
+        ] {
+            assert!(parse_rules(json).is_err(), "expected error for {:?}", json);
+        }
+
+        // 空のオブジェクトは有効（全て拒否される）
+        let empty = parse_rules("{}").unwrap();
+        assert!(!evaluate(&empty, &roles(&["admin"]), "GET", "/api/todos").allowed);
+    }
+
+    #[test]
+    fn test_path_prefix_matches_on_segment_boundary() {
+        assert!(path_matches("/api/todos", "/api/todos"));
+        assert!(path_matches("/api/todos", "/api/todos/"));
+        assert!(path_matches("/api/todos", "/api/todos/1"));
+        assert!(path_matches("/api/todos", "/api/todos/1/complete"));
+        assert!(!path_matches("/api/todos", "/api/todos2"));
+        assert!(!path_matches("/api/todos", "/api/todo"));
+        assert!(!path_matches("/api/todos", "/api"));
+
+        // 末尾のスラッシュは同じプレフィックスとして扱う
+        assert!(path_matches("/api/todos/", "/api/todos"));
+        assert!(path_matches("/api/todos/", "/api/todos/1"));
+        assert!(!path_matches("/api/todos/", "/api/todos2"));
+
+        // "/" は全てのパスに一致
+        assert!(path_matches("/", "/"));
+        assert!(path_matches("/", "/api/admin/users"));
+    }
+
+    #[test]
+    fn test_path_wildcard_matches_sub_paths_only() {
+        assert!(path_matches("/api/admin/*", "/api/admin/users"));
+        assert!(path_matches("/api/admin/*", "/api/admin/users/1"));
+        assert!(!path_matches("/api/admin/*", "/api/admin"));
+        assert!(!path_matches("/api/admin/*", "/api/admin/"));
+        assert!(!path_matches("/api/admin/*", "/api/administrators"));
+
+        assert!(path_matches("/*", "/api"));
+        assert!(!path_matches("/*", "/"));
+    }
+
+    #[test]
+    fn test_method_matching() {
+        let get_post = vec!["GET".to_string(), "POST".to_string()];
+        assert!(method_matches(&get_post, "GET"));
+        assert!(method_matches(&get_post, "post"));
+        assert!(!method_matches(&get_post, "DELETE"));
+
+        let any = vec![ANY_METHOD.to_string()];
+        for method in KNOWN_METHODS {
+            assert!(method_matches(&any, method));
+        }
+
+        assert!(!method_matches(&[], "GET"));
+    }
+
+    #[test]
+    fn test_any_role_applies_to_all_authenticated_users() {
+        // ロールなし（コア層が発行したトークン）でも "*" のルールは適用される
+        assert!(allowed(&[], "DELETE", "/api/todos/1"));
+        assert!(allowed(&[], "GET", "/api/users/me"));
+        assert!(allowed(&["user"], "patch", "/api/todos/1"));
+
+        // "*" のルールにないメソッド・パスは拒否
+        assert!(!allowed(&[], "POST", "/api/users/me"));
+        assert!(!allowed(&[], "GET", "/api/admin/users"));
+    }
+
+    #[test]
+    fn test_role_rules() {
+        assert!(allowed(&["admin"], "GET", "/api/admin/users"));
+        assert!(allowed(&["admin"], "POST", "/api/admin/users/1/role"));
+        assert!(!allowed(&["admin"], "DELETE", "/api/admin/users/1"));
+        assert!(!allowed(&["admin"], "GET", "/api/admin"));
+
+        // 複数ロールはいずれかが一致すれば許可
+        assert!(allowed(&["auditor", "admin"], "GET", "/api/jobs/1"));
+        assert!(allowed(&["auditor", "admin"], "POST", "/api/admin/users"));
+        assert!(!allowed(&["auditor"], "POST", "/api/jobs"));
+
+        // 未知のロールは "*" のルールのみ
+        assert!(!allowed(&["superuser"], "GET", "/api/admin/users"));
+    }
+
+    #[test]
+    fn test_denial_reason() {
+        let decision = evaluate(&policy(), &roles(&[]), "delete", "/api/admin/users/1");
+
+        assert!(!decision.allowed);
+        assert_eq!(
+            decision.reason.as_deref(),
+            Some("Forbidden: DELETE /api/admin/users/1 is not allowed")
+        );
+
+        let decision = evaluate(&policy(), &roles(&[]), "GET", "/api/todos");
+        assert!(decision.allowed);
+        assert_eq!(decision.reason, None);
+    }
+
+    #[test]
+    fn test_dot_segments_are_denied() {
+        // "/api/todos" のプレフィックスに一致するが、正規化すると "/api/admin/..." になる
+        for path in [
+            "/api/todos/../admin/users",
+            "/api/todos/./1",
+            "/api/todos/%2e%2e/admin/users",
+            "/api/todos/%2E./admin/users",
+            "/api/todos/.%2e",
+        ] {
+            let decision = evaluate(&policy(), &roles(&["auditor"]), "GET", path);
+            assert!(!decision.allowed, "expected deny for {}", path);
+            assert!(decision.reason.unwrap().contains("invalid request path"));
+        }
+
+        // ドットを含むだけのセグメントは通常のパス
+        assert!(allowed(&[], "GET", "/api/todos/file.csv"));
+        assert!(allowed(&[], "GET", "/api/todos/..."));
+    }
+}
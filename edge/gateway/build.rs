@@ -0,0 +1,98 @@
+// =============================================================================
+// gateway/build.rs: ビルドスクリプト
+// =============================================================================
+// /version エンドポイント（version モジュール）が返すビルド情報を環境変数として埋め込む。
+//
+// - GATEWAY_GIT_SHA: 環境変数 GIT_SHA（CI で指定）、なければ `git rev-parse --short HEAD`
+// - GATEWAY_SPIN_SDK_VERSION / GATEWAY_WIT_BINDGEN_VERSION: Cargo.lock で解決したバージョン
+//
+// 取得できない値（git がない環境・Cargo.lock がないソースアーカイブなど）は "unknown" にする。
+// =============================================================================
+
+use std::path::Path;
+use std::process::Command;
+
+/// 取得できない値の代わりに埋め込む文字列
+const UNKNOWN: &str = "unknown";
+
+fn main() {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let manifest_dir = Path::new(&manifest_dir);
+
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    // コミット・ブランチの切り替えで再ビルドされるよう、HEAD と refs を監視する
+    let git_dir = manifest_dir.join("../../.git");
+    for path in [git_dir.join("HEAD"), git_dir.join("refs")] {
+        if path.exists() {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+    }
+    println!("cargo:rustc-env=GATEWAY_GIT_SHA={}", git_sha(manifest_dir));
+
+    let lock_path = manifest_dir.join("../Cargo.lock");
+    println!("cargo:rerun-if-changed={}", lock_path.display());
+    let lock = std::fs::read_to_string(&lock_path).unwrap_or_default();
+    for (crate_name, env_name) in [
+        ("spin-sdk", "GATEWAY_SPIN_SDK_VERSION"),
+        ("wit-bindgen", "GATEWAY_WIT_BINDGEN_VERSION"),
+    ] {
+        let version = locked_version(&lock, crate_name).unwrap_or_else(|| UNKNOWN.to_string());
+        println!("cargo:rustc-env={}={}", env_name, version);
+    }
+}
+
+/// ビルドしたコミットの短い SHA を返す
+fn git_sha(manifest_dir: &Path) -> String {
+    if let Some(sha) = std::env::var("GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.trim().is_empty())
+    {
+        return sha.trim().to_string();
+    }
+
+    Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .current_dir(manifest_dir)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|sha| sha.trim().to_string())
+        .filter(|sha| !sha.is_empty())
+        .unwrap_or_else(|| UNKNOWN.to_string())
+}
+
+/// Cargo.lock から gateway が依存するクレートのバージョンを探す
+///
+/// 同じクレートの複数のバージョンがある場合、Cargo.lock の gateway の dependencies は
+/// "wit-bindgen 0.51.0" のようにバージョンを含むため、それを使う。
+/// 1 つしかない場合は "spin-sdk" のように名前だけなので、そのクレートの version を使う。
+fn locked_version(lock: &str, crate_name: &str) -> Option<String> {
+    let packages: Vec<&str> = lock.split("[[package]]").skip(1).collect();
+    let gateway = packages
+        .iter()
+        .find(|package| field(package, "name") == Some("gateway"))?;
+
+    let dependency = gateway
+        .lines()
+        .map(|line| line.trim().trim_end_matches(',').trim_matches('"'))
+        .find(|line| line.split(' ').next() == Some(crate_name))?;
+    if let Some((_, version)) = dependency.split_once(' ') {
+        // "wit-bindgen 0.51.0 (registry+...)" のようにソースを含む場合もある
+        return version.split(' ').next().map(str::to_string);
+    }
+
+    packages
+        .iter()
+        .find(|package| field(package, "name") == Some(crate_name))
+        .and_then(|package| field(package, "version"))
+        .map(str::to_string)
+}
+
+/// [[package]] の `key = "value"` の値を返す
+fn field<'a>(package: &'a str, key: &str) -> Option<&'a str> {
+    package.lines().find_map(|line| {
+        let (name, value) = line.split_once(" = ")?;
+        (name.trim() == key).then(|| value.trim().trim_matches('"'))
+    })
+}
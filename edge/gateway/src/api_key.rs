@@ -0,0 +1,222 @@
+//! # API キー認証（マシン間通信）
+//!
+//! cron ジョブなど、ユーザーのログインを経ずに /api/* を呼び出すクライアント向けに、
+//! `X-Api-Key` ヘッダーの API キーで認証します。
+//! 有効なキーは設定したサービスユーザーの ID に対応し、JWT で認証したユーザーと同じく
+//! `X-User-Id` としてコア層に転送します（ロールはなく、authz の `*` のルールで認可します）。
+//!
+//! | 項目 | 内容 |
+//! |------|------|
+//! | 設定 | Spin 変数 `api_keys`（`<キーの SHA-256（16 進数）>=<サービスユーザー ID>` のカンマ区切り、空の場合は無効） |
+//! | 優先順位 | JWT（Authorization ヘッダー・Cookie）がある場合は JWT のみで認証し、`X-Api-Key` は使わない |
+//! | 失敗 | 一致するキーがない場合は 401（`Invalid API key`） |
+//!
+//! キー自体は設定に保存せず、SHA-256 のハッシュのみを保存します
+//! （例: `printf %s "$API_KEY" | sha256sum`）。
+//! 受信したキーのハッシュは、全ての設定と定数時間で比較します（一致した位置で打ち切らない）。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+// キーのハッシュ
+use sha2::{Digest, Sha256};
+
+// ハッシュの定数時間の比較
+use subtle::ConstantTimeEq;
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// API キーを受け取るヘッダー名
+pub(crate) const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// API キーの設定を読み込む Spin 変数名（エラーメッセージ用）
+const API_KEYS_VARIABLE: &str = "api_keys";
+
+/// SHA-256 のハッシュの長さ（バイト）
+const HASH_LEN: usize = 32;
+
+// =============================================================================
+// 型定義
+// =============================================================================
+
+/// 設定した API キー（キーのハッシュとサービスユーザー ID）
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ApiKey {
+    /// キーの SHA-256
+    hash: [u8; HASH_LEN],
+
+    /// 認証成功時に X-User-Id として転送するサービスユーザー ID
+    user_id: String,
+}
+
+// =============================================================================
+// 設定の解析
+// =============================================================================
+
+/// API キーの設定（カンマ区切り、`<SHA-256（16 進数）>=<サービスユーザー ID>`）を解析する
+///
+/// ハッシュは 64 文字の 16 進数（大文字小文字を区別しない）、ユーザー ID は空白・制御文字を含まない ASCII。
+/// エラーメッセージにはハッシュを含めず、何番目の要素かのみを示す。
+///
+/// # 引数
+/// * `value` - Spin 変数 api_keys の値（空白のみでない）
+///
+/// # 戻り値
+/// * `Ok(Vec<ApiKey>)` - 解析した API キー
+/// * `Err(String)` - 不正な要素がある場合
+pub(crate) fn parse_api_keys(value: &str) -> Result<Vec<ApiKey>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .enumerate()
+        .map(|(index, entry)| {
+            let invalid = |reason: &str| {
+                format!(
+                    "Invalid {} entry #{}: {}",
+                    API_KEYS_VARIABLE,
+                    index + 1,
+                    reason
+                )
+            };
+
+            let (hash, user_id) = entry
+                .split_once('=')
+                .ok_or_else(|| invalid("must be <sha256 hex>=<user id>"))?;
+            let hash = decode_hash(hash.trim())
+                .ok_or_else(|| invalid("hash must be 64 hexadecimal characters"))?;
+            let user_id = user_id.trim();
+            if user_id.is_empty() || !user_id.chars().all(|c| c.is_ascii_graphic()) {
+                return Err(invalid("user id must be non-empty printable ASCII"));
+            }
+
+            Ok(ApiKey {
+                hash,
+                user_id: user_id.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// 16 進数の SHA-256 をバイト列に変換する（64 文字でない・16 進数でない場合は None）
+fn decode_hash(hex: &str) -> Option<[u8; HASH_LEN]> {
+    if hex.len() != HASH_LEN * 2 || !hex.is_ascii() {
+        return None;
+    }
+    let mut hash = [0u8; HASH_LEN];
+    for (byte, pair) in hash.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+    Some(hash)
+}
+
+// =============================================================================
+// 認証
+// =============================================================================
+
+/// API キーを照合し、対応するサービスユーザー ID を返す
+///
+/// # 引数
+/// * `keys` - 設定した API キー（Spin 変数 api_keys）
+/// * `presented` - X-Api-Key ヘッダーの値
+///
+/// # 戻り値
+/// * `Some(&str)` - 一致したキーのサービスユーザー ID
+/// * `None` - 一致するキーがない（空のキー・API キーが未設定の場合を含む）
+pub(crate) fn authenticate<'a>(keys: &'a [ApiKey], presented: &str) -> Option<&'a str> {
+    let presented = presented.trim();
+    if presented.is_empty() {
+        return None;
+    }
+    let hash: [u8; HASH_LEN] = Sha256::digest(presented.as_bytes()).into();
+
+    // 一致した後も残りのキーと比較し、比較の回数から位置がわからないようにする
+    let mut matched = None;
+    for key in keys {
+        if bool::from(key.hash.ct_eq(&hash)) && matched.is_none() {
+            matched = Some(key.user_id.as_str());
+        }
+    }
+    matched
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// キーの SHA-256（16 進数）
+    fn sha256_hex(key: &str) -> String {
+        Sha256::digest(key.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_api_keys() {
+        let value = format!(
+            "{}=svc-cron, {}=svc-report ,",
+            sha256_hex("cron-key"),
+            sha256_hex("report-key").to_uppercase()
+        );
+        let keys = parse_api_keys(&value).unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0].user_id, "svc-cron");
+        assert_eq!(keys[1].user_id, "svc-report");
+        assert_eq!(
+            keys[1].hash,
+            decode_hash(&sha256_hex("report-key")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_api_keys_rejects_invalid_entries() {
+        let hash = sha256_hex("cron-key");
+        for value in [
+            "svc-cron".to_string(),
+            "cron-key=svc-cron".to_string(),
+            format!("{}=", hash),
+            format!("{}=svc cron", hash),
+            format!("{}0=svc-cron", hash),
+            format!("{}=svc-cron,{}zz=svc-report", hash, &hash[..62]),
+        ] {
+            let error = parse_api_keys(&value).unwrap_err();
+            assert!(error.starts_with("Invalid api_keys entry #"), "{}", error);
+            // ハッシュはエラーメッセージに含めない
+            assert!(!error.contains(&hash[..16]), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_authenticate() {
+        let keys = parse_api_keys(&format!(
+            "{}=svc-cron,{}=svc-report",
+            sha256_hex("cron-key"),
+            sha256_hex("report-key")
+        ))
+        .unwrap();
+
+        assert_eq!(authenticate(&keys, "cron-key"), Some("svc-cron"));
+        assert_eq!(authenticate(&keys, " report-key "), Some("svc-report"));
+        assert_eq!(authenticate(&keys, "unknown-key"), None);
+        assert_eq!(authenticate(&keys, "CRON-KEY"), None);
+        assert_eq!(authenticate(&keys, ""), None);
+
+        // API キーが未設定の場合は常に失敗
+        assert_eq!(authenticate(&[], "cron-key"), None);
+    }
+
+    #[test]
+    fn test_authenticate_uses_first_matching_key() {
+        let hash = sha256_hex("shared-key");
+        let keys = parse_api_keys(&format!("{}=svc-a,{}=svc-b", hash, hash)).unwrap();
+        assert_eq!(authenticate(&keys, "shared-key"), Some("svc-a"));
+    }
+}
@@ -0,0 +1,537 @@
+//! # トークンの検証結果のキャッシュ
+//!
+//! 同じ Bearer トークンが短時間に繰り返し送られても、WIT 境界を越えて auth コンポーネントの
+//! `verify_token` を毎回呼び出さないよう、成功した検証結果を key-value ストア（`default`）に保存します。
+//!
+//! | 項目 | 内容 |
+//! |------|------|
+//! | キー | `gateway:auth:<トークンの SHA-256（16 進数）>`（トークン自体は保存しない） |
+//! | 値 | ユーザー ID・有効期限（exp）・ロール・許可リストのクレーム・キャッシュの期限（JSON） |
+//! | 期間 | Spin 変数 `auth_cache_ttl_secs`（既定 30 秒）とトークンの残り有効期間の小さい方 |
+//! | 無効化 | Spin 変数 `gateway_auth_cache=off`（デバッグ用）、または `auth_cache_ttl_secs=0` |
+//!
+//! Spin の key-value ストアには TTL がないため、キャッシュの期限を値に含め、
+//! 読み込み時に期限を過ぎたエントリーは使わずに削除します。
+//! 認証に失敗した結果はキャッシュしません（失効・修正したトークンをすぐに再検証するため）。
+//! 使い捨てのトークン（auth-result の one-time）もキャッシュせず、2 回目以降の提示を auth に拒否させます。
+//! ストアの操作に失敗した場合はキャッシュを使わずに verify_token を呼び出します。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+// キャッシュの値の JSON
+use serde::{Deserialize, Serialize};
+
+// トークンのハッシュ（キー）
+use sha2::{Digest, Sha256};
+
+// key-value ストア
+use spin_sdk::wit::wasi::keyvalue::store;
+
+use crate::config::GatewayConfig;
+use crate::logging;
+use crate::AuthResult;
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// キャッシュを保存する key-value ストア名
+const STORE_NAME: &str = "default";
+
+/// キャッシュのキーのプレフィックス
+///
+/// 同じストアを使う ratelimit・auth・サーキットブレーカーのキーと衝突しないようにする。
+const KEY_PREFIX: &str = "gateway:auth";
+
+// =============================================================================
+// 保存先
+// =============================================================================
+
+/// キャッシュの保存先
+///
+/// テストではメモリ上の実装に差し替え、key-value ストアなしで検証します。
+pub(crate) trait Store {
+    /// キーの値を取得する（キーがない場合は None）
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String>;
+
+    /// キーに値を設定する
+    fn set(&self, key: &str, value: &[u8]) -> Result<(), String>;
+
+    /// キーを削除する
+    fn delete(&self, key: &str) -> Result<(), String>;
+}
+
+/// Spin の key-value ストアを使う保存先
+pub(crate) struct KvStore {
+    bucket: store::Bucket,
+}
+
+impl KvStore {
+    /// key-value ストアを開く（トークンの失効の書き込みにも使う）
+    pub(crate) fn open() -> Result<Self, String> {
+        store::open(STORE_NAME)
+            .map(|bucket| Self { bucket })
+            .map_err(|e| format!("{:?}", e))
+    }
+}
+
+impl Store for KvStore {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        self.bucket
+            .get(key)
+            .map_err(|e| format!("Failed to get {}: {:?}", key, e))
+    }
+
+    fn set(&self, key: &str, value: &[u8]) -> Result<(), String> {
+        self.bucket
+            .set(key, value)
+            .map_err(|e| format!("Failed to set {}: {:?}", key, e))
+    }
+
+    fn delete(&self, key: &str) -> Result<(), String> {
+        self.bucket
+            .delete(key)
+            .map_err(|e| format!("Failed to delete {}: {:?}", key, e))
+    }
+}
+
+// =============================================================================
+// キャッシュ
+// =============================================================================
+
+/// キャッシュに保存する検証結果
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedAuth {
+    /// ユーザー ID（sub クレーム）
+    user_id: String,
+
+    /// トークンの有効期限（exp クレーム、ない場合は None）
+    expires_at: Option<u64>,
+
+    /// ロール（roles クレーム）
+    roles: Vec<String>,
+
+    /// 許可リストのクレーム（X-Claim-* で転送する）
+    claims: Vec<(String, String)>,
+
+    /// このエントリーを使ってよい期限（Unix タイムスタンプ秒、exp を超えない）
+    cached_until: u64,
+
+    /// トークンの発行時刻（iat クレーム、ない場合は None）
+    /// issued_at を保存する前のエントリーも読めるよう、ない場合は None とする
+    #[serde(default)]
+    issued_at: Option<u64>,
+}
+
+/// トークンの検証結果のキャッシュ
+pub(crate) struct AuthCache<S> {
+    store: S,
+
+    /// キャッシュする期間の上限（秒、1 以上）
+    ttl_secs: u64,
+}
+
+impl AuthCache<KvStore> {
+    /// 設定と key-value ストアからキャッシュを作る
+    ///
+    /// # 戻り値
+    /// * `Some(AuthCache)` - 有効な場合
+    /// * `None` - 無効（gateway_auth_cache=off・期間が 0）、またはストアを開けない場合
+    pub(crate) fn load(config: &GatewayConfig) -> Option<Self> {
+        if !config.auth_cache_enabled || config.auth_cache_ttl_secs == 0 {
+            return None;
+        }
+
+        match KvStore::open() {
+            Ok(store) => Some(Self::new(store, config.auth_cache_ttl_secs)),
+            Err(e) => {
+                logging::warn(&format!("Auth cache unavailable, verifying token: {}", e));
+                None
+            }
+        }
+    }
+}
+
+impl<S: Store> AuthCache<S> {
+    /// キャッシュを作る
+    ///
+    /// # 引数
+    /// * `store` - 保存先
+    /// * `ttl_secs` - キャッシュする期間の上限（秒）
+    fn new(store: S, ttl_secs: u64) -> Self {
+        Self { store, ttl_secs }
+    }
+
+    /// キャッシュした検証結果を取得する
+    ///
+    /// # 引数
+    /// * `token` - Bearer トークン
+    /// * `now` - 現在時刻（Unix タイムスタンプ秒）
+    ///
+    /// # 戻り値
+    /// * `Some(AuthResult)` - 期限内のエントリーがある場合（authenticated = true）
+    /// * `None` - ない・期限切れ・読み込めない場合
+    pub(crate) fn get(&self, token: &str, now: u64) -> Option<AuthResult> {
+        if token.is_empty() {
+            return None;
+        }
+
+        let key = cache_key(token);
+        let value = match self.store.get(&key) {
+            Ok(value) => value?,
+            Err(e) => {
+                logging::warn(&format!("Auth cache error, verifying token: {}", e));
+                return None;
+            }
+        };
+
+        // 壊れた値・期限切れのエントリーは使わずに削除する
+        let cached = serde_json::from_slice::<CachedAuth>(&value)
+            .ok()
+            .filter(|cached| now < cached.cached_until);
+        let Some(cached) = cached else {
+            if let Err(e) = self.store.delete(&key) {
+                logging::warn(&format!("Failed to evict auth cache entry: {}", e));
+            }
+            return None;
+        };
+
+        Some(AuthResult {
+            authenticated: true,
+            user_id: Some(cached.user_id),
+            error: None,
+            expires_at: cached.expires_at,
+            roles: cached.roles,
+            claims: cached.claims,
+            issued_at: cached.issued_at,
+            error_code: None,
+            one_time: false,
+        })
+    }
+
+    /// 検証結果を保存する
+    ///
+    /// 認証に失敗した結果・ユーザー ID のない結果・期限切れのトークン・使い捨てのトークンは保存しない。
+    ///
+    /// # 引数
+    /// * `token` - Bearer トークン
+    /// * `result` - auth コンポーネントの検証結果
+    /// * `now` - 現在時刻（Unix タイムスタンプ秒）
+    pub(crate) fn put(&self, token: &str, result: &AuthResult, now: u64) {
+        if token.is_empty() || !result.authenticated || result.one_time {
+            return;
+        }
+        let Some(user_id) = result.user_id.clone() else {
+            return;
+        };
+        let ttl = cache_ttl(result.expires_at, now, self.ttl_secs);
+        if ttl == 0 {
+            return;
+        }
+
+        let cached = CachedAuth {
+            user_id,
+            expires_at: result.expires_at,
+            roles: result.roles.clone(),
+            claims: result.claims.clone(),
+            cached_until: now.saturating_add(ttl),
+            issued_at: result.issued_at,
+        };
+        let value = serde_json::to_vec(&cached).unwrap();
+        if let Err(e) = self.store.set(&cache_key(token), &value) {
+            logging::warn(&format!("Failed to store auth cache entry: {}", e));
+        }
+    }
+}
+
+/// キャッシュした検証結果を使い、ない場合は verify で検証して保存する
+///
+/// # 引数
+/// * `cache` - 検証結果のキャッシュ（無効な場合は None）
+/// * `token` - Bearer トークン
+/// * `now` - 現在時刻（Unix タイムスタンプ秒）
+/// * `verify` - トークンを検証する（auth コンポーネントの verify_token）
+///
+/// # 戻り値
+/// * `(AuthResult, bool)` - 検証結果と、キャッシュした結果を使ったか
+pub(crate) fn get_or_verify<S: Store>(
+    cache: Option<&AuthCache<S>>,
+    token: &str,
+    now: u64,
+    verify: impl FnOnce(&str) -> AuthResult,
+) -> (AuthResult, bool) {
+    if let Some(cached) = cache.and_then(|cache| cache.get(token, now)) {
+        return (cached, true);
+    }
+
+    let result = verify(token);
+    // 成功した検証結果のみ保存する（失敗・使い捨てのトークンはキャッシュしない）
+    if let Some(cache) = cache {
+        cache.put(token, &result, now);
+    }
+    (result, false)
+}
+
+/// 検証結果をキャッシュしてよい期間（秒）を計算する
+///
+/// トークンの残り有効期間と max_ttl_secs の小さい方。
+/// exp のないトークンは上限の期間、期限切れのトークンは 0（キャッシュしない）。
+///
+/// # 引数
+/// * `expires_at` - トークンの有効期限（auth-result の expires-at）
+/// * `now` - 現在時刻（Unix タイムスタンプ秒）
+/// * `max_ttl_secs` - キャッシュする期間の上限（Spin 変数 auth_cache_ttl_secs）
+pub(crate) fn cache_ttl(expires_at: Option<u64>, now: u64, max_ttl_secs: u64) -> u64 {
+    match expires_at {
+        Some(exp) => exp.saturating_sub(now).min(max_ttl_secs),
+        None => max_ttl_secs,
+    }
+}
+
+/// トークンからキャッシュのキーを作る（`gateway:auth:<SHA-256 の 16 進数>`）
+fn cache_key(token: &str) -> String {
+    let digest = Sha256::digest(token.as_bytes());
+    let hex: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("{}:{}", KEY_PREFIX, hex)
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AuthErrorCode;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    /// メモリ上の保存先
+    #[derive(Default)]
+    struct MemoryStore {
+        values: RefCell<HashMap<String, Vec<u8>>>,
+    }
+
+    impl Store for &MemoryStore {
+        fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+            Ok(self.values.borrow().get(key).cloned())
+        }
+
+        fn set(&self, key: &str, value: &[u8]) -> Result<(), String> {
+            self.values
+                .borrow_mut()
+                .insert(key.to_string(), value.to_vec());
+            Ok(())
+        }
+
+        fn delete(&self, key: &str) -> Result<(), String> {
+            self.values.borrow_mut().remove(key);
+            Ok(())
+        }
+    }
+
+    /// 失敗する保存先
+    struct FailingStore;
+
+    impl Store for FailingStore {
+        fn get(&self, _key: &str) -> Result<Option<Vec<u8>>, String> {
+            Err("unavailable".to_string())
+        }
+
+        fn set(&self, _key: &str, _value: &[u8]) -> Result<(), String> {
+            Err("unavailable".to_string())
+        }
+
+        fn delete(&self, _key: &str) -> Result<(), String> {
+            Err("unavailable".to_string())
+        }
+    }
+
+    const NOW: u64 = 1_700_000_000;
+    const TOKEN: &str = "header.payload.signature";
+
+    fn authenticated(expires_at: Option<u64>) -> AuthResult {
+        AuthResult {
+            authenticated: true,
+            user_id: Some("user-1".to_string()),
+            error: None,
+            expires_at,
+            roles: vec!["admin".to_string()],
+            claims: vec![("tenant_id".to_string(), "acme".to_string())],
+            issued_at: Some(NOW - 60),
+            error_code: None,
+            one_time: false,
+        }
+    }
+
+    #[test]
+    fn test_cached_result_is_reused_until_ttl() {
+        let store = MemoryStore::default();
+        let cache = AuthCache::new(&store, 30);
+
+        assert!(cache.get(TOKEN, NOW).is_none());
+        cache.put(TOKEN, &authenticated(Some(NOW + 3600)), NOW);
+
+        let cached = cache.get(TOKEN, NOW + 29).unwrap();
+        assert!(cached.authenticated);
+        assert_eq!(cached.user_id.as_deref(), Some("user-1"));
+        assert_eq!(cached.expires_at, Some(NOW + 3600));
+        assert_eq!(cached.issued_at, Some(NOW - 60));
+        assert_eq!(cached.roles, vec!["admin"]);
+        assert_eq!(
+            cached.claims,
+            vec![("tenant_id".to_string(), "acme".to_string())]
+        );
+
+        // 別のトークンには使わない
+        assert!(cache.get("other.token.value", NOW).is_none());
+
+        // 期限を過ぎたエントリーは使わずに削除する
+        assert!(cache.get(TOKEN, NOW + 30).is_none());
+        assert!(store.values.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_ttl_never_exceeds_token_expiry() {
+        let store = MemoryStore::default();
+        let cache = AuthCache::new(&store, 30);
+
+        cache.put(TOKEN, &authenticated(Some(NOW + 10)), NOW);
+        assert!(cache.get(TOKEN, NOW + 9).is_some());
+        assert!(cache.get(TOKEN, NOW + 10).is_none());
+
+        // 期限切れのトークンは保存しない
+        cache.put(TOKEN, &authenticated(Some(NOW - 1)), NOW);
+        assert!(store.values.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_failures_are_never_cached() {
+        let store = MemoryStore::default();
+        let cache = AuthCache::new(&store, 30);
+
+        let failed = AuthResult {
+            authenticated: false,
+            user_id: None,
+            error: Some("Invalid signature".to_string()),
+            expires_at: None,
+            roles: Vec::new(),
+            claims: Vec::new(),
+            issued_at: None,
+            error_code: Some(AuthErrorCode::InvalidSignature),
+            one_time: false,
+        };
+        cache.put(TOKEN, &failed, NOW);
+        // トークンがない場合（Missing token）も保存しない
+        cache.put("", &authenticated(None), NOW);
+
+        assert!(store.values.borrow().is_empty());
+        assert!(cache.get(TOKEN, NOW).is_none());
+    }
+
+    #[test]
+    fn test_one_time_tokens_are_verified_on_every_presentation() {
+        let store = MemoryStore::default();
+        let cache = AuthCache::new(&store, 30);
+        // auth コンポーネントの代わりに、使い捨てのトークンの 2 回目以降の提示を拒否する
+        let presented = RefCell::new(0);
+        let verify = |_token: &str| {
+            *presented.borrow_mut() += 1;
+            if *presented.borrow() == 1 {
+                AuthResult {
+                    one_time: true,
+                    ..authenticated(Some(NOW + 900))
+                }
+            } else {
+                AuthResult {
+                    authenticated: false,
+                    user_id: None,
+                    error: Some("Token already used".to_string()),
+                    expires_at: None,
+                    roles: Vec::new(),
+                    claims: Vec::new(),
+                    issued_at: None,
+                    error_code: Some(AuthErrorCode::Revoked),
+                    one_time: false,
+                }
+            }
+        };
+
+        let (first, cache_hit) = get_or_verify(Some(&cache), TOKEN, NOW, verify);
+        assert!(first.authenticated);
+        assert!(!cache_hit);
+        assert!(store.values.borrow().is_empty());
+
+        // キャッシュが有効でも 2 回目の提示は auth で検証し、拒否される
+        let (second, cache_hit) = get_or_verify(Some(&cache), TOKEN, NOW + 1, verify);
+        assert!(!second.authenticated);
+        assert_eq!(second.error.as_deref(), Some("Token already used"));
+        assert!(!cache_hit);
+        assert_eq!(*presented.borrow(), 2);
+
+        // 使い捨てでないトークンは 2 回目の提示でキャッシュを使う
+        let (_, cache_hit) = get_or_verify(Some(&cache), TOKEN, NOW, |_| authenticated(None));
+        assert!(!cache_hit);
+        let (cached, cache_hit) = get_or_verify(Some(&cache), TOKEN, NOW + 1, |_| unreachable!());
+        assert!(cached.authenticated);
+        assert!(cache_hit);
+    }
+
+    #[test]
+    fn test_key_does_not_contain_token() {
+        let key = cache_key(TOKEN);
+
+        assert!(key.starts_with("gateway:auth:"));
+        assert_eq!(key.len(), "gateway:auth:".len() + 64);
+        assert!(!key.contains(TOKEN));
+        assert_ne!(key, cache_key("header.payload.signaturE"));
+    }
+
+    #[test]
+    fn test_corrupt_entry_is_evicted() {
+        let store = MemoryStore::default();
+        let cache = AuthCache::new(&store, 30);
+        store
+            .values
+            .borrow_mut()
+            .insert(cache_key(TOKEN), b"not json".to_vec());
+
+        assert!(cache.get(TOKEN, NOW).is_none());
+        assert!(store.values.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_entry_without_issued_at_is_still_used() {
+        let store = MemoryStore::default();
+        let cache = AuthCache::new(&store, 30);
+        // issued_at を保存する前のエントリー
+        store.values.borrow_mut().insert(
+            cache_key(TOKEN),
+            br#"{"user_id":"user-1","expires_at":null,"roles":[],"claims":[],"cached_until":1700000030}"#
+                .to_vec(),
+        );
+
+        let cached = cache.get(TOKEN, NOW).unwrap();
+        assert_eq!(cached.user_id.as_deref(), Some("user-1"));
+        assert_eq!(cached.issued_at, None);
+    }
+
+    #[test]
+    fn test_store_errors_fall_back_to_verification() {
+        let cache = AuthCache::new(FailingStore, 30);
+
+        cache.put(TOKEN, &authenticated(None), NOW);
+        assert!(cache.get(TOKEN, NOW).is_none());
+    }
+
+    #[test]
+    fn test_cache_ttl() {
+        assert_eq!(cache_ttl(Some(NOW + 3600), NOW, 30), 30);
+        assert_eq!(cache_ttl(Some(NOW + 10), NOW, 30), 10);
+        assert_eq!(cache_ttl(Some(NOW - 10), NOW, 30), 0);
+        assert_eq!(cache_ttl(None, NOW, 30), 30);
+        assert_eq!(cache_ttl(None, NOW, 5), 5);
+    }
+}
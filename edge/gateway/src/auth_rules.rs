@@ -0,0 +1,136 @@
+//! # 認証のルールの確認（/gateway/auth-rules）
+//!
+//! Spin 変数 public_paths・auth_policies から解決した、現在有効な認証のルールを JSON で返します。
+//! 変数を変更した後に、再デプロイせずに反映された内容を確かめるためのエンドポイントです。
+//!
+//! ```json
+//! {"public_paths": ["/api/auth/register", "/api/auth/login"],
+//!  "auth_policies": [{"method": "GET", "path": "/api/todos", "policy": "public"}],
+//!  "errors": []}
+//! ```
+//!
+//! `errors` は解析できずに既定値に戻した変数のエラーメッセージです（空でない場合は変数を見直す）。
+//! 認証と認可（authz_rules でこのパスを許可したユーザーのみ）が必要です。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+use serde::Serialize;
+use spin_sdk::http::Response;
+
+use crate::config::{AuthPolicy, GatewayConfig};
+
+// =============================================================================
+// 型定義
+// =============================================================================
+
+/// /gateway/auth-rules のレスポンスのボディ
+#[derive(Serialize)]
+struct AuthRulesResponse<'a> {
+    /// 認証不要のパブリックパスのパターン（末尾の `/*` はプレフィックス一致）
+    public_paths: &'a [String],
+
+    /// メソッド・パスプレフィックスごとの認証ポリシー（public_paths より優先）
+    auth_policies: Vec<AuthPolicyView<'a>>,
+
+    /// 解析できずに既定値に戻した変数のエラーメッセージ
+    errors: &'a [String],
+}
+
+/// 認証ポリシーの 1 件（JSON 用）
+#[derive(Serialize)]
+struct AuthPolicyView<'a> {
+    /// HTTP メソッド（全てのメソッドの場合は "*"）
+    method: &'a str,
+
+    /// パスプレフィックス
+    path: &'a str,
+
+    /// "public" または "authenticated"
+    policy: &'static str,
+}
+
+// =============================================================================
+// レスポンス
+// =============================================================================
+
+/// /gateway/auth-rules のレスポンスを組み立てる
+///
+/// # 引数
+/// * `config` - ゲートウェイの設定（public_paths・auth_policies・auth_rule_errors）
+///
+/// # 戻り値
+/// * `Response` - 現在有効な認証のルールの JSON（200）
+pub(crate) fn response(config: &GatewayConfig) -> Response {
+    let body = serde_json::to_string(&AuthRulesResponse {
+        public_paths: &config.public_paths,
+        auth_policies: config
+            .auth_policies
+            .iter()
+            .map(|rule| AuthPolicyView {
+                method: rule.method.as_deref().unwrap_or("*"),
+                path: &rule.prefix,
+                policy: match rule.policy {
+                    AuthPolicy::Public => "public",
+                    AuthPolicy::Authenticated => "authenticated",
+                },
+            })
+            .collect(),
+        errors: &config.auth_rule_errors,
+    })
+    .unwrap();
+
+    Response::builder()
+        .status(200)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-store")
+        .body(body)
+        .build()
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Variables;
+
+    #[test]
+    fn test_response_lists_effective_rules() {
+        let config = GatewayConfig::resolve(Variables {
+            public_paths: Some("/api/auth/login, /api/public/*".to_string()),
+            auth_policies: Some("GET /api/todos=public, * /api/admin=authenticated".to_string()),
+            ..Variables::default()
+        })
+        .unwrap();
+        let response = response(&config);
+
+        assert_eq!(*response.status(), 200);
+        assert_eq!(
+            response.body(),
+            br#"{"public_paths":["/api/auth/login","/api/public/*"],"auth_policies":[{"method":"GET","path":"/api/todos","policy":"public"},{"method":"*","path":"/api/admin","policy":"authenticated"}],"errors":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_response_reports_fallback_errors() {
+        let config = GatewayConfig::resolve(Variables {
+            public_paths: Some("/api/*/todos".to_string()),
+            ..Variables::default()
+        })
+        .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(response(&config).body()).unwrap();
+
+        assert_eq!(
+            body["public_paths"],
+            serde_json::json!(["/api/auth/register", "/api/auth/login"])
+        );
+        assert!(body["errors"][0]
+            .as_str()
+            .unwrap()
+            .starts_with("Invalid public_paths"));
+    }
+}
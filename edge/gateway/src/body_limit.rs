@@ -0,0 +1,204 @@
+//! # リクエストボディの上限
+//!
+//! コア層に転送する前に、リクエストボディのサイズを制限します。
+//! 上限はパスプレフィックスごとに設定できます（config モジュールの body_limit）。
+//!
+//! - `Content-Length` が上限を超える場合: ボディを読まずに 413 Payload Too Large を返す
+//! - `Content-Length` がない（chunked など）・偽りの場合: ボディを読みながら数え、
+//!   上限を超えた時点で読むのをやめて 413 を返す
+//!
+//! spin_sdk の `Request` に変換するとボディ全体がメモリに読み込まれるため、
+//! ハンドラーは `IncomingRequest` を受け取り、このモジュールで上限を確認してから変換します。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+// ボディのストリームをチャンクごとに読む
+use futures::TryStreamExt;
+
+use spin_sdk::http::{IncomingRequest, Request};
+
+// Request に変換する際の URI のパスの正規化
+use crate::normalize;
+
+// =============================================================================
+// 型定義
+// =============================================================================
+
+/// ボディを読み込めなかった理由
+#[derive(Debug, PartialEq)]
+pub(crate) enum BodyError {
+    /// 上限（バイト）を超えた
+    TooLarge(u64),
+
+    /// ストリームの読み込みに失敗した
+    Read(String),
+}
+
+// =============================================================================
+// ボディの読み込み
+// =============================================================================
+
+/// リクエストのヘッダーの値（最初の値）を取得する
+///
+/// # 引数
+/// * `req` - 受信したリクエスト
+/// * `name` - ヘッダー名（小文字）
+pub(crate) fn header(req: &IncomingRequest, name: &str) -> Option<String> {
+    req.headers()
+        .get(name)
+        .into_iter()
+        .next()
+        .and_then(|value| String::from_utf8(value).ok())
+}
+
+/// 上限を確認しながら `IncomingRequest` を `Request` に変換する
+///
+/// URI のパスは正規化する（normalize::normalize_uri、クエリ文字列はそのまま）。
+///
+/// # 引数
+/// * `req` - 受信したリクエスト
+/// * `limit` - ボディの上限（バイト、0 の場合は制限しない）
+///
+/// # 戻り値
+/// * `Ok(Request)` - ボディを読み込んだリクエスト
+/// * `Err(BodyError)` - 上限を超えた、または読み込みに失敗した場合
+pub(crate) async fn read_request(req: IncomingRequest, limit: u64) -> Result<Request, BodyError> {
+    // Content-Length で上限を超えることがわかる場合は、ボディを読まずに拒否する
+    if exceeds_limit(header(&req, "content-length").as_deref(), limit) {
+        return Err(BodyError::TooLarge(limit));
+    }
+
+    let mut builder = Request::builder();
+    builder
+        .method(req.method())
+        .uri(normalize::normalize_uri(&req.uri()))
+        .headers(req.headers());
+
+    let mut body = Vec::new();
+    let mut stream = req.into_body_stream();
+    while let Some(chunk) = stream
+        .try_next()
+        .await
+        .map_err(|e| BodyError::Read(e.to_debug_string()))?
+    {
+        append_chunk(&mut body, chunk, limit)?;
+    }
+
+    Ok(builder.body(body).build())
+}
+
+/// 1 つしか許可しないヘッダーの値を取得する（全ての値を確認する）
+///
+/// Request は同じ名前のヘッダーの最後の値だけを残すため、変換する前に数える
+/// （どちらの値を使ったかで結果が変わらないよう、複数ある場合は拒否する）。
+///
+/// # 引数
+/// * `req` - 受信したリクエスト
+/// * `name` - ヘッダー名（小文字、例: "authorization"）
+///
+/// # 戻り値
+/// * `Ok(Option<String>)` - 値（ない場合は None、UTF-8 でない値は置き換え文字にする）
+/// * `Err(usize)` - 複数ある場合はその数
+pub(crate) fn single_header(req: &IncomingRequest, name: &str) -> Result<Option<String>, usize> {
+    single_value(req.headers().get(name))
+}
+
+/// ヘッダーの値の一覧から 1 つの値を取り出す（複数ある場合は Err でその数を返す）
+fn single_value(values: Vec<Vec<u8>>) -> Result<Option<String>, usize> {
+    match values.as_slice() {
+        [] => Ok(None),
+        [value] => Ok(Some(String::from_utf8_lossy(value).into_owned())),
+        _ => Err(values.len()),
+    }
+}
+
+/// Content-Length の値が上限を超えるかを判定する
+///
+/// 解析できない値は判定せず（false）、読み込み時の上限確認に任せる。
+///
+/// # 引数
+/// * `content_length` - Content-Length ヘッダーの値（ない場合は None）
+/// * `limit` - ボディの上限（バイト、0 の場合は制限しない）
+fn exceeds_limit(content_length: Option<&str>, limit: u64) -> bool {
+    limit != 0
+        && content_length
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .is_some_and(|length| length > limit)
+}
+
+/// 読み込んだチャンクをボディに追加する（上限を超える場合はエラー）
+///
+/// # 引数
+/// * `body` - これまでに読み込んだボディ
+/// * `chunk` - 新しく読み込んだチャンク
+/// * `limit` - ボディの上限（バイト、0 の場合は制限しない）
+fn append_chunk(body: &mut Vec<u8>, chunk: Vec<u8>, limit: u64) -> Result<(), BodyError> {
+    if limit != 0 && (body.len() + chunk.len()) as u64 > limit {
+        return Err(BodyError::TooLarge(limit));
+    }
+    body.extend(chunk);
+    Ok(())
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exceeds_limit() {
+        assert!(exceeds_limit(Some("1025"), 1024));
+        assert!(exceeds_limit(Some(" 209715200 "), 1024));
+        assert!(!exceeds_limit(Some("1024"), 1024));
+        assert!(!exceeds_limit(None, 1024));
+        // 解析できない値は読み込み時に確認する
+        assert!(!exceeds_limit(Some("abc"), 1024));
+        // 0 の場合は制限しない
+        assert!(!exceeds_limit(Some("209715200"), 0));
+    }
+
+    #[test]
+    fn test_single_value() {
+        // 0 個・1 個
+        assert_eq!(single_value(vec![]), Ok(None));
+        assert_eq!(
+            single_value(vec![b"Bearer a".to_vec()]),
+            Ok(Some("Bearer a".to_string()))
+        );
+
+        // 2 個以上（同じ値でも拒否する）
+        assert_eq!(
+            single_value(vec![b"Bearer a".to_vec(), b"Bearer b".to_vec()]),
+            Err(2)
+        );
+        assert_eq!(
+            single_value(vec![b"Bearer a".to_vec(), b"Bearer a".to_vec()]),
+            Err(2)
+        );
+        assert_eq!(single_value(vec![Vec::new(); 3]), Err(3));
+    }
+
+    #[test]
+    fn test_append_chunk_stops_at_limit() {
+        let mut body = Vec::new();
+        append_chunk(&mut body, vec![0; 600], 1024).unwrap();
+        append_chunk(&mut body, vec![0; 424], 1024).unwrap();
+        assert_eq!(body.len(), 1024);
+
+        // 上限を超えるチャンクは追加しない
+        assert_eq!(
+            append_chunk(&mut body, vec![0; 1], 1024),
+            Err(BodyError::TooLarge(1024))
+        );
+        assert_eq!(body.len(), 1024);
+
+        let mut body = Vec::new();
+        append_chunk(&mut body, vec![0; 4096], 0).unwrap();
+        assert_eq!(body.len(), 4096);
+    }
+}
@@ -0,0 +1,130 @@
+//! # カナリアリリースの振り分け
+//!
+//! 新しいバージョンのコア層を並べてデプロイし、リクエストの一部（Spin 変数 canary_percent）を
+//! core_canary_url に転送します。
+//!
+//! 振り分けはキー（認証済みの場合はユーザー ID、パブリックパスの場合はリクエスト ID）の
+//! ハッシュで決めるため、同じユーザーは常に同じ転送先に届きます。
+//! ハッシュは Rust のバージョンやインスタンスによらず同じ値になる FNV-1a を使います
+//! （std の DefaultHasher はアルゴリズムが保証されないため使わない）。
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// 転送先をレスポンスに付与するヘッダー名（デバッグ用、"primary" / "canary"）
+pub(crate) const SERVED_BY_HEADER: &str = "X-Served-By";
+
+/// FNV-1a（64 ビット）のオフセット基底
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// FNV-1a（64 ビット）の素数
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+// =============================================================================
+// 型定義
+// =============================================================================
+
+/// リクエストの転送先
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Backend {
+    /// core_url（既存のデプロイ）
+    Primary,
+
+    /// core_canary_url（新しいバージョン）
+    Canary,
+}
+
+impl Backend {
+    /// X-Served-By ヘッダーの値
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Backend::Primary => "primary",
+            Backend::Canary => "canary",
+        }
+    }
+}
+
+// =============================================================================
+// 振り分け
+// =============================================================================
+
+/// キーのハッシュで転送先を選ぶ
+///
+/// # 引数
+/// * `percent` - カナリアに転送する割合（0〜100、0 の場合は常に Primary）
+/// * `key` - 振り分けのキー（ユーザー ID・リクエスト ID）
+///
+/// # 戻り値
+/// * `Backend` - 転送先
+pub(crate) fn select(percent: u8, key: &str) -> Backend {
+    if bucket(key) < percent {
+        Backend::Canary
+    } else {
+        Backend::Primary
+    }
+}
+
+/// キーを 0〜99 のバケットに割り当てる
+fn bucket(key: &str) -> u8 {
+    let hash = key.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    });
+    (hash % 100) as u8
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_is_stable() {
+        // FNV-1a の既知の値（"" はオフセット基底そのもの）
+        assert_eq!(bucket(""), (FNV_OFFSET_BASIS % 100) as u8);
+        assert_eq!(bucket("a"), (0xaf63_dc4c_8601_ec8c_u64 % 100) as u8);
+        // 同じキーは常に同じバケット
+        assert_eq!(bucket("user-1"), bucket("user-1"));
+    }
+
+    #[test]
+    fn test_select_bounds() {
+        for key in [
+            "user-1",
+            "user-2",
+            "",
+            "550e8400-e29b-41d4-a716-446655440000",
+        ] {
+            assert_eq!(select(0, key), Backend::Primary, "{}", key);
+            assert_eq!(select(100, key), Backend::Canary, "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_select_is_sticky_and_weighted() {
+        let keys: Vec<String> = (0..10_000).map(|i| format!("user-{}", i)).collect();
+
+        // 割合を増やしてもカナリアに振り分けたユーザーは Primary に戻らない
+        for key in &keys {
+            if select(5, key) == Backend::Canary {
+                assert_eq!(select(20, key), Backend::Canary, "{}", key);
+            }
+        }
+
+        // おおよそ指定した割合になる
+        let canary = keys
+            .iter()
+            .filter(|key| select(5, key) == Backend::Canary)
+            .count();
+        assert!((350..=650).contains(&canary), "{}", canary);
+    }
+
+    #[test]
+    fn test_backend_as_str() {
+        assert_eq!(Backend::Primary.as_str(), "primary");
+        assert_eq!(Backend::Canary.as_str(), "canary");
+    }
+}
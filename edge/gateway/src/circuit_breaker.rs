@@ -0,0 +1,470 @@
+//! # コア層のサーキットブレーカー
+//!
+//! コア層への送信（`spin_sdk::http::send`）が連続して失敗した場合に回路を開き、
+//! 一定時間は認証やコア層への転送を行わずに 503 Service Unavailable（`Retry-After` 付き）を返します。
+//!
+//! | 状態 | 条件 | 動作 |
+//! |------|------|------|
+//! | Closed | 通常 | 転送する。送信の失敗を数え、閾値に達したら Open にする |
+//! | Open | 開いてからクールダウン期間内 | 転送せずに 503 |
+//! | Half-Open | クールダウン期間の経過後 | 1 件だけ試しに転送する（プローブ）。成功すれば Closed、失敗すれば再び Open |
+//!
+//! 状態はコンポーネントのインスタンス間で共有するため key-value ストア（`default`）に保存します。
+//! プローブはクールダウン期間ごとに 1 件（アトミックなインクリメントで選ぶ）で、
+//! プローブの結果が記録されなかった場合も次の期間に別のリクエストがプローブになります。
+//! ストアの操作に失敗した場合は判定をスキップします（フェイルオープン）。
+//!
+//! 失敗として数えるのは送信自体の失敗（リトライ後）のみで、コア層が返した 5xx は数えません。
+//! 閾値とクールダウン期間は Spin 変数 `circuit_failure_threshold` / `circuit_cooldown_secs` で設定します。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+// key-value ストア（アトミックなインクリメント）
+use spin_sdk::wit::wasi::keyvalue::{atomics, store};
+
+use crate::config::GatewayConfig;
+use crate::logging;
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// 状態を保存する key-value ストア名
+const STORE_NAME: &str = "default";
+
+/// 連続した失敗の数のキー（アトミックに加算する）
+const FAILURES_KEY: &str = "gateway:circuit:failures";
+
+/// 回路を開いた時刻（UNIX 秒、ない場合は Closed）のキー
+const OPENED_AT_KEY: &str = "gateway:circuit:opened_at";
+
+/// プローブを選ぶカウンタのキーのプレフィックス（`{prefix}:{開いた時刻}:{期間の番号}`）
+const PROBE_KEY_PREFIX: &str = "gateway:circuit:probe";
+
+// =============================================================================
+// 状態の保存先
+// =============================================================================
+
+/// サーキットブレーカーの状態の保存先
+///
+/// テストではメモリ上の実装に差し替え、key-value ストアなしで状態遷移を検証します。
+pub(crate) trait Store {
+    /// キーの値を取得する（キーがない場合は 0）
+    fn get(&self, key: &str) -> Result<u64, String>;
+
+    /// キーに値を設定する
+    fn set(&self, key: &str, value: u64) -> Result<(), String>;
+
+    /// キーの値をアトミックに delta 増やし、増やした後の値を返す（キーがなければ 0 から数える）
+    fn increment(&self, key: &str, delta: u64) -> Result<u64, String>;
+
+    /// キーを削除する
+    fn delete(&self, key: &str) -> Result<(), String>;
+}
+
+/// Spin の key-value ストアを使う保存先
+pub(crate) struct KvStore {
+    bucket: store::Bucket,
+}
+
+impl Store for KvStore {
+    fn get(&self, key: &str) -> Result<u64, String> {
+        let value = self
+            .bucket
+            .get(key)
+            .map_err(|e| format!("Failed to get {}: {:?}", key, e))?;
+        Ok(value
+            .and_then(|value| String::from_utf8(value).ok())
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0))
+    }
+
+    fn set(&self, key: &str, value: u64) -> Result<(), String> {
+        self.bucket
+            .set(key, value.to_string().as_bytes())
+            .map_err(|e| format!("Failed to set {}: {:?}", key, e))
+    }
+
+    fn increment(&self, key: &str, delta: u64) -> Result<u64, String> {
+        atomics::increment(&self.bucket, key, delta as i64)
+            .map(|count| count.max(0) as u64)
+            .map_err(|e| format!("Failed to increment {}: {:?}", key, e))
+    }
+
+    fn delete(&self, key: &str) -> Result<(), String> {
+        self.bucket
+            .delete(key)
+            .map_err(|e| format!("Failed to delete {}: {:?}", key, e))
+    }
+}
+
+// =============================================================================
+// サーキットブレーカー
+// =============================================================================
+
+/// 送信してよいかの判定結果
+#[derive(Debug, PartialEq)]
+pub(crate) enum Admission {
+    /// Closed: 通常どおり送信する
+    Closed,
+
+    /// Half-Open: プローブとして送信する（値はプローブのカウンタのキー）
+    Probe(String),
+
+    /// Open: 送信しない（値は Retry-After の秒数）
+    Open(u64),
+}
+
+/// コア層のサーキットブレーカー
+pub(crate) struct CircuitBreaker<S> {
+    store: S,
+
+    /// 回路を開く連続した失敗の数
+    threshold: u32,
+
+    /// 回路を開いてからプローブを許可するまでの秒数（1 以上）
+    cooldown_secs: u64,
+}
+
+impl CircuitBreaker<KvStore> {
+    /// 設定と key-value ストアからサーキットブレーカーを作る
+    ///
+    /// # 戻り値
+    /// * `Some(CircuitBreaker)` - 有効な場合
+    /// * `None` - 閾値が 0（無効）、またはストアを開けない場合（フェイルオープン）
+    pub(crate) fn load(config: &GatewayConfig) -> Option<Self> {
+        if config.circuit_failure_threshold == 0 {
+            return None;
+        }
+
+        match store::open(STORE_NAME) {
+            Ok(bucket) => Some(Self::new(
+                KvStore { bucket },
+                config.circuit_failure_threshold,
+                config.circuit_cooldown_secs,
+            )),
+            Err(e) => {
+                logging::warn(&format!(
+                    "Circuit breaker unavailable, allowing request: {:?}",
+                    e
+                ));
+                None
+            }
+        }
+    }
+}
+
+impl<S: Store> CircuitBreaker<S> {
+    /// サーキットブレーカーを作る
+    ///
+    /// # 引数
+    /// * `store` - 状態の保存先
+    /// * `threshold` - 回路を開く連続した失敗の数
+    /// * `cooldown_secs` - 回路を開いてからプローブを許可するまでの秒数（0 の場合は 1 秒）
+    fn new(store: S, threshold: u32, cooldown_secs: u64) -> Self {
+        Self {
+            store,
+            threshold,
+            cooldown_secs: cooldown_secs.max(1),
+        }
+    }
+
+    /// 回路が開いている場合、Retry-After の秒数を返す（認証前の判定用、状態は変更しない）
+    ///
+    /// # 引数
+    /// * `now` - 現在時刻（UNIX 秒）
+    ///
+    /// # 戻り値
+    /// * `Some(u64)` - クールダウン期間内の場合、残りの秒数
+    /// * `None` - Closed、またはプローブを許可する期間の場合
+    pub(crate) fn retry_after(&self, now: u64) -> Option<u64> {
+        let opened_at = self.opened_at()?;
+        let until = opened_at + self.cooldown_secs;
+        (now < until).then(|| until - now)
+    }
+
+    /// 送信する直前に、送信してよいかを判定する
+    ///
+    /// クールダウン期間の経過後は、期間ごとに最初の 1 件だけをプローブとして送信する。
+    ///
+    /// # 引数
+    /// * `now` - 現在時刻（UNIX 秒）
+    /// * `request_id` - ログ用のリクエスト ID
+    pub(crate) fn admit(&self, now: u64, request_id: &str) -> Admission {
+        let Some(opened_at) = self.opened_at() else {
+            return Admission::Closed;
+        };
+
+        let until = opened_at + self.cooldown_secs;
+        if now < until {
+            return Admission::Open(until - now);
+        }
+
+        // クールダウン期間ごとに 1 件だけプローブにする
+        let elapsed = now - until;
+        let key = format!(
+            "{}:{}:{}",
+            PROBE_KEY_PREFIX,
+            opened_at,
+            elapsed / self.cooldown_secs
+        );
+        match self.store.increment(&key, 1) {
+            Ok(1) => {
+                logging::info(&format!(
+                    "Circuit half-open, probing core (request_id={})",
+                    request_id
+                ));
+                Admission::Probe(key)
+            }
+            Ok(_) => Admission::Open(self.cooldown_secs - elapsed % self.cooldown_secs),
+            Err(e) => {
+                logging::warn(&format!(
+                    "Circuit breaker error, allowing request (request_id={}): {}",
+                    request_id, e
+                ));
+                Admission::Closed
+            }
+        }
+    }
+
+    /// 送信の結果を記録し、状態を遷移させる
+    ///
+    /// # 引数
+    /// * `admission` - admit の判定結果
+    /// * `success` - 送信に成功した（コア層からレスポンスを受け取った）か
+    /// * `now` - 現在時刻（UNIX 秒）
+    /// * `request_id` - ログ用のリクエスト ID
+    pub(crate) fn record(&self, admission: &Admission, success: bool, now: u64, request_id: &str) {
+        let result = match (admission, success) {
+            (Admission::Closed, true) => self.reset_failures(),
+            (Admission::Closed, false) => self.record_failure(now, request_id),
+            (Admission::Probe(key), true) => {
+                logging::info(&format!(
+                    "Circuit closed, probe succeeded (request_id={})",
+                    request_id
+                ));
+                self.store
+                    .delete(OPENED_AT_KEY)
+                    .and_then(|_| self.store.delete(FAILURES_KEY))
+                    .and_then(|_| self.store.delete(key))
+            }
+            (Admission::Probe(key), false) => {
+                logging::info(&format!(
+                    "Circuit re-opened, probe failed (request_id={})",
+                    request_id
+                ));
+                self.store
+                    .set(OPENED_AT_KEY, now)
+                    .and_then(|_| self.store.delete(key))
+            }
+            (Admission::Open(_), _) => Ok(()),
+        };
+
+        if let Err(e) = result {
+            logging::warn(&format!(
+                "Failed to record circuit breaker state (request_id={}): {}",
+                request_id, e
+            ));
+        }
+    }
+
+    /// 回路を開いた時刻（Closed の場合・読み込めない場合は None）
+    fn opened_at(&self) -> Option<u64> {
+        match self.store.get(OPENED_AT_KEY) {
+            Ok(0) => None,
+            Ok(opened_at) => Some(opened_at),
+            Err(e) => {
+                logging::warn(&format!("Circuit breaker error, allowing request: {}", e));
+                None
+            }
+        }
+    }
+
+    /// 成功したら連続した失敗の数を 0 に戻す（失敗がない場合は書き込まない）
+    fn reset_failures(&self) -> Result<(), String> {
+        if self.store.increment(FAILURES_KEY, 0)? > 0 {
+            self.store.delete(FAILURES_KEY)?;
+        }
+        Ok(())
+    }
+
+    /// 失敗を数え、閾値に達したら回路を開く
+    fn record_failure(&self, now: u64, request_id: &str) -> Result<(), String> {
+        let failures = self.store.increment(FAILURES_KEY, 1)?;
+        if failures >= u64::from(self.threshold) && self.opened_at().is_none() {
+            logging::info(&format!(
+                "Circuit opened after {} consecutive failures for {}s (request_id={})",
+                failures, self.cooldown_secs, request_id
+            ));
+            self.store.set(OPENED_AT_KEY, now)?;
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    /// メモリ上の保存先
+    #[derive(Default)]
+    struct MemoryStore {
+        values: RefCell<HashMap<String, u64>>,
+    }
+
+    impl Store for &MemoryStore {
+        fn get(&self, key: &str) -> Result<u64, String> {
+            Ok(self.values.borrow().get(key).copied().unwrap_or(0))
+        }
+
+        fn set(&self, key: &str, value: u64) -> Result<(), String> {
+            self.values.borrow_mut().insert(key.to_string(), value);
+            Ok(())
+        }
+
+        fn increment(&self, key: &str, delta: u64) -> Result<u64, String> {
+            let mut values = self.values.borrow_mut();
+            let value = values.entry(key.to_string()).or_insert(0);
+            *value += delta;
+            Ok(*value)
+        }
+
+        fn delete(&self, key: &str) -> Result<(), String> {
+            self.values.borrow_mut().remove(key);
+            Ok(())
+        }
+    }
+
+    /// 失敗する保存先
+    struct FailingStore;
+
+    impl Store for FailingStore {
+        fn get(&self, _key: &str) -> Result<u64, String> {
+            Err("unavailable".to_string())
+        }
+
+        fn set(&self, _key: &str, _value: u64) -> Result<(), String> {
+            Err("unavailable".to_string())
+        }
+
+        fn increment(&self, _key: &str, _delta: u64) -> Result<u64, String> {
+            Err("unavailable".to_string())
+        }
+
+        fn delete(&self, _key: &str) -> Result<(), String> {
+            Err("unavailable".to_string())
+        }
+    }
+
+    const NOW: u64 = 1_700_000_000;
+
+    /// 送信して結果を記録する
+    fn send(breaker: &CircuitBreaker<&MemoryStore>, now: u64, success: bool) -> Admission {
+        let admission = breaker.admit(now, "req-1");
+        breaker.record(&admission, success, now, "req-1");
+        admission
+    }
+
+    #[test]
+    fn test_opens_after_consecutive_failures() {
+        let store = MemoryStore::default();
+        let breaker = CircuitBreaker::new(&store, 3, 30);
+
+        assert_eq!(send(&breaker, NOW, false), Admission::Closed);
+        assert_eq!(send(&breaker, NOW, false), Admission::Closed);
+        assert_eq!(breaker.retry_after(NOW), None);
+
+        // 3 回目の失敗で開く
+        assert_eq!(send(&breaker, NOW, false), Admission::Closed);
+        assert_eq!(breaker.retry_after(NOW), Some(30));
+        assert_eq!(breaker.retry_after(NOW + 10), Some(20));
+        assert_eq!(breaker.admit(NOW + 10, "req-2"), Admission::Open(20));
+    }
+
+    #[test]
+    fn test_success_resets_consecutive_failures() {
+        let store = MemoryStore::default();
+        let breaker = CircuitBreaker::new(&store, 3, 30);
+
+        send(&breaker, NOW, false);
+        send(&breaker, NOW, false);
+        send(&breaker, NOW, true);
+        send(&breaker, NOW, false);
+        send(&breaker, NOW, false);
+
+        assert_eq!(breaker.retry_after(NOW), None);
+        assert_eq!(breaker.admit(NOW, "req-1"), Admission::Closed);
+    }
+
+    #[test]
+    fn test_half_open_allows_single_probe() {
+        let store = MemoryStore::default();
+        let breaker = CircuitBreaker::new(&store, 1, 30);
+        send(&breaker, NOW, false);
+
+        // クールダウン後は 1 件だけプローブにする
+        let probe = breaker.admit(NOW + 30, "req-2");
+        assert!(matches!(probe, Admission::Probe(_)));
+        assert_eq!(breaker.retry_after(NOW + 30), None);
+        assert_eq!(breaker.admit(NOW + 31, "req-3"), Admission::Open(29));
+
+        // プローブが成功したら閉じる
+        breaker.record(&probe, true, NOW + 31, "req-2");
+        assert_eq!(breaker.admit(NOW + 32, "req-4"), Admission::Closed);
+        assert!(store.values.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_failed_probe_reopens_circuit() {
+        let store = MemoryStore::default();
+        let breaker = CircuitBreaker::new(&store, 1, 30);
+        send(&breaker, NOW, false);
+
+        assert!(matches!(
+            send(&breaker, NOW + 40, false),
+            Admission::Probe(_)
+        ));
+        assert_eq!(breaker.retry_after(NOW + 40), Some(30));
+        assert_eq!(breaker.admit(NOW + 69, "req-2"), Admission::Open(1));
+        assert!(matches!(
+            breaker.admit(NOW + 70, "req-3"),
+            Admission::Probe(_)
+        ));
+    }
+
+    #[test]
+    fn test_lost_probe_is_replaced_in_next_cooldown_period() {
+        let store = MemoryStore::default();
+        let breaker = CircuitBreaker::new(&store, 1, 30);
+        send(&breaker, NOW, false);
+
+        // プローブの結果が記録されない（認証失敗で転送されなかった）場合
+        assert!(matches!(
+            breaker.admit(NOW + 30, "req-2"),
+            Admission::Probe(_)
+        ));
+        assert_eq!(breaker.admit(NOW + 59, "req-3"), Admission::Open(1));
+        assert!(matches!(
+            breaker.admit(NOW + 60, "req-4"),
+            Admission::Probe(_)
+        ));
+    }
+
+    #[test]
+    fn test_store_errors_fail_open() {
+        let breaker = CircuitBreaker::new(FailingStore, 1, 30);
+
+        assert_eq!(breaker.retry_after(NOW), None);
+        assert_eq!(breaker.admit(NOW, "req-1"), Admission::Closed);
+        // 記録の失敗はログのみ
+        breaker.record(&Admission::Closed, false, NOW, "req-1");
+    }
+}
@@ -0,0 +1,2827 @@
+//! # ゲートウェイの設定
+//!
+//! コア層の URL・パスプレフィックスごとの転送先・Edge 検証用シークレット・レート制限の上限・CORS の許可オリジン・
+//! リクエストボディの上限・レスポンスをストリーミングする閾値・パブリックパス・コア層に転送するヘッダー・コア層への転送のリトライ・タイムアウト・サーキットブレーカー・
+//! トークンの検証結果のキャッシュ・トークンを読み込む Cookie 名・メンテナンスモード・メトリクスを取得できる IP・ログの形式・
+//! API キー・クライアントに返さない内部ヘッダー・ルートのルールを
+//! Spin 変数から読み込みます。
+//! 同じ Wasm をステージング・本番にデプロイできるよう、値はビルド時ではなく
+//! リクエストごとに `spin_sdk::variables::get` で取得します。
+//!
+//! | Spin 変数 | 既定値（変数が未定義の場合） |
+//! |-----------|------------------------------|
+//! | `core_url` | `http://localhost:3001` |
+//! | `upstream_routes` | なし（`パスプレフィックス=URL` のカンマ区切り、一致しないパスは `core_url`） |
+//! | `core_canary_url` | なし（カナリアリリースのコア層の URL、canary_percent が 1 以上の場合は必須） |
+//! | `canary_percent` | `0`（core_url へのリクエストのうち core_canary_url に転送する割合、0〜100、0 で無効） |
+//! | `edge_secret` | `super-secret-edge-key` |
+//! | `rate_limit_per_minute` | `60`（ユーザー単位、0 で無効） |
+//! | `ip_rate_limit_per_minute` | `120`（IP 単位、0 で無効） |
+//! | `cors_allowed_origins` | なし（カンマ区切り、`*` で全てのオリジン） |
+//! | `max_body_bytes` | `1048576`（1 MiB、0 で無効） |
+//! | `max_body_bytes_by_path` | `/api/files=104923136,/api/todos/with-files=104923136`（`パス=バイト数` のカンマ区切り） |
+//! | `stream_threshold_bytes` | `1048576`（コア層のレスポンスの Content-Length がこれを超える場合はストリーミング、0 でダウンロードのみ） |
+//! | `public_paths` | `/api/auth/register,/api/auth/login`（カンマ区切り、末尾の `/*` で配下の全てのパス、解析できない場合は警告して既定値） |
+//! | `auth_policies` | なし（`メソッド パスプレフィックス=public\|authenticated` のカンマ区切り、一致しない場合は public_paths 以外の全てに認証が必要、解析できない場合は警告してなし、コア層は `X-User-Id` のないリクエストをまだ 401 にするため公開したパスは未対応） |
+//! | `forwarded_headers` | `Content-Type,Idempotency-Key,If-None-Match,Accept,Accept-Language,Accept-Encoding`（カンマ区切り） |
+//! | `proxy_max_retries` | `2`（冪等なリクエストのリトライ回数、0 で無効） |
+//! | `proxy_backoff_ms` | `100`（初回のリトライまでの待機時間、以降は倍々） |
+//! | `proxy_timeout_ms` | `30000`（コア層への転送のタイムアウト、0 で無効） |
+//! | `health_timeout_ms` | `2000`（/health の転送のタイムアウト、0 で無効） |
+//! | `health_degraded_ms` | `1000`（/health でコア層のレイテンシがこれを超えたら degraded、0 で無効） |
+//! | `circuit_failure_threshold` | `5`（回路を開く連続した送信の失敗の数、0 で無効） |
+//! | `circuit_cooldown_secs` | `30`（回路を開いてからプローブを許可するまでの秒数） |
+//! | `gateway_auth_cache` | `on`（`off` で検証結果のキャッシュを無効） |
+//! | `auth_cache_ttl_secs` | `30`（検証結果をキャッシュする秒数の上限、トークンの exp は超えない、0 で無効） |
+//! | `auth_cookie_name` | `access_token`（Authorization ヘッダーがない場合にトークンを読み込む Cookie 名） |
+//! | `max_token_bytes` | `8192`（8 KiB、超えるトークンは auth コンポーネントに渡さずに 401、0 で無効） |
+//! | `maintenance_mode` | `off`（`on` で /api/* に 503 を返し、コア層に転送しない） |
+//! | `maintenance_allow_paths` | なし（メンテナンス中も GET・HEAD を転送するパス、カンマ区切り、末尾の `/*` で配下の全てのパス） |
+//! | `maintenance_retry_after_secs` | `300`（メンテナンス中の 503 の Retry-After） |
+//! | `metrics_allowed_ips` | なし（/metrics・/gateway/metrics を取得できる IP、カンマ区切り、空の場合は全て） |
+//! | `log_format` | `text`（`json` で 1 行に 1 つの JSON オブジェクト） |
+//! | `trust_proxy_headers` | `off`（`on` でクライアントの X-Forwarded-For を引き継ぐ、ロードバランサーの背後で使う） |
+//! | `ip_denylist` | なし（403 を返すクライアントの CIDR、カンマ区切り、不正な要素は警告して無視） |
+//! | `ip_allowlist` | なし（指定した場合、ip_allowlist_paths のパスはこの CIDR 以外に 403、不正な要素は警告して無視） |
+//! | `ip_allowlist_paths` | なし（ip_allowlist を適用するパス、カンマ区切り、末尾の `/*` で配下の全てのパス、空の場合は全て） |
+//! | `head_fallback_to_get` | `on`（転送先が HEAD を 405 / 501 で拒否した場合に GET で再送し、ボディを捨てる） |
+//! | `slow_upstream_ms` | `1000`（コア層のレイテンシがこれを超えたら警告をログに出力、0 で無効） |
+//! | `large_response_bytes` | `10485760`（コア層のレスポンスのボディがこれを超えたら警告をログに出力、0 で無効） |
+//! | `internal_response_headers` | `X-Powered-By,X-Internal-*,X-Debug-*`（コア層のレスポンスからクライアントに返さないヘッダー名、カンマ区切り、末尾の `*` で前方一致） |
+//! | `api_keys` | なし（`X-Api-Key` で認証するキー、`<キーの SHA-256（16 進数）>=<サービスユーザー ID>` のカンマ区切り、api_key モジュール） |
+//! | `allowed_upstream_hosts` | 既定の core_url の `localhost:3001`（送信を許可する転送先の `ホスト:ポート`、カンマ区切り、outbound_guard モジュール） |
+//! | `route_rules` | なし（JSON の配列、埋め込みのルール `/api` より先に照合する、route_rules モジュール） |
+//!
+//! 値の検証は `GatewayConfig::resolve` で行い、Spin ランタイムなしでテストできます。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+// Spin 変数の取得とエラー型（Undefined: 変数が定義されていない）
+use spin_sdk::variables::{self, Error as VariableError};
+
+// 上限の数値（u32 / u64）の解析
+use std::str::FromStr;
+
+// タイムアウト
+use std::time::Duration;
+
+// メトリクスを取得できる IP
+use std::net::IpAddr;
+
+use crate::api_key::{self, ApiKey};
+use crate::canary::{self, Backend};
+use crate::ip_filter::{self, Cidr};
+use crate::logging::LogFormat;
+use crate::outbound_guard;
+use crate::route_rules::{self, Route, RouteMiss, RouteRule};
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// コア層の URL を読み込む Spin 変数名
+const CORE_URL_VARIABLE: &str = "core_url";
+
+/// パスプレフィックスごとの転送先（`パス=URL` のカンマ区切り）を読み込む Spin 変数名
+const UPSTREAM_ROUTES_VARIABLE: &str = "upstream_routes";
+
+/// カナリアリリースのコア層の URL を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_CORE_CANARY_URL）
+const CORE_CANARY_URL_VARIABLE: &str = "core_canary_url";
+
+/// カナリアに転送する割合（0〜100）を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_CANARY_PERCENT）
+const CANARY_PERCENT_VARIABLE: &str = "canary_percent";
+
+/// Edge 検証用シークレットを読み込む Spin 変数名
+const EDGE_SECRET_VARIABLE: &str = "edge_secret";
+
+/// ユーザー単位のレート制限（1 分あたりの上限）を読み込む Spin 変数名
+const USER_RATE_LIMIT_VARIABLE: &str = "rate_limit_per_minute";
+
+/// IP 単位のレート制限（1 分あたりの上限）を読み込む Spin 変数名
+const IP_RATE_LIMIT_VARIABLE: &str = "ip_rate_limit_per_minute";
+
+/// CORS の許可オリジン（カンマ区切り）を読み込む Spin 変数名
+const CORS_ALLOWED_ORIGINS_VARIABLE: &str = "cors_allowed_origins";
+
+/// リクエストボディの上限（バイト）を読み込む Spin 変数名
+const MAX_BODY_BYTES_VARIABLE: &str = "max_body_bytes";
+
+/// パスプレフィックスごとのリクエストボディの上限を読み込む Spin 変数名
+const BODY_LIMITS_VARIABLE: &str = "max_body_bytes_by_path";
+
+/// コア層のレスポンスをストリーミングする閾値（バイト）を読み込む Spin 変数名
+const STREAM_THRESHOLD_BYTES_VARIABLE: &str = "stream_threshold_bytes";
+
+/// 認証不要のパブリックパス（カンマ区切り）を読み込む Spin 変数名
+const PUBLIC_PATHS_VARIABLE: &str = "public_paths";
+
+/// メソッド・パスプレフィックスごとの認証ポリシー（カンマ区切り）を読み込む Spin 変数名
+/// （環境変数 SPIN_VARIABLE_AUTH_POLICIES）
+///
+/// public にしたリクエストは `X-User-Id` なしでコア層に転送するが、コア層の `UserContext` は
+/// `X-User-Id` のないリクエストを 401 にする。ユーザーを指定しない読み取りはコア層が未対応のため、
+/// Todo の API を public にしても現状は 401 が返る。
+const AUTH_POLICIES_VARIABLE: &str = "auth_policies";
+
+/// コア層に転送するクライアントのヘッダー名（カンマ区切り）を読み込む Spin 変数名
+const FORWARDED_HEADERS_VARIABLE: &str = "forwarded_headers";
+
+/// コア層への転送のリトライ回数を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_PROXY_MAX_RETRIES）
+const PROXY_MAX_RETRIES_VARIABLE: &str = "proxy_max_retries";
+
+/// リトライの初回の待機時間（ミリ秒）を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_PROXY_BACKOFF_MS）
+const PROXY_BACKOFF_MS_VARIABLE: &str = "proxy_backoff_ms";
+
+/// コア層への転送のタイムアウト（ミリ秒）を読み込む Spin 変数名
+const PROXY_TIMEOUT_MS_VARIABLE: &str = "proxy_timeout_ms";
+
+/// /health の転送のタイムアウト（ミリ秒）を読み込む Spin 変数名
+const HEALTH_TIMEOUT_MS_VARIABLE: &str = "health_timeout_ms";
+
+/// /health でコア層を degraded とするレイテンシ（ミリ秒）を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_HEALTH_DEGRADED_MS）
+const HEALTH_DEGRADED_MS_VARIABLE: &str = "health_degraded_ms";
+
+/// サーキットブレーカーの閾値（連続した失敗の数）を読み込む Spin 変数名
+const CIRCUIT_FAILURE_THRESHOLD_VARIABLE: &str = "circuit_failure_threshold";
+
+/// サーキットブレーカーのクールダウン期間（秒）を読み込む Spin 変数名
+const CIRCUIT_COOLDOWN_SECS_VARIABLE: &str = "circuit_cooldown_secs";
+
+/// 検証結果のキャッシュの有効・無効（on / off）を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_GATEWAY_AUTH_CACHE）
+const AUTH_CACHE_VARIABLE: &str = "gateway_auth_cache";
+
+/// 検証結果をキャッシュする期間の上限（秒）を読み込む Spin 変数名
+const AUTH_CACHE_TTL_SECS_VARIABLE: &str = "auth_cache_ttl_secs";
+
+/// トークンを読み込む Cookie 名を読み込む Spin 変数名
+const AUTH_COOKIE_NAME_VARIABLE: &str = "auth_cookie_name";
+
+/// トークンの長さの上限（バイト）を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_MAX_TOKEN_BYTES）
+const MAX_TOKEN_BYTES_VARIABLE: &str = "max_token_bytes";
+
+/// メンテナンスモードの有効・無効（on / off）を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_MAINTENANCE_MODE）
+const MAINTENANCE_MODE_VARIABLE: &str = "maintenance_mode";
+
+/// メンテナンス中も転送するパス（カンマ区切り）を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_MAINTENANCE_ALLOW_PATHS）
+const MAINTENANCE_ALLOW_PATHS_VARIABLE: &str = "maintenance_allow_paths";
+
+/// メンテナンス中の Retry-After（秒）を読み込む Spin 変数名
+const MAINTENANCE_RETRY_AFTER_SECS_VARIABLE: &str = "maintenance_retry_after_secs";
+
+/// メトリクスを取得できる IP（カンマ区切り）を読み込む Spin 変数名
+const METRICS_ALLOWED_IPS_VARIABLE: &str = "metrics_allowed_ips";
+
+/// ログの形式（text / json）を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_LOG_FORMAT）
+const LOG_FORMAT_VARIABLE: &str = "log_format";
+
+/// 受信した X-Forwarded-For を信頼するか（on / off）を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_TRUST_PROXY_HEADERS）
+const TRUST_PROXY_HEADERS_VARIABLE: &str = "trust_proxy_headers";
+
+/// 拒否するクライアントの CIDR（カンマ区切り）を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_IP_DENYLIST）
+const IP_DENYLIST_VARIABLE: &str = "ip_denylist";
+
+/// 許可するクライアントの CIDR（カンマ区切り）を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_IP_ALLOWLIST）
+const IP_ALLOWLIST_VARIABLE: &str = "ip_allowlist";
+
+/// ip_allowlist を適用するパス（カンマ区切り）を読み込む Spin 変数名
+const IP_ALLOWLIST_PATHS_VARIABLE: &str = "ip_allowlist_paths";
+
+/// HEAD を拒否された場合に GET で再送するか（on / off）を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_HEAD_FALLBACK_TO_GET）
+const HEAD_FALLBACK_TO_GET_VARIABLE: &str = "head_fallback_to_get";
+
+/// 遅いコア層のレスポンスを警告する閾値（ミリ秒）を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_SLOW_UPSTREAM_MS）
+const SLOW_UPSTREAM_MS_VARIABLE: &str = "slow_upstream_ms";
+
+/// 大きいコア層のレスポンスを警告する閾値（バイト）を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_LARGE_RESPONSE_BYTES）
+const LARGE_RESPONSE_BYTES_VARIABLE: &str = "large_response_bytes";
+
+/// コア層のレスポンスから取り除く内部ヘッダー名を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_INTERNAL_RESPONSE_HEADERS）
+const INTERNAL_RESPONSE_HEADERS_VARIABLE: &str = "internal_response_headers";
+
+/// API キーのハッシュとサービスユーザー ID を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_API_KEYS）
+const API_KEYS_VARIABLE: &str = "api_keys";
+
+/// 送信を許可する転送先のホストを読み込む Spin 変数名（環境変数 SPIN_VARIABLE_ALLOWED_UPSTREAM_HOSTS）
+const ALLOWED_UPSTREAM_HOSTS_VARIABLE: &str = "allowed_upstream_hosts";
+
+/// ルートのルール（JSON）を読み込む Spin 変数名（環境変数 SPIN_VARIABLE_ROUTE_RULES）
+const ROUTE_RULES_VARIABLE: &str = "route_rules";
+
+/// コア層（axum サーバー）の URL の既定値
+///
+/// Spin 変数 core_url が未定義・空の場合に使用します。
+const DEFAULT_CORE_URL: &str = "http://localhost:3001";
+
+/// Edge 検証用シークレットの既定値
+///
+/// Spin 変数 edge_secret が未定義・空の場合に使用します。
+/// 本番環境では必ず変数（SPIN_VARIABLE_EDGE_SECRET など）で設定すること。
+const DEFAULT_EDGE_SECRET: &str = "super-secret-edge-key";
+
+/// ユーザー単位のレート制限の既定値（1 分あたり）
+///
+/// 認証に成功したリクエストに適用する。
+const DEFAULT_USER_RATE_LIMIT: u32 = 60;
+
+/// IP 単位のレート制限の既定値（1 分あたり）
+///
+/// 認証前（パブリックパスを含む）の全てのリクエストに適用する。
+/// 同じ IP の背後に複数のユーザーがいる場合を考慮し、ユーザー単位より緩くする。
+const DEFAULT_IP_RATE_LIMIT: u32 = 120;
+
+/// リクエストボディの上限の既定値（1 MiB）
+///
+/// JSON を受け取るエンドポイント（/api/todos など）に適用する。
+const DEFAULT_MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+/// ファイルアップロード（multipart）のパスプレフィックス
+///
+/// ファイルのアップロードと TODO + ファイルの同時作成（/api/todos/with-files）。
+const UPLOAD_PATH_PREFIXES: &[&str] = &["/api/files", "/api/todos/with-files"];
+
+/// ファイルアップロードのリクエストボディの上限の既定値（100 MiB + 64 KiB）
+///
+/// コア層の DEFAULT_MAX_UPLOAD_BYTES（multipart の境界・ヘッダー分を含む）と揃える。
+const DEFAULT_UPLOAD_MAX_BODY_BYTES: u64 = 100 * 1024 * 1024 + 64 * 1024;
+
+/// コア層のレスポンスをストリーミングする閾値の既定値（1 MiB）
+///
+/// これを超える Content-Length のレスポンスは gateway のメモリに読み込まない。
+/// ファイルのダウンロード（/api/files/{id}/download）は閾値によらずストリーミングする。
+const DEFAULT_STREAM_THRESHOLD_BYTES: u64 = 1024 * 1024;
+
+/// コア層への転送のリトライ回数の既定値
+///
+/// 初回と合わせて最大 3 回送信する（コア層の再起動中の一時的な接続拒否を吸収する）。
+const DEFAULT_PROXY_MAX_RETRIES: u32 = 2;
+
+/// リトライの初回の待機時間の既定値（ミリ秒）
+const DEFAULT_PROXY_BACKOFF_MS: u64 = 100;
+
+/// コア層への転送のタイムアウトの既定値（ミリ秒）
+///
+/// リトライを含めた送信全体に適用する。
+const DEFAULT_PROXY_TIMEOUT_MS: u64 = 30_000;
+
+/// 遅いコア層のレスポンスを警告する閾値の既定値（ミリ秒）
+const DEFAULT_SLOW_UPSTREAM_MS: u64 = 1000;
+
+/// 大きいコア層のレスポンスを警告する閾値の既定値（10 MiB）
+const DEFAULT_LARGE_RESPONSE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// /health の転送のタイムアウトの既定値（ミリ秒）
+///
+/// ロードバランサーのヘルスプローブが待たされないよう短くする。
+const DEFAULT_HEALTH_TIMEOUT_MS: u64 = 2_000;
+
+/// /health でコア層を degraded とするレイテンシの既定値（ミリ秒）
+///
+/// タイムアウト（DEFAULT_HEALTH_TIMEOUT_MS）の半分。超えた場合も 200 を返し、ロードバランサーからは切り離さない。
+const DEFAULT_HEALTH_DEGRADED_MS: u64 = 1_000;
+
+/// サーキットブレーカーの閾値の既定値
+///
+/// リトライ後も送信に失敗したリクエストが 5 件続いたら回路を開く。
+const DEFAULT_CIRCUIT_FAILURE_THRESHOLD: u32 = 5;
+
+/// サーキットブレーカーのクールダウン期間の既定値（秒）
+const DEFAULT_CIRCUIT_COOLDOWN_SECS: u64 = 30;
+
+/// 検証結果をキャッシュする期間の上限の既定値（秒）
+///
+/// 実際の期間はトークンの残り有効期間（auth-result の expires-at から計算）と
+/// この値の小さい方。有効期限を過ぎたトークンの検証結果を使わないようにする。
+const DEFAULT_AUTH_CACHE_TTL_SECS: u64 = 30;
+
+/// トークンを読み込む Cookie 名の既定値
+///
+/// Web フロントエンドは httpOnly Cookie にトークンを保存する（Authorization ヘッダーを付けられない）。
+const DEFAULT_AUTH_COOKIE_NAME: &str = "access_token";
+
+/// トークンの長さの上限の既定値（8 KiB）
+///
+/// 通常の JWT（数百バイト〜数 KiB）より十分大きく、巨大なヘッダーを WIT 境界越しに
+/// auth コンポーネント（base64 のデコード）へ渡さないための値。
+const DEFAULT_MAX_TOKEN_BYTES: u64 = 8 * 1024;
+
+/// コア層のレスポンスから取り除く内部ヘッダー名の既定値（末尾の `*` は前方一致）
+///
+/// コア層・ミドルウェアが診断用に付与するヘッダー。ホップバイホップヘッダーと
+/// 識別ヘッダー（X-Edge-Verified など）は設定に関わらず常に取り除く（crate::core_headers）。
+const DEFAULT_INTERNAL_RESPONSE_HEADERS: &[&str] = &["X-Powered-By", "X-Internal-*", "X-Debug-*"];
+
+/// メンテナンスの対象となる API のパスプレフィックス
+///
+/// /health・/gateway/metrics はメンテナンス中も処理する（ヘルスチェックはコア層の状態をそのまま返す）。
+const API_PATH_PREFIX: &str = "/api";
+
+/// メンテナンス中の Retry-After の既定値（秒）
+///
+/// データベースのマイグレーションの所要時間の目安。クライアントはこの間隔で再試行する。
+const DEFAULT_MAINTENANCE_RETRY_AFTER_SECS: u64 = 300;
+
+// =============================================================================
+// 設定
+// =============================================================================
+
+/// リクエストごとに解決するゲートウェイの設定
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GatewayConfig {
+    /// コア層の URL（スキームとホストのみ、末尾の `/` なし）
+    /// 例: "http://localhost:3001"
+    pub(crate) core_url: String,
+
+    /// パスプレフィックスごとの転送先（最も長いプレフィックスを優先、一致しない場合は core_url）
+    pub(crate) upstream_routes: Vec<UpstreamRoute>,
+
+    /// カナリアリリースのコア層の URL（スキームとホストのみ、canary_percent が 0 の場合は使わない）
+    pub(crate) core_canary_url: Option<String>,
+
+    /// core_url へのリクエストのうち core_canary_url に転送する割合（0〜100、0 の場合は無効）
+    pub(crate) canary_percent: u8,
+
+    /// Edge 検証用シークレット（X-Edge-Verified ヘッダーの値）
+    pub(crate) edge_secret: String,
+
+    /// ユーザー単位のレート制限（1 分あたりの上限、0 の場合は制限しない）
+    pub(crate) user_rate_limit: u32,
+
+    /// IP 単位のレート制限（1 分あたりの上限、0 の場合は制限しない）
+    pub(crate) ip_rate_limit: u32,
+
+    /// CORS の許可オリジン（例: "https://app.example.com"、"*" は全て、空の場合は CORS 無効）
+    pub(crate) cors_allowed_origins: Vec<String>,
+
+    /// リクエストボディの上限（バイト、body_limits に一致しないパスに適用、0 の場合は制限しない）
+    pub(crate) max_body_bytes: u64,
+
+    /// パスプレフィックスごとのリクエストボディの上限（最も長いプレフィックスを優先）
+    pub(crate) body_limits: Vec<BodyLimit>,
+
+    /// コア層のレスポンスをストリーミングする Content-Length の閾値（バイト、0 の場合はダウンロードのみ）
+    pub(crate) stream_threshold_bytes: u64,
+
+    /// 認証不要のパブリックパスのパターン（例: "/api/auth/login"、"/api/public/*"）
+    ///
+    /// 末尾の `/*` はセグメント単位のプレフィックス一致、それ以外は完全一致（is_public_path）。
+    pub(crate) public_paths: Vec<String>,
+
+    /// メソッド・パスプレフィックスごとの認証ポリシー（public_paths より優先、auth_policy）
+    pub(crate) auth_policies: Vec<AuthPolicyRule>,
+
+    /// public_paths・auth_policies の解析エラー（既定値に戻した理由、/gateway/auth-rules で確認する）
+    pub(crate) auth_rule_errors: Vec<String>,
+
+    /// コア層に転送するクライアントのヘッダー名
+    ///
+    /// 識別ヘッダー・Authorization はここに含めても転送しない（lib.rs の client_headers）。
+    pub(crate) forwarded_headers: Vec<String>,
+
+    /// コア層への転送のリトライ回数（冪等なリクエストのみ、0 の場合はリトライしない）
+    pub(crate) proxy_max_retries: u32,
+
+    /// リトライの初回の待機時間（ミリ秒、以降は倍々でジッター付き）
+    pub(crate) proxy_backoff_ms: u64,
+
+    /// コア層への転送のタイムアウト（リトライを含む、0 の場合は無効）
+    pub(crate) proxy_timeout: Duration,
+
+    /// /health の転送のタイムアウト（0 の場合は無効）
+    pub(crate) health_timeout: Duration,
+
+    /// /health でコア層を degraded とするレイテンシ（0 の場合は無効）
+    pub(crate) health_degraded: Duration,
+
+    /// サーキットブレーカーの閾値（連続した送信の失敗の数、0 の場合は無効）
+    pub(crate) circuit_failure_threshold: u32,
+
+    /// サーキットブレーカーのクールダウン期間（秒）
+    pub(crate) circuit_cooldown_secs: u64,
+
+    /// トークンの検証結果をキャッシュするか（gateway_auth_cache=off の場合は false）
+    pub(crate) auth_cache_enabled: bool,
+
+    /// 検証結果をキャッシュする期間の上限（秒、0 の場合はキャッシュしない）
+    pub(crate) auth_cache_ttl_secs: u64,
+
+    /// Authorization ヘッダーがない場合にトークンを読み込む Cookie 名
+    pub(crate) auth_cookie_name: String,
+
+    /// トークンの長さの上限（バイト、超える場合は verify_token を呼び出さずに 401、0 の場合は制限しない）
+    pub(crate) max_token_bytes: u64,
+
+    /// メンテナンスモード（true の場合は /api/* に 503 を返す、maintenance_mode=on）
+    pub(crate) maintenance_mode: bool,
+
+    /// メンテナンス中も GET・HEAD を転送するパスのパターン（public_paths と同じ形式）
+    pub(crate) maintenance_allow_paths: Vec<String>,
+
+    /// メンテナンス中の 503 の Retry-After（秒）
+    pub(crate) maintenance_retry_after_secs: u64,
+
+    /// /metrics・/gateway/metrics を取得できる IP（空の場合は制限しない）
+    pub(crate) metrics_allowed_ips: Vec<IpAddr>,
+
+    /// ログの形式（log_format=json の場合は JSON）
+    pub(crate) log_format: LogFormat,
+
+    /// 受信した X-Forwarded-For を引き継ぐか（trust_proxy_headers=on、false の場合は接続元の IP のみ）
+    pub(crate) trust_proxy_headers: bool,
+
+    /// 403 を返すクライアントの範囲（不正な要素は読み飛ばしたもの）
+    pub(crate) ip_denylist: Vec<Cidr>,
+
+    /// 許可するクライアントの範囲（空の場合は制限しない）
+    pub(crate) ip_allowlist: Vec<Cidr>,
+
+    /// ip_allowlist を適用するパスのパターン（public_paths と同じ形式、空の場合は全てのパス）
+    pub(crate) ip_allowlist_paths: Vec<String>,
+
+    /// 転送先が HEAD を拒否した場合（405・501）に GET で再送するか（head_fallback_to_get=off で無効）
+    pub(crate) head_fallback_to_get: bool,
+
+    /// コア層のレイテンシ（リトライ・ボディの受信を含む）の警告の閾値（0 で無効）
+    pub(crate) slow_upstream: Duration,
+
+    /// コア層のレスポンスのボディの大きさの警告の閾値（バイト、0 で無効）
+    pub(crate) large_response_bytes: u64,
+
+    /// X-Api-Key で認証する API キー（空の場合は API キー認証を行わない）
+    pub(crate) api_keys: Vec<ApiKey>,
+
+    /// コア層のレスポンスからクライアントに返さないヘッダー名（末尾の `*` は前方一致）
+    pub(crate) internal_response_headers: Vec<String>,
+
+    /// 送信を許可する転送先の `ホスト:ポート`（小文字、既定は DEFAULT_CORE_URL のホストのみ）
+    pub(crate) allowed_upstream_hosts: Vec<String>,
+
+    /// 照合する順のルートのルール（Spin 変数 route_rules のルール、埋め込みの既定のルールの順）
+    pub(crate) route_rules: Vec<RouteRule>,
+}
+
+/// パスプレフィックスごとのリクエストボディの上限
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BodyLimit {
+    /// パスプレフィックス（例: "/api/files"、セグメント単位で一致）
+    pub(crate) prefix: String,
+
+    /// 上限（バイト、0 の場合は制限しない）
+    pub(crate) max_bytes: u64,
+}
+
+/// パスプレフィックスごとの転送先
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct UpstreamRoute {
+    /// パスプレフィックス（例: "/api/files"、セグメント単位で一致）
+    pub(crate) prefix: String,
+
+    /// 転送先の URL（スキームとホストのみ、末尾の `/` なし）
+    /// 例: "http://localhost:3002"
+    pub(crate) url: String,
+}
+
+/// 認証ポリシー（JWT 認証を行うかどうか）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum AuthPolicy {
+    /// 認証せずにコア層に転送する（X-User-Id を付与しない）
+    Public,
+
+    /// JWT 認証が必要
+    Authenticated,
+}
+
+/// メソッド・パスプレフィックスごとの認証ポリシー
+///
+/// 例: `GET /api/todos=public` は未認証の GET /api/todos・/api/todos/1 を許可し、
+/// POST・PATCH・DELETE には引き続き認証を要求する。
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AuthPolicyRule {
+    /// HTTP メソッド（大文字、None の場合は全てのメソッド、`*` で指定）
+    pub(crate) method: Option<String>,
+
+    /// パスプレフィックス（例: "/api/todos"、セグメント単位で一致）
+    pub(crate) prefix: String,
+
+    /// 一致したリクエストに適用するポリシー
+    pub(crate) policy: AuthPolicy,
+}
+
+/// Spin 変数の値（未定義の場合は None）
+///
+/// GatewayConfig::resolve の入力。テストでは直接組み立てます。
+#[derive(Debug, Default)]
+pub(crate) struct Variables {
+    /// core_url
+    pub(crate) core_url: Option<String>,
+
+    /// upstream_routes
+    pub(crate) upstream_routes: Option<String>,
+
+    /// core_canary_url
+    pub(crate) core_canary_url: Option<String>,
+
+    /// canary_percent
+    pub(crate) canary_percent: Option<String>,
+
+    /// edge_secret
+    pub(crate) edge_secret: Option<String>,
+
+    /// rate_limit_per_minute
+    pub(crate) user_rate_limit: Option<String>,
+
+    /// ip_rate_limit_per_minute
+    pub(crate) ip_rate_limit: Option<String>,
+
+    /// cors_allowed_origins
+    pub(crate) cors_allowed_origins: Option<String>,
+
+    /// max_body_bytes
+    pub(crate) max_body_bytes: Option<String>,
+
+    /// max_body_bytes_by_path
+    pub(crate) body_limits: Option<String>,
+
+    /// stream_threshold_bytes
+    pub(crate) stream_threshold_bytes: Option<String>,
+
+    /// public_paths
+    pub(crate) public_paths: Option<String>,
+
+    /// auth_policies
+    pub(crate) auth_policies: Option<String>,
+
+    /// forwarded_headers
+    pub(crate) forwarded_headers: Option<String>,
+
+    /// proxy_max_retries
+    pub(crate) proxy_max_retries: Option<String>,
+
+    /// proxy_backoff_ms
+    pub(crate) proxy_backoff_ms: Option<String>,
+
+    /// proxy_timeout_ms
+    pub(crate) proxy_timeout_ms: Option<String>,
+
+    /// health_timeout_ms
+    pub(crate) health_timeout_ms: Option<String>,
+
+    /// health_degraded_ms
+    pub(crate) health_degraded_ms: Option<String>,
+
+    /// circuit_failure_threshold
+    pub(crate) circuit_failure_threshold: Option<String>,
+
+    /// circuit_cooldown_secs
+    pub(crate) circuit_cooldown_secs: Option<String>,
+
+    /// gateway_auth_cache
+    pub(crate) auth_cache: Option<String>,
+
+    /// auth_cache_ttl_secs
+    pub(crate) auth_cache_ttl_secs: Option<String>,
+
+    /// auth_cookie_name
+    pub(crate) auth_cookie_name: Option<String>,
+
+    /// max_token_bytes
+    pub(crate) max_token_bytes: Option<String>,
+
+    /// maintenance_mode
+    pub(crate) maintenance_mode: Option<String>,
+
+    /// maintenance_allow_paths
+    pub(crate) maintenance_allow_paths: Option<String>,
+
+    /// maintenance_retry_after_secs
+    pub(crate) maintenance_retry_after_secs: Option<String>,
+
+    /// metrics_allowed_ips
+    pub(crate) metrics_allowed_ips: Option<String>,
+
+    /// log_format
+    pub(crate) log_format: Option<String>,
+
+    /// trust_proxy_headers
+    pub(crate) trust_proxy_headers: Option<String>,
+
+    /// ip_denylist
+    pub(crate) ip_denylist: Option<String>,
+
+    /// ip_allowlist
+    pub(crate) ip_allowlist: Option<String>,
+
+    /// ip_allowlist_paths
+    pub(crate) ip_allowlist_paths: Option<String>,
+
+    /// head_fallback_to_get
+    pub(crate) head_fallback_to_get: Option<String>,
+
+    /// slow_upstream_ms
+    pub(crate) slow_upstream_ms: Option<String>,
+
+    /// large_response_bytes
+    pub(crate) large_response_bytes: Option<String>,
+
+    /// api_keys
+    pub(crate) api_keys: Option<String>,
+
+    /// internal_response_headers
+    pub(crate) internal_response_headers: Option<String>,
+
+    /// allowed_upstream_hosts
+    pub(crate) allowed_upstream_hosts: Option<String>,
+
+    /// route_rules
+    pub(crate) route_rules: Option<String>,
+}
+
+impl GatewayConfig {
+    /// Spin 変数から設定を読み込む
+    ///
+    /// # 戻り値
+    /// * `Ok(GatewayConfig)` - 解決した設定
+    /// * `Err(String)` - 変数の取得に失敗した（未定義以外のエラー）、または値が不正な場合
+    pub(crate) fn load() -> Result<Self, String> {
+        Self::resolve(Variables {
+            core_url: read_variable(CORE_URL_VARIABLE)?,
+            core_canary_url: read_variable(CORE_CANARY_URL_VARIABLE)?,
+            canary_percent: read_variable(CANARY_PERCENT_VARIABLE)?,
+            upstream_routes: read_variable(UPSTREAM_ROUTES_VARIABLE)?,
+            edge_secret: read_variable(EDGE_SECRET_VARIABLE)?,
+            user_rate_limit: read_variable(USER_RATE_LIMIT_VARIABLE)?,
+            ip_rate_limit: read_variable(IP_RATE_LIMIT_VARIABLE)?,
+            cors_allowed_origins: read_variable(CORS_ALLOWED_ORIGINS_VARIABLE)?,
+            max_body_bytes: read_variable(MAX_BODY_BYTES_VARIABLE)?,
+            body_limits: read_variable(BODY_LIMITS_VARIABLE)?,
+            stream_threshold_bytes: read_variable(STREAM_THRESHOLD_BYTES_VARIABLE)?,
+            public_paths: read_variable(PUBLIC_PATHS_VARIABLE)?,
+            auth_policies: read_variable(AUTH_POLICIES_VARIABLE)?,
+            forwarded_headers: read_variable(FORWARDED_HEADERS_VARIABLE)?,
+            proxy_max_retries: read_variable(PROXY_MAX_RETRIES_VARIABLE)?,
+            proxy_backoff_ms: read_variable(PROXY_BACKOFF_MS_VARIABLE)?,
+            proxy_timeout_ms: read_variable(PROXY_TIMEOUT_MS_VARIABLE)?,
+            health_timeout_ms: read_variable(HEALTH_TIMEOUT_MS_VARIABLE)?,
+            health_degraded_ms: read_variable(HEALTH_DEGRADED_MS_VARIABLE)?,
+            circuit_failure_threshold: read_variable(CIRCUIT_FAILURE_THRESHOLD_VARIABLE)?,
+            circuit_cooldown_secs: read_variable(CIRCUIT_COOLDOWN_SECS_VARIABLE)?,
+            auth_cache: read_variable(AUTH_CACHE_VARIABLE)?,
+            auth_cache_ttl_secs: read_variable(AUTH_CACHE_TTL_SECS_VARIABLE)?,
+            auth_cookie_name: read_variable(AUTH_COOKIE_NAME_VARIABLE)?,
+            max_token_bytes: read_variable(MAX_TOKEN_BYTES_VARIABLE)?,
+            maintenance_mode: read_variable(MAINTENANCE_MODE_VARIABLE)?,
+            maintenance_allow_paths: read_variable(MAINTENANCE_ALLOW_PATHS_VARIABLE)?,
+            maintenance_retry_after_secs: read_variable(MAINTENANCE_RETRY_AFTER_SECS_VARIABLE)?,
+            metrics_allowed_ips: read_variable(METRICS_ALLOWED_IPS_VARIABLE)?,
+            log_format: read_variable(LOG_FORMAT_VARIABLE)?,
+            trust_proxy_headers: read_variable(TRUST_PROXY_HEADERS_VARIABLE)?,
+            ip_denylist: read_variable(IP_DENYLIST_VARIABLE)?,
+            ip_allowlist: read_variable(IP_ALLOWLIST_VARIABLE)?,
+            ip_allowlist_paths: read_variable(IP_ALLOWLIST_PATHS_VARIABLE)?,
+            head_fallback_to_get: read_variable(HEAD_FALLBACK_TO_GET_VARIABLE)?,
+            slow_upstream_ms: read_variable(SLOW_UPSTREAM_MS_VARIABLE)?,
+            large_response_bytes: read_variable(LARGE_RESPONSE_BYTES_VARIABLE)?,
+            api_keys: read_variable(API_KEYS_VARIABLE)?,
+            internal_response_headers: read_variable(INTERNAL_RESPONSE_HEADERS_VARIABLE)?,
+            allowed_upstream_hosts: read_variable(ALLOWED_UPSTREAM_HOSTS_VARIABLE)?,
+            route_rules: read_variable(ROUTE_RULES_VARIABLE)?,
+        })
+    }
+
+    /// 変数の値から設定を解決する
+    ///
+    /// 未定義（None）・空白のみの値は既定値に置き換えます。
+    ///
+    /// # 引数
+    /// * `variables` - Spin 変数の値
+    ///
+    /// # 戻り値
+    /// * `Ok(GatewayConfig)` - 解決した設定
+    /// * `Err(String)` - 値が不正な場合のエラーメッセージ
+    ///   （コア層・転送先の URL のスキームが http / https でない・ホストがない・パス・クエリを含む、
+    ///   転送先が `パス=URL` の形式でない、カナリアに転送する割合が 0〜100 でない・割合を指定したのにカナリアの URL がない、
+    ///   レート制限・ボディ・トークンの上限・ストリーミング・警告の閾値・リトライ・タイムアウト・サーキットブレーカー・キャッシュ期間・Retry-After の値が 0 以上の整数でない、許可オリジンがスキームとホストの形式でない、
+    ///   パスごとの上限が `パス=バイト数` の形式でない、メンテナンス中に転送するパス・ip_allowlist を適用するパスのパターンが不正、転送するヘッダー名が不正、
+    ///   gateway_auth_cache・maintenance_mode・trust_proxy_headers・head_fallback_to_get が on / off でない、Cookie 名が不正、メトリクスを取得できる IP が IP アドレスでない、
+    ///   ログの形式が text / json でない、取り除くレスポンスヘッダー名が不正、API キーが `SHA-256（16 進数）=ユーザー ID` の形式でない、
+    ///   送信を許可するホストが `ホスト:ポート` の形式でない、ルートのルールが解析できない）
+    ///
+    /// ip_denylist・ip_allowlist の不正な CIDR はエラーにせず、警告をログに出力して読み飛ばします。
+    /// public_paths・auth_policies が解析できない場合もエラーにせず、警告をログに出力して
+    /// 変数全体を既定値（crate::PUBLIC_PATHS・ポリシーなし）に戻します（全てのパスを公開しない）。
+    pub(crate) fn resolve(variables: Variables) -> Result<Self, String> {
+        let mut auth_rule_errors = Vec::new();
+        let public_paths = parse_public_paths(variables.public_paths).unwrap_or_else(|error| {
+            crate::logging::warn(&format!("{}; using default public paths", error));
+            auth_rule_errors.push(error);
+            default_public_paths()
+        });
+        let auth_policies = parse_auth_policies(variables.auth_policies).unwrap_or_else(|error| {
+            crate::logging::warn(&format!("{}; using no auth policies", error));
+            auth_rule_errors.push(error);
+            Vec::new()
+        });
+
+        let core_url =
+            non_empty(variables.core_url).unwrap_or_else(|| DEFAULT_CORE_URL.to_string());
+        let edge_secret =
+            non_empty(variables.edge_secret).unwrap_or_else(|| DEFAULT_EDGE_SECRET.to_string());
+        let (core_canary_url, canary_percent) =
+            parse_canary(variables.core_canary_url, variables.canary_percent)?;
+
+        Ok(Self {
+            core_url: validate_core_url(&core_url)?,
+            upstream_routes: parse_upstream_routes(variables.upstream_routes)?,
+            core_canary_url,
+            canary_percent,
+            edge_secret,
+            user_rate_limit: parse_limit(
+                USER_RATE_LIMIT_VARIABLE,
+                variables.user_rate_limit,
+                DEFAULT_USER_RATE_LIMIT,
+            )?,
+            ip_rate_limit: parse_limit(
+                IP_RATE_LIMIT_VARIABLE,
+                variables.ip_rate_limit,
+                DEFAULT_IP_RATE_LIMIT,
+            )?,
+            cors_allowed_origins: parse_origins(variables.cors_allowed_origins)?,
+            max_body_bytes: parse_limit(
+                MAX_BODY_BYTES_VARIABLE,
+                variables.max_body_bytes,
+                DEFAULT_MAX_BODY_BYTES,
+            )?,
+            body_limits: parse_body_limits(variables.body_limits)?,
+            stream_threshold_bytes: parse_limit(
+                STREAM_THRESHOLD_BYTES_VARIABLE,
+                variables.stream_threshold_bytes,
+                DEFAULT_STREAM_THRESHOLD_BYTES,
+            )?,
+            public_paths,
+            auth_policies,
+            auth_rule_errors,
+            forwarded_headers: parse_header_names(variables.forwarded_headers)?,
+            proxy_max_retries: parse_limit(
+                PROXY_MAX_RETRIES_VARIABLE,
+                variables.proxy_max_retries,
+                DEFAULT_PROXY_MAX_RETRIES,
+            )?,
+            proxy_backoff_ms: parse_limit(
+                PROXY_BACKOFF_MS_VARIABLE,
+                variables.proxy_backoff_ms,
+                DEFAULT_PROXY_BACKOFF_MS,
+            )?,
+            proxy_timeout: Duration::from_millis(parse_limit(
+                PROXY_TIMEOUT_MS_VARIABLE,
+                variables.proxy_timeout_ms,
+                DEFAULT_PROXY_TIMEOUT_MS,
+            )?),
+            health_timeout: Duration::from_millis(parse_limit(
+                HEALTH_TIMEOUT_MS_VARIABLE,
+                variables.health_timeout_ms,
+                DEFAULT_HEALTH_TIMEOUT_MS,
+            )?),
+            health_degraded: Duration::from_millis(parse_limit(
+                HEALTH_DEGRADED_MS_VARIABLE,
+                variables.health_degraded_ms,
+                DEFAULT_HEALTH_DEGRADED_MS,
+            )?),
+            circuit_failure_threshold: parse_limit(
+                CIRCUIT_FAILURE_THRESHOLD_VARIABLE,
+                variables.circuit_failure_threshold,
+                DEFAULT_CIRCUIT_FAILURE_THRESHOLD,
+            )?,
+            circuit_cooldown_secs: parse_limit(
+                CIRCUIT_COOLDOWN_SECS_VARIABLE,
+                variables.circuit_cooldown_secs,
+                DEFAULT_CIRCUIT_COOLDOWN_SECS,
+            )?,
+            auth_cache_enabled: parse_switch(AUTH_CACHE_VARIABLE, variables.auth_cache, true)?,
+            auth_cache_ttl_secs: parse_limit(
+                AUTH_CACHE_TTL_SECS_VARIABLE,
+                variables.auth_cache_ttl_secs,
+                DEFAULT_AUTH_CACHE_TTL_SECS,
+            )?,
+            auth_cookie_name: parse_cookie_name(variables.auth_cookie_name)?,
+            max_token_bytes: parse_limit(
+                MAX_TOKEN_BYTES_VARIABLE,
+                variables.max_token_bytes,
+                DEFAULT_MAX_TOKEN_BYTES,
+            )?,
+            maintenance_mode: parse_switch(
+                MAINTENANCE_MODE_VARIABLE,
+                variables.maintenance_mode,
+                false,
+            )?,
+            maintenance_allow_paths: match non_empty(variables.maintenance_allow_paths) {
+                Some(value) => parse_path_patterns(MAINTENANCE_ALLOW_PATHS_VARIABLE, &value)?,
+                None => Vec::new(),
+            },
+            maintenance_retry_after_secs: parse_limit(
+                MAINTENANCE_RETRY_AFTER_SECS_VARIABLE,
+                variables.maintenance_retry_after_secs,
+                DEFAULT_MAINTENANCE_RETRY_AFTER_SECS,
+            )?,
+            metrics_allowed_ips: parse_ips(variables.metrics_allowed_ips)?,
+            log_format: parse_log_format(variables.log_format)?,
+            trust_proxy_headers: parse_switch(
+                TRUST_PROXY_HEADERS_VARIABLE,
+                variables.trust_proxy_headers,
+                false,
+            )?,
+            ip_denylist: parse_cidr_list(IP_DENYLIST_VARIABLE, variables.ip_denylist),
+            ip_allowlist: parse_cidr_list(IP_ALLOWLIST_VARIABLE, variables.ip_allowlist),
+            ip_allowlist_paths: match non_empty(variables.ip_allowlist_paths) {
+                Some(value) => parse_path_patterns(IP_ALLOWLIST_PATHS_VARIABLE, &value)?,
+                None => Vec::new(),
+            },
+            head_fallback_to_get: parse_switch(
+                HEAD_FALLBACK_TO_GET_VARIABLE,
+                variables.head_fallback_to_get,
+                true,
+            )?,
+            slow_upstream: Duration::from_millis(parse_limit(
+                SLOW_UPSTREAM_MS_VARIABLE,
+                variables.slow_upstream_ms,
+                DEFAULT_SLOW_UPSTREAM_MS,
+            )?),
+            large_response_bytes: parse_limit(
+                LARGE_RESPONSE_BYTES_VARIABLE,
+                variables.large_response_bytes,
+                DEFAULT_LARGE_RESPONSE_BYTES,
+            )?,
+            api_keys: match non_empty(variables.api_keys) {
+                Some(value) => api_key::parse_api_keys(&value)?,
+                None => Vec::new(),
+            },
+            internal_response_headers: parse_internal_headers(variables.internal_response_headers)?,
+            allowed_upstream_hosts: parse_allowed_upstream_hosts(variables.allowed_upstream_hosts)?,
+            route_rules: parse_route_rules(variables.route_rules)?,
+        })
+    }
+
+    /// パスに適用するリクエストボディの上限を返す
+    ///
+    /// body_limits のうち最も長く一致するプレフィックスの上限、一致しない場合は max_body_bytes。
+    ///
+    /// # 引数
+    /// * `path` - リクエストパス（例: "/api/files/upload"）
+    ///
+    /// # 戻り値
+    /// * `u64` - 上限（バイト、0 の場合は制限しない）
+    pub(crate) fn body_limit(&self, path: &str) -> u64 {
+        self.body_limits
+            .iter()
+            .filter(|limit| path_has_prefix(path, &limit.prefix))
+            .max_by_key(|limit| limit.prefix.len())
+            .map_or(self.max_body_bytes, |limit| limit.max_bytes)
+    }
+
+    /// リクエストに適用するルートを返す（route_rules のうち最初に一致したルール）
+    ///
+    /// ルールで省略した項目は既存の設定で補う（認証: auth_policy、ボディの上限: body_limit、
+    /// タイムアウト: proxy_timeout、転送先: upstream_routes・core_url）。
+    ///
+    /// # 引数
+    /// * `method` - HTTP メソッド（大文字、例: "GET"）
+    /// * `path` - 正規化したリクエストパス
+    ///
+    /// # 戻り値
+    /// * `Ok(Route)` - 適用するルート
+    /// * `Err(RouteMiss)` - 一致するルールがない（404）、メソッドを許可していない（405）
+    pub(crate) fn route(&self, method: &str, path: &str) -> Result<Route<'_>, RouteMiss> {
+        let rule = route_rules::find(&self.route_rules, method, path)?;
+        Ok(Route {
+            prefix: &rule.prefix,
+            auth: rule.auth.unwrap_or_else(|| self.auth_policy(method, path)),
+            max_body_bytes: rule.max_body_bytes.unwrap_or_else(|| self.body_limit(path)),
+            timeout: rule.timeout.unwrap_or(self.proxy_timeout),
+            upstream: rule.upstream.as_deref(),
+        })
+    }
+
+    /// リクエストボディを読み込む際の上限を返す
+    ///
+    /// ルートに一致する場合はルートの上限、それ以外（/health など）は body_limit。
+    ///
+    /// # 引数
+    /// * `method` - HTTP メソッド（大文字）
+    /// * `path` - 正規化したリクエストパス
+    pub(crate) fn request_body_limit(&self, method: &str, path: &str) -> u64 {
+        self.route(method, path)
+            .map_or_else(|_| self.body_limit(path), |route| route.max_body_bytes)
+    }
+
+    /// パブリックパス（認証不要）かどうかを判定する
+    ///
+    /// `/api/public/*` は "/api/public" と "/api/public/docs" に一致し、"/api/publicity" には一致しない。
+    /// それ以外のパターンは完全一致のみ（"/api/auth/login" は "/api/auth/login-history" に一致しない）。
+    ///
+    /// # 引数
+    /// * `path` - リクエストパス
+    ///
+    /// # 戻り値
+    /// * `bool` - パブリックパスの場合 true
+    pub(crate) fn is_public_path(&self, path: &str) -> bool {
+        matches_path_patterns(&self.public_paths, path)
+    }
+
+    /// リクエストに適用する認証ポリシーを返す
+    ///
+    /// auth_policies のうちメソッドとパスプレフィックスが一致するもの（最も長いプレフィックス、
+    /// 同じ長さの場合はメソッドを指定したもの）を優先し、一致しない場合はパブリックパス（is_public_path）
+    /// なら Public、それ以外は Authenticated。auth_policies が空の場合は従来どおり。
+    ///
+    /// # 引数
+    /// * `method` - HTTP メソッド（例: "GET"）
+    /// * `path` - リクエストパス
+    ///
+    /// # 戻り値
+    /// * `AuthPolicy` - 適用するポリシー
+    pub(crate) fn auth_policy(&self, method: &str, path: &str) -> AuthPolicy {
+        let rule = self
+            .auth_policies
+            .iter()
+            .filter(|rule| {
+                rule.method
+                    .as_deref()
+                    .is_none_or(|rule_method| rule_method.eq_ignore_ascii_case(method))
+                    && path_has_prefix(path, &rule.prefix)
+            })
+            .max_by_key(|rule| (rule.prefix.len(), rule.method.is_some()));
+
+        match rule {
+            Some(rule) => rule.policy,
+            None if self.is_public_path(path) => AuthPolicy::Public,
+            None => AuthPolicy::Authenticated,
+        }
+    }
+
+    /// メンテナンス中のため 503 を返すリクエストかどうかを判定する
+    ///
+    /// maintenance_mode=on の場合の /api/* のうち、maintenance_allow_paths に一致する
+    /// 読み取り専用のリクエスト（GET・HEAD）以外が対象。
+    ///
+    /// # 引数
+    /// * `path` - リクエストパス
+    /// * `read_only` - GET・HEAD の場合 true
+    ///
+    /// # 戻り値
+    /// * `bool` - 503 を返す場合 true
+    pub(crate) fn is_under_maintenance(&self, path: &str, read_only: bool) -> bool {
+        self.maintenance_mode
+            && path_has_prefix(path, API_PATH_PREFIX)
+            && !(read_only && matches_path_patterns(&self.maintenance_allow_paths, path))
+    }
+
+    /// パスの転送先のベース URL を返す
+    ///
+    /// upstream_routes のうち最も長く一致するプレフィックスの URL、一致しない場合は core_url。
+    ///
+    /// # 引数
+    /// * `path` - リクエストパス（例: "/api/files/123/download"）
+    ///
+    /// # 戻り値
+    /// * `&str` - 例: "http://localhost:3002"
+    pub(crate) fn upstream_url(&self, path: &str) -> &str {
+        self.upstream_routes
+            .iter()
+            .filter(|route| path_has_prefix(path, &route.prefix))
+            .max_by_key(|route| route.prefix.len())
+            .map_or(&self.core_url, |route| &route.url)
+    }
+
+    /// カナリアリリースの転送先を選ぶ
+    ///
+    /// canary_percent が 0 の場合、upstream_routes に一致するパス（core_url 以外に転送するもの）の
+    /// 場合は None（振り分けない、X-Served-By を付与しない）。
+    ///
+    /// # 引数
+    /// * `path` - リクエストパス
+    /// * `key` - 振り分けのキー（認証済みの場合はユーザー ID、それ以外はリクエスト ID）
+    ///
+    /// # 戻り値
+    /// * `Option<Backend>` - 転送先（Primary: core_url、Canary: core_canary_url）
+    pub(crate) fn core_backend(&self, path: &str, key: &str) -> Option<Backend> {
+        if self.canary_percent == 0 || self.upstream_url(path) != self.core_url {
+            return None;
+        }
+        Some(canary::select(self.canary_percent, key))
+    }
+
+    /// メトリクスを返してよいクライアントかどうかを判定する
+    ///
+    /// metrics_allowed_ips が空の場合は全て許可する。指定した場合、アドレスが取得できない
+    /// クライアントは許可しない。IPv6 の表記の違い（"::1" と "0:0::1"）は区別しない。
+    ///
+    /// # 引数
+    /// * `client_ip` - クライアントの IP（spin-client-addr からポートを除いたもの）
+    ///
+    /// # 戻り値
+    /// * `bool` - 許可する場合 true
+    pub(crate) fn is_metrics_allowed(&self, client_ip: Option<&str>) -> bool {
+        if self.metrics_allowed_ips.is_empty() {
+            return true;
+        }
+        client_ip
+            .and_then(|ip| ip.parse::<IpAddr>().ok())
+            .is_some_and(|ip| self.metrics_allowed_ips.contains(&ip))
+    }
+
+    /// クライアントの IP からのリクエストを受け付けるかどうかを判定する（認証の前に判定する）
+    ///
+    /// ip_denylist に含まれる場合は拒否する。ip_allowlist を指定した場合、ip_allowlist_paths
+    /// （空の場合は全てのパス）のリクエストは ip_allowlist に含まれるもののみ許可し、
+    /// アドレスが取得できないクライアントは許可しない。
+    ///
+    /// # 引数
+    /// * `path` - リクエストパス
+    /// * `client_ip` - クライアントの IP（trust_proxy_headers=on の場合は信頼するプロキシが X-Forwarded-For の末尾に追加した IP）
+    ///
+    /// # 戻り値
+    /// * `bool` - 許可する場合 true
+    pub(crate) fn is_ip_allowed(&self, path: &str, client_ip: Option<IpAddr>) -> bool {
+        if client_ip.is_some_and(|ip| ip_filter::matches_any(&self.ip_denylist, &ip)) {
+            return false;
+        }
+        if self.ip_allowlist.is_empty()
+            || !(self.ip_allowlist_paths.is_empty()
+                || matches_path_patterns(&self.ip_allowlist_paths, path))
+        {
+            return true;
+        }
+        client_ip.is_some_and(|ip| ip_filter::matches_any(&self.ip_allowlist, &ip))
+    }
+
+    /// コア層（パスの転送先、upstream_url）への URL を組み立てる
+    ///
+    /// # 引数
+    /// * `path` - リクエストパス（例: "/api/todos"）
+    /// * `query` - クエリ文字列（`?` を含まない、空の場合は付けない）
+    ///
+    /// # 戻り値
+    /// * `String` - 例: "http://localhost:3001/api/todos?completed=false"
+    pub(crate) fn url(&self, path: &str, query: &str) -> String {
+        join_url(self.upstream_url(path), path, query)
+    }
+
+    /// 転送先（core_backend）に応じた URL を組み立てる
+    ///
+    /// Canary の場合は core_canary_url、それ以外は url と同じ。
+    ///
+    /// # 引数
+    /// * `path` - リクエストパス
+    /// * `query` - クエリ文字列（`?` を含まない、空の場合は付けない）
+    /// * `backend` - core_backend で選んだ転送先
+    pub(crate) fn backend_url(&self, path: &str, query: &str, backend: Option<Backend>) -> String {
+        match (backend, &self.core_canary_url) {
+            (Some(Backend::Canary), Some(canary_url)) => join_url(canary_url, path, query),
+            _ => self.url(path, query),
+        }
+    }
+
+    /// ルートの転送先と URL を決める
+    ///
+    /// ルールで転送先を指定した場合はその URL（カナリアリリースの振り分けなし）、
+    /// それ以外は core_backend・backend_url と同じ。
+    ///
+    /// # 引数
+    /// * `route` - route で決めたルート
+    /// * `path` - リクエストパス
+    /// * `query` - クエリ文字列（`?` を含まない、空の場合は付けない）
+    /// * `key` - カナリアリリースの振り分けのキー（core_backend と同じ）
+    ///
+    /// # 戻り値
+    /// * `(Option<Backend>, String)` - 振り分けた転送先（X-Served-By 用）と URL
+    pub(crate) fn route_target(
+        &self,
+        route: &Route,
+        path: &str,
+        query: &str,
+        key: &str,
+    ) -> (Option<Backend>, String) {
+        match route.upstream {
+            Some(upstream) => (None, join_url(upstream, path, query)),
+            None => {
+                let backend = self.core_backend(path, key);
+                (backend, self.backend_url(path, query, backend))
+            }
+        }
+    }
+}
+
+/// ベース URL・パス・クエリ文字列から URL を組み立てる（エンコードは upstream_url::build）
+fn join_url(base: &str, path: &str, query: &str) -> String {
+    crate::upstream_url::build(base, path, query)
+}
+
+/// Spin 変数を読む
+///
+/// 未定義の場合は None（既定値を使う）。それ以外のエラーは設定の誤りとして返す。
+fn read_variable(name: &str) -> Result<Option<String>, String> {
+    match variables::get(name) {
+        Ok(value) => Ok(Some(value)),
+        Err(VariableError::Undefined(_)) => Ok(None),
+        Err(e) => Err(format!("Failed to read variable {}: {}", name, e)),
+    }
+}
+
+/// 空白のみの値を None にする（前後の空白は取り除く）
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// 0 以上の整数の設定値（レート制限・ボディ・トークンの上限・ストリーミング・警告の閾値・リトライ・タイムアウト・サーキットブレーカー・キャッシュ期間・Retry-After）を解析する（未定義・空の場合は既定値）
+fn parse_limit<T: FromStr>(name: &str, value: Option<String>, default: T) -> Result<T, String> {
+    match non_empty(value) {
+        None => Ok(default),
+        Some(value) => value.parse().map_err(|_| {
+            format!(
+                "Invalid {} {:?}: must be a non-negative integer",
+                name, value
+            )
+        }),
+    }
+}
+
+/// on / off の設定値を解析する（大文字小文字を区別しない、未定義・空の場合は既定値）
+fn parse_switch(name: &str, value: Option<String>, default: bool) -> Result<bool, String> {
+    match non_empty(value) {
+        None => Ok(default),
+        Some(value) if value.eq_ignore_ascii_case("on") => Ok(true),
+        Some(value) if value.eq_ignore_ascii_case("off") => Ok(false),
+        Some(value) => Err(format!("Invalid {} {:?}: must be on or off", name, value)),
+    }
+}
+
+/// トークンを読み込む Cookie 名を解析する（未定義・空の場合は既定値）
+///
+/// Cookie 名は区切り文字（`=`・`;`・`,`・空白など）を含まない表示可能な ASCII のみ許可する。
+fn parse_cookie_name(value: Option<String>) -> Result<String, String> {
+    let Some(name) = non_empty(value) else {
+        return Ok(DEFAULT_AUTH_COOKIE_NAME.to_string());
+    };
+
+    let is_separator = |c: char| "()<>@,;:\\\"/[]?={}".contains(c);
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_graphic() && !is_separator(c))
+    {
+        return Err(format!(
+            "Invalid {} {:?}: must be a cookie name",
+            AUTH_COOKIE_NAME_VARIABLE, name
+        ));
+    }
+    Ok(name)
+}
+
+/// パスごとのリクエストボディの上限（`パス=バイト数` のカンマ区切り）を解析する
+///
+/// 未定義・空の場合は UPLOAD_PATH_PREFIXES（`/api/files`・`/api/todos/with-files`）に
+/// DEFAULT_UPLOAD_MAX_BODY_BYTES を適用する。
+/// パスは `/` で始まる必要があり、末尾の `/` は取り除く。
+fn parse_body_limits(value: Option<String>) -> Result<Vec<BodyLimit>, String> {
+    let Some(value) = non_empty(value) else {
+        return Ok(UPLOAD_PATH_PREFIXES
+            .iter()
+            .map(|prefix| BodyLimit {
+                prefix: prefix.to_string(),
+                max_bytes: DEFAULT_UPLOAD_MAX_BODY_BYTES,
+            })
+            .collect());
+    };
+
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let invalid =
+                |reason: &str| format!("Invalid {} {:?}: {}", BODY_LIMITS_VARIABLE, entry, reason);
+
+            let (prefix, max_bytes) = entry
+                .split_once('=')
+                .ok_or_else(|| invalid("must be <path>=<bytes>"))?;
+            let prefix = prefix.trim();
+            if !prefix.starts_with('/') {
+                return Err(invalid("path must start with /"));
+            }
+            let max_bytes = max_bytes
+                .trim()
+                .parse()
+                .map_err(|_| invalid("bytes must be a non-negative integer"))?;
+
+            Ok(BodyLimit {
+                prefix: match prefix.trim_end_matches('/') {
+                    "" => "/".to_string(),
+                    prefix => prefix.to_string(),
+                },
+                max_bytes,
+            })
+        })
+        .collect()
+}
+
+/// パスプレフィックスごとの転送先（`パス=URL` のカンマ区切り）を解析する
+///
+/// 未定義・空の場合は全てのパスを core_url に転送する。
+/// パスは `/` で始まる必要があり、末尾の `/` は取り除く。URL は core_url と同じくスキームとホストのみ。
+fn parse_upstream_routes(value: Option<String>) -> Result<Vec<UpstreamRoute>, String> {
+    let Some(value) = non_empty(value) else {
+        return Ok(Vec::new());
+    };
+
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let invalid = |reason: &str| {
+                format!(
+                    "Invalid {} {:?}: {}",
+                    UPSTREAM_ROUTES_VARIABLE, entry, reason
+                )
+            };
+
+            let (prefix, url) = entry
+                .split_once('=')
+                .ok_or_else(|| invalid("must be <path>=<url>"))?;
+            let prefix = prefix.trim();
+            if !prefix.starts_with('/') {
+                return Err(invalid("path must start with /"));
+            }
+            let url = validate_origin(url.trim()).map_err(invalid)?;
+
+            Ok(UpstreamRoute {
+                prefix: match prefix.trim_end_matches('/') {
+                    "" => "/".to_string(),
+                    prefix => prefix.to_string(),
+                },
+                url,
+            })
+        })
+        .collect()
+}
+
+/// 認証ポリシー（カンマ区切り、`メソッド パスプレフィックス=public|authenticated`）を解析する
+///
+/// 未定義・空の場合は空（public_paths 以外の全てに認証が必要）。メソッドは英字または `*`（全てのメソッド）、
+/// ポリシーは大文字小文字を区別しない。
+fn parse_auth_policies(value: Option<String>) -> Result<Vec<AuthPolicyRule>, String> {
+    let Some(value) = non_empty(value) else {
+        return Ok(Vec::new());
+    };
+
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let invalid = |reason: &str| {
+                format!("Invalid {} {:?}: {}", AUTH_POLICIES_VARIABLE, entry, reason)
+            };
+
+            let (target, policy) = entry
+                .split_once('=')
+                .ok_or_else(|| invalid("must be <method> <path>=public|authenticated"))?;
+            let (method, prefix) = target
+                .trim()
+                .split_once(' ')
+                .ok_or_else(|| invalid("must be <method> <path>=public|authenticated"))?;
+
+            let method = match method {
+                "*" => None,
+                method if !method.is_empty() && method.bytes().all(|b| b.is_ascii_alphabetic()) => {
+                    Some(method.to_ascii_uppercase())
+                }
+                _ => return Err(invalid("method must be letters or *")),
+            };
+            let prefix = prefix.trim();
+            if !prefix.starts_with('/') {
+                return Err(invalid("path must start with /"));
+            }
+            if prefix.contains(['*', ' ', '?', '#']) {
+                return Err(invalid(
+                    "path must be a prefix without *, spaces, a query or fragment",
+                ));
+            }
+            let policy = match policy.trim().to_ascii_lowercase().as_str() {
+                "public" => AuthPolicy::Public,
+                "authenticated" => AuthPolicy::Authenticated,
+                _ => return Err(invalid("policy must be public or authenticated")),
+            };
+
+            Ok(AuthPolicyRule {
+                method,
+                prefix: match prefix.trim_end_matches('/') {
+                    "" => "/".to_string(),
+                    prefix => prefix.to_string(),
+                },
+                policy,
+            })
+        })
+        .collect()
+}
+
+/// カナリアリリースの URL と割合を解析する
+///
+/// 割合は 0〜100 の整数（未定義・空の場合は 0）。1 以上の場合は URL が必須（core_url と同じ形式）。
+/// 割合が 0 の場合は URL を検証しない（振り分けを無効にしたまま URL を残せるように）。
+fn parse_canary(
+    url: Option<String>,
+    percent: Option<String>,
+) -> Result<(Option<String>, u8), String> {
+    let percent: u8 = parse_limit(CANARY_PERCENT_VARIABLE, percent, 0)?;
+    if percent > 100 {
+        return Err(format!(
+            "Invalid {} {:?}: must be between 0 and 100",
+            CANARY_PERCENT_VARIABLE, percent
+        ));
+    }
+    if percent == 0 {
+        return Ok((None, 0));
+    }
+
+    let url = non_empty(url).ok_or_else(|| {
+        format!(
+            "Invalid {}: {} is required when {} is greater than 0",
+            CANARY_PERCENT_VARIABLE, CORE_CANARY_URL_VARIABLE, CANARY_PERCENT_VARIABLE
+        )
+    })?;
+    let url = validate_origin(&url)
+        .map_err(|reason| format!("Invalid {} {:?}: {}", CORE_CANARY_URL_VARIABLE, url, reason))?;
+    Ok((Some(url), percent))
+}
+
+/// ルートのルール（JSON の配列）を解析する
+///
+/// 未定義・空の場合は埋め込みの既定のルールのみ。指定した場合はそのルールを既定のルールより先に照合する。
+fn parse_route_rules(value: Option<String>) -> Result<Vec<RouteRule>, String> {
+    let mut rules = match non_empty(value) {
+        Some(value) => route_rules::parse(&value)
+            .map_err(|reason| format!("Invalid {}: {}", ROUTE_RULES_VARIABLE, reason))?,
+        None => Vec::new(),
+    };
+    rules.extend(route_rules::default_rules());
+    Ok(rules)
+}
+
+/// パスがプレフィックスに一致するかをセグメント単位で判定する
+///
+/// "/api/files" は "/api/files" と "/api/files/upload" に一致し、"/api/filesystem" には一致しない。
+pub(crate) fn path_has_prefix(path: &str, prefix: &str) -> bool {
+    if prefix == "/" {
+        return true;
+    }
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// パブリックパスのパターン（カンマ区切り）を解析する
+///
+/// 未定義・空の場合は既定値（crate::PUBLIC_PATHS）。
+fn parse_public_paths(value: Option<String>) -> Result<Vec<String>, String> {
+    match non_empty(value) {
+        Some(value) => parse_path_patterns(PUBLIC_PATHS_VARIABLE, &value),
+        None => Ok(default_public_paths()),
+    }
+}
+
+/// パブリックパスの既定値（crate::PUBLIC_PATHS、未定義・空・解析できない場合）
+fn default_public_paths() -> Vec<String> {
+    crate::PUBLIC_PATHS
+        .iter()
+        .map(|path| path.to_string())
+        .collect()
+}
+
+/// パスのパターン（カンマ区切り、public_paths・maintenance_allow_paths）を解析する
+///
+/// 空の要素は無視する。パターンは `/` で始まる必要があり、`*` は末尾の `/*` としてのみ許可する
+/// （"/api/public*" のようにセグメントの途中で一致させると意図しないパスまで一致するため）。
+fn parse_path_patterns(name: &str, value: &str) -> Result<Vec<String>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(|pattern| {
+            let invalid = |reason: &str| format!("Invalid {} {:?}: {}", name, pattern, reason);
+
+            if !pattern.starts_with('/') {
+                return Err(invalid("path must start with /"));
+            }
+            if pattern.strip_suffix("/*").unwrap_or(pattern).contains('*') {
+                return Err(invalid("* is only allowed as a trailing /*"));
+            }
+            if pattern.contains([' ', '?', '#']) {
+                return Err(invalid("must not contain spaces, a query or fragment"));
+            }
+            Ok(pattern.to_string())
+        })
+        .collect()
+}
+
+/// パスがいずれかのパターンに一致するかを判定する
+///
+/// 末尾の `/*` はセグメント単位のプレフィックス一致（"/*" は全てのパス）、それ以外は完全一致。
+fn matches_path_patterns(patterns: &[String], path: &str) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| match pattern.strip_suffix("/*") {
+            Some(prefix) => path_has_prefix(path, if prefix.is_empty() { "/" } else { prefix }),
+            None => path == pattern,
+        })
+}
+
+/// コア層に転送するヘッダー名（カンマ区切り）を解析する
+///
+/// 未定義・空の場合は既定値（crate::FORWARDED_CLIENT_HEADERS）。空の要素は無視し、
+/// 大文字小文字だけが異なる重複は最初のものを残す。ヘッダー名は英数字と `-` のみ許可する。
+fn parse_header_names(value: Option<String>) -> Result<Vec<String>, String> {
+    let Some(value) = non_empty(value) else {
+        return Ok(crate::FORWARDED_CLIENT_HEADERS
+            .iter()
+            .map(|name| name.to_string())
+            .collect());
+    };
+
+    let mut names: Vec<String> = Vec::new();
+    for name in value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(format!(
+                "Invalid forwarded_headers {:?}: must be a header name",
+                name
+            ));
+        }
+        if !names.iter().any(|known| known.eq_ignore_ascii_case(name)) {
+            names.push(name.to_string());
+        }
+    }
+    Ok(names)
+}
+
+/// コア層のレスポンスから取り除く内部ヘッダー名（カンマ区切り）を解析する
+///
+/// 未定義・空の場合は既定値（DEFAULT_INTERNAL_RESPONSE_HEADERS）。空の要素は無視し、
+/// 大文字小文字だけが異なる重複は最初のものを残す。ヘッダー名は英数字と `-` のみで、
+/// 末尾に `*` を付けた場合は前方一致（例: `X-Debug-*`）。
+fn parse_internal_headers(value: Option<String>) -> Result<Vec<String>, String> {
+    let Some(value) = non_empty(value) else {
+        return Ok(DEFAULT_INTERNAL_RESPONSE_HEADERS
+            .iter()
+            .map(|name| name.to_string())
+            .collect());
+    };
+
+    let mut names: Vec<String> = Vec::new();
+    for name in value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        let prefix = name.strip_suffix('*').unwrap_or(name);
+        if prefix.is_empty()
+            || !prefix
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err(format!(
+                "Invalid {} {:?}: must be a header name or a prefix ending with *",
+                INTERNAL_RESPONSE_HEADERS_VARIABLE, name
+            ));
+        }
+        if !names.iter().any(|known| known.eq_ignore_ascii_case(name)) {
+            names.push(name.to_string());
+        }
+    }
+    Ok(names)
+}
+
+/// 送信を許可する転送先のホスト（カンマ区切り、`ホスト:ポート`）を解析する
+///
+/// 未定義・空の場合は既定の core_url（DEFAULT_CORE_URL）のホストとポートのみ。
+/// core_url などを変更する場合は、この変数にも転送先のホストを追加する。
+fn parse_allowed_upstream_hosts(value: Option<String>) -> Result<Vec<String>, String> {
+    match non_empty(value) {
+        Some(value) => outbound_guard::parse_allowed_hosts(&value),
+        None => Ok(outbound_guard::authority(DEFAULT_CORE_URL)
+            .into_iter()
+            .collect()),
+    }
+}
+
+/// ログの形式（text / json、大文字小文字を区別しない）を解析する（未定義・空の場合は text）
+fn parse_log_format(value: Option<String>) -> Result<LogFormat, String> {
+    match non_empty(value) {
+        None => Ok(LogFormat::Text),
+        Some(value) => LogFormat::parse(&value).ok_or_else(|| {
+            format!(
+                "Invalid {} {:?}: must be text or json",
+                LOG_FORMAT_VARIABLE, value
+            )
+        }),
+    }
+}
+
+/// IP アドレス（カンマ区切り）を解析する（未定義・空の場合は空リスト）
+fn parse_ips(value: Option<String>) -> Result<Vec<IpAddr>, String> {
+    let Some(value) = non_empty(value) else {
+        return Ok(Vec::new());
+    };
+
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|ip| !ip.is_empty())
+        .map(|ip| {
+            ip.parse().map_err(|_| {
+                format!(
+                    "Invalid {} {:?}: must be an IP address",
+                    METRICS_ALLOWED_IPS_VARIABLE, ip
+                )
+            })
+        })
+        .collect()
+}
+
+/// CIDR の一覧（カンマ区切り）を解析する（未定義・空の場合は空、不正な要素は警告して読み飛ばす）
+fn parse_cidr_list(name: &str, value: Option<String>) -> Vec<Cidr> {
+    non_empty(value).map_or_else(Vec::new, |value| ip_filter::parse_cidrs(name, &value))
+}
+
+/// CORS の許可オリジン（カンマ区切り）を解析する
+///
+/// 空の要素は無視し、末尾の `/` を取り除く。`*` 以外はスキームとホスト（ポート）の形式のみ許可する。
+fn parse_origins(value: Option<String>) -> Result<Vec<String>, String> {
+    let Some(value) = non_empty(value) else {
+        return Ok(Vec::new());
+    };
+
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|origin| !origin.is_empty())
+        .map(|origin| {
+            if origin == crate::cors::ANY_ORIGIN {
+                return Ok(origin.to_string());
+            }
+            validate_origin(origin)
+                .map_err(|reason| format!("Invalid cors_allowed_origins {:?}: {}", origin, reason))
+        })
+        .collect()
+}
+
+/// スキーム（http / https）とホストだけからなる URL を検証し、末尾の `/` を取り除く
+///
+/// # 戻り値
+/// * `Ok(String)` - 末尾の `/` を取り除いた URL
+/// * `Err(&str)` - 不正な理由
+pub(crate) fn validate_origin(url: &str) -> Result<String, &'static str> {
+    let rest = url
+        .strip_prefix("http://")
+        .or_else(|| url.strip_prefix("https://"))
+        .ok_or("must start with http:// or https://")?;
+
+    let authority = rest.trim_end_matches('/');
+    if authority.is_empty() {
+        return Err("missing host");
+    }
+    if authority.contains(['/', '?', '#', ' ']) {
+        return Err("must not contain a path, query or fragment");
+    }
+
+    Ok(url.trim_end_matches('/').to_string())
+}
+
+/// コア層の URL を検証し、末尾の `/` を取り除く
+///
+/// パスはリクエストのパスをそのまま連結するため、URL にはスキームとホスト（ポート）だけを許可します。
+fn validate_core_url(url: &str) -> Result<String, String> {
+    validate_origin(url).map_err(|reason| format!("Invalid core_url {:?}: {}", url, reason))
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn some(value: &str) -> Option<String> {
+        Some(value.to_string())
+    }
+
+    /// core_url だけを指定した変数
+    fn with_core_url(url: &str) -> Variables {
+        Variables {
+            core_url: some(url),
+            ..Variables::default()
+        }
+    }
+
+    #[test]
+    fn test_resolve_uses_defaults_when_missing_or_blank() {
+        let expected = GatewayConfig {
+            core_url: DEFAULT_CORE_URL.to_string(),
+            core_canary_url: None,
+            canary_percent: 0,
+            upstream_routes: Vec::new(),
+            edge_secret: DEFAULT_EDGE_SECRET.to_string(),
+            user_rate_limit: DEFAULT_USER_RATE_LIMIT,
+            ip_rate_limit: DEFAULT_IP_RATE_LIMIT,
+            cors_allowed_origins: Vec::new(),
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            body_limits: vec![
+                BodyLimit {
+                    prefix: "/api/files".to_string(),
+                    max_bytes: DEFAULT_UPLOAD_MAX_BODY_BYTES,
+                },
+                BodyLimit {
+                    prefix: "/api/todos/with-files".to_string(),
+                    max_bytes: DEFAULT_UPLOAD_MAX_BODY_BYTES,
+                },
+            ],
+            stream_threshold_bytes: DEFAULT_STREAM_THRESHOLD_BYTES,
+            public_paths: crate::PUBLIC_PATHS
+                .iter()
+                .map(|path| path.to_string())
+                .collect(),
+            auth_policies: Vec::new(),
+            auth_rule_errors: Vec::new(),
+            forwarded_headers: crate::FORWARDED_CLIENT_HEADERS
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            proxy_max_retries: DEFAULT_PROXY_MAX_RETRIES,
+            proxy_backoff_ms: DEFAULT_PROXY_BACKOFF_MS,
+            proxy_timeout: Duration::from_millis(DEFAULT_PROXY_TIMEOUT_MS),
+            health_timeout: Duration::from_millis(DEFAULT_HEALTH_TIMEOUT_MS),
+            health_degraded: Duration::from_millis(DEFAULT_HEALTH_DEGRADED_MS),
+            circuit_failure_threshold: DEFAULT_CIRCUIT_FAILURE_THRESHOLD,
+            circuit_cooldown_secs: DEFAULT_CIRCUIT_COOLDOWN_SECS,
+            auth_cache_enabled: true,
+            auth_cache_ttl_secs: DEFAULT_AUTH_CACHE_TTL_SECS,
+            auth_cookie_name: DEFAULT_AUTH_COOKIE_NAME.to_string(),
+            max_token_bytes: DEFAULT_MAX_TOKEN_BYTES,
+            maintenance_mode: false,
+            maintenance_allow_paths: Vec::new(),
+            maintenance_retry_after_secs: DEFAULT_MAINTENANCE_RETRY_AFTER_SECS,
+            metrics_allowed_ips: Vec::new(),
+            log_format: LogFormat::Text,
+            trust_proxy_headers: false,
+            ip_denylist: Vec::new(),
+            ip_allowlist: Vec::new(),
+            ip_allowlist_paths: Vec::new(),
+            head_fallback_to_get: true,
+            slow_upstream: Duration::from_millis(DEFAULT_SLOW_UPSTREAM_MS),
+            large_response_bytes: DEFAULT_LARGE_RESPONSE_BYTES,
+            api_keys: Vec::new(),
+            internal_response_headers: DEFAULT_INTERNAL_RESPONSE_HEADERS
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            allowed_upstream_hosts: vec!["localhost:3001".to_string()],
+            route_rules: route_rules::default_rules(),
+        };
+
+        assert_eq!(
+            GatewayConfig::resolve(Variables::default()).unwrap(),
+            expected
+        );
+        let blank = Variables {
+            core_url: some(""),
+            core_canary_url: some(" "),
+            canary_percent: some(""),
+            upstream_routes: some(" "),
+            edge_secret: some("  "),
+            user_rate_limit: some(""),
+            ip_rate_limit: some(" "),
+            cors_allowed_origins: some(" "),
+            max_body_bytes: some(""),
+            body_limits: some(" "),
+            stream_threshold_bytes: some(""),
+            public_paths: some(" "),
+            auth_policies: some(""),
+            forwarded_headers: some(""),
+            proxy_max_retries: some(""),
+            proxy_backoff_ms: some(" "),
+            proxy_timeout_ms: some(" "),
+            health_timeout_ms: some(""),
+            health_degraded_ms: some(" "),
+            circuit_failure_threshold: some(""),
+            circuit_cooldown_secs: some(""),
+            auth_cache: some(" "),
+            auth_cache_ttl_secs: some(""),
+            auth_cookie_name: some(" "),
+            max_token_bytes: some(""),
+            maintenance_mode: some(""),
+            maintenance_allow_paths: some(" "),
+            maintenance_retry_after_secs: some(""),
+            metrics_allowed_ips: some(" "),
+            log_format: some(""),
+            trust_proxy_headers: some(" "),
+            ip_denylist: some(""),
+            ip_allowlist: some(" "),
+            ip_allowlist_paths: some(""),
+            head_fallback_to_get: some(" "),
+            slow_upstream_ms: some(""),
+            large_response_bytes: some(" "),
+            api_keys: some(""),
+            internal_response_headers: some(" "),
+            allowed_upstream_hosts: some(""),
+            route_rules: some(" "),
+        };
+        assert_eq!(GatewayConfig::resolve(blank).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_resolve_uses_variables() {
+        let config = GatewayConfig::resolve(Variables {
+            core_url: some(" https://core.staging.example.com/ "),
+            core_canary_url: some("https://core-canary.staging.example.com/"),
+            canary_percent: some("5"),
+            upstream_routes: some("/api/files/ = http://files:3002/,,"),
+            edge_secret: some("s3cret"),
+            user_rate_limit: some("30"),
+            ip_rate_limit: some(" 0 "),
+            cors_allowed_origins: some("https://app.example.com/, http://localhost:5173,,"),
+            max_body_bytes: some("2048"),
+            body_limits: some("/api/files/=0, /api/todos/bulk = 65536"),
+            stream_threshold_bytes: some("0"),
+            public_paths: some("/api/auth/login, /api/public/*,,"),
+            auth_policies: some("get /api/todos/ = Public, * /api/todos/private=authenticated,,"),
+            forwarded_headers: some("Content-Type, If-Match,,content-type"),
+            proxy_max_retries: some("0"),
+            proxy_backoff_ms: some("250"),
+            proxy_timeout_ms: some("5000"),
+            health_timeout_ms: some("0"),
+            health_degraded_ms: some("300"),
+            circuit_failure_threshold: some("0"),
+            circuit_cooldown_secs: some("10"),
+            auth_cache: some(" OFF "),
+            auth_cache_ttl_secs: some("5"),
+            auth_cookie_name: some(" __Host-token "),
+            max_token_bytes: some("4096"),
+            maintenance_mode: some("on"),
+            maintenance_allow_paths: some("/api/todos, /api/files/*"),
+            maintenance_retry_after_secs: some("600"),
+            metrics_allowed_ips: some("10.0.0.7, ::1,,"),
+            log_format: some(" Json "),
+            trust_proxy_headers: some("on"),
+            ip_denylist: some("203.0.113.0/24, bogus"),
+            ip_allowlist: some("10.0.0.0/8, 2001:db8::/32"),
+            ip_allowlist_paths: some("/api/admin/*"),
+            head_fallback_to_get: some("off"),
+            slow_upstream_ms: some("250"),
+            large_response_bytes: some("0"),
+            // SHA-256("cron-key")
+            api_keys: some(
+                " e4e5219a9a7e2594c2d6c2bae2142876bf654aa945f485d94094c9e3044168e9=svc-cron ",
+            ),
+            internal_response_headers: some("X-Backend-Node, x-trace-*, X-BACKEND-NODE,"),
+            allowed_upstream_hosts: some("core.staging.example.com:443, Files:3002"),
+            route_rules: some(
+                r#"[{"prefix": "/api/status", "methods": ["GET"], "auth": "public"}]"#,
+            ),
+        })
+        .unwrap();
+
+        assert_eq!(config.core_url, "https://core.staging.example.com");
+        assert_eq!(
+            config.core_canary_url.as_deref(),
+            Some("https://core-canary.staging.example.com")
+        );
+        assert_eq!(config.canary_percent, 5);
+        assert_eq!(
+            config.upstream_routes,
+            vec![UpstreamRoute {
+                prefix: "/api/files".to_string(),
+                url: "http://files:3002".to_string(),
+            }]
+        );
+        assert_eq!(config.edge_secret, "s3cret");
+        assert_eq!(config.user_rate_limit, 30);
+        assert_eq!(config.ip_rate_limit, 0);
+        assert_eq!(
+            config.cors_allowed_origins,
+            vec!["https://app.example.com", "http://localhost:5173"]
+        );
+        assert_eq!(config.max_body_bytes, 2048);
+        assert_eq!(config.stream_threshold_bytes, 0);
+        assert_eq!(
+            config.public_paths,
+            vec!["/api/auth/login", "/api/public/*"]
+        );
+        assert_eq!(
+            config.auth_policies,
+            vec![
+                AuthPolicyRule {
+                    method: Some("GET".to_string()),
+                    prefix: "/api/todos".to_string(),
+                    policy: AuthPolicy::Public,
+                },
+                AuthPolicyRule {
+                    method: None,
+                    prefix: "/api/todos/private".to_string(),
+                    policy: AuthPolicy::Authenticated,
+                },
+            ]
+        );
+        assert_eq!(config.forwarded_headers, vec!["Content-Type", "If-Match"]);
+        assert_eq!(config.proxy_max_retries, 0);
+        assert_eq!(config.proxy_backoff_ms, 250);
+        assert_eq!(config.proxy_timeout, Duration::from_secs(5));
+        assert_eq!(config.health_timeout, Duration::ZERO);
+        assert_eq!(config.health_degraded, Duration::from_millis(300));
+        assert_eq!(config.circuit_failure_threshold, 0);
+        assert_eq!(config.circuit_cooldown_secs, 10);
+        assert!(!config.auth_cache_enabled);
+        assert_eq!(config.auth_cache_ttl_secs, 5);
+        assert_eq!(config.auth_cookie_name, "__Host-token");
+        assert_eq!(config.max_token_bytes, 4096);
+        assert!(config.maintenance_mode);
+        assert_eq!(
+            config.maintenance_allow_paths,
+            vec!["/api/todos", "/api/files/*"]
+        );
+        assert_eq!(config.maintenance_retry_after_secs, 600);
+        assert_eq!(config.log_format, LogFormat::Json);
+        assert!(config.trust_proxy_headers);
+        assert_eq!(
+            config.ip_denylist,
+            vec![Cidr::parse("203.0.113.0/24").unwrap()]
+        );
+        assert_eq!(
+            config.ip_allowlist,
+            vec![
+                Cidr::parse("10.0.0.0/8").unwrap(),
+                Cidr::parse("2001:db8::/32").unwrap()
+            ]
+        );
+        assert_eq!(config.ip_allowlist_paths, vec!["/api/admin/*"]);
+        assert!(!config.head_fallback_to_get);
+        assert_eq!(config.slow_upstream, Duration::from_millis(250));
+        assert_eq!(config.large_response_bytes, 0);
+        assert_eq!(
+            api_key::authenticate(&config.api_keys, "cron-key"),
+            Some("svc-cron")
+        );
+        assert_eq!(
+            config.internal_response_headers,
+            vec!["X-Backend-Node", "x-trace-*"]
+        );
+        assert_eq!(
+            config.allowed_upstream_hosts,
+            vec!["core.staging.example.com:443", "files:3002"]
+        );
+        // 変数のルールを既定のルールより先に照合する
+        assert_eq!(config.route_rules.len(), 2);
+        assert_eq!(config.route_rules[0].prefix, "/api/status");
+        assert_eq!(config.route_rules[1], route_rules::default_rules()[0]);
+        assert_eq!(
+            config.metrics_allowed_ips,
+            vec![
+                "10.0.0.7".parse::<IpAddr>().unwrap(),
+                "::1".parse::<IpAddr>().unwrap()
+            ]
+        );
+        assert_eq!(
+            config.body_limits,
+            vec![
+                BodyLimit {
+                    prefix: "/api/files".to_string(),
+                    max_bytes: 0,
+                },
+                BodyLimit {
+                    prefix: "/api/todos/bulk".to_string(),
+                    max_bytes: 65536,
+                },
+            ]
+        );
+
+        let config = GatewayConfig::resolve(with_core_url("http://10.0.0.5:3001")).unwrap();
+        assert_eq!(config.core_url, "http://10.0.0.5:3001");
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_rate_limit() {
+        for value in ["-1", "1.5", "sixty", "4294967296"] {
+            let error = GatewayConfig::resolve(Variables {
+                user_rate_limit: some(value),
+                ..Variables::default()
+            })
+            .unwrap_err();
+            assert!(
+                error.starts_with("Invalid rate_limit_per_minute"),
+                "{}",
+                error
+            );
+        }
+
+        let error = GatewayConfig::resolve(Variables {
+            ip_rate_limit: some("lots"),
+            ..Variables::default()
+        })
+        .unwrap_err();
+        assert!(error.starts_with("Invalid ip_rate_limit_per_minute"));
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_retry_settings() {
+        let error = GatewayConfig::resolve(Variables {
+            proxy_max_retries: some("-1"),
+            ..Variables::default()
+        })
+        .unwrap_err();
+        assert!(error.starts_with("Invalid proxy_max_retries"), "{}", error);
+
+        let error = GatewayConfig::resolve(Variables {
+            proxy_backoff_ms: some("100ms"),
+            ..Variables::default()
+        })
+        .unwrap_err();
+        assert!(error.starts_with("Invalid proxy_backoff_ms"), "{}", error);
+
+        let error = GatewayConfig::resolve(Variables {
+            proxy_timeout_ms: some("30s"),
+            ..Variables::default()
+        })
+        .unwrap_err();
+        assert!(error.starts_with("Invalid proxy_timeout_ms"), "{}", error);
+
+        let error = GatewayConfig::resolve(Variables {
+            health_degraded_ms: some("1s"),
+            ..Variables::default()
+        })
+        .unwrap_err();
+        assert!(error.starts_with("Invalid health_degraded_ms"), "{}", error);
+
+        let error = GatewayConfig::resolve(Variables {
+            stream_threshold_bytes: some("1MiB"),
+            ..Variables::default()
+        })
+        .unwrap_err();
+        assert!(
+            error.starts_with("Invalid stream_threshold_bytes"),
+            "{}",
+            error
+        );
+
+        let error = GatewayConfig::resolve(Variables {
+            slow_upstream_ms: some("1s"),
+            ..Variables::default()
+        })
+        .unwrap_err();
+        assert!(error.starts_with("Invalid slow_upstream_ms"), "{}", error);
+
+        let error = GatewayConfig::resolve(Variables {
+            large_response_bytes: some("-1"),
+            ..Variables::default()
+        })
+        .unwrap_err();
+        assert!(
+            error.starts_with("Invalid large_response_bytes"),
+            "{}",
+            error
+        );
+
+        let error = GatewayConfig::resolve(Variables {
+            api_keys: some("cron-key=svc-cron"),
+            ..Variables::default()
+        })
+        .unwrap_err();
+        assert!(error.starts_with("Invalid api_keys"), "{}", error);
+
+        let error = GatewayConfig::resolve(Variables {
+            circuit_failure_threshold: some("five"),
+            ..Variables::default()
+        })
+        .unwrap_err();
+        assert!(
+            error.starts_with("Invalid circuit_failure_threshold"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_resolve_auth_cache_settings() {
+        let resolve = |value: &str| {
+            GatewayConfig::resolve(Variables {
+                auth_cache: some(value),
+                ..Variables::default()
+            })
+        };
+
+        assert!(resolve("on").unwrap().auth_cache_enabled);
+        assert!(resolve("On").unwrap().auth_cache_enabled);
+        assert!(!resolve("off").unwrap().auth_cache_enabled);
+        for value in ["false", "0", "disabled"] {
+            let error = resolve(value).unwrap_err();
+            assert!(error.starts_with("Invalid gateway_auth_cache"), "{}", error);
+        }
+
+        let error = GatewayConfig::resolve(Variables {
+            auth_cache_ttl_secs: some("30s"),
+            ..Variables::default()
+        })
+        .unwrap_err();
+        assert!(
+            error.starts_with("Invalid auth_cache_ttl_secs"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_auth_cookie_name() {
+        for value in ["access token", "token=1", "a;b", "\"token\""] {
+            let error = GatewayConfig::resolve(Variables {
+                auth_cookie_name: some(value),
+                ..Variables::default()
+            })
+            .unwrap_err();
+            assert!(error.starts_with("Invalid auth_cookie_name"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_forwarded_headers() {
+        for value in ["Content Type", "Accept,X-Trace:1", "If-None-Match;"] {
+            let error = GatewayConfig::resolve(Variables {
+                forwarded_headers: some(value),
+                ..Variables::default()
+            })
+            .unwrap_err();
+            assert!(error.starts_with("Invalid forwarded_headers"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_internal_response_headers() {
+        for value in ["X Debug", "*", "X-*-Debug", "X-Debug:1"] {
+            let error = GatewayConfig::resolve(Variables {
+                internal_response_headers: some(value),
+                ..Variables::default()
+            })
+            .unwrap_err();
+            assert!(
+                error.starts_with("Invalid internal_response_headers"),
+                "{}",
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_allowed_upstream_hosts() {
+        for value in ["core", "http://core:3001", "core:port"] {
+            let error = GatewayConfig::resolve(Variables {
+                allowed_upstream_hosts: some(value),
+                ..Variables::default()
+            })
+            .unwrap_err();
+            assert!(
+                error.starts_with("Invalid allowed_upstream_hosts"),
+                "{}",
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_falls_back_for_invalid_public_paths() {
+        for value in [
+            "api/auth/login",
+            "/api/public*",
+            "/api/*/todos",
+            "/api/*/*",
+            "/api/auth/login?x=1",
+            // 一部の要素だけが不正な場合も、変数全体を既定値に戻す
+            "/*, /api/*/todos",
+        ] {
+            let config = GatewayConfig::resolve(Variables {
+                public_paths: some(value),
+                ..Variables::default()
+            })
+            .unwrap();
+            assert_eq!(config.public_paths, default_public_paths(), "{}", value);
+            assert!(!config.is_public_path("/api/todos"));
+            assert_eq!(config.auth_rule_errors.len(), 1);
+            assert!(
+                config.auth_rule_errors[0].starts_with("Invalid public_paths"),
+                "{}",
+                config.auth_rule_errors[0]
+            );
+        }
+    }
+
+    #[test]
+    fn test_auth_policy_default() {
+        let config = GatewayConfig::resolve(Variables::default()).unwrap();
+
+        // 既定では public_paths 以外の /api 以下の全てに認証が必要
+        assert_eq!(
+            config.auth_policy("POST", "/api/auth/login"),
+            AuthPolicy::Public
+        );
+        for method in ["GET", "HEAD", "POST", "PATCH", "DELETE"] {
+            assert_eq!(
+                config.auth_policy(method, "/api/todos"),
+                AuthPolicy::Authenticated,
+                "{}",
+                method
+            );
+        }
+    }
+
+    #[test]
+    fn test_auth_policy_per_method() {
+        let config = GatewayConfig::resolve(Variables {
+            auth_policies: some(
+                "GET /api/todos=public, HEAD /api/todos=public, \
+                 GET /api/todos/drafts=authenticated, * /api/auth/login=authenticated, \
+                 * /api/demo=public, DELETE /api/demo=authenticated",
+            ),
+            ..Variables::default()
+        })
+        .unwrap();
+
+        // 読み取りのみ公開（書き込みは認証が必要）
+        assert_eq!(config.auth_policy("GET", "/api/todos"), AuthPolicy::Public);
+        assert_eq!(
+            config.auth_policy("get", "/api/todos/1"),
+            AuthPolicy::Public
+        );
+        assert_eq!(config.auth_policy("HEAD", "/api/todos"), AuthPolicy::Public);
+        for method in ["POST", "PATCH", "DELETE"] {
+            assert_eq!(
+                config.auth_policy(method, "/api/todos/1"),
+                AuthPolicy::Authenticated,
+                "{}",
+                method
+            );
+        }
+        // セグメント単位で一致する
+        assert_eq!(
+            config.auth_policy("GET", "/api/todosx"),
+            AuthPolicy::Authenticated
+        );
+        // 長いプレフィックスが優先
+        assert_eq!(
+            config.auth_policy("GET", "/api/todos/drafts/1"),
+            AuthPolicy::Authenticated
+        );
+        // 同じプレフィックスではメソッドを指定したものが優先
+        assert_eq!(config.auth_policy("GET", "/api/demo"), AuthPolicy::Public);
+        assert_eq!(
+            config.auth_policy("DELETE", "/api/demo/1"),
+            AuthPolicy::Authenticated
+        );
+        // auth_policies は public_paths より優先
+        assert_eq!(
+            config.auth_policy("POST", "/api/auth/login"),
+            AuthPolicy::Authenticated
+        );
+        assert_eq!(
+            config.auth_policy("POST", "/api/auth/register"),
+            AuthPolicy::Public
+        );
+    }
+
+    #[test]
+    fn test_resolve_falls_back_for_invalid_auth_policies() {
+        for value in [
+            "/api/todos=public",
+            "GET /api/todos",
+            "GET api/todos=public",
+            "GET /api/todos/*=public",
+            "GET /api/todos=open",
+            "G3T /api/todos=public",
+        ] {
+            let config = GatewayConfig::resolve(Variables {
+                auth_policies: some(value),
+                ..Variables::default()
+            })
+            .unwrap();
+            assert_eq!(config.auth_policies, Vec::new(), "{}", value);
+            assert_eq!(
+                config.auth_policy("GET", "/api/todos"),
+                AuthPolicy::Authenticated
+            );
+            assert!(
+                config.auth_rule_errors[0].starts_with("Invalid auth_policies"),
+                "{}",
+                config.auth_rule_errors[0]
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_public_path_default() {
+        let config = GatewayConfig::resolve(Variables::default()).unwrap();
+
+        assert!(config.is_public_path("/api/auth/register"));
+        assert!(config.is_public_path("/api/auth/login"));
+
+        // 完全一致のみ（前方一致・末尾の / で認証を迂回できない）
+        for path in [
+            "/api/auth/login-history",
+            "/api/auth/login/",
+            "/api/auth/login/admin",
+            "/api/auth/registered",
+            "/api/auth",
+            "/api/todos",
+        ] {
+            assert!(!config.is_public_path(path), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_is_public_path_wildcard() {
+        let config = GatewayConfig::resolve(Variables {
+            public_paths: some("/api/auth/login,/api/public/*"),
+            ..Variables::default()
+        })
+        .unwrap();
+
+        for path in [
+            "/api/auth/login",
+            "/api/public",
+            "/api/public/",
+            "/api/public/docs",
+            "/api/public/docs/openapi.json",
+        ] {
+            assert!(config.is_public_path(path), "{}", path);
+        }
+        // セグメント単位で一致する（似た名前のパスは公開しない）
+        for path in [
+            "/api/publicity",
+            "/api/public-admin/users",
+            "/api/auth/login-history",
+            "/api/auth/register",
+            "/api",
+        ] {
+            assert!(!config.is_public_path(path), "{}", path);
+        }
+
+        // "/*" は全てのパス
+        let config = GatewayConfig::resolve(Variables {
+            public_paths: some("/*"),
+            ..Variables::default()
+        })
+        .unwrap();
+        assert!(config.is_public_path("/api/todos/1"));
+    }
+
+    #[test]
+    fn test_is_under_maintenance() {
+        let config = GatewayConfig::resolve(Variables {
+            maintenance_allow_paths: some("/api/todos,/api/files/*"),
+            ..Variables::default()
+        })
+        .unwrap();
+        // maintenance_mode=off（既定）の場合は対象外
+        assert!(!config.is_under_maintenance("/api/users", true));
+
+        let config = GatewayConfig {
+            maintenance_mode: true,
+            ..config
+        };
+        for path in ["/api", "/api/users", "/api/auth/login", "/api/todos/1"] {
+            assert!(config.is_under_maintenance(path, true), "{}", path);
+        }
+        // 許可したパスは読み取り専用のリクエストのみ転送する
+        for path in ["/api/todos", "/api/files/1/download"] {
+            assert!(!config.is_under_maintenance(path, true), "{}", path);
+            assert!(config.is_under_maintenance(path, false), "{}", path);
+        }
+        // /health など API 以外のパスは対象外
+        for path in ["/health", "/gateway/metrics", "/apis"] {
+            assert!(!config.is_under_maintenance(path, false), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_maintenance_settings() {
+        let error = GatewayConfig::resolve(Variables {
+            maintenance_mode: some("true"),
+            ..Variables::default()
+        })
+        .unwrap_err();
+        assert!(error.starts_with("Invalid maintenance_mode"), "{}", error);
+
+        for value in ["api/todos", "/api/todos*"] {
+            let error = GatewayConfig::resolve(Variables {
+                maintenance_allow_paths: some(value),
+                ..Variables::default()
+            })
+            .unwrap_err();
+            assert!(
+                error.starts_with("Invalid maintenance_allow_paths"),
+                "{}",
+                error
+            );
+        }
+
+        let error = GatewayConfig::resolve(Variables {
+            maintenance_retry_after_secs: some("5m"),
+            ..Variables::default()
+        })
+        .unwrap_err();
+        assert!(
+            error.starts_with("Invalid maintenance_retry_after_secs"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_log_format() {
+        for value in ["logfmt", "plain", "1"] {
+            let error = GatewayConfig::resolve(Variables {
+                log_format: some(value),
+                ..Variables::default()
+            })
+            .unwrap_err();
+            assert!(error.starts_with("Invalid log_format"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_is_ip_allowed() {
+        let ip = |value: &str| Some(value.parse::<IpAddr>().unwrap());
+
+        // 既定では全て許可する
+        let config = GatewayConfig::resolve(Variables::default()).unwrap();
+        assert!(config.is_ip_allowed("/api/todos", ip("203.0.113.9")));
+        assert!(config.is_ip_allowed("/api/todos", None));
+
+        // 拒否リストは全てのパスに適用する（不正な CIDR は読み飛ばす）
+        let config = GatewayConfig::resolve(Variables {
+            ip_denylist: some("203.0.113.0/24, 10.0.0.0/99, 2001:db8:bad::/48"),
+            ..Variables::default()
+        })
+        .unwrap();
+        assert!(!config.is_ip_allowed("/api/todos", ip("203.0.113.9")));
+        assert!(!config.is_ip_allowed("/health", ip("2001:db8:bad::1")));
+        assert!(config.is_ip_allowed("/api/todos", ip("10.0.0.1")));
+        assert!(config.is_ip_allowed("/api/todos", None));
+
+        // 許可リストは ip_allowlist_paths のパスのみ（拒否リストが優先）
+        let config = GatewayConfig::resolve(Variables {
+            ip_denylist: some("10.9.0.0/16"),
+            ip_allowlist: some("10.0.0.0/8"),
+            ip_allowlist_paths: some("/api/admin/*"),
+            ..Variables::default()
+        })
+        .unwrap();
+        assert!(config.is_ip_allowed("/api/admin/users", ip("10.1.2.3")));
+        assert!(!config.is_ip_allowed("/api/admin/users", ip("192.0.2.1")));
+        assert!(!config.is_ip_allowed("/api/admin", None));
+        assert!(!config.is_ip_allowed("/api/admin/users", ip("10.9.0.1")));
+        assert!(config.is_ip_allowed("/api/todos", ip("192.0.2.1")));
+        assert!(config.is_ip_allowed("/api/administrators", ip("192.0.2.1")));
+
+        // ip_allowlist_paths が空の場合は全てのパス
+        let config = GatewayConfig::resolve(Variables {
+            ip_allowlist: some("192.0.2.0/24"),
+            ..Variables::default()
+        })
+        .unwrap();
+        assert!(config.is_ip_allowed("/api/todos", ip("192.0.2.1")));
+        assert!(!config.is_ip_allowed("/health", ip("198.51.100.1")));
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_ip_allowlist_paths() {
+        let error = GatewayConfig::resolve(Variables {
+            ip_allowlist_paths: some("api/admin"),
+            ..Variables::default()
+        })
+        .unwrap_err();
+        assert!(error.starts_with("Invalid ip_allowlist_paths"), "{}", error);
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_head_fallback_to_get() {
+        for value in ["yes", "get", "0"] {
+            let error = GatewayConfig::resolve(Variables {
+                head_fallback_to_get: some(value),
+                ..Variables::default()
+            })
+            .unwrap_err();
+            assert!(
+                error.starts_with("Invalid head_fallback_to_get"),
+                "{}",
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_trust_proxy_headers() {
+        for value in ["yes", "true", "1"] {
+            let error = GatewayConfig::resolve(Variables {
+                trust_proxy_headers: some(value),
+                ..Variables::default()
+            })
+            .unwrap_err();
+            assert!(
+                error.starts_with("Invalid trust_proxy_headers"),
+                "{}",
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_metrics_allowed() {
+        // 既定では全て許可する（アドレスが取得できない場合も）
+        let config = GatewayConfig::resolve(Variables::default()).unwrap();
+        assert!(config.is_metrics_allowed(Some("203.0.113.5")));
+        assert!(config.is_metrics_allowed(None));
+
+        let config = GatewayConfig::resolve(Variables {
+            metrics_allowed_ips: some("10.0.0.7,::1"),
+            ..Variables::default()
+        })
+        .unwrap();
+        assert!(config.is_metrics_allowed(Some("10.0.0.7")));
+        assert!(config.is_metrics_allowed(Some("0:0::1")));
+        for ip in [Some("10.0.0.8"), Some("10.0.0.70"), Some("unknown"), None] {
+            assert!(!config.is_metrics_allowed(ip), "{:?}", ip);
+        }
+
+        for value in ["10.0.0.0/8", "localhost", "10.0.0.7:9090"] {
+            let error = GatewayConfig::resolve(Variables {
+                metrics_allowed_ips: some(value),
+                ..Variables::default()
+            })
+            .unwrap_err();
+            assert!(
+                error.starts_with("Invalid metrics_allowed_ips"),
+                "{}",
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_cors_allowed_origins() {
+        let resolve = |value: &str| {
+            GatewayConfig::resolve(Variables {
+                cors_allowed_origins: some(value),
+                ..Variables::default()
+            })
+        };
+
+        assert_eq!(
+            resolve("*").unwrap().cors_allowed_origins,
+            vec![crate::cors::ANY_ORIGIN]
+        );
+        for value in [
+            "app.example.com",
+            "https://app.example.com/path",
+            "https://",
+            "https://app.example.com, *.example.com",
+        ] {
+            let error = resolve(value).unwrap_err();
+            assert!(
+                error.starts_with("Invalid cors_allowed_origins"),
+                "{}: {}",
+                value,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_body_limit_uses_longest_matching_prefix() {
+        let config = GatewayConfig::resolve(Variables {
+            body_limits: some("/api=4096, /api/files=0, /api/files/upload=8192"),
+            ..Variables::default()
+        })
+        .unwrap();
+
+        assert_eq!(config.body_limit("/api/todos"), 4096);
+        assert_eq!(config.body_limit("/api/files/123/download"), 0);
+        assert_eq!(config.body_limit("/api/files/upload"), 8192);
+        // セグメント単位で一致するため "/api/files" の上限は適用しない
+        assert_eq!(config.body_limit("/api/filesystem"), 4096);
+        assert_eq!(config.body_limit("/health"), DEFAULT_MAX_BODY_BYTES);
+
+        // 既定ではアップロードのみ大きな上限を許可する
+        let config = GatewayConfig::resolve(Variables::default()).unwrap();
+        assert_eq!(config.body_limit("/api/todos"), DEFAULT_MAX_BODY_BYTES);
+        assert_eq!(
+            config.body_limit("/api/files/upload"),
+            DEFAULT_UPLOAD_MAX_BODY_BYTES
+        );
+        assert_eq!(
+            config.body_limit("/api/todos/with-files"),
+            DEFAULT_UPLOAD_MAX_BODY_BYTES
+        );
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_body_limits() {
+        let error = GatewayConfig::resolve(Variables {
+            max_body_bytes: some("1MiB"),
+            ..Variables::default()
+        })
+        .unwrap_err();
+        assert!(error.starts_with("Invalid max_body_bytes"), "{}", error);
+
+        let error = GatewayConfig::resolve(Variables {
+            max_token_bytes: some("8KiB"),
+            ..Variables::default()
+        })
+        .unwrap_err();
+        assert!(error.starts_with("Invalid max_token_bytes"), "{}", error);
+
+        for value in ["/api/files", "api/files=1024", "/api/files=-1", "/api=1,=2"] {
+            let error = GatewayConfig::resolve(Variables {
+                body_limits: some(value),
+                ..Variables::default()
+            })
+            .unwrap_err();
+            assert!(
+                error.starts_with("Invalid max_body_bytes_by_path"),
+                "{}: {}",
+                value,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_upstream_url_uses_longest_matching_prefix() {
+        let config = GatewayConfig::resolve(Variables {
+            core_url: some("http://core:3001"),
+            upstream_routes: some(
+                "/api/files=http://files:3002, /api/files/archive=https://archive.example.com",
+            ),
+            ..Variables::default()
+        })
+        .unwrap();
+
+        assert_eq!(config.upstream_url("/api/files"), "http://files:3002");
+        assert_eq!(
+            config.upstream_url("/api/files/123/download"),
+            "http://files:3002"
+        );
+        assert_eq!(
+            config.upstream_url("/api/files/archive/1"),
+            "https://archive.example.com"
+        );
+        // セグメント単位で一致するため、似た名前のパスと未知のプレフィックスは core_url
+        assert_eq!(config.upstream_url("/api/filesystem"), "http://core:3001");
+        assert_eq!(config.upstream_url("/api/todos"), "http://core:3001");
+        assert_eq!(config.upstream_url("/health"), "http://core:3001");
+
+        assert_eq!(
+            config.url("/api/files/1", "inline=true"),
+            "http://files:3002/api/files/1?inline=true"
+        );
+        assert_eq!(config.url("/api/todos", ""), "http://core:3001/api/todos");
+    }
+
+    #[test]
+    fn test_core_backend() {
+        // canary_percent=0（既定）では振り分けない
+        let config = GatewayConfig::resolve(Variables {
+            core_canary_url: some("http://core-canary:3001"),
+            ..Variables::default()
+        })
+        .unwrap();
+        assert_eq!(config.core_canary_url, None);
+        assert_eq!(config.core_backend("/api/todos", "user-1"), None);
+        assert_eq!(
+            config.backend_url("/api/todos", "", None),
+            "http://localhost:3001/api/todos"
+        );
+
+        let config = GatewayConfig::resolve(Variables {
+            core_url: some("http://core:3001"),
+            upstream_routes: some("/api/files=http://files:3002"),
+            core_canary_url: some("http://core-canary:3001/"),
+            canary_percent: some("100"),
+            ..Variables::default()
+        })
+        .unwrap();
+        assert_eq!(
+            config.core_backend("/api/todos", "user-1"),
+            Some(Backend::Canary)
+        );
+        assert_eq!(
+            config.backend_url("/api/todos", "completed=true", Some(Backend::Canary)),
+            "http://core-canary:3001/api/todos?completed=true"
+        );
+        assert_eq!(
+            config.backend_url("/api/todos", "", Some(Backend::Primary)),
+            "http://core:3001/api/todos"
+        );
+        // core_url 以外に転送するパスは振り分けない
+        assert_eq!(config.core_backend("/api/files/1", "user-1"), None);
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_canary_settings() {
+        for (url, percent) in [
+            (None, "5"),
+            (Some("http://core-canary:3001"), "101"),
+            (Some("http://core-canary:3001"), "-1"),
+            (Some("http://core-canary:3001"), "5%"),
+            (Some("core-canary:3001"), "5"),
+            (Some("http://core-canary:3001/v2"), "5"),
+        ] {
+            let error = GatewayConfig::resolve(Variables {
+                core_canary_url: url.map(str::to_string),
+                canary_percent: some(percent),
+                ..Variables::default()
+            })
+            .unwrap_err();
+            assert!(
+                error.starts_with("Invalid canary_percent")
+                    || error.starts_with("Invalid core_canary_url"),
+                "{:?} {}: {}",
+                url,
+                percent,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_upstream_routes() {
+        for value in [
+            "/api/files",
+            "api/files=http://files:3002",
+            "/api/files=files:3002",
+            "/api/files=http://files:3002/v1",
+            "/api/files=",
+        ] {
+            let error = GatewayConfig::resolve(Variables {
+                upstream_routes: some(value),
+                ..Variables::default()
+            })
+            .unwrap_err();
+            assert!(
+                error.starts_with("Invalid upstream_routes"),
+                "{}: {}",
+                value,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_route_rules() {
+        for value in [
+            "/api=public",
+            r#"[{"prefix": "api"}]"#,
+            r#"[{"prefix": "/api", "upstream": "http://files:3002/v1"}]"#,
+            r#"[{"prefix": "/api", "auth": "none"}]"#,
+        ] {
+            let error = GatewayConfig::resolve(Variables {
+                route_rules: some(value),
+                ..Variables::default()
+            })
+            .unwrap_err();
+            assert!(
+                error.starts_with("Invalid route_rules"),
+                "{}: {}",
+                value,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_route_falls_back_to_settings() {
+        let config = GatewayConfig::resolve(Variables {
+            core_url: some("http://core:3001"),
+            upstream_routes: some("/api/files=http://files:3002"),
+            core_canary_url: some("http://core-canary:3001"),
+            canary_percent: some("100"),
+            max_body_bytes: some("4096"),
+            proxy_timeout_ms: some("5000"),
+            auth_policies: some("GET /api/todos=public"),
+            route_rules: some(
+                r#"[{"prefix": "/api/reports", "methods": ["GET"], "auth": "public", "max_body_bytes": 0,
+                     "timeout_ms": 60000, "upstream": "http://reports:3003"},
+                    {"prefix": "/api/todos", "auth": "authenticated"}]"#,
+            ),
+            ..Variables::default()
+        })
+        .unwrap();
+
+        // ルールで指定した項目はルールの値（転送先を指定した場合はカナリアに振り分けない）
+        let route = config.route("GET", "/api/reports/1").unwrap();
+        assert_eq!(
+            route,
+            Route {
+                prefix: "/api/reports",
+                auth: AuthPolicy::Public,
+                max_body_bytes: 0,
+                timeout: Duration::from_secs(60),
+                upstream: Some("http://reports:3003"),
+            }
+        );
+        assert_eq!(
+            config.route_target(&route, "/api/reports/1", "year=2026", "user-1"),
+            (
+                None,
+                "http://reports:3003/api/reports/1?year=2026".to_string()
+            )
+        );
+        assert_eq!(config.request_body_limit("GET", "/api/reports/1"), 0);
+
+        // ルールの auth は auth_policies より優先する
+        assert_eq!(
+            config.route("GET", "/api/todos").unwrap().auth,
+            AuthPolicy::Authenticated
+        );
+
+        // 省略した項目は既存の設定（既定のルール /api）
+        let route = config.route("POST", "/api/files").unwrap();
+        assert_eq!(
+            route,
+            Route {
+                prefix: "/api",
+                auth: AuthPolicy::Authenticated,
+                max_body_bytes: DEFAULT_UPLOAD_MAX_BODY_BYTES,
+                timeout: Duration::from_secs(5),
+                upstream: None,
+            }
+        );
+        assert_eq!(
+            config.route_target(&route, "/api/files", "", "user-1"),
+            (None, "http://files:3002/api/files".to_string())
+        );
+        let route = config.route("POST", "/api/auth/login").unwrap();
+        assert_eq!(route.auth, AuthPolicy::Public);
+        assert_eq!(
+            config.route_target(&route, "/api/auth/login", "", "req-1"),
+            (
+                Some(Backend::Canary),
+                "http://core-canary:3001/api/auth/login".to_string()
+            )
+        );
+
+        // 一致しない・許可していないメソッド（ボディの上限は body_limit）
+        assert_eq!(config.route("GET", "/robots.txt"), Err(RouteMiss::NotFound));
+        assert_eq!(
+            config.route("POST", "/api/reports"),
+            Err(RouteMiss::MethodNotAllowed(vec![
+                "GET".to_string(),
+                "HEAD".to_string()
+            ]))
+        );
+        assert_eq!(config.request_body_limit("POST", "/api/reports"), 4096);
+        assert_eq!(config.request_body_limit("GET", "/health"), 4096);
+    }
+
+    #[test]
+    fn test_resolve_rejects_malformed_core_url() {
+        for url in [
+            "localhost:3001",
+            "core.example.com",
+            "ftp://core.example.com",
+            "http://",
+            "https:///",
+            "http://core.example.com/api",
+            "http://core.example.com?x=1",
+            "http://core.example.com#top",
+            "http://core example.com",
+        ] {
+            let error = GatewayConfig::resolve(with_core_url(url)).unwrap_err();
+            assert!(error.starts_with("Invalid core_url"), "{}: {}", url, error);
+        }
+    }
+
+    #[test]
+    fn test_url() {
+        let config = GatewayConfig::resolve(with_core_url("http://core:3001/")).unwrap();
+
+        assert_eq!(config.url("/health", ""), "http://core:3001/health");
+        assert_eq!(
+            config.url("/api/todos", "completed=false"),
+            "http://core:3001/api/todos?completed=false"
+        );
+        // ランタイムがデコードした値はエンコードし直す
+        assert_eq!(
+            config.url("/api/todos", "q=東京 タワー"),
+            "http://core:3001/api/todos?q=%E6%9D%B1%E4%BA%AC%20%E3%82%BF%E3%83%AF%E3%83%BC"
+        );
+    }
+}
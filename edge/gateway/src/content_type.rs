@@ -0,0 +1,298 @@
+//! # 変更リクエストの Content-Type の検証
+//!
+//! POST・PUT・PATCH のボディの Content-Type を許可リストと照合し、
+//! 対応していない場合はコア層に転送せずに 415 Unsupported Media Type を返します
+//! （text/plain などを送ったクライアントがコア層の 422 で原因を追えないため）。
+//!
+//! | パスプレフィックス | 許可する Content-Type |
+//! |--------------------|------------------------|
+//! | `/api/files` | `multipart/form-data` |
+//! | `/api/todos/import-csv` | `text/csv`, `multipart/form-data` |
+//! | `/api/todos/with-files` | `application/json`, `multipart/form-data` |
+//! | それ以外 | `application/json`（`application/*+json` を含む） |
+//!
+//! charset などのパラメータ（`application/json; charset=utf-8`）は無視します。
+//! `multipart/form-data` は boundary パラメータが必要です（ない場合はコア層が解析できないため 415）。
+//! multipart の Content-Type は boundary を含めてそのままコア層に転送します（is_multipart）。
+//! Content-Type がない場合は検証しません（gateway が application/json を付与して転送する）。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+use spin_sdk::http::Method;
+
+use crate::config::path_has_prefix;
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// JSON のメディアタイプ（既定の許可リスト）
+const JSON: &str = "application/json";
+
+/// multipart のメディアタイプ（ファイルのアップロード）
+const MULTIPART: &str = "multipart/form-data";
+
+/// パスプレフィックスごとの許可リスト（最も長いプレフィックスを優先、セグメント単位で一致）
+///
+/// コア層のハンドラ（Json・Multipart・Bytes のエクストラクタ）が受け付けるものと揃える。
+const MEDIA_TYPE_RULES: &[MediaTypeRule] = &[
+    MediaTypeRule {
+        prefix: "/api/files",
+        allowed: &[MULTIPART],
+    },
+    MediaTypeRule {
+        prefix: "/api/todos/import-csv",
+        allowed: &["text/csv", MULTIPART],
+    },
+    MediaTypeRule {
+        prefix: "/api/todos/with-files",
+        allowed: &[JSON, MULTIPART],
+    },
+];
+
+/// MEDIA_TYPE_RULES に一致しないパスの許可リスト
+const DEFAULT_MEDIA_TYPES: &[&str] = &[JSON];
+
+// =============================================================================
+// 型定義
+// =============================================================================
+
+/// パスプレフィックスごとの許可する Content-Type
+struct MediaTypeRule {
+    /// パスプレフィックス（例: "/api/files"）
+    prefix: &'static str,
+
+    /// 許可するメディアタイプ（パラメータなし、小文字）
+    allowed: &'static [&'static str],
+}
+
+// =============================================================================
+// 検証
+// =============================================================================
+
+/// リクエストの Content-Type を検証する
+///
+/// # 引数
+/// * `method` - HTTP メソッド（POST・PUT・PATCH 以外は検証しない）
+/// * `path` - リクエストパス
+/// * `content_type` - Content-Type ヘッダーの値（ない場合は None、検証しない）
+///
+/// # 戻り値
+/// * `Ok(())` - 検証しない、または許可リストに含まれる場合
+/// * `Err(String)` - 415 のエラーメッセージ
+pub(crate) fn check(method: &Method, path: &str, content_type: Option<&str>) -> Result<(), String> {
+    if !matches!(method, Method::Post | Method::Put | Method::Patch) {
+        return Ok(());
+    }
+    let Some(content_type) = content_type else {
+        return Ok(());
+    };
+
+    let media_type = media_type(content_type);
+    let allowed = allowed_media_types(path);
+    if allowed.iter().any(|allowed| is_match(allowed, &media_type)) {
+        if media_type == MULTIPART && boundary(content_type).is_none() {
+            return Err(format!(
+                "Unsupported Media Type: {:?} requires a boundary parameter",
+                media_type
+            ));
+        }
+        return Ok(());
+    }
+    Err(format!(
+        "Unsupported Media Type: {:?} is not accepted (expected {})",
+        media_type,
+        allowed.join(" or ")
+    ))
+}
+
+/// Content-Type が multipart/form-data かを判定する
+///
+/// multipart のボディは boundary で区切られるため、Content-Type を書き換えずに転送する必要がある。
+///
+/// # 引数
+/// * `content_type` - Content-Type ヘッダーの値
+///
+/// # 戻り値
+/// * `true` - multipart/form-data（パラメータは問わない）
+pub(crate) fn is_multipart(content_type: &str) -> bool {
+    media_type(content_type) == MULTIPART
+}
+
+/// パスの許可リストを返す
+fn allowed_media_types(path: &str) -> &'static [&'static str] {
+    MEDIA_TYPE_RULES
+        .iter()
+        .filter(|rule| path_has_prefix(path, rule.prefix))
+        .max_by_key(|rule| rule.prefix.len())
+        .map_or(DEFAULT_MEDIA_TYPES, |rule| rule.allowed)
+}
+
+/// Content-Type からパラメータを除いたメディアタイプ（小文字）を取り出す
+///
+/// 例: "Application/JSON; charset=utf-8" → "application/json"
+fn media_type(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Content-Type の boundary パラメータ（空でない値、引用符を除く）を取り出す
+///
+/// 例: "multipart/form-data; boundary=\"----abc\"" → "----abc"
+fn boundary(content_type: &str) -> Option<&str> {
+    content_type
+        .split(';')
+        .skip(1)
+        .filter_map(|param| param.split_once('='))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("boundary"))
+        .map(|(_, value)| value.trim().trim_matches('"'))
+        .filter(|value| !value.is_empty())
+}
+
+/// メディアタイプが許可リストの要素に一致するか
+///
+/// application/json は構造化構文の接尾辞 +json（例: application/merge-patch+json）にも一致する
+/// （コア層の Json エクストラクタと同じ判定）。
+fn is_match(allowed: &str, media_type: &str) -> bool {
+    allowed == media_type
+        || (allowed == JSON
+            && media_type
+                .strip_prefix("application/")
+                .is_some_and(|subtype| subtype.ends_with("+json")))
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_accepts_json_with_parameters() {
+        for content_type in [
+            "application/json",
+            "application/json; charset=utf-8",
+            "Application/JSON;charset=UTF-8",
+            "application/merge-patch+json",
+        ] {
+            assert_eq!(
+                check(&Method::Post, "/api/todos", Some(content_type)),
+                Ok(()),
+                "{}",
+                content_type
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_rejects_unsupported_media_types() {
+        for method in [Method::Post, Method::Put, Method::Patch] {
+            let error = check(&method, "/api/todos/1", Some("text/plain")).unwrap_err();
+            assert!(
+                error.starts_with("Unsupported Media Type: \"text/plain\""),
+                "{}",
+                error
+            );
+        }
+        assert!(check(
+            &Method::Post,
+            "/api/todos",
+            Some("multipart/form-data; boundary=x")
+        )
+        .is_err());
+        assert!(check(&Method::Post, "/api/todos", Some("application/jsonp")).is_err());
+        assert!(check(&Method::Post, "/api/todos", Some("text/x+json")).is_err());
+    }
+
+    #[test]
+    fn test_check_uses_path_rules() {
+        let multipart = Some("multipart/form-data; boundary=----abc");
+        assert_eq!(check(&Method::Post, "/api/files/upload", multipart), Ok(()));
+        assert_eq!(
+            check(&Method::Post, "/api/todos/import-csv", multipart),
+            Ok(())
+        );
+        assert_eq!(
+            check(
+                &Method::Post,
+                "/api/todos/import-csv",
+                Some("text/csv; charset=utf-8")
+            ),
+            Ok(())
+        );
+
+        let error =
+            check(&Method::Post, "/api/files/upload", Some("application/json")).unwrap_err();
+        assert!(
+            error.ends_with("(expected multipart/form-data)"),
+            "{}",
+            error
+        );
+        // セグメント単位で一致する（/api/filesystem は JSON）
+        assert_eq!(
+            check(&Method::Post, "/api/filesystem", Some("application/json")),
+            Ok(())
+        );
+        let error = check(&Method::Post, "/api/todos/import", Some("text/csv")).unwrap_err();
+        assert!(error.ends_with("(expected application/json)"), "{}", error);
+
+        // TODO + ファイルの同時作成は JSON と multipart の両方を受け付ける
+        for content_type in ["application/json", "multipart/form-data; boundary=x"] {
+            assert_eq!(
+                check(&Method::Post, "/api/todos/with-files", Some(content_type)),
+                Ok(()),
+                "{}",
+                content_type
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_requires_multipart_boundary() {
+        for content_type in [
+            "multipart/form-data",
+            "multipart/form-data; charset=utf-8",
+            "multipart/form-data; boundary=",
+            "multipart/form-data; boundary=\"\"",
+        ] {
+            let error = check(&Method::Post, "/api/files/upload", Some(content_type)).unwrap_err();
+            assert!(
+                error.ends_with("requires a boundary parameter"),
+                "{}",
+                error
+            );
+        }
+        assert_eq!(
+            check(
+                &Method::Post,
+                "/api/files/upload",
+                Some("Multipart/Form-Data; charset=utf-8; Boundary=\"----abc\"")
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_is_multipart() {
+        assert!(is_multipart("multipart/form-data; boundary=----abc"));
+        assert!(is_multipart("Multipart/Form-Data"));
+        assert!(!is_multipart("multipart/mixed; boundary=x"));
+        assert!(!is_multipart("application/json"));
+    }
+
+    #[test]
+    fn test_check_skips_reads_and_missing_content_type() {
+        for method in [Method::Get, Method::Head, Method::Delete, Method::Options] {
+            assert_eq!(check(&method, "/api/todos", Some("text/plain")), Ok(()));
+        }
+        assert_eq!(check(&Method::Post, "/api/todos", None), Ok(()));
+    }
+}
@@ -0,0 +1,278 @@
+//! # CORS（Cross-Origin Resource Sharing）
+//!
+//! ブラウザからのクロスオリジンリクエストに対応します。
+//!
+//! - プリフライト（OPTIONS）: 認証せずに 204 No Content を返す
+//!   （プリフライトには Authorization ヘッダーが付かないため、認証に回すと 401 になる）
+//! - それ以外: Origin が許可リストに含まれる場合、レスポンスに
+//!   `Access-Control-Allow-Origin` を付与する（401・429 などのエラーを含む）
+//!
+//! 許可リストは Spin 変数 `cors_allowed_origins`（カンマ区切り）で設定します（config モジュール）。
+//! `*` を含む場合は全てのオリジンを許可し、`Access-Control-Allow-Origin: *` を返します。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+use spin_sdk::http::Response;
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// 全てのオリジンを許可する許可リストの値
+pub(crate) const ANY_ORIGIN: &str = "*";
+
+/// プリフライトで許可するメソッド
+const ALLOW_METHODS: &str = "GET, POST, PUT, PATCH, DELETE, OPTIONS";
+
+/// プリフライトで許可するリクエストヘッダー
+///
+/// `Access-Control-Allow-Headers: *` は Authorization を含まない（CORS 仕様）ため、
+/// Bearer トークンを送れるよう Authorization を明示的に列挙する。
+const ALLOW_HEADERS: &str = "Authorization, Content-Type, Idempotency-Key, X-Request-Id";
+
+/// ブラウザの JavaScript から読み取れるようにするレスポンスヘッダー
+const EXPOSE_HEADERS: &str =
+    "X-Request-Id, Retry-After, X-RateLimit-Limit, X-RateLimit-Remaining, X-Served-By";
+
+/// プリフライトの結果をブラウザがキャッシュしてよい期間（秒）
+const MAX_AGE_SECS: &str = "600";
+
+// =============================================================================
+// CORS の判定
+// =============================================================================
+
+/// Access-Control-Allow-Origin の値を決める
+///
+/// # 引数
+/// * `origin` - リクエストの Origin ヘッダー（ない場合は None）
+/// * `allowed` - 許可リスト（config の cors_allowed_origins）
+///
+/// # 戻り値
+/// * `Some("*")` - 許可リストに `*` が含まれる場合（Origin の有無によらない）
+/// * `Some(origin)` - Origin が許可リストに含まれる場合（大文字小文字を区別しない）
+/// * `None` - 許可しない（CORS ヘッダーを付与しない）
+fn allow_origin(origin: Option<&str>, allowed: &[String]) -> Option<String> {
+    if allowed.iter().any(|allowed| allowed == ANY_ORIGIN) {
+        return Some(ANY_ORIGIN.to_string());
+    }
+
+    let origin = origin?;
+    allowed
+        .iter()
+        .any(|allowed| allowed.eq_ignore_ascii_case(origin))
+        .then(|| origin.to_string())
+}
+
+/// プリフライト（OPTIONS）に 204 No Content を返す
+///
+/// Origin が許可されない場合は CORS ヘッダーを付与しない（ブラウザが本リクエストを送らない）。
+///
+/// # 引数
+/// * `origin` - リクエストの Origin ヘッダー
+/// * `allowed` - 許可リスト
+pub(crate) fn preflight_response(origin: Option<&str>, allowed: &[String]) -> Response {
+    let mut builder = Response::builder();
+    builder.status(204);
+
+    if let Some(allow_origin) = allow_origin(origin, allowed) {
+        let vary = allow_origin != ANY_ORIGIN;
+        builder
+            .header("Access-Control-Allow-Origin", allow_origin)
+            .header("Access-Control-Allow-Methods", ALLOW_METHODS)
+            .header("Access-Control-Allow-Headers", ALLOW_HEADERS)
+            .header("Access-Control-Max-Age", MAX_AGE_SECS);
+        if vary {
+            // オリジンごとに異なるレスポンスをキャッシュが混同しないようにする
+            builder.header("Vary", "Origin");
+        }
+    }
+
+    builder.build()
+}
+
+/// レスポンスに CORS ヘッダーを付与する
+///
+/// # 引数
+/// * `response` - コア層からのレスポンス、またはゲートウェイのエラーレスポンス
+/// * `origin` - リクエストの Origin ヘッダー
+/// * `allowed` - 許可リスト
+pub(crate) fn apply_cors(response: &mut Response, origin: Option<&str>, allowed: &[String]) {
+    let Some(allow_origin) = allow_origin(origin, allowed) else {
+        return;
+    };
+
+    // set_header はヘッダー名を小文字に変換しないため、header() で参照できるよう小文字で設定する
+    if allow_origin != ANY_ORIGIN {
+        let vary = vary_with_origin(response.header("vary").and_then(|value| value.as_str()));
+        response.set_header("vary", vary);
+    }
+    response.set_header("access-control-allow-origin", allow_origin);
+    response.set_header("access-control-expose-headers", EXPOSE_HEADERS);
+}
+
+/// 既存の Vary ヘッダー（コア層の `Accept-Encoding` など）に Origin を追加する
+fn vary_with_origin(existing: Option<&str>) -> String {
+    match existing.map(str::trim).filter(|value| !value.is_empty()) {
+        None => "Origin".to_string(),
+        Some(value)
+            if value
+                .split(',')
+                .any(|name| name.trim() == "*" || name.trim().eq_ignore_ascii_case("origin")) =>
+        {
+            value.to_string()
+        }
+        Some(value) => format!("{}, Origin", value),
+    }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn origins(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn header(response: &Response, name: &str) -> Option<String> {
+        response
+            .header(name)
+            .and_then(|value| value.as_str())
+            .map(str::to_string)
+    }
+
+    #[test]
+    fn test_allow_origin() {
+        let allowed = origins(&["https://app.example.com", "http://localhost:5173"]);
+
+        assert_eq!(
+            allow_origin(Some("https://app.example.com"), &allowed).as_deref(),
+            Some("https://app.example.com")
+        );
+        assert_eq!(
+            allow_origin(Some("HTTP://LOCALHOST:5173"), &allowed).as_deref(),
+            Some("HTTP://LOCALHOST:5173")
+        );
+        // 前方一致・サブドメイン・ポート違いは許可しない
+        for origin in [
+            "https://app.example.com.evil.test",
+            "https://evil.app.example.com",
+            "http://localhost:5174",
+            "null",
+        ] {
+            assert_eq!(allow_origin(Some(origin), &allowed), None, "{}", origin);
+        }
+        assert_eq!(allow_origin(None, &allowed), None);
+
+        // 許可リストが空の場合は CORS を無効にする
+        assert_eq!(allow_origin(Some("https://app.example.com"), &[]), None);
+    }
+
+    #[test]
+    fn test_wildcard_allows_any_origin() {
+        let allowed = origins(&["https://app.example.com", "*"]);
+
+        assert_eq!(
+            allow_origin(Some("https://other.example.com"), &allowed).as_deref(),
+            Some("*")
+        );
+        assert_eq!(allow_origin(None, &allowed).as_deref(), Some("*"));
+    }
+
+    #[test]
+    fn test_preflight_response_for_explicit_origin() {
+        let response = preflight_response(
+            Some("https://app.example.com"),
+            &origins(&["https://app.example.com"]),
+        );
+
+        assert_eq!(*response.status(), 204);
+        assert!(response.body().is_empty());
+        assert_eq!(
+            header(&response, "access-control-allow-origin").as_deref(),
+            Some("https://app.example.com")
+        );
+        assert_eq!(header(&response, "vary").as_deref(), Some("Origin"));
+        assert_eq!(
+            header(&response, "access-control-allow-methods").as_deref(),
+            Some(ALLOW_METHODS)
+        );
+        // ワイルドカードでは Authorization を許可できないため明示する
+        let allow_headers = header(&response, "access-control-allow-headers").unwrap();
+        assert!(allow_headers
+            .split(", ")
+            .any(|name| name == "Authorization"));
+        assert!(!allow_headers.contains('*'));
+    }
+
+    #[test]
+    fn test_preflight_response_for_wildcard_and_disallowed_origin() {
+        let response = preflight_response(Some("https://any.example.com"), &origins(&["*"]));
+        assert_eq!(*response.status(), 204);
+        assert_eq!(
+            header(&response, "access-control-allow-origin").as_deref(),
+            Some("*")
+        );
+        assert_eq!(header(&response, "vary"), None);
+
+        // 許可されないオリジンにも 204 を返すが、CORS ヘッダーは付与しない
+        let response = preflight_response(
+            Some("https://evil.example.com"),
+            &origins(&["https://app.example.com"]),
+        );
+        assert_eq!(*response.status(), 204);
+        assert_eq!(header(&response, "access-control-allow-origin"), None);
+        assert_eq!(header(&response, "access-control-allow-headers"), None);
+    }
+
+    #[test]
+    fn test_apply_cors() {
+        let allowed = origins(&["https://app.example.com"]);
+
+        let mut response = Response::new(401, "{}");
+        apply_cors(&mut response, Some("https://app.example.com"), &allowed);
+        assert_eq!(
+            header(&response, "access-control-allow-origin").as_deref(),
+            Some("https://app.example.com")
+        );
+        assert_eq!(header(&response, "vary").as_deref(), Some("Origin"));
+        assert_eq!(
+            header(&response, "access-control-expose-headers").as_deref(),
+            Some(EXPOSE_HEADERS)
+        );
+
+        let mut response = Response::new(200, "[]");
+        apply_cors(&mut response, Some("https://evil.example.com"), &allowed);
+        assert_eq!(header(&response, "access-control-allow-origin"), None);
+
+        let mut response = Response::new(200, "[]");
+        apply_cors(&mut response, None, &origins(&["*"]));
+        assert_eq!(
+            header(&response, "access-control-allow-origin").as_deref(),
+            Some("*")
+        );
+        assert_eq!(header(&response, "vary"), None);
+    }
+
+    #[test]
+    fn test_apply_cors_keeps_existing_vary() {
+        let allowed = origins(&["https://app.example.com"]);
+
+        let mut response = Response::new(200, "[]");
+        response.set_header("vary", "Accept-Encoding");
+        apply_cors(&mut response, Some("https://app.example.com"), &allowed);
+        assert_eq!(
+            header(&response, "vary").as_deref(),
+            Some("Accept-Encoding, Origin")
+        );
+
+        assert_eq!(vary_with_origin(Some("origin")), "origin");
+        assert_eq!(vary_with_origin(Some("*")), "*");
+        assert_eq!(vary_with_origin(Some(" ")), "Origin");
+    }
+}
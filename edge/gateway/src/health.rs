@@ -0,0 +1,291 @@
+//! # ヘルスチェック（/health）
+//!
+//! コア層の /health への転送の結果とレイテンシから、gateway とコア層の状態をまとめた JSON を返します。
+//! コア層が遅いが応答している場合（degraded）をロードバランサー・監視が区別できるようにします。
+//!
+//! ```json
+//! {"gateway": "ok", "core": {"status": "ok", "latency_ms": 12, "details": {"status": "ok"}}}
+//! ```
+//!
+//! | コア層の状態 | 条件 | ステータス |
+//! |--------------|------|------------|
+//! | `ok` | 2xx を health_degraded_ms 以内に返した | 200 |
+//! | `degraded` | 2xx を返したが health_degraded_ms を超えた | 200 |
+//! | `down` | 送信の失敗・タイムアウト（health_timeout_ms）・2xx 以外 | 503 |
+//!
+//! `details` はコア層のレスポンスのボディ（JSON の場合のみ）です。
+//! `/health?deep=true` の場合のみ `deep=true` をコア層に転送し、DB・Redis の疎通確認の結果を含めます
+//! （既定はコア層の依存サービスを確認しない軽いチェック）。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+use std::time::Duration;
+
+use serde::Serialize;
+use spin_sdk::http::Response;
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// コア層に依存サービスの疎通確認を要求するクエリ文字列
+pub(crate) const DEEP_QUERY: &str = "deep=true";
+
+// =============================================================================
+// 型定義
+// =============================================================================
+
+/// コア層の /health への転送の結果
+#[derive(Debug)]
+pub(crate) enum Probe {
+    /// コア層が応答した（ステータスによらない）
+    Responded {
+        /// HTTP ステータスコード
+        status: u16,
+
+        /// レスポンスのボディ
+        body: Vec<u8>,
+    },
+
+    /// 送信に失敗した・タイムアウトした（理由）
+    Failed(String),
+}
+
+/// コア層の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum CoreStatus {
+    /// 正常
+    Ok,
+
+    /// 応答しているが遅い
+    Degraded,
+
+    /// 応答しない・異常を返した
+    Down,
+}
+
+/// /health のレスポンスのボディ
+#[derive(Serialize)]
+struct HealthResponse {
+    /// gateway 自身の状態（応答している時点で常に "ok"）
+    gateway: &'static str,
+
+    /// コア層の状態
+    core: CoreHealth,
+}
+
+/// コア層の状態とレイテンシ
+#[derive(Serialize)]
+struct CoreHealth {
+    /// ok / degraded / down
+    status: CoreStatus,
+
+    /// コア層のレイテンシ（リトライを含む、ミリ秒）
+    latency_ms: u64,
+
+    /// コア層のレスポンスのボディ（JSON として解析できる場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<serde_json::Value>,
+
+    /// down の理由（送信の失敗・タイムアウト・コア層のステータス）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+// =============================================================================
+// 判定
+// =============================================================================
+
+/// クエリ文字列が deep（依存サービスの疎通確認）を要求しているかを判定する
+///
+/// # 引数
+/// * `query` - /health のクエリ文字列（`?` を含まない）
+///
+/// # 戻り値
+/// * `true` - `deep=true` を含む（コア層に DEEP_QUERY を転送する）
+pub(crate) fn is_deep(query: &str) -> bool {
+    query.split('&').any(|pair| pair == DEEP_QUERY)
+}
+
+/// 転送の結果とレイテンシからコア層の状態を判定する
+///
+/// # 引数
+/// * `probe` - コア層の /health への転送の結果
+/// * `latency` - コア層のレイテンシ
+/// * `degraded_after` - degraded とするレイテンシ（Spin 変数 health_degraded_ms、0 の場合は判定しない）
+pub(crate) fn core_status(
+    probe: &Probe,
+    latency: Duration,
+    degraded_after: Duration,
+) -> CoreStatus {
+    match probe {
+        Probe::Responded { status, .. } if (200..300).contains(status) => {
+            if !degraded_after.is_zero() && latency > degraded_after {
+                CoreStatus::Degraded
+            } else {
+                CoreStatus::Ok
+            }
+        }
+        _ => CoreStatus::Down,
+    }
+}
+
+// =============================================================================
+// レスポンス
+// =============================================================================
+
+/// /health のレスポンスを組み立てる
+///
+/// # 引数
+/// * `probe` - コア層の /health への転送の結果
+/// * `latency` - コア層のレイテンシ
+/// * `degraded_after` - degraded とするレイテンシ（0 の場合は判定しない）
+///
+/// # 戻り値
+/// * `Response` - 200（ok / degraded）または 503（down）
+pub(crate) fn response(probe: Probe, latency: Duration, degraded_after: Duration) -> Response {
+    let status = core_status(&probe, latency, degraded_after);
+    let (details, error) = match probe {
+        Probe::Responded { status: code, body } => (
+            serde_json::from_slice(&body).ok(),
+            (status == CoreStatus::Down).then(|| format!("Core returned {}", code)),
+        ),
+        Probe::Failed(error) => (None, Some(error)),
+    };
+
+    let body = serde_json::to_string(&HealthResponse {
+        gateway: "ok",
+        core: CoreHealth {
+            status,
+            latency_ms: u64::try_from(latency.as_millis()).unwrap_or(u64::MAX),
+            details,
+            error,
+        },
+    })
+    .unwrap();
+
+    Response::builder()
+        .status(if status == CoreStatus::Down { 503 } else { 200 })
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-store")
+        .body(body)
+        .build()
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEGRADED_AFTER: Duration = Duration::from_millis(1000);
+
+    fn responded(status: u16, body: &str) -> Probe {
+        Probe::Responded {
+            status,
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_is_deep() {
+        assert!(is_deep("deep=true"));
+        assert!(is_deep("verbose=1&deep=true"));
+        assert!(!is_deep(""));
+        assert!(!is_deep("deep=false"));
+        assert!(!is_deep("deep=1"));
+        assert!(!is_deep("notdeep=true"));
+    }
+
+    #[test]
+    fn test_core_status() {
+        let fast = Duration::from_millis(12);
+        let slow = Duration::from_millis(1500);
+
+        assert_eq!(
+            core_status(&responded(200, ""), fast, DEGRADED_AFTER),
+            CoreStatus::Ok
+        );
+        assert_eq!(
+            core_status(&responded(200, ""), slow, DEGRADED_AFTER),
+            CoreStatus::Degraded
+        );
+        // 0 の場合はレイテンシで判定しない
+        assert_eq!(
+            core_status(&responded(200, ""), slow, Duration::ZERO),
+            CoreStatus::Ok
+        );
+        assert_eq!(
+            core_status(&responded(503, ""), fast, DEGRADED_AFTER),
+            CoreStatus::Down
+        );
+        assert_eq!(
+            core_status(&Probe::Failed("refused".to_string()), fast, DEGRADED_AFTER),
+            CoreStatus::Down
+        );
+    }
+
+    #[test]
+    fn test_response_ok() {
+        let response = response(
+            responded(200, r#"{"status":"ok"}"#),
+            Duration::from_millis(12),
+            DEGRADED_AFTER,
+        );
+
+        assert_eq!(*response.status(), 200);
+        assert_eq!(
+            response.body(),
+            br#"{"gateway":"ok","core":{"status":"ok","latency_ms":12,"details":{"status":"ok"}}}"#
+        );
+    }
+
+    #[test]
+    fn test_response_degraded_is_200() {
+        let response = response(
+            responded(200, "not json"),
+            Duration::from_millis(1500),
+            DEGRADED_AFTER,
+        );
+
+        assert_eq!(*response.status(), 200);
+        assert_eq!(
+            response.body(),
+            br#"{"gateway":"ok","core":{"status":"degraded","latency_ms":1500}}"#
+        );
+    }
+
+    #[test]
+    fn test_response_down_is_503() {
+        let response = response(
+            Probe::Failed("Upstream timeout after 2000ms".to_string()),
+            Duration::from_millis(2000),
+            DEGRADED_AFTER,
+        );
+        assert_eq!(*response.status(), 503);
+        assert_eq!(
+            response.body(),
+            br#"{"gateway":"ok","core":{"status":"down","latency_ms":2000,"error":"Upstream timeout after 2000ms"}}"#
+        );
+
+        // deep の疎通確認に失敗した場合はコア層の結果を含める
+        let deep = super::response(
+            responded(
+                503,
+                r#"{"status":"unavailable","checks":{"redis":"error: refused"}}"#,
+            ),
+            Duration::from_millis(30),
+            DEGRADED_AFTER,
+        );
+        assert_eq!(*deep.status(), 503);
+        assert_eq!(
+            deep.body(),
+            br#"{"gateway":"ok","core":{"status":"down","latency_ms":30,"details":{"checks":{"redis":"error: refused"},"status":"unavailable"},"error":"Core returned 503"}}"#
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! # IP アドレスの許可・拒否リスト
+//!
+//! クライアントの IP を CIDR（例: `10.0.0.0/8`, `2001:db8::/32`）の一覧と照合します。
+//! 一覧は config モジュール（Spin 変数 ip_denylist / ip_allowlist）で読み込み、
+//! gateway は認証・リクエストボディの読み込みより前に判定します（拒否時は 403）。
+//!
+//! 不正な CIDR は警告をログに出力して読み飛ばします（1 件の誤記で gateway 全体を止めないため）。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+use std::net::IpAddr;
+
+use crate::logging;
+
+// =============================================================================
+// 型定義
+// =============================================================================
+
+/// CIDR 表記のアドレス範囲
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Cidr {
+    /// ネットワークアドレス（プレフィックス長より後ろのビットは 0）
+    network: IpAddr,
+
+    /// プレフィックス長（IPv4: 0〜32、IPv6: 0〜128）
+    prefix_len: u8,
+}
+
+impl Cidr {
+    /// CIDR 表記を解析する
+    ///
+    /// プレフィックス長を省略した場合は単一のアドレス（/32・/128）として扱う。
+    /// ホスト部のビットが立っている場合（例: `10.0.0.1/8`）はネットワークアドレスに丸める。
+    ///
+    /// # 引数
+    /// * `value` - CIDR 表記（例: "192.0.2.0/24", "2001:db8::/32", "192.0.2.1"）
+    ///
+    /// # 戻り値
+    /// * `Some(Cidr)` - 解析した範囲
+    /// * `None` - アドレス・プレフィックス長が不正な場合
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        let (address, prefix_len) = match value.split_once('/') {
+            Some((address, prefix_len)) => (address, Some(prefix_len)),
+            None => (value, None),
+        };
+        let network: IpAddr = address.parse().ok()?;
+        let max_len = max_prefix_len(&network);
+        let prefix_len = match prefix_len {
+            // "+8" などを受け付けないよう、数字のみを許可する
+            Some(len) if !len.is_empty() && len.bytes().all(|b| b.is_ascii_digit()) => {
+                len.parse::<u8>().ok().filter(|len| *len <= max_len)?
+            }
+            Some(_) => return None,
+            None => max_len,
+        };
+
+        Some(Self {
+            network: mask(network, prefix_len),
+            prefix_len,
+        })
+    }
+
+    /// アドレスが範囲に含まれるか
+    ///
+    /// IPv4 の範囲は IPv4 射影 IPv6 アドレス（`::ffff:192.0.2.1`）にも一致する。
+    pub(crate) fn contains(&self, ip: &IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(*ip, IpAddr::V4),
+            IpAddr::V4(_) => *ip,
+        };
+        // IPv4 と IPv6 は一致しない（0.0.0.0/0 も IPv6 アドレスを含まない）
+        max_prefix_len(&ip) == max_prefix_len(&self.network)
+            && mask(ip, self.prefix_len) == self.network
+    }
+}
+
+// =============================================================================
+// 解析
+// =============================================================================
+
+/// CIDR の一覧（カンマ区切り）を解析する
+///
+/// 空の要素は無視し、不正な要素は警告をログに出力して読み飛ばす。
+///
+/// # 引数
+/// * `name` - Spin 変数名（警告のメッセージ用）
+/// * `value` - カンマ区切りの CIDR（例: "10.0.0.0/8, 2001:db8::/32"）
+///
+/// # 戻り値
+/// * `Vec<Cidr>` - 解析できた範囲
+pub(crate) fn parse_cidrs(name: &str, value: &str) -> Vec<Cidr> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|cidr| !cidr.is_empty())
+        .filter_map(|cidr| {
+            let parsed = Cidr::parse(cidr);
+            if parsed.is_none() {
+                logging::warn(&format!("Invalid {} entry {:?} ignored", name, cidr));
+            }
+            parsed
+        })
+        .collect()
+}
+
+/// アドレスがいずれかの範囲に含まれるか
+pub(crate) fn matches_any(cidrs: &[Cidr], ip: &IpAddr) -> bool {
+    cidrs.iter().any(|cidr| cidr.contains(ip))
+}
+
+/// アドレスの種類ごとのプレフィックス長の最大値
+fn max_prefix_len(ip: &IpAddr) -> u8 {
+    match ip {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+/// プレフィックス長より後ろのビットを 0 にする
+fn mask(ip: IpAddr, prefix_len: u8) -> IpAddr {
+    match ip {
+        IpAddr::V4(v4) => {
+            let bits = u32::from(v4);
+            let mask = u32::MAX
+                .checked_shl(32 - u32::from(prefix_len))
+                .unwrap_or(0);
+            IpAddr::V4((bits & mask).into())
+        }
+        IpAddr::V6(v6) => {
+            let bits = u128::from(v6);
+            let mask = u128::MAX
+                .checked_shl(128 - u32::from(prefix_len))
+                .unwrap_or(0);
+            IpAddr::V6((bits & mask).into())
+        }
+    }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_cidr() {
+        let cidr = Cidr::parse("10.1.2.3/8").unwrap();
+        assert_eq!(cidr.network, ip("10.0.0.0"));
+        assert_eq!(cidr.prefix_len, 8);
+
+        let cidr = Cidr::parse("2001:db8::1/32").unwrap();
+        assert_eq!(cidr.network, ip("2001:db8::"));
+        assert_eq!(cidr.prefix_len, 32);
+
+        // プレフィックス長の省略は単一のアドレス
+        assert_eq!(Cidr::parse("192.0.2.1").unwrap().prefix_len, 32);
+        assert_eq!(Cidr::parse("::1").unwrap().prefix_len, 128);
+
+        for value in [
+            "",
+            "10.0.0.0/",
+            "10.0.0.0/33",
+            "10.0.0.0/+8",
+            "10.0.0/8",
+            "2001:db8::/129",
+            "example.com/24",
+            "10.0.0.0/8/8",
+        ] {
+            assert_eq!(Cidr::parse(value), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_cidr_contains_ipv4() {
+        let cidr = Cidr::parse("192.0.2.0/24").unwrap();
+        assert!(cidr.contains(&ip("192.0.2.0")));
+        assert!(cidr.contains(&ip("192.0.2.255")));
+        assert!(!cidr.contains(&ip("192.0.3.0")));
+        // IPv4 射影 IPv6 アドレスも一致する
+        assert!(cidr.contains(&ip("::ffff:192.0.2.7")));
+        // IPv6 アドレスは一致しない
+        assert!(!cidr.contains(&ip("2001:db8::1")));
+
+        let any = Cidr::parse("0.0.0.0/0").unwrap();
+        assert!(any.contains(&ip("203.0.113.9")));
+        assert!(!any.contains(&ip("::1")));
+
+        let single = Cidr::parse("198.51.100.7").unwrap();
+        assert!(single.contains(&ip("198.51.100.7")));
+        assert!(!single.contains(&ip("198.51.100.8")));
+    }
+
+    #[test]
+    fn test_cidr_contains_ipv6() {
+        let cidr = Cidr::parse("2001:db8::/32").unwrap();
+        assert!(cidr.contains(&ip("2001:db8::1")));
+        assert!(cidr.contains(&ip("2001:db8:ffff::1")));
+        assert!(!cidr.contains(&ip("2001:db9::1")));
+        assert!(!cidr.contains(&ip("192.0.2.1")));
+
+        let any = Cidr::parse("::/0").unwrap();
+        assert!(any.contains(&ip("fe80::1")));
+        assert!(!any.contains(&ip("10.0.0.1")));
+    }
+
+    #[test]
+    fn test_parse_cidrs_skips_invalid_entries() {
+        let cidrs = parse_cidrs(
+            "ip_denylist",
+            "10.0.0.0/8, bogus,, 2001:db8::/32, 10.0.0.0/40",
+        );
+        assert_eq!(
+            cidrs,
+            vec![
+                Cidr::parse("10.0.0.0/8").unwrap(),
+                Cidr::parse("2001:db8::/32").unwrap(),
+            ]
+        );
+        assert!(matches_any(&cidrs, &ip("10.20.30.40")));
+        assert!(matches_any(&cidrs, &ip("2001:db8::5")));
+        assert!(!matches_any(&cidrs, &ip("192.0.2.1")));
+        assert!(!matches_any(&[], &ip("192.0.2.1")));
+    }
+}
@@ -0,0 +1,539 @@
+//! # gateway のログ
+//!
+//! リクエストごとに 1 行のアクセスログ（RequestLog）と、処理中の出来事のログ（info / warn）、
+//! 遅い・大きいコア層のレスポンスの警告（RequestLog::warn_upstream）を出力します。
+//! 形式は Spin 変数 `log_format` で切り替えます。
+//!
+//! | 形式 | 出力 |
+//! |------|------|
+//! | `text`（既定） | `[Gateway] GET /api/todos 200 12ms (request_id=..., user_id=..., upstream=9ms)` |
+//! | `json` | `{"timestamp":"2026-01-01T00:00:00.000Z","level":"info","method":"GET","path":"/api/todos","status":200,...}` |
+//!
+//! ログパイプラインで解析できるよう、`json` では 1 行に 1 つの JSON オブジェクトを出力します。
+//! 形式はリクエストごとに設定を読み込んだ後で `set_format` し、それまで（設定の読み込みに失敗した場合を含む）は `text` です。
+//! Spin はコンポーネントのインスタンスをリクエストごとに作るため、形式はスレッドローカルに保持します。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+// =============================================================================
+// 形式
+// =============================================================================
+
+/// ログの形式（Spin 変数 log_format）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LogFormat {
+    /// 人が読む形式（`[Gateway] ...`、ローカル開発用）
+    Text,
+
+    /// 1 行に 1 つの JSON オブジェクト（ログパイプライン用）
+    Json,
+}
+
+impl LogFormat {
+    /// "text" / "json" を解析する（大文字小文字を区別しない）
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        if value.eq_ignore_ascii_case("text") {
+            Some(Self::Text)
+        } else if value.eq_ignore_ascii_case("json") {
+            Some(Self::Json)
+        } else {
+            None
+        }
+    }
+}
+
+thread_local! {
+    /// 現在のリクエストのログの形式
+    static FORMAT: Cell<LogFormat> = const { Cell::new(LogFormat::Text) };
+}
+
+/// ログの形式を設定する（設定の読み込み後に 1 回呼び出す）
+pub(crate) fn set_format(format: LogFormat) {
+    FORMAT.with(|current| current.set(format));
+}
+
+/// 現在のログの形式
+fn format() -> LogFormat {
+    FORMAT.with(Cell::get)
+}
+
+// =============================================================================
+// 出来事のログ
+// =============================================================================
+
+/// ログのレベル
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Level {
+    /// 通常の処理（認証の成功・リトライ・回路の状態遷移など）
+    Info,
+
+    /// 処理は続けるが確認が必要なもの（key-value ストアの失敗など）
+    Warn,
+
+    /// 設定の誤りなど、直ちに対応が必要なもの（許可していないホストへの送信など）
+    Error,
+}
+
+/// 出来事のログの JSON
+#[derive(Serialize)]
+struct EventLine<'a> {
+    timestamp: String,
+    level: Level,
+    message: &'a str,
+}
+
+/// 処理中の出来事を出力する
+///
+/// # 引数
+/// * `message` - メッセージ（例: "Circuit opened after 5 consecutive failures"）
+pub(crate) fn info(message: &str) {
+    event(Level::Info, message);
+}
+
+/// 処理は続けるが確認が必要な出来事を出力する
+///
+/// # 引数
+/// * `message` - メッセージ（例: "Failed to record metric ..."）
+pub(crate) fn warn(message: &str) {
+    event(Level::Warn, message);
+}
+
+/// 直ちに対応が必要な出来事を出力する
+///
+/// # 引数
+/// * `message` - メッセージ（例: "Blocked outbound request to ..."）
+pub(crate) fn error(message: &str) {
+    event(Level::Error, message);
+}
+
+fn event(level: Level, message: &str) {
+    println!("{}", render_event(format(), level, message, now_millis()));
+}
+
+/// 出来事のログの 1 行を組み立てる
+fn render_event(format: LogFormat, level: Level, message: &str, now_millis: u64) -> String {
+    match format {
+        LogFormat::Text => format!("[Gateway] {}", message),
+        LogFormat::Json => serde_json::to_string(&EventLine {
+            timestamp: rfc3339(now_millis),
+            level,
+            message,
+        })
+        .unwrap(),
+    }
+}
+
+// =============================================================================
+// アクセスログ
+// =============================================================================
+
+/// リクエストごとのアクセスログ
+///
+/// handle_request で作成し、処理の途中で分かった値（リクエスト ID・ユーザー ID・
+/// コア層のレイテンシ・エラー）を記録して、レスポンスのステータスとともに finish で出力する。
+/// 参照（&RequestLog）で渡せるよう、記録する値は内部可変にする。
+#[derive(Debug)]
+pub(crate) struct RequestLog {
+    /// 受信した時刻（レイテンシの計測）
+    started: Instant,
+
+    /// HTTP メソッド（例: "GET"）
+    method: String,
+
+    /// リクエストパス（クエリ文字列を除く）
+    path: String,
+
+    /// リクエスト追跡用 ID（/api/* のみ）
+    request_id: RefCell<Option<String>>,
+
+    /// 認証済みユーザー ID
+    user_id: RefCell<Option<String>>,
+
+    /// コア層への送信（リトライを含む）からレスポンスのヘッダーの受信までの時間
+    upstream: Cell<Option<Duration>>,
+
+    /// エラーの詳細（認証失敗・転送の失敗など）
+    error: RefCell<Option<String>>,
+}
+
+/// アクセスログの JSON
+#[derive(Serialize)]
+struct AccessLine<'a> {
+    timestamp: String,
+    level: Level,
+    method: &'a str,
+    path: &'a str,
+    status: u16,
+    latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upstream_latency_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+}
+
+/// 遅い・大きいコア層のレスポンスの警告の JSON
+#[derive(Serialize)]
+struct UpstreamWarningLine<'a> {
+    timestamp: String,
+    level: Level,
+    message: &'a str,
+    method: &'a str,
+    path: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_id: Option<&'a str>,
+    upstream_latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body_bytes: Option<u64>,
+}
+
+impl RequestLog {
+    /// リクエストの受信時に作成する
+    ///
+    /// # 引数
+    /// * `method` - HTTP メソッド
+    /// * `path` - リクエストパス（クエリ文字列を除く）
+    pub(crate) fn new(method: impl ToString, path: impl ToString) -> Self {
+        Self {
+            started: Instant::now(),
+            method: method.to_string(),
+            path: path.to_string(),
+            request_id: RefCell::new(None),
+            user_id: RefCell::new(None),
+            upstream: Cell::new(None),
+            error: RefCell::new(None),
+        }
+    }
+
+    /// 受信してからの時間
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// リクエスト追跡用 ID を記録する
+    pub(crate) fn set_request_id(&self, request_id: &str) {
+        *self.request_id.borrow_mut() = Some(request_id.to_string());
+    }
+
+    /// 記録したリクエスト追跡用 ID（ない場合は "-"）
+    pub(crate) fn request_id(&self) -> String {
+        self.request_id
+            .borrow()
+            .clone()
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    /// 認証済みユーザー ID を記録する
+    pub(crate) fn set_user_id(&self, user_id: &str) {
+        *self.user_id.borrow_mut() = Some(user_id.to_string());
+    }
+
+    /// コア層のレイテンシを記録する
+    pub(crate) fn set_upstream_latency(&self, elapsed: Duration) {
+        self.upstream.set(Some(elapsed));
+    }
+
+    /// エラーの詳細を記録する（複数ある場合は最後のもの）
+    pub(crate) fn set_error(&self, error: impl Into<String>) {
+        *self.error.borrow_mut() = Some(error.into());
+    }
+
+    /// 遅い・大きいコア層のレスポンスの警告を出力する（メソッド・パス・リクエスト ID・ユーザー ID を含む）
+    ///
+    /// # 引数
+    /// * `message` - 警告の内容（例: "Slow upstream response (threshold 1000ms)"）
+    /// * `upstream` - コア層のレイテンシ
+    /// * `body_bytes` - レスポンスのボディの大きさ（ストリーミングで Content-Length がない場合は None）
+    pub(crate) fn warn_upstream(&self, message: &str, upstream: Duration, body_bytes: Option<u64>) {
+        println!(
+            "{}",
+            self.render_upstream_warning(format(), message, upstream, body_bytes, now_millis())
+        );
+    }
+
+    /// 遅い・大きいコア層のレスポンスの警告の 1 行を組み立てる
+    fn render_upstream_warning(
+        &self,
+        format: LogFormat,
+        message: &str,
+        upstream: Duration,
+        body_bytes: Option<u64>,
+        now_millis: u64,
+    ) -> String {
+        let request_id = self.request_id.borrow();
+        let user_id = self.user_id.borrow();
+
+        match format {
+            LogFormat::Text => {
+                let mut fields = Vec::new();
+                if let Some(request_id) = request_id.as_deref() {
+                    fields.push(format!("request_id={}", request_id));
+                }
+                if let Some(user_id) = user_id.as_deref() {
+                    fields.push(format!("user_id={}", user_id));
+                }
+                fields.push(format!("upstream={}ms", millis(upstream)));
+                if let Some(body_bytes) = body_bytes {
+                    fields.push(format!("body={}B", body_bytes));
+                }
+                format!(
+                    "[Gateway] {}: {} {} ({})",
+                    message,
+                    self.method,
+                    self.path,
+                    fields.join(", ")
+                )
+            }
+            LogFormat::Json => serde_json::to_string(&UpstreamWarningLine {
+                timestamp: rfc3339(now_millis),
+                level: Level::Warn,
+                message,
+                method: &self.method,
+                path: &self.path,
+                request_id: request_id.as_deref(),
+                user_id: user_id.as_deref(),
+                upstream_latency_ms: millis(upstream),
+                body_bytes,
+            })
+            .unwrap(),
+        }
+    }
+
+    /// アクセスログを出力する
+    ///
+    /// # 引数
+    /// * `status` - レスポンスのステータスコード
+    pub(crate) fn finish(&self, status: u16) {
+        println!(
+            "{}",
+            self.render(format(), status, self.elapsed(), now_millis())
+        );
+    }
+
+    /// アクセスログの 1 行を組み立てる
+    fn render(&self, format: LogFormat, status: u16, elapsed: Duration, now_millis: u64) -> String {
+        let request_id = self.request_id.borrow();
+        let user_id = self.user_id.borrow();
+        let error = self.error.borrow();
+        let upstream_ms = self.upstream.get().map(millis);
+
+        match format {
+            LogFormat::Text => {
+                let mut fields = Vec::new();
+                if let Some(request_id) = request_id.as_deref() {
+                    fields.push(format!("request_id={}", request_id));
+                }
+                if let Some(user_id) = user_id.as_deref() {
+                    fields.push(format!("user_id={}", user_id));
+                }
+                if let Some(upstream_ms) = upstream_ms {
+                    fields.push(format!("upstream={}ms", upstream_ms));
+                }
+                if let Some(error) = error.as_deref() {
+                    fields.push(format!("error={}", error));
+                }
+
+                let line = format!(
+                    "[Gateway] {} {} {} {}ms",
+                    self.method,
+                    self.path,
+                    status,
+                    millis(elapsed)
+                );
+                if fields.is_empty() {
+                    line
+                } else {
+                    format!("{} ({})", line, fields.join(", "))
+                }
+            }
+            LogFormat::Json => serde_json::to_string(&AccessLine {
+                timestamp: rfc3339(now_millis),
+                level: if status >= 500 {
+                    Level::Warn
+                } else {
+                    Level::Info
+                },
+                method: &self.method,
+                path: &self.path,
+                status,
+                latency_ms: millis(elapsed),
+                request_id: request_id.as_deref(),
+                user_id: user_id.as_deref(),
+                upstream_latency_ms: upstream_ms,
+                error: error.as_deref(),
+            })
+            .unwrap(),
+        }
+    }
+}
+
+// =============================================================================
+// 時刻
+// =============================================================================
+
+/// 時間をミリ秒にする
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// 現在時刻（Unix エポックからのミリ秒、取得できない場合は 0）
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(millis)
+        .unwrap_or_default()
+}
+
+/// Unix エポックからのミリ秒を RFC 3339（UTC、ミリ秒まで）にする
+///
+/// 例: 1_700_000_000_123 → "2023-11-14T22:13:20.123Z"
+fn rfc3339(unix_millis: u64) -> String {
+    let secs = unix_millis / 1000;
+    let days = secs / 86_400;
+    let secs_of_day = secs % 86_400;
+
+    // 1970-01-01 からの日数を年月日にする（proleptic グレゴリオ暦）
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60,
+        unix_millis % 1000
+    )
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000_123;
+
+    #[test]
+    fn test_log_format_parse() {
+        assert_eq!(LogFormat::parse("text"), Some(LogFormat::Text));
+        assert_eq!(LogFormat::parse("JSON"), Some(LogFormat::Json));
+        assert_eq!(LogFormat::parse("logfmt"), None);
+    }
+
+    #[test]
+    fn test_rfc3339() {
+        assert_eq!(rfc3339(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(rfc3339(NOW), "2023-11-14T22:13:20.123Z");
+        // うるう日・年末
+        assert_eq!(rfc3339(951_782_400_000), "2000-02-29T00:00:00.000Z");
+        assert_eq!(rfc3339(1_798_761_599_999), "2026-12-31T23:59:59.999Z");
+    }
+
+    #[test]
+    fn test_render_access_log() {
+        let log = RequestLog::new("GET", "/api/todos");
+        let elapsed = Duration::from_millis(15);
+
+        // 値を記録する前（/health など）
+        assert_eq!(
+            log.render(LogFormat::Text, 200, elapsed, NOW),
+            "[Gateway] GET /api/todos 200 15ms"
+        );
+        assert_eq!(
+            log.render(LogFormat::Json, 200, elapsed, NOW),
+            r#"{"timestamp":"2023-11-14T22:13:20.123Z","level":"info","method":"GET","path":"/api/todos","status":200,"latency_ms":15}"#
+        );
+
+        log.set_request_id("req-1");
+        log.set_user_id("user-1");
+        log.set_upstream_latency(Duration::from_millis(12));
+        assert_eq!(
+            log.render(LogFormat::Text, 200, elapsed, NOW),
+            "[Gateway] GET /api/todos 200 15ms (request_id=req-1, user_id=user-1, upstream=12ms)"
+        );
+        assert_eq!(log.request_id(), "req-1");
+
+        log.set_error("Proxy error: connection refused");
+        let line: serde_json::Value =
+            serde_json::from_str(&log.render(LogFormat::Json, 502, elapsed, NOW)).unwrap();
+        assert_eq!(line["level"], "warn");
+        assert_eq!(line["status"], 502);
+        assert_eq!(line["request_id"], "req-1");
+        assert_eq!(line["user_id"], "user-1");
+        assert_eq!(line["upstream_latency_ms"], 12);
+        assert_eq!(line["error"], "Proxy error: connection refused");
+    }
+
+    #[test]
+    fn test_render_upstream_warning() {
+        let log = RequestLog::new("GET", "/api/todos/export");
+        let upstream = Duration::from_millis(1530);
+
+        // パブリックパス（ユーザー ID なし）
+        log.set_request_id("req-1");
+        assert_eq!(
+            log.render_upstream_warning(
+                LogFormat::Text,
+                "Slow upstream response (threshold 1000ms)",
+                upstream,
+                Some(2048),
+                NOW
+            ),
+            "[Gateway] Slow upstream response (threshold 1000ms): GET /api/todos/export (request_id=req-1, upstream=1530ms, body=2048B)"
+        );
+
+        log.set_user_id("user-1");
+        assert_eq!(
+            log.render_upstream_warning(
+                LogFormat::Json,
+                "Large upstream response",
+                upstream,
+                None,
+                NOW
+            ),
+            r#"{"timestamp":"2023-11-14T22:13:20.123Z","level":"warn","message":"Large upstream response","method":"GET","path":"/api/todos/export","request_id":"req-1","user_id":"user-1","upstream_latency_ms":1530}"#
+        );
+    }
+
+    #[test]
+    fn test_render_event() {
+        assert_eq!(
+            render_event(LogFormat::Text, Level::Info, "Circuit opened", NOW),
+            "[Gateway] Circuit opened"
+        );
+        assert_eq!(
+            render_event(LogFormat::Json, Level::Warn, "Failed to \"record\"", NOW),
+            r#"{"timestamp":"2023-11-14T22:13:20.123Z","level":"warn","message":"Failed to \"record\""}"#
+        );
+        assert_eq!(
+            render_event(LogFormat::Json, Level::Error, "Blocked", NOW),
+            r#"{"timestamp":"2023-11-14T22:13:20.123Z","level":"error","message":"Blocked"}"#
+        );
+    }
+}
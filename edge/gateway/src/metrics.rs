@@ -0,0 +1,464 @@
+//! # gateway のメトリクス
+//!
+//! gateway 自身のカウンタを key-value ストア（`default`）に保存し、
+//! auth コンポーネントのカウンタ（`get-metrics`）と合わせて返します。
+//!
+//! | パス | 形式 |
+//! |------|------|
+//! | `/gateway/metrics` | JSON（カウンタ名 → 値） |
+//! | `/metrics` | Prometheus のテキスト形式（ステータスクラス別のレスポンス数・レイテンシのヒストグラムを含む） |
+//!
+//! カウンタはアトミックなインクリメントで加算するため、コンポーネントのインスタンス間で共有され、
+//! 同時に実行されても失われません。ただしヒストグラムのバケット・合計・件数は別々のキーのため、
+//! 読み込み中に記録されたリクエストの分だけ一時的にずれることがあります。
+//! ストアの操作に失敗してもリクエストの処理には影響しません（ログのみ）。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+// メトリクスの JSON（カウンタ名でソートして出力）
+use std::collections::BTreeMap;
+
+// Prometheus のテキスト形式の組み立て
+use std::fmt::Write;
+
+// レイテンシ
+use std::time::Duration;
+
+// Spin の key-value ストア（gateway 自身のカウンタ）
+// spin_sdk::key_value::Store には increment がないため、WIT バインディングを直接使用
+use spin_sdk::wit::wasi::keyvalue::{atomics, store};
+
+use spin_sdk::http::Response;
+
+// auth コンポーネントの検証のカウンタ
+use crate::demo::auth::metrics::get_metrics;
+
+// key-value ストアの失敗のログ
+use crate::logging;
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// カウンタを保存する key-value ストア名
+///
+/// spin.toml の `key_value_stores` で許可したストアと一致させる。
+const METRICS_STORE: &str = "default";
+
+/// gateway のカウンタのキーのプレフィックス
+///
+/// 同じストアを使う ratelimit・auth のキーと衝突しないようにする。
+const METRICS_KEY_PREFIX: &str = "gateway:metrics";
+
+/// 受信したリクエストの数
+pub(crate) const REQUESTS_TOTAL: &str = "gateway_requests_total";
+
+/// レート制限で拒否した数（429）
+pub(crate) const RATE_LIMITED_TOTAL: &str = "gateway_rate_limited_total";
+
+/// 認証に失敗した数（401）
+pub(crate) const AUTH_FAILURES_TOTAL: &str = "gateway_auth_failures_total";
+
+/// 認可・IP の許可・拒否リストで拒否した数（403）
+pub(crate) const FORBIDDEN_TOTAL: &str = "gateway_forbidden_total";
+
+/// コア層への転送に失敗した数（502 / 503 / 504）
+pub(crate) const PROXY_ERRORS_TOTAL: &str = "gateway_proxy_errors_total";
+
+/// /gateway/metrics・/metrics で返す gateway のカウンタ名
+const GATEWAY_METRIC_NAMES: [&str; 5] = [
+    REQUESTS_TOTAL,
+    RATE_LIMITED_TOTAL,
+    AUTH_FAILURES_TOTAL,
+    FORBIDDEN_TOTAL,
+    PROXY_ERRORS_TOTAL,
+];
+
+/// ステータスクラス別のレスポンス数（`class` ラベルで区別する）
+const RESPONSES_TOTAL: &str = "gateway_responses_total";
+
+/// レスポンス数を数えるステータスクラス
+const STATUS_CLASSES: [&str; 5] = ["1xx", "2xx", "3xx", "4xx", "5xx"];
+
+/// レイテンシのヒストグラム名（秒）
+const DURATION_HISTOGRAM: &str = "gateway_request_duration_seconds";
+
+/// レイテンシのヒストグラムのバケットの上限（ミリ秒）
+///
+/// これを超えたリクエストは +Inf のバケットに数える。
+const DURATION_BUCKETS_MS: [u64; 11] = [5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000];
+
+/// Prometheus のテキスト形式の Content-Type
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+// =============================================================================
+// 記録
+// =============================================================================
+
+/// gateway のカウンタを 1 増やす
+///
+/// # 引数
+/// * `name` - カウンタ名（GATEWAY_METRIC_NAMES のいずれか）
+pub(crate) fn increment(name: &str) {
+    add(&metric_key(name), 1);
+}
+
+/// レスポンスのステータスクラスとレイテンシを記録する
+///
+/// レイテンシはリクエストの受信からレスポンスのヘッダーの送信までの時間
+/// （ストリーミングするボディの転送時間は含まない）。
+///
+/// # 引数
+/// * `status` - レスポンスのステータスコード
+/// * `elapsed` - レイテンシ
+pub(crate) fn record_response(status: u16, elapsed: Duration) {
+    if let Some(class) = status_class(status) {
+        add(&format!("{}:{}", metric_key(RESPONSES_TOTAL), class), 1);
+    }
+
+    let millis = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX);
+    add(&bucket_key(bucket_index(millis)), 1);
+    add(&sum_key(), millis);
+}
+
+/// key-value ストアのキーに delta を加算する（失敗はログのみ）
+fn add(key: &str, delta: u64) {
+    let delta = i64::try_from(delta).unwrap_or(i64::MAX);
+    let result =
+        store::open(METRICS_STORE).and_then(|bucket| atomics::increment(&bucket, key, delta));
+    if let Err(e) = result {
+        logging::warn(&format!("Failed to record metric {}: {:?}", key, e));
+    }
+}
+
+/// ステータスコードのクラス（"2xx" など、100〜599 以外は None）
+fn status_class(status: u16) -> Option<&'static str> {
+    STATUS_CLASSES
+        .get(usize::from(status / 100).checked_sub(1)?)
+        .copied()
+}
+
+/// レイテンシを数えるバケットの番号（DURATION_BUCKETS_MS の添字、超えた場合は +Inf の番号）
+fn bucket_index(millis: u64) -> usize {
+    DURATION_BUCKETS_MS
+        .iter()
+        .position(|&upper| millis <= upper)
+        .unwrap_or(DURATION_BUCKETS_MS.len())
+}
+
+/// gateway のカウンタのキーを組み立てる
+///
+/// 例: `gateway:metrics:gateway_requests_total`
+fn metric_key(name: &str) -> String {
+    format!("{}:{}", METRICS_KEY_PREFIX, name)
+}
+
+/// レイテンシのバケットのキー（累積ではなくバケットごとの件数）
+///
+/// 例: `gateway:metrics:gateway_request_duration_seconds:bucket:3`
+fn bucket_key(index: usize) -> String {
+    format!("{}:bucket:{}", metric_key(DURATION_HISTOGRAM), index)
+}
+
+/// レイテンシの合計（ミリ秒）のキー
+fn sum_key() -> String {
+    format!("{}:sum_ms", metric_key(DURATION_HISTOGRAM))
+}
+
+// =============================================================================
+// 読み込み
+// =============================================================================
+
+/// 読み込んだ gateway のメトリクス
+#[derive(Debug, Default, PartialEq)]
+struct Snapshot {
+    /// gateway のカウンタ（名前, 値）
+    counters: Vec<(String, u64)>,
+
+    /// ステータスクラス別のレスポンス数（クラス, 値）
+    responses: Vec<(&'static str, u64)>,
+
+    /// レイテンシのバケットごとの件数（DURATION_BUCKETS_MS の順、最後は +Inf）
+    duration_buckets: Vec<u64>,
+
+    /// レイテンシの合計（ミリ秒）
+    duration_sum_ms: u64,
+}
+
+/// gateway のメトリクスの現在の値を読む
+///
+/// 0 を加算して現在の値を取得する（まだ数えていないカウンタは 0）。
+/// key-value ストアにアクセスできない場合は空。
+fn read() -> Snapshot {
+    let bucket = match store::open(METRICS_STORE) {
+        Ok(bucket) => bucket,
+        Err(e) => {
+            logging::warn(&format!("Failed to read metrics: {:?}", e));
+            return Snapshot::default();
+        }
+    };
+    let get = |key: &str| {
+        atomics::increment(&bucket, key, 0)
+            .ok()
+            .map(|value| value.max(0) as u64)
+    };
+
+    Snapshot {
+        counters: GATEWAY_METRIC_NAMES
+            .iter()
+            .filter_map(|&name| Some((name.to_string(), get(&metric_key(name))?)))
+            .collect(),
+        responses: STATUS_CLASSES
+            .iter()
+            .filter_map(|&class| {
+                let key = format!("{}:{}", metric_key(RESPONSES_TOTAL), class);
+                Some((class, get(&key)?))
+            })
+            .collect(),
+        duration_buckets: (0..=DURATION_BUCKETS_MS.len())
+            .map(|index| get(&bucket_key(index)).unwrap_or(0))
+            .collect(),
+        duration_sum_ms: get(&sum_key()).unwrap_or(0),
+    }
+}
+
+// =============================================================================
+// レスポンス
+// =============================================================================
+
+/// /gateway/metrics のレスポンスを組み立てる
+///
+/// auth コンポーネントの get_metrics と gateway 自身のカウンタを 1 つの JSON オブジェクトにする。
+/// 例: {"auth_verifications_total": 42, ..., "gateway_requests_total": 50}
+pub(crate) fn json_response() -> Response {
+    let body = serde_json::to_string(&merge_metrics(get_metrics(), read().counters)).unwrap();
+
+    Response::builder()
+        .status(200)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-store")
+        .body(body)
+        .build()
+}
+
+/// /metrics のレスポンス（Prometheus のテキスト形式）を組み立てる
+pub(crate) fn prometheus_response() -> Response {
+    let body = render_prometheus(get_metrics(), &read());
+
+    Response::builder()
+        .status(200)
+        .header("Content-Type", PROMETHEUS_CONTENT_TYPE)
+        .header("Cache-Control", "no-store")
+        .body(body)
+        .build()
+}
+
+/// auth と gateway のカウンタを合わせる
+///
+/// カウンタ名はコンポーネントごとのプレフィックス（auth_ / gateway_）で区別されるが、
+/// 同じ名前が含まれていた場合は値を合計する。
+///
+/// # 引数
+/// * `auth` - auth コンポーネントのカウンタ（名前, 値）
+/// * `gateway` - gateway のカウンタ（名前, 値）
+///
+/// # 戻り値
+/// * `BTreeMap<String, u64>` - カウンタ名でソートしたカウンタ
+fn merge_metrics(auth: Vec<(String, u64)>, gateway: Vec<(String, u64)>) -> BTreeMap<String, u64> {
+    let mut merged = BTreeMap::new();
+    for (name, value) in auth.into_iter().chain(gateway) {
+        let total: &mut u64 = merged.entry(name).or_default();
+        *total = total.saturating_add(value);
+    }
+    merged
+}
+
+/// メトリクスを Prometheus のテキスト形式にする
+///
+/// auth と gateway のカウンタはそのままの名前の counter（merge_metrics で合わせる）、
+/// ステータスクラス別のレスポンス数は `gateway_responses_total{class="2xx"}`、
+/// レイテンシは `gateway_request_duration_seconds` の histogram（バケットは累積）にする。
+/// Prometheus のメトリクス名として使えない名前のカウンタは出力しない。
+///
+/// # 引数
+/// * `auth` - auth コンポーネントのカウンタ（名前, 値）
+/// * `snapshot` - gateway のメトリクス
+///
+/// # 戻り値
+/// * `String` - 例: "# TYPE gateway_requests_total counter\ngateway_requests_total 50\n..."
+fn render_prometheus(auth: Vec<(String, u64)>, snapshot: &Snapshot) -> String {
+    let mut out = String::new();
+
+    let counters = merge_metrics(auth, snapshot.counters.clone());
+    for (name, value) in counters.iter().filter(|(name, _)| is_metric_name(name)) {
+        let _ = writeln!(out, "# TYPE {} counter", name);
+        let _ = writeln!(out, "{} {}", name, value);
+    }
+
+    let _ = writeln!(out, "# HELP {} Responses by status class.", RESPONSES_TOTAL);
+    let _ = writeln!(out, "# TYPE {} counter", RESPONSES_TOTAL);
+    for (class, value) in &snapshot.responses {
+        let _ = writeln!(out, "{}{{class=\"{}\"}} {}", RESPONSES_TOTAL, class, value);
+    }
+
+    let _ = writeln!(
+        out,
+        "# HELP {} Time until the response headers are sent.",
+        DURATION_HISTOGRAM
+    );
+    let _ = writeln!(out, "# TYPE {} histogram", DURATION_HISTOGRAM);
+    let mut cumulative = 0u64;
+    for (index, count) in snapshot.duration_buckets.iter().enumerate() {
+        cumulative = cumulative.saturating_add(*count);
+        let le = DURATION_BUCKETS_MS
+            .get(index)
+            .map_or("+Inf".to_string(), |&millis| seconds(millis));
+        let _ = writeln!(
+            out,
+            "{}_bucket{{le=\"{}\"}} {}",
+            DURATION_HISTOGRAM, le, cumulative
+        );
+    }
+    let _ = writeln!(
+        out,
+        "{}_sum {}",
+        DURATION_HISTOGRAM,
+        seconds(snapshot.duration_sum_ms)
+    );
+    let _ = writeln!(out, "{}_count {}", DURATION_HISTOGRAM, cumulative);
+
+    out
+}
+
+/// ミリ秒を秒の文字列にする（例: 2500 → "2.5"、5 → "0.005"）
+fn seconds(millis: u64) -> String {
+    let fraction = format!("{:03}", millis % 1000);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        (millis / 1000).to_string()
+    } else {
+        format!("{}.{}", millis / 1000, fraction)
+    }
+}
+
+/// Prometheus のメトリクス名として使えるか（`[a-zA-Z_:][a-zA-Z0-9_:]*`）
+fn is_metric_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == ':')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_metrics() {
+        let auth = vec![
+            ("auth_verifications_total".to_string(), 5),
+            ("auth_failures_expired".to_string(), 2),
+        ];
+        let gateway = vec![
+            ("gateway_requests_total".to_string(), 9),
+            ("auth_failures_expired".to_string(), 1),
+        ];
+
+        let merged = merge_metrics(auth, gateway);
+        assert_eq!(
+            serde_json::to_string(&merged).unwrap(),
+            r#"{"auth_failures_expired":3,"auth_verifications_total":5,"gateway_requests_total":9}"#
+        );
+
+        // auth のカウンタが取得できない場合（空リスト）も gateway のカウンタは返す
+        let merged = merge_metrics(Vec::new(), vec![("gateway_requests_total".to_string(), 1)]);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged["gateway_requests_total"], 1);
+    }
+
+    #[test]
+    fn test_status_class() {
+        assert_eq!(status_class(101), Some("1xx"));
+        assert_eq!(status_class(200), Some("2xx"));
+        assert_eq!(status_class(304), Some("3xx"));
+        assert_eq!(status_class(401), Some("4xx"));
+        assert_eq!(status_class(599), Some("5xx"));
+        assert_eq!(status_class(99), None);
+        assert_eq!(status_class(600), None);
+    }
+
+    #[test]
+    fn test_bucket_index() {
+        assert_eq!(bucket_index(0), 0);
+        assert_eq!(bucket_index(5), 0);
+        assert_eq!(bucket_index(6), 1);
+        assert_eq!(bucket_index(1000), 7);
+        assert_eq!(bucket_index(10_000), 10);
+        assert_eq!(bucket_index(10_001), DURATION_BUCKETS_MS.len());
+    }
+
+    #[test]
+    fn test_seconds() {
+        assert_eq!(seconds(0), "0");
+        assert_eq!(seconds(5), "0.005");
+        assert_eq!(seconds(250), "0.25");
+        assert_eq!(seconds(2500), "2.5");
+        assert_eq!(seconds(10_000), "10");
+    }
+
+    #[test]
+    fn test_render_prometheus() {
+        let auth = vec![
+            ("auth_verifications_total".to_string(), 5),
+            ("auth failures".to_string(), 1),
+        ];
+        let mut duration_buckets = vec![0; DURATION_BUCKETS_MS.len() + 1];
+        duration_buckets[0] = 2; // 5ms 以下
+        duration_buckets[4] = 1; // 100ms 以下
+        duration_buckets[DURATION_BUCKETS_MS.len()] = 1; // 10 秒超
+        let snapshot = Snapshot {
+            counters: vec![
+                (REQUESTS_TOTAL.to_string(), 4),
+                (AUTH_FAILURES_TOTAL.to_string(), 1),
+            ],
+            responses: vec![("2xx", 3), ("4xx", 1)],
+            duration_buckets,
+            duration_sum_ms: 12_084,
+        };
+
+        let text = render_prometheus(auth, &snapshot);
+        let lines: Vec<&str> = text.lines().collect();
+
+        for expected in [
+            "# TYPE auth_verifications_total counter",
+            "auth_verifications_total 5",
+            "# TYPE gateway_auth_failures_total counter",
+            "gateway_auth_failures_total 1",
+            "gateway_requests_total 4",
+            "# TYPE gateway_responses_total counter",
+            "gateway_responses_total{class=\"2xx\"} 3",
+            "gateway_responses_total{class=\"4xx\"} 1",
+            "# TYPE gateway_request_duration_seconds histogram",
+            "gateway_request_duration_seconds_bucket{le=\"0.005\"} 2",
+            "gateway_request_duration_seconds_bucket{le=\"0.05\"} 2",
+            "gateway_request_duration_seconds_bucket{le=\"0.1\"} 3",
+            "gateway_request_duration_seconds_bucket{le=\"10\"} 3",
+            "gateway_request_duration_seconds_bucket{le=\"+Inf\"} 4",
+            "gateway_request_duration_seconds_sum 12.084",
+            "gateway_request_duration_seconds_count 4",
+        ] {
+            assert!(lines.contains(&expected), "{}\n{}", expected, text);
+        }
+        // メトリクス名として使えない名前は出力しない
+        assert!(!text.contains("auth failures"));
+        // 各行は改行で終わる
+        assert!(text.ends_with('\n'));
+    }
+}
@@ -0,0 +1,143 @@
+//! # リクエストパスの正規化
+//!
+//! ルーティング（パブリックパス・認証ポリシー・/api/* の判定）とコア層への転送の前に、
+//! パスの連続した `/` を 1 つにまとめ、末尾の `/` を 1 つ取り除きます（ルート `/` を除く）。
+//!
+//! | 受信したパス | 正規化したパス |
+//! |--------------|----------------|
+//! | `/api/todos/` | `/api/todos` |
+//! | `//api/todos` | `/api/todos` |
+//! | `/api//todos//` | `/api/todos` |
+//! | `/` | `/` |
+//!
+//! axum のルーターは `/api/todos/` を `/api/todos` と区別して 404 を返し、`//api/todos` は
+//! `starts_with("/api/")` の判定をすり抜けるため、gateway で揃えます。
+//! パーセントエンコードした部分（例: `%2F`）はデコードせず、クエリ文字列も変更しません。
+
+// =============================================================================
+// 正規化
+// =============================================================================
+
+/// パスを正規化する
+///
+/// # 引数
+/// * `path` - リクエストパス（クエリ文字列を除く）
+///
+/// # 戻り値
+/// * `String` - 連続した `/` をまとめ、末尾の `/` を取り除いたパス
+///   （`/` で始まらないパス（例: OPTIONS の `*`）はそのまま）
+pub(crate) fn normalize_path(path: &str) -> String {
+    if !path.starts_with('/') {
+        return path.to_string();
+    }
+
+    let mut normalized = String::with_capacity(path.len());
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        normalized.push('/');
+        normalized.push_str(segment);
+    }
+    if normalized.is_empty() {
+        normalized.push('/');
+    }
+    normalized
+}
+
+/// パスとクエリ文字列のうち、パスだけを正規化する
+///
+/// # 引数
+/// * `path_with_query` - パスとクエリ文字列（例: "/api//todos/?completed=false"）
+///
+/// # 戻り値
+/// * `String` - 正規化したパスとクエリ文字列（例: "/api/todos?completed=false"）
+pub(crate) fn normalize_path_with_query(path_with_query: &str) -> String {
+    match path_with_query.split_once('?') {
+        Some((path, query)) => format!("{}?{}", normalize_path(path), query),
+        None => normalize_path(path_with_query),
+    }
+}
+
+/// URI（`scheme://authority/path?query`）のパスだけを正規化する
+///
+/// スキーム・オーソリティがない場合（パスとクエリ文字列のみ）は全体をパスとして扱う。
+///
+/// # 引数
+/// * `uri` - 受信したリクエストの URI（例: "http://localhost:3000//api/todos/"）
+pub(crate) fn normalize_uri(uri: &str) -> String {
+    let Some((scheme, rest)) = uri.split_once("://") else {
+        return normalize_path_with_query(uri);
+    };
+    let (authority, path_with_query) = match rest.find(['/', '?']) {
+        Some(index) => rest.split_at(index),
+        None => (rest, ""),
+    };
+    let path_with_query = if path_with_query.starts_with('/') {
+        normalize_path_with_query(path_with_query)
+    } else {
+        // パスが空（"http://host?query" など）
+        format!("/{}", path_with_query)
+    };
+    format!("{}://{}{}", scheme, authority, path_with_query)
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_path() {
+        for (path, expected) in [
+            ("/api/todos/", "/api/todos"),
+            ("//api/todos", "/api/todos"),
+            ("/api//todos//", "/api/todos"),
+            ("/api/todos", "/api/todos"),
+            ("/", "/"),
+            ("//", "/"),
+            ("/health/", "/health"),
+        ] {
+            assert_eq!(normalize_path(path), expected, "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_normalize_path_keeps_percent_encoding() {
+        // エンコードした "/"（%2F）・"%2f%2f" はまとめない
+        assert_eq!(
+            normalize_path("/api/files/a%2F%2Fb//download/"),
+            "/api/files/a%2F%2Fb/download"
+        );
+        assert_eq!(normalize_path("/api/todos/%20/"), "/api/todos/%20");
+        // "/" で始まらないパスはそのまま
+        assert_eq!(normalize_path("*"), "*");
+    }
+
+    #[test]
+    fn test_normalize_path_with_query() {
+        assert_eq!(
+            normalize_path_with_query("/api//todos/?redirect=//example.com/"),
+            "/api/todos?redirect=//example.com/"
+        );
+        assert_eq!(normalize_path_with_query("/api/todos/"), "/api/todos");
+    }
+
+    #[test]
+    fn test_normalize_uri() {
+        assert_eq!(
+            normalize_uri("http://localhost:3000//api//todos/?completed=false"),
+            "http://localhost:3000/api/todos?completed=false"
+        );
+        assert_eq!(
+            normalize_uri("https://example.com/"),
+            "https://example.com/"
+        );
+        assert_eq!(normalize_uri("http://example.com"), "http://example.com/");
+        assert_eq!(
+            normalize_uri("http://example.com?q=1"),
+            "http://example.com/?q=1"
+        );
+        assert_eq!(normalize_uri("/api/todos/"), "/api/todos");
+    }
+}
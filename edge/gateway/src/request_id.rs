@@ -0,0 +1,199 @@
+//! # リクエスト追跡用 ID（X-Request-Id）
+//!
+//! ロードバランサーなどが付与した X-Request-Id は、安全な形式の場合のみ引き継ぎます。
+//! クライアントが任意の値を送れるため、そのままではコア層のログに不正な値が混ざります。
+//!
+//! | 受信した値 | 使う ID |
+//! |------------|---------|
+//! | 8〜64 文字の `[A-Za-z0-9._-]`（ハイフン区切りの UUID を含む） | そのまま引き継ぐ |
+//! | ない・それ以外（空白・`:`・制御文字・長すぎる値など） | UUID v4 を生成する（リクエストは拒否しない） |
+//!
+//! 決めた ID は gateway のログ・コア層への転送・全てのレスポンス（IP の拒否・413・429・
+//! プリフライトなど、ルーティングの前に返すものを含む）で同じ値を使います（handle_request）。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+use std::fmt;
+use std::ops::Deref;
+
+use spin_sdk::http::Response;
+
+// UUID v4 の生成
+use uuid::Uuid;
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// リクエスト追跡用 ID のヘッダー名
+pub(crate) const HEADER: &str = "X-Request-Id";
+
+/// 引き継ぐ X-Request-Id の最小長（文字数）
+const MIN_LEN: usize = 8;
+
+/// 引き継ぐ X-Request-Id の最大長（文字数）
+const MAX_LEN: usize = 64;
+
+// =============================================================================
+// 型定義
+// =============================================================================
+
+/// 検証済みのリクエスト追跡用 ID
+///
+/// 引き継いだ値（is_valid を満たす）か、生成した UUID v4 のみを保持する。
+/// `&str` として参照できる（Deref）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RequestId(String);
+
+impl RequestId {
+    /// 受信した X-Request-Id から ID を決める
+    ///
+    /// # 引数
+    /// * `value` - X-Request-Id ヘッダーの値（ない場合は None）
+    ///
+    /// # 戻り値
+    /// * `RequestId` - 安全な形式の場合は受信した値、それ以外は生成した UUID v4
+    pub(crate) fn from_header(value: Option<&str>) -> Self {
+        match value {
+            Some(id) if is_valid(id) => Self(id.to_string()),
+            _ => Self::generate(),
+        }
+    }
+
+    /// UUID v4 を生成する
+    pub(crate) fn generate() -> Self {
+        Self(Uuid::new_v4().to_string())
+    }
+
+    /// ID を文字列として返す
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// レスポンスに X-Request-Id を付与する（既にある場合は上書きする）
+    ///
+    /// コア層が返した X-Request-Id も gateway の値にそろえる。
+    ///
+    /// # 引数
+    /// * `response` - クライアントに返すレスポンス（ストリーミングする場合はヘッダー部分）
+    pub(crate) fn apply(&self, response: &mut Response) {
+        // set_header はヘッダー名を小文字に変換しないため、builder の header() と同じ小文字で設定する
+        response.set_header(HEADER.to_ascii_lowercase(), self.as_str());
+    }
+}
+
+impl Deref for RequestId {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+// =============================================================================
+// 検証
+// =============================================================================
+
+/// 受信した X-Request-Id を引き継いでよいか
+///
+/// ログやヘッダーを壊さないよう、MIN_LEN〜MAX_LEN 文字の英数字と `.`・`_`・`-` のみ許可する
+/// （ハイフン区切りの UUID は 36 文字のためこれに含まれる）。
+///
+/// # 引数
+/// * `id` - X-Request-Id ヘッダーの値
+pub(crate) fn is_valid(id: &str) -> bool {
+    (MIN_LEN..=MAX_LEN).contains(&id.len())
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_accepts_safe_ids() {
+        for id in [
+            "0f8c2c1e-8d3b-4f7e-9a55-2b1c0d9e7a61",
+            "0F8C2C1E-8D3B-4F7E-9A55-2B1C0D9E7A61",
+            "lb-7f3a01",
+            "trace_2024.10.15",
+            "abcdefgh",
+        ] {
+            assert!(is_valid(id), "{}", id);
+        }
+        assert!(is_valid(&"a".repeat(MAX_LEN)));
+    }
+
+    #[test]
+    fn test_is_valid_rejects_unsafe_ids() {
+        let too_long = "a".repeat(MAX_LEN + 1);
+        for id in [
+            "",
+            "abc1234",
+            too_long.as_str(),
+            "lb-7f3a:01",
+            "two words",
+            "id\r\nX-User-Id: admin",
+            "id\t12345678",
+            "Root=1-5759e988-bd862e3fe1be46a994272793",
+            "{0f8c2c1e-8d3b-4f7e-9a55-2b1c0d9e7a61}",
+            "ｉｄｉｄｉｄｉｄ",
+        ] {
+            assert!(!is_valid(id), "{:?}", id);
+        }
+    }
+
+    #[test]
+    fn test_from_header() {
+        assert_eq!(
+            RequestId::from_header(Some("lb-7f3a01")).as_str(),
+            "lb-7f3a01"
+        );
+
+        // ない・不正な場合は UUID v4 を生成する（拒否しない）
+        for value in [None, Some(""), Some("two words"), Some("short")] {
+            let generated = RequestId::from_header(value);
+            let uuid = Uuid::parse_str(&generated).unwrap();
+            assert_eq!(uuid.get_version_num(), 4, "{:?}", value);
+            assert!(is_valid(&generated));
+        }
+    }
+
+    #[test]
+    fn test_apply_overwrites_response_header() {
+        let request_id = RequestId::from_header(Some("gateway-req-1"));
+
+        let mut response = Response::builder().status(204).build();
+        request_id.apply(&mut response);
+        assert_eq!(
+            response.header("X-Request-Id").and_then(|v| v.as_str()),
+            Some("gateway-req-1")
+        );
+
+        // コア層が返した値は gateway の値で上書きする
+        let mut response = Response::builder()
+            .status(200)
+            .header("X-Request-Id", "core-value")
+            .build();
+        request_id.apply(&mut response);
+        assert_eq!(
+            response.header("x-request-id").and_then(|v| v.as_str()),
+            Some("gateway-req-1")
+        );
+        assert_eq!(response.headers().count(), 1);
+    }
+}
@@ -0,0 +1,240 @@
+//! # コア層への転送のリトライ
+//!
+//! コア層（axum）の再起動中などに接続が拒否された場合、冪等なリクエストに限り
+//! 指数バックオフ（ジッター付き）で再送します。再送しても失敗した場合は呼び出し元が 502 を返します。
+//!
+//! | 条件 | リトライ |
+//! |------|----------|
+//! | GET / HEAD / PUT / DELETE（冪等なメソッド、RFC 9110 9.2.2） | する |
+//! | `Idempotency-Key` ヘッダーがある（POST・PATCH など） | する |
+//! | それ以外（キーのない POST・PATCH） | しない（TODO が二重に作成される恐れがあるため） |
+//!
+//! 回数と間隔は Spin 変数 `proxy_max_retries` / `proxy_backoff_ms` で設定します（config モジュール）。
+//! リトライするのは送信自体の失敗（接続拒否など）のみで、コア層が返した 5xx はそのまま返します。
+//! 再送するリクエストには `X-Retry-Attempt: n`（1 回目の再送は 1）を付与し、
+//! コア層が同じリクエストの再送であることをログなどで識別できるようにします。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+use std::time::Duration;
+
+use spin_sdk::http::conversions::TryFromIncomingResponse;
+use spin_sdk::http::{Method, Request, SendError};
+
+// ジッターの乱数（v4 UUID の乱数部分を使う）
+use uuid::Uuid;
+
+use crate::logging;
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// 再送の番号を付与するヘッダー名（初回の送信には付与しない）
+pub(crate) const RETRY_ATTEMPT_HEADER: &str = "X-Retry-Attempt";
+
+/// バックオフの上限（ミリ秒）
+///
+/// リトライ回数を増やしても 1 回の待機がこれより長くならないようにする。
+const MAX_BACKOFF_MS: u64 = 2_000;
+
+// =============================================================================
+// リトライの判定
+// =============================================================================
+
+/// リクエストをリトライしてよいかを判定する
+///
+/// # 引数
+/// * `method` - HTTP メソッド
+/// * `idempotency_key` - `Idempotency-Key` ヘッダーの値（ない場合は None）
+///
+/// # 戻り値
+/// * `true` - GET / HEAD / PUT / DELETE、または空でない `Idempotency-Key` がある場合
+pub(crate) fn is_retryable(method: &Method, idempotency_key: Option<&str>) -> bool {
+    matches!(
+        method,
+        Method::Get | Method::Head | Method::Put | Method::Delete
+    ) || idempotency_key.is_some_and(|key| !key.trim().is_empty())
+}
+
+/// 送信エラーがリトライで回復しうるかを判定する
+///
+/// HTTP の送信エラー（接続拒否・接続リセット・DNS エラーなど）のみリトライする。
+/// リクエスト・レスポンスの変換エラーは再送しても変わらないためリトライしない。
+fn is_transient(error: &SendError) -> bool {
+    matches!(error, SendError::Http(_))
+}
+
+/// attempt 回目（0 始まり）の失敗後に待機する時間を計算する
+///
+/// `backoff_ms * 2^attempt`（MAX_BACKOFF_MS で打ち切り）の半分に、
+/// 残り半分の範囲のジッターを加える（複数のリクエストが同時に再送しないように）。
+///
+/// # 引数
+/// * `backoff_ms` - 初回の待機時間（ミリ秒）
+/// * `attempt` - 失敗した試行の番号（0 始まり）
+/// * `random` - ジッターに使う乱数
+fn backoff_delay(backoff_ms: u64, attempt: u32, random: u64) -> Duration {
+    let exponential = backoff_ms
+        .saturating_mul(1u64.checked_shl(attempt).unwrap_or(u64::MAX))
+        .min(MAX_BACKOFF_MS);
+    let half = exponential / 2;
+    let jitter = random % (exponential - half + 1);
+    Duration::from_millis(half + jitter)
+}
+
+// =============================================================================
+// 送信
+// =============================================================================
+
+/// コア層にリクエストを送信し、失敗した場合はバックオフしてリトライする
+///
+/// spin_sdk の `Request` は複製できないため、試行ごとに `build` でリクエストを組み立てる。
+/// 再送するリクエストには RETRY_ATTEMPT_HEADER（再送の番号）を付与する（with_retry_attempt）。
+/// レスポンスの型は呼び出し元が選ぶ（`Response`: ボディを読み込む、`IncomingResponse`: 読み込まない）。
+///
+/// # 引数
+/// * `build` - 送信するリクエストを組み立てる関数
+/// * `max_retries` - リトライの最大回数（0 の場合は 1 回だけ送信する）
+/// * `backoff_ms` - 初回の待機時間（ミリ秒）
+/// * `request_id` - ログ用のリクエスト ID
+///
+/// # 戻り値
+/// * `Ok(O)` - コア層のレスポンス（ステータスによらない）
+/// * `Err(SendError)` - 全ての試行が失敗した場合の最後のエラー
+pub(crate) async fn send_with_retry<O>(
+    build: impl Fn() -> Request,
+    max_retries: u32,
+    backoff_ms: u64,
+    request_id: &str,
+) -> Result<O, SendError>
+where
+    O: TryFromIncomingResponse,
+    O::Error: Into<Box<dyn std::error::Error + Send + Sync>> + 'static,
+{
+    let mut attempt = 0;
+    loop {
+        match spin_sdk::http::send::<_, O>(with_retry_attempt(build(), attempt)).await {
+            Err(e) if attempt < max_retries && is_transient(&e) => {
+                let delay = backoff_delay(backoff_ms, attempt, Uuid::new_v4().as_u64_pair().0);
+                logging::info(&format!(
+                    "Proxy attempt {}/{} failed (request_id={}): {}; retrying in {}ms",
+                    attempt + 1,
+                    max_retries + 1,
+                    request_id,
+                    e,
+                    delay.as_millis()
+                ));
+                // 待機中もタイムアウト（timeout モジュール）の期限を判定できるよう非同期に待つ
+                crate::timeout::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// 再送するリクエストに RETRY_ATTEMPT_HEADER を付与する
+///
+/// # 引数
+/// * `request` - 送信するリクエスト
+/// * `attempt` - 再送の番号（0 は初回の送信で、ヘッダーを付与しない）
+fn with_retry_attempt(mut request: Request, attempt: u32) -> Request {
+    if attempt > 0 {
+        // set_header はヘッダー名を小文字に変換しないため、builder の header() と同じ小文字で設定する
+        request.set_header(
+            RETRY_ATTEMPT_HEADER.to_ascii_lowercase(),
+            attempt.to_string(),
+        );
+    }
+    request
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_retryable() {
+        // 冪等なメソッドはキーがなくてもリトライする
+        for method in [Method::Get, Method::Head, Method::Put, Method::Delete] {
+            assert!(is_retryable(&method, None), "{}", method);
+        }
+        assert!(is_retryable(&Method::Delete, Some("key-1")));
+        assert!(is_retryable(&Method::Post, Some("key-1")));
+        assert!(is_retryable(&Method::Patch, Some("key-1")));
+
+        // キーのない POST・PATCH はリトライしない
+        assert!(!is_retryable(&Method::Post, None));
+        assert!(!is_retryable(&Method::Patch, None));
+        assert!(!is_retryable(&Method::Post, Some("  ")));
+    }
+
+    #[test]
+    fn test_with_retry_attempt() {
+        let build = || {
+            Request::builder()
+                .method(Method::Post)
+                .uri("http://localhost:3001/api/todos")
+                .header("Idempotency-Key", "key-1")
+                .build()
+        };
+
+        // 初回の送信には付与しない
+        assert!(with_retry_attempt(build(), 0)
+            .header(RETRY_ATTEMPT_HEADER)
+            .is_none());
+
+        for attempt in [1, 2] {
+            let request = with_retry_attempt(build(), attempt);
+            assert_eq!(
+                request
+                    .header(RETRY_ATTEMPT_HEADER)
+                    .and_then(|v| v.as_str()),
+                Some(attempt.to_string().as_str())
+            );
+            assert_eq!(
+                request.header("idempotency-key").and_then(|v| v.as_str()),
+                Some("key-1")
+            );
+        }
+    }
+
+    #[test]
+    fn test_backoff_delay_grows_exponentially_with_jitter() {
+        // ジッターは 0 〜 半分の範囲
+        assert_eq!(backoff_delay(100, 0, 0), Duration::from_millis(50));
+        assert_eq!(backoff_delay(100, 0, 50), Duration::from_millis(100));
+        assert_eq!(backoff_delay(100, 1, 0), Duration::from_millis(100));
+        assert_eq!(backoff_delay(100, 2, 0), Duration::from_millis(200));
+
+        for random in [0, 1, 12345, u64::MAX] {
+            let delay = backoff_delay(100, 3, random);
+            assert!(delay >= Duration::from_millis(400) && delay <= Duration::from_millis(800));
+        }
+    }
+
+    #[test]
+    fn test_backoff_delay_is_capped() {
+        assert_eq!(
+            backoff_delay(100, 10, 0),
+            Duration::from_millis(MAX_BACKOFF_MS / 2)
+        );
+        assert_eq!(
+            backoff_delay(100, 10, MAX_BACKOFF_MS / 2),
+            Duration::from_millis(MAX_BACKOFF_MS)
+        );
+        for attempt in [5, 64, u32::MAX] {
+            assert!(
+                backoff_delay(u64::MAX, attempt, u64::MAX) <= Duration::from_millis(MAX_BACKOFF_MS)
+            );
+        }
+        assert_eq!(backoff_delay(0, 3, 7), Duration::ZERO);
+    }
+}
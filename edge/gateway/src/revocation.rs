@@ -0,0 +1,261 @@
+//! # トークンの失効の書き込み（ログアウト・パスワードの変更）
+//!
+//! コア層は JWT を発行するだけで、発行済みのトークンを失効させる手段を持ちません。
+//! ログアウト・パスワードの変更のハンドラがレスポンスに `X-Revoke-Tokens: all` を付けると、
+//! gateway はリクエストのユーザーの失効時刻を key-value ストア（`default`）の失効リストに書き込みます。
+//! auth コンポーネントは検証のたびに失効リストと照合し、失効時刻より前に発行したトークンを拒否します。
+//! 同様に `X-Token-Version: <整数>` を付けると、ユーザーのトークンのバージョンを書き込み、
+//! auth コンポーネントは ver クレーム（ない場合は 0）がそれより小さいトークンを拒否します。
+//!
+//! | 項目 | 内容 |
+//! |------|------|
+//! | ヘッダー | `X-Revoke-Tokens: all` / `X-Token-Version: <整数>`（認証済みのリクエストへのコア層のレスポンスのみ、クライアントには返さない） |
+//! | キー | `auth:revocation:user:<ユーザー ID>:revoked_before` / `auth:revocation:user:<ユーザー ID>:token_version`（auth の revocation モジュールと同じ） |
+//! | 値 | 現在時刻（Unix タイムスタンプ秒） / バージョン（今の値より小さい場合は書き込まない） |
+//!
+//! 失効させるのはリクエストのユーザー（X-User-Id）のトークンのみで、コア層が他のユーザーを指定することはできません。
+//! gateway の検証結果のキャッシュにあるトークンは、キャッシュの期限（auth_cache_ttl_secs、既定 30 秒）までは
+//! auth コンポーネントを呼び出さずに受け付けます。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+// コア層のレスポンス
+use spin_sdk::http::Response;
+
+use crate::auth_cache::{KvStore, Store};
+use crate::logging;
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// コア層がトークンの失効を指示するレスポンスヘッダー名
+pub(crate) const HEADER: &str = "X-Revoke-Tokens";
+
+/// コア層がユーザーのトークンのバージョンを通知するレスポンスヘッダー名
+pub(crate) const VERSION_HEADER: &str = "X-Token-Version";
+
+/// ユーザーの発行済みのトークンをすべて失効させる値
+const REVOKE_ALL: &str = "all";
+
+/// 失効リストのキーのプレフィックス
+///
+/// auth コンポーネントの revocation モジュールが読み込むキーと同じ（変更する場合は両方を変更すること）。
+const KEY_PREFIX: &str = "auth:revocation";
+
+// =============================================================================
+// 失効の書き込み
+// =============================================================================
+
+/// コア層のレスポンスに X-Revoke-Tokens / X-Token-Version がある場合、ユーザーのトークンを失効させる
+///
+/// ヘッダーがない場合・コア層が 2xx 以外を返した場合は何もしない。値が不正・ストアに書き込めない場合は
+/// 警告のみ（コア層のレスポンスはそのまま返す）。
+///
+/// # 引数
+/// * `response` - コア層のレスポンス
+/// * `user_id` - 認証済みユーザー ID
+/// * `now` - 現在時刻（Unix タイムスタンプ秒）
+pub(crate) fn apply(response: &Response, user_id: &str, now: u64) {
+    if !(200..300).contains(response.status()) {
+        return;
+    }
+    let header = |name: &str| {
+        response
+            .header(&name.to_ascii_lowercase())
+            .and_then(|value| value.as_str())
+    };
+
+    if let Some(value) = header(HEADER) {
+        match KvStore::open().and_then(|store| revoke(&store, value, user_id, now)) {
+            Ok(()) => logging::info(&format!(
+                "Revoked tokens: user_id={} revoked_before={}",
+                user_id, now
+            )),
+            Err(e) => logging::warn(&format!(
+                "Failed to revoke tokens for user_id={}: {}",
+                user_id, e
+            )),
+        }
+    }
+
+    if let Some(value) = header(VERSION_HEADER) {
+        match KvStore::open().and_then(|store| raise_version(&store, value, user_id)) {
+            Ok(version) => logging::info(&format!(
+                "Token version: user_id={} version={}",
+                user_id, version
+            )),
+            Err(e) => logging::warn(&format!(
+                "Failed to set token version for user_id={}: {}",
+                user_id, e
+            )),
+        }
+    }
+}
+
+/// ユーザーの失効時刻を書き込む
+///
+/// # 引数
+/// * `store` - 失効リストの保存先
+/// * `value` - X-Revoke-Tokens の値（all のみ、大文字小文字を区別しない）
+/// * `user_id` - 認証済みユーザー ID
+/// * `now` - 現在時刻（この時刻より前に発行したトークンを失効させる）
+fn revoke(store: &impl Store, value: &str, user_id: &str, now: u64) -> Result<(), String> {
+    if !value.trim().eq_ignore_ascii_case(REVOKE_ALL) {
+        return Err(format!(
+            "Invalid {} {:?}: must be {}",
+            HEADER, value, REVOKE_ALL
+        ));
+    }
+    store.set(&user_key(user_id), now.to_string().as_bytes())
+}
+
+/// ユーザーのトークンのバージョンを書き込む
+///
+/// 今のバージョンより小さい値では書き込まない（順序が入れ替わったレスポンスで古いトークンを有効に戻さない）。
+/// 今の値が不正な場合は上書きする。
+///
+/// # 引数
+/// * `store` - 失効リストの保存先
+/// * `value` - X-Token-Version の値（0 以上の整数）
+/// * `user_id` - 認証済みユーザー ID
+///
+/// # 戻り値
+/// * `Ok(u64)` - 書き込んだ後のユーザーのバージョン
+/// * `Err(String)` - 値が不正、またはストアを読み書きできない場合
+fn raise_version(store: &impl Store, value: &str, user_id: &str) -> Result<u64, String> {
+    let version = value.trim().parse::<u64>().map_err(|_| {
+        format!(
+            "Invalid {} {:?}: must be a non-negative integer",
+            VERSION_HEADER, value
+        )
+    })?;
+    let key = version_key(user_id);
+    let current = store.get(&key)?.and_then(|value| {
+        std::str::from_utf8(&value)
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+    });
+    if let Some(current) = current.filter(|&current| current >= version) {
+        return Ok(current);
+    }
+    store.set(&key, version.to_string().as_bytes())?;
+    Ok(version)
+}
+
+/// ユーザー単位の失効時刻のキー（`auth:revocation:user:<ユーザー ID>:revoked_before`）
+fn user_key(user_id: &str) -> String {
+    format!("{}:user:{}:revoked_before", KEY_PREFIX, user_id)
+}
+
+/// ユーザーのトークンのバージョンのキー（`auth:revocation:user:<ユーザー ID>:token_version`）
+fn version_key(user_id: &str) -> String {
+    format!("{}:user:{}:token_version", KEY_PREFIX, user_id)
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    /// メモリ上の保存先
+    #[derive(Default)]
+    struct MemoryStore {
+        values: RefCell<HashMap<String, Vec<u8>>>,
+    }
+
+    impl Store for MemoryStore {
+        fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+            Ok(self.values.borrow().get(key).cloned())
+        }
+
+        fn set(&self, key: &str, value: &[u8]) -> Result<(), String> {
+            self.values
+                .borrow_mut()
+                .insert(key.to_string(), value.to_vec());
+            Ok(())
+        }
+
+        fn delete(&self, key: &str) -> Result<(), String> {
+            self.values.borrow_mut().remove(key);
+            Ok(())
+        }
+    }
+
+    const NOW: u64 = 1_700_000_000;
+
+    #[test]
+    fn test_revoke_all_writes_revoked_before() {
+        let store = MemoryStore::default();
+        assert_eq!(revoke(&store, " ALL ", "user-1", NOW), Ok(()));
+        assert_eq!(
+            store
+                .get("auth:revocation:user:user-1:revoked_before")
+                .unwrap(),
+            Some(b"1700000000".to_vec())
+        );
+
+        // 同じユーザーの失効時刻は上書きする
+        assert_eq!(revoke(&store, "all", "user-1", NOW + 60), Ok(()));
+        assert_eq!(
+            store
+                .get("auth:revocation:user:user-1:revoked_before")
+                .unwrap(),
+            Some(b"1700000060".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_unknown_value_is_rejected() {
+        let store = MemoryStore::default();
+        for value in ["", "token", "user-2"] {
+            let error = revoke(&store, value, "user-1", NOW).unwrap_err();
+            assert!(error.starts_with("Invalid X-Revoke-Tokens"), "{}", error);
+        }
+        assert!(store.values.borrow().is_empty());
+    }
+    #[test]
+    fn test_token_version_is_only_raised() {
+        let store = MemoryStore::default();
+        let version = || {
+            store
+                .get("auth:revocation:user:user-1:token_version")
+                .unwrap()
+        };
+
+        assert_eq!(raise_version(&store, " 2 ", "user-1"), Ok(2));
+        assert_eq!(version(), Some(b"2".to_vec()));
+
+        // 小さい・同じ値では下げない
+        assert_eq!(raise_version(&store, "1", "user-1"), Ok(2));
+        assert_eq!(raise_version(&store, "2", "user-1"), Ok(2));
+        assert_eq!(version(), Some(b"2".to_vec()));
+
+        assert_eq!(raise_version(&store, "3", "user-1"), Ok(3));
+        assert_eq!(version(), Some(b"3".to_vec()));
+
+        // 今の値が不正な場合は上書きする
+        store
+            .set("auth:revocation:user:user-1:token_version", b"x")
+            .unwrap();
+        assert_eq!(raise_version(&store, "1", "user-1"), Ok(1));
+        assert_eq!(version(), Some(b"1".to_vec()));
+    }
+
+    #[test]
+    fn test_invalid_token_version_is_rejected() {
+        let store = MemoryStore::default();
+        for value in ["", "-1", "1.5", "all"] {
+            let error = raise_version(&store, value, "user-1").unwrap_err();
+            assert!(error.starts_with("Invalid X-Token-Version"), "{}", error);
+        }
+        assert!(store.values.borrow().is_empty());
+    }
+}
@@ -0,0 +1,477 @@
+//! # ルートのルール（パスプレフィックスごとのメソッド・認証・ボディの上限・タイムアウト・転送先）
+//!
+//! /api/* へのリクエストは、順序付きのルールの一覧のうち一致したものに従って処理します
+//! （route_request: ルールの照合 → ルールに従った認証 → ルールに従った転送）。
+//! ルールは埋め込みの既定値（`gateway/route_rules.json`）と、Spin 変数 `route_rules`（JSON）から読み込み、
+//! 変数のルールを既定値より先に照合します。
+//!
+//! upstream_routes・max_body_bytes_by_path と同じく最も長く一致するプレフィックスを優先し、
+//! 同じプレフィックスのルールは記述した順に、メソッドを許可する最初のものを使います
+//! （既定の `/api` を変数で上書きする場合は、同じプレフィックスのルールを書く）。
+//!
+//! ```json
+//! [{"prefix": "/api/files", "methods": ["GET", "POST", "DELETE"], "auth": "authenticated",
+//!   "max_body_bytes": 104923136, "timeout_ms": 120000, "upstream": "http://files:3002"},
+//!  {"prefix": "/api/status", "methods": ["GET"], "auth": "public"}]
+//! ```
+//!
+//! | フィールド | 省略した場合 |
+//! |------------|--------------|
+//! | `prefix`（必須） | -（`/` で始まるパスプレフィックス、セグメント単位で一致、末尾の `/` は取り除く） |
+//! | `methods` | 全てのメソッド（GET を許可した場合は HEAD も許可する） |
+//! | `auth` | `public_paths`・`auth_policies` で決める（`public` / `authenticated` で上書き） |
+//! | `max_body_bytes` | `max_body_bytes_by_path`・`max_body_bytes`（0 で無効） |
+//! | `timeout_ms` | `proxy_timeout_ms`（0 で無効） |
+//! | `upstream` | `upstream_routes`・`core_url`（カナリアリリースの振り分けを含む）。指定した場合は振り分けない |
+//!
+//! | 照合の結果 | レスポンス |
+//! |------------|------------|
+//! | 最も長く一致するプレフィックスのルールがメソッドを許可する | そのうち最初のルールに従って処理する |
+//! | 最も長く一致するプレフィックスのルールがメソッドを許可しない | 405 Method Not Allowed（Allow にそれらのルールのメソッド、短いプレフィックスのルールには戻らない） |
+//! | どのルールにも一致しない | 404 Not Found（public_paths に含まれるパスでも転送しない） |
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+use std::time::Duration;
+
+// Spin 変数の JSON の解析
+use serde::Deserialize;
+
+use crate::config::{path_has_prefix, validate_origin, AuthPolicy};
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// 埋め込みのルールの既定値（Spin 変数 route_rules のルールの後に照合する）
+const DEFAULT_ROUTE_RULES: &str = include_str!("../route_rules.json");
+
+// =============================================================================
+// 型定義
+// =============================================================================
+
+/// パスプレフィックスごとのルール（省略した項目は既存の Spin 変数の値を使う）
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RouteRule {
+    /// パスプレフィックス（例: "/api/files"、セグメント単位で一致）
+    pub(crate) prefix: String,
+
+    /// 許可するメソッド（大文字、空の場合は全てのメソッド）
+    pub(crate) methods: Vec<String>,
+
+    /// 認証ポリシー（None の場合は public_paths・auth_policies で決める）
+    pub(crate) auth: Option<AuthPolicy>,
+
+    /// リクエストボディの上限（バイト、0 の場合は制限しない、None の場合は body_limit）
+    pub(crate) max_body_bytes: Option<u64>,
+
+    /// コア層への転送のタイムアウト（ZERO の場合は期限なし、None の場合は proxy_timeout）
+    pub(crate) timeout: Option<Duration>,
+
+    /// 転送先の URL（スキームとホストのみ、None の場合は upstream_routes・core_url）
+    pub(crate) upstream: Option<String>,
+}
+
+/// リクエストに適用するルート（ルールの省略した項目を設定の値で補ったもの、GatewayConfig::route）
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Route<'a> {
+    /// 一致したルールのパスプレフィックス（ログ用）
+    pub(crate) prefix: &'a str,
+
+    /// 認証ポリシー
+    pub(crate) auth: AuthPolicy,
+
+    /// リクエストボディの上限（バイト、0 の場合は制限しない）
+    pub(crate) max_body_bytes: u64,
+
+    /// コア層への転送のタイムアウト（ZERO の場合は期限なし）
+    pub(crate) timeout: Duration,
+
+    /// ルールで指定した転送先（None の場合は upstream_routes・core_url）
+    pub(crate) upstream: Option<&'a str>,
+}
+
+/// どのルールにも従えない理由
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RouteMiss {
+    /// パスに一致するルールがない（404）
+    NotFound,
+
+    /// パスに一致するルールはあるが、メソッドを許可していない（405、Allow に返すメソッド）
+    MethodNotAllowed(Vec<String>),
+}
+
+/// Spin 変数 route_rules の JSON の 1 件
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RouteRuleSpec {
+    prefix: String,
+    #[serde(default)]
+    methods: Vec<String>,
+    auth: Option<AuthSpec>,
+    max_body_bytes: Option<u64>,
+    timeout_ms: Option<u64>,
+    upstream: Option<String>,
+}
+
+/// JSON の auth の値
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum AuthSpec {
+    Public,
+    Authenticated,
+}
+
+impl RouteRule {
+    /// メソッドを許可するかどうか（GET を許可した場合は HEAD も許可する）
+    fn allows(&self, method: &str) -> bool {
+        self.methods.is_empty()
+            || self.methods.iter().any(|allowed| allowed == method)
+            || (method == "HEAD" && self.methods.iter().any(|allowed| allowed == "GET"))
+    }
+
+    /// 405 の Allow に返すメソッド（GET を許可した場合は HEAD を加える）
+    fn allowed_methods(&self) -> impl Iterator<Item = &str> {
+        let head = (self.methods.iter().any(|m| m == "GET")
+            && !self.methods.iter().any(|m| m == "HEAD"))
+        .then_some("HEAD");
+        self.methods.iter().map(String::as_str).chain(head)
+    }
+}
+
+// =============================================================================
+// 解析
+// =============================================================================
+
+/// ルールの JSON（オブジェクトの配列）を解析する
+///
+/// # 引数
+/// * `json` - 例: `[{"prefix": "/api/files", "methods": ["GET"]}]`
+///
+/// # 戻り値
+/// * `Ok(Vec<RouteRule>)` - 記述した順のルール
+/// * `Err(String)` - JSON として不正・不明なフィールドがある・プレフィックスが `/` で始まらない、
+///   `*`・空白・クエリ・フラグメントを含む・メソッドが英字でない・転送先がスキームとホストの形式でない場合
+pub(crate) fn parse(json: &str) -> Result<Vec<RouteRule>, String> {
+    let specs: Vec<RouteRuleSpec> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+    specs
+        .into_iter()
+        .enumerate()
+        .map(|(index, spec)| {
+            let invalid = |reason: &str| format!("rule {} ({:?}): {}", index, spec.prefix, reason);
+
+            if !spec.prefix.starts_with('/') {
+                return Err(invalid("prefix must start with /"));
+            }
+            if spec.prefix.contains(['*', ' ', '?', '#']) {
+                return Err(invalid(
+                    "prefix must not contain *, spaces, a query or fragment",
+                ));
+            }
+            let methods = spec
+                .methods
+                .iter()
+                .map(|method| {
+                    if !method.is_empty() && method.bytes().all(|b| b.is_ascii_alphabetic()) {
+                        Ok(method.to_ascii_uppercase())
+                    } else {
+                        Err(invalid(&format!("method {:?} must be letters", method)))
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let upstream = spec
+                .upstream
+                .as_deref()
+                .map(|url| {
+                    validate_origin(url).map_err(|reason| invalid(&format!("upstream {}", reason)))
+                })
+                .transpose()?;
+
+            Ok(RouteRule {
+                prefix: match spec.prefix.trim_end_matches('/') {
+                    "" => "/".to_string(),
+                    prefix => prefix.to_string(),
+                },
+                methods,
+                auth: spec.auth.map(|auth| match auth {
+                    AuthSpec::Public => AuthPolicy::Public,
+                    AuthSpec::Authenticated => AuthPolicy::Authenticated,
+                }),
+                max_body_bytes: spec.max_body_bytes,
+                timeout: spec.timeout_ms.map(Duration::from_millis),
+                upstream,
+            })
+        })
+        .collect()
+}
+
+/// 埋め込みの既定のルール（gateway/route_rules.json）
+pub(crate) fn default_rules() -> Vec<RouteRule> {
+    parse(DEFAULT_ROUTE_RULES).expect("embedded route_rules.json must be valid")
+}
+
+// =============================================================================
+// 照合
+// =============================================================================
+
+/// リクエストに一致するルールを返す
+///
+/// 最も長く一致するパスプレフィックスのルールを順に調べ、メソッドを許可する最初のルールを返す。
+/// 許可しない場合は短いプレフィックスのルール（既定の `/api` など）には戻らずに 405 とする
+/// （メソッドを制限したルールが全てのメソッドを許可する既定のルールで迂回されないように）。
+///
+/// # 引数
+/// * `rules` - 照合する順のルール（Spin 変数のルール、既定のルールの順）
+/// * `method` - HTTP メソッド（大文字、例: "GET"）
+/// * `path` - 正規化したリクエストパス
+///
+/// # 戻り値
+/// * `Ok(&RouteRule)` - 一致したルール
+/// * `Err(RouteMiss)` - NotFound（パスが一致しない）、MethodNotAllowed（最も長く一致したルールのメソッドの和集合）
+pub(crate) fn find<'a>(
+    rules: &'a [RouteRule],
+    method: &str,
+    path: &str,
+) -> Result<&'a RouteRule, RouteMiss> {
+    let longest = rules
+        .iter()
+        .filter(|rule| path_has_prefix(path, &rule.prefix))
+        .map(|rule| rule.prefix.len())
+        .max()
+        .ok_or(RouteMiss::NotFound)?;
+
+    // パスに一致するプレフィックスの長さが同じ場合、プレフィックスも同じ
+    let mut allowed: Vec<String> = Vec::new();
+    for rule in rules
+        .iter()
+        .filter(|rule| rule.prefix.len() == longest && path_has_prefix(path, &rule.prefix))
+    {
+        if rule.allows(method) {
+            return Ok(rule);
+        }
+        for method in rule.allowed_methods() {
+            if !allowed.iter().any(|m| m == method) {
+                allowed.push(method.to_string());
+            }
+        }
+    }
+
+    Err(RouteMiss::MethodNotAllowed(allowed))
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 照合のテストに使うルール（Spin 変数のルールの後に既定のルール）
+    fn rules() -> Vec<RouteRule> {
+        let mut rules = parse(
+            r#"[
+                {"prefix": "/api/files", "methods": ["GET", "POST", "DELETE"], "max_body_bytes": 104923136,
+                 "timeout_ms": 120000, "upstream": "http://files:3002"},
+                {"prefix": "/api/status", "methods": ["GET"], "auth": "public"},
+                {"prefix": "/api/admin", "methods": ["get"], "auth": "authenticated"},
+                {"prefix": "/api/admin", "methods": ["POST"], "timeout_ms": 0},
+                {"prefix": "/api/reports/", "methods": ["GET", "HEAD"]}
+            ]"#,
+        )
+        .unwrap();
+        rules.extend(default_rules());
+        rules
+    }
+
+    #[test]
+    fn test_find() {
+        let rules = rules();
+        let not_allowed = |methods: &[&str]| {
+            Err::<&str, _>(RouteMiss::MethodNotAllowed(
+                methods.iter().map(|m| m.to_string()).collect(),
+            ))
+        };
+
+        // (メソッド, パス, 期待する結果: 一致したルールのプレフィックス・RouteMiss)
+        let cases: &[(&str, &str, Result<&str, RouteMiss>)] = &[
+            // プレフィックスはセグメント単位で一致する
+            ("GET", "/api/files", Ok("/api/files")),
+            ("POST", "/api/files/upload", Ok("/api/files")),
+            ("GET", "/api/filesystem", Ok("/api")),
+            // GET を許可した場合は HEAD も許可する
+            ("HEAD", "/api/files/1/download", Ok("/api/files")),
+            // 許可していないメソッドは 405（Allow は一致したルールのメソッド、GET があれば HEAD を加える）
+            (
+                "PATCH",
+                "/api/files/1",
+                not_allowed(&["GET", "POST", "DELETE", "HEAD"]),
+            ),
+            ("POST", "/api/status", not_allowed(&["GET", "HEAD"])),
+            // 短いプレフィックスのルール（既定の /api）には戻らない
+            ("PUT", "/api/status/1", not_allowed(&["GET", "HEAD"])),
+            // 同じプレフィックスのルールは順に調べる（メソッドは大文字にそろえる）
+            ("GET", "/api/admin/users", Ok("/api/admin")),
+            ("POST", "/api/admin/users", Ok("/api/admin")),
+            (
+                "DELETE",
+                "/api/admin/users",
+                not_allowed(&["GET", "HEAD", "POST"]),
+            ),
+            // HEAD を明示した場合は重複させない、末尾の / は取り除く
+            ("PUT", "/api/reports/1", not_allowed(&["GET", "HEAD"])),
+            // 既定のルール（/api）は全てのメソッドを許可する
+            ("PATCH", "/api/todos/1", Ok("/api")),
+            ("GET", "/api", Ok("/api")),
+            // どのルールにも一致しない
+            ("GET", "/favicon.ico", Err(RouteMiss::NotFound)),
+            ("GET", "/apis", Err(RouteMiss::NotFound)),
+            ("GET", "/", Err(RouteMiss::NotFound)),
+        ];
+
+        for (method, path, expected) in cases {
+            let actual = find(&rules, method, path).map(|rule| rule.prefix.as_str());
+            assert_eq!(&actual, expected, "{} {}", method, path);
+        }
+    }
+
+    #[test]
+    fn test_find_returns_first_matching_rule() {
+        let rules = rules();
+
+        let files = find(&rules, "GET", "/api/files/1").unwrap();
+        assert_eq!(files.max_body_bytes, Some(104923136));
+        assert_eq!(files.timeout, Some(Duration::from_secs(120)));
+        assert_eq!(files.upstream.as_deref(), Some("http://files:3002"));
+        assert_eq!(files.auth, None);
+
+        assert_eq!(
+            find(&rules, "GET", "/api/status").unwrap().auth,
+            Some(AuthPolicy::Public)
+        );
+        assert_eq!(
+            find(&rules, "GET", "/api/admin").unwrap().auth,
+            Some(AuthPolicy::Authenticated)
+        );
+        let admin_post = find(&rules, "POST", "/api/admin").unwrap();
+        assert_eq!(
+            (admin_post.auth, admin_post.timeout),
+            (None, Some(Duration::ZERO))
+        );
+
+        // 省略した項目は None（設定の値を使う）
+        let default = find(&rules, "GET", "/api/todos").unwrap();
+        assert_eq!(
+            default,
+            &RouteRule {
+                prefix: "/api".to_string(),
+                methods: Vec::new(),
+                auth: None,
+                max_body_bytes: None,
+                timeout: None,
+                upstream: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_find_overrides_default_rule_with_same_prefix() {
+        let mut rules = parse(r#"[{"prefix": "/api/", "timeout_ms": 5000}]"#).unwrap();
+        rules.extend(default_rules());
+
+        let rule = find(&rules, "GET", "/api/todos").unwrap();
+        assert_eq!(rule.timeout, Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_find_without_rules() {
+        assert_eq!(find(&[], "GET", "/api/todos"), Err(RouteMiss::NotFound));
+
+        let root = parse(r#"[{"prefix": "/"}]"#).unwrap();
+        assert_eq!(find(&root, "GET", "/anything").unwrap().prefix, "/");
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_rules() {
+        // (JSON, エラーメッセージに含まれる内容)
+        let cases = [
+            ("", "EOF"),
+            (r#"{"prefix": "/api"}"#, "invalid type"),
+            (r#"[{"methods": ["GET"]}]"#, "missing field `prefix`"),
+            (
+                r#"[{"prefix": "/api", "timeout": 100}]"#,
+                "unknown field `timeout`",
+            ),
+            (
+                r#"[{"prefix": "api"}]"#,
+                "rule 0 (\"api\"): prefix must start with /",
+            ),
+            (r#"[{"prefix": "/api"}, {"prefix": "/api/*"}]"#, "rule 1"),
+            (r#"[{"prefix": "/api?x=1"}]"#, "must not contain"),
+            (
+                r#"[{"prefix": "/api", "methods": ["GET", ""]}]"#,
+                "method \"\" must be letters",
+            ),
+            (
+                r#"[{"prefix": "/api", "methods": ["*"]}]"#,
+                "method \"*\" must be letters",
+            ),
+            (
+                r#"[{"prefix": "/api", "auth": "anonymous"}]"#,
+                "unknown variant `anonymous`",
+            ),
+            (
+                r#"[{"prefix": "/api", "max_body_bytes": -1}]"#,
+                "invalid value",
+            ),
+            (
+                r#"[{"prefix": "/api", "timeout_ms": "30s"}]"#,
+                "invalid type",
+            ),
+            (
+                r#"[{"prefix": "/api", "upstream": "files:3002"}]"#,
+                "upstream must start with http:// or https://",
+            ),
+            (
+                r#"[{"prefix": "/api", "upstream": "http://files:3002/api"}]"#,
+                "upstream must not contain a path",
+            ),
+        ];
+
+        for (json, expected) in cases {
+            let error = parse(json).unwrap_err();
+            assert!(error.contains(expected), "{}: {}", json, error);
+        }
+    }
+
+    #[test]
+    fn test_parse_normalizes_rules() {
+        let rules = parse(
+            r#"[{"prefix": "/api/files/", "methods": ["get", "Post"], "upstream": "https://files.example.com/"},
+                {"prefix": "/"}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(rules[0].prefix, "/api/files");
+        assert_eq!(rules[0].methods, ["GET", "POST"]);
+        assert_eq!(
+            rules[0].upstream.as_deref(),
+            Some("https://files.example.com")
+        );
+        assert_eq!(rules[1].prefix, "/");
+        assert_eq!(parse("[]").unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn test_default_rules_cover_api() {
+        let rules = default_rules();
+        assert_eq!(
+            find(&rules, "DELETE", "/api/todos/1").unwrap().prefix,
+            "/api"
+        );
+        assert_eq!(find(&rules, "GET", "/health"), Err(RouteMiss::NotFound));
+    }
+}
@@ -0,0 +1,469 @@
+//! # コア層のレスポンスのストリーミング
+//!
+//! コア層のレスポンスのボディを gateway のメモリに読み込まず、
+//! 受信したチャンク（incoming-body）をそのままクライアントへのレスポンス（outgoing-body）に書き込みます。
+//! ファイルのダウンロード（50 MB など）でも gateway（Wasm）のメモリ使用量が増えません。
+//!
+//! | 条件 | 動作 |
+//! |------|------|
+//! | ファイルのダウンロード（`/api/files/{id}/download`） | 常にストリーミング |
+//! | Server-Sent Events（コア層の `Content-Type: text/event-stream`） | 常にストリーミング（イベントを受信するたびにクライアントに書き込む） |
+//! | コア層の `Content-Length` が Spin 変数 `stream_threshold_bytes`（既定 1 MiB）を超える | ストリーミング |
+//! | それ以外（`Content-Length` がない場合を含む） | ボディを読み込んでから返す（従来どおり） |
+//!
+//! ストリーミングする場合もコア層の `Content-Length`・`Content-Type` はそのまま返します。
+//! ボディの転送中にコア層との接続が切れた場合は、outgoing-body を完了（finish）せずに破棄し、
+//! クライアントへのレスポンスを途中で打ち切ります（不完全なボディを正常なレスポンスとして返さない）。
+//! proxy_timeout はレスポンスのヘッダーを受信するまでに適用し、ストリーミング中のボディには適用しません。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+use std::task::{Context, Poll};
+
+// チャンクの受信（Stream::next）と書き込みの待機（poll_fn）
+use futures::future;
+use futures::StreamExt;
+
+// Spin のエグゼキューター（Pollable が準備できたら Future を再度ポーリングする）
+use spin_executor::CancelOnDropToken;
+
+use spin_sdk::http::{
+    IncomingResponse, OutgoingBody, OutgoingResponse, Response, ResponseOutparam,
+};
+
+// WASI のストリーム（outgoing-body への書き込み）
+use wasi::io::streams::{OutputStream, StreamError};
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// ファイル操作のパスプレフィックス（`/api/files/{id}/download` の判定）
+const FILES_PATH_PREFIX: &str = "/api/files/";
+
+/// ファイルのダウンロードのパスの末尾のセグメント
+const DOWNLOAD_SEGMENT: &str = "download";
+
+/// Server-Sent Events のメディアタイプ（ボディが終わらないため、読み込まずに常にストリーミングする）
+const EVENT_STREAM: &str = "text/event-stream";
+
+// =============================================================================
+// レスポンス
+// =============================================================================
+
+/// クライアントに返すレスポンス
+pub(crate) enum ProxyResponse {
+    /// ボディを読み込んだレスポンス（ゲートウェイのエラーレスポンスを含む）
+    Buffered(Response),
+
+    /// ボディをコア層からストリーミングするレスポンス
+    Streaming(StreamingResponse),
+}
+
+/// ボディをコア層からストリーミングするレスポンス
+pub(crate) struct StreamingResponse {
+    /// ステータスとヘッダー（ボディは空）
+    head: Response,
+
+    /// コア層のレスポンス（ボディを読み込まずに保持する、破棄すると転送が取り消される）
+    body: IncomingResponse,
+}
+
+impl From<Response> for ProxyResponse {
+    fn from(response: Response) -> Self {
+        Self::Buffered(response)
+    }
+}
+
+impl ProxyResponse {
+    /// ステータスとヘッダー（CORS ヘッダーの付与・ログの X-Request-Id 用）
+    ///
+    /// ストリーミングする場合もヘッダーは送信前に変更できる。
+    pub(crate) fn head_mut(&mut self) -> &mut Response {
+        match self {
+            Self::Buffered(response) => response,
+            Self::Streaming(streaming) => &mut streaming.head,
+        }
+    }
+
+    /// コア層のレスポンスのステータス・ヘッダーを差し替える（ボディはそのまま）
+    ///
+    /// # 引数
+    /// * `build` - コア層のステータス・ヘッダー（ボディは空）からクライアントに返すものを組み立てる関数
+    pub(crate) fn map_head(self, build: impl FnOnce(&Response) -> Response) -> Self {
+        match self {
+            Self::Buffered(mut response) => {
+                let body = std::mem::take(response.body_mut());
+                let mut head = build(&response);
+                *head.body_mut() = body;
+                Self::Buffered(head)
+            }
+            Self::Streaming(StreamingResponse { head, body }) => {
+                Self::Streaming(StreamingResponse {
+                    head: build(&head),
+                    body,
+                })
+            }
+        }
+    }
+
+    /// ボディの大きさ（バイト）
+    ///
+    /// ストリーミングする場合はコア層の Content-Length（ない場合は None、Server-Sent Events など）。
+    pub(crate) fn body_len(&self) -> Option<u64> {
+        match self {
+            Self::Buffered(response) => Some(response.body().len() as u64),
+            Self::Streaming(streaming) => content_length(&streaming.head),
+        }
+    }
+
+    /// HEAD リクエストへのレスポンスとしてボディを取り除く（ステータス・ヘッダーはそのまま）
+    ///
+    /// ストリーミングする場合はコア層のボディを受信せずに破棄する（転送が取り消される）。
+    /// コア層が Content-Length を返さずにボディを返した場合（GET で再送した場合など）は、
+    /// ボディの長さを Content-Length に設定する。
+    pub(crate) fn without_body(self) -> Self {
+        match self {
+            Self::Buffered(mut response) => {
+                let body = std::mem::take(response.body_mut());
+                if content_length(&response).is_none() && !body.is_empty() {
+                    // set_header はヘッダー名を小文字に変換しないため、content_length で参照できるよう小文字で設定する
+                    response.set_header("content-length", body.len().to_string());
+                }
+                Self::Buffered(response)
+            }
+            Self::Streaming(StreamingResponse { head, .. }) => Self::Buffered(head),
+        }
+    }
+
+    /// クライアントにレスポンスを送信する
+    ///
+    /// # 引数
+    /// * `response_out` - Spin のレスポンスの出力先
+    /// * `request_id` - ログ用のリクエスト ID（ストリーミングの中断時）
+    ///
+    /// # 戻り値
+    /// * `Ok(())` - 送信した
+    /// * `Err(String)` - 送信に失敗した、またはストリーミングを中断した
+    pub(crate) async fn send(
+        self,
+        response_out: ResponseOutparam,
+        request_id: &str,
+    ) -> Result<(), String> {
+        match self {
+            Self::Buffered(mut response) => {
+                let body = std::mem::take(response.body_mut());
+                let response = OutgoingResponse::try_from(response)
+                    .map_err(|e| format!("Could not convert response: {}", e))?;
+                response_out
+                    .set_with_body(response, body)
+                    .await
+                    .map_err(|e| format!("Could not send response: {}", e))
+            }
+            Self::Streaming(streaming) => streaming.send(response_out, request_id).await,
+        }
+    }
+}
+
+impl StreamingResponse {
+    /// ボディをチャンクごとにクライアントに書き込む
+    ///
+    /// コア層からの受信に失敗した場合は outgoing-body を完了せずに破棄し、
+    /// クライアントへのレスポンスを打ち切る。
+    async fn send(self, response_out: ResponseOutparam, request_id: &str) -> Result<(), String> {
+        let StreamingResponse { head, body } = self;
+
+        let response = OutgoingResponse::try_from(head)
+            .map_err(|e| format!("Could not convert response: {}", e))?;
+        let outgoing = response
+            .body()
+            .map_err(|()| "Response body was already taken".to_string())?;
+        response_out.set(response);
+
+        let output = outgoing
+            .write()
+            .map_err(|()| "Response body is not writable".to_string())?;
+        let mut chunks = body.take_body_stream();
+        let mut sent: u64 = 0;
+        while let Some(chunk) = chunks.next().await {
+            // 受信に失敗した場合は finish せずに破棄する（クライアントには不完全なレスポンスとして伝わる）
+            let chunk = chunk.map_err(|e| {
+                format!(
+                    "Upstream stream interrupted after {} bytes (request_id={}): {}",
+                    sent,
+                    request_id,
+                    e.to_debug_string()
+                )
+            })?;
+            write_all(&output, &chunk).await.map_err(|e| {
+                format!(
+                    "Client stream closed after {} bytes (request_id={}): {:?}",
+                    sent, request_id, e
+                )
+            })?;
+            sent += chunk.len() as u64;
+        }
+
+        // 書き込みを完了する（Content-Length と書き込んだバイト数が異なる場合はエラー）
+        drop(output);
+        OutgoingBody::finish(outgoing, None)
+            .map_err(|e| format!("Could not finish response body: {:?}", e))
+    }
+}
+
+/// チャンクを outgoing-body のストリームに書き込む
+///
+/// 書き込める量（check_write）が 0 の間はエグゼキューターに Pollable を登録して待つ
+/// （他の Future の処理は止めない）。
+async fn write_all(output: &OutputStream, mut bytes: &[u8]) -> Result<(), StreamError> {
+    while !bytes.is_empty() {
+        let permitted = output.check_write()?;
+        if permitted == 0 {
+            writable(output).await;
+            continue;
+        }
+        let count = usize::try_from(permitted)
+            .unwrap_or(usize::MAX)
+            .min(bytes.len());
+        output.write(&bytes[..count])?;
+        bytes = &bytes[count..];
+    }
+    output.flush()
+}
+
+/// ストリームに書き込めるようになるまで待つ
+async fn writable(output: &OutputStream) {
+    // エグゼキューターに登録した Pollable（破棄時に登録を取り消す）
+    let mut token: Option<CancelOnDropToken> = None;
+    future::poll_fn(|cx: &mut Context<'_>| {
+        let pollable = output.subscribe();
+        if pollable.ready() {
+            token = None;
+            return Poll::Ready(());
+        }
+        token = Some(spin_executor::push_waker_and_get_token(pollable, cx.waker().clone()).into());
+        Poll::Pending
+    })
+    .await
+}
+
+// =============================================================================
+// ストリーミングの判定
+// =============================================================================
+
+/// コア層のレスポンスを読み込む（ストリーミングする場合は読み込まない）
+///
+/// # 引数
+/// * `incoming` - コア層のレスポンス
+/// * `path` - リクエストパス
+/// * `threshold` - ストリーミングする Content-Length の閾値（バイト、0 の場合はダウンロードのみ）
+///
+/// # 戻り値
+/// * `Ok(ProxyResponse)` - 読み込んだ、またはストリーミングするレスポンス
+/// * `Err(streams::Error)` - ボディの受信に失敗した場合
+pub(crate) async fn read_response(
+    incoming: IncomingResponse,
+    path: &str,
+    threshold: u64,
+) -> Result<ProxyResponse, wasi::io::streams::Error> {
+    let head = Response::builder()
+        .status(incoming.status())
+        .headers(incoming.headers())
+        .build();
+
+    if is_event_stream(&head) || should_stream(path, content_length(&head), threshold) {
+        return Ok(ProxyResponse::Streaming(StreamingResponse {
+            head,
+            body: incoming,
+        }));
+    }
+
+    let body = incoming.into_body().await?;
+    let mut response = head;
+    *response.body_mut() = body;
+    Ok(ProxyResponse::Buffered(response))
+}
+
+/// レスポンスをストリーミングするかを判定する
+///
+/// # 引数
+/// * `path` - リクエストパス
+/// * `content_length` - コア層の Content-Length（ない場合は None）
+/// * `threshold` - 閾値（バイト、0 の場合はダウンロードのみストリーミングする）
+pub(crate) fn should_stream(path: &str, content_length: Option<u64>, threshold: u64) -> bool {
+    is_download_path(path)
+        || (threshold > 0 && content_length.is_some_and(|length| length > threshold))
+}
+
+/// レスポンスが Server-Sent Events（`Content-Type: text/event-stream`、パラメータは無視）かを判定する
+pub(crate) fn is_event_stream(response: &Response) -> bool {
+    response
+        .header("content-type")
+        .and_then(|value| value.as_str())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|media_type| media_type.trim().eq_ignore_ascii_case(EVENT_STREAM))
+}
+
+/// ファイルのダウンロード（`/api/files/{id}/download`）のパスかを判定する
+fn is_download_path(path: &str) -> bool {
+    path.strip_prefix(FILES_PATH_PREFIX)
+        .and_then(|rest| rest.split_once('/'))
+        .is_some_and(|(id, segment)| !id.is_empty() && segment == DOWNLOAD_SEGMENT)
+}
+
+/// レスポンスの Content-Length（ない・数値でない場合は None）
+pub(crate) fn content_length(response: &Response) -> Option<u64> {
+    response
+        .header("content-length")
+        .and_then(|value| value.as_str())
+        .and_then(|value| value.trim().parse().ok())
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_stream_downloads_and_large_responses() {
+        // ファイルのダウンロードは常にストリーミングする
+        assert!(should_stream("/api/files/42/download", None, 1024));
+        assert!(should_stream("/api/files/42/download", Some(10), 0));
+
+        // Content-Length が閾値を超える場合
+        assert!(should_stream("/api/todos/export", Some(1025), 1024));
+        assert!(!should_stream("/api/todos/export", Some(1024), 1024));
+
+        // Content-Length がない・閾値が 0 の場合は読み込む
+        assert!(!should_stream("/api/todos", None, 1024));
+        assert!(!should_stream("/api/todos", Some(u64::MAX), 0));
+    }
+
+    #[test]
+    fn test_is_download_path() {
+        assert!(is_download_path("/api/files/42/download"));
+        assert!(is_download_path(
+            "/api/files/0b5f1c2e-6a43-4b7e-9f61-3f4f2d1c9a10/download"
+        ));
+
+        for path in [
+            "/api/files/download",
+            "/api/files//download",
+            "/api/files/42",
+            "/api/files/42/download/extra",
+            "/api/files/42/downloads",
+            "/api/files/upload",
+            "/api/todos/42/download",
+        ] {
+            assert!(!is_download_path(path), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_is_event_stream() {
+        let response = |content_type: &str| {
+            Response::builder()
+                .status(200)
+                .header("Content-Type", content_type)
+                .build()
+        };
+        assert!(is_event_stream(&response("text/event-stream")));
+        assert!(is_event_stream(&response(
+            "Text/Event-Stream; charset=utf-8"
+        )));
+        assert!(!is_event_stream(&response("application/json")));
+        assert!(!is_event_stream(&response("text/event-streams")));
+        assert!(!is_event_stream(&Response::new(200, ())));
+    }
+
+    #[test]
+    fn test_content_length() {
+        let response = Response::builder()
+            .status(200)
+            .header("Content-Length", "52428800")
+            .build();
+        assert_eq!(content_length(&response), Some(52_428_800));
+
+        let response = Response::builder()
+            .status(200)
+            .header("content-length", "lots")
+            .build();
+        assert_eq!(content_length(&response), None);
+        assert_eq!(content_length(&Response::new(200, ())), None);
+    }
+
+    #[test]
+    fn test_map_head_keeps_buffered_body() {
+        let response = ProxyResponse::from(
+            Response::builder()
+                .status(201)
+                .header("content-type", "text/csv")
+                .body("a,b")
+                .build(),
+        );
+
+        let mapped = response.map_head(|core| {
+            Response::builder()
+                .status(*core.status())
+                .header("x-request-id", "req-1")
+                .build()
+        });
+        let ProxyResponse::Buffered(response) = mapped else {
+            panic!("expected a buffered response");
+        };
+        assert_eq!(*response.status(), 201);
+        assert_eq!(
+            response.header("x-request-id").and_then(|v| v.as_str()),
+            Some("req-1")
+        );
+        assert_eq!(response.body(), b"a,b");
+    }
+
+    #[test]
+    fn test_without_body_keeps_headers() {
+        // コア層の Content-Length・Content-Type はそのまま
+        let response = ProxyResponse::from(
+            Response::builder()
+                .status(200)
+                .header("content-type", "application/json")
+                .header("content-length", "42")
+                .body("{}")
+                .build(),
+        );
+        let ProxyResponse::Buffered(response) = response.without_body() else {
+            panic!("expected a buffered response");
+        };
+        assert_eq!(*response.status(), 200);
+        assert!(response.body().is_empty());
+        assert_eq!(content_length(&response), Some(42));
+        assert_eq!(
+            response.header("content-type").and_then(|v| v.as_str()),
+            Some("application/json")
+        );
+
+        // Content-Length がない場合はボディの長さ
+        let response = ProxyResponse::from(Response::new(200, "[1,2,3]"));
+        let ProxyResponse::Buffered(response) = response.without_body() else {
+            panic!("expected a buffered response");
+        };
+        assert!(response.body().is_empty());
+        assert_eq!(content_length(&response), Some(7));
+        assert_eq!(
+            ProxyResponse::from(Response::new(200, "[1,2,3]")).body_len(),
+            Some(7)
+        );
+
+        // ボディが空の場合は付与しない
+        let ProxyResponse::Buffered(response) =
+            ProxyResponse::from(Response::new(204, ())).without_body()
+        else {
+            panic!("expected a buffered response");
+        };
+        assert_eq!(content_length(&response), None);
+    }
+}
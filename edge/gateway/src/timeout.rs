@@ -0,0 +1,122 @@
+//! # コア層への送信のタイムアウト
+//!
+//! `spin_sdk::http::send` にはタイムアウトがないため、コア層が応答しないと
+//! ランタイムに打ち切られるまでリクエストが止まります。
+//! WASI の単調時計（monotonic-clock）の Pollable を Spin のエグゼキューターに登録し、
+//! 送信と期限のどちらが先に完了するかで判定します。
+//!
+//! 期限を過ぎた場合は送信の Future を破棄します（送信中のリクエストは取り消される）。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+use std::future::Future;
+use std::pin::{pin, Pin};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::future::{self, Either};
+
+// Spin のエグゼキューター（Pollable が準備できたら Future を再度ポーリングする）
+use spin_executor::CancelOnDropToken;
+
+// WASI の単調時計（ナノ秒）
+use wasi::clocks::monotonic_clock;
+
+// =============================================================================
+// 型定義
+// =============================================================================
+
+/// 期限までに完了しなかったことを表すエラー
+#[derive(Debug, PartialEq)]
+pub(crate) struct Elapsed(pub(crate) Duration);
+
+// =============================================================================
+// タイムアウト
+// =============================================================================
+
+/// 指定した時間だけ待機する（他の Future の処理は止めない）
+///
+/// # 引数
+/// * `duration` - 待機する時間
+pub(crate) fn sleep(duration: Duration) -> Sleep {
+    Sleep {
+        deadline: monotonic_clock::now().saturating_add(duration_nanos(duration)),
+        token: None,
+    }
+}
+
+/// sleep が返す Future
+pub(crate) struct Sleep {
+    /// 期限（単調時計のナノ秒）
+    deadline: u64,
+
+    /// エグゼキューターに登録した Pollable（破棄時に登録を取り消す）
+    token: Option<CancelOnDropToken>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let pollable = monotonic_clock::subscribe_instant(self.deadline);
+        if pollable.ready() {
+            self.token = None;
+            return Poll::Ready(());
+        }
+        self.token =
+            Some(spin_executor::push_waker_and_get_token(pollable, cx.waker().clone()).into());
+        Poll::Pending
+    }
+}
+
+/// Future を期限付きで実行する
+///
+/// # 引数
+/// * `duration` - 期限（`Duration::ZERO` の場合は期限なし）
+/// * `future` - 実行する Future
+///
+/// # 戻り値
+/// * `Ok(T)` - 期限までに完了した場合の結果
+/// * `Err(Elapsed)` - 期限を過ぎた場合（future は破棄する）
+pub(crate) async fn with_timeout<T>(
+    duration: Duration,
+    future: impl Future<Output = T>,
+) -> Result<T, Elapsed> {
+    if duration.is_zero() {
+        return Ok(future.await);
+    }
+
+    match future::select(pin!(future), pin!(sleep(duration))).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(((), _)) => Err(Elapsed(duration)),
+    }
+}
+
+/// Duration をナノ秒に変換する（u64 を超える場合は u64::MAX）
+fn duration_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_duration_disables_timeout() {
+        // 期限なしの場合は時計を使わない（WASI なしで実行できる）
+        let result = futures::executor::block_on(with_timeout(Duration::ZERO, async { 42 }));
+        assert_eq!(result, Ok(42));
+    }
+
+    #[test]
+    fn test_duration_nanos_saturates() {
+        assert_eq!(duration_nanos(Duration::from_millis(2)), 2_000_000);
+        assert_eq!(duration_nanos(Duration::MAX), u64::MAX);
+    }
+}
@@ -0,0 +1,183 @@
+//! # コア層への URL の組み立て
+//!
+//! 転送先のベース URL（core_url・upstream_routes・core_canary_url）、リクエストパス、
+//! クエリ文字列から、コア層に送る URL を `url::Url` で組み立てます。
+//!
+//! | 入力 | 扱い |
+//! |------|------|
+//! | パーセントエンコードした値（例: `%2F`・`%20`） | そのまま（デコード・二重エンコードしない） |
+//! | `+` | そのまま（`%20` に変換しない、コア層がフォームの規則で解釈する） |
+//! | URL に使えない文字（ランタイムがデコードした空白・`"`・`#`・`<`・`>`・非 ASCII） | UTF-8 でパーセントエンコード |
+//! | クエリの値の中の `?` | そのまま（クエリ文字列では有効な文字） |
+//! | 空のクエリ | `?` を付けない |
+//!
+//! Edge 署名（X-Edge-Signature）はコア層が受け取るパスとクエリに対して作るため、
+//! 署名にも同じ規則でエンコードした `path_and_query` を使います。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+// URL の解析・エンコード
+use url::{Position, Url};
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// path_and_query でエンコードに使うベース URL（パスとクエリのみを取り出す）
+const PLACEHOLDER_BASE: &str = "http://localhost";
+
+// =============================================================================
+// URL の組み立て
+// =============================================================================
+
+/// ベース URL・パス・クエリ文字列からコア層への URL を組み立てる
+///
+/// ベース URL が解析できない場合（設定の検証を通るため通常は起きない）は、そのまま連結する。
+///
+/// # 引数
+/// * `base` - 転送先のベース URL（スキームとホストのみ、例: "http://localhost:3001"）
+/// * `path` - リクエストパス（例: "/api/todos"）
+/// * `query` - クエリ文字列（`?` を含まない、空の場合は付けない）
+///
+/// # 戻り値
+/// * `String` - 例: "http://localhost:3001/api/todos?q=%E6%9D%B1%E4%BA%AC"
+pub(crate) fn build(base: &str, path: &str, query: &str) -> String {
+    match encode(base, path, query) {
+        Some(url) => url.into(),
+        None => concat(base, path, query),
+    }
+}
+
+/// コア層が受け取るパスとクエリ文字列（build と同じ規則でエンコード）
+///
+/// # 引数
+/// * `path` - リクエストパス
+/// * `query` - クエリ文字列（`?` を含まない、空の場合は付けない）
+///
+/// # 戻り値
+/// * `String` - 例: "/api/todos?q=a%20b"
+pub(crate) fn path_and_query(path: &str, query: &str) -> String {
+    match encode(PLACEHOLDER_BASE, path, query) {
+        Some(url) => url[Position::BeforePath..].to_string(),
+        None => concat("", path, query),
+    }
+}
+
+/// ベース URL にパスとクエリ文字列を設定する（ベース URL が解析できない場合は None）
+fn encode(base: &str, path: &str, query: &str) -> Option<Url> {
+    let mut url = Url::parse(base).ok()?;
+    if url.cannot_be_a_base() {
+        return None;
+    }
+    url.set_path(path);
+    url.set_query((!query.is_empty()).then_some(query));
+    Some(url)
+}
+
+/// 文字列を連結して URL を作る（エンコードしない）
+fn concat(base: &str, path: &str, query: &str) -> String {
+    if query.is_empty() {
+        format!("{}{}", base, path)
+    } else {
+        format!("{}{}?{}", base, path, query)
+    }
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "http://core:3001";
+
+    #[test]
+    fn test_build_without_query() {
+        assert_eq!(build(BASE, "/api/todos", ""), "http://core:3001/api/todos");
+        assert_eq!(build(BASE, "/health", ""), "http://core:3001/health");
+    }
+
+    #[test]
+    fn test_build_keeps_percent_encoded_values() {
+        assert_eq!(
+            build(BASE, "/api/todos", "q=a%20b&tag=c%2Fd&sep=%26"),
+            "http://core:3001/api/todos?q=a%20b&tag=c%2Fd&sep=%26"
+        );
+        // `+` を `%20` に変換しない
+        assert_eq!(
+            build(BASE, "/api/todos", "q=a+b"),
+            "http://core:3001/api/todos?q=a+b"
+        );
+        // パスのパーセントエンコードもそのまま
+        assert_eq!(
+            build(BASE, "/api/files/a%2Fb", ""),
+            "http://core:3001/api/files/a%2Fb"
+        );
+    }
+
+    #[test]
+    fn test_build_encodes_decoded_characters() {
+        assert_eq!(
+            build(BASE, "/api/todos", "q=a b&title=\"x\""),
+            "http://core:3001/api/todos?q=a%20b&title=%22x%22"
+        );
+        // `#` はフラグメントにせずエンコードする
+        assert_eq!(
+            build(BASE, "/api/todos", "tag=#1"),
+            "http://core:3001/api/todos?tag=%231"
+        );
+        assert_eq!(
+            build(BASE, "/api/files/a b", ""),
+            "http://core:3001/api/files/a%20b"
+        );
+    }
+
+    #[test]
+    fn test_build_keeps_question_mark_in_values() {
+        assert_eq!(
+            build(BASE, "/api/todos", "q=why?&next=/a?b=c"),
+            "http://core:3001/api/todos?q=why?&next=/a?b=c"
+        );
+    }
+
+    #[test]
+    fn test_build_encodes_unicode_search_terms() {
+        assert_eq!(
+            build(BASE, "/api/todos", "q=東京 タワー"),
+            "http://core:3001/api/todos?q=%E6%9D%B1%E4%BA%AC%20%E3%82%BF%E3%83%AF%E3%83%BC"
+        );
+        // エンコード済みの値は二重にエンコードしない
+        assert_eq!(
+            build(BASE, "/api/todos", "q=%E6%9D%B1%E4%BA%AC"),
+            "http://core:3001/api/todos?q=%E6%9D%B1%E4%BA%AC"
+        );
+    }
+
+    #[test]
+    fn test_path_and_query_matches_build() {
+        for (path, query) in [
+            ("/api/todos", ""),
+            ("/api/todos", "q=東京 タワー&x=why?"),
+            ("/api/todos/1", "notify=false"),
+        ] {
+            let url = build(BASE, path, query);
+            assert_eq!(
+                url.strip_prefix(BASE),
+                Some(path_and_query(path, query).as_str())
+            );
+        }
+        assert_eq!(path_and_query("/api/todos", "q=a b"), "/api/todos?q=a%20b");
+    }
+
+    #[test]
+    fn test_build_falls_back_for_unparsable_base() {
+        assert_eq!(
+            build("not a url", "/api/todos", "q=1"),
+            "not a url/api/todos?q=1"
+        );
+    }
+}
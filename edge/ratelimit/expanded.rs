@@ -0,0 +1,415 @@
+#![feature(prelude_import)]
+//! # レート制限コンポーネント
+//!
+//! このコンポーネントは WIT インターフェース `demo:ratelimit/limiter` を実装し、
+//! 固定ウィンドウ方式のレート制限を提供します。
+//!
+//! ## 責務
+//! - key（ユーザー・IP など）ごとのリクエスト数のカウント
+//! - ウィンドウあたりの上限との比較、残り件数・Retry-After の計算
+//!
+//! ## カウンタの保存先
+//! Spin の key-value ストア（`default`）に保存し、アトミックにインクリメントします。
+//! Wasm インスタンスはリクエストごとに作られるため、メモリ上のカウンタは使えません。
+//!
+//! ## 使用方法
+//! gateway コンポーネントから WIT 経由で `check` 関数が呼び出されます。
+#[macro_use]
+extern crate std;
+#[prelude_import]
+use std::prelude::rust_2021::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+use spin_sdk::wit::wasi::keyvalue::{atomics, store};
+#[allow(dead_code, clippy::all)]
+pub mod exports {
+    pub mod demo {
+        pub mod ratelimit {
+            /// =============================================================================
+            /// limiter インターフェース
+            /// =============================================================================
+            /// レート制限を提供するインターフェース
+            #[allow(dead_code, async_fn_in_trait, unused_imports, clippy::all)]
+            pub mod limiter {
+                #[used]
+                #[doc(hidden)]
+                static __FORCE_SECTION_REF: fn() = super::super::super::super::__link_custom_section_describing_imports;
+                use super::super::super::super::_rt;
+                /// レート制限の判定結果
+                #[repr(C)]
+                pub struct CheckResult {
+                    /// リクエストを許可するかどうか
+                    pub allowed: bool,
+                    /// 現在のウィンドウで残っているリクエスト数
+                    /// 拒否した場合は 0
+                    pub remaining: u32,
+                    /// 次のウィンドウまでの秒数（Retry-After ヘッダーの値）
+                    /// 許可した場合は 0
+                    pub retry_after: u32,
+                }
+                #[automatically_derived]
+                #[doc(hidden)]
+                unsafe impl ::core::clone::TrivialClone for CheckResult {}
+                #[automatically_derived]
+                impl ::core::clone::Clone for CheckResult {
+                    #[inline]
+                    fn clone(&self) -> CheckResult {
+                        let _: ::core::clone::AssertParamIsClone<bool>;
+                        let _: ::core::clone::AssertParamIsClone<u32>;
+                        *self
+                    }
+                }
+                #[automatically_derived]
+                impl ::core::marker::Copy for CheckResult {}
+                impl ::core::fmt::Debug for CheckResult {
+                    fn fmt(
+                        &self,
+                        f: &mut ::core::fmt::Formatter<'_>,
+                    ) -> ::core::fmt::Result {
+                        f.debug_struct("CheckResult")
+                            .field("allowed", &self.allowed)
+                            .field("remaining", &self.remaining)
+                            .field("retry-after", &self.retry_after)
+                            .finish()
+                    }
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case, unused_unsafe)]
+                pub unsafe fn _export_check_cabi<T: Guest>(
+                    arg0: *mut u8,
+                    arg1: usize,
+                    arg2: i32,
+                    arg3: i32,
+                ) -> *mut u8 {
+                    unsafe {
+                        _rt::run_ctors_once();
+                        let result1 = {
+                            let len0 = arg1;
+                            let bytes0 = _rt::Vec::from_raw_parts(
+                                arg0.cast(),
+                                len0,
+                                len0,
+                            );
+                            T::check(_rt::string_lift(bytes0), arg2 as u32, arg3 as u32)
+                        };
+                        let ptr2 = (&raw mut _RET_AREA.0).cast::<u8>();
+                        let CheckResult {
+                            allowed: allowed3,
+                            remaining: remaining3,
+                            retry_after: retry_after3,
+                        } = result1;
+                        *ptr2.add(0).cast::<u8>() = (match allowed3 {
+                            true => 1,
+                            false => 0,
+                        }) as u8;
+                        *ptr2.add(4).cast::<i32>() = _rt::as_i32(remaining3);
+                        *ptr2.add(8).cast::<i32>() = _rt::as_i32(retry_after3);
+                        ptr2
+                    }
+                }
+                pub trait Guest {
+                    /// リクエストを 1 件数え、制限内かどうかを判定する
+                    ///
+                    /// 固定ウィンドウ方式: window-seconds ごとの区間で、key ごとに limit 件まで許可します。
+                    /// カウンタは Spin の key-value ストアにあり、インスタンス間で共有されます。
+                    ///
+                    /// # 引数
+                    /// * `key` - 制限の単位（例: "user:{ユーザーID}", "ip:{IP アドレス}"）
+                    /// * `limit` - ウィンドウあたりの上限（0 の場合は常に拒否）
+                    /// * `window-seconds` - ウィンドウの長さ（秒、0 の場合は 1 秒として扱う）
+                    ///
+                    /// # 戻り値
+                    /// * `check-result` - 判定結果
+                    ///
+                    /// # 障害時
+                    /// key-value ストアにアクセスできない場合は許可します（フェイルオープン）。
+                    /// レート制限の障害で全てのリクエストを止めないためです。
+                    #[allow(async_fn_in_trait)]
+                    fn check(
+                        key: _rt::String,
+                        limit: u32,
+                        window_seconds: u32,
+                    ) -> CheckResult;
+                }
+                #[doc(hidden)]
+                pub(crate) use __export_demo_ratelimit_limiter_0_1_0_cabi;
+                #[repr(align(4))]
+                struct _RetArea([::core::mem::MaybeUninit<u8>; 12]);
+                static mut _RET_AREA: _RetArea = _RetArea(
+                    [::core::mem::MaybeUninit::uninit(); 12],
+                );
+            }
+        }
+    }
+}
+mod _rt {
+    #![allow(dead_code, unused_imports, clippy::all)]
+    pub fn run_ctors_once() {
+        wit_bindgen::rt::run_ctors_once();
+    }
+    pub use alloc_crate::vec::Vec;
+    pub unsafe fn string_lift(bytes: Vec<u8>) -> String {
+        if true {
+            String::from_utf8(bytes).unwrap()
+        } else {
+            unsafe { String::from_utf8_unchecked(bytes) }
+        }
+    }
+    pub fn as_i32<T: AsI32>(t: T) -> i32 {
+        t.as_i32()
+    }
+    pub trait AsI32 {
+        fn as_i32(self) -> i32;
+    }
+    impl<'a, T: Copy + AsI32> AsI32 for &'a T {
+        fn as_i32(self) -> i32 {
+            (*self).as_i32()
+        }
+    }
+    impl AsI32 for i32 {
+        #[inline]
+        fn as_i32(self) -> i32 {
+            self as i32
+        }
+    }
+    impl AsI32 for u32 {
+        #[inline]
+        fn as_i32(self) -> i32 {
+            self as i32
+        }
+    }
+    impl AsI32 for i16 {
+        #[inline]
+        fn as_i32(self) -> i32 {
+            self as i32
+        }
+    }
+    impl AsI32 for u16 {
+        #[inline]
+        fn as_i32(self) -> i32 {
+            self as i32
+        }
+    }
+    impl AsI32 for i8 {
+        #[inline]
+        fn as_i32(self) -> i32 {
+            self as i32
+        }
+    }
+    impl AsI32 for u8 {
+        #[inline]
+        fn as_i32(self) -> i32 {
+            self as i32
+        }
+    }
+    impl AsI32 for char {
+        #[inline]
+        fn as_i32(self) -> i32 {
+            self as i32
+        }
+    }
+    impl AsI32 for usize {
+        #[inline]
+        fn as_i32(self) -> i32 {
+            self as i32
+        }
+    }
+    pub use alloc_crate::string::String;
+    extern crate alloc as alloc_crate;
+}
+#[doc(inline)]
+pub(crate) use __export_ratelimit_world_impl as export;
+#[unsafe(
+    link_section = "component-type:wit-bindgen:0.51.0:demo:ratelimit@0.1.0:ratelimit-world:encoded world"
+)]
+#[doc(hidden)]
+#[allow(clippy::octal_escapes)]
+pub static __WIT_BINDGEN_COMPONENT_TYPE: [u8; 314] = *b"\
+\0asm\x0d\0\x01\0\0\x19\x16wit-component-encoding\x04\0\x07\xb4\x01\x01A\x02\x01\
+A\x02\x01B\x04\x01r\x03\x07allowed\x7f\x09remainingy\x0bretry-aftery\x04\0\x0cch\
+eck-result\x03\0\0\x01@\x03\x03keys\x05limity\x0ewindow-secondsy\0\x01\x04\0\x05\
+check\x01\x02\x04\0\x1cdemo:ratelimit/limiter@0.1.0\x05\0\x04\0$demo:ratelimit/r\
+atelimit-world@0.1.0\x04\0\x0b\x15\x01\0\x0fratelimit-world\x03\0\0\0G\x09produc\
+ers\x01\x0cprocessed-by\x02\x0dwit-component\x070.244.0\x10wit-bindgen-rust\x060\
+.51.0";
+#[inline(never)]
+#[doc(hidden)]
+pub fn __link_custom_section_describing_imports() {
+    wit_bindgen::rt::maybe_link_cabi_realloc();
+}
+const _: &[u8] = b"// =============================================================================\n// WIT \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe5\xae\x9a\xe7\xbe\xa9: \xe3\x83\xac\xe3\x83\xbc\xe3\x83\x88\xe5\x88\xb6\xe9\x99\x90\n// =============================================================================\n//\n// \xe3\x83\xac\xe3\x83\xbc\xe3\x83\x88\xe5\x88\xb6\xe9\x99\x90\xe3\x82\x92\xe7\x8b\xac\xe7\xab\x8b\xe3\x81\x97\xe3\x81\x9f Wasm \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xef\xbc\x88ratelimit\xef\xbc\x89\xe3\x81\xa8\xe3\x81\x97\xe3\x81\xa6\xe6\x8f\x90\xe4\xbe\x9b\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x9f\xe3\x82\x81\xe3\x81\xae\n// \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x81\xa7\xe3\x81\x99\xe3\x80\x82gateway \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe3\x82\xa4\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xe3\x81\x97\xe3\x80\x81\n// \xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbc\xe5\x8d\x98\xe4\xbd\x8d\xe3\x83\xbbIP \xe5\x8d\x98\xe4\xbd\x8d\xe3\x81\xae\xe5\x88\xb6\xe9\x99\x90\xe3\x81\xab\xe4\xbd\xbf\xe7\x94\xa8\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n//\n// \xe9\x85\x8d\xe7\xbd\xae:\n// - demo:auth\xef\xbc\x88wit/auth.wit\xef\xbc\x89\xe3\x81\xae gateway-world \xe3\x81\x8b\xe3\x82\x89\xe3\x82\xa4\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x9f\xe3\x82\x81\xe3\x80\x81\n//   \xe4\xbe\x9d\xe5\xad\x98\xe3\x83\x91\xe3\x83\x83\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb8\xe3\x81\xa8\xe3\x81\x97\xe3\x81\xa6 wit/deps/ratelimit/ \xe3\x81\xab\xe7\xbd\xae\xe3\x81\x84\xe3\x81\xa6\xe3\x81\x84\xe3\x81\xbe\xe3\x81\x99\n// - ratelimit \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\xaf\xe3\x81\x93\xe3\x81\xae\xe3\x83\x87\xe3\x82\xa3\xe3\x83\xac\xe3\x82\xaf\xe3\x83\x88\xe3\x83\xaa\xe3\x81\xa0\xe3\x81\x91\xe3\x82\x92\xe5\x8f\x82\xe7\x85\xa7\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\n//\n// =============================================================================\n\n// -----------------------------------------------------------------------------\n// \xe3\x83\x91\xe3\x83\x83\xe3\x82\xb1\xe3\x83\xbc\xe3\x82\xb8\xe5\xae\xa3\xe8\xa8\x80\n// -----------------------------------------------------------------------------\npackage demo:ratelimit@0.1.0;\n\n// =============================================================================\n// limiter \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\n// =============================================================================\n\n/// \xe3\x83\xac\xe3\x83\xbc\xe3\x83\x88\xe5\x88\xb6\xe9\x99\x90\xe3\x82\x92\xe6\x8f\x90\xe4\xbe\x9b\xe3\x81\x99\xe3\x82\x8b\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\ninterface limiter {\n    /// \xe3\x83\xac\xe3\x83\xbc\xe3\x83\x88\xe5\x88\xb6\xe9\x99\x90\xe3\x81\xae\xe5\x88\xa4\xe5\xae\x9a\xe7\xb5\x90\xe6\x9e\x9c\n    record check-result {\n        /// \xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x82\x92\xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x99\xe3\x82\x8b\xe3\x81\x8b\xe3\x81\xa9\xe3\x81\x86\xe3\x81\x8b\n        allowed: bool,\n\n        /// \xe7\x8f\xbe\xe5\x9c\xa8\xe3\x81\xae\xe3\x82\xa6\xe3\x82\xa3\xe3\x83\xb3\xe3\x83\x89\xe3\x82\xa6\xe3\x81\xa7\xe6\xae\x8b\xe3\x81\xa3\xe3\x81\xa6\xe3\x81\x84\xe3\x82\x8b\xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe6\x95\xb0\n        /// \xe6\x8b\x92\xe5\x90\xa6\xe3\x81\x97\xe3\x81\x9f\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf 0\n        remaining: u32,\n\n        /// \xe6\xac\xa1\xe3\x81\xae\xe3\x82\xa6\xe3\x82\xa3\xe3\x83\xb3\xe3\x83\x89\xe3\x82\xa6\xe3\x81\xbe\xe3\x81\xa7\xe3\x81\xae\xe7\xa7\x92\xe6\x95\xb0\xef\xbc\x88Retry-After \xe3\x83\x98\xe3\x83\x83\xe3\x83\x80\xe3\x83\xbc\xe3\x81\xae\xe5\x80\xa4\xef\xbc\x89\n        /// \xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x97\xe3\x81\x9f\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf 0\n        retry-after: u32,\n    }\n\n    /// \xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x82\x92 1 \xe4\xbb\xb6\xe6\x95\xb0\xe3\x81\x88\xe3\x80\x81\xe5\x88\xb6\xe9\x99\x90\xe5\x86\x85\xe3\x81\x8b\xe3\x81\xa9\xe3\x81\x86\xe3\x81\x8b\xe3\x82\x92\xe5\x88\xa4\xe5\xae\x9a\xe3\x81\x99\xe3\x82\x8b\n    ///\n    /// \xe5\x9b\xba\xe5\xae\x9a\xe3\x82\xa6\xe3\x82\xa3\xe3\x83\xb3\xe3\x83\x89\xe3\x82\xa6\xe6\x96\xb9\xe5\xbc\x8f: window-seconds \xe3\x81\x94\xe3\x81\xa8\xe3\x81\xae\xe5\x8c\xba\xe9\x96\x93\xe3\x81\xa7\xe3\x80\x81key \xe3\x81\x94\xe3\x81\xa8\xe3\x81\xab limit \xe4\xbb\xb6\xe3\x81\xbe\xe3\x81\xa7\xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    /// \xe3\x82\xab\xe3\x82\xa6\xe3\x83\xb3\xe3\x82\xbf\xe3\x81\xaf Spin \xe3\x81\xae key-value \xe3\x82\xb9\xe3\x83\x88\xe3\x82\xa2\xe3\x81\xab\xe3\x81\x82\xe3\x82\x8a\xe3\x80\x81\xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xb9\xe3\x82\xbf\xe3\x83\xb3\xe3\x82\xb9\xe9\x96\x93\xe3\x81\xa7\xe5\x85\xb1\xe6\x9c\x89\xe3\x81\x95\xe3\x82\x8c\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\n    ///\n    /// # \xe5\xbc\x95\xe6\x95\xb0\n    /// * `key` - \xe5\x88\xb6\xe9\x99\x90\xe3\x81\xae\xe5\x8d\x98\xe4\xbd\x8d\xef\xbc\x88\xe4\xbe\x8b: \"user:{\xe3\x83\xa6\xe3\x83\xbc\xe3\x82\xb6\xe3\x83\xbcID}\", \"ip:{IP \xe3\x82\xa2\xe3\x83\x89\xe3\x83\xac\xe3\x82\xb9}\"\xef\xbc\x89\n    /// * `limit` - \xe3\x82\xa6\xe3\x82\xa3\xe3\x83\xb3\xe3\x83\x89\xe3\x82\xa6\xe3\x81\x82\xe3\x81\x9f\xe3\x82\x8a\xe3\x81\xae\xe4\xb8\x8a\xe9\x99\x90\xef\xbc\x880 \xe3\x81\xae\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf\xe5\xb8\xb8\xe3\x81\xab\xe6\x8b\x92\xe5\x90\xa6\xef\xbc\x89\n    /// * `window-seconds` - \xe3\x82\xa6\xe3\x82\xa3\xe3\x83\xb3\xe3\x83\x89\xe3\x82\xa6\xe3\x81\xae\xe9\x95\xb7\xe3\x81\x95\xef\xbc\x88\xe7\xa7\x92\xe3\x80\x810 \xe3\x81\xae\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf 1 \xe7\xa7\x92\xe3\x81\xa8\xe3\x81\x97\xe3\x81\xa6\xe6\x89\xb1\xe3\x81\x86\xef\xbc\x89\n    ///\n    /// # \xe6\x88\xbb\xe3\x82\x8a\xe5\x80\xa4\n    /// * `check-result` - \xe5\x88\xa4\xe5\xae\x9a\xe7\xb5\x90\xe6\x9e\x9c\n    ///\n    /// # \xe9\x9a\x9c\xe5\xae\xb3\xe6\x99\x82\n    /// key-value \xe3\x82\xb9\xe3\x83\x88\xe3\x82\xa2\xe3\x81\xab\xe3\x82\xa2\xe3\x82\xaf\xe3\x82\xbb\xe3\x82\xb9\xe3\x81\xa7\xe3\x81\x8d\xe3\x81\xaa\xe3\x81\x84\xe5\xa0\xb4\xe5\x90\x88\xe3\x81\xaf\xe8\xa8\xb1\xe5\x8f\xaf\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xef\xbc\x88\xe3\x83\x95\xe3\x82\xa7\xe3\x82\xa4\xe3\x83\xab\xe3\x82\xaa\xe3\x83\xbc\xe3\x83\x97\xe3\x83\xb3\xef\xbc\x89\xe3\x80\x82\n    /// \xe3\x83\xac\xe3\x83\xbc\xe3\x83\x88\xe5\x88\xb6\xe9\x99\x90\xe3\x81\xae\xe9\x9a\x9c\xe5\xae\xb3\xe3\x81\xa7\xe5\x85\xa8\xe3\x81\xa6\xe3\x81\xae\xe3\x83\xaa\xe3\x82\xaf\xe3\x82\xa8\xe3\x82\xb9\xe3\x83\x88\xe3\x82\x92\xe6\xad\xa2\xe3\x82\x81\xe3\x81\xaa\xe3\x81\x84\xe3\x81\x9f\xe3\x82\x81\xe3\x81\xa7\xe3\x81\x99\xe3\x80\x82\n    check: func(key: string, limit: u32, window-seconds: u32) -> check-result;\n}\n\n// =============================================================================\n// World \xe5\xae\x9a\xe7\xbe\xa9\n// =============================================================================\n\n/// ratelimit \xe3\x82\xb3\xe3\x83\xb3\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x8d\xe3\x83\xb3\xe3\x83\x88\xe3\x81\x8c\xe5\xae\x9f\xe8\xa3\x85\xe3\x81\x99\xe3\x82\x8b world\n///\n/// limiter \xe3\x82\xa4\xe3\x83\xb3\xe3\x82\xbf\xe3\x83\xbc\xe3\x83\x95\xe3\x82\xa7\xe3\x83\xbc\xe3\x82\xb9\xe3\x82\x92\xe3\x82\xa8\xe3\x82\xaf\xe3\x82\xb9\xe3\x83\x9d\xe3\x83\xbc\xe3\x83\x88\xe3\x81\x97\xe3\x81\xbe\xe3\x81\x99\xe3\x80\x82\nworld ratelimit-world {\n    export limiter;\n}\n";
+use exports::demo::ratelimit::limiter::{CheckResult, Guest};
+/// カウンタを保存する key-value ストア名
+///
+/// spin.toml の `key_value_stores` で許可したストアと一致させます。
+const STORE_NAME: &str = "default";
+/// カウンタのキーのプレフィックス
+///
+/// 同じストアを使う他の用途のキーと衝突しないようにします。
+const KEY_PREFIX: &str = "rl";
+/// ウィンドウごとのカウンタの保存先
+///
+/// テストではメモリ上の実装に差し替え、key-value ストアなしで判定ロジックを検証します。
+trait Counter {
+    /// キーの値を 1 増やし、増やした後の値を返す（キーがなければ 0 から数える）
+    fn increment(&self, key: &str) -> Result<u64, String>;
+    /// キーを削除する（失敗しても判定には影響しない）
+    fn delete(&self, key: &str);
+}
+/// Spin の key-value ストアを使うカウンタ
+struct KvCounter {
+    bucket: store::Bucket,
+}
+impl KvCounter {
+    /// key-value ストアを開く
+    fn open() -> Result<Self, String> {
+        store::open(STORE_NAME)
+            .map(|bucket| Self { bucket })
+            .map_err(|e| ::alloc::__export::must_use({
+                ::alloc::fmt::format(
+                    format_args!("Failed to open key-value store: {0:?}", e),
+                )
+            }))
+    }
+}
+impl Counter for KvCounter {
+    fn increment(&self, key: &str) -> Result<u64, String> {
+        atomics::increment(&self.bucket, key, 1)
+            .map(|count| count.max(0) as u64)
+            .map_err(|e| ::alloc::__export::must_use({
+                ::alloc::fmt::format(
+                    format_args!("Failed to increment counter: {0:?}", e),
+                )
+            }))
+    }
+    fn delete(&self, key: &str) {
+        if let Err(e) = self.bucket.delete(key) {
+            {
+                ::std::io::_print(
+                    format_args!(
+                        "[ratelimit] Failed to delete expired counter {0}: {1:?}\n", key,
+                        e
+                    ),
+                );
+            };
+        }
+    }
+}
+/// レート制限コンポーネントの実装構造体
+struct RateLimitComponent;
+impl Guest for RateLimitComponent {
+    /// リクエストを 1 件数え、制限内かどうかを判定する
+    ///
+    /// key-value ストアにアクセスできない場合は許可します（フェイルオープン）。
+    fn check(key: String, limit: u32, window_seconds: u32) -> CheckResult {
+        check_or_allow(KvCounter::open(), &key, limit, window_seconds, unix_now())
+    }
+}
+const _: () = {
+    #[unsafe(export_name = "demo:ratelimit/limiter@0.1.0#check")]
+    unsafe extern "C" fn export_check(
+        arg0: *mut u8,
+        arg1: usize,
+        arg2: i32,
+        arg3: i32,
+    ) -> *mut u8 {
+        unsafe {
+            self::exports::demo::ratelimit::limiter::_export_check_cabi::<
+                RateLimitComponent,
+            >(arg0, arg1, arg2, arg3)
+        }
+    }
+};
+/// 固定ウィンドウ方式でリクエストを 1 件数え、判定する
+///
+/// ウィンドウは UNIX 時間を window_seconds で区切った区間です。
+/// キーにウィンドウの開始時刻を含めるため、ウィンドウが変わるとカウントは 0 から始まります。
+///
+/// # 引数
+/// * `counter` - カウンタの保存先
+/// * `key` - 制限の単位
+/// * `limit` - ウィンドウあたりの上限（0 の場合は常に拒否）
+/// * `window_seconds` - ウィンドウの長さ（0 の場合は 1 秒として扱う）
+/// * `now` - 現在時刻（UNIX 秒）
+///
+/// # 戻り値
+/// * `Ok(CheckResult)` - 判定結果
+/// * `Err(String)` - カウンタの更新に失敗した場合のエラーメッセージ
+fn check_with(
+    counter: &impl Counter,
+    key: &str,
+    limit: u32,
+    window_seconds: u32,
+    now: u64,
+) -> Result<CheckResult, String> {
+    let window = u64::from(window_seconds.max(1));
+    let window_start = now / window * window;
+    let count = counter.increment(&counter_key(key, window_start))?;
+    if count == 1 && window_start >= window {
+        counter.delete(&counter_key(key, window_start - window));
+    }
+    let limit_u64 = u64::from(limit);
+    if count <= limit_u64 {
+        Ok(CheckResult {
+            allowed: true,
+            remaining: (limit_u64 - count) as u32,
+            retry_after: 0,
+        })
+    } else {
+        let retry_after = (window_start + window - now).max(1);
+        Ok(CheckResult {
+            allowed: false,
+            remaining: 0,
+            retry_after: u32::try_from(retry_after).unwrap_or(u32::MAX),
+        })
+    }
+}
+/// 判定し、カウンタが使えない場合は許可する（フェイルオープン）
+///
+/// レート制限の障害（key-value ストアを開けない・インクリメントできない）で
+/// 全てのリクエストを止めないため、エラーの場合は上限いっぱいの残り件数で許可します。
+///
+/// # 引数
+/// * `counter` - カウンタの保存先（開けなかった場合はエラー）
+/// * `key` / `limit` / `window_seconds` / `now` - check_with と同じ
+fn check_or_allow(
+    counter: Result<impl Counter, String>,
+    key: &str,
+    limit: u32,
+    window_seconds: u32,
+    now: u64,
+) -> CheckResult {
+    let result = counter
+        .and_then(|counter| check_with(&counter, key, limit, window_seconds, now));
+    match result {
+        Ok(result) => result,
+        Err(e) => {
+            {
+                ::std::io::_print(
+                    format_args!(
+                        "[ratelimit] {0} (key: {1}), allowing request\n", e, key
+                    ),
+                );
+            };
+            CheckResult {
+                allowed: true,
+                remaining: limit,
+                retry_after: 0,
+            }
+        }
+    }
+}
+/// ウィンドウごとのカウンタのキーを組み立てる
+///
+/// 例: `rl:user:123:1700000040`
+fn counter_key(key: &str, window_start: u64) -> String {
+    ::alloc::__export::must_use({
+        ::alloc::fmt::format(format_args!("{0}:{1}:{2}", KEY_PREFIX, key, window_start))
+    })
+}
+/// 現在時刻（UNIX 秒）
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
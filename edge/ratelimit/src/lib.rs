@@ -0,0 +1,401 @@
+//! # レート制限コンポーネント
+//!
+//! このコンポーネントは WIT インターフェース `demo:ratelimit/limiter` を実装し、
+//! 固定ウィンドウ方式のレート制限を提供します。
+//!
+//! ## 責務
+//! - key（ユーザー・IP など）ごとのリクエスト数のカウント
+//! - ウィンドウあたりの上限との比較、残り件数・Retry-After の計算
+//!
+//! ## カウンタの保存先
+//! Spin の key-value ストア（`default`）に保存し、アトミックにインクリメントします。
+//! Wasm インスタンスはリクエストごとに作られるため、メモリ上のカウンタは使えません。
+//!
+//! ## 使用方法
+//! gateway コンポーネントから WIT 経由で `check` 関数が呼び出されます。
+
+// =============================================================================
+// 外部クレートのインポート
+// =============================================================================
+
+// 現在時刻の取得（ウィンドウの開始時刻の計算）
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Spin の key-value ストア（wasi:keyvalue@0.2.0-draft2）
+// store: ストアを開く、キーを削除する
+// atomics: アトミックなインクリメント
+// spin_sdk::key_value::Store には increment がないため、WIT バインディングを直接使用
+use spin_sdk::wit::wasi::keyvalue::{atomics, store};
+
+// =============================================================================
+// WIT バインディングの生成
+// =============================================================================
+
+// wit_bindgen マクロで WIT インターフェースから Rust コードを自動生成
+// world: このコンポーネントが実装する world 名（ratelimit-world）
+// path: demo:ratelimit パッケージのディレクトリへの相対パス
+wit_bindgen::generate!({
+    world: "ratelimit-world",
+    path: "../wit/deps/ratelimit",
+});
+
+// wit-bindgen が生成した型をインポート
+// Guest: WIT の export を実装するためのトレイト
+// CheckResult: 判定結果を表す構造体（WIT の check-result レコードに対応）
+use exports::demo::ratelimit::limiter::{CheckResult, Guest};
+
+// =============================================================================
+// 定数定義
+// =============================================================================
+
+/// カウンタを保存する key-value ストア名
+///
+/// spin.toml の `key_value_stores` で許可したストアと一致させます。
+const STORE_NAME: &str = "default";
+
+/// カウンタのキーのプレフィックス
+///
+/// 同じストアを使う他の用途のキーと衝突しないようにします。
+const KEY_PREFIX: &str = "rl";
+
+// =============================================================================
+// カウンタ
+// =============================================================================
+
+/// ウィンドウごとのカウンタの保存先
+///
+/// テストではメモリ上の実装に差し替え、key-value ストアなしで判定ロジックを検証します。
+trait Counter {
+    /// キーの値を 1 増やし、増やした後の値を返す（キーがなければ 0 から数える）
+    fn increment(&self, key: &str) -> Result<u64, String>;
+
+    /// キーを削除する（失敗しても判定には影響しない）
+    fn delete(&self, key: &str);
+}
+
+/// Spin の key-value ストアを使うカウンタ
+struct KvCounter {
+    bucket: store::Bucket,
+}
+
+impl KvCounter {
+    /// key-value ストアを開く
+    fn open() -> Result<Self, String> {
+        store::open(STORE_NAME)
+            .map(|bucket| Self { bucket })
+            .map_err(|e| format!("Failed to open key-value store: {:?}", e))
+    }
+}
+
+impl Counter for KvCounter {
+    fn increment(&self, key: &str) -> Result<u64, String> {
+        atomics::increment(&self.bucket, key, 1)
+            .map(|count| count.max(0) as u64)
+            .map_err(|e| format!("Failed to increment counter: {:?}", e))
+    }
+
+    fn delete(&self, key: &str) {
+        if let Err(e) = self.bucket.delete(key) {
+            println!(
+                "[ratelimit] Failed to delete expired counter {}: {:?}",
+                key, e
+            );
+        }
+    }
+}
+
+// =============================================================================
+// コンポーネント実装
+// =============================================================================
+
+/// レート制限コンポーネントの実装構造体
+struct RateLimitComponent;
+
+impl Guest for RateLimitComponent {
+    /// リクエストを 1 件数え、制限内かどうかを判定する
+    ///
+    /// key-value ストアにアクセスできない場合は許可します（フェイルオープン）。
+    fn check(key: String, limit: u32, window_seconds: u32) -> CheckResult {
+        check_or_allow(KvCounter::open(), &key, limit, window_seconds, unix_now())
+    }
+}
+
+// wit-bindgen マクロでコンポーネントをエクスポート
+export!(RateLimitComponent);
+
+// =============================================================================
+// 判定ロジック
+// =============================================================================
+
+/// 固定ウィンドウ方式でリクエストを 1 件数え、判定する
+///
+/// ウィンドウは UNIX 時間を window_seconds で区切った区間です。
+/// キーにウィンドウの開始時刻を含めるため、ウィンドウが変わるとカウントは 0 から始まります。
+///
+/// # 引数
+/// * `counter` - カウンタの保存先
+/// * `key` - 制限の単位
+/// * `limit` - ウィンドウあたりの上限（0 の場合は常に拒否）
+/// * `window_seconds` - ウィンドウの長さ（0 の場合は 1 秒として扱う）
+/// * `now` - 現在時刻（UNIX 秒）
+///
+/// # 戻り値
+/// * `Ok(CheckResult)` - 判定結果
+/// * `Err(String)` - カウンタの更新に失敗した場合のエラーメッセージ
+fn check_with(
+    counter: &impl Counter,
+    key: &str,
+    limit: u32,
+    window_seconds: u32,
+    now: u64,
+) -> Result<CheckResult, String> {
+    let window = u64::from(window_seconds.max(1));
+    let window_start = now / window * window;
+
+    let count = counter.increment(&counter_key(key, window_start))?;
+
+    // ウィンドウの最初のリクエストで前のウィンドウのカウンタを削除
+    // key-value ストアには有効期限がないため、古いキーが溜まらないようにする
+    if count == 1 && window_start >= window {
+        counter.delete(&counter_key(key, window_start - window));
+    }
+
+    let limit_u64 = u64::from(limit);
+    if count <= limit_u64 {
+        Ok(CheckResult {
+            allowed: true,
+            remaining: (limit_u64 - count) as u32,
+            retry_after: 0,
+        })
+    } else {
+        // 次のウィンドウの開始までの秒数（最低 1 秒）
+        let retry_after = (window_start + window - now).max(1);
+        Ok(CheckResult {
+            allowed: false,
+            remaining: 0,
+            retry_after: u32::try_from(retry_after).unwrap_or(u32::MAX),
+        })
+    }
+}
+
+/// 判定し、カウンタが使えない場合は許可する（フェイルオープン）
+///
+/// レート制限の障害（key-value ストアを開けない・インクリメントできない）で
+/// 全てのリクエストを止めないため、エラーの場合は上限いっぱいの残り件数で許可します。
+///
+/// # 引数
+/// * `counter` - カウンタの保存先（開けなかった場合はエラー）
+/// * `key` / `limit` / `window_seconds` / `now` - check_with と同じ
+fn check_or_allow(
+    counter: Result<impl Counter, String>,
+    key: &str,
+    limit: u32,
+    window_seconds: u32,
+    now: u64,
+) -> CheckResult {
+    let result = counter.and_then(|counter| check_with(&counter, key, limit, window_seconds, now));
+
+    match result {
+        Ok(result) => result,
+        Err(e) => {
+            println!("[ratelimit] {} (key: {}), allowing request", e, key);
+            CheckResult {
+                allowed: true,
+                remaining: limit,
+                retry_after: 0,
+            }
+        }
+    }
+}
+
+/// ウィンドウごとのカウンタのキーを組み立てる
+///
+/// 例: `rl:user:123:1700000040`
+fn counter_key(key: &str, window_start: u64) -> String {
+    format!("{}:{}:{}", KEY_PREFIX, key, window_start)
+}
+
+/// 現在時刻（UNIX 秒）
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+// =============================================================================
+// テスト
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    /// メモリ上のカウンタ（key-value ストアの代わり）
+    #[derive(Default)]
+    struct MemoryCounter {
+        values: Mutex<HashMap<String, u64>>,
+    }
+
+    impl Counter for MemoryCounter {
+        fn increment(&self, key: &str) -> Result<u64, String> {
+            let mut values = self.values.lock().unwrap();
+            let value = values.entry(key.to_string()).or_default();
+            *value += 1;
+            Ok(*value)
+        }
+
+        fn delete(&self, key: &str) {
+            self.values.lock().unwrap().remove(key);
+        }
+    }
+
+    #[test]
+    fn test_denies_after_limit_within_window() {
+        let counter = MemoryCounter::default();
+
+        for expected_remaining in [2, 1, 0] {
+            let result = check_with(&counter, "user:1", 3, 60, 1_000).unwrap();
+            assert!(result.allowed);
+            assert_eq!(result.remaining, expected_remaining);
+            assert_eq!(result.retry_after, 0);
+        }
+
+        // ウィンドウ [960, 1020) の 4 件目 → 次のウィンドウまで 20 秒
+        let denied = check_with(&counter, "user:1", 3, 60, 1_000).unwrap();
+        assert!(!denied.allowed);
+        assert_eq!(denied.remaining, 0);
+        assert_eq!(denied.retry_after, 20);
+
+        // 別のキーは独立して数える
+        assert!(
+            check_with(&counter, "user:2", 3, 60, 1_000)
+                .unwrap()
+                .allowed
+        );
+    }
+
+    #[test]
+    fn test_window_rollover_resets_count_and_removes_old_key() {
+        let counter = MemoryCounter::default();
+
+        assert!(
+            check_with(&counter, "ip:10.0.0.1", 1, 60, 1_019)
+                .unwrap()
+                .allowed
+        );
+        let denied = check_with(&counter, "ip:10.0.0.1", 1, 60, 1_019).unwrap();
+        assert!(!denied.allowed);
+        assert_eq!(denied.retry_after, 1);
+
+        // 次のウィンドウ [1020, 1080) に入ると再び許可される
+        let result = check_with(&counter, "ip:10.0.0.1", 1, 60, 1_020).unwrap();
+        assert!(result.allowed);
+        assert_eq!(result.remaining, 0);
+
+        // 前のウィンドウのカウンタは削除されている
+        let values = counter.values.lock().unwrap();
+        assert!(!values.contains_key(&counter_key("ip:10.0.0.1", 960)));
+        assert_eq!(values.get(&counter_key("ip:10.0.0.1", 1_020)), Some(&1));
+    }
+
+    #[test]
+    fn test_zero_limit_and_zero_window() {
+        let counter = MemoryCounter::default();
+
+        // limit 0 は常に拒否
+        let denied = check_with(&counter, "user:1", 0, 60, 1_000).unwrap();
+        assert!(!denied.allowed);
+        assert_eq!(denied.retry_after, 20);
+
+        // window 0 は 1 秒として扱う（0 除算しない）
+        assert!(check_with(&counter, "user:2", 1, 0, 1_000).unwrap().allowed);
+        let denied = check_with(&counter, "user:2", 1, 0, 1_000).unwrap();
+        assert!(!denied.allowed);
+        assert_eq!(denied.retry_after, 1);
+    }
+
+    /// 参照経由でも同じカウンタを使う（check_or_allow は所有権を受け取るため）
+    impl Counter for &MemoryCounter {
+        fn increment(&self, key: &str) -> Result<u64, String> {
+            (*self).increment(key)
+        }
+
+        fn delete(&self, key: &str) {
+            (*self).delete(key)
+        }
+    }
+
+    /// 常に失敗するカウンタ（key-value ストアの障害）
+    struct FailingCounter;
+
+    impl Counter for FailingCounter {
+        fn increment(&self, _key: &str) -> Result<u64, String> {
+            Err("Failed to increment counter: store unavailable".to_string())
+        }
+
+        fn delete(&self, _key: &str) {}
+    }
+
+    #[test]
+    fn test_fails_open_when_store_is_unavailable() {
+        // ストアを開けない場合
+        let unavailable: Result<MemoryCounter, String> =
+            Err("Failed to open key-value store: NoSuchStore".to_string());
+        let result = check_or_allow(unavailable, "user:1", 60, 60, 1_000);
+        assert!(result.allowed);
+        assert_eq!(result.remaining, 60);
+        assert_eq!(result.retry_after, 0);
+
+        // インクリメントに失敗する場合（limit 0 でも拒否しない）
+        for limit in [0, 1, 60] {
+            let result = check_or_allow(Ok(FailingCounter), "user:1", limit, 60, 1_000);
+            assert!(result.allowed);
+            assert_eq!(result.remaining, limit);
+        }
+    }
+
+    #[test]
+    fn test_check_or_allow_enforces_limit_when_store_is_available() {
+        let counter = MemoryCounter::default();
+
+        assert!(check_or_allow(Ok(&counter), "user:1", 1, 60, 1_000).allowed);
+        let denied = check_or_allow(Ok(&counter), "user:1", 1, 60, 1_000);
+        assert!(!denied.allowed);
+        assert_eq!(denied.retry_after, 20);
+    }
+
+    #[test]
+    fn test_concurrent_increments_allow_exactly_limit() {
+        let counter = Arc::new(MemoryCounter::default());
+
+        let handles: Vec<_> = (0..16)
+            .map(|_| {
+                let counter = Arc::clone(&counter);
+                thread::spawn(move || {
+                    (0..10)
+                        .filter(|_| {
+                            check_with(counter.as_ref(), "user:1", 25, 60, 1_000)
+                                .unwrap()
+                                .allowed
+                        })
+                        .count()
+                })
+            })
+            .collect();
+
+        let allowed: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+        assert_eq!(allowed, 25);
+        assert_eq!(
+            counter
+                .values
+                .lock()
+                .unwrap()
+                .get(&counter_key("user:1", 960)),
+            Some(&160)
+        );
+    }
+}
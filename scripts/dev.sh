@@ -48,7 +48,8 @@ echo "      RUST_LOG=info \\"
 echo "      cargo run -p api"
 echo ""
 echo "  [Edge 層]"
-echo "    cd $PROJECT_ROOT/edge && spin build && spin up"
+echo "    cd $PROJECT_ROOT/edge && spin build && \\"
+echo "      SPIN_VARIABLE_JWT_SECRET=super-secret-key spin up"
 echo ""
 echo "=== 認証フロー（ローカル認証）==="
 echo ""